@@ -29,6 +29,10 @@ export interface ChangeSet {
   updatedAt?: IsoDateString;
   abandonRequestedAt?: IsoDateString;
   abandonRequestedByUserId?: UserId;
+  // what the current user can do with the change set, as decided by the backend
+  canApply?: boolean;
+  canApprove?: boolean;
+  canEdit?: boolean;
 }
 
 export type ChangeStatus = "added" | "deleted" | "modified" | "unmodified";
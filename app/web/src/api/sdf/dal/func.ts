@@ -124,6 +124,7 @@ export enum FuncBackendKind {
   String = "String",
   Unset = "Unset",
   Validation = "Validation",
+  Wasm = "Wasm",
 }
 export interface FuncSummary {
   funcId: FuncId;
@@ -33,6 +33,22 @@ export interface ViewDescription {
   name: string;
   isDefault: boolean;
 }
+
+export type AnnotationId = string;
+
+export interface Annotation {
+  id: AnnotationId;
+  viewId: ViewId;
+  /** markdown */
+  body: string;
+  author: string | null;
+  /** relative to the anchor component, if any */
+  x: number;
+  y: number;
+  componentId: ComponentId | null;
+  created_at: IsoDateString;
+  updated_at: IsoDateString;
+}
 export interface StringGeometry {
   x: string;
   y: string;
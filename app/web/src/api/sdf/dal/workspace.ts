@@ -15,3 +15,7 @@ export interface WorkspaceMetadata {
   changeSets: ChangeSet[];
   approvers: string[];
 }
+
+export interface WorkspaceView extends WorkspaceMetadata {
+  canApprove: boolean;
+}
@@ -389,6 +389,8 @@ export type DiagramNodeDef = {
   updatedInfo: ActorAndTimestamp;
   icon: IconNames;
   resourceId: string;
+  /** user-defined labels used to group components */
+  labels: Record<string, string>;
 };
 
 export type DiagramSocketDef = {
@@ -9,7 +9,7 @@ import {
   ChangeSetId,
   ChangeSetStatus,
 } from "@/api/sdf/dal/change_set";
-import { WorkspaceMetadata } from "@/api/sdf/dal/workspace";
+import { WorkspaceView } from "@/api/sdf/dal/workspace";
 import router from "@/router";
 import { UserId, useAuthStore } from "@/store/auth.store";
 import IncomingChangesMerging from "@/components/toasts/IncomingChangesMerging.vue";
@@ -64,12 +64,11 @@ export function useChangeSetsStore() {
         changeSetApprovals: {} as Record<UserId, string>,
         statusWithBase: {} as Record<ChangeSetId, StatusWithBase>,
         approvers: [] as UserId[],
+        currentUserCanApprove: false as boolean,
       }),
       getters: {
         currentUserIsApprover(): boolean {
-          const userPk = authStore.user?.pk;
-          if (!userPk) return false;
-          return this.approvers.includes(userPk);
+          return this.currentUserCanApprove;
         },
         allChangeSets: (state) => _.values(state.changeSetsById),
         changeSetsNeedingApproval(): ChangeSet[] {
@@ -150,13 +149,14 @@ export function useChangeSetsStore() {
 
         async FETCH_CHANGE_SETS() {
           if (featureFlagsStore.REBAC) {
-            return new ApiRequest<WorkspaceMetadata>({
+            return new ApiRequest<WorkspaceView>({
               method: "get",
               url: BASE_API,
               onSuccess: (response) => {
                 this.headChangeSetId = response.defaultChangeSetId;
                 this.changeSetsById = _.keyBy(response.changeSets, "id");
                 this.approvers = response.approvers;
+                this.currentUserCanApprove = response.canApprove;
               },
             });
           } else {
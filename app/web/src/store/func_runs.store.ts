@@ -53,6 +53,7 @@ export enum FuncBackendKind {
   Unset,
   Validation,
   Management,
+  Wasm,
 }
 
 export enum FuncBackendResponseType {
@@ -125,6 +126,20 @@ export interface FuncRun {
   logs?: FuncRunLog;
 }
 
+export type FuncRunStatus =
+  | { state: "created" | "dispatched" | "running" | "postProcessing" }
+  | { state: "success" | "failure" | "killed"; durationMs: number };
+
+export interface FuncRunStatusEvent {
+  funcRunId: FuncRunId;
+  functionName: string;
+  functionKind: FuncKind;
+  componentId?: ComponentId;
+  actionId?: ActionId;
+  status: FuncRunStatus;
+  at: string;
+}
+
 export interface GetFuncRunResponse {
   funcRun?: FuncRun;
 }
@@ -145,6 +160,7 @@ export const useFuncRunsStore = () => {
       state: () => ({
         funcRuns: {} as Record<FuncRunId, FuncRun>,
         lastRuns: {} as Record<ActionId, Date>,
+        liveStatuses: {} as Record<FuncRunId, FuncRunStatusEvent>,
       }),
       actions: {
         async GET_FUNC_RUN(funcRunId: FuncRunId) {
@@ -172,6 +188,15 @@ export const useFuncRunsStore = () => {
                 this.lastRuns[payload.actionId] = new Date();
             },
           },
+          {
+            eventType: "FuncRunStatusChanged",
+            callback: (payload) => {
+              // events can arrive out of order, keep the latest one
+              const current = this.liveStatuses[payload.funcRunId];
+              if (current && new Date(current.at) > new Date(payload.at)) return;
+              this.liveStatuses[payload.funcRunId] = payload;
+            },
+          },
         ]);
 
         return () => {
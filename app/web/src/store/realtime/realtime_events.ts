@@ -16,7 +16,12 @@ import {
   SchemaVariantId,
 } from "@/api/sdf/dal/schema";
 import { ActionId } from "@/api/sdf/dal/action";
-import { ViewDescription, ViewId } from "@/api/sdf/dal/views";
+import {
+  Annotation,
+  AnnotationId,
+  ViewDescription,
+  ViewId,
+} from "@/api/sdf/dal/views";
 import { WorkspacePk } from "../workspaces.store";
 import { StatusUpdate } from "../status.store";
 import { CursorContainerKind } from "../presence.store";
@@ -24,12 +29,13 @@ import { UserId } from "../auth.store";
 import { SecretId } from "../secrets.store";
 import { FuncRunId } from "../actions.store";
 import { AwsCliCommand } from "../func/funcs.store";
-import { FuncRunLogId } from "../func_runs.store";
+import { FuncRunLogId, FuncRunStatusEvent } from "../func_runs.store";
 
 export type WebsocketRequest =
   | CursorRequest
   | OnlineRequest
-  | ComponentPositionRequest;
+  | ComponentPositionRequest
+  | SubscribeRequest;
 
 export interface CursorRequest {
   kind: "Cursor";
@@ -67,6 +73,17 @@ export interface ComponentPositionRequest {
   };
 }
 
+// replaces the server side filter of the events sent down the socket
+// any criteria left out (or empty) match all events
+export interface SubscribeRequest {
+  kind: "Subscribe";
+  data: {
+    changeSetId?: ChangeSetId | null;
+    eventKinds?: (keyof WsEventPayloadMap)[];
+    componentIds?: ComponentId[];
+  };
+}
+
 // TODO: a few of these use the same id objects (ex: componentId)
 // but in a few cases the change set ID may have been accidentally left out?
 // once things are working again, we should do a big review of all the realtime events coming from the backend...
@@ -85,6 +102,9 @@ export type WsEventPayloadMap = {
   ChangeSetCreated: string;
   ChangeSetWritten: string;
   ChangeSetCancelled: string;
+  ChangeSetApplyLocked: ChangeSetId;
+  ChangeSetApplyUnlocked: ChangeSetId;
+  ChangeSetQuarantined: ChangeSetId;
   Conflict: string;
 
   SetComponentPosition: {
@@ -366,6 +386,7 @@ export type WsEventPayloadMap = {
     funcRunLogId: FuncRunLogId;
     actionId?: ActionId;
   };
+  FuncRunStatusChanged: FuncRunStatusEvent;
   ViewUpdated: { view: ViewDescription };
   ViewDeleted: { viewId: ViewId };
   ViewCreated: { view: ViewDescription };
@@ -381,10 +402,24 @@ export type WsEventPayloadMap = {
   };
   ViewObjectCreated: { viewId: ViewId; viewObjectId: ViewId; geometry: IRect };
   ViewObjectRemoved: { viewId: ViewId; viewObjectId: ViewId };
+  AnnotationCreated: { changeSetId: ChangeSetId; annotation: Annotation };
+  AnnotationUpdated: { changeSetId: ChangeSetId; annotation: Annotation };
+  AnnotationDeleted: {
+    changeSetId: ChangeSetId;
+    viewId: ViewId;
+    annotationId: AnnotationId;
+  };
   AuditLogsPublished: {
     changeSetId: ChangeSetId;
     changeSetStatus: ChangeSetStatus;
   };
 
   PromptUpdated: { kind: string; overridden: boolean };
+
+  FeatureFlagUpdated: {
+    flag: string;
+    defaultEnabled: boolean;
+    overrideEnabled: boolean | null;
+    enabled: boolean;
+  };
 };
@@ -71,6 +71,14 @@ pub(crate) struct Args {
     #[arg(long, group = "bind")]
     pub(crate) bind_vsock: Option<String>,
 
+    /// Also serves executions over gRPC on a socket address [example: 0.0.0.0:5158]
+    #[arg(long)]
+    pub(crate) grpc_bind_addr: Option<SocketAddr>,
+
+    /// Requires gRPC calls to present this bearer token.
+    #[arg(long, env = "SI_CYCLONE_GRPC_AUTH_TOKEN", hide_env_values = true)]
+    pub(crate) grpc_auth_token: Option<String>,
+
     /// Enables active/watch behavior.
     #[arg(long, group = "watch")]
     pub(crate) enable_watch: bool,
@@ -115,6 +123,14 @@ pub(crate) struct Args {
     #[arg(long, group = "configuration")]
     pub(crate) disable_configuration: bool,
 
+    /// Enables the experimental WASM resolver function endpoint.
+    #[arg(long, group = "wasm")]
+    pub(crate) enable_wasm: bool,
+
+    /// Disables the experimental WASM resolver function endpoint.
+    #[arg(long, group = "wasm")]
+    pub(crate) disable_wasm: bool,
+
     /// Path to the lang server program.
     #[arg(long, env = "SI_LANG_SERVER", hide_env = true)]
     pub(crate) lang_server: PathBuf,
@@ -123,6 +139,25 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) lang_server_function_timeout: Option<usize>,
 
+    /// Kills a function execution after it has run for the given number of seconds.
+    #[arg(long)]
+    pub(crate) execution_max_wall_time_secs: Option<u64>,
+
+    /// Kills a function execution after its lang server process has used the given number of
+    /// seconds of CPU time.
+    #[arg(long)]
+    pub(crate) execution_max_cpu_time_secs: Option<u64>,
+
+    /// Kills a function execution when its lang server process uses more than the given number
+    /// of bytes of resident memory.
+    #[arg(long)]
+    pub(crate) execution_max_memory_bytes: Option<u64>,
+
+    /// Kills a function execution after it has streamed more than the given number of bytes of
+    /// output.
+    #[arg(long)]
+    pub(crate) execution_max_output_bytes: Option<u64>,
+
     /// Limits execution requests to 1 before shutting down
     #[arg(long, group = "request_limiting")]
     pub(crate) oneshot: bool,
@@ -173,8 +208,15 @@ impl TryFrom<Args> for Config {
             builder.incoming_stream(IncomingStream::VsockSocket(vsock_addr));
         }
 
+        builder.grpc_socket_addr(args.grpc_bind_addr);
+        builder.grpc_auth_token(args.grpc_auth_token.map(Into::into));
+
         builder.try_lang_server_path(args.lang_server)?;
         builder.lang_server_function_timeout(args.lang_server_function_timeout);
+        builder.execution_max_wall_time(args.execution_max_wall_time_secs.map(Duration::from_secs));
+        builder.execution_max_cpu_time(args.execution_max_cpu_time_secs.map(Duration::from_secs));
+        builder.execution_max_memory_bytes(args.execution_max_memory_bytes);
+        builder.execution_max_output_bytes(args.execution_max_output_bytes);
 
         if args.enable_watch {
             builder.watch(Some(Duration::from_secs(args.watch_timeout)));
@@ -194,6 +236,12 @@ impl TryFrom<Args> for Config {
             builder.enable_resolver(false);
         }
 
+        if args.enable_wasm {
+            builder.enable_wasm(true);
+        } else if args.disable_wasm {
+            builder.enable_wasm(false);
+        }
+
         if args.oneshot {
             builder.limit_requests(1);
         } else if let Some(limit_requests) = args.limit_requests {
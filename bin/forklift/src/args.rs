@@ -56,6 +56,10 @@ pub(crate) struct Args {
     )]
     pub(crate) log_json: bool,
 
+    /// Loads and validates the configuration, reporting every problem found, then exits.
+    #[arg(long = "validate-config", default_value = "false")]
+    pub(crate) validate_config: bool,
+
     /// The ID of this forklift instance [example: 01GWEAANW5BVFK5KDRVS6DEY0F"]
     #[arg(long)]
     pub(crate) instance_id: Option<String>,
@@ -50,7 +50,12 @@ async fn async_main() -> Result<()> {
     }
     debug!(arguments =?args, "parsed cli arguments");
 
+    let validate_config = args.validate_config;
     let config = Config::try_from(args)?;
+    if validate_config {
+        info!("configuration is valid");
+        return Ok(());
+    }
     debug!(?config, "computed configuration");
 
     let server = Server::from_config(config, main_token.clone()).await?;
@@ -1035,6 +1035,7 @@ export interface Asset {
   inputSockets: SocketDefinition[];
   outputSockets: SocketDefinition[];
   docLinks: Record<string, string>;
+  traits?: string[];
 }
 
 export interface IAssetBuilder {
@@ -1054,6 +1055,8 @@ export interface IAssetBuilder {
 
   addDocLink(key: string, value: string): this;
 
+  addTrait(name: string): this;
+
   build(): Asset;
 }
 
@@ -1232,6 +1235,23 @@ export class AssetBuilder implements IAssetBuilder {
     return this;
   }
 
+  /**
+   * Includes a workspace trait (a reusable set of props and sockets) in the
+   * asset. The trait is merged into the asset when it is regenerated.
+   *
+   * @param name - the name of the trait
+   * @returns This AssetBuilder instance for method chaining
+   */
+  addTrait(name: string) {
+    if (!this.asset.traits) {
+      this.asset.traits = [];
+    }
+    if (!this.asset.traits.includes(name)) {
+      this.asset.traits.push(name);
+    }
+    return this;
+  }
+
   build() {
     if (this.asset.secretDefinition && this.asset.outputSockets?.length > 1) {
       throw new Error(
@@ -15,6 +15,7 @@ import schema_variant_definition, {
   SchemaVariantDefinitionFunc,
 } from "./function_kinds/schema_variant_definition";
 import management_run, { ManagementFunc } from "./function_kinds/management";
+import discovery, { DiscoveryFunc } from "./function_kinds/discovery";
 import action_run, { ActionRunFunc } from "./function_kinds/action_run";
 import before from "./function_kinds/before";
 import { rawStorageRequest } from "./sandbox/requestStorage";
@@ -23,6 +24,7 @@ import { Debugger } from "./debug";
 export enum FunctionKind {
   ActionRun = "actionRun",
   Before = "before",
+  Discovery = "discovery",
   Management = "management",
   ResolverFunction = "resolverfunction",
   Validation = "validation",
@@ -166,6 +168,15 @@ export async function executeFunction(kind: FunctionKind, request: Request, time
         management_run,
       );
       break;
+    case FunctionKind.Discovery:
+      result = await executor(
+        ctx,
+        request as DiscoveryFunc,
+        kind,
+        timeout,
+        discovery,
+      );
+      break;
     default:
       throw Error(`Unknown Kind variant: ${kind}`);
   }
@@ -216,7 +227,9 @@ export async function executor<F extends Func, Result>(
 
   debug({ code });
 
-  const vm = createNodeVm(createSandbox(kind, ctx.executionId));
+  // Before functions only load secrets, so they are run with the full sandbox
+  const simulate = ctx.simulate && kind !== FunctionKind.Before;
+  const vm = createNodeVm(createSandbox(kind, ctx.executionId, { simulate }));
 
   debug({ timeout });
 
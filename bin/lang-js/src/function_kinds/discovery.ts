@@ -0,0 +1,117 @@
+import * as _ from "lodash-es";
+import { NodeVM } from "vm2";
+import { Debug } from "../debug";
+import {
+  failureExecution,
+  Func,
+  ResultFailure,
+  ResultSuccess,
+} from "../function";
+import { RequestCtx } from "../request";
+
+const debug = Debug("langJs:discovery");
+
+export interface DiscoveryFunc extends Func {
+  args: unknown;
+}
+
+export interface DiscoveredResource {
+  resourceId: string;
+  properties: object;
+  payload?: unknown;
+}
+
+export type DiscoveryResult = DiscoveryResultSuccess | DiscoveryResultFailure;
+
+export interface DiscoveryResultSuccess extends ResultSuccess {
+  resources: DiscoveredResource[];
+  health: "ok" | "warning" | "error";
+  message?: string;
+}
+
+export type DiscoveryResultFailure = ResultFailure;
+
+function isDiscoveredResource(resource: unknown): resource is DiscoveredResource {
+  return _.isPlainObject(resource)
+    && _.isString((resource as Record<string, unknown>).resourceId)
+    && _.isPlainObject((resource as Record<string, unknown>).properties);
+}
+
+async function execute(
+  vm: NodeVM,
+  { executionId }: RequestCtx,
+  { args }: DiscoveryFunc,
+  code: string,
+): Promise<DiscoveryResult> {
+  let discoveryResult: Record<string, unknown> | undefined | null;
+  try {
+    const runner = vm.run(code);
+    discoveryResult = await new Promise((resolve) => {
+      runner(args, (resolution: Record<string, unknown>) => resolve(resolution));
+    });
+  } catch (err) {
+    return failureExecution(err as Error, executionId);
+  }
+
+  const status = discoveryResult?.status;
+  if (
+    !_.isString(status)
+    || !["ok", "warning", "error"].includes(status)
+  ) {
+    return {
+      protocol: "result",
+      status: "failure",
+      executionId,
+      error: {
+        kind: "InvalidReturnType",
+        message:
+          "Discovery functions must return a status of either \"ok\", \"warning\" or \"error\"",
+      },
+    };
+  }
+
+  const resources = discoveryResult?.resources ?? [];
+  if (!_.isArray(resources) || !resources.every(isDiscoveredResource)) {
+    return {
+      protocol: "result",
+      status: "failure",
+      executionId,
+      error: {
+        kind: "InvalidReturnType",
+        message:
+          "Discovery functions must return resources as a list of objects with a resourceId string and a properties object",
+      },
+    };
+  }
+
+  return {
+    protocol: "result",
+    status: "success",
+    executionId,
+    resources,
+    health: status as "ok" | "warning" | "error",
+    message: discoveryResult?.message as string | undefined,
+  };
+}
+
+const wrapCode = (code: string, handle: string) => `
+module.exports = function(args, callback) {
+  ${code}
+  const returnValue = ${handle}(args);
+  if (returnValue instanceof Promise) {
+    returnValue.then((data) => callback(data))
+        .catch((err) => callback({
+          status: "error",
+          resources: [],
+          message: err.message,
+  }));
+  } else {
+    callback(returnValue);
+  }
+};`;
+
+export default {
+  debug,
+  execute,
+  wrapCode,
+};
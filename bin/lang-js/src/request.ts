@@ -1,5 +1,6 @@
 import { ActionRunFunc } from "./function_kinds/action_run";
 import { BeforeFunc } from "./function_kinds/before";
+import { DiscoveryFunc } from "./function_kinds/discovery";
 import { JoiValidationFunc } from "./function_kinds/joi_validation";
 import { ResolverFunc } from "./function_kinds/resolver_function";
 import { ManagementFunc } from "./function_kinds/management";
@@ -13,18 +14,23 @@ export type AnyFunction =
   | BeforeFunc
   | ResolverFunc
   | SchemaVariantDefinitionFunc
-  | ManagementFunc;
+  | ManagementFunc
+  | DiscoveryFunc;
 
 export type Request = AnyFunction &
 RequestCtx & {
   before?: BeforeFunc[];
   timeout?: number,
+  simulate?: boolean,
 };
 
 export interface RequestCtx {
   executionId: string;
+  // When set, the function is being run as a dry run and must not have side effects
+  simulate: boolean;
 }
 
-export const ctxFromRequest = ({ executionId }: Request): RequestCtx => ({
+export const ctxFromRequest = ({ executionId, simulate }: Request): RequestCtx => ({
   executionId,
+  simulate: simulate ?? false,
 });
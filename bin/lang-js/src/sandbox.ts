@@ -54,15 +54,85 @@ function schemaVariantDefinitionSandbox(): Sandbox {
   };
 }
 
+function simulationError(helper: string): Error {
+  const err = new Error(
+    `${helper} is not available while simulating an action, as it could have side effects`,
+  );
+  err.name = "SimulationSideEffect";
+  return err;
+}
+
+// Helpers which cannot reach outside of the function, and so can be used while simulating.
+// Anything else, including helpers added later, is denied by default.
+const SIMULATION_SAFE_HELPERS = new Set([
+  "console",
+  "_",
+  "Buffer",
+  "requestStorage",
+  "zlib",
+  "YAML",
+  "path",
+  "Joi",
+  "toml",
+  "jsonpatch",
+]);
+
+// Replaces every function reachable from a helper with one that throws, keeping the shape of
+// the helper so that functions can still check for what they need.
+function forbid(
+  helper: string,
+  value: unknown,
+  seen: WeakSet<object>,
+): unknown {
+  if (typeof value === "function") {
+    return () => {
+      throw simulationError(helper);
+    };
+  }
+  if (value && typeof value === "object" && !seen.has(value)) {
+    seen.add(value);
+    return Object.fromEntries(
+      Object.keys(value).map((key) => [
+        key,
+        forbid(
+          `${helper}.${key}`,
+          (value as Record<string, unknown>)[key],
+          seen,
+        ),
+      ]),
+    );
+  }
+  return value;
+}
+
+// Simulated actions must not touch the outside world, so every helper which could have side
+// effects is replaced with one that throws.
+function simulationSandbox(sandbox: Sandbox): Sandbox {
+  const seen = new WeakSet<object>();
+  return Object.fromEntries(
+    Object.entries(sandbox).map(([helper, value]) => [
+      helper,
+      SIMULATION_SAFE_HELPERS.has(helper)
+        ? value
+        : forbid(helper, value, seen),
+    ]),
+  );
+}
+
 function beforeFunctionSandbox(executionId: string): Sandbox {
   return {
     requestStorage: makeBeforeRequestStorage(executionId),
   };
 }
 
+export interface SandboxOptions {
+  simulate?: boolean;
+}
+
 export function createSandbox(
   kind: FunctionKind,
   executionId: string,
+  options: SandboxOptions = {},
 ): Sandbox {
   let sandbox = commonSandbox(executionId);
 
@@ -83,5 +153,9 @@ export function createSandbox(
       break;
   }
 
+  if (options.simulate) {
+    sandbox = simulationSandbox(sandbox);
+  }
+
   return sandbox;
 }
@@ -8,4 +8,27 @@ describe("createSandbox", () => {
     expect(sandbox).toHaveProperty("console");
     expect(sandbox).toHaveProperty("_");
   });
+
+  test("forbids side effect helpers when simulating", () => {
+    const sandbox = createSandbox(FunctionKind.ActionRun, "poop", {
+      simulate: true,
+    });
+    expect(sandbox).toHaveProperty("_");
+    expect(() => (sandbox.fetch as () => unknown)()).toThrow(/simulating/);
+    const siExec = sandbox.siExec as Record<string, () => unknown>;
+    expect(() => siExec.waitUntilEnd()).toThrow(/simulating/);
+    expect(() => siExec.watch()).toThrow(/simulating/);
+  });
+
+  test("forbids helpers which are not known to be safe when simulating", () => {
+    const sandbox = createSandbox(FunctionKind.ActionRun, "poop", {
+      simulate: true,
+    });
+    const fs = sandbox.fs as Record<string, () => unknown>;
+    expect(() => fs.writeFileSync()).toThrow(/fs.writeFileSync/);
+    const os = sandbox.os as Record<string, () => unknown>;
+    expect(() => os.setPriority()).toThrow(/simulating/);
+    const path = sandbox.path as typeof import("path");
+    expect(path.join("a", "b")).toBe("a/b");
+  });
 });
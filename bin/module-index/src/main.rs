@@ -52,7 +52,12 @@ async fn async_main() -> Result<()> {
     }
     debug!(arguments =?args, "parsed cli arguments");
 
+    let validate_config = args.validate_config;
     let config = Config::try_from(args)?;
+    if validate_config {
+        info!("configuration is valid");
+        return Ok(());
+    }
 
     let jwt_public_signing_key = Server::load_jwt_public_signing_key(&config).await?;
 
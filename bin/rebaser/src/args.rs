@@ -55,6 +55,10 @@ pub(crate) struct Args {
     )]
     pub(crate) log_json: bool,
 
+    /// Loads and validates the configuration, reporting every problem found, then exits.
+    #[arg(long = "validate-config", default_value = "false")]
+    pub(crate) validate_config: bool,
+
     /// PostgreSQL connection pool dbname [example: myapp]
     #[arg(long)]
     pub(crate) pg_dbname: Option<String>,
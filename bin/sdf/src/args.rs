@@ -63,6 +63,10 @@ pub(crate) struct Args {
     )]
     pub(crate) log_json: bool,
 
+    /// Loads and validates the configuration, reporting every problem found, then exits.
+    #[arg(long = "validate-config", default_value = "false")]
+    pub(crate) validate_config: bool,
+
     /// PostgreSQL connection pool dbname [example: myapp]
     #[arg(long)]
     pub(crate) pg_dbname: Option<String>,
@@ -236,6 +240,14 @@ pub(crate) struct Args {
     #[arg(long, env = "SI_ASSET_SPRAYER_PROMPTS_DIR")]
     pub(crate) asset_sprayer_prompts_dir: Option<String>,
 
+    /// Serve the web app embedded in the binary, for deployments without a separate web server
+    #[arg(long, env = "SI_SERVE_EMBEDDED_WEB")]
+    pub(crate) serve_embedded_web: bool,
+
+    /// Serve the read-only GraphQL API
+    #[arg(long, env = "SI_SERVE_GRAPHQL")]
+    pub(crate) serve_graphql: bool,
+
     /// Instance ID [example: 01GWEAANW5BVFK5KDRVS6DEY0F"]
     ///
     /// And instance ID is used when tracking the execution of jobs in a way that can be traced
@@ -412,6 +424,14 @@ impl TryFrom<Args> for Config {
                 );
             }
 
+            if args.serve_embedded_web {
+                config_map.set("serve_embedded_web", true);
+            }
+
+            if args.serve_graphql {
+                config_map.set("serve_graphql", true);
+            }
+
             config_map.set("nats.connection_name", NAME);
             config_map.set("pg.application_name", NAME);
             config_map.set("layer_db_config.pg_pool_config.application_name", NAME);
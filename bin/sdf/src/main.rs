@@ -7,7 +7,7 @@ use si_service::{
     color_eyre,
     prelude::*,
     rt, shutdown, startup,
-    telemetry_application::{self, TelemetryShutdownGuard},
+    telemetry_application::{self, ApplicationTelemetryClient, TelemetryShutdownGuard},
 };
 
 mod args;
@@ -88,10 +88,14 @@ async fn async_main() -> Result<()> {
         )
         .await
     } else {
+        let validate_config = args.validate_config;
         let config = Config::try_from(args)?;
         debug!(?config, "computed configuration");
 
-        if config.migration_mode().is_run_and_quit() {
+        if validate_config {
+            info!("configuration is valid");
+            Ok(())
+        } else if config.migration_mode().is_run_and_quit() {
             migrate_and_quit(
                 config,
                 main_tracker,
@@ -106,6 +110,7 @@ async fn async_main() -> Result<()> {
         } else {
             run_server(
                 config,
+                telemetry,
                 main_tracker,
                 main_token,
                 helping_tasks_tracker,
@@ -123,6 +128,7 @@ async fn async_main() -> Result<()> {
 #[allow(clippy::too_many_arguments)]
 async fn run_server(
     config: Config,
+    telemetry: ApplicationTelemetryClient,
     main_tracker: TaskTracker,
     main_token: CancellationToken,
     helping_tasks_tracker: TaskTracker,
@@ -139,6 +145,7 @@ async fn run_server(
         main_token.clone(),
         &helping_tasks_tracker,
         helping_tasks_token.clone(),
+        telemetry,
     )
     .await?;
 
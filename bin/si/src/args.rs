@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use ulid::Ulid;
+
+const NAME: &str = "si";
+
+/// Parse, validate, and return the CLI arguments as a typed struct.
+pub(crate) fn parse() -> Args {
+    Args::parse()
+}
+
+/// The System Initiative command line.
+///
+/// Operates on a workspace through the sdf API, authenticating with a workspace token, so that
+/// automation does not have to call the API by hand.
+#[derive(Parser, Debug)]
+#[command(name = NAME, max_term_width = 100)]
+pub(crate) struct Args {
+    /// The base URL of the sdf API [example: https://app.systeminit.com]
+    #[arg(long, env = "SI_API_URL", default_value = "http://localhost:8080")]
+    pub(crate) api_url: String,
+
+    /// The token to authenticate with, such as an automation token created with `si token create`
+    #[arg(long, env = "SI_API_TOKEN", hide_env_values = true)]
+    pub(crate) token: String,
+
+    /// The workspace to operate on
+    #[arg(long, env = "SI_WORKSPACE_ID")]
+    pub(crate) workspace_id: Ulid,
+
+    #[command(subcommand)]
+    pub(crate) command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Manage change sets
+    #[command(name = "changeset", subcommand)]
+    ChangeSet(ChangeSetCommand),
+    /// Manage modules
+    #[command(subcommand)]
+    Module(ModuleCommand),
+    /// Manage workspace tokens
+    #[command(subcommand)]
+    Token(TokenCommand),
+    /// Back up and restore the workspace
+    #[command(subcommand)]
+    Workspace(WorkspaceCommand),
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ChangeSetCommand {
+    /// Apply a change set to HEAD
+    Apply {
+        /// The change set to apply
+        change_set_id: Ulid,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ModuleCommand {
+    /// Install modules from the module index into a change set
+    Install {
+        /// The change set to install the modules into
+        #[arg(long)]
+        change_set_id: Ulid,
+        /// The modules to install
+        #[arg(required = true)]
+        module_ids: Vec<Ulid>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum TokenCommand {
+    /// Create an automation token and print it
+    Create {
+        /// The name of the token
+        name: String,
+        /// When the token expires [example: 2025-01-01T00:00:00Z]. Tokens without an expiry are
+        /// valid until revoked
+        #[arg(long)]
+        expires_at: Option<DateTime<Utc>>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum WorkspaceCommand {
+    /// Download a backup of the workspace
+    Export {
+        /// The file to write the backup to, instead of standard output
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Upload a backup and import it into the workspace
+    Import {
+        /// The backup to import, as written by `si workspace export`
+        file: PathBuf,
+    },
+}
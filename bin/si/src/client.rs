@@ -0,0 +1,193 @@
+//! A minimal client for the parts of the sdf API used by the subcommands.
+
+use chrono::{DateTime, Utc};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use reqwest::{header::HeaderMap, Method, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use ulid::Ulid;
+
+/// Chunks of an imported backup must fit in sdf's default request body limit (2 MiB).
+const IMPORT_CHUNK_SIZE: usize = 1024 * 1024;
+
+const UPLOAD_ID_HEADER: &str = "upload-id";
+const UPLOAD_OFFSET_HEADER: &str = "upload-offset";
+const UPLOAD_LENGTH_HEADER: &str = "upload-length";
+const FORCE_CHANGE_SET_HEADER: &str = "force_change_set_id";
+
+#[derive(Clone, Debug)]
+pub(crate) struct SdfClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: String,
+    workspace_id: Ulid,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportStatus {
+    pub(crate) upload_id: Ulid,
+    pub(crate) offset: u64,
+    pub(crate) complete: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreatedToken {
+    pub(crate) jwt: String,
+}
+
+/// The result of installing modules, along with the change set sdf created for them if they were
+/// installed into HEAD.
+#[derive(Debug)]
+pub(crate) struct InstalledModules {
+    pub(crate) variants: Value,
+    pub(crate) force_change_set_id: Option<String>,
+}
+
+impl SdfClient {
+    pub(crate) fn new(
+        api_url: impl Into<String>,
+        token: impl Into<String>,
+        workspace_id: Ulid,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url: api_url.into().trim_end_matches('/').to_owned(),
+            token: token.into(),
+            workspace_id,
+        }
+    }
+
+    /// Downloads a backup of the workspace.
+    pub(crate) async fn export_workspace(&self) -> Result<Vec<u8>> {
+        let response = self
+            .send(self.workspace_request(Method::GET, "/export"))
+            .await?;
+
+        Ok(response
+            .bytes()
+            .await
+            .wrap_err("failed to read workspace backup")?
+            .to_vec())
+    }
+
+    /// Uploads a backup in chunks. Once the last chunk is received, sdf imports the backup
+    /// asynchronously.
+    pub(crate) async fn import_workspace(&self, backup: &[u8]) -> Result<ImportStatus> {
+        let mut upload_id = None;
+        let mut offset = 0;
+        loop {
+            let end = (offset + IMPORT_CHUNK_SIZE).min(backup.len());
+            let mut request = self
+                .workspace_request(Method::POST, "/import")
+                .header(UPLOAD_OFFSET_HEADER, offset)
+                .body(backup[offset..end].to_vec());
+            request = match upload_id {
+                Some(upload_id) => request.header(UPLOAD_ID_HEADER, upload_id.to_string()),
+                None => request.header(UPLOAD_LENGTH_HEADER, backup.len()),
+            };
+
+            let status: ImportStatus = Self::json(self.send(request).await?).await?;
+            if status.complete {
+                return Ok(status);
+            }
+            if status.offset as usize <= offset {
+                return Err(eyre!(
+                    "sdf did not accept the chunk at offset {offset} of upload {}",
+                    status.upload_id
+                ));
+            }
+            upload_id = Some(status.upload_id);
+            offset = status.offset as usize;
+        }
+    }
+
+    /// Applies the change set to HEAD, returning the metrics of the apply.
+    pub(crate) async fn apply_change_set(&self, change_set_id: Ulid) -> Result<Value> {
+        let request =
+            self.workspace_request(Method::POST, format!("/change-sets/{change_set_id}/apply"));
+
+        Self::json(self.send(request).await?).await
+    }
+
+    pub(crate) async fn create_token(
+        &self,
+        name: impl Into<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<CreatedToken> {
+        let request = self
+            .workspace_request(Method::POST, "/tokens")
+            .json(&json!({ "name": name.into(), "expiresAt": expires_at }));
+
+        Self::json(self.send(request).await?).await
+    }
+
+    /// Installs modules from the module index. Module installs are not part of the v2 API yet, so
+    /// this uses the original route.
+    pub(crate) async fn install_modules(
+        &self,
+        change_set_id: Ulid,
+        module_ids: &[Ulid],
+    ) -> Result<InstalledModules> {
+        let request = self
+            .request(Method::POST, "/api/module/install_module")
+            .json(&json!({
+                "ids": module_ids,
+                "visibility_change_set_pk": change_set_id,
+            }));
+        let response = self.send(request).await?;
+        let force_change_set_id = header_str(response.headers(), FORCE_CHANGE_SET_HEADER);
+
+        Ok(InstalledModules {
+            variants: Self::json(response).await?,
+            force_change_set_id,
+        })
+    }
+
+    fn request(&self, method: Method, path: impl AsRef<str>) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", self.api_url, path.as_ref()))
+            .bearer_auth(&self.token)
+    }
+
+    /// Starts a request for a path of the workspace in the v2 API, such as `/export`.
+    fn workspace_request(&self, method: Method, path: impl AsRef<str>) -> RequestBuilder {
+        self.request(
+            method,
+            format!("/api/v2/workspaces/{}{}", self.workspace_id, path.as_ref()),
+        )
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request
+            .send()
+            .await
+            .wrap_err("failed to send request to sdf")?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        // sdf describes errors in the body, which is more useful than the status alone
+        let body = response.text().await.unwrap_or_default();
+        Err(eyre!("sdf responded with {status}: {body}"))
+    }
+
+    async fn json<T: DeserializeOwned>(response: Response) -> Result<T> {
+        response
+            .json()
+            .await
+            .wrap_err("failed to deserialize sdf response")
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(ToOwned::to_owned)
+}
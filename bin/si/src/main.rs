@@ -0,0 +1,68 @@
+use std::io::Write;
+
+use color_eyre::{eyre::WrapErr, Result};
+
+use crate::{
+    args::{ChangeSetCommand, Command, ModuleCommand, TokenCommand, WorkspaceCommand},
+    client::SdfClient,
+};
+
+mod args;
+mod client;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = args::parse();
+    let client = SdfClient::new(args.api_url, args.token, args.workspace_id);
+
+    match args.command {
+        Command::ChangeSet(ChangeSetCommand::Apply { change_set_id }) => {
+            let metrics = client.apply_change_set(change_set_id).await?;
+            print_json(&metrics)?;
+        }
+        Command::Module(ModuleCommand::Install {
+            change_set_id,
+            module_ids,
+        }) => {
+            let installed = client.install_modules(change_set_id, &module_ids).await?;
+            if let Some(force_change_set_id) = installed.force_change_set_id {
+                eprintln!("installed into new change set {force_change_set_id}");
+            }
+            print_json(&installed.variants)?;
+        }
+        Command::Token(TokenCommand::Create { name, expires_at }) => {
+            // Only the token itself is printed, so that it can be captured by scripts
+            let token = client.create_token(name, expires_at).await?;
+            println!("{}", token.jwt);
+        }
+        Command::Workspace(WorkspaceCommand::Export { output }) => {
+            let backup = client.export_workspace().await?;
+            match output {
+                Some(path) => tokio::fs::write(&path, backup)
+                    .await
+                    .wrap_err_with(|| format!("failed to write {}", path.display()))?,
+                None => std::io::stdout()
+                    .write_all(&backup)
+                    .wrap_err("failed to write backup")?,
+            }
+        }
+        Command::Workspace(WorkspaceCommand::Import { file }) => {
+            let backup = tokio::fs::read(&file)
+                .await
+                .wrap_err_with(|| format!("failed to read {}", file.display()))?;
+            let status = client.import_workspace(&backup).await?;
+            eprintln!(
+                "uploaded backup, importing it as upload {}",
+                status.upload_id
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn print_json(value: &serde_json::Value) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
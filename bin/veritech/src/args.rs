@@ -55,6 +55,10 @@ pub(crate) struct Args {
     )]
     pub(crate) log_json: bool,
 
+    /// Loads and validates the configuration, reporting every problem found, then exits.
+    #[arg(long = "validate-config", default_value = "false")]
+    pub(crate) validate_config: bool,
+
     /// NATS connection URL [example: 0.0.0.0:4222]
     #[arg(long, short = 'u')]
     pub(crate) nats_url: Option<String>,
@@ -99,6 +103,10 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) concurrency: Option<u32>,
 
+    /// The number of concurrent functions a single workspace can execute [default: unlimited]
+    #[arg(long)]
+    pub(crate) workspace_concurrency: Option<u32>,
+
     /// Instance ID [example: 01GWEAANW5BVFK5KDRVS6DEY0F"]
     ///
     /// And instance ID is used when tracking the execution of jobs in a way that can be traced
@@ -149,6 +157,9 @@ impl TryFrom<Args> for Config {
             if let Some(concurrency) = args.concurrency {
                 config_map.set("concurrency_limit", i64::from(concurrency));
             }
+            if let Some(concurrency) = args.workspace_concurrency {
+                config_map.set("workspace_concurrency_limit", i64::from(concurrency));
+            }
             if let Some(instance_id) = args.instance_id {
                 config_map.set("instance_id", instance_id);
             }
@@ -98,6 +98,22 @@ pub struct AuditLogRow {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Filters for [querying](AuditLogRow::query) the audit logs of a workspace. Every filter that is
+/// set must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLogFilter {
+    /// Only include logs for actions taken by the actor.
+    pub actor: Option<Actor>,
+    /// Only include logs for the entity type (e.g. "Component").
+    pub entity_type: Option<String>,
+    /// Only include logs for the change set.
+    pub change_set_id: Option<ChangeSetId>,
+    /// Only include logs at or after the timestamp.
+    pub from: Option<DateTime<Utc>>,
+    /// Only include logs before the timestamp.
+    pub to: Option<DateTime<Utc>>,
+}
+
 impl AuditLogRow {
     /// Inserts a new row into the audit logs table of the audit database.
     #[allow(clippy::too_many_arguments)]
@@ -216,6 +232,86 @@ impl AuditLogRow {
 
         Ok((result, can_load_more))
     }
+
+    /// Queries rows of the audit logs table in the audit database for a workspace, newest first
+    /// unless sorting ascending. Returns at most `limit` rows, along with the cursor for the next
+    /// page if there are more rows. The cursor is opaque and only valid for the same filter and
+    /// sort order.
+    #[instrument(
+        name = "audit_log.database.query",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn query(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        filter: &AuditLogFilter,
+        cursor: Option<i64>,
+        limit: usize,
+        sort_ascending: bool,
+    ) -> Result<(Vec<Self>, Option<i64>)> {
+        let (system_actor, user_id) = match filter.actor {
+            None => (false, None),
+            Some(Actor::System) => (true, None),
+            Some(Actor::User(user_id)) => (false, Some(user_id.to_string())),
+        };
+        // Fetch an extra row to find out whether there is a next page
+        let fetch_limit = limit.saturating_add(1) as i64;
+
+        // The pk breaks ties between logs with the same timestamp, which keeps the cursor stable
+        let (comparison, direction) = if sort_ascending {
+            (">", "ASC")
+        } else {
+            ("<", "DESC")
+        };
+        let query = format!(
+            "SELECT * FROM audit_logs
+            WHERE workspace_id = $1
+                AND ($2::text IS NULL OR change_set_id = $2)
+                AND ($3::text IS NULL OR entity_type = $3)
+                AND ($4::timestamptz IS NULL OR timestamp >= $4)
+                AND ($5::timestamptz IS NULL OR timestamp < $5)
+                AND (NOT $6 OR user_id IS NULL)
+                AND ($7::text IS NULL OR user_id = $7)
+                AND ($8::bigint IS NULL OR (timestamp, pk) {comparison}
+                    (SELECT timestamp, pk FROM audit_logs WHERE pk = $8))
+            ORDER BY timestamp {direction}, pk {direction}
+            LIMIT $9"
+        );
+
+        let rows = context
+            .pg_pool()
+            .get()
+            .await?
+            .query(
+                &query,
+                &[
+                    &workspace_id.to_string(),
+                    &filter.change_set_id.map(|id| id.to_string()),
+                    &filter.entity_type,
+                    &filter.from,
+                    &filter.to,
+                    &system_actor,
+                    &user_id,
+                    &cursor,
+                    &fetch_limit,
+                ],
+            )
+            .await?;
+
+        let has_more = rows.len() > limit;
+        let mut result = Vec::with_capacity(rows.len().min(limit));
+        let mut last_pk = None;
+        for row in rows.into_iter().take(limit) {
+            last_pk = Some(row.try_get("pk")?);
+            result.push(Self::try_from(row)?);
+        }
+
+        Ok((result, if has_more { last_pk } else { None }))
+    }
 }
 
 impl TryFrom<PgRow> for AuditLogRow {
@@ -0,0 +1,3 @@
+CREATE INDEX audit_logs_workspace_timestamp ON audit_logs (workspace_id, timestamp DESC, pk DESC);
+CREATE INDEX audit_logs_workspace_user_timestamp ON audit_logs (workspace_id, user_id, timestamp DESC);
+CREATE INDEX audit_logs_workspace_entity_type_timestamp ON audit_logs (workspace_id, entity_type, timestamp DESC);
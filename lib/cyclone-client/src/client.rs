@@ -11,7 +11,7 @@ use std::{
 use async_trait::async_trait;
 use cyclone_core::{
     CycloneRequest, CycloneRequestable, LivenessStatus, LivenessStatusParseError, ReadinessStatus,
-    ReadinessStatusParseError,
+    ReadinessStatusParseError, RuntimeVersion, RuntimeVersionParseError,
 };
 use http::{
     request::Builder,
@@ -36,7 +36,11 @@ use tokio_tungstenite::{
     WebSocketStream,
 };
 
-use crate::{new_unstarted_execution, ping, watch, Execution, PingExecution, Watch};
+use crate::{
+    new_unstarted_execution, ping,
+    pool::{ConnectionPool, PoolMetrics},
+    watch, Execution, PingExecution, Watch,
+};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -51,6 +55,8 @@ pub enum ClientError {
     InvalidLivenessStatus(#[from] LivenessStatusParseError),
     #[error("invalid readiness status")]
     InvalidReadinessStatus(#[from] ReadinessStatusParseError),
+    #[error("invalid runtime version")]
+    InvalidRuntimeVersion(#[from] RuntimeVersionParseError),
     #[error("invalid URI")]
     InvalidUri(#[from] InvalidUri),
     #[error("invalid websocket uri scheme: {0}")]
@@ -100,6 +106,7 @@ pub struct Client<Conn, Strm, Sock> {
     connector: Conn,
     socket: Sock,
     uri: Uri,
+    pool: Arc<ConnectionPool<Strm>>,
     _phantom: PhantomData<Strm>,
 }
 
@@ -115,6 +122,7 @@ where
             connector: self.connector.clone(),
             socket: self.socket.clone(),
             uri: self.uri.clone(),
+            pool: self.pool.clone(),
             _phantom: PhantomData,
         }
     }
@@ -134,6 +142,9 @@ where
 
     async fn readiness(&mut self) -> result::Result<ReadinessStatus, ClientError>;
 
+    /// The version of the lang server, or `None` if the server could not determine it.
+    async fn lang_server_version(&mut self) -> result::Result<Option<RuntimeVersion>, ClientError>;
+
     async fn execute_ping(&mut self) -> result::Result<PingExecution<Strm>, ClientError>;
 
     async fn prepare_execution<Request>(
@@ -164,6 +175,10 @@ impl Client<(), (), ()> {
             .build()
             .map_err(ClientError::ClientUri)?;
         let config = Arc::new(ClientConfig::default());
+        let pool = Arc::new(ConnectionPool::new(
+            config.pool_max_idle,
+            config.pool_idle_timeout,
+        ));
 
         Ok(Client {
             config,
@@ -171,6 +186,7 @@ impl Client<(), (), ()> {
             connector,
             socket,
             uri,
+            pool,
             _phantom: PhantomData,
         })
     }
@@ -195,6 +211,10 @@ impl Client<(), (), ()> {
             .path_and_query("/")
             .build()
             .map_err(ClientError::ClientUri)?;
+        let pool = Arc::new(ConnectionPool::new(
+            config.pool_max_idle,
+            config.pool_idle_timeout,
+        ));
 
         Ok(Client {
             config,
@@ -202,6 +222,7 @@ impl Client<(), (), ()> {
             connector,
             socket,
             uri,
+            pool,
             _phantom: PhantomData,
         })
     }
@@ -214,7 +235,7 @@ where
     Conn::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
     Conn::Future: Unpin + Send,
     Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
-    Sock: Send + Sync + std::fmt::Debug,
+    Sock: Clone + Send + Sync + std::fmt::Debug + 'static,
 {
     async fn watch(&mut self) -> Result<Watch<Strm>> {
         let stream = self.websocket_stream("/watch").await?;
@@ -249,6 +270,22 @@ where
         Ok(result)
     }
 
+    async fn lang_server_version(&mut self) -> Result<Option<RuntimeVersion>> {
+        let response = self.get("/lang-server/version").await?;
+
+        match response.status() {
+            StatusCode::OK => {}
+            StatusCode::NOT_FOUND => return Ok(None),
+            status => return Err(ClientError::UnexpectedStatusCode(status)),
+        }
+        let body = body::to_bytes(response)
+            .await
+            .map_err(ClientError::ReadResponseBody)?;
+        let result = RuntimeVersion::from_str(str::from_utf8(body.as_ref())?)?;
+
+        Ok(Some(result))
+    }
+
     async fn execute_ping(&mut self) -> Result<PingExecution<Strm>> {
         let stream = self.websocket_stream("/execute/ping").await?;
         Ok(ping::execute(stream))
@@ -272,7 +309,7 @@ where
     Conn::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
     Conn::Future: Unpin + Send,
     Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
-    Sock: Send + Sync + std::fmt::Debug,
+    Sock: Clone + Send + Sync + std::fmt::Debug + 'static,
 {
     fn http_request_uri<P>(&self, path_and_query: P) -> Result<Uri>
     where
@@ -351,7 +388,61 @@ where
         self.inner_client.request(req)
     }
 
+    /// Returns a snapshot of the connection pool counters.
+    pub fn pool_metrics(&self) -> PoolMetrics {
+        self.pool.metrics()
+    }
+
+    /// Connects streams until the connection pool is full, returning how many were added.
+    ///
+    /// This is a no-op when pooling is disabled via [`ClientConfig::pool_max_idle`], or when the
+    /// pool is already being refilled.
+    pub async fn fill_pool(&mut self) -> Result<usize> {
+        match self.pool.try_start_refill() {
+            Some(_refilling) => self.refill_pool().await,
+            None => Ok(0),
+        }
+    }
+
+    async fn refill_pool(&mut self) -> Result<usize> {
+        let mut added = 0;
+        for _ in 0..self.pool.deficit() {
+            let stream = self.connect_new().await?;
+            if !self.pool.checkin(stream) {
+                break;
+            }
+            added += 1;
+        }
+
+        Ok(added)
+    }
+
     async fn connect(&mut self) -> Result<Strm> {
+        if !self.pool.is_enabled() {
+            return self.connect_new().await;
+        }
+
+        let stream = match self.pool.checkout() {
+            Some(stream) => stream,
+            None => self.connect_new().await?,
+        };
+
+        // Top the pool back up off the execution path, so the next execution finds a stream. A
+        // running refill will already top it up, so there is never more than one at a time.
+        if let Some(refilling) = self.pool.try_start_refill() {
+            let mut client = self.clone();
+            tokio::spawn(async move {
+                let _refilling = refilling;
+                if let Err(err) = client.refill_pool().await {
+                    debug!(si.error.message = ?err, "failed to refill cyclone connection pool");
+                }
+            });
+        }
+
+        Ok(stream)
+    }
+
+    async fn connect_new(&mut self) -> Result<Strm> {
         let mut stream = self
             .connector
             .call(self.uri.clone())
@@ -426,6 +517,10 @@ where
 pub struct ClientConfig {
     pub connect_timeout: Duration,
     pub firecracker_connect: bool,
+    /// How long a pooled connection may sit unused before it is discarded.
+    pub pool_idle_timeout: Duration,
+    /// How many connected streams to keep ready for upcoming executions. Zero disables pooling.
+    pub pool_max_idle: usize,
     pub watch_timeout: Duration,
 }
 
@@ -435,6 +530,8 @@ impl Default for ClientConfig {
             connect_timeout: Duration::from_millis(10),
             // firecracker-setup: change firecracker_connect to "true"
             firecracker_connect: false,
+            pool_idle_timeout: Duration::from_secs(30),
+            pool_max_idle: 0,
             watch_timeout: Duration::from_secs(10),
         }
     }
@@ -448,11 +545,12 @@ mod tests {
     use base64::{engine::general_purpose, Engine};
     use buck2_resources::Buck2Resources;
     use cyclone_core::{
-        ActionRunRequest, ComponentKind, ComponentView, ComponentViewWithGeometry, FunctionResult,
-        ManagementRequest, ProgressMessage, ResolverFunctionComponent, ResolverFunctionRequest,
+        ActionRunRequest, ComponentKind, ComponentView, ComponentViewWithGeometry,
+        DiscoveryRequest, FunctionResult, ManagementRequest, Message, ProgressMessage,
+        ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResultSuccess,
         SchemaVariantDefinitionRequest, ValidationRequest,
     };
-    use cyclone_server::{Config, ConfigBuilder, Runnable as _, Server};
+    use cyclone_server::{Config, ConfigBuilder, JsonCodec, Runnable as _, Server};
     use futures::StreamExt;
     use serde_json::json;
     use tempfile::{NamedTempFile, TempPath};
@@ -729,6 +827,41 @@ mod tests {
         }
     }
 
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_ping_with_pool() {
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let server = uds_server(builder.enable_ping(true), &tmp_socket).await;
+        let path = server
+            .local_socket()
+            .as_domain_socket()
+            .expect("expected a domain socket")
+            .to_owned();
+        tokio::spawn(async move { server.run().await });
+        let config = Arc::new(ClientConfig {
+            pool_max_idle: 2,
+            ..Default::default()
+        });
+        let mut client = Client::uds(path, config).expect("failed to create uds client");
+
+        assert_eq!(2, client.fill_pool().await.expect("failed to fill pool"));
+
+        for _ in 0..3 {
+            client
+                .execute_ping()
+                .await
+                .expect("failed to establish websocket stream")
+                .start()
+                .await
+                .expect("failed to start protocol");
+        }
+
+        let metrics = client.pool_metrics();
+        assert!(metrics.hits >= 2, "unexpected pool metrics: {metrics:?}");
+        assert_eq!(0, metrics.evictions);
+    }
+
     #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
     #[test(tokio::test)]
     async fn http_execute_resolver() {
@@ -764,6 +897,8 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            min_runtime_version: None,
+            backend: Default::default(),
         };
 
         // Start the protocol
@@ -865,6 +1000,8 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            min_runtime_version: None,
+            backend: Default::default(),
         };
 
         // Start the protocol
@@ -939,6 +1076,7 @@ mod tests {
             validation_format: r#"{"type":"number","flags":{"presence":"required"},"rules":[{"name":"integer"},{"name":"min","args":{"limit":33}},{"name":"max","args":{"limit":33}}]}"#.to_string(),
             code_base64: "".to_string(),
             before: vec![],
+            min_runtime_version: None,
         };
         let mut progress = client
             .prepare_execution(CycloneRequest::from_parts(req, Default::default()))
@@ -1007,6 +1145,8 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            min_runtime_version: None,
+            simulate: false,
         };
 
         // Start the protocol
@@ -1073,6 +1213,73 @@ mod tests {
         }
     }
 
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn http_execute_action_run_streams_sequenced_and_chunked_output() {
+        let mut builder = Config::builder();
+        let mut client = http_client_for_running_server(builder.enable_action_run(true)).await;
+
+        let req = ActionRunRequest {
+            execution_id: "1234".to_string(),
+            handler: "workit".to_string(),
+            args: Default::default(),
+            code_base64: base64_encode(
+                r#"function workit() {
+                    console.log('first');
+                    console.log('x'.repeat(100000));
+                    console.log('last');
+                    return { status: 'ok' };
+                }"#,
+            ),
+            before: vec![],
+            min_runtime_version: None,
+            simulate: false,
+        };
+
+        let mut progress = client
+            .prepare_execution(CycloneRequest::from_parts(req, Default::default()))
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        let mut outputs = vec![];
+        loop {
+            match progress.next().await {
+                Some(Ok(ProgressMessage::OutputStream(output))) => outputs.push(output),
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(Err(err)) => panic!("failed to receive output: err={err:?}"),
+                None => break,
+            }
+        }
+        let result = progress.finish().await.expect("failed to return result");
+        assert!(matches!(result, FunctionResult::Success(_)));
+
+        // Every message is numbered in the order it was sent
+        let sequences: Vec<u64> = outputs.iter().map(|output| output.sequence).collect();
+        let expected: Vec<u64> = (0..outputs.len() as u64).collect();
+        assert_eq!(expected, sequences);
+
+        // The long line is split into chunks which add back up to the line
+        assert_eq!("first", outputs[0].message);
+        assert_eq!(None, outputs[0].chunk);
+        let chunks: Vec<_> = outputs
+            .iter()
+            .filter(|output| output.chunk.is_some())
+            .collect();
+        assert_eq!(2, chunks.len());
+        for (index, chunk) in chunks.iter().enumerate() {
+            let output_chunk = chunk.chunk.expect("chunk is set");
+            assert_eq!(index as u32, output_chunk.index);
+            assert_eq!(2, output_chunk.count);
+        }
+        let line: String = chunks.iter().map(|chunk| chunk.message.as_str()).collect();
+        assert_eq!("x".repeat(100_000), line);
+        assert_eq!("last", outputs[3].message);
+        assert_eq!(None, outputs[3].chunk);
+    }
+
     #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
     #[test(tokio::test)]
     async fn uds_execute_action_run() {
@@ -1093,6 +1300,8 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            min_runtime_version: None,
+            simulate: false,
         };
 
         // Start the protocol
@@ -1181,6 +1390,7 @@ mod tests {
                     return new AssetBuilder().build();
                 }"#,
             ),
+            min_runtime_version: None,
         };
 
         // Start the protocol
@@ -1258,6 +1468,7 @@ mod tests {
                     return new AssetBuilder().build();
                 }"#,
             ),
+            min_runtime_version: None,
         };
 
         // Start the protocol
@@ -1343,6 +1554,7 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            min_runtime_version: None,
         };
 
         // Start the protocol
@@ -1428,6 +1640,7 @@ mod tests {
                 }"#,
             ),
             before: vec![],
+            min_runtime_version: None,
         };
 
         // Start the protocol
@@ -1483,4 +1696,365 @@ mod tests {
             }
         }
     }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_discovery_func() {
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let mut client =
+            uds_client_for_running_server(builder.enable_discovery(true), &tmp_socket).await;
+
+        let req = DiscoveryRequest {
+            execution_id: "1234".to_string(),
+            handler: "discover".to_string(),
+            args: json!({ "region": "us-east-2" }),
+            code_base64: base64_encode(
+                r#"function discover(args) {
+                    console.log('first');
+                    return {
+                        status: 'ok',
+                        resources: [{
+                            resourceId: 'i-1234',
+                            properties: { region: args.region },
+                        }],
+                    };
+                }"#,
+            ),
+            before: vec![],
+            min_runtime_version: None,
+        };
+
+        // Start the protocol
+        let mut progress = client
+            .prepare_execution(CycloneRequest::from_parts(req, Default::default()))
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        // Consume the output messages
+        loop {
+            match progress.next().await {
+                Some(Ok(ProgressMessage::OutputStream(output))) => {
+                    assert_eq!(output.message, "first");
+                    break;
+                }
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(Err(err)) => panic!("failed to receive 'first' output: err={err:?}"),
+                None => panic!("output stream ended early"),
+            };
+        }
+        loop {
+            match progress.next().await {
+                None => break,
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(unexpected) => panic!("output stream should be done: {unexpected:?}"),
+            };
+        }
+        // Get the result
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Success(success) => {
+                assert_eq!(1, success.resources.len());
+                assert_eq!("i-1234", success.resources[0].resource_id);
+                assert_eq!(
+                    json!({ "region": "us-east-2" }),
+                    success.resources[0].properties
+                );
+            }
+            FunctionResult::Failure(failure) => {
+                panic!("result should be success; failure={failure:?}")
+            }
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_cancel_execution() {
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let mut client =
+            uds_client_for_running_server(builder.enable_resolver(true), &tmp_socket).await;
+
+        let req = ResolverFunctionRequest {
+            execution_id: "1234".to_string(),
+            handler: "doit".to_string(),
+            component: ResolverFunctionComponent {
+                data: ComponentView {
+                    properties: serde_json::json!({}),
+                    kind: ComponentKind::Standard,
+                },
+                parents: vec![],
+            },
+            response_type: cyclone_core::ResolverFunctionResponseType::Object,
+            code_base64: base64_encode(
+                r#"async function doit(input) {
+                    console.log('started');
+                    await new Promise((resolve) => setTimeout(resolve, 60000));
+                    return { a: 'b' };
+                }"#,
+            ),
+            before: vec![],
+            min_runtime_version: None,
+            backend: Default::default(),
+        };
+
+        // Start the protocol
+        let mut progress = client
+            .prepare_execution(CycloneRequest::from_parts(req, Default::default()))
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        // Wait until the function is running
+        loop {
+            match progress.next().await {
+                Some(Ok(ProgressMessage::OutputStream(output))) => {
+                    assert_eq!(output.message, "started");
+                    break;
+                }
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(Err(err)) => panic!("failed to receive 'started' output: err={err:?}"),
+                None => panic!("output stream ended early"),
+            };
+        }
+
+        progress.cancel().await.expect("failed to cancel execution");
+
+        // The server is still healthy after killing the lang server
+        let status = client.liveness().await.expect("failed to get liveness");
+        assert_eq!(status, LivenessStatus::Ok);
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn grpc_execute_resolver() {
+        // Find a free port for the gRPC socket, which is bound when the server runs
+        let grpc_addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("failed to find a free socket addr");
+        let mut builder = Config::builder();
+        let server = http_server(
+            builder
+                .enable_resolver(true)
+                .grpc_socket_addr(Some(grpc_addr))
+                .grpc_auth_token(Some("sekrit".into()))
+                .limit_requests(1),
+        )
+        .await;
+        let server = tokio::spawn(async move { server.run().await });
+
+        let endpoint = tonic::transport::Endpoint::from_shared(format!("http://{grpc_addr}"))
+            .expect("failed to build grpc endpoint");
+        let mut attempts = 0;
+        let channel = loop {
+            match endpoint.connect().await {
+                Ok(channel) => break channel,
+                Err(_) if attempts < 50 => {
+                    attempts += 1;
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                Err(err) => panic!("failed to connect to grpc server: {err:?}"),
+            }
+        };
+        let mut grpc = tonic::client::Grpc::new(channel);
+        let path =
+            tonic::codegen::http::uri::PathAndQuery::from_static("/cyclone.Execution/Resolver");
+        let codec = || {
+            JsonCodec::<
+                CycloneRequest<ResolverFunctionRequest>,
+                Message<ResolverFunctionResultSuccess>,
+            >::default()
+        };
+        let req = || {
+            CycloneRequest::from_parts(
+                ResolverFunctionRequest {
+                    execution_id: "1234".to_string(),
+                    handler: "doit".to_string(),
+                    component: ResolverFunctionComponent {
+                        data: ComponentView {
+                            properties: serde_json::json!({"salt": "n"}),
+                            kind: ComponentKind::Standard,
+                        },
+                        parents: vec![],
+                    },
+                    response_type: cyclone_core::ResolverFunctionResponseType::Object,
+                    code_base64: base64_encode(
+                        r#"function doit(input) {
+                            return { a: 'b' };
+                        }"#,
+                    ),
+                    before: vec![],
+                    min_runtime_version: None,
+                    backend: Default::default(),
+                },
+                Default::default(),
+            )
+        };
+
+        // A call without the auth token is rejected and does not count against the limit
+        grpc.ready().await.expect("grpc channel not ready");
+        let status = grpc
+            .server_streaming(tonic::Request::new(req()), path.clone(), codec())
+            .await
+            .expect_err("unauthenticated call should be rejected");
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let mut request = tonic::Request::new(req());
+        request.metadata_mut().insert(
+            "authorization",
+            "Bearer sekrit".parse().expect("failed to parse metadata"),
+        );
+        grpc.ready().await.expect("grpc channel not ready");
+        let mut messages = grpc
+            .server_streaming(request, path, codec())
+            .await
+            .expect("failed to call resolver")
+            .into_inner();
+
+        assert_eq!(
+            Some(Message::Start),
+            messages.message().await.expect("failed to read message")
+        );
+        let mut succeeded = false;
+        while let Some(message) = messages.message().await.expect("failed to read message") {
+            match message {
+                Message::Result(FunctionResult::Success(success)) => {
+                    assert_eq!(success.data, json!({"a": "b"}));
+                    succeeded = true;
+                }
+                Message::Finish => break,
+                Message::Heartbeat | Message::OutputStream(_) => continue,
+                unexpected => panic!("unexpected msg kind: {unexpected:?}"),
+            }
+        }
+        assert!(succeeded, "resolver function did not return a result");
+        drop(messages);
+        drop(grpc);
+
+        // The gRPC call was the only request allowed, so the server shuts itself down
+        tokio::time::timeout(Duration::from_secs(10), server)
+            .await
+            .expect("server did not shut down after reaching its request limit")
+            .expect("server task panicked")
+            .expect("server failed");
+    }
+
+    fn sleeping_resolver_request() -> CycloneRequest<ResolverFunctionRequest> {
+        CycloneRequest::from_parts(
+            ResolverFunctionRequest {
+                execution_id: "1234".to_string(),
+                handler: "doit".to_string(),
+                component: ResolverFunctionComponent {
+                    data: ComponentView {
+                        properties: serde_json::json!({}),
+                        kind: ComponentKind::Standard,
+                    },
+                    parents: vec![],
+                },
+                response_type: cyclone_core::ResolverFunctionResponseType::Object,
+                code_base64: base64_encode(
+                    r#"async function doit(input) {
+                        console.log('started');
+                        await new Promise((resolve) => setTimeout(resolve, 60000));
+                        return { a: 'b' };
+                    }"#,
+                ),
+                before: vec![],
+                min_runtime_version: None,
+                backend: Default::default(),
+            },
+            Default::default(),
+        )
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_cancel_execution_ends_request() {
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let server = uds_server(builder.enable_resolver(true).limit_requests(1), &tmp_socket).await;
+        let path = server
+            .local_socket()
+            .as_domain_socket()
+            .expect("expected a domain socket")
+            .to_owned();
+        let server = tokio::spawn(async move { server.run().await });
+        let mut client = Client::uds(path, Arc::new(ClientConfig::default()))
+            .expect("failed to create uds client");
+
+        let mut progress = client
+            .prepare_execution(sleeping_resolver_request())
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+        loop {
+            match progress.next().await {
+                Some(Ok(ProgressMessage::OutputStream(output))) => {
+                    assert_eq!(output.message, "started");
+                    break;
+                }
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(Err(err)) => panic!("failed to receive 'started' output: err={err:?}"),
+                None => panic!("output stream ended early"),
+            };
+        }
+
+        progress.cancel().await.expect("failed to cancel execution");
+
+        // The function would sleep for a minute, so the request limit is only reached this soon
+        // if cancelling killed the lang server
+        tokio::time::timeout(Duration::from_secs(10), server)
+            .await
+            .expect("cancelled execution did not end its request")
+            .expect("server task panicked")
+            .expect("server failed");
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_abandoned_execution_ends_request() {
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let server = uds_server(builder.enable_resolver(true).limit_requests(1), &tmp_socket).await;
+        let path = server
+            .local_socket()
+            .as_domain_socket()
+            .expect("expected a domain socket")
+            .to_owned();
+        let server = tokio::spawn(async move { server.run().await });
+        let mut client = Client::uds(path, Arc::new(ClientConfig::default()))
+            .expect("failed to create uds client");
+
+        let mut progress = client
+            .prepare_execution(sleeping_resolver_request())
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+        loop {
+            match progress.next().await {
+                Some(Ok(ProgressMessage::OutputStream(_))) => break,
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(Err(err)) => panic!("failed to receive 'started' output: err={err:?}"),
+                None => panic!("output stream ended early"),
+            };
+        }
+
+        // Going away without a cancel message is treated as a cancellation too
+        drop(progress);
+
+        tokio::time::timeout(Duration::from_secs(10), server)
+            .await
+            .expect("abandoned execution did not end its request")
+            .expect("server task panicked")
+            .expect("server failed");
+    }
 }
@@ -4,7 +4,9 @@ use std::{
     task::{Context, Poll},
 };
 
-use cyclone_core::{CycloneRequest, CycloneRequestable, FunctionResult, Message, ProgressMessage};
+use cyclone_core::{
+    ControlMessage, CycloneRequest, CycloneRequestable, FunctionResult, Message, ProgressMessage,
+};
 use futures::{Future, SinkExt, Stream, StreamExt};
 use hyper::client::connect::Connection;
 use serde::{de::DeserializeOwned, Serialize};
@@ -128,6 +130,24 @@ where
     pub async fn finish(self) -> Result<FunctionResult<Success>, ExecutionError<Success>> {
         ExecutionClosing::try_from(self)?.finish().await
     }
+
+    /// Asks the server to kill the lang server process of the execution, then closes the web
+    /// socket without waiting for a result.
+    pub async fn cancel(mut self) -> Result<(), ExecutionError<Success>> {
+        let msg = ControlMessage::Cancel
+            .serialize_to_string()
+            .map_err(ExecutionError::JSONSerialize)?;
+        self.stream
+            .send(WebSocketMessage::Text(msg))
+            .await
+            .map_err(ExecutionError::WSSendIO)?;
+        self.stream
+            .close(None)
+            .await
+            .map_err(ExecutionError::WSSendIO)?;
+
+        Ok(())
+    }
 }
 
 impl<T, Success> Stream for ExecutionStarted<T, Success>
@@ -1,19 +1,21 @@
 mod client;
 mod execution;
 mod ping;
+mod pool;
 mod watch;
 
 pub use client::{Client, ClientConfig, ClientError, CycloneClient, HttpClient, UdsClient};
 pub use cyclone_core::{
     ActionRunRequest, ActionRunResultSuccess, CycloneRequest, LivenessStatus,
     LivenessStatusParseError, ReadinessStatus, ReadinessStatusParseError, ResolverFunctionRequest,
-    ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
+    ResolverFunctionResultSuccess, RuntimeVersion, SchemaVariantDefinitionRequest,
     SchemaVariantDefinitionResultSuccess, SensitiveStrings,
 };
 pub use execution::{new_unstarted_execution, Execution, ExecutionError};
 pub use hyper::client::connect::Connection;
 pub use hyperlocal::UnixStream;
 pub use ping::{PingExecution, PingExecutionError};
+pub use pool::PoolMetrics;
 pub use tokio_tungstenite::tungstenite::{
     protocol::frame::CloseFrame as WebSocketCloseFrame, Message as WebSocketMessage,
 };
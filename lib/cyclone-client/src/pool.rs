@@ -0,0 +1,259 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::task::noop_waker_ref;
+use telemetry::prelude::*;
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// A snapshot of the connection pool counters of a [`Client`](crate::Client).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolMetrics {
+    /// Number of connected streams currently waiting in the pool.
+    pub idle: usize,
+    /// Number of connections which were served from the pool.
+    pub hits: u64,
+    /// Number of connections which had to be established on demand.
+    pub misses: u64,
+    /// Number of pooled streams which were dropped as expired or broken.
+    pub evictions: u64,
+}
+
+struct IdleStream<Strm> {
+    stream: Strm,
+    since: Instant,
+}
+
+/// A pool of connected, but otherwise unused, streams to a single cyclone instance.
+///
+/// As every execution consumes its stream, the pool is topped up again after each checkout, which
+/// moves the cost of connecting (including the Firecracker connect handshake) off the execution
+/// path. Only one refill runs at a time, see [`ConnectionPool::try_start_refill`].
+pub(crate) struct ConnectionPool<Strm> {
+    idle: Mutex<VecDeque<IdleStream<Strm>>>,
+    max_idle: usize,
+    idle_timeout: Duration,
+    refilling: AtomicBool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<Strm> fmt::Debug for ConnectionPool<Strm> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionPool")
+            .field("max_idle", &self.max_idle)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("metrics", &self.metrics())
+            .finish()
+    }
+}
+
+impl<Strm> ConnectionPool<Strm> {
+    pub(crate) fn new(max_idle: usize, idle_timeout: Duration) -> Self {
+        Self {
+            idle: Mutex::new(VecDeque::with_capacity(max_idle)),
+            max_idle,
+            idle_timeout,
+            refilling: AtomicBool::new(false),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.max_idle > 0
+    }
+
+    /// Adds a freshly connected stream to the pool, returning `false` if the pool is already full
+    /// (in which case the stream is dropped).
+    pub(crate) fn checkin(&self, stream: Strm) -> bool {
+        let mut idle = self.lock_idle();
+        if idle.len() >= self.max_idle {
+            return false;
+        }
+        idle.push_back(IdleStream {
+            stream,
+            since: Instant::now(),
+        });
+
+        true
+    }
+
+    /// Returns how many streams are missing for the pool to be full.
+    pub(crate) fn deficit(&self) -> usize {
+        self.max_idle.saturating_sub(self.lock_idle().len())
+    }
+
+    /// Marks the pool as being refilled, returning `None` if a refill is already running.
+    ///
+    /// The pool counts as being refilled until the returned guard is dropped.
+    pub(crate) fn try_start_refill(self: &Arc<Self>) -> Option<RefillGuard<Strm>> {
+        self.refilling
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| RefillGuard { pool: self.clone() })
+    }
+
+    pub(crate) fn metrics(&self) -> PoolMetrics {
+        PoolMetrics {
+            idle: self.lock_idle().len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock_idle(&self) -> std::sync::MutexGuard<'_, VecDeque<IdleStream<Strm>>> {
+        // The lock is never held across an await or a panic-prone call, so recover from poisoning
+        self.idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<Strm> ConnectionPool<Strm>
+where
+    Strm: AsyncRead + Unpin,
+{
+    /// Takes the oldest healthy stream out of the pool, evicting any expired or broken streams
+    /// found on the way.
+    pub(crate) fn checkout(&self) -> Option<Strm> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let mut idle = self.lock_idle();
+        while let Some(mut candidate) = idle.pop_front() {
+            if candidate.since.elapsed() < self.idle_timeout && is_healthy(&mut candidate.stream) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Some(candidate.stream);
+            }
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+            trace!("evicted expired or broken pooled cyclone connection");
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        None
+    }
+}
+
+/// Marks a [`ConnectionPool`] as being refilled for as long as it is held.
+pub(crate) struct RefillGuard<Strm> {
+    pool: Arc<ConnectionPool<Strm>>,
+}
+
+impl<Strm> Drop for RefillGuard<Strm> {
+    fn drop(&mut self) {
+        self.pool.refilling.store(false, Ordering::Release);
+    }
+}
+
+/// Checks whether an idle stream is still usable without blocking.
+///
+/// A healthy idle stream has nothing to read yet. A stream which reports end-of-file or an error
+/// was closed by the server, and any unsolicited data means its state is unknown, so neither may be
+/// handed out.
+fn is_healthy<Strm>(stream: &mut Strm) -> bool
+where
+    Strm: AsyncRead + Unpin,
+{
+    let mut cx = Context::from_waker(noop_waker_ref());
+    let mut byte = [0u8; 1];
+    let mut buf = ReadBuf::new(&mut byte);
+
+    matches!(Pin::new(stream).poll_read(&mut cx, &mut buf), Poll::Pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{duplex, AsyncWriteExt, DuplexStream};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn checkout_returns_pooled_stream() {
+        let pool = ConnectionPool::new(2, Duration::from_secs(60));
+        let (client, _server) = duplex(64);
+
+        assert!(pool.checkin(client));
+        assert!(pool.checkout().is_some());
+        assert_eq!(
+            PoolMetrics {
+                idle: 0,
+                hits: 1,
+                misses: 0,
+                evictions: 0,
+            },
+            pool.metrics()
+        );
+    }
+
+    #[tokio::test]
+    async fn checkin_is_bounded() {
+        let pool = ConnectionPool::new(1, Duration::from_secs(60));
+        let (first, _first_server) = duplex(64);
+        let (second, _second_server) = duplex(64);
+
+        assert!(pool.checkin(first));
+        assert!(!pool.checkin(second));
+        assert_eq!(0, pool.deficit());
+    }
+
+    #[tokio::test]
+    async fn checkout_evicts_broken_streams() {
+        let pool = ConnectionPool::new(3, Duration::from_secs(60));
+
+        let (closed, closed_server) = duplex(64);
+        drop(closed_server);
+        let (chatty, mut chatty_server) = duplex(64);
+        chatty_server
+            .write_all(b"surprise")
+            .await
+            .expect("failed to write");
+        let (healthy, _healthy_server) = duplex(64);
+
+        assert!(pool.checkin(closed));
+        assert!(pool.checkin(chatty));
+        assert!(pool.checkin(healthy));
+
+        assert!(pool.checkout().is_some());
+        assert_eq!(2, pool.metrics().evictions);
+        assert!(pool.checkout().is_none());
+        assert_eq!(1, pool.metrics().misses);
+    }
+
+    #[test]
+    fn refills_run_one_at_a_time() {
+        let pool = Arc::new(ConnectionPool::<DuplexStream>::new(
+            2,
+            Duration::from_secs(60),
+        ));
+
+        let refilling = pool.try_start_refill();
+        assert!(refilling.is_some());
+        assert!(pool.try_start_refill().is_none());
+
+        drop(refilling);
+        assert!(pool.try_start_refill().is_some());
+    }
+
+    #[tokio::test]
+    async fn checkout_evicts_expired_streams() {
+        let pool = ConnectionPool::new(1, Duration::ZERO);
+        let (client, _server) = duplex(64);
+
+        assert!(pool.checkin(client));
+        assert!(pool.checkout().is_none());
+        assert_eq!(1, pool.metrics().evictions);
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 use telemetry_utils::metric;
 
-use crate::{BeforeFunction, CycloneRequestable};
+use crate::{BeforeFunction, CycloneRequestable, RuntimeVersion};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +12,14 @@ pub struct ActionRunRequest {
     pub code_base64: String,
     pub args: serde_json::Value,
     pub before: Vec<BeforeFunction>,
+    /// The oldest lang server version which can run the function, for functions which use newer
+    /// sandbox APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_runtime_version: Option<RuntimeVersion>,
+    /// Runs the function as a dry run: the function is told that it is being simulated and the
+    /// sandbox refuses helpers with side effects, such as `siExec` and `fetch`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub simulate: bool,
 }
 
 #[remain::sorted]
@@ -42,6 +50,10 @@ impl CycloneRequestable for ActionRunRequest {
         &self.execution_id
     }
 
+    fn min_runtime_version(&self) -> Option<&RuntimeVersion> {
+        self.min_runtime_version.as_ref()
+    }
+
     fn websocket_path(&self) -> &str {
         "/execute/command"
     }
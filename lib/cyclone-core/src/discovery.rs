@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+
+use crate::{BeforeFunction, CycloneRequestable, ResourceStatus, RuntimeVersion};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryRequest {
+    pub execution_id: String,
+    pub handler: String,
+    pub code_base64: String,
+    /// Narrows down what is discovered, such as the region to list resources in.
+    pub args: serde_json::Value,
+    /// Sets up the credentials which are used to list the resources.
+    pub before: Vec<BeforeFunction>,
+    /// The oldest lang server version which can run the function, for functions which use newer
+    /// sandbox APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_runtime_version: Option<RuntimeVersion>,
+}
+
+/// An existing cloud resource which could be imported as a component.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredResource {
+    pub resource_id: String,
+    /// The properties of the component which would represent the resource.
+    pub properties: serde_json::Value,
+    /// The resource payload, as a refresh action would have returned it.
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryResultSuccess {
+    pub execution_id: String,
+    pub resources: Vec<DiscoveredResource>,
+    pub status: ResourceStatus,
+    pub message: Option<String>,
+    // Collects the error if the function throws
+    pub error: Option<String>,
+}
+
+impl CycloneRequestable for DiscoveryRequest {
+    type Response = DiscoveryResultSuccess;
+
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn min_runtime_version(&self) -> Option<&RuntimeVersion> {
+        self.min_runtime_version.as_ref()
+    }
+
+    fn websocket_path(&self) -> &str {
+        "/execute/discovery"
+    }
+
+    fn inc_run_metric(&self) {
+        metric!(counter.function_run.discovery = 1);
+    }
+
+    fn dec_run_metric(&self) {
+        metric!(counter.function_run.discovery = -1);
+    }
+}
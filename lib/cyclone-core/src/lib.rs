@@ -15,6 +15,7 @@ mod action_run;
 mod before;
 mod canonical_command;
 mod component_view;
+mod discovery;
 mod kill_execution;
 mod liveness;
 mod management;
@@ -23,6 +24,7 @@ mod progress;
 mod readiness;
 mod request;
 mod resolver_function;
+mod runtime_version;
 mod schema_variant_definition;
 mod sensitive_container;
 mod validation;
@@ -33,19 +35,22 @@ pub use action_run::{ActionRunRequest, ActionRunResultSuccess, ResourceStatus};
 pub use before::BeforeFunction;
 pub use canonical_command::{CanonicalCommand, CanonicalCommandError};
 pub use component_view::{ComponentKind, ComponentView, ComponentViewWithGeometry};
+pub use discovery::{DiscoveredResource, DiscoveryRequest, DiscoveryResultSuccess};
 pub use kill_execution::KillExecutionRequest;
 pub use liveness::{LivenessStatus, LivenessStatusParseError};
 pub use management::{ManagementFuncStatus, ManagementRequest, ManagementResultSuccess};
 pub use progress::{
-    FunctionResult, FunctionResultFailure, FunctionResultFailureError,
-    FunctionResultFailureErrorKind, Message, OutputStream, ProgressMessage,
+    ControlMessage, ExecutionLimit, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, FunctionResultFailureErrorKind, Message, OutputChunk, OutputStream,
+    ProgressMessage,
 };
 pub use readiness::{ReadinessStatus, ReadinessStatusParseError};
 pub use request::{CycloneRequest, CycloneRequestable};
 pub use resolver_function::{
-    ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResponseType,
-    ResolverFunctionResultSuccess,
+    ResolverFunctionBackend, ResolverFunctionComponent, ResolverFunctionRequest,
+    ResolverFunctionResponseType, ResolverFunctionResultSuccess,
 };
+pub use runtime_version::{RuntimeVersion, RuntimeVersionParseError};
 pub use schema_variant_definition::{
     SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
 };
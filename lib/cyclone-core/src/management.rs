@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 use telemetry_utils::metric;
 
-use crate::{component_view::ComponentViewWithGeometry, BeforeFunction, CycloneRequestable};
+use crate::{
+    component_view::ComponentViewWithGeometry, BeforeFunction, CycloneRequestable, RuntimeVersion,
+};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,6 +18,10 @@ pub struct ManagementRequest {
     pub this_component: ComponentViewWithGeometry,
     pub components: HashMap<String, ComponentViewWithGeometry>,
     pub before: Vec<BeforeFunction>,
+    /// The oldest lang server version which can run the function, for functions which use newer
+    /// sandbox APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_runtime_version: Option<RuntimeVersion>,
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -42,6 +48,10 @@ impl CycloneRequestable for ManagementRequest {
         &self.execution_id
     }
 
+    fn min_runtime_version(&self) -> Option<&RuntimeVersion> {
+        self.min_runtime_version.as_ref()
+    }
+
     fn websocket_path(&self) -> &str {
         "/execute/management"
     }
@@ -33,6 +33,67 @@ pub struct OutputStream {
     ///
     /// The timestamp generated locally when the message was created.
     pub timestamp: u64,
+    /// The position of the message in the output of its execution, starting at `0`.
+    ///
+    /// Lets consumers detect missing output and restore the order of output which was delivered
+    /// out of order. Producers which do not track the position leave it at `0`.
+    #[serde(default)]
+    pub sequence: u64,
+    /// Set if the line was too long to be sent as one message and was split into chunks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk: Option<OutputChunk>,
+}
+
+impl OutputStream {
+    /// Splits the message into chunks of at most `max_bytes` bytes, without splitting characters.
+    ///
+    /// Each chunk is returned as its own [`OutputStream`] with a [`OutputChunk`] describing its
+    /// place in the original line. Messages which fit are returned as they are.
+    pub fn into_chunks(mut self, max_bytes: usize) -> Vec<Self> {
+        if self.message.len() <= max_bytes || max_bytes == 0 {
+            return vec![self];
+        }
+
+        let message = std::mem::take(&mut self.message);
+        let mut messages = Vec::new();
+        let mut rest = message.as_str();
+        while !rest.is_empty() {
+            let mut end = max_bytes.min(rest.len());
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            // A single character is wider than the chunk size, so send it whole
+            if end == 0 {
+                end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+            }
+            let (chunk, remainder) = rest.split_at(end);
+            messages.push(chunk.to_owned());
+            rest = remainder;
+        }
+
+        let count = messages.len() as u32;
+        messages
+            .into_iter()
+            .enumerate()
+            .map(|(index, message)| Self {
+                message,
+                chunk: Some(OutputChunk {
+                    index: index as u32,
+                    count,
+                }),
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// The place of an [`OutputStream`] message in a line which was split into chunks.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone, Copy)]
+pub struct OutputChunk {
+    /// The index of the chunk, starting at `0`.
+    pub index: u32,
+    /// The number of chunks the line was split into.
+    pub count: u32,
 }
 
 /// A message produced as a function is executing.
@@ -89,6 +150,24 @@ where
     }
 }
 
+/// A message sent by the client over the web socket of an execution once it has started.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ControlMessage {
+    /// Asks the server to kill the lang server process and abandon the execution.
+    Cancel,
+}
+
+impl ControlMessage {
+    pub fn deserialize_from_str(s: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    pub fn serialize_to_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
 #[remain::sorted]
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum FunctionResult<S> {
@@ -150,10 +229,27 @@ pub enum FunctionResultFailureErrorKind {
     ActionFieldWrongType,
     InvalidReturnType,
     KilledExecution,
+    /// The execution was killed for exceeding one of its resource limits.
+    LimitExceeded(ExecutionLimit),
+    UnsupportedRuntimeVersion,
     UserCodeException(String),
     VeritechServer,
 }
 
+/// A resource limit enforced around a single function execution.
+#[remain::sorted]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Display)]
+pub enum ExecutionLimit {
+    /// The CPU time spent by the lang server process, in user and kernel mode.
+    MaxCpuTime,
+    /// The resident memory of the lang server process.
+    MaxMemory,
+    /// The total size of the output lines streamed by the function.
+    MaxOutputBytes,
+    /// The time from starting the lang server process until the function returns a result.
+    MaxWallTime,
+}
+
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone)]
 pub struct FunctionResultFailureError {
     pub kind: FunctionResultFailureErrorKind,
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use si_crypto::SensitiveStrings;
 use si_std::SensitiveString;
 
+use crate::RuntimeVersion;
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CycloneRequest<R>
@@ -38,6 +40,10 @@ pub trait CycloneRequestable {
     type Response;
 
     fn execution_id(&self) -> &str;
+    /// The oldest lang server version which can run the request, if it needs a specific one.
+    fn min_runtime_version(&self) -> Option<&RuntimeVersion> {
+        None
+    }
     fn websocket_path(&self) -> &str;
     fn inc_run_metric(&self);
     fn dec_run_metric(&self);
@@ -1,4 +1,4 @@
-use crate::{before::BeforeFunction, request::CycloneRequestable};
+use crate::{before::BeforeFunction, request::CycloneRequestable, RuntimeVersion};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use telemetry::prelude::*;
@@ -15,6 +15,33 @@ pub struct ResolverFunctionRequest {
     pub response_type: ResolverFunctionResponseType,
     pub code_base64: String,
     pub before: Vec<BeforeFunction>,
+    /// The oldest lang server version which can run the function, for functions which use newer
+    /// sandbox APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_runtime_version: Option<RuntimeVersion>,
+    /// What runs the function. The code of WASM functions is a base64 encoded WASI module.
+    #[serde(default, skip_serializing_if = "ResolverFunctionBackend::is_js")]
+    pub backend: ResolverFunctionBackend,
+}
+
+/// What runs the code of a resolver function.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolverFunctionBackend {
+    /// JavaScript, run by the lang server.
+    #[default]
+    Js,
+    /// (Experimental) A WASI module, run by Cyclone itself. The module reads the request from
+    /// stdin and writes lang server messages to stdout, one per line.
+    Wasm,
+}
+
+impl ResolverFunctionBackend {
+    #[must_use]
+    pub fn is_js(&self) -> bool {
+        matches!(self, Self::Js)
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Default)]
@@ -62,8 +89,15 @@ impl CycloneRequestable for ResolverFunctionRequest {
         &self.execution_id
     }
 
+    fn min_runtime_version(&self) -> Option<&RuntimeVersion> {
+        self.min_runtime_version.as_ref()
+    }
+
     fn websocket_path(&self) -> &str {
-        "/execute/resolver"
+        match self.backend {
+            ResolverFunctionBackend::Js => "/execute/resolver",
+            ResolverFunctionBackend::Wasm => "/execute/wasm/resolver",
+        }
     }
 
     fn inc_run_metric(&self) {
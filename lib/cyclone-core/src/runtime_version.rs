@@ -0,0 +1,77 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("failed to parse '{0}' into RuntimeVersion")]
+pub struct RuntimeVersionParseError(String);
+
+/// The version of the lang server which runs functions, e.g. `1.2.3`.
+///
+/// Functions may declare the minimum version they need, for when they use sandbox APIs that older
+/// lang servers do not provide. Pre-release and build suffixes are ignored when comparing.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RuntimeVersion {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl RuntimeVersion {
+    #[must_use]
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Whether this version is at least the required version.
+    #[must_use]
+    pub fn satisfies(&self, required: &RuntimeVersion) -> bool {
+        self >= required
+    }
+}
+
+impl fmt::Display for RuntimeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for RuntimeVersion {
+    type Err = RuntimeVersionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let version = trimmed.strip_prefix('v').unwrap_or(trimmed);
+        let version = version.split(['-', '+']).next().unwrap_or(version);
+
+        let mut parts = version.split('.').map(str::parse::<u64>);
+        let (major, minor, patch) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), None, None, None) => (major, 0, 0),
+            (Some(Ok(major)), Some(Ok(minor)), None, None) => (major, minor, 0),
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => (major, minor, patch),
+            _ => return Err(RuntimeVersionParseError(s.to_string())),
+        };
+
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+impl TryFrom<String> for RuntimeVersion {
+    type Error = RuntimeVersionParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<RuntimeVersion> for String {
+    fn from(value: RuntimeVersion) -> Self {
+        value.to_string()
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 use telemetry_utils::metric;
 
-use crate::request::CycloneRequestable;
+use crate::{request::CycloneRequestable, RuntimeVersion};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +10,10 @@ pub struct SchemaVariantDefinitionRequest {
     pub execution_id: String,
     pub handler: String,
     pub code_base64: String,
+    /// The oldest lang server version which can run the function, for functions which use newer
+    /// sandbox APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_runtime_version: Option<RuntimeVersion>,
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -29,6 +33,10 @@ impl CycloneRequestable for SchemaVariantDefinitionRequest {
         &self.execution_id
     }
 
+    fn min_runtime_version(&self) -> Option<&RuntimeVersion> {
+        self.min_runtime_version.as_ref()
+    }
+
     fn websocket_path(&self) -> &str {
         "/execute/schema_variant_definition"
     }
@@ -1,4 +1,4 @@
-use crate::{request::CycloneRequestable, BeforeFunction};
+use crate::{request::CycloneRequestable, BeforeFunction, RuntimeVersion};
 use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 use telemetry_utils::metric;
@@ -12,6 +12,10 @@ pub struct ValidationRequest {
     pub value: Option<serde_json::Value>,
     pub validation_format: String,
     pub before: Vec<BeforeFunction>,
+    /// The oldest lang server version which can run the function, for functions which use newer
+    /// sandbox APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_runtime_version: Option<RuntimeVersion>,
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -28,6 +32,10 @@ impl CycloneRequestable for ValidationRequest {
         &self.execution_id
     }
 
+    fn min_runtime_version(&self) -> Option<&RuntimeVersion> {
+        self.min_runtime_version.as_ref()
+    }
+
     fn websocket_path(&self) -> &str {
         "/execute/validation"
     }
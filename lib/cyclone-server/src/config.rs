@@ -5,9 +5,11 @@ use std::{
 };
 
 use derive_builder::Builder;
-use si_std::{CanonicalFile, CanonicalFileError};
+use si_std::{CanonicalFile, CanonicalFileError, SensitiveString};
 use thiserror::Error;
 
+use crate::execution::ExecutionLimits;
+
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -46,9 +48,21 @@ pub struct Config {
     #[builder(default = "true")]
     enable_management: bool,
 
+    #[builder(default = "true")]
+    enable_discovery: bool,
+
+    #[builder(default = "false")]
+    enable_wasm: bool,
+
     #[builder(default = "IncomingStream::default()")]
     incoming_stream: IncomingStream,
 
+    #[builder(default)]
+    grpc_socket_addr: Option<SocketAddr>,
+
+    #[builder(default)]
+    grpc_auth_token: Option<SensitiveString>,
+
     #[builder(try_setter, setter(into))]
     lang_server_path: CanonicalFile,
 
@@ -58,6 +72,18 @@ pub struct Config {
     #[builder(default)]
     lang_server_process_timeout: Option<u64>,
 
+    #[builder(default)]
+    execution_max_wall_time: Option<Duration>,
+
+    #[builder(default)]
+    execution_max_cpu_time: Option<Duration>,
+
+    #[builder(default)]
+    execution_max_memory_bytes: Option<u64>,
+
+    #[builder(default)]
+    execution_max_output_bytes: Option<u64>,
+
     #[builder(setter(into), default)]
     limit_requests: Option<u32>,
 
@@ -117,12 +143,37 @@ impl Config {
         self.enable_management
     }
 
+    /// Gets the config's enable discovery
+    #[must_use]
+    pub fn enable_discovery(&self) -> bool {
+        self.enable_discovery
+    }
+
+    /// Gets the config's enable wasm, for the experimental WASM resolver function endpoint.
+    #[must_use]
+    pub fn enable_wasm(&self) -> bool {
+        self.enable_wasm
+    }
+
     /// Gets a reference to the config's incoming stream.
     #[must_use]
     pub fn incoming_stream(&self) -> &IncomingStream {
         &self.incoming_stream
     }
 
+    /// Gets the config's socket address for the gRPC execution service, which is only served when
+    /// set.
+    #[must_use]
+    pub fn grpc_socket_addr(&self) -> Option<SocketAddr> {
+        self.grpc_socket_addr
+    }
+
+    /// Gets a reference to the config's bearer token which gRPC calls must present, if any.
+    #[must_use]
+    pub fn grpc_auth_token(&self) -> Option<&SensitiveString> {
+        self.grpc_auth_token.as_ref()
+    }
+
     /// Gets a reference to the config's lang server path.
     #[must_use]
     pub fn lang_server_path(&self) -> &Path {
@@ -141,6 +192,43 @@ impl Config {
         self.lang_server_process_timeout
     }
 
+    /// Gets the config's maximum wall time for a single function execution.
+    #[must_use]
+    pub fn execution_max_wall_time(&self) -> Option<Duration> {
+        self.execution_max_wall_time
+    }
+
+    /// Gets the config's maximum CPU time of the lang server process for a single function
+    /// execution.
+    #[must_use]
+    pub fn execution_max_cpu_time(&self) -> Option<Duration> {
+        self.execution_max_cpu_time
+    }
+
+    /// Gets the config's maximum resident memory of the lang server process for a single function
+    /// execution.
+    #[must_use]
+    pub fn execution_max_memory_bytes(&self) -> Option<u64> {
+        self.execution_max_memory_bytes
+    }
+
+    /// Gets the config's maximum number of output bytes streamed by a single function execution.
+    #[must_use]
+    pub fn execution_max_output_bytes(&self) -> Option<u64> {
+        self.execution_max_output_bytes
+    }
+
+    /// Gets the config's resource limits for a single function execution.
+    #[must_use]
+    pub fn execution_limits(&self) -> ExecutionLimits {
+        ExecutionLimits {
+            max_wall_time: self.execution_max_wall_time,
+            max_cpu_time: self.execution_max_cpu_time,
+            max_memory_bytes: self.execution_max_memory_bytes,
+            max_output_bytes: self.execution_max_output_bytes,
+        }
+    }
+
     /// Gets a reference to the config's limit requests.
     #[must_use]
     pub fn limit_requests(&self) -> Option<u32> {
@@ -12,10 +12,11 @@ use axum::extract::ws::WebSocket;
 use bytes_lines_codec::BytesLinesCodec;
 use cyclone_core::{
     process::{self, ShutdownError},
-    CycloneRequest, CycloneRequestable, FunctionResult, FunctionResultFailure,
-    FunctionResultFailureError, FunctionResultFailureErrorKind, Message, OutputStream,
+    ControlMessage, CycloneRequest, CycloneRequestable, ExecutionLimit, FunctionResult,
+    FunctionResultFailure, FunctionResultFailureError, FunctionResultFailureErrorKind, Message,
+    OutputStream,
 };
-use futures::{SinkExt, StreamExt, TryStreamExt};
+use futures::{future, Future, Sink, SinkExt, Stream, StreamExt, TryStreamExt};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use si_crypto::SensitiveStrings;
@@ -33,12 +34,28 @@ use crate::WebSocketMessage;
 
 const TX_TIMEOUT_SECS: Duration = Duration::from_secs(5);
 const DEFAULT_LANG_SERVER_PROCESS_TIMEOUT: Duration = Duration::from_secs(32 * 60);
+const RESOURCE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// Output lines longer than this are split into chunks, so that a single huge line does not hold up
+/// the rest of the output or exceed the message size limits of the client.
+const MAX_OUTPUT_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// Resource limits enforced around the lang server child process of a single execution. Limits
+/// which are not set are not enforced.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionLimits {
+    pub max_wall_time: Option<Duration>,
+    pub max_cpu_time: Option<Duration>,
+    pub max_memory_bytes: Option<u64>,
+    pub max_output_bytes: Option<u64>,
+}
 
 pub fn new<Request, LangServerSuccess, Success>(
     lang_server_path: impl Into<PathBuf>,
     lang_server_debugging: bool,
     lang_server_function_timeout: Option<usize>,
     lang_server_process_timeout: Option<u64>,
+    execution_limits: ExecutionLimits,
     command: String,
 ) -> Execution<Request, LangServerSuccess, Success>
 where
@@ -52,6 +69,7 @@ where
             Some(timeout) => Duration::from_secs(timeout),
             None => DEFAULT_LANG_SERVER_PROCESS_TIMEOUT,
         },
+        execution_limits,
         command,
         request_marker: PhantomData,
         lang_server_success_marker: PhantomData,
@@ -62,6 +80,8 @@ where
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ExecutionError {
+    #[error("execution was cancelled by the client")]
+    Cancelled,
     #[error("failed to consume the {0} stream for the child process")]
     ChildIO(&'static str),
     #[error("failed to receive child process message")]
@@ -80,6 +100,10 @@ pub enum ExecutionError {
     JSONDeserialize(#[source] serde_json::Error),
     #[error("failed to serialize json message")]
     JSONSerialize(#[source] serde_json::Error),
+    #[error("execution exceeded its {0} limit")]
+    LimitExceeded(ExecutionLimit),
+    #[error("response channel is closed")]
+    ResponseChannelClosed,
     #[error("send timeout")]
     SendTimeout(#[source] tokio::time::error::Elapsed),
     #[error("unexpected websocket message type: {0:?}")]
@@ -105,6 +129,7 @@ where
     lang_server_debugging: bool,
     lang_server_function_timeout: Option<usize>,
     lang_server_process_timeout: Duration,
+    execution_limits: ExecutionLimits,
     command: String,
     request_marker: PhantomData<Request>,
     lang_server_success_marker: PhantomData<LangServerSuccess>,
@@ -125,7 +150,17 @@ where
         Self::ws_send_start(ws).await?;
         // Read the request message from the web socket
         let cyclone_request = Self::read_request(ws).await?;
+        self.spawn(cyclone_request).await
+    }
+
+    /// Spawns the lang server for an already received request. This is used directly by
+    /// transports which receive the request as part of the call, rather than as a first message.
+    pub async fn spawn(
+        self,
+        cyclone_request: CycloneRequest<Request>,
+    ) -> Result<ExecutionStarted<LangServerSuccess, Success>> {
         let (request, sensitive_strings) = cyclone_request.into_parts();
+        let execution_id = request.execution_id().to_owned();
 
         // Spawn lang server as a child process with handles on all i/o descriptors
         let mut command = Command::new(&self.lang_server_path);
@@ -174,10 +209,12 @@ where
             sensitive_strings: Arc::new(sensitive_strings),
             success_marker: self.success_marker,
             lang_server_process_timeout: self.lang_server_process_timeout,
+            execution_limits: self.execution_limits,
+            execution_id,
         })
     }
 
-    async fn read_request(ws: &mut WebSocket) -> Result<CycloneRequest<Request>> {
+    pub(crate) async fn read_request(ws: &mut WebSocket) -> Result<CycloneRequest<Request>> {
         let request = match ws.next().await {
             Some(Ok(WebSocketMessage::Text(json_str))) => {
                 serde_json::from_str(&json_str).map_err(ExecutionError::JSONDeserialize)?
@@ -189,7 +226,7 @@ where
         Ok(request)
     }
 
-    async fn ws_send_start(ws: &mut WebSocket) -> Result<()> {
+    pub(crate) async fn ws_send_start(ws: &mut WebSocket) -> Result<()> {
         let msg = Message::<Success>::Start
             .serialize_to_string()
             .map_err(ExecutionError::JSONSerialize)?;
@@ -233,6 +270,8 @@ pub struct ExecutionStarted<LangServerSuccess, Success> {
     sensitive_strings: Arc<SensitiveStrings>,
     success_marker: PhantomData<Success>,
     lang_server_process_timeout: Duration,
+    execution_limits: ExecutionLimits,
+    execution_id: String,
 }
 
 // TODO: implement shutdown oneshot
@@ -265,44 +304,124 @@ where
     SymmetricalJson<SiMessage<LangServerSuccess>>: Deserializer<SiMessage<LangServerSuccess>>,
     SiDecoderError: From<SiJsonError<LangServerSuccess>>,
 {
-    pub async fn process(mut self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
+    pub async fn process(self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
+        let (sink, stream) = ws.split();
+        let mut sink = sink
+            .sink_map_err(ExecutionError::WSSendIO)
+            .with(|msg: Message<Success>| {
+                future::ready(
+                    msg.serialize_to_string()
+                        .map(WebSocketMessage::Text)
+                        .map_err(ExecutionError::JSONSerialize),
+                )
+            });
+
+        self.process_into(&mut sink, ws_cancelled(stream)).await
+    }
+
+    /// Streams the output and result messages of the lang server into the given sink, which lets
+    /// transports other than the web socket apply their own framing and backpressure.
+    ///
+    /// The lang server process is killed as soon as `cancelled` resolves, in which case
+    /// [`ExecutionError::Cancelled`] is returned.
+    pub async fn process_into<S>(
+        mut self,
+        sink: &mut S,
+        cancelled: impl Future<Output = ()>,
+    ) -> Result<ExecutionClosing<Success>>
+    where
+        S: Sink<Message<Success>, Error = ExecutionError> + Unpin,
+    {
         tokio::spawn(handle_stderr(self.stderr, self.sensitive_strings.clone()));
 
-        let mut stream = self
-            .stdout
-            .map(|ls_result| match ls_result {
-                Ok(ls_msg) => match ls_msg {
-                    LangServerMessage::Output(mut output) => {
-                        Self::filter_output(&mut output, &self.sensitive_strings)?;
-                        Ok(Message::OutputStream(output.into()))
+        let max_output_bytes = self.execution_limits.max_output_bytes;
+        let mut output_bytes: u64 = 0;
+        let mut stream = self.stdout.map(|ls_result| match ls_result {
+            Ok(ls_msg) => match ls_msg {
+                LangServerMessage::Output(mut output) => {
+                    output_bytes += output.message.len() as u64;
+                    if max_output_bytes.is_some_and(|max| output_bytes > max) {
+                        return Err(ExecutionError::LimitExceeded(
+                            ExecutionLimit::MaxOutputBytes,
+                        ));
                     }
-                    LangServerMessage::Result(mut result) => {
-                        Self::filter_result(&mut result, &self.sensitive_strings)?;
-                        Ok(Message::Result(result.into()))
-                    }
-                },
-                Err(err) => Err(ExecutionError::ChildRecvIO(err)),
-            })
-            .map(|msg_result: Result<_>| match msg_result {
-                Ok(msg) => match msg
-                    .serialize_to_string()
-                    .map_err(ExecutionError::JSONSerialize)
-                {
-                    Ok(json_str) => Ok(WebSocketMessage::Text(json_str)),
-                    Err(err) => Err(err),
-                },
-                Err(err) => Err(err),
-            });
+                    Self::filter_output(&mut output, &self.sensitive_strings)?;
+                    Ok(Message::OutputStream(output.into()))
+                }
+                LangServerMessage::Result(mut result) => {
+                    Self::filter_result(&mut result, &self.sensitive_strings)?;
+                    Ok(Message::Result(result.into()))
+                }
+            },
+            Err(err) => Err(ExecutionError::ChildRecvIO(err)),
+        });
 
+        // Every message is flushed before the next line of output is read, so a slow client holds
+        // up the lang server through its stdout pipe rather than output piling up in memory.
         let receive_loop = async {
-            while let Some(msg) = stream.try_next().await? {
-                ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+            let mut sequence: u64 = 0;
+            loop {
+                let msg = match time::timeout(HEARTBEAT_INTERVAL, stream.try_next()).await {
+                    Ok(msg) => msg?,
+                    // Let the client know that a quiet function is still running
+                    Err(_) => {
+                        sink.send(Message::Heartbeat).await?;
+                        continue;
+                    }
+                };
+
+                match msg {
+                    Some(Message::OutputStream(output)) => {
+                        for mut chunk in output.into_chunks(MAX_OUTPUT_MESSAGE_BYTES) {
+                            chunk.sequence = sequence;
+                            sequence += 1;
+                            sink.send(Message::OutputStream(chunk)).await?;
+                        }
+                    }
+                    Some(msg) => sink.send(msg).await?,
+                    None => break,
+                }
             }
 
             Result::<_>::Ok(())
         };
 
-        match timeout(self.lang_server_process_timeout, receive_loop).await {
+        let limits_watch = watch_limits(self.child.id(), self.execution_limits);
+        let execution = async {
+            tokio::select! {
+                result = receive_loop => result,
+                limit = limits_watch => Err(ExecutionError::LimitExceeded(limit)),
+                () = cancelled => Err(ExecutionError::Cancelled),
+            }
+        };
+
+        match timeout(self.lang_server_process_timeout, execution).await {
+            Ok(Err(ExecutionError::LimitExceeded(limit))) => {
+                // Kill the child process and report a structured failure instead of erroring
+                process::child_shutdown(&mut self.child, Some(process::Signal::SIGKILL), None)
+                    .await?;
+                warn!(
+                    execution_id = %self.execution_id,
+                    %limit,
+                    "killed child process for exceeding execution limit",
+                );
+                time::timeout(
+                    TX_TIMEOUT_SECS,
+                    sink.send(Self::limit_exceeded_message(&self.execution_id, limit)),
+                )
+                .await
+                .map_err(ExecutionError::SendTimeout)??;
+            }
+            Ok(Err(ExecutionError::Cancelled)) => {
+                // Nobody is waiting for the result anymore, so don't let the function finish
+                process::child_shutdown(&mut self.child, Some(process::Signal::SIGKILL), None)
+                    .await?;
+                info!(
+                    execution_id = %self.execution_id,
+                    "killed child process for cancelled execution",
+                );
+                return Err(ExecutionError::Cancelled);
+            }
             Ok(execution) => execution?,
             Err(err) => {
                 // Exceeded timeout, shutdown child process
@@ -323,18 +442,34 @@ where
         })
     }
 
-    fn filter_output(
+    fn limit_exceeded_message(execution_id: &str, limit: ExecutionLimit) -> Message<Success> {
+        Message::Result(FunctionResult::Failure(FunctionResultFailure::new(
+            execution_id,
+            FunctionResultFailureError {
+                kind: FunctionResultFailureErrorKind::LimitExceeded(limit),
+                message: format!("function execution was killed for exceeding its {limit} limit"),
+            },
+            crate::timestamp(),
+        )))
+    }
+
+    pub(crate) fn filter_output(
         output: &mut LangServerOutput,
         sensitive_strings: &SensitiveStrings,
     ) -> Result<()> {
         if sensitive_strings.has_sensitive(&output.message) {
             output.message = sensitive_strings.redact(&output.message);
         }
+        if let Some(group) = output.group.as_mut() {
+            if sensitive_strings.has_sensitive(group) {
+                *group = sensitive_strings.redact(group);
+            }
+        }
 
         Ok(())
     }
 
-    fn filter_result(
+    pub(crate) fn filter_result(
         result: &mut LangServerResult<LangServerSuccess>,
         sensitive_strings: &SensitiveStrings,
     ) -> Result<()> {
@@ -359,6 +494,93 @@ where
     }
 }
 
+/// Resolves once the client of a web socket execution sends a [`ControlMessage::Cancel`] or goes
+/// away.
+async fn ws_cancelled<S>(mut stream: S)
+where
+    S: Stream<Item = std::result::Result<WebSocketMessage, axum::Error>> + Unpin,
+{
+    while let Some(msg) = stream.next().await {
+        match msg {
+            Ok(WebSocketMessage::Text(text)) => {
+                if let Ok(ControlMessage::Cancel) = ControlMessage::deserialize_from_str(&text) {
+                    return;
+                }
+            }
+            Ok(WebSocketMessage::Close(_)) | Err(_) => return,
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Resolves with the first of the wall time, CPU time and memory limits which the child process
+/// exceeds, and never resolves if none are set.
+async fn watch_limits(pid: Option<u32>, limits: ExecutionLimits) -> ExecutionLimit {
+    let wall_time = async {
+        match limits.max_wall_time {
+            Some(max_wall_time) => time::sleep(max_wall_time).await,
+            None => future::pending().await,
+        }
+    };
+    let usage = async {
+        let pid = match pid {
+            Some(pid) if limits.max_cpu_time.is_some() || limits.max_memory_bytes.is_some() => pid,
+            _ => future::pending().await,
+        };
+        loop {
+            time::sleep(RESOURCE_POLL_INTERVAL).await;
+            if let Some(max_cpu_time) = limits.max_cpu_time {
+                if cpu_time(pid).is_some_and(|cpu_time| cpu_time > max_cpu_time) {
+                    return ExecutionLimit::MaxCpuTime;
+                }
+            }
+            if let Some(max_memory_bytes) = limits.max_memory_bytes {
+                if resident_memory_bytes(pid).is_some_and(|bytes| bytes > max_memory_bytes) {
+                    return ExecutionLimit::MaxMemory;
+                }
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = wall_time => ExecutionLimit::MaxWallTime,
+        limit = usage => limit,
+    }
+}
+
+/// The CPU time the process has spent in user and kernel mode.
+#[cfg(target_os = "linux")]
+fn cpu_time(pid: u32) -> Option<Duration> {
+    let pid = i32::try_from(pid).ok()?;
+    let stat = procfs::process::Process::new(pid).ok()?.stat().ok()?;
+    let ticks = stat.utime.saturating_add(stat.stime);
+    let ticks_per_second = procfs::ticks_per_second();
+    if ticks_per_second == 0 {
+        return None;
+    }
+    Some(Duration::from_millis(
+        ticks.saturating_mul(1000) / ticks_per_second,
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cpu_time(_pid: u32) -> Option<Duration> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn resident_memory_bytes(pid: u32) -> Option<u64> {
+    let pid = i32::try_from(pid).ok()?;
+    let status = procfs::process::Process::new(pid).ok()?.status().ok()?;
+    // Reported in kB
+    status.vmrss.map(|kilobytes| kilobytes * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resident_memory_bytes(_pid: u32) -> Option<u64> {
+    None
+}
+
 #[derive(Debug)]
 pub struct ExecutionClosing<Success> {
     child: Child,
@@ -411,7 +633,17 @@ where
         }
     }
 
-    async fn ws_send_finish(ws: &mut WebSocket) -> Result<()> {
+    /// Shuts down the lang server child process, for transports which signal the end of the
+    /// execution themselves.
+    pub async fn shutdown(mut self) -> Result<()> {
+        let shutdown =
+            process::child_shutdown(&mut self.child, Some(process::Signal::SIGTERM), None).await;
+        drop(self.child);
+
+        shutdown.map_err(Into::into)
+    }
+
+    pub(crate) async fn ws_send_finish(ws: &mut WebSocket) -> Result<()> {
         let msg = Message::<Success>::Finish
             .serialize_to_string()
             .map_err(ExecutionError::JSONSerialize)?;
@@ -455,6 +687,8 @@ impl From<LangServerOutput> for OutputStream {
             group: value.group,
             message: value.message,
             timestamp: crate::timestamp(),
+            sequence: 0,
+            chunk: None,
         }
     }
 }
@@ -31,6 +31,32 @@ impl RequestLimiter {
             shutdown_tx,
         }
     }
+
+    /// Counts a request against the limit, returning a guard which signals a shutdown when it is
+    /// dropped if this was the last request allowed.
+    pub fn acquire(&self) -> LimitRequestGuard {
+        let shutdown_tx = match (*self.remaining).as_ref() {
+            Some(remaining) => {
+                // Both transports count against the same limit, so the decrement must be atomic
+                let previous = remaining
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                        Some(remaining.saturating_sub(1))
+                    })
+                    .unwrap_or_else(|remaining| remaining);
+                let updated = previous.saturating_sub(1);
+                debug!("requests remaining: {}", updated);
+
+                if updated > 0 {
+                    None
+                } else {
+                    Some(self.shutdown_tx.clone())
+                }
+            }
+            None => None,
+        };
+
+        LimitRequestGuard(shutdown_tx)
+    }
 }
 
 pub struct LimitRequestGuard(Option<mpsc::Sender<ShutdownSource>>);
@@ -62,23 +88,7 @@ where
             .await
             .map_err(internal_error)?;
 
-        let shutdown_tx = match (*limiter.remaining).as_ref() {
-            Some(remaining) => {
-                let mut updated = remaining.load(Ordering::Relaxed);
-                updated = updated.saturating_sub(1);
-                remaining.store(updated, Ordering::Relaxed);
-                debug!("requests remaining: {}", updated);
-
-                if updated > 0 {
-                    None
-                } else {
-                    Some(limiter.shutdown_tx.clone())
-                }
-            }
-            None => None,
-        };
-
-        Ok(Self(shutdown_tx))
+        Ok(limiter.acquire())
     }
 }
 
@@ -0,0 +1,385 @@
+//! A gRPC transport for function executions, served alongside the web socket routes.
+//!
+//! Every execution kind is a server streaming RPC of the `cyclone.Execution` service which takes a
+//! [`CycloneRequest`] and streams back the same [`Messages`](Message) as the web socket protocol,
+//! ending with [`Message::Finish`]. Messages are encoded as JSON rather than protobuf so that both
+//! transports share the request and result types.
+
+use std::{
+    convert::Infallible,
+    fmt,
+    marker::{PhantomData, Unpin},
+};
+
+use axum::extract::FromRef;
+use bytes::{Buf, BufMut};
+use cyclone_core::{
+    ActionRunRequest, ActionRunResultSuccess, CycloneRequest, CycloneRequestable, DiscoveryRequest,
+    DiscoveryResultSuccess, ManagementRequest, ManagementResultSuccess, Message,
+    ResolverFunctionRequest, ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
+};
+use futures::{SinkExt, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use si_std::SensitiveString;
+use telemetry::prelude::*;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::PollSender;
+use tonic::{
+    body::BoxBody,
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    codegen::{empty_body, http, Body, BoxFuture, BoxStream, Context, Poll, Service, StdError},
+    server::{Grpc, NamedService, ServerStreamingService},
+    Status,
+};
+
+use crate::{
+    execution::{self, Execution, ExecutionError, ExecutionLimits},
+    extract::{LimitRequestGuard, RequestLimiter},
+    result::{
+        LangServerActionRunResultSuccess, LangServerDiscoveryResultSuccess,
+        LangServerResolverFunctionResultSuccess, LangServerValidationResultSuccess,
+    },
+    state::{
+        AppState, LangServerFunctionTimeout, LangServerPath, LangServerProcessTimeout,
+        TelemetryLevel,
+    },
+    Config,
+};
+
+/// The number of messages which are buffered for a call before the lang server output is no
+/// longer read, so that a slow client applies backpressure to its execution.
+const RESPONSE_BUFFER_SIZE: usize = 64;
+
+/// The `cyclone.Execution` gRPC service. Only the RPCs whose execution kind is enabled in the
+/// [`Config`] are served, the others respond with `UNIMPLEMENTED`.
+///
+/// Every call counts against the same request limit as the web socket routes. When an auth token
+/// is configured, calls must present it as a bearer token or are rejected with `UNAUTHENTICATED`.
+#[derive(Clone)]
+pub struct ExecutionService {
+    state: AppState,
+    limiter: RequestLimiter,
+    auth_token: Option<SensitiveString>,
+    enable_resolver: bool,
+    enable_action_run: bool,
+    enable_validation: bool,
+    enable_schema_variant_definition: bool,
+    enable_management: bool,
+    enable_discovery: bool,
+}
+
+impl ExecutionService {
+    pub fn new(config: &Config, state: AppState, limiter: RequestLimiter) -> Self {
+        Self {
+            state,
+            limiter,
+            auth_token: config.grpc_auth_token().cloned(),
+            enable_resolver: config.enable_resolver(),
+            enable_action_run: config.enable_action_run(),
+            enable_validation: config.enable_validation(),
+            enable_schema_variant_definition: config.enable_schema_variant_definition(),
+            enable_management: config.enable_management(),
+            enable_discovery: config.enable_discovery(),
+        }
+    }
+
+    fn is_authorized<B>(&self, req: &http::Request<B>) -> bool {
+        let Some(auth_token) = &self.auth_token else {
+            return true;
+        };
+
+        req.headers()
+            .get(http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| token == auth_token.as_str())
+    }
+}
+
+impl NamedService for ExecutionService {
+    const NAME: &'static str = "cyclone.Execution";
+}
+
+impl<B> Service<http::Request<B>> for ExecutionService
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if !self.is_authorized(&req) {
+            return status_response(tonic::Code::Unauthenticated);
+        }
+
+        let state = self.state.clone();
+        let limiter = self.limiter.clone();
+        match req.uri().path() {
+            "/cyclone.Execution/Resolver" if self.enable_resolver => {
+                serve::<
+                    ResolverFunctionRequest,
+                    LangServerResolverFunctionResultSuccess,
+                    ResolverFunctionResultSuccess,
+                    B,
+                >(state, limiter, "resolverfunction", req)
+            }
+            "/cyclone.Execution/Validation" if self.enable_validation => {
+                serve::<
+                    ValidationRequest,
+                    LangServerValidationResultSuccess,
+                    ValidationResultSuccess,
+                    B,
+                >(state, limiter, "validation", req)
+            }
+            "/cyclone.Execution/ActionRun" if self.enable_action_run => {
+                serve::<ActionRunRequest, LangServerActionRunResultSuccess, ActionRunResultSuccess, B>(
+                    state,
+                    limiter,
+                    "actionRun",
+                    req,
+                )
+            }
+            "/cyclone.Execution/SchemaVariantDefinition"
+                if self.enable_schema_variant_definition =>
+            {
+                serve::<
+                    SchemaVariantDefinitionRequest,
+                    SchemaVariantDefinitionResultSuccess,
+                    SchemaVariantDefinitionResultSuccess,
+                    B,
+                >(state, limiter, "schemaVariantDefinition", req)
+            }
+            "/cyclone.Execution/Management" if self.enable_management => {
+                serve::<ManagementRequest, ManagementResultSuccess, ManagementResultSuccess, B>(
+                    state,
+                    limiter,
+                    "management",
+                    req,
+                )
+            }
+            "/cyclone.Execution/Discovery" if self.enable_discovery => {
+                serve::<DiscoveryRequest, LangServerDiscoveryResultSuccess, DiscoveryResultSuccess, B>(
+                    state,
+                    limiter,
+                    "discovery",
+                    req,
+                )
+            }
+            _ => status_response(tonic::Code::Unimplemented),
+        }
+    }
+}
+
+fn status_response(code: tonic::Code) -> BoxFuture<http::Response<BoxBody>, Infallible> {
+    Box::pin(async move {
+        let mut response = http::Response::new(empty_body());
+        let headers = response.headers_mut();
+        headers.insert(Status::GRPC_STATUS, (code as i32).into());
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            tonic::metadata::GRPC_CONTENT_TYPE,
+        );
+        Ok(response)
+    })
+}
+
+fn serve<Request, LangServerSuccess, Success, B>(
+    state: AppState,
+    limiter: RequestLimiter,
+    sub_command: &'static str,
+    req: http::Request<B>,
+) -> BoxFuture<http::Response<BoxBody>, Infallible>
+where
+    Request:
+        Serialize + DeserializeOwned + Unpin + fmt::Debug + CycloneRequestable + Send + 'static,
+    Success: Serialize + Unpin + fmt::Debug + Send + 'static,
+    LangServerSuccess:
+        Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success> + Send + 'static,
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    Box::pin(async move {
+        let method = ExecuteSvc::<Request, LangServerSuccess, Success> {
+            state,
+            limiter,
+            sub_command,
+            marker: PhantomData,
+        };
+        let mut grpc = Grpc::new(JsonCodec::<Message<Success>, CycloneRequest<Request>>::default());
+        Ok(grpc.server_streaming(method, req).await)
+    })
+}
+
+struct ExecuteSvc<Request, LangServerSuccess, Success> {
+    state: AppState,
+    limiter: RequestLimiter,
+    sub_command: &'static str,
+    marker: PhantomData<fn() -> (Request, LangServerSuccess, Success)>,
+}
+
+impl<Request, LangServerSuccess, Success> ServerStreamingService<CycloneRequest<Request>>
+    for ExecuteSvc<Request, LangServerSuccess, Success>
+where
+    Request:
+        Serialize + DeserializeOwned + Unpin + fmt::Debug + CycloneRequestable + Send + 'static,
+    Success: Serialize + Unpin + fmt::Debug + Send + 'static,
+    LangServerSuccess:
+        Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success> + Send + 'static,
+{
+    type Response = Message<Success>;
+    type ResponseStream = BoxStream<Message<Success>>;
+    type Future = BoxFuture<tonic::Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: tonic::Request<CycloneRequest<Request>>) -> Self::Future {
+        let state = self.state.clone();
+        let sub_command = self.sub_command;
+        // Held until the execution finishes, as the web socket handlers do for their connection
+        let limit_request_guard = self.limiter.acquire();
+
+        Box::pin(async move {
+            let execution: Execution<Request, LangServerSuccess, Success> = execution::new(
+                LangServerPath::from_ref(&state).as_path(),
+                TelemetryLevel::from_ref(&state).is_debug_or_lower().await,
+                LangServerFunctionTimeout::from_ref(&state).inner(),
+                LangServerProcessTimeout::from_ref(&state).inner(),
+                ExecutionLimits::from_ref(&state),
+                sub_command.to_owned(),
+            );
+
+            let (tx, rx) = mpsc::channel(RESPONSE_BUFFER_SIZE);
+            tokio::spawn(execute(
+                execution,
+                request.into_inner(),
+                tx,
+                limit_request_guard,
+            ));
+
+            let stream: Self::ResponseStream = Box::pin(ReceiverStream::new(rx).map(Ok));
+            Ok(tonic::Response::new(stream))
+        })
+    }
+}
+
+#[instrument(name = "grpc.execute", level = "info", skip_all, fields())]
+async fn execute<Request, LangServerSuccess, Success>(
+    execution: Execution<Request, LangServerSuccess, Success>,
+    request: CycloneRequest<Request>,
+    tx: mpsc::Sender<Message<Success>>,
+    _limit_request_guard: LimitRequestGuard,
+) where
+    Request:
+        Serialize + DeserializeOwned + Unpin + fmt::Debug + CycloneRequestable + Send + 'static,
+    Success: Serialize + Unpin + fmt::Debug + Send + 'static,
+    LangServerSuccess:
+        Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success> + Send + 'static,
+{
+    let span = current_span_for_instrument_at!("info");
+
+    let mut sink =
+        PollSender::new(tx.clone()).sink_map_err(|_| ExecutionError::ResponseChannelClosed);
+
+    let result = async {
+        sink.send(Message::Start).await?;
+        let closing = execution
+            .spawn(request)
+            .await?
+            .process_into(&mut sink, tx.closed())
+            .await?;
+        let finished = sink.send(Message::Finish).await;
+        let shutdown = closing.shutdown().await;
+        finished.and(shutdown)
+    }
+    .await;
+
+    match result {
+        Ok(()) => span.record_ok(),
+        // The client went away, so there is nobody left to report the failure to
+        Err(ExecutionError::ResponseChannelClosed | ExecutionError::Cancelled) => {
+            trace!(
+                kind = std::any::type_name::<Request>(),
+                "client disconnected"
+            );
+        }
+        Err(err) => {
+            warn!(
+                error = ?err,
+                kind = std::any::type_name::<Request>(),
+                "failed to execute function",
+            );
+            span.record_err(&err);
+            let _ = tx
+                .send(Message::fail(format!(
+                    "failed to execute function: {err:?}"
+                )))
+                .await;
+        }
+    }
+}
+
+/// A [`Codec`] which encodes and decodes messages as JSON, for use by clients of the
+/// `cyclone.Execution` service.
+pub struct JsonCodec<E, D>(PhantomData<fn(E) -> D>);
+
+impl<E, D> Default for JsonCodec<E, D> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E, D> Codec for JsonCodec<E, D>
+where
+    E: Serialize + Send + 'static,
+    D: DeserializeOwned + Send + 'static,
+{
+    type Encode = E;
+    type Decode = D;
+    type Encoder = JsonEncoder<E>;
+    type Decoder = JsonDecoder<D>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        JsonEncoder(PhantomData)
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        JsonDecoder(PhantomData)
+    }
+}
+
+pub struct JsonEncoder<T>(PhantomData<fn(T)>);
+
+impl<T: Serialize> Encoder for JsonEncoder<T> {
+    type Item = T;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        serde_json::to_writer(dst.writer(), &item)
+            .map_err(|err| Status::internal(format!("failed to serialize message: {err}")))
+    }
+}
+
+pub struct JsonDecoder<T>(PhantomData<fn() -> T>);
+
+impl<T: DeserializeOwned> Decoder for JsonDecoder<T> {
+    type Item = T;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        if !src.has_remaining() {
+            return Ok(None);
+        }
+
+        serde_json::from_reader(src.reader())
+            .map(Some)
+            .map_err(|err| {
+                Status::invalid_argument(format!("failed to deserialize request: {err}"))
+            })
+    }
+}
@@ -13,10 +13,11 @@ use axum::{
     response::IntoResponse,
 };
 use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, CycloneRequestable, LivenessStatus,
-    ManagementRequest, ManagementResultSuccess, Message, ReadinessStatus, ResolverFunctionRequest,
-    ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
-    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
+    ActionRunRequest, ActionRunResultSuccess, CycloneRequestable, DiscoveryRequest,
+    DiscoveryResultSuccess, LivenessStatus, ManagementRequest, ManagementResultSuccess, Message,
+    ReadinessStatus, ResolverFunctionRequest, ResolverFunctionResultSuccess,
+    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, ValidationRequest,
+    ValidationResultSuccess,
 };
 use hyper::StatusCode;
 use serde::{de::DeserializeOwned, Serialize};
@@ -25,15 +26,16 @@ use telemetry_http::ParentSpan;
 
 use super::extract::LimitRequestGuard;
 use crate::{
-    execution::{self, Execution},
+    execution::{self, Execution, ExecutionError, ExecutionLimits},
     result::{
-        LangServerActionRunResultSuccess, LangServerResolverFunctionResultSuccess,
-        LangServerValidationResultSuccess,
+        LangServerActionRunResultSuccess, LangServerDiscoveryResultSuccess,
+        LangServerResolverFunctionResultSuccess, LangServerValidationResultSuccess,
     },
     state::{
-        LangServerFunctionTimeout, LangServerPath, LangServerProcessTimeout, TelemetryLevel,
-        WatchKeepalive,
+        LangServerFunctionTimeout, LangServerPath, LangServerProcessTimeout, LangServerVersion,
+        TelemetryLevel, WatchKeepalive,
     },
+    wasm::WasmRuntime,
     watch,
 };
 
@@ -47,6 +49,16 @@ pub async fn readiness() -> Result<&'static str, StatusCode> {
     Ok(ReadinessStatus::Ready.into())
 }
 
+#[allow(clippy::unused_async)]
+pub async fn lang_server_version(
+    State(lang_server_version): State<LangServerVersion>,
+) -> Result<String, StatusCode> {
+    lang_server_version
+        .inner()
+        .map(|version| format!("{version}\n"))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
 pub async fn ws_watch(
     wsu: WebSocketUpgrade,
     Extension(watch_keepalive): Extension<Arc<WatchKeepalive>>,
@@ -104,6 +116,7 @@ pub async fn ws_execute_resolver(
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -119,6 +132,7 @@ pub async fn ws_execute_resolver(
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
+            execution_limits,
             limit_request_guard,
             "resolverfunction".to_owned(),
             request,
@@ -135,6 +149,7 @@ pub async fn ws_execute_validation(
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -150,6 +165,7 @@ pub async fn ws_execute_validation(
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
+            execution_limits,
             limit_request_guard,
             "validation".to_owned(),
             request,
@@ -166,6 +182,7 @@ pub async fn ws_execute_action_run(
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -181,6 +198,7 @@ pub async fn ws_execute_action_run(
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
+            execution_limits,
             limit_request_guard,
             "actionRun".to_owned(),
             request,
@@ -197,6 +215,7 @@ pub async fn ws_execute_schema_variant_definition(
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -212,6 +231,7 @@ pub async fn ws_execute_schema_variant_definition(
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
+            execution_limits,
             limit_request_guard,
             "schemaVariantDefinition".to_owned(),
             request,
@@ -228,6 +248,7 @@ pub async fn ws_execute_management(
     State(telemetry_level): State<TelemetryLevel>,
     State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
     State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_limits): State<ExecutionLimits>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
@@ -243,6 +264,7 @@ pub async fn ws_execute_management(
             telemetry_level,
             lang_server_function_timeout.inner(),
             lang_server_process_timeout.inner(),
+            execution_limits,
             limit_request_guard,
             "management".to_owned(),
             request,
@@ -253,6 +275,92 @@ pub async fn ws_execute_management(
     })
 }
 
+pub async fn ws_execute_discovery(
+    wsu: WebSocketUpgrade,
+    State(lang_server_path): State<LangServerPath>,
+    State(telemetry_level): State<TelemetryLevel>,
+    State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
+    State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_limits): State<ExecutionLimits>,
+    limit_request_guard: LimitRequestGuard,
+    Extension(request_span): Extension<ParentSpan>,
+) -> impl IntoResponse {
+    let lang_server_path = lang_server_path.as_path().to_path_buf();
+    let telemetry_level = telemetry_level.is_debug_or_lower().await;
+    wsu.on_upgrade(move |socket| {
+        let request: PhantomData<DiscoveryRequest> = PhantomData;
+        let lang_server_success: PhantomData<LangServerDiscoveryResultSuccess> = PhantomData;
+        let success: PhantomData<DiscoveryResultSuccess> = PhantomData;
+        handle_socket(
+            socket,
+            lang_server_path,
+            telemetry_level,
+            lang_server_function_timeout.inner(),
+            lang_server_process_timeout.inner(),
+            execution_limits,
+            limit_request_guard,
+            "discovery".to_owned(),
+            request,
+            lang_server_success,
+            success,
+            request_span.into_inner(),
+        )
+    })
+}
+
+pub async fn ws_execute_wasm_resolver(
+    wsu: WebSocketUpgrade,
+    Extension(wasm_runtime): Extension<WasmRuntime>,
+    limit_request_guard: LimitRequestGuard,
+    Extension(request_span): Extension<ParentSpan>,
+) -> impl IntoResponse {
+    wsu.on_upgrade(move |socket| {
+        handle_wasm_socket(
+            socket,
+            wasm_runtime,
+            limit_request_guard,
+            request_span.into_inner(),
+        )
+    })
+}
+
+#[instrument(
+    name = "web_socket.handle_wasm_socket",
+    parent = &request_span,
+    level = "info",
+    skip_all,
+    fields()
+)]
+async fn handle_wasm_socket(
+    mut socket: WebSocket,
+    wasm_runtime: WasmRuntime,
+    _limit_request_guard: LimitRequestGuard,
+    request_span: Span,
+) {
+    if let Err(err) = wasm_runtime.execute(&mut socket).await {
+        warn!(error = ?err, "failed to execute wasm function");
+        request_span.record_err(&err);
+        let success_marker: PhantomData<ResolverFunctionResultSuccess> = PhantomData;
+        if let Err(err) = fail_to_process(
+            socket,
+            format!("failed to execute wasm function: {err:?}"),
+            success_marker,
+        )
+        .await
+        {
+            warn!(error = ?err, "failed to fail execute wasm function");
+        };
+        return;
+    }
+    if let Err(err) = socket.close().await {
+        request_span.record_err(&err);
+        warn!(error = ?err, "failed to close websocket");
+        return;
+    }
+
+    request_span.record_ok();
+}
+
 #[instrument(
     name = "web_socket.handle_socket",
     parent = &request_span,
@@ -267,6 +375,7 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     lang_server_debugging: bool,
     lang_server_function_timeout: Option<usize>,
     lang_server_process_timeout: Option<u64>,
+    execution_limits: ExecutionLimits,
     _limit_request_guard: LimitRequestGuard,
     sub_command: String,
     _request_marker: PhantomData<Request>,
@@ -284,6 +393,7 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
             lang_server_debugging,
             lang_server_function_timeout,
             lang_server_process_timeout,
+            execution_limits,
             sub_command,
         );
         match execution.start(&mut socket).await {
@@ -306,6 +416,15 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     };
     let proto = match proto.process(&mut socket).await {
         Ok(processed) => processed,
+        // The client cancelled the execution, so there is nobody left to report to
+        Err(ExecutionError::Cancelled) => {
+            debug!(
+                kind = std::any::type_name::<Request>(),
+                "execution cancelled by client"
+            );
+            request_span.record_ok();
+            return;
+        }
         Err(err) => {
             warn!(error = ?err, "failed to process protocol");
             request_span.record_err(&err);
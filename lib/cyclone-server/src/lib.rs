@@ -1,6 +1,7 @@
 mod config;
 mod execution;
 mod extract;
+mod grpc;
 mod handlers;
 #[cfg(target_os = "linux")]
 pub mod process_gatherer;
@@ -13,10 +14,12 @@ mod tower;
 mod uds;
 #[cfg(target_os = "linux")]
 mod vsock;
+mod wasm;
 mod watch;
 
 pub use axum::extract::ws::Message as WebSocketMessage;
 pub use config::{Config, ConfigBuilder, ConfigError, IncomingStream};
+pub use grpc::JsonCodec;
 #[cfg(target_os = "linux")]
 pub use process_gatherer::init;
 pub use server::{Runnable, Server, ShutdownSource};
@@ -26,3 +29,4 @@ pub use tokio_vsock::VsockAddr;
 pub use uds::{UdsIncomingStream, UdsIncomingStreamError};
 #[cfg(target_os = "linux")]
 pub use vsock::{VsockIncomingStream, VsockIncomingStreamError};
+pub use wasm::WasmError;
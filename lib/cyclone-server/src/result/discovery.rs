@@ -0,0 +1,30 @@
+use cyclone_core::{DiscoveredResource, DiscoveryResultSuccess, ResourceStatus};
+use serde::{Deserialize, Serialize};
+
+/// This struct contains the lang-js server execution response. All fields without the
+/// `#[serde(default)]` macro must be populated.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangServerDiscoveryResultSuccess {
+    pub execution_id: String,
+    #[serde(default)]
+    pub resources: Vec<DiscoveredResource>,
+    pub health: ResourceStatus,
+    #[serde(default)]
+    pub message: Option<String>,
+    // Collects the error if the function throws
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl From<LangServerDiscoveryResultSuccess> for DiscoveryResultSuccess {
+    fn from(value: LangServerDiscoveryResultSuccess) -> Self {
+        Self {
+            execution_id: value.execution_id,
+            resources: value.resources,
+            status: value.health,
+            message: value.message,
+            error: value.error,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 mod action_run;
+mod discovery;
 mod resolver_function;
 mod validation;
 
 pub use action_run::LangServerActionRunResultSuccess;
+pub use discovery::LangServerDiscoveryResultSuccess;
 pub use resolver_function::LangServerResolverFunctionResultSuccess;
 pub use validation::LangServerValidationResultSuccess;
@@ -11,12 +11,15 @@ use crate::{
     handlers,
     state::{AppState, WatchKeepalive},
     tower::WebSocketTraceLayer,
+    wasm::WasmRuntime,
     watch, Config, ShutdownSource,
 };
 
 pub fn routes(
     config: &Config,
     state: AppState,
+    wasm_runtime: Option<WasmRuntime>,
+    limiter: RequestLimiter,
     shutdown_tx: mpsc::Sender<ShutdownSource>,
 ) -> Router {
     let http_trace_layer = TraceLayer::new_for_http()
@@ -37,9 +40,13 @@ pub fn routes(
                 .head(handlers::readiness)
                 .layer(http_trace_layer.clone()),
         )
+        .route(
+            "/lang-server/version",
+            get(handlers::lang_server_version).layer(http_trace_layer.clone()),
+        )
         .nest(
             "/execute",
-            execute_routes(config, shutdown_tx.clone())
+            execute_routes(config, wasm_runtime, limiter)
                 .layer(http_trace_layer.clone())
                 .layer(web_socket_trace_layer),
         )
@@ -72,7 +79,11 @@ pub fn routes(
     router.with_state(state)
 }
 
-fn execute_routes(config: &Config, shutdown_tx: mpsc::Sender<ShutdownSource>) -> Router<AppState> {
+fn execute_routes(
+    config: &Config,
+    wasm_runtime: Option<WasmRuntime>,
+    limiter: RequestLimiter,
+) -> Router<AppState> {
     let mut router = Router::new();
 
     if config.enable_ping() {
@@ -105,8 +116,20 @@ fn execute_routes(config: &Config, shutdown_tx: mpsc::Sender<ShutdownSource>) ->
         router =
             router.merge(Router::new().route("/management", get(handlers::ws_execute_management)));
     }
+    if config.enable_discovery() {
+        debug!("enabling discovery function endpoint");
+        router =
+            router.merge(Router::new().route("/discovery", get(handlers::ws_execute_discovery)));
+    }
 
-    let limit_requests = Arc::new(config.limit_requests().map(|i| i.into()));
+    if let Some(wasm_runtime) = wasm_runtime {
+        debug!("enabling experimental wasm resolver function endpoint");
+        router = router.merge(
+            Router::new()
+                .route("/wasm/resolver", get(handlers::ws_execute_wasm_resolver))
+                .layer(Extension(wasm_runtime)),
+        );
+    }
 
-    router.layer(Extension(RequestLimiter::new(limit_requests, shutdown_tx)))
+    router.layer(Extension(limiter))
 }
@@ -2,22 +2,27 @@ use std::{
     io,
     net::SocketAddr,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use async_trait::async_trait;
 use axum::routing::{IntoMakeService, Router};
+use cyclone_core::RuntimeVersion;
 use hyper::server::accept::Accept;
 use telemetry::{prelude::*, TelemetryLevel};
 use thiserror::Error;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
+    process::Command,
     signal::unix,
-    sync::{mpsc, oneshot},
+    sync::mpsc,
 };
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    routes::routes, state::AppState, Config, IncomingStream, UdsIncomingStream,
-    UdsIncomingStreamError,
+    extract::RequestLimiter, grpc::ExecutionService, routes::routes, state::AppState,
+    wasm::WasmRuntime, Config, IncomingStream, UdsIncomingStream, UdsIncomingStreamError,
+    WasmError,
 };
 
 #[cfg(target_os = "linux")]
@@ -26,6 +31,8 @@ use crate::{VsockIncomingStream, VsockIncomingStreamError};
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ServerError {
+    #[error("grpc server error")]
+    Grpc(#[from] tonic::transport::Error),
     #[error("hyper server error")]
     Hyper(#[from] hyper::Error),
     #[error("failed to setup signal handler")]
@@ -35,6 +42,8 @@ pub enum ServerError {
     #[cfg(target_os = "linux")]
     #[error("Vsock incoming stream error")]
     Vsock(#[from] VsockIncomingStreamError),
+    #[error("wasm error")]
+    Wasm(#[from] WasmError),
     #[error("wrong incoming stream for {0} server: {1:?}")]
     WrongIncomingStream(&'static str, IncomingStream),
 }
@@ -89,7 +98,9 @@ impl Server {
         config: Config,
         telemetry_level: Box<dyn TelemetryLevel>,
     ) -> Result<Self> {
-        let (service, shutdown_rx) = build_service(&config, telemetry_level)?;
+        let lang_server_version = detect_lang_server_version(config.lang_server_path()).await;
+        let (service, grpc, shutdown_token) =
+            build_service(&config, telemetry_level, lang_server_version)?;
 
         match config.incoming_stream() {
             IncomingStream::HTTPSocket(socket_addr) => {
@@ -99,7 +110,11 @@ impl Server {
                 info!(socket = %socket, "http server serving");
 
                 Ok(Self {
-                    inner: Box::new(InnerServer { inner, shutdown_rx }),
+                    inner: Box::new(InnerServer {
+                        inner,
+                        grpc,
+                        shutdown_token,
+                    }),
                     config,
                     socket: ServerSocket::SocketAddr(socket),
                 })
@@ -112,7 +127,11 @@ impl Server {
                 debug!(socket = %socket.display(), "unix domain server serving");
 
                 Ok(Self {
-                    inner: Box::new(InnerServer { inner, shutdown_rx }),
+                    inner: Box::new(InnerServer {
+                        inner,
+                        grpc,
+                        shutdown_token,
+                    }),
                     config,
                     socket: ServerSocket::DomainSocket(socket),
                 })
@@ -126,7 +145,11 @@ impl Server {
                 info!(socket = %socket, "vsock server serving");
 
                 Ok(Self {
-                    inner: Box::new(InnerServer { inner, shutdown_rx }),
+                    inner: Box::new(InnerServer {
+                        inner,
+                        grpc,
+                        shutdown_token,
+                    }),
                     config,
                     socket: ServerSocket::VsockAddr(socket),
                 })
@@ -154,7 +177,8 @@ impl Runnable for Server {
 
 struct InnerServer<I> {
     inner: axum::Server<I, IntoMakeService<Router>>,
-    shutdown_rx: oneshot::Receiver<()>,
+    grpc: Option<GrpcServer>,
+    shutdown_token: CancellationToken,
 }
 
 #[async_trait]
@@ -165,12 +189,36 @@ where
     IE: Into<Box<dyn std::error::Error + Send + Sync>>,
 {
     async fn run(self) -> Result<()> {
-        let shutdown_rx = self.shutdown_rx;
+        let http = self
+            .inner
+            .with_graceful_shutdown(self.shutdown_token.clone().cancelled_owned());
+
+        match self.grpc {
+            Some(grpc) => {
+                tokio::try_join!(
+                    async { http.await.map_err(ServerError::from) },
+                    grpc.run(self.shutdown_token),
+                )?;
+                Ok(())
+            }
+            None => http.await.map_err(Into::into),
+        }
+    }
+}
+
+/// The gRPC execution service, served on its own socket next to the http server.
+struct GrpcServer {
+    socket_addr: SocketAddr,
+    service: ExecutionService,
+}
 
-        self.inner
-            .with_graceful_shutdown(async {
-                shutdown_rx.await.ok();
-            })
+impl GrpcServer {
+    async fn run(self, shutdown_token: CancellationToken) -> Result<()> {
+        info!(socket = %self.socket_addr, "grpc server serving");
+
+        tonic::transport::Server::builder()
+            .add_service(self.service)
+            .serve_with_shutdown(self.socket_addr, shutdown_token.cancelled_owned())
             .await
             .map_err(Into::into)
     }
@@ -211,7 +259,12 @@ impl ServerSocket {
 fn build_service(
     config: &Config,
     telemetry_level: Box<dyn TelemetryLevel>,
-) -> Result<(IntoMakeService<Router>, oneshot::Receiver<()>)> {
+    lang_server_version: Option<RuntimeVersion>,
+) -> Result<(
+    IntoMakeService<Router>,
+    Option<GrpcServer>,
+    CancellationToken,
+)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
 
     let state = AppState::new(
@@ -219,40 +272,83 @@ fn build_service(
         telemetry_level,
         config.lang_server_function_timeout(),
         config.lang_server_process_timeout(),
+        lang_server_version,
+        config.execution_limits(),
     );
 
-    let routes = routes(config, state, shutdown_tx);
+    let wasm_runtime = if config.enable_wasm() {
+        Some(WasmRuntime::new(config.lang_server_function_timeout())?)
+    } else {
+        None
+    };
+
+    // Executions over web sockets and gRPC count against the same request limit
+    let limit_requests = Arc::new(config.limit_requests().map(|i| i.into()));
+    let limiter = RequestLimiter::new(limit_requests, shutdown_tx.clone());
+
+    let grpc = config.grpc_socket_addr().map(|socket_addr| GrpcServer {
+        socket_addr,
+        service: ExecutionService::new(config, state.clone(), limiter.clone()),
+    });
+
+    let routes = routes(config, state, wasm_runtime, limiter, shutdown_tx);
 
-    let graceful_shutdown_rx = prepare_graceful_shutdown(shutdown_rx)?;
+    let graceful_shutdown_token = prepare_graceful_shutdown(shutdown_rx)?;
 
-    Ok((routes.into_make_service(), graceful_shutdown_rx))
+    Ok((routes.into_make_service(), grpc, graceful_shutdown_token))
+}
+
+/// Asks the lang server for its version, so that functions which need a newer one can be
+/// rejected before they run.
+async fn detect_lang_server_version(lang_server_path: &Path) -> Option<RuntimeVersion> {
+    let output = match Command::new(lang_server_path)
+        .arg("--version")
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(status = %output.status, "lang server failed to report its version");
+            return None;
+        }
+        Err(err) => {
+            warn!(si.error.message = ?err, "failed to run lang server to detect its version");
+            return None;
+        }
+    };
+
+    match String::from_utf8_lossy(&output.stdout).parse() {
+        Ok(version) => {
+            info!(%version, "detected lang server version");
+            Some(version)
+        }
+        Err(err) => {
+            warn!(si.error.message = ?err, "failed to parse lang server version");
+            None
+        }
+    }
 }
 
 fn prepare_graceful_shutdown(
     mut shutdown_rx: mpsc::Receiver<ShutdownSource>,
-) -> Result<oneshot::Receiver<()>> {
-    let (graceful_shutdown_tx, graceful_shutdown_rx) = oneshot::channel::<()>();
+) -> Result<CancellationToken> {
+    let graceful_shutdown_token = CancellationToken::new();
     let mut sigterm_stream =
         unix::signal(unix::SignalKind::terminate()).map_err(ServerError::Signal)?;
 
+    let token = graceful_shutdown_token.clone();
     tokio::spawn(async move {
-        fn send_graceful_shutdown(tx: oneshot::Sender<()>) {
-            if tx.send(()).is_err() {
-                error!("the server graceful shutdown receiver has already dropped");
-            }
-        }
-
         tokio::select! {
             _ = sigterm_stream.recv() => {
                 trace!("received SIGTERM signal, performing graceful shutdown");
-                send_graceful_shutdown(graceful_shutdown_tx);
+                token.cancel();
             }
             source = shutdown_rx.recv() => {
                 trace!(
                     "received internal shutdown, performing graceful shutdown; source={:?}",
                     source,
                 );
-                send_graceful_shutdown(graceful_shutdown_tx);
+                token.cancel();
             }
             else => {
                 // All other arms are closed, nothing left to do but return
@@ -261,7 +357,7 @@ fn prepare_graceful_shutdown(
         };
     });
 
-    Ok(graceful_shutdown_rx)
+    Ok(graceful_shutdown_token)
 }
 
 #[remain::sorted]
@@ -6,14 +6,19 @@ use std::{
 };
 
 use axum::extract::FromRef;
+use cyclone_core::RuntimeVersion;
 use tokio::sync::mpsc;
 
+use crate::execution::ExecutionLimits;
+
 #[derive(Clone, FromRef)]
 pub struct AppState {
     lang_server_path: LangServerPath,
     telemetry_level: TelemetryLevel,
     lang_server_function_timeout: LangServerFunctionTimeout,
     lang_server_process_timeout: LangServerProcessTimeout,
+    lang_server_version: LangServerVersion,
+    execution_limits: ExecutionLimits,
 }
 
 impl AppState {
@@ -22,6 +27,8 @@ impl AppState {
         telemetry_level: Box<dyn telemetry::TelemetryLevel>,
         lang_server_function_timeout: Option<usize>,
         lang_server_process_timeout: Option<u64>,
+        lang_server_version: Option<RuntimeVersion>,
+        execution_limits: ExecutionLimits,
     ) -> Self {
         Self {
             lang_server_path: LangServerPath(Arc::new(lang_server_path.into())),
@@ -32,6 +39,8 @@ impl AppState {
             lang_server_process_timeout: LangServerProcessTimeout(Arc::new(
                 lang_server_process_timeout,
             )),
+            lang_server_version: LangServerVersion(lang_server_version),
+            execution_limits,
         }
     }
 }
@@ -74,6 +83,15 @@ impl LangServerProcessTimeout {
     }
 }
 
+#[derive(Clone, Copy, Debug, FromRef)]
+pub struct LangServerVersion(Option<RuntimeVersion>);
+
+impl LangServerVersion {
+    pub fn inner(&self) -> Option<RuntimeVersion> {
+        self.0
+    }
+}
+
 pub struct WatchKeepalive {
     tx: mpsc::Sender<()>,
     timeout: Duration,
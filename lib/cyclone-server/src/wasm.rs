@@ -0,0 +1,377 @@
+//! (Experimental) Runs resolver functions compiled to WASM in-process with wasmtime, rather than
+//! spawning the lang server for every execution. This opens the door to functions written in
+//! languages such as Go or Rust, at a much lower per-invocation overhead.
+//!
+//! A function is a WASI command module which uses the same envelope as the lang server: it reads
+//! the request as JSON from stdin and writes lang server messages (outputs and a result) to
+//! stdout, one JSON object per line. Any other line written to stdout is forwarded as output and
+//! anything written to stderr is logged. Modules have no access to the filesystem, network or
+//! environment and are interrupted once they exceed the function timeout. Their output is
+//! forwarded once they have finished, rather than as it is written.
+//!
+//! Compiling a module costs far more than running most functions, so recently used modules are
+//! kept compiled, keyed by a hash of their code.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+};
+
+use axum::extract::ws::WebSocket;
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+use cyclone_core::{
+    FunctionResult, FunctionResultFailure, FunctionResultFailureError,
+    FunctionResultFailureErrorKind, Message, OutputStream, ResolverFunctionRequest,
+    ResolverFunctionResultSuccess,
+};
+use futures::SinkExt;
+use si_crypto::SensitiveStrings;
+use thiserror::Error;
+use wasmtime::{Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+use wasmtime_wasi::{
+    pipe::{MemoryInputPipe, MemoryOutputPipe},
+    preview1::{self, WasiP1Ctx},
+    I32Exit, WasiCtxBuilder,
+};
+
+use crate::{
+    execution::{Execution, ExecutionClosing, ExecutionError, ExecutionStarted, LangServerMessage},
+    result::LangServerResolverFunctionResultSuccess,
+    WebSocketMessage,
+};
+
+/// How often the epoch of the engine advances, i.e. the granularity of function timeouts.
+const EPOCH_TICK: Duration = Duration::from_secs(1);
+/// The default function timeout, which matches the default of the lang server.
+const DEFAULT_FUNCTION_TIMEOUT_SECS: u64 = 30 * 60;
+/// The most a module may write to either stdout or stderr.
+const MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+/// The most linear memory a module may use.
+const MAX_MEMORY_BYTES: usize = 512 * 1024 * 1024;
+/// The most compiled modules kept around for reuse.
+const MAX_CACHED_MODULES: usize = 64;
+
+/// Func code is stored without padding, but padded code is accepted as well.
+const CODE_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+type ResolverExecution = Execution<
+    ResolverFunctionRequest,
+    LangServerResolverFunctionResultSuccess,
+    ResolverFunctionResultSuccess,
+>;
+type ResolverExecutionStarted =
+    ExecutionStarted<LangServerResolverFunctionResultSuccess, ResolverFunctionResultSuccess>;
+type ResolverExecutionClosing = ExecutionClosing<ResolverFunctionResultSuccess>;
+type ResolverMessage = LangServerMessage<LangServerResolverFunctionResultSuccess>;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WasmError {
+    #[error("failed to create wasm engine: {0:#}")]
+    Engine(wasmtime::Error),
+    #[error("failed to spawn epoch ticker thread")]
+    EpochTicker(#[source] std::io::Error),
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+    #[error("wasm function task failed")]
+    Join(#[from] tokio::task::JoinError),
+    #[error("failed to link wasi: {0:#}")]
+    Link(wasmtime::Error),
+}
+
+type Result<T> = std::result::Result<T, WasmError>;
+
+/// Compiles and runs WASM functions. Every clone shares the same engine.
+#[derive(Clone)]
+pub struct WasmRuntime {
+    engine: Engine,
+    linker: Arc<Linker<WasmState>>,
+    modules: Arc<Mutex<ModuleCache>>,
+    function_timeout: Duration,
+}
+
+impl fmt::Debug for WasmRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmRuntime")
+            .field("function_timeout", &self.function_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compiled modules by the hash of their code, evicting the least recently used.
+#[derive(Default)]
+struct ModuleCache {
+    modules: HashMap<blake3::Hash, Module>,
+    recently_used: VecDeque<blake3::Hash>,
+}
+
+impl ModuleCache {
+    fn get(&mut self, hash: &blake3::Hash) -> Option<Module> {
+        let module = self.modules.get(hash)?.clone();
+        self.touch(hash);
+        Some(module)
+    }
+
+    fn insert(&mut self, hash: blake3::Hash, module: Module) {
+        if self.modules.insert(hash, module).is_none() {
+            self.recently_used.push_back(hash);
+            while self.recently_used.len() > MAX_CACHED_MODULES {
+                if let Some(evicted) = self.recently_used.pop_front() {
+                    self.modules.remove(&evicted);
+                }
+            }
+        } else {
+            self.touch(&hash);
+        }
+    }
+
+    fn touch(&mut self, hash: &blake3::Hash) {
+        if let Some(index) = self.recently_used.iter().position(|used| used == hash) {
+            self.recently_used.remove(index);
+        }
+        self.recently_used.push_back(*hash);
+    }
+}
+
+struct WasmState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+/// What a module wrote, and how it exited.
+struct WasmRun {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    outcome: wasmtime::Result<()>,
+}
+
+impl WasmRuntime {
+    /// Creates a runtime whose functions time out after the given number of seconds, like the
+    /// `--timeout` of the lang server.
+    pub fn new(function_timeout: Option<usize>) -> Result<Self> {
+        let mut config = wasmtime::Config::new();
+        config.epoch_interruption(true);
+        let engine = Engine::new(&config).map_err(WasmError::Engine)?;
+
+        let mut linker = Linker::new(&engine);
+        preview1::add_to_linker_sync(&mut linker, |state: &mut WasmState| &mut state.wasi)
+            .map_err(WasmError::Link)?;
+
+        // Stores are interrupted once the epoch passes their deadline, so the epoch has to advance
+        // for as long as the engine is around
+        let weak_engine = engine.weak();
+        thread::Builder::new()
+            .name("cyclone-wasm-epoch".to_owned())
+            .spawn(move || loop {
+                thread::sleep(EPOCH_TICK);
+                match weak_engine.upgrade() {
+                    Some(engine) => engine.increment_epoch(),
+                    None => break,
+                }
+            })
+            .map_err(WasmError::EpochTicker)?;
+
+        Ok(Self {
+            engine,
+            linker: Arc::new(linker),
+            modules: Default::default(),
+            function_timeout: Duration::from_secs(
+                function_timeout
+                    .map(|secs| secs as u64)
+                    .unwrap_or(DEFAULT_FUNCTION_TIMEOUT_SECS),
+            ),
+        })
+    }
+
+    /// Reads a request from the web socket, runs its function and sends the messages it wrote
+    /// back. The web socket is left open for the caller to close.
+    pub async fn execute(&self, ws: &mut WebSocket) -> Result<()> {
+        ResolverExecution::ws_send_start(ws).await?;
+        let (request, sensitive_strings) = ResolverExecution::read_request(ws).await?.into_parts();
+        let execution_id = request.execution_id.clone();
+
+        let module = match CODE_BASE64.decode(&request.code_base64) {
+            Ok(module) => module,
+            Err(err) => {
+                let failure = Self::failure(
+                    &execution_id,
+                    FunctionResultFailureErrorKind::UserCodeException("WasmError".to_owned()),
+                    format!("function code is not a base64 encoded wasm module: {err}"),
+                );
+                Self::send(ws, Message::Result(failure)).await?;
+                return Ok(ResolverExecutionClosing::ws_send_finish(ws).await?);
+            }
+        };
+        let input = serde_json::to_vec(&request).map_err(ExecutionError::JSONSerialize)?;
+
+        let runtime = self.clone();
+        let run = tokio::task::spawn_blocking(move || runtime.run_module(&module, input)).await?;
+
+        Self::log_stderr(&run.stderr, &sensitive_strings);
+
+        let mut has_result = false;
+        let mut sequence: u64 = 0;
+        for line in run.stdout.split(|byte| *byte == b'\n') {
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let mut message = match serde_json::from_slice::<ResolverMessage>(line) {
+                Ok(LangServerMessage::Output(mut output)) => {
+                    ResolverExecutionStarted::filter_output(&mut output, &sensitive_strings)?;
+                    Message::OutputStream(output.into())
+                }
+                Ok(LangServerMessage::Result(mut result)) => {
+                    ResolverExecutionStarted::filter_result(&mut result, &sensitive_strings)?;
+                    has_result = true;
+                    Message::Result(result.into())
+                }
+                Err(_) => Message::OutputStream(OutputStream {
+                    execution_id: execution_id.clone(),
+                    stream: "output".to_owned(),
+                    level: "info".to_owned(),
+                    group: None,
+                    message: sensitive_strings.redact(&String::from_utf8_lossy(line)),
+                    timestamp: crate::timestamp(),
+                    sequence: 0,
+                    chunk: None,
+                }),
+            };
+            if let Message::OutputStream(output) = &mut message {
+                output.sequence = sequence;
+                sequence += 1;
+            }
+            Self::send(ws, message).await?;
+        }
+
+        if !has_result {
+            let (kind, message) = match run.outcome {
+                Ok(()) => (
+                    FunctionResultFailureErrorKind::InvalidReturnType,
+                    "wasm function exited without writing a result".to_owned(),
+                ),
+                Err(err) if err.downcast_ref::<Trap>() == Some(&Trap::Interrupt) => (
+                    FunctionResultFailureErrorKind::KilledExecution,
+                    format!(
+                        "wasm function timed out after {}s",
+                        self.function_timeout.as_secs()
+                    ),
+                ),
+                Err(err) => (
+                    FunctionResultFailureErrorKind::UserCodeException("WasmError".to_owned()),
+                    sensitive_strings.redact(&format!("{err:#}")),
+                ),
+            };
+            Self::send(
+                ws,
+                Message::Result(Self::failure(&execution_id, kind, message)),
+            )
+            .await?;
+        }
+
+        ResolverExecutionClosing::ws_send_finish(ws).await?;
+        Ok(())
+    }
+
+    fn run_module(&self, module: &[u8], input: Vec<u8>) -> WasmRun {
+        let stdout = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+        let stderr = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+        let outcome = self.instantiate_and_run(module, input, stdout.clone(), stderr.clone());
+
+        WasmRun {
+            stdout: stdout.contents().to_vec(),
+            stderr: stderr.contents().to_vec(),
+            outcome,
+        }
+    }
+
+    fn instantiate_and_run(
+        &self,
+        module: &[u8],
+        input: Vec<u8>,
+        stdout: MemoryOutputPipe,
+        stderr: MemoryOutputPipe,
+    ) -> wasmtime::Result<()> {
+        let module = self.compile(module)?;
+        let wasi = WasiCtxBuilder::new()
+            .stdin(MemoryInputPipe::new(input))
+            .stdout(stdout)
+            .stderr(stderr)
+            .build_p1();
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .build();
+
+        let mut store = Store::new(&self.engine, WasmState { wasi, limits });
+        store.limiter(|state| &mut state.limits);
+        store.set_epoch_deadline(self.function_timeout.as_secs() / EPOCH_TICK.as_secs());
+
+        let instance = self.linker.instantiate(&mut store, &module)?;
+        let start = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+        match start.call(&mut store, ()) {
+            Ok(()) => Ok(()),
+            // Exiting with a zero status is how a WASI command finishes early
+            Err(err) if matches!(err.downcast_ref::<I32Exit>(), Some(I32Exit(0))) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Compiles the module, or reuses it if it was compiled recently. Modules are compiled
+    /// without holding the cache lock, so the same module may occasionally be compiled twice.
+    fn compile(&self, code: &[u8]) -> wasmtime::Result<Module> {
+        let hash = blake3::hash(code);
+        if let Some(module) = self.lock_modules().get(&hash) {
+            return Ok(module);
+        }
+
+        let module = Module::new(&self.engine, code)?;
+        self.lock_modules().insert(hash, module.clone());
+        Ok(module)
+    }
+
+    fn lock_modules(&self) -> MutexGuard<'_, ModuleCache> {
+        self.modules.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn log_stderr(stderr: &[u8], sensitive_strings: &SensitiveStrings) {
+        for line in String::from_utf8_lossy(stderr).lines() {
+            if !line.is_empty() {
+                eprintln!("{}", sensitive_strings.redact(line));
+            }
+        }
+    }
+
+    fn failure(
+        execution_id: &str,
+        kind: FunctionResultFailureErrorKind,
+        message: String,
+    ) -> FunctionResult<ResolverFunctionResultSuccess> {
+        FunctionResult::Failure(FunctionResultFailure::new(
+            execution_id,
+            FunctionResultFailureError { kind, message },
+            crate::timestamp(),
+        ))
+    }
+
+    async fn send(
+        ws: &mut WebSocket,
+        message: Message<ResolverFunctionResultSuccess>,
+    ) -> Result<()> {
+        let msg = message
+            .serialize_to_string()
+            .map_err(ExecutionError::JSONSerialize)?;
+        ws.send(WebSocketMessage::Text(msg))
+            .await
+            .map_err(ExecutionError::WSSendIO)?;
+        Ok(())
+    }
+}
@@ -51,6 +51,15 @@ pub async fn create_change_set_and_update_ctx(
         .expect("could not update visibility and snapshot");
 }
 
+/// This function is used during macro expansion for setting up a context for a second user, who
+/// works in the same [`ChangeSet`] as the user of the default context.
+pub async fn join_change_set_as_new_user(ctx: &mut DalContext, change_set_id: ChangeSetId) {
+    ctx.update_visibility_and_snapshot_to_visibility(change_set_id)
+        .await
+        .expect("could not update visibility and snapshot");
+    setup_history_actor_ctx(ctx).await;
+}
+
 /// This function is used during macro expansion for setting up tracing in an integration test.
 pub fn tracing_init(span_events_env_var: &'static str, log_env_var: &'static str) {
     use std::thread;
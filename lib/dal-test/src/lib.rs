@@ -69,6 +69,7 @@ pub mod expand_helpers;
 pub mod expected;
 pub mod helpers;
 
+mod sdf_client;
 mod signup;
 mod test_exclusive_schemas;
 
@@ -76,6 +77,8 @@ pub use color_eyre::{
     self,
     eyre::{eyre, Result, WrapErr},
 };
+pub use reqwest;
+pub use sdf_client::SdfTestClient;
 pub use si_test_macros::{dal_test as test, sdf_test};
 pub use signup::WorkspaceSignup;
 pub use telemetry;
@@ -102,10 +105,22 @@ const ENV_VAR_PG_DBNAME: &str = "SI_TEST_PG_DBNAME";
 const ENV_VAR_PG_USER: &str = "SI_TEST_PG_USER";
 const ENV_VAR_PG_PORT: &str = "SI_TEST_PG_PORT";
 const ENV_VAR_KEEP_OLD_DBS: &str = "SI_TEST_KEEP_OLD_DBS";
+const ENV_VAR_REBUILD_TEMPLATE_DBS: &str = "SI_TEST_REBUILD_TEMPLATE_DBS";
 
 const ENV_VAR_LAYER_CACHE_PG_DBNAME: &str = "SI_TEST_LAYER_CACHE_PG_DBNAME";
 const ENV_VAR_AUDIT_PG_DBNAME: &str = "SI_TEST_AUDIT_PG_DBNAME";
 
+/// The sources, relative to the root of the repository, which determine the contents of the
+/// template databases besides the builtin packages.
+const TEMPLATE_DB_SOURCES: &[&str] = &[
+    "lib/audit-database/src/migrations",
+    "lib/dal/src/builtins",
+    "lib/dal/src/func/intrinsics.rs",
+    "lib/dal/src/migrations",
+    "lib/dal-test/src/test_exclusive_schemas",
+    "lib/si-layer-cache/src/migrations",
+];
+
 #[allow(missing_docs)]
 pub static COLOR_EYRE_INIT: Once = Once::new();
 
@@ -152,6 +167,25 @@ impl fmt::Debug for DalContextHeadMutRef<'_> {
     }
 }
 
+/// A [`DalContext`] for a second user, working in the same workspace and change set as the
+/// default `DalContext` of a test
+///
+/// The default `DalContext` (borrowed or owned) must be requested before this one in the test
+/// function's arguments.
+pub struct DalContextSecondUser(pub DalContext);
+
+impl fmt::Debug for DalContextSecondUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DalContextSecondUser")
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`WorkspaceSignup`] for a workspace other than the default workspace of a test, used for
+/// checking isolation between workspaces
+#[derive(Debug)]
+pub struct OtherWorkspaceSignup(pub WorkspaceSignup);
+
 /// An authentication token, used when making SDF API requests
 #[derive(Debug)]
 pub struct AuthToken(pub String);
@@ -324,6 +358,10 @@ impl TestContext {
     ///
     /// This functions wraps over a mutex which ensures that only the first caller will run global
     /// database creation, migrations, and other preparations.
+    ///
+    /// The migrated databases are kept as templates for the test-specific databases. If they were
+    /// set up by an identical test executable with identical builtin packages, migrations are
+    /// skipped entirely. Set `SI_TEST_REBUILD_TEMPLATE_DBS` to force re-creating them.
     #[allow(clippy::disallowed_methods)]
     pub async fn global(
         pg_dbname: &'static str,
@@ -457,6 +495,15 @@ impl TestContext {
     pub fn nats_conn(&self) -> &NatsClient {
         &self.nats_conn
     }
+
+    /// Gets the pools of all databases which are used as templates for test-specific databases.
+    fn template_pg_pools(&self) -> [&PgPool; 3] {
+        [
+            &self.pg_pool,
+            &self.layer_db_pg_pool,
+            self.audit_database_context.pg_pool(),
+        ]
+    }
 }
 
 /// A builder for a [`TestContext`].
@@ -764,6 +811,7 @@ pub async fn forklift_server(
             config.audit().insert_concurrency_limit,
         )),
         None,
+        &[],
         token,
     )
     .await
@@ -776,6 +824,31 @@ async fn global_setup(test_context_builer: TestContextBuilder) -> Result<()> {
     info!("running global test setup");
     let test_context = test_context_builer.build_for_global().await?;
 
+    debug!("initializing crypto");
+    sodiumoxide::init().map_err(|_| eyre!("failed to init sodiumoxide crypto"))?;
+
+    #[allow(clippy::expect_used)]
+    let pkgs_path = test_context
+        .config
+        .pkgs_path
+        .to_owned()
+        .expect("no pkgs path configured");
+
+    let fingerprint = template_fingerprint(pkgs_path.clone()).await?;
+    #[allow(clippy::disallowed_methods)] // Environment variables are used exclusively in test and
+    // all are prefixed with `SI_TEST_`
+    let rebuild_requested = env::var(ENV_VAR_REBUILD_TEMPLATE_DBS).is_ok_and(|v| !v.is_empty());
+    if let Some(fingerprint) = fingerprint.as_deref().filter(|_| !rebuild_requested) {
+        if template_dbs_match_fingerprint(&test_context, fingerprint).await? {
+            // The template databases were migrated from identical migrations, intrinsics, test
+            // exclusive schemas and builtins, so tests can be created from them as they are. Old
+            // test-specific databases are left alone as they may belong to a concurrently running
+            // test process.
+            info!(%fingerprint, "template databases are up to date, skipping global test setup");
+            return Ok(());
+        }
+    }
+
     // We need to be the only person connected to the real database. This drops all connections
     // that aren't this one from the database. This disconnects the PgBouncers, any client
     // terminals, and anyone else - ensuring we always get the global template to ourselves.
@@ -790,9 +863,6 @@ async fn global_setup(test_context_builer: TestContextBuilder) -> Result<()> {
     )
     .await?;
 
-    debug!("initializing crypto");
-    sodiumoxide::init().map_err(|_| eyre!("failed to init sodiumoxide crypto"))?;
-
     let token = CancellationToken::new();
     let tracker = TaskTracker::new();
 
@@ -824,6 +894,11 @@ async fn global_setup(test_context_builer: TestContextBuilder) -> Result<()> {
             .wrap_err("failed to drop old test-specific content store databases")?;
     }
 
+    // Unmark the templates first, so they are never reused while only partially migrated
+    set_template_dbs_fingerprint(&test_context, None)
+        .await
+        .wrap_err("failed to unmark template databases")?;
+
     // Ensure the database is totally clean, then run all migrations
     info!("dropping and re-creating the database schema");
     services_ctx
@@ -888,13 +963,6 @@ async fn global_setup(test_context_builer: TestContextBuilder) -> Result<()> {
 
     tracker.close();
 
-    #[allow(clippy::expect_used)]
-    let pkgs_path = test_context
-        .config
-        .pkgs_path
-        .to_owned()
-        .expect("no pkgs path configured");
-
     info!("creating builtins");
     migrate_local_builtins(
         services_ctx.pg_pool(),
@@ -919,10 +987,129 @@ async fn global_setup(test_context_builer: TestContextBuilder) -> Result<()> {
     token.cancel();
     tracker.wait().await;
 
+    // Only mark the templates once everything has been migrated, so an interrupted setup is
+    // always redone by the next run
+    if let Some(fingerprint) = &fingerprint {
+        info!(%fingerprint, "marking template databases as up to date");
+        set_template_dbs_fingerprint(&test_context, Some(fingerprint))
+            .await
+            .wrap_err("failed to mark template databases")?;
+    }
+
     info!("global test setup complete");
     Ok(())
 }
 
+/// Computes a fingerprint of everything which ends up in the migrated template databases: the
+/// sources of the database migrations, the intrinsic funcs and the test exclusive schemas, along
+/// with the builtin packages read from `pkgs_path`.
+///
+/// Returns `None` when the sources can't be found, e.g. when the tests were not built from this
+/// checkout, in which case the template databases are always migrated from scratch. Changes to how
+/// the dal imports packages are not covered, so the rebuild has to be requested with
+/// `SI_TEST_REBUILD_TEMPLATE_DBS` after making one.
+async fn template_fingerprint(pkgs_path: PathBuf) -> Result<Option<String>> {
+    let Some(manifest_dir) = option_env!("CARGO_MANIFEST_DIR") else {
+        return Ok(None);
+    };
+    let repo_root = Path::new(manifest_dir).join("../..");
+
+    tokio::task::spawn_blocking(move || {
+        let mut hasher = blake3::Hasher::new();
+
+        for source in TEMPLATE_DB_SOURCES {
+            let path = repo_root.join(source);
+            if !path.exists() {
+                debug!(path = %path.display(), "template database source not found");
+                return Ok(None);
+            }
+            hash_path(&mut hasher, &path)
+                .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        }
+        hash_path(&mut hasher, &pkgs_path)
+            .wrap_err_with(|| format!("failed to read pkgs path: {}", pkgs_path.display()))?;
+
+        Ok(Some(hasher.finalize().to_hex().to_string()))
+    })
+    .await?
+}
+
+/// Hashes the name and contents of a file, or of every file under a directory in a stable order.
+fn hash_path(hasher: &mut blake3::Hasher, path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        let mut entries = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            hash_path(hasher, &entry)?;
+        }
+    } else if path.is_file() {
+        if let Some(file_name) = path.file_name() {
+            hasher.update(file_name.as_encoded_bytes());
+        }
+        hasher.update_reader(std::fs::File::open(path)?)?;
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if all template databases were marked with the given fingerprint by a
+/// previously completed global setup.
+async fn template_dbs_match_fingerprint(
+    test_context: &TestContext,
+    fingerprint: &str,
+) -> Result<bool> {
+    for pg_pool in test_context.template_pg_pools() {
+        let conn = pg_pool.get().await?;
+        let row = conn
+            .query_opt(
+                "SELECT shobj_description(oid, 'pg_database') AS fingerprint
+                    FROM pg_database
+                    WHERE datname = current_database()",
+                &[],
+            )
+            .await?;
+        let current: Option<String> = match row {
+            Some(row) => row.try_get("fingerprint")?,
+            None => None,
+        };
+        if current.as_deref() != Some(fingerprint) {
+            debug!(
+                db_name = %pg_pool.db_name(),
+                ?current,
+                "template database fingerprint does not match",
+            );
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Stores the fingerprint as the comment of each template database, or clears it if `None` is
+/// given. Database comments are not copied to databases created from a template, so
+/// test-specific databases remain unmarked.
+async fn set_template_dbs_fingerprint(
+    test_context: &TestContext,
+    fingerprint: Option<&str>,
+) -> Result<()> {
+    let comment = match fingerprint {
+        Some(fingerprint) => format!("'{fingerprint}'"),
+        None => "NULL".to_string(),
+    };
+    for pg_pool in test_context.template_pg_pools() {
+        let conn = pg_pool.get().await?;
+        conn.execute(
+            &format!("COMMENT ON DATABASE {} IS {comment}", pg_pool.db_name()),
+            &[],
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 #[instrument(level = "info", skip_all)]
 async fn migrate_local_builtins(
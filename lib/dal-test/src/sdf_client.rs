@@ -0,0 +1,71 @@
+//! This module contains [`SdfTestClient`], which is created during
+//! [`macro expansion`](crate::expand_helpers) for tests that make requests against a running sdf
+//! server.
+
+use color_eyre::eyre::WrapErr;
+use reqwest::{Method, RequestBuilder};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// An HTTP client for an sdf server started for a single test, authenticated as the user of the
+/// test's workspace.
+#[derive(Clone, Debug)]
+pub struct SdfTestClient {
+    client: reqwest::Client,
+    base_url: String,
+    auth_token: String,
+}
+
+impl SdfTestClient {
+    /// Creates a client for the server listening at `base_url` which sends `auth_token` as a
+    /// bearer token with each request.
+    pub fn new(base_url: impl Into<String>, auth_token: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into(),
+            auth_token: auth_token.into(),
+        }
+    }
+
+    /// Gets the base URL of the server, such as `http://127.0.0.1:43219`.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Gets the auth token sent with each request.
+    pub fn auth_token(&self) -> &str {
+        &self.auth_token
+    }
+
+    /// Starts an authenticated request for a path, such as `/api/change_set/list_open_change_sets`.
+    pub fn request(&self, method: Method, path: impl AsRef<str>) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", self.base_url, path.as_ref()))
+            .bearer_auth(&self.auth_token)
+    }
+
+    /// Sends an authenticated `GET` request and deserializes the JSON response.
+    pub async fn get<Res: DeserializeOwned>(&self, path: impl AsRef<str>) -> crate::Result<Res> {
+        Self::send(self.request(Method::GET, path)).await
+    }
+
+    /// Sends an authenticated `POST` request with a JSON body and deserializes the JSON response.
+    pub async fn post<Req: Serialize + ?Sized, Res: DeserializeOwned>(
+        &self,
+        path: impl AsRef<str>,
+        body: &Req,
+    ) -> crate::Result<Res> {
+        Self::send(self.request(Method::POST, path).json(body)).await
+    }
+
+    async fn send<Res: DeserializeOwned>(request: RequestBuilder) -> crate::Result<Res> {
+        request
+            .send()
+            .await
+            .wrap_err("failed to send request to sdf")?
+            .error_for_status()
+            .wrap_err("sdf responded with an error status")?
+            .json()
+            .await
+            .wrap_err("failed to deserialize sdf response")
+    }
+}
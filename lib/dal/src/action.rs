@@ -11,6 +11,9 @@ use thiserror::Error;
 
 use crate::{
     action::{
+        concurrency_limit::{
+            ActionConcurrencyLimitError, ActionConcurrencyLimits, ActionConcurrencyTracker,
+        },
         dependency_graph::ActionDependencyGraph,
         prototype::{ActionKind, ActionPrototype, ActionPrototypeError},
     },
@@ -27,12 +30,17 @@ use crate::{
     WorkspaceSnapshotError, WsEvent, WsEventError, WsEventResult, WsPayload,
 };
 
+pub mod concurrency_limit;
 pub mod dependency_graph;
+pub mod plan;
 pub mod prototype;
+pub mod run;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ActionError {
+    #[error("action concurrency limit error: {0}")]
+    ActionConcurrencyLimit(#[from] ActionConcurrencyLimitError),
     #[error("action prototype error: {0}")]
     ActionPrototype(#[from] ActionPrototypeError),
     #[error("AttributeValue error: {0}")]
@@ -553,6 +561,9 @@ impl Action {
     ///   * The graph of values for `DependentValuesUpdate` does *NOT* include
     ///     *ANY* [`AttributeValue`s](AttributeValue) for the same
     ///     [`Component`](crate::Component) as the [`Action`].
+    ///   * Dispatching the action would not exceed the workspace's
+    ///     [`ActionConcurrencyLimits`]. Actions held back by a limit are considered in
+    ///     [`ActionId`] order, so the oldest queued actions are dispatched first.
     pub async fn eligible_to_dispatch(ctx: &DalContext) -> ActionResult<Vec<ActionId>> {
         let action_dependency_graph = ActionDependencyGraph::for_workspace(ctx).await?;
         let mut result = Vec::new();
@@ -573,7 +584,20 @@ impl Action {
             dvu_component_ids.insert(AttributeValue::component_id(ctx, *av_id).await?);
         }
 
-        for possible_action_id in action_dependency_graph.independent_actions() {
+        let concurrency_limits = ActionConcurrencyLimits::for_workspace(ctx).await?;
+        let mut concurrency_tracker = if concurrency_limits.is_empty() {
+            None
+        } else {
+            Some(
+                ActionConcurrencyTracker::new(ctx, concurrency_limits, &Self::all_ids(ctx).await?)
+                    .await?,
+            )
+        };
+
+        let mut possible_action_ids = action_dependency_graph.independent_actions();
+        possible_action_ids.sort();
+
+        for possible_action_id in possible_action_ids {
             let action = Action::get_by_id(ctx, possible_action_id).await?;
 
             if action.is_eligible_to_dispatch() {
@@ -586,6 +610,15 @@ impl Action {
                         continue;
                     }
                 }
+                if let Some(tracker) = concurrency_tracker.as_mut() {
+                    let schema_id = tracker
+                        .schema_id_for_action(ctx, possible_action_id)
+                        .await?;
+                    if !tracker.try_admit(schema_id) {
+                        // Leave the action queued until enough in-flight actions finish.
+                        continue;
+                    }
+                }
                 result.push(possible_action_id);
             }
         }
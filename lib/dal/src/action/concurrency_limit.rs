@@ -0,0 +1,259 @@
+//! This module contains [`ActionConcurrencyLimits`], which bound how many [`Actions`](Action) may
+//! be in flight (dispatched or running) at once for a workspace, optionally narrowed to the
+//! components of a single [`Schema`](crate::Schema).
+//!
+//! Limits are enforced when dispatching in [`Action::eligible_to_dispatch`]: actions beyond a
+//! limit stay queued and are dispatched in [`ActionId`] order as in-flight actions finish.
+
+use std::collections::HashMap;
+
+use si_data_pg::PgError;
+use thiserror::Error;
+
+use crate::{Component, DalContext, SchemaId, TransactionsError};
+
+use super::{Action, ActionId, ActionResult, ActionState};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ActionConcurrencyLimitError {
+    #[error("invalid max concurrency (must be at least 1): {0}")]
+    InvalidMaxConcurrency(u32),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ActionConcurrencyLimitResult<T> = Result<T, ActionConcurrencyLimitError>;
+
+/// The configured action concurrency limits for a workspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionConcurrencyLimits {
+    workspace: Option<usize>,
+    schemas: HashMap<SchemaId, usize>,
+}
+
+impl ActionConcurrencyLimits {
+    /// Load the limits for the workspace of the provided [`DalContext`].
+    pub async fn for_workspace(ctx: &DalContext) -> ActionConcurrencyLimitResult<Self> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT schema_id, max_concurrency FROM action_concurrency_limits WHERE workspace_pk = $1",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        let mut limits = Self::default();
+        for row in rows {
+            let schema_id: Option<SchemaId> = row.try_get("schema_id")?;
+            let max_concurrency: i32 = row.try_get("max_concurrency")?;
+            let max_concurrency = max_concurrency.max(1) as usize;
+            match schema_id {
+                Some(schema_id) => {
+                    limits.schemas.insert(schema_id, max_concurrency);
+                }
+                None => limits.workspace = Some(max_concurrency),
+            }
+        }
+
+        Ok(limits)
+    }
+
+    /// The maximum number of actions that may be in flight across the whole workspace.
+    pub fn workspace(&self) -> Option<usize> {
+        self.workspace
+    }
+
+    /// The maximum number of actions that may be in flight for components of the [`Schema`].
+    ///
+    /// [`Schema`]: crate::Schema
+    pub fn schema(&self, schema_id: SchemaId) -> Option<usize> {
+        self.schemas.get(&schema_id).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.workspace.is_none() && self.schemas.is_empty()
+    }
+
+    /// Set (or remove, with `None`) the limit covering all actions in the workspace of the
+    /// provided [`DalContext`].
+    pub async fn set_workspace_limit(
+        ctx: &DalContext,
+        max_concurrency: Option<u32>,
+    ) -> ActionConcurrencyLimitResult<()> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        match max_concurrency {
+            Some(max_concurrency) => {
+                let max_concurrency = Self::validate(max_concurrency)?;
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(
+                        "INSERT INTO action_concurrency_limits (workspace_pk, schema_id, max_concurrency)
+                            VALUES ($1, NULL, $2)
+                        ON CONFLICT (workspace_pk) WHERE schema_id IS NULL DO
+                        UPDATE SET max_concurrency = $2",
+                        &[&workspace_pk, &max_concurrency],
+                    )
+                    .await?;
+            }
+            None => {
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(
+                        "DELETE FROM action_concurrency_limits WHERE workspace_pk = $1 AND schema_id IS NULL",
+                        &[&workspace_pk],
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set (or remove, with `None`) the limit for actions on components of the [`Schema`] in the
+    /// workspace of the provided [`DalContext`].
+    ///
+    /// [`Schema`]: crate::Schema
+    pub async fn set_schema_limit(
+        ctx: &DalContext,
+        schema_id: SchemaId,
+        max_concurrency: Option<u32>,
+    ) -> ActionConcurrencyLimitResult<()> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        match max_concurrency {
+            Some(max_concurrency) => {
+                let max_concurrency = Self::validate(max_concurrency)?;
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(
+                        "INSERT INTO action_concurrency_limits (workspace_pk, schema_id, max_concurrency)
+                            VALUES ($1, $2, $3)
+                        ON CONFLICT (workspace_pk, schema_id) WHERE schema_id IS NOT NULL DO
+                        UPDATE SET max_concurrency = $3",
+                        &[&workspace_pk, &schema_id, &max_concurrency],
+                    )
+                    .await?;
+            }
+            None => {
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(
+                        "DELETE FROM action_concurrency_limits WHERE workspace_pk = $1 AND schema_id = $2",
+                        &[&workspace_pk, &schema_id],
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate(max_concurrency: u32) -> ActionConcurrencyLimitResult<i32> {
+        match i32::try_from(max_concurrency) {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err(ActionConcurrencyLimitError::InvalidMaxConcurrency(
+                max_concurrency,
+            )),
+        }
+    }
+}
+
+/// Tracks in-flight actions against [`ActionConcurrencyLimits`] while deciding what to dispatch.
+#[derive(Debug)]
+pub(crate) struct ActionConcurrencyTracker {
+    limits: ActionConcurrencyLimits,
+    workspace_in_flight: usize,
+    schema_in_flight: HashMap<SchemaId, usize>,
+}
+
+impl ActionConcurrencyTracker {
+    /// Count every action that is currently dispatched or running.
+    pub(crate) async fn new(
+        ctx: &DalContext,
+        limits: ActionConcurrencyLimits,
+        action_ids: &[ActionId],
+    ) -> ActionResult<Self> {
+        let mut tracker = Self {
+            limits,
+            workspace_in_flight: 0,
+            schema_in_flight: HashMap::new(),
+        };
+
+        for &action_id in action_ids {
+            let action = Action::get_by_id(ctx, action_id).await?;
+            if matches!(
+                action.state(),
+                ActionState::Dispatched | ActionState::Running
+            ) {
+                let schema_id = tracker.schema_id_for_action(ctx, action_id).await?;
+                tracker.record(schema_id);
+            }
+        }
+
+        Ok(tracker)
+    }
+
+    /// Returns whether one more action for the schema (if any) fits within the limits and, if it
+    /// does, counts it as in flight.
+    pub(crate) fn try_admit(&mut self, schema_id: Option<SchemaId>) -> bool {
+        if self
+            .limits
+            .workspace()
+            .is_some_and(|limit| self.workspace_in_flight >= limit)
+        {
+            return false;
+        }
+        if let Some(schema_id) = schema_id {
+            let in_flight = self.schema_in_flight.get(&schema_id).copied().unwrap_or(0);
+            if self
+                .limits
+                .schema(schema_id)
+                .is_some_and(|limit| in_flight >= limit)
+            {
+                return false;
+            }
+        }
+
+        self.record(schema_id);
+        true
+    }
+
+    /// Only look up the schema when there are per-schema limits to check it against.
+    pub(crate) async fn schema_id_for_action(
+        &self,
+        ctx: &DalContext,
+        action_id: ActionId,
+    ) -> ActionResult<Option<SchemaId>> {
+        if self.limits.schemas.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(match Action::component_id(ctx, action_id).await? {
+            Some(component_id) => Some(
+                Component::schema_for_component_id(ctx, component_id)
+                    .await?
+                    .id(),
+            ),
+            None => None,
+        })
+    }
+
+    fn record(&mut self, schema_id: Option<SchemaId>) {
+        self.workspace_in_flight += 1;
+        if let Some(schema_id) = schema_id {
+            *self.schema_in_flight.entry(schema_id).or_default() += 1;
+        }
+    }
+}
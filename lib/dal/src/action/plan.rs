@@ -0,0 +1,127 @@
+//! This module contains [`ActionPlan`], a preview of what the queued [`Actions`](Action) of a
+//! change set would do, produced by running their funcs as dry runs.
+//!
+//! Only [`ActionKind::Create`], [`ActionKind::Update`] and [`ActionKind::Destroy`] actions are
+//! simulated. The funcs receive `simulate: true` and are not allowed to use helpers with side
+//! effects, so the plan is only as accurate as the funcs' handling of the flag.
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use veritech_client::ResourceStatus;
+
+use crate::{Component, ComponentId, DalContext};
+
+use super::{
+    prototype::{ActionKind, ActionPrototype},
+    Action, ActionError, ActionId, ActionResult,
+};
+
+/// What a single [`Action`] would do.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPlanEntry {
+    pub action_id: ActionId,
+    pub kind: ActionKind,
+    pub name: String,
+    /// The status reported by the simulated func, or `None` if it did not return a result.
+    pub status: Option<ResourceStatus>,
+    pub message: Option<String>,
+    /// The resource payload of the component before the action.
+    pub current_payload: Option<serde_json::Value>,
+    /// The resource payload the simulated func reported the action would result in.
+    pub planned_payload: Option<serde_json::Value>,
+}
+
+impl ActionPlanEntry {
+    pub fn is_ok(&self) -> bool {
+        self.status == Some(ResourceStatus::Ok)
+    }
+}
+
+/// The [`ActionPlanEntries`](ActionPlanEntry) for a component, in the order the actions would
+/// run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentActionPlan {
+    pub component_id: ComponentId,
+    pub entries: Vec<ActionPlanEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPlan {
+    pub components: Vec<ComponentActionPlan>,
+}
+
+impl ActionPlan {
+    /// The kinds of [`Actions`](Action) which are simulated.
+    pub const SIMULATED_KINDS: [ActionKind; 3] =
+        [ActionKind::Create, ActionKind::Update, ActionKind::Destroy];
+
+    /// Simulate every queued [`Action`] of the change set of the provided [`DalContext`], in the
+    /// order they would be dispatched.
+    #[instrument(name = "action.plan.for_change_set", level = "info", skip_all)]
+    pub async fn for_change_set(ctx: &DalContext) -> ActionResult<Self> {
+        let mut plan = Self::default();
+
+        for action_id in Action::list_topologically(ctx).await? {
+            let prototype_id = Action::prototype_id(ctx, action_id).await?;
+            let prototype = ActionPrototype::get_by_id(ctx, prototype_id).await?;
+            if !Self::SIMULATED_KINDS.contains(&prototype.kind) {
+                continue;
+            }
+            let component_id = Action::component_id(ctx, action_id)
+                .await?
+                .ok_or(ActionError::ComponentNotFoundForAction(action_id))?;
+
+            let current_payload = Component::resource_by_id(ctx, component_id)
+                .await?
+                .and_then(|resource| resource.payload);
+            let maybe_run_result =
+                ActionPrototype::simulate(ctx, prototype_id, component_id).await?;
+
+            let entry = ActionPlanEntry {
+                action_id,
+                kind: prototype.kind,
+                name: prototype.name().to_owned(),
+                status: maybe_run_result.as_ref().map(|result| result.status),
+                message: maybe_run_result
+                    .as_ref()
+                    .and_then(|result| result.message.clone()),
+                current_payload,
+                planned_payload: maybe_run_result.and_then(|result| result.payload),
+            };
+            plan.push(component_id, entry);
+        }
+
+        Ok(plan)
+    }
+
+    fn push(&mut self, component_id: ComponentId, entry: ActionPlanEntry) {
+        match self
+            .components
+            .iter_mut()
+            .find(|component| component.component_id == component_id)
+        {
+            Some(component) => component.entries.push(entry),
+            None => self.components.push(ComponentActionPlan {
+                component_id,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    pub fn for_component(&self, component_id: ComponentId) -> Option<&ComponentActionPlan> {
+        self.components
+            .iter()
+            .find(|component| component.component_id == component_id)
+    }
+
+    /// Returns every entry whose simulated func did not report success.
+    pub fn failures(&self) -> impl Iterator<Item = &ActionPlanEntry> {
+        self.components
+            .iter()
+            .flat_map(|component| &component.entries)
+            .filter(|entry| !entry.is_ok())
+    }
+}
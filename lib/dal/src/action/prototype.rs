@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use si_events::{ActionResultState, FuncRunId};
 use si_layer_cache::LayerDbError;
 use si_pkg::ActionFuncSpecKind;
-use strum::Display;
+use strum::{Display, EnumString};
 use thiserror::Error;
 use veritech_client::{ActionRunResultSuccess, ResourceStatus};
 
@@ -69,7 +69,7 @@ pub enum ActionPrototypeError {
 pub type ActionPrototypeResult<T> = Result<T, ActionPrototypeError>;
 
 #[remain::sorted]
-#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Display, Hash)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Display, EnumString, Hash)]
 pub enum ActionKind {
     /// Create the "outside world" version of the modeled object.
     Create,
@@ -296,24 +296,55 @@ impl ActionPrototype {
         ctx: &DalContext,
         id: ActionPrototypeId,
         component_id: ComponentId,
+    ) -> ActionPrototypeResult<(Option<ActionRunResultSuccess>, FuncRunId)> {
+        Self::run_inner(ctx, id, component_id, false).await
+    }
+
+    /// Runs the [`ActionPrototype`]'s func as a dry run. The func receives `simulate: true` and
+    /// is not allowed to use helpers with side effects. Unlike [`Self::run`], the func run is not
+    /// persisted, so it is neither recorded as the outcome of the action nor shown in its history.
+    pub async fn simulate(
+        ctx: &DalContext,
+        id: ActionPrototypeId,
+        component_id: ComponentId,
+    ) -> ActionPrototypeResult<Option<ActionRunResultSuccess>> {
+        let (maybe_run_result, _) = Self::run_inner(ctx, id, component_id, true).await?;
+        Ok(maybe_run_result)
+    }
+
+    async fn run_inner(
+        ctx: &DalContext,
+        id: ActionPrototypeId,
+        component_id: ComponentId,
+        simulate: bool,
     ) -> ActionPrototypeResult<(Option<ActionRunResultSuccess>, FuncRunId)> {
         let component = Component::get_by_id(ctx, component_id).await?;
         let component_view = component.view(ctx).await?;
         let func_id = Self::func_id(ctx, id).await?;
 
-        let result_channel = FuncRunner::run_action(
-            ctx,
-            id,
-            component_id,
-            func_id,
-            serde_json::json!({ "properties" : component_view }),
-        )
-        .await?;
+        let result_channel = if simulate {
+            let args = serde_json::json!({ "properties" : component_view, "simulate": true });
+            FuncRunner::simulate_action(ctx, id, component_id, func_id, args).await?
+        } else {
+            let args = serde_json::json!({ "properties" : component_view });
+            FuncRunner::run_action(ctx, id, component_id, func_id, args).await?
+        };
 
         let func_run_value = result_channel
             .await
             .map_err(|_| ActionPrototypeError::FuncRunnerSend)??;
 
+        // Simulated runs are not persisted, so there is nothing to record their result on
+        if simulate {
+            let maybe_run_result = match func_run_value.value() {
+                Some(value) => Some(serde_json::from_value::<ActionRunResultSuccess>(
+                    value.clone(),
+                )?),
+                None => None,
+            };
+            return Ok((maybe_run_result, func_run_value.func_run_id()));
+        }
+
         let content_value: Option<si_events::CasValue> =
             func_run_value.value().cloned().map(Into::into);
         let content_unprocessed_value: Option<si_events::CasValue> =
@@ -349,7 +380,8 @@ impl ActionPrototype {
             None => None,
         };
 
-        ctx.layer_db()
+        let func_run = ctx
+            .layer_db()
             .func_run()
             .set_values_and_set_state_to_success(
                 func_run_value.func_run_id(),
@@ -359,6 +391,7 @@ impl ActionPrototype {
                 ctx.events_actor(),
             )
             .await?;
+        FuncRunner::publish_status(ctx, &func_run).await;
 
         let maybe_run_result = match func_run_value.value() {
             Some(value) => Some(serde_json::from_value::<ActionRunResultSuccess>(
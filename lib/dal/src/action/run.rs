@@ -0,0 +1,263 @@
+//! The history of the runs of [`Actions`](crate::Action).
+//!
+//! An action only knows its latest state, and is removed from the graph once it succeeds. Every
+//! attempt at running one is recorded as an [`ActionRun`], along with when it started and
+//! finished, how it went and the func run whose logs it produced, so that what happened to a
+//! component's resource can be looked back on.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use si_events::{content_hash::ContentHashParseError, ContentHash, FuncRunId, FuncRunLog};
+use si_layer_cache::LayerDbError;
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+use crate::{
+    action::{prototype::ActionKind, ActionId},
+    ChangeSetId, ComponentId, DalContext, TransactionsError,
+};
+
+pub use si_id::ActionRunId;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ActionRunError {
+    #[error("content hash parse error: {0}")]
+    ContentHashParse(#[from] ContentHashParseError),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ActionRunResult<T> = Result<T, ActionRunError>;
+
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize)]
+pub enum ActionRunStatus {
+    Failed,
+    Running,
+    Succeeded,
+}
+
+/// One attempt at running an action.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionRun {
+    pub id: ActionRunId,
+    /// The change set the action was run in.
+    pub change_set_id: ChangeSetId,
+    /// The change set the action was enqueued in.
+    pub originating_change_set_id: ChangeSetId,
+    pub action_id: ActionId,
+    pub component_id: ComponentId,
+    /// The func run of the action's function, which holds the logs of this attempt. Missing if
+    /// the attempt failed before the function was run.
+    pub func_run_id: Option<FuncRunId>,
+    pub kind: ActionKind,
+    /// Starts at 1 and goes up every time the same action is retried.
+    pub attempt: i32,
+    pub status: ActionRunStatus,
+    /// The hash of the resource payload the function returned, if any.
+    pub result_payload_hash: Option<ContentHash>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+}
+
+impl TryFrom<PgRow> for ActionRun {
+    type Error = ActionRunError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let kind: String = row.try_get("kind")?;
+        let status: String = row.try_get("status")?;
+        let result_payload_hash: Option<String> = row.try_get("result_payload_hash")?;
+        let started_at: DateTime<Utc> = row.try_get("started_at")?;
+        let finished_at: Option<DateTime<Utc>> = row.try_get("finished_at")?;
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            change_set_id: row.try_get("change_set_id")?,
+            originating_change_set_id: row.try_get("originating_change_set_id")?,
+            action_id: row.try_get("action_id")?,
+            component_id: row.try_get("component_id")?,
+            func_run_id: row.try_get("func_run_id")?,
+            kind: kind.parse()?,
+            attempt: row.try_get("attempt")?,
+            status: status.parse()?,
+            result_payload_hash: result_payload_hash.as_deref().map(str::parse).transpose()?,
+            started_at,
+            finished_at,
+            duration_ms: finished_at
+                .map(|finished_at| (finished_at - started_at).num_milliseconds()),
+        })
+    }
+}
+
+impl ActionRun {
+    /// Records the start of a new attempt at running the action, in the change set of the
+    /// context.
+    pub async fn start(
+        ctx: &DalContext,
+        action_id: ActionId,
+        originating_change_set_id: ChangeSetId,
+        component_id: ComponentId,
+        kind: ActionKind,
+    ) -> ActionRunResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO action_runs (workspace_pk, change_set_id, originating_change_set_id,
+                        action_id, component_id, kind, attempt, status)
+                    SELECT $1, $2, $3, $4, $5, $6, COALESCE(MAX(attempt), 0) + 1, $7
+                        FROM action_runs WHERE action_id = $4
+                    RETURNING *",
+                &[
+                    &ctx.workspace_pk()?,
+                    &ctx.change_set_id(),
+                    &originating_change_set_id,
+                    &action_id,
+                    &component_id,
+                    &kind.to_string(),
+                    &ActionRunStatus::Running.to_string(),
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Records how the attempt went.
+    pub async fn finish(
+        &mut self,
+        ctx: &DalContext,
+        status: ActionRunStatus,
+        func_run_id: Option<FuncRunId>,
+        result_payload_hash: Option<ContentHash>,
+    ) -> ActionRunResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "UPDATE action_runs
+                    SET status = $2, func_run_id = $3, result_payload_hash = $4,
+                        finished_at = CLOCK_TIMESTAMP()
+                    WHERE id = $1
+                    RETURNING *",
+                &[
+                    &self.id,
+                    &status.to_string(),
+                    &func_run_id,
+                    &result_payload_hash,
+                ],
+            )
+            .await?;
+        *self = Self::try_from(row)?;
+
+        Ok(())
+    }
+
+    /// Marks the attempts at running the action which never finished as failed. Used when the
+    /// action job bailed out before it could record how the attempt went.
+    pub async fn fail_unfinished_for_action(
+        ctx: &DalContext,
+        action_id: ActionId,
+    ) -> ActionRunResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "UPDATE action_runs SET status = $2, finished_at = CLOCK_TIMESTAMP()
+                    WHERE action_id = $1 AND finished_at IS NULL",
+                &[&action_id, &ActionRunStatus::Failed.to_string()],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lists the attempts at running the action, oldest first.
+    pub async fn list_for_action(
+        ctx: &DalContext,
+        action_id: ActionId,
+    ) -> ActionRunResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM action_runs
+                    WHERE workspace_pk = $1 AND action_id = $2
+                    ORDER BY attempt ASC",
+                &[&ctx.workspace_pk()?, &action_id],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Lists the action runs for the component, most recent first.
+    pub async fn list_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ActionRunResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM action_runs
+                    WHERE workspace_pk = $1 AND component_id = $2
+                    ORDER BY started_at DESC",
+                &[&ctx.workspace_pk()?, &component_id],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Lists the runs of the actions which were enqueued in the change set, most recent first.
+    /// Actions are usually run once their change set has been applied, so these are runs on
+    /// HEAD.
+    pub async fn list_for_change_set(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> ActionRunResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM action_runs
+                    WHERE workspace_pk = $1 AND originating_change_set_id = $2
+                    ORDER BY started_at DESC",
+                &[&ctx.workspace_pk()?, &change_set_id],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Returns the logs of the func run of this attempt, if the function got to run.
+    pub async fn logs(&self, ctx: &DalContext) -> ActionRunResult<Option<Arc<FuncRunLog>>> {
+        let Some(func_run_id) = self.func_run_id else {
+            return Ok(None);
+        };
+
+        Ok(ctx
+            .layer_db()
+            .func_run_log()
+            .get_for_func_run_id(func_run_id)
+            .await?)
+    }
+}
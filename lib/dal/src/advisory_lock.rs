@@ -0,0 +1,67 @@
+//! Session-level postgres advisory locks, for work which must only run once at a time across every
+//! server, such as [content garbage collection](crate::content_gc) and
+//! [snapshot pruning](crate::snapshot_pruning).
+//!
+//! A lock is held on a connection of its own, outside of any request transaction, until it is
+//! released. If it is dropped instead, the lock is released once its connection is closed or
+//! recycled by the pool.
+
+use si_data_pg::{InstrumentedClient, PgError, PgPool, PgPoolError};
+
+/// Keys of the advisory locks taken by dal. The migrations hold `42`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i64)]
+pub enum AdvisoryLockKey {
+    ContentGc = 4201,
+    SnapshotPruning = 4202,
+}
+
+/// A held advisory lock.
+#[derive(Debug)]
+pub struct AdvisoryLock {
+    client: InstrumentedClient,
+    key: AdvisoryLockKey,
+}
+
+impl AdvisoryLock {
+    /// Takes the lock, waiting for it to be released if it is held elsewhere.
+    pub async fn acquire<E>(pg_pool: &PgPool, key: AdvisoryLockKey) -> Result<Self, E>
+    where
+        E: From<PgError> + From<PgPoolError>,
+    {
+        let client = pg_pool.get().await?;
+        client
+            .query_one("SELECT pg_advisory_lock($1)", &[&(key as i64)])
+            .await?;
+
+        Ok(Self { client, key })
+    }
+
+    /// Takes the lock, returning `None` if it is held elsewhere.
+    pub async fn try_acquire<E>(pg_pool: &PgPool, key: AdvisoryLockKey) -> Result<Option<Self>, E>
+    where
+        E: From<PgError> + From<PgPoolError>,
+    {
+        let client = pg_pool.get().await?;
+        let row = client
+            .query_one(
+                "SELECT pg_try_advisory_lock($1) AS locked",
+                &[&(key as i64)],
+            )
+            .await?;
+        let locked: bool = row.try_get("locked")?;
+
+        Ok(locked.then_some(Self { client, key }))
+    }
+
+    pub async fn release<E>(self) -> Result<(), E>
+    where
+        E: From<PgError>,
+    {
+        self.client
+            .query_one("SELECT pg_advisory_unlock($1)", &[&(self.key as i64)])
+            .await?;
+
+        Ok(())
+    }
+}
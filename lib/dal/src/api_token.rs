@@ -0,0 +1,165 @@
+//! This module contains [`ApiToken`], the metadata for a long-lived automation token scoped to a
+//! workspace. The token itself is a signed JWT that is only returned when it is created; only its
+//! metadata is stored, so that tokens can be listed and revoked.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use thiserror::Error;
+
+use crate::{DalContext, HistoryActor, TransactionsError, UserPk, WorkspacePk};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ApiTokenError {
+    #[error("api tokens can only be created by users")]
+    NotCreatedByUser,
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ApiTokenResult<T> = Result<T, ApiTokenError>;
+
+pub use si_id::ApiTokenId;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    id: ApiTokenId,
+    workspace_pk: WorkspacePk,
+    name: String,
+    /// The user the token acts on behalf of.
+    created_by_user_pk: UserPk,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<PgRow> for ApiToken {
+    type Error = ApiTokenError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            name: row.try_get("name")?,
+            created_by_user_pk: row.try_get("created_by_user_pk")?,
+            created_at: row.try_get("created_at")?,
+            expires_at: row.try_get("expires_at")?,
+            revoked_at: row.try_get("revoked_at")?,
+        })
+    }
+}
+
+impl ApiToken {
+    pub fn id(&self) -> ApiTokenId {
+        self.id
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn created_by_user_pk(&self) -> UserPk {
+        self.created_by_user_pk
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    /// Whether the token can still be used, i.e. it has neither been revoked nor expired.
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && !self.expires_at.is_some_and(|at| at <= Utc::now())
+    }
+
+    /// Record a new [`ApiToken`] in the workspace of the provided [`DalContext`], on behalf of
+    /// the user in its [`HistoryActor`].
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl Into<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ApiTokenResult<Self> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let name = name.into();
+        let HistoryActor::User(user_pk) = ctx.history_actor() else {
+            return Err(ApiTokenError::NotCreatedByUser);
+        };
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO api_tokens (workspace_pk, name, created_by_user_pk, expires_at)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *",
+                &[&workspace_pk, &name, user_pk, &expires_at],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    pub async fn list_for_workspace(ctx: &DalContext) -> ApiTokenResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM api_tokens WHERE workspace_pk = $1 ORDER BY created_at",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Find an [`ApiToken`] by its [`ApiTokenId`] within the provided workspace.
+    pub async fn get_by_id(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        api_token_id: ApiTokenId,
+    ) -> ApiTokenResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM api_tokens WHERE id = $1 AND workspace_pk = $2",
+                &[&api_token_id, &workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Revoke the token. Revoking an already revoked token keeps the original revocation time.
+    pub async fn revoke(self, ctx: &DalContext) -> ApiTokenResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "UPDATE api_tokens SET revoked_at = COALESCE(revoked_at, CLOCK_TIMESTAMP())
+                WHERE id = $1
+                RETURNING *",
+                &[&self.id],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+}
@@ -30,6 +30,7 @@ use crate::{
 };
 
 use self::{
+    expression::ExpressionError,
     static_value::{StaticArgumentValue, StaticArgumentValueId},
     value_source::ValueSource,
 };
@@ -37,6 +38,7 @@ use self::{
 use super::AttributePrototypeError;
 pub use crate::workspace_snapshot::node_weight::attribute_prototype_argument_node_weight::ArgumentTargets;
 
+pub mod expression;
 pub mod static_value;
 pub mod value_source;
 
@@ -51,6 +53,8 @@ pub enum AttributePrototypeArgumentError {
     AttributeValue(String),
     #[error("change set error: {0}")]
     ChangeSet(#[from] ChangeSetError),
+    #[error("expression error: {0}")]
+    Expression(#[from] ExpressionError),
     #[error("func argument error: {0}")]
     FuncArgument(#[from] FuncArgumentError),
     #[error("helper error: {0}")]
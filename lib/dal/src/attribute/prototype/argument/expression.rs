@@ -0,0 +1,285 @@
+//! An [`Expression`] is a [`StaticArgumentValue`](super::static_value::StaticArgumentValue) that
+//! is templated on other values of the same component rather than being fixed JSON, such as
+//! `${/domain/name}-suffix`.
+//!
+//! References are prop paths relative to the root prop. An expression that is nothing but a
+//! single reference evaluates to the referenced value as is, so it must reference a prop of the
+//! same kind as the one it sets. Any other expression interpolates its references into a string,
+//! so it can only set string props and only reference scalar props. A literal `${` is written as
+//! `$${`.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{prop::PropPath, PropKind};
+
+const REFERENCE_START: &str = "${";
+const REFERENCE_END: char = '}';
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ExpressionError {
+    #[error("expression sets a {0} prop, but only expressions made of a single reference can set non-string props")]
+    InterpolationIntoNonString(PropKind),
+    #[error("reference {0} is to a {1} prop, which cannot be interpolated into a string")]
+    InterpolationOfNonScalar(String, PropKind),
+    #[error("invalid reference \"{0}\": references are absolute prop paths, such as /domain/name")]
+    InvalidReference(String),
+    #[error("expressions can only set scalar props, not {0} props")]
+    NonScalarTarget(PropKind),
+    #[error("reference {0} is to a {1} prop, but the expression sets a {2} prop")]
+    ReferenceKindMismatch(String, PropKind, PropKind),
+    #[error("value for reference {0} cannot be interpolated into a string: {1}")]
+    UninterpolatableValue(String, Value),
+    #[error("reference {0} was not resolved")]
+    UnresolvedReference(String),
+    #[error("unterminated reference in expression: {0}")]
+    UnterminatedReference(String),
+}
+
+pub type ExpressionResult<T> = Result<T, ExpressionError>;
+
+/// A reference to the value of a prop on the same component, such as `/domain/name`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ExpressionReference {
+    path: String,
+    parts: Vec<String>,
+}
+
+impl ExpressionReference {
+    fn parse(raw: &str) -> ExpressionResult<Self> {
+        let invalid = || ExpressionError::InvalidReference(raw.to_owned());
+
+        let parts: Vec<String> = raw
+            .trim()
+            .strip_prefix('/')
+            .ok_or_else(invalid)?
+            .split('/')
+            .map(ToOwned::to_owned)
+            .collect();
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            path: format!("/{}", parts.join("/")),
+            parts,
+        })
+    }
+
+    /// The normalized path of the reference, such as `/domain/name`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The full path of the referenced prop, starting from the root prop.
+    pub fn prop_path(&self) -> PropPath {
+        PropPath::new(std::iter::once("root").chain(self.parts.iter().map(String::as_str)))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Segment {
+    Literal(String),
+    Reference(ExpressionReference),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Expression {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl Expression {
+    pub fn parse(source: impl Into<String>) -> ExpressionResult<Self> {
+        let source = source.into();
+
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut rest = source.as_str();
+        while let Some(start) = rest.find(REFERENCE_START) {
+            let before = &rest[..start];
+            let after = &rest[start + REFERENCE_START.len()..];
+
+            if let Some(escaped) = before.strip_suffix('$') {
+                literal.push_str(escaped);
+                literal.push_str(REFERENCE_START);
+                rest = after;
+                continue;
+            }
+
+            let end = after
+                .find(REFERENCE_END)
+                .ok_or_else(|| ExpressionError::UnterminatedReference(source.clone()))?;
+
+            literal.push_str(before);
+            if !literal.is_empty() {
+                segments.push(Segment::Literal(std::mem::take(&mut literal)));
+            }
+            segments.push(Segment::Reference(ExpressionReference::parse(
+                &after[..end],
+            )?));
+
+            rest = &after[end + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { source, segments })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn references(&self) -> impl Iterator<Item = &ExpressionReference> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Reference(reference) => Some(reference),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    fn single_reference(&self) -> Option<&ExpressionReference> {
+        match self.segments.as_slice() {
+            [Segment::Reference(reference)] => Some(reference),
+            _ => None,
+        }
+    }
+
+    /// Checks that the expression can set a prop of `target_kind`, given the kinds of the props
+    /// its references resolve to, keyed by reference path.
+    pub fn check_types(
+        &self,
+        target_kind: PropKind,
+        referenced_kinds: &HashMap<String, PropKind>,
+    ) -> ExpressionResult<()> {
+        let kind_for = |reference: &ExpressionReference| {
+            referenced_kinds
+                .get(reference.path())
+                .copied()
+                .ok_or_else(|| ExpressionError::UnresolvedReference(reference.path().to_owned()))
+        };
+
+        if !target_kind.is_scalar() {
+            return Err(ExpressionError::NonScalarTarget(target_kind));
+        }
+
+        if let Some(reference) = self.single_reference() {
+            let kind = kind_for(reference)?;
+            if kind != target_kind {
+                return Err(ExpressionError::ReferenceKindMismatch(
+                    reference.path().to_owned(),
+                    kind,
+                    target_kind,
+                ));
+            }
+            return Ok(());
+        }
+
+        if target_kind != PropKind::String {
+            return Err(ExpressionError::InterpolationIntoNonString(target_kind));
+        }
+        for reference in self.references() {
+            let kind = kind_for(reference)?;
+            if !kind.is_scalar() {
+                return Err(ExpressionError::InterpolationOfNonScalar(
+                    reference.path().to_owned(),
+                    kind,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates the expression given the values of its references, keyed by reference path.
+    pub fn evaluate(&self, values: &HashMap<String, Value>) -> ExpressionResult<Value> {
+        let value_for = |reference: &ExpressionReference| {
+            values
+                .get(reference.path())
+                .ok_or_else(|| ExpressionError::UnresolvedReference(reference.path().to_owned()))
+        };
+
+        if let Some(reference) = self.single_reference() {
+            return value_for(reference).cloned();
+        }
+
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => rendered.push_str(literal),
+                Segment::Reference(reference) => match value_for(reference)? {
+                    // An unset reference leaves the whole expression unset rather than rendering
+                    // a partial string
+                    Value::Null => return Ok(Value::Null),
+                    Value::String(string) => rendered.push_str(string),
+                    Value::Bool(boolean) => rendered.push_str(&boolean.to_string()),
+                    Value::Number(number) => rendered.push_str(&number.to_string()),
+                    other => {
+                        return Err(ExpressionError::UninterpolatableValue(
+                            reference.path().to_owned(),
+                            other.to_owned(),
+                        ))
+                    }
+                },
+            }
+        }
+
+        Ok(Value::String(rendered))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_and_interpolates() {
+        let expression =
+            Expression::parse("${/domain/name}-$${literal}-${ /si/name }").expect("parses");
+
+        let paths: Vec<&str> = expression.references().map(|r| r.path()).collect();
+        assert_eq!(vec!["/domain/name", "/si/name"], paths);
+
+        let values = HashMap::from([
+            ("/domain/name".to_owned(), serde_json::json!("ship")),
+            ("/si/name".to_owned(), serde_json::json!(5)),
+        ]);
+        assert_eq!(
+            serde_json::json!("ship-${literal}-5"),
+            expression.evaluate(&values).expect("evaluates")
+        );
+
+        let values = HashMap::from([
+            ("/domain/name".to_owned(), serde_json::json!("ship")),
+            ("/si/name".to_owned(), Value::Null),
+        ]);
+        assert_eq!(
+            Value::Null,
+            expression.evaluate(&values).expect("evaluates")
+        );
+    }
+
+    #[test]
+    fn checks_types() {
+        let single = Expression::parse("${/domain/replicas}").expect("parses");
+        let kinds = HashMap::from([("/domain/replicas".to_owned(), PropKind::Integer)]);
+        assert!(single.check_types(PropKind::Integer, &kinds).is_ok());
+        assert!(single.check_types(PropKind::String, &kinds).is_err());
+
+        let template = Expression::parse("x${/domain/replicas}").expect("parses");
+        assert!(template.check_types(PropKind::String, &kinds).is_ok());
+        assert!(template.check_types(PropKind::Integer, &kinds).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_references() {
+        assert!(Expression::parse("${/domain/name").is_err());
+        assert!(Expression::parse("${domain/name}").is_err());
+        assert!(Expression::parse("${/domain//name}").is_err());
+    }
+}
@@ -3,30 +3,44 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    layer_db_types::{StaticArgumentValueContent, StaticArgumentValueContentV1},
+    layer_db_types::{StaticArgumentValueContent, StaticArgumentValueContentV2},
     workspace_snapshot::{
-        content_address::ContentAddress, node_weight::NodeWeight, WorkspaceSnapshotError,
+        content_address::ContentAddress,
+        edge_weight::{EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants},
+        node_weight::NodeWeight,
+        WorkspaceSnapshotError,
     },
-    DalContext, Timestamp,
+    DalContext, PropId, Timestamp,
 };
 
-use super::AttributePrototypeArgumentResult;
+use super::{expression::Expression, AttributePrototypeArgumentResult};
 
 pub use si_id::StaticArgumentValueId;
 
+/// Whether a [`StaticArgumentValue`] is passed to the function as is, or is an [`Expression`]
+/// evaluated against the component's other values first.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaticArgumentValueKind {
+    Expression,
+    Value,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct StaticArgumentValue {
     pub id: StaticArgumentValueId,
     pub timestamp: Timestamp,
+    /// For expressions, this is the source of the expression as a string.
     pub value: serde_json::Value,
+    pub kind: StaticArgumentValueKind,
 }
 
 impl StaticArgumentValue {
-    pub fn assemble(id: StaticArgumentValueId, inner: StaticArgumentValueContentV1) -> Self {
+    pub fn assemble(id: StaticArgumentValueId, inner: StaticArgumentValueContentV2) -> Self {
         Self {
             id,
             timestamp: inner.timestamp,
             value: inner.value.into(),
+            kind: inner.kind,
         }
     }
 
@@ -34,18 +48,65 @@ impl StaticArgumentValue {
         self.id
     }
 
+    /// Parses the [`Expression`] held by this value, if it is one.
+    pub fn expression(&self) -> AttributePrototypeArgumentResult<Option<Expression>> {
+        Ok(match (self.kind, &self.value) {
+            (StaticArgumentValueKind::Expression, serde_json::Value::String(source)) => {
+                Some(Expression::parse(source.as_str())?)
+            }
+            _ => None,
+        })
+    }
+
     pub async fn new(
         ctx: &DalContext,
         value: serde_json::Value,
+    ) -> AttributePrototypeArgumentResult<Self> {
+        Self::new_inner(ctx, value, StaticArgumentValueKind::Value).await
+    }
+
+    /// Creates a [`StaticArgumentValue`] holding an [`Expression`], with an edge to each of the
+    /// props its references resolve to so that the dependent values update knows to re-evaluate
+    /// it when they change.
+    pub async fn new_expression(
+        ctx: &DalContext,
+        expression: &Expression,
+        referenced_prop_ids: &[PropId],
+    ) -> AttributePrototypeArgumentResult<Self> {
+        let static_value = Self::new_inner(
+            ctx,
+            expression.source().into(),
+            StaticArgumentValueKind::Expression,
+        )
+        .await?;
+
+        for prop_id in referenced_prop_ids {
+            ctx.workspace_snapshot()?
+                .add_edge(
+                    static_value.id,
+                    EdgeWeight::new(EdgeWeightKind::ExpressionReference),
+                    *prop_id,
+                )
+                .await?;
+        }
+
+        Ok(static_value)
+    }
+
+    async fn new_inner(
+        ctx: &DalContext,
+        value: serde_json::Value,
+        kind: StaticArgumentValueKind,
     ) -> AttributePrototypeArgumentResult<Self> {
         let timestamp = Timestamp::now();
-        let content = StaticArgumentValueContentV1 {
+        let content = StaticArgumentValueContentV2 {
             timestamp,
             value: value.into(),
+            kind,
         };
 
         let (hash, _) = ctx.layer_db().cas().write(
-            Arc::new(StaticArgumentValueContent::V1(content.clone()).into()),
+            Arc::new(StaticArgumentValueContent::V2(content.clone()).into()),
             None,
             ctx.events_tenancy(),
             ctx.events_actor(),
@@ -81,9 +142,33 @@ impl StaticArgumentValue {
             .await?
             .ok_or(WorkspaceSnapshotError::MissingContentFromStore(ulid))?;
 
-        // NOTE(nick,jacob,zack): if we had a v2, then there would be migration logic here.
-        let StaticArgumentValueContent::V1(inner) = content;
+        Ok(StaticArgumentValue::assemble(id, content.extract()))
+    }
+
+    /// Lists the props referenced by the [`Expression`] held by this value, if it is one.
+    pub async fn referenced_prop_ids(
+        ctx: &DalContext,
+        id: StaticArgumentValueId,
+    ) -> AttributePrototypeArgumentResult<Vec<PropId>> {
+        let workspace_snapshot = ctx.workspace_snapshot()?;
+
+        let mut prop_ids = vec![];
+        for prop_idx in workspace_snapshot
+            .outgoing_targets_for_edge_weight_kind(
+                id,
+                EdgeWeightKindDiscriminants::ExpressionReference,
+            )
+            .await?
+        {
+            prop_ids.push(
+                workspace_snapshot
+                    .get_node_weight(prop_idx)
+                    .await?
+                    .id()
+                    .into(),
+            );
+        }
 
-        Ok(StaticArgumentValue::assemble(id, inner))
+        Ok(prop_ids)
     }
 }
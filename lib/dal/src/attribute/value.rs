@@ -51,9 +51,12 @@ use si_events::FuncRunValue;
 use si_pkg::{AttributeValuePath, KeyOrIndex};
 use telemetry::prelude::*;
 use thiserror::Error;
-use tokio::sync::{RwLock, TryLockError};
+use tokio::sync::{RwLock, RwLockReadGuard, TryLockError};
 
 pub use dependent_value_graph::DependentValueGraph;
+pub use explain::{
+    AttributeValueExplanation, AttributeValueExplanationInput, AttributeValueExplanationSource,
+};
 
 use crate::attribute::prototype::{AttributePrototypeError, AttributePrototypeSource};
 use crate::change_set::ChangeSetError;
@@ -78,6 +81,7 @@ use crate::{
     OutputSocket, OutputSocketId, Prop, PropId, PropKind, Secret, SecretError, TransactionsError,
 };
 
+use super::prototype::argument::expression::{Expression, ExpressionError, ExpressionReference};
 use super::prototype::argument::static_value::StaticArgumentValue;
 use super::prototype::argument::value_source::ValueSourceError;
 use super::prototype::argument::{
@@ -86,10 +90,13 @@ use super::prototype::argument::{
 };
 
 pub use is_for::ValueIsFor;
+pub use memo::{AttributeFuncMemo, AttributeFuncMemoKey};
 
 pub mod debug;
 pub mod dependent_value_graph;
+pub mod explain;
 pub mod is_for;
+pub mod memo;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -114,6 +121,8 @@ pub enum AttributeValueError {
     AttributeValueMultiplePropEdges(AttributeValueId),
     #[error("before func error: {0}")]
     BeforeFunc(String),
+    #[error("cannot copy attribute value {0} to component {1} with a different schema variant")]
+    CannotCopySubtreeBetweenVariants(AttributeValueId, ComponentId),
     #[error("Cannot create nested values for {0} since it is not the value for a prop")]
     CannotCreateNestedValuesForNonPropValues(AttributeValueId),
     #[error("Cannot create attribute value for root prop without component id")]
@@ -134,6 +143,14 @@ pub enum AttributeValueError {
     },
     #[error("empty attribute prototype arguments for group name: {0}")]
     EmptyAttributePrototypeArgumentsForGroup(String),
+    #[error("expression error: {0}")]
+    Expression(#[from] ExpressionError),
+    #[error("expression for prop {0} on component {1} would depend on itself")]
+    ExpressionCycle(PropId, ComponentId),
+    #[error("expression reference {0} does not resolve to a prop on component {1}")]
+    ExpressionReferenceNotFound(String, ComponentId),
+    #[error("expression reference {0} does not resolve to exactly one value on component {1}; props inside arrays and maps cannot be referenced")]
+    ExpressionReferenceNotSingular(String, ComponentId),
     #[error("object field is not a child prop of the object prop: {0}")]
     FieldNotChildOfObject(AttributeValueId),
     #[error("func error: {0}")]
@@ -525,6 +542,7 @@ impl AttributeValue {
         ctx: &DalContext,
         attribute_value_id: AttributeValueId,
         read_lock: Arc<RwLock<()>>,
+        memo: Option<&AttributeFuncMemo>,
     ) -> AttributeValueResult<(FuncRunValue, Func, Vec<AttributeValueId>)> {
         // When functions are being executed in the dependent values update job,
         // we need to ensure we are not reading our input sources from a graph
@@ -543,23 +561,65 @@ impl AttributeValue {
             Self::prepare_arguments_for_prototype_function_execution(ctx, attribute_value_id)
                 .await?;
 
-        let result_channel = FuncRunner::run_attribute_value(
-            ctx,
-            attribute_value_id,
-            prototype_func_id,
-            prepared_args.clone(),
-        )
-        .await
-        .map_err(Box::new)?;
+        let func = Func::get_by_id_or_error(ctx, prototype_func_id).await?;
 
-        // We have gathered all our inputs and so no longer need a lock on the graph. Be sure not to
-        // add graph walk operations below this drop.
-        drop(read_guard);
+        // Identical functions run with identical inputs produce identical outputs, so an execution
+        // which has already run, or is running, in this job is shared rather than dispatched again.
+        let memo_key = match memo {
+            Some(memo) if AttributeFuncMemo::is_eligible(&func) => {
+                Some((memo, AttributeFuncMemoKey::new(&func, &prepared_args)))
+            }
+            _ => None,
+        };
 
-        let mut func_values = result_channel
-            .await
-            .map_err(|_| AttributeValueError::FuncRunnerSend)?
-            .map_err(Box::new)?;
+        let mut func_values = match memo_key {
+            Some((memo, key)) => {
+                // Whichever execution ends up being dispatched takes the read lock again, so that
+                // the ones waiting on it do not hold the lock in the meantime.
+                drop(read_guard);
+                let read_lock = &read_lock;
+                let args = &prepared_args;
+                let (func_values, memoized) = memo
+                    .get_or_execute(key, || async move {
+                        let read_guard = read_lock.read().await;
+                        Self::dispatch_prototype_function(
+                            ctx,
+                            attribute_value_id,
+                            prototype_func_id,
+                            args.clone(),
+                            read_guard,
+                        )
+                        .await
+                    })
+                    .await?;
+
+                if memoized {
+                    // Record a run of its own for this value, so that its history is complete
+                    let _read_guard = read_lock.read().await;
+                    FuncRunner::run_attribute_value_memoized(
+                        ctx,
+                        attribute_value_id,
+                        prototype_func_id,
+                        prepared_args,
+                        &func_values,
+                    )
+                    .await
+                    .map_err(Box::new)?
+                } else {
+                    func_values
+                }
+            }
+            None => {
+                Self::dispatch_prototype_function(
+                    ctx,
+                    attribute_value_id,
+                    prototype_func_id,
+                    prepared_args,
+                    read_guard,
+                )
+                .await?
+            }
+        };
 
         // If the value is for a prop, we need to make sure container-type props are initialized
         // properly when the unprocessed value is populated.
@@ -616,9 +676,9 @@ impl AttributeValue {
             None => None,
         };
 
-        let func = Func::get_by_id_or_error(ctx, prototype_func_id).await?;
         if !func.is_intrinsic() {
-            ctx.layer_db()
+            let func_run = ctx
+                .layer_db()
                 .func_run()
                 .set_values_and_set_state_to_success(
                     func_values.func_run_id(),
@@ -628,11 +688,40 @@ impl AttributeValue {
                     ctx.events_actor(),
                 )
                 .await?;
+            FuncRunner::publish_status(ctx, &func_run).await;
         }
 
         Ok((func_values, func, input_attribute_value_ids))
     }
 
+    /// Dispatches the function of an attribute value and waits for its result. The read lock on the
+    /// graph is released once the function is dispatched.
+    async fn dispatch_prototype_function(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+        prototype_func_id: FuncId,
+        prepared_args: Value,
+        read_guard: RwLockReadGuard<'_, ()>,
+    ) -> AttributeValueResult<FuncRunValue> {
+        let result_channel = FuncRunner::run_attribute_value(
+            ctx,
+            attribute_value_id,
+            prototype_func_id,
+            prepared_args,
+        )
+        .await
+        .map_err(Box::new)?;
+
+        // We have gathered all our inputs and so no longer need a lock on the graph. Be sure not to
+        // add graph walk operations below this drop.
+        drop(read_guard);
+
+        Ok(result_channel
+            .await
+            .map_err(|_| AttributeValueError::FuncRunnerSend)?
+            .map_err(Box::new)?)
+    }
+
     #[instrument(level = "debug" skip(ctx))]
     pub async fn prepare_arguments_for_prototype_function_execution(
         ctx: &DalContext,
@@ -688,11 +777,24 @@ impl AttributeValue {
                             ),
                         )? {
                         ValueSource::StaticArgumentValue(static_argument_value_id) => {
-                            vec![
+                            let static_value =
                                 StaticArgumentValue::get_by_id(ctx, static_argument_value_id)
-                                    .await?
-                                    .value,
-                            ]
+                                    .await?;
+                            match static_value.expression()? {
+                                Some(expression) => {
+                                    let (value, referenced_attribute_value_ids) =
+                                        Self::evaluate_expression(
+                                            ctx,
+                                            destination_component_id,
+                                            &expression,
+                                        )
+                                        .await?;
+                                    input_attribute_value_ids
+                                        .extend(referenced_attribute_value_ids);
+                                    vec![value]
+                                }
+                                None => vec![static_value.value],
+                            }
                         }
                         ValueSource::Secret(secret_id) => {
                             vec![Secret::payload_for_prototype_execution(ctx, secret_id)
@@ -923,7 +1025,8 @@ impl AttributeValue {
         let read_lock = Arc::new(RwLock::new(()));
         // Don't need to pass in an Inferred Dependency Graph for one off updates, we can just calculate
         let (execution_result, func, _) =
-            AttributeValue::execute_prototype_function(ctx, attribute_value_id, read_lock).await?;
+            AttributeValue::execute_prototype_function(ctx, attribute_value_id, read_lock, None)
+                .await?;
 
         AttributeValue::set_values_from_func_run_value(
             ctx,
@@ -1963,6 +2066,177 @@ impl AttributeValue {
         Ok(())
     }
 
+    /// Sets the value to an [`Expression`] over other values of the same component. The
+    /// expression is evaluated now, and again by the dependent values update whenever any of the
+    /// values it references change.
+    #[instrument(
+        name = "attribute_value.set_expression",
+        level = "info",
+        skip_all,
+        fields(
+            attribute_value.id = ?attribute_value_id
+        ))]
+    pub async fn set_expression(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+        expression: Expression,
+    ) -> AttributeValueResult<()> {
+        let prop_id = match AttributeValue::is_for(ctx, attribute_value_id).await? {
+            ValueIsFor::Prop(prop_id) => prop_id,
+            _ => {
+                return Err(AttributeValueError::CannotExplicitlySetSocketValues(
+                    attribute_value_id,
+                ));
+            }
+        };
+        let component_id = Self::component_id(ctx, attribute_value_id).await?;
+        let prop_kind = Prop::get_by_id(ctx, prop_id).await?.kind;
+
+        let mut referenced_prop_ids = Vec::new();
+        let mut referenced_kinds = HashMap::new();
+        for reference in expression.references() {
+            let (referenced_prop_id, _) =
+                Self::resolve_expression_reference(ctx, component_id, reference).await?;
+            referenced_kinds.insert(
+                reference.path().to_owned(),
+                Prop::get_by_id(ctx, referenced_prop_id).await?.kind,
+            );
+            if !referenced_prop_ids.contains(&referenced_prop_id) {
+                referenced_prop_ids.push(referenced_prop_id);
+            }
+        }
+        expression.check_types(prop_kind, &referenced_kinds)?;
+        Self::check_for_expression_cycle(ctx, component_id, prop_id, &referenced_prop_ids).await?;
+
+        // Identity is dynamic, so the dependent values update will write the re-evaluated
+        // expression back to the value
+        let func_id = Func::find_intrinsic(ctx, IntrinsicFunc::Identity).await?;
+        let func_arg_id = *FuncArgument::list_ids_for_func(ctx, func_id)
+            .await?
+            .first()
+            .ok_or(FuncArgumentError::IntrinsicMissingFuncArgumentEdge(
+                IntrinsicFunc::Identity.name().into(),
+                func_id,
+            ))?;
+
+        let prototype = AttributePrototype::new(ctx, func_id).await?;
+        let static_value =
+            StaticArgumentValue::new_expression(ctx, &expression, &referenced_prop_ids).await?;
+        AttributePrototypeArgument::new(ctx, prototype.id(), func_arg_id)
+            .await?
+            .set_value_from_static_value_id(ctx, static_value.id())
+            .await?;
+        Self::set_component_prototype_id(ctx, attribute_value_id, prototype.id(), None).await?;
+
+        Self::update_from_prototype_function(ctx, attribute_value_id).await?;
+        ctx.add_dependent_values_and_enqueue(vec![attribute_value_id])
+            .await?;
+
+        Ok(())
+    }
+
+    /// Resolves an [`ExpressionReference`] to the referenced prop and its value on the given
+    /// component.
+    async fn resolve_expression_reference(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        reference: &ExpressionReference,
+    ) -> AttributeValueResult<(PropId, AttributeValueId)> {
+        let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+        let prop_id =
+            Prop::find_prop_id_by_path_opt(ctx, schema_variant_id, &reference.prop_path())
+                .await?
+                .ok_or_else(|| {
+                    AttributeValueError::ExpressionReferenceNotFound(
+                        reference.path().to_owned(),
+                        component_id,
+                    )
+                })?;
+
+        match Component::attribute_values_for_prop_id(ctx, component_id, prop_id)
+            .await?
+            .as_slice()
+        {
+            [attribute_value_id] => Ok((prop_id, *attribute_value_id)),
+            _ => Err(AttributeValueError::ExpressionReferenceNotSingular(
+                reference.path().to_owned(),
+                component_id,
+            )),
+        }
+    }
+
+    /// Evaluates an [`Expression`] against the values of the given component, returning the
+    /// result and the values it was evaluated from.
+    async fn evaluate_expression(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        expression: &Expression,
+    ) -> AttributeValueResult<(Value, Vec<AttributeValueId>)> {
+        let mut values = HashMap::new();
+        let mut referenced_attribute_value_ids = Vec::new();
+        for reference in expression.references() {
+            let (_, attribute_value_id) =
+                Self::resolve_expression_reference(ctx, component_id, reference).await?;
+            let value = AttributeValue::get_by_id(ctx, attribute_value_id)
+                .await?
+                .view(ctx)
+                .await?
+                .unwrap_or(Value::Null);
+
+            values.insert(reference.path().to_owned(), value);
+            referenced_attribute_value_ids.push(attribute_value_id);
+        }
+
+        Ok((
+            expression.evaluate(&values)?,
+            referenced_attribute_value_ids,
+        ))
+    }
+
+    /// Errors if the props referenced by an expression for `prop_id` are themselves set, directly
+    /// or through further expressions, from `prop_id` on the same component. The dependent values
+    /// update could never evaluate such an expression.
+    async fn check_for_expression_cycle(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        prop_id: PropId,
+        referenced_prop_ids: &[PropId],
+    ) -> AttributeValueResult<()> {
+        let mut work_queue = VecDeque::from_iter(referenced_prop_ids.iter().copied());
+        let mut seen = HashSet::new();
+        while let Some(current_prop_id) = work_queue.pop_front() {
+            if current_prop_id == prop_id {
+                return Err(AttributeValueError::ExpressionCycle(prop_id, component_id));
+            }
+            if !seen.insert(current_prop_id) {
+                continue;
+            }
+
+            for attribute_value_id in
+                Component::attribute_values_for_prop_id(ctx, component_id, current_prop_id).await?
+            {
+                let Some(prototype_id) =
+                    Self::component_prototype_id(ctx, attribute_value_id).await?
+                else {
+                    continue;
+                };
+                for apa_id in
+                    AttributePrototypeArgument::list_ids_for_prototype(ctx, prototype_id).await?
+                {
+                    if let Some(ValueSource::StaticArgumentValue(static_value_id)) =
+                        AttributePrototypeArgument::value_source_by_id(ctx, apa_id).await?
+                    {
+                        work_queue.extend(
+                            StaticArgumentValue::referenced_prop_ids(ctx, static_value_id).await?,
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     async fn set_real_values(
         ctx: &DalContext,
         attribute_value_id: AttributeValueId,
@@ -2011,7 +2285,8 @@ impl AttributeValue {
         };
 
         if !func.is_intrinsic() {
-            ctx.layer_db()
+            let func_run = ctx
+                .layer_db()
                 .func_run()
                 .set_values_and_set_state_to_success(
                     func_run_value.func_run_id(),
@@ -2021,6 +2296,7 @@ impl AttributeValue {
                     ctx.events_actor(),
                 )
                 .await?;
+            FuncRunner::publish_status(ctx, &func_run).await;
         }
 
         let mut new_av_node_weight = av_node_weight.clone();
@@ -2181,6 +2457,146 @@ impl AttributeValue {
         Ok(())
     }
 
+    /// Copies the subtree rooted at the source attribute value onto the value at the same path in
+    /// a component of the same schema variant, creating any map or array entries along the way,
+    /// and returns the id of the value it was copied onto.
+    ///
+    /// Explicitly set values and component level overrides are copied, while values set by
+    /// dependent functions, in either component, are left to be computed from the target
+    /// component's own inputs. Map and array entries which only the target has are removed, so
+    /// that the subtree ends up matching the source's.
+    #[instrument(level = "info", skip(ctx))]
+    pub async fn copy_subtree(
+        ctx: &DalContext,
+        source_av_id: AttributeValueId,
+        target_component_id: ComponentId,
+    ) -> AttributeValueResult<AttributeValueId> {
+        let source_component_id = Self::component_id(ctx, source_av_id).await?;
+        if Component::schema_variant_id(ctx, source_component_id).await?
+            != Component::schema_variant_id(ctx, target_component_id).await?
+        {
+            return Err(AttributeValueError::CannotCopySubtreeBetweenVariants(
+                source_av_id,
+                target_component_id,
+            ));
+        }
+
+        // Walk up to the root, so that we can walk down the same path in the target
+        let mut source_path = vec![source_av_id];
+        let mut current_av_id = source_av_id;
+        while let Some(parent_av_id) = Self::parent_attribute_value_id(ctx, current_av_id).await? {
+            source_path.push(parent_av_id);
+            current_av_id = parent_av_id;
+        }
+        source_path.reverse();
+        // Only values for props descend from the root, socket values are roots of their own
+        if current_av_id != Component::root_attribute_value_id(ctx, source_component_id).await? {
+            return Err(AttributeValueError::CannotExplicitlySetSocketValues(
+                source_av_id,
+            ));
+        }
+        if source_component_id == target_component_id {
+            return Ok(source_av_id);
+        }
+
+        let mut target_av_id = Component::root_attribute_value_id(ctx, target_component_id).await?;
+        for source_parent_and_child in source_path.windows(2) {
+            let (source_parent_av_id, source_child_av_id) =
+                (source_parent_and_child[0], source_parent_and_child[1]);
+            target_av_id = Self::matching_child_for_copy(
+                ctx,
+                source_parent_av_id,
+                source_child_av_id,
+                target_av_id,
+                target_component_id,
+            )
+            .await?;
+        }
+
+        let mut copied_av_ids = vec![];
+        let mut work_queue = VecDeque::from([(source_av_id, target_av_id)]);
+        while let Some((from_av_id, dest_av_id)) = work_queue.pop_front() {
+            if Self::is_set_by_dependent_function(ctx, from_av_id).await?
+                || Self::is_set_by_dependent_function(ctx, dest_av_id).await?
+            {
+                continue;
+            }
+
+            Self::clone_value_from(ctx, dest_av_id, from_av_id).await?;
+            copied_av_ids.push(dest_av_id);
+
+            for child_pair in
+                Self::get_child_av_id_pairs_in_order(ctx, from_av_id, dest_av_id).await?
+            {
+                match child_pair {
+                    ChildAttributeValuePair::Both(_, from_child_av_id, dest_child_av_id) => {
+                        work_queue.push_back((from_child_av_id, dest_child_av_id));
+                    }
+                    ChildAttributeValuePair::FirstOnly(key, from_child_av_id) => {
+                        let dest_child_av_id = Self::new(
+                            ctx,
+                            Self::is_for(ctx, from_child_av_id).await?,
+                            Some(target_component_id),
+                            Some(dest_av_id),
+                            key,
+                        )
+                        .await?
+                        .id;
+                        work_queue.push_back((from_child_av_id, dest_child_av_id));
+                    }
+                    ChildAttributeValuePair::SecondOnly(_, dest_child_av_id) => {
+                        ctx.workspace_snapshot()?
+                            .remove_node_by_id(dest_child_av_id)
+                            .await?;
+                    }
+                }
+            }
+        }
+
+        ctx.add_dependent_values_and_enqueue(copied_av_ids).await?;
+
+        Ok(target_av_id)
+    }
+
+    /// Finds the child of the target value matching the given child of the source value, creating
+    /// it if it is a map or array entry the target doesn't have.
+    async fn matching_child_for_copy(
+        ctx: &DalContext,
+        source_parent_av_id: AttributeValueId,
+        source_child_av_id: AttributeValueId,
+        target_parent_av_id: AttributeValueId,
+        target_component_id: ComponentId,
+    ) -> AttributeValueResult<AttributeValueId> {
+        for child_pair in
+            Self::get_child_av_id_pairs_in_order(ctx, source_parent_av_id, target_parent_av_id)
+                .await?
+        {
+            match child_pair {
+                ChildAttributeValuePair::Both(_, from_child_av_id, dest_child_av_id)
+                    if from_child_av_id == source_child_av_id =>
+                {
+                    return Ok(dest_child_av_id);
+                }
+                ChildAttributeValuePair::FirstOnly(key, from_child_av_id)
+                    if from_child_av_id == source_child_av_id =>
+                {
+                    return Ok(Self::new(
+                        ctx,
+                        Self::is_for(ctx, from_child_av_id).await?,
+                        Some(target_component_id),
+                        Some(target_parent_av_id),
+                        key,
+                    )
+                    .await?
+                    .id);
+                }
+                _ => {}
+            }
+        }
+
+        Err(AttributeValueError::MissingForId(source_child_av_id))
+    }
+
     pub async fn get_by_id(
         ctx: &DalContext,
         attribute_value_id: AttributeValueId,
@@ -220,6 +220,51 @@ impl DependentValueGraph {
                         }
                     }
                 }
+
+                // Expressions are not sourced from the props they reference, so find the
+                // arguments whose expressions reference the current value's prop separately
+                if let ValueIsFor::Prop(prop_id) = value_is_for {
+                    for static_value_idx in workspace_snapshot
+                        .incoming_sources_for_edge_weight_kind(
+                            prop_id,
+                            EdgeWeightKindDiscriminants::ExpressionReference,
+                        )
+                        .await?
+                    {
+                        let static_value_id = workspace_snapshot
+                            .get_node_weight(static_value_idx)
+                            .await?
+                            .id();
+                        for apa_idx in workspace_snapshot
+                            .incoming_sources_for_edge_weight_kind(
+                                static_value_id,
+                                EdgeWeightKindDiscriminants::PrototypeArgumentValue,
+                            )
+                            .await?
+                        {
+                            let apa = workspace_snapshot
+                                .get_node_weight(apa_idx)
+                                .await?
+                                .get_attribute_prototype_argument_node_weight()?;
+
+                            // Skip arguments left behind by a component prototype that has since
+                            // been replaced
+                            if workspace_snapshot
+                                .incoming_sources_for_edge_weight_kind(
+                                    apa.id(),
+                                    EdgeWeightKindDiscriminants::PrototypeArgument,
+                                )
+                                .await?
+                                .is_empty()
+                            {
+                                continue;
+                            }
+
+                            relevant_apas.push(apa);
+                        }
+                    }
+                }
+
                 relevant_apas
             };
 
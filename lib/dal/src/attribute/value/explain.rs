@@ -0,0 +1,271 @@
+//! This module contains [`AttributeValueExplanation`], which describes why an [`AttributeValue`]
+//! has its current value: which function produced it, which inputs were fed into that function
+//! and where those inputs came from.
+//!
+//! Every input lists the [`AttributeValueIds`](AttributeValueId) it was read from, so callers can
+//! walk the provenance chain by explaining each upstream value in turn.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_events::FuncRunId;
+use telemetry::prelude::*;
+
+use crate::attribute::prototype::argument::{
+    static_value::{StaticArgumentValue, StaticArgumentValueId},
+    value_source::ValueSource,
+    AttributePrototypeArgument,
+};
+use crate::func::argument::FuncArgument;
+use crate::{
+    AttributePrototype, AttributePrototypeId, Component, ComponentId, DalContext, Func, FuncId,
+    InputSocketId, OutputSocket, OutputSocketId, PropId, SecretId,
+};
+
+use super::{
+    AttributeValue, AttributeValueError, AttributeValueId, AttributeValueResult, ValueIsFor,
+};
+
+/// Describes how an [`AttributeValue`] came to have its current value.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueExplanation {
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub path: Option<String>,
+    pub value: Option<Value>,
+    pub prototype_id: AttributePrototypeId,
+    /// Whether the prototype was set on the component itself (as opposed to coming from the
+    /// schema variant).
+    pub prototype_is_component_specific: bool,
+    pub func_id: FuncId,
+    pub func_name: String,
+    pub inputs: Vec<AttributeValueExplanationInput>,
+    /// The most recent execution of a function for this value, if it has ever been executed.
+    pub last_func_run_id: Option<FuncRunId>,
+    pub last_executed_at: Option<DateTime<Utc>>,
+    /// Every [`AttributeValue`] that this value was computed from, across all inputs.
+    pub upstream_attribute_value_ids: Vec<AttributeValueId>,
+}
+
+/// A single input fed into the function of an [`AttributeValueExplanation`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueExplanationInput {
+    pub func_argument_name: String,
+    pub source: AttributeValueExplanationSource,
+    /// The [`AttributeValues`](AttributeValue) that the input was read from. This is empty for
+    /// static arguments and secrets.
+    pub attribute_value_ids: Vec<AttributeValueId>,
+    /// Inputs from components marked for deletion are not used by components that are not.
+    pub is_used: bool,
+}
+
+/// Where an [`AttributeValueExplanationInput`] comes from.
+#[remain::sorted]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum AttributeValueExplanationSource {
+    /// An output socket of a component connected through frame inference.
+    #[serde(rename_all = "camelCase")]
+    InferredOutputSocket {
+        output_socket_id: OutputSocketId,
+        component_id: ComponentId,
+    },
+    #[serde(rename_all = "camelCase")]
+    InputSocket { input_socket_id: InputSocketId },
+    #[serde(rename_all = "camelCase")]
+    OutputSocket { output_socket_id: OutputSocketId },
+    #[serde(rename_all = "camelCase")]
+    Prop { prop_id: PropId },
+    /// The secret payload is never included.
+    #[serde(rename_all = "camelCase")]
+    Secret { secret_id: SecretId },
+    #[serde(rename_all = "camelCase")]
+    StaticArgument {
+        static_argument_value_id: StaticArgumentValueId,
+        value: Value,
+    },
+}
+
+impl AttributeValue {
+    /// Explain why the [`AttributeValue`] corresponding to the provided [`AttributeValueId`] has
+    /// its current value. This mirrors the way arguments are gathered when the prototype
+    /// function is executed, without executing anything.
+    #[instrument(level = "info", skip(ctx))]
+    pub async fn explain(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+    ) -> AttributeValueResult<AttributeValueExplanation> {
+        let attribute_value = Self::get_by_id(ctx, attribute_value_id).await?;
+        let prototype_id = Self::prototype_id(ctx, attribute_value_id).await?;
+        let prototype_is_component_specific = Self::component_prototype_id(ctx, attribute_value_id)
+            .await?
+            .is_some();
+        let func_id = AttributePrototype::func_id(ctx, prototype_id).await?;
+        let func_name = Func::get_by_id_or_error(ctx, func_id).await?.name;
+        let component_id = Self::component_id(ctx, attribute_value_id).await?;
+
+        let mut inputs = Vec::new();
+        for apa_id in AttributePrototypeArgument::list_ids_for_prototype(ctx, prototype_id).await? {
+            let apa = AttributePrototypeArgument::get_by_id(ctx, apa_id).await?;
+            let source_component_id = apa
+                .targets()
+                .map(|targets| targets.source_component_id)
+                .unwrap_or(component_id);
+            if apa
+                .targets()
+                .is_some_and(|targets| targets.destination_component_id != component_id)
+            {
+                continue;
+            }
+
+            let is_used = Component::should_data_flow_between_components(
+                ctx,
+                component_id,
+                source_component_id,
+            )
+            .await
+            .map_err(Box::new)?;
+            let func_argument_name = FuncArgument::get_name_by_id(
+                ctx,
+                AttributePrototypeArgument::func_argument_id_by_id(ctx, apa_id).await?,
+            )
+            .await?;
+
+            let value_source = AttributePrototypeArgument::value_source_by_id(ctx, apa_id)
+                .await?
+                .ok_or(AttributeValueError::AttributePrototypeArgumentMissingValueSource(apa_id))?;
+            let source = match value_source {
+                ValueSource::InputSocket(input_socket_id) => {
+                    AttributeValueExplanationSource::InputSocket { input_socket_id }
+                }
+                ValueSource::OutputSocket(output_socket_id) => {
+                    AttributeValueExplanationSource::OutputSocket { output_socket_id }
+                }
+                ValueSource::Prop(prop_id) => AttributeValueExplanationSource::Prop { prop_id },
+                ValueSource::Secret(secret_id) => {
+                    AttributeValueExplanationSource::Secret { secret_id }
+                }
+                ValueSource::StaticArgumentValue(static_argument_value_id) => {
+                    AttributeValueExplanationSource::StaticArgument {
+                        static_argument_value_id,
+                        value: StaticArgumentValue::get_by_id(ctx, static_argument_value_id)
+                            .await?
+                            .value,
+                    }
+                }
+            };
+            let attribute_value_ids = match value_source {
+                ValueSource::Secret(_) | ValueSource::StaticArgumentValue(_) => vec![],
+                ValueSource::InputSocket(_)
+                | ValueSource::OutputSocket(_)
+                | ValueSource::Prop(_) => {
+                    value_source
+                        .attribute_values_for_component_id(ctx, source_component_id)
+                        .await?
+                }
+            };
+
+            inputs.push(AttributeValueExplanationInput {
+                func_argument_name,
+                source,
+                attribute_value_ids,
+                is_used,
+            });
+        }
+
+        // Inputs are only inferred (e.g. through frames) when there are no explicit arguments.
+        if inputs.is_empty() {
+            inputs.extend(Self::explain_inferred_inputs(ctx, attribute_value_id, func_id).await?);
+        }
+
+        let mut upstream_attribute_value_ids = Vec::new();
+        for input in inputs.iter().filter(|input| input.is_used) {
+            for &upstream_id in &input.attribute_value_ids {
+                if !upstream_attribute_value_ids.contains(&upstream_id) {
+                    upstream_attribute_value_ids.push(upstream_id);
+                }
+            }
+        }
+
+        let last_func_run = ctx
+            .layer_db()
+            .func_run()
+            .get_last_run_for_attribute_value_id(
+                ctx.events_tenancy().workspace_pk,
+                ctx.events_tenancy().change_set_id,
+                attribute_value_id,
+            )
+            .await?;
+
+        Ok(AttributeValueExplanation {
+            attribute_value_id,
+            component_id,
+            path: Self::get_path_for_id(ctx, attribute_value_id).await?,
+            value: attribute_value.view(ctx).await?,
+            prototype_id,
+            prototype_is_component_specific,
+            func_id,
+            func_name,
+            inputs,
+            last_func_run_id: last_func_run.as_ref().map(|func_run| func_run.id()),
+            last_executed_at: last_func_run.as_ref().map(|func_run| func_run.updated_at()),
+            upstream_attribute_value_ids,
+        })
+    }
+
+    async fn explain_inferred_inputs(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+        func_id: FuncId,
+    ) -> AttributeValueResult<Vec<AttributeValueExplanationInput>> {
+        let ValueIsFor::InputSocket(input_socket_id) =
+            Self::is_for(ctx, attribute_value_id).await?
+        else {
+            return Ok(vec![]);
+        };
+        let Some(func_argument) = FuncArgument::list_for_func(ctx, func_id).await?.pop() else {
+            return Ok(vec![]);
+        };
+
+        let component_id = Self::component_id(ctx, attribute_value_id).await?;
+        let mut inferred_connection_graph = ctx
+            .workspace_snapshot()?
+            .inferred_connection_graph(ctx)
+            .await?;
+
+        let mut inputs = Vec::new();
+        for inferred_connection in inferred_connection_graph
+            .inferred_connections_for_input_socket(ctx, component_id, input_socket_id)
+            .await?
+        {
+            let is_used = Component::should_data_flow_between_components(
+                ctx,
+                inferred_connection.destination_component_id,
+                inferred_connection.source_component_id,
+            )
+            .await
+            .map_err(Box::new)?;
+            let output_attribute_value_id =
+                OutputSocket::component_attribute_value_for_output_socket_id(
+                    ctx,
+                    inferred_connection.output_socket_id,
+                    inferred_connection.source_component_id,
+                )
+                .await?;
+
+            inputs.push(AttributeValueExplanationInput {
+                func_argument_name: func_argument.name.clone(),
+                source: AttributeValueExplanationSource::InferredOutputSocket {
+                    output_socket_id: inferred_connection.output_socket_id,
+                    component_id: inferred_connection.source_component_id,
+                },
+                attribute_value_ids: vec![output_attribute_value_id],
+                is_used,
+            });
+        }
+
+        Ok(inputs)
+    }
+}
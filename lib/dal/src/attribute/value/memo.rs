@@ -0,0 +1,208 @@
+//! This module contains [`AttributeFuncMemo`], a memoization table for the results of attribute
+//! function executions within a single dependent values update.
+//!
+//! Many [`Components`](crate::Component) run the exact same attribute function with the exact
+//! same inputs (e.g. normalizing a region). Rather than dispatching an identical execution to
+//! veritech for each of them, the first execution is shared by every execution with the same
+//! function code and arguments in the same job, including the ones which start while it is still
+//! running. Every re-use still records its own [`FuncRun`](si_events::FuncRun) for the value it
+//! was used for.
+//!
+//! The table lives only as long as the job which created it, so it never outlives the snapshot
+//! that the functions and their arguments were read from.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use si_events::{ContentHash, FuncRunId, FuncRunValue};
+use telemetry_utils::metric;
+use tokio::sync::OnceCell;
+
+use crate::func::FuncKind;
+use crate::{Func, FuncId};
+
+/// Identifies a single attribute function execution: which function (and which version of its
+/// code) was run and with what arguments.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AttributeFuncMemoKey {
+    func_id: FuncId,
+    func_hash: ContentHash,
+    args_hash: ContentHash,
+}
+
+impl AttributeFuncMemoKey {
+    pub fn new(func: &Func, args: &Value) -> Self {
+        Self {
+            func_id: func.id,
+            func_hash: func.code_blake3,
+            args_hash: ContentHash::from(args),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AttributeFuncMemoEntry {
+    func_run_id: FuncRunId,
+    unprocessed_value: Option<Value>,
+    value: Option<Value>,
+}
+
+impl From<&FuncRunValue> for AttributeFuncMemoEntry {
+    fn from(func_run_value: &FuncRunValue) -> Self {
+        Self {
+            func_run_id: func_run_value.func_run_id(),
+            unprocessed_value: func_run_value.unprocessed_value().cloned(),
+            value: func_run_value.value().cloned(),
+        }
+    }
+}
+
+impl From<&AttributeFuncMemoEntry> for FuncRunValue {
+    fn from(entry: &AttributeFuncMemoEntry) -> Self {
+        FuncRunValue::new(
+            entry.func_run_id,
+            entry.unprocessed_value.clone(),
+            entry.value.clone(),
+        )
+    }
+}
+
+/// A memoization table for attribute function results, keyed by [`AttributeFuncMemoKey`].
+///
+/// Since the key contains the hashes of the function code and of the prepared arguments,
+/// editing a function or any of its arguments always results in a miss.
+#[derive(Debug, Default)]
+pub struct AttributeFuncMemo {
+    entries: Mutex<HashMap<AttributeFuncMemoKey, Arc<OnceCell<AttributeFuncMemoEntry>>>>,
+}
+
+impl AttributeFuncMemo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Determines whether or not results for the given [`Func`] may be memoized. Only
+    /// non-intrinsic attribute functions are eligible: intrinsics are never dispatched to veritech
+    /// and other kinds (e.g. qualifications) are allowed to depend on the outside world.
+    pub fn is_eligible(func: &Func) -> bool {
+        !func.is_intrinsic() && func.kind == FuncKind::Attribute
+    }
+
+    /// Returns the result of the execution for the key, running `execute` if there is none yet.
+    /// Callers with the same key wait on the execution of the first one rather than running their
+    /// own, and if it fails, the next caller runs its own instead. The returned flag is `true` when
+    /// the result comes from the execution of another caller, in which case it still refers to the
+    /// [`FuncRun`](si_events::FuncRun) which produced it.
+    pub async fn get_or_execute<F, Fut, E>(
+        &self,
+        key: AttributeFuncMemoKey,
+        execute: F,
+    ) -> Result<(FuncRunValue, bool), E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<FuncRunValue, E>>,
+    {
+        let cell = {
+            let mut entries = match self.entries.lock() {
+                Ok(entries) => entries,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            entries.entry(key).or_default().clone()
+        };
+
+        let mut executed = false;
+        let entry = cell
+            .get_or_try_init(|| {
+                executed = true;
+                let execution = execute();
+                async move {
+                    execution
+                        .await
+                        .map(|func_run_value| AttributeFuncMemoEntry::from(&func_run_value))
+                }
+            })
+            .await?;
+
+        if executed {
+            metric!(counter.attribute_func_memo.miss = 1);
+        } else {
+            metric!(counter.attribute_func_memo.hit = 1);
+        }
+
+        Ok((entry.into(), !executed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    fn key() -> AttributeFuncMemoKey {
+        AttributeFuncMemoKey {
+            func_id: FuncId::generate(),
+            func_hash: ContentHash::new(b"async function main() { return 1; }"),
+            args_hash: ContentHash::from(&serde_json::json!({})),
+        }
+    }
+
+    async fn execute(executions: &AtomicUsize, fail: bool) -> Result<FuncRunValue, ()> {
+        executions.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        if fail {
+            return Err(());
+        }
+        Ok(FuncRunValue::new(
+            FuncRunId::new(),
+            Some(serde_json::json!(1)),
+            Some(serde_json::json!(1)),
+        ))
+    }
+
+    #[tokio::test]
+    async fn concurrent_executions_share_the_first_one() {
+        let memo = AttributeFuncMemo::new();
+        let executions = AtomicUsize::new(0);
+        let key = key();
+
+        let (first, second) = tokio::join!(
+            memo.get_or_execute(key, || execute(&executions, false)),
+            memo.get_or_execute(key, || execute(&executions, false)),
+        );
+        let (first, first_memoized) = first.expect("first execution failed");
+        let (second, second_memoized) = second.expect("second execution failed");
+
+        assert_eq!(1, executions.load(Ordering::SeqCst));
+        assert!(!first_memoized);
+        assert!(second_memoized);
+        assert_eq!(first.func_run_id(), second.func_run_id());
+
+        let (_, memoized) = memo
+            .get_or_execute(key, || execute(&executions, false))
+            .await
+            .expect("execution failed");
+        assert!(memoized);
+        assert_eq!(1, executions.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn failed_executions_are_not_shared() {
+        let memo = AttributeFuncMemo::new();
+        let executions = AtomicUsize::new(0);
+        let key = key();
+
+        let (first, second) = tokio::join!(
+            memo.get_or_execute(key, || execute(&executions, true)),
+            memo.get_or_execute(key, || execute(&executions, false)),
+        );
+
+        assert!(first.is_err());
+        let (_, memoized) = second.expect("second execution failed");
+        assert!(!memoized);
+        assert_eq!(2, executions.load(Ordering::SeqCst));
+    }
+}
@@ -2,6 +2,7 @@
 
 use audit_database::AuditDatabaseContext;
 use audit_database::AuditDatabaseError;
+use audit_database::AuditLogFilter;
 use audit_database::AuditLogRow;
 use audit_logs_stream::AuditLogsStream;
 use audit_logs_stream::AuditLogsStreamError;
@@ -200,6 +201,11 @@ pub(crate) async fn write(
     entity_name: String,
     override_destination_change_set_id: Option<si_events::ChangeSetId>,
 ) -> Result<()> {
+    // Audit logs are published as they are written, so a dry run must not write any
+    if ctx.dry_run().is_some() {
+        return Ok(());
+    }
+
     // TODO(nick): nuke this from intergalactic orbit. Then do it again.
     let workspace_id = match ctx.workspace_pk() {
         Ok(workspace_id) => workspace_id,
@@ -230,6 +236,11 @@ pub(crate) async fn write(
 
 #[instrument(name = "audit_logging.write_final_message", level = "debug", skip_all)]
 pub(crate) async fn write_final_message(ctx: &DalContext) -> Result<()> {
+    // Audit logs are published as they are written, so a dry run must not write any
+    if ctx.dry_run().is_some() {
+        return Ok(());
+    }
+
     // TODO(nick): nuke this from intergalactic orbit. Then do it again.
     let workspace_id = match ctx.workspace_pk() {
         Ok(workspace_id) => workspace_id,
@@ -286,6 +297,31 @@ pub async fn list(
     .await?)
 }
 
+/// Queries the audit logs across the workspace of the [`DalContext`], regardless of its change set.
+///
+/// Returns at most `limit` rows, along with the cursor for the next page if there are more.
+#[instrument(name = "audit_logging.query", level = "debug", skip_all)]
+pub async fn query(
+    ctx: &DalContext,
+    audit_database_context: &AuditDatabaseContext,
+    filter: &AuditLogFilter,
+    cursor: Option<i64>,
+    limit: usize,
+    sort_ascending: bool,
+) -> Result<(Vec<AuditLogRow>, Option<i64>)> {
+    let workspace_id = ctx.workspace_pk().map_err(Box::new)?;
+
+    Ok(AuditLogRow::query(
+        audit_database_context,
+        workspace_id,
+        filter,
+        cursor,
+        limit,
+        sort_ascending,
+    )
+    .await?)
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct AuditLogsPublishedPayload {
@@ -6,7 +6,6 @@ use postgres_types::ToSql;
 use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 use thiserror::Error;
-use tokio::task::JoinSet;
 use ulid::Ulid;
 
 use crate::{
@@ -16,6 +15,7 @@ use crate::{
 use module_index_client::{ModuleDetailsResponse, ModuleIndexClient, ModuleIndexClientError};
 use si_data_pg::{PgError, PgRow};
 use si_pkg::{SiPkg, SiPkgError};
+use si_std::{BoundedTaskGroup, TaskGroupError};
 
 pub use si_id::CachedModuleId;
 
@@ -38,6 +38,8 @@ pub enum CachedModuleError {
     SlowRuntime(#[from] SlowRuntimeError),
     #[error("strum parse error: {0}")]
     StrumParse(#[from] strum::ParseError),
+    #[error("task group error: {0}")]
+    TaskGroup(#[from] TaskGroupError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
     #[error("url parse error: {0}")]
@@ -46,6 +48,9 @@ pub enum CachedModuleError {
 
 pub type CachedModuleResult<T> = Result<T, CachedModuleError>;
 
+/// The maximum number of builtin modules fetched from the module index at once.
+const MAX_CONCURRENT_MODULE_DOWNLOADS: usize = 16;
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CachedModule {
@@ -185,7 +190,7 @@ impl CachedModule {
         let hashes: Vec<_> = modules.keys().map(ToOwned::to_owned).collect();
         let uncached_hashes = CachedModule::find_missing_entries(ctx, hashes).await?;
 
-        let mut join_set = JoinSet::new();
+        let mut join_set = BoundedTaskGroup::new(MAX_CONCURRENT_MODULE_DOWNLOADS);
         for uncached_hash in &uncached_hashes {
             let Some(module) = modules.get(uncached_hash).cloned() else {
                 continue;
@@ -207,7 +212,7 @@ impl CachedModule {
 
         let mut new_modules = vec![];
         for res in join_set.join_all().await {
-            let (module, module_bytes) = res?;
+            let (module, module_bytes) = res??;
             if let Some(new_cached_module) = Self::insert(ctx, &module, module_bytes).await? {
                 new_modules.push(new_cached_module);
             }
@@ -5,14 +5,17 @@ use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use si_data_pg::{PgError, PgRow};
+use si_data_pg::{PgError, PgPoolError, PgRow};
 use si_events::{ulid::Ulid, WorkspaceSnapshotAddress};
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
 use thiserror::Error;
-use tokio::time;
+use tokio::time::{self, Instant};
 
 use crate::billing_publish::BillingPublishError;
+use crate::change_set::apply_metrics::ChangeSetApplyMetrics;
+use crate::change_set::size::{ChangeSetSizeMetrics, ChangeSetSizeReport};
+use crate::schema::variant::variant_trait::{SchemaVariantTrait, SchemaVariantTraitError};
 use crate::slow_rt::SlowRuntimeError;
 use crate::workspace_snapshot::graph::RebaseBatch;
 use crate::{
@@ -22,11 +25,18 @@ use crate::{
     WorkspaceSnapshotError, WsEvent, WsEventError,
 };
 use crate::{
-    billing_publish, Func, FuncError, Schema, SchemaError, SchemaVariant, SchemaVariantError,
-    WorkspaceError,
+    billing_publish, func::runner::FuncRunner, Func, FuncError, Schema, SchemaError, SchemaVariant,
+    SchemaVariantError, WorkspaceError,
 };
 
+pub mod apply_lock;
+pub mod apply_metrics;
+pub mod approval;
+pub mod blueprint;
 pub mod event;
+pub mod quarantine;
+pub mod size;
+pub mod stats;
 pub mod status;
 pub mod view;
 
@@ -39,8 +49,12 @@ pub enum ChangeSetError {
     BillingPublish(#[from] Box<BillingPublishError>),
     #[error("change set not approved for apply. Current state: {0}")]
     ChangeSetNotApprovedForApply(ChangeSetStatus),
+    #[error("change set {0} is not awaiting approval. Current state: {1}")]
+    ChangeSetNotAwaitingApproval(ChangeSetId, ChangeSetStatus),
     #[error("change set with id {0} not found")]
     ChangeSetNotFound(ChangeSetId),
+    #[error("change set {0} is too large to apply: {1}")]
+    ChangeSetTooLarge(ChangeSetId, String),
     #[error("could not find default change set: {0}")]
     DefaultChangeSetNotFound(ChangeSetId),
     #[error("default change set {0} has no workspace snapshot pointer")]
@@ -67,6 +81,8 @@ pub enum ChangeSetError {
     Mutex(String),
     #[error("Changeset {0} does not have a base change set")]
     NoBaseChangeSet(ChangeSetId),
+    #[error("change set {0} has no verified snapshot to restore")]
+    NoLastGoodSnapshot(ChangeSetId),
     #[error("no tenancy set in context")]
     NoTenancySet,
     #[error("no workspace_pk is set for change_set_id={0}")]
@@ -75,12 +91,22 @@ pub enum ChangeSetError {
     NoWorkspaceSnapshot(ChangeSetId),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[from] PgPoolError),
+    #[error("change set {0} is quarantined because its snapshot is corrupt")]
+    Quarantined(ChangeSetId),
+    #[error("change set {0} is read-only")]
+    ReadOnly(ChangeSetId),
     #[error("rebaser client error: {0}")]
     RebaserClient(#[from] rebaser_client::ClientError),
     #[error("schema error: {0}")]
     Schema(#[from] Box<SchemaError>),
     #[error("schema variant error: {0}")]
     SchemaVariant(#[from] Box<SchemaVariantError>),
+    #[error("schema variant trait error: {0}")]
+    SchemaVariantTrait(#[from] Box<SchemaVariantTraitError>),
+    #[error("approval of change set {0} was requested by the approving user")]
+    SelfApproval(ChangeSetId),
     #[error("serde json error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("slow runtime error: {0}")]
@@ -164,6 +190,13 @@ pub struct ChangeSet {
     pub merge_requested_at: Option<DateTime<Utc>>,
     pub reviewed_by_user_id: Option<UserPk>,
     pub reviewed_at: Option<DateTime<Utc>>,
+    /// Set while the change set is quarantined, which makes it read-only.
+    pub quarantined_at: Option<DateTime<Utc>>,
+    /// The last snapshot whose content was verified, for restoring a quarantined change set.
+    pub last_good_snapshot_address: Option<WorkspaceSnapshotAddress>,
+    /// Set for change sets which can be viewed but never written to or applied, such as ones
+    /// holding an [imported snapshot](crate::Workspace::import_snapshot).
+    pub read_only: bool,
 }
 
 impl TryFrom<PgRow> for ChangeSet {
@@ -185,6 +218,9 @@ impl TryFrom<PgRow> for ChangeSet {
             merge_requested_at: value.try_get("merge_requested_at")?,
             reviewed_by_user_id: value.try_get("reviewed_by_user_id")?,
             reviewed_at: value.try_get("reviewed_at")?,
+            quarantined_at: value.try_get("quarantined_at")?,
+            last_good_snapshot_address: value.try_get("last_good_snapshot_address")?,
+            read_only: value.try_get("read_only")?,
         })
     }
 }
@@ -306,6 +342,41 @@ impl ChangeSet {
         Ok(change_set)
     }
 
+    /// Converts the [`ChangeSet`] into a [`si_frontend_types::ChangeSetView`], with the
+    /// capabilities of a user who is (or is not) an approver of the workspace.
+    pub async fn into_frontend_view(
+        &self,
+        ctx: &DalContext,
+        is_approver: bool,
+    ) -> ChangeSetResult<si_frontend_types::ChangeSetView> {
+        let is_head = self.is_head(ctx).await?;
+        let is_active = matches!(
+            self.status,
+            ChangeSetStatus::Open
+                | ChangeSetStatus::NeedsApproval
+                | ChangeSetStatus::NeedsAbandonApproval
+                | ChangeSetStatus::Approved
+                | ChangeSetStatus::Rejected
+        );
+
+        let can_approve = !is_head
+            && is_approver
+            && self.status == ChangeSetStatus::NeedsApproval
+            && !self.is_self_approval_forbidden(ctx).await?;
+
+        Ok(si_frontend_types::ChangeSetView {
+            change_set: self.into_frontend_type(ctx).await?,
+            // Approvers can force apply, everyone else needs an approval first
+            can_apply: !is_head
+                && is_active
+                && (is_approver || self.status == ChangeSetStatus::Approved),
+            can_approve,
+            // Change sets waiting on an approval are locked until the request is withdrawn, which
+            // sdf enforces for every request that would edit them
+            can_edit: !is_head && is_active && !self.status.is_awaiting_approval(),
+        })
+    }
+
     pub async fn update_workspace_id(
         &mut self,
         ctx: &DalContext,
@@ -333,10 +404,28 @@ impl ChangeSet {
         Ok(Workspace::get_by_pk_or_error(ctx, self.workspace_id()?).await?)
     }
 
+    /// Makes the change set read-only for good, so that it can be viewed but never written to or
+    /// applied.
+    pub async fn mark_read_only(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "UPDATE change_set_pointers SET read_only = TRUE, updated_at = CLOCK_TIMESTAMP() WHERE id = $1",
+                &[&self.id],
+            )
+            .await?;
+        self.read_only = true;
+
+        Ok(())
+    }
+
     pub async fn is_head(&self, ctx: &DalContext) -> ChangeSetResult<bool> {
         Ok(self.workspace(ctx).await?.default_change_set_id() == self.id)
     }
 
+    /// Points the change set at a new snapshot. Fails if the change set is quarantined or
+    /// read-only.
     pub async fn update_pointer(
         &mut self,
         ctx: &DalContext,
@@ -345,11 +434,16 @@ impl ChangeSet {
         ctx.txns()
             .await?
             .pg()
-            .query_none(
-                "UPDATE change_set_pointers SET workspace_snapshot_address = $2, updated_at = CLOCK_TIMESTAMP() WHERE id = $1",
+            .query_opt(
+                "UPDATE change_set_pointers SET workspace_snapshot_address = $2, updated_at = CLOCK_TIMESTAMP() WHERE id = $1 AND quarantined_at IS NULL AND NOT read_only RETURNING id",
                 &[&self.id, &workspace_snapshot_address],
             )
-            .await?;
+            .await?
+            .ok_or(if self.read_only {
+                ChangeSetError::ReadOnly(self.id)
+            } else {
+                ChangeSetError::Quarantined(self.id)
+            })?;
 
         self.workspace_snapshot_address = workspace_snapshot_address;
 
@@ -384,16 +478,19 @@ impl ChangeSet {
     pub async fn request_change_set_approval(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         let user_pk = Self::extract_userid_from_context_or_error(ctx).await?;
         let status = ChangeSetStatus::NeedsApproval;
-        ctx.txns()
+        let row = ctx
+            .txns()
             .await?
             .pg()
-            .query_none(
-                "UPDATE change_set_pointers SET merge_requested_by_user_id = $2, merge_requested_at = CLOCK_TIMESTAMP(), status = $3, updated_at = CLOCK_TIMESTAMP() WHERE id = $1",
+            .query_one(
+                "UPDATE change_set_pointers SET merge_requested_by_user_id = $2, merge_requested_at = CLOCK_TIMESTAMP(), status = $3, updated_at = CLOCK_TIMESTAMP() WHERE id = $1 RETURNING merge_requested_at",
                 &[&self.id, &user_pk, &status.to_string()],
             )
             .await?;
 
         self.status = status;
+        self.merge_requested_by_user_id = Some(user_pk);
+        self.merge_requested_at = row.try_get("merge_requested_at")?;
 
         Ok(())
     }
@@ -417,14 +514,19 @@ impl ChangeSet {
             .await?;
 
         self.status = status;
+        self.reviewed_by_user_id = None;
+        self.reviewed_at = None;
+        self.merge_requested_by_user_id = None;
+        self.merge_requested_at = None;
 
         Ok(())
     }
 
     /// First, transitions the status of the [`ChangeSet`] to [`ChangeSetStatus::NeedsApproval`]
     /// then [`ChangeSetStatus::Approved`]. Next, checks if DVU Roots still exist. Finally,
-    /// lock every [`SchemaVariant`] and [`Func`] that is currently unlocked
-    pub async fn prepare_for_force_apply(ctx: &DalContext) -> ChangeSetResult<()> {
+    /// lock every [`SchemaVariant`] and [`Func`] that is currently unlocked. Returns the
+    /// [`ChangeSetSizeReport`] of the change set, whose warnings should be shown to the user.
+    pub async fn prepare_for_force_apply(ctx: &DalContext) -> ChangeSetResult<ChangeSetSizeReport> {
         // first change the status to approved and who did it
         let mut change_set = ChangeSet::find(ctx, ctx.change_set_id())
             .await?
@@ -439,8 +541,9 @@ impl ChangeSet {
 
     /// First, checks if DVU Roots still exist. Next, ensures the [`ChangeSet`] has an
     /// [`ChangeSetStatus::Approved`]. Finally,
-    /// lock every [`SchemaVariant`] and [`Func`] that is currently unlocked
-    pub async fn prepare_for_apply(ctx: &DalContext) -> ChangeSetResult<()> {
+    /// lock every [`SchemaVariant`] and [`Func`] that is currently unlocked. Returns the
+    /// [`ChangeSetSizeReport`] of the change set, whose warnings should be shown to the user.
+    pub async fn prepare_for_apply(ctx: &DalContext) -> ChangeSetResult<ChangeSetSizeReport> {
         let change_set = ChangeSet::find(ctx, ctx.change_set_id())
             .await?
             .ok_or(TransactionsError::ChangeSetNotFound(ctx.change_set_id()))?;
@@ -462,6 +565,28 @@ impl ChangeSet {
             return Err(ChangeSetError::DvuRootsNotEmpty(ctx.change_set_id()));
         }
 
+        // Large change sets are almost always accidental, so warn about them and refuse to
+        // apply ones which are over the hard limits. The warnings are returned to the caller, so
+        // that the user learns about them too.
+        let size_report = Self::size_report(ctx).await?;
+        for violation in &size_report.warnings {
+            warn!(
+                si.change_set.id = %ctx.change_set_id(),
+                "change set exceeds soft size limit: {violation}"
+            );
+        }
+        if size_report.is_blocked() {
+            return Err(ChangeSetError::ChangeSetTooLarge(
+                ctx.change_set_id(),
+                size_report
+                    .blocking
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+        }
+
         // if the change set status isn't approved, we shouldn't go
         // locking stuff
         if change_set.status != ChangeSetStatus::Approved {
@@ -499,7 +624,7 @@ impl ChangeSet {
                 func.lock(ctx).await.map_err(Box::new)?;
             }
         }
-        Ok(())
+        Ok(size_report)
     }
 
     pub async fn approve_change_set_for_apply(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
@@ -596,10 +721,7 @@ impl ChangeSet {
     pub async fn list_active(ctx: &DalContext) -> ChangeSetResult<Vec<Self>> {
         let mut result = vec![];
         let rows = ctx
-            .txns()
-            .await?
-            .pg()
-            .query(
+            .read_query(
                 "SELECT * from change_set_pointers WHERE workspace_id = $1 AND status IN ($2, $3, $4, $5, $6)",
                 &[
                     &ctx.tenancy().workspace_pk_opt(),
@@ -627,10 +749,7 @@ impl ChangeSet {
         let mut result = Vec::new();
 
         let rows = ctx
-            .txns()
-            .await?
-            .pg()
-            .query(
+            .read_query(
                 "SELECT * from change_set_pointers WHERE workspace_id = $1 AND status = $2",
                 &[&workspace_pk, &ChangeSetStatus::Applied.to_string()],
             )
@@ -651,10 +770,7 @@ impl ChangeSet {
         let mut result = vec![];
 
         let rows = ctx
-            .txns()
-            .await?
-            .pg()
-            .query(
+            .read_query(
                 "SELECT * from change_set_pointers WHERE workspace_id = $1",
                 &[&workspace_pk],
             )
@@ -671,10 +787,7 @@ impl ChangeSet {
     pub async fn list_open_for_all_workspaces(ctx: &DalContext) -> ChangeSetResult<Vec<Self>> {
         let mut result = vec![];
         let rows = ctx
-            .txns()
-            .await?
-            .pg()
-            .query(
+            .read_query(
                 "SELECT * from change_set_pointers WHERE status IN ($1, $2, $3)",
                 &[
                     &ChangeSetStatus::Open.to_string(),
@@ -691,25 +804,90 @@ impl ChangeSet {
         Ok(result)
     }
 
+    /// List the change sets with any of the given statuses across all workspaces, least recently
+    /// updated first. Take care when working on these change sets to set the workspace id on the
+    /// dal context!!!
+    pub async fn list_by_status_for_all_workspaces(
+        ctx: &DalContext,
+        statuses: &[ChangeSetStatus],
+        after: Option<ChangeSetId>,
+        limit: i64,
+    ) -> ChangeSetResult<Vec<Self>> {
+        let statuses: Vec<String> = statuses.iter().map(ToString::to_string).collect();
+
+        // Pages are keyed on (updated_at, id) so change sets sharing a timestamp are neither
+        // skipped nor repeated across pages.
+        let mut result = vec![];
+        let rows = ctx
+            .read_query(
+                "SELECT * FROM change_set_pointers
+                 WHERE status = ANY($1)
+                   AND ($2::ident IS NULL OR (updated_at, id) > (
+                       SELECT updated_at, id FROM change_set_pointers WHERE id = $2
+                   ))
+                 ORDER BY updated_at ASC, id ASC
+                 LIMIT $3",
+                &[&statuses, &after, &limit],
+            )
+            .await?;
+
+        for row in rows {
+            result.push(Self::try_from(row)?);
+        }
+
+        Ok(result)
+    }
+
     /// Applies the current [`ChangeSet`] in the provided [`DalContext`]. [`Actions`](Action)
     /// are enqueued as needed and only done so if the base [`ChangeSet`] is "HEAD" (i.e.
     /// the default [`ChangeSet`] of the [`Workspace`]).
     #[instrument(level = "info", skip_all)]
     pub async fn apply_to_base_change_set(ctx: &mut DalContext) -> ChangeSetApplyResult<ChangeSet> {
+        let (change_set, _metrics) = Self::apply_to_base_change_set_with_metrics(ctx).await?;
+        Ok(change_set)
+    }
+
+    /// Like [`Self::apply_to_base_change_set`], but also returns the
+    /// [`ChangeSetApplyMetrics`] collected while applying.
+    #[instrument(
+        level = "info",
+        skip_all,
+        fields(
+            si.change_set.apply.rebase_duration_ms = Empty,
+            si.change_set.apply.total_duration_ms = Empty,
+        )
+    )]
+    pub async fn apply_to_base_change_set_with_metrics(
+        ctx: &mut DalContext,
+    ) -> ChangeSetApplyResult<(ChangeSet, ChangeSetApplyMetrics)> {
+        let span = current_span_for_instrument_at!("info");
+        let started = Instant::now();
+
         // Apply to the base change with the current change set (non-editing) and commit.
         let mut change_set_to_be_applied = Self::find(ctx, ctx.change_set_id())
             .await?
             .ok_or(ChangeSetApplyError::ChangeSetNotFound(ctx.change_set_id()))?;
         ctx.update_visibility_and_snapshot_to_visibility(ctx.change_set_id())
             .await?;
-        change_set_to_be_applied
+        let mut metrics = change_set_to_be_applied
             .apply_to_base_change_set_inner(ctx)
             .await?;
 
         // This is just to send the ws events
         ctx.blocking_commit_no_rebase().await?;
 
-        Ok(change_set_to_be_applied)
+        metrics.set_total_duration(started.elapsed());
+        metrics.record();
+        span.record(
+            "si.change_set.apply.rebase_duration_ms",
+            metrics.rebase_duration_ms,
+        );
+        span.record(
+            "si.change_set.apply.total_duration_ms",
+            metrics.total_duration_ms,
+        );
+
+        Ok((change_set_to_be_applied, metrics))
     }
 
     pub async fn detect_updates_that_will_be_applied(
@@ -740,7 +918,13 @@ impl ChangeSet {
     ///
     /// This function neither changes the visibility nor the snapshot after performing the
     /// aforementioned actions.
-    async fn apply_to_base_change_set_inner(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+    async fn apply_to_base_change_set_inner(
+        &mut self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<ChangeSetApplyMetrics> {
+        if self.read_only {
+            return Err(ChangeSetError::ReadOnly(self.id));
+        }
         let workspace_id = self
             .workspace_id
             .ok_or(ChangeSetError::NoWorkspacePkSet(self.id))?;
@@ -748,7 +932,10 @@ impl ChangeSet {
             .base_change_set_id
             .ok_or(ChangeSetError::NoBaseChangeSet(self.id))?;
 
+        let mut metrics = ChangeSetApplyMetrics::default();
         if let Some(rebase_batch) = self.detect_updates_that_will_be_applied(ctx).await? {
+            metrics.size = ChangeSetSizeMetrics::from_updates(rebase_batch.updates());
+            let rebase_started = Instant::now();
             let updates_address = ctx.write_rebase_batch(rebase_batch).await?;
 
             let (request_id, reply_fut) = ctx
@@ -773,8 +960,13 @@ impl ChangeSet {
                 .map_err(|_elapsed| {
                     TransactionsError::RebaserReplyDeadlineElasped(timeout, request_id)
                 })??;
+            metrics.set_rebase_duration(rebase_started.elapsed());
         }
 
+        SchemaVariantTrait::promote(ctx, self.id, base_change_set_id)
+            .await
+            .map_err(Box::new)?;
+
         self.update_status(ctx, ChangeSetStatus::Applied).await?;
         let user = Self::extract_userid_from_context(ctx).await;
         WsEvent::change_set_applied(ctx, self.id, base_change_set_id, user)
@@ -782,7 +974,7 @@ impl ChangeSet {
             .publish_on_commit(ctx)
             .await?;
 
-        Ok(())
+        Ok(metrics)
     }
 
     /// Returns a new [`ChangeSetId`](ChangeSet) if a new [`ChangeSet`] was created.
@@ -893,6 +1085,14 @@ impl ChangeSet {
 
     pub async fn abandon(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         self.update_status(ctx, ChangeSetStatus::Abandoned).await?;
+        // Nobody will look at the results of the functions still running for the change set
+        if let Err(err) = FuncRunner::cancel_executions_for_change_set(ctx, self.id).await {
+            warn!(
+                si.error.message = ?err,
+                change_set_id = %self.id,
+                "failed to cancel func runs of abandoned change set",
+            );
+        }
         let user_id = Self::extract_userid_from_context(ctx).await;
         WsEvent::change_set_abandoned(ctx, self.id, user_id)
             .await?
@@ -1021,6 +1221,8 @@ impl std::fmt::Debug for ChangeSet {
                     .merge_requested_by_user_id
                     .map(|user_pk| user_pk.to_string()),
             )
+            .field("quarantined_at", &self.quarantined_at)
+            .field("read_only", &self.read_only)
             .finish()
     }
 }
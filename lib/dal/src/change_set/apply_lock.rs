@@ -0,0 +1,111 @@
+//! Locks held while a [`ChangeSet`](super::ChangeSet) is being applied.
+//!
+//! The locks are stored in the database so that every server sees them, and are acquired and
+//! released outside of any request transaction so that they are visible right away. A lock
+//! expires on its own after a while, so a server going away mid-apply does not leave the change
+//! set locked forever.
+//!
+//! A change set awaiting approval is locked for edits as well, for as long as it is waiting (see
+//! [`ChangeSetStatus::is_awaiting_approval`]).
+
+use std::time::Duration;
+
+use si_data_pg::PgPool;
+
+use super::ChangeSetResult;
+use crate::{ChangeSetId, ChangeSetStatus, WorkspacePk};
+
+/// How long a lock is held for if it is never released.
+pub const DEFAULT_APPLY_LOCK_TTL: Duration = Duration::from_secs(10 * 60);
+
+const ACQUIRE_QUERY: &str = "
+    INSERT INTO change_set_apply_locks (change_set_id, expires_at)
+        VALUES ($1, CLOCK_TIMESTAMP() + make_interval(secs => $2))
+    ON CONFLICT (change_set_id) DO
+        UPDATE SET locked_at = CLOCK_TIMESTAMP(), expires_at = EXCLUDED.expires_at
+        WHERE change_set_apply_locks.expires_at <= CLOCK_TIMESTAMP()
+    RETURNING change_set_id
+";
+const IS_LOCKED_QUERY: &str = "
+    SELECT change_set_id FROM change_set_apply_locks
+        WHERE change_set_id = $1 AND expires_at > CLOCK_TIMESTAMP()
+";
+const IS_AWAITING_APPROVAL_QUERY: &str = "
+    SELECT id FROM change_set_pointers WHERE id = $1 AND status = ANY($2)
+";
+const IS_IN_WORKSPACE_QUERY: &str = "
+    SELECT id FROM change_set_pointers WHERE id = $1 AND workspace_id = $2
+";
+const RELEASE_QUERY: &str = "DELETE FROM change_set_apply_locks WHERE change_set_id = $1";
+
+/// The apply locks of all change sets.
+#[derive(Clone, Debug)]
+pub struct ChangeSetApplyLocks {
+    pg_pool: PgPool,
+    ttl: Duration,
+}
+
+impl ChangeSetApplyLocks {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self {
+            pg_pool,
+            ttl: DEFAULT_APPLY_LOCK_TTL,
+        }
+    }
+
+    /// Locks the change set, returning `false` if it is already locked.
+    pub async fn try_acquire(&self, change_set_id: ChangeSetId) -> ChangeSetResult<bool> {
+        let client = self.pg_pool.get().await?;
+        let maybe_row = client
+            .query_opt(ACQUIRE_QUERY, &[&change_set_id, &self.ttl.as_secs_f64()])
+            .await?;
+
+        Ok(maybe_row.is_some())
+    }
+
+    pub async fn is_locked(&self, change_set_id: ChangeSetId) -> ChangeSetResult<bool> {
+        let client = self.pg_pool.get().await?;
+        let maybe_row = client.query_opt(IS_LOCKED_QUERY, &[&change_set_id]).await?;
+
+        Ok(maybe_row.is_some())
+    }
+
+    /// Whether the change set is waiting on an approval, and so may not be edited.
+    pub async fn is_awaiting_approval(&self, change_set_id: ChangeSetId) -> ChangeSetResult<bool> {
+        let awaiting_statuses = [
+            ChangeSetStatus::NeedsAbandonApproval.to_string(),
+            ChangeSetStatus::NeedsApproval.to_string(),
+        ];
+        let client = self.pg_pool.get().await?;
+        let maybe_row = client
+            .query_opt(
+                IS_AWAITING_APPROVAL_QUERY,
+                &[&change_set_id, &awaiting_statuses.as_slice()],
+            )
+            .await?;
+
+        Ok(maybe_row.is_some())
+    }
+
+    /// Whether the change set belongs to the workspace, so that callers can avoid revealing the
+    /// locks of other workspaces.
+    pub async fn is_in_workspace(
+        &self,
+        change_set_id: ChangeSetId,
+        workspace_pk: WorkspacePk,
+    ) -> ChangeSetResult<bool> {
+        let client = self.pg_pool.get().await?;
+        let maybe_row = client
+            .query_opt(IS_IN_WORKSPACE_QUERY, &[&change_set_id, &workspace_pk])
+            .await?;
+
+        Ok(maybe_row.is_some())
+    }
+
+    pub async fn release(&self, change_set_id: ChangeSetId) -> ChangeSetResult<()> {
+        let client = self.pg_pool.get().await?;
+        client.execute(RELEASE_QUERY, &[&change_set_id]).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+//! This module contains [`ChangeSetApplyMetrics`], the timing and size of applying a [`ChangeSet`](super::ChangeSet)
+//! to its base change set, as collected along the apply pipeline.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use telemetry_utils::metric;
+
+use super::size::ChangeSetSizeMetrics;
+
+/// How long applying a [`ChangeSet`](super::ChangeSet) took and how much it changed on its base change set.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApplyMetrics {
+    /// How long the rebaser took to rebase the base change set, including the round trip.
+    pub rebase_duration_ms: u64,
+    /// How long the whole apply took, including running the jobs it enqueued.
+    pub total_duration_ms: u64,
+    /// What the apply changed on the base change set. Every changed value is recomputed there.
+    #[serde(flatten)]
+    pub size: ChangeSetSizeMetrics,
+}
+
+impl ChangeSetApplyMetrics {
+    pub(crate) fn set_rebase_duration(&mut self, duration: Duration) {
+        self.rebase_duration_ms = duration_ms(duration);
+    }
+
+    pub(crate) fn set_total_duration(&mut self, duration: Duration) {
+        self.total_duration_ms = duration_ms(duration);
+    }
+
+    /// Records the metrics so that apply performance accumulates across change sets.
+    pub(crate) fn record(&self) {
+        metric!(histogram.change_set.apply.rebase_duration_ms = self.rebase_duration_ms);
+        metric!(histogram.change_set.apply.total_duration_ms = self.total_duration_ms);
+        metric!(histogram.change_set.apply.values_changed = self.size.values_changed as u64);
+        metric!(histogram.change_set.apply.actions_queued = self.size.actions_queued as u64);
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
@@ -0,0 +1,230 @@
+//! Approval of change sets before they are applied.
+//!
+//! Once approval of a [`ChangeSet`] has been requested, users approve or reject it, optionally
+//! with a comment. A rejection sends the change set back to its author right away, whereas it
+//! takes as many approvals as the workspace's policy requires for the change set to be approved
+//! for apply. Only the decisions made since approval was last requested count.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgRow;
+use strum::{Display, EnumString};
+
+use super::{ChangeSet, ChangeSetError, ChangeSetResult};
+use crate::{
+    ChangeSetId, ChangeSetStatus, DalContext, HistoryActor, UserPk, Workspace, WsEvent,
+    WsEventResult, WsPayload,
+};
+
+pub use si_id::ChangeSetApprovalId;
+
+/// The number of approvals needed in workspaces without an approval policy.
+const DEFAULT_REQUIRED_APPROVALS: usize = 1;
+
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize)]
+pub enum ChangeSetApprovalStatus {
+    Approved,
+    Rejected,
+}
+
+/// A decision made by a user on a change set awaiting approval.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApproval {
+    pub id: ChangeSetApprovalId,
+    pub change_set_id: ChangeSetId,
+    pub user_pk: UserPk,
+    pub status: ChangeSetApprovalStatus,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for ChangeSetApproval {
+    type Error = ChangeSetError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let status: String = row.try_get("status")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            change_set_id: row.try_get("change_set_id")?,
+            user_pk: row.try_get("user_pk")?,
+            status: status.parse()?,
+            comment: row.try_get("comment")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+/// Where a change set stands in getting approved for apply.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApprovalState {
+    pub change_set_id: ChangeSetId,
+    pub name: String,
+    pub status: ChangeSetStatus,
+    pub requested_by_user_id: Option<UserPk>,
+    pub requested_at: Option<DateTime<Utc>>,
+    /// The number of approvals needed for the change set to be approved.
+    pub required_approvals: usize,
+    /// Whether the approval of the user who requested it counts towards the required approvals.
+    pub self_approval_allowed: bool,
+    /// The decisions made since approval was last requested, oldest first.
+    pub approvals: Vec<ChangeSetApproval>,
+}
+
+impl ChangeSetApprovalState {
+    /// Returns true if enough distinct users have approved the change set.
+    pub fn is_satisfied(&self) -> bool {
+        let approvers: HashSet<UserPk> = self
+            .approvals
+            .iter()
+            .filter(|approval| approval.status == ChangeSetApprovalStatus::Approved)
+            .filter(|approval| {
+                self.self_approval_allowed || Some(approval.user_pk) != self.requested_by_user_id
+            })
+            .map(|approval| approval.user_pk)
+            .collect();
+
+        approvers.len() >= self.required_approvals
+    }
+}
+
+impl ChangeSet {
+    /// Returns the approvals of this change set since approval was last requested, along with what
+    /// the workspace's approval policy requires.
+    pub async fn approval_state(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<ChangeSetApprovalState> {
+        let workspace = Workspace::get_by_pk_or_error(ctx, self.workspace_id()?).await?;
+        let (required_approvals, self_approval_allowed) =
+            match workspace.required_change_set_approvals() {
+                Some(required_approvals) => (required_approvals.max(1) as usize, false),
+                None => (DEFAULT_REQUIRED_APPROVALS, true),
+            };
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_approvals
+                    WHERE change_set_id = $1 AND ($2::timestamptz IS NULL OR created_at >= $2)
+                    ORDER BY created_at ASC",
+                &[&self.id, &self.merge_requested_at],
+            )
+            .await?;
+        let approvals = rows
+            .into_iter()
+            .map(ChangeSetApproval::try_from)
+            .collect::<ChangeSetResult<_>>()?;
+
+        Ok(ChangeSetApprovalState {
+            change_set_id: self.id,
+            name: self.name.clone(),
+            status: self.status,
+            requested_by_user_id: self.merge_requested_by_user_id,
+            requested_at: self.merge_requested_at,
+            required_approvals,
+            self_approval_allowed,
+            approvals,
+        })
+    }
+
+    /// Returns true if the user of the context requested approval of this change set in a workspace
+    /// with an approval policy, which means that they can't approve it themselves.
+    pub async fn is_self_approval_forbidden(&self, ctx: &DalContext) -> ChangeSetResult<bool> {
+        let HistoryActor::User(user_pk) = ctx.history_actor() else {
+            return Ok(false);
+        };
+        if self.merge_requested_by_user_id != Some(*user_pk) {
+            return Ok(false);
+        }
+
+        let workspace = Workspace::get_by_pk_or_error(ctx, self.workspace_id()?).await?;
+        Ok(workspace.required_change_set_approvals().is_some())
+    }
+
+    /// Records the decision of the user of the context on this change set, which must be awaiting
+    /// approval. A rejection rejects the change set, while an approval approves it if it was the
+    /// last one needed.
+    ///
+    /// In workspaces with an approval policy, users can't approve change sets they requested
+    /// approval for themselves.
+    pub async fn record_approval(
+        &mut self,
+        ctx: &DalContext,
+        status: ChangeSetApprovalStatus,
+        comment: Option<String>,
+    ) -> ChangeSetResult<ChangeSetApprovalState> {
+        if self.status != ChangeSetStatus::NeedsApproval {
+            return Err(ChangeSetError::ChangeSetNotAwaitingApproval(
+                self.id,
+                self.status,
+            ));
+        }
+
+        let user_pk = Self::extract_userid_from_context_or_error(ctx).await?;
+        let state = self.approval_state(ctx).await?;
+        if status == ChangeSetApprovalStatus::Approved
+            && !state.self_approval_allowed
+            && state.requested_by_user_id == Some(user_pk)
+        {
+            return Err(ChangeSetError::SelfApproval(self.id));
+        }
+
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "INSERT INTO change_set_approvals (workspace_pk, change_set_id, user_pk, status, comment)
+                    VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &self.workspace_id()?,
+                    &self.id,
+                    &user_pk,
+                    &status.to_string(),
+                    &comment,
+                ],
+            )
+            .await?;
+
+        match status {
+            ChangeSetApprovalStatus::Approved => {
+                if self.approval_state(ctx).await?.is_satisfied() {
+                    self.approve_change_set_for_apply(ctx).await?;
+                }
+            }
+            ChangeSetApprovalStatus::Rejected => self.reject_change_set_for_apply(ctx).await?,
+        }
+
+        self.approval_state(ctx).await
+    }
+
+    /// Lists the change sets of the workspace which are awaiting approval.
+    pub async fn list_pending_approvals(
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<ChangeSetApprovalState>> {
+        let mut pending = vec![];
+        for change_set in Self::list_active(ctx).await? {
+            if change_set.status == ChangeSetStatus::NeedsApproval {
+                pending.push(change_set.approval_state(ctx).await?);
+            }
+        }
+
+        Ok(pending)
+    }
+}
+
+impl WsEvent {
+    /// Sent when a user approves or rejects a change set.
+    pub async fn change_set_approvals_changed(
+        ctx: &DalContext,
+        approval_state: ChangeSetApprovalState,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetApprovalsChanged(approval_state)).await
+    }
+}
@@ -0,0 +1,472 @@
+//! Blueprints: reusable templates captured from the components of a change set.
+//!
+//! [`capture`] records a set of components, the connections between them, their parentage and
+//! the values that were set on them (rather than computed by functions) as a [`BlueprintSpec`].
+//! Some of those values can be turned into parameters, which are supplied when the blueprint is
+//! instantiated and fall back to the captured value otherwise. Blueprints are stored as si-pkg
+//! packages (see [`into_pkg`] and [`list_from_pkg`]), and [`instantiate`] stamps one into a new
+//! change set.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+use si_pkg::{
+    BlueprintComponentSpec, BlueprintConnectionSpec, BlueprintParameterSpec, BlueprintSpec,
+    PkgSpec, PositionSpec, SiPkg, SiPkgError, SpecError,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    attribute::value::AttributeValueError,
+    component::frame::{Frame, FrameError},
+    diagram::{geometry::RawGeometry, view::View, DiagramError},
+    management::{
+        generator::generate_template_with_placeholders, update_component, ManagementCreateGeometry,
+        ManagementError,
+    },
+    prop::{PropError, PropPath},
+    socket::{input::InputSocketError, output::OutputSocketError},
+    AttributeValue, ChangeSet, ChangeSetError, ChangeSetId, Component, ComponentError, ComponentId,
+    DalContext, InputSocket, OutputSocket, Prop, Schema, SchemaError, TransactionsError, ViewId,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum BlueprintError {
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component {0} of the blueprint connects to {1}, which is not in the blueprint")]
+    ConnectionTargetNotFound(String, String),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] DiagramError),
+    #[error("parameter {0} is defined more than once")]
+    DuplicateParameter(String),
+    #[error("frame error: {0}")]
+    Frame(#[from] FrameError),
+    #[error("input socket error: {0}")]
+    InputSocket(#[from] InputSocketError),
+    #[error("component {0} of the blueprint has an invalid position")]
+    InvalidPosition(String),
+    #[error("management error: {0}")]
+    Management(#[from] ManagementError),
+    #[error("no value supplied for required parameter {0}")]
+    MissingParameterValue(String),
+    #[error("output socket error: {0}")]
+    OutputSocket(#[from] OutputSocketError),
+    #[error("parameter {0} is for component {1}, which is not being captured")]
+    ParameterComponentNotCaptured(String, ComponentId),
+    #[error("parameter {0} is for component {1}, which is not in the blueprint")]
+    ParameterComponentNotFound(String, String),
+    #[error("parameter {0} is for prop {1}, which does not exist on its component")]
+    ParameterPropNotFound(String, String),
+    #[error("parameter {0} is for prop {1}, which is set by a function")]
+    ParameterPropSetByFunction(String, String),
+    #[error("si pkg error: {0}")]
+    Pkg(#[from] SiPkgError),
+    #[error("prop error: {0}")]
+    Prop(#[from] PropError),
+    #[error("schema error: {0}")]
+    Schema(#[from] SchemaError),
+    #[error("schema {0} used by the blueprint does not exist")]
+    SchemaNotFound(String),
+    #[error("si pkg spec error: {0}")]
+    Spec(#[from] SpecError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("the blueprint has no parameter named {0}")]
+    UnknownParameter(String),
+}
+
+pub type BlueprintResult<T> = Result<T, BlueprintError>;
+
+/// Turns a value of one of the captured components into a parameter of the blueprint.
+#[derive(Clone, Debug)]
+pub struct BlueprintParameterDefinition {
+    pub name: String,
+    pub description: Option<String>,
+    pub component_id: ComponentId,
+    pub prop_path: PropPath,
+    /// If true, the captured value is not kept as the parameter's default, so a value has to be
+    /// supplied when the blueprint is instantiated.
+    pub required: bool,
+}
+
+/// The result of stamping a blueprint into a change set.
+#[derive(Clone, Debug)]
+pub struct BlueprintInstantiation {
+    pub change_set_id: ChangeSetId,
+    /// The created components, keyed by their placeholder in the blueprint.
+    pub component_ids: HashMap<String, ComponentId>,
+}
+
+/// Captures the given components, as they appear in the given view, as a [`BlueprintSpec`].
+pub async fn capture(
+    ctx: &DalContext,
+    name: impl Into<String>,
+    description: impl Into<String>,
+    view_id: ViewId,
+    component_ids: &[ComponentId],
+    parameters: &[BlueprintParameterDefinition],
+) -> BlueprintResult<BlueprintSpec> {
+    let (creates, _, placeholders) =
+        generate_template_with_placeholders(ctx, view_id, component_ids).await?;
+
+    let mut builder = BlueprintSpec::builder();
+    builder.name(name).description(description);
+
+    let mut parameter_names = HashSet::new();
+    for parameter in parameters {
+        if !parameter_names.insert(parameter.name.as_str()) {
+            return Err(BlueprintError::DuplicateParameter(
+                parameter.name.to_owned(),
+            ));
+        }
+        let placeholder = placeholders.get(&parameter.component_id).ok_or_else(|| {
+            BlueprintError::ParameterComponentNotCaptured(
+                parameter.name.to_owned(),
+                parameter.component_id,
+            )
+        })?;
+
+        let display_path = parameter.prop_path.as_parts().join("/");
+        let variant_id = Component::schema_variant_id(ctx, parameter.component_id).await?;
+        let prop_id = Prop::find_prop_id_by_path_opt(ctx, variant_id, &parameter.prop_path)
+            .await?
+            .ok_or_else(|| {
+                BlueprintError::ParameterPropNotFound(
+                    parameter.name.to_owned(),
+                    display_path.to_owned(),
+                )
+            })?;
+        let attribute_value_id =
+            Component::attribute_value_for_prop_id(ctx, parameter.component_id, prop_id).await?;
+        if AttributeValue::is_set_by_dependent_function(ctx, attribute_value_id).await? {
+            return Err(BlueprintError::ParameterPropSetByFunction(
+                parameter.name.to_owned(),
+                display_path,
+            ));
+        }
+
+        let mut parameter_builder = BlueprintParameterSpec::builder();
+        parameter_builder
+            .name(&parameter.name)
+            .placeholder(placeholder)
+            .prop_path(parameter.prop_path.as_owned_parts());
+        if let Some(description) = &parameter.description {
+            parameter_builder.description(description);
+        }
+        if !parameter.required {
+            if let Some(value) = AttributeValue::get_by_id(ctx, attribute_value_id)
+                .await?
+                .view(ctx)
+                .await?
+            {
+                parameter_builder.default_value(value);
+            }
+        }
+        builder.parameter(parameter_builder.build()?);
+    }
+
+    // Sort by placeholder so that capturing the same components produces the same blueprint
+    let mut creates: Vec<_> = creates.into_iter().collect();
+    creates.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (placeholder, create) in creates {
+        let geometry: RawGeometry = match create.geometry {
+            Some(ManagementCreateGeometry::CurrentView(geometry)) => geometry.into(),
+            Some(ManagementCreateGeometry::WithViews(geometries)) => geometries
+                .into_values()
+                .next()
+                .map(Into::into)
+                .unwrap_or_default(),
+            None => RawGeometry::default(),
+        };
+
+        let mut component_builder = BlueprintComponentSpec::builder();
+        component_builder
+            .placeholder(&placeholder)
+            .schema_name(create.kind.unwrap_or_default())
+            .properties(create.properties.unwrap_or(Value::Null))
+            .position(
+                PositionSpec::builder()
+                    .x(geometry.x.to_string())
+                    .y(geometry.y.to_string())
+                    .width(geometry.width.map(|width| width.to_string()))
+                    .height(geometry.height.map(|height| height.to_string()))
+                    .build()?,
+            );
+        if let Some(parent) = create.parent {
+            component_builder.parent(parent);
+        }
+        for connection in create.connect.unwrap_or_default() {
+            component_builder.connection(
+                BlueprintConnectionSpec::builder()
+                    .from_socket_name(connection.from)
+                    .to_placeholder(connection.to.component)
+                    .to_socket_name(connection.to.socket)
+                    .build()?,
+            );
+        }
+
+        builder.component(component_builder.build()?);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Wraps the blueprint in an si-pkg package so that it can be stored and shared.
+pub fn into_pkg(
+    blueprint: BlueprintSpec,
+    version: impl Into<String>,
+    created_by: impl Into<String>,
+) -> BlueprintResult<SiPkg> {
+    let spec = PkgSpec::builder()
+        .name(&blueprint.name)
+        .description(&blueprint.description)
+        .version(version)
+        .created_by(created_by)
+        .blueprint(blueprint)
+        .build()?;
+
+    Ok(SiPkg::load_from_spec(spec)?)
+}
+
+/// Lists the blueprints stored in the given package.
+pub fn list_from_pkg(pkg: &SiPkg) -> BlueprintResult<Vec<BlueprintSpec>> {
+    let mut blueprints = vec![];
+    for blueprint in pkg.blueprints()? {
+        blueprints.push(BlueprintSpec::try_from(blueprint)?);
+    }
+
+    Ok(blueprints)
+}
+
+/// Forks a new change set from HEAD and stamps the blueprint into it, using the given values for
+/// its parameters. The context is moved to the new change set, and it is left to the caller to
+/// commit.
+pub async fn instantiate(
+    ctx: &mut DalContext,
+    blueprint: &BlueprintSpec,
+    change_set_name: impl AsRef<str>,
+    values: HashMap<String, Value>,
+) -> BlueprintResult<BlueprintInstantiation> {
+    let properties = resolve_properties(blueprint, values)?;
+
+    let change_set = ChangeSet::fork_head(ctx, change_set_name).await?;
+    ctx.update_visibility_and_snapshot_to_visibility(change_set.id)
+        .await?;
+
+    let component_ids = stamp(ctx, blueprint, properties).await?;
+
+    Ok(BlueprintInstantiation {
+        change_set_id: change_set.id,
+        component_ids,
+    })
+}
+
+/// Applies the parameter values to the captured properties of each component, keyed by
+/// placeholder.
+fn resolve_properties(
+    blueprint: &BlueprintSpec,
+    mut values: HashMap<String, Value>,
+) -> BlueprintResult<HashMap<String, Value>> {
+    let mut properties: HashMap<String, Value> = blueprint
+        .components
+        .iter()
+        .map(|component| {
+            (
+                component.placeholder.to_owned(),
+                component.properties.to_owned(),
+            )
+        })
+        .collect();
+
+    for parameter in &blueprint.parameters {
+        let value = match values.remove(&parameter.name) {
+            Some(value) => value,
+            None => parameter
+                .default_value
+                .to_owned()
+                .ok_or_else(|| BlueprintError::MissingParameterValue(parameter.name.to_owned()))?,
+        };
+
+        let component_properties = properties.get_mut(&parameter.placeholder).ok_or_else(|| {
+            BlueprintError::ParameterComponentNotFound(
+                parameter.name.to_owned(),
+                parameter.placeholder.to_owned(),
+            )
+        })?;
+        // Prop paths start at the root prop, which is the properties object itself
+        set_value_at_path(
+            component_properties,
+            parameter.prop_path.iter().skip(1).map(String::as_str),
+            value,
+        );
+    }
+
+    if let Some(name) = values.into_keys().next() {
+        return Err(BlueprintError::UnknownParameter(name));
+    }
+
+    Ok(properties)
+}
+
+fn set_value_at_path<'a>(
+    properties: &mut Value,
+    path: impl IntoIterator<Item = &'a str>,
+    value: Value,
+) {
+    let mut cursor = properties;
+    for part in path {
+        if !cursor.is_object() {
+            *cursor = Value::Object(Default::default());
+        }
+        let Value::Object(object) = cursor else {
+            return;
+        };
+        cursor = object.entry(part).or_insert(Value::Null);
+    }
+    *cursor = value;
+}
+
+async fn stamp(
+    ctx: &DalContext,
+    blueprint: &BlueprintSpec,
+    mut properties: HashMap<String, Value>,
+) -> BlueprintResult<HashMap<String, ComponentId>> {
+    let view_id = View::get_id_for_default(ctx).await?;
+
+    let mut component_ids = HashMap::new();
+    let mut variant_ids = HashMap::new();
+    for component_spec in &blueprint.components {
+        let schema = Schema::find_by_name(ctx, &component_spec.schema_name)
+            .await?
+            .ok_or_else(|| BlueprintError::SchemaNotFound(component_spec.schema_name.to_owned()))?;
+        let variant_id = Schema::get_or_install_default_variant(ctx, schema.id()).await?;
+
+        let mut component =
+            Component::new(ctx, &component_spec.placeholder, variant_id, view_id).await?;
+        let geometry = raw_geometry(component_spec)?;
+        component
+            .set_geometry(
+                ctx,
+                view_id,
+                geometry.x,
+                geometry.y,
+                geometry.width,
+                geometry.height,
+            )
+            .await?;
+
+        if let Some(properties) = properties.remove(&component_spec.placeholder) {
+            update_component(ctx, component.id(), &properties, &[&["root", "si", "name"]]).await?;
+        }
+
+        component_ids.insert(component_spec.placeholder.to_owned(), component.id());
+        variant_ids.insert(component.id(), variant_id);
+    }
+
+    for component_spec in &blueprint.components {
+        let Some(&component_id) = component_ids.get(&component_spec.placeholder) else {
+            continue;
+        };
+
+        if let Some(parent) = &component_spec.parent {
+            let parent_id = component_ids.get(parent).copied().ok_or_else(|| {
+                BlueprintError::ConnectionTargetNotFound(
+                    component_spec.placeholder.to_owned(),
+                    parent.to_owned(),
+                )
+            })?;
+            Frame::upsert_parent(ctx, component_id, parent_id).await?;
+        }
+
+        for connection in &component_spec.connections {
+            let to_component_id = component_ids
+                .get(&connection.to_placeholder)
+                .copied()
+                .ok_or_else(|| {
+                    BlueprintError::ConnectionTargetNotFound(
+                        component_spec.placeholder.to_owned(),
+                        connection.to_placeholder.to_owned(),
+                    )
+                })?;
+
+            let output_socket = OutputSocket::find_with_name_or_error(
+                ctx,
+                &connection.from_socket_name,
+                variant_ids[&component_id],
+            )
+            .await?;
+            let input_socket = InputSocket::find_with_name_or_error(
+                ctx,
+                &connection.to_socket_name,
+                variant_ids[&to_component_id],
+            )
+            .await?;
+
+            Component::connect(
+                ctx,
+                component_id,
+                output_socket.id(),
+                to_component_id,
+                input_socket.id(),
+            )
+            .await?;
+        }
+    }
+
+    debug!(
+        blueprint = %blueprint.name,
+        components = component_ids.len(),
+        "stamped blueprint"
+    );
+
+    Ok(component_ids)
+}
+
+fn raw_geometry(component_spec: &BlueprintComponentSpec) -> BlueprintResult<RawGeometry> {
+    let parse = |value: &str| {
+        value
+            .parse::<isize>()
+            .map_err(|_| BlueprintError::InvalidPosition(component_spec.placeholder.to_owned()))
+    };
+    let position = &component_spec.position;
+
+    Ok(RawGeometry {
+        x: parse(&position.x)?,
+        y: parse(&position.y)?,
+        width: position.width.as_deref().map(parse).transpose()?,
+        height: position.height.as_deref().map(parse).transpose()?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sets_value_at_path() {
+        let mut properties = serde_json::json!({ "si": { "name": "a" } });
+
+        set_value_at_path(
+            &mut properties,
+            ["domain", "region"],
+            serde_json::json!("us-east-1"),
+        );
+        set_value_at_path(&mut properties, ["si", "name"], serde_json::json!("b"));
+
+        assert_eq!(
+            serde_json::json!({
+                "si": { "name": "b" },
+                "domain": { "region": "us-east-1" }
+            }),
+            properties
+        );
+    }
+}
@@ -64,6 +64,31 @@ impl WsEvent {
         .await
     }
 
+    /// Sent when a change set starts being applied. Mutations to it are rejected until the
+    /// corresponding [`WsPayload::ChangeSetApplyUnlocked`] event.
+    pub async fn change_set_apply_locked(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetApplyLocked(change_set_id)).await
+    }
+
+    pub async fn change_set_apply_unlocked(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetApplyUnlocked(change_set_id)).await
+    }
+
+    /// Sent when a change set is quarantined because its snapshot is corrupt. The change set is
+    /// read-only until an admin restores it.
+    pub async fn change_set_quarantined(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetQuarantined(change_set_id)).await
+    }
+
     pub async fn change_set_canceled(
         ctx: &DalContext,
         change_set_id: ChangeSetId,
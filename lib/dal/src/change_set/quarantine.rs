@@ -0,0 +1,199 @@
+//! Quarantining of change sets whose snapshot content is corrupt.
+//!
+//! With [`FeatureFlag::SnapshotContentVerification`] enabled, a sample of the loads of a change set
+//! check a sample of the content store objects referenced by its snapshot against their hashes.
+//! Every write makes a new snapshot, so verifying every load would re-hash the whole content store
+//! of a change set after each edit. A snapshot that passes is recorded as the change set's last
+//! good snapshot, and is not checked again. One that fails quarantines the change set instead of
+//! letting work proceed on corrupt data: a quarantined change set still loads, so it can be
+//! inspected, but it is read-only until an admin restores it.
+
+use rand::Rng;
+use si_events::{ContentHash, WorkspaceSnapshotAddress};
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+
+use super::{ChangeSet, ChangeSetError, ChangeSetResult};
+use crate::{billing_publish, feature_flags::FeatureFlag, DalContext, WorkspaceSnapshot, WsEvent};
+
+// Enough to find the corrupt objects in the logs without flooding them
+const LOGGED_CORRUPTED_CONTENT_LIMIT: usize = 10;
+// One in this many snapshot loads is verified
+const VERIFIED_LOAD_RATIO: u32 = 100;
+// How many content store objects of a snapshot a verification checks at most
+const VERIFIED_CONTENT_SAMPLE_SIZE: usize = 1000;
+
+impl ChangeSet {
+    /// Quarantined change sets are read-only.
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantined_at.is_some()
+    }
+
+    /// Verifies a sample of the content of the change set's snapshot, on a sample of the calls, if
+    /// [`FeatureFlag::SnapshotContentVerification`] is enabled. Fails with
+    /// [`ChangeSetError::Quarantined`] after quarantining the change set if any of it is corrupt.
+    ///
+    /// Snapshots which passed are not verified again, and neither are quarantined change sets.
+    #[instrument(
+        name = "change_set.verify_snapshot",
+        level = "info",
+        skip_all,
+        fields(
+            si.change_set.id = %self.id,
+            si.workspace_snapshot.address = Empty,
+        ),
+    )]
+    pub async fn verify_snapshot(
+        &mut self,
+        ctx: &DalContext,
+        workspace_snapshot: &WorkspaceSnapshot,
+    ) -> ChangeSetResult<()> {
+        let verification_enabled = ctx
+            .services_context()
+            .feature_flags_service()
+            .feature_is_enabled(&FeatureFlag::SnapshotContentVerification);
+        if !verification_enabled || self.is_quarantined() {
+            return Ok(());
+        }
+
+        let address = workspace_snapshot.id().await;
+        if self.last_good_snapshot_address == Some(address)
+            || !rand::thread_rng().gen_ratio(1, VERIFIED_LOAD_RATIO)
+        {
+            return Ok(());
+        }
+        current_span_for_instrument_at!("info")
+            .record("si.workspace_snapshot.address", address.to_string());
+
+        let corrupted = workspace_snapshot
+            .find_corrupted_content(ctx, VERIFIED_CONTENT_SAMPLE_SIZE)
+            .await
+            .map_err(Box::new)?;
+        if corrupted.is_empty() {
+            self.record_good_snapshot(ctx, address).await
+        } else {
+            self.quarantine(ctx, address, &corrupted).await?;
+            Err(ChangeSetError::Quarantined(self.id))
+        }
+    }
+
+    /// Points a quarantined change set back at its last good snapshot and lifts the quarantine.
+    pub async fn restore_last_good_snapshot(
+        &mut self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<WorkspaceSnapshotAddress> {
+        let address = self
+            .last_good_snapshot_address
+            .ok_or(ChangeSetError::NoLastGoodSnapshot(self.id))?;
+        self.restore_snapshot(ctx, address).await?;
+
+        Ok(address)
+    }
+
+    /// Points the change set at a snapshot even if it is quarantined, lifting any quarantine.
+    pub async fn restore_snapshot(
+        &mut self,
+        ctx: &DalContext,
+        workspace_snapshot_address: WorkspaceSnapshotAddress,
+    ) -> ChangeSetResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "UPDATE change_set_pointers SET workspace_snapshot_address = $2, quarantined_at = NULL, updated_at = CLOCK_TIMESTAMP() WHERE id = $1",
+                &[&self.id, &workspace_snapshot_address],
+            )
+            .await?;
+
+        self.workspace_snapshot_address = workspace_snapshot_address;
+        self.quarantined_at = None;
+
+        billing_publish::for_head_change_set_pointer_update(ctx, self)
+            .await
+            .map_err(Box::new)?;
+        WsEvent::change_set_written(ctx, self.id)
+            .await
+            .map_err(Box::new)?
+            .publish_on_commit(ctx)
+            .await
+            .map_err(Box::new)?;
+
+        Ok(())
+    }
+
+    // Written outside of the context's transactions so that it sticks even when loading fails
+    async fn record_good_snapshot(
+        &mut self,
+        ctx: &DalContext,
+        address: WorkspaceSnapshotAddress,
+    ) -> ChangeSetResult<()> {
+        ctx.pg_pool()
+            .get()
+            .await?
+            .execute(
+                "UPDATE change_set_pointers SET last_good_snapshot_address = $2 WHERE id = $1",
+                &[&self.id, &address],
+            )
+            .await?;
+        self.last_good_snapshot_address = Some(address);
+
+        Ok(())
+    }
+
+    /// Clears the last good snapshot of every change set whose last good snapshot is one of
+    /// `addresses`, since those snapshots were pruned. Returns how many change sets were updated.
+    pub async fn clear_last_good_snapshot_addresses(
+        ctx: &DalContext,
+        addresses: &[WorkspaceSnapshotAddress],
+    ) -> ChangeSetResult<u64> {
+        let addresses: Vec<String> = addresses.iter().map(ToString::to_string).collect();
+
+        Ok(ctx
+            .pg_pool()
+            .get()
+            .await?
+            .execute(
+                "UPDATE change_set_pointers SET last_good_snapshot_address = NULL
+                   WHERE last_good_snapshot_address = ANY($1)",
+                &[&addresses],
+            )
+            .await?)
+    }
+
+    async fn quarantine(
+        &mut self,
+        ctx: &DalContext,
+        address: WorkspaceSnapshotAddress,
+        corrupted: &[ContentHash],
+    ) -> ChangeSetResult<()> {
+        error!(
+            si.change_set.id = %self.id,
+            si.workspace.id = ?self.workspace_id,
+            si.workspace_snapshot.address = %address,
+            corrupted_content.count = corrupted.len(),
+            corrupted_content = ?&corrupted[..corrupted.len().min(LOGGED_CORRUPTED_CONTENT_LIMIT)],
+            "snapshot content is corrupt, quarantining change set",
+        );
+        metric!(monotonic_counter.change_set.quarantined = 1);
+
+        let row = ctx
+            .pg_pool()
+            .get()
+            .await?
+            .query_one(
+                "UPDATE change_set_pointers SET quarantined_at = CLOCK_TIMESTAMP(), updated_at = CLOCK_TIMESTAMP() WHERE id = $1 RETURNING quarantined_at",
+                &[&self.id],
+            )
+            .await?;
+        self.quarantined_at = row.try_get("quarantined_at")?;
+
+        WsEvent::change_set_quarantined(ctx, self.id)
+            .await
+            .map_err(Box::new)?
+            .publish_immediately(ctx)
+            .await
+            .map_err(Box::new)?;
+
+        Ok(())
+    }
+}
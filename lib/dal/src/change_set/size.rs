@@ -0,0 +1,309 @@
+//! This module contains [`ChangeSetSizeMetrics`], which describe how much applying a [`ChangeSet`]
+//! would change on its base change set, and [`ChangeSetSizeThresholds`], the per-workspace soft and
+//! hard limits those metrics are checked against.
+//!
+//! Exceeding a soft limit only produces advisory warnings. Exceeding a hard limit blocks
+//! [`ChangeSet::prepare_for_apply`], since extremely large change sets strain the rebaser and are
+//! almost always accidental.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use si_events::ulid::Ulid;
+use telemetry::prelude::*;
+
+use crate::workspace_snapshot::graph::detect_updates::Update;
+use crate::workspace_snapshot::node_weight::NodeWeight;
+use crate::{DalContext, NodeWeightDiscriminants};
+
+use super::{ChangeSet, ChangeSetError, ChangeSetResult};
+
+/// The default soft limits, used when a workspace has not configured its own thresholds.
+const DEFAULT_SOFT_LIMITS: ChangeSetSizeLimits = ChangeSetSizeLimits {
+    components_touched: Some(500),
+    values_changed: Some(20_000),
+    actions_queued: Some(250),
+};
+
+/// What applying a [`ChangeSet`] would change on its base change set.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetSizeMetrics {
+    /// The number of distinct components that were created, updated or (dis)connected.
+    pub components_touched: usize,
+    /// The number of attribute values that were created or updated.
+    pub values_changed: usize,
+    /// The number of actions that would be enqueued.
+    pub actions_queued: usize,
+}
+
+/// A limit for each of the [`ChangeSetSizeMetrics`]. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetSizeLimits {
+    pub components_touched: Option<usize>,
+    pub values_changed: Option<usize>,
+    pub actions_queued: Option<usize>,
+}
+
+/// The soft (warn) and hard (block) [`ChangeSetSizeLimits`] for a workspace.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetSizeThresholds {
+    pub soft: ChangeSetSizeLimits,
+    pub hard: ChangeSetSizeLimits,
+}
+
+impl Default for ChangeSetSizeThresholds {
+    fn default() -> Self {
+        Self {
+            soft: DEFAULT_SOFT_LIMITS,
+            hard: ChangeSetSizeLimits::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeSetSizeMetric {
+    ComponentsTouched,
+    ValuesChanged,
+    ActionsQueued,
+}
+
+impl fmt::Display for ChangeSetSizeMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ComponentsTouched => "components touched",
+            Self::ValuesChanged => "values changed",
+            Self::ActionsQueued => "actions queued",
+        })
+    }
+}
+
+/// A metric which is over one of its limits.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetSizeViolation {
+    pub metric: ChangeSetSizeMetric,
+    pub value: usize,
+    pub limit: usize,
+}
+
+impl fmt::Display for ChangeSetSizeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} (limit {})", self.value, self.metric, self.limit)
+    }
+}
+
+/// The [`ChangeSetSizeMetrics`] of a [`ChangeSet`] checked against its workspace's
+/// [`ChangeSetSizeThresholds`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetSizeReport {
+    pub metrics: ChangeSetSizeMetrics,
+    pub thresholds: ChangeSetSizeThresholds,
+    /// Soft limits that were exceeded. Applying is still allowed.
+    pub warnings: Vec<ChangeSetSizeViolation>,
+    /// Hard limits that were exceeded. Applying is blocked.
+    pub blocking: Vec<ChangeSetSizeViolation>,
+}
+
+impl ChangeSetSizeReport {
+    pub fn is_blocked(&self) -> bool {
+        !self.blocking.is_empty()
+    }
+}
+
+impl ChangeSetSizeMetrics {
+    /// Compute the metrics for the [`Updates`](Update) that applying would perform.
+    pub fn from_updates(updates: &[Update]) -> Self {
+        let mut components = HashSet::new();
+        let mut values = HashSet::new();
+        let mut actions = HashSet::new();
+
+        for update in updates {
+            match update {
+                Update::NewNode { node_weight } | Update::ReplaceNode { node_weight } => {
+                    match node_weight {
+                        NodeWeight::Component(_) => {
+                            components.insert(node_weight.id());
+                        }
+                        NodeWeight::AttributeValue(_) => {
+                            values.insert(node_weight.id());
+                        }
+                        // Only new actions are enqueued, updates to existing ones are not
+                        NodeWeight::Action(_) if matches!(update, Update::NewNode { .. }) => {
+                            actions.insert(node_weight.id());
+                        }
+                        _ => {}
+                    }
+                }
+                Update::NewEdge {
+                    source,
+                    destination,
+                    ..
+                }
+                | Update::RemoveEdge {
+                    source,
+                    destination,
+                    ..
+                } => {
+                    for node in [source, destination] {
+                        if node.node_weight_kind == NodeWeightDiscriminants::Component {
+                            components.insert(Ulid::from(node.id));
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            components_touched: components.len(),
+            values_changed: values.len(),
+            actions_queued: actions.len(),
+        }
+    }
+
+    fn get(&self, metric: ChangeSetSizeMetric) -> usize {
+        match metric {
+            ChangeSetSizeMetric::ComponentsTouched => self.components_touched,
+            ChangeSetSizeMetric::ValuesChanged => self.values_changed,
+            ChangeSetSizeMetric::ActionsQueued => self.actions_queued,
+        }
+    }
+
+    /// Returns every metric which is over its limit.
+    pub fn exceeding(&self, limits: &ChangeSetSizeLimits) -> Vec<ChangeSetSizeViolation> {
+        [
+            ChangeSetSizeMetric::ComponentsTouched,
+            ChangeSetSizeMetric::ValuesChanged,
+            ChangeSetSizeMetric::ActionsQueued,
+        ]
+        .into_iter()
+        .filter_map(|metric| {
+            let value = self.get(metric);
+            limits
+                .get(metric)
+                .filter(|limit| value > *limit)
+                .map(|limit| ChangeSetSizeViolation {
+                    metric,
+                    value,
+                    limit,
+                })
+        })
+        .collect()
+    }
+}
+
+impl ChangeSetSizeLimits {
+    fn get(&self, metric: ChangeSetSizeMetric) -> Option<usize> {
+        match metric {
+            ChangeSetSizeMetric::ComponentsTouched => self.components_touched,
+            ChangeSetSizeMetric::ValuesChanged => self.values_changed,
+            ChangeSetSizeMetric::ActionsQueued => self.actions_queued,
+        }
+    }
+}
+
+impl ChangeSetSizeThresholds {
+    /// Load the thresholds for the workspace of the provided [`DalContext`], falling back to the
+    /// defaults if none have been configured.
+    pub async fn for_workspace(ctx: &DalContext) -> ChangeSetResult<Self> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk_opt()
+            .ok_or(ChangeSetError::NoTenancySet)?;
+
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM change_set_size_thresholds WHERE workspace_pk = $1",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        let Some(row) = maybe_row else {
+            return Ok(Self::default());
+        };
+
+        let limit = |column: &str| -> ChangeSetResult<Option<usize>> {
+            let value: Option<i32> = row.try_get(column)?;
+            Ok(value.map(|value| value.max(0) as usize))
+        };
+
+        Ok(Self {
+            soft: ChangeSetSizeLimits {
+                components_touched: limit("soft_components_touched")?,
+                values_changed: limit("soft_values_changed")?,
+                actions_queued: limit("soft_actions_queued")?,
+            },
+            hard: ChangeSetSizeLimits {
+                components_touched: limit("hard_components_touched")?,
+                values_changed: limit("hard_values_changed")?,
+                actions_queued: limit("hard_actions_queued")?,
+            },
+        })
+    }
+
+    /// Store the thresholds for the workspace of the provided [`DalContext`].
+    pub async fn set_for_workspace(&self, ctx: &DalContext) -> ChangeSetResult<()> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk_opt()
+            .ok_or(ChangeSetError::NoTenancySet)?;
+
+        let column = |value: Option<usize>| value.map(|value| value.min(i32::MAX as usize) as i32);
+
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "INSERT INTO change_set_size_thresholds (
+                    workspace_pk,
+                    soft_components_touched, soft_values_changed, soft_actions_queued,
+                    hard_components_touched, hard_values_changed, hard_actions_queued
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (workspace_pk) DO UPDATE SET
+                    soft_components_touched = $2, soft_values_changed = $3, soft_actions_queued = $4,
+                    hard_components_touched = $5, hard_values_changed = $6, hard_actions_queued = $7,
+                    updated_at = CLOCK_TIMESTAMP()",
+                &[
+                    &workspace_pk,
+                    &column(self.soft.components_touched),
+                    &column(self.soft.values_changed),
+                    &column(self.soft.actions_queued),
+                    &column(self.hard.components_touched),
+                    &column(self.hard.values_changed),
+                    &column(self.hard.actions_queued),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl ChangeSet {
+    /// Compute the [`ChangeSetSizeMetrics`] for the change set of the provided [`DalContext`]
+    /// and check them against the workspace's [`ChangeSetSizeThresholds`].
+    #[instrument(level = "info", name = "change_set.size_report", skip_all)]
+    pub async fn size_report(ctx: &DalContext) -> ChangeSetResult<ChangeSetSizeReport> {
+        let change_set = ctx.change_set()?;
+        let metrics = match change_set.detect_updates_that_will_be_applied(ctx).await? {
+            Some(rebase_batch) => ChangeSetSizeMetrics::from_updates(rebase_batch.updates()),
+            None => ChangeSetSizeMetrics::default(),
+        };
+        let thresholds = ChangeSetSizeThresholds::for_workspace(ctx).await?;
+
+        Ok(ChangeSetSizeReport {
+            metrics,
+            thresholds,
+            warnings: metrics.exceeding(&thresholds.soft),
+            blocking: metrics.exceeding(&thresholds.hard),
+        })
+    }
+}
@@ -0,0 +1,151 @@
+//! This module contains [`ChangeSetStats`] and [`WorkspaceStats`], which describe the size of the
+//! graph (and the content it references) for a single [`ChangeSet`] or for all active change sets
+//! in a workspace.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use si_events::ContentHash;
+use telemetry::prelude::*;
+
+use crate::workspace_snapshot::content_address::ContentAddressDiscriminants;
+use crate::workspace_snapshot::node_weight::NodeWeight;
+use crate::{DalContext, EdgeWeightKindDiscriminants, NodeWeightDiscriminants, WorkspaceSnapshot};
+
+use super::{ChangeSet, ChangeSetId, ChangeSetResult};
+
+/// Graph and content store statistics for a single [`ChangeSet`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetStats {
+    pub change_set_id: ChangeSetId,
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// Node counts keyed by [`NodeWeightDiscriminants`].
+    pub nodes_by_kind: BTreeMap<String, usize>,
+    /// Edge counts keyed by [`EdgeWeightKindDiscriminants`].
+    pub edges_by_kind: BTreeMap<String, usize>,
+    /// The number of distinct content store objects referenced by the graph.
+    pub content_store_object_count: usize,
+    /// The stored (compressed) size of all distinct content store objects referenced by the
+    /// graph. Objects which have not been persisted yet are not counted.
+    pub content_store_bytes: u64,
+    pub component_count: usize,
+    pub func_count: usize,
+    pub schema_variant_count: usize,
+}
+
+/// A rollup of [`ChangeSetStats`] for every active [`ChangeSet`] in a workspace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStats {
+    pub change_set_count: usize,
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// The number of distinct content store objects referenced across all change sets.
+    pub content_store_object_count: usize,
+    /// The stored (compressed) size of all distinct content store objects referenced across all
+    /// change sets. Objects shared between change sets are only counted once.
+    pub content_store_bytes: u64,
+    pub change_sets: Vec<ChangeSetStats>,
+}
+
+impl ChangeSet {
+    /// Gather [`ChangeSetStats`] for the change set of the provided [`DalContext`].
+    #[instrument(level = "info", name = "change_set.stats", skip_all)]
+    pub async fn stats(ctx: &DalContext) -> ChangeSetResult<ChangeSetStats> {
+        let workspace_snapshot = ctx.workspace_snapshot().map_err(Box::new)?;
+        let (stats, _) =
+            Self::stats_for_snapshot(ctx, ctx.change_set_id(), &workspace_snapshot).await?;
+
+        Ok(stats)
+    }
+
+    /// Gather [`ChangeSetStats`] for every active [`ChangeSet`] in the workspace of the provided
+    /// [`DalContext`] and roll them up into [`WorkspaceStats`].
+    #[instrument(level = "info", name = "change_set.workspace_stats", skip_all)]
+    pub async fn workspace_stats(ctx: &DalContext) -> ChangeSetResult<WorkspaceStats> {
+        let mut workspace_stats = WorkspaceStats::default();
+        let mut all_content_hashes = HashSet::new();
+
+        for change_set in Self::list_active(ctx).await? {
+            let workspace_snapshot = WorkspaceSnapshot::find_for_change_set(ctx, change_set.id)
+                .await
+                .map_err(Box::new)?;
+            let (stats, content_hashes) =
+                Self::stats_for_snapshot(ctx, change_set.id, &workspace_snapshot).await?;
+
+            workspace_stats.change_set_count += 1;
+            workspace_stats.node_count += stats.node_count;
+            workspace_stats.edge_count += stats.edge_count;
+            workspace_stats.change_sets.push(stats);
+            all_content_hashes.extend(content_hashes);
+        }
+
+        let all_content_hashes: Vec<ContentHash> = all_content_hashes.into_iter().collect();
+        workspace_stats.content_store_object_count = all_content_hashes.len();
+        workspace_stats.content_store_bytes =
+            Self::content_store_bytes(ctx, &all_content_hashes).await?;
+
+        Ok(workspace_stats)
+    }
+
+    async fn stats_for_snapshot(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+        workspace_snapshot: &WorkspaceSnapshot,
+    ) -> ChangeSetResult<(ChangeSetStats, HashSet<ContentHash>)> {
+        let mut stats = ChangeSetStats {
+            change_set_id,
+            ..Default::default()
+        };
+        let mut content_hashes = HashSet::new();
+
+        for (node_weight, _) in workspace_snapshot.nodes().await.map_err(Box::new)? {
+            stats.node_count += 1;
+            *stats
+                .nodes_by_kind
+                .entry(NodeWeightDiscriminants::from(&node_weight).to_string())
+                .or_default() += 1;
+
+            match &node_weight {
+                NodeWeight::Component(_) => stats.component_count += 1,
+                NodeWeight::Func(_) => stats.func_count += 1,
+                NodeWeight::SchemaVariant(_) => stats.schema_variant_count += 1,
+                // Older graphs store schema variants as content nodes
+                NodeWeight::Content(content)
+                    if content.content_address_discriminants()
+                        == ContentAddressDiscriminants::SchemaVariant =>
+                {
+                    stats.schema_variant_count += 1
+                }
+                _ => {}
+            }
+
+            content_hashes.extend(node_weight.content_store_hashes());
+        }
+
+        for (edge_weight, _, _) in workspace_snapshot.edges().await.map_err(Box::new)? {
+            stats.edge_count += 1;
+            *stats
+                .edges_by_kind
+                .entry(EdgeWeightKindDiscriminants::from(edge_weight.kind()).to_string())
+                .or_default() += 1;
+        }
+
+        let hashes: Vec<ContentHash> = content_hashes.iter().copied().collect();
+        stats.content_store_object_count = hashes.len();
+        stats.content_store_bytes = Self::content_store_bytes(ctx, &hashes).await?;
+
+        Ok((stats, content_hashes))
+    }
+
+    /// Sums the stored sizes of the objects in the database rather than loading (and
+    /// re-serializing) every one of them.
+    async fn content_store_bytes(
+        ctx: &DalContext,
+        content_hashes: &[ContentHash],
+    ) -> ChangeSetResult<u64> {
+        Ok(ctx.layer_db().cas().stored_size(content_hashes).await?)
+    }
+}
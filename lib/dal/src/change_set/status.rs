@@ -28,6 +28,20 @@ pub enum ChangeSetStatus {
     Rejected,
 }
 
+impl ChangeSetStatus {
+    /// Whether the change set was applied or abandoned, after which it is no longer worked on and
+    /// its snapshots may be pruned.
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Self::Abandoned | Self::Applied)
+    }
+
+    /// Whether the change set is waiting on an approval, during which it is locked for edits until
+    /// the request is withdrawn.
+    pub fn is_awaiting_approval(&self) -> bool {
+        matches!(self, Self::NeedsAbandonApproval | Self::NeedsApproval)
+    }
+}
+
 impl From<si_events::ChangeSetStatus> for ChangeSetStatus {
     fn from(value: si_events::ChangeSetStatus) -> Self {
         match value {
@@ -6,18 +6,19 @@ use petgraph::Direction::Outgoing;
 use serde::{Deserialize, Serialize};
 use si_pkg::KeyOrIndex;
 use socket::{ComponentInputSocket, ComponentOutputSocket};
-use std::collections::{hash_map, HashMap, HashSet, VecDeque};
+use std::collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
 use std::num::{ParseFloatError, ParseIntError};
 use std::sync::Arc;
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::TryLockError;
 
-use si_events::{ulid::Ulid, ContentHash};
+use si_events::{merkle_tree_hash::MerkleTreeHash, ulid::Ulid, ContentHash};
 
 use crate::action::prototype::{ActionKind, ActionPrototype, ActionPrototypeError};
 use crate::action::{Action, ActionError, ActionState};
 use crate::actor_view::ActorView;
+use crate::attribute::prototype::argument::static_value::StaticArgumentValue;
 use crate::attribute::prototype::argument::value_source::ValueSource;
 use crate::attribute::prototype::argument::{
     AttributePrototypeArgument, AttributePrototypeArgumentError, AttributePrototypeArgumentId,
@@ -34,7 +35,7 @@ use crate::diagram::{
 };
 use crate::func::argument::FuncArgumentError;
 use crate::history_event::HistoryEventMetadata;
-use crate::layer_db_types::{ComponentContent, ComponentContentV2};
+use crate::layer_db_types::{ComponentContent, ComponentContentV3};
 use crate::module::{Module, ModuleError};
 use crate::prop::{PropError, PropPath};
 use crate::qualification::QualificationError;
@@ -68,16 +69,22 @@ use crate::{
     WsEventError, WsEventResult, WsPayload,
 };
 
+pub mod bulk;
 pub mod code;
+pub mod connect_matching;
 pub mod debug;
 pub mod delete;
 pub mod diff;
+pub mod duplicate;
 pub mod frame;
 pub mod inferred_connection_graph;
+pub mod label;
 pub mod properties;
 pub mod qualification;
 pub mod resource;
 pub mod socket;
+pub mod socket_compatibility;
+pub mod suggestion;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -92,6 +99,8 @@ pub enum ComponentError {
     AttributePrototypeArgument(#[from] AttributePrototypeArgumentError),
     #[error("attribute value error: {0}")]
     AttributeValue(#[from] AttributeValueError),
+    #[error("bulk builder has no pending component at position {0}")]
+    BulkPendingComponentNotFound(usize),
     #[error("cannot clone attributes from a component with a different schema variant id")]
     CannotCloneFromDifferentVariants,
     #[error("change set error: {0}")]
@@ -142,6 +151,8 @@ pub enum ComponentError {
     InputSocketTooManyAttributeValues(InputSocketId),
     #[error("invalid component type update from {0} to {1}")]
     InvalidComponentTypeUpdate(ComponentType, ComponentType),
+    #[error("invalid label key: {0:?}")]
+    InvalidLabelKey(String),
     #[error("layer db error: {0}")]
     LayerDb(#[from] si_layer_cache::LayerDbError),
     #[error("component {0} missing attribute value for code")]
@@ -270,12 +281,15 @@ pub struct Component {
     #[serde(flatten)]
     timestamp: Timestamp,
     to_delete: bool,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
 }
 
-impl From<Component> for ComponentContentV2 {
+impl From<Component> for ComponentContentV3 {
     fn from(value: Component) -> Self {
         Self {
             timestamp: value.timestamp,
+            labels: value.labels,
         }
     }
 }
@@ -288,11 +302,12 @@ pub struct ControllingFuncData {
 }
 
 impl Component {
-    pub fn assemble(node_weight: &ComponentNodeWeight, content: ComponentContentV2) -> Self {
+    pub fn assemble(node_weight: &ComponentNodeWeight, content: ComponentContentV3) -> Self {
         Self {
             id: node_weight.id().into(),
             timestamp: content.timestamp,
             to_delete: node_weight.to_delete(),
+            labels: content.labels,
         }
     }
 
@@ -408,27 +423,50 @@ impl Component {
         schema_variant_id: SchemaVariantId,
         view_id: ViewId,
     ) -> ComponentResult<Self> {
-        let content = ComponentContentV2 {
+        let (component, dvu_roots) =
+            Self::new_deferring_dependent_values(ctx, name, schema_variant_id, view_id).await?;
+
+        let component_graph = DependentValueGraph::new(ctx, dvu_roots).await?;
+        let leaf_value_ids = component_graph.independent_values();
+        ctx.add_dependent_values_and_enqueue(leaf_value_ids).await?;
+
+        Ok(component)
+    }
+
+    /// Like [`Self::new`], but instead of calculating and enqueueing the dependent values of the
+    /// new [`Component`], returns them so that the caller can do it once for many
+    /// [`Components`](Component) (see [`BulkBuilder`](bulk::BulkBuilder)).
+    pub(crate) async fn new_deferring_dependent_values(
+        ctx: &DalContext,
+        name: impl Into<String>,
+        schema_variant_id: SchemaVariantId,
+        view_id: ViewId,
+    ) -> ComponentResult<(Self, Vec<DependentValueRoot>)> {
+        let content = ComponentContentV3 {
             timestamp: Timestamp::now(),
+            labels: BTreeMap::new(),
         };
 
         let (hash, _) = ctx.layer_db().cas().write(
-            Arc::new(ComponentContent::V2(content.clone()).into()),
+            Arc::new(ComponentContent::V3(content.clone()).into()),
             None,
             ctx.events_tenancy(),
             ctx.events_actor(),
         )?;
 
-        let component =
-            Self::new_with_content_address_and_no_geometry(ctx, name, schema_variant_id, hash)
-                .await?;
+        let (component, dvu_roots) = Self::new_with_content_address_deferring_dependent_values(
+            ctx,
+            name,
+            schema_variant_id,
+            hash,
+        )
+        .await?;
 
-        // Create geometry node
         Geometry::new_for_component(ctx, component.id, view_id)
             .await
             .map_err(|e| ComponentError::Diagram(Box::new(e)))?;
 
-        Ok(component)
+        Ok((component, dvu_roots))
     }
 
     /// Create new component node but retain existing content address
@@ -440,6 +478,29 @@ impl Component {
         schema_variant_id: SchemaVariantId,
         content_address: ContentHash,
     ) -> ComponentResult<Self> {
+        let (component, dvu_roots) = Self::new_with_content_address_deferring_dependent_values(
+            ctx,
+            name,
+            schema_variant_id,
+            content_address,
+        )
+        .await?;
+
+        let component_graph = DependentValueGraph::new(ctx, dvu_roots).await?;
+        let leaf_value_ids = component_graph.independent_values();
+        ctx.add_dependent_values_and_enqueue(leaf_value_ids).await?;
+
+        Ok(component)
+    }
+
+    /// Creates the [`Component`] node, its attribute values and its create actions, returning the
+    /// roots whose dependent values still need to be calculated.
+    async fn new_with_content_address_deferring_dependent_values(
+        ctx: &DalContext,
+        name: impl Into<String>,
+        schema_variant_id: SchemaVariantId,
+        content_address: ContentHash,
+    ) -> ComponentResult<(Self, Vec<DependentValueRoot>)> {
         let name: String = name.into();
 
         let workspace_snapshot = ctx.workspace_snapshot()?;
@@ -571,10 +632,6 @@ impl Component {
             component.set_type(ctx, sv_type).await?;
         }
 
-        let component_graph = DependentValueGraph::new(ctx, dvu_roots).await?;
-        let leaf_value_ids = component_graph.independent_values();
-        ctx.add_dependent_values_and_enqueue(leaf_value_ids).await?;
-
         // Find all create action prototypes for the variant and create actions for them.
         for prototype_id in SchemaVariant::find_action_prototypes_by_kind(
             ctx,
@@ -588,7 +645,7 @@ impl Component {
                 .map_err(|err| ComponentError::Action(Box::new(err)))?;
         }
 
-        Ok(component)
+        Ok((component, dvu_roots))
     }
 
     /// Attempts to merge the values other_component into this component, if
@@ -944,7 +1001,42 @@ impl Component {
                             }
                         }
                     }
-                    ValueSource::Secret(_) | ValueSource::StaticArgumentValue(_) => {
+                    ValueSource::StaticArgumentValue(static_value_id) => {
+                        let static_value =
+                            StaticArgumentValue::get_by_id(ctx, static_value_id).await?;
+                        match static_value.expression()? {
+                            // Expressions reference props by path, so they carry over as long
+                            // as the props they reference still exist in self
+                            Some(expression) => {
+                                let mut referenced_prop_ids = vec![];
+                                for reference in expression.references() {
+                                    match self_props.get(&reference.prop_path().as_owned_parts()) {
+                                        Some(self_prop_id) => {
+                                            if !referenced_prop_ids.contains(self_prop_id) {
+                                                referenced_prop_ids.push(*self_prop_id);
+                                            }
+                                        }
+                                        None => {
+                                            return Ok(());
+                                        }
+                                    }
+                                }
+
+                                let new_static_value = StaticArgumentValue::new_expression(
+                                    ctx,
+                                    &expression,
+                                    &referenced_prop_ids,
+                                )
+                                .await?;
+                                new_value_sources.push((
+                                    func_arg.id,
+                                    ValueSource::StaticArgumentValue(new_static_value.id()),
+                                ));
+                            }
+                            None => new_value_sources.push((func_arg.id, source)),
+                        }
+                    }
+                    ValueSource::Secret(_) => {
                         // Should we determine if this secret is still compatible?
                         new_value_sources.push((func_arg.id, source));
                     }
@@ -1274,7 +1366,7 @@ impl Component {
     async fn try_get_node_weight_and_content(
         ctx: &DalContext,
         component_id: ComponentId,
-    ) -> ComponentResult<Option<(ComponentNodeWeight, ComponentContentV2)>> {
+    ) -> ComponentResult<Option<(ComponentNodeWeight, ComponentContentV3)>> {
         if let Some((component_node_weight, content_hash)) =
             Self::try_get_node_weight_and_content_hash(ctx, component_id).await?
         {
@@ -1296,7 +1388,7 @@ impl Component {
     async fn get_node_weight_and_content(
         ctx: &DalContext,
         component_id: ComponentId,
-    ) -> ComponentResult<(ComponentNodeWeight, ComponentContentV2)> {
+    ) -> ComponentResult<(ComponentNodeWeight, ComponentContentV3)> {
         Self::try_get_node_weight_and_content(ctx, component_id)
             .await?
             .ok_or(ComponentError::NotFound(component_id))
@@ -1456,6 +1548,30 @@ impl Component {
         Ok(None)
     }
 
+    /// Returns the version of the [`Component`], derived from the merkle tree hash of its node in
+    /// the snapshot. The hash covers the entire subgraph of the [`Component`] (including all of
+    /// its [`AttributeValues`](AttributeValue)), so any change to it produces a new version.
+    ///
+    /// _Note:_ merkle tree hashes are only recalculated when the snapshot is written, so changes
+    /// made with the current [`DalContext`] are not reflected until they have been committed.
+    pub async fn version_by_id(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<MerkleTreeHash> {
+        let workspace_snapshot = ctx.workspace_snapshot()?;
+        let Some(node_index) = workspace_snapshot
+            .get_node_index_by_id_opt(component_id)
+            .await
+        else {
+            return Err(ComponentError::NotFound(component_id));
+        };
+
+        Ok(workspace_snapshot
+            .get_node_weight(node_index)
+            .await?
+            .merkle_tree_hash())
+    }
+
     pub async fn geometry(&self, ctx: &DalContext, view_id: ViewId) -> ComponentResult<Geometry> {
         Geometry::get_by_component_and_view(ctx, self.id, view_id)
             .await
@@ -2437,7 +2553,7 @@ impl Component {
         let original_component = self.clone();
         let mut component = self;
 
-        let before = ComponentContentV2::from(component.clone());
+        let before = ComponentContentV3::from(component.clone());
         lambda(&mut component)?;
 
         // The `to_delete` lives on the node itself, not in the content, so we need to be a little
@@ -2459,10 +2575,10 @@ impl Component {
                 .await?;
         }
 
-        let updated = ComponentContentV2::from(component.clone());
+        let updated = ComponentContentV3::from(component.clone());
         if updated != before {
             let (hash, _) = ctx.layer_db().cas().write(
-                Arc::new(ComponentContent::V2(updated.clone()).into()),
+                Arc::new(ComponentContent::V3(updated.clone()).into()),
                 None,
                 ctx.events_tenancy(),
                 ctx.events_actor(),
@@ -3624,6 +3740,7 @@ impl Component {
             can_be_upgraded,
             from_base_change_set: false,
             view_data: geometry,
+            labels: self.labels.clone(),
         })
     }
 
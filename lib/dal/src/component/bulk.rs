@@ -0,0 +1,289 @@
+//! This module contains [`BulkBuilder`], which collects [`Component`] creations, attribute sets
+//! and connections and applies them all at once.
+//!
+//! Every change made through a [`DalContext`] lands in its snapshot and is only sent to the
+//! rebaser on commit, so applying a [`BulkBuilder`] and committing once results in one rebase
+//! request, regardless of how many [`Components`](Component) were scaffolded (e.g. by a
+//! management func or an import). On top of that, the work which [`Component::new`] and
+//! [`Component::connect`] would repeat for every single change is done once per batch: the
+//! dependent values of all created [`Components`](Component) are calculated in one pass, and
+//! schema variants and sockets are looked up once per name.
+
+use std::collections::HashMap;
+
+use telemetry::prelude::*;
+
+use crate::attribute::value::DependentValueGraph;
+use crate::diagram::view::{View, ViewId};
+use crate::{
+    AttributeValue, Component, ComponentError, ComponentId, DalContext, InputSocket, InputSocketId,
+    OutputSocket, OutputSocketId, SchemaVariantId,
+};
+
+use super::ComponentResult;
+
+/// Refers to a [`Component`] in a [`BulkBuilder`], either one that the builder will create or one
+/// that already exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BulkComponentRef {
+    Existing(ComponentId),
+    /// The position of the creation within the builder.
+    Pending(usize),
+}
+
+impl From<ComponentId> for BulkComponentRef {
+    fn from(value: ComponentId) -> Self {
+        Self::Existing(value)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct BulkComponent {
+    name: String,
+    schema_variant_id: SchemaVariantId,
+    view_id: Option<ViewId>,
+}
+
+#[derive(Clone, Debug)]
+struct BulkAttribute {
+    component: BulkComponentRef,
+    prop_path: Vec<String>,
+    value: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Debug)]
+struct BulkConnection {
+    source: BulkComponentRef,
+    output_socket_name: String,
+    destination: BulkComponentRef,
+    input_socket_name: String,
+}
+
+/// Collects changes to apply to the graph in one go. Changes are applied in the following order,
+/// regardless of the order they were added in: creations, attribute sets and then connections.
+#[derive(Clone, Debug, Default)]
+pub struct BulkBuilder {
+    components: Vec<BulkComponent>,
+    attributes: Vec<BulkAttribute>,
+    connections: Vec<BulkConnection>,
+}
+
+impl BulkBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty() && self.attributes.is_empty() && self.connections.is_empty()
+    }
+
+    /// Add a [`Component`] to create. Without a [`ViewId`], the [`Component`] is created in the
+    /// default [`View`].
+    pub fn create_component(
+        &mut self,
+        name: impl Into<String>,
+        schema_variant_id: SchemaVariantId,
+        view_id: Option<ViewId>,
+    ) -> BulkComponentRef {
+        self.components.push(BulkComponent {
+            name: name.into(),
+            schema_variant_id,
+            view_id,
+        });
+        BulkComponentRef::Pending(self.components.len() - 1)
+    }
+
+    /// Set the value of the attribute at the prop path (e.g. `["root", "domain", "region"]`).
+    /// A value of `None` unsets the attribute.
+    pub fn set_attribute(
+        &mut self,
+        component: impl Into<BulkComponentRef>,
+        prop_path: &[&str],
+        value: Option<serde_json::Value>,
+    ) -> &mut Self {
+        self.attributes.push(BulkAttribute {
+            component: component.into(),
+            prop_path: prop_path.iter().map(|part| (*part).to_owned()).collect(),
+            value,
+        });
+        self
+    }
+
+    /// Connect the named output socket of the source to the named input socket of the
+    /// destination.
+    pub fn connect(
+        &mut self,
+        source: impl Into<BulkComponentRef>,
+        output_socket_name: impl Into<String>,
+        destination: impl Into<BulkComponentRef>,
+        input_socket_name: impl Into<String>,
+    ) -> &mut Self {
+        self.connections.push(BulkConnection {
+            source: source.into(),
+            output_socket_name: output_socket_name.into(),
+            destination: destination.into(),
+            input_socket_name: input_socket_name.into(),
+        });
+        self
+    }
+
+    /// Apply every change to the snapshot of the provided [`DalContext`], without committing.
+    ///
+    /// Returns the ids of the created [`Components`](Component), in the order they were added.
+    #[instrument(
+        name = "component.bulk.apply",
+        level = "info",
+        skip_all,
+        fields(
+            si.bulk.components.count = self.components.len(),
+            si.bulk.attributes.count = self.attributes.len(),
+            si.bulk.connections.count = self.connections.len(),
+        )
+    )]
+    pub async fn apply(self, ctx: &DalContext) -> ComponentResult<Vec<ComponentId>> {
+        // Fail before mutating anything if a reference cannot be resolved
+        let references = self
+            .attributes
+            .iter()
+            .map(|attribute| attribute.component)
+            .chain(
+                self.connections
+                    .iter()
+                    .flat_map(|connection| [connection.source, connection.destination]),
+            );
+        for reference in references {
+            if let BulkComponentRef::Pending(position) = reference {
+                if position >= self.components.len() {
+                    return Err(ComponentError::BulkPendingComponentNotFound(position));
+                }
+            }
+        }
+
+        let mut default_view_id = None;
+        let mut created = Vec::with_capacity(self.components.len());
+        let mut schema_variant_ids = HashMap::new();
+        let mut dvu_roots = Vec::new();
+        for component in self.components {
+            let view_id = match component.view_id.or(default_view_id) {
+                Some(view_id) => view_id,
+                None => {
+                    let view_id = View::get_id_for_default(ctx)
+                        .await
+                        .map_err(|e| ComponentError::Diagram(Box::new(e)))?;
+                    default_view_id = Some(view_id);
+                    view_id
+                }
+            };
+            let (new_component, roots) = Component::new_deferring_dependent_values(
+                ctx,
+                component.name,
+                component.schema_variant_id,
+                view_id,
+            )
+            .await?;
+            schema_variant_ids.insert(new_component.id(), component.schema_variant_id);
+            created.push(new_component.id());
+            dvu_roots.extend(roots);
+        }
+
+        // Calculate the dependent values of every new component at once, instead of once per
+        // component
+        if !dvu_roots.is_empty() {
+            let dependent_value_graph = DependentValueGraph::new(ctx, dvu_roots).await?;
+            ctx.add_dependent_values_and_enqueue(dependent_value_graph.independent_values())
+                .await?;
+        }
+
+        let resolve = |reference: BulkComponentRef| match reference {
+            BulkComponentRef::Existing(component_id) => Ok(component_id),
+            BulkComponentRef::Pending(position) => created
+                .get(position)
+                .copied()
+                .ok_or(ComponentError::BulkPendingComponentNotFound(position)),
+        };
+
+        for attribute in self.attributes {
+            let component_id = resolve(attribute.component)?;
+            let prop_path: Vec<&str> = attribute.prop_path.iter().map(String::as_str).collect();
+            let attribute_value_id =
+                Component::attribute_value_for_prop_by_id(ctx, component_id, &prop_path).await?;
+            AttributeValue::update(ctx, attribute_value_id, attribute.value).await?;
+        }
+
+        let mut output_socket_ids: HashMap<(SchemaVariantId, String), OutputSocketId> =
+            HashMap::new();
+        let mut input_socket_ids: HashMap<(SchemaVariantId, String), InputSocketId> =
+            HashMap::new();
+        for connection in self.connections {
+            let source_id = resolve(connection.source)?;
+            let destination_id = resolve(connection.destination)?;
+
+            let source_variant_id = match schema_variant_ids.get(&source_id) {
+                Some(schema_variant_id) => *schema_variant_id,
+                None => {
+                    let schema_variant_id = Component::schema_variant_id(ctx, source_id).await?;
+                    schema_variant_ids.insert(source_id, schema_variant_id);
+                    schema_variant_id
+                }
+            };
+            let destination_variant_id = match schema_variant_ids.get(&destination_id) {
+                Some(schema_variant_id) => *schema_variant_id,
+                None => {
+                    let schema_variant_id =
+                        Component::schema_variant_id(ctx, destination_id).await?;
+                    schema_variant_ids.insert(destination_id, schema_variant_id);
+                    schema_variant_id
+                }
+            };
+
+            let output_socket_key = (source_variant_id, connection.output_socket_name);
+            let output_socket_id = match output_socket_ids.get(&output_socket_key) {
+                Some(output_socket_id) => *output_socket_id,
+                None => {
+                    let output_socket_id = OutputSocket::find_with_name_or_error(
+                        ctx,
+                        &output_socket_key.1,
+                        source_variant_id,
+                    )
+                    .await?
+                    .id();
+                    output_socket_ids.insert(output_socket_key, output_socket_id);
+                    output_socket_id
+                }
+            };
+            let input_socket_key = (destination_variant_id, connection.input_socket_name);
+            let input_socket_id = match input_socket_ids.get(&input_socket_key) {
+                Some(input_socket_id) => *input_socket_id,
+                None => {
+                    let input_socket_id = InputSocket::find_with_name_or_error(
+                        ctx,
+                        &input_socket_key.1,
+                        destination_variant_id,
+                    )
+                    .await?
+                    .id();
+                    input_socket_ids.insert(input_socket_key, input_socket_id);
+                    input_socket_id
+                }
+            };
+
+            Component::connect(
+                ctx,
+                source_id,
+                output_socket_id,
+                destination_id,
+                input_socket_id,
+            )
+            .await?;
+        }
+
+        Ok(created)
+    }
+
+    /// Apply every change and commit, resulting in a single rebase request.
+    pub async fn apply_and_commit(self, ctx: &DalContext) -> ComponentResult<Vec<ComponentId>> {
+        let created = self.apply(ctx).await?;
+        ctx.commit().await?;
+        Ok(created)
+    }
+}
@@ -0,0 +1,184 @@
+//! This module contains the ability to connect many source [`Components`](Component) to a single
+//! destination [`Component`] in one operation, pairing up their sockets by connection annotation.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::attribute::prototype::argument::AttributePrototypeArgumentId;
+use crate::{
+    Component, ComponentId, DalContext, InputSocket, InputSocketId, OutputSocket, OutputSocketId,
+    SocketArity,
+};
+
+use super::ComponentResult;
+
+/// A connection created by [`Component::connect_matching_sockets`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedConnection {
+    pub source_component_id: ComponentId,
+    pub output_socket_id: OutputSocketId,
+    pub input_socket_id: InputSocketId,
+    pub attribute_prototype_argument_id: AttributePrototypeArgumentId,
+}
+
+/// Why [`Component::connect_matching_sockets`] did not connect a source [`Component`], or one of
+/// its compatible socket pairs.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// The pair is already connected.
+    AlreadyConnected,
+    /// The input socket only accepts one connection and already has one.
+    InputSocketOccupied,
+    /// None of the source's output sockets fit any of the destination's input sockets.
+    NoCompatibleSockets,
+    /// The source is the destination.
+    SameComponent,
+}
+
+/// A source [`Component`] (or one of its socket pairs) that was not connected. The socket ids are
+/// only present when the skip applies to a specific pair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedConnection {
+    pub source_component_id: ComponentId,
+    pub output_socket_id: Option<OutputSocketId>,
+    pub input_socket_id: Option<InputSocketId>,
+    pub reason: SkipReason,
+}
+
+/// The outcome of [`Component::connect_matching_sockets`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectMatchingReport {
+    pub created: Vec<MatchedConnection>,
+    pub skipped: Vec<SkippedConnection>,
+}
+
+impl Component {
+    /// Connect every compatible output socket of each source [`Component`] to the input sockets
+    /// of the destination [`Component`] they fit, as determined by
+    /// [`OutputSocket::fits_input`].
+    ///
+    /// Existing connections are never replaced: a pair is skipped if it is already connected or
+    /// if the input socket has an arity of [`SocketArity::One`] and is already taken, either
+    /// beforehand or by an earlier source in the same operation. Sources are handled in the
+    /// order provided and duplicates are ignored.
+    #[instrument(
+        name = "component.connect_matching_sockets",
+        level = "info",
+        skip(ctx, source_component_ids),
+        fields(si.connect_matching.sources.count = source_component_ids.len())
+    )]
+    pub async fn connect_matching_sockets(
+        ctx: &DalContext,
+        source_component_ids: &[ComponentId],
+        destination_component_id: ComponentId,
+    ) -> ComponentResult<ConnectMatchingReport> {
+        let destination_schema_variant_id =
+            Self::schema_variant_id(ctx, destination_component_id).await?;
+        let input_sockets = InputSocket::list(ctx, destination_schema_variant_id).await?;
+
+        let mut connected = HashSet::new();
+        let mut occupied_input_socket_ids = HashSet::new();
+        for connection in Self::incoming_connections_for_id(ctx, destination_component_id).await? {
+            connected.insert((
+                connection.from_component_id,
+                connection.from_output_socket_id,
+                connection.to_input_socket_id,
+            ));
+            occupied_input_socket_ids.insert(connection.to_input_socket_id);
+        }
+
+        let mut report = ConnectMatchingReport::default();
+        let mut seen = HashSet::new();
+        for &source_component_id in source_component_ids {
+            if !seen.insert(source_component_id) {
+                continue;
+            }
+            let skip_component = |reason| SkippedConnection {
+                source_component_id,
+                output_socket_id: None,
+                input_socket_id: None,
+                reason,
+            };
+            if source_component_id == destination_component_id {
+                report
+                    .skipped
+                    .push(skip_component(SkipReason::SameComponent));
+                continue;
+            }
+
+            let source_schema_variant_id =
+                Self::schema_variant_id(ctx, source_component_id).await?;
+            let output_sockets = OutputSocket::list(ctx, source_schema_variant_id).await?;
+
+            let mut found_compatible = false;
+            for output_socket in &output_sockets {
+                for input_socket in &input_sockets {
+                    if !output_socket.fits_input(input_socket) {
+                        continue;
+                    }
+                    found_compatible = true;
+
+                    let skip_pair = |reason| SkippedConnection {
+                        source_component_id,
+                        output_socket_id: Some(output_socket.id()),
+                        input_socket_id: Some(input_socket.id()),
+                        reason,
+                    };
+                    if connected.contains(&(
+                        source_component_id,
+                        output_socket.id(),
+                        input_socket.id(),
+                    )) {
+                        report.skipped.push(skip_pair(SkipReason::AlreadyConnected));
+                        continue;
+                    }
+                    if input_socket.arity() == SocketArity::One
+                        && occupied_input_socket_ids.contains(&input_socket.id())
+                    {
+                        report
+                            .skipped
+                            .push(skip_pair(SkipReason::InputSocketOccupied));
+                        continue;
+                    }
+
+                    match Self::connect(
+                        ctx,
+                        source_component_id,
+                        output_socket.id(),
+                        destination_component_id,
+                        input_socket.id(),
+                    )
+                    .await?
+                    {
+                        Some(attribute_prototype_argument_id) => {
+                            report.created.push(MatchedConnection {
+                                source_component_id,
+                                output_socket_id: output_socket.id(),
+                                input_socket_id: input_socket.id(),
+                                attribute_prototype_argument_id,
+                            });
+                        }
+                        None => report.skipped.push(skip_pair(SkipReason::AlreadyConnected)),
+                    }
+                    connected.insert((source_component_id, output_socket.id(), input_socket.id()));
+                    occupied_input_socket_ids.insert(input_socket.id());
+                }
+            }
+
+            if !found_compatible {
+                report
+                    .skipped
+                    .push(skip_component(SkipReason::NoCompatibleSockets));
+            }
+        }
+
+        Ok(report)
+    }
+}
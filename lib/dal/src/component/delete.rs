@@ -1,11 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
+use serde::{Deserialize, Serialize};
 use si_events::audit_log::AuditLogKind;
 use si_id::ComponentId;
+use telemetry::prelude::*;
 
-use crate::{change_status::ChangeStatus, diagram::SummaryDiagramEdge, DalContext, WsEvent};
+use crate::{
+    change_status::ChangeStatus, diagram::SummaryDiagramEdge, AttributeValueId, DalContext,
+    InputSocketId, OutputSocketId, WsEvent,
+};
 
-use super::{Component, ComponentResult};
+use super::{socket::ComponentInputSocket, Component, ComponentResult, InferredConnection};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ComponentDeletionStatus {
@@ -158,3 +163,133 @@ async fn delete_component(
 
     Ok(status)
 }
+
+/// What deleting a [`Component`] would break, so that the blast radius can be confirmed before
+/// the delete goes ahead. Based on the connections as they are now.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDeletionImpact {
+    pub component_id: ComponentId,
+    /// The components consuming the component's output sockets, through edges or inferred
+    /// connections.
+    pub affected_component_ids: Vec<ComponentId>,
+    /// Every edge and inferred connection to or from the component.
+    pub edges: Vec<ComponentDeletionImpactEdge>,
+    /// The input socket values of affected components which have no source other than the
+    /// component, and so would become unset along with everything computed from them.
+    pub unset_attribute_value_ids: Vec<AttributeValueId>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDeletionImpactEdge {
+    pub from_component_id: ComponentId,
+    pub from_output_socket_id: OutputSocketId,
+    pub to_component_id: ComponentId,
+    pub to_input_socket_id: InputSocketId,
+    pub inferred: bool,
+}
+
+impl Component {
+    /// Finds what deleting the component would affect, without deleting it.
+    #[instrument(level = "info", skip(ctx))]
+    pub async fn deletion_impact(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<ComponentDeletionImpact> {
+        let component = Component::get_by_id(ctx, component_id).await?;
+
+        let mut edges = vec![];
+        let mut downstream_sockets = BTreeSet::new();
+        for incoming in component.incoming_connections(ctx).await? {
+            edges.push(ComponentDeletionImpactEdge {
+                from_component_id: incoming.from_component_id,
+                from_output_socket_id: incoming.from_output_socket_id,
+                to_component_id: incoming.to_component_id,
+                to_input_socket_id: incoming.to_input_socket_id,
+                inferred: false,
+            });
+        }
+        for incoming in component.inferred_incoming_connections(ctx).await? {
+            edges.push(ComponentDeletionImpactEdge::from_inferred(incoming));
+        }
+        for outgoing in component.outgoing_connections(ctx).await? {
+            downstream_sockets.insert((outgoing.to_component_id, outgoing.to_input_socket_id));
+            edges.push(ComponentDeletionImpactEdge {
+                from_component_id: outgoing.from_component_id,
+                from_output_socket_id: outgoing.from_output_socket_id,
+                to_component_id: outgoing.to_component_id,
+                to_input_socket_id: outgoing.to_input_socket_id,
+                inferred: false,
+            });
+        }
+        for outgoing in component.inferred_outgoing_connections(ctx).await? {
+            downstream_sockets.insert((outgoing.to_component_id, outgoing.to_input_socket_id));
+            edges.push(ComponentDeletionImpactEdge::from_inferred(outgoing));
+        }
+
+        let mut affected_component_ids = BTreeSet::new();
+        let mut unset_attribute_value_ids = vec![];
+        for (to_component_id, to_input_socket_id) in downstream_sockets {
+            if to_component_id == component_id {
+                continue;
+            }
+            affected_component_ids.insert(to_component_id);
+
+            let Some(input_socket) =
+                ComponentInputSocket::get_by_ids(ctx, to_component_id, to_input_socket_id).await?
+            else {
+                continue;
+            };
+            if !Self::has_other_source(ctx, input_socket, component_id).await? {
+                unset_attribute_value_ids.push(input_socket.attribute_value_id);
+            }
+        }
+
+        Ok(ComponentDeletionImpact {
+            component_id,
+            affected_component_ids: affected_component_ids.into_iter().collect(),
+            edges,
+            unset_attribute_value_ids,
+        })
+    }
+
+    /// Whether the input socket has a source, explicit or inferred, other than the given
+    /// component.
+    async fn has_other_source(
+        ctx: &DalContext,
+        input_socket: ComponentInputSocket,
+        component_id: ComponentId,
+    ) -> ComponentResult<bool> {
+        let has_other_explicit_source =
+            Component::incoming_connections_for_id(ctx, input_socket.component_id)
+                .await?
+                .iter()
+                .any(|incoming| {
+                    incoming.to_input_socket_id == input_socket.input_socket_id
+                        && incoming.from_component_id != component_id
+                });
+        if has_other_explicit_source {
+            return Ok(true);
+        }
+
+        Ok(
+            ComponentInputSocket::find_inferred_connections(ctx, input_socket)
+                .await?
+                .iter()
+                .any(|output_socket| output_socket.component_id != component_id),
+        )
+    }
+}
+
+impl ComponentDeletionImpactEdge {
+    fn from_inferred(connection: InferredConnection) -> Self {
+        Self {
+            from_component_id: connection.from_component_id,
+            from_output_socket_id: connection.from_output_socket_id,
+            to_component_id: connection.to_component_id,
+            to_input_socket_id: connection.to_input_socket_id,
+            inferred: true,
+        }
+    }
+}
@@ -1,11 +1,15 @@
-//! This module contains [`ComponentDiff`].
+//! This module contains [`ComponentDiff`] and [`ComponentStructuredDiff`].
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 
 use crate::code_view::{CodeLanguage, CodeView};
 use crate::component::properties::ComponentProperties;
-use crate::component::ComponentResult;
-use crate::{Component, ComponentId, DalContext};
+use crate::component::{ComponentError, ComponentResult};
+use crate::{
+    Component, ComponentId, DalContext, InputSocket, InputSocketId, OutputSocket, OutputSocketId,
+};
 
 //
 const NEWLINE: &str = "\n";
@@ -26,7 +30,274 @@ pub struct ComponentDiff {
     pub diffs: Vec<CodeView>,
 }
 
+/// How a single value, code generation output or connection differs between _head_ and the
+/// current change set.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffKind {
+    Added,
+    Changed,
+    Removed,
+}
+
+/// How a [`Component`] as a whole differs between _head_ and the current change set.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ComponentDiffStatus {
+    Added,
+    Modified,
+    Removed,
+    Unchanged,
+}
+
+/// A difference in the value at a prop path, i.e. `/domain/region`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PropDiff {
+    pub path: String,
+    pub kind: DiffKind,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// A difference in the generated code of a code generation function.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeGenerationDiff {
+    pub name: String,
+    pub kind: DiffKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// An incoming connection which only exists on one side of the diff.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionDiff {
+    pub kind: DiffKind,
+    pub from_component_id: ComponentId,
+    pub from_component_name: String,
+    pub from_output_socket_id: OutputSocketId,
+    pub from_output_socket_name: String,
+    pub to_input_socket_id: InputSocketId,
+    pub to_input_socket_name: String,
+}
+
+/// A prop-by-prop diff of a [`Component`] between _head_ and the current change set. Generated
+/// by [`Component::get_structured_diff()`].
+///
+/// Only "/root/si", "/root/domain" and the generated code are compared. On _head_ the diff is
+/// always empty.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentStructuredDiff {
+    pub component_id: ComponentId,
+    pub status: ComponentDiffStatus,
+    pub props: Vec<PropDiff>,
+    pub code_generation: Vec<CodeGenerationDiff>,
+    pub connections: Vec<ConnectionDiff>,
+}
+
+/// The parts of a [`Component`] in one change set which are compared by
+/// [`Component::get_structured_diff()`].
+#[derive(Debug, Default)]
+struct DiffSide {
+    props: BTreeMap<String, Value>,
+    code: BTreeMap<String, String>,
+    connections: BTreeMap<(ComponentId, OutputSocketId, InputSocketId), ConnectionDiff>,
+}
+
+impl DiffSide {
+    async fn load(ctx: &DalContext, component_id: ComponentId) -> ComponentResult<Option<Self>> {
+        let Some(component) = Component::try_get_by_id(ctx, component_id).await? else {
+            return Ok(None);
+        };
+
+        let mut side = Self::default();
+
+        if let Some(view) = component.view(ctx).await? {
+            let properties = ComponentProperties::try_from(view)?;
+            flatten_into("/si", properties.si, &mut side.props);
+            if let Some(domain) = properties.domain {
+                flatten_into("/domain", domain, &mut side.props);
+            }
+            if let Some(Value::Object(code)) = properties.code {
+                for (name, output) in code {
+                    if let Some(generated) = output.get("code").and_then(Value::as_str) {
+                        side.code.insert(name, generated.to_owned());
+                    }
+                }
+            }
+        }
+
+        for connection in component.incoming_connections(ctx).await? {
+            let output_socket =
+                OutputSocket::get_by_id(ctx, connection.from_output_socket_id).await?;
+            let input_socket = InputSocket::get_by_id(ctx, connection.to_input_socket_id).await?;
+            side.connections.insert(
+                (
+                    connection.from_component_id,
+                    connection.from_output_socket_id,
+                    connection.to_input_socket_id,
+                ),
+                ConnectionDiff {
+                    // Set once we know which side the connection is missing from
+                    kind: DiffKind::Added,
+                    from_component_id: connection.from_component_id,
+                    from_component_name: Component::name_by_id(ctx, connection.from_component_id)
+                        .await?,
+                    from_output_socket_id: connection.from_output_socket_id,
+                    from_output_socket_name: output_socket.name().to_owned(),
+                    to_input_socket_id: connection.to_input_socket_id,
+                    to_input_socket_name: input_socket.name().to_owned(),
+                },
+            );
+        }
+
+        Ok(Some(side))
+    }
+}
+
+/// Flattens `value` into leaf values keyed by their path below `path`. Empty objects and arrays
+/// are kept as leaves so that adding or clearing them shows up in the diff.
+fn flatten_into(path: &str, value: Value, leaves: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                flatten_into(&format!("{path}/{key}"), value, leaves);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, value) in items.into_iter().enumerate() {
+                flatten_into(&format!("{path}/{index}"), value, leaves);
+            }
+        }
+        value => {
+            leaves.insert(path.to_owned(), value);
+        }
+    }
+}
+
+/// Compares two maps key by key, calling `diff` for every key whose values differ.
+fn diff_maps<K, V, T>(
+    before: &BTreeMap<K, V>,
+    after: &BTreeMap<K, V>,
+    mut diff: impl FnMut(&K, DiffKind, Option<&V>, Option<&V>) -> T,
+) -> Vec<T>
+where
+    K: Ord,
+    V: PartialEq,
+{
+    let mut diffs = Vec::new();
+    for (key, before_value) in before {
+        match after.get(key) {
+            None => diffs.push(diff(key, DiffKind::Removed, Some(before_value), None)),
+            Some(after_value) if after_value != before_value => diffs.push(diff(
+                key,
+                DiffKind::Changed,
+                Some(before_value),
+                Some(after_value),
+            )),
+            Some(_) => {}
+        }
+    }
+    for (key, after_value) in after {
+        if !before.contains_key(key) {
+            diffs.push(diff(key, DiffKind::Added, None, Some(after_value)));
+        }
+    }
+    diffs
+}
+
 impl Component {
+    /// Computes a [`ComponentStructuredDiff`] of the [`Component`] between _head_ and the current
+    /// change set.
+    pub async fn get_structured_diff(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<ComponentStructuredDiff> {
+        if ctx.change_set_id() == ctx.get_workspace_default_change_set_id().await? {
+            // We are on HEAD, so there is nothing to compare against
+            return Ok(ComponentStructuredDiff {
+                component_id,
+                status: ComponentDiffStatus::Unchanged,
+                props: vec![],
+                code_generation: vec![],
+                connections: vec![],
+            });
+        }
+
+        let current = DiffSide::load(ctx, component_id).await?;
+        let head_ctx = ctx.clone_with_head().await?;
+        let head = DiffSide::load(&head_ctx, component_id).await?;
+
+        let status = match (&head, &current) {
+            (None, None) => return Err(ComponentError::NotFound(component_id)),
+            (None, Some(_)) => ComponentDiffStatus::Added,
+            (Some(_), None) => ComponentDiffStatus::Removed,
+            (Some(_), Some(_)) => ComponentDiffStatus::Unchanged,
+        };
+        let head = head.unwrap_or_default();
+        let current = current.unwrap_or_default();
+
+        let props = diff_maps(&head.props, &current.props, |path, kind, before, after| {
+            PropDiff {
+                path: path.clone(),
+                kind,
+                before: before.cloned(),
+                after: after.cloned(),
+            }
+        });
+        let code_generation = diff_maps(&head.code, &current.code, |name, kind, before, after| {
+            CodeGenerationDiff {
+                name: name.clone(),
+                kind,
+                before: before.cloned(),
+                after: after.cloned(),
+            }
+        });
+        // Connections are compared by their endpoints alone, so that renaming the component on
+        // the other end does not show up as a changed connection
+        let mut connections: Vec<ConnectionDiff> = head
+            .connections
+            .iter()
+            .filter(|(key, _)| !current.connections.contains_key(key))
+            .map(|(_, connection)| ConnectionDiff {
+                kind: DiffKind::Removed,
+                ..connection.clone()
+            })
+            .collect();
+        connections.extend(
+            current
+                .connections
+                .iter()
+                .filter(|(key, _)| !head.connections.contains_key(key))
+                .map(|(_, connection)| ConnectionDiff {
+                    kind: DiffKind::Added,
+                    ..connection.clone()
+                }),
+        );
+
+        let status = match status {
+            ComponentDiffStatus::Unchanged
+                if !props.is_empty() || !code_generation.is_empty() || !connections.is_empty() =>
+            {
+                ComponentDiffStatus::Modified
+            }
+            status => status,
+        };
+
+        Ok(ComponentStructuredDiff {
+            component_id,
+            status,
+            props,
+            code_generation,
+            connections,
+        })
+    }
+
     pub async fn get_diff(
         ctx: &DalContext,
         component_id: ComponentId,
@@ -0,0 +1,147 @@
+//! This module contains the ability to duplicate an entire tree of [`Components`](Component),
+//! rooted at a given [`Component`], in a single operation.
+
+use std::collections::HashMap;
+
+use telemetry::prelude::*;
+
+use crate::component::frame::Frame;
+use crate::diagram::geometry::Geometry;
+use crate::diagram::view::View;
+use crate::{Component, ComponentError, ComponentId, DalContext};
+
+use super::ComponentResult;
+
+impl Component {
+    /// Duplicate the [`Component`] corresponding to the provided [`ComponentId`] alongside all of
+    /// its descendants (i.e. every [`Component`] contained within it if it is a frame).
+    ///
+    /// Each duplicate receives the attributes of its original (including component-specific
+    /// overrides), the same geometry in every [`View`] the original is in and a name equal to the
+    /// original name with the provided suffix appended. Frame parentage, management edges and
+    /// connections between members of the tree are remapped onto the duplicates. Connections to
+    /// or from [`Components`](Component) outside of the tree are not copied.
+    ///
+    /// The duplicated root is placed in the same parent as the original root, if any.
+    ///
+    /// Returns a map of original [`ComponentIds`](ComponentId) to their duplicates.
+    #[instrument(level = "info", skip(ctx, name_suffix))]
+    pub async fn duplicate_subtree(
+        ctx: &DalContext,
+        root_component_id: ComponentId,
+        name_suffix: impl AsRef<str>,
+    ) -> ComponentResult<HashMap<ComponentId, ComponentId>> {
+        let name_suffix = name_suffix.as_ref();
+
+        // Descendants are gathered breadth first, so parents are always duplicated before their
+        // children.
+        let mut originals = vec![root_component_id];
+        originals.extend(Self::get_all_descendants_for_id(ctx, root_component_id).await?);
+
+        let mut duplicates_by_original = HashMap::with_capacity(originals.len());
+        for &original_id in &originals {
+            let duplicate = Self::duplicate_single(ctx, original_id, name_suffix).await?;
+            duplicates_by_original.insert(original_id, duplicate.id());
+        }
+
+        for &original_id in &originals {
+            let duplicate_id = duplicates_by_original
+                .get(&original_id)
+                .copied()
+                .ok_or(ComponentError::NotFound(original_id))?;
+
+            if let Some(original_parent_id) = Self::get_parent_by_id(ctx, original_id).await? {
+                // The root keeps the original parent, everything else is re-parented into the
+                // duplicated tree.
+                let parent_id = duplicates_by_original
+                    .get(&original_parent_id)
+                    .copied()
+                    .unwrap_or(original_parent_id);
+                Frame::upsert_parent(ctx, duplicate_id, parent_id)
+                    .await
+                    .map_err(Box::new)?;
+            }
+
+            let original = Self::get_by_id(ctx, original_id).await?;
+
+            for original_manager_id in original.managers(ctx).await? {
+                if let Some(&manager_id) = duplicates_by_original.get(&original_manager_id) {
+                    Self::manage_component(ctx, manager_id, duplicate_id).await?;
+                }
+            }
+
+            for connection in original.incoming_connections(ctx).await? {
+                let Some(&from_component_id) =
+                    duplicates_by_original.get(&connection.from_component_id)
+                else {
+                    continue;
+                };
+
+                Self::connect(
+                    ctx,
+                    from_component_id,
+                    connection.from_output_socket_id,
+                    duplicate_id,
+                    connection.to_input_socket_id,
+                )
+                .await?;
+            }
+        }
+
+        Ok(duplicates_by_original)
+    }
+
+    /// Duplicate a single [`Component`] into every [`View`] that the original is in, without
+    /// handling any relationships to other [`Components`](Component).
+    async fn duplicate_single(
+        ctx: &DalContext,
+        original_id: ComponentId,
+        name_suffix: &str,
+    ) -> ComponentResult<Self> {
+        let original = Self::get_by_id(ctx, original_id).await?;
+        let name = format!("{}{name_suffix}", original.name(ctx).await?);
+
+        let mut geometries = Geometry::by_view_for_component_id(ctx, original_id)
+            .await
+            .map_err(|e| ComponentError::Diagram(Box::new(e)))?
+            .into_iter();
+
+        let duplicate = match geometries.next() {
+            Some((view_id, geometry)) => {
+                let mut duplicate = Self::new(
+                    ctx,
+                    name.clone(),
+                    original.schema_variant(ctx).await?.id(),
+                    view_id,
+                )
+                .await?;
+                duplicate
+                    .set_raw_geometry(ctx, geometry.into_raw(), view_id)
+                    .await?;
+                for (view_id, geometry) in geometries {
+                    Self::add_to_view(ctx, duplicate.id(), view_id, geometry.into_raw()).await?;
+                }
+                duplicate
+            }
+            None => {
+                let view_id = View::get_id_for_default(ctx)
+                    .await
+                    .map_err(|e| ComponentError::Diagram(Box::new(e)))?;
+                Self::new(
+                    ctx,
+                    name.clone(),
+                    original.schema_variant(ctx).await?.id(),
+                    view_id,
+                )
+                .await?
+            }
+        };
+
+        duplicate.clone_attributes_from(ctx, original_id).await?;
+        // Cloning the attributes renames the duplicate to a generic copy name, so we have to set
+        // the requested name afterwards.
+        duplicate.set_name(ctx, &name).await?;
+
+        duplicate.set_labels(ctx, original.labels().clone()).await
+    }
+}
@@ -0,0 +1,114 @@
+//! This module contains the ability to manage labels on a [`Component`]. Labels are free-form
+//! key/value pairs that live alongside the component in the graph (and not in its domain), which
+//! makes them suitable for grouping components by team, environment, etc.
+
+use std::collections::BTreeMap;
+
+use crate::{Component, ComponentError, DalContext};
+
+use super::ComponentResult;
+
+/// The maximum length of a label key.
+const MAX_LABEL_KEY_LENGTH: usize = 63;
+
+impl Component {
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.labels.get(key).map(String::as_str)
+    }
+
+    /// Set a single label, replacing the previous value for the key (if any).
+    pub async fn set_label(
+        self,
+        ctx: &DalContext,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> ComponentResult<Self> {
+        let key = key.into();
+        validate_label_key(&key)?;
+        let value = value.into();
+
+        self.modify(ctx, |component| {
+            component.labels.insert(key, value);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Remove a single label. Removing a label that does not exist is not an error.
+    pub async fn remove_label(self, ctx: &DalContext, key: &str) -> ComponentResult<Self> {
+        self.modify(ctx, |component| {
+            component.labels.remove(key);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replace all labels at once.
+    pub async fn set_labels(
+        self,
+        ctx: &DalContext,
+        labels: BTreeMap<String, String>,
+    ) -> ComponentResult<Self> {
+        for key in labels.keys() {
+            validate_label_key(key)?;
+        }
+
+        self.modify(ctx, |component| {
+            component.labels = labels;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Returns whether or not the [`Component`] has every one of the provided labels. A filter
+    /// value of [`None`] matches any value for the key.
+    pub fn matches_labels(&self, filters: &BTreeMap<String, Option<String>>) -> bool {
+        filters.iter().all(
+            |(key, filter_value)| match (self.labels.get(key), filter_value) {
+                (Some(_), None) => true,
+                (Some(value), Some(filter_value)) => value == filter_value,
+                (None, _) => false,
+            },
+        )
+    }
+
+    /// List all [`Components`](Component) that match the provided label filters. See
+    /// [`Self::matches_labels`] for how filters are applied.
+    pub async fn list_by_labels(
+        ctx: &DalContext,
+        filters: &BTreeMap<String, Option<String>>,
+    ) -> ComponentResult<Vec<Self>> {
+        Ok(Self::list(ctx)
+            .await?
+            .into_iter()
+            .filter(|component| component.matches_labels(filters))
+            .collect())
+    }
+
+    /// List all [`Components`](Component) with the label set to the provided value.
+    pub async fn find_by_label(
+        ctx: &DalContext,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> ComponentResult<Vec<Self>> {
+        Self::list_by_labels(ctx, &BTreeMap::from([(key.into(), Some(value.into()))])).await
+    }
+}
+
+fn validate_label_key(key: &str) -> ComponentResult<()> {
+    let is_valid = !key.is_empty()
+        && key.len() <= MAX_LABEL_KEY_LENGTH
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(ComponentError::InvalidLabelKey(key.to_owned()))
+    }
+}
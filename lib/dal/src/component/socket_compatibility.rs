@@ -0,0 +1,105 @@
+//! This module contains the ability to find every input socket in the change set that a given
+//! output socket of a [`Component`] could be connected to.
+
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    Component, ComponentId, DalContext, InputSocket, InputSocketId, OutputSocket, OutputSocketId,
+    SchemaVariantId, SocketArity,
+};
+
+use super::{ComponentError, ComponentResult};
+
+/// An input socket of a [`Component`] that an output socket can be connected to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibleInputSocket {
+    pub component_id: ComponentId,
+    pub input_socket_id: InputSocketId,
+}
+
+impl Component {
+    /// List the input sockets of every other [`Component`] in the change set that the given
+    /// output socket of this [`Component`] could be connected to.
+    ///
+    /// An input socket is compatible if the output socket fits it, as determined by
+    /// [`OutputSocket::fits_input`]. Input sockets already connected to the output socket are
+    /// left out, as are input sockets with an arity of [`SocketArity::One`] that already have a
+    /// connection.
+    #[instrument(
+        name = "component.compatible_input_sockets",
+        level = "debug",
+        skip(ctx)
+    )]
+    pub async fn compatible_input_sockets(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        output_socket_id: OutputSocketId,
+    ) -> ComponentResult<Vec<CompatibleInputSocket>> {
+        let source_schema_variant_id = Self::schema_variant_id(ctx, component_id).await?;
+        let output_socket = OutputSocket::list(ctx, source_schema_variant_id)
+            .await?
+            .into_iter()
+            .find(|output_socket| output_socket.id() == output_socket_id)
+            .ok_or(ComponentError::OutputSocketNotFoundForComponentId(
+                output_socket_id,
+                component_id,
+            ))?;
+
+        // Many components share a schema variant, so only work out its fitting sockets once
+        let mut fitting_by_variant: HashMap<SchemaVariantId, Vec<InputSocket>> = HashMap::new();
+
+        let mut compatible = Vec::new();
+        for destination_component_id in Self::list_ids(ctx).await? {
+            if destination_component_id == component_id {
+                continue;
+            }
+
+            let schema_variant_id = Self::schema_variant_id(ctx, destination_component_id).await?;
+            let fitting = match fitting_by_variant.entry(schema_variant_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    InputSocket::list(ctx, schema_variant_id)
+                        .await?
+                        .into_iter()
+                        .filter(|input_socket| output_socket.fits_input(input_socket))
+                        .collect(),
+                ),
+            };
+            if fitting.is_empty() {
+                continue;
+            }
+
+            let mut connected = HashSet::new();
+            let mut occupied_input_socket_ids = HashSet::new();
+            for connection in
+                Self::incoming_connections_for_id(ctx, destination_component_id).await?
+            {
+                if connection.from_component_id == component_id
+                    && connection.from_output_socket_id == output_socket_id
+                {
+                    connected.insert(connection.to_input_socket_id);
+                }
+                occupied_input_socket_ids.insert(connection.to_input_socket_id);
+            }
+
+            for input_socket in fitting.iter() {
+                if connected.contains(&input_socket.id())
+                    || (input_socket.arity() == SocketArity::One
+                        && occupied_input_socket_ids.contains(&input_socket.id()))
+                {
+                    continue;
+                }
+                compatible.push(CompatibleInputSocket {
+                    component_id: destination_component_id,
+                    input_socket_id: input_socket.id(),
+                });
+            }
+        }
+
+        Ok(compatible)
+    }
+}
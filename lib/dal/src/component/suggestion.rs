@@ -0,0 +1,135 @@
+//! This module contains the ability to suggest values for a prop on a [`Component`] from the
+//! output sockets of the other components in the change set. A prop that is normally fed by an
+//! input socket (a VPC id, for example) can then be filled in by hand from a list of the values
+//! that a connection would have provided.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AttributeValue, Component, ComponentId, DalContext, InputSocket, OutputSocket, OutputSocketId,
+    Prop, PropId, SchemaVariantId,
+};
+
+use super::ComponentResult;
+
+/// A candidate value for a prop, taken from an output socket of another [`Component`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PropValueSuggestion {
+    pub component_id: ComponentId,
+    pub component_name: String,
+    pub output_socket_id: OutputSocketId,
+    pub output_socket_name: String,
+    pub value: serde_json::Value,
+    /// Whether the output socket is already connected to the [`Component`] the suggestion is for.
+    pub connected: bool,
+}
+
+impl Component {
+    /// Lists values for the given prop on this [`Component`] from the output sockets of every
+    /// other component whose connection annotations fit an input socket feeding the prop.
+    ///
+    /// Output sockets without a value are skipped. Suggestions are ordered by component name,
+    /// then output socket name.
+    pub async fn suggest_values_for_prop(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        prop_id: PropId,
+    ) -> ComponentResult<Vec<PropValueSuggestion>> {
+        let prop = Prop::get_by_id(ctx, prop_id).await?;
+        let mut input_sockets = Vec::new();
+        for input_socket_id in prop.input_socket_sources(ctx).await? {
+            input_sockets.push(InputSocket::get_by_id(ctx, input_socket_id).await?);
+        }
+        if input_sockets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let connected: HashSet<(ComponentId, OutputSocketId)> =
+            Self::incoming_connections_for_id(ctx, component_id)
+                .await?
+                .into_iter()
+                .map(|connection| {
+                    (
+                        connection.from_component_id,
+                        connection.from_output_socket_id,
+                    )
+                })
+                .collect();
+
+        // Which output sockets of a schema variant fit is the same for all of its components
+        let mut fitting_sockets_by_variant: HashMap<SchemaVariantId, Vec<OutputSocket>> =
+            HashMap::new();
+
+        let mut suggestions = Vec::new();
+        for other_component_id in Self::list_ids(ctx).await? {
+            if other_component_id == component_id {
+                continue;
+            }
+
+            let schema_variant_id = Self::schema_variant_id(ctx, other_component_id).await?;
+            if !fitting_sockets_by_variant.contains_key(&schema_variant_id) {
+                let sockets = OutputSocket::list(ctx, schema_variant_id)
+                    .await?
+                    .into_iter()
+                    .filter(|output_socket| {
+                        input_sockets
+                            .iter()
+                            .any(|input_socket| output_socket.fits_input(input_socket))
+                    })
+                    .collect();
+                fitting_sockets_by_variant.insert(schema_variant_id, sockets);
+            }
+            let fitting_sockets = fitting_sockets_by_variant
+                .get(&schema_variant_id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            if fitting_sockets.is_empty() {
+                continue;
+            }
+
+            let component_name = Self::get_by_id(ctx, other_component_id)
+                .await?
+                .name(ctx)
+                .await?;
+            for output_socket in fitting_sockets {
+                let attribute_value_id =
+                    OutputSocket::component_attribute_value_for_output_socket_id(
+                        ctx,
+                        output_socket.id(),
+                        other_component_id,
+                    )
+                    .await?;
+                let Some(value) = AttributeValue::get_by_id(ctx, attribute_value_id)
+                    .await?
+                    .value(ctx)
+                    .await?
+                else {
+                    continue;
+                };
+                if value.is_null() {
+                    continue;
+                }
+
+                suggestions.push(PropValueSuggestion {
+                    component_id: other_component_id,
+                    component_name: component_name.clone(),
+                    output_socket_id: output_socket.id(),
+                    output_socket_name: output_socket.name().to_owned(),
+                    value,
+                    connected: connected.contains(&(other_component_id, output_socket.id())),
+                });
+            }
+        }
+
+        suggestions.sort_by(|a, b| {
+            a.component_name
+                .cmp(&b.component_name)
+                .then_with(|| a.output_socket_name.cmp(&b.output_socket_name))
+        });
+
+        Ok(suggestions)
+    }
+}
@@ -0,0 +1,323 @@
+//! Garbage collection for the content store ("cas" in the layer db), which otherwise grows forever
+//! since nothing is removed from it when content is no longer referenced, for example after a
+//! change set is abandoned.
+//!
+//! Content is reachable when it is referenced by a node of the snapshot (or last good snapshot) of
+//! any change set which was not abandoned before the retention window, or by a func run. Content
+//! which is not reachable and was not written within the retention window is deleted in batches.
+//! The retention window protects content which was written for a snapshot that has not been
+//! committed yet, so it must comfortably outlast any in-flight work.
+//!
+//! Only one run happens at a time across every server, see [`ContentGcRun`].
+
+use std::{collections::HashSet, time::Duration};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgPoolError};
+use si_events::ContentHash;
+use si_layer_cache::{pg::CREATED_AT_REFRESH_INTERVAL, LayerDbError};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    advisory_lock::{AdvisoryLock, AdvisoryLockKey},
+    ChangeSet, ChangeSetError, ChangeSetStatus, DalContext, TransactionsError, WorkspaceSnapshot,
+    WorkspaceSnapshotAddress, WorkspaceSnapshotError,
+};
+
+/// The default retention window for unreferenced content.
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// The default number of content store keys scanned (and deleted) at a time.
+pub const DEFAULT_BATCH_SIZE: usize = 1000;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ContentGcError {
+    #[error("a content garbage collection run is already in progress")]
+    AlreadyRunning,
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
+    #[error("invalid batch size: {0}")]
+    InvalidBatchSize(usize),
+    #[error("invalid retention window (must be at least {CREATED_AT_REFRESH_INTERVAL:?}): {0:?}")]
+    InvalidRetention(Duration),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[from] PgPoolError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace snapshot error: {0}")]
+    WorkspaceSnapshot(#[from] WorkspaceSnapshotError),
+}
+
+pub type ContentGcResult<T> = Result<T, ContentGcError>;
+
+/// Options for a single garbage collection run. By default, nothing is deleted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentGcOptions {
+    /// Unreferenced content is only deleted once it is older than this.
+    pub retention: Duration,
+    /// How many content store keys to scan (and delete) at a time.
+    pub batch_size: usize,
+    /// Only count the unreferenced content instead of deleting it.
+    pub dry_run: bool,
+}
+
+impl Default for ContentGcOptions {
+    fn default() -> Self {
+        Self {
+            retention: DEFAULT_RETENTION,
+            batch_size: DEFAULT_BATCH_SIZE,
+            dry_run: true,
+        }
+    }
+}
+
+impl ContentGcOptions {
+    pub fn validate(&self) -> ContentGcResult<()> {
+        if self.batch_size == 0 {
+            return Err(ContentGcError::InvalidBatchSize(self.batch_size));
+        }
+        if self.retention < CREATED_AT_REFRESH_INTERVAL {
+            return Err(ContentGcError::InvalidRetention(self.retention));
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of a garbage collection run.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentGcReport {
+    pub dry_run: bool,
+    /// The number of distinct snapshots whose content was walked.
+    pub snapshots_walked: usize,
+    /// The number of distinct content hashes referenced by those snapshots.
+    pub reachable: usize,
+    /// The number of content store keys older than the retention window.
+    pub scanned: u64,
+    /// The number of those keys which are not reachable.
+    pub unreferenced: u64,
+    /// The number of keys which were deleted. This can be lower than `unreferenced` if content was
+    /// written again while collecting, and is always zero for dry runs.
+    pub deleted: u64,
+}
+
+/// The claim on the single content garbage collection run, which is held until the run finishes.
+///
+/// Claiming the run ahead of it lets callers which run it in the background report right away
+/// that another run is already in progress.
+#[derive(Debug)]
+pub struct ContentGcRun {
+    lock: AdvisoryLock,
+}
+
+impl ContentGcRun {
+    /// Claims the run, waiting for any run in progress on any server to finish first.
+    pub async fn claim(ctx: &DalContext) -> ContentGcResult<Self> {
+        let lock = AdvisoryLock::acquire(ctx.pg_pool(), AdvisoryLockKey::ContentGc).await?;
+
+        Ok(Self { lock })
+    }
+
+    /// Claims the run, failing with [`ContentGcError::AlreadyRunning`] if a run is in progress on
+    /// any server.
+    pub async fn try_claim(ctx: &DalContext) -> ContentGcResult<Self> {
+        let lock = AdvisoryLock::try_acquire(ctx.pg_pool(), AdvisoryLockKey::ContentGc)
+            .await?
+            .ok_or(ContentGcError::AlreadyRunning)?;
+
+        Ok(Self { lock })
+    }
+
+    /// Deletes (or, for a dry run, counts) content store objects which are older than the
+    /// retention window and not referenced by any workspace snapshot or func run.
+    ///
+    /// A [dry run context](DalContext::dry_run) never deletes anything, whatever the options say.
+    #[instrument(
+        name = "content_gc.collect_garbage",
+        level = "info",
+        skip_all,
+        fields(
+            si.content_gc.dry_run = options.dry_run,
+            si.content_gc.retention_secs = options.retention.as_secs(),
+        )
+    )]
+    pub async fn collect_garbage(
+        self,
+        ctx: &DalContext,
+        options: ContentGcOptions,
+    ) -> ContentGcResult<ContentGcReport> {
+        let result = collect_garbage_unclaimed(ctx, options).await;
+        self.lock.release::<ContentGcError>().await?;
+
+        result
+    }
+}
+
+/// Claims the run (see [`ContentGcRun::claim`]) and collects garbage.
+pub async fn collect_garbage(
+    ctx: &DalContext,
+    options: ContentGcOptions,
+) -> ContentGcResult<ContentGcReport> {
+    options.validate()?;
+    ContentGcRun::claim(ctx)
+        .await?
+        .collect_garbage(ctx, options)
+        .await
+}
+
+async fn collect_garbage_unclaimed(
+    ctx: &DalContext,
+    options: ContentGcOptions,
+) -> ContentGcResult<ContentGcReport> {
+    options.validate()?;
+    let dry_run = options.dry_run || ctx.dry_run().is_some();
+    // The cutoff has to be taken before walking the snapshots: anything written afterwards is
+    // newer than the cutoff and will not be considered for deletion
+    let cutoff = Utc::now()
+        - chrono::Duration::from_std(options.retention)
+            .map_err(|_| ContentGcError::InvalidRetention(options.retention))?;
+
+    let mut report = ContentGcReport {
+        dry_run,
+        ..Default::default()
+    };
+
+    let (reachable, snapshots_walked) = reachable_content(ctx, cutoff).await?;
+    report.reachable = reachable.len();
+    report.snapshots_walked = snapshots_walked;
+
+    let cas = ctx.layer_db().cas();
+    let mut after_key: Option<ContentHash> = None;
+    loop {
+        let keys = cas
+            .list_keys_created_before(cutoff, after_key.as_ref(), options.batch_size as i64)
+            .await?;
+        let Some(last_key) = keys.last().copied() else {
+            break;
+        };
+        after_key = Some(last_key);
+
+        let mut unreferenced: Vec<ContentHash> = keys
+            .iter()
+            .filter(|key| !reachable.contains(key))
+            .copied()
+            .collect();
+        if !unreferenced.is_empty() {
+            let referenced_by_func_runs = ctx
+                .layer_db()
+                .func_run()
+                .filter_referenced_cas_addresses(&unreferenced)
+                .await?;
+            unreferenced.retain(|key| !referenced_by_func_runs.contains(key));
+        }
+        report.scanned += keys.len() as u64;
+        report.unreferenced += unreferenced.len() as u64;
+
+        if !dry_run && !unreferenced.is_empty() {
+            let deleted = cas.delete_created_before(&unreferenced, cutoff).await?;
+            debug!(deleted, "deleted batch of unreferenced content");
+            report.deleted += deleted;
+        }
+    }
+
+    info!(
+        snapshots_walked = report.snapshots_walked,
+        reachable = report.reachable,
+        scanned = report.scanned,
+        unreferenced = report.unreferenced,
+        deleted = report.deleted,
+        dry_run = report.dry_run,
+        "content garbage collection finished",
+    );
+
+    Ok(report)
+}
+
+/// Collects the content hashes referenced by the snapshots of every change set which was not
+/// abandoned before `cutoff`. Also returns the number of snapshots walked.
+///
+/// Content referenced by func runs is checked a batch at a time while scanning instead, as there is
+/// far too much of it to hold in memory.
+async fn reachable_content(
+    ctx: &DalContext,
+    cutoff: chrono::DateTime<Utc>,
+) -> ContentGcResult<(HashSet<ContentHash>, usize)> {
+    let rows = ctx
+        .txns()
+        .await?
+        .pg()
+        .query(
+            "SELECT * FROM change_set_pointers WHERE status != $1 OR updated_at >= $2",
+            &[&ChangeSetStatus::Abandoned.to_string(), &cutoff],
+        )
+        .await?;
+
+    let mut reachable = HashSet::new();
+    let mut seen: HashSet<WorkspaceSnapshotAddress> = HashSet::new();
+    let mut snapshots_walked = 0;
+    for row in rows {
+        let change_set = ChangeSet::try_from(row)?;
+
+        if seen.insert(change_set.workspace_snapshot_address) {
+            if change_set.status.is_closed() {
+                if let Some(snapshot) =
+                    find_unless_pruned(ctx, change_set.workspace_snapshot_address).await?
+                {
+                    extend_with_snapshot_content(&mut reachable, &snapshot).await?;
+                    snapshots_walked += 1;
+                }
+            } else {
+                // The pointer may have moved on since it was listed, in which case this finds its
+                // current snapshot instead, which is just as good
+                let snapshot = WorkspaceSnapshot::find_for_change_set(ctx, change_set.id).await?;
+                let address = snapshot.id().await;
+                if address == change_set.workspace_snapshot_address || seen.insert(address) {
+                    extend_with_snapshot_content(&mut reachable, &snapshot).await?;
+                    snapshots_walked += 1;
+                }
+            }
+        }
+
+        if let Some(address) = change_set.last_good_snapshot_address {
+            if seen.insert(address) {
+                if let Some(snapshot) = find_unless_pruned(ctx, address).await? {
+                    extend_with_snapshot_content(&mut reachable, &snapshot).await?;
+                    snapshots_walked += 1;
+                }
+            }
+        }
+    }
+
+    Ok((reachable, snapshots_walked))
+}
+
+/// Finds a snapshot which may have been pruned (see [`crate::snapshot_pruning`]), in which case its
+/// content does not need to be kept either.
+async fn find_unless_pruned(
+    ctx: &DalContext,
+    address: WorkspaceSnapshotAddress,
+) -> ContentGcResult<Option<WorkspaceSnapshot>> {
+    match WorkspaceSnapshot::find(ctx, address).await {
+        Ok(snapshot) => Ok(Some(snapshot)),
+        Err(WorkspaceSnapshotError::WorkspaceSnapshotGraphMissing(_)) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn extend_with_snapshot_content(
+    reachable: &mut HashSet<ContentHash>,
+    snapshot: &WorkspaceSnapshot,
+) -> ContentGcResult<()> {
+    for (node_weight, _) in snapshot.nodes().await? {
+        reachable.extend(node_weight.content_store_hashes());
+    }
+
+    Ok(())
+}
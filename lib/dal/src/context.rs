@@ -2,8 +2,11 @@ use std::collections::HashSet;
 use std::time::Duration;
 use std::{fmt, mem, path::PathBuf, sync::Arc};
 
+use chrono::{DateTime, Utc};
 use futures::future::BoxFuture;
 use futures::Future;
+use postgres_types::ToSql;
+use rebaser_client::api_types::enqueue_updates_request::RebasePrecondition;
 use rebaser_client::api_types::enqueue_updates_response::v1::RebaseStatus;
 use rebaser_client::api_types::enqueue_updates_response::EnqueueUpdatesResponse;
 use rebaser_client::{RebaserClient, RequestId};
@@ -11,8 +14,9 @@ use serde::{Deserialize, Serialize};
 use si_crypto::SymmetricCryptoService;
 use si_crypto::VeritechEncryptionKey;
 use si_data_nats::{jetstream, NatsClient, NatsError, NatsTxn};
-use si_data_pg::{InstrumentedClient, PgError, PgPool, PgPoolError, PgPoolResult, PgTxn};
+use si_data_pg::{InstrumentedClient, PgError, PgPool, PgPoolError, PgPoolResult, PgRow, PgTxn};
 use si_events::audit_log::AuditLogKind;
+use si_events::merkle_tree_hash::MerkleTreeHash;
 use si_events::rebase_batch_address::RebaseBatchAddress;
 use si_events::EventSessionId;
 use si_events::WorkspaceSnapshotAddress;
@@ -38,7 +42,7 @@ use crate::workspace_snapshot::graph::{RebaseBatch, WorkspaceSnapshotGraph};
 use crate::workspace_snapshot::DependentValueRoot;
 use crate::{audit_logging, slow_rt, EncryptedSecret, Workspace, WorkspaceError};
 use crate::{
-    change_set::{ChangeSet, ChangeSetId},
+    change_set::{ChangeSet, ChangeSetError, ChangeSetId},
     job::{
         definition::ActionJob,
         processor::{JobQueueProcessor, JobQueueProcessorError},
@@ -50,6 +54,12 @@ use crate::{
     WorkspaceSnapshot,
 };
 
+mod dry_run;
+mod retry;
+
+pub use dry_run::{DryRun, DryRunMutations, DryRunSummary};
+pub use retry::RetryPolicy;
+
 pub type DalLayerDb = LayerDb<ContentTypes, EncryptedSecret, WorkspaceSnapshotGraph, RebaseBatch>;
 
 /// A context type which contains handles to common core service dependencies.
@@ -121,12 +131,53 @@ impl ServicesContext {
         }
     }
 
+    /// Returns a copy of this context which uses the provided connections to Postgres and NATS
+    /// (and the clients built upon them) instead, e.g. to serve a workspace from the region its
+    /// data has to reside in. Everything else is shared with this context.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_regional_services(
+        &self,
+        pg_pool: PgPool,
+        nats_conn: NatsClient,
+        jetstream_streams: JetstreamStreams,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        rebaser: RebaserClient,
+        veritech: VeritechClient,
+        layer_db: DalLayerDb,
+    ) -> Self {
+        Self {
+            pg_pool,
+            nats_conn,
+            jetstream_streams,
+            job_processor,
+            rebaser,
+            veritech,
+            layer_db,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of this context which encrypts and decrypts with the provided
+    /// [`SymmetricCryptoService`] instead, e.g. one with rotated keys. Everything else is shared
+    /// with this context.
+    pub fn with_symmetric_crypto_service(
+        &self,
+        symmetric_crypto_service: SymmetricCryptoService,
+    ) -> Self {
+        Self {
+            symmetric_crypto_service,
+            ..self.clone()
+        }
+    }
+
     /// Consumes and returns [`DalContextBuilder`].
     pub fn into_builder(self, blocking: bool) -> DalContextBuilder {
         DalContextBuilder {
             services_context: self,
             blocking,
             no_dependent_values: false,
+            dry_run: None,
+            replica_reads: false,
         }
     }
 
@@ -251,6 +302,7 @@ impl ConnectionState {
                     change_set_id,
                     updates_address,
                     event_session_id,
+                    preconditions,
                 } = maybe_rebase
                 {
                     rebase_with_reply(
@@ -259,6 +311,7 @@ impl ConnectionState {
                         change_set_id,
                         updates_address,
                         event_session_id,
+                        preconditions,
                     )
                     .await?;
                 }
@@ -292,6 +345,7 @@ impl ConnectionState {
                     change_set_id,
                     updates_address,
                     event_session_id,
+                    preconditions,
                 } = maybe_rebase
                 {
                     rebase_with_reply(
@@ -300,6 +354,7 @@ impl ConnectionState {
                         change_set_id,
                         updates_address,
                         event_session_id,
+                        preconditions,
                     )
                     .await?;
                 }
@@ -351,12 +406,21 @@ pub struct DalContext {
     /// Determines if we should not enqueue dependent value update jobs for attribute updates in
     /// this context. Useful for builtin migrations, since we don't care about attribute values propagation then.
     no_dependent_values: bool,
+    /// Set if this context is a dry run, which rolls back instead of committing.
+    dry_run: Option<DryRun>,
+    /// Determines if read-only queries may go to the read replica, if one is configured. Reads
+    /// from the replica don't see this context's uncommitted writes and may lag behind the
+    /// primary, so this is only for contexts which don't write.
+    replica_reads: bool,
     /// The workspace snapshot for this context
     workspace_snapshot: Option<Arc<WorkspaceSnapshot>>,
     /// The change set for this context
     change_set: Option<ChangeSet>,
     /// The event session identifier
     event_session_id: EventSessionId,
+    /// Nodes which must be unchanged when this context's updates are rebased, see
+    /// [`Self::add_commit_precondition`].
+    commit_preconditions: Arc<Mutex<Vec<RebasePrecondition>>>,
 }
 
 impl DalContext {
@@ -367,6 +431,8 @@ impl DalContext {
             services_context,
             blocking,
             no_dependent_values: false,
+            dry_run: None,
+            replica_reads: false,
         }
     }
 
@@ -403,7 +469,7 @@ impl DalContext {
 
     /// Update the context to use the most recent snapshot pointed to by the current `ChangeSetId`.
     pub async fn update_snapshot_to_visibility(&mut self) -> TransactionsResult<()> {
-        let change_set = ChangeSet::find(self, self.change_set_id())
+        let mut change_set = ChangeSet::find(self, self.change_set_id())
             .await
             .map_err(|err| TransactionsError::ChangeSet(err.to_string()))?
             .ok_or(TransactionsError::ChangeSetNotFound(self.change_set_id()))?;
@@ -411,12 +477,36 @@ impl DalContext {
         let workspace_snapshot = WorkspaceSnapshot::find_for_change_set(self, change_set.id)
             .await
             .map_err(|err| TransactionsError::WorkspaceSnapshot(Box::new(err)))?;
+        change_set
+            .verify_snapshot(self, &workspace_snapshot)
+            .await
+            .map_err(|err| match err {
+                ChangeSetError::Quarantined(id) => TransactionsError::ChangeSetQuarantined(id),
+                err => TransactionsError::ChangeSet(err.to_string()),
+            })?;
 
         self.set_change_set(change_set)?;
         self.set_workspace_snapshot(workspace_snapshot);
         Ok(())
     }
 
+    /// Requires the node to be unchanged from the given merkle tree hash when this context's
+    /// updates are rebased. If it has changed by then, nothing is applied and committing fails
+    /// with [`TransactionsError::RebasePreconditionFailed`].
+    pub async fn add_commit_precondition(
+        &self,
+        node_id: impl Into<si_events::ulid::Ulid>,
+        merkle_tree_hash: MerkleTreeHash,
+    ) {
+        self.commit_preconditions
+            .lock()
+            .await
+            .push(RebasePrecondition {
+                node_id: node_id.into(),
+                merkle_tree_hash,
+            });
+    }
+
     pub async fn write_snapshot(
         &self,
     ) -> Result<Option<WorkspaceSnapshotAddress>, TransactionsError> {
@@ -441,6 +531,7 @@ impl DalContext {
             change_set_id,
             updates_address,
             self.event_session_id,
+            Vec::new(),
         )
         .await
     }
@@ -515,6 +606,8 @@ impl DalContext {
             services_context: self.services_context.clone(),
             blocking: self.blocking,
             no_dependent_values: self.no_dependent_values,
+            dry_run: self.dry_run.clone(),
+            replica_reads: self.replica_reads,
         }
     }
 
@@ -546,6 +639,14 @@ impl DalContext {
     ) -> Result<Option<RebaseBatchAddress>, TransactionsError> {
         Ok(if let Some(snapshot) = &self.workspace_snapshot {
             if let Some(rebase_batch) = snapshot.current_rebase_batch().await.map_err(Box::new)? {
+                // Nothing is committed to a quarantined change set until it is restored
+                if let Some(change_set) = self.change_set.as_ref().filter(|cs| cs.is_quarantined())
+                {
+                    return Err(TransactionsError::ChangeSetQuarantined(change_set.id));
+                }
+                if let Some(change_set) = self.change_set.as_ref().filter(|cs| cs.read_only) {
+                    return Err(TransactionsError::ChangeSetReadOnly(change_set.id));
+                }
                 Some(self.write_rebase_batch(rebase_batch).await?)
             } else {
                 None
@@ -557,6 +658,10 @@ impl DalContext {
 
     /// Consumes all inner transactions and committing all changes made within them.
     pub async fn commit(&self) -> TransactionsResult<()> {
+        if let Some(dry_run) = &self.dry_run {
+            return self.commit_dry_run(dry_run).await;
+        }
+
         let maybe_rebase = match self.write_current_rebase_batch().await? {
             Some(updates_address) => DelayedRebaseWithReply::WithUpdates {
                 rebaser: self.rebaser(),
@@ -564,6 +669,7 @@ impl DalContext {
                 change_set_id: self.change_set_id(),
                 updates_address,
                 event_session_id: self.event_session_id,
+                preconditions: self.commit_preconditions.lock().await.clone(),
             },
             None => {
                 // Since we are not rebasing, we need to write the final message and flush all
@@ -582,6 +688,10 @@ impl DalContext {
     }
 
     pub async fn commit_no_rebase(&self) -> TransactionsResult<()> {
+        if let Some(dry_run) = &self.dry_run {
+            return self.commit_dry_run(dry_run).await;
+        }
+
         // Since we are not rebasing, we need to write the final message and flush all
         // pending audit logs.
         self.write_audit_log_final_message().await?;
@@ -656,6 +766,90 @@ impl DalContext {
         self.no_dependent_values
     }
 
+    /// Returns the [`DryRun`] if this context is a dry run.
+    pub fn dry_run(&self) -> Option<&DryRun> {
+        self.dry_run.as_ref()
+    }
+
+    /// Returns true if read-only queries may go to the read replica.
+    pub fn replica_reads(&self) -> bool {
+        self.replica_reads
+    }
+
+    fn reads_from_replica(&self) -> bool {
+        self.replica_reads && self.pg_pool().has_read_replica()
+    }
+
+    /// Runs a read-only query on the read replica if [`replica reads`](Self::replica_reads) are
+    /// enabled and one is configured, or in this context's transactions otherwise.
+    pub async fn read_query(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> TransactionsResult<Vec<PgRow>> {
+        if self.reads_from_replica() {
+            Ok(self
+                .pg_pool()
+                .read()
+                .get()
+                .await?
+                .query(statement, params)
+                .await?)
+        } else {
+            Ok(self.txns().await?.pg().query(statement, params).await?)
+        }
+    }
+
+    /// Like [`read_query`](Self::read_query), for a query which returns exactly one row.
+    pub async fn read_query_one(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> TransactionsResult<PgRow> {
+        if self.reads_from_replica() {
+            Ok(self
+                .pg_pool()
+                .read()
+                .get()
+                .await?
+                .query_one(statement, params)
+                .await?)
+        } else {
+            Ok(self.txns().await?.pg().query_one(statement, params).await?)
+        }
+    }
+
+    /// Like [`read_query`](Self::read_query), for a query which returns at most one row.
+    pub async fn read_query_opt(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> TransactionsResult<Option<PgRow>> {
+        if self.reads_from_replica() {
+            Ok(self
+                .pg_pool()
+                .read()
+                .get()
+                .await?
+                .query_opt(statement, params)
+                .await?)
+        } else {
+            Ok(self.txns().await?.pg().query_opt(statement, params).await?)
+        }
+    }
+
+    /// Records the mutations a commit would have made and rolls back all inner transactions, so
+    /// that nothing is persisted, enqueued, or published.
+    async fn commit_dry_run(&self, dry_run: &DryRun) -> TransactionsResult<()> {
+        let rebase_batch = match &self.workspace_snapshot {
+            Some(snapshot) => snapshot.current_rebase_batch().await.map_err(Box::new)?,
+            None => None,
+        };
+        dry_run.record_commit(rebase_batch.as_ref());
+
+        self.rollback().await
+    }
+
     pub fn services_context(&self) -> ServicesContext {
         self.services_context.clone()
     }
@@ -667,6 +861,10 @@ impl DalContext {
     /// Consumes all inner transactions, committing all changes made within them, and
     /// blocks until all queued jobs have reported as finishing.
     pub async fn blocking_commit(&self) -> TransactionsResult<()> {
+        if let Some(dry_run) = &self.dry_run {
+            return self.commit_dry_run(dry_run).await;
+        }
+
         let maybe_rebase = match self.write_current_rebase_batch().await? {
             Some(updates_address) => DelayedRebaseWithReply::WithUpdates {
                 rebaser: self.rebaser(),
@@ -674,6 +872,7 @@ impl DalContext {
                 change_set_id: self.change_set_id(),
                 updates_address,
                 event_session_id: self.event_session_id,
+                preconditions: self.commit_preconditions.lock().await.clone(),
             },
             None => {
                 // Since we are not rebasing, we need to write the final message and flush all
@@ -688,6 +887,10 @@ impl DalContext {
     }
 
     pub async fn blocking_commit_no_rebase(&self) -> TransactionsResult<()> {
+        if let Some(dry_run) = &self.dry_run {
+            return self.commit_dry_run(dry_run).await;
+        }
+
         self.blocking_commit_internal(DelayedRebaseWithReply::NoUpdates)
             .await?;
         Ok(())
@@ -811,6 +1014,22 @@ impl DalContext {
         Ok(())
     }
 
+    /// Enqueues a job which is flushed to the processing system on `commit`, like any other, but
+    /// which is not run before `run_at`. Useful for retrying with a backoff and for scheduled
+    /// work.
+    pub async fn enqueue_job_at(
+        &self,
+        job: Box<dyn JobProducer + Send + Sync>,
+        run_at: DateTime<Utc>,
+    ) -> TransactionsResult<()> {
+        self.txns()
+            .await?
+            .job_queue
+            .enqueue_scheduled_job(job, run_at)
+            .await;
+        Ok(())
+    }
+
     /// Add the node ids to the workspace snapshot graph and enqueue a dependent values update.
     /// This update will only be run on commit if blocking_commit is used. If commit is used, the
     /// DVU debouncer will run the job. Note that the DVU debouncer might still pick up the job
@@ -1120,6 +1339,10 @@ pub struct DalContextBuilder {
     /// Determines if we should not enqueue dependent value update jobs for attribute value
     /// changes.
     no_dependent_values: bool,
+    /// Set if the built contexts are dry runs, which roll back instead of committing.
+    dry_run: Option<DryRun>,
+    /// Determines if read-only queries in the built contexts may go to the read replica.
+    replica_reads: bool,
 }
 
 impl fmt::Debug for DalContextBuilder {
@@ -1127,6 +1350,8 @@ impl fmt::Debug for DalContextBuilder {
         f.debug_struct("DalContextBuilder")
             .field("blocking", &self.blocking)
             .field("no_dependent_values", &self.no_dependent_values)
+            .field("dry_run", &self.dry_run.is_some())
+            .field("replica_reads", &self.replica_reads)
             .finish_non_exhaustive()
     }
 }
@@ -1144,9 +1369,12 @@ impl DalContextBuilder {
             visibility: Visibility::new_head_fake(),
             history_actor: HistoryActor::SystemInit,
             no_dependent_values: self.no_dependent_values,
+            dry_run: self.dry_run.clone(),
+            replica_reads: self.replica_reads,
             workspace_snapshot: None,
             change_set: None,
             event_session_id: EventSessionId::new(),
+            commit_preconditions: Default::default(),
         })
     }
 
@@ -1167,9 +1395,12 @@ impl DalContextBuilder {
             visibility: Visibility::new(change_set_id),
             history_actor: HistoryActor::SystemInit,
             no_dependent_values: self.no_dependent_values,
+            dry_run: self.dry_run.clone(),
+            replica_reads: self.replica_reads,
             workspace_snapshot: None,
             change_set: None,
             event_session_id: EventSessionId::new(),
+            commit_preconditions: Default::default(),
         };
 
         ctx.update_snapshot_to_visibility().await?;
@@ -1192,9 +1423,12 @@ impl DalContextBuilder {
             history_actor: access_builder.history_actor,
             visibility: Visibility::new_head_fake(),
             no_dependent_values: self.no_dependent_values,
+            dry_run: self.dry_run.clone(),
+            replica_reads: self.replica_reads,
             workspace_snapshot: None,
             change_set: None,
             event_session_id: EventSessionId::new(),
+            commit_preconditions: Default::default(),
         };
 
         // TODO(nick): there's a chicken and egg problem here. We want a dal context to get the
@@ -1220,9 +1454,12 @@ impl DalContextBuilder {
             visibility: request_context.visibility,
             history_actor: request_context.history_actor,
             no_dependent_values: self.no_dependent_values,
+            dry_run: self.dry_run.clone(),
+            replica_reads: self.replica_reads,
             workspace_snapshot: None,
             change_set: None,
             event_session_id: EventSessionId::new(),
+            commit_preconditions: Default::default(),
         };
 
         if ctx.history_actor() != &HistoryActor::SystemInit {
@@ -1288,6 +1525,18 @@ impl DalContextBuilder {
     pub fn set_no_dependent_values(&mut self) {
         self.no_dependent_values = true;
     }
+
+    /// Makes the built contexts dry runs, which record their mutations in the [`DryRun`] instead
+    /// of committing them.
+    pub fn set_dry_run(&mut self, dry_run: DryRun) {
+        self.dry_run = Some(dry_run);
+    }
+
+    /// Lets read-only queries in the built contexts go to the read replica, if one is configured.
+    /// Only for contexts which don't write, since the replica can't see their writes.
+    pub fn set_replica_reads(&mut self) {
+        self.replica_reads = true;
+    }
 }
 
 #[remain::sorted]
@@ -1308,6 +1557,10 @@ pub enum TransactionsError {
     ChangeSetNotFound(ChangeSetId),
     #[error("change set not set on DalContext")]
     ChangeSetNotSet,
+    #[error("change set {0} is quarantined because its snapshot is corrupt")]
+    ChangeSetQuarantined(ChangeSetId),
+    #[error("change set {0} is read-only")]
+    ChangeSetReadOnly(ChangeSetId),
     #[error("job queue processor error: {0}")]
     JobQueueProcessor(#[from] JobQueueProcessorError),
     #[error("tokio join error: {0}")]
@@ -1324,6 +1577,8 @@ pub enum TransactionsError {
     PgPool(#[from] PgPoolError),
     #[error("rebase of batch {0} for change set id {1} failed: {2}")]
     RebaseFailed(RebaseBatchAddress, ChangeSetId, String),
+    #[error("rebase of batch {0} for change set id {1} not performed: node {2} has changed")]
+    RebasePreconditionFailed(RebaseBatchAddress, ChangeSetId, si_events::ulid::Ulid),
     #[error("rebaser client error: {0}")]
     Rebaser(#[from] rebaser_client::ClientError),
     #[error("rebaser reply deadline elapsed; waited={0:?}, request_id={1}")]
@@ -1473,6 +1728,7 @@ impl Transactions {
             change_set_id,
             updates_address,
             event_session_id,
+            preconditions,
         } = maybe_rebase
         {
             // remove the dependent value job since it will be handled by the rebaser
@@ -1483,6 +1739,7 @@ impl Transactions {
                 change_set_id,
                 updates_address,
                 event_session_id,
+                preconditions,
             )
             .await?;
         }
@@ -1518,6 +1775,7 @@ impl Transactions {
             change_set_id,
             updates_address,
             event_session_id,
+            preconditions,
         } = maybe_rebase
         {
             span.record("si.change_set.id", change_set_id.to_string());
@@ -1528,6 +1786,7 @@ impl Transactions {
                 change_set_id,
                 updates_address,
                 event_session_id,
+                preconditions,
             )
             .await?;
         }
@@ -1579,6 +1838,7 @@ enum DelayedRebaseWithReply<'a> {
         change_set_id: ChangeSetId,
         updates_address: RebaseBatchAddress,
         event_session_id: EventSessionId,
+        preconditions: Vec<RebasePrecondition>,
     },
 }
 
@@ -1598,6 +1858,7 @@ async fn rebase_with_reply(
     change_set_id: ChangeSetId,
     updates_address: RebaseBatchAddress,
     event_session_id: EventSessionId,
+    preconditions: Vec<RebasePrecondition>,
 ) -> TransactionsResult<()> {
     let timeout = Duration::from_secs(60);
 
@@ -1607,6 +1868,7 @@ async fn rebase_with_reply(
             change_set_id,
             updates_address,
             event_session_id,
+            preconditions,
         )
         .await?;
 
@@ -1631,5 +1893,8 @@ async fn rebase_with_reply(
             change_set_id,
             message.clone(),
         )),
+        RebaseStatus::PreconditionFailed { node_id } => Err(
+            TransactionsError::RebasePreconditionFailed(updates_address, change_set_id, *node_id),
+        ),
     }
 }
@@ -0,0 +1,97 @@
+//! Dry runs, which let a [`DalContext`](crate::DalContext) go through all of the motions of
+//! mutating a change set without committing anything.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::workspace_snapshot::{
+    edge_weight::EdgeWeightKindDiscriminants, graph::detect_updates::Update, graph::RebaseBatch,
+    node_weight::NodeWeightDiscriminants,
+};
+
+/// Marks the contexts built from a [`DalContextBuilder`](crate::DalContextBuilder) as dry runs
+/// and collects a summary of the graph mutations they would have committed.
+///
+/// Instead of committing, a dry run context rolls back its transactions, so neither the database,
+/// the rebaser, nor any job or event consumer ever sees its changes. Audit logs and events which
+/// would otherwise be published right away are dropped, and content garbage collection only
+/// counts. Clones share the summary.
+#[derive(Clone, Debug, Default)]
+pub struct DryRun {
+    summary: Arc<Mutex<DryRunSummary>>,
+}
+
+impl DryRun {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the summary of the mutations recorded so far.
+    pub fn summary(&self) -> DryRunSummary {
+        self.summary
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Records a commit which was skipped. The rebase batch of a commit holds every change since
+    /// the snapshot was fetched, so it replaces the mutations of any earlier commit.
+    pub(crate) fn record_commit(&self, rebase_batch: Option<&RebaseBatch>) {
+        let mut summary = self.summary.lock().unwrap_or_else(|err| err.into_inner());
+        summary.commits += 1;
+        if let Some(rebase_batch) = rebase_batch {
+            summary.mutations = DryRunMutations::from_updates(rebase_batch.updates());
+        }
+    }
+}
+
+/// The graph mutations a dry run would have committed.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunSummary {
+    /// How many times the context was asked to commit.
+    pub commits: usize,
+    pub mutations: DryRunMutations,
+}
+
+/// Counts of the mutations to the graph, keyed by the kind of node or edge.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunMutations {
+    pub new_nodes: BTreeMap<String, usize>,
+    pub replaced_nodes: BTreeMap<String, usize>,
+    pub new_edges: BTreeMap<String, usize>,
+    pub removed_edges: BTreeMap<String, usize>,
+}
+
+impl DryRunMutations {
+    fn from_updates(updates: &[Update]) -> Self {
+        let mut mutations = Self::default();
+        for update in updates {
+            let (counts, kind) = match update {
+                Update::NewNode { node_weight } => (
+                    &mut mutations.new_nodes,
+                    NodeWeightDiscriminants::from(node_weight).to_string(),
+                ),
+                Update::ReplaceNode { node_weight } => (
+                    &mut mutations.replaced_nodes,
+                    NodeWeightDiscriminants::from(node_weight).to_string(),
+                ),
+                Update::NewEdge { edge_weight, .. } => (
+                    &mut mutations.new_edges,
+                    EdgeWeightKindDiscriminants::from(edge_weight.kind()).to_string(),
+                ),
+                Update::RemoveEdge { edge_kind, .. } => {
+                    (&mut mutations.removed_edges, edge_kind.to_string())
+                }
+            };
+            *counts.entry(kind).or_default() += 1;
+        }
+
+        mutations
+    }
+}
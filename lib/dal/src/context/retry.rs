@@ -0,0 +1,147 @@
+//! Retrying of work which fails because another writer to the same change set committed first.
+//!
+//! Commits are rebased onto whatever the change set points to by the time the rebaser gets to
+//! them, so a commit built from a snapshot which another writer has since changed can fail to
+//! apply. Rerunning the work against a freshly fetched snapshot is usually enough to get past it.
+
+use std::{error::Error, future::Future, sync::Arc, time::Duration};
+
+use rand::Rng;
+use si_events::EventSessionId;
+use telemetry::prelude::*;
+use tokio::sync::Mutex;
+
+use super::{ConnectionState, DalContext, TransactionsError, TransactionsResult};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(50);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// How [`DalContext::run_with_retries`] retries work which fails with a conflict.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of times the work is run, including the first.
+    pub max_attempts: u32,
+    /// The delay before the first retry, which doubles for each retry after it.
+    pub base_delay: Duration,
+    /// The cap on the delay between retries.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A random delay of up to the exponential backoff for the given retry, so that writers which
+    /// conflicted with each other don't retry in lockstep.
+    fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let backoff_micros = u64::try_from(backoff.as_micros()).unwrap_or(u64::MAX);
+
+        Duration::from_micros(rand::thread_rng().gen_range(0..=backoff_micros))
+    }
+}
+
+impl TransactionsError {
+    /// Returns true if the error is caused by a concurrent write to the same change set, meaning
+    /// that the work which led to it may succeed if run again against a fresh snapshot.
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, Self::RebaseFailed(..))
+    }
+}
+
+impl DalContext {
+    /// Runs `fun` with [`RetryPolicy::default`], see
+    /// [`run_with_retry_policy`](Self::run_with_retry_policy).
+    pub async fn run_with_retries<F, Fut, T, E>(&self, fun: F) -> Result<T, E>
+    where
+        F: FnMut(DalContext) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Error + From<TransactionsError> + 'static,
+    {
+        self.run_with_retry_policy(RetryPolicy::default(), fun)
+            .await
+    }
+
+    /// Runs `fun`, running it again with a backoff if it fails with a
+    /// [conflict](TransactionsError::is_conflict) anywhere in its error's chain, up to the
+    /// policy's maximum number of attempts.
+    ///
+    /// Each attempt gets a new context for the same tenancy, visibility and actor as this one,
+    /// with its own transactions and the latest snapshot of the change set, so `fun` must commit
+    /// the context it is given for its work to stick. This context is left untouched.
+    pub async fn run_with_retry_policy<F, Fut, T, E>(
+        &self,
+        policy: RetryPolicy,
+        mut fun: F,
+    ) -> Result<T, E>
+    where
+        F: FnMut(DalContext) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Error + From<TransactionsError> + 'static,
+    {
+        let mut attempt = 1;
+        loop {
+            let ctx = self.fresh_for_retry().await?;
+            match fun(ctx).await {
+                Err(err) if attempt < policy.max_attempts && is_conflict(&err) => {
+                    let delay = policy.delay(attempt - 1);
+                    warn!(
+                        si.change_set.id = %self.change_set_id(),
+                        si.error.message = %err,
+                        attempt,
+                        max_attempts = policy.max_attempts,
+                        ?delay,
+                        "conflicting write to change set, retrying",
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn fresh_for_retry(&self) -> TransactionsResult<DalContext> {
+        let conns = self.services_context.connections().await?;
+        let mut ctx = DalContext {
+            conns_state: Arc::new(Mutex::new(ConnectionState::new_from_conns(conns))),
+            workspace_snapshot: None,
+            change_set: None,
+            event_session_id: EventSessionId::new(),
+            ..self.clone()
+        };
+        ctx.update_snapshot_to_visibility().await?;
+
+        Ok(ctx)
+    }
+}
+
+fn is_conflict(err: &(dyn Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        let conflict = err
+            .downcast_ref::<TransactionsError>()
+            .or_else(|| {
+                err.downcast_ref::<Box<TransactionsError>>()
+                    .map(AsRef::as_ref)
+            })
+            .is_some_and(TransactionsError::is_conflict);
+        if conflict {
+            return true;
+        }
+        source = err.source();
+    }
+
+    false
+}
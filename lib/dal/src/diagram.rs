@@ -1,3 +1,4 @@
+pub mod annotation;
 mod diagram_object;
 pub mod geometry;
 pub mod view;
@@ -25,6 +26,7 @@ use crate::{
         IncomingConnection, InferredConnection, OutgoingConnection,
     },
     diagram::{
+        annotation::AnnotationId,
         geometry::{Geometry, GeometryId, GeometryRepresents},
         view::{View, ViewId, ViewObjectView},
     },
@@ -45,6 +47,8 @@ use si_layer_cache::LayerDbError;
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum DiagramError {
+    #[error("annotation not found: {0}")]
+    AnnotationNotFound(AnnotationId),
     #[error("attribute prototype argument error: {0}")]
     AttributePrototypeArgument(#[from] AttributePrototypeArgumentError),
     #[error("attribute prototype not found")]
@@ -133,6 +137,8 @@ pub enum DiagramError {
     ViewCategoryNotFound,
     #[error("view not found: {0}")]
     ViewNotFound(ViewId),
+    #[error("view not found for annotation id: {0}")]
+    ViewNotFoundForAnnotation(AnnotationId),
     #[error("view not found for geometry id: {0}")]
     ViewNotFoundForGeometry(GeometryId),
     #[error("Workspace error: {0}")]
@@ -0,0 +1,468 @@
+//! Free-text (markdown) notes placed on a [`View`], either at a position on the diagram or
+//! anchored to a [`Component`](crate::Component) (or frame).
+//!
+//! View --Contain--> Annotation --Represents--> Geometry (of the anchor component on the view)
+//!
+//! An annotation anchored to a component is positioned relative to that component's geometry on
+//! the view. If the component leaves the view, the annotation is detached and keeps its position
+//! on the diagram.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diagram::{
+        geometry::{Geometry, GeometryId, GeometryRepresents},
+        view::{View, ViewId},
+        DiagramError, DiagramResult,
+    },
+    implement_add_edge_to,
+    layer_db_types::{AnnotationContent, AnnotationContentV1},
+    workspace_snapshot::{
+        content_address::{ContentAddress, ContentAddressDiscriminants},
+        node_weight::NodeWeight,
+    },
+    ChangeSetId, ComponentId, DalContext, EdgeWeightKind, EdgeWeightKindDiscriminants,
+    HistoryActor, Timestamp, UserPk, WorkspaceSnapshotError, WsEvent, WsEventResult, WsPayload,
+};
+
+pub use si_id::AnnotationId;
+
+/// A note on a [`View`]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    id: AnnotationId,
+    view_id: ViewId,
+    body: String,
+    author: Option<UserPk>,
+    /// Relative to the geometry of the anchor component, if any
+    x: isize,
+    /// Relative to the geometry of the anchor component, if any
+    y: isize,
+    component_id: Option<ComponentId>,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl Annotation {
+    implement_add_edge_to!(
+        source_id: ViewId,
+        destination_id: AnnotationId,
+        add_fn: add_view_edge,
+        discriminant: EdgeWeightKindDiscriminants::Contain,
+        result: DiagramResult,
+    );
+
+    implement_add_edge_to!(
+        source_id: AnnotationId,
+        destination_id: GeometryId,
+        add_fn: add_edge_to_geometry,
+        discriminant: EdgeWeightKindDiscriminants::Represents,
+        result: DiagramResult,
+    );
+
+    pub fn id(&self) -> AnnotationId {
+        self.id
+    }
+
+    pub fn view_id(&self) -> ViewId {
+        self.view_id
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn author(&self) -> Option<UserPk> {
+        self.author
+    }
+
+    pub fn x(&self) -> isize {
+        self.x
+    }
+
+    pub fn y(&self) -> isize {
+        self.y
+    }
+
+    /// The [`Component`](crate::Component) this annotation is anchored to, if any.
+    pub fn component_id(&self) -> Option<ComponentId> {
+        self.component_id
+    }
+
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.timestamp
+    }
+
+    fn assemble(
+        id: AnnotationId,
+        view_id: ViewId,
+        component_id: Option<ComponentId>,
+        content: AnnotationContent,
+    ) -> Self {
+        let content = content.extract();
+
+        Self {
+            id,
+            view_id,
+            body: content.body,
+            author: content.author,
+            x: content.x,
+            y: content.y,
+            component_id,
+            timestamp: content.timestamp,
+        }
+    }
+
+    /// Creates an annotation on the view, authored by the user in the context (if any). When
+    /// anchored to a component, the component must be on the view.
+    pub async fn new(
+        ctx: &DalContext,
+        view_id: ViewId,
+        body: impl Into<String>,
+        x: isize,
+        y: isize,
+        component_id: Option<ComponentId>,
+    ) -> DiagramResult<Self> {
+        // Make sure the view (and the anchor, if any) exist before we write anything
+        View::get_by_id(ctx, view_id).await?;
+        let anchor_geometry_id = match component_id {
+            Some(component_id) => Some(
+                Geometry::get_by_component_and_view(ctx, component_id, view_id)
+                    .await?
+                    .id(),
+            ),
+            None => None,
+        };
+
+        let author = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        };
+        let content = AnnotationContent::V1(AnnotationContentV1 {
+            timestamp: Timestamp::now(),
+            body: body.into(),
+            author,
+            x,
+            y,
+        });
+
+        let (hash, _) = ctx.layer_db().cas().write(
+            Arc::new(content.clone().into()),
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )?;
+
+        let snap = ctx.workspace_snapshot()?;
+        let id = snap.generate_ulid().await?;
+        let lineage_id = snap.generate_ulid().await?;
+        snap.add_or_replace_node(NodeWeight::new_content(
+            id,
+            lineage_id,
+            ContentAddress::Annotation(hash),
+        ))
+        .await?;
+
+        Self::add_view_edge(ctx, view_id, id.into(), EdgeWeightKind::Contain(None)).await?;
+        if let Some(geometry_id) = anchor_geometry_id {
+            Self::add_edge_to_geometry(ctx, id.into(), geometry_id, EdgeWeightKind::Represents)
+                .await?;
+        }
+
+        Ok(Self::assemble(id.into(), view_id, component_id, content))
+    }
+
+    pub async fn get_by_id(ctx: &DalContext, annotation_id: AnnotationId) -> DiagramResult<Self> {
+        let snap = ctx.workspace_snapshot()?;
+
+        if snap.get_node_index_by_id_opt(annotation_id).await.is_none() {
+            return Err(DiagramError::AnnotationNotFound(annotation_id));
+        }
+
+        let view_idx = snap
+            .incoming_sources_for_edge_weight_kind(
+                annotation_id,
+                EdgeWeightKindDiscriminants::Contain,
+            )
+            .await?
+            .pop()
+            .ok_or(DiagramError::ViewNotFoundForAnnotation(annotation_id))?;
+        let view_id = snap.get_node_weight(view_idx).await?.id().into();
+
+        Self::get_for_view(ctx, view_id, annotation_id).await
+    }
+
+    async fn get_for_view(
+        ctx: &DalContext,
+        view_id: ViewId,
+        annotation_id: AnnotationId,
+    ) -> DiagramResult<Self> {
+        let snap = ctx.workspace_snapshot()?;
+
+        let node_index = snap
+            .get_node_index_by_id_opt(annotation_id)
+            .await
+            .ok_or(DiagramError::AnnotationNotFound(annotation_id))?;
+        let node_weight = snap
+            .get_node_weight(node_index)
+            .await?
+            .get_content_node_weight_of_kind(ContentAddressDiscriminants::Annotation)?;
+
+        let content: AnnotationContent = ctx
+            .layer_db()
+            .cas()
+            .try_read_as(&node_weight.content_hash())
+            .await?
+            .ok_or(WorkspaceSnapshotError::MissingContentFromStore(
+                annotation_id.into(),
+            ))?;
+
+        let component_id = Self::anchor_component_id(ctx, annotation_id).await?;
+
+        Ok(Self::assemble(
+            annotation_id,
+            view_id,
+            component_id,
+            content,
+        ))
+    }
+
+    async fn anchor_geometry_id(
+        ctx: &DalContext,
+        annotation_id: AnnotationId,
+    ) -> DiagramResult<Option<GeometryId>> {
+        let snap = ctx.workspace_snapshot()?;
+
+        let Some(geometry_idx) = snap
+            .outgoing_targets_for_edge_weight_kind(
+                annotation_id,
+                EdgeWeightKindDiscriminants::Represents,
+            )
+            .await?
+            .pop()
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(snap.get_node_weight(geometry_idx).await?.id().into()))
+    }
+
+    async fn anchor_component_id(
+        ctx: &DalContext,
+        annotation_id: AnnotationId,
+    ) -> DiagramResult<Option<ComponentId>> {
+        let Some(geometry_id) = Self::anchor_geometry_id(ctx, annotation_id).await? else {
+            return Ok(None);
+        };
+
+        Ok(match Geometry::represented_id(ctx, geometry_id).await? {
+            GeometryRepresents::Component(component_id) => Some(component_id),
+            GeometryRepresents::View(_) => None,
+        })
+    }
+
+    pub async fn list_for_view(ctx: &DalContext, view_id: ViewId) -> DiagramResult<Vec<Self>> {
+        let snap = ctx.workspace_snapshot()?;
+
+        let mut annotations = vec![];
+        for annotation_idx in snap
+            .outgoing_targets_for_edge_weight_kind(view_id, EdgeWeightKindDiscriminants::Contain)
+            .await?
+        {
+            let annotation_id = snap.get_node_weight(annotation_idx).await?.id().into();
+            annotations.push(Self::get_for_view(ctx, view_id, annotation_id).await?);
+        }
+
+        Ok(annotations)
+    }
+
+    /// Sets the body and position of the annotation, and re-anchors it to the given component
+    /// (which must be on the annotation's view), or detaches it.
+    pub async fn update(
+        &mut self,
+        ctx: &DalContext,
+        body: impl Into<String>,
+        x: isize,
+        y: isize,
+        component_id: Option<ComponentId>,
+    ) -> DiagramResult<()> {
+        if component_id != self.component_id {
+            let anchor_geometry_id = match component_id {
+                Some(component_id) => Some(
+                    Geometry::get_by_component_and_view(ctx, component_id, self.view_id)
+                        .await?
+                        .id(),
+                ),
+                None => None,
+            };
+
+            Self::remove_anchor(ctx, self.id).await?;
+            if let Some(geometry_id) = anchor_geometry_id {
+                Self::add_edge_to_geometry(ctx, self.id, geometry_id, EdgeWeightKind::Represents)
+                    .await?;
+            }
+            self.component_id = component_id;
+        }
+
+        self.body = body.into();
+        self.x = x;
+        self.y = y;
+        self.write_content(ctx).await
+    }
+
+    async fn remove_anchor(ctx: &DalContext, annotation_id: AnnotationId) -> DiagramResult<()> {
+        let snap = ctx.workspace_snapshot()?;
+
+        let annotation_idx = snap.get_node_index_by_id(annotation_id).await?;
+        for geometry_idx in snap
+            .outgoing_targets_for_edge_weight_kind(
+                annotation_id,
+                EdgeWeightKindDiscriminants::Represents,
+            )
+            .await?
+        {
+            snap.remove_edge(
+                annotation_idx,
+                geometry_idx,
+                EdgeWeightKindDiscriminants::Represents,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn write_content(&mut self, ctx: &DalContext) -> DiagramResult<()> {
+        self.timestamp.updated_at = Utc::now();
+        let (hash, _) = ctx.layer_db().cas().write(
+            Arc::new(
+                AnnotationContent::V1(AnnotationContentV1 {
+                    timestamp: self.timestamp,
+                    body: self.body.clone(),
+                    author: self.author,
+                    x: self.x,
+                    y: self.y,
+                })
+                .into(),
+            ),
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )?;
+
+        ctx.workspace_snapshot()?
+            .update_content(self.id.into(), hash)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove(ctx: &DalContext, annotation_id: AnnotationId) -> DiagramResult<()> {
+        ctx.workspace_snapshot()?
+            .remove_node_by_id(annotation_id)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Detaches the annotations anchored to a geometry which is about to be removed, moving them
+    /// to where they are currently shown on the diagram.
+    pub(crate) async fn detach_all_from_geometry(
+        ctx: &DalContext,
+        geometry_id: GeometryId,
+    ) -> DiagramResult<()> {
+        let snap = ctx.workspace_snapshot()?;
+
+        let annotation_ids: Vec<AnnotationId> = {
+            let mut ids = vec![];
+            for annotation_idx in snap
+                .incoming_sources_for_edge_weight_kind(
+                    geometry_id,
+                    EdgeWeightKindDiscriminants::Represents,
+                )
+                .await?
+            {
+                ids.push(snap.get_node_weight(annotation_idx).await?.id().into());
+            }
+            ids
+        };
+        if annotation_ids.is_empty() {
+            return Ok(());
+        }
+
+        let geometry = Geometry::get_by_id(ctx, geometry_id).await?;
+        for annotation_id in annotation_ids {
+            let mut annotation = Self::get_by_id(ctx, annotation_id).await?;
+            let (x, y) = (annotation.x + geometry.x(), annotation.y + geometry.y());
+            let body = annotation.body.clone();
+            annotation.update(ctx, body, x, y, None).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationWsPayload {
+    change_set_id: ChangeSetId,
+    annotation: Annotation,
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationDeletedPayload {
+    change_set_id: ChangeSetId,
+    view_id: ViewId,
+    annotation_id: AnnotationId,
+}
+
+impl WsEvent {
+    pub async fn annotation_created(
+        ctx: &DalContext,
+        annotation: Annotation,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::AnnotationCreated(AnnotationWsPayload {
+                change_set_id: ctx.change_set_id(),
+                annotation,
+            }),
+        )
+        .await
+    }
+
+    pub async fn annotation_updated(
+        ctx: &DalContext,
+        annotation: Annotation,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::AnnotationUpdated(AnnotationWsPayload {
+                change_set_id: ctx.change_set_id(),
+                annotation,
+            }),
+        )
+        .await
+    }
+
+    pub async fn annotation_deleted(
+        ctx: &DalContext,
+        view_id: ViewId,
+        annotation_id: AnnotationId,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::AnnotationDeleted(AnnotationDeletedPayload {
+                change_set_id: ctx.change_set_id(),
+                view_id,
+                annotation_id,
+            }),
+        )
+        .await
+    }
+}
@@ -1,3 +1,4 @@
+use crate::diagram::annotation::Annotation;
 use crate::diagram::diagram_object::DiagramObject;
 use crate::diagram::view::{View, ViewId};
 use crate::diagram::{DiagramError, DiagramResult};
@@ -542,6 +543,7 @@ impl Geometry {
             Err(e) => return Err(e),
         }
 
+        Annotation::detach_all_from_geometry(ctx, geometry_id).await?;
         ctx.workspace_snapshot()?
             .remove_node_by_id(geometry_id)
             .await?;
@@ -565,6 +567,7 @@ impl Geometry {
         for geometry_idx in geometries {
             let geometry_id = snap.get_node_weight(geometry_idx).await?.id();
 
+            Annotation::detach_all_from_geometry(ctx, geometry_id.into()).await?;
             snap.remove_node_by_id(geometry_id).await?;
         }
 
@@ -4,8 +4,9 @@ use crate::{
         geometry::{Geometry, GeometryId},
         DiagramError, DiagramResult,
     },
+    environment::Environment,
     implement_add_edge_to,
-    layer_db_types::{ViewContent, ViewContentV1},
+    layer_db_types::{ViewContent, ViewContentV2},
     workspace_snapshot::{
         node_weight::{
             category_node_weight::CategoryNodeKind, traits::SiVersionedNodeWeight,
@@ -33,6 +34,7 @@ pub use si_id::ViewId;
 pub struct View {
     id: ViewId,
     name: String,
+    environment: Option<Environment>,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -66,6 +68,12 @@ impl View {
         &self.timestamp
     }
 
+    /// The [`Environment`] that the [`Components`](crate::Component) in this view belong to, if
+    /// any.
+    pub fn environment(&self) -> Option<Environment> {
+        self.environment
+    }
+
     pub async fn is_default(&self, ctx: &DalContext) -> DiagramResult<bool> {
         let default_id = Self::get_id_for_default(ctx).await?;
 
@@ -79,6 +87,7 @@ impl View {
             id: node_weight.id().into(),
             timestamp: content.timestamp,
             name: content.name,
+            environment: content.environment,
         }
     }
 
@@ -87,9 +96,10 @@ impl View {
         let id = snap.generate_ulid().await?;
         let lineage_id = snap.generate_ulid().await?;
 
-        let content = ViewContent::V1(ViewContentV1 {
+        let content = ViewContent::V2(ViewContentV2 {
             timestamp: Timestamp::now(),
             name: name.as_ref().to_owned(),
+            environment: None,
         });
 
         let (content_address, _) = ctx.layer_db().cas().write(
@@ -276,14 +286,27 @@ impl View {
     }
 
     pub async fn set_name(&mut self, ctx: &DalContext, name: impl AsRef<str>) -> DiagramResult<()> {
+        self.name = name.as_ref().to_string();
+        self.write_content(ctx).await
+    }
+
+    pub async fn set_environment(
+        &mut self,
+        ctx: &DalContext,
+        environment: Option<Environment>,
+    ) -> DiagramResult<()> {
+        self.environment = environment;
+        self.write_content(ctx).await
+    }
+
+    async fn write_content(&mut self, ctx: &DalContext) -> DiagramResult<()> {
+        self.timestamp.updated_at = Utc::now();
         let (hash, _) = ctx.layer_db().cas().write(
             Arc::new(
-                ViewContent::V1(ViewContentV1 {
-                    timestamp: Timestamp {
-                        created_at: self.timestamp.created_at,
-                        updated_at: Utc::now(),
-                    },
-                    name: name.as_ref().to_owned(),
+                ViewContent::V2(ViewContentV2 {
+                    timestamp: self.timestamp,
+                    name: self.name.to_owned(),
+                    environment: self.environment,
                 })
                 .into(),
             ),
@@ -296,8 +319,6 @@ impl View {
             .update_content(self.id.into(), hash)
             .await?;
 
-        self.name = name.as_ref().to_string();
-
         Ok(())
     }
 
@@ -411,6 +432,10 @@ impl ViewObjectView {
             geometry,
         })
     }
+
+    pub fn id(&self) -> ViewId {
+        self.view.id
+    }
 }
 
 /// Frontend representation for a [View](View).
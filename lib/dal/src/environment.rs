@@ -0,0 +1,263 @@
+//! This module contains [`Environment`], which [`Views`](View) can be assigned to, and
+//! [`Promotion`], which copies the configuration of a [`Component`] to its counterpart in another
+//! [`Environment`].
+//!
+//! A [`Component`] belongs to the [`Environment`] of the [`Views`](View) it is in. Its counterpart
+//! in another [`Environment`] is the [`Component`] of the same [`Schema`](crate::Schema), with the
+//! same name, in a [`View`] assigned to that [`Environment`].
+
+use std::collections::{BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    attribute::value::{AttributeValueError, ChildAttributeValuePair},
+    diagram::{
+        geometry::{Geometry, GeometryRepresents},
+        view::View,
+        DiagramError,
+    },
+    AttributeValue, AttributeValueId, Component, ComponentError, ComponentId, DalContext, PropKind,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum EnvironmentError {
+    #[error("more than one counterpart found for component {0} in environment {1}")]
+    AmbiguousCounterpart(ComponentId, Environment),
+    #[error("component {0} is in views assigned to more than one environment")]
+    AmbiguousEnvironment(ComponentId),
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("no counterpart found for component {0} in environment {1}")]
+    CounterpartNotFound(ComponentId, Environment),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] DiagramError),
+    #[error("component {0} is not in a view assigned to an environment")]
+    NoEnvironment(ComponentId),
+    #[error("cannot promote component {0} to its own environment ({1})")]
+    SameEnvironment(ComponentId, Environment),
+}
+
+pub type EnvironmentResult<T> = Result<T, EnvironmentError>;
+
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum Environment {
+    Development,
+    Production,
+    Staging,
+}
+
+impl Environment {
+    /// Find the [`Environment`] of the [`Component`], i.e. the one assigned to the
+    /// [`Views`](View) it is in. Views without an [`Environment`] are ignored.
+    pub async fn for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> EnvironmentResult<Option<Self>> {
+        let mut environments = BTreeSet::new();
+        for geometry_id in Geometry::list_ids_by_component(ctx, component_id).await? {
+            let view_id = Geometry::get_view_id_by_id(ctx, geometry_id).await?;
+            if let Some(environment) = View::get_by_id(ctx, view_id).await?.environment() {
+                environments.insert(environment);
+            }
+        }
+
+        if environments.len() > 1 {
+            return Err(EnvironmentError::AmbiguousEnvironment(component_id));
+        }
+        Ok(environments.pop_first())
+    }
+
+    /// Find the counterpart of the [`Component`] in this [`Environment`].
+    pub async fn counterpart(
+        self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> EnvironmentResult<ComponentId> {
+        let component = Component::get_by_id(ctx, component_id).await?;
+        let name = component.name(ctx).await?;
+        let schema_id = Component::schema_for_component_id(ctx, component_id)
+            .await?
+            .id();
+
+        let mut counterparts = BTreeSet::new();
+        for view in View::list(ctx).await? {
+            if view.environment() != Some(self) {
+                continue;
+            }
+            for geometry in Geometry::list_by_view_id(ctx, view.id()).await? {
+                let GeometryRepresents::Component(candidate_id) =
+                    Geometry::represented_id(ctx, geometry.id()).await?
+                else {
+                    continue;
+                };
+                if candidate_id == component_id || counterparts.contains(&candidate_id) {
+                    continue;
+                }
+                let candidate = Component::get_by_id(ctx, candidate_id).await?;
+                if candidate.name(ctx).await? == name
+                    && Component::schema_for_component_id(ctx, candidate_id)
+                        .await?
+                        .id()
+                        == schema_id
+                {
+                    counterparts.insert(candidate_id);
+                }
+            }
+        }
+
+        if counterparts.len() > 1 {
+            return Err(EnvironmentError::AmbiguousCounterpart(component_id, self));
+        }
+        counterparts
+            .pop_first()
+            .ok_or(EnvironmentError::CounterpartNotFound(component_id, self))
+    }
+}
+
+/// Copies the configuration of a [`Component`] to its counterpart in the target [`Environment`].
+///
+/// Only the values set directly on the component's domain are copied, and values set directly on
+/// the counterpart but not on the component are reset to their defaults; values that are computed
+/// (e.g. from sockets) are left for the counterpart to compute from its own inputs. Parameters
+/// that differ between environments (e.g. a region or an instance size) are mapped to the value
+/// they should have in the target [`Environment`] and set after copying.
+#[derive(Clone, Debug)]
+pub struct Promotion {
+    target: Environment,
+    parameters: Vec<(Vec<String>, Option<serde_json::Value>)>,
+}
+
+impl Promotion {
+    pub fn new(target: Environment) -> Self {
+        Self {
+            target,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Set the prop at the path (e.g. `["root", "domain", "region"]`) to the value on the
+    /// counterpart, instead of copying it.
+    pub fn map_parameter(mut self, prop_path: &[&str], value: Option<serde_json::Value>) -> Self {
+        self.parameters.push((
+            prop_path
+                .iter()
+                .map(|segment| segment.to_string())
+                .collect(),
+            value,
+        ));
+        self
+    }
+
+    /// Promote the [`Component`], returning the id of its counterpart.
+    #[instrument(
+        name = "environment.promotion.promote",
+        level = "info",
+        skip_all,
+        fields(si.component.id = %component_id, target = %self.target),
+    )]
+    pub async fn promote(
+        &self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> EnvironmentResult<ComponentId> {
+        let source = Environment::for_component(ctx, component_id)
+            .await?
+            .ok_or(EnvironmentError::NoEnvironment(component_id))?;
+        if source == self.target {
+            return Err(EnvironmentError::SameEnvironment(component_id, source));
+        }
+        let counterpart_id = self.target.counterpart(ctx, component_id).await?;
+
+        let from_domain_id =
+            Component::attribute_value_for_prop_by_id(ctx, component_id, &["root", "domain"])
+                .await?;
+        let to_domain_id =
+            Component::attribute_value_for_prop_by_id(ctx, counterpart_id, &["root", "domain"])
+                .await?;
+        copy_set_values(ctx, from_domain_id, to_domain_id).await?;
+
+        for (prop_path, value) in &self.parameters {
+            let prop_path: Vec<&str> = prop_path.iter().map(String::as_str).collect();
+            let attribute_value_id =
+                Component::attribute_value_for_prop_by_id(ctx, counterpart_id, &prop_path).await?;
+            AttributeValue::update(ctx, attribute_value_id, value.to_owned()).await?;
+        }
+
+        Ok(counterpart_id)
+    }
+}
+
+/// Walk both attribute value trees, setting every value that was set directly on the source onto
+/// the destination and resetting every value that was only set directly on the destination. Maps
+/// and arrays set directly are copied as a whole.
+async fn copy_set_values(
+    ctx: &DalContext,
+    from_root_id: AttributeValueId,
+    to_root_id: AttributeValueId,
+) -> EnvironmentResult<()> {
+    let mut work_queue = VecDeque::from([(from_root_id, to_root_id)]);
+    while let Some((from_id, to_id)) = work_queue.pop_front() {
+        let kind = AttributeValue::prop(ctx, from_id).await?.kind;
+        let set_directly = AttributeValue::component_prototype_id(ctx, from_id)
+            .await?
+            .is_some()
+            && !AttributeValue::is_set_by_dependent_function(ctx, from_id).await?;
+
+        match kind {
+            PropKind::Object => {
+                for pair in
+                    AttributeValue::get_child_av_id_pairs_in_order(ctx, from_id, to_id).await?
+                {
+                    if let ChildAttributeValuePair::Both(_, from_child_id, to_child_id) = pair {
+                        work_queue.push_back((from_child_id, to_child_id));
+                    }
+                }
+            }
+            _ if set_directly => {
+                let value = AttributeValue::get_by_id(ctx, from_id)
+                    .await?
+                    .view(ctx)
+                    .await?;
+                AttributeValue::update(ctx, to_id, value).await?;
+            }
+            // Values only set on the destination would otherwise survive the promotion, so they
+            // go back to the default, like on the source
+            _ => {
+                if AttributeValue::component_prototype_id(ctx, to_id)
+                    .await?
+                    .is_some()
+                    && !AttributeValue::is_set_by_dependent_function(ctx, to_id).await?
+                {
+                    AttributeValue::use_default_prototype(ctx, to_id).await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
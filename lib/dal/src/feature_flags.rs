@@ -1,15 +1,59 @@
+//! This module contains [`FeatureFlag`], the features which can be gated, and the
+//! [`FeatureFlagService`], which holds the flags enabled when the service booted.
+//!
+//! The boot flags are the defaults for every workspace. A workspace can override the default of
+//! any flag, which is persisted and takes effect without a redeploy. Changing an override
+//! publishes a [`WsPayload::FeatureFlagUpdated`] event so that the frontend can react to it.
+
 use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
 use si_settings::ValueKind;
-use std::collections::HashSet;
-use strum::Display;
+use std::collections::{HashMap, HashSet};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    DalContext, HistoryActor, TransactionsError, UserPk, WorkspacePk, WsEvent, WsEventError,
+    WsEventResult, WsPayload,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum FeatureFlagError {
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type FeatureFlagResult<T> = Result<T, FeatureFlagError>;
 
-#[derive(Debug, Display, Deserialize, Serialize, Clone, clap::ValueEnum, Hash, Eq, PartialEq)]
+#[derive(
+    Debug,
+    Display,
+    Deserialize,
+    Serialize,
+    Clone,
+    Copy,
+    clap::ValueEnum,
+    EnumIter,
+    EnumString,
+    Hash,
+    Eq,
+    PartialEq,
+)]
 #[strum(serialize_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 #[clap(rename_all = "snake_case")]
 pub enum FeatureFlag {
     Secrets,
     ActionsV2,
+    SnapshotContentVerification,
 }
 
 impl From<FeatureFlag> for ValueKind {
@@ -18,6 +62,180 @@ impl From<FeatureFlag> for ValueKind {
     }
 }
 
+/// Whether a [`FeatureFlag`] is enabled for a workspace, and why.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagState {
+    pub flag: FeatureFlag,
+    /// Whether the flag is enabled for workspaces without an override.
+    pub default_enabled: bool,
+    /// The override of the workspace, if it has one.
+    pub override_enabled: Option<bool>,
+    pub enabled: bool,
+}
+
+impl FeatureFlagState {
+    fn new(flag: FeatureFlag, default_enabled: bool, override_enabled: Option<bool>) -> Self {
+        Self {
+            flag,
+            default_enabled,
+            override_enabled,
+            enabled: override_enabled.unwrap_or(default_enabled),
+        }
+    }
+}
+
+impl WsEvent {
+    pub async fn feature_flag_updated(
+        ctx: &DalContext,
+        state: FeatureFlagState,
+    ) -> WsEventResult<Self> {
+        WsEvent::new_for_workspace(ctx, WsPayload::FeatureFlagUpdated(state)).await
+    }
+}
+
+impl FeatureFlag {
+    /// Returns whether the flag is enabled for the workspace of the context, taking its override
+    /// into account.
+    pub async fn is_enabled(self, ctx: &DalContext) -> FeatureFlagResult<bool> {
+        Ok(self.state(ctx).await?.enabled)
+    }
+
+    /// Returns the [`FeatureFlagState`] of the flag for the workspace of the context.
+    pub async fn state(self, ctx: &DalContext) -> FeatureFlagResult<FeatureFlagState> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT enabled FROM workspace_feature_flag_overrides WHERE workspace_pk = $1 AND flag = $2",
+                &[&workspace_pk, &self.to_string()],
+            )
+            .await?;
+        let override_enabled = match maybe_row {
+            Some(row) => Some(row.try_get("enabled")?),
+            None => None,
+        };
+
+        Ok(FeatureFlagState::new(
+            self,
+            default_enabled(ctx, self),
+            override_enabled,
+        ))
+    }
+
+    /// Returns the [`FeatureFlagState`] of every flag for the workspace of the context.
+    pub async fn list_for_workspace(ctx: &DalContext) -> FeatureFlagResult<Vec<FeatureFlagState>> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT flag, enabled FROM workspace_feature_flag_overrides WHERE workspace_pk = $1",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        let mut overrides = HashMap::with_capacity(rows.len());
+        for row in rows {
+            let flag: String = row.try_get("flag")?;
+            // Overrides of flags which have since been removed are ignored
+            match flag.parse::<Self>() {
+                Ok(flag) => {
+                    overrides.insert(flag, row.try_get::<_, bool>("enabled")?);
+                }
+                Err(_) => debug!(%flag, "ignoring override of unknown feature flag"),
+            }
+        }
+
+        Ok(Self::iter()
+            .map(|flag| {
+                FeatureFlagState::new(
+                    flag,
+                    default_enabled(ctx, flag),
+                    overrides.get(&flag).copied(),
+                )
+            })
+            .collect())
+    }
+
+    /// Overrides the default of the flag for the workspace of the context, or goes back to the
+    /// default when `None`. Publishes a [`WsPayload::FeatureFlagUpdated`] event on commit.
+    #[instrument(
+        name = "feature_flag.set_workspace_override",
+        level = "info",
+        skip(ctx)
+    )]
+    pub async fn set_workspace_override(
+        self,
+        ctx: &DalContext,
+        enabled: Option<bool>,
+    ) -> FeatureFlagResult<FeatureFlagState> {
+        let workspace_pk = workspace_pk(ctx)?;
+        let updated_by_user_pk: Option<UserPk> = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        };
+
+        match enabled {
+            Some(enabled) => {
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(
+                        "
+                            INSERT INTO workspace_feature_flag_overrides
+                                (workspace_pk, flag, enabled, updated_by_user_pk)
+                                VALUES
+                                ($1, $2, $3, $4)
+                            ON CONFLICT (workspace_pk, flag) DO
+                            UPDATE SET enabled = $3, updated_by_user_pk = $4, updated_at = CLOCK_TIMESTAMP()
+                        ",
+                        &[
+                            &workspace_pk,
+                            &self.to_string(),
+                            &enabled,
+                            &updated_by_user_pk,
+                        ],
+                    )
+                    .await?;
+            }
+            None => {
+                ctx.txns()
+                    .await?
+                    .pg()
+                    .execute(
+                        "DELETE FROM workspace_feature_flag_overrides WHERE workspace_pk = $1 AND flag = $2",
+                        &[&workspace_pk, &self.to_string()],
+                    )
+                    .await?;
+            }
+        }
+
+        let state = FeatureFlagState::new(self, default_enabled(ctx, self), enabled);
+        WsEvent::feature_flag_updated(ctx, state)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(state)
+    }
+}
+
+fn workspace_pk(ctx: &DalContext) -> FeatureFlagResult<WorkspacePk> {
+    ctx.tenancy()
+        .workspace_pk_opt()
+        .ok_or(FeatureFlagError::NoWorkspaceInTenancy)
+}
+
+fn default_enabled(ctx: &DalContext, flag: FeatureFlag) -> bool {
+    ctx.services_context()
+        .feature_flags_service()
+        .feature_is_enabled(&flag)
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct FeatureFlagService {
     feature_flags: HashSet<FeatureFlag>,
@@ -30,6 +248,8 @@ impl FeatureFlagService {
         }
     }
 
+    /// Returns whether the flag was enabled when the service booted, which is the default for
+    /// every workspace. Use [`FeatureFlag::is_enabled`] to take workspace overrides into account.
     #[allow(unused)]
     pub fn feature_is_enabled(
         &self,
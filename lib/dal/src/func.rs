@@ -14,11 +14,12 @@ use strum::IntoEnumIterator;
 use telemetry::prelude::*;
 use thiserror::Error;
 use ulid::Ulid as CoreUlid;
+use veritech_client::RuntimeVersion;
 
 use crate::change_set::ChangeSetError;
 use crate::func::argument::FuncArgumentId;
 use crate::func::intrinsics::IntrinsicFunc;
-use crate::layer_db_types::{FuncContent, FuncContentV2};
+use crate::layer_db_types::{FuncContent, FuncContentV3};
 use crate::workspace_snapshot::edge_weight::{EdgeWeightKind, EdgeWeightKindDiscriminants};
 use crate::workspace_snapshot::graph::WorkspaceSnapshotGraphError;
 use crate::workspace_snapshot::node_weight::category_node_weight::CategoryNodeKind;
@@ -96,7 +97,7 @@ pub type FuncResult<T> = Result<T, FuncError>;
 
 impl From<Func> for FuncContent {
     fn from(value: Func) -> Self {
-        Self::V2(FuncContentV2 {
+        Self::V3(FuncContentV3 {
             timestamp: value.timestamp,
             display_name: value.display_name,
             description: value.description,
@@ -109,6 +110,7 @@ impl From<Func> for FuncContent {
             code_base64: value.code_base64,
             code_blake3: value.code_blake3,
             is_locked: value.is_locked,
+            min_runtime_version: value.min_runtime_version,
         })
     }
 }
@@ -154,10 +156,11 @@ pub struct Func {
     pub code_base64: Option<String>,
     pub code_blake3: ContentHash,
     pub is_locked: bool,
+    pub min_runtime_version: Option<RuntimeVersion>,
 }
 
 impl Func {
-    pub fn assemble(node_weight: &FuncNodeWeight, content: FuncContentV2) -> Self {
+    pub fn assemble(node_weight: &FuncNodeWeight, content: FuncContentV3) -> Self {
         Self {
             id: node_weight.id().into(),
             name: node_weight.name().to_owned(),
@@ -175,6 +178,7 @@ impl Func {
             code_base64: content.code_base64,
             code_blake3: content.code_blake3,
             is_locked: content.is_locked,
+            min_runtime_version: content.min_runtime_version,
         }
     }
 
@@ -206,6 +210,77 @@ impl Func {
         backend_response_type: FuncBackendResponseType,
         handler: Option<impl Into<String>>,
         code_base64: Option<impl Into<String>>,
+    ) -> FuncResult<Self> {
+        let id = ctx.workspace_snapshot()?.generate_ulid().await?;
+        let lineage_id = ctx.workspace_snapshot()?.generate_ulid().await?;
+        Self::new_inner(
+            ctx,
+            id,
+            lineage_id,
+            name,
+            display_name,
+            description,
+            link,
+            hidden,
+            builtin,
+            backend_kind,
+            backend_response_type,
+            handler,
+            code_base64,
+        )
+        .await
+    }
+
+    /// Creates a [`Func`] with a caller provided id, which is also used as its lineage id so that
+    /// the same func created in different change sets is treated as the same node.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_with_id(
+        ctx: &DalContext,
+        id: FuncId,
+        name: impl Into<String> + Clone,
+        display_name: Option<impl Into<String>>,
+        description: Option<impl Into<String>>,
+        link: Option<impl Into<String>>,
+        hidden: bool,
+        builtin: bool,
+        backend_kind: FuncBackendKind,
+        backend_response_type: FuncBackendResponseType,
+        handler: Option<impl Into<String>>,
+        code_base64: Option<impl Into<String>>,
+    ) -> FuncResult<Self> {
+        Self::new_inner(
+            ctx,
+            id.into(),
+            id.into(),
+            name,
+            display_name,
+            description,
+            link,
+            hidden,
+            builtin,
+            backend_kind,
+            backend_response_type,
+            handler,
+            code_base64,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn new_inner(
+        ctx: &DalContext,
+        id: Ulid,
+        lineage_id: Ulid,
+        name: impl Into<String> + Clone,
+        display_name: Option<impl Into<String>>,
+        description: Option<impl Into<String>>,
+        link: Option<impl Into<String>>,
+        hidden: bool,
+        builtin: bool,
+        backend_kind: FuncBackendKind,
+        backend_response_type: FuncBackendResponseType,
+        handler: Option<impl Into<String>>,
+        code_base64: Option<impl Into<String>>,
     ) -> FuncResult<Self> {
         let timestamp = Timestamp::now();
         let _finalized_once = false;
@@ -226,7 +301,7 @@ impl Func {
             ContentHash::new("".as_bytes())
         };
 
-        let content = FuncContentV2 {
+        let content = FuncContentV3 {
             timestamp,
             display_name: display_name.map(Into::into),
             description: description.map(Into::into),
@@ -239,10 +314,11 @@ impl Func {
             code_base64,
             code_blake3,
             is_locked: false,
+            min_runtime_version: None,
         };
 
         let (hash, _) = ctx.layer_db().cas().write(
-            Arc::new(FuncContent::V2(content.clone()).into()),
+            Arc::new(FuncContent::V3(content.clone()).into()),
             None,
             ctx.events_tenancy(),
             ctx.events_actor(),
@@ -250,8 +326,6 @@ impl Func {
 
         let func_kind = FuncKind::new(backend_kind, backend_response_type)?;
 
-        let id = ctx.workspace_snapshot()?.generate_ulid().await?;
-        let lineage_id = ctx.workspace_snapshot()?.generate_ulid().await?;
         let node_weight =
             NodeWeight::new_func(id, lineage_id, name.clone().into(), func_kind, hash);
 
@@ -340,7 +414,7 @@ impl Func {
         )?;
 
         // migrate if necessary!
-        let inner: FuncContentV2 = content.extract();
+        let inner: FuncContentV3 = content.extract();
 
         Ok(Self::assemble(func_node_weight, inner))
     }
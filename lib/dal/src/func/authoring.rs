@@ -299,7 +299,8 @@ impl FuncAuthoringClient {
         };
 
         if !is_intrinsic {
-            ctx.layer_db()
+            let func_run = ctx
+                .layer_db()
                 .func_run()
                 .set_values_and_set_state_to_success(
                     func_run_value.func_run_id(),
@@ -309,6 +310,7 @@ impl FuncAuthoringClient {
                     ctx.events_actor(),
                 )
                 .await?;
+            FuncRunner::publish_status(ctx, &func_run).await;
         }
 
         Ok(func_run_id)
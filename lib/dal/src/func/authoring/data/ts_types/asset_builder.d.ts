@@ -634,6 +634,7 @@ interface Asset {
     inputSockets: SocketDefinition[];
     outputSockets: SocketDefinition[];
     docLinks: Record<string, string>;
+    traits?: string[];
 }
 interface IAssetBuilder {
     addProp(prop: PropDefinition): this;
@@ -644,6 +645,7 @@ interface IAssetBuilder {
     addOutputSocket(socket: SocketDefinition): this;
     addSiPropValueFrom(siPropValueFrom: SiPropValueFromDefinition): this;
     addDocLink(key: string, value: string): this;
+    addTrait(name: string): this;
     build(): Asset;
 }
 declare class AssetBuilder implements IAssetBuilder {
@@ -657,5 +659,6 @@ declare class AssetBuilder implements IAssetBuilder {
     addOutputSocket(socket: SocketDefinition): this;
     addSiPropValueFrom(siPropValueFrom: SiPropValueFromDefinition): this;
     addDocLink(key: string, value: string): this;
+    addTrait(name: string): this;
     build(): Asset;
 }
\ No newline at end of file
@@ -781,6 +781,7 @@ interface Asset {
   inputSockets: SocketDefinition[];
   outputSockets: SocketDefinition[];
   docLinks: Record<string, string>;
+  traits?: string[];
 }
 
 interface IAssetBuilder {
@@ -800,6 +801,8 @@ interface IAssetBuilder {
 
   addDocLink(key: string, value: string): this;
 
+  addTrait(name: string): this;
+
   build(): Asset;
 }
 
@@ -824,5 +827,7 @@ declare class AssetBuilder implements IAssetBuilder {
 
   addDocLink(key: string, value: string): this;
 
+  addTrait(name: string): this;
+
   build(): Asset;
 }
\ No newline at end of file
@@ -9,7 +9,7 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use veritech_client::{
     ActionRunResultSuccess, BeforeFunction, Client as VeritechClient, FunctionResult,
-    FunctionResultFailureErrorKind, OutputStream, ResolverFunctionResponseType,
+    FunctionResultFailureErrorKind, OutputStream, ResolverFunctionResponseType, RuntimeVersion,
 };
 
 use crate::label_list::ToLabelList;
@@ -99,6 +99,8 @@ pub enum FuncBackendKind {
     Unset,
     Validation,
     Management,
+    /// (Experimental) An attribute function compiled to a WASI module, e.g. from Go or Rust.
+    Wasm,
 }
 
 impl From<FuncBackendKind> for si_events::FuncBackendKind {
@@ -124,6 +126,7 @@ impl From<FuncBackendKind> for si_events::FuncBackendKind {
             FuncBackendKind::Unset => si_events::FuncBackendKind::Unset,
             FuncBackendKind::Validation => si_events::FuncBackendKind::Validation,
             FuncBackendKind::Management => si_events::FuncBackendKind::Management,
+            FuncBackendKind::Wasm => si_events::FuncBackendKind::Wasm,
         }
     }
 }
@@ -151,6 +154,7 @@ impl From<si_events::FuncBackendKind> for FuncBackendKind {
             si_events::FuncBackendKind::Unset => FuncBackendKind::Unset,
             si_events::FuncBackendKind::Validation => FuncBackendKind::Validation,
             si_events::FuncBackendKind::Management => FuncBackendKind::Management,
+            si_events::FuncBackendKind::Wasm => FuncBackendKind::Wasm,
         }
     }
 }
@@ -397,7 +401,14 @@ pub trait FuncDispatch: std::fmt::Debug {
             .handler
             .as_deref()
             .ok_or_else(|| FuncBackendError::DispatchMissingHandler(func.id))?;
-        let value = Self::new(context, code_base64, handler, args, before);
+        let value = Self::new(
+            context,
+            code_base64,
+            handler,
+            args,
+            before,
+            func.min_runtime_version,
+        );
         Ok(value)
     }
 
@@ -447,6 +458,7 @@ pub trait FuncDispatch: std::fmt::Debug {
         handler: &str,
         args: Self::Args,
         before: Vec<BeforeFunction>,
+        min_runtime_version: Option<RuntimeVersion>,
     ) -> Box<Self>;
     async fn dispatch(self: Box<Self>) -> FuncBackendResult<FunctionResult<Self::Output>>;
 }
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use telemetry::tracing::trace;
 use veritech_client::{
     ActionRunRequest, ActionRunResultSuccess, BeforeFunction, FunctionResult, OutputStream,
-    ResourceStatus,
+    ResourceStatus, RuntimeVersion,
 };
 
 use crate::func::backend::{
@@ -31,13 +31,23 @@ impl FuncDispatch for FuncBackendJsAction {
         handler: &str,
         args: Self::Args,
         before: Vec<BeforeFunction>,
+        min_runtime_version: Option<RuntimeVersion>,
     ) -> Box<Self> {
+        // Simulated runs are requested by the caller through the func's args, which is also how
+        // the func itself learns that it is being simulated
+        let simulate = args
+            .0
+            .get("simulate")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
         let request = ActionRunRequest {
             execution_id: context.func_run_id.to_string(), // RIP PAULO - GONE (from si) BUT NOT FORGOTTEN
             handler: handler.into(),
             code_base64: code_base64.into(),
             args: args.0,
             before,
+            min_runtime_version,
+            simulate,
         };
 
         Box::new(Self { context, request })
@@ -66,6 +76,8 @@ impl FuncDispatch for FuncBackendJsAction {
                             group: None,
                             message: message.clone(),
                             timestamp: std::cmp::max(Utc::now().timestamp(), 0) as u64,
+                            sequence: 0,
+                            chunk: None,
                         })
                         .await
                         .map_err(|_| FuncBackendError::SendError)?;
@@ -84,6 +96,8 @@ impl FuncDispatch for FuncBackendJsAction {
                         group: None,
                         message: failure.error().message.to_owned(),
                         timestamp: std::cmp::max(Utc::now().timestamp(), 0) as u64,
+                        sequence: 0,
+                        chunk: None,
                     })
                     .await
                     .map_err(|_| FuncBackendError::SendError)?;
@@ -2,8 +2,9 @@ use async_trait::async_trait;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use veritech_client::{
-    BeforeFunction, FunctionResult, ResolverFunctionComponent, ResolverFunctionRequest,
-    ResolverFunctionResponseType, ResolverFunctionResultSuccess,
+    BeforeFunction, FunctionResult, ResolverFunctionBackend, ResolverFunctionComponent,
+    ResolverFunctionRequest, ResolverFunctionResponseType, ResolverFunctionResultSuccess,
+    RuntimeVersion,
 };
 
 use crate::func::backend::{ExtractPayload, FuncBackendResult, FuncDispatch, FuncDispatchContext};
@@ -12,6 +13,8 @@ use crate::func::backend::{ExtractPayload, FuncBackendResult, FuncDispatch, Func
 pub struct FuncBackendJsAttributeArgs {
     pub component: ResolverFunctionComponent,
     pub response_type: ResolverFunctionResponseType,
+    #[serde(default)]
+    pub backend: ResolverFunctionBackend,
 }
 
 #[derive(Debug)]
@@ -31,6 +34,7 @@ impl FuncDispatch for FuncBackendJsAttribute {
         handler: &str,
         args: Self::Args,
         before: Vec<BeforeFunction>,
+        min_runtime_version: Option<RuntimeVersion>,
     ) -> Box<Self> {
         let request = ResolverFunctionRequest {
             execution_id: context.func_run_id.to_string(),
@@ -39,6 +43,8 @@ impl FuncDispatch for FuncBackendJsAttribute {
             response_type: args.response_type,
             code_base64: code_base64.into(),
             before,
+            min_runtime_version,
+            backend: args.backend,
         };
 
         Box::new(Self { context, request })
@@ -2,7 +2,7 @@ use crate::func::backend::{ExtractPayload, FuncBackendResult, FuncDispatch, Func
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use veritech_client::{
-    BeforeFunction, FunctionResult, SchemaVariantDefinitionRequest,
+    BeforeFunction, FunctionResult, RuntimeVersion, SchemaVariantDefinitionRequest,
     SchemaVariantDefinitionResultSuccess,
 };
 #[derive(Debug, Clone)]
@@ -22,11 +22,13 @@ impl FuncDispatch for FuncBackendJsSchemaVariantDefinition {
         handler: &str,
         _args: Self::Args,
         _before: Vec<BeforeFunction>,
+        min_runtime_version: Option<RuntimeVersion>,
     ) -> Box<Self> {
         let request = SchemaVariantDefinitionRequest {
             execution_id: context.func_run_id.to_string(),
             handler: handler.into(),
             code_base64: code_base64.to_owned(),
+            min_runtime_version,
         };
 
         Box::new(Self { context, request })
@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use veritech_client::{
     BeforeFunction, ComponentViewWithGeometry, FunctionResult, ManagementRequest,
-    ManagementResultSuccess,
+    ManagementResultSuccess, RuntimeVersion,
 };
 
 use crate::func::backend::{FuncBackendResult, FuncDispatch, FuncDispatchContext};
@@ -35,6 +35,7 @@ impl FuncDispatch for FuncBackendManagement {
         handler: &str,
         args: Self::Args,
         before: Vec<BeforeFunction>,
+        min_runtime_version: Option<RuntimeVersion>,
     ) -> Box<Self> {
         let request = ManagementRequest {
             execution_id: context.func_run_id.to_string(),
@@ -44,6 +45,7 @@ impl FuncDispatch for FuncBackendManagement {
             components: args.components,
             current_view: args.current_view,
             before,
+            min_runtime_version,
         };
 
         Box::new(Self { context, request })
@@ -1,7 +1,9 @@
 use crate::func::backend::{ExtractPayload, FuncBackendResult, FuncDispatch, FuncDispatchContext};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use veritech_client::{BeforeFunction, FunctionResult, ValidationRequest, ValidationResultSuccess};
+use veritech_client::{
+    BeforeFunction, FunctionResult, RuntimeVersion, ValidationRequest, ValidationResultSuccess,
+};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct FuncBackendJsAttributeArgs {
@@ -26,6 +28,7 @@ impl FuncDispatch for FuncBackendValidation {
         _handler: &str,
         args: Self::Args,
         _before: Vec<BeforeFunction>,
+        _min_runtime_version: Option<RuntimeVersion>,
     ) -> Box<Self> {
         let request = ValidationRequest {
             execution_id: context.func_run_id.to_string(),
@@ -34,6 +37,7 @@ impl FuncDispatch for FuncBackendValidation {
             handler: "".to_string(),
             code_base64: "".to_string(),
             before: vec![],
+            min_runtime_version: None,
         };
 
         Box::new(Self { context, request })
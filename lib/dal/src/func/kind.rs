@@ -60,11 +60,13 @@ impl FuncKind {
         func_backend_response_type: FuncBackendResponseType,
     ) -> FuncResult<FuncKind> {
         Ok(match func_backend_kind {
-            FuncBackendKind::JsAttribute => match func_backend_response_type {
-                FuncBackendResponseType::CodeGeneration => FuncKind::CodeGeneration,
-                FuncBackendResponseType::Qualification => FuncKind::Qualification,
-                _ => FuncKind::Attribute,
-            },
+            FuncBackendKind::JsAttribute | FuncBackendKind::Wasm => {
+                match func_backend_response_type {
+                    FuncBackendResponseType::CodeGeneration => FuncKind::CodeGeneration,
+                    FuncBackendResponseType::Qualification => FuncKind::Qualification,
+                    _ => FuncKind::Attribute,
+                }
+            }
             FuncBackendKind::JsAction => FuncKind::Action,
             FuncBackendKind::JsAuthentication => FuncKind::Authentication,
             FuncBackendKind::JsSchemaVariantDefinition => FuncKind::SchemaVariantDefinition,
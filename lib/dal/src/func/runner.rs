@@ -7,7 +7,8 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use si_events::{
     ActionId, ActionResultState, CasValue, ContentHash, EncryptedSecretKey, FuncRun,
-    FuncRunBuilder, FuncRunBuilderError, FuncRunId, FuncRunLog, FuncRunLogId, FuncRunValue,
+    FuncRunBuilder, FuncRunBuilderError, FuncRunId, FuncRunLog, FuncRunLogEntry,
+    FuncRunLogEntryPage, FuncRunLogId, FuncRunValue,
 };
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
@@ -15,8 +16,8 @@ use thiserror::Error;
 use tokio::sync::{mpsc, oneshot};
 use veritech_client::{
     encrypt_value_tree, BeforeFunction, FunctionResult, FunctionResultFailure,
-    FunctionResultFailureErrorKind, KillExecutionRequest, OutputStream, ResolverFunctionComponent,
-    VeritechValueEncryptError,
+    FunctionResultFailureErrorKind, KillExecutionRequest, OutputStream, ResolverFunctionBackend,
+    ResolverFunctionComponent, VeritechValueEncryptError,
 };
 
 use crate::attribute::prototype::argument::value_source::ValueSource;
@@ -26,6 +27,9 @@ use crate::attribute::prototype::argument::{
 use crate::component::socket::ComponentInputSocket;
 use crate::management::prototype::ManagementPrototypeId;
 use crate::prop::PropError;
+use crate::provider_credential::{
+    ProviderCredential, ProviderCredentialError, DEFAULT_DERIVED_CREDENTIAL_TTL,
+};
 use crate::schema::variant::root_prop::RootPropChild;
 use crate::workspace::WorkspaceId;
 use crate::{
@@ -35,10 +39,10 @@ use crate::{
     },
     attribute::value::AttributeValueError,
     func::backend::FuncBackendError,
-    ActionPrototypeId, AttributeValue, AttributeValueId, ChangeSet, ChangeSetError, Component,
-    ComponentError, ComponentId, DalContext, EncryptedSecret, Func, FuncBackendKind, FuncError,
-    FuncId, KeyPairError, Prop, PropId, SchemaVariant, SchemaVariantError, Secret, SecretError,
-    WsEvent, WsEventError, WsEventResult, WsPayload,
+    ActionPrototypeId, AttributeValue, AttributeValueId, ChangeSet, ChangeSetError, ChangeSetId,
+    Component, ComponentError, ComponentId, DalContext, EncryptedSecret, Func, FuncBackendKind,
+    FuncError, FuncId, KeyPairError, Prop, PropId, SchemaVariant, SchemaVariantError, Secret,
+    SecretError, WsEvent, WsEventError, WsEventResult, WsPayload,
 };
 use crate::{HistoryEventError, TransactionsError};
 
@@ -59,6 +63,7 @@ use super::backend::{
     validation::FuncBackendValidation,
     FuncBackend, FuncDispatch, FuncDispatchContext, InvalidResolverFunctionTypeError,
 };
+use super::is_intrinsic;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -111,6 +116,8 @@ pub enum FuncRunnerError {
     NoWidgetOptionsForSecretProp(PropId),
     #[error("prop error: {0}")]
     Prop(#[from] PropError),
+    #[error("provider credential error: {0}")]
+    ProviderCredential(#[from] ProviderCredentialError),
     #[error("reconciliation funcs are no longer supported (found: {0})")]
     ReconciliationFuncsNoLongerSupported(FuncId),
     #[error("function run result failure: kind={kind}, message={message}, backend={backend}")]
@@ -150,12 +157,18 @@ pub type FuncRunnerResult<T> = Result<T, FuncRunnerError>;
 
 pub type FuncRunnerValueChannel = oneshot::Receiver<FuncRunnerResult<FuncRunValue>>;
 
+/// The number of log entries returned per page by [`FuncRunner::list_log_entries`].
+pub const FUNC_RUN_LOG_PAGE_SIZE: u64 = 500;
+
 pub struct FuncRunner {
     func_run: Arc<FuncRun>,
 
     func: Func,
     args: serde_json::Value,
     before: Vec<BeforeFunction>,
+    /// Whether the func run is written to the layer db and its status and logs are published.
+    /// Only simulated runs are not, so that they are never mistaken for real runs.
+    persist: bool,
 }
 
 impl FuncRunner {
@@ -280,6 +293,7 @@ impl FuncRunner {
                 func,
                 args,
                 before,
+                persist: true,
             })
         }
 
@@ -427,6 +441,7 @@ impl FuncRunner {
                 func: func.clone(),
                 args,
                 before: vec![],
+                persist: true,
             })
         }
 
@@ -602,6 +617,7 @@ impl FuncRunner {
                 func,
                 args,
                 before: vec![],
+                persist: true,
             })
         }
 
@@ -646,141 +662,191 @@ impl FuncRunner {
     ) -> FuncRunnerResult<FuncRunnerValueChannel> {
         let span = current_span_for_instrument_at!("info");
 
-        // Prepares the function for execution.
-        //
-        // Note: this function is internal so we can record early-returning errors in span metadata
-        // and in order to time the function's preparation vs. execution timings.
-        #[instrument(
-            name = "func_runner.run_attribute_value.prepare",
-            level = "info",
-            skip_all,
-            fields()
-        )]
-        #[inline]
-        async fn prepare(
-            ctx: &DalContext,
-            attribute_value_id: AttributeValueId,
-            func_id: FuncId,
-            args: serde_json::Value,
-            parent_span: &Span,
-        ) -> FuncRunnerResult<FuncRunner> {
-            let func = Func::get_by_id_or_error(ctx, func_id).await?;
+        let runner = Self::prepare_attribute_value(ctx, attribute_value_id, func_id, args, &span)
+            .await
+            .map_err(|err| span.record_err(err))?;
 
-            let function_args: CasValue = args.clone().into();
+        let result_channel = runner.execute(ctx.clone(), span).await;
 
-            let component_id = AttributeValue::component_id(ctx, attribute_value_id).await?;
-            let before = FuncRunner::before_funcs(ctx, component_id).await?;
+        Ok(result_channel)
+    }
 
-            let func_run_create_time = Utc::now();
-            let mut func_run_builder = FuncRunBuilder::default();
+    /// Records a [`FuncRun`] for an attribute function without dispatching it, using the values
+    /// produced by an identical execution (same function code and arguments) for another
+    /// [`AttributeValue`] earlier in the same job.
+    ///
+    /// The new run belongs to the given [`AttributeValue`] and its [`Component`], so the run
+    /// history of every value is complete even when executions were shared.
+    #[instrument(
+        name = "func_runner.run_attribute_value_memoized",
+        level = "info",
+        skip_all,
+        fields(
+            job.id = Empty,
+            job.invoked_args = Empty,
+            job.invoked_name = Empty,
+            otel.kind = SpanKind::Internal.as_str(),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+            si.attribute_value.id = Empty,
+            si.component.id = Empty,
+            si.func_run.func.args = Empty,
+            si.func_run.func.backend_kind = Empty,
+            si.func_run.func.backend_response_type = Empty,
+            si.func_run.func.id = Empty,
+            si.func_run.func.kind = Empty,
+            si.func_run.func.name = Empty,
+            si.func_run.id = Empty,
+        )
+    )]
+    pub async fn run_attribute_value_memoized(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+        func_id: FuncId,
+        args: serde_json::Value,
+        memoized: &FuncRunValue,
+    ) -> FuncRunnerResult<FuncRunValue> {
+        let span = current_span_for_instrument_at!("info");
 
-            func_run_builder
-                .actor(ctx.events_actor())
-                .tenancy(ctx.events_tenancy())
-                .backend_kind(func.backend_kind.into())
-                .backend_response_type(func.backend_response_type.into())
-                .function_name(func.name.clone())
-                .function_kind(func.kind.into())
-                .function_display_name(func.display_name.clone())
-                .function_description(func.description.clone())
-                .function_link(func.link.clone())
-                .attribute_value_id(Some(attribute_value_id))
-                .component_id(Some(component_id))
-                .created_at(func_run_create_time)
-                .updated_at(func_run_create_time);
+        let runner = Self::prepare_attribute_value(ctx, attribute_value_id, func_id, args, &span)
+            .await
+            .map_err(|err| span.record_err(err))?;
+
+        span.record_ok();
+        Ok(FuncRunValue::new(
+            runner.func_run.id(),
+            memoized.unprocessed_value().cloned(),
+            memoized.value().cloned(),
+        ))
+    }
+
+    /// Prepares an attribute function for execution, recording its [`FuncRun`].
+    ///
+    /// Note: this function is internal so we can record early-returning errors in span metadata
+    /// and in order to time the function's preparation vs. execution timings.
+    #[instrument(
+        name = "func_runner.run_attribute_value.prepare",
+        level = "info",
+        skip_all,
+        fields()
+    )]
+    #[inline]
+    async fn prepare_attribute_value(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+        func_id: FuncId,
+        args: serde_json::Value,
+        parent_span: &Span,
+    ) -> FuncRunnerResult<FuncRunner> {
+        let func = Func::get_by_id_or_error(ctx, func_id).await?;
+
+        let function_args: CasValue = args.clone().into();
+
+        let component_id = AttributeValue::component_id(ctx, attribute_value_id).await?;
+        let before = FuncRunner::before_funcs(ctx, component_id).await?;
+
+        let func_run_create_time = Utc::now();
+        let mut func_run_builder = FuncRunBuilder::default();
+
+        func_run_builder
+            .actor(ctx.events_actor())
+            .tenancy(ctx.events_tenancy())
+            .backend_kind(func.backend_kind.into())
+            .backend_response_type(func.backend_response_type.into())
+            .function_name(func.name.clone())
+            .function_kind(func.kind.into())
+            .function_display_name(func.display_name.clone())
+            .function_description(func.description.clone())
+            .function_link(func.link.clone())
+            .attribute_value_id(Some(attribute_value_id))
+            .component_id(Some(component_id))
+            .created_at(func_run_create_time)
+            .updated_at(func_run_create_time);
+
+        if !func.is_intrinsic() {
+            let (function_args_cas_address, _) = ctx.layer_db().cas().write(
+                Arc::new(function_args.into()),
+                None,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )?;
 
-            if !func.is_intrinsic() {
-                let (function_args_cas_address, _) = ctx.layer_db().cas().write(
-                    Arc::new(function_args.into()),
+            let code_cas_hash = if let Some(code) = func.code_base64.as_ref() {
+                let code_json_value: serde_json::Value = code.clone().into();
+                let code_cas_value: CasValue = code_json_value.into();
+                let (hash, _) = ctx.layer_db().cas().write(
+                    Arc::new(code_cas_value.into()),
                     None,
                     ctx.events_tenancy(),
                     ctx.events_actor(),
                 )?;
-
-                let code_cas_hash = if let Some(code) = func.code_base64.as_ref() {
-                    let code_json_value: serde_json::Value = code.clone().into();
-                    let code_cas_value: CasValue = code_json_value.into();
-                    let (hash, _) = ctx.layer_db().cas().write(
-                        Arc::new(code_cas_value.into()),
-                        None,
-                        ctx.events_tenancy(),
-                        ctx.events_actor(),
-                    )?;
-                    hash
-                } else {
-                    ContentHash::new("".as_bytes())
-                };
-
-                func_run_builder.function_args_cas_address(function_args_cas_address);
-                func_run_builder.function_code_cas_address(code_cas_hash);
+                hash
             } else {
-                // We could turn these into an option, except we postcard
-                // serialize this data so we'd have to create a new type
-                func_run_builder.function_args_cas_address(ContentHash::new("".as_bytes()));
-                func_run_builder.function_code_cas_address(ContentHash::new("".as_bytes()));
-            }
+                ContentHash::new("".as_bytes())
+            };
 
-            let func_run_inner = func_run_builder.build()?;
+            func_run_builder.function_args_cas_address(function_args_cas_address);
+            func_run_builder.function_code_cas_address(code_cas_hash);
+        } else {
+            // We could turn these into an option, except we postcard
+            // serialize this data so we'd have to create a new type
+            func_run_builder.function_args_cas_address(ContentHash::new("".as_bytes()));
+            func_run_builder.function_code_cas_address(ContentHash::new("".as_bytes()));
+        }
 
-            if !parent_span.is_disabled() {
-                let mut id_buf = FuncRunId::array_to_str_buf();
+        let func_run_inner = func_run_builder.build()?;
 
-                let id = func_run_inner.id().array_to_str(&mut id_buf);
-                parent_span.record("job.id", &id);
-                parent_span.record("si.func_run.id", &id);
+        if !parent_span.is_disabled() {
+            let mut id_buf = FuncRunId::array_to_str_buf();
 
-                let invoked_args = serde_json::to_string(&args)
-                    .unwrap_or_else(|_| "args failed to serialize".to_owned());
-                parent_span.record("job.invoked_args", invoked_args.as_str());
-                parent_span.record("si.func_run.func.args", invoked_args.as_str());
+            let id = func_run_inner.id().array_to_str(&mut id_buf);
+            parent_span.record("job.id", &id);
+            parent_span.record("si.func_run.id", &id);
 
-                parent_span.record("job.invoked_name", func.name.as_str());
-                parent_span.record("si.func_run.func.name", func.name.as_str());
+            let invoked_args = serde_json::to_string(&args)
+                .unwrap_or_else(|_| "args failed to serialize".to_owned());
+            parent_span.record("job.invoked_args", invoked_args.as_str());
+            parent_span.record("si.func_run.func.args", invoked_args.as_str());
 
-                parent_span.record("si.func_run.func.backend_kind", func.backend_kind.as_ref());
-                parent_span.record(
-                    "si.func_run.func.backend_response_type",
-                    func.backend_response_type.as_ref(),
-                );
-                parent_span.record("si.func_run.func.id", func.id.array_to_str(&mut id_buf));
-                parent_span.record("si.func_run.func.kind", func.kind.as_ref());
+            parent_span.record("job.invoked_name", func.name.as_str());
+            parent_span.record("si.func_run.func.name", func.name.as_str());
 
-                parent_span.record(
-                    "si.attribute_value.id",
-                    attribute_value_id.array_to_str(&mut id_buf),
-                );
-                parent_span.record("si.component.id", component_id.array_to_str(&mut id_buf));
-            }
-
-            let func_run = Arc::new(func_run_inner);
-
-            if !func.is_intrinsic() {
-                ctx.layer_db()
-                    .func_run()
-                    .write(
-                        func_run.clone(),
-                        None,
-                        ctx.events_tenancy(),
-                        ctx.events_actor(),
-                    )
-                    .await?;
-            }
+            parent_span.record("si.func_run.func.backend_kind", func.backend_kind.as_ref());
+            parent_span.record(
+                "si.func_run.func.backend_response_type",
+                func.backend_response_type.as_ref(),
+            );
+            parent_span.record("si.func_run.func.id", func.id.array_to_str(&mut id_buf));
+            parent_span.record("si.func_run.func.kind", func.kind.as_ref());
 
-            Ok(FuncRunner {
-                func_run,
-                func,
-                args,
-                before,
-            })
+            parent_span.record(
+                "si.attribute_value.id",
+                attribute_value_id.array_to_str(&mut id_buf),
+            );
+            parent_span.record("si.component.id", component_id.array_to_str(&mut id_buf));
         }
 
-        let runner = prepare(ctx, attribute_value_id, func_id, args, &span)
-            .await
-            .map_err(|err| span.record_err(err))?;
+        let func_run = Arc::new(func_run_inner);
 
-        let result_channel = runner.execute(ctx.clone(), span).await;
+        if !func.is_intrinsic() {
+            ctx.layer_db()
+                .func_run()
+                .write(
+                    func_run.clone(),
+                    None,
+                    ctx.events_tenancy(),
+                    ctx.events_actor(),
+                )
+                .await?;
+        }
 
-        Ok(result_channel)
+        Ok(FuncRunner {
+            func_run,
+            func,
+            args,
+            before,
+            persist: true,
+        })
     }
 
     #[instrument(
@@ -863,7 +929,15 @@ impl FuncRunner {
                 ContentHash::new("".as_bytes())
             };
 
-            let before = FuncRunner::before_funcs(ctx, manager_component_id).await?;
+            let mut before = FuncRunner::before_funcs(ctx, manager_component_id).await?;
+            // Management funcs get the provider credentials they were granted on top of the
+            // secrets of the manager component
+            for credential in
+                ProviderCredential::derive_for_func(ctx, &func, DEFAULT_DERIVED_CREDENTIAL_TTL)
+                    .await?
+            {
+                before.push(credential.into_before_function(ctx)?);
+            }
             let manager_component = Component::get_by_id(ctx, manager_component_id).await?;
             let component_name = manager_component.name(ctx).await?;
             let schema_name = manager_component.schema(ctx).await?.name;
@@ -949,6 +1023,7 @@ impl FuncRunner {
                 func,
                 args,
                 before,
+                persist: true,
             })
         }
 
@@ -968,6 +1043,29 @@ impl FuncRunner {
         Ok(result_channel)
     }
 
+    pub async fn run_action(
+        ctx: &DalContext,
+        action_prototype_id: ActionPrototypeId,
+        component_id: ComponentId,
+        func_id: FuncId,
+        args: serde_json::Value,
+    ) -> FuncRunnerResult<FuncRunnerValueChannel> {
+        Self::run_action_inner(ctx, action_prototype_id, component_id, func_id, args, true).await
+    }
+
+    /// Like [`Self::run_action`], but the func run is neither written to the layer db nor
+    /// published, so that a simulated run never shows up in the history of the action or
+    /// component.
+    pub async fn simulate_action(
+        ctx: &DalContext,
+        action_prototype_id: ActionPrototypeId,
+        component_id: ComponentId,
+        func_id: FuncId,
+        args: serde_json::Value,
+    ) -> FuncRunnerResult<FuncRunnerValueChannel> {
+        Self::run_action_inner(ctx, action_prototype_id, component_id, func_id, args, false).await
+    }
+
     #[instrument(
         name = "func_runner.run_action",
         level = "debug",
@@ -995,12 +1093,13 @@ impl FuncRunner {
             si.workspace.id = Empty,
         )
     )]
-    pub async fn run_action(
+    async fn run_action_inner(
         ctx: &DalContext,
         action_prototype_id: ActionPrototypeId,
         component_id: ComponentId,
         func_id: FuncId,
         args: serde_json::Value,
+        persist: bool,
     ) -> FuncRunnerResult<FuncRunnerValueChannel> {
         let span = current_span_for_instrument_at!("debug");
 
@@ -1021,6 +1120,7 @@ impl FuncRunner {
             component_id: ComponentId,
             func_id: FuncId,
             args: serde_json::Value,
+            persist: bool,
             span: &Span,
         ) -> FuncRunnerResult<FuncRunner> {
             let func = Func::get_by_id_or_error(ctx, func_id).await?;
@@ -1151,27 +1251,38 @@ impl FuncRunner {
 
             let func_run = Arc::new(func_run_inner);
 
-            ctx.layer_db()
-                .func_run()
-                .write(
-                    func_run.clone(),
-                    None,
-                    ctx.events_tenancy(),
-                    ctx.events_actor(),
-                )
-                .await?;
+            if persist {
+                ctx.layer_db()
+                    .func_run()
+                    .write(
+                        func_run.clone(),
+                        None,
+                        ctx.events_tenancy(),
+                        ctx.events_actor(),
+                    )
+                    .await?;
+            }
 
             Ok(FuncRunner {
                 func_run,
                 func,
                 args,
                 before,
+                persist,
             })
         }
 
-        let runner = prepare(ctx, action_prototype_id, component_id, func_id, args, &span)
-            .await
-            .map_err(|err| span.record_err(err))?;
+        let runner = prepare(
+            ctx,
+            action_prototype_id,
+            component_id,
+            func_id,
+            args,
+            persist,
+            &span,
+        )
+        .await
+        .map_err(|err| span.record_err(err))?;
 
         let result_channel = runner.execute(ctx.clone(), span).await;
 
@@ -1199,6 +1310,60 @@ impl FuncRunner {
             return Err(FuncRunnerError::DoNotHavePermissionToKillExecution);
         }
 
+        Self::kill_execution_unchecked(ctx, func_run_id).await
+    }
+
+    /// Lists a page of the output lines logged by a func run, in the order they were written.
+    ///
+    /// Pass the `next_cursor` of a page as the `cursor` to fetch the page after it.
+    pub async fn list_log_entries(
+        ctx: &DalContext,
+        func_run_id: FuncRunId,
+        cursor: Option<u64>,
+    ) -> FuncRunnerResult<FuncRunLogEntryPage> {
+        Ok(ctx
+            .layer_db()
+            .func_run_log()
+            .list_entries(func_run_id, cursor, FUNC_RUN_LOG_PAGE_SIZE)
+            .await?)
+    }
+
+    /// Cancels the func runs of a change set which are still in flight, e.g. because the change
+    /// set was abandoned and nobody will look at their results. Cancelled func runs end up in the
+    /// [`Killed`](si_events::FuncRunState::Killed) state.
+    ///
+    /// Func runs which no veritech instance is executing (yet) cannot be cancelled and are left
+    /// alone.
+    #[instrument(
+        name = "func_runner.cancel_executions_for_change_set",
+        level = "info",
+        skip(ctx)
+    )]
+    pub async fn cancel_executions_for_change_set(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> FuncRunnerResult<()> {
+        let func_runs = ctx
+            .layer_db()
+            .func_run()
+            .list_in_flight_for_change_set(ctx.events_tenancy().workspace_pk, change_set_id)
+            .await?
+            .unwrap_or_default();
+
+        for func_run in func_runs {
+            let func_run_id = func_run.id();
+            if let Err(err) = Self::kill_execution_unchecked(ctx, func_run_id).await {
+                debug!(si.error.message = ?err, %func_run_id, "could not cancel func run");
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn kill_execution_unchecked(
+        ctx: &DalContext,
+        func_run_id: FuncRunId,
+    ) -> FuncRunnerResult<()> {
         let result = ctx
             .veritech()
             .kill_execution(&KillExecutionRequest {
@@ -1216,10 +1381,12 @@ impl FuncRunner {
                 // state. For cancellation, there is no other entity. We need to do that here. Because of that, we also
                 // need to know what the result of the cancellation request was. Therefore, this entire operation is
                 // blocking and we do not return a result channel.
-                ctx.layer_db()
+                let func_run = ctx
+                    .layer_db()
                     .func_run()
                     .set_state_to_killed(func_run_id, ctx.events_tenancy(), ctx.events_actor())
                     .await?;
+                Self::publish_status(ctx, &func_run).await;
 
                 // NOTE(nick): we may need to consider action result state as well as other fields on the func run
                 // struct. This will require more testing and investigation. For now, I think what we have will
@@ -1230,6 +1397,32 @@ impl FuncRunner {
         }
     }
 
+    /// Publishes the status of the func run to the workspace, so that clients can show live
+    /// execution state. A func run is published once when it starts running and once when it
+    /// reaches a terminal state. Func runs of intrinsic funcs are left out, since there are far
+    /// too many of them to be worth following. Failing to publish is logged rather than returned,
+    /// as it must not fail the func run itself.
+    pub async fn publish_status(ctx: &DalContext, func_run: &FuncRun) {
+        if is_intrinsic(func_run.function_name()) {
+            return;
+        }
+
+        let result = async {
+            WsEvent::func_run_status_changed(ctx, func_run)
+                .await?
+                .publish_immediately(ctx)
+                .await
+        }
+        .await;
+        if let Err(err) = result {
+            warn!(
+                si.error.message = ?err,
+                si.func_run.id = %func_run.id(),
+                "failed to publish func run status"
+            );
+        }
+    }
+
     fn id(&self) -> FuncRunId {
         self.func_run.id()
     }
@@ -1250,6 +1443,7 @@ impl FuncRunner {
             func_run_id,
             output_stream_rx,
             action_id,
+            persist: self.persist,
         };
 
         let execution_task = FuncRunnerExecutionTask {
@@ -1260,6 +1454,7 @@ impl FuncRunner {
             func: self.func,
             args: self.args,
             before: self.before,
+            persist: self.persist,
             parent_span: execution_parent_span,
         };
 
@@ -1522,6 +1717,7 @@ struct FuncRunnerLogsTask {
     func_run_id: FuncRunId,
     output_stream_rx: mpsc::Receiver<OutputStream>,
     action_id: Option<ActionId>,
+    persist: bool,
 }
 
 impl FuncRunnerLogsTask {
@@ -1538,16 +1734,42 @@ impl FuncRunnerLogsTask {
     }
 
     async fn try_run(mut self) -> FuncRunnerResult<()> {
+        // The output of runs which are not persisted is drained so the execution is not blocked
+        // on it, but is not stored anywhere
+        if !self.persist {
+            while self.output_stream_rx.recv().await.is_some() {}
+            return Ok(());
+        }
+
         let mut func_run_log = FuncRunLog::new(self.func_run_id, self.ctx.events_tenancy());
+        let mut sequence = 0;
         while let Some(item) = self.output_stream_rx.recv().await {
-            func_run_log.push_log(si_events::OutputLine {
+            let output_line = si_events::OutputLine {
                 stream: item.stream,
                 execution_id: item.execution_id,
                 level: item.level,
                 group: item.group,
                 message: item.message,
                 timestamp: item.timestamp,
-            });
+            };
+
+            // Each line is stored as its own entry so that long logs can be read a page at a
+            // time rather than as a whole
+            self.ctx
+                .layer_db()
+                .func_run_log()
+                .write_entries(
+                    self.ctx.events_tenancy(),
+                    &[FuncRunLogEntry::new(
+                        self.func_run_id,
+                        sequence,
+                        output_line.clone(),
+                    )],
+                )
+                .await?;
+            sequence += 1;
+
+            func_run_log.push_log(output_line);
 
             WsEvent::func_run_log_updated(
                 &self.ctx,
@@ -1602,6 +1824,7 @@ struct FuncRunnerExecutionTask {
     func: Func,
     args: serde_json::Value,
     before: Vec<BeforeFunction>,
+    persist: bool,
     parent_span: Span,
 }
 
@@ -1641,11 +1864,14 @@ impl FuncRunnerExecutionTask {
     }
 
     async fn try_run(self) -> FuncRunnerResult<()> {
+        // Whether the state of the func run is written to the layer db as it progresses
+        let records = self.persist && !self.func.is_intrinsic();
+
         let mut running_state_func_run_inner = Arc::unwrap_or_clone(self.func_run.clone());
         running_state_func_run_inner.set_state_to_running();
         let running_state_func_run = Arc::new(running_state_func_run_inner);
 
-        if !self.func.is_intrinsic() {
+        if records {
             self.ctx
                 .layer_db()
                 .func_run()
@@ -1657,6 +1883,9 @@ impl FuncRunnerExecutionTask {
                 )
                 .await?;
         }
+        if self.persist {
+            FuncRunner::publish_status(&self.ctx, &running_state_func_run).await;
+        }
 
         let execution_result = match self.func_run.backend_kind().into() {
             FuncBackendKind::JsAction => {
@@ -1668,7 +1897,7 @@ impl FuncRunnerExecutionTask {
                 )
                 .await
             }
-            FuncBackendKind::JsAttribute => {
+            backend_kind @ (FuncBackendKind::JsAttribute | FuncBackendKind::Wasm) => {
                 let args = FuncBackendJsAttributeArgs {
                     component: ResolverFunctionComponent {
                         data: veritech_client::ComponentView {
@@ -1678,6 +1907,10 @@ impl FuncRunnerExecutionTask {
                         parents: Vec::new(),
                     },
                     response_type: self.func.backend_response_type.try_into()?,
+                    backend: match backend_kind {
+                        FuncBackendKind::Wasm => ResolverFunctionBackend::Wasm,
+                        _ => ResolverFunctionBackend::Js,
+                    },
                 };
                 FuncBackendJsAttribute::create_and_execute(
                     self.func_dispatch_context,
@@ -1771,7 +2004,7 @@ impl FuncRunnerExecutionTask {
                 next_state_inner.set_state_to_post_processing();
                 let next_state = Arc::new(next_state_inner);
 
-                if !self.func.is_intrinsic() {
+                if records {
                     self.ctx
                         .layer_db()
                         .func_run()
@@ -1783,6 +2016,8 @@ impl FuncRunnerExecutionTask {
                         )
                         .await?;
                 }
+                // Post processing is not published, as whoever processes the value publishes
+                // the func run once it is done
 
                 let _ = self.result_tx.send(Ok(FuncRunValue::new(
                     next_state.id(),
@@ -1798,7 +2033,7 @@ impl FuncRunnerExecutionTask {
                 let mut next_state_inner = Arc::unwrap_or_clone(running_state_func_run.clone());
                 next_state_inner.set_state_to_failure();
                 let next_state = Arc::new(next_state_inner);
-                if !self.func.is_intrinsic() {
+                if records {
                     self.ctx
                         .layer_db()
                         .func_run()
@@ -1810,6 +2045,9 @@ impl FuncRunnerExecutionTask {
                         )
                         .await?;
                 }
+                if self.persist {
+                    FuncRunner::publish_status(&self.ctx, &next_state).await;
+                }
 
                 let _ = self.result_tx.send(Err(FuncRunnerError::ResultFailure {
                     kind,
@@ -1822,7 +2060,7 @@ impl FuncRunnerExecutionTask {
                 next_state_inner.set_state_to_failure();
                 let next_state = Arc::new(next_state_inner);
 
-                if !self.func.is_intrinsic() {
+                if records {
                     self.ctx
                         .layer_db()
                         .func_run()
@@ -1834,6 +2072,9 @@ impl FuncRunnerExecutionTask {
                         )
                         .await?;
                 }
+                if self.persist {
+                    FuncRunner::publish_status(&self.ctx, &next_state).await;
+                }
 
                 let _ = self.result_tx.send(Err(err.into()));
             }
@@ -1852,6 +2093,13 @@ pub struct FuncRunLogUpdatedPayload {
 }
 
 impl WsEvent {
+    pub async fn func_run_status_changed(
+        ctx: &DalContext,
+        func_run: &FuncRun,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::FuncRunStatusChanged(func_run.into())).await
+    }
+
     pub async fn func_run_log_updated(
         ctx: &DalContext,
         func_run_id: FuncRunId,
@@ -1,4 +1,5 @@
 pub mod consumer;
+pub mod dead_letter;
 pub mod definition;
 pub mod processor;
 pub mod producer;
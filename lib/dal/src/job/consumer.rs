@@ -17,8 +17,8 @@ use crate::prop::PropError;
 use crate::validation::ValidationError;
 use crate::FuncError;
 use crate::{
-    action::prototype::ActionPrototypeError, action::ActionError,
-    attribute::value::AttributeValueError,
+    action::prototype::ActionPrototypeError, action::run::ActionRunError, action::ActionError,
+    attribute::value::AttributeValueError, job::dead_letter::JobAttempt,
     job::definition::dependent_values_update::DependentValueUpdateError,
     job::producer::BlockingJobError, job::producer::JobProducerError, AccessBuilder,
     ActionPrototypeId, ComponentError, ComponentId, DalContext, DalContextBuilder,
@@ -34,6 +34,8 @@ pub enum JobConsumerError {
     ActionPrototype(#[from] ActionPrototypeError),
     #[error("ActionProtoype {0} not found")]
     ActionPrototypeNotFound(ActionPrototypeId),
+    #[error("action run error: {0}")]
+    ActionRun(#[from] ActionRunError),
     #[error("arg {0:?} not found at index {1}")]
     ArgNotFound(JobInfo, usize),
     #[error("attribute value error: {0}")]
@@ -105,6 +107,12 @@ pub struct JobInfo {
     pub access_builder: AccessBuilder,
     pub visibility: Visibility,
     pub blocking: bool,
+    /// When set, the job is not run before this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_at: Option<DateTime<Utc>>,
+    /// The failed attempts of a dead-lettered job which was replayed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_attempts: Vec<JobAttempt>,
 }
 
 pub enum RetryBackoff {
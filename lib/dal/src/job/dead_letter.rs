@@ -0,0 +1,295 @@
+//! Jobs which failed permanently are dead-lettered: published to a dead letter stream along with
+//! their original payload and attempt history, so that they can be inspected and replayed into the
+//! main queue instead of only leaving log lines behind.
+
+use std::{error, time::Duration};
+
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use pinga_core::{
+    pinga_dead_letter_queue, pinga_work_queue,
+    subject::{dead_letter, dead_letters_for_workspace, pinga_job},
+};
+use serde::{Deserialize, Serialize};
+use si_data_nats::{
+    async_nats::{
+        self,
+        jetstream::{consumer, stream::RawMessageErrorKind},
+    },
+    jetstream, NatsClient,
+};
+use telemetry::prelude::*;
+use telemetry_nats::propagation;
+use thiserror::Error;
+
+use crate::{job::consumer::JobInfo, DalContext, WorkspacePk};
+
+// Listing consumers are only around for a single listing
+const LISTING_INACTIVE_THRESHOLD: Duration = Duration::from_secs(30);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum DeadLetterError {
+    #[error("jetstream error: {0}")]
+    JetStream(#[source] async_nats::Error),
+    #[error("missing required workspace_pk")]
+    MissingWorkspacePk,
+    #[error("dead letter {0} not found")]
+    NotFound(u64),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+impl DeadLetterError {
+    fn jetstream(err: impl Into<async_nats::Error>) -> Self {
+        Self::JetStream(err.into())
+    }
+}
+
+pub type DeadLetterResult<T> = Result<T, DeadLetterError>;
+
+/// A failed attempt at running a job.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobAttempt {
+    pub failed_at: DateTime<Utc>,
+    /// The instance which ran the attempt.
+    pub instance_id: String,
+    /// The error of the attempt, followed by each of its sources.
+    pub error_chain: Vec<String>,
+}
+
+impl JobAttempt {
+    pub fn failed(instance_id: impl Into<String>, err: &(dyn error::Error + 'static)) -> Self {
+        let mut error_chain = vec![err.to_string()];
+        let mut source = err.source();
+        while let Some(err) = source {
+            error_chain.push(err.to_string());
+            source = err.source();
+        }
+
+        Self {
+            failed_at: Utc::now(),
+            instance_id: instance_id.into(),
+            error_chain,
+        }
+    }
+}
+
+/// A job which failed permanently. Its attempts, the last of which dead-lettered it, are in its
+/// [`JobInfo`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetter {
+    pub job_info: JobInfo,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+/// A [`DeadLetter`] in the dead letter stream, identified by its stream sequence.
+#[derive(Clone, Debug)]
+pub struct StoredDeadLetter {
+    pub sequence: u64,
+    pub dead_letter: DeadLetter,
+}
+
+impl DeadLetter {
+    pub fn new(job_info: JobInfo) -> Self {
+        Self {
+            job_info,
+            dead_lettered_at: Utc::now(),
+        }
+    }
+
+    /// The error chain of the attempt which dead-lettered the job.
+    pub fn error_chain(&self) -> &[String] {
+        self.job_info
+            .failed_attempts
+            .last()
+            .map(|attempt| attempt.error_chain.as_slice())
+            .unwrap_or_default()
+    }
+
+    #[instrument(name = "dead_letter.publish", level = "info", skip_all)]
+    pub async fn publish(&self, nats: &NatsClient) -> DeadLetterResult<()> {
+        let prefix = nats.metadata().subject_prefix().map(|s| s.to_owned());
+        let context = jetstream::new(nats.clone());
+        let _stream = pinga_dead_letter_queue(&context, prefix.as_deref())
+            .await
+            .map_err(DeadLetterError::jetstream)?;
+
+        let workspace_pk = self
+            .job_info
+            .access_builder
+            .tenancy()
+            .workspace_pk_opt()
+            .ok_or(DeadLetterError::MissingWorkspacePk)?;
+        let subject = dead_letter(
+            prefix.as_deref(),
+            &workspace_pk.to_string(),
+            &self.job_info.visibility.change_set_id.to_string(),
+            &self.job_info.kind,
+        );
+
+        context
+            .publish_with_headers(
+                subject,
+                propagation::empty_injected_headers(),
+                serde_json::to_vec(self)?.into(),
+            )
+            .await
+            // If `Err` then message failed to publish
+            .map_err(DeadLetterError::jetstream)?
+            .await
+            // If `Err` then NATS server failed to ack
+            .map_err(DeadLetterError::jetstream)?;
+
+        Ok(())
+    }
+
+    /// Lists the oldest dead-lettered jobs of a workspace, up to `limit` of them.
+    #[instrument(name = "dead_letter.list_for_workspace", level = "info", skip(ctx))]
+    pub async fn list_for_workspace(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        limit: usize,
+    ) -> DeadLetterResult<Vec<StoredDeadLetter>> {
+        let prefix = subject_prefix(ctx);
+        let stream = pinga_dead_letter_queue(&ctx.jetstream_context(), prefix.as_deref())
+            .await
+            .map_err(DeadLetterError::jetstream)?;
+
+        let consumer = stream
+            .create_consumer(consumer::pull::Config {
+                filter_subject: dead_letters_for_workspace(
+                    prefix.as_deref(),
+                    &workspace_pk.to_string(),
+                )
+                .to_string(),
+                deliver_policy: consumer::DeliverPolicy::All,
+                ack_policy: consumer::AckPolicy::None,
+                inactive_threshold: LISTING_INACTIVE_THRESHOLD,
+                ..Default::default()
+            })
+            .await
+            .map_err(DeadLetterError::jetstream)?;
+
+        // Fetching does not wait for messages, so this ends once every stored one was fetched
+        let mut messages = consumer
+            .fetch()
+            .max_messages(limit)
+            .messages()
+            .await
+            .map_err(DeadLetterError::jetstream)?;
+
+        let mut dead_letters = Vec::new();
+        while let Some(message) = messages.next().await {
+            let message = message.map_err(DeadLetterError::jetstream)?;
+            let sequence = message
+                .info()
+                .map_err(DeadLetterError::jetstream)?
+                .stream_sequence;
+            dead_letters.push(StoredDeadLetter {
+                sequence,
+                dead_letter: serde_json::from_slice(&message.payload)?,
+            });
+        }
+
+        Ok(dead_letters)
+    }
+
+    #[instrument(name = "dead_letter.get", level = "info", skip(ctx))]
+    pub async fn get(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        sequence: u64,
+    ) -> DeadLetterResult<StoredDeadLetter> {
+        let stream =
+            pinga_dead_letter_queue(&ctx.jetstream_context(), subject_prefix(ctx).as_deref())
+                .await
+                .map_err(DeadLetterError::jetstream)?;
+
+        let message = stream
+            .get_raw_message(sequence)
+            .await
+            .map_err(|err| match err.kind() {
+                // Including dead letters which were replayed, and so deleted
+                RawMessageErrorKind::NoMessageFound => DeadLetterError::NotFound(sequence),
+                _ => DeadLetterError::jetstream(err),
+            })?;
+        let dead_letter: DeadLetter = serde_json::from_slice(&message.payload)?;
+
+        // Dead letters of other workspaces are not found from this one
+        if dead_letter
+            .job_info
+            .access_builder
+            .tenancy()
+            .workspace_pk_opt()
+            != Some(workspace_pk)
+        {
+            return Err(DeadLetterError::NotFound(sequence));
+        }
+
+        Ok(StoredDeadLetter {
+            sequence,
+            dead_letter,
+        })
+    }
+
+    /// Publishes a dead-lettered job back to the main queue and removes it from the dead letter
+    /// stream. The replayed job keeps its attempt history, so that dead-lettering it again
+    /// records every attempt.
+    #[instrument(name = "dead_letter.replay", level = "info", skip(ctx))]
+    pub async fn replay(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        sequence: u64,
+    ) -> DeadLetterResult<JobInfo> {
+        let StoredDeadLetter { dead_letter, .. } = Self::get(ctx, workspace_pk, sequence).await?;
+        let mut job_info = dead_letter.job_info;
+        // Nothing waits on a replayed job and it is due right away
+        job_info.blocking = false;
+        job_info.run_at = None;
+
+        let prefix = subject_prefix(ctx);
+        let context = ctx.jetstream_context();
+        let _stream = pinga_work_queue(&context, prefix.as_deref())
+            .await
+            .map_err(DeadLetterError::jetstream)?;
+
+        let subject = pinga_job(
+            prefix.as_deref(),
+            &workspace_pk.to_string(),
+            &job_info.visibility.change_set_id.to_string(),
+            &job_info.kind,
+        );
+        context
+            .publish_with_headers(
+                subject,
+                propagation::empty_injected_headers(),
+                serde_json::to_vec(&job_info)?.into(),
+            )
+            .await
+            // If `Err` then message failed to publish
+            .map_err(DeadLetterError::jetstream)?
+            .await
+            // If `Err` then NATS server failed to ack
+            .map_err(DeadLetterError::jetstream)?;
+
+        // A dead letter left behind can be replayed again, so this is only worth a warning
+        let dead_letters = pinga_dead_letter_queue(&context, prefix.as_deref())
+            .await
+            .map_err(DeadLetterError::jetstream)?;
+        if let Err(err) = dead_letters.delete_message(sequence).await {
+            warn!(si.error.message = ?err, sequence, "failed to delete replayed dead letter");
+        }
+
+        Ok(job_info)
+    }
+}
+
+fn subject_prefix(ctx: &DalContext) -> Option<String> {
+    ctx.nats_conn()
+        .metadata()
+        .subject_prefix()
+        .map(|s| s.to_owned())
+}
@@ -5,7 +5,7 @@ use std::{
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use si_events::{audit_log::AuditLogKind, ActionResultState, FuncRunId};
+use si_events::{audit_log::AuditLogKind, ActionResultState, ContentHash, FuncRunId};
 use telemetry::prelude::*;
 use telemetry_utils::metric;
 use veritech_client::{ActionRunResultSuccess, ResourceStatus};
@@ -13,6 +13,7 @@ use veritech_client::{ActionRunResultSuccess, ResourceStatus};
 use crate::{
     action::{
         prototype::{ActionKind, ActionPrototype},
+        run::{ActionRun, ActionRunStatus},
         Action, ActionError, ActionId, ActionState,
     },
     billing_publish,
@@ -139,14 +140,22 @@ async fn inner_run(
     ctx: &mut DalContext,
     action_id: ActionId,
 ) -> JobConsumerResult<Option<ActionRunResultSuccess>> {
-    let (prototype_id, component_id) = prepare_for_execution(ctx, action_id).await?;
+    let (prototype_id, component_id, mut action_run) =
+        prepare_for_execution(ctx, action_id).await?;
 
     // Execute the action function
     let (maybe_resource, func_run_id) =
         ActionPrototype::run(ctx, prototype_id, component_id).await?;
 
     // process the result
-    process_execution(ctx, maybe_resource.as_ref(), action_id, func_run_id).await?;
+    process_execution(
+        ctx,
+        maybe_resource.as_ref(),
+        action_id,
+        &mut action_run,
+        func_run_id,
+    )
+    .await?;
 
     // if the action kind was a delete, let's see if any components are ready to be removed that weren't already
     let prototype = ActionPrototype::get_by_id(ctx, prototype_id).await?;
@@ -178,7 +187,7 @@ async fn inner_run(
 async fn prepare_for_execution(
     ctx: &mut DalContext,
     action_id: ActionId,
-) -> JobConsumerResult<(ActionPrototypeId, ComponentId)> {
+) -> JobConsumerResult<(ActionPrototypeId, ComponentId, ActionRun)> {
     let span = Span::current();
 
     let component_id = Action::component_id(ctx, action_id)
@@ -191,7 +200,19 @@ async fn prepare_for_execution(
     span.record("si.component.id", tracing::field::debug(&component_id));
     Action::set_state(ctx, action_id, ActionState::Running).await?;
 
-    // Updates the action's state
+    let originating_change_set_id = Action::get_by_id(ctx, action_id)
+        .await?
+        .originating_changeset_id();
+    let action_run = ActionRun::start(
+        ctx,
+        action_id,
+        originating_change_set_id,
+        component_id,
+        prototype.kind,
+    )
+    .await?;
+
+    // Updates the action's state and records the start of the run
     ctx.commit().await?;
     ctx.update_snapshot_to_visibility().await?;
 
@@ -199,7 +220,7 @@ async fn prepare_for_execution(
         .await?
         .ok_or(ActionError::ComponentNotFoundForAction(action_id))?;
 
-    Ok((prototype_id, component_id))
+    Ok((prototype_id, component_id, action_run))
 }
 
 #[instrument(name = "action_job.process_execution",
@@ -209,6 +230,7 @@ async fn process_execution(
     ctx: &mut DalContext,
     action_run_result: Option<&ActionRunResultSuccess>,
     action_id: ActionId,
+    action_run: &mut ActionRun,
     func_run_id: FuncRunId,
 ) -> JobConsumerResult<()> {
     let prototype_id = Action::prototype_id(ctx, action_id).await?;
@@ -300,6 +322,21 @@ async fn process_execution(
         Action::set_state(ctx, action_id, ActionState::Failed).await?;
     }
 
+    action_run
+        .finish(
+            ctx,
+            if success {
+                ActionRunStatus::Succeeded
+            } else {
+                ActionRunStatus::Failed
+            },
+            Some(func_run_id),
+            action_run_result
+                .and_then(|run_result| run_result.payload.as_ref())
+                .map(ContentHash::from),
+        )
+        .await?;
+
     WsEvent::action_list_updated(ctx)
         .await?
         .publish_on_commit(ctx)
@@ -333,6 +370,7 @@ async fn process_failed_action(ctx: &DalContext, action_id: ActionId) -> JobCons
     info!(%action_id, "processing action failed");
 
     Action::set_state(ctx, action_id, ActionState::Failed).await?;
+    ActionRun::fail_unfinished_for_action(ctx, action_id).await?;
 
     ctx.layer_db()
         .func_run()
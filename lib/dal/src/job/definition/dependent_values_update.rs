@@ -18,7 +18,9 @@ use tokio::{
 use ulid::Ulid;
 
 use crate::{
-    attribute::value::{dependent_value_graph::DependentValueGraph, AttributeValueError},
+    attribute::value::{
+        dependent_value_graph::DependentValueGraph, AttributeFuncMemo, AttributeValueError,
+    },
     job::{
         consumer::{
             JobCompletionState, JobConsumer, JobConsumerError, JobConsumerMetadata,
@@ -278,6 +280,8 @@ impl DependentValuesUpdate {
         metric!(counter.dvu.values_to_run = all_value_ids.len());
 
         let mut tracker = StatusUpdateTracker::new_for_values(ctx, all_value_ids).await?;
+        // Results of identical executions are shared between the values of this run only
+        let memo = Arc::new(AttributeFuncMemo::new());
 
         let mut spawned_ids = HashSet::new();
         let mut task_id_to_av_id = HashMap::new();
@@ -332,6 +336,7 @@ impl DependentValuesUpdate {
                             attribute_value_id,
                             before_value,
                             self.set_value_lock.clone(),
+                            memo.clone(),
                             status_update,
                         ));
                         task_id_to_av_id.insert(id, attribute_value_id);
@@ -513,6 +518,7 @@ type PrototypeFunctionExecutionResult = (
 
 /// Wrapper around `AttributeValue.values_from_prototype_function_execution(&ctx)` to get it to
 /// play more nicely with being spawned into a `JoinSet`.
+#[allow(clippy::too_many_arguments)]
 #[instrument(
     name = "dependent_values_update.values_from_prototype_function_execution",
     level = "info",
@@ -529,6 +535,7 @@ async fn values_from_prototype_function_execution(
     attribute_value_id: AttributeValueId,
     before_value: Option<serde_json::Value>,
     set_value_lock: Arc<RwLock<()>>,
+    memo: Arc<AttributeFuncMemo>,
     status_update: Option<StatusUpdate>,
 ) -> PrototypeFunctionExecutionResult {
     metric!(counter.dvu.function_execution = 1);
@@ -539,10 +546,14 @@ async fn values_from_prototype_function_execution(
         }
     }
 
-    let result =
-        AttributeValue::execute_prototype_function(&ctx, attribute_value_id, set_value_lock)
-            .await
-            .map_err(Into::into);
+    let result = AttributeValue::execute_prototype_function(
+        &ctx,
+        attribute_value_id,
+        set_value_lock,
+        Some(&memo),
+    )
+    .await
+    .map_err(Into::into);
 
     (task_id, result, before_value)
 }
@@ -39,6 +39,8 @@ pub trait JobQueueProcessor: std::fmt::Debug + DynClone {
     ) -> BlockingJobResult;
     async fn process_queue(&self, queue: JobQueue) -> JobQueueProcessorResult<()>;
     async fn blocking_process_queue(&self, queue: JobQueue) -> JobQueueProcessorResult<()>;
+    /// Checks that jobs can currently be queued, without queueing any.
+    async fn check_ready(&self) -> JobQueueProcessorResult<()>;
 }
 
 dyn_clone::clone_trait_object!(JobQueueProcessor);
@@ -1,10 +1,14 @@
 use async_trait::async_trait;
 use futures::StreamExt;
-use pinga_core::{pinga_work_queue, subject::pinga_job, REPLY_INBOX_HEADER_NAME};
-use si_data_nats::{jetstream, NatsClient, Subject};
+use pinga_core::{
+    pinga_scheduled_queue, pinga_work_queue,
+    subject::{pinga_job, scheduled_job},
+    REPLY_INBOX_HEADER_NAME, RUN_AT_HEADER_NAME,
+};
+use si_data_nats::{jetstream, HeaderMap, NatsClient, Subject};
+use si_std::BoundedTaskGroup;
 use telemetry::prelude::*;
 use telemetry_nats::propagation;
-use tokio::task::JoinSet;
 
 use crate::job::{
     consumer::JobInfo,
@@ -14,6 +18,9 @@ use crate::job::{
 
 use super::{JobQueueProcessor, JobQueueProcessorError, JobQueueProcessorResult};
 
+/// The maximum number of blocking jobs awaited concurrently by a single processor.
+const MAX_CONCURRENT_BLOCKING_JOBS: usize = 64;
+
 #[derive(Clone, Debug)]
 pub struct NatsProcessor {
     client: NatsClient,
@@ -48,33 +55,82 @@ impl NatsProcessor {
 
         while let Some(element) = queue.fetch_job().await {
             let job_info = JobInfo::new(element)?;
+            let subject = self.job_subject(&job_info, pinga_job)?;
+            self.publish_job(subject, &job_info, headers.clone())
+                .await?;
+        }
+        self.push_scheduled_jobs(&queue).await?;
+
+        Ok(())
+    }
+
+    /// Publishes the scheduled jobs of the queue to their own stream, which pinga moves them out of
+    /// into the work queue once they are due.
+    #[instrument(
+        name = "nats_processor.push_scheduled_jobs",
+        level = "debug",
+        skip_all,
+        fields()
+    )]
+    async fn push_scheduled_jobs(&self, queue: &JobQueue) -> JobQueueProcessorResult<()> {
+        let mut scheduled = Vec::new();
+        while let Some(element) = queue.fetch_scheduled_job().await {
+            scheduled.push(element);
+        }
+        if scheduled.is_empty() {
+            return Ok(());
+        }
+
+        // Ensure the Jetstream `Stream` is created before publishing to it
+        let _stream = pinga_scheduled_queue(&self.context, self.prefix.as_deref()).await?;
+
+        for (element, run_at) in scheduled {
+            let job_info = JobInfo::new_scheduled(element, run_at)?;
+            let subject = self.job_subject(&job_info, scheduled_job)?;
+
+            let mut headers = propagation::empty_injected_headers();
+            headers.insert(RUN_AT_HEADER_NAME, run_at.to_rfc3339());
 
-            let workspace_pk = job_info
-                .access_builder
-                .tenancy()
-                .workspace_pk_opt()
-                .ok_or(JobQueueProcessorError::MissingWorkspacePk)?;
-
-            let subject = pinga_job(
-                self.prefix.as_deref(),
-                &String::from(workspace_pk),
-                &String::from(job_info.visibility.change_set_id),
-                &job_info.kind,
-            );
-
-            self.context
-                .publish_with_headers(
-                    subject,
-                    headers.clone(),
-                    serde_json::to_vec(&job_info)?.into(),
-                )
-                .await
-                // If `Err` then message failed to publish
-                .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?
-                .await
-                // If `Err` then NATS server failed to ack
-                .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?;
+            self.publish_job(subject, &job_info, headers).await?;
         }
+
+        Ok(())
+    }
+
+    fn job_subject(
+        &self,
+        job_info: &JobInfo,
+        subject_for: fn(Option<&str>, &str, &str, &str) -> Subject,
+    ) -> JobQueueProcessorResult<Subject> {
+        let workspace_pk = job_info
+            .access_builder
+            .tenancy()
+            .workspace_pk_opt()
+            .ok_or(JobQueueProcessorError::MissingWorkspacePk)?;
+
+        Ok(subject_for(
+            self.prefix.as_deref(),
+            &String::from(workspace_pk),
+            &String::from(job_info.visibility.change_set_id),
+            &job_info.kind,
+        ))
+    }
+
+    async fn publish_job(
+        &self,
+        subject: Subject,
+        job_info: &JobInfo,
+        headers: HeaderMap,
+    ) -> JobQueueProcessorResult<()> {
+        self.context
+            .publish_with_headers(subject, headers, serde_json::to_vec(job_info)?.into())
+            .await
+            // If `Err` then message failed to publish
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?
+            .await
+            // If `Err` then NATS server failed to ack
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?;
+
         Ok(())
     }
 }
@@ -148,7 +204,7 @@ impl JobQueueProcessor for NatsProcessor {
     ) -> BlockingJobResult {
         let span = Span::current();
 
-        let mut dispatched_jobs = JoinSet::new();
+        let mut dispatched_jobs = BoundedTaskGroup::new(MAX_CONCURRENT_BLOCKING_JOBS);
 
         // Fan out, dispatching all queued jobs to pinga over nats.
         for job in jobs {
@@ -173,12 +229,20 @@ impl JobQueueProcessor for NatsProcessor {
                 Some(Ok(Err(job_error))) => {
                     results.push(job_error);
                 }
-                Some(Err(join_err)) => {
-                    results.push(BlockingJobError::JobExecution(join_err.to_string()));
+                Some(Err(task_err)) => {
+                    results.push(BlockingJobError::JobExecution(task_err.to_string()));
                 }
             }
         }
 
+        let metrics = dispatched_jobs.metrics();
+        debug!(
+            spawned = metrics.spawned,
+            completed = metrics.completed,
+            failed = metrics.failed,
+            "finished blocking on jobs",
+        );
+
         if !results.is_empty() {
             Err(BlockingJobError::JobExecution(
                 results
@@ -223,6 +287,9 @@ impl JobQueueProcessor for NatsProcessor {
 
         span.record("queue.size", queue.size().await);
 
+        // Scheduled jobs are not due yet, so there is nothing to block on for them
+        self.push_scheduled_jobs(&queue).await?;
+
         let mut jobs = Vec::with_capacity(queue.size().await);
         while let Some(element) = queue.fetch_job().await {
             jobs.push(element);
@@ -233,4 +300,12 @@ impl JobQueueProcessor for NatsProcessor {
 
         Ok(())
     }
+
+    #[instrument(name = "nats_processor.check_ready", level = "debug", skip_all)]
+    async fn check_ready(&self) -> JobQueueProcessorResult<()> {
+        // Looking up the work queue stream requires a round trip to Jetstream, which is what
+        // queueing a job relies on
+        let _stream = pinga_work_queue(&self.context, self.prefix.as_deref()).await?;
+        Ok(())
+    }
 }
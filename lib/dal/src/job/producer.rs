@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ulid::Ulid;
@@ -53,6 +53,8 @@ impl JobInfo {
             access_builder: job_producer.access_builder(),
             visibility: job_producer.visibility(),
             blocking: false,
+            run_at: None,
+            failed_attempts: Vec::new(),
         })
     }
 
@@ -67,6 +69,17 @@ impl JobInfo {
             access_builder: job_producer.access_builder(),
             visibility: job_producer.visibility(),
             blocking: true,
+            run_at: None,
+            failed_attempts: Vec::new(),
         })
     }
+
+    pub fn new_scheduled(
+        job_producer: Box<dyn JobProducer + Send + Sync>,
+        run_at: DateTime<Utc>,
+    ) -> JobProducerResult<Self> {
+        let mut job_info = Self::new(job_producer)?;
+        job_info.run_at = Some(run_at);
+        Ok(job_info)
+    }
 }
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     sync::Arc,
@@ -19,10 +20,13 @@ type AttributeValueBasedJobs = Arc<
     >,
 >;
 
+type ScheduledJobs = Arc<Mutex<VecDeque<(Box<dyn JobProducer + Send + Sync>, DateTime<Utc>)>>>;
+
 #[derive(Debug, Clone, Default)]
 pub struct JobQueue {
     queue: Arc<Mutex<VecDeque<Box<dyn JobProducer + Send + Sync>>>>,
     attribute_value_based_jobs: AttributeValueBasedJobs,
+    scheduled_jobs: ScheduledJobs,
 }
 
 impl JobQueue {
@@ -30,6 +34,7 @@ impl JobQueue {
         Self {
             queue: Default::default(),
             attribute_value_based_jobs: Default::default(),
+            scheduled_jobs: Default::default(),
         }
     }
 
@@ -55,6 +60,21 @@ impl JobQueue {
         lock.push_back(job);
     }
 
+    /// Enqueues a job which is not to run before `run_at`.
+    pub async fn enqueue_scheduled_job(
+        &self,
+        job: Box<dyn JobProducer + Send + Sync>,
+        run_at: DateTime<Utc>,
+    ) {
+        self.scheduled_jobs.lock().await.push_back((job, run_at));
+    }
+
+    pub async fn fetch_scheduled_job(
+        &self,
+    ) -> Option<(Box<dyn JobProducer + Send + Sync>, DateTime<Utc>)> {
+        self.scheduled_jobs.lock().await.pop_front()
+    }
+
     pub async fn fetch_job(&self) -> Option<Box<dyn JobProducer + Send + Sync>> {
         match self.queue.lock().await.pop_front() {
             Some(job) => Some(job),
@@ -144,10 +164,12 @@ impl JobQueue {
     pub async fn is_empty(&self) -> bool {
         self.queue.lock().await.is_empty()
             && self.attribute_value_based_jobs.lock().await.is_empty()
+            && self.scheduled_jobs.lock().await.is_empty()
     }
 
     pub async fn size(&self) -> usize {
         self.queue.lock().await.len()
             + (!self.attribute_value_based_jobs.lock().await.is_empty() as usize)
+            + self.scheduled_jobs.lock().await.len()
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -6,8 +6,11 @@ use si_events::ulid::Ulid;
 use si_events::{CasValue, ContentHash};
 use strum::EnumDiscriminants;
 use thiserror::Error;
+use veritech_client::RuntimeVersion;
 
 use crate::action::prototype::ActionKind;
+use crate::attribute::prototype::argument::static_value::StaticArgumentValueKind;
+use crate::environment::Environment;
 use crate::validation::ValidationStatus;
 use crate::{
     action::ActionCompletionStatus, func::argument::FuncArgumentKind, prop::WidgetOptions,
@@ -60,6 +63,7 @@ pub enum ContentTypes {
     ManagementPrototype(ManagementPrototypeContent),
     Geometry(GeometryContent),
     View(ViewContent),
+    Annotation(AnnotationContent),
 }
 
 macro_rules! impl_into_content_types {
@@ -117,6 +121,7 @@ impl_into_content_types!(Validation);
 impl_into_content_types!(ManagementPrototype);
 impl_into_content_types!(Geometry);
 impl_into_content_types!(View);
+impl_into_content_types!(Annotation);
 
 // Here we've broken the Foo, FooContent convention so we need to implement
 // these traits manually
@@ -222,15 +227,21 @@ pub struct AttributePrototypeContentV1 {
 pub enum ComponentContent {
     V1(ComponentContentV1),
     V2(ComponentContentV2),
+    V3(ComponentContentV3),
 }
 
 impl ComponentContent {
-    pub fn extract(self) -> ComponentContentV2 {
+    pub fn extract(self) -> ComponentContentV3 {
         match self {
-            ComponentContent::V1(v1) => ComponentContentV2 {
+            ComponentContent::V1(v1) => ComponentContentV3 {
                 timestamp: v1.timestamp,
+                labels: BTreeMap::new(),
             },
-            ComponentContent::V2(v2) => v2,
+            ComponentContent::V2(v2) => ComponentContentV3 {
+                timestamp: v2.timestamp,
+                labels: BTreeMap::new(),
+            },
+            ComponentContent::V3(v3) => v3,
         }
     }
 }
@@ -249,15 +260,28 @@ pub struct ComponentContentV2 {
     pub timestamp: Timestamp,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ComponentContentV3 {
+    pub timestamp: Timestamp,
+    pub labels: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum ViewContent {
     V1(ViewContentV1),
+    V2(ViewContentV2),
 }
 
 impl ViewContent {
-    pub fn extract(self) -> ViewContentV1 {
-        let ViewContent::V1(content) = self;
-        content
+    pub fn extract(self) -> ViewContentV2 {
+        match self {
+            ViewContent::V1(v1) => ViewContentV2 {
+                timestamp: v1.timestamp,
+                name: v1.name,
+                environment: None,
+            },
+            ViewContent::V2(v2) => v2,
+        }
     }
 }
 
@@ -267,6 +291,13 @@ pub struct ViewContentV1 {
     pub name: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ViewContentV2 {
+    pub timestamp: Timestamp,
+    pub name: String,
+    pub environment: Option<Environment>,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum GeometryContent {
     V1(GeometryContentV1),
@@ -288,10 +319,33 @@ pub struct GeometryContentV1 {
     pub height: Option<String>,
 }
 
+#[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
+pub enum AnnotationContent {
+    V1(AnnotationContentV1),
+}
+
+impl AnnotationContent {
+    pub fn extract(self) -> AnnotationContentV1 {
+        let AnnotationContent::V1(content) = self;
+        content
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct AnnotationContentV1 {
+    pub timestamp: Timestamp,
+    /// Markdown
+    pub body: String,
+    pub author: Option<UserPk>,
+    pub x: isize,
+    pub y: isize,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum FuncContent {
     V1(FuncContentV1),
     V2(FuncContentV2),
+    V3(FuncContentV3),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -326,10 +380,29 @@ pub struct FuncContentV2 {
     pub is_locked: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FuncContentV3 {
+    pub timestamp: Timestamp,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub hidden: bool,
+    pub builtin: bool,
+    pub backend_response_type: FuncBackendResponseType,
+    pub backend_kind: FuncBackendKind,
+    pub handler: Option<String>,
+    pub code_base64: Option<String>,
+    /// A hash of the code above
+    pub code_blake3: ContentHash,
+    pub is_locked: bool,
+    /// The oldest lang server version able to run the code above
+    pub min_runtime_version: Option<RuntimeVersion>,
+}
+
 impl FuncContent {
-    pub fn extract(self) -> FuncContentV2 {
+    pub fn extract(self) -> FuncContentV3 {
         match self {
-            FuncContent::V1(v1) => FuncContentV2 {
+            FuncContent::V1(v1) => FuncContentV3 {
                 timestamp: v1.timestamp,
                 hidden: v1.hidden,
                 display_name: v1.display_name,
@@ -342,8 +415,24 @@ impl FuncContent {
                 handler: v1.handler,
                 code_base64: v1.code_base64,
                 code_blake3: v1.code_blake3,
+                min_runtime_version: None,
             },
-            FuncContent::V2(v2) => v2,
+            FuncContent::V2(v2) => FuncContentV3 {
+                timestamp: v2.timestamp,
+                hidden: v2.hidden,
+                display_name: v2.display_name,
+                link: v2.link,
+                description: v2.description,
+                is_locked: v2.is_locked,
+                builtin: v2.builtin,
+                backend_response_type: v2.backend_response_type,
+                backend_kind: v2.backend_kind,
+                handler: v2.handler,
+                code_base64: v2.code_base64,
+                code_blake3: v2.code_blake3,
+                min_runtime_version: None,
+            },
+            FuncContent::V3(v3) => v3,
         }
     }
 }
@@ -652,6 +741,20 @@ pub struct SecretContentV1 {
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum StaticArgumentValueContent {
     V1(StaticArgumentValueContentV1),
+    V2(StaticArgumentValueContentV2),
+}
+
+impl StaticArgumentValueContent {
+    pub fn extract(self) -> StaticArgumentValueContentV2 {
+        match self {
+            StaticArgumentValueContent::V1(v1) => StaticArgumentValueContentV2 {
+                timestamp: v1.timestamp,
+                value: v1.value,
+                kind: StaticArgumentValueKind::Value,
+            },
+            StaticArgumentValueContent::V2(v2) => v2,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -660,6 +763,13 @@ pub struct StaticArgumentValueContentV1 {
     pub value: si_events::CasValue,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct StaticArgumentValueContentV2 {
+    pub timestamp: Timestamp,
+    pub value: si_events::CasValue,
+    pub kind: StaticArgumentValueKind,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum ValidationContent {
     V1(ValidationContentV1),
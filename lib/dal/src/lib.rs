@@ -21,6 +21,8 @@ use tokio::time::Instant;
 
 pub mod action;
 pub mod actor_view;
+pub mod advisory_lock;
+pub mod api_token;
 pub mod attribute;
 pub mod audit_logging;
 pub mod authentication_prototype;
@@ -31,9 +33,11 @@ pub mod change_set;
 pub mod change_status;
 pub mod code_view;
 pub mod component;
+pub mod content_gc;
 pub mod context;
 pub mod dependency_graph;
 pub mod diagram;
+pub mod environment;
 pub mod feature_flags;
 pub mod func;
 pub mod history_event;
@@ -49,12 +53,17 @@ pub mod pkg;
 pub mod prompt_override;
 pub mod prop;
 pub mod property_editor;
+pub mod provider_credential;
 pub mod qualification;
 pub mod resource_metadata;
 pub mod schema;
+pub mod search;
 pub mod secret;
 pub mod serde_impls;
+pub mod share_link;
 pub mod slow_rt;
+pub mod snapshot_pruning;
+pub mod snapshot_subscription;
 pub mod socket;
 pub mod standard_accessors;
 pub mod standard_connection;
@@ -66,7 +75,9 @@ pub mod user;
 pub mod validation;
 pub mod visibility;
 pub mod workspace;
+pub mod workspace_hooks;
 pub mod workspace_integrations;
+pub mod workspace_role;
 pub mod workspace_snapshot;
 pub mod ws_event;
 
@@ -17,6 +17,22 @@ pub async fn generate_template(
     view_id: ViewId,
     component_ids: &[ComponentId],
 ) -> ManagementResult<(ManagementCreateOperations, Vec<SchemaId>)> {
+    let (creates, schema_ids, _) =
+        generate_template_with_placeholders(ctx, view_id, component_ids).await?;
+
+    Ok((creates, schema_ids))
+}
+
+/// Like [`generate_template`], but also returns the placeholder each component was given.
+pub(crate) async fn generate_template_with_placeholders(
+    ctx: &DalContext,
+    view_id: ViewId,
+    component_ids: &[ComponentId],
+) -> ManagementResult<(
+    ManagementCreateOperations,
+    Vec<SchemaId>,
+    HashMap<ComponentId, String>,
+)> {
     #[derive(Debug, Clone)]
     struct ConnectionInfo {
         from_socket_name: String,
@@ -202,6 +218,7 @@ pub async fn generate_template(
     Ok((
         creates,
         schema_names.keys().map(ToOwned::to_owned).collect(),
+        placeholders_by_component_id,
     ))
 }
 
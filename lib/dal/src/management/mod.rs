@@ -184,8 +184,8 @@ pub struct ConnectionIdentifier {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ManagementConnection {
-    from: String,
-    to: ConnectionIdentifier,
+    pub(crate) from: String,
+    pub(crate) to: ConnectionIdentifier,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -216,11 +216,11 @@ pub enum ManagementCreateGeometry {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ManagementCreateOperation {
-    kind: Option<String>,
-    properties: Option<serde_json::Value>,
-    geometry: Option<ManagementCreateGeometry>,
-    connect: Option<Vec<ManagementConnection>>,
-    parent: Option<String>,
+    pub(crate) kind: Option<String>,
+    pub(crate) properties: Option<serde_json::Value>,
+    pub(crate) geometry: Option<ManagementCreateGeometry>,
+    pub(crate) connect: Option<Vec<ManagementConnection>>,
+    pub(crate) parent: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -1511,7 +1511,7 @@ const IGNORE_PATHS: [&[&str]; 6] = [
 
 const ROOT_SI_TYPE_PATH: &[&str] = &["root", "si", "type"];
 
-async fn update_component(
+pub(crate) async fn update_component(
     ctx: &DalContext,
     component_id: ComponentId,
     properties: &serde_json::Value,
@@ -381,6 +381,20 @@ impl ManagementPrototype {
         id: ManagementPrototypeId,
         manager_component_id: ComponentId,
         view_id: Option<ViewId>,
+    ) -> ManagementPrototypeResult<ManagementPrototypeExecution> {
+        Self::execute_by_id_with_payload(ctx, id, manager_component_id, view_id, None).await
+    }
+
+    /// Execute the [`ManagementPrototype`] corresponding to the provided
+    /// [`ManagementPrototypeId`], passing the provided payload (if any) to the management function
+    /// as the `payload` argument. This is used when execution is triggered from outside of SI,
+    /// e.g. by a workspace hook.
+    pub async fn execute_by_id_with_payload(
+        ctx: &DalContext,
+        id: ManagementPrototypeId,
+        manager_component_id: ComponentId,
+        view_id: Option<ViewId>,
+        payload: Option<serde_json::Value>,
     ) -> ManagementPrototypeResult<ManagementPrototypeExecution> {
         let prototype = Self::get_by_id(ctx, id)
             .await?
@@ -429,11 +443,14 @@ impl ManagementPrototype {
             ManagedComponent::new(ctx, manager_component_id, &this_schema, &views).await?;
         let manager_component_geometry = manager_component.geometry.to_owned();
 
-        let args = serde_json::json!({
+        let mut args = serde_json::json!({
             "current_view": current_view,
             "this_component": manager_component,
             "components": managed_components,
         });
+        if let (Some(payload), Some(args)) = (payload, args.as_object_mut()) {
+            args.insert("payload".to_string(), payload);
+        }
 
         let result_channel =
             FuncRunner::run_management(ctx, id, manager_component_id, management_func_id, args)
@@ -478,7 +495,8 @@ impl ManagementPrototype {
             })
             .unwrap_or(si_events::ActionResultState::Unknown);
 
-        ctx.layer_db()
+        let func_run = ctx
+            .layer_db()
             .func_run()
             .set_management_result_success(
                 func_run_id,
@@ -489,6 +507,7 @@ impl ManagementPrototype {
                 ctx.events_actor(),
             )
             .await?;
+        FuncRunner::publish_status(ctx, &func_run).await;
 
         // We publish this immediately because the management "operator" could
         // fail because of a bad function, but we stil want to know that the
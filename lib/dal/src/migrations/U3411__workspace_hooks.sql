@@ -0,0 +1,11 @@
+CREATE TABLE workspace_hooks
+(
+    pk                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    secret_crypted              bytea NOT NULL,
+    secret_nonce                bytea NOT NULL,
+    secret_key_hash             text NOT NULL,
+    target                      jsonb NOT NULL,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE INDEX ON workspace_hooks (workspace_pk);
@@ -0,0 +1,10 @@
+CREATE TABLE action_concurrency_limits
+(
+    workspace_pk                ident NOT NULL,
+    schema_id                   ident,
+    max_concurrency             integer NOT NULL CHECK (max_concurrency > 0),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+-- A workspace has at most one limit covering all actions, and at most one limit per schema
+CREATE UNIQUE INDEX ON action_concurrency_limits (workspace_pk) WHERE schema_id IS NULL;
+CREATE UNIQUE INDEX ON action_concurrency_limits (workspace_pk, schema_id) WHERE schema_id IS NOT NULL;
@@ -0,0 +1,25 @@
+-- Traits and their links are written in the change set they were authored in and only promoted to
+-- the base change set when it is applied, so that edits never leak out of unapplied change sets.
+-- A null fragment marks a trait which was deleted in the change set.
+CREATE TABLE schema_variant_traits
+(
+    workspace_pk                ident NOT NULL,
+    change_set_id               ident NOT NULL,
+    name                        text NOT NULL,
+    fragment                    jsonb,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    PRIMARY KEY (workspace_pk, change_set_id, name)
+);
+
+-- Links are kept per schema (rather than per variant) since regenerating a variant creates a new
+-- one. The links of a schema are kept as a whole, so that an empty set in a change set overrides
+-- the links of the default change set.
+CREATE TABLE schema_variant_trait_links
+(
+    workspace_pk                ident NOT NULL,
+    change_set_id               ident NOT NULL,
+    schema_id                   ident NOT NULL,
+    trait_names                 text[] NOT NULL,
+    PRIMARY KEY (workspace_pk, change_set_id, schema_id)
+);
@@ -0,0 +1,11 @@
+CREATE TABLE api_tokens
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    name                        text NOT NULL,
+    created_by_user_pk          ident NOT NULL,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    expires_at                  timestamp with time zone,
+    revoked_at                  timestamp with time zone
+);
+CREATE INDEX ON api_tokens (workspace_pk);
@@ -0,0 +1,13 @@
+CREATE TABLE share_links
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    change_set_id               ident NOT NULL,
+    view_id                     ident,
+    token_hash                  text NOT NULL,
+    created_by_user_pk          ident NOT NULL,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    expires_at                  timestamp with time zone NOT NULL,
+    revoked_at                  timestamp with time zone
+);
+CREATE INDEX ON share_links (workspace_pk);
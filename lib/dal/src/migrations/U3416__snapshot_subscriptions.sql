@@ -0,0 +1,11 @@
+CREATE TABLE snapshot_subscriptions
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    name                        text NOT NULL,
+    filter                      jsonb NOT NULL,
+    projection                  jsonb NOT NULL,
+    sequence                    bigint NOT NULL DEFAULT 0,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE INDEX ON snapshot_subscriptions (workspace_pk);
@@ -0,0 +1,12 @@
+CREATE TABLE change_set_size_thresholds
+(
+    workspace_pk                ident PRIMARY KEY,
+    soft_components_touched     integer CHECK (soft_components_touched >= 0),
+    soft_values_changed         integer CHECK (soft_values_changed >= 0),
+    soft_actions_queued         integer CHECK (soft_actions_queued >= 0),
+    hard_components_touched     integer CHECK (hard_components_touched >= 0),
+    hard_values_changed         integer CHECK (hard_values_changed >= 0),
+    hard_actions_queued         integer CHECK (hard_actions_queued >= 0),
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
@@ -0,0 +1,30 @@
+CREATE TABLE provider_credentials
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    name                        text NOT NULL,
+    kind                        text NOT NULL,
+    material_envelope           bytea NOT NULL,
+    material_key_hash           text NOT NULL,
+    created_by_user_pk          ident,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    expires_at                  timestamp with time zone,
+    revoked_at                  timestamp with time zone
+);
+CREATE INDEX ON provider_credentials (workspace_pk);
+CREATE UNIQUE INDEX ON provider_credentials (workspace_pk, name) WHERE revoked_at IS NULL;
+CREATE INDEX ON provider_credentials (material_key_hash);
+
+CREATE TABLE provider_credential_consents
+(
+    id                          ident primary key default ident_create_v1(),
+    credential_id               ident NOT NULL REFERENCES provider_credentials (id),
+    func_id                     ident NOT NULL,
+    func_code_hash              text NOT NULL,
+    granted_by_user_pk          ident,
+    granted_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    revoked_at                  timestamp with time zone
+);
+CREATE INDEX ON provider_credential_consents (credential_id);
+CREATE INDEX ON provider_credential_consents (func_id, func_code_hash);
@@ -0,0 +1,2 @@
+ALTER TABLE change_set_pointers ADD COLUMN quarantined_at TIMESTAMPTZ;
+ALTER TABLE change_set_pointers ADD COLUMN last_good_snapshot_address text;
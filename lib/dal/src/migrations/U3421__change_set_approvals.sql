@@ -0,0 +1,14 @@
+ALTER TABLE workspaces
+    ADD COLUMN required_change_set_approvals integer NULL;
+
+CREATE TABLE change_set_approvals
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    change_set_id               ident NOT NULL,
+    user_pk                     ident NOT NULL,
+    status                      text NOT NULL,
+    comment                     text,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE INDEX ON change_set_approvals (change_set_id);
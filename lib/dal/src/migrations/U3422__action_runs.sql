@@ -0,0 +1,19 @@
+CREATE TABLE action_runs
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    change_set_id               ident NOT NULL,
+    originating_change_set_id   ident NOT NULL,
+    action_id                   ident NOT NULL,
+    component_id                ident NOT NULL,
+    func_run_id                 ident,
+    kind                        text NOT NULL,
+    attempt                     integer NOT NULL,
+    status                      text NOT NULL,
+    result_payload_hash         text,
+    started_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    finished_at                 timestamp with time zone
+);
+CREATE INDEX ON action_runs (action_id);
+CREATE INDEX ON action_runs (component_id);
+CREATE INDEX ON action_runs (originating_change_set_id);
@@ -0,0 +1,10 @@
+CREATE TABLE workspace_feature_flag_overrides
+(
+    workspace_pk                ident NOT NULL,
+    flag                        text NOT NULL,
+    enabled                     boolean NOT NULL,
+    updated_by_user_pk          ident,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    PRIMARY KEY (workspace_pk, flag)
+);
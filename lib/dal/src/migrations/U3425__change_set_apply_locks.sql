@@ -0,0 +1,8 @@
+-- Change sets which are being applied. A lock expires on its own so that a change set is not
+-- locked forever when the server holding it goes away mid-apply.
+CREATE TABLE change_set_apply_locks
+(
+    change_set_id ident PRIMARY KEY,
+    locked_at     timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    expires_at    timestamp with time zone NOT NULL
+);
@@ -0,0 +1,63 @@
+-- Token buckets limiting the requests made with one token in one workspace, shared by every sdf
+-- server so that a client cannot get around its limit by spreading requests across servers.
+CREATE TABLE rate_limit_buckets
+(
+    workspace_pk ident                    NOT NULL,
+    subject      text                     NOT NULL,
+    route_group  text                     NOT NULL,
+    tokens       double precision         NOT NULL,
+    updated_at   timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    PRIMARY KEY (workspace_pk, subject, route_group)
+);
+
+CREATE INDEX rate_limit_buckets_updated_at ON rate_limit_buckets (updated_at);
+
+-- Takes a token from a bucket, creating it full if it does not exist yet. The bucket row is locked
+-- while it is refilled and taken from, so concurrent requests never take the same token.
+-- When no token is available, returns how long it will be until one is (or NULL if the bucket
+-- never refills).
+CREATE OR REPLACE FUNCTION rate_limit_take_v1(this_workspace_pk ident,
+                                              this_subject text,
+                                              this_route_group text,
+                                              this_capacity double precision,
+                                              this_refill_per_second double precision,
+                                              OUT taken bool,
+                                              OUT retry_after_seconds double precision) AS
+$$
+DECLARE
+    this_tokens     double precision;
+    this_updated_at timestamp with time zone;
+    this_now        timestamp with time zone;
+BEGIN
+    INSERT INTO rate_limit_buckets (workspace_pk, subject, route_group, tokens)
+        VALUES (this_workspace_pk, this_subject, this_route_group, this_capacity)
+    ON CONFLICT DO NOTHING;
+
+    SELECT tokens, updated_at
+    INTO this_tokens, this_updated_at
+    FROM rate_limit_buckets
+    WHERE workspace_pk = this_workspace_pk
+      AND subject = this_subject
+      AND route_group = this_route_group
+        FOR UPDATE;
+
+    this_now := CLOCK_TIMESTAMP();
+    this_tokens := LEAST(this_capacity,
+                         this_tokens + GREATEST(0, EXTRACT(EPOCH FROM this_now - this_updated_at))
+                             * this_refill_per_second);
+
+    taken := this_tokens >= 1;
+    IF taken THEN
+        this_tokens := this_tokens - 1;
+    ELSIF this_refill_per_second > 0 THEN
+        retry_after_seconds := (1 - this_tokens) / this_refill_per_second;
+    END IF;
+
+    UPDATE rate_limit_buckets
+    SET tokens     = this_tokens,
+        updated_at = this_now
+    WHERE workspace_pk = this_workspace_pk
+      AND subject = this_subject
+      AND route_group = this_route_group;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,12 @@
+-- The latest versions of the diagram of each change set handed out by sdf, shared by every sdf
+-- server so that any of them can compute the delta from a version another one handed out.
+CREATE TABLE diagram_versions
+(
+    change_set_id    ident                    NOT NULL,
+    snapshot_address text                     NOT NULL,
+    diagram          jsonb                    NOT NULL,
+    used_at          timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    PRIMARY KEY (change_set_id, snapshot_address)
+);
+
+CREATE INDEX diagram_versions_used_at ON diagram_versions (used_at);
@@ -0,0 +1,3 @@
+-- Read-only change sets (such as ones holding an imported snapshot) can be viewed but never
+-- written to or applied.
+ALTER TABLE change_set_pointers ADD COLUMN read_only BOOLEAN NOT NULL DEFAULT FALSE;
@@ -3,19 +3,26 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
+use module_index_client::{
+    ModuleChannel, ModuleDetailsResponse, ModuleIndexClient, ModuleIndexClientError, ModuleUpload,
+    PublishProgress,
+};
 use serde::{Deserialize, Serialize};
 use si_events::ulid::Ulid;
 use si_events::ContentHash;
 use si_frontend_types as frontend_types;
 use si_layer_cache::LayerDbError;
+use si_pkg::{SiPkg, SiPkgError};
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::TryLockError;
 use tokio::time::Instant;
 
+use crate::action::ActionError;
 use crate::layer_db_types::{ModuleContent, ModuleContentV2};
 use crate::pkg::export::PkgExporter;
 use crate::pkg::PkgError;
+use crate::prop::PropError;
 use crate::workspace_snapshot::content_address::{ContentAddress, ContentAddressDiscriminants};
 use crate::workspace_snapshot::edge_weight::{
     EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants,
@@ -24,43 +31,68 @@ use crate::workspace_snapshot::node_weight::category_node_weight::CategoryNodeKi
 use crate::workspace_snapshot::node_weight::traits::SiNodeWeight;
 use crate::workspace_snapshot::node_weight::{NodeWeight, NodeWeightError};
 use crate::workspace_snapshot::WorkspaceSnapshotError;
+use crate::ws_event::{WsEvent, WsEventError, WsEventResult, WsPayload};
 use crate::{
-    ChangeSetError, DalContext, Func, FuncError, HistoryActor, Schema, SchemaError, SchemaId,
-    SchemaVariant, SchemaVariantError, SchemaVariantId, Timestamp, TransactionsError, User,
-    UserError,
+    ChangeSetError, ComponentError, DalContext, Func, FuncError, HistoryActor, Schema, SchemaError,
+    SchemaId, SchemaVariant, SchemaVariantError, SchemaVariantId, Timestamp, TransactionsError,
+    User, UserError,
 };
 
+mod upgrade;
+
+pub use upgrade::{ModuleUpgradeReport, SchemaVariantUpgrade};
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ModuleError {
+    #[error("action error: {0}")]
+    Action(#[from] Box<ActionError>),
     #[error("change set error: {0}")]
     ChangeSet(#[from] ChangeSetError),
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("schema variant has no changes compared to its module (schema variant id: {0})")]
+    EmptyDelta(SchemaVariantId),
     #[error("found empty metadata (name: '{0}') (version: '{1}')")]
     EmptyMetadata(String, String),
     #[error("func error: {0}")]
     Func(#[from] FuncError),
+    #[error("base module hash mismatch (expected: {0}) (found: {1})")]
+    HashMismatch(String, String),
     #[error("layer db error: {0}")]
     LayerDb(#[from] LayerDbError),
     #[error("module missing schema id (module id: {0}) (module hash: {1})")]
     MissingSchemaId(String, String),
+    #[error("module index client error: {0}")]
+    ModuleIndexClient(#[from] ModuleIndexClientError),
     #[error("node weight error: {0}")]
     NodeWeight(#[from] NodeWeightError),
+    #[error("schema variant is not derived from an installed module: {0}")]
+    NotDerivedFromModule(SchemaVariantId),
     #[error("pkg error: {0}")]
     Pkg(#[from] Box<PkgError>),
+    #[error("prop error: {0}")]
+    Prop(#[from] PropError),
     #[error("schema error: {0}")]
     Schema(#[from] SchemaError),
     #[error("schema variant error: {0}")]
     SchemaVariant(#[from] SchemaVariantError),
+    #[error("si pkg error: {0}")]
+    SiPkg(#[from] SiPkgError),
     #[error("too many latest modules for schema: {0} (at least two hashes found: {1} and {2})")]
     TooManyLatestModulesForSchema(SchemaId, String, String),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
     #[error("try lock error: {0}")]
     TryLock(#[from] TryLockError),
+    #[error("modules can only be upgraded in a change set (module hash: {0})")]
+    UpgradeOnHead(String),
     #[error("user error: {0}")]
     User(#[from] UserError),
     #[error("workspace snapshot error: {0}")]
     WorkspaceSnapshot(#[from] WorkspaceSnapshotError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
 }
 
 pub type ModuleResult<T> = Result<T, ModuleError>;
@@ -643,4 +675,282 @@ impl Module {
             variant.version().to_string(),
         ))
     }
+
+    /// Finds the installed [`Module`] that the schema of the given [`SchemaVariantId`] was
+    /// installed from, if any.
+    pub async fn find_for_schema_variant_id(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> ModuleResult<Option<Self>> {
+        let schema_id =
+            SchemaVariant::schema_id_for_schema_variant_id(ctx, schema_variant_id).await?;
+        Self::find_for_member_id(ctx, schema_id).await
+    }
+
+    /// Prepares a contribution for a [`SchemaVariantId`] derived from an installed [`Module`]
+    /// which only contains what was added locally (new funcs, props and sockets) on top of the
+    /// provided base package. The base package must be the one the module was installed from.
+    #[instrument(
+        name = "module.prepare_delta_contribution"
+        level = "info",
+        skip_all,
+        fields(
+            name = name.as_ref(),
+            version = version.as_ref(),
+            %schema_variant_id
+        )
+    )]
+    pub async fn prepare_delta_contribution(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        version: impl AsRef<str>,
+        schema_variant_id: SchemaVariantId,
+        base_pkg: &SiPkg,
+    ) -> ModuleResult<ModuleDeltaContribution> {
+        let base_module = Self::find_for_schema_variant_id(ctx, schema_variant_id)
+            .await?
+            .ok_or(ModuleError::NotDerivedFromModule(schema_variant_id))?;
+        let base_hash = base_pkg.hash()?.to_string();
+        if base_hash != base_module.root_hash() {
+            return Err(ModuleError::HashMismatch(
+                base_module.root_hash().to_owned(),
+                base_hash,
+            ));
+        }
+
+        let (
+            name,
+            version,
+            _,
+            schema_id,
+            payload,
+            created_by_name,
+            created_by_email,
+            schema_variant_version,
+        ) = Self::prepare_contribution(ctx, name, version, schema_variant_id).await?;
+
+        let local_spec = SiPkg::load_from_bytes(&payload)?.to_spec().await?;
+        let base_spec = base_pkg.to_spec().await?;
+        let mut delta = si_pkg::delta(&base_spec, &local_spec);
+        if delta.is_empty() {
+            return Err(ModuleError::EmptyDelta(schema_variant_id));
+        }
+        debug!(
+            funcs = ?delta.funcs,
+            props = ?delta.props,
+            sockets = ?delta.sockets,
+            "prepared module delta"
+        );
+
+        // Record where the delta came from in the package itself, so that it can be reviewed
+        // without knowing how it was uploaded.
+        delta.spec.description = format!(
+            "Changes to {} {} (based on {})",
+            base_module.name(),
+            base_module.version(),
+            base_module.root_hash(),
+        );
+        let payload = SiPkg::load_from_spec(delta.spec)?.write_to_bytes()?;
+
+        Ok(ModuleDeltaContribution {
+            name,
+            version,
+            based_on_hash: base_module.root_hash().to_owned(),
+            based_on_name: base_module.name().to_owned(),
+            based_on_version: base_module.version().to_owned(),
+            schema_id,
+            payload,
+            created_by_name,
+            created_by_email,
+            schema_variant_version,
+            funcs: delta.funcs,
+            props: delta.props,
+            sockets: delta.sockets,
+        })
+    }
+
+    /// Exports the given [`SchemaVariantId`] as a module and publishes it to the module index on
+    /// the given [`ModuleChannel`]. If the schema was installed from a module, the export is
+    /// uploaded as a new version of it, otherwise it is published as a new module.
+    ///
+    /// Progress is reported to the workspace as it goes, since exporting and uploading a large
+    /// schema variant can take a while.
+    #[instrument(
+        name = "module.publish"
+        level = "info",
+        skip_all,
+        fields(
+            name = name.as_ref(),
+            version = version.as_ref(),
+            %schema_variant_id,
+            %channel,
+        )
+    )]
+    pub async fn publish(
+        ctx: &DalContext,
+        client: &ModuleIndexClient,
+        name: impl AsRef<str>,
+        version: impl AsRef<str>,
+        schema_variant_id: SchemaVariantId,
+        channel: ModuleChannel,
+    ) -> ModuleResult<ModulePublication> {
+        WsEvent::module_publish_progress(
+            ctx,
+            schema_variant_id,
+            ModulePublishStage::Exporting,
+            None,
+        )
+        .await?
+        .publish_immediately(ctx)
+        .await?;
+
+        let (
+            name,
+            version,
+            based_on_hash,
+            schema_id,
+            payload,
+            created_by_name,
+            created_by_email,
+            schema_variant_version,
+        ) = Self::prepare_contribution(ctx, name, version, schema_variant_id).await?;
+
+        let upload = ModuleUpload {
+            name: name.clone(),
+            version: version.clone(),
+            schema_variant_id: Some(schema_variant_id.to_string()),
+            schema_variant_version: Some(schema_variant_version.clone()),
+            bytes: payload,
+        };
+
+        // The client reports progress from a plain callback, so forward it on as the upload runs.
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let report = move |step: PublishProgress| {
+            let _ = progress_tx.send(step);
+        };
+        let uploading = async {
+            match based_on_hash.clone() {
+                Some(based_on_hash) => {
+                    client
+                        .upload_module_version(
+                            based_on_hash,
+                            schema_id.map(|id| id.to_string()),
+                            upload,
+                            channel,
+                            report,
+                        )
+                        .await
+                }
+                None => client.publish_module(upload, channel, report).await,
+            }
+        };
+        let forward_progress = async {
+            while let Some(step) = progress_rx.recv().await {
+                let (stage, total_bytes) = match step {
+                    PublishProgress::Uploading { total_bytes } => {
+                        (ModulePublishStage::Uploading, Some(total_bytes))
+                    }
+                    PublishProgress::Published => (ModulePublishStage::Published, None),
+                };
+                WsEvent::module_publish_progress(ctx, schema_variant_id, stage, total_bytes)
+                    .await?
+                    .publish_immediately(ctx)
+                    .await?;
+            }
+            Ok::<_, ModuleError>(())
+        };
+        let (details, forwarded) = tokio::join!(uploading, forward_progress);
+        let details = details?;
+        forwarded?;
+
+        Ok(ModulePublication {
+            name,
+            version,
+            based_on_hash,
+            schema_id,
+            created_by_name,
+            created_by_email,
+            schema_variant_version,
+            channel,
+            details,
+        })
+    }
+}
+
+/// A contribution prepared by [`Module::prepare_delta_contribution`].
+#[derive(Debug, Clone)]
+pub struct ModuleDeltaContribution {
+    pub name: String,
+    pub version: String,
+    /// The root hash of the installed module the delta was computed against.
+    pub based_on_hash: String,
+    pub based_on_name: String,
+    pub based_on_version: String,
+    pub schema_id: Option<SchemaId>,
+    /// The delta package.
+    pub payload: Vec<u8>,
+    pub created_by_name: String,
+    pub created_by_email: String,
+    pub schema_variant_version: String,
+    /// The names of new or changed funcs.
+    pub funcs: Vec<String>,
+    /// The paths of new props.
+    pub props: Vec<String>,
+    /// The names of new sockets.
+    pub sockets: Vec<String>,
+}
+
+/// A module published by [`Module::publish`].
+#[derive(Debug, Clone)]
+pub struct ModulePublication {
+    pub name: String,
+    pub version: String,
+    /// The root hash of the installed module this was uploaded as a new version of, if any.
+    pub based_on_hash: Option<String>,
+    pub schema_id: Option<SchemaId>,
+    pub created_by_name: String,
+    pub created_by_email: String,
+    pub schema_variant_version: String,
+    pub channel: ModuleChannel,
+    /// The module as stored by the module index.
+    pub details: ModuleDetailsResponse,
+}
+
+#[remain::sorted]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModulePublishStage {
+    /// The schema variant is being exported into a module.
+    Exporting,
+    /// The module index has stored the module.
+    Published,
+    /// The module is being uploaded to the module index.
+    Uploading,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModulePublishProgressPayload {
+    schema_variant_id: SchemaVariantId,
+    stage: ModulePublishStage,
+    total_bytes: Option<u64>,
+}
+
+impl WsEvent {
+    pub async fn module_publish_progress(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        stage: ModulePublishStage,
+        total_bytes: Option<u64>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ModulePublishProgress(ModulePublishProgressPayload {
+                schema_variant_id,
+                stage,
+                total_bytes,
+            }),
+        )
+        .await
+    }
 }
@@ -0,0 +1,182 @@
+//! Upgrading an installed [`Module`] to a newer package of it, including the components built
+//! from it.
+//!
+//! The newer package is installed alongside the module, as a new version of the same schemas, and
+//! every component on one of the module's schema variants is then moved onto the variant which
+//! replaces it. Values are carried over prop by prop, matching props by their path, so props
+//! which were removed or changed kind lose their values. Those are reported back so that they can
+//! be reviewed before the change set is applied.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use si_pkg::SiPkg;
+use telemetry::prelude::*;
+
+use crate::action::{Action, ActionState};
+use crate::pkg::import::{import_pkg_from_pkg, ImportOptions};
+use crate::prop::WidgetOption;
+use crate::{
+    Component, ComponentId, DalContext, PropKind, SchemaId, SchemaVariant, SchemaVariantId,
+};
+
+use super::{Module, ModuleError, ModuleId, ModuleResult};
+
+/// What [`Module::upgrade`] did.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleUpgradeReport {
+    /// The module recorded for the newer package.
+    pub module_id: Option<ModuleId>,
+    pub schema_variants: Vec<SchemaVariantUpgrade>,
+}
+
+/// The upgrade of the components on one of the upgraded module's schema variants.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaVariantUpgrade {
+    pub from_schema_variant_id: SchemaVariantId,
+    pub to_schema_variant_id: SchemaVariantId,
+    /// The paths of props which only exist on the new variant, or which changed kind.
+    pub added_props: Vec<String>,
+    /// The paths of props which no longer exist on the new variant, or which changed kind, and
+    /// whose values were dropped as a result.
+    pub dropped_props: Vec<String>,
+    pub migrated_component_ids: Vec<ComponentId>,
+    /// Components left on the old variant because they have actions dispatched or running.
+    pub skipped_component_ids: Vec<ComponentId>,
+}
+
+impl Module {
+    /// Installs `pkg` as a newer version of this module and moves every component on one of the
+    /// module's schema variants onto the new default variant of its schema.
+    ///
+    /// Upgrades can touch a lot of components, so they can only be run in a change set, where
+    /// the result can be reviewed and abandoned if need be.
+    #[instrument(
+        name = "module.upgrade",
+        level = "info",
+        skip_all,
+        fields(
+            module.name = self.name(),
+            module.root_hash = self.root_hash(),
+        )
+    )]
+    pub async fn upgrade(
+        &self,
+        ctx: &DalContext,
+        pkg: &SiPkg,
+    ) -> ModuleResult<ModuleUpgradeReport> {
+        if ctx.change_set()?.is_head(ctx).await? {
+            return Err(ModuleError::UpgradeOnHead(self.root_hash().to_owned()));
+        }
+
+        let mut old_variants_by_schema: HashMap<SchemaId, Vec<SchemaVariantId>> = HashMap::new();
+        for variant in self.list_associated_schema_variants(ctx).await? {
+            let schema_id =
+                SchemaVariant::schema_id_for_schema_variant_id(ctx, variant.id()).await?;
+            old_variants_by_schema
+                .entry(schema_id)
+                .or_default()
+                .push(variant.id());
+        }
+
+        let (module_id, new_variant_ids, _) = import_pkg_from_pkg(
+            ctx,
+            pkg,
+            Some(ImportOptions {
+                schema_id: self.schema_id(),
+                past_module_hashes: Some(vec![self.root_hash().to_owned()]),
+                ..Default::default()
+            }),
+        )
+        .await
+        .map_err(Box::new)?;
+
+        let mut schema_variants = vec![];
+        for new_variant_id in new_variant_ids {
+            let schema_id =
+                SchemaVariant::schema_id_for_schema_variant_id(ctx, new_variant_id).await?;
+            let Some(old_variant_ids) = old_variants_by_schema.remove(&schema_id) else {
+                continue;
+            };
+            for old_variant_id in old_variant_ids {
+                schema_variants.push(upgrade_variant(ctx, old_variant_id, new_variant_id).await?);
+            }
+        }
+
+        Ok(ModuleUpgradeReport {
+            module_id,
+            schema_variants,
+        })
+    }
+}
+
+async fn upgrade_variant(
+    ctx: &DalContext,
+    old_variant_id: SchemaVariantId,
+    new_variant_id: SchemaVariantId,
+) -> ModuleResult<SchemaVariantUpgrade> {
+    let old_props = props_by_path(ctx, old_variant_id).await?;
+    let new_props = props_by_path(ctx, new_variant_id).await?;
+    let dropped_props = old_props
+        .iter()
+        .filter(|&(path, prop)| new_props.get(path) != Some(prop))
+        .map(|(path, _)| path.to_owned())
+        .collect();
+    let added_props = new_props
+        .iter()
+        .filter(|&(path, prop)| old_props.get(path) != Some(prop))
+        .map(|(path, _)| path.to_owned())
+        .collect();
+
+    let mut migrated_component_ids = vec![];
+    let mut skipped_component_ids = vec![];
+    for component_id in SchemaVariant::list_component_ids(ctx, old_variant_id).await? {
+        let blocking_actions = Action::find_for_states_and_component_id(
+            ctx,
+            component_id,
+            vec![ActionState::Dispatched, ActionState::Running],
+        )
+        .await
+        .map_err(Box::new)?;
+        if !blocking_actions.is_empty() {
+            warn!(%component_id, "skipping upgrade of component with actions in flight");
+            skipped_component_ids.push(component_id);
+            continue;
+        }
+
+        Component::get_by_id(ctx, component_id)
+            .await
+            .map_err(Box::new)?
+            .upgrade_to_new_variant(ctx, new_variant_id)
+            .await
+            .map_err(Box::new)?;
+        migrated_component_ids.push(component_id);
+    }
+
+    Ok(SchemaVariantUpgrade {
+        from_schema_variant_id: old_variant_id,
+        to_schema_variant_id: new_variant_id,
+        added_props,
+        dropped_props,
+        migrated_component_ids,
+        skipped_component_ids,
+    })
+}
+
+/// The props of a schema variant by path, along with what decides whether a value can be carried
+/// over to a prop at the same path on another variant.
+async fn props_by_path(
+    ctx: &DalContext,
+    schema_variant_id: SchemaVariantId,
+) -> ModuleResult<BTreeMap<String, (PropKind, Option<WidgetOption>)>> {
+    let mut props = BTreeMap::new();
+    for prop in SchemaVariant::all_props(ctx, schema_variant_id).await? {
+        let path = prop.path(ctx).await?.with_replaced_sep_and_prefix("/");
+        let secret_kind = prop.secret_kind_widget_option();
+        props.insert(path, (prop.kind, secret_kind));
+    }
+
+    Ok(props)
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SpecError};
 use std::collections::HashMap;
 use thiserror::Error;
+use ulid::Ulid;
 use url::ParseError;
 
 use crate::attribute::prototype::argument::{
@@ -56,6 +57,12 @@ pub enum PkgError {
     ConnectionAnnotation(#[from] ConnectionAnnotationError),
     #[error("expected data on an SiPkg node, but none found: {0}")]
     DataNotFound(String),
+    #[error("deltas of modules are built from packages and cannot be exported")]
+    DeltaExportNotSupported,
+    #[error("package {0} is a delta of another module and cannot be installed")]
+    DeltaNotInstallable(String),
+    #[error("deterministic id {0} for {1} is already in use")]
+    DeterministicIdInUse(si_events::ulid::Ulid, String),
     #[error("func error: {0}")]
     Func(#[from] FuncError),
     #[error("func argument error: {0}")]
@@ -153,6 +160,7 @@ impl From<FuncBackendKind> for FuncSpecBackendKind {
             FuncBackendKind::Validation => Self::Validation,
             FuncBackendKind::JsAuthentication => Self::JsAuthentication,
             FuncBackendKind::Management => Self::Management,
+            FuncBackendKind::Wasm => Self::Wasm,
         }
     }
 }
@@ -178,6 +186,7 @@ impl From<FuncSpecBackendKind> for FuncBackendKind {
             FuncSpecBackendKind::Validation => Self::Validation,
             FuncSpecBackendKind::JsAuthentication => Self::JsAuthentication,
             FuncSpecBackendKind::Management => Self::Management,
+            FuncSpecBackendKind::Wasm => Self::Wasm,
         }
     }
 }
@@ -307,6 +316,25 @@ pub struct WorkspaceImportApprovalActorPayload {
     name: String,
 }
 
+#[remain::sorted]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceImportStage {
+    /// The uploaded workspace is being imported.
+    Importing,
+    /// The workspace is being uploaded in chunks.
+    Uploading,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceImportProgressPayload {
+    id: Ulid,
+    stage: WorkspaceImportStage,
+    bytes_received: u64,
+    total_bytes: u64,
+}
+
 impl WsEvent {
     pub async fn module_imported(
         ctx: &DalContext,
@@ -367,4 +395,23 @@ impl WsEvent {
         )
         .await
     }
+
+    pub async fn workspace_import_progress(
+        ctx: &DalContext,
+        id: Ulid,
+        stage: WorkspaceImportStage,
+        bytes_received: u64,
+        total_bytes: u64,
+    ) -> WsEventResult<Self> {
+        WsEvent::new_for_workspace(
+            ctx,
+            WsPayload::WorkspaceImportProgress(WorkspaceImportProgressPayload {
+                id,
+                stage,
+                bytes_received,
+                total_bytes,
+            }),
+        )
+        .await
+    }
 }
@@ -5,12 +5,12 @@ use std::ops::Deref;
 use strum::IntoEnumIterator;
 
 use si_pkg::{
-    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, AuthenticationFuncSpec,
-    ComponentSpec, EdgeSpec, FuncArgumentSpec, FuncSpec, FuncSpecData, LeafFunctionSpec,
-    ManagementFuncSpec, MapKeyFuncSpec, PkgSpec, PropSpec, PropSpecBuilder, PropSpecKind,
-    RootPropFuncSpec, SchemaSpec, SchemaSpecData, SchemaVariantSpec, SchemaVariantSpecBuilder,
-    SchemaVariantSpecData, SchemaVariantSpecPropRoot, SiPkg, SiPkgKind, SiPropFuncSpec,
-    SiPropFuncSpecKind, SocketSpec, SocketSpecData, SocketSpecKind, SpecError,
+    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, AuthenticationFuncSpec, BindingSpec,
+    BindingSpecKind, ComponentSpec, EdgeSpec, FuncArgumentSpec, FuncSpec, FuncSpecData,
+    LeafFunctionSpec, ManagementFuncSpec, MapKeyFuncSpec, PkgSpec, PropSpec, PropSpecBuilder,
+    PropSpecKind, RootPropFuncSpec, SchemaSpec, SchemaSpecData, SchemaVariantSpec,
+    SchemaVariantSpecBuilder, SchemaVariantSpecData, SchemaVariantSpecPropRoot, SiPkg, SiPkgKind,
+    SiPropFuncSpec, SiPropFuncSpecKind, SocketSpec, SocketSpecData, SocketSpecKind, SpecError,
 };
 use telemetry::prelude::*;
 
@@ -86,6 +86,7 @@ impl PkgExporter {
 
     pub async fn export_as_bytes(&mut self, ctx: &DalContext) -> PkgResult<Vec<u8>> {
         match self.kind {
+            SiPkgKind::Delta => return Err(PkgError::DeltaExportNotSupported),
             SiPkgKind::Module => info!("Building module package"),
             SiPkgKind::WorkspaceBackup => return Err(PkgError::WorkspaceExportNotSupported()),
         }
@@ -995,12 +996,7 @@ impl PkgExporter {
 
         let mut schemas = vec![];
         for schema in Schema::list(ctx).await? {
-            if self
-                .schema_ids
-                .as_ref()
-                .map(|schema_ids| schema_ids.contains(&schema.id()))
-                .unwrap_or(true)
-            {
+            if self.is_exported_schema(schema.id()) {
                 schemas.push(schema)
             }
         }
@@ -1021,6 +1017,64 @@ impl PkgExporter {
         ))
     }
 
+    fn is_exported_schema(&self, schema_id: SchemaId) -> bool {
+        self.schema_ids
+            .as_ref()
+            .map(|schema_ids| schema_ids.contains(&schema_id))
+            .unwrap_or(true)
+    }
+
+    /// Records which output sockets of the default variants of schemas that are _not_ being
+    /// exported fit the input sockets of the default variants that are, so that the importer can
+    /// restore that wiring in workspaces where those schemas also exist.
+    async fn export_bindings(&self, ctx: &DalContext) -> PkgResult<Vec<BindingSpec>> {
+        let mut sources = vec![];
+        for schema in Schema::list(ctx).await? {
+            if self.is_exported_schema(schema.id()) {
+                continue;
+            }
+            let Some(variant_id) =
+                Schema::get_default_schema_variant_by_id(ctx, schema.id()).await?
+            else {
+                continue;
+            };
+            let kind = if SchemaVariant::is_secret_defining(ctx, variant_id).await? {
+                BindingSpecKind::SecretDefinition
+            } else {
+                BindingSpecKind::SocketAnnotation
+            };
+            for output_socket in OutputSocket::list(ctx, variant_id).await? {
+                sources.push((schema.name().to_owned(), kind, output_socket));
+            }
+        }
+
+        let mut specs = vec![];
+        for schema in Schema::list(ctx).await? {
+            if !self.is_exported_schema(schema.id()) {
+                continue;
+            }
+            let variant_id = SchemaVariant::get_default_id_for_schema(ctx, schema.id()).await?;
+            for input_socket in InputSocket::list(ctx, variant_id).await? {
+                for (source_schema_name, kind, output_socket) in &sources {
+                    if !output_socket.fits_input(&input_socket) {
+                        continue;
+                    }
+                    specs.push(
+                        BindingSpec::builder()
+                            .kind(*kind)
+                            .schema_name(schema.name())
+                            .input_socket_name(input_socket.name())
+                            .source_schema_name(source_schema_name)
+                            .source_output_socket_name(output_socket.name())
+                            .build()?,
+                    );
+                }
+            }
+        }
+
+        Ok(specs)
+    }
+
     pub async fn export_as_spec(&mut self, ctx: &DalContext) -> PkgResult<PkgSpec> {
         let mut pkg_spec_builder = PkgSpec::builder();
         pkg_spec_builder
@@ -1042,10 +1096,12 @@ impl PkgExporter {
         }
 
         match self.kind {
+            SiPkgKind::Delta => return Err(PkgError::DeltaExportNotSupported),
             SiPkgKind::Module => {
                 let (funcs, _, schemas, _, _) = self.export_change_set(ctx).await?;
                 pkg_spec_builder.funcs(funcs);
                 pkg_spec_builder.schemas(schemas);
+                pkg_spec_builder.bindings(self.export_bindings(ctx).await?);
             }
             SiPkgKind::WorkspaceBackup => return Err(PkgError::WorkspaceExportNotSupported()),
         }
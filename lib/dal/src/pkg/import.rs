@@ -1,10 +1,11 @@
 use chrono::NaiveDateTime;
 use si_events::ulid::Ulid;
 use si_pkg::{
-    SchemaVariantSpecPropRoot, SiPkg, SiPkgActionFunc, SiPkgAttrFuncInputView, SiPkgAuthFunc,
-    SiPkgComponent, SiPkgEdge, SiPkgError, SiPkgFunc, SiPkgFuncArgument, SiPkgFuncData, SiPkgKind,
-    SiPkgLeafFunction, SiPkgManagementFunc, SiPkgMetadata, SiPkgProp, SiPkgPropData, SiPkgSchema,
-    SiPkgSchemaData, SiPkgSchemaVariant, SiPkgSocket, SiPkgSocketData, SocketSpecKind,
+    BindingSpecKind, SchemaVariantSpecPropRoot, SiPkg, SiPkgActionFunc, SiPkgAttrFuncInputView,
+    SiPkgAuthFunc, SiPkgBinding, SiPkgComponent, SiPkgEdge, SiPkgError, SiPkgFunc,
+    SiPkgFuncArgument, SiPkgFuncData, SiPkgKind, SiPkgLeafFunction, SiPkgManagementFunc,
+    SiPkgMetadata, SiPkgProp, SiPkgPropData, SiPkgSchema, SiPkgSchemaData, SiPkgSchemaVariant,
+    SiPkgSocket, SiPkgSocketData, SocketSpecKind,
 };
 use std::collections::HashSet;
 use std::fmt::Debug;
@@ -73,6 +74,54 @@ pub struct ImportOptions {
     /// A list of "past hashes" for this module, used to find the existing
     /// schema if a schema_id is not provided
     pub past_module_hashes: Option<Vec<String>>,
+    /// If set to `true`, the ids of newly created funcs and schemas are derived from the
+    /// workspace, the package and their names instead of being generated, so that importing the
+    /// same package into a fresh environment yields the same ids. A provided `schema_id` still
+    /// takes precedence.
+    pub deterministic_ids: bool,
+}
+
+/// Derives ids from the workspace, the package being imported and a stable name path, such as
+/// `["func", "si:setString"]`.
+#[derive(Clone, Debug)]
+struct DeterministicIds {
+    seed: blake3::Hasher,
+}
+
+impl DeterministicIds {
+    fn new(ctx: &DalContext, root_hash: &str) -> Self {
+        let mut seed = blake3::Hasher::new();
+        seed.update(&ctx.tenancy().to_bytes());
+        seed.update(&[0]);
+        seed.update(root_hash.as_bytes());
+        seed.update(&[0]);
+
+        Self { seed }
+    }
+
+    /// Returns the id for the name path, failing if something in the snapshot already has it
+    /// rather than silently falling back to a generated id.
+    async fn id(&self, ctx: &DalContext, name_path: &[&str]) -> PkgResult<Ulid> {
+        let mut hasher = self.seed.clone();
+        for segment in name_path {
+            hasher.update(segment.as_bytes());
+            hasher.update(&[0]);
+        }
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hasher.finalize().as_bytes()[..16]);
+        let id: Ulid = ulid::Ulid::from_bytes(bytes).into();
+
+        if ctx
+            .workspace_snapshot()?
+            .get_node_index_by_id_opt(id)
+            .await
+            .is_some()
+        {
+            return Err(PkgError::DeterministicIdInUse(id, name_path.join("/")));
+        }
+
+        Ok(id)
+    }
 }
 
 const SPECIAL_CASE_FUNCS: [&str; 2] = ["si:resourcePayloadToValue", "si:normalizeToArray"];
@@ -88,6 +137,7 @@ async fn import_change_set(
     installed_module: Option<Module>,
     thing_map: &mut ThingMap,
     options: &ImportOptions,
+    deterministic_ids: Option<&DeterministicIds>,
 ) -> PkgResult<(
     Vec<SchemaVariantId>,
     Vec<(String, Vec<bool /*ImportAttributeSkip*/>)>,
@@ -112,6 +162,7 @@ async fn import_change_set(
                     installed_module.clone(),
                     thing_map,
                     options.create_unlocked,
+                    deterministic_ids,
                 )
                 .await?;
 
@@ -146,6 +197,7 @@ async fn import_change_set(
                         installed_module.clone(),
                         thing_map,
                         options.create_unlocked,
+                        deterministic_ids,
                     )
                     .await?,
                 )
@@ -203,6 +255,7 @@ async fn import_change_set(
             thing_map,
             options.create_unlocked,
             options.past_module_hashes.clone(),
+            deterministic_ids,
         )
         .await?;
 
@@ -224,6 +277,78 @@ async fn import_change_set(
     ))
 }
 
+/// Restores the wiring recorded in the package's bindings: for each binding whose source schema
+/// exists in this workspace, the connection annotations of the source output socket are added to
+/// the bound input socket of the imported variant, unless they already fit. Bindings whose source
+/// does not exist here are skipped.
+async fn import_bindings(
+    ctx: &DalContext,
+    bindings: &[SiPkgBinding<'_>],
+    schema_variant_ids: &[SchemaVariantId],
+) -> PkgResult<()> {
+    if bindings.is_empty() {
+        return Ok(());
+    }
+
+    let mut variant_id_by_schema_name = HashMap::new();
+    for schema_variant_id in schema_variant_ids {
+        let schema = SchemaVariant::schema_for_schema_variant_id(ctx, *schema_variant_id).await?;
+        variant_id_by_schema_name.insert(schema.name().to_owned(), *schema_variant_id);
+    }
+
+    for binding in bindings {
+        let Some(schema_variant_id) = variant_id_by_schema_name.get(binding.schema_name()) else {
+            continue;
+        };
+        let Some(input_socket) =
+            InputSocket::find_with_name(ctx, binding.input_socket_name(), *schema_variant_id)
+                .await?
+        else {
+            continue;
+        };
+
+        let source_variant_id =
+            match Schema::find_by_name(ctx, binding.source_schema_name()).await? {
+                Some(source_schema) => source_schema.get_default_schema_variant_id(ctx).await?,
+                None => None,
+            };
+        let Some(source_variant_id) = source_variant_id else {
+            debug!(
+                "skipping binding for socket '{}' of '{}': schema '{}' is not in this workspace",
+                binding.input_socket_name(),
+                binding.schema_name(),
+                binding.source_schema_name(),
+            );
+            continue;
+        };
+        if binding.kind() == BindingSpecKind::SecretDefinition
+            && !SchemaVariant::is_secret_defining(ctx, source_variant_id).await?
+        {
+            continue;
+        }
+        let Some(output_socket) = OutputSocket::find_with_name(
+            ctx,
+            binding.source_output_socket_name(),
+            source_variant_id,
+        )
+        .await?
+        else {
+            continue;
+        };
+
+        if !output_socket.fits_input(&input_socket) {
+            InputSocket::add_connection_annotations(
+                ctx,
+                input_socket.id(),
+                output_socket.connection_annotations(),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn import_pkg_from_pkg(
     ctx: &DalContext,
     pkg: &SiPkg,
@@ -242,6 +367,11 @@ pub async fn import_pkg_from_pkg(
     }
 
     let metadata = pkg.metadata()?;
+    match metadata.kind() {
+        SiPkgKind::Delta => return Err(PkgError::DeltaNotInstallable(metadata.name().to_owned())),
+        SiPkgKind::Module => {}
+        SiPkgKind::WorkspaceBackup => return Err(PkgError::WorkspaceExportNotSupported()),
+    }
 
     let installed_module: Option<Module> = if options.no_record {
         None
@@ -261,26 +391,27 @@ pub async fn import_pkg_from_pkg(
         )
     };
     let mut change_set_things = ThingMap::new();
+    let deterministic_ids = options
+        .deterministic_ids
+        .then(|| DeterministicIds::new(ctx, &root_hash));
 
-    match metadata.kind() {
-        SiPkgKind::Module => {
-            let (installed_schema_variant_ids, _, _) = import_change_set(
-                ctx,
-                &metadata,
-                &pkg.funcs()?,
-                &pkg.schemas()?,
-                &[],
-                &[],
-                installed_module,
-                &mut change_set_things,
-                &options,
-            )
-            .await?;
+    let (installed_schema_variant_ids, _, _) = import_change_set(
+        ctx,
+        &metadata,
+        &pkg.funcs()?,
+        &pkg.schemas()?,
+        &[],
+        &[],
+        installed_module,
+        &mut change_set_things,
+        &options,
+        deterministic_ids.as_ref(),
+    )
+    .await?;
 
-            Ok((None, installed_schema_variant_ids, None))
-        }
-        SiPkgKind::WorkspaceBackup => Err(PkgError::WorkspaceExportNotSupported()),
-    }
+    import_bindings(ctx, &pkg.bindings()?, &installed_schema_variant_ids).await?;
+
+    Ok((None, installed_schema_variant_ids, None))
 }
 
 pub async fn import_pkg(ctx: &DalContext, pkg_file_path: impl AsRef<Path>) -> PkgResult<SiPkg> {
@@ -296,6 +427,7 @@ async fn create_func(
     ctx: &DalContext,
     func_spec: &SiPkgFunc<'_>,
     is_builtin: bool,
+    deterministic_ids: Option<&DeterministicIds>,
 ) -> PkgResult<Func> {
     let name = func_spec.name();
 
@@ -303,22 +435,50 @@ async fn create_func(
         .data()
         .ok_or(PkgError::DataNotFound(name.into()))?;
 
-    let func = Func::new(
-        ctx,
-        name,
-        func_spec_data
-            .display_name()
-            .map(|display_name| display_name.to_owned()),
-        func_spec_data.description().map(|desc| desc.to_owned()),
-        func_spec_data.link().map(|l| l.to_string()),
-        func_spec_data.hidden(),
-        is_builtin,
-        func_spec_data.backend_kind().into(),
-        func_spec_data.response_type().into(),
-        Some(func_spec_data.handler().to_owned()),
-        Some(func_spec_data.code_base64().to_owned()),
-    )
-    .await?;
+    let display_name = func_spec_data
+        .display_name()
+        .map(|display_name| display_name.to_owned());
+    let description = func_spec_data.description().map(|desc| desc.to_owned());
+    let link = func_spec_data.link().map(|l| l.to_string());
+    let handler = Some(func_spec_data.handler().to_owned());
+    let code_base64 = Some(func_spec_data.code_base64().to_owned());
+
+    let func = match deterministic_ids {
+        Some(deterministic_ids) => {
+            let id = deterministic_ids.id(ctx, &["func", name]).await?;
+            Func::new_with_id(
+                ctx,
+                id.into(),
+                name,
+                display_name,
+                description,
+                link,
+                func_spec_data.hidden(),
+                is_builtin,
+                func_spec_data.backend_kind().into(),
+                func_spec_data.response_type().into(),
+                handler,
+                code_base64,
+            )
+            .await?
+        }
+        None => {
+            Func::new(
+                ctx,
+                name,
+                display_name,
+                description,
+                link,
+                func_spec_data.hidden(),
+                is_builtin,
+                func_spec_data.backend_kind().into(),
+                func_spec_data.response_type().into(),
+                handler,
+                code_base64,
+            )
+            .await?
+        }
+    };
 
     Ok(func)
 }
@@ -356,6 +516,7 @@ pub async fn import_func(
     installed_module: Option<Module>,
     thing_map: &mut ThingMap,
     create_unlocked: bool,
+    deterministic_ids: Option<&DeterministicIds>,
 ) -> PkgResult<Func> {
     let mut existing_func: Option<Func> = None;
     if let Some(installed_pkg) = installed_module.clone() {
@@ -372,7 +533,7 @@ pub async fn import_func(
     let func = if let Some(func) = existing_func {
         func
     } else {
-        let func = create_func(ctx, func_spec, false).await?;
+        let func = create_func(ctx, func_spec, false, deterministic_ids).await?;
 
         if !create_unlocked {
             func.lock(ctx).await?
@@ -432,8 +593,19 @@ async fn create_schema(
     ctx: &DalContext,
     maybe_existing_schema_id: Option<Ulid>,
     schema_spec_data: &SiPkgSchemaData,
+    deterministic_ids: Option<&DeterministicIds>,
 ) -> PkgResult<Schema> {
-    let schema = match maybe_existing_schema_id {
+    let maybe_schema_id = match (maybe_existing_schema_id, deterministic_ids) {
+        (Some(id), _) => Some(id),
+        (None, Some(deterministic_ids)) => Some(
+            deterministic_ids
+                .id(ctx, &["schema", schema_spec_data.name()])
+                .await?,
+        ),
+        (None, None) => None,
+    };
+
+    let schema = match maybe_schema_id {
         Some(id) => Schema::new_with_id(ctx, id.into(), schema_spec_data.name()).await?,
         None => Schema::new(ctx, schema_spec_data.name()).await?,
     }
@@ -453,6 +625,7 @@ async fn import_schema(
     thing_map: &mut ThingMap,
     create_unlocked: bool,
     past_hashes: Option<Vec<String>>,
+    deterministic_ids: Option<&DeterministicIds>,
 ) -> PkgResult<Vec<SchemaVariantId>> {
     let mut existing_schema: Option<Schema> = None;
     let mut existing_schema_id = None;
@@ -483,7 +656,7 @@ async fn import_schema(
 
     let schema_already_existed = existing_schema.is_some();
     let schema = match existing_schema {
-        None => create_schema(ctx, existing_schema_id, data).await?,
+        None => create_schema(ctx, existing_schema_id, data, deterministic_ids).await?,
         Some(installed_schema_record) => installed_schema_record,
     };
 
@@ -1051,7 +1224,7 @@ pub async fn import_only_new_funcs(
             {
                 func
             } else {
-                create_func(ctx, &func_spec, false).await?
+                create_func(ctx, &func_spec, false, None).await?
             };
 
             // Find or create the func arguments for the provided spec.
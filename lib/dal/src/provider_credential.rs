@@ -0,0 +1,1027 @@
+//! This module contains [`ProviderCredential`], a workspace-scoped vault entry holding the
+//! credentials for a cloud provider (or any other external service). Unlike component secrets,
+//! provider credentials live outside of the graph and are never attached to components. Management
+//! functions only ever receive [`DerivedProviderCredentials`](DerivedProviderCredential), and only
+//! for the credentials a user consented to the current code of the function using (see
+//! [`ProviderCredentialConsent`]).
+//!
+//! Where the provider can mint short-lived credentials from the stored ones (AWS session
+//! credentials, Azure and GCP access tokens), only the minted credentials are handed to the
+//! function. Bearer tokens can't be narrowed by the vault, so they must be stored with an expiry
+//! and are never handed out past it.
+//!
+//! The credential material is sealed at rest in a [`SymmetricEnvelope`] and is only decrypted to
+//! be handed to a function, re-encrypted for veritech. After the active symmetric key is rotated,
+//! [`ProviderCredential::reencrypt_materials`] re-wraps the envelopes with the new key so that the
+//! old one can be retired.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use async_trait::async_trait;
+use aws_sdk_sts::{
+    config::{BehaviorVersion, Credentials, Region},
+    error::SdkError,
+    operation::get_session_token::GetSessionTokenError,
+};
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Duration, Utc};
+use jwt_simple::{
+    algorithms::{RS256KeyPair, RSAKeyPairLike},
+    claims::Claims,
+};
+use serde::{Deserialize, Serialize};
+use si_crypto::{SymmetricCryptoError, SymmetricEnvelope, SymmetricEnvelopeStore};
+use si_data_pg::{PgError, PgPoolError, PgRow};
+use si_events::ContentHash;
+use si_hash::Hash;
+use strum::{AsRefStr, Display, EnumDiscriminants, EnumString};
+use thiserror::Error;
+use veritech_client::{encrypt_value_tree, BeforeFunction, VeritechValueEncryptError};
+
+use crate::{DalContext, Func, FuncId, HistoryActor, TransactionsError, UserPk, WorkspacePk};
+
+/// How long a derived credential may be used for when the caller does not ask for less.
+pub const DEFAULT_DERIVED_CREDENTIAL_TTL: Duration = Duration::minutes(15);
+/// The longest a derived credential may be used for, whatever the caller asks for.
+pub const MAX_DERIVED_CREDENTIAL_TTL: Duration = Duration::hours(1);
+
+/// STS mints session credentials for at least this long.
+const MIN_AWS_SESSION_TTL: Duration = Duration::minutes(15);
+/// STS is global, so any region works for minting session credentials.
+const AWS_STS_REGION: &str = "us-east-1";
+const AZURE_MANAGEMENT_SCOPE: &str = "https://management.azure.com/.default";
+const GCP_CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const GCP_DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+const MINT_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// The handler of the before function which hands a derived credential to a management function.
+const PROVIDE_CREDENTIAL_HANDLER: &str = "provideCredential";
+/// Stores the derived credential under `providerCredential:<name>` in the request storage and sets
+/// the conventional environment variables of its kind.
+const PROVIDE_CREDENTIAL_CODE: &str = r#"function provideCredential(credential) {
+    requestStorage.setItem(`providerCredential:${credential.name}`, credential);
+    for (const [key, value] of Object.entries(credential.env)) {
+        requestStorage.setEnv(key, value);
+    }
+}"#;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ProviderCredentialError {
+    #[error("aws sts get session token error: {0}")]
+    AwsStsGetSessionToken(#[source] Box<SdkError<GetSessionTokenError>>),
+    #[error("aws sts returned no session credentials")]
+    AwsStsMissingCredentials,
+    #[error("provider credential expiry must be in the future")]
+    ExpiryInPast,
+    #[error("provider credentials of kind {0} must be stored with an expiry")]
+    ExpiryRequired(ProviderCredentialKind),
+    #[error("invalid gcp service account key: {0}")]
+    GcpServiceAccountKey(String),
+    #[error("invalid kind for provider credential: {0}")]
+    InvalidKind(String),
+    #[error("provider credential ttl must be positive")]
+    InvalidTtl,
+    #[error("provider credential consents can only be granted by users")]
+    NotGrantedByUser,
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[from] PgPoolError),
+    #[error("provider credential mint request error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("provider credential has been revoked: {0}")]
+    Revoked(ProviderCredentialId),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("symmetric crypto error: {0}")]
+    SymmetricCrypto(#[from] SymmetricCryptoError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("veritech value encrypt error: {0}")]
+    VeritechValueEncrypt(#[from] VeritechValueEncryptError),
+}
+
+pub type ProviderCredentialResult<T> = Result<T, ProviderCredentialError>;
+
+pub use si_id::{ProviderCredentialConsentId, ProviderCredentialId};
+
+/// The secret material of a [`ProviderCredential`], typed by the kind of provider it is for.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, EnumDiscriminants)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+#[strum_discriminants(
+    name(ProviderCredentialKind),
+    derive(AsRefStr, Display, EnumString, Deserialize, Serialize),
+    serde(rename_all = "camelCase"),
+    strum(serialize_all = "camelCase")
+)]
+pub enum ProviderCredentialMaterial {
+    #[serde(rename_all = "camelCase")]
+    AwsAccessKey {
+        access_key_id: String,
+        secret_access_key: String,
+        session_token: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    AzureServicePrincipal {
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    DigitalOceanToken { token: String },
+    #[serde(rename_all = "camelCase")]
+    GcpServiceAccount { service_account_key_json: String },
+    /// A bearer token for any other service.
+    #[serde(rename_all = "camelCase")]
+    Token { token: String },
+}
+
+impl ProviderCredentialMaterial {
+    pub fn kind(&self) -> ProviderCredentialKind {
+        self.into()
+    }
+
+    /// Whether short-lived credentials can be minted from the material. Material which can't be
+    /// minted from is handed out as stored, so it must be stored with an expiry.
+    pub fn can_mint(&self) -> bool {
+        match self {
+            Self::AwsAccessKey { session_token, .. } => session_token.is_none(),
+            Self::AzureServicePrincipal { .. } | Self::GcpServiceAccount { .. } => true,
+            Self::DigitalOceanToken { .. } | Self::Token { .. } => false,
+        }
+    }
+
+    /// Mints credentials for a single function execution which expire no later than
+    /// `expires_at`, or as soon after it as the provider allows. Returns the minted credentials
+    /// along with when the provider expires them.
+    async fn mint(
+        &self,
+        expires_at: DateTime<Utc>,
+    ) -> ProviderCredentialResult<(DerivedProviderCredentialMaterial, DateTime<Utc>)> {
+        match self {
+            Self::AwsAccessKey {
+                access_key_id,
+                secret_access_key,
+                session_token: None,
+            } => {
+                let config = aws_sdk_sts::Config::builder()
+                    .behavior_version(BehaviorVersion::latest())
+                    .region(Region::from_static(AWS_STS_REGION))
+                    .credentials_provider(Credentials::new(
+                        access_key_id,
+                        secret_access_key,
+                        None,
+                        None,
+                        "si-provider-credential",
+                    ))
+                    .build();
+                let ttl = (expires_at - Utc::now()).max(MIN_AWS_SESSION_TTL);
+                let output = aws_sdk_sts::Client::from_conf(config)
+                    .get_session_token()
+                    .duration_seconds(i32::try_from(ttl.num_seconds()).unwrap_or(i32::MAX))
+                    .send()
+                    .await
+                    .map_err(|err| ProviderCredentialError::AwsStsGetSessionToken(Box::new(err)))?;
+                let credentials = output
+                    .credentials
+                    .ok_or(ProviderCredentialError::AwsStsMissingCredentials)?;
+                let expiration = DateTime::from_timestamp(
+                    credentials.expiration.secs(),
+                    credentials.expiration.subsec_nanos(),
+                )
+                .unwrap_or(expires_at);
+
+                Ok((
+                    DerivedProviderCredentialMaterial::AwsAccessKey {
+                        access_key_id: credentials.access_key_id,
+                        secret_access_key: credentials.secret_access_key,
+                        session_token: Some(credentials.session_token),
+                    },
+                    expiration,
+                ))
+            }
+            Self::AzureServicePrincipal {
+                tenant_id,
+                client_id,
+                client_secret,
+            } => {
+                let token = request_access_token(
+                    format!("https://login.microsoftonline.com/{tenant_id}/oauth2/v2.0/token"),
+                    &[
+                        ("grant_type", "client_credentials"),
+                        ("client_id", client_id.as_str()),
+                        ("client_secret", client_secret.as_str()),
+                        ("scope", AZURE_MANAGEMENT_SCOPE),
+                    ],
+                )
+                .await?;
+
+                Ok((
+                    DerivedProviderCredentialMaterial::AzureAccessToken {
+                        tenant_id: tenant_id.to_owned(),
+                        access_token: token.access_token,
+                    },
+                    Utc::now() + Duration::seconds(token.expires_in),
+                ))
+            }
+            Self::GcpServiceAccount {
+                service_account_key_json,
+            } => {
+                let key: GcpServiceAccountKey = serde_json::from_str(service_account_key_json)?;
+                let token_uri = key
+                    .token_uri
+                    .unwrap_or_else(|| GCP_DEFAULT_TOKEN_URI.to_owned());
+                let ttl = (expires_at - Utc::now())
+                    .min(MAX_DERIVED_CREDENTIAL_TTL)
+                    .to_std()
+                    .map_err(|_| ProviderCredentialError::InvalidTtl)?;
+                let claims = Claims::with_custom_claims(
+                    GcpAssertionClaims {
+                        scope: GCP_CLOUD_PLATFORM_SCOPE.to_owned(),
+                    },
+                    ttl.into(),
+                )
+                .with_issuer(&key.client_email)
+                .with_audience(&token_uri);
+                let assertion = RS256KeyPair::from_pem(&key.private_key)
+                    .and_then(|key_pair| key_pair.sign(claims))
+                    .map_err(|err| {
+                        ProviderCredentialError::GcpServiceAccountKey(err.to_string())
+                    })?;
+                let token = request_access_token(
+                    token_uri,
+                    &[
+                        ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                        ("assertion", assertion.as_str()),
+                    ],
+                )
+                .await?;
+
+                Ok((
+                    DerivedProviderCredentialMaterial::GcpAccessToken {
+                        access_token: token.access_token,
+                    },
+                    Utc::now() + Duration::seconds(token.expires_in),
+                ))
+            }
+            // Bearer tokens and session credentials are handed out as stored, until the expiry
+            // they were stored with
+            Self::AwsAccessKey {
+                access_key_id,
+                secret_access_key,
+                session_token: Some(session_token),
+            } => Ok((
+                DerivedProviderCredentialMaterial::AwsAccessKey {
+                    access_key_id: access_key_id.to_owned(),
+                    secret_access_key: secret_access_key.to_owned(),
+                    session_token: Some(session_token.to_owned()),
+                },
+                expires_at,
+            )),
+            Self::DigitalOceanToken { token } => Ok((
+                DerivedProviderCredentialMaterial::DigitalOceanToken {
+                    token: token.to_owned(),
+                },
+                expires_at,
+            )),
+            Self::Token { token } => Ok((
+                DerivedProviderCredentialMaterial::Token {
+                    token: token.to_owned(),
+                },
+                expires_at,
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GcpServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    token_uri: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct GcpAssertionClaims {
+    scope: String,
+}
+
+#[derive(Deserialize)]
+struct AccessTokenResponse {
+    access_token: String,
+    expires_in: i64,
+}
+
+async fn request_access_token(
+    token_uri: String,
+    form: &[(&str, &str)],
+) -> ProviderCredentialResult<AccessTokenResponse> {
+    Ok(reqwest::Client::new()
+        .post(token_uri)
+        .timeout(MINT_REQUEST_TIMEOUT)
+        .form(form)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+/// Consents are for the code a user reviewed, so they are keyed by the hash of the code too.
+fn func_code_hash(func: &Func) -> String {
+    ContentHash::new(func.code_base64.as_deref().unwrap_or_default().as_bytes()).to_string()
+}
+
+// Never print the material itself
+impl fmt::Debug for ProviderCredentialMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProviderCredentialMaterial")
+            .field("kind", &self.kind())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCredential {
+    id: ProviderCredentialId,
+    workspace_pk: WorkspacePk,
+    name: String,
+    kind: ProviderCredentialKind,
+    #[serde(skip)]
+    material_envelope: Vec<u8>,
+    #[serde(skip)]
+    material_key_hash: String,
+    created_by_user_pk: Option<UserPk>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<PgRow> for ProviderCredential {
+    type Error = ProviderCredentialError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let kind: String = row.try_get("kind")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            name: row.try_get("name")?,
+            kind: ProviderCredentialKind::from_str(&kind)
+                .map_err(|_| ProviderCredentialError::InvalidKind(kind))?,
+            material_envelope: row.try_get("material_envelope")?,
+            material_key_hash: row.try_get("material_key_hash")?,
+            created_by_user_pk: row.try_get("created_by_user_pk")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            expires_at: row.try_get("expires_at")?,
+            revoked_at: row.try_get("revoked_at")?,
+        })
+    }
+}
+
+impl ProviderCredential {
+    pub fn id(&self) -> ProviderCredentialId {
+        self.id
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> ProviderCredentialKind {
+        self.kind
+    }
+
+    pub fn created_by_user_pk(&self) -> Option<UserPk> {
+        self.created_by_user_pk
+    }
+
+    /// When the stored material stops being valid, if it was stored with an expiry.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    /// Create a new [`ProviderCredential`] in the workspace of the provided [`DalContext`].
+    /// Material which can't be minted from must be stored with the expiry it has at the provider.
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        material: &ProviderCredentialMaterial,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ProviderCredentialResult<Self> {
+        Self::check_expiry(material, expires_at)?;
+        let workspace_pk = ctx.workspace_pk()?;
+        let created_by_user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        };
+        let (envelope, key_hash) = Self::encrypt(ctx, material)?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO provider_credentials
+                (workspace_pk, name, kind, material_envelope, material_key_hash, created_by_user_pk, expires_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING *",
+                &[
+                    &workspace_pk,
+                    &name.as_ref(),
+                    &material.kind().as_ref(),
+                    &envelope,
+                    &key_hash,
+                    &created_by_user_pk,
+                    &expires_at,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Lists the active credentials of the workspace of the provided [`DalContext`].
+    pub async fn list_for_workspace(ctx: &DalContext) -> ProviderCredentialResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM provider_credentials
+                WHERE workspace_pk = $1 AND revoked_at IS NULL
+                ORDER BY created_at",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Find a [`ProviderCredential`] by its [`ProviderCredentialId`] within the provided
+    /// workspace.
+    pub async fn get_by_id(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        credential_id: ProviderCredentialId,
+    ) -> ProviderCredentialResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM provider_credentials WHERE id = $1 AND workspace_pk = $2",
+                &[&credential_id, &workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Decrypts the material of the credential.
+    pub fn material(
+        &self,
+        ctx: &DalContext,
+    ) -> ProviderCredentialResult<ProviderCredentialMaterial> {
+        let envelope = SymmetricEnvelope::from_bytes(&self.material_envelope)?;
+        let bytes = ctx.symmetric_crypto_service().open_envelope(&envelope)?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Re-encrypts the material of every credential, in every workspace, which is not wrapped
+    /// with the active symmetric key, in batches of `batch_size` with a `pause` in between. Each
+    /// credential is committed as it is re-encrypted. Returns the number of re-encrypted
+    /// credentials.
+    pub async fn reencrypt_materials(
+        ctx: &DalContext,
+        batch_size: usize,
+        pause: std::time::Duration,
+    ) -> ProviderCredentialResult<usize> {
+        let store = ProviderCredentialEnvelopes {
+            ctx,
+            workspace_pk: None,
+        };
+
+        ctx.symmetric_crypto_service()
+            .reencrypt_envelopes(&store, batch_size, pause)
+            .await
+    }
+
+    /// Like [`ProviderCredential::reencrypt_materials`], for the credentials of the workspace of
+    /// the provided [`DalContext`] only.
+    pub async fn reencrypt_materials_for_workspace(
+        ctx: &DalContext,
+        batch_size: usize,
+        pause: std::time::Duration,
+    ) -> ProviderCredentialResult<usize> {
+        let store = ProviderCredentialEnvelopes {
+            ctx,
+            workspace_pk: Some(ctx.workspace_pk()?),
+        };
+
+        ctx.symmetric_crypto_service()
+            .reencrypt_envelopes(&store, batch_size, pause)
+            .await
+    }
+
+    /// Replaces the material of the credential, e.g. when its keys are rotated at the provider.
+    pub async fn rotate(
+        self,
+        ctx: &DalContext,
+        material: &ProviderCredentialMaterial,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ProviderCredentialResult<Self> {
+        if !self.is_active() {
+            return Err(ProviderCredentialError::Revoked(self.id));
+        }
+        Self::check_expiry(material, expires_at)?;
+        let (envelope, key_hash) = Self::encrypt(ctx, material)?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "UPDATE provider_credentials
+                SET kind = $2, material_envelope = $3, material_key_hash = $4, expires_at = $5,
+                    updated_at = CLOCK_TIMESTAMP()
+                WHERE id = $1
+                RETURNING *",
+                &[
+                    &self.id,
+                    &material.kind().as_ref(),
+                    &envelope,
+                    &key_hash,
+                    &expires_at,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Revoke the credential, and with it every consent granted for it. Revoking an already
+    /// revoked credential keeps the original revocation time.
+    pub async fn revoke(self, ctx: &DalContext) -> ProviderCredentialResult<Self> {
+        let txns = ctx.txns().await?;
+        txns.pg()
+            .execute(
+                "UPDATE provider_credential_consents SET revoked_at = CLOCK_TIMESTAMP()
+                WHERE credential_id = $1 AND revoked_at IS NULL",
+                &[&self.id],
+            )
+            .await?;
+        let row = txns
+            .pg()
+            .query_one(
+                "UPDATE provider_credentials SET revoked_at = COALESCE(revoked_at, CLOCK_TIMESTAMP())
+                WHERE id = $1
+                RETURNING *",
+                &[&self.id],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Derives credentials for a function from every active, unexpired credential of the
+    /// workspace which a user consented to the current code of the function using. The derived
+    /// credentials are minted at the provider to be used for `ttl`, capped at
+    /// [`MAX_DERIVED_CREDENTIAL_TTL`] and at the expiry the credential was stored with.
+    pub async fn derive_for_func(
+        ctx: &DalContext,
+        func: &Func,
+        ttl: Duration,
+    ) -> ProviderCredentialResult<Vec<DerivedProviderCredential>> {
+        if ttl <= Duration::zero() {
+            return Err(ProviderCredentialError::InvalidTtl);
+        }
+        let workspace_pk = ctx.workspace_pk()?;
+        let expires_at = Utc::now() + ttl.min(MAX_DERIVED_CREDENTIAL_TTL);
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM provider_credentials
+                WHERE workspace_pk = $1 AND revoked_at IS NULL
+                    AND (expires_at IS NULL OR expires_at > CLOCK_TIMESTAMP())
+                    AND id IN (
+                        SELECT credential_id FROM provider_credential_consents
+                        WHERE func_id = $2 AND func_code_hash = $3 AND revoked_at IS NULL
+                    )
+                ORDER BY name",
+                &[&workspace_pk, &func.id, &func_code_hash(func)],
+            )
+            .await?;
+
+        let mut derived = Vec::with_capacity(rows.len());
+        for row in rows {
+            let credential = Self::try_from(row)?;
+            let expires_at = credential
+                .expires_at
+                .map_or(expires_at, |credential_expires_at| {
+                    credential_expires_at.min(expires_at)
+                });
+            let (material, expires_at) = credential.material(ctx)?.mint(expires_at).await?;
+            derived.push(DerivedProviderCredential {
+                credential_id: credential.id,
+                name: credential.name,
+                material,
+                expires_at,
+            });
+        }
+
+        Ok(derived)
+    }
+
+    fn check_expiry(
+        material: &ProviderCredentialMaterial,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ProviderCredentialResult<()> {
+        match expires_at {
+            Some(expires_at) if expires_at <= Utc::now() => {
+                Err(ProviderCredentialError::ExpiryInPast)
+            }
+            None if !material.can_mint() => {
+                Err(ProviderCredentialError::ExpiryRequired(material.kind()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn encrypt(
+        ctx: &DalContext,
+        material: &ProviderCredentialMaterial,
+    ) -> ProviderCredentialResult<(Vec<u8>, String)> {
+        let bytes = serde_json::to_vec(material)?;
+        let envelope = ctx.symmetric_crypto_service().seal_envelope(&bytes);
+
+        Ok((envelope.to_bytes()?, envelope.key_hash().to_string()))
+    }
+}
+
+/// The provider credentials of a workspace, or of every workspace, as a
+/// [`SymmetricEnvelopeStore`]. Queries go straight to the pool rather than through the
+/// transactions of the context, so that each re-encrypted credential is committed on its own.
+struct ProviderCredentialEnvelopes<'a> {
+    ctx: &'a DalContext,
+    workspace_pk: Option<WorkspacePk>,
+}
+
+#[async_trait]
+impl SymmetricEnvelopeStore for ProviderCredentialEnvelopes<'_> {
+    type Id = ProviderCredentialId;
+    type Error = ProviderCredentialError;
+
+    async fn list_not_wrapped_with(
+        &self,
+        key_hash: &Hash,
+        limit: usize,
+    ) -> ProviderCredentialResult<Vec<(ProviderCredentialId, SymmetricEnvelope)>> {
+        let client = self.ctx.pg_pool().get().await?;
+        let key_hash = key_hash.to_string();
+        let limit = limit as i64;
+        let rows = match &self.workspace_pk {
+            Some(workspace_pk) => {
+                client
+                    .query(
+                        "SELECT id, material_envelope
+                        FROM provider_credentials
+                        WHERE material_key_hash != $1 AND workspace_pk = $3
+                        LIMIT $2",
+                        &[&key_hash, &limit, workspace_pk],
+                    )
+                    .await?
+            }
+            None => {
+                client
+                    .query(
+                        "SELECT id, material_envelope
+                        FROM provider_credentials
+                        WHERE material_key_hash != $1
+                        LIMIT $2",
+                        &[&key_hash, &limit],
+                    )
+                    .await?
+            }
+        };
+
+        let mut envelopes = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: ProviderCredentialId = row.try_get("id")?;
+            let envelope: Vec<u8> = row.try_get("material_envelope")?;
+            let envelope = SymmetricEnvelope::from_bytes(&envelope)?;
+            envelopes.push((id, envelope));
+        }
+
+        Ok(envelopes)
+    }
+
+    async fn replace(
+        &self,
+        id: ProviderCredentialId,
+        envelope: SymmetricEnvelope,
+    ) -> ProviderCredentialResult<()> {
+        self.ctx
+            .pg_pool()
+            .get()
+            .await?
+            .execute(
+                "UPDATE provider_credentials
+                SET material_envelope = $2, material_key_hash = $3
+                WHERE id = $1",
+                &[&id, &envelope.to_bytes()?, &envelope.key_hash().to_string()],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// A user's consent for a function to use a [`ProviderCredential`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCredentialConsent {
+    id: ProviderCredentialConsentId,
+    credential_id: ProviderCredentialId,
+    func_id: FuncId,
+    func_code_hash: String,
+    granted_by_user_pk: Option<UserPk>,
+    granted_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<PgRow> for ProviderCredentialConsent {
+    type Error = ProviderCredentialError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            credential_id: row.try_get("credential_id")?,
+            func_id: row.try_get("func_id")?,
+            func_code_hash: row.try_get("func_code_hash")?,
+            granted_by_user_pk: row.try_get("granted_by_user_pk")?,
+            granted_at: row.try_get("granted_at")?,
+            revoked_at: row.try_get("revoked_at")?,
+        })
+    }
+}
+
+impl ProviderCredentialConsent {
+    pub fn id(&self) -> ProviderCredentialConsentId {
+        self.id
+    }
+
+    pub fn credential_id(&self) -> ProviderCredentialId {
+        self.credential_id
+    }
+
+    pub fn func_id(&self) -> FuncId {
+        self.func_id
+    }
+
+    /// The hash of the code of the function which the consent was granted for. Editing the code
+    /// of the function voids the consent.
+    pub fn func_code_hash(&self) -> &str {
+        &self.func_code_hash
+    }
+
+    pub fn granted_by_user_pk(&self) -> Option<UserPk> {
+        self.granted_by_user_pk
+    }
+
+    pub fn granted_at(&self) -> DateTime<Utc> {
+        self.granted_at
+    }
+
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    /// Grant the current code of a function the use of a credential, on behalf of the user in the
+    /// [`HistoryActor`] of the provided [`DalContext`].
+    pub async fn grant(
+        ctx: &DalContext,
+        credential: &ProviderCredential,
+        func: &Func,
+    ) -> ProviderCredentialResult<Self> {
+        let HistoryActor::User(user_pk) = ctx.history_actor() else {
+            return Err(ProviderCredentialError::NotGrantedByUser);
+        };
+        if !credential.is_active() {
+            return Err(ProviderCredentialError::Revoked(credential.id));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO provider_credential_consents
+                (credential_id, func_id, func_code_hash, granted_by_user_pk)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *",
+                &[&credential.id, &func.id, &func_code_hash(func), user_pk],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Find a [`ProviderCredentialConsent`] by its [`ProviderCredentialConsentId`] among the
+    /// consents granted for a credential.
+    pub async fn get_by_id(
+        ctx: &DalContext,
+        credential_id: ProviderCredentialId,
+        consent_id: ProviderCredentialConsentId,
+    ) -> ProviderCredentialResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM provider_credential_consents WHERE id = $1 AND credential_id = $2",
+                &[&consent_id, &credential_id],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Lists the consents granted for a credential which have not been revoked.
+    pub async fn list_for_credential(
+        ctx: &DalContext,
+        credential_id: ProviderCredentialId,
+    ) -> ProviderCredentialResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM provider_credential_consents
+                WHERE credential_id = $1 AND revoked_at IS NULL
+                ORDER BY granted_at",
+                &[&credential_id],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Revoke the consent. Revoking an already revoked consent keeps the original revocation time.
+    pub async fn revoke(self, ctx: &DalContext) -> ProviderCredentialResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "UPDATE provider_credential_consents SET revoked_at = COALESCE(revoked_at, CLOCK_TIMESTAMP())
+                WHERE id = $1
+                RETURNING *",
+                &[&self.id],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+}
+
+/// The material handed to a function, minted from a [`ProviderCredentialMaterial`].
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum DerivedProviderCredentialMaterial {
+    #[serde(rename_all = "camelCase")]
+    AwsAccessKey {
+        access_key_id: String,
+        secret_access_key: String,
+        session_token: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    AzureAccessToken {
+        tenant_id: String,
+        access_token: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    DigitalOceanToken { token: String },
+    #[serde(rename_all = "camelCase")]
+    GcpAccessToken { access_token: String },
+    #[serde(rename_all = "camelCase")]
+    Token { token: String },
+}
+
+impl DerivedProviderCredentialMaterial {
+    /// The environment variables which the SDKs and CLIs of the provider read credentials from.
+    pub fn env(&self) -> BTreeMap<&'static str, String> {
+        let mut env = BTreeMap::new();
+        match self {
+            Self::AwsAccessKey {
+                access_key_id,
+                secret_access_key,
+                session_token,
+            } => {
+                env.insert("AWS_ACCESS_KEY_ID", access_key_id.to_owned());
+                env.insert("AWS_SECRET_ACCESS_KEY", secret_access_key.to_owned());
+                if let Some(session_token) = session_token {
+                    env.insert("AWS_SESSION_TOKEN", session_token.to_owned());
+                }
+            }
+            Self::AzureAccessToken {
+                tenant_id,
+                access_token,
+            } => {
+                env.insert("AZURE_TENANT_ID", tenant_id.to_owned());
+                env.insert("AZURE_ACCESS_TOKEN", access_token.to_owned());
+            }
+            Self::DigitalOceanToken { token } => {
+                env.insert("DIGITALOCEAN_ACCESS_TOKEN", token.to_owned());
+            }
+            Self::GcpAccessToken { access_token } => {
+                env.insert("CLOUDSDK_AUTH_ACCESS_TOKEN", access_token.to_owned());
+                env.insert("GOOGLE_OAUTH_ACCESS_TOKEN", access_token.to_owned());
+            }
+            Self::Token { .. } => {}
+        }
+        env
+    }
+}
+
+// Never print the material itself
+impl fmt::Debug for DerivedProviderCredentialMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DerivedProviderCredentialMaterial")
+            .finish_non_exhaustive()
+    }
+}
+
+/// A credential derived from a [`ProviderCredential`] for a single function execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivedProviderCredential {
+    credential_id: ProviderCredentialId,
+    name: String,
+    material: DerivedProviderCredentialMaterial,
+    expires_at: DateTime<Utc>,
+}
+
+impl DerivedProviderCredential {
+    pub fn credential_id(&self) -> ProviderCredentialId {
+        self.credential_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn material(&self) -> &DerivedProviderCredentialMaterial {
+        &self.material
+    }
+
+    /// When the provider expires the material, or for material handed out as stored, when the
+    /// function must stop using it.
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// A before function which hands the credential to the function it runs before. The function
+    /// finds it under `providerCredential:<name>` in its request storage, and in the conventional
+    /// environment variables of its kind.
+    pub(crate) fn into_before_function(
+        self,
+        ctx: &DalContext,
+    ) -> ProviderCredentialResult<BeforeFunction> {
+        let mut arg = serde_json::json!({
+            "name": self.name,
+            "expiresAt": self.expires_at,
+            "env": self.material.env(),
+            "credential": self.material,
+        });
+        encrypt_value_tree(&mut arg, ctx.encryption_key())?;
+
+        Ok(BeforeFunction {
+            handler: PROVIDE_CREDENTIAL_HANDLER.to_owned(),
+            code_base64: general_purpose::STANDARD_NO_PAD.encode(PROVIDE_CREDENTIAL_CODE),
+            arg,
+        })
+    }
+}
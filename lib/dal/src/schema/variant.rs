@@ -64,15 +64,19 @@ use self::root_prop::RootPropChild;
 
 pub use json::SchemaVariantJson;
 pub use json::SchemaVariantMetadataJson;
+pub use metadata_update::SchemaVariantMetadataUpdate;
 pub use metadata_view::SchemaVariantMetadataView;
 pub use value_from::ValueFrom;
 
 pub mod authoring;
 mod json;
 pub mod leaves;
+mod materialized_view;
+mod metadata_update;
 mod metadata_view;
 pub mod root_prop;
 mod value_from;
+pub mod variant_trait;
 
 // FIXME(nick,theo): colors should be required for all schema variants.
 // There should be no default in the backend as there should always be a color.
@@ -2271,7 +2275,8 @@ impl SchemaVariant {
                 | EdgeWeightKindDiscriminants::SocketValue
                 | EdgeWeightKindDiscriminants::ValidationOutput
                 | EdgeWeightKindDiscriminants::Manages
-                | EdgeWeightKindDiscriminants::DiagramObject => {}
+                | EdgeWeightKindDiscriminants::DiagramObject
+                | EdgeWeightKindDiscriminants::ExpressionReference => {}
             }
         }
 
@@ -2348,7 +2353,7 @@ impl SchemaVariant {
                 schema_variants.insert(
                     default_schema_variant.id,
                     default_schema_variant
-                        .into_frontend_type(ctx, schema_id)
+                        .into_frontend_type_materialized(ctx, schema_id)
                         .await?,
                 );
             }
@@ -2357,7 +2362,9 @@ impl SchemaVariant {
                 if !unlocked.ui_hidden() {
                     schema_variants.insert(
                         unlocked.id,
-                        unlocked.into_frontend_type(ctx, schema_id).await?,
+                        unlocked
+                            .into_frontend_type_materialized(ctx, schema_id)
+                            .await?,
                     );
                 }
             }
@@ -2369,7 +2376,9 @@ impl SchemaVariant {
                 {
                     schema_variants.insert(
                         schema_variant.id,
-                        schema_variant.into_frontend_type(ctx, schema_id).await?,
+                        schema_variant
+                            .into_frontend_type_materialized(ctx, schema_id)
+                            .await?,
                     );
                 }
             }
@@ -30,6 +30,7 @@ use crate::pkg::export::PkgExporter;
 use crate::pkg::import::import_only_new_funcs;
 use crate::pkg::{import_pkg_from_pkg, ImportOptions, PkgError};
 use crate::prop::PropError;
+use crate::schema::variant::variant_trait::{SchemaVariantTrait, SchemaVariantTraitError};
 use crate::schema::variant::{SchemaVariantJson, SchemaVariantMetadataJson};
 use crate::socket::input::InputSocketError;
 use crate::socket::output::OutputSocketError;
@@ -97,6 +98,8 @@ pub enum VariantAuthoringError {
     SchemaVariantAssetNotFound(SchemaVariantId),
     #[error("schema variant not found: {0}")]
     SchemaVariantNotFound(SchemaVariantId),
+    #[error("schema variant trait error: {0}")]
+    SchemaVariantTrait(#[from] SchemaVariantTraitError),
     #[error("schema variant not updated: {0}")]
     SchemaVariantUpdatedFailed(SchemaVariantId),
     #[error("json serialization error: {0}")]
@@ -175,6 +178,7 @@ impl VariantAuthoringClient {
             description: description.clone(),
         };
         let email = ctx.history_actor().email(ctx).await?;
+        let trait_names = definition.traits.clone();
 
         let pkg_spec =
             build_pkg_spec_for_variant(&name, definition, &asset_func_spec, &metadata, &email)?;
@@ -200,8 +204,16 @@ impl VariantAuthoringClient {
             .first()
             .copied()
             .ok_or(VariantAuthoringError::NoAssetCreated)?;
+        let schema_variant = SchemaVariant::get_by_id_or_error(ctx, schema_variant_id).await?;
+
+        SchemaVariantTrait::set_links_for_schema(
+            ctx,
+            schema_variant.schema_id(ctx).await?,
+            &trait_names,
+        )
+        .await?;
 
-        Ok(SchemaVariant::get_by_id_or_error(ctx, schema_variant_id).await?)
+        Ok(schema_variant)
     }
 
     pub async fn create_schema_and_variant(
@@ -262,6 +274,7 @@ impl VariantAuthoringClient {
                 description: variant.description().clone(),
             };
             let email = ctx.history_actor().email(ctx).await?;
+            let trait_names = definition.traits.clone();
             let pkg_spec = build_pkg_spec_for_variant(
                 &schema.name,
                 definition,
@@ -291,11 +304,17 @@ impl VariantAuthoringClient {
                 .first()
                 .copied()
                 .ok_or(VariantAuthoringError::NoAssetCreated)?;
+            let new_schema_variant =
+                SchemaVariant::get_by_id_or_error(ctx, new_schema_variant_id).await?;
 
-            Ok((
-                SchemaVariant::get_by_id_or_error(ctx, new_schema_variant_id).await?,
-                schema,
-            ))
+            SchemaVariantTrait::set_links_for_schema(
+                ctx,
+                new_schema_variant.schema_id(ctx).await?,
+                &trait_names,
+            )
+            .await?;
+
+            Ok((new_schema_variant, schema))
         } else {
             return Err(VariantAuthoringError::SchemaVariantAssetNotFound(
                 schema_variant_id,
@@ -417,6 +436,7 @@ impl VariantAuthoringClient {
             description: description.clone(),
         };
 
+        let trait_names = definition.traits.clone();
         let (new_variant_spec, _skips, variant_funcs) =
             build_variant_spec_based_on_existing_variant(
                 ctx,
@@ -464,6 +484,7 @@ impl VariantAuthoringClient {
                 Ok(())
             })
             .await?;
+        SchemaVariantTrait::set_links_for_schema(ctx, schema.id(), &trait_names).await?;
 
         // We need to clean up the old graph before we re-import the new parts!
         current_schema_variant
@@ -549,6 +570,7 @@ impl VariantAuthoringClient {
             description: description.clone(),
         };
 
+        let trait_names = definition.traits.clone();
         let (new_variant_spec, _skips, variant_funcs) =
             build_variant_spec_based_on_existing_variant(
                 ctx,
@@ -598,6 +620,7 @@ impl VariantAuthoringClient {
                 Ok(())
             })
             .await?;
+        SchemaVariantTrait::set_links_for_schema(ctx, schema.id(), &trait_names).await?;
 
         let mut thing_map = import_only_new_funcs(ctx, pkg.funcs()?).await?;
 
@@ -839,7 +862,8 @@ impl VariantAuthoringClient {
             ));
         };
 
-        ctx.layer_db()
+        let func_run = ctx
+            .layer_db()
             .func_run()
             .set_state_to_success(
                 func_run_value.func_run_id(),
@@ -847,8 +871,47 @@ impl VariantAuthoringClient {
                 ctx.events_actor(),
             )
             .await?;
+        FuncRunner::publish_status(ctx, &func_run).await;
+
+        Ok(SchemaVariantTrait::apply(ctx, definition).await?)
+    }
+
+    /// Regenerate the unlocked variant of every [`Schema`] that includes the named
+    /// [`SchemaVariantTrait`] in this change set, so that they pick up its current fragment.
+    /// Schemas whose default variant is locked get a new unlocked copy first.
+    ///
+    /// Returns the ids of the regenerated variants.
+    #[instrument(name = "variant.authoring.propagate_trait", level = "info", skip(ctx))]
+    pub async fn propagate_trait(
+        ctx: &DalContext,
+        trait_name: &str,
+    ) -> VariantAuthoringResult<Vec<SchemaVariantId>> {
+        let mut regenerated = Vec::new();
+
+        for schema_id in SchemaVariantTrait::list_consuming_schema_ids(ctx, trait_name).await? {
+            // Links of the default change set are visible here, but the schema may not exist in
+            // this change set
+            if !Schema::exists_locally(ctx, schema_id).await? {
+                continue;
+            }
+
+            let unlocked_variant_id = match SchemaVariant::get_unlocked_for_schema(ctx, schema_id)
+                .await?
+            {
+                Some(unlocked_variant) => unlocked_variant.id,
+                None => {
+                    let default_variant_id =
+                        Schema::get_default_schema_variant_by_id_or_error(ctx, schema_id).await?;
+                    Self::create_unlocked_variant_copy(ctx, default_variant_id)
+                        .await?
+                        .id
+                }
+            };
+
+            regenerated.push(Self::regenerate_variant(ctx, unlocked_variant_id).await?);
+        }
 
-        Ok(definition)
+        Ok(regenerated)
     }
 }
 
@@ -77,6 +77,10 @@ pub struct SchemaVariantJson {
     /// the "doc_link_ref" field for a [`PropDefinition`].
     #[serde(skip_serializing_if = "Option::is_none")]
     pub doc_links: Option<HashMap<String, String>>,
+    /// The names of the [`SchemaVariantTraits`](crate::schema::variant::variant_trait::SchemaVariantTrait)
+    /// whose fragments are merged into this definition.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub traits: Vec<String>,
 }
 
 impl SchemaVariantJson {
@@ -0,0 +1,73 @@
+//! Rendering a [`SchemaVariant`] into its [frontend type](FrontendVariant) walks its props,
+//! sockets and funcs. Since that only changes when the variant (or its schema) is mutated, the
+//! rendered view is materialized in the layer db and reused across requests and change sets.
+//!
+//! Views are keyed by the merkle tree hash of the [`Schema`](crate::Schema) node, which covers
+//! every node underneath it, including all of its variants, their props, sockets and funcs and
+//! which variant is the default. Any mutation therefore results in a new key, which is what
+//! invalidates the cached view.
+
+use si_events::ContentHash;
+use si_frontend_types::SchemaVariant as FrontendVariant;
+use telemetry::prelude::*;
+
+use crate::{DalContext, SchemaId};
+
+use super::{SchemaVariant, SchemaVariantId, SchemaVariantResult};
+
+/// Bump this whenever [`SchemaVariant::into_frontend_type`] or [`FrontendVariant`] changes, so that
+/// views rendered by older code are not used.
+const VIEW_VERSION: &str = "v1";
+
+impl SchemaVariant {
+    /// Like [`Self::into_frontend_type`], but reuses the materialized view if nothing that it was
+    /// rendered from has changed since.
+    ///
+    /// Whether the variant can be contributed depends on module membership, which is not covered
+    /// by the key, so it is always computed.
+    pub async fn into_frontend_type_materialized(
+        self,
+        ctx: &DalContext,
+        schema_id: SchemaId,
+    ) -> SchemaVariantResult<FrontendVariant> {
+        let Some(key) = Self::materialized_view_key(ctx, schema_id, self.id).await? else {
+            return self.into_frontend_type(ctx, schema_id).await;
+        };
+
+        let layer_db = ctx.layer_db().materialized_view();
+        if let Some(mut view) = layer_db.read::<FrontendVariant>(&key).await? {
+            view.can_contribute = Self::can_be_contributed_by_id(ctx, self.id).await?;
+            return Ok(view);
+        }
+
+        let schema_variant_id = self.id;
+        let view = self.into_frontend_type(ctx, schema_id).await?;
+        layer_db.write(key, &view, None, ctx.events_tenancy(), ctx.events_actor())?;
+        debug!(
+            si.schema_variant.id = %schema_variant_id,
+            "materialized schema variant view",
+        );
+
+        Ok(view)
+    }
+
+    /// Returns `None` if the snapshot has been modified, since merkle tree hashes are only
+    /// recalculated when the snapshot is written.
+    async fn materialized_view_key(
+        ctx: &DalContext,
+        schema_id: SchemaId,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<Option<ContentHash>> {
+        let Some(schema_hash) = ctx
+            .workspace_snapshot()?
+            .merkle_tree_hash_for_id_if_unmodified(schema_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(ContentHash::new(
+            format!("{VIEW_VERSION}:{schema_id}:{schema_variant_id}:{schema_hash}").as_bytes(),
+        )))
+    }
+}
@@ -0,0 +1,111 @@
+use telemetry::prelude::*;
+
+use crate::schema::variant::{SchemaVariantError, SchemaVariantResult};
+use crate::{ComponentType, DalContext, Func, SchemaVariant, SchemaVariantId};
+
+/// The metadata of an unlocked [`SchemaVariant`] to change with
+/// [`SchemaVariant::update_metadata`]. Fields left as `None` are not changed. For the optional
+/// metadata, `Some(None)` clears the value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaVariantMetadataUpdate {
+    pub display_name: Option<String>,
+    pub category: Option<String>,
+    pub color: Option<String>,
+    pub component_type: Option<ComponentType>,
+    pub description: Option<Option<String>>,
+    pub link: Option<Option<String>>,
+}
+
+impl SchemaVariantMetadataUpdate {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl SchemaVariant {
+    /// Updates the metadata of an unlocked [`SchemaVariant`] in place, without regenerating it
+    /// from its asset func.
+    ///
+    /// The defaults for "/root/si/color" and "/root/si/type" are updated alongside the variant so
+    /// that new components pick up the change, and the display name, description and link are
+    /// mirrored onto the asset func, as they are when saving the variant. Since regenerating
+    /// reads the metadata from the variant itself, the changes are kept by later regenerates.
+    #[instrument(
+        name = "schema_variant.update_metadata",
+        level = "info",
+        skip(ctx, update)
+    )]
+    pub async fn update_metadata(
+        ctx: &DalContext,
+        id: SchemaVariantId,
+        update: SchemaVariantMetadataUpdate,
+    ) -> SchemaVariantResult<Self> {
+        let schema_variant = Self::get_by_id_or_error(ctx, id).await?;
+        if schema_variant.is_locked() {
+            return Err(SchemaVariantError::SchemaVariantLocked(id));
+        }
+        if update.is_empty() {
+            return Ok(schema_variant);
+        }
+
+        let original_color = schema_variant.color.clone();
+        let original_type = schema_variant.component_type;
+
+        let SchemaVariantMetadataUpdate {
+            display_name,
+            category,
+            color,
+            component_type,
+            description,
+            link,
+        } = update;
+        let asset_func_changed = display_name.is_some() || description.is_some() || link.is_some();
+
+        let schema_variant = schema_variant
+            .modify(ctx, |sv| {
+                if let Some(display_name) = display_name {
+                    sv.display_name = display_name;
+                }
+                if let Some(category) = category {
+                    sv.category = category;
+                }
+                if let Some(color) = color {
+                    sv.color = color;
+                }
+                if let Some(component_type) = component_type {
+                    sv.component_type = component_type;
+                }
+                if let Some(description) = description {
+                    sv.description = description;
+                }
+                if let Some(link) = link {
+                    sv.link = link;
+                }
+                Ok(())
+            })
+            .await?;
+
+        if schema_variant.color != original_color {
+            schema_variant.set_color(ctx, &schema_variant.color).await?;
+        }
+        if schema_variant.component_type != original_type {
+            schema_variant
+                .set_type(ctx, schema_variant.component_type.to_string())
+                .await?;
+        }
+
+        if asset_func_changed {
+            if let Some(asset_func_id) = schema_variant.asset_func_id {
+                Func::modify_by_id(ctx, asset_func_id, |func| {
+                    func.display_name = Some(schema_variant.display_name.clone());
+                    func.description.clone_from(&schema_variant.description);
+                    func.link.clone_from(&schema_variant.link);
+                    Ok(())
+                })
+                .await?;
+            }
+        }
+
+        Ok(schema_variant)
+    }
+}
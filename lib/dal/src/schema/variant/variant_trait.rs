@@ -0,0 +1,388 @@
+//! This module contains [`SchemaVariantTrait`], a named, reusable fragment of a
+//! [`SchemaVariantJson`] (e.g. the "tags", "region" or credential props shared by many assets).
+//!
+//! Asset funcs include a trait by name with `AssetBuilder.addTrait()`. When the asset func is
+//! executed, the fragment is merged into the definition and a link between the
+//! [`Schema`](crate::Schema) and the trait is stored, so that updates to the trait can be
+//! propagated to every consuming variant (see
+//! [`VariantAuthoringClient::propagate_trait`](super::authoring::VariantAuthoringClient::propagate_trait)).
+//!
+//! Like the variants themselves, traits and links are authored in a change set. They are stored
+//! for that change set, shadowing the ones of the default change set, and moved over to the base
+//! change set when the change set is applied (see [`SchemaVariantTrait::promote`]).
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use thiserror::Error;
+
+use crate::{workspace::WorkspaceId, ChangeSetId, DalContext, SchemaId, TransactionsError};
+
+use super::json::{PropDefinition, SocketDefinition};
+use super::SchemaVariantJson;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SchemaVariantTraitError {
+    #[error("trait {0} defines {1}, which the asset already defines")]
+    Conflict(String, String),
+    #[error("trait not found: {0}")]
+    NotFound(String),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SchemaVariantTraitResult<T> = Result<T, SchemaVariantTraitError>;
+
+/// The parts of a [`SchemaVariantJson`] that a [`SchemaVariantTrait`] contributes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SchemaVariantTraitFragment {
+    /// Props added underneath "/root/domain".
+    #[serde(default)]
+    pub props: Vec<PropDefinition>,
+    /// Props added underneath "/root/secrets".
+    #[serde(default)]
+    pub secret_props: Vec<PropDefinition>,
+    #[serde(default)]
+    pub input_sockets: Vec<SocketDefinition>,
+    #[serde(default)]
+    pub output_sockets: Vec<SocketDefinition>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaVariantTrait {
+    workspace_pk: WorkspaceId,
+    name: String,
+    fragment: SchemaVariantTraitFragment,
+}
+
+impl TryFrom<PgRow> for SchemaVariantTrait {
+    type Error = SchemaVariantTraitError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let fragment: serde_json::Value = row.try_get("fragment")?;
+        Ok(Self {
+            workspace_pk: row.try_get("workspace_pk")?,
+            name: row.try_get("name")?,
+            fragment: serde_json::from_value(fragment)?,
+        })
+    }
+}
+
+impl SchemaVariantTrait {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn fragment(&self) -> &SchemaVariantTraitFragment {
+        &self.fragment
+    }
+
+    /// Create the named trait in the change set of the provided [`DalContext`], or replace its
+    /// fragment if it already exists. Consuming variants are not regenerated.
+    pub async fn upsert(
+        ctx: &DalContext,
+        name: impl Into<String>,
+        fragment: SchemaVariantTraitFragment,
+    ) -> SchemaVariantTraitResult<Self> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let name = name.into();
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO schema_variant_traits (workspace_pk, change_set_id, name, fragment)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (workspace_pk, change_set_id, name) DO
+                UPDATE SET fragment = $4, updated_at = CLOCK_TIMESTAMP()
+                RETURNING *",
+                &[
+                    &workspace_pk,
+                    &ctx.change_set_id(),
+                    &name,
+                    &serde_json::to_value(&fragment)?,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    pub async fn get_by_name(
+        ctx: &DalContext,
+        name: &str,
+    ) -> SchemaVariantTraitResult<Option<Self>> {
+        let (workspace_pk, change_set_id, head_change_set_id) = Self::scope(ctx).await?;
+
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM schema_variant_traits
+                WHERE workspace_pk = $1 AND change_set_id IN ($2, $3) AND name = $4
+                ORDER BY change_set_id = $2 DESC
+                LIMIT 1",
+                &[&workspace_pk, &change_set_id, &head_change_set_id, &name],
+            )
+            .await?;
+
+        maybe_row
+            .filter(|row| {
+                // Deleted traits are recorded without a fragment
+                matches!(
+                    row.try_get::<_, Option<serde_json::Value>>("fragment"),
+                    Ok(Some(_))
+                )
+            })
+            .map(Self::try_from)
+            .transpose()
+    }
+
+    pub async fn list(ctx: &DalContext) -> SchemaVariantTraitResult<Vec<Self>> {
+        let (workspace_pk, change_set_id, head_change_set_id) = Self::scope(ctx).await?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM (
+                    SELECT DISTINCT ON (name) * FROM schema_variant_traits
+                    WHERE workspace_pk = $1 AND change_set_id IN ($2, $3)
+                    ORDER BY name, change_set_id = $2 DESC
+                ) AS visible
+                WHERE fragment IS NOT NULL
+                ORDER BY name",
+                &[&workspace_pk, &change_set_id, &head_change_set_id],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Delete the trait in the change set of the provided [`DalContext`]. Variants that include it
+    /// will fail to regenerate until the include is removed from their asset funcs.
+    pub async fn delete(self, ctx: &DalContext) -> SchemaVariantTraitResult<()> {
+        // The deletion is recorded rather than performed, so that it hides the trait of the
+        // default change set until this change set is applied
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "INSERT INTO schema_variant_traits (workspace_pk, change_set_id, name, fragment)
+                VALUES ($1, $2, $3, NULL)
+                ON CONFLICT (workspace_pk, change_set_id, name) DO
+                UPDATE SET fragment = NULL, updated_at = CLOCK_TIMESTAMP()",
+                &[&self.workspace_pk, &ctx.change_set_id(), &self.name],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// List the [`Schemas`](crate::Schema) whose asset funcs include the named trait.
+    pub async fn list_consuming_schema_ids(
+        ctx: &DalContext,
+        name: &str,
+    ) -> SchemaVariantTraitResult<Vec<SchemaId>> {
+        let (workspace_pk, change_set_id, head_change_set_id) = Self::scope(ctx).await?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT schema_id FROM (
+                    SELECT DISTINCT ON (schema_id) schema_id, trait_names
+                    FROM schema_variant_trait_links
+                    WHERE workspace_pk = $1 AND change_set_id IN ($2, $3)
+                    ORDER BY schema_id, change_set_id = $2 DESC
+                ) AS visible
+                WHERE $4 = ANY(trait_names)
+                ORDER BY schema_id",
+                &[&workspace_pk, &change_set_id, &head_change_set_id, &name],
+            )
+            .await?;
+
+        let mut schema_ids = Vec::with_capacity(rows.len());
+        for row in rows {
+            schema_ids.push(row.try_get("schema_id")?);
+        }
+
+        Ok(schema_ids)
+    }
+
+    /// Replace the traits linked to the [`Schema`](crate::Schema) with the provided names, in the
+    /// change set of the provided [`DalContext`].
+    pub async fn set_links_for_schema(
+        ctx: &DalContext,
+        schema_id: SchemaId,
+        trait_names: &[String],
+    ) -> SchemaVariantTraitResult<()> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "INSERT INTO schema_variant_trait_links
+                    (workspace_pk, change_set_id, schema_id, trait_names)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (workspace_pk, change_set_id, schema_id) DO
+                UPDATE SET trait_names = $4",
+                &[
+                    &workspace_pk,
+                    &ctx.change_set_id(),
+                    &schema_id,
+                    &trait_names,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Moves the traits and links written in a change set which is being applied to its base
+    /// change set, replacing the ones there.
+    pub async fn promote(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+        base_change_set_id: ChangeSetId,
+    ) -> SchemaVariantTraitResult<()> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let txns = ctx.txns().await?;
+
+        txns.pg()
+            .execute(
+                "INSERT INTO schema_variant_traits (workspace_pk, change_set_id, name, fragment)
+                SELECT workspace_pk, $3, name, fragment FROM schema_variant_traits
+                WHERE workspace_pk = $1 AND change_set_id = $2
+                ON CONFLICT (workspace_pk, change_set_id, name) DO
+                UPDATE SET fragment = EXCLUDED.fragment, updated_at = CLOCK_TIMESTAMP()",
+                &[&workspace_pk, &change_set_id, &base_change_set_id],
+            )
+            .await?;
+        txns.pg()
+            .execute(
+                "DELETE FROM schema_variant_traits WHERE workspace_pk = $1 AND change_set_id = $2",
+                &[&workspace_pk, &change_set_id],
+            )
+            .await?;
+
+        txns.pg()
+            .execute(
+                "INSERT INTO schema_variant_trait_links
+                    (workspace_pk, change_set_id, schema_id, trait_names)
+                SELECT workspace_pk, $3, schema_id, trait_names FROM schema_variant_trait_links
+                WHERE workspace_pk = $1 AND change_set_id = $2
+                ON CONFLICT (workspace_pk, change_set_id, schema_id) DO
+                UPDATE SET trait_names = EXCLUDED.trait_names",
+                &[&workspace_pk, &change_set_id, &base_change_set_id],
+            )
+            .await?;
+        txns.pg()
+            .execute(
+                "DELETE FROM schema_variant_trait_links
+                WHERE workspace_pk = $1 AND change_set_id = $2",
+                &[&workspace_pk, &change_set_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Traits and links are read from the change set of the provided [`DalContext`], falling
+    /// back to the ones of the default change set.
+    async fn scope(
+        ctx: &DalContext,
+    ) -> SchemaVariantTraitResult<(WorkspaceId, ChangeSetId, ChangeSetId)> {
+        Ok((
+            ctx.workspace_pk()?,
+            ctx.change_set_id(),
+            ctx.get_workspace_default_change_set_id().await?,
+        ))
+    }
+
+    /// Merge the fragments of every trait included by the definition into it. Names of props and
+    /// sockets must not collide with the ones the asset defines itself (or another trait).
+    pub async fn apply(
+        ctx: &DalContext,
+        mut definition: SchemaVariantJson,
+    ) -> SchemaVariantTraitResult<SchemaVariantJson> {
+        for trait_name in definition.traits.clone() {
+            let variant_trait = Self::get_by_name(ctx, &trait_name)
+                .await?
+                .ok_or_else(|| SchemaVariantTraitError::NotFound(trait_name.clone()))?;
+            variant_trait.merge_into(&mut definition)?;
+        }
+
+        Ok(definition)
+    }
+
+    fn merge_into(self, definition: &mut SchemaVariantJson) -> SchemaVariantTraitResult<()> {
+        let SchemaVariantTraitFragment {
+            props,
+            secret_props,
+            input_sockets,
+            output_sockets,
+        } = self.fragment;
+
+        merge_named(&self.name, "prop", &mut definition.props, props, |p| {
+            &p.name
+        })?;
+        merge_named(
+            &self.name,
+            "secret prop",
+            &mut definition.secret_props,
+            secret_props,
+            |p| &p.name,
+        )?;
+        merge_named(
+            &self.name,
+            "input socket",
+            &mut definition.input_sockets,
+            input_sockets,
+            |s| &s.name,
+        )?;
+        merge_named(
+            &self.name,
+            "output socket",
+            &mut definition.output_sockets,
+            output_sockets,
+            |s| &s.name,
+        )?;
+
+        Ok(())
+    }
+}
+
+fn merge_named<T>(
+    trait_name: &str,
+    kind: &str,
+    existing: &mut Vec<T>,
+    additions: Vec<T>,
+    name: impl Fn(&T) -> &String,
+) -> SchemaVariantTraitResult<()> {
+    let mut names: HashSet<String> = existing.iter().map(|item| name(item).clone()).collect();
+    for addition in additions {
+        if !names.insert(name(&addition).clone()) {
+            return Err(SchemaVariantTraitError::Conflict(
+                trait_name.to_owned(),
+                format!("{kind} \"{}\"", name(&addition)),
+            ));
+        }
+        existing.push(addition);
+    }
+
+    Ok(())
+}
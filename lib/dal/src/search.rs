@@ -0,0 +1,473 @@
+//! This module contains [`SearchIndex`], an inverted index over the names of the
+//! [`Schemas`](Schema), [`Components`](Component), [`Funcs`](Func) and [`Props`](Prop) in a
+//! snapshot, as well as the code of funcs and the values set on components, used for
+//! workspace-wide fuzzy search.
+//!
+//! Indexes are stored as materialized views in the layer db, keyed by the address of the snapshot
+//! they were built from. The rebaser builds the index whenever it writes a new snapshot for HEAD;
+//! snapshots without an index (e.g. ones written before this existed) are indexed on first search.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use si_events::{ulid::Ulid, ContentHash};
+use si_layer_cache::LayerDbError;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    attribute::value::AttributeValueError, prop::PropError, AttributeValue, Component,
+    ComponentError, DalContext, Func, FuncError, Prop, Schema, SchemaError, SchemaVariant,
+    SchemaVariantError, TransactionsError, WorkspaceSnapshotError,
+};
+
+/// Bump this whenever the contents of [`SearchIndex`] change, so that indexes built by older code
+/// are not used.
+const INDEX_VERSION: &str = "v2";
+
+/// Query terms shorter than this are only matched exactly or as a prefix (and only exactly
+/// against func code and values).
+const MIN_FUZZY_TERM_LEN: usize = 4;
+
+/// Values longer than this are truncated in the names of their entries. All of the value is
+/// still searchable.
+const MAX_VALUE_NAME_LEN: usize = 120;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SearchError {
+    #[error("attribute value error: {0}")]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("prop error: {0}")]
+    Prop(#[from] PropError),
+    #[error("schema error: {0}")]
+    Schema(#[from] SchemaError),
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] SchemaVariantError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace snapshot error: {0}")]
+    WorkspaceSnapshot(#[from] WorkspaceSnapshotError),
+}
+
+pub type SearchResult<T> = Result<T, SearchError>;
+
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumString,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum SearchResultKind {
+    /// A value set on a component. The entry is named after the value.
+    AttributeValue,
+    Component,
+    /// Matches on func code as well as on names.
+    Func,
+    /// A prop of the default variant of a schema.
+    Prop,
+    Schema,
+}
+
+impl SearchResultKind {
+    /// Breaks ties between equally good hits, lowest first, so that e.g. a component ranks above
+    /// the value its name is copied into.
+    fn priority(self) -> u8 {
+        match self {
+            Self::Component => 0,
+            Self::Func => 1,
+            Self::Schema => 2,
+            Self::Prop => 3,
+            Self::AttributeValue => 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchEntry {
+    pub kind: SearchResultKind,
+    pub id: Ulid,
+    pub name: String,
+    /// Additional context for the entry, e.g. the schema name of a component.
+    pub detail: Option<String>,
+    /// Where the entry lives, e.g. the path of a prop or of a value within its component. Not
+    /// searchable.
+    pub path: Option<String>,
+    /// The component a value is set on, or the schema variant a prop belongs to.
+    pub parent_id: Option<Ulid>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub entry: SearchEntry,
+    /// Higher is better. Only comparable between hits for the same query.
+    pub score: u32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SearchIndex {
+    entries: Vec<SearchEntry>,
+    /// Maps each token to the positions of the entries containing it.
+    tokens: BTreeMap<String, BTreeSet<usize>>,
+    /// Like `tokens`, for func code and long values, which rank below names.
+    body_tokens: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl SearchIndex {
+    /// Build the index for the snapshot of the provided [`DalContext`].
+    #[instrument(name = "search_index.build", level = "debug", skip_all)]
+    pub async fn build(ctx: &DalContext) -> SearchResult<Self> {
+        let mut index = Self::default();
+
+        for schema in Schema::list(ctx).await? {
+            index.insert(
+                SearchEntry {
+                    kind: SearchResultKind::Schema,
+                    id: schema.id().into(),
+                    name: schema.name().to_owned(),
+                    detail: None,
+                    path: None,
+                    parent_id: None,
+                },
+                None,
+            );
+
+            let Some(schema_variant_id) = schema.get_default_schema_variant_id(ctx).await? else {
+                continue;
+            };
+            for prop_id in SchemaVariant::all_prop_ids(ctx, schema_variant_id).await? {
+                let prop = Prop::get_by_id(ctx, prop_id).await?;
+                index.insert(
+                    SearchEntry {
+                        kind: SearchResultKind::Prop,
+                        id: prop_id.into(),
+                        name: prop.name,
+                        detail: Some(schema.name().to_owned()),
+                        path: Some(Prop::path_by_id(ctx, prop_id).await?.with_replaced_sep("/")),
+                        parent_id: Some(schema_variant_id.into()),
+                    },
+                    None,
+                );
+            }
+        }
+
+        for component in Component::list(ctx).await? {
+            let schema = Component::schema_for_component_id(ctx, component.id()).await?;
+            let name = component.name(ctx).await?;
+            index.insert(
+                SearchEntry {
+                    kind: SearchResultKind::Component,
+                    id: component.id().into(),
+                    name: name.clone(),
+                    detail: Some(schema.name().to_owned()),
+                    path: None,
+                    parent_id: None,
+                },
+                None,
+            );
+
+            let mut work_queue = vec![component.domain_prop_attribute_value(ctx).await?];
+            while let Some(attribute_value_id) = work_queue.pop() {
+                let child_ids =
+                    AttributeValue::get_child_av_ids_in_order(ctx, attribute_value_id).await?;
+                if !child_ids.is_empty() {
+                    work_queue.extend(child_ids);
+                    continue;
+                }
+
+                let value = match AttributeValue::get_by_id(ctx, attribute_value_id)
+                    .await?
+                    .value(ctx)
+                    .await?
+                {
+                    Some(serde_json::Value::String(value)) => value,
+                    Some(serde_json::Value::Number(value)) => value.to_string(),
+                    _ => continue,
+                };
+                if value.trim().is_empty() {
+                    continue;
+                }
+                let (value_name, body) = if value.chars().count() > MAX_VALUE_NAME_LEN {
+                    let truncated: String = value.chars().take(MAX_VALUE_NAME_LEN).collect();
+                    (format!("{truncated}…"), Some(value))
+                } else {
+                    (value, None)
+                };
+                index.insert(
+                    SearchEntry {
+                        kind: SearchResultKind::AttributeValue,
+                        id: attribute_value_id.into(),
+                        name: value_name,
+                        detail: Some(name.clone()),
+                        path: AttributeValue::get_path_for_id(ctx, attribute_value_id).await?,
+                        parent_id: Some(component.id().into()),
+                    },
+                    body.as_deref(),
+                );
+            }
+        }
+
+        for func in Func::list_all(ctx).await? {
+            if func.hidden {
+                continue;
+            }
+            let code = func.code_plaintext()?;
+            index.insert(
+                SearchEntry {
+                    kind: SearchResultKind::Func,
+                    id: func.id.into(),
+                    detail: func
+                        .display_name
+                        .filter(|display_name| *display_name != func.name),
+                    name: func.name,
+                    path: None,
+                    parent_id: None,
+                },
+                code.as_deref(),
+            );
+        }
+
+        Ok(index)
+    }
+
+    /// Build the index for the snapshot of the provided [`DalContext`] and store it in the layer
+    /// db, replacing any existing index for the snapshot.
+    pub async fn materialize(ctx: &DalContext) -> SearchResult<Self> {
+        let key = Self::key(ctx).await?;
+        let index = Self::build(ctx).await?;
+        ctx.layer_db().materialized_view().write(
+            key,
+            &index,
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )?;
+
+        Ok(index)
+    }
+
+    /// Get the index for the snapshot of the provided [`DalContext`], building it if it has not
+    /// been built yet.
+    pub async fn for_snapshot(ctx: &DalContext) -> SearchResult<Self> {
+        let key = Self::key(ctx).await?;
+        match ctx.layer_db().materialized_view().read(&key).await? {
+            Some(index) => Ok(index),
+            None => Self::materialize(ctx).await,
+        }
+    }
+
+    async fn key(ctx: &DalContext) -> SearchResult<ContentHash> {
+        let snapshot_address = ctx.workspace_snapshot()?.id().await;
+        Ok(ContentHash::new(
+            format!("search-index:{INDEX_VERSION}:{snapshot_address}").as_bytes(),
+        ))
+    }
+
+    fn insert(&mut self, entry: SearchEntry, body: Option<&str>) {
+        let position = self.entries.len();
+        let mut tokens = tokenize(&entry.name);
+        if let Some(detail) = entry.detail.as_deref() {
+            tokens.extend(tokenize(detail));
+        }
+        for token in tokens {
+            self.tokens.entry(token).or_default().insert(position);
+        }
+        for token in body.map(tokenize).unwrap_or_default() {
+            self.body_tokens.entry(token).or_default().insert(position);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Find the entries matching every term of the query, best matches first. Terms match tokens
+    /// exactly, as a prefix, or (for longer terms) within a small edit distance.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        self.search_kinds(query, &[], limit)
+    }
+
+    /// Like [`Self::search`], only returning entries of the given kinds (or of every kind, if
+    /// none are given).
+    pub fn search_kinds(
+        &self,
+        query: &str,
+        kinds: &[SearchResultKind],
+        limit: usize,
+    ) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return vec![];
+        }
+
+        let mut scores: Option<HashMap<usize, u32>> = None;
+        for term in &terms {
+            let term_scores = self.score_term(term);
+            scores = Some(match scores {
+                None => term_scores,
+                // Entries must match every term
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(position, score)| {
+                        term_scores
+                            .get(&position)
+                            .map(|term_score| (position, score + term_score))
+                    })
+                    .collect(),
+            });
+        }
+
+        let query = query.trim().to_lowercase();
+        let mut hits: Vec<SearchHit> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(position, score)| {
+                let entry = self.entries.get(position)?;
+                if !kinds.is_empty() && !kinds.contains(&entry.kind) {
+                    return None;
+                }
+                let name = entry.name.to_lowercase();
+                let bonus = if name == query {
+                    10
+                } else if name.starts_with(&query) {
+                    5
+                } else {
+                    0
+                };
+                Some(SearchHit {
+                    entry: entry.clone(),
+                    score: score + bonus,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.entry.name.len().cmp(&b.entry.name.len()))
+                .then_with(|| a.entry.name.cmp(&b.entry.name))
+                .then_with(|| a.entry.kind.priority().cmp(&b.entry.kind.priority()))
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    /// The best score of the term for each entry containing a matching token.
+    fn score_term(&self, term: &str) -> HashMap<usize, u32> {
+        let mut scores = HashMap::new();
+        let mut record = |positions: &BTreeSet<usize>, score: u32| {
+            for position in positions {
+                let best = scores.entry(*position).or_insert(0);
+                *best = score.max(*best);
+            }
+        };
+
+        for (token, positions) in self
+            .tokens
+            .range(term.to_owned()..)
+            .take_while(|(token, _)| token.starts_with(term))
+        {
+            record(positions, if token == term { 3 } else { 2 });
+        }
+
+        // Func code and long values only count when nothing better matched
+        for (token, positions) in self
+            .body_tokens
+            .range(term.to_owned()..)
+            .take_while(|(token, _)| token.starts_with(term))
+        {
+            if token == term || term.len() >= MIN_FUZZY_TERM_LEN {
+                record(positions, 1);
+            }
+        }
+
+        if term.len() >= MIN_FUZZY_TERM_LEN {
+            let max_distance = if term.len() >= 8 { 2 } else { 1 };
+            for (token, positions) in &self.tokens {
+                if token.len().abs_diff(term.len()) <= max_distance
+                    && edit_distance(term, token) <= max_distance
+                {
+                    record(positions, 1);
+                }
+            }
+        }
+
+        scores
+    }
+}
+
+/// Split on anything that is not alphanumeric and on camelCase boundaries, lowercasing each
+/// token.
+fn tokenize(value: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut previous_lowercase = false;
+
+    for c in value.chars() {
+        if !c.is_alphanumeric() {
+            previous_lowercase = false;
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && previous_lowercase && !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        previous_lowercase = c.is_lowercase() || c.is_numeric();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Search the snapshot of the provided [`DalContext`], best matches first, only returning entries
+/// of the given kinds (or of every kind, if none are given).
+pub async fn search(
+    ctx: &DalContext,
+    query: &str,
+    kinds: &[SearchResultKind],
+    limit: usize,
+) -> SearchResult<Vec<SearchHit>> {
+    Ok(SearchIndex::for_snapshot(ctx)
+        .await?
+        .search_kinds(query, kinds, limit))
+}
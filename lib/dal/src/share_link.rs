@@ -0,0 +1,217 @@
+//! This module contains [`ShareLink`], which grants anonymous, read-only access to the diagram of a
+//! change set until it expires or is revoked. Every link has its own random secret, which is handed
+//! out once when the link is created; only a hash of it is stored.
+
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use thiserror::Error;
+
+use crate::diagram::view::ViewId;
+use crate::{ChangeSetId, DalContext, HistoryActor, TransactionsError, UserPk, WorkspacePk};
+
+/// The number of random bytes used for a share link secret.
+const SECRET_LENGTH: usize = 32;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ShareLinkError {
+    #[error("share links can only be created by users")]
+    NotCreatedByUser,
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ShareLinkResult<T> = Result<T, ShareLinkError>;
+
+pub use si_id::ShareLinkId;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLink {
+    id: ShareLinkId,
+    workspace_pk: WorkspacePk,
+    change_set_id: ChangeSetId,
+    /// The view that is shared. Links without a view share the default view.
+    view_id: Option<ViewId>,
+    #[serde(skip)]
+    token_hash: String,
+    created_by_user_pk: UserPk,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<PgRow> for ShareLink {
+    type Error = ShareLinkError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            change_set_id: row.try_get("change_set_id")?,
+            view_id: row.try_get("view_id")?,
+            token_hash: row.try_get("token_hash")?,
+            created_by_user_pk: row.try_get("created_by_user_pk")?,
+            created_at: row.try_get("created_at")?,
+            expires_at: row.try_get("expires_at")?,
+            revoked_at: row.try_get("revoked_at")?,
+        })
+    }
+}
+
+impl ShareLink {
+    pub fn id(&self) -> ShareLinkId {
+        self.id
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn change_set_id(&self) -> ChangeSetId {
+        self.change_set_id
+    }
+
+    pub fn view_id(&self) -> Option<ViewId> {
+        self.view_id
+    }
+
+    pub fn created_by_user_pk(&self) -> UserPk {
+        self.created_by_user_pk
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    /// Whether the link can still be used, i.e. it has neither been revoked nor expired.
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+
+    /// Whether the provided secret is the one handed out when the link was created. The hashes are
+    /// compared in constant time.
+    pub fn verify_secret(&self, secret: &str) -> bool {
+        blake3::Hash::from_hex(&self.token_hash)
+            .is_ok_and(|token_hash| token_hash == blake3::hash(secret.as_bytes()))
+    }
+
+    /// Create a new [`ShareLink`] with a freshly generated secret for a change set in the
+    /// workspace of the provided [`DalContext`], on behalf of the user in its [`HistoryActor`].
+    ///
+    /// The secret is returned alongside the link, as this is the only time it is available.
+    pub async fn new(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+        view_id: Option<ViewId>,
+        expires_at: DateTime<Utc>,
+    ) -> ShareLinkResult<(Self, String)> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let HistoryActor::User(user_pk) = ctx.history_actor() else {
+            return Err(ShareLinkError::NotCreatedByUser);
+        };
+
+        let mut secret_bytes = [0u8; SECRET_LENGTH];
+        rand::thread_rng().fill_bytes(&mut secret_bytes);
+        let secret = hex::encode(secret_bytes);
+        let token_hash = blake3::hash(secret.as_bytes()).to_hex().to_string();
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO share_links
+                (workspace_pk, change_set_id, view_id, token_hash, created_by_user_pk, expires_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING *",
+                &[
+                    &workspace_pk,
+                    &change_set_id,
+                    &view_id,
+                    &token_hash,
+                    user_pk,
+                    &expires_at,
+                ],
+            )
+            .await?;
+
+        Ok((Self::try_from(row)?, secret))
+    }
+
+    pub async fn list_for_workspace(ctx: &DalContext) -> ShareLinkResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM share_links WHERE workspace_pk = $1 ORDER BY created_at",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Find a [`ShareLink`] by its [`ShareLinkId`] within the provided workspace.
+    pub async fn get_by_id(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        share_link_id: ShareLinkId,
+    ) -> ShareLinkResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM share_links WHERE id = $1 AND workspace_pk = $2",
+                &[&share_link_id, &workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Find a [`ShareLink`] by its [`ShareLinkId`] in any workspace. This is only meant for
+    /// resolving the link of an anonymous request, whose token must be verified before use.
+    pub async fn get_by_id_for_any_workspace(
+        ctx: &DalContext,
+        share_link_id: ShareLinkId,
+    ) -> ShareLinkResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt("SELECT * FROM share_links WHERE id = $1", &[&share_link_id])
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Revoke the link. Revoking an already revoked link keeps the original revocation time.
+    pub async fn revoke(self, ctx: &DalContext) -> ShareLinkResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "UPDATE share_links SET revoked_at = COALESCE(revoked_at, CLOCK_TIMESTAMP())
+                WHERE id = $1
+                RETURNING *",
+                &[&self.id],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+}
@@ -0,0 +1,241 @@
+//! Pruning of workspace snapshots. Every change to a change set produces a new snapshot, and while
+//! the rebaser evicts the snapshots it replaces, snapshots of closed (applied or abandoned) change
+//! sets and any evictions which were missed stay around forever, so long-lived workspaces
+//! accumulate thousands of them.
+//!
+//! A snapshot is retained while it is the current (or last good) snapshot of a change set which
+//! is open, is the default change set of its workspace, was updated within the minimum age, or has
+//! func runs in flight. Every other snapshot older than the minimum age is pruned. The content of
+//! pruned snapshots is reclaimed by the next [content garbage collection](crate::content_gc) run.
+//!
+//! Only one run happens at a time across every server.
+
+use std::{collections::HashSet, time::Duration};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgPoolError};
+use si_layer_cache::{pg::CREATED_AT_REFRESH_INTERVAL, LayerDbError};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    advisory_lock::{AdvisoryLock, AdvisoryLockKey},
+    ChangeSet, ChangeSetError, DalContext, TransactionsError, WorkspaceSnapshotAddress,
+};
+
+/// The default minimum age of pruned snapshots.
+pub const DEFAULT_MIN_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// The default number of snapshots scanned (and pruned) at a time.
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SnapshotPruningError {
+    #[error("a snapshot pruning run is already in progress")]
+    AlreadyRunning,
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
+    #[error("invalid batch size: {0}")]
+    InvalidBatchSize(usize),
+    #[error("invalid minimum age (must be at least {CREATED_AT_REFRESH_INTERVAL:?}): {0:?}")]
+    InvalidMinAge(Duration),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[from] PgPoolError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SnapshotPruningResult<T> = Result<T, SnapshotPruningError>;
+
+/// Options for a single pruning run. By default, nothing is pruned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapshotPruningOptions {
+    /// Snapshots are only pruned once they are older than this, and change sets updated within
+    /// this keep their snapshots.
+    pub min_age: Duration,
+    /// How many snapshots to scan (and prune) at a time.
+    pub batch_size: usize,
+    /// Only report what would be pruned.
+    pub dry_run: bool,
+}
+
+impl Default for SnapshotPruningOptions {
+    fn default() -> Self {
+        Self {
+            min_age: DEFAULT_MIN_AGE,
+            batch_size: DEFAULT_BATCH_SIZE,
+            dry_run: true,
+        }
+    }
+}
+
+impl SnapshotPruningOptions {
+    pub fn validate(&self) -> SnapshotPruningResult<()> {
+        if self.batch_size == 0 {
+            return Err(SnapshotPruningError::InvalidBatchSize(self.batch_size));
+        }
+        if self.min_age < CREATED_AT_REFRESH_INTERVAL {
+            return Err(SnapshotPruningError::InvalidMinAge(self.min_age));
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of a pruning run.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotPruningReport {
+    pub dry_run: bool,
+    /// The number of distinct snapshots which are retained.
+    pub retained: usize,
+    /// The number of change sets whose snapshots are retained only because they have func runs in
+    /// flight.
+    pub retained_for_in_flight_func_runs: usize,
+    /// The number of snapshots older than the minimum age.
+    pub scanned: u64,
+    /// The number of those snapshots which were pruned (or would be, for a dry run).
+    pub pruned: u64,
+    /// The size of the pruned snapshots, in bytes.
+    pub reclaimed_bytes: u64,
+    /// The number of change sets whose last good snapshot was pruned, and is no longer set.
+    pub cleared_last_good_snapshots: u64,
+}
+
+/// Prunes (or, for a dry run, reports) workspace snapshots which are older than the minimum age
+/// and not retained by any change set. Fails with [`SnapshotPruningError::AlreadyRunning`] if a
+/// run is in progress on any server.
+#[instrument(
+    name = "snapshot_pruning.prune_snapshots",
+    level = "info",
+    skip_all,
+    fields(
+        si.snapshot_pruning.dry_run = options.dry_run,
+        si.snapshot_pruning.min_age_secs = options.min_age.as_secs(),
+    )
+)]
+pub async fn prune_snapshots(
+    ctx: &DalContext,
+    options: SnapshotPruningOptions,
+) -> SnapshotPruningResult<SnapshotPruningReport> {
+    options.validate()?;
+    let lock = AdvisoryLock::try_acquire(ctx.pg_pool(), AdvisoryLockKey::SnapshotPruning)
+        .await?
+        .ok_or(SnapshotPruningError::AlreadyRunning)?;
+
+    let result = prune_unlocked(ctx, options).await;
+    lock.release::<SnapshotPruningError>().await?;
+
+    result
+}
+
+async fn prune_unlocked(
+    ctx: &DalContext,
+    options: SnapshotPruningOptions,
+) -> SnapshotPruningResult<SnapshotPruningReport> {
+    // Snapshots written (or rewritten) after the cutoff are never pruned, so anything a change set
+    // moves to while this runs is safe
+    let cutoff = Utc::now()
+        - chrono::Duration::from_std(options.min_age)
+            .map_err(|_| SnapshotPruningError::InvalidMinAge(options.min_age))?;
+
+    let mut report = SnapshotPruningReport {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+
+    let in_flight = ctx
+        .layer_db()
+        .func_run()
+        .list_change_sets_with_in_flight_runs()
+        .await?;
+
+    let rows = ctx
+        .txns()
+        .await?
+        .pg()
+        .query(
+            "SELECT change_set_pointers.*, EXISTS (
+                 SELECT 1 FROM workspaces
+                   WHERE workspaces.default_change_set_id = change_set_pointers.id
+               ) AS is_default
+               FROM change_set_pointers",
+            &[],
+        )
+        .await?;
+
+    let mut retained: HashSet<WorkspaceSnapshotAddress> = HashSet::new();
+    for row in rows {
+        let is_default: bool = row.try_get("is_default")?;
+        let change_set = ChangeSet::try_from(row)?;
+
+        let is_live =
+            is_default || !change_set.status.is_closed() || change_set.updated_at >= cutoff;
+        if !is_live {
+            if !in_flight.contains(&change_set.id) {
+                continue;
+            }
+            report.retained_for_in_flight_func_runs += 1;
+        }
+
+        retained.insert(change_set.workspace_snapshot_address);
+        retained.extend(change_set.last_good_snapshot_address);
+    }
+    report.retained = retained.len();
+
+    let snapshots = ctx.layer_db().workspace_snapshot();
+    let mut after: Option<WorkspaceSnapshotAddress> = None;
+    loop {
+        let batch = snapshots
+            .list_created_before(cutoff, after.as_ref(), options.batch_size as i64)
+            .await?;
+        let Some((last, _)) = batch.last().copied() else {
+            break;
+        };
+        after = Some(last);
+        report.scanned += batch.len() as u64;
+
+        let prunable: Vec<(WorkspaceSnapshotAddress, u64)> = batch
+            .into_iter()
+            .filter(|(address, _)| !retained.contains(address))
+            .collect();
+        if prunable.is_empty() {
+            continue;
+        }
+
+        let pruned = if options.dry_run {
+            prunable
+        } else {
+            let addresses: Vec<WorkspaceSnapshotAddress> =
+                prunable.iter().map(|(address, _)| *address).collect();
+            let pruned = snapshots.delete_created_before(&addresses, cutoff).await?;
+
+            let pruned_addresses: Vec<WorkspaceSnapshotAddress> =
+                pruned.iter().map(|(address, _)| *address).collect();
+            report.cleared_last_good_snapshots +=
+                ChangeSet::clear_last_good_snapshot_addresses(ctx, &pruned_addresses).await?;
+
+            pruned
+        };
+        report.pruned += pruned.len() as u64;
+        report.reclaimed_bytes += pruned.iter().map(|(_, size)| size).sum::<u64>();
+    }
+
+    info!(
+        retained = report.retained,
+        retained_for_in_flight_func_runs = report.retained_for_in_flight_func_runs,
+        scanned = report.scanned,
+        pruned = report.pruned,
+        reclaimed_bytes = report.reclaimed_bytes,
+        cleared_last_good_snapshots = report.cleared_last_good_snapshots,
+        dry_run = report.dry_run,
+        "snapshot pruning finished",
+    );
+
+    Ok(report)
+}
@@ -0,0 +1,370 @@
+//! This module contains [`SnapshotSubscription`], a filtered projection of the components on HEAD
+//! for external systems (e.g. a CMDB sync) that want to mirror a slice of the model without
+//! polling full exports.
+//!
+//! The projection of a subscription maps component ids to the name, schema name and selected prop
+//! values of the components. Whenever the rebaser writes a new snapshot for HEAD, the projection
+//! of every subscription in the workspace is rebuilt and the differences are published to the
+//! [`subject`](SnapshotSubscription::subject) of the subscription as a JSON patch (RFC 6902).
+//! Every update carries a sequence number, so that consumers can detect missed updates and
+//! resynchronize from the stored projection.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_nats::NatsError;
+use si_data_pg::{PgError, PgRow};
+use thiserror::Error;
+
+use crate::{Component, ComponentError, DalContext, TransactionsError, WorkspacePk};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SnapshotSubscriptionError {
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("nats error: {0}")]
+    Nats(#[from] NatsError),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SnapshotSubscriptionResult<T> = Result<T, SnapshotSubscriptionError>;
+
+pub use si_id::SnapshotSubscriptionId;
+
+/// The prop included for every component when a filter does not select any props.
+const DEFAULT_PROP_PATH: &str = "/domain";
+
+/// Selects the components and props that make up the projection of a [`SnapshotSubscription`].
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSubscriptionFilter {
+    /// The names of the schemas whose components are included. Empty includes every schema.
+    #[serde(default)]
+    pub schema_names: Vec<String>,
+    /// JSON pointers into the root prop of each component, e.g. `/domain/region`. Empty includes
+    /// the whole domain.
+    #[serde(default)]
+    pub prop_paths: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectedComponent {
+    pub name: String,
+    pub schema_name: String,
+    /// The values of the selected props, keyed by their path. Props without a value are null.
+    pub props: BTreeMap<String, Value>,
+}
+
+/// A single operation of a JSON patch (RFC 6902). Only the operations needed to describe the
+/// difference between two projections are produced.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "op")]
+pub enum JsonPatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+/// The message published to the subject of a [`SnapshotSubscription`] when its projection changes.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSubscriptionUpdate {
+    pub subscription_id: SnapshotSubscriptionId,
+    /// Increases by one with every update. The stored projection starts at sequence zero.
+    pub sequence: i64,
+    pub patch: Vec<JsonPatchOperation>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSubscription {
+    id: SnapshotSubscriptionId,
+    workspace_pk: WorkspacePk,
+    name: String,
+    filter: SnapshotSubscriptionFilter,
+    #[serde(skip)]
+    projection: Value,
+    sequence: i64,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for SnapshotSubscription {
+    type Error = SnapshotSubscriptionError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let filter: Value = row.try_get("filter")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            name: row.try_get("name")?,
+            filter: serde_json::from_value(filter)?,
+            projection: row.try_get("projection")?,
+            sequence: row.try_get("sequence")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl SnapshotSubscription {
+    pub fn id(&self) -> SnapshotSubscriptionId {
+        self.id
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn filter(&self) -> &SnapshotSubscriptionFilter {
+        &self.filter
+    }
+
+    /// The projection as of the latest published update.
+    pub fn projection(&self) -> &Value {
+        &self.projection
+    }
+
+    /// The sequence number of the latest published update.
+    pub fn sequence(&self) -> i64 {
+        self.sequence
+    }
+
+    /// The NATS subject that updates to the projection are published to.
+    pub fn subject(&self) -> String {
+        format!(
+            "si.workspace_pk.{}.snapshot_subscription.{}",
+            self.workspace_pk, self.id
+        )
+    }
+
+    /// Create a new [`SnapshotSubscription`] in the workspace of the provided [`DalContext`],
+    /// which is expected to be on HEAD. The initial projection is stored, but not published.
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl Into<String>,
+        filter: SnapshotSubscriptionFilter,
+    ) -> SnapshotSubscriptionResult<Self> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let name = name.into();
+        let projection = Self::build_projection(ctx, &filter).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO snapshot_subscriptions (workspace_pk, name, filter, projection)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *",
+                &[
+                    &workspace_pk,
+                    &name,
+                    &serde_json::to_value(&filter)?,
+                    &projection,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    pub async fn list_for_workspace(ctx: &DalContext) -> SnapshotSubscriptionResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM snapshot_subscriptions WHERE workspace_pk = $1 ORDER BY created_at",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Find a [`SnapshotSubscription`] by its [`SnapshotSubscriptionId`] within the provided
+    /// workspace.
+    pub async fn get_by_id(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        id: SnapshotSubscriptionId,
+    ) -> SnapshotSubscriptionResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM snapshot_subscriptions WHERE id = $1 AND workspace_pk = $2",
+                &[&id, &workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    pub async fn delete(self, ctx: &DalContext) -> SnapshotSubscriptionResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "DELETE FROM snapshot_subscriptions WHERE id = $1",
+                &[&self.id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Build the projection of the snapshot of the provided [`DalContext`] for a filter.
+    pub async fn build_projection(
+        ctx: &DalContext,
+        filter: &SnapshotSubscriptionFilter,
+    ) -> SnapshotSubscriptionResult<Value> {
+        let mut projection = serde_json::Map::new();
+
+        for component in Component::list(ctx).await? {
+            let schema = Component::schema_for_component_id(ctx, component.id()).await?;
+            if !filter.schema_names.is_empty()
+                && !filter.schema_names.iter().any(|name| name == schema.name())
+            {
+                continue;
+            }
+
+            let root = component.view(ctx).await?.unwrap_or(Value::Null);
+            let props = if filter.prop_paths.is_empty() {
+                vec![DEFAULT_PROP_PATH]
+            } else {
+                filter.prop_paths.iter().map(String::as_str).collect()
+            }
+            .into_iter()
+            .map(|path| {
+                let value = root.pointer(path).cloned().unwrap_or(Value::Null);
+                (path.to_owned(), value)
+            })
+            .collect();
+
+            projection.insert(
+                component.id().to_string(),
+                serde_json::to_value(ProjectedComponent {
+                    name: component.name(ctx).await?,
+                    schema_name: schema.name().to_owned(),
+                    props,
+                })?,
+            );
+        }
+
+        Ok(Value::Object(projection))
+    }
+
+    /// Rebuild the projections of every subscription in the workspace of the provided
+    /// [`DalContext`] from the latest snapshot of its change set, and publish the differences when
+    /// the context is committed.
+    pub async fn publish_updates(ctx: &mut DalContext) -> SnapshotSubscriptionResult<()> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        // Locking the subscriptions serializes concurrent updates, so that every update is
+        // published exactly once and in order
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM snapshot_subscriptions WHERE workspace_pk = $1 FOR UPDATE",
+                &[&workspace_pk],
+            )
+            .await?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let subscriptions = rows
+            .into_iter()
+            .map(Self::try_from)
+            .collect::<SnapshotSubscriptionResult<Vec<_>>>()?;
+
+        // The snapshot may have moved on while waiting for the lock
+        ctx.update_snapshot_to_visibility().await?;
+
+        for subscription in subscriptions {
+            let projection = Self::build_projection(ctx, &subscription.filter).await?;
+            let mut patch = Vec::new();
+            diff(&subscription.projection, &projection, "", &mut patch);
+            if patch.is_empty() {
+                continue;
+            }
+
+            let sequence = subscription.sequence + 1;
+            ctx.txns()
+                .await?
+                .pg()
+                .execute(
+                    "UPDATE snapshot_subscriptions SET projection = $2, sequence = $3
+                    WHERE id = $1",
+                    &[&subscription.id, &projection, &sequence],
+                )
+                .await?;
+            ctx.txns()
+                .await?
+                .nats()
+                .publish(
+                    subscription.subject(),
+                    &SnapshotSubscriptionUpdate {
+                        subscription_id: subscription.id,
+                        sequence,
+                        patch,
+                    },
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Append the operations turning `old` into `new` to the patch. Objects are compared key by key;
+/// any other differing values are replaced as a whole.
+fn diff(old: &Value, new: &Value, path: &str, patch: &mut Vec<JsonPatchOperation>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for key in old.keys().filter(|key| !new.contains_key(*key)) {
+                patch.push(JsonPatchOperation::Remove {
+                    path: format!("{path}/{}", escape_pointer_token(key)),
+                });
+            }
+            for (key, new_value) in new {
+                let key_path = format!("{path}/{}", escape_pointer_token(key));
+                match old.get(key) {
+                    Some(old_value) => diff(old_value, new_value, &key_path, patch),
+                    None => patch.push(JsonPatchOperation::Add {
+                        path: key_path,
+                        value: new_value.clone(),
+                    }),
+                }
+            }
+        }
+        (old, new) if old != new => patch.push(JsonPatchOperation::Replace {
+            path: path.to_owned(),
+            value: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Escape a key for use as a JSON pointer (RFC 6901) token.
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
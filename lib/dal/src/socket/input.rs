@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 use si_frontend_types as frontend_types;
 use si_layer_cache::LayerDbError;
@@ -12,7 +14,7 @@ use crate::{
     change_set::ChangeSetError,
     func::FuncError,
     implement_add_edge_to,
-    layer_db_types::InputSocketContentV2,
+    layer_db_types::{InputSocketContent, InputSocketContentV2},
     socket::{
         connection_annotation::{ConnectionAnnotation, ConnectionAnnotationError},
         output::OutputSocketError,
@@ -196,6 +198,54 @@ impl InputSocket {
             .map_err(Into::into)
     }
 
+    /// Adds the given [`ConnectionAnnotations`](ConnectionAnnotation) to those of the
+    /// [`InputSocket`], skipping any it already has.
+    pub async fn add_connection_annotations(
+        ctx: &DalContext,
+        id: InputSocketId,
+        connection_annotations: Vec<ConnectionAnnotation>,
+    ) -> InputSocketResult<Self> {
+        let mut input_socket = Self::get_by_id(ctx, id).await?;
+
+        let before = input_socket.connection_annotations.len();
+        for connection_annotation in connection_annotations {
+            if !input_socket
+                .connection_annotations
+                .contains(&connection_annotation)
+            {
+                input_socket
+                    .connection_annotations
+                    .push(connection_annotation);
+            }
+        }
+        if input_socket.connection_annotations.len() == before {
+            return Ok(input_socket);
+        }
+
+        let content = InputSocketContentV2 {
+            timestamp: input_socket.timestamp,
+            name: input_socket.name.clone(),
+            inbound_type_definition: input_socket.inbound_type_definition.clone(),
+            outbound_type_definition: input_socket.outbound_type_definition.clone(),
+            kind: input_socket.kind,
+            required: input_socket.required,
+            ui_hidden: input_socket.ui_hidden,
+            connection_annotations: input_socket.connection_annotations.clone(),
+        };
+        let (hash, _) = ctx.layer_db().cas().write(
+            Arc::new(InputSocketContent::V2(content).into()),
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )?;
+
+        ctx.workspace_snapshot()?
+            .update_content(id.into(), hash)
+            .await?;
+
+        Ok(input_socket)
+    }
+
     pub async fn list_ids_for_schema_variant(
         ctx: &DalContext,
         schema_variant_id: SchemaVariantId,
@@ -60,10 +60,7 @@ pub async fn get_by_pk<PK: Send + Sync + ToSql, OBJECT: DeserializeOwned>(
     pk: &PK,
 ) -> StandardModelResult<OBJECT> {
     let row = ctx
-        .txns()
-        .await?
-        .pg()
-        .query_one("SELECT object FROM get_by_pk_v1($1, $2)", &[&table, &pk])
+        .read_query_one("SELECT object FROM get_by_pk_v1($1, $2)", &[&table, &pk])
         .await?;
     let json: serde_json::Value = row.try_get("object")?;
     let object: OBJECT = serde_json::from_value(json)?;
@@ -77,10 +74,7 @@ pub async fn get_by_id<ID: Send + Sync + ToSql, OBJECT: DeserializeOwned>(
     id: &ID,
 ) -> StandardModelResult<Option<OBJECT>> {
     let row_option = ctx
-        .txns()
-        .await?
-        .pg()
-        .query_opt(
+        .read_query_opt(
             "SELECT * FROM get_by_id_v1($1, $2, $3, $4)",
             &[&table, ctx.tenancy(), ctx.visibility(), &id],
         )
@@ -99,10 +93,7 @@ pub async fn find_by_attr<V: Send + Sync + ToString + Debug, OBJECT: Deserialize
     value: &V,
 ) -> StandardModelResult<Vec<OBJECT>> {
     let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
+        .read_query(
             "SELECT * FROM find_by_attr_v1($1, $2, $3, $4, $5)",
             &[
                 &table,
@@ -123,10 +114,7 @@ pub async fn find_by_attr_null<OBJECT: DeserializeOwned>(
     attr_name: &str,
 ) -> StandardModelResult<Vec<OBJECT>> {
     let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
+        .read_query(
             "SELECT * FROM find_by_attr_null_v1($1, $2, $3, $4)",
             &[&table, ctx.tenancy(), ctx.visibility(), &attr_name],
         )
@@ -142,10 +130,7 @@ pub async fn find_by_attr_in<V: Send + Sync + ToString + Debug, OBJECT: Deserial
     value: &[&V],
 ) -> StandardModelResult<Vec<OBJECT>> {
     let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
+        .read_query(
             "SELECT * FROM find_by_attr_in_v1($1, $2, $3, $4, $5)",
             &[
                 &table,
@@ -167,10 +152,7 @@ pub async fn find_by_attr_not_in<V: Send + Sync + ToString + Debug, OBJECT: Dese
     value: &[&V],
 ) -> StandardModelResult<Vec<OBJECT>> {
     let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
+        .read_query(
             "SELECT * FROM find_by_attr_not_in_v1($1, $2, $3, $4, $5)",
             &[
                 &table,
@@ -205,10 +187,7 @@ pub async fn belongs_to<ID: Send + Sync + ToSql, OBJECT: DeserializeOwned>(
     id: &ID,
 ) -> StandardModelResult<Option<OBJECT>> {
     let row_option = ctx
-        .txns()
-        .await?
-        .pg()
-        .query_opt(
+        .read_query_opt(
             "SELECT * FROM belongs_to_v1($1, $2, $3, $4, $5)",
             &[
                 &table,
@@ -322,10 +301,7 @@ pub async fn has_many<ID: Send + Sync + ToSql, OBJECT: DeserializeOwned>(
     belongs_to_id: &ID,
 ) -> StandardModelResult<Vec<OBJECT>> {
     let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
+        .read_query(
             "SELECT * FROM has_many_v1($1, $2, $3, $4, $5)",
             &[
                 &table,
@@ -354,10 +330,7 @@ pub async fn many_to_many<
     right_object_id: Option<&RightId>,
 ) -> StandardModelResult<Vec<Object>> {
     let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
+        .read_query(
             "SELECT * FROM many_to_many_v1($1, $2, $3, $4, $5, $6, $7)",
             &[
                 &table,
@@ -514,10 +487,7 @@ pub async fn list<OBJECT: DeserializeOwned>(
     table: &str,
 ) -> StandardModelResult<Vec<OBJECT>> {
     let rows = ctx
-        .txns()
-        .await?
-        .pg()
-        .query(
+        .read_query(
             "SELECT * FROM list_models_v1($1, $2, $3)",
             &[&table, ctx.tenancy(), ctx.visibility()],
         )
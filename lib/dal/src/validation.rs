@@ -310,7 +310,8 @@ impl ValidationOutput {
 
         let output = ValidationOutput { status, message };
 
-        ctx.layer_db()
+        let func_run = ctx
+            .layer_db()
             .func_run()
             .set_state_to_success(
                 func_result_value.func_run_id(),
@@ -318,6 +319,7 @@ impl ValidationOutput {
                 ctx.events_actor(),
             )
             .await?;
+        FuncRunner::publish_status(ctx, &func_run).await;
 
         Ok(Some(output))
     }
@@ -33,6 +33,8 @@ use crate::{
 pub use si_id::WorkspaceId;
 pub use si_id::WorkspacePk;
 
+pub mod snapshot_export;
+
 const WORKSPACE_GET_BY_PK: &str = include_str!("queries/workspace/get_by_pk.sql");
 const WORKSPACE_LIST_FOR_USER: &str = include_str!("queries/workspace/list_for_user.sql");
 const SEARCH_WORKSPACES_BY_ULID: &str = include_str!("queries/workspace/search_ulid.sql");
@@ -65,6 +67,8 @@ pub enum WorkspaceError {
     ImportingOrphanChangeset(ChangeSetId),
     #[error("invalid user {0}")]
     InvalidUser(UserPk),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
     #[error(transparent)]
     KeyPair(#[from] KeyPairError),
     #[error("LayerDb error: {0}")]
@@ -77,6 +81,12 @@ pub enum WorkspaceError {
     Pg(#[from] PgError),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
+    #[error("snapshot export is missing content store value for hash: {0}")]
+    SnapshotExportMissingContent(ContentHash),
+    #[error("snapshot import address mismatch (expected: {0}, actual: {1})")]
+    SnapshotImportAddressMismatch(WorkspaceSnapshotAddress, WorkspaceSnapshotAddress),
+    #[error("snapshot import content hash mismatch (expected: {0}, actual: {1})")]
+    SnapshotImportContentHashMismatch(ContentHash, ContentHash),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error("strum parse error: {0}")]
@@ -106,6 +116,7 @@ pub struct Workspace {
     token: Option<String>,
     snapshot_version: WorkspaceSnapshotGraphDiscriminants,
     component_concurrency_limit: Option<i32>,
+    required_change_set_approvals: Option<i32>,
 }
 
 impl TryFrom<PgRow> for Workspace {
@@ -124,6 +135,7 @@ impl TryFrom<PgRow> for Workspace {
             token: row.try_get("token")?,
             snapshot_version: WorkspaceSnapshotGraphDiscriminants::from_str(&snapshot_version)?,
             component_concurrency_limit: row.try_get("component_concurrency_limit")?,
+            required_change_set_approvals: row.try_get("required_change_set_approvals")?,
         })
     }
 }
@@ -733,6 +745,36 @@ impl Workspace {
         Ok(())
     }
 
+    /// The number of users, other than the one who requested it, who have to approve a change
+    /// set before it can be applied. Without a policy, a single approval from anyone is enough.
+    pub fn required_change_set_approvals(&self) -> Option<i32> {
+        self.required_change_set_approvals
+    }
+
+    pub async fn set_required_change_set_approvals(
+        &mut self,
+        ctx: &DalContext,
+        required_approvals: Option<i32>,
+    ) -> WorkspaceResult<()> {
+        let required_approvals = match required_approvals {
+            Some(required_approvals) if required_approvals <= 0 => None,
+            other => other,
+        };
+
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "UPDATE workspaces SET required_change_set_approvals = $2 WHERE pk = $1",
+                &[&self.pk, &required_approvals],
+            )
+            .await?;
+
+        self.required_change_set_approvals = required_approvals;
+
+        Ok(())
+    }
+
     pub fn timestamp(&self) -> &Timestamp {
         &self.timestamp
     }
@@ -0,0 +1,186 @@
+//! This module contains the ability to export the [`WorkspaceSnapshot`] of a single change set,
+//! alongside every content store object it references, into a self-contained file and to import
+//! such a file elsewhere (e.g. into a development environment when debugging a customer issue).
+//!
+//! Every content store object is stored in its serialized form, keyed by its [`ContentHash`], and
+//! the serialized snapshot is stored alongside its [`WorkspaceSnapshotAddress`]. Both are verified
+//! on import before anything is written.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_events::{ContentHash, WorkspaceSnapshotAddress};
+use si_layer_cache::db::serialize;
+use telemetry::prelude::*;
+
+use crate::layer_db_types::ContentTypes;
+use crate::{ChangeSet, ChangeSetId, DalContext, WorkspacePk, WorkspaceSnapshot};
+
+use super::{Workspace, WorkspaceError, WorkspaceResult};
+
+/// A self-contained export of a single [`WorkspaceSnapshot`] and the content store objects it
+/// references.
+#[remain::sorted]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WorkspaceSnapshotExport {
+    V0(WorkspaceSnapshotExportV0),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSnapshotExportV0 {
+    pub metadata: WorkspaceSnapshotExportMetadata,
+    /// The [`WorkspaceSnapshotAddress`] of the serialized snapshot, used for verification.
+    pub snapshot_address: WorkspaceSnapshotAddress,
+    /// The serialized snapshot graph.
+    pub snapshot: Vec<u8>,
+    /// Serialized content store objects, keyed by their [`ContentHash`].
+    pub content_store_values: BTreeMap<ContentHash, Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSnapshotExportMetadata {
+    pub workspace_pk: WorkspacePk,
+    pub change_set_id: ChangeSetId,
+    pub exported_at: DateTime<Utc>,
+}
+
+impl WorkspaceSnapshotExport {
+    pub fn into_latest(self) -> WorkspaceSnapshotExportV0 {
+        match self {
+            Self::V0(inner) => inner,
+        }
+    }
+}
+
+impl Workspace {
+    /// Export the [`WorkspaceSnapshot`] for the change set of the provided [`DalContext`] and all
+    /// content store objects it references into the writer. The snapshot is not modified.
+    #[instrument(level = "info", name = "workspace.export_snapshot", skip_all)]
+    pub async fn export_snapshot(ctx: &DalContext, mut writer: impl Write) -> WorkspaceResult<()> {
+        let workspace_snapshot = ctx.workspace_snapshot()?;
+
+        let mut content_hashes = HashSet::new();
+        for (node_weight, _) in workspace_snapshot.nodes().await? {
+            content_hashes.extend(node_weight.content_store_hashes());
+        }
+        let content_hashes: Vec<ContentHash> = content_hashes.into_iter().collect();
+
+        let content_values = ctx.layer_db().cas().read_many(&content_hashes).await?;
+
+        let mut content_store_values = BTreeMap::new();
+        for hash in content_hashes {
+            let content = content_values
+                .get(&hash)
+                .ok_or(WorkspaceError::SnapshotExportMissingContent(hash))?;
+            let (bytes, _) = serialize::to_vec(content.as_ref())?;
+            content_store_values.insert(hash, bytes);
+        }
+
+        let snapshot = workspace_snapshot.serialized().await?;
+
+        let export = WorkspaceSnapshotExport::V0(WorkspaceSnapshotExportV0 {
+            metadata: WorkspaceSnapshotExportMetadata {
+                workspace_pk: ctx.workspace_pk()?,
+                change_set_id: ctx.change_set_id(),
+                exported_at: Utc::now(),
+            },
+            snapshot_address: WorkspaceSnapshotAddress::new(&snapshot),
+            snapshot,
+            content_store_values,
+        });
+
+        let (bytes, _) = serialize::to_vec(&export)?;
+        writer.write_all(&bytes)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Import a snapshot written by [`Self::export_snapshot`] into a new
+    /// [read-only](ChangeSet::read_only) change set, based on the default change set of the
+    /// workspace for the provided [`DalContext`].
+    ///
+    /// The snapshot and every content store object are verified against their recorded hashes
+    /// before anything is written. Returns the id of the new change set.
+    #[instrument(level = "info", name = "workspace.import_snapshot", skip_all)]
+    pub async fn import_snapshot(
+        ctx: &DalContext,
+        mut reader: impl Read,
+    ) -> WorkspaceResult<ChangeSetId> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let WorkspaceSnapshotExportV0 {
+            metadata,
+            snapshot_address,
+            snapshot,
+            content_store_values,
+        } = serialize::from_bytes::<WorkspaceSnapshotExport>(&bytes)?.into_latest();
+
+        // Verify everything first so that we never write partial or corrupt data
+        let actual_snapshot_address = WorkspaceSnapshotAddress::new(&snapshot);
+        if actual_snapshot_address != snapshot_address {
+            return Err(WorkspaceError::SnapshotImportAddressMismatch(
+                snapshot_address,
+                actual_snapshot_address,
+            ));
+        }
+
+        let mut contents = Vec::with_capacity(content_store_values.len());
+        for (expected_hash, content_bytes) in content_store_values {
+            let actual_hash = ContentHash::new(&content_bytes);
+            if actual_hash != expected_hash {
+                return Err(WorkspaceError::SnapshotImportContentHashMismatch(
+                    expected_hash,
+                    actual_hash,
+                ));
+            }
+            let content: ContentTypes = serialize::from_bytes(&content_bytes)?;
+            contents.push((expected_hash, content));
+        }
+
+        let imported_snapshot = WorkspaceSnapshot::from_bytes(&snapshot)?;
+
+        let layer_db = ctx.layer_db();
+        for (expected_hash, content) in contents {
+            let (written_hash, _) = layer_db.cas().write(
+                Arc::new(content),
+                None,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )?;
+            if written_hash != expected_hash {
+                return Err(WorkspaceError::SnapshotImportContentHashMismatch(
+                    expected_hash,
+                    written_hash,
+                ));
+            }
+        }
+
+        let new_snapshot_address = imported_snapshot.write_readonly_graph(ctx).await?;
+
+        let mut change_set = ChangeSet::new(
+            ctx,
+            format!(
+                "Imported snapshot ({} / {})",
+                metadata.workspace_pk, metadata.change_set_id
+            ),
+            Some(ctx.get_workspace_default_change_set_id().await?),
+            new_snapshot_address,
+        )
+        .await?;
+        change_set.mark_read_only(ctx).await?;
+
+        info!(
+            si.change_set.id = %change_set.id,
+            source.workspace.id = %metadata.workspace_pk,
+            source.change_set.id = %metadata.change_set_id,
+            "imported workspace snapshot",
+        );
+
+        Ok(change_set.id)
+    }
+}
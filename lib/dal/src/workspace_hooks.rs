@@ -0,0 +1,200 @@
+//! This module contains [`WorkspaceHook`], a configured inbound trigger that external systems
+//! (e.g. a git provider on push) can call to run a management function or refresh resources in a
+//! workspace. Every hook has its own secret, which callers use to sign their payloads. Secrets
+//! are encrypted at rest with the symmetric crypto service.
+
+use std::str::FromStr;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use si_crypto::{SymmetricCryptoError, SymmetricNonce};
+use si_data_pg::{PgError, PgRow};
+use si_hash::Hash;
+use thiserror::Error;
+
+use crate::diagram::view::ViewId;
+use crate::management::prototype::ManagementPrototypeId;
+use crate::{workspace::WorkspaceId, ComponentId, DalContext, TransactionsError};
+
+/// The number of random bytes used for a hook secret.
+const SECRET_LENGTH: usize = 32;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WorkspaceHooksError {
+    #[error("invalid key hash for workspace hook: {0}")]
+    InvalidKeyHash(String),
+    #[error("invalid nonce for workspace hook: {0}")]
+    InvalidNonce(WorkspaceHookId),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("symmetric crypto error: {0}")]
+    SymmetricCrypto(#[from] SymmetricCryptoError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace hook secret is not valid utf-8: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+}
+
+pub type WorkspaceHooksResult<T> = Result<T, WorkspaceHooksError>;
+
+pub use si_id::WorkspaceHookId;
+
+/// What a [`WorkspaceHook`] does when it is triggered.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum WorkspaceHookTarget {
+    /// Run a management function for a component, passing the hook payload as the `payload`
+    /// argument.
+    #[serde(rename_all = "camelCase")]
+    ManagementPrototype {
+        prototype_id: ManagementPrototypeId,
+        component_id: ComponentId,
+        view_id: Option<ViewId>,
+    },
+    /// Enqueue refresh actions for the components.
+    #[serde(rename_all = "camelCase")]
+    Refresh { component_ids: Vec<ComponentId> },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceHook {
+    pk: WorkspaceHookId,
+    workspace_pk: WorkspaceId,
+    #[serde(skip_serializing)]
+    secret_crypted: Vec<u8>,
+    #[serde(skip_serializing)]
+    secret_nonce: Vec<u8>,
+    #[serde(skip_serializing)]
+    secret_key_hash: String,
+    target: WorkspaceHookTarget,
+}
+
+impl TryFrom<PgRow> for WorkspaceHook {
+    type Error = WorkspaceHooksError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let target: serde_json::Value = row.try_get("target")?;
+
+        Ok(Self {
+            pk: row.try_get("pk")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            secret_crypted: row.try_get("secret_crypted")?,
+            secret_nonce: row.try_get("secret_nonce")?,
+            secret_key_hash: row.try_get("secret_key_hash")?,
+            target: serde_json::from_value(target)?,
+        })
+    }
+}
+
+impl WorkspaceHook {
+    pub fn pk(&self) -> WorkspaceHookId {
+        self.pk
+    }
+
+    pub fn workspace_pk(&self) -> WorkspaceId {
+        self.workspace_pk
+    }
+
+    /// Decrypts the secret used to sign payloads sent to this hook.
+    pub fn secret(&self, ctx: &DalContext) -> WorkspaceHooksResult<String> {
+        let nonce = SymmetricNonce::from_slice(&self.secret_nonce)
+            .ok_or(WorkspaceHooksError::InvalidNonce(self.pk))?;
+        let key_hash = Hash::from_str(&self.secret_key_hash)
+            .map_err(|_| WorkspaceHooksError::InvalidKeyHash(self.secret_key_hash.to_owned()))?;
+        let bytes =
+            ctx.symmetric_crypto_service()
+                .decrypt(&self.secret_crypted, &nonce, &key_hash)?;
+
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    pub fn target(&self) -> &WorkspaceHookTarget {
+        &self.target
+    }
+
+    /// Create a new [`WorkspaceHook`] with a freshly generated secret for the workspace of the
+    /// provided [`DalContext`]. Returns the hook along with its secret, which is only stored
+    /// encrypted.
+    pub async fn new(
+        ctx: &DalContext,
+        target: WorkspaceHookTarget,
+    ) -> WorkspaceHooksResult<(Self, String)> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let mut secret_bytes = [0u8; SECRET_LENGTH];
+        rand::thread_rng().fill_bytes(&mut secret_bytes);
+        let secret = hex::encode(secret_bytes);
+        let (crypted, nonce, key_hash) = ctx.symmetric_crypto_service().encrypt(secret.as_bytes());
+        let nonce: &[u8] = nonce.as_ref();
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO workspace_hooks
+                    (workspace_pk, secret_crypted, secret_nonce, secret_key_hash, target)
+                    VALUES ($1, $2, $3, $4, $5) RETURNING *",
+                &[
+                    &workspace_pk,
+                    &crypted,
+                    &nonce,
+                    &key_hash.to_string(),
+                    &serde_json::to_value(&target)?,
+                ],
+            )
+            .await?;
+
+        Ok((Self::try_from(row)?, secret))
+    }
+
+    pub async fn list_for_workspace(ctx: &DalContext) -> WorkspaceHooksResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM workspace_hooks WHERE workspace_pk = $1 ORDER BY created_at",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Find a [`WorkspaceHook`] by its [`WorkspaceHookId`] within the provided workspace.
+    pub async fn get_by_pk(
+        ctx: &DalContext,
+        workspace_pk: WorkspaceId,
+        workspace_hook_id: WorkspaceHookId,
+    ) -> WorkspaceHooksResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM workspace_hooks WHERE pk = $1 AND workspace_pk = $2",
+                &[&workspace_hook_id, &workspace_pk],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    pub async fn delete(self, ctx: &DalContext) -> WorkspaceHooksResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute("DELETE FROM workspace_hooks WHERE pk = $1", &[&self.pk])
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+//! This module contains [`WorkspaceRole`], the role a member has in a workspace, which decides
+//! which destructive [`WorkspaceOperation`]s they are allowed to perform.
+//!
+//! Roles are kept on the workspace membership and are synced from the auth api. Members whose
+//! role has never been synced are editors, which is what every member was before roles existed.
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+use crate::{DalContext, HistoryActor, TransactionsError, UserPk, WorkspacePk};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WorkspaceRoleError {
+    #[error("user {0} is not a member of workspace {1}")]
+    NotAMember(UserPk, WorkspacePk),
+    #[error("workspace role {0} is not permitted to {1}")]
+    NotPermitted(WorkspaceRole, WorkspaceOperation),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type WorkspaceRoleResult<T> = Result<T, WorkspaceRoleError>;
+
+/// The role of a member of a workspace. Serialized the same way as the auth api does.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum WorkspaceRole {
+    Approver,
+    Editor,
+    Owner,
+    Viewer,
+}
+
+/// The operations which are gated on the [`WorkspaceRole`] of the user performing them.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum WorkspaceOperation {
+    ApplyChangeSet,
+    ApproveChangeSet,
+    DeleteComponent,
+    EditFunc,
+    ForceApplyChangeSet,
+    SetApprovalPolicy,
+    SetFeatureFlags,
+}
+
+impl WorkspaceRole {
+    /// Returns true if members with this role may perform the operation. Only owners may force
+    /// apply change sets and set the approval policy and feature flags and only approvers and
+    /// owners may approve change sets, while viewers may not perform any of the operations.
+    pub fn can(&self, operation: WorkspaceOperation) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Approver => !matches!(
+                operation,
+                WorkspaceOperation::ForceApplyChangeSet
+                    | WorkspaceOperation::SetApprovalPolicy
+                    | WorkspaceOperation::SetFeatureFlags
+            ),
+            Self::Editor => matches!(
+                operation,
+                WorkspaceOperation::ApplyChangeSet
+                    | WorkspaceOperation::DeleteComponent
+                    | WorkspaceOperation::EditFunc
+            ),
+            Self::Viewer => false,
+        }
+    }
+
+    /// Returns the role of the user in the workspace, or `None` if they are not a member of it.
+    pub async fn get_for_user(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        user_pk: UserPk,
+    ) -> WorkspaceRoleResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT role FROM user_belongs_to_workspaces WHERE user_pk = $1 AND workspace_pk = $2",
+                &[&user_pk, &workspace_pk],
+            )
+            .await?;
+
+        match maybe_row {
+            Some(row) => {
+                let role: String = row.try_get("role")?;
+                Ok(Some(role.parse()?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the role of the user in the workspace, making them a member of it if they are not
+    /// one already.
+    pub async fn set_for_user(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        user_pk: UserPk,
+        role: Self,
+    ) -> WorkspaceRoleResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "INSERT INTO user_belongs_to_workspaces (user_pk, workspace_pk, role)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (user_pk, workspace_pk)
+                    DO UPDATE SET role = EXCLUDED.role, updated_at = CLOCK_TIMESTAMP()",
+                &[&user_pk, &workspace_pk, &role.to_string()],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Ensures that the actor of the context may perform the operation in the workspace of the
+    /// context. The system is allowed to perform every operation.
+    pub async fn authorize(
+        ctx: &DalContext,
+        operation: WorkspaceOperation,
+    ) -> WorkspaceRoleResult<()> {
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::SystemInit => return Ok(()),
+            HistoryActor::User(user_pk) => *user_pk,
+        };
+        let workspace_pk = ctx.workspace_pk()?;
+
+        Self::get_for_user(ctx, workspace_pk, user_pk)
+            .await?
+            .ok_or(WorkspaceRoleError::NotAMember(user_pk, workspace_pk))?
+            .ensure_can(operation)
+    }
+
+    /// Ensures that members with this role may perform the operation.
+    pub fn ensure_can(self, operation: WorkspaceOperation) -> WorkspaceRoleResult<()> {
+        if self.can(operation) {
+            Ok(())
+        } else {
+            Err(WorkspaceRoleError::NotPermitted(self, operation))
+        }
+    }
+}
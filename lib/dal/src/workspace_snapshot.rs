@@ -46,9 +46,12 @@ use std::sync::Arc;
 
 use petgraph::prelude::*;
 pub use petgraph::Direction;
+use rand::seq::IteratorRandom;
 use serde::{Deserialize, Serialize};
 use si_data_pg::PgError;
-use si_events::{ulid::Ulid, ContentHash, WorkspaceSnapshotAddress};
+use si_events::{
+    merkle_tree_hash::MerkleTreeHash, ulid::Ulid, ContentHash, WorkspaceSnapshotAddress,
+};
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -190,6 +193,9 @@ impl WorkspaceSnapshotError {
 
 pub type WorkspaceSnapshotResult<T> = Result<T, WorkspaceSnapshotError>;
 
+// How many content store objects to read from durable storage at once when verifying content
+const CONTENT_VERIFICATION_BATCH_SIZE: usize = 1000;
+
 /// The workspace graph. The public interface for this is provided through the the various `Ext`
 /// traits that are implemented for [`WorkspaceSnapshot`].
 ///
@@ -838,6 +844,43 @@ impl WorkspaceSnapshot {
             .collect())
     }
 
+    /// Returns the content store hashes of nodes whose stored content no longer hashes to them,
+    /// out of a random sample of at most `sample_size` of the snapshot's content store hashes.
+    #[instrument(
+        name = "workspace_snapshot.find_corrupted_content",
+        level = "info",
+        skip_all,
+        fields(
+            content_hash.count = Empty,
+            content_hash.sampled = Empty,
+        )
+    )]
+    pub async fn find_corrupted_content(
+        &self,
+        ctx: &DalContext,
+        sample_size: usize,
+    ) -> WorkspaceSnapshotResult<Vec<ContentHash>> {
+        let span = current_span_for_instrument_at!("info");
+
+        let mut content_hashes = HashSet::new();
+        for (node_weight, _) in self.nodes().await? {
+            content_hashes.extend(node_weight.content_store_hashes());
+        }
+        span.record("content_hash.count", content_hashes.len());
+
+        let sample_size = sample_size.min(content_hashes.len());
+        let content_hashes = content_hashes
+            .into_iter()
+            .choose_multiple(&mut rand::thread_rng(), sample_size);
+        span.record("content_hash.sampled", content_hashes.len());
+        let mut corrupted = Vec::new();
+        for chunk in content_hashes.chunks(CONTENT_VERIFICATION_BATCH_SIZE) {
+            corrupted.extend(ctx.layer_db().cas().find_corrupted(chunk).await?);
+        }
+
+        Ok(corrupted)
+    }
+
     #[instrument(name = "workspace_snapshot.edges", level = "debug", skip_all, fields())]
     pub async fn edges(&self) -> WorkspaceSnapshotResult<Vec<(EdgeWeight, NodeIndex, NodeIndex)>> {
         Ok(self
@@ -892,6 +935,24 @@ impl WorkspaceSnapshot {
         self.working_copy().await.get_node_index_by_id_opt(id)
     }
 
+    /// Returns the merkle tree hash of the node for the id, but only if the snapshot has not been
+    /// modified since it was fetched. The hashes of a modified working copy are stale until
+    /// [`Self::cleanup_and_merkle_tree_hash`] is called.
+    pub async fn merkle_tree_hash_for_id_if_unmodified(
+        &self,
+        id: impl Into<Ulid>,
+    ) -> WorkspaceSnapshotResult<Option<MerkleTreeHash>> {
+        let working_copy = self.working_copy().await;
+        if working_copy.working_copy_read_guard.is_some() {
+            return Ok(None);
+        }
+
+        let node_index = working_copy.get_node_index_by_id(id)?;
+        Ok(Some(
+            working_copy.get_node_weight(node_index)?.merkle_tree_hash(),
+        ))
+    }
+
     #[instrument(name = "workspace_snapshot.find", level = "debug", skip_all, fields())]
     pub async fn find(
         ctx: &DalContext,
@@ -1606,6 +1667,7 @@ impl WorkspaceSnapshot {
                 }
 
                 ContentAddressDiscriminants::ActionPrototype
+                | ContentAddressDiscriminants::Annotation
                 | ContentAddressDiscriminants::Component
                 | ContentAddressDiscriminants::DeprecatedAction
                 | ContentAddressDiscriminants::DeprecatedActionBatch
@@ -38,6 +38,7 @@ pub enum ContentAddress {
     ManagementPrototype(ContentHash),
     Geometry(ContentHash),
     View(ContentHash),
+    Annotation(ContentHash),
 }
 
 impl ContentAddress {
@@ -45,6 +46,7 @@ impl ContentAddress {
         match self {
             ContentAddress::Root => None,
             ContentAddress::ActionPrototype(id)
+            | ContentAddress::Annotation(id)
             | ContentAddress::AttributePrototype(id)
             | ContentAddress::Component(id)
             | ContentAddress::DeprecatedAction(id)
@@ -69,6 +69,9 @@ pub enum EdgeWeightKind {
     Manages,
     /// From a view node to a diagram object node, to which geometries can be connected.
     DiagramObject,
+    /// From a [`StaticArgumentValue`](crate::attribute::prototype::argument::static_value::StaticArgumentValue)
+    /// holding an expression to each [`Prop`](crate::Prop) the expression references.
+    ExpressionReference,
 }
 
 impl EdgeWeightKind {
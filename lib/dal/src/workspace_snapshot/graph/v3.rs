@@ -751,6 +751,7 @@ impl WorkspaceSnapshotGraphV3 {
                     EdgeWeightKindDiscriminants::ManagementPrototype => "pink",
                     EdgeWeightKindDiscriminants::Manages => "pink",
                     EdgeWeightKindDiscriminants::DiagramObject => "black",
+                    EdgeWeightKindDiscriminants::ExpressionReference => "green",
                 };
 
                 match edgeref.weight().kind() {
@@ -798,6 +799,7 @@ impl WorkspaceSnapshotGraphV3 {
                             ContentAddressDiscriminants::ValidationOutput => "darkcyan",
                             ContentAddressDiscriminants::ManagementPrototype => "black",
                             ContentAddressDiscriminants::View => "black",
+                            ContentAddressDiscriminants::Annotation => "black",
                         };
                         (discrim.to_string(), color)
                     }
@@ -1414,7 +1416,8 @@ impl WorkspaceSnapshotGraphV3 {
                     | EdgeWeightKind::ValidationOutput
                     | EdgeWeightKind::ManagementPrototype
                     | EdgeWeightKind::Manages
-                    | EdgeWeightKind::DiagramObject => {}
+                    | EdgeWeightKind::DiagramObject
+                    | EdgeWeightKind::ExpressionReference => {}
                 }
             }
         }
@@ -875,6 +875,7 @@ impl WorkspaceSnapshotGraphV4 {
                     EdgeWeightKindDiscriminants::ManagementPrototype => "pink",
                     EdgeWeightKindDiscriminants::Manages => "pink",
                     EdgeWeightKindDiscriminants::DiagramObject => "black",
+                    EdgeWeightKindDiscriminants::ExpressionReference => "green",
                 };
 
                 match edgeref.weight().kind() {
@@ -922,6 +923,7 @@ impl WorkspaceSnapshotGraphV4 {
                             ContentAddressDiscriminants::ValidationOutput => "darkcyan",
                             ContentAddressDiscriminants::ManagementPrototype => "black",
                             ContentAddressDiscriminants::View => "black",
+                            ContentAddressDiscriminants::Annotation => "black",
                         };
                         (discrim.to_string(), color)
                     }
@@ -1560,7 +1562,8 @@ impl WorkspaceSnapshotGraphV4 {
                     | EdgeWeightKind::ValidationOutput
                     | EdgeWeightKind::ManagementPrototype
                     | EdgeWeightKind::Manages
-                    | EdgeWeightKind::DiagramObject => {}
+                    | EdgeWeightKind::DiagramObject
+                    | EdgeWeightKind::ExpressionReference => {}
                 }
             }
         }
@@ -108,6 +108,17 @@ impl SnapshotGraphMigrator {
             // (or there's no clone anymore and we always change it via following method)
             ctx_after_migration.set_change_set(change_set.clone())?;
 
+            // Corrupt content is not carried forward, quarantined change sets are left for an
+            // admin to restore
+            if change_set.is_quarantined() {
+                warn!(
+                    si.change_set.id = %change_set.id,
+                    si.workspace.id = ?change_set.workspace_id,
+                    "skipping migration of quarantined change set",
+                );
+                continue;
+            }
+
             let snapshot_address = change_set.workspace_snapshot_address;
 
             let new_snapshot = match self
@@ -96,6 +96,7 @@ impl ContentNodeWeight {
                 ContentAddress::DeprecatedActionRunner(content_hash)
             }
             ContentAddress::ActionPrototype(_) => ContentAddress::ActionPrototype(content_hash),
+            ContentAddress::Annotation(_) => ContentAddress::Annotation(content_hash),
             ContentAddress::AttributePrototype(_) => {
                 ContentAddress::AttributePrototype(content_hash)
             }
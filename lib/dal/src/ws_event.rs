@@ -3,11 +3,13 @@ use std::num::ParseIntError;
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use si_events::{FuncRunStatusEvent, WsEventKind, WsEventVersion};
 use si_frontend_types as frontend_types;
 use thiserror::Error;
 use ulid::Ulid;
 
 use crate::audit_logging::AuditLogsPublishedPayload;
+use crate::change_set::approval::ChangeSetApprovalState;
 use crate::change_set::event::{
     ChangeSetActorPayload, ChangeSetAppliedPayload, ChangeSetMergeVotePayload,
     ChangeSetRenamePayload, ChangeSetStateChangePayload,
@@ -17,10 +19,12 @@ use crate::component::{
     ComponentUpdatedPayload, ComponentUpgradedPayload, ConnectionDeletedPayload,
     ConnectionUpsertedPayload, InferredEdgeRemovePayload, InferredEdgeUpsertPayload,
 };
+use crate::diagram::annotation::{AnnotationDeletedPayload, AnnotationWsPayload};
 use crate::diagram::view::{
     ViewComponentsUpdatePayload, ViewDeletedPayload, ViewObjectCreatedPayload,
     ViewObjectRemovedPayload, ViewWsPayload,
 };
+use crate::feature_flags::FeatureFlagState;
 use crate::func::runner::FuncRunLogUpdatedPayload;
 use crate::func::{
     FuncWsEventCodeSaved, FuncWsEventFuncSummary, FuncWsEventGenerating, FuncWsEventPayload,
@@ -28,8 +32,10 @@ use crate::func::{
 use crate::management::prototype::{
     ManagementFuncExecutedPayload, ManagementOperationsCompletePayload,
 };
+use crate::module::ModulePublishProgressPayload;
 use crate::pkg::{
     ImportWorkspaceVotePayload, WorkspaceActorPayload, WorkspaceImportApprovalActorPayload,
+    WorkspaceImportProgressPayload,
 };
 use crate::prompt_override::PromptUpdatedPayload;
 use crate::qualification::QualificationCheckPayload;
@@ -79,12 +85,18 @@ pub type WsEventResult<T> = Result<T, WsEventError>;
 #[allow(clippy::large_enum_variant)]
 pub enum WsPayload {
     ActionsListUpdated(ChangeSetId),
+    AnnotationCreated(AnnotationWsPayload),
+    AnnotationDeleted(AnnotationDeletedPayload),
+    AnnotationUpdated(AnnotationWsPayload),
     AsyncError(ErrorPayload),
     AsyncFinish(FinishPayload),
     AuditLogsPublished(AuditLogsPublishedPayload),
     ChangeSetAbandoned(ChangeSetActorPayload),
     ChangeSetAbandonVote(ChangeSetMergeVotePayload),
     ChangeSetApplied(ChangeSetAppliedPayload),
+    ChangeSetApplyLocked(ChangeSetId),
+    ChangeSetApplyUnlocked(ChangeSetId),
+    ChangeSetApprovalsChanged(ChangeSetApprovalState),
     ChangeSetBeginAbandonProcess(ChangeSetActorPayload),
     ChangeSetBeginApprovalProcess(ChangeSetActorPayload),
     ChangeSetCancelAbandonProcess(ChangeSetActorPayload),
@@ -92,6 +104,7 @@ pub enum WsPayload {
     ChangeSetCanceled(ChangeSetId),
     ChangeSetCreated(ChangeSetId),
     ChangeSetMergeVote(ChangeSetMergeVotePayload),
+    ChangeSetQuarantined(ChangeSetId),
     ChangeSetRename(ChangeSetRenamePayload),
     ChangeSetStatusChanged(ChangeSetStateChangePayload),
     ChangeSetWritten(ChangeSetId),
@@ -103,12 +116,14 @@ pub enum WsPayload {
     ConnectionDeleted(ConnectionDeletedPayload),
     ConnectionUpserted(ConnectionUpsertedPayload),
     Cursor(CursorPayload),
+    FeatureFlagUpdated(FeatureFlagState),
     FuncArgumentsSaved(FuncWsEventPayload),
     FuncCodeSaved(FuncWsEventCodeSaved),
     FuncCreated(FuncWsEventFuncSummary),
     FuncDeleted(FuncWsEventPayload),
     FuncGenerating(FuncWsEventGenerating),
     FuncRunLogUpdated(FuncRunLogUpdatedPayload),
+    FuncRunStatusChanged(FuncRunStatusEvent),
     FuncSaved(FuncWsEventPayload),
     FuncUpdated(FuncWsEventFuncSummary),
     ImportWorkspaceVote(ImportWorkspaceVotePayload),
@@ -117,6 +132,7 @@ pub enum WsPayload {
     ManagementFuncExecuted(ManagementFuncExecutedPayload),
     ManagementOperationsComplete(ManagementOperationsCompletePayload),
     ModuleImported(Vec<si_frontend_types::SchemaVariant>),
+    ModulePublishProgress(ModulePublishProgressPayload),
     Online(OnlinePayload),
     PromptUpdated(PromptUpdatedPayload),
     ResourceRefreshed(ComponentUpdatedPayload),
@@ -140,6 +156,92 @@ pub enum WsPayload {
     ViewUpdated(ViewWsPayload),
     WorkspaceImportBeginApprovalProcess(WorkspaceImportApprovalActorPayload),
     WorkspaceImportCancelApprovalProcess(WorkspaceActorPayload),
+    WorkspaceImportProgress(WorkspaceImportProgressPayload),
+}
+
+impl WsPayload {
+    /// Returns the kind of event in the [`WsEventKind`] registry this payload is for.
+    pub fn kind(&self) -> WsEventKind {
+        match self {
+            Self::ActionsListUpdated(_) => WsEventKind::ActionsListUpdated,
+            Self::AnnotationCreated(_) => WsEventKind::AnnotationCreated,
+            Self::AnnotationDeleted(_) => WsEventKind::AnnotationDeleted,
+            Self::AnnotationUpdated(_) => WsEventKind::AnnotationUpdated,
+            Self::AsyncError(_) => WsEventKind::AsyncError,
+            Self::AsyncFinish(_) => WsEventKind::AsyncFinish,
+            Self::AuditLogsPublished(_) => WsEventKind::AuditLogsPublished,
+            Self::ChangeSetAbandoned(_) => WsEventKind::ChangeSetAbandoned,
+            Self::ChangeSetAbandonVote(_) => WsEventKind::ChangeSetAbandonVote,
+            Self::ChangeSetApplied(_) => WsEventKind::ChangeSetApplied,
+            Self::ChangeSetApplyLocked(_) => WsEventKind::ChangeSetApplyLocked,
+            Self::ChangeSetApplyUnlocked(_) => WsEventKind::ChangeSetApplyUnlocked,
+            Self::ChangeSetApprovalsChanged(_) => WsEventKind::ChangeSetApprovalsChanged,
+            Self::ChangeSetBeginAbandonProcess(_) => WsEventKind::ChangeSetBeginAbandonProcess,
+            Self::ChangeSetBeginApprovalProcess(_) => WsEventKind::ChangeSetBeginApprovalProcess,
+            Self::ChangeSetCancelAbandonProcess(_) => WsEventKind::ChangeSetCancelAbandonProcess,
+            Self::ChangeSetCancelApprovalProcess(_) => WsEventKind::ChangeSetCancelApprovalProcess,
+            Self::ChangeSetCanceled(_) => WsEventKind::ChangeSetCanceled,
+            Self::ChangeSetCreated(_) => WsEventKind::ChangeSetCreated,
+            Self::ChangeSetMergeVote(_) => WsEventKind::ChangeSetMergeVote,
+            Self::ChangeSetQuarantined(_) => WsEventKind::ChangeSetQuarantined,
+            Self::ChangeSetRename(_) => WsEventKind::ChangeSetRename,
+            Self::ChangeSetStatusChanged(_) => WsEventKind::ChangeSetStatusChanged,
+            Self::ChangeSetWritten(_) => WsEventKind::ChangeSetWritten,
+            Self::CheckedQualifications(_) => WsEventKind::CheckedQualifications,
+            Self::ComponentCreated(_) => WsEventKind::ComponentCreated,
+            Self::ComponentDeleted(_) => WsEventKind::ComponentDeleted,
+            Self::ComponentUpdated(_) => WsEventKind::ComponentUpdated,
+            Self::ComponentUpgraded(_) => WsEventKind::ComponentUpgraded,
+            Self::ConnectionDeleted(_) => WsEventKind::ConnectionDeleted,
+            Self::ConnectionUpserted(_) => WsEventKind::ConnectionUpserted,
+            Self::Cursor(_) => WsEventKind::Cursor,
+            Self::FeatureFlagUpdated(_) => WsEventKind::FeatureFlagUpdated,
+            Self::FuncArgumentsSaved(_) => WsEventKind::FuncArgumentsSaved,
+            Self::FuncCodeSaved(_) => WsEventKind::FuncCodeSaved,
+            Self::FuncCreated(_) => WsEventKind::FuncCreated,
+            Self::FuncDeleted(_) => WsEventKind::FuncDeleted,
+            Self::FuncGenerating(_) => WsEventKind::FuncGenerating,
+            Self::FuncRunLogUpdated(_) => WsEventKind::FuncRunLogUpdated,
+            Self::FuncRunStatusChanged(_) => WsEventKind::FuncRunStatusChanged,
+            Self::FuncSaved(_) => WsEventKind::FuncSaved,
+            Self::FuncUpdated(_) => WsEventKind::FuncUpdated,
+            Self::ImportWorkspaceVote(_) => WsEventKind::ImportWorkspaceVote,
+            Self::InferredEdgeRemove(_) => WsEventKind::InferredEdgeRemove,
+            Self::InferredEdgeUpsert(_) => WsEventKind::InferredEdgeUpsert,
+            Self::ManagementFuncExecuted(_) => WsEventKind::ManagementFuncExecuted,
+            Self::ManagementOperationsComplete(_) => WsEventKind::ManagementOperationsComplete,
+            Self::ModuleImported(_) => WsEventKind::ModuleImported,
+            Self::ModulePublishProgress(_) => WsEventKind::ModulePublishProgress,
+            Self::Online(_) => WsEventKind::Online,
+            Self::PromptUpdated(_) => WsEventKind::PromptUpdated,
+            Self::ResourceRefreshed(_) => WsEventKind::ResourceRefreshed,
+            Self::SchemaVariantCloned(_) => WsEventKind::SchemaVariantCloned,
+            Self::SchemaVariantCreated(_) => WsEventKind::SchemaVariantCreated,
+            Self::SchemaVariantDeleted(_) => WsEventKind::SchemaVariantDeleted,
+            Self::SchemaVariantReplaced(_) => WsEventKind::SchemaVariantReplaced,
+            Self::SchemaVariantSaved(_) => WsEventKind::SchemaVariantSaved,
+            Self::SchemaVariantUpdated(_) => WsEventKind::SchemaVariantUpdated,
+            Self::SchemaVariantUpdateFinished(_) => WsEventKind::SchemaVariantUpdateFinished,
+            Self::SecretCreated(_) => WsEventKind::SecretCreated,
+            Self::SecretDeleted(_) => WsEventKind::SecretDeleted,
+            Self::SecretUpdated(_) => WsEventKind::SecretUpdated,
+            Self::SetComponentPosition(_) => WsEventKind::SetComponentPosition,
+            Self::StatusUpdate(_) => WsEventKind::StatusUpdate,
+            Self::ViewComponentsUpdate(_) => WsEventKind::ViewComponentsUpdate,
+            Self::ViewCreated(_) => WsEventKind::ViewCreated,
+            Self::ViewDeleted(_) => WsEventKind::ViewDeleted,
+            Self::ViewObjectCreated(_) => WsEventKind::ViewObjectCreated,
+            Self::ViewObjectRemoved(_) => WsEventKind::ViewObjectRemoved,
+            Self::ViewUpdated(_) => WsEventKind::ViewUpdated,
+            Self::WorkspaceImportBeginApprovalProcess(_) => {
+                WsEventKind::WorkspaceImportBeginApprovalProcess
+            }
+            Self::WorkspaceImportCancelApprovalProcess(_) => {
+                WsEventKind::WorkspaceImportCancelApprovalProcess
+            }
+            Self::WorkspaceImportProgress(_) => WsEventKind::WorkspaceImportProgress,
+        }
+    }
 }
 
 #[remain::sorted]
@@ -157,6 +259,9 @@ pub struct WsEvent {
     version: i64,
     workspace_pk: WorkspacePk,
     change_set_id: Option<ChangeSetId>,
+    /// The version of the shape of the payload, for clients to check against the version of its
+    /// kind they know about.
+    payload_version: WsEventVersion,
     payload: WsPayload,
 }
 
@@ -170,6 +275,7 @@ impl WsEvent {
             version: 1,
             workspace_pk,
             change_set_id,
+            payload_version: payload.kind().version(),
             payload,
         })
     }
@@ -210,6 +316,10 @@ impl WsEvent {
         self.change_set_id
     }
 
+    pub fn payload_version(&self) -> WsEventVersion {
+        self.payload_version
+    }
+
     fn workspace_subject(&self) -> String {
         format!("si.workspace_pk.{}.event", self.workspace_pk)
     }
@@ -230,6 +340,11 @@ impl WsEvent {
     /// sending data to the frontend, such as object ids, that will only be
     /// valid if the transaction commits successfully.
     pub async fn publish_immediately(&self, ctx: &DalContext) -> WsEventResult<()> {
+        // Nothing a dry run does may be seen outside of it
+        if ctx.dry_run().is_some() {
+            return Ok(());
+        }
+
         ctx.txns()
             .await?
             .nats()
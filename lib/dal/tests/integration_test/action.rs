@@ -1,8 +1,14 @@
+use dal::action::concurrency_limit::ActionConcurrencyLimits;
 use dal::action::dependency_graph::ActionDependencyGraph;
+use dal::action::plan::ActionPlan;
 use dal::component::frame::Frame;
 use dal::{
-    action::prototype::ActionKind, action::prototype::ActionPrototype, action::Action,
-    action::ActionState, AttributeValue, Component, DalContext,
+    action::prototype::ActionKind,
+    action::prototype::ActionPrototype,
+    action::run::{ActionRun, ActionRunStatus},
+    action::Action,
+    action::ActionState,
+    AttributeValue, Component, DalContext,
 };
 use dal_test::helpers::create_component_for_default_schema_name_in_default_view;
 use dal_test::helpers::create_component_for_schema_name_with_type_on_default_view;
@@ -182,6 +188,45 @@ async fn run(ctx: &mut DalContext) {
     assert!(maybe_resource.is_some());
 }
 
+#[test]
+async fn plan(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "style")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let plan = ActionPlan::for_change_set(ctx)
+        .await
+        .expect("unable to plan actions");
+
+    assert_eq!(plan.components.len(), 1);
+    let component_plan = plan
+        .for_component(component.id())
+        .expect("component is in the plan");
+    assert_eq!(component_plan.entries.len(), 1);
+    let entry = &component_plan.entries[0];
+    assert_eq!(entry.kind, ActionKind::Create);
+    assert!(entry.current_payload.is_none());
+
+    // Simulating must leave the queue untouched
+    let action = Action::get_by_id(ctx, entry.action_id)
+        .await
+        .expect("unable to get action by id");
+    assert_eq!(action.state(), ActionState::Queued);
+
+    // ... and must not be recorded as a run of the action
+    let last_run = ctx
+        .layer_db()
+        .func_run()
+        .get_last_run_for_action_id(ctx.events_tenancy().workspace_pk, entry.action_id)
+        .await
+        .expect("unable to get last run for action");
+    assert!(last_run.is_none());
+}
+
 #[test]
 async fn auto_queue_creation(ctx: &mut DalContext) {
     // ======================================================
@@ -529,3 +574,151 @@ async fn actions_are_ordered_correctly(ctx: &mut DalContext) {
         vec![first_component_action]
     );
 }
+
+#[test]
+async fn dispatch_respects_concurrency_limits(ctx: &mut DalContext) {
+    let first_component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "first")
+            .await
+            .expect("could not create component");
+    let second_component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "second")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let eligible = Action::eligible_to_dispatch(ctx)
+        .await
+        .expect("could not get eligible actions");
+    assert_eq!(eligible.len(), 2);
+
+    // Only one action is dispatched with a workspace limit of one
+    ActionConcurrencyLimits::set_workspace_limit(ctx, Some(1))
+        .await
+        .expect("could not set workspace limit");
+    let first_action_id = Action::find_for_component_id(ctx, first_component.id())
+        .await
+        .expect("could not find actions")
+        .pop()
+        .expect("no action for first component");
+    let second_action_id = Action::find_for_component_id(ctx, second_component.id())
+        .await
+        .expect("could not find actions")
+        .pop()
+        .expect("no action for second component");
+    let eligible = Action::eligible_to_dispatch(ctx)
+        .await
+        .expect("could not get eligible actions");
+    assert_eq!(eligible, vec![first_action_id.min(second_action_id)]);
+
+    // In-flight actions count against the limit
+    Action::set_state(ctx, first_action_id, ActionState::Running)
+        .await
+        .expect("could not set state");
+    let eligible = Action::eligible_to_dispatch(ctx)
+        .await
+        .expect("could not get eligible actions");
+    assert!(eligible.is_empty());
+
+    // Per-schema limits apply on their own as well
+    ActionConcurrencyLimits::set_workspace_limit(ctx, None)
+        .await
+        .expect("could not remove workspace limit");
+    let schema = Component::schema_for_component_id(ctx, second_component.id())
+        .await
+        .expect("could not get schema");
+    ActionConcurrencyLimits::set_schema_limit(ctx, schema.id(), Some(1))
+        .await
+        .expect("could not set schema limit");
+    let eligible = Action::eligible_to_dispatch(ctx)
+        .await
+        .expect("could not get eligible actions");
+    assert!(eligible.is_empty());
+
+    ActionConcurrencyLimits::set_schema_limit(ctx, schema.id(), Some(2))
+        .await
+        .expect("could not set schema limit");
+    let eligible = Action::eligible_to_dispatch(ctx)
+        .await
+        .expect("could not get eligible actions");
+    assert_eq!(eligible, vec![second_action_id]);
+
+    assert!(ActionConcurrencyLimits::set_workspace_limit(ctx, Some(0))
+        .await
+        .is_err());
+}
+
+#[test]
+async fn run_history(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "shake it off")
+            .await
+            .expect("could not create component");
+    let action = Action::find_for_component_id(ctx, component.id())
+        .await
+        .expect("unable to list actions for component")
+        .pop()
+        .expect("no action found");
+
+    let mut first = ActionRun::start(
+        ctx,
+        action,
+        ctx.change_set_id(),
+        component.id(),
+        ActionKind::Create,
+    )
+    .await
+    .expect("unable to start action run");
+    assert_eq!(1, first.attempt);
+    assert_eq!(ActionRunStatus::Running, first.status);
+    assert!(first.finished_at.is_none());
+
+    first
+        .finish(ctx, ActionRunStatus::Failed, None, None)
+        .await
+        .expect("unable to finish action run");
+    assert_eq!(ActionRunStatus::Failed, first.status);
+    assert!(first.duration_ms.is_some());
+
+    let second = ActionRun::start(
+        ctx,
+        action,
+        ctx.change_set_id(),
+        component.id(),
+        ActionKind::Create,
+    )
+    .await
+    .expect("unable to start action run");
+    assert_eq!(2, second.attempt);
+
+    ActionRun::fail_unfinished_for_action(ctx, action)
+        .await
+        .expect("unable to fail unfinished action runs");
+
+    let runs = ActionRun::list_for_action(ctx, action)
+        .await
+        .expect("unable to list action runs");
+    assert_eq!(
+        vec![(1, ActionRunStatus::Failed), (2, ActionRunStatus::Failed)],
+        runs.iter()
+            .map(|run| (run.attempt, run.status))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        2,
+        ActionRun::list_for_component(ctx, component.id())
+            .await
+            .expect("unable to list action runs for component")
+            .len()
+    );
+    assert_eq!(
+        2,
+        ActionRun::list_for_change_set(ctx, ctx.change_set_id())
+            .await
+            .expect("unable to list action runs for change set")
+            .len()
+    );
+    assert!(first.logs(ctx).await.expect("unable to get logs").is_none());
+}
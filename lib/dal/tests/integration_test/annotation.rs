@@ -0,0 +1,104 @@
+use dal::{
+    diagram::{annotation::Annotation, geometry::Geometry, view::View},
+    Component, DalContext,
+};
+use dal_test::{
+    helpers::{create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers},
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use si_frontend_types::RawGeometry;
+
+#[test]
+async fn annotations_merge_to_head(ctx: &mut DalContext) {
+    let view_id = View::get_id_for_default(ctx)
+        .await
+        .expect("could not get default view");
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "annotated")
+            .await
+            .expect("could not create component");
+
+    let free = Annotation::new(ctx, view_id, "# todo\nsplit this up", 10, 20, None)
+        .await
+        .expect("could not create annotation");
+    let mut anchored =
+        Annotation::new(ctx, view_id, "owned by *ops*", 0, -40, Some(component.id()))
+            .await
+            .expect("could not create anchored annotation");
+    assert_eq!(Some(component.id()), anchored.component_id());
+
+    anchored
+        .update(ctx, "owned by *platform*", 5, -40, Some(component.id()))
+        .await
+        .expect("could not update annotation");
+
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set to base");
+
+    let mut annotations = Annotation::list_for_view(ctx, view_id)
+        .await
+        .expect("could not list annotations");
+    annotations.sort_by_key(|annotation| annotation.id());
+    let mut expected = vec![free, anchored];
+    expected.sort_by_key(|annotation| annotation.id());
+    assert_eq!(expected, annotations);
+
+    let anchored = Annotation::get_by_id(ctx, expected[1].id())
+        .await
+        .expect("could not get annotation");
+    assert_eq!(expected[1], anchored);
+}
+
+#[test]
+async fn removing_anchor_component_detaches_annotation(ctx: &mut DalContext) {
+    let view_id = View::get_id_for_default(ctx)
+        .await
+        .expect("could not get default view");
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "annotated")
+            .await
+            .expect("could not create component");
+    let mut geometry = Geometry::get_by_component_and_view(ctx, component.id(), view_id)
+        .await
+        .expect("could not get geometry");
+    geometry
+        .update(
+            ctx,
+            RawGeometry {
+                x: 100,
+                y: 200,
+                width: Some(500),
+                height: Some(500),
+            },
+        )
+        .await
+        .expect("could not update geometry");
+
+    let annotation = Annotation::new(ctx, view_id, "remove me", 10, -20, Some(component.id()))
+        .await
+        .expect("could not create annotation");
+
+    Component::remove(ctx, component.id())
+        .await
+        .expect("could not remove component");
+
+    let annotation = Annotation::get_by_id(ctx, annotation.id())
+        .await
+        .expect("could not get annotation");
+    assert_eq!(None, annotation.component_id());
+    assert_eq!((110, 180), (annotation.x(), annotation.y()));
+    assert_eq!("remove me", annotation.body());
+
+    Annotation::remove(ctx, annotation.id())
+        .await
+        .expect("could not remove annotation");
+    assert!(Annotation::list_for_view(ctx, view_id)
+        .await
+        .expect("could not list annotations")
+        .is_empty());
+}
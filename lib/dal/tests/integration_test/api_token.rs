@@ -0,0 +1,36 @@
+use chrono::{Duration, Utc};
+use dal::api_token::ApiToken;
+use dal::{DalContext, HistoryActor};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn create_list_and_revoke(ctx: &DalContext) {
+    let token = ApiToken::new(ctx, "ci", None)
+        .await
+        .expect("could not create api token");
+    let HistoryActor::User(user_pk) = ctx.history_actor() else {
+        panic!("test context should have a user history actor");
+    };
+    assert_eq!(*user_pk, token.created_by_user_pk());
+    assert!(token.is_active());
+
+    let expired = ApiToken::new(ctx, "expired", Some(Utc::now() - Duration::hours(1)))
+        .await
+        .expect("could not create api token");
+    assert!(!expired.is_active());
+
+    let tokens = ApiToken::list_for_workspace(ctx)
+        .await
+        .expect("could not list api tokens");
+    assert_eq!(vec![token.clone(), expired], tokens);
+
+    let revoked = token.revoke(ctx).await.expect("could not revoke api token");
+    assert!(!revoked.is_active());
+
+    let fetched = ApiToken::get_by_id(ctx, ctx.workspace_pk().expect("workspace pk"), revoked.id())
+        .await
+        .expect("could not get api token")
+        .expect("api token not found");
+    assert_eq!(revoked, fetched);
+}
@@ -1,6 +1,20 @@
-use dal::{AttributeValue, DalContext};
+use dal::attribute::prototype::argument::expression::Expression;
+use dal::attribute::value::{AttributeValueError, AttributeValueExplanationSource};
+use dal::func::argument::FuncArgumentKind;
+use dal::func::authoring::FuncAuthoringClient;
+use dal::func::binding::attribute::AttributeBinding;
+use dal::func::binding::{
+    AttributeArgumentBinding, AttributeFuncArgumentSource, AttributeFuncDestination,
+    EventualParent, FuncBinding,
+};
+use dal::prop::PropPath;
+use dal::{AttributeValue, DalContext, Prop};
 use dal_test::expected::ExpectComponent;
-use dal_test::helpers::ChangeSetTestHelpers;
+use dal_test::helpers::{
+    create_named_component_for_schema_variant_on_default_view,
+    create_unlocked_variant_copy_for_schema_name, get_attribute_value_for_component,
+    update_attribute_value_for_component, ChangeSetTestHelpers,
+};
 use dal_test::{test, Result};
 use pretty_assertions_sorted::assert_eq;
 use serde_json::json;
@@ -28,3 +42,262 @@ async fn arguments_for_prototype_function_execution(ctx: &mut DalContext) -> Res
     );
     Ok(())
 }
+
+#[test]
+async fn explain(ctx: &mut DalContext) -> Result<()> {
+    // The test exclusive schema has the identity function set on "/root/domain/name" with an
+    // input from "/root/si/name", so the explanation should point back at the latter.
+    let component = ExpectComponent::create_named(ctx, "swifty", "explain yourself").await;
+    let domain_name_prop = component.prop(ctx, ["root", "domain", "name"]).await;
+    let si_name_prop = component.prop(ctx, ["root", "si", "name"]).await;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    let domain_name_av_id = domain_name_prop.attribute_value(ctx).await.id();
+    let si_name_av_id = si_name_prop.attribute_value(ctx).await.id();
+
+    let explanation = AttributeValue::explain(ctx, domain_name_av_id).await?;
+    assert_eq!(domain_name_av_id, explanation.attribute_value_id);
+    assert_eq!(component.id(), explanation.component_id);
+    assert_eq!("si:identity", explanation.func_name);
+    assert_eq!(Some(json!("explain yourself")), explanation.value);
+    assert!(!explanation.prototype_is_component_specific);
+    assert_eq!(
+        vec![si_name_av_id],
+        explanation.upstream_attribute_value_ids
+    );
+
+    assert_eq!(1, explanation.inputs.len());
+    let input = &explanation.inputs[0];
+    assert_eq!("identity", input.func_argument_name);
+    assert_eq!(
+        AttributeValueExplanationSource::Prop {
+            prop_id: si_name_prop.prop().id()
+        },
+        input.source
+    );
+    assert!(input.is_used);
+
+    // Following the chain upstream ends at a value that was set directly.
+    let upstream = AttributeValue::explain(ctx, si_name_av_id).await?;
+    assert!(upstream.upstream_attribute_value_ids.is_empty());
+
+    Ok(())
+}
+
+#[test]
+async fn copy_subtree(ctx: &mut DalContext) -> Result<()> {
+    let source = ExpectComponent::create_named(ctx, "pirate", "long john silver").await;
+    let target = ExpectComponent::create_named(ctx, "pirate", "captain flint").await;
+    let source_treasure = source.prop(ctx, ["root", "domain", "treasure"]).await;
+    let target_treasure = target.prop(ctx, ["root", "domain", "treasure"]).await;
+    source_treasure.push_with_key(ctx, "gold", "island").await;
+    source_treasure.push_with_key(ctx, "rum", "ship").await;
+    target_treasure.push_with_key(ctx, "map", "cave").await;
+    target
+        .prop(ctx, ["root", "domain", "working_eyes"])
+        .await
+        .set(ctx, 1)
+        .await;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    // Entries only the target had are replaced by the source's, everything else is untouched
+    let source_treasure_av_id = source_treasure.attribute_value(ctx).await.id();
+    let copied_av_id =
+        AttributeValue::copy_subtree(ctx, source_treasure_av_id, target.id()).await?;
+    assert_eq!(
+        target_treasure.attribute_value(ctx).await.id(),
+        copied_av_id
+    );
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    assert_eq!(
+        json!({ "gold": "island", "rum": "ship" }),
+        target_treasure.get(ctx).await
+    );
+    assert_eq!(
+        json!(1),
+        target
+            .prop(ctx, ["root", "domain", "working_eyes"])
+            .await
+            .get(ctx)
+            .await
+    );
+    assert_eq!(
+        json!({ "gold": "island", "rum": "ship" }),
+        source_treasure.get(ctx).await
+    );
+
+    // Values can only be copied between components of the same schema variant
+    let swifty = ExpectComponent::create(ctx, "swifty").await;
+    assert!(matches!(
+        AttributeValue::copy_subtree(ctx, source_treasure_av_id, swifty.id()).await,
+        Err(AttributeValueError::CannotCopySubtreeBetweenVariants(..))
+    ));
+
+    Ok(())
+}
+
+#[test]
+async fn set_expression(ctx: &mut DalContext) -> Result<()> {
+    // The test exclusive schema has the identity function set on "/root/domain/name" with an
+    // input from "/root/si/name", so renaming the component should flow through to expressions
+    // referencing the former.
+    let component = ExpectComponent::create_named(ctx, "starfield", "constellation").await;
+    let name_prop = component.prop(ctx, ["root", "si", "name"]).await;
+    let freestar_prop = component.prop(ctx, ["root", "domain", "freestar"]).await;
+    let hidden_prop = component.prop(ctx, ["root", "domain", "hidden_prop"]).await;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    let freestar_av_id = freestar_prop.attribute_value(ctx).await.id();
+    AttributeValue::set_expression(
+        ctx,
+        freestar_av_id,
+        Expression::parse("${/domain/name}-collective")?,
+    )
+    .await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    assert_eq!(
+        json!("constellation-collective"),
+        freestar_prop.get(ctx).await
+    );
+
+    name_prop.set(ctx, "crimson fleet").await;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    assert_eq!(
+        json!("crimson fleet-collective"),
+        freestar_prop.get(ctx).await
+    );
+
+    // Expressions which would end up depending on themselves are rejected
+    let hidden_av_id = hidden_prop.attribute_value(ctx).await.id();
+    AttributeValue::set_expression(ctx, hidden_av_id, Expression::parse("${/domain/freestar}")?)
+        .await?;
+    assert!(matches!(
+        AttributeValue::set_expression(
+            ctx,
+            freestar_av_id,
+            Expression::parse("${/domain/hidden_prop}")?,
+        )
+        .await,
+        Err(AttributeValueError::ExpressionCycle(..))
+    ));
+
+    // As are references to props which do not exist
+    assert!(matches!(
+        AttributeValue::set_expression(ctx, freestar_av_id, Expression::parse("${/domain/nope}")?)
+            .await,
+        Err(AttributeValueError::ExpressionReferenceNotFound(..))
+    ));
+
+    Ok(())
+}
+
+#[test]
+async fn memoized_executions_follow_func_and_argument_edits(ctx: &mut DalContext) -> Result<()> {
+    // Replace the identity function on "/root/domain/name" with a function that shouts the value
+    // of "/root/si/name".
+    let schema_variant_id = create_unlocked_variant_copy_for_schema_name(ctx, "swifty").await?;
+    let si_name_prop_id = Prop::find_prop_id_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "si", "name"]),
+    )
+    .await?;
+    let domain_name_prop_id = Prop::find_prop_id_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "domain", "name"]),
+    )
+    .await?;
+    let func = FuncAuthoringClient::create_new_attribute_func(
+        ctx,
+        Some("shout".to_string()),
+        Some(EventualParent::SchemaVariant(schema_variant_id)),
+        AttributeFuncDestination::Prop(domain_name_prop_id),
+        vec![],
+    )
+    .await?;
+    let func_argument = FuncAuthoringClient::create_func_argument(
+        ctx,
+        func.id,
+        "name",
+        FuncArgumentKind::String,
+        None,
+    )
+    .await?;
+    let binding = FuncBinding::get_attribute_bindings_for_func_id(ctx, func.id)
+        .await?
+        .pop()
+        .expect("func has no binding");
+    AttributeBinding::update_attribute_binding_arguments(
+        ctx,
+        binding.attribute_prototype_id,
+        vec![AttributeArgumentBinding {
+            func_argument_id: func_argument.id,
+            attribute_prototype_argument_id: None,
+            attribute_func_input_location: AttributeFuncArgumentSource::Prop(si_name_prop_id),
+        }],
+    )
+    .await?;
+    FuncAuthoringClient::save_code(
+        ctx,
+        func.id,
+        "async function main(input) { return input.name.toUpperCase(); }".to_string(),
+    )
+    .await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    // Both components run the function with the same inputs in the same job, so one of them
+    // re-uses the result of the other.
+    let brick =
+        create_named_component_for_schema_variant_on_default_view(ctx, "brick", schema_variant_id)
+            .await?;
+    let other_brick =
+        create_named_component_for_schema_variant_on_default_view(ctx, "brick", schema_variant_id)
+            .await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    for component_id in [brick.id(), other_brick.id()] {
+        assert_eq!(
+            Some(json!("BRICK")),
+            get_attribute_value_for_component(ctx, component_id, &["root", "domain", "name"])
+                .await?
+        );
+    }
+
+    // Editing the function must not re-use results of the previous code.
+    FuncAuthoringClient::save_code(
+        ctx,
+        func.id,
+        "async function main(input) { return input.name + \"!\"; }".to_string(),
+    )
+    .await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    for component_id in [brick.id(), other_brick.id()] {
+        assert_eq!(
+            Some(json!("brick!")),
+            get_attribute_value_for_component(ctx, component_id, &["root", "domain", "name"])
+                .await?
+        );
+    }
+
+    // Editing the argument of one component must not re-use the result for the other.
+    update_attribute_value_for_component(
+        ctx,
+        other_brick.id(),
+        &["root", "si", "name"],
+        json!("plate"),
+    )
+    .await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    assert_eq!(
+        Some(json!("brick!")),
+        get_attribute_value_for_component(ctx, brick.id(), &["root", "domain", "name"]).await?
+    );
+    assert_eq!(
+        Some(json!("plate!")),
+        get_attribute_value_for_component(ctx, other_brick.id(), &["root", "domain", "name"])
+            .await?
+    );
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
-use audit_database::AuditDatabaseContext;
+use audit_database::{AuditDatabaseContext, AuditLogFilter};
 use audit_logs_stream::AuditLogsStream;
-use dal::{prop::PropPath, AttributeValue, DalContext, Prop, Schema, SchemaVariant};
+use chrono::Utc;
+use dal::{audit_logging, prop::PropPath, AttributeValue, DalContext, Prop, Schema, SchemaVariant};
 use dal_test::helpers::{
     confirm_jetstream_stream_has_no_messages,
     create_named_component_for_schema_variant_on_default_view,
@@ -240,3 +241,126 @@ async fn round_trip(ctx: &mut DalContext, audit_database_context: AuditDatabaseC
         .expect("could not list audit logs");
     }
 }
+
+#[test]
+async fn query_with_filters_and_cursor(
+    ctx: &mut DalContext,
+    audit_database_context: AuditDatabaseContext,
+) {
+    let context = audit_database_context;
+
+    let schema = Schema::find_by_name(ctx, "swifty")
+        .await
+        .expect("could not perform find by name")
+        .expect("schema not found by name");
+    let schema_variant_id = schema
+        .get_default_schema_variant_id(ctx)
+        .await
+        .expect("could not get default schema variant id")
+        .expect("no default schema variant id found");
+    let schema_variant = SchemaVariant::get_by_id_or_error(ctx, schema_variant_id)
+        .await
+        .expect("could not get schema variant");
+
+    let component_name = "the cure";
+    let component = create_named_component_for_schema_variant_on_default_view(
+        ctx,
+        component_name,
+        schema_variant_id,
+    )
+    .await
+    .expect("could not create component");
+    ctx.write_audit_log(
+        AuditLogKind::CreateComponent {
+            name: component_name.to_string(),
+            component_id: component.id(),
+            schema_variant_id,
+            schema_variant_name: schema_variant.display_name().to_string(),
+        },
+        component_name.to_string(),
+    )
+    .await
+    .expect("could not write audit log");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let expected_total = 5;
+    let all_logs = list_audit_logs_until_expected_number_of_rows(
+        ctx,
+        &context,
+        SIZE,
+        expected_total,
+        DATABASE_RETRY_TIMEOUT_SECONDS,
+        DATABASE_RETRY_INTERVAL_MILLISECONDS,
+    )
+    .await
+    .expect("could not list audit logs");
+
+    // Page through every log, two at a time.
+    let mut paged_logs = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (logs, next_cursor) =
+            audit_logging::query(ctx, &context, &AuditLogFilter::default(), cursor, 2, false)
+                .await
+                .expect("could not query audit logs");
+        assert!(logs.len() <= 2);
+        paged_logs.extend(logs);
+        match next_cursor {
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => break,
+        }
+    }
+    assert_eq!(
+        expected_total,   // expected
+        paged_logs.len()  // actual
+    );
+    assert!(paged_logs
+        .windows(2)
+        .all(|pair| pair[0].timestamp >= pair[1].timestamp));
+
+    // Filter by the entity type of the component log.
+    let create_component_log = all_logs
+        .iter()
+        .find(|log| log.kind == "CreateComponent")
+        .expect("could not find create component log");
+    let entity_type = create_component_log
+        .entity_type
+        .to_owned()
+        .expect("create component log has no entity type");
+    let (logs, next_cursor) = audit_logging::query(
+        ctx,
+        &context,
+        &AuditLogFilter {
+            entity_type: Some(entity_type.to_owned()),
+            ..Default::default()
+        },
+        None,
+        SIZE,
+        false,
+    )
+    .await
+    .expect("could not query audit logs");
+    assert!(next_cursor.is_none());
+    assert!(logs.contains(create_component_log));
+    assert!(logs
+        .iter()
+        .all(|log| log.entity_type.as_deref() == Some(entity_type.as_str())));
+
+    // Nothing is logged in the future.
+    let (logs, _) = audit_logging::query(
+        ctx,
+        &context,
+        &AuditLogFilter {
+            from: Some(Utc::now() + chrono::Duration::hours(1)),
+            ..Default::default()
+        },
+        None,
+        SIZE,
+        false,
+    )
+    .await
+    .expect("could not query audit logs");
+    assert!(logs.is_empty());
+}
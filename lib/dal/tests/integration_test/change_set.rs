@@ -1,16 +1,25 @@
+use dal::change_set::apply_lock::ChangeSetApplyLocks;
+use dal::change_set::approval::ChangeSetApprovalStatus;
+use dal::change_set::blueprint::{self, BlueprintError, BlueprintParameterDefinition};
+use dal::change_set::size::ChangeSetSizeMetric;
 use dal::change_set::view::OpenChangeSetsView;
+use dal::prop::PropPath;
 use dal::{
     context::TransactionsErrorDiscriminants, DalContext, DalContextBuilder, HistoryActor,
     RequestContext, Workspace, WorkspacePk,
 };
-use dal::{ChangeSet, ChangeSetStatus, Component};
+use dal::{ChangeSet, ChangeSetError, ChangeSetStatus, Component};
+use dal_test::expected::ExpectView;
 use dal_test::helpers::{
-    create_component_for_default_schema_name_in_default_view, create_user, ChangeSetTestHelpers,
+    connect_components_with_socket_names, create_component_for_default_schema_name_in_default_view,
+    create_user, get_attribute_value_for_component, update_attribute_value_for_component,
+    ChangeSetTestHelpers,
 };
 use dal_test::test;
 use itertools::Itertools;
 use pretty_assertions_sorted::assert_eq;
-use std::collections::HashSet;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
 
 #[test]
 async fn open_change_sets(ctx: &mut DalContext) {
@@ -474,3 +483,604 @@ async fn change_set_approval_flow(ctx: &mut DalContext) {
         .collect_vec();
     assert_eq!(components.len(), 2);
 }
+
+#[test]
+async fn change_set_approval_policy(ctx: &mut DalContext) {
+    let new_change_set = ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork head");
+    let requester = *ctx.history_actor();
+    let workspace_pk = ctx.workspace_pk().expect("no workspace pk on context");
+    let mut approvers = vec![];
+    for _ in 0..2 {
+        let user = create_user(ctx).await.expect("could not create user");
+        user.associate_workspace(ctx, workspace_pk)
+            .await
+            .expect("could not associate user with workspace");
+        approvers.push(HistoryActor::User(user.pk()));
+    }
+
+    // production workspaces require two approvals from people other than the requester
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("could not get workspace")
+        .expect("workspace not found");
+    workspace
+        .set_required_change_set_approvals(ctx, Some(2))
+        .await
+        .expect("could not set required approvals");
+
+    let mut change_set = ChangeSet::find(ctx, new_change_set.id)
+        .await
+        .expect("could not find change set")
+        .expect("change set is some");
+    change_set
+        .request_change_set_approval(ctx)
+        .await
+        .expect("could not request approval");
+    let pending = ChangeSet::list_pending_approvals(ctx)
+        .await
+        .expect("could not list pending approvals");
+    assert_eq!(
+        vec![new_change_set.id],
+        pending
+            .iter()
+            .map(|state| state.change_set_id)
+            .collect_vec()
+    );
+
+    // the requester can't approve their own change set
+    let result = change_set
+        .record_approval(ctx, ChangeSetApprovalStatus::Approved, None)
+        .await;
+    assert!(matches!(result, Err(ChangeSetError::SelfApproval(_))));
+    assert!(
+        !change_set
+            .into_frontend_view(ctx, true)
+            .await
+            .expect("could not get change set view")
+            .can_approve,
+        "the requester should not be offered to approve their own change set"
+    );
+
+    // approving twice only counts once
+    ctx.update_history_actor(approvers[0]);
+    assert!(
+        change_set
+            .into_frontend_view(ctx, true)
+            .await
+            .expect("could not get change set view")
+            .can_approve
+    );
+    for _ in 0..2 {
+        let state = change_set
+            .record_approval(ctx, ChangeSetApprovalStatus::Approved, None)
+            .await
+            .expect("could not approve");
+        assert!(!state.is_satisfied());
+        assert_eq!(ChangeSetStatus::NeedsApproval, change_set.status);
+    }
+    assert!(
+        ChangeSetTestHelpers::apply_change_set_to_base_approvals(ctx)
+            .await
+            .is_err(),
+        "change set should not apply before it is approved"
+    );
+
+    ctx.update_history_actor(approvers[1]);
+    let state = change_set
+        .record_approval(
+            ctx,
+            ChangeSetApprovalStatus::Approved,
+            Some("looks good".to_owned()),
+        )
+        .await
+        .expect("could not approve");
+    assert!(state.is_satisfied());
+    assert_eq!(3, state.approvals.len());
+    assert_eq!(Some("looks good"), state.approvals[2].comment.as_deref());
+    assert_eq!(ChangeSetStatus::Approved, change_set.status);
+    assert!(ChangeSet::list_pending_approvals(ctx)
+        .await
+        .expect("could not list pending approvals")
+        .is_empty());
+
+    // approvals don't carry over to the next request
+    ctx.update_history_actor(requester);
+    change_set
+        .reopen_change_set(ctx)
+        .await
+        .expect("could not reopen change set");
+    change_set
+        .request_change_set_approval(ctx)
+        .await
+        .expect("could not request approval");
+    let mut change_set = ChangeSet::find(ctx, new_change_set.id)
+        .await
+        .expect("could not find change set")
+        .expect("change set is some");
+    assert!(change_set
+        .approval_state(ctx)
+        .await
+        .expect("could not get approval state")
+        .approvals
+        .is_empty());
+
+    ctx.update_history_actor(approvers[0]);
+    let state = change_set
+        .record_approval(
+            ctx,
+            ChangeSetApprovalStatus::Rejected,
+            Some("needs tests".to_owned()),
+        )
+        .await
+        .expect("could not reject");
+    assert_eq!(ChangeSetStatus::Rejected, state.status);
+    let result = change_set
+        .record_approval(ctx, ChangeSetApprovalStatus::Approved, None)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::ChangeSetNotAwaitingApproval(
+            _,
+            ChangeSetStatus::Rejected
+        ))
+    ));
+}
+
+#[test]
+async fn stats(ctx: &mut DalContext) {
+    let before = ChangeSet::stats(ctx).await.expect("could not get stats");
+    assert_eq!(ctx.change_set_id(), before.change_set_id);
+    assert!(before.node_count > 0);
+    assert!(before.func_count > 0);
+    assert!(before.schema_variant_count > 0);
+    assert_eq!(
+        before.node_count,                            // expected
+        before.nodes_by_kind.values().sum::<usize>()  // actual
+    );
+    assert_eq!(
+        before.edge_count,                            // expected
+        before.edges_by_kind.values().sum::<usize>()  // actual
+    );
+
+    create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "small")
+        .await
+        .expect("could not create component");
+
+    let after = ChangeSet::stats(ctx).await.expect("could not get stats");
+    assert_eq!(
+        before.component_count + 1, // expected
+        after.component_count       // actual
+    );
+    assert!(after.node_count > before.node_count);
+    assert!(after.content_store_object_count > before.content_store_object_count);
+    // Sizes only cover persisted objects, so the new content may not be counted yet
+    assert!(after.content_store_bytes >= before.content_store_bytes);
+
+    let workspace_stats = ChangeSet::workspace_stats(ctx)
+        .await
+        .expect("could not get workspace stats");
+    assert!(workspace_stats
+        .change_sets
+        .iter()
+        .any(|stats| stats.change_set_id == ctx.change_set_id()));
+    assert_eq!(
+        workspace_stats.change_sets.len(), // expected
+        workspace_stats.change_set_count   // actual
+    );
+}
+
+#[test]
+async fn size_guardrails(ctx: &mut DalContext) {
+    create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "small")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let report = ChangeSet::size_report(ctx)
+        .await
+        .expect("could not get size report");
+    assert_eq!(
+        1,                                 // expected
+        report.metrics.components_touched  // actual
+    );
+    assert!(report.metrics.values_changed > 0);
+    assert!(report.warnings.is_empty());
+    assert!(!report.is_blocked());
+
+    // Tighten the thresholds so that the same change set warns and then blocks apply.
+    let mut thresholds = report.thresholds;
+    thresholds.soft.components_touched = Some(0);
+    thresholds
+        .set_for_workspace(ctx)
+        .await
+        .expect("could not set thresholds");
+    let report = ChangeSet::size_report(ctx)
+        .await
+        .expect("could not get size report");
+    assert_eq!(
+        vec![ChangeSetSizeMetric::ComponentsTouched], // expected
+        report
+            .warnings
+            .iter()
+            .map(|violation| violation.metric)
+            .collect_vec()  // actual
+    );
+    assert!(!report.is_blocked());
+
+    // Soft limits do not block apply, but their warnings are returned to show to the user.
+    let report = ChangeSet::prepare_for_force_apply(ctx)
+        .await
+        .expect("could not prepare for apply");
+    assert_eq!(
+        vec![ChangeSetSizeMetric::ComponentsTouched], // expected
+        report
+            .warnings
+            .iter()
+            .map(|violation| violation.metric)
+            .collect_vec()  // actual
+    );
+
+    thresholds.hard.values_changed = Some(0);
+    thresholds
+        .set_for_workspace(ctx)
+        .await
+        .expect("could not set thresholds");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let result = ChangeSet::prepare_for_force_apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::ChangeSetTooLarge(change_set_id, _)) if change_set_id == ctx.change_set_id()
+    ));
+}
+
+#[test]
+async fn apply_metrics(ctx: &mut DalContext) {
+    create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "small")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let (applied_change_set, metrics) = ChangeSet::apply_to_base_change_set_with_metrics(ctx)
+        .await
+        .expect("could not apply change set");
+    assert_eq!(
+        ChangeSetStatus::Applied,  // expected
+        applied_change_set.status  // actual
+    );
+    assert_eq!(
+        1,                               // expected
+        metrics.size.components_touched  // actual
+    );
+    assert!(metrics.size.values_changed > 0);
+    assert!(metrics.total_duration_ms >= metrics.rebase_duration_ms);
+}
+
+#[test]
+async fn quarantined_change_set_is_read_only(ctx: &mut DalContext) {
+    let change_set_id = ctx.change_set_id();
+    ctx.txns()
+        .await
+        .expect("could not get txns")
+        .pg()
+        .query_none(
+            "UPDATE change_set_pointers SET quarantined_at = CLOCK_TIMESTAMP() WHERE id = $1",
+            &[&change_set_id],
+        )
+        .await
+        .expect("could not quarantine change set");
+
+    let mut change_set = ChangeSet::find(ctx, change_set_id)
+        .await
+        .expect("could not find change set")
+        .expect("change set not found");
+    assert!(change_set.is_quarantined());
+    let address = change_set.workspace_snapshot_address;
+
+    let result = change_set.update_pointer(ctx, address).await;
+    assert!(matches!(result, Err(ChangeSetError::Quarantined(id)) if id == change_set_id));
+
+    // No snapshot was verified, so there is nothing to restore to
+    let result = change_set.restore_last_good_snapshot(ctx).await;
+    assert!(matches!(result, Err(ChangeSetError::NoLastGoodSnapshot(id)) if id == change_set_id));
+
+    change_set
+        .restore_snapshot(ctx, address)
+        .await
+        .expect("could not restore snapshot");
+    let change_set = ChangeSet::find(ctx, change_set_id)
+        .await
+        .expect("could not find change set")
+        .expect("change set not found");
+    assert!(!change_set.is_quarantined());
+    assert_eq!(
+        address,                               // expected
+        change_set.workspace_snapshot_address  // actual
+    );
+}
+
+#[test]
+async fn capture_and_instantiate_blueprint(ctx: &mut DalContext) {
+    let odd =
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "odd")
+            .await
+            .expect("could not create component");
+    let even =
+        create_component_for_default_schema_name_in_default_view(ctx, "small even lego", "even")
+            .await
+            .expect("could not create component");
+    update_attribute_value_for_component(ctx, odd.id(), &["root", "domain", "two"], json!("brick"))
+        .await
+        .expect("could not update value");
+    connect_components_with_socket_names(ctx, odd.id(), "two", even.id(), "two")
+        .await
+        .expect("could not connect components");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let view_id = ExpectView::get_id_for_default(ctx).await;
+    let captured = blueprint::capture(
+        ctx,
+        "lego pair",
+        "an odd lego feeding an even one",
+        view_id,
+        &[odd.id(), even.id()],
+        &[
+            BlueprintParameterDefinition {
+                name: "odd_two".to_string(),
+                description: None,
+                component_id: odd.id(),
+                prop_path: PropPath::new(["root", "domain", "two"]),
+                required: false,
+            },
+            BlueprintParameterDefinition {
+                name: "even_one".to_string(),
+                description: Some("required".to_string()),
+                component_id: even.id(),
+                prop_path: PropPath::new(["root", "domain", "one"]),
+                required: true,
+            },
+        ],
+    )
+    .await
+    .expect("could not capture blueprint");
+
+    // Values computed from sockets cannot be parameters
+    let result = blueprint::capture(
+        ctx,
+        "lego pair",
+        "",
+        view_id,
+        &[odd.id(), even.id()],
+        &[BlueprintParameterDefinition {
+            name: "even_two".to_string(),
+            description: None,
+            component_id: even.id(),
+            prop_path: PropPath::new(["root", "domain", "two"]),
+            required: false,
+        }],
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(BlueprintError::ParameterPropSetByFunction(_, _))
+    ));
+
+    let pkg = blueprint::into_pkg(captured, "0.1.0", "sally@systeminit.com")
+        .expect("could not build pkg");
+    let mut blueprints = blueprint::list_from_pkg(&pkg).expect("could not list blueprints");
+    assert_eq!(1, blueprints.len());
+    let blueprint = blueprints.pop().expect("blueprint not found");
+    assert_eq!(2, blueprint.components.len());
+
+    let result = blueprint::instantiate(ctx, &blueprint, "missing value", HashMap::new()).await;
+    assert!(matches!(
+        result,
+        Err(BlueprintError::MissingParameterValue(name)) if name == "even_one"
+    ));
+
+    let instantiation = blueprint::instantiate(
+        ctx,
+        &blueprint,
+        "from blueprint",
+        HashMap::from([
+            ("even_one".to_string(), json!("stud")),
+            ("odd_two".to_string(), json!("plate")),
+        ]),
+    )
+    .await
+    .expect("could not instantiate blueprint");
+    assert_eq!(instantiation.change_set_id, ctx.change_set_id());
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let new_odd_id = instantiation.component_ids["odd"];
+    let new_even_id = instantiation.component_ids["even"];
+    assert_eq!(
+        2,                                                         // expected
+        Component::list(ctx).await.expect("could not list").len()  // actual
+    );
+    assert_eq!(
+        Some(json!("stud")),
+        get_attribute_value_for_component(ctx, new_even_id, &["root", "domain", "one"])
+            .await
+            .expect("could not get value")
+    );
+    // The parameter value flows through the captured connection
+    assert_eq!(
+        Some(json!("plate")),
+        get_attribute_value_for_component(ctx, new_even_id, &["root", "domain", "two"])
+            .await
+            .expect("could not get value")
+    );
+    assert_eq!(
+        Some(json!("plate")),
+        get_attribute_value_for_component(ctx, new_odd_id, &["root", "domain", "two"])
+            .await
+            .expect("could not get value")
+    );
+}
+
+#[test]
+async fn apply_locks(ctx: &DalContext) {
+    let change_set_id = ctx.change_set_id();
+    // Locks are shared through the database, so separate instances see each other's locks
+    let locks = ChangeSetApplyLocks::new(ctx.services_context().pg_pool().clone());
+    let other_locks = ChangeSetApplyLocks::new(ctx.services_context().pg_pool().clone());
+
+    assert!(!locks
+        .is_locked(change_set_id)
+        .await
+        .expect("could not check lock"));
+    assert!(locks
+        .try_acquire(change_set_id)
+        .await
+        .expect("could not acquire lock"));
+    assert!(other_locks
+        .is_locked(change_set_id)
+        .await
+        .expect("could not check lock"));
+    assert!(!other_locks
+        .try_acquire(change_set_id)
+        .await
+        .expect("could not acquire lock"));
+
+    locks
+        .release(change_set_id)
+        .await
+        .expect("could not release lock");
+    assert!(!other_locks
+        .is_locked(change_set_id)
+        .await
+        .expect("could not check lock"));
+    assert!(other_locks
+        .try_acquire(change_set_id)
+        .await
+        .expect("could not acquire lock"));
+    other_locks
+        .release(change_set_id)
+        .await
+        .expect("could not release lock");
+
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+    assert!(locks
+        .is_in_workspace(change_set_id, workspace_pk)
+        .await
+        .expect("could not check workspace"));
+    assert!(!locks
+        .is_in_workspace(change_set_id, WorkspacePk::generate())
+        .await
+        .expect("could not check workspace"));
+}
+
+#[test]
+async fn list_by_status_for_all_workspaces_pages(ctx: &mut DalContext) {
+    let mut expected_ids = HashSet::new();
+    for _ in 0..3 {
+        let mut change_set = ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+            .await
+            .expect("could not fork head");
+        change_set
+            .update_status(ctx, ChangeSetStatus::NeedsAbandonApproval)
+            .await
+            .expect("could not update status");
+        expected_ids.insert(change_set.id);
+    }
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    // Other tests may have change sets in the same status, so walk every page and check that
+    // ours show up exactly once.
+    let mut seen_ids = HashSet::new();
+    let mut cursor = None;
+    loop {
+        let page = ChangeSet::list_by_status_for_all_workspaces(
+            ctx,
+            &[ChangeSetStatus::NeedsAbandonApproval],
+            cursor,
+            2,
+        )
+        .await
+        .expect("could not list change sets");
+        assert!(page.len() <= 2);
+
+        for change_set in &page {
+            assert!(
+                seen_ids.insert(change_set.id),
+                "change set listed on more than one page"
+            );
+        }
+
+        match page.last() {
+            Some(last) if page.len() == 2 => cursor = Some(last.id),
+            _ => break,
+        }
+    }
+
+    assert!(expected_ids.is_subset(&seen_ids));
+}
+
+#[test]
+async fn healthy_snapshot_content_is_not_corrupted(ctx: &mut DalContext) {
+    let snapshot = ctx
+        .workspace_snapshot()
+        .expect("could not get workspace snapshot");
+
+    // A sample smaller than the content is enough to verify some of it
+    for sample_size in [1, usize::MAX] {
+        let corrupted = snapshot
+            .find_corrupted_content(ctx, sample_size)
+            .await
+            .expect("could not verify snapshot content");
+        assert!(corrupted.is_empty());
+    }
+}
+
+#[test]
+async fn replica_reads_fall_back_to_transactions_without_a_read_replica(
+    ctx: &mut DalContext,
+    ctx_builder: DalContextBuilder,
+) {
+    // Without a configured replica, the primary serves reads
+    assert!(!ctx.pg_pool().has_read_replica());
+    assert_eq!(ctx.pg_pool().db_name(), ctx.pg_pool().read().db_name());
+
+    // Reads run in the context's transactions by default, so they see its uncommitted writes
+    assert!(!ctx.replica_reads());
+    let change_set = ChangeSet::fork_head(ctx, "uncommitted")
+        .await
+        .expect("could not fork head");
+    assert!(ChangeSet::list_active(ctx)
+        .await
+        .expect("could not list change sets")
+        .iter()
+        .any(|cs| cs.id == change_set.id));
+
+    // And so do contexts which allow replica reads when there is no replica to read from
+    let mut ctx_builder = ctx_builder;
+    ctx_builder.set_replica_reads();
+    let replica_ctx = ctx_builder
+        .build_head(ctx.access_builder())
+        .await
+        .expect("could not build context");
+    assert!(replica_ctx.replica_reads());
+    let change_set = ChangeSet::fork_head(&replica_ctx, "uncommitted")
+        .await
+        .expect("could not fork head");
+    assert!(ChangeSet::list_active(&replica_ctx)
+        .await
+        .expect("could not list change sets")
+        .iter()
+        .any(|cs| cs.id == change_set.id));
+}
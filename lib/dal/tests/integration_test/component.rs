@@ -14,12 +14,18 @@ use dal_test::test;
 use pretty_assertions_sorted::assert_eq;
 use serde_json::json;
 
+mod bulk;
+mod connect_matching;
 mod debug;
 mod delete;
+mod duplicate;
 mod get_code;
 mod get_diff;
+mod label;
 mod property_order;
 mod set_type;
+mod socket_compatibility;
+mod suggestion;
 mod upgrade;
 
 #[test]
@@ -0,0 +1,134 @@
+use dal::component::bulk::{BulkBuilder, BulkComponentRef};
+use dal::{Component, ComponentError, DalContext, Schema};
+use dal_test::helpers::ChangeSetTestHelpers;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+#[test]
+async fn apply_creates_sets_and_connects(ctx: &mut DalContext) {
+    let odd_variant_id = Schema::find_by_name(ctx, "small odd lego")
+        .await
+        .expect("could not find schema")
+        .expect("schema not found")
+        .get_default_schema_variant_id_or_error(ctx)
+        .await
+        .expect("could not get default variant");
+    let even_variant_id = Schema::find_by_name(ctx, "small even lego")
+        .await
+        .expect("could not find schema")
+        .expect("schema not found")
+        .get_default_schema_variant_id_or_error(ctx)
+        .await
+        .expect("could not get default variant");
+
+    let mut builder = BulkBuilder::new();
+    let odd = builder.create_component("odd", odd_variant_id, None);
+    let even = builder.create_component("even", even_variant_id, None);
+    builder
+        .set_attribute(odd, &["root", "si", "name"], Some(json!("renamed odd")))
+        .connect(odd, "two", even, "two");
+
+    let created = builder
+        .apply(ctx)
+        .await
+        .expect("could not apply bulk changes");
+    assert_eq!(2, created.len());
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let odd_component = Component::get_by_id(ctx, created[0])
+        .await
+        .expect("could not get component");
+    assert_eq!(
+        "renamed odd",
+        odd_component.name(ctx).await.expect("could not get name")
+    );
+
+    let even_component = Component::get_by_id(ctx, created[1])
+        .await
+        .expect("could not get component");
+    let connections = even_component
+        .incoming_connections(ctx)
+        .await
+        .expect("could not get incoming connections");
+    assert_eq!(1, connections.len());
+    assert_eq!(created[0], connections[0].from_component_id);
+}
+
+#[test]
+async fn apply_rejects_unknown_pending_components(ctx: &mut DalContext) {
+    let mut builder = BulkBuilder::new();
+    builder.set_attribute(
+        BulkComponentRef::Pending(0),
+        &["root", "si", "name"],
+        Some(json!("nobody")),
+    );
+
+    let result = builder.apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ComponentError::BulkPendingComponentNotFound(0))
+    ));
+    assert!(Component::list(ctx)
+        .await
+        .expect("could not list components")
+        .is_empty());
+}
+
+#[test]
+async fn apply_batches_many_components(ctx: &mut DalContext) {
+    let odd_variant_id = Schema::find_by_name(ctx, "small odd lego")
+        .await
+        .expect("could not find schema")
+        .expect("schema not found")
+        .get_default_schema_variant_id_or_error(ctx)
+        .await
+        .expect("could not get default variant");
+    let even_variant_id = Schema::find_by_name(ctx, "small even lego")
+        .await
+        .expect("could not find schema")
+        .expect("schema not found")
+        .get_default_schema_variant_id_or_error(ctx)
+        .await
+        .expect("could not get default variant");
+
+    let mut builder = BulkBuilder::new();
+    let mut pairs = Vec::new();
+    for index in 0..20 {
+        let odd = builder.create_component(format!("odd {index}"), odd_variant_id, None);
+        let even = builder.create_component(format!("even {index}"), even_variant_id, None);
+        builder.connect(odd, "two", even, "two");
+        pairs.push((odd, even));
+    }
+
+    let created = builder
+        .apply(ctx)
+        .await
+        .expect("could not apply bulk changes");
+    assert_eq!(40, created.len());
+    assert!(ctx
+        .workspace_snapshot()
+        .expect("could not get snapshot")
+        .has_dependent_value_roots()
+        .await
+        .expect("could not check for dependent value roots"));
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    for (odd, even) in pairs {
+        let (BulkComponentRef::Pending(odd), BulkComponentRef::Pending(even)) = (odd, even) else {
+            panic!("expected pending references");
+        };
+        let connections = Component::get_by_id(ctx, created[even])
+            .await
+            .expect("could not get component")
+            .incoming_connections(ctx)
+            .await
+            .expect("could not get incoming connections");
+        assert_eq!(1, connections.len());
+        assert_eq!(created[odd], connections[0].from_component_id);
+    }
+}
@@ -0,0 +1,94 @@
+use dal::component::connect_matching::{SkipReason, SkippedConnection};
+use dal::{Component, DalContext};
+use dal_test::expected::{self, ExpectComponent};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn connect_matching_sockets_reports_created_and_skipped(ctx: &mut DalContext) {
+    let target = ExpectComponent::create_named(ctx, "medium even lego", "target").await;
+    let first = ExpectComponent::create_named(ctx, "small odd lego", "first").await;
+    let second = ExpectComponent::create_named(ctx, "small odd lego", "second").await;
+    let third = ExpectComponent::create_named(ctx, "small odd lego", "third").await;
+    // Even legos only output the sockets that even legos take as inputs
+    let incompatible = ExpectComponent::create_named(ctx, "small even lego", "even").await;
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let sources = [
+        first.id(),
+        second.id(),
+        third.id(),
+        second.id(),
+        incompatible.id(),
+        target.id(),
+    ];
+    let report = Component::connect_matching_sockets(ctx, &sources, target.id())
+        .await
+        .expect("could not connect matching sockets");
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let output_socket_id = first.output_socket(ctx, "two").await.prop().id();
+    let input_socket_id = target.input_socket(ctx, "two").await.prop().id();
+    assert_eq!(
+        vec![
+            (first.id(), output_socket_id, input_socket_id),
+            (second.id(), output_socket_id, input_socket_id),
+            (third.id(), output_socket_id, input_socket_id),
+        ],
+        report
+            .created
+            .iter()
+            .map(|created| (
+                created.source_component_id,
+                created.output_socket_id,
+                created.input_socket_id
+            ))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![
+            SkippedConnection {
+                source_component_id: incompatible.id(),
+                output_socket_id: None,
+                input_socket_id: None,
+                reason: SkipReason::NoCompatibleSockets,
+            },
+            SkippedConnection {
+                source_component_id: target.id(),
+                output_socket_id: None,
+                input_socket_id: None,
+                reason: SkipReason::SameComponent,
+            },
+        ],
+        report.skipped
+    );
+
+    let mut incoming: Vec<_> = target
+        .component(ctx)
+        .await
+        .incoming_connections(ctx)
+        .await
+        .expect("could not get incoming connections")
+        .into_iter()
+        .map(|connection| connection.from_component_id)
+        .collect();
+    incoming.sort();
+    let mut expected_incoming = vec![first.id(), second.id(), third.id()];
+    expected_incoming.sort();
+    assert_eq!(expected_incoming, incoming);
+
+    // Running it again leaves the existing connections alone
+    let report = Component::connect_matching_sockets(ctx, &[first.id()], target.id())
+        .await
+        .expect("could not connect matching sockets");
+    assert!(report.created.is_empty());
+    assert_eq!(
+        vec![SkippedConnection {
+            source_component_id: first.id(),
+            output_socket_id: Some(output_socket_id),
+            input_socket_id: Some(input_socket_id),
+            reason: SkipReason::AlreadyConnected,
+        }],
+        report.skipped
+    );
+}
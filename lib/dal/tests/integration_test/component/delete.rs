@@ -2,9 +2,12 @@ use std::time::Duration;
 
 use dal::action::prototype::{ActionKind, ActionPrototype};
 use dal::action::Action;
-use dal::component::delete::{delete_components, ComponentDeletionStatus};
+use dal::component::delete::{
+    delete_components, ComponentDeletionImpactEdge, ComponentDeletionStatus,
+};
 use dal::component::frame::Frame;
 use dal::component::resource::ResourceData;
+use dal::component::socket::ComponentInputSocket;
 use dal::func::intrinsics::IntrinsicFunc;
 use dal::{AttributeValue, ComponentType, Func, InputSocket, OutputSocket};
 use dal::{Component, DalContext, Schema, SchemaVariant};
@@ -1113,3 +1116,130 @@ async fn delete_multiple_components(ctx: &mut DalContext) {
         "component with resource should be marked as to delete"
     );
 }
+
+#[test]
+async fn deletion_impact(ctx: &mut DalContext) {
+    let docker_image_schema = Schema::find_by_name(ctx, "Docker Image")
+        .await
+        .expect("could not perform find by name")
+        .expect("no schema found");
+    let docker_image_schema_variant_id =
+        SchemaVariant::get_default_id_for_schema(ctx, docker_image_schema.id())
+            .await
+            .expect("could not get default schema variant id");
+    let butane_schema = Schema::find_by_name(ctx, "Butane")
+        .await
+        .expect("could not perform find by name")
+        .expect("no schema found");
+    let butane_schema_variant_id =
+        SchemaVariant::get_default_id_for_schema(ctx, butane_schema.id())
+            .await
+            .expect("could not get default schema variant id");
+
+    let output_socket =
+        OutputSocket::find_with_name(ctx, "Container Image", docker_image_schema_variant_id)
+            .await
+            .expect("could not perform find output socket")
+            .expect("output socket not found");
+    let input_socket =
+        InputSocket::find_with_name(ctx, "Container Image", butane_schema_variant_id)
+            .await
+            .expect("could not perform find input socket")
+            .expect("input socket not found");
+
+    let oysters_component = create_named_component_for_schema_variant_on_default_view(
+        ctx,
+        "oysters in my pocket",
+        docker_image_schema_variant_id,
+    )
+    .await
+    .expect("could not create component");
+    let lunch_component = create_named_component_for_schema_variant_on_default_view(
+        ctx,
+        "were saving for lunch",
+        docker_image_schema_variant_id,
+    )
+    .await
+    .expect("could not create component");
+    let royel_component = create_named_component_for_schema_variant_on_default_view(
+        ctx,
+        "royel otis",
+        butane_schema_variant_id,
+    )
+    .await
+    .expect("could not create component");
+
+    Component::connect(
+        ctx,
+        oysters_component.id(),
+        output_socket.id(),
+        royel_component.id(),
+        input_socket.id(),
+    )
+    .await
+    .expect("could not connect components");
+
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let input_socket_value_id =
+        ComponentInputSocket::get_by_ids_or_error(ctx, royel_component.id(), input_socket.id())
+            .await
+            .expect("could not get component input socket")
+            .attribute_value_id;
+    let edge = ComponentDeletionImpactEdge {
+        from_component_id: oysters_component.id(),
+        from_output_socket_id: output_socket.id(),
+        to_component_id: royel_component.id(),
+        to_input_socket_id: input_socket.id(),
+        inferred: false,
+    };
+
+    // As the only source, deleting oysters would leave the input socket unset.
+    let impact = Component::deletion_impact(ctx, oysters_component.id())
+        .await
+        .expect("could not get deletion impact");
+    assert_eq!(vec![royel_component.id()], impact.affected_component_ids);
+    assert_eq!(vec![edge.clone()], impact.edges);
+    assert_eq!(
+        vec![input_socket_value_id],
+        impact.unset_attribute_value_ids
+    );
+
+    // The destination sees the edge too, but nothing downstream of it is affected.
+    let impact = Component::deletion_impact(ctx, royel_component.id())
+        .await
+        .expect("could not get deletion impact");
+    assert!(impact.affected_component_ids.is_empty());
+    assert_eq!(vec![edge.clone()], impact.edges);
+    assert!(impact.unset_attribute_value_ids.is_empty());
+
+    Component::connect(
+        ctx,
+        lunch_component.id(),
+        output_socket.id(),
+        royel_component.id(),
+        input_socket.id(),
+    )
+    .await
+    .expect("could not connect components");
+
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // With lunch feeding the same socket, the value survives deleting oysters.
+    let impact = Component::deletion_impact(ctx, oysters_component.id())
+        .await
+        .expect("could not get deletion impact");
+    assert_eq!(vec![royel_component.id()], impact.affected_component_ids);
+    assert_eq!(vec![edge], impact.edges);
+    assert!(impact.unset_attribute_value_ids.is_empty());
+
+    // Nothing was deleted along the way.
+    assert!(!Component::get_by_id(ctx, oysters_component.id())
+        .await
+        .expect("could not get component")
+        .to_delete());
+}
@@ -0,0 +1,112 @@
+use dal::{Component, ComponentType, DalContext};
+use dal_test::expected::{self, ExpectComponent};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+#[test]
+async fn duplicate_subtree_remaps_parents_and_connections(ctx: &mut DalContext) {
+    let frame = ExpectComponent::create_named(ctx, "pet_shop", "Petopia").await;
+    frame
+        .set_type(ctx, ComponentType::ConfigurationFrameDown)
+        .await;
+    let child = ExpectComponent::create_named(ctx, "pirate", "Long John Silver").await;
+    child.upsert_parent(ctx, frame.id()).await;
+    let sibling = ExpectComponent::create_named(ctx, "pirate", "Billy Bones").await;
+    sibling.upsert_parent(ctx, frame.id()).await;
+
+    let parrots = child.prop(ctx, ["root", "domain", "parrot_names"]).await;
+    parrots.push(ctx, "Captain Flint").await;
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let duplicates = Component::duplicate_subtree(ctx, frame.id(), " (dupe)")
+        .await
+        .expect("could not duplicate subtree");
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    assert_eq!(3, duplicates.len());
+    let frame_copy = ExpectComponent(duplicates[&frame.id()]);
+    let child_copy = ExpectComponent(duplicates[&child.id()]);
+    let sibling_copy = ExpectComponent(duplicates[&sibling.id()]);
+
+    assert_eq!(
+        "Petopia (dupe)",
+        frame_copy
+            .component(ctx)
+            .await
+            .name(ctx)
+            .await
+            .expect("get name")
+    );
+    assert_eq!(
+        ComponentType::ConfigurationFrameDown,
+        frame_copy.get_type(ctx).await
+    );
+    assert_eq!(
+        None,
+        frame_copy
+            .component(ctx)
+            .await
+            .parent(ctx)
+            .await
+            .expect("get parent")
+    );
+    for copy in [child_copy, sibling_copy] {
+        assert_eq!(
+            Some(frame_copy.id()),
+            copy.component(ctx)
+                .await
+                .parent(ctx)
+                .await
+                .expect("get parent")
+        );
+    }
+
+    let parrots_copy = child_copy.prop(ctx, parrots).await;
+    assert_eq!(json!(["Captain Flint"]), parrots_copy.get(ctx).await);
+
+    // The original tree is untouched
+    let mut children = Component::get_children_for_id(ctx, frame.id())
+        .await
+        .expect("get children");
+    children.sort();
+    let mut expected_children = vec![child.id(), sibling.id()];
+    expected_children.sort();
+    assert_eq!(expected_children, children);
+}
+
+#[test]
+async fn duplicate_subtree_only_copies_intra_tree_connections(ctx: &mut DalContext) {
+    let outside = ExpectComponent::create_named(ctx, "pet_shop", "Outside Shop").await;
+    let root = ExpectComponent::create_named(ctx, "pet_shop", "Petopia").await;
+    root.set_type(ctx, ComponentType::ConfigurationFrameDown)
+        .await;
+    let inside = ExpectComponent::create_named(ctx, "pirate", "Long John Silver").await;
+    inside.upsert_parent(ctx, root.id()).await;
+    let downstream = ExpectComponent::create_named(ctx, "pirate", "Billy Bones").await;
+
+    root.connect(ctx, "parrot_names", inside, "parrot_names")
+        .await;
+    outside
+        .connect(ctx, "parrot_names", downstream, "parrot_names")
+        .await;
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let duplicates = Component::duplicate_subtree(ctx, root.id(), " - 2")
+        .await
+        .expect("could not duplicate subtree");
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    assert_eq!(2, duplicates.len());
+    let root_copy = duplicates[&root.id()];
+    let inside_copy = ExpectComponent(duplicates[&inside.id()]);
+
+    let incoming = inside_copy
+        .component(ctx)
+        .await
+        .incoming_connections(ctx)
+        .await
+        .expect("get incoming connections");
+    assert_eq!(1, incoming.len());
+    assert_eq!(root_copy, incoming[0].from_component_id);
+}
@@ -1,4 +1,5 @@
 use dal::code_view::CodeLanguage;
+use dal::component::diff::{ComponentDiffStatus, DiffKind, PropDiff};
 use dal::{Component, ComponentType, DalContext};
 use dal_test::helpers::create_component_for_default_schema_name_in_default_view;
 use dal_test::helpers::ChangeSetTestHelpers;
@@ -144,3 +145,66 @@ async fn get_diff_component_change_comp_type(ctx: &mut DalContext) {
         first_diff.code // actual
     );
 }
+
+#[test]
+async fn get_structured_diff_component_change_comp_type(ctx: &mut DalContext) {
+    let starfield_component = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "starfield",
+        "this is a new component",
+    )
+    .await
+    .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    let diff = Component::get_structured_diff(ctx, starfield_component.id())
+        .await
+        .expect("unable to get structured diff");
+    assert_eq!(ComponentDiffStatus::Added, diff.status);
+    assert!(diff
+        .props
+        .iter()
+        .all(|prop_diff| prop_diff.kind == DiffKind::Added));
+
+    // Apply the change set and create a new change set.
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set");
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+
+    let diff = Component::get_structured_diff(ctx, starfield_component.id())
+        .await
+        .expect("unable to get structured diff");
+    assert_eq!(ComponentDiffStatus::Unchanged, diff.status);
+    assert!(diff.props.is_empty());
+
+    Component::set_type_by_id(
+        ctx,
+        starfield_component.id(),
+        ComponentType::ConfigurationFrameDown,
+    )
+    .await
+    .expect("could not set type");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    let diff = Component::get_structured_diff(ctx, starfield_component.id())
+        .await
+        .expect("unable to get structured diff");
+    assert_eq!(ComponentDiffStatus::Modified, diff.status);
+    assert_eq!(
+        vec![PropDiff {
+            path: "/si/type".to_string(),
+            kind: DiffKind::Changed,
+            before: Some(serde_json::json!("component")),
+            after: Some(serde_json::json!("configurationFrameDown")),
+        }],
+        diff.props
+    );
+    assert!(diff.connections.is_empty());
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use dal::{Component, ComponentError, DalContext};
+use dal_test::expected::{self, ExpectComponent};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn set_and_remove_labels(ctx: &mut DalContext) {
+    let component = ExpectComponent::create_named(ctx, "pirate", "Long John Silver").await;
+
+    component
+        .component(ctx)
+        .await
+        .set_label(ctx, "team", "crew")
+        .await
+        .expect("could not set label")
+        .set_label(ctx, "env", "production")
+        .await
+        .expect("could not set label");
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let labels = component.component(ctx).await.labels().clone();
+    assert_eq!(
+        BTreeMap::from([
+            ("env".to_string(), "production".to_string()),
+            ("team".to_string(), "crew".to_string()),
+        ]),
+        labels
+    );
+
+    component
+        .component(ctx)
+        .await
+        .remove_label(ctx, "team")
+        .await
+        .expect("could not remove label");
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let component = component.component(ctx).await;
+    assert_eq!(None, component.label("team"));
+    assert_eq!(Some("production"), component.label("env"));
+}
+
+#[test]
+async fn set_label_rejects_invalid_keys(ctx: &mut DalContext) {
+    let component = ExpectComponent::create_named(ctx, "pirate", "Long John Silver").await;
+
+    for key in ["", "has space", "a".repeat(64).as_str()] {
+        let result = component
+            .component(ctx)
+            .await
+            .set_label(ctx, key, "value")
+            .await;
+        assert!(
+            matches!(result, Err(ComponentError::InvalidLabelKey(_))),
+            "expected {key:?} to be rejected"
+        );
+    }
+}
+
+#[test]
+async fn list_by_labels(ctx: &mut DalContext) {
+    let production = ExpectComponent::create_named(ctx, "pirate", "Long John Silver").await;
+    let staging = ExpectComponent::create_named(ctx, "pirate", "Billy Bones").await;
+    let unlabeled = ExpectComponent::create_named(ctx, "pirate", "Ben Gunn").await;
+
+    production
+        .component(ctx)
+        .await
+        .set_labels(
+            ctx,
+            BTreeMap::from([
+                ("env".to_string(), "production".to_string()),
+                ("team".to_string(), "crew".to_string()),
+            ]),
+        )
+        .await
+        .expect("could not set labels");
+    staging
+        .component(ctx)
+        .await
+        .set_label(ctx, "env", "staging")
+        .await
+        .expect("could not set label");
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let ids_for = |components: Vec<Component>| {
+        let mut ids: Vec<_> = components.iter().map(Component::id).collect();
+        ids.sort();
+        ids
+    };
+
+    let any_env = Component::list_by_labels(ctx, &BTreeMap::from([("env".to_string(), None)]))
+        .await
+        .expect("could not list by labels");
+    let mut expected_ids = vec![production.id(), staging.id()];
+    expected_ids.sort();
+    assert_eq!(expected_ids, ids_for(any_env));
+
+    let production_crew = Component::list_by_labels(
+        ctx,
+        &BTreeMap::from([
+            ("env".to_string(), Some("production".to_string())),
+            ("team".to_string(), Some("crew".to_string())),
+        ]),
+    )
+    .await
+    .expect("could not list by labels");
+    assert_eq!(vec![production.id()], ids_for(production_crew));
+
+    let all = Component::list_by_labels(ctx, &BTreeMap::new())
+        .await
+        .expect("could not list by labels");
+    assert!(ids_for(all).contains(&unlabeled.id()));
+}
+
+#[test]
+async fn find_by_label(ctx: &mut DalContext) {
+    let production = ExpectComponent::create_named(ctx, "pirate", "Long John Silver").await;
+    let staging = ExpectComponent::create_named(ctx, "pirate", "Billy Bones").await;
+
+    production
+        .component(ctx)
+        .await
+        .set_label(ctx, "env", "production")
+        .await
+        .expect("could not set label");
+    staging
+        .component(ctx)
+        .await
+        .set_label(ctx, "env", "staging")
+        .await
+        .expect("could not set label");
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let found: Vec<_> = Component::find_by_label(ctx, "env", "staging")
+        .await
+        .expect("could not find by label")
+        .iter()
+        .map(Component::id)
+        .collect();
+    assert_eq!(vec![staging.id()], found);
+
+    assert!(Component::find_by_label(ctx, "env", "development")
+        .await
+        .expect("could not find by label")
+        .is_empty());
+}
@@ -0,0 +1,49 @@
+use std::collections::HashSet;
+
+use dal::component::socket_compatibility::CompatibleInputSocket;
+use dal::{Component, DalContext};
+use dal_test::expected::{self, ExpectComponent};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn compatible_input_sockets(ctx: &mut DalContext) {
+    let source = ExpectComponent::create_named(ctx, "small odd lego", "source").await;
+    let connected = ExpectComponent::create_named(ctx, "small even lego", "connected").await;
+    let unconnected = ExpectComponent::create_named(ctx, "small even lego", "unconnected").await;
+    // Odd legos only take the sockets that even legos output
+    let _incompatible = ExpectComponent::create_named(ctx, "small odd lego", "incompatible").await;
+    source.connect(ctx, "two", connected, "two").await;
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let output_socket_id = source.output_socket(ctx, "two").await.prop().id();
+    let compatible: HashSet<_> =
+        Component::compatible_input_sockets(ctx, source.id(), output_socket_id)
+            .await
+            .expect("could not list compatible input sockets")
+            .into_iter()
+            .collect();
+
+    // The socket it is already connected to is left out
+    assert_eq!(
+        HashSet::from([CompatibleInputSocket {
+            component_id: unconnected.id(),
+            input_socket_id: unconnected.input_socket(ctx, "two").await.prop().id(),
+        }]),
+        compatible
+    );
+}
+
+#[test]
+async fn compatible_input_sockets_for_foreign_output_socket(ctx: &mut DalContext) {
+    let source = ExpectComponent::create_named(ctx, "small odd lego", "source").await;
+    let other = ExpectComponent::create_named(ctx, "small even lego", "other").await;
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let output_socket_id = other.output_socket(ctx, "one").await.prop().id();
+    assert!(
+        Component::compatible_input_sockets(ctx, source.id(), output_socket_id)
+            .await
+            .is_err()
+    );
+}
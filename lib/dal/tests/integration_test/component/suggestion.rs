@@ -0,0 +1,86 @@
+use dal::{prop::PropPath, Component, DalContext, Prop};
+use dal_test::{
+    helpers::{
+        connect_components_with_socket_names,
+        create_component_for_default_schema_name_in_default_view,
+        update_attribute_value_for_component, ChangeSetTestHelpers,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+#[test]
+async fn suggest_values_from_fitting_output_sockets(ctx: &mut DalContext) {
+    let starfield =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "new atlantis")
+            .await
+            .expect("could not create component");
+    let vault_111 =
+        create_component_for_default_schema_name_in_default_view(ctx, "fallout", "vault 111")
+            .await
+            .expect("could not create component");
+    let vault_76 =
+        create_component_for_default_schema_name_in_default_view(ctx, "fallout", "vault 76")
+            .await
+            .expect("could not create component");
+
+    update_attribute_value_for_component(
+        ctx,
+        vault_111.id(),
+        &["root", "domain", "special"],
+        json!("charisma"),
+    )
+    .await
+    .expect("could not update special");
+    connect_components_with_socket_names(
+        ctx,
+        vault_111.id(),
+        "bethesda",
+        starfield.id(),
+        "bethesda",
+    )
+    .await
+    .expect("could not connect components");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // The "attributes" prop is fed by the "bethesda" input socket
+    let schema_variant_id = Component::schema_variant_id(ctx, starfield.id())
+        .await
+        .expect("could not get schema variant id");
+    let prop_id = Prop::find_prop_id_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "domain", "attributes"]),
+    )
+    .await
+    .expect("could not find prop");
+
+    let suggestions = Component::suggest_values_for_prop(ctx, starfield.id(), prop_id)
+        .await
+        .expect("could not suggest values");
+
+    assert!(suggestions
+        .iter()
+        .all(|suggestion| suggestion.component_id != starfield.id()));
+
+    // Only vault 111 has a value for its "bethesda" output socket
+    let bethesda: Vec<_> = suggestions
+        .iter()
+        .filter(|suggestion| suggestion.output_socket_name == "bethesda")
+        .collect();
+    assert_eq!(1, bethesda.len());
+    assert_eq!(vault_111.id(), bethesda[0].component_id);
+    assert_eq!("vault 111", bethesda[0].component_name);
+    assert_eq!(json!("charisma"), bethesda[0].value);
+    assert!(bethesda[0].connected);
+
+    // The "fallout" output socket also fits, and always has a value
+    assert!(suggestions.iter().any(|suggestion| {
+        suggestion.component_id == vault_76.id()
+            && suggestion.output_socket_name == "fallout"
+            && !suggestion.connected
+    }));
+}
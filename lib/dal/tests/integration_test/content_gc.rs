@@ -0,0 +1,170 @@
+use std::{sync::Arc, time::Duration};
+
+use dal::{
+    content_gc::{collect_garbage, ContentGcOptions},
+    context::DryRun,
+    DalContext,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+use si_events::{CasValue, ContentHash};
+
+async fn backdate_content(ctx: &DalContext, keys: &[ContentHash]) {
+    let keys: Vec<String> = keys.iter().map(ToString::to_string).collect();
+    ctx.layer_db()
+        .pg_pool()
+        .get()
+        .await
+        .expect("could not get layer db connection")
+        .query(
+            "UPDATE cas SET created_at = CLOCK_TIMESTAMP() - INTERVAL '2 days' WHERE key = ANY($1)",
+            &[&keys],
+        )
+        .await
+        .expect("could not backdate content");
+}
+
+async fn content_is_stored(ctx: &DalContext, key: &ContentHash) -> bool {
+    ctx.layer_db()
+        .cas()
+        .cache
+        .pg()
+        .contains_key(&key.to_string())
+        .await
+        .expect("could not check for content")
+}
+
+#[test]
+async fn collects_only_unreferenced_content(ctx: &DalContext) {
+    let value: CasValue = json!({ "orphaned": true }).into();
+    let (orphaned, status) = ctx
+        .layer_db()
+        .cas()
+        .write(
+            Arc::new(value.into()),
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )
+        .expect("could not write content");
+    status
+        .get_status()
+        .await
+        .expect("could not persist content");
+
+    let mut referenced = None;
+    for (node_weight, _) in ctx
+        .workspace_snapshot()
+        .expect("could not get snapshot")
+        .nodes()
+        .await
+        .expect("could not get nodes")
+    {
+        if let Some(hash) = node_weight.content_store_hashes().first() {
+            referenced = Some(*hash);
+            break;
+        }
+    }
+    let referenced = referenced.expect("snapshot has no content");
+
+    backdate_content(ctx, &[orphaned, referenced]).await;
+
+    let options = ContentGcOptions {
+        retention: Duration::from_secs(24 * 60 * 60),
+        ..Default::default()
+    };
+    let report = collect_garbage(ctx, options)
+        .await
+        .expect("could not run dry run");
+    assert!(report.dry_run);
+    assert!(report.unreferenced >= 1);
+    assert_eq!(0, report.deleted);
+    assert!(content_is_stored(ctx, &orphaned).await);
+
+    let report = collect_garbage(
+        ctx,
+        ContentGcOptions {
+            dry_run: false,
+            ..options
+        },
+    )
+    .await
+    .expect("could not collect garbage");
+    assert_eq!(report.unreferenced, report.deleted);
+    assert!(!content_is_stored(ctx, &orphaned).await);
+    assert!(content_is_stored(ctx, &referenced).await);
+}
+
+#[test]
+async fn keeps_recent_unreferenced_content(ctx: &DalContext) {
+    let value: CasValue = json!({ "orphaned": "recently" }).into();
+    let (orphaned, status) = ctx
+        .layer_db()
+        .cas()
+        .write(
+            Arc::new(value.into()),
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )
+        .expect("could not write content");
+    status
+        .get_status()
+        .await
+        .expect("could not persist content");
+
+    collect_garbage(
+        ctx,
+        ContentGcOptions {
+            dry_run: false,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not collect garbage");
+    assert!(content_is_stored(ctx, &orphaned).await);
+}
+
+#[test]
+async fn dry_run_context_deletes_nothing(ctx: &DalContext) {
+    let value: CasValue = json!({ "orphaned": "in a dry run" }).into();
+    let (orphaned, status) = ctx
+        .layer_db()
+        .cas()
+        .write(
+            Arc::new(value.into()),
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )
+        .expect("could not write content");
+    status
+        .get_status()
+        .await
+        .expect("could not persist content");
+    backdate_content(ctx, &[orphaned]).await;
+
+    let mut builder = ctx.services_context().into_builder(true);
+    builder.set_dry_run(DryRun::new());
+    let dry_run_ctx = builder
+        .build_default()
+        .await
+        .expect("could not build dry run context");
+
+    // Even when asked to delete, a dry run context only counts
+    let report = collect_garbage(
+        &dry_run_ctx,
+        ContentGcOptions {
+            retention: Duration::from_secs(24 * 60 * 60),
+            dry_run: false,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not collect garbage");
+    assert!(report.dry_run);
+    assert!(report.unreferenced >= 1);
+    assert_eq!(0, report.deleted);
+    assert!(content_is_stored(ctx, &orphaned).await);
+}
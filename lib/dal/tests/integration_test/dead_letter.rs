@@ -0,0 +1,63 @@
+use std::io;
+
+use dal::job::{
+    consumer::JobInfo,
+    dead_letter::{DeadLetter, DeadLetterError, JobAttempt},
+    definition::DependentValuesUpdate,
+};
+use dal::{DalContext, WorkspacePk};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn dead_letters_are_listed_and_replayed_per_workspace(ctx: &DalContext) {
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+    let mut job_info = JobInfo::new(DependentValuesUpdate::new(
+        ctx.access_builder(),
+        *ctx.visibility(),
+    ))
+    .expect("could not create job info");
+    job_info.failed_attempts.push(JobAttempt::failed(
+        "pinga-test",
+        &io::Error::new(io::ErrorKind::Other, "boom"),
+    ));
+    let job_id = job_info.id.clone();
+    DeadLetter::new(job_info)
+        .publish(ctx.nats_conn())
+        .await
+        .expect("could not publish dead letter");
+
+    let dead_letters = DeadLetter::list_for_workspace(ctx, workspace_pk, 10)
+        .await
+        .expect("could not list dead letters");
+    assert_eq!(1, dead_letters.len());
+    let stored = &dead_letters[0];
+    assert_eq!(job_id, stored.dead_letter.job_info.id);
+    assert_eq!(&["boom".to_string()], stored.dead_letter.error_chain());
+
+    // Dead letters of other workspaces are not found from this one
+    assert!(DeadLetter::list_for_workspace(ctx, WorkspacePk::new(), 10)
+        .await
+        .expect("could not list dead letters")
+        .is_empty());
+    assert!(matches!(
+        DeadLetter::get(ctx, WorkspacePk::new(), stored.sequence).await,
+        Err(DeadLetterError::NotFound(_))
+    ));
+
+    let replayed = DeadLetter::replay(ctx, workspace_pk, stored.sequence)
+        .await
+        .expect("could not replay dead letter");
+    assert_eq!(job_id, replayed.id);
+    assert_eq!(1, replayed.failed_attempts.len());
+
+    // A replayed dead letter is gone, as is one which never existed
+    assert!(matches!(
+        DeadLetter::get(ctx, workspace_pk, stored.sequence).await,
+        Err(DeadLetterError::NotFound(_))
+    ));
+    assert!(matches!(
+        DeadLetter::get(ctx, workspace_pk, stored.sequence + 1_000).await,
+        Err(DeadLetterError::NotFound(_))
+    ));
+}
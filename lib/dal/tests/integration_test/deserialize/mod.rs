@@ -197,6 +197,7 @@ fn make_me_one_with_everything(graph: &mut WorkspaceSnapshotGraphVCurrent) {
             EdgeWeightKindDiscriminants::Represents => EdgeWeightKind::Represents,
             EdgeWeightKindDiscriminants::Manages => EdgeWeightKind::Manages,
             EdgeWeightKindDiscriminants::DiagramObject => EdgeWeightKind::DiagramObject,
+            EdgeWeightKindDiscriminants::ExpressionReference => EdgeWeightKind::ExpressionReference,
         };
 
         let edge_weight = EdgeWeight::new(edge_weight_kind);
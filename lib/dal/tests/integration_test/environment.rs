@@ -0,0 +1,170 @@
+use dal::{
+    diagram::view::View,
+    environment::{Environment, EnvironmentError, Promotion},
+    DalContext,
+};
+use dal_test::{
+    helpers::{
+        create_component_for_default_schema_name, get_attribute_value_for_component,
+        update_attribute_value_for_component, ChangeSetTestHelpers,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+async fn create_view_for_environment(
+    ctx: &DalContext,
+    name: &str,
+    environment: Environment,
+) -> View {
+    let mut view = View::new(ctx, name).await.expect("could not create view");
+    view.set_environment(ctx, Some(environment))
+        .await
+        .expect("could not set environment");
+    view
+}
+
+#[test]
+async fn promote_component(ctx: &mut DalContext) {
+    let dev_view = create_view_for_environment(ctx, "dev", Environment::Development).await;
+    let prod_view = create_view_for_environment(ctx, "prod", Environment::Production).await;
+
+    let dev_component =
+        create_component_for_default_schema_name(ctx, "fallout", "vault 111", dev_view.id())
+            .await
+            .expect("could not create component");
+    let prod_component =
+        create_component_for_default_schema_name(ctx, "fallout", "vault 111", prod_view.id())
+            .await
+            .expect("could not create component");
+
+    update_attribute_value_for_component(
+        ctx,
+        dev_component.id(),
+        &["root", "domain", "special"],
+        json!("charisma"),
+    )
+    .await
+    .expect("could not update special");
+    update_attribute_value_for_component(
+        ctx,
+        dev_component.id(),
+        &["root", "domain", "rads"],
+        json!(10),
+    )
+    .await
+    .expect("could not update rads");
+    // Only set on the counterpart, so promoting resets it to the default
+    update_attribute_value_for_component(
+        ctx,
+        prod_component.id(),
+        &["root", "domain", "active"],
+        json!(false),
+    )
+    .await
+    .expect("could not update active");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert_eq!(
+        Some(Environment::Development),
+        Environment::for_component(ctx, dev_component.id())
+            .await
+            .expect("could not get environment")
+    );
+    assert_eq!(
+        prod_component.id(),
+        Environment::Production
+            .counterpart(ctx, dev_component.id())
+            .await
+            .expect("could not find counterpart")
+    );
+
+    let counterpart_id = Promotion::new(Environment::Production)
+        .map_parameter(&["root", "domain", "rads"], Some(json!(0)))
+        .promote(ctx, dev_component.id())
+        .await
+        .expect("could not promote component");
+    assert_eq!(prod_component.id(), counterpart_id);
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // The directly set value is copied, the mapped parameter is not
+    assert_eq!(
+        Some(json!("charisma")),
+        get_attribute_value_for_component(ctx, counterpart_id, &["root", "domain", "special"])
+            .await
+            .expect("could not get special")
+    );
+    assert_eq!(
+        Some(json!(0)),
+        get_attribute_value_for_component(ctx, counterpart_id, &["root", "domain", "rads"])
+            .await
+            .expect("could not get rads")
+    );
+    assert_eq!(
+        Some(json!(true)),
+        get_attribute_value_for_component(ctx, counterpart_id, &["root", "domain", "active"])
+            .await
+            .expect("could not get active")
+    );
+    // The name is computed from the component's own name, which is the same
+    assert_eq!(
+        Some(json!("vault 111")),
+        get_attribute_value_for_component(ctx, counterpart_id, &["root", "domain", "name"])
+            .await
+            .expect("could not get name")
+    );
+    // The source is unchanged
+    assert_eq!(
+        Some(json!(10)),
+        get_attribute_value_for_component(ctx, dev_component.id(), &["root", "domain", "rads"])
+            .await
+            .expect("could not get rads")
+    );
+}
+
+#[test]
+async fn promote_component_errors(ctx: &mut DalContext) {
+    let dev_view = create_view_for_environment(ctx, "dev", Environment::Development).await;
+    let unassigned_view = View::new(ctx, "scratch")
+        .await
+        .expect("could not create view");
+
+    let dev_component =
+        create_component_for_default_schema_name(ctx, "fallout", "vault 76", dev_view.id())
+            .await
+            .expect("could not create component");
+    let unassigned_component =
+        create_component_for_default_schema_name(ctx, "fallout", "vault 76", unassigned_view.id())
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert!(matches!(
+        Promotion::new(Environment::Development)
+            .promote(ctx, dev_component.id())
+            .await,
+        Err(EnvironmentError::SameEnvironment(component_id, Environment::Development))
+            if component_id == dev_component.id()
+    ));
+    assert!(matches!(
+        Promotion::new(Environment::Staging)
+            .promote(ctx, dev_component.id())
+            .await,
+        Err(EnvironmentError::CounterpartNotFound(component_id, Environment::Staging))
+            if component_id == dev_component.id()
+    ));
+    assert!(matches!(
+        Promotion::new(Environment::Production)
+            .promote(ctx, unassigned_component.id())
+            .await,
+        Err(EnvironmentError::NoEnvironment(component_id))
+            if component_id == unassigned_component.id()
+    ));
+}
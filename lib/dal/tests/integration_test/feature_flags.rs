@@ -0,0 +1,57 @@
+use dal::feature_flags::{FeatureFlag, FeatureFlagState};
+use dal::DalContext;
+use dal_test::{color_eyre::Result, test};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn workspace_overrides(ctx: &mut DalContext) -> Result<()> {
+    // No flags are enabled at boot in tests, so every flag starts disabled
+    assert!(!FeatureFlag::ActionsV2.is_enabled(ctx).await?);
+    assert!(FeatureFlag::list_for_workspace(ctx)
+        .await?
+        .iter()
+        .all(|state| !state.enabled && state.override_enabled.is_none()));
+
+    // Enable a flag for the workspace
+    let state = FeatureFlag::ActionsV2
+        .set_workspace_override(ctx, Some(true))
+        .await?;
+    assert_eq!(
+        FeatureFlagState {
+            flag: FeatureFlag::ActionsV2,
+            default_enabled: false,
+            override_enabled: Some(true),
+            enabled: true,
+        },
+        state
+    );
+    assert!(FeatureFlag::ActionsV2.is_enabled(ctx).await?);
+    assert!(!FeatureFlag::Secrets.is_enabled(ctx).await?);
+    assert_eq!(
+        vec![FeatureFlag::ActionsV2],
+        FeatureFlag::list_for_workspace(ctx)
+            .await?
+            .into_iter()
+            .filter(|state| state.enabled)
+            .map(|state| state.flag)
+            .collect::<Vec<_>>()
+    );
+
+    // Disable it explicitly
+    FeatureFlag::ActionsV2
+        .set_workspace_override(ctx, Some(false))
+        .await?;
+    let state = FeatureFlag::ActionsV2.state(ctx).await?;
+    assert_eq!(Some(false), state.override_enabled);
+    assert!(!state.enabled);
+
+    // Go back to the default
+    FeatureFlag::ActionsV2
+        .set_workspace_override(ctx, None)
+        .await?;
+    let state = FeatureFlag::ActionsV2.state(ctx).await?;
+    assert_eq!(None, state.override_enabled);
+    assert!(!state.enabled);
+
+    Ok(())
+}
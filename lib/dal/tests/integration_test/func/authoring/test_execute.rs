@@ -1,4 +1,5 @@
-use dal::func::authoring::FuncAuthoringClient;
+use dal::func::authoring::{FuncAuthoringClient, FuncAuthoringError};
+use dal::func::runner::FuncRunnerError;
 use dal::{DalContext, Func};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
@@ -7,6 +8,7 @@ use dal_test::test;
 use si_events::{FuncRun, FuncRunId, FuncRunState};
 use std::sync::Arc;
 use std::time::Duration;
+use veritech_client::{FunctionResultFailureErrorKind, RuntimeVersion};
 
 #[test]
 async fn test_execute_action_func(ctx: &mut DalContext) {
@@ -220,6 +222,58 @@ async fn test_execute_with_modified_code(ctx: &mut DalContext) {
     );
 }
 
+#[test]
+async fn test_execute_rejects_func_requiring_newer_runtime(ctx: &mut DalContext) {
+    let component_name = "Jimmy";
+    let func_name = "test:falloutEntriesToGalaxies";
+    let func_args = serde_json::Value::Array(Vec::new());
+    let schema_name = "starfield";
+
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, schema_name, component_name)
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let old_func_id = Func::find_id_by_name(ctx, func_name)
+        .await
+        .expect("could not perform find func by name")
+        .expect("no func found");
+    let func_id = FuncAuthoringClient::create_unlocked_func_copy(ctx, old_func_id, None)
+        .await
+        .expect("could create new func")
+        .id;
+
+    // Require a lang server version that no cyclone can provide.
+    Func::modify_by_id(ctx, func_id, |func| {
+        func.min_runtime_version = Some(RuntimeVersion::new(999, 0, 0));
+        Ok(())
+    })
+    .await
+    .expect("could not modify func");
+    assert_eq!(
+        Some(RuntimeVersion::new(999, 0, 0)), // expected
+        Func::get_by_id_or_error(ctx, func_id)
+            .await
+            .expect("could not get func by id")
+            .min_runtime_version  // actual
+    );
+
+    // Perform the test execution, which must fail before any code runs.
+    let err = FuncAuthoringClient::test_execute_func(ctx, func_id, func_args, None, component.id())
+        .await
+        .expect_err("test execution should have been rejected");
+    assert!(matches!(
+        err,
+        FuncAuthoringError::FuncRunner(FuncRunnerError::ResultFailure {
+            kind: FunctionResultFailureErrorKind::UnsupportedRuntimeVersion,
+            ..
+        })
+    ));
+}
+
 async fn wait_for_func_run_with_success_state(ctx: &DalContext, func_run_id: FuncRunId) -> FuncRun {
     let seconds = 15;
 
@@ -1,14 +1,20 @@
 mod action;
+mod annotation;
+mod api_token;
 mod asset;
 mod attribute;
 mod audit_logging;
 mod change_set;
 mod component;
 mod connection;
+mod content_gc;
 mod cycle_check_guard;
+mod dead_letter;
 mod dependent_values_update;
 mod deserialize;
 mod diagram;
+mod environment;
+mod feature_flags;
 mod frame;
 mod func;
 mod input_sources;
@@ -19,11 +25,19 @@ mod pkg;
 mod prompt_overrides;
 mod prop;
 mod property_editor;
+mod provider_credential;
 mod qualifications;
 mod rebaser;
 mod resource_metadata;
+mod scheduled_job;
 mod schema;
+mod search;
 mod secret;
+mod share_link;
+mod snapshot_pruning;
+mod snapshot_subscription;
 mod validations;
 mod view;
 mod workspace;
+mod workspace_hooks;
+mod workspace_role;
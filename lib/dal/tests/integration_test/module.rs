@@ -1,10 +1,13 @@
 use chrono::Utc;
-use dal::module::Module;
+use dal::module::{Module, ModuleError};
 use dal::pkg::export::PkgExporter;
-use dal::{DalContext, Schema, SchemaVariant};
+use dal::{Component, DalContext, Schema, SchemaVariant};
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
+};
 use dal_test::test;
 use pretty_assertions_sorted::assert_eq;
-use si_pkg::{SocketSpecArity, SocketSpecKind};
+use si_pkg::{SiPkg, SocketSpecArity, SocketSpecKind};
 use ulid::Ulid;
 
 #[test]
@@ -283,3 +286,74 @@ async fn prepare_contribution_works(ctx: &DalContext) {
         actual_version              // actual
     );
 }
+
+#[test]
+async fn upgrade_migrates_components(ctx: &mut DalContext) {
+    let schema = Schema::find_by_name(ctx, "swifty")
+        .await
+        .expect("could not find by name")
+        .expect("schema not found");
+    let old_variant_id = schema
+        .get_default_schema_variant_id(ctx)
+        .await
+        .expect("unable to get a default variant")
+        .expect("error getting the default variant id");
+    let module = Module::find_for_member_id(ctx, schema.id())
+        .await
+        .expect("could not find module")
+        .expect("swifty was not installed from a module");
+
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "shake it off")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // Package the same variant up as a newer version of the module.
+    let (_, _, _, _, payload, _, _, _) =
+        Module::prepare_contribution(ctx, "swifty", "2", old_variant_id)
+            .await
+            .expect("could not prepare contribution");
+    let pkg = SiPkg::load_from_bytes(&payload).expect("could not load pkg");
+
+    // Upgrades are only allowed in a change set.
+    let head_ctx = ctx.clone_with_head().await.expect("could not get head");
+    assert!(matches!(
+        module.upgrade(&head_ctx, &pkg).await,
+        Err(ModuleError::UpgradeOnHead(_))
+    ));
+
+    let report = module
+        .upgrade(ctx, &pkg)
+        .await
+        .expect("could not upgrade module");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert!(report.module_id.is_some());
+    assert_eq!(1, report.schema_variants.len());
+    let upgrade = &report.schema_variants[0];
+    assert_eq!(old_variant_id, upgrade.from_schema_variant_id);
+    assert_ne!(old_variant_id, upgrade.to_schema_variant_id);
+    assert!(upgrade.added_props.is_empty());
+    assert!(upgrade.dropped_props.is_empty());
+    assert_eq!(vec![component.id()], upgrade.migrated_component_ids);
+    assert!(upgrade.skipped_component_ids.is_empty());
+
+    assert_eq!(
+        upgrade.to_schema_variant_id,
+        Component::schema_variant_id(ctx, component.id())
+            .await
+            .expect("could not get schema variant id")
+    );
+    assert_eq!(
+        Some(upgrade.to_schema_variant_id),
+        schema
+            .get_default_schema_variant_id(ctx)
+            .await
+            .expect("unable to get a default variant")
+    );
+}
@@ -1,9 +1,17 @@
+use dal::module::Module;
 use dal::pkg::export::PkgExporter;
-use dal::pkg::{import_pkg_from_pkg, ImportOptions};
+use dal::pkg::{import_pkg_from_pkg, ImportOptions, PkgError};
 use dal::schema::variant::authoring::VariantAuthoringClient;
-use dal::{DalContext, FuncBackendKind, FuncBackendResponseType};
+use dal::{
+    DalContext, Func, FuncBackendKind, FuncBackendResponseType, InputSocket, OutputSocket, Schema,
+    SchemaVariant,
+};
+use dal_test::helpers::ChangeSetTestHelpers;
 use dal_test::test;
-use si_pkg::{FuncSpec, FuncSpecData, PkgSpec, SchemaSpec, SchemaSpecData, SiPkg};
+use si_pkg::{
+    BindingSpec, BindingSpecKind, FuncSpec, FuncSpecData, PkgSpec, SchemaSpec, SchemaSpecData,
+    SiPkg, SiPkgKind, SocketSpec, SocketSpecArity, SocketSpecData, SocketSpecKind,
+};
 
 #[test]
 async fn import_pkg_from_pkg_set_latest_default(ctx: &mut DalContext) {
@@ -111,3 +119,229 @@ async fn import_pkg_from_pkg_set_latest_default(ctx: &mut DalContext) {
         Some(variants.pop().expect("should pop"))
     );
 }
+
+#[test]
+async fn import_pkg_from_pkg_deterministic_ids(ctx: &mut DalContext) {
+    let func_spec = FuncSpec::builder()
+        .name("deterministic")
+        .unique_id("deterministic")
+        .data(
+            FuncSpecData::builder()
+                .name("deterministic")
+                .backend_kind(FuncBackendKind::JsAttribute)
+                .response_type(FuncBackendResponseType::String)
+                .handler("main")
+                .code_plaintext("function main() { return 'hi'; }")
+                .build()
+                .expect("should build data"),
+        )
+        .build()
+        .expect("should build func spec");
+    let schema_spec = SchemaSpec::builder()
+        .name("deterministic")
+        .data(
+            SchemaSpecData::builder()
+                .name("deterministic")
+                .category("Integration Tests")
+                .build()
+                .expect("should build data"),
+        )
+        .build()
+        .expect("should build schema spec");
+    let pkg_spec = PkgSpec::builder()
+        .name("deterministic")
+        .created_by("sally@systeminit.com")
+        .func(func_spec)
+        .schema(schema_spec)
+        .version("0")
+        .build()
+        .expect("should build");
+    let pkg = SiPkg::load_from_spec(pkg_spec).expect("should load from spec");
+
+    let options = ImportOptions {
+        deterministic_ids: true,
+        ..Default::default()
+    };
+
+    // Import the package into two change sets which both start out without it
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+            .await
+            .expect("should fork change set");
+        import_pkg_from_pkg(ctx, &pkg, Some(options.clone()))
+            .await
+            .expect("should import");
+
+        let func_id = Func::find_id_by_name(ctx, "deterministic")
+            .await
+            .expect("should look up func")
+            .expect("func should exist");
+        let schema_id = Schema::find_by_name(ctx, "deterministic")
+            .await
+            .expect("should look up schema")
+            .expect("schema should exist")
+            .id();
+        ids.push((func_id, schema_id));
+    }
+
+    assert_eq!(ids[0], ids[1]);
+}
+
+#[test]
+async fn import_pkg_from_pkg_restores_bindings(ctx: &mut DalContext) {
+    let asset_name = "boundasset".to_string();
+    let category = "Integration Tests".to_string();
+    let variant = VariantAuthoringClient::create_schema_and_variant(
+        ctx,
+        asset_name.clone(),
+        None,
+        None,
+        category.clone(),
+        "#00b0b0".to_string(),
+    )
+    .await
+    .expect("Unable to create new asset");
+    let schema = variant
+        .schema(ctx)
+        .await
+        .expect("Unable to get the schema for the variant");
+
+    let (mut variant_spec, variant_funcs) =
+        PkgExporter::export_variant_standalone(ctx, &variant, schema.name(), None)
+            .await
+            .expect("should go to spec");
+
+    // An input socket whose annotations don't fit the dummy secret on their own
+    variant_spec.sockets.push(
+        SocketSpec::builder()
+            .name("Credential")
+            .data(
+                SocketSpecData::builder()
+                    .name("Credential")
+                    .connection_annotations(
+                        serde_json::to_string(&vec!["credential"]).expect("serialize"),
+                    )
+                    .kind(SocketSpecKind::Input)
+                    .arity(SocketSpecArity::One)
+                    .build()
+                    .expect("should build data"),
+            )
+            .build()
+            .expect("should build socket"),
+    );
+
+    let schema_spec = SchemaSpec::builder()
+        .name(schema.name())
+        .unique_id(schema.id())
+        .variant(variant_spec)
+        .data(
+            SchemaSpecData::builder()
+                .name(schema.name())
+                .category(category)
+                .default_schema_variant(variant.id())
+                .build()
+                .expect("should build data"),
+        )
+        .build()
+        .expect("should build spec");
+
+    let pkg_spec = PkgSpec::builder()
+        .name(asset_name.clone())
+        .created_by("sally@systeminit.com")
+        .funcs(variant_funcs)
+        .schema(schema_spec)
+        .binding(
+            BindingSpec::builder()
+                .kind(BindingSpecKind::SecretDefinition)
+                .schema_name(asset_name.clone())
+                .input_socket_name("Credential")
+                .source_schema_name("dummy-secret")
+                .source_output_socket_name("dummy")
+                .build()
+                .expect("should build binding"),
+        )
+        .binding(
+            BindingSpec::builder()
+                .kind(BindingSpecKind::SocketAnnotation)
+                .schema_name(asset_name.clone())
+                .input_socket_name("Credential")
+                .source_schema_name("not in this workspace")
+                .source_output_socket_name("nope")
+                .build()
+                .expect("should build binding"),
+        )
+        .version("0")
+        .build()
+        .expect("should build");
+    let pkg = SiPkg::load_from_spec(pkg_spec).expect("should load from spec");
+
+    let (_, mut variants, _) = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions {
+            schema_id: Some(schema.id().into()),
+            ..Default::default()
+        }),
+    )
+    .await
+    .expect("should import");
+    let imported_variant_id = variants.pop().expect("should have imported a variant");
+
+    // The secret definition exists here, so its output socket now fits the input socket
+    let input_socket = InputSocket::find_with_name_or_error(ctx, "Credential", imported_variant_id)
+        .await
+        .expect("should find input socket");
+    let dummy_secret_variant_id = SchemaVariant::get_default_id_for_schema(
+        ctx,
+        Schema::find_by_name(ctx, "dummy-secret")
+            .await
+            .expect("should look up schema")
+            .expect("schema should exist")
+            .id(),
+    )
+    .await
+    .expect("should get default variant");
+    let output_socket =
+        OutputSocket::find_with_name_or_error(ctx, "dummy", dummy_secret_variant_id)
+            .await
+            .expect("should find output socket");
+    assert!(output_socket.fits_input(&input_socket));
+
+    // Exporting the schema again records the binding
+    let mut exporter = PkgExporter::new_for_module_contribution(
+        asset_name.clone(),
+        "1",
+        "sally@systeminit.com",
+        schema.id(),
+    );
+    let exported = exporter.export_as_spec(ctx).await.expect("should export");
+    assert!(exported.bindings.iter().any(|binding| {
+        binding.kind == BindingSpecKind::SecretDefinition
+            && binding.schema_name == asset_name
+            && binding.input_socket_name == "Credential"
+            && binding.source_schema_name == "dummy-secret"
+            && binding.source_output_socket_name == "dummy"
+    }));
+}
+
+#[test]
+async fn import_pkg_from_pkg_rejects_delta(ctx: &mut DalContext) {
+    let pkg_spec = PkgSpec::builder()
+        .kind(SiPkgKind::Delta)
+        .name("delta")
+        .created_by("sally@systeminit.com")
+        .version("0")
+        .build()
+        .expect("should build");
+    let pkg = SiPkg::load_from_spec(pkg_spec).expect("should load from spec");
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None).await;
+    assert!(matches!(result, Err(PkgError::DeltaNotInstallable(name)) if name == "delta"));
+
+    let root_hash = pkg.hash().expect("should hash").to_string();
+    assert!(Module::find_by_root_hash(ctx, &root_hash)
+        .await
+        .expect("should find by root hash")
+        .is_none());
+}
@@ -0,0 +1,294 @@
+use base64::{engine::general_purpose, Engine};
+use chrono::{Duration, Utc};
+use dal::provider_credential::{
+    DerivedProviderCredentialMaterial, ProviderCredential, ProviderCredentialConsent,
+    ProviderCredentialError, ProviderCredentialKind, ProviderCredentialMaterial,
+    MAX_DERIVED_CREDENTIAL_TTL,
+};
+use dal::{
+    DalContext, Func, FuncBackendKind, FuncBackendResponseType, HistoryActor, RequestContext,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use si_crypto::{SymmetricCryptoService, SymmetricKey};
+
+async fn management_func(ctx: &DalContext, name: &str) -> Func {
+    Func::new(
+        ctx,
+        name,
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        false,
+        false,
+        FuncBackendKind::Management,
+        FuncBackendResponseType::Management,
+        Some("main"),
+        Some(general_purpose::STANDARD_NO_PAD.encode("async function main() { return {}; }")),
+    )
+    .await
+    .expect("could not create func")
+}
+
+/// Builds a context for the workspace of the provided one, which encrypts with the given keys.
+async fn ctx_with_keys(
+    ctx: &DalContext,
+    active_key: SymmetricKey,
+    extra_keys: Vec<SymmetricKey>,
+) -> DalContext {
+    ctx.services_context()
+        .with_symmetric_crypto_service(SymmetricCryptoService::new(active_key, extra_keys))
+        .into_builder(false)
+        .build(RequestContext {
+            tenancy: *ctx.tenancy(),
+            visibility: *ctx.visibility(),
+            history_actor: HistoryActor::SystemInit,
+        })
+        .await
+        .expect("could not build context")
+}
+
+#[test]
+async fn consent_gates_derived_credentials(ctx: &DalContext) {
+    let material = ProviderCredentialMaterial::Token {
+        token: "t0ken".to_string(),
+    };
+    let credential = ProviderCredential::new(
+        ctx,
+        "ci token",
+        &material,
+        Some(Utc::now() + Duration::days(30)),
+    )
+    .await
+    .expect("could not create provider credential");
+    assert_eq!(ProviderCredentialKind::Token, credential.kind());
+    assert_eq!(
+        material,
+        credential
+            .material(ctx)
+            .expect("could not decrypt material")
+    );
+
+    let func = management_func(ctx, "import").await;
+    let derived = ProviderCredential::derive_for_func(ctx, &func, Duration::hours(12))
+        .await
+        .expect("could not derive credentials");
+    assert!(derived.is_empty());
+
+    let consent = ProviderCredentialConsent::grant(ctx, &credential, &func)
+        .await
+        .expect("could not grant consent");
+    let derived = ProviderCredential::derive_for_func(ctx, &func, Duration::hours(12))
+        .await
+        .expect("could not derive credentials");
+    assert_eq!(1, derived.len());
+    assert_eq!(credential.id(), derived[0].credential_id());
+    assert_eq!(
+        &DerivedProviderCredentialMaterial::Token {
+            token: "t0ken".to_string()
+        },
+        derived[0].material()
+    );
+    assert!(derived[0].expires_at() <= Utc::now() + MAX_DERIVED_CREDENTIAL_TTL);
+
+    // Other funcs were not granted the credential
+    let other_func = management_func(ctx, "other").await;
+    let derived = ProviderCredential::derive_for_func(ctx, &other_func, Duration::hours(1))
+        .await
+        .expect("could not derive credentials");
+    assert!(derived.is_empty());
+
+    consent.revoke(ctx).await.expect("could not revoke consent");
+    let derived = ProviderCredential::derive_for_func(ctx, &func, Duration::hours(1))
+        .await
+        .expect("could not derive credentials");
+    assert!(derived.is_empty());
+}
+
+#[test]
+async fn editing_func_code_voids_consent(ctx: &DalContext) {
+    let credential = ProviderCredential::new(
+        ctx,
+        "ci token",
+        &ProviderCredentialMaterial::Token {
+            token: "t0ken".to_string(),
+        },
+        Some(Utc::now() + Duration::days(30)),
+    )
+    .await
+    .expect("could not create provider credential");
+    let func = management_func(ctx, "import").await;
+    ProviderCredentialConsent::grant(ctx, &credential, &func)
+        .await
+        .expect("could not grant consent");
+
+    let func = Func::modify_by_id(ctx, func.id, |func| {
+        func.code_base64 = Some(
+            general_purpose::STANDARD_NO_PAD
+                .encode("async function main() { return { exfiltrate: true }; }"),
+        );
+        Ok(())
+    })
+    .await
+    .expect("could not edit func code");
+    let derived = ProviderCredential::derive_for_func(ctx, &func, Duration::hours(1))
+        .await
+        .expect("could not derive credentials");
+    assert!(derived.is_empty());
+
+    // The edited code needs its own consent
+    ProviderCredentialConsent::grant(ctx, &credential, &func)
+        .await
+        .expect("could not grant consent");
+    let derived = ProviderCredential::derive_for_func(ctx, &func, Duration::hours(1))
+        .await
+        .expect("could not derive credentials");
+    assert_eq!(1, derived.len());
+}
+
+#[test]
+async fn expiry_is_enforced_at_issue(ctx: &DalContext) {
+    let material = ProviderCredentialMaterial::Token {
+        token: "t0ken".to_string(),
+    };
+
+    // Bearer tokens can't be minted from, so they can't be stored without an expiry
+    assert!(matches!(
+        ProviderCredential::new(ctx, "forever", &material, None).await,
+        Err(ProviderCredentialError::ExpiryRequired(
+            ProviderCredentialKind::Token
+        ))
+    ));
+    assert!(matches!(
+        ProviderCredential::new(
+            ctx,
+            "stale",
+            &material,
+            Some(Utc::now() - Duration::minutes(1))
+        )
+        .await,
+        Err(ProviderCredentialError::ExpiryInPast)
+    ));
+
+    let credential = ProviderCredential::new(
+        ctx,
+        "short lived",
+        &material,
+        Some(Utc::now() + Duration::minutes(5)),
+    )
+    .await
+    .expect("could not create provider credential");
+    let func = management_func(ctx, "import").await;
+    ProviderCredentialConsent::grant(ctx, &credential, &func)
+        .await
+        .expect("could not grant consent");
+
+    // Derived credentials never outlive the stored ones
+    let derived = ProviderCredential::derive_for_func(ctx, &func, Duration::hours(1))
+        .await
+        .expect("could not derive credentials");
+    assert_eq!(1, derived.len());
+    assert_eq!(credential.expires_at(), Some(derived[0].expires_at()));
+
+    assert!(matches!(
+        ProviderCredential::derive_for_func(ctx, &func, Duration::zero()).await,
+        Err(ProviderCredentialError::InvalidTtl)
+    ));
+}
+
+#[test]
+async fn revoking_credential_revokes_consents(ctx: &DalContext) {
+    let credential = ProviderCredential::new(
+        ctx,
+        "ci token",
+        &ProviderCredentialMaterial::Token {
+            token: "t0ken".to_string(),
+        },
+        Some(Utc::now() + Duration::days(30)),
+    )
+    .await
+    .expect("could not create provider credential");
+    let func = management_func(ctx, "import").await;
+    ProviderCredentialConsent::grant(ctx, &credential, &func)
+        .await
+        .expect("could not grant consent");
+
+    let revoked = credential
+        .revoke(ctx)
+        .await
+        .expect("could not revoke credential");
+    assert!(!revoked.is_active());
+    assert!(
+        ProviderCredentialConsent::list_for_credential(ctx, revoked.id())
+            .await
+            .expect("could not list consents")
+            .is_empty()
+    );
+    assert!(ProviderCredential::list_for_workspace(ctx)
+        .await
+        .expect("could not list credentials")
+        .is_empty());
+    assert!(ProviderCredentialConsent::grant(ctx, &revoked, &func)
+        .await
+        .is_err());
+}
+
+#[test]
+async fn materials_are_reencrypted_after_key_rotation(ctx: &DalContext) {
+    let old_key = SymmetricCryptoService::generate_key();
+    let new_key = SymmetricCryptoService::generate_key();
+    let material = ProviderCredentialMaterial::Token {
+        token: "t0ken".to_string(),
+    };
+
+    let old_ctx = ctx_with_keys(ctx, old_key.clone(), vec![]).await;
+    let credential = ProviderCredential::new(
+        &old_ctx,
+        "rotated token",
+        &material,
+        Some(Utc::now() + Duration::days(30)),
+    )
+    .await
+    .expect("could not create provider credential");
+    old_ctx.commit_no_rebase().await.expect("could not commit");
+
+    // Both keys are loaded while the material is re-encrypted
+    let rotating_ctx = ctx_with_keys(ctx, new_key.clone(), vec![old_key]).await;
+    assert_eq!(
+        1,
+        ProviderCredential::reencrypt_materials_for_workspace(
+            &rotating_ctx,
+            10,
+            std::time::Duration::ZERO
+        )
+        .await
+        .expect("could not re-encrypt materials")
+    );
+    assert_eq!(
+        0,
+        ProviderCredential::reencrypt_materials_for_workspace(
+            &rotating_ctx,
+            10,
+            std::time::Duration::ZERO
+        )
+        .await
+        .expect("could not re-encrypt materials")
+    );
+
+    // After which the old key can be retired
+    let new_ctx = ctx_with_keys(ctx, new_key, vec![]).await;
+    let credential = ProviderCredential::get_by_id(
+        &new_ctx,
+        new_ctx.workspace_pk().expect("could not get workspace pk"),
+        credential.id(),
+    )
+    .await
+    .expect("could not get provider credential")
+    .expect("provider credential not found");
+    assert_eq!(
+        material,
+        credential
+            .material(&new_ctx)
+            .expect("could not decrypt material with the new key")
+    );
+}
@@ -1,11 +1,16 @@
 use base64::{engine::general_purpose, Engine};
+use dal::context::RetryPolicy;
 use dal::func::argument::{FuncArgument, FuncArgumentKind};
-use dal::{AttributeValue, Component, DalContext, Func, FuncBackendKind, FuncBackendResponseType};
+use dal::{
+    AttributeValue, Component, DalContext, Func, FuncBackendKind, FuncBackendResponseType,
+    TransactionsError,
+};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
 };
 use dal_test::test;
 use pretty_assertions_sorted::assert_eq;
+use si_events::rebase_batch_address::RebaseBatchAddress;
 
 #[test]
 async fn modify_func_node(ctx: &mut DalContext) {
@@ -354,3 +359,67 @@ async fn correctly_detect_unrelated_unmodified_data(ctx: &mut DalContext) -> dal
 
     Ok(())
 }
+
+#[test]
+async fn run_with_retries_retries_conflicts(ctx: &DalContext) {
+    let change_set_id = ctx.change_set_id();
+    let mut attempts = 0;
+    let succeeded_on = ctx
+        .run_with_retries(|_ctx| {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                if attempt < 3 {
+                    Err(TransactionsError::RebaseFailed(
+                        RebaseBatchAddress::new(b"conflict"),
+                        change_set_id,
+                        "conflict".to_string(),
+                    ))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await
+        .expect("should succeed once the conflicts stop");
+
+    assert_eq!(3, succeeded_on);
+}
+
+#[test]
+async fn run_with_retries_gives_up(ctx: &DalContext) {
+    let change_set_id = ctx.change_set_id();
+    let mut attempts = 0;
+    let result: Result<(), _> = ctx
+        .run_with_retry_policy(
+            RetryPolicy {
+                max_attempts: 2,
+                ..Default::default()
+            },
+            |_ctx| {
+                attempts += 1;
+                async move {
+                    Err(TransactionsError::RebaseFailed(
+                        RebaseBatchAddress::new(b"conflict"),
+                        change_set_id,
+                        "conflict".to_string(),
+                    ))
+                }
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(TransactionsError::RebaseFailed(..))));
+    assert_eq!(2, attempts);
+
+    attempts = 0;
+    let result: Result<(), _> = ctx
+        .run_with_retries(|_ctx| {
+            attempts += 1;
+            async move { Err(TransactionsError::ChangeSetNotSet) }
+        })
+        .await;
+
+    assert!(matches!(result, Err(TransactionsError::ChangeSetNotSet)));
+    assert_eq!(1, attempts);
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use dal::job::{consumer::JobInfo, definition::DependentValuesUpdate};
+use dal::DalContext;
+use dal_test::test;
+use pinga_core::{pinga_scheduled_queue, subject::scheduled_job};
+use pretty_assertions_sorted::assert_eq;
+use si_data_nats::jetstream;
+
+#[test]
+async fn scheduled_jobs_wait_in_their_own_stream_until_due(ctx: &DalContext) {
+    let run_at = Utc::now() + chrono::Duration::seconds(2);
+    ctx.enqueue_job_at(
+        DependentValuesUpdate::new(ctx.access_builder(), *ctx.visibility()),
+        run_at,
+    )
+    .await
+    .expect("could not enqueue scheduled job");
+    ctx.commit().await.expect("could not commit");
+
+    let prefix = ctx
+        .nats_conn()
+        .metadata()
+        .subject_prefix()
+        .map(ToOwned::to_owned);
+    let stream = pinga_scheduled_queue(&jetstream::new(ctx.nats_conn().clone()), prefix.as_deref())
+        .await
+        .expect("could not get scheduled jobs stream");
+    let subject = scheduled_job(
+        prefix.as_deref(),
+        &ctx.workspace_pk()
+            .expect("could not get workspace pk")
+            .to_string(),
+        &ctx.change_set_id().to_string(),
+        "DependentValuesUpdate",
+    );
+
+    // The job waits in the scheduled jobs stream rather than the work queue
+    let message = stream
+        .direct_get_last_for_subject(subject.as_str())
+        .await
+        .expect("scheduled job is not in the scheduled jobs stream");
+    let job_info: JobInfo =
+        serde_json::from_slice(&message.payload).expect("could not deserialize job info");
+    assert_eq!(Some(run_at), job_info.run_at);
+
+    // Once due, pinga moves it to the work queue
+    let mut attempts = 0;
+    while stream
+        .direct_get_last_for_subject(subject.as_str())
+        .await
+        .is_ok()
+    {
+        attempts += 1;
+        assert!(attempts < 100, "scheduled job was not moved once due");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
@@ -1,8 +1,10 @@
+use dal::prop::PropPath;
 use dal::schema::variant::root_prop::RootPropChild;
 use dal::{
     schema::{variant::leaves::LeafKind, SchemaVariant},
-    ComponentType, DalContext, Func, Prop, Schema,
+    ComponentType, DalContext, Func, Prop, PropKind, Schema,
 };
+use dal_test::helpers::ChangeSetTestHelpers;
 use dal_test::{helpers::create_schema, test};
 use pretty_assertions_sorted::assert_eq;
 
@@ -251,6 +253,67 @@ async fn list_user_facing_works(ctx: &DalContext) {
         .expect("could not list user facing schema variants");
 }
 
+#[test]
+async fn list_user_facing_uses_materialized_views(ctx: &mut DalContext) {
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let schema = Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("could not perform find by name")
+        .expect("schema not found");
+    let schema_variant_id = Schema::get_default_schema_variant_by_id_or_error(ctx, schema.id())
+        .await
+        .expect("could not get default schema variant id");
+    let find_view = |views: Vec<si_frontend_types::SchemaVariant>| {
+        views
+            .into_iter()
+            .find(|view| view.schema_variant_id == schema_variant_id)
+            .expect("schema variant not listed")
+    };
+
+    // The first list materializes the view, the second one reads it back.
+    let materialized = find_view(
+        SchemaVariant::list_user_facing(ctx)
+            .await
+            .expect("could not list user facing schema variants"),
+    );
+    let cached = find_view(
+        SchemaVariant::list_user_facing(ctx)
+            .await
+            .expect("could not list user facing schema variants"),
+    );
+    let rendered = SchemaVariant::get_by_id_or_error(ctx, schema_variant_id)
+        .await
+        .expect("could not get schema variant")
+        .into_frontend_type(ctx, schema.id())
+        .await
+        .expect("could not render schema variant");
+    assert_eq!(rendered, materialized);
+    assert_eq!(rendered, cached);
+
+    // Mutating the variant must not serve the stale view.
+    let domain_prop_id =
+        Prop::find_prop_id_by_path(ctx, schema_variant_id, &PropPath::new(["root", "domain"]))
+            .await
+            .expect("could not find domain prop");
+    Prop::new_without_ui_optionals(ctx, "materialized", PropKind::String, domain_prop_id)
+        .await
+        .expect("could not create prop");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let updated = find_view(
+        SchemaVariant::list_user_facing(ctx)
+            .await
+            .expect("could not list user facing schema variants"),
+    );
+    assert!(updated.props.iter().any(|prop| prop.name == "materialized"));
+    assert_eq!(materialized.props.len() + 1, updated.props.len());
+}
+
 fn prepare_for_assertion(expected: &[&str], all_funcs: &[Func]) -> (Vec<String>, Vec<String>) {
     let expected = expected.iter().map(|s| s.to_string()).collect();
 
@@ -3,5 +3,7 @@ mod create_variant;
 mod delete_unlocked_variant;
 mod regenerate;
 mod save_variant;
+mod traits;
 mod unlock_and_edit_variant;
+mod update_metadata;
 mod update_variant;
@@ -0,0 +1,168 @@
+use dal::prop::PropPath;
+use dal::schema::variant::authoring::VariantAuthoringClient;
+use dal::schema::variant::variant_trait::{SchemaVariantTrait, SchemaVariantTraitFragment};
+use dal::{DalContext, Prop, SchemaVariant};
+use dal_test::helpers::ChangeSetTestHelpers;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+#[test]
+async fn include_and_propagate_trait(ctx: &mut DalContext) {
+    let fragment: SchemaVariantTraitFragment = serde_json::from_value(json!({
+        "props": [{ "name": "region", "kind": "string" }],
+    }))
+    .expect("could not deserialize fragment");
+    SchemaVariantTrait::upsert(ctx, "networking", fragment)
+        .await
+        .expect("could not create trait");
+
+    let asset_func = "function main() {
+        const asset = new AssetBuilder();
+        asset.addProp(new PropBuilder().setName(\"name\").setKind(\"string\").build());
+        asset.addTrait(\"networking\");
+        return asset.build();
+    }";
+    let schema_variant = VariantAuthoringClient::create_schema_and_variant_from_code(
+        ctx,
+        "Max Verstappen",
+        None,
+        None,
+        "Red Bull",
+        "#1E41FF",
+        asset_func,
+    )
+    .await
+    .expect("could not create schema and variant");
+    let schema_id = schema_variant
+        .schema_id(ctx)
+        .await
+        .expect("could not get schema id");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    // The trait's props are merged into the asset and the link is recorded
+    Prop::find_prop_id_by_path(
+        ctx,
+        schema_variant.id(),
+        &PropPath::new(["root", "domain", "region"]),
+    )
+    .await
+    .expect("trait prop not found");
+    assert_eq!(
+        vec![schema_id],
+        SchemaVariantTrait::list_consuming_schema_ids(ctx, "networking")
+            .await
+            .expect("could not list consuming schemas")
+    );
+
+    // Updating the trait and propagating it regenerates the consuming variant
+    let fragment: SchemaVariantTraitFragment = serde_json::from_value(json!({
+        "props": [
+            { "name": "region", "kind": "string" },
+            { "name": "availabilityZone", "kind": "string" },
+        ],
+    }))
+    .expect("could not deserialize fragment");
+    SchemaVariantTrait::upsert(ctx, "networking", fragment)
+        .await
+        .expect("could not update trait");
+
+    let regenerated = VariantAuthoringClient::propagate_trait(ctx, "networking")
+        .await
+        .expect("could not propagate trait");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    assert_eq!(1, regenerated.len());
+    let regenerated_id = regenerated[0];
+    assert_eq!(
+        schema_id,
+        SchemaVariant::get_by_id_or_error(ctx, regenerated_id)
+            .await
+            .expect("could not get variant")
+            .schema_id(ctx)
+            .await
+            .expect("could not get schema id")
+    );
+    Prop::find_prop_id_by_path(
+        ctx,
+        regenerated_id,
+        &PropPath::new(["root", "domain", "availabilityZone"]),
+    )
+    .await
+    .expect("propagated trait prop not found");
+
+    // Props defined by the asset itself cannot be redefined by a trait
+    let fragment: SchemaVariantTraitFragment = serde_json::from_value(json!({
+        "props": [{ "name": "name", "kind": "string" }],
+    }))
+    .expect("could not deserialize fragment");
+    SchemaVariantTrait::upsert(ctx, "networking", fragment)
+        .await
+        .expect("could not update trait");
+    assert!(VariantAuthoringClient::propagate_trait(ctx, "networking")
+        .await
+        .is_err());
+}
+
+#[test]
+async fn traits_are_scoped_to_change_sets(ctx: &mut DalContext) {
+    let fragment: SchemaVariantTraitFragment = serde_json::from_value(json!({
+        "props": [{ "name": "size", "kind": "integer" }],
+    }))
+    .expect("could not deserialize fragment");
+    SchemaVariantTrait::upsert(ctx, "storage", fragment.clone())
+        .await
+        .expect("could not create trait");
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set");
+    assert!(SchemaVariantTrait::get_by_name(ctx, "storage")
+        .await
+        .expect("could not get trait")
+        .is_some());
+
+    // A deletion in one change set does not affect the others until it is applied
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+    SchemaVariantTrait::get_by_name(ctx, "storage")
+        .await
+        .expect("could not get trait")
+        .expect("trait not found")
+        .delete(ctx)
+        .await
+        .expect("could not delete trait");
+    assert!(SchemaVariantTrait::get_by_name(ctx, "storage")
+        .await
+        .expect("could not get trait")
+        .is_none());
+
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+    assert_eq!(
+        vec!["storage".to_string()],
+        SchemaVariantTrait::list(ctx)
+            .await
+            .expect("could not list traits")
+            .into_iter()
+            .map(|schema_variant_trait| schema_variant_trait.name().to_owned())
+            .collect::<Vec<_>>()
+    );
+
+    // Neither does a creation
+    SchemaVariantTrait::upsert(ctx, "compute", fragment)
+        .await
+        .expect("could not create trait");
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+    assert!(SchemaVariantTrait::get_by_name(ctx, "compute")
+        .await
+        .expect("could not get trait")
+        .is_none());
+}
@@ -0,0 +1,116 @@
+use dal::schema::variant::authoring::VariantAuthoringClient;
+use dal::schema::variant::SchemaVariantMetadataUpdate;
+use dal::{ComponentType, DalContext, Func, SchemaVariant, SchemaVariantError};
+use dal_test::helpers::ChangeSetTestHelpers;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn update_metadata_without_regenerating(ctx: &mut DalContext) {
+    let variant = VariantAuthoringClient::create_schema_and_variant(
+        ctx,
+        "metadataAsset",
+        Some("original description".to_string()),
+        None,
+        "Integration Tests",
+        "#00b0b0",
+    )
+    .await
+    .expect("unable to create new asset");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    let updated = SchemaVariant::update_metadata(
+        ctx,
+        variant.id(),
+        SchemaVariantMetadataUpdate {
+            category: Some("Updated Category".to_string()),
+            color: Some("#ff0000".to_string()),
+            component_type: Some(ComponentType::ConfigurationFrameDown),
+            description: Some(None),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("unable to update metadata");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    assert_eq!(variant.id(), updated.id());
+    assert_eq!("Updated Category", updated.category());
+    assert_eq!("#ff0000", updated.color());
+    assert_eq!(
+        "#ff0000",
+        updated.get_color(ctx).await.expect("unable to get color")
+    );
+    assert_eq!(
+        Some(ComponentType::ConfigurationFrameDown),
+        updated.get_type(ctx).await.expect("unable to get type")
+    );
+    assert_eq!(None, updated.description());
+    // Untouched metadata is left alone
+    assert_eq!(variant.display_name(), updated.display_name());
+
+    let asset_func = Func::get_by_id_or_error(
+        ctx,
+        updated
+            .asset_func_id()
+            .expect("unable to get asset func id from variant"),
+    )
+    .await
+    .expect("unable to get asset func");
+    assert_eq!(None, asset_func.description);
+
+    // Regenerating keeps the updated metadata
+    let regenerated_id = VariantAuthoringClient::regenerate_variant(ctx, updated.id())
+        .await
+        .expect("unable to regenerate variant");
+    let regenerated = SchemaVariant::get_by_id_or_error(ctx, regenerated_id)
+        .await
+        .expect("unable to get regenerated variant");
+    assert_eq!("Updated Category", regenerated.category());
+    assert_eq!(
+        "#ff0000",
+        regenerated
+            .get_color(ctx)
+            .await
+            .expect("unable to get color")
+    );
+    assert_eq!(
+        ComponentType::ConfigurationFrameDown,
+        regenerated.component_type()
+    );
+}
+
+#[test]
+async fn update_metadata_of_locked_variant(ctx: &mut DalContext) {
+    let variant = VariantAuthoringClient::create_schema_and_variant(
+        ctx,
+        "lockedMetadataAsset",
+        None,
+        None,
+        "Integration Tests",
+        "#00b0b0",
+    )
+    .await
+    .expect("unable to create new asset")
+    .lock(ctx)
+    .await
+    .expect("unable to lock variant");
+
+    let result = SchemaVariant::update_metadata(
+        ctx,
+        variant.id(),
+        SchemaVariantMetadataUpdate {
+            category: Some("Updated Category".to_string()),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(SchemaVariantError::SchemaVariantLocked(id)) if id == variant.id()
+    ));
+}
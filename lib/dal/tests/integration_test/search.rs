@@ -0,0 +1,101 @@
+use dal::search::{search, SearchIndex, SearchResultKind};
+use dal::DalContext;
+use dal_test::helpers::create_component_for_default_schema_name_in_default_view;
+use dal_test::helpers::ChangeSetTestHelpers;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn search_components_and_schemas(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "shake it off")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let index = SearchIndex::for_snapshot(ctx)
+        .await
+        .expect("could not get search index");
+
+    let hits = index.search("shake off", 10);
+    let hit = hits.first().expect("no hits");
+    assert_eq!(SearchResultKind::Component, hit.entry.kind);
+    assert_eq!(component.id().to_string(), hit.entry.id.to_string());
+    assert_eq!(Some("swifty"), hit.entry.detail.as_deref());
+
+    // Typos in longer terms are tolerated
+    let hits = index.search("swity", 10);
+    assert!(hits
+        .iter()
+        .any(|hit| hit.entry.kind == SearchResultKind::Schema && hit.entry.name == "swifty"));
+
+    // Every term must match
+    assert!(index.search("shake nothing", 10).is_empty());
+
+    // The index is stored for the snapshot, so it is reused
+    let reread = SearchIndex::for_snapshot(ctx)
+        .await
+        .expect("could not get search index");
+    assert_eq!(index.search("shake", 10), reread.search("shake", 10));
+}
+
+#[test]
+async fn search_values_and_func_code(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "ami-0abc123def")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // The swifty domain name is copied from the component name
+    let hits = search(
+        ctx,
+        "ami-0abc123def",
+        &[SearchResultKind::AttributeValue],
+        10,
+    )
+    .await
+    .expect("could not search");
+    let hit = hits.first().expect("no hits");
+    assert_eq!(SearchResultKind::AttributeValue, hit.entry.kind);
+    assert_eq!(
+        Some(component.id().to_string()),
+        hit.entry.parent_id.map(|id| id.to_string())
+    );
+    assert!(hit
+        .entry
+        .path
+        .as_deref()
+        .is_some_and(|path| path.ends_with("domain/name")));
+
+    // Without a kind filter, the component itself ranks first
+    let hits = search(ctx, "ami-0abc123def", &[], 10)
+        .await
+        .expect("could not search");
+    assert_eq!(
+        Some(SearchResultKind::Component),
+        hits.first().map(|hit| hit.entry.kind)
+    );
+
+    let hits = search(ctx, "name", &[SearchResultKind::Prop], 100)
+        .await
+        .expect("could not search");
+    assert!(hits
+        .iter()
+        .all(|hit| hit.entry.kind == SearchResultKind::Prop));
+    assert!(hits
+        .iter()
+        .any(|hit| hit.entry.detail.as_deref() == Some("swifty")));
+
+    // Funcs match on their code
+    let hits = search(ctx, "this cannot fail", &[SearchResultKind::Func], 100)
+        .await
+        .expect("could not search");
+    assert!(hits
+        .iter()
+        .any(|hit| hit.entry.name == "test:swiftyQualification"));
+}
@@ -0,0 +1,51 @@
+use chrono::{Duration, Utc};
+use dal::share_link::ShareLink;
+use dal::{DalContext, HistoryActor};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn create_list_and_revoke(ctx: &DalContext) {
+    let (link, secret) = ShareLink::new(
+        ctx,
+        ctx.change_set_id(),
+        None,
+        Utc::now() + Duration::days(1),
+    )
+    .await
+    .expect("could not create share link");
+    let HistoryActor::User(user_pk) = ctx.history_actor() else {
+        panic!("test context should have a user history actor");
+    };
+    assert_eq!(*user_pk, link.created_by_user_pk());
+    assert_eq!(ctx.change_set_id(), link.change_set_id());
+    assert!(link.is_active());
+    assert!(link.verify_secret(&secret));
+
+    let (expired, expired_secret) = ShareLink::new(
+        ctx,
+        ctx.change_set_id(),
+        None,
+        Utc::now() - Duration::hours(1),
+    )
+    .await
+    .expect("could not create share link");
+    assert!(!expired.is_active());
+    assert_ne!(secret, expired_secret);
+    assert!(!link.verify_secret(&expired_secret));
+    assert!(!expired.verify_secret(&secret));
+
+    let links = ShareLink::list_for_workspace(ctx)
+        .await
+        .expect("could not list share links");
+    assert_eq!(vec![link.clone(), expired], links);
+
+    let revoked = link.revoke(ctx).await.expect("could not revoke share link");
+    assert!(!revoked.is_active());
+
+    let fetched = ShareLink::get_by_id_for_any_workspace(ctx, revoked.id())
+        .await
+        .expect("could not get share link")
+        .expect("share link not found");
+    assert_eq!(revoked, fetched);
+}
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use dal::{
+    snapshot_pruning::{prune_snapshots, SnapshotPruningOptions},
+    ChangeSet, DalContext, WorkspaceSnapshotAddress,
+};
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+async fn snapshot_is_stored(ctx: &DalContext, address: WorkspaceSnapshotAddress) -> bool {
+    ctx.layer_db()
+        .workspace_snapshot()
+        .cache
+        .pg()
+        .contains_key(&address.to_string())
+        .await
+        .expect("could not check for snapshot")
+}
+
+#[test]
+async fn prunes_snapshots_of_abandoned_change_sets(ctx: &mut DalContext) {
+    let head_change_set_id = ctx
+        .get_workspace_default_change_set_id()
+        .await
+        .expect("could not get default change set id");
+    let head_address = ChangeSet::find(ctx, head_change_set_id)
+        .await
+        .expect("could not find head change set")
+        .expect("head change set not found")
+        .workspace_snapshot_address;
+
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+    create_component_for_default_schema_name_in_default_view(ctx, "swifty", "abandoned")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    let abandoned_address = ChangeSet::find(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set")
+        .expect("change set not found")
+        .workspace_snapshot_address;
+    ChangeSetTestHelpers::abandon_change_set(ctx)
+        .await
+        .expect("could not abandon change set");
+
+    // Make everything old enough to be pruned
+    ctx.txns()
+        .await
+        .expect("could not get transactions")
+        .pg()
+        .query(
+            "UPDATE change_set_pointers SET updated_at = CLOCK_TIMESTAMP() - INTERVAL '2 days'",
+            &[],
+        )
+        .await
+        .expect("could not backdate change sets");
+    ctx.layer_db()
+        .pg_pool()
+        .get()
+        .await
+        .expect("could not get layer db connection")
+        .query(
+            "UPDATE workspace_snapshots SET created_at = CLOCK_TIMESTAMP() - INTERVAL '2 days'",
+            &[],
+        )
+        .await
+        .expect("could not backdate snapshots");
+
+    let options = SnapshotPruningOptions {
+        min_age: Duration::from_secs(24 * 60 * 60),
+        ..Default::default()
+    };
+    let report = prune_snapshots(ctx, options)
+        .await
+        .expect("could not run dry run");
+    assert!(report.pruned >= 1);
+    assert!(snapshot_is_stored(ctx, abandoned_address).await);
+
+    let dry_run_pruned = report.pruned;
+    let report = prune_snapshots(
+        ctx,
+        SnapshotPruningOptions {
+            dry_run: false,
+            ..options
+        },
+    )
+    .await
+    .expect("could not prune snapshots");
+    assert_eq!(dry_run_pruned, report.pruned);
+    assert!(report.reclaimed_bytes > 0);
+    assert!(!snapshot_is_stored(ctx, abandoned_address).await);
+    assert!(snapshot_is_stored(ctx, head_address).await);
+}
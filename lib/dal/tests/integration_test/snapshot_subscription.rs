@@ -0,0 +1,104 @@
+use dal::{
+    snapshot_subscription::{SnapshotSubscription, SnapshotSubscriptionFilter},
+    DalContext,
+};
+use dal_test::{
+    helpers::{
+        create_component_for_default_schema_name_in_default_view,
+        update_attribute_value_for_component, ChangeSetTestHelpers,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+#[test]
+async fn projection_follows_snapshot(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "fallout", "vault 111")
+            .await
+            .expect("could not create component");
+    create_component_for_default_schema_name_in_default_view(ctx, "starfield", "constellation")
+        .await
+        .expect("could not create component");
+    update_attribute_value_for_component(
+        ctx,
+        component.id(),
+        &["root", "domain", "rads"],
+        json!(10),
+    )
+    .await
+    .expect("could not update rads");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let subscription = SnapshotSubscription::new(
+        ctx,
+        "cmdb",
+        SnapshotSubscriptionFilter {
+            schema_names: vec!["fallout".to_string()],
+            prop_paths: vec!["/domain/rads".to_string()],
+        },
+    )
+    .await
+    .expect("could not create subscription");
+
+    // Only the selected schema and props are projected
+    assert_eq!(0, subscription.sequence());
+    assert_eq!(
+        &json!({
+            component.id().to_string(): {
+                "name": "vault 111",
+                "schemaName": "fallout",
+                "props": { "/domain/rads": 10 },
+            },
+        }),
+        subscription.projection()
+    );
+
+    update_attribute_value_for_component(
+        ctx,
+        component.id(),
+        &["root", "domain", "rads"],
+        json!(20),
+    )
+    .await
+    .expect("could not update rads");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    SnapshotSubscription::publish_updates(ctx)
+        .await
+        .expect("could not publish updates");
+    let updated = SnapshotSubscription::get_by_id(
+        ctx,
+        ctx.workspace_pk().expect("workspace pk"),
+        subscription.id(),
+    )
+    .await
+    .expect("could not get subscription")
+    .expect("subscription not found");
+    assert_eq!(1, updated.sequence());
+    assert_eq!(
+        Some(&json!(20)),
+        updated
+            .projection()
+            .pointer(&format!("/{}/props/~1domain~1rads", component.id()))
+    );
+
+    // Without changes, nothing is published
+    SnapshotSubscription::publish_updates(ctx)
+        .await
+        .expect("could not publish updates");
+    let unchanged = SnapshotSubscription::get_by_id(
+        ctx,
+        ctx.workspace_pk().expect("workspace pk"),
+        subscription.id(),
+    )
+    .await
+    .expect("could not get subscription")
+    .expect("subscription not found");
+    assert_eq!(1, unchanged.sequence());
+}
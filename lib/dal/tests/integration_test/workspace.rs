@@ -1,11 +1,11 @@
 use dal::change_set::view::OpenChangeSetsView;
 use dal::diagram::Diagram;
-use dal::{DalContext, Workspace};
+use dal::{ChangeSet, Component, DalContext, TransactionsError, Workspace};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
     PropEditorTestView,
 };
-use dal_test::test;
+use dal_test::{test, DalContextSecondUser, OtherWorkspaceSignup};
 use pretty_assertions_sorted::assert_eq;
 
 #[test]
@@ -111,3 +111,114 @@ async fn export_import_loop(ctx: &mut DalContext) {
             .expect("get value for domain/name")
     );
 }
+
+#[test]
+async fn export_import_snapshot(ctx: &mut DalContext) {
+    let pirate_name = "Long John Silver";
+    create_component_for_default_schema_name_in_default_view(ctx, "pirate", pirate_name)
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("commit and update snapshot to visibility");
+
+    let mut exported = Vec::new();
+    Workspace::export_snapshot(ctx, &mut exported)
+        .await
+        .expect("export snapshot");
+
+    let imported_change_set_id = Workspace::import_snapshot(ctx, exported.as_slice())
+        .await
+        .expect("import snapshot");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("commit and update snapshot to visibility");
+
+    ctx.update_visibility_and_snapshot_to_visibility(imported_change_set_id)
+        .await
+        .expect("update context to use imported data");
+
+    let diagram = Diagram::assemble_for_default_view(ctx)
+        .await
+        .expect("load diagram");
+    assert_eq!(
+        1,                        // expected
+        diagram.components.len()  // actual
+    );
+    let component = diagram.components.first().expect("get component");
+    assert_eq!(
+        pirate_name, // expected
+        PropEditorTestView::for_component_id(ctx, component.id) // actual
+            .await
+            .expect("could not get property editor test view")
+            .get_value(&["root", "domain", "name"])
+            .expect("could not get value")
+            .get("value")
+            .expect("get value for domain/name")
+    );
+
+    // Imported snapshots are only there to be looked at
+    let change_set = ChangeSet::find(ctx, imported_change_set_id)
+        .await
+        .expect("find change set")
+        .expect("change set exists");
+    assert!(change_set.read_only);
+    create_component_for_default_schema_name_in_default_view(ctx, "pirate", "Anne Bonny")
+        .await
+        .expect("could not create component");
+    assert!(matches!(
+        ctx.commit().await,
+        Err(TransactionsError::ChangeSetReadOnly(id)) if id == imported_change_set_id
+    ));
+}
+
+#[test]
+async fn import_snapshot_rejects_corrupt_data(ctx: &mut DalContext) {
+    let mut exported = Vec::new();
+    Workspace::export_snapshot(ctx, &mut exported)
+        .await
+        .expect("export snapshot");
+
+    // Flip a byte in the middle of the export, which is either rejected while decoding or when
+    // verifying the hashes.
+    let middle = exported.len() / 2;
+    exported[middle] ^= 0xff;
+
+    assert!(Workspace::import_snapshot(ctx, exported.as_slice())
+        .await
+        .is_err());
+}
+
+#[test]
+async fn second_user_shares_change_set(
+    ctx: &mut DalContext,
+    DalContextSecondUser(mut ctx_2): DalContextSecondUser,
+) {
+    assert_eq!(ctx.change_set_id(), ctx_2.change_set_id());
+    assert_ne!(ctx.history_actor(), ctx_2.history_actor());
+
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "shared")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("commit and update snapshot to visibility");
+
+    ctx_2
+        .update_snapshot_to_visibility()
+        .await
+        .expect("update snapshot to visibility");
+    let components = Component::list(&ctx_2).await.expect("list components");
+    assert!(components.iter().any(|c| c.id() == component.id()));
+}
+
+#[test]
+async fn other_workspace_is_isolated(ctx: &DalContext, other_nw: &OtherWorkspaceSignup) {
+    let workspace_pk = ctx.tenancy().workspace_pk_opt().expect("find workspace pk");
+    assert_ne!(workspace_pk, *other_nw.0.workspace.pk());
+    assert_ne!(
+        ctx.change_set_id(),
+        other_nw.0.workspace.default_change_set_id()
+    );
+}
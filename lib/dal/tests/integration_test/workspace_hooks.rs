@@ -0,0 +1,34 @@
+use dal::workspace_hooks::{WorkspaceHook, WorkspaceHookTarget};
+use dal::DalContext;
+use dal_test::{color_eyre::Result, test};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn secret_is_encrypted_at_rest(ctx: &mut DalContext) -> Result<()> {
+    let (hook, secret) = WorkspaceHook::new(
+        ctx,
+        WorkspaceHookTarget::Refresh {
+            component_ids: vec![],
+        },
+    )
+    .await?;
+
+    let row = ctx
+        .txns()
+        .await?
+        .pg()
+        .query_one(
+            "SELECT secret_crypted FROM workspace_hooks WHERE pk = $1",
+            &[&hook.pk()],
+        )
+        .await?;
+    let crypted: Vec<u8> = row.try_get("secret_crypted")?;
+    assert_ne!(secret.as_bytes(), crypted.as_slice());
+
+    let found = WorkspaceHook::get_by_pk(ctx, hook.workspace_pk(), hook.pk())
+        .await?
+        .expect("hook not found");
+    assert_eq!(secret, found.secret(ctx)?);
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use dal::workspace_role::{WorkspaceOperation, WorkspaceRole, WorkspaceRoleError};
+use dal::{DalContext, HistoryActor, UserPk};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn members_are_authorized_by_role(ctx: &mut DalContext) {
+    let HistoryActor::User(user_pk) = *ctx.history_actor() else {
+        panic!("test context should have a user history actor");
+    };
+    let workspace_pk = ctx.workspace_pk().expect("no workspace pk on context");
+
+    // Members whose role was never set are editors
+    assert_eq!(
+        Some(WorkspaceRole::Editor),
+        WorkspaceRole::get_for_user(ctx, workspace_pk, user_pk)
+            .await
+            .expect("could not get role"),
+    );
+    WorkspaceRole::authorize(ctx, WorkspaceOperation::DeleteComponent)
+        .await
+        .expect("editors should be able to delete components");
+
+    WorkspaceRole::set_for_user(ctx, workspace_pk, user_pk, WorkspaceRole::Viewer)
+        .await
+        .expect("could not set role");
+    for operation in [
+        WorkspaceOperation::ApplyChangeSet,
+        WorkspaceOperation::DeleteComponent,
+        WorkspaceOperation::EditFunc,
+    ] {
+        let result = WorkspaceRole::authorize(ctx, operation).await;
+        assert!(
+            matches!(
+                result,
+                Err(WorkspaceRoleError::NotPermitted(WorkspaceRole::Viewer, denied)) if denied == operation
+            ),
+            "viewers should not be able to {operation}: {result:?}",
+        );
+    }
+
+    WorkspaceRole::set_for_user(ctx, workspace_pk, user_pk, WorkspaceRole::Owner)
+        .await
+        .expect("could not set role");
+    WorkspaceRole::authorize(ctx, WorkspaceOperation::ApplyChangeSet)
+        .await
+        .expect("owners should be able to apply change sets");
+
+    let stranger_pk = UserPk::generate();
+    ctx.update_history_actor(HistoryActor::User(stranger_pk));
+    let result = WorkspaceRole::authorize(ctx, WorkspaceOperation::EditFunc).await;
+    assert!(
+        matches!(result, Err(WorkspaceRoleError::NotAMember(pk, _)) if pk == stranger_pk),
+        "non members should not be authorized: {result:?}",
+    );
+
+    ctx.update_history_actor(HistoryActor::SystemInit);
+    WorkspaceRole::authorize(ctx, WorkspaceOperation::EditFunc)
+        .await
+        .expect("the system should be authorized for everything");
+}
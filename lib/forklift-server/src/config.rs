@@ -5,11 +5,14 @@ use buck2_resources::Buck2Resources;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsConfig;
+use si_settings::ValidationReport;
 use si_std::CanonicalFileError;
 use telemetry::prelude::*;
 use thiserror::Error;
 use ulid::Ulid;
 
+use crate::sink::SinkConfig;
+
 pub(crate) use si_settings::StandardConfig;
 
 pub use si_settings::StandardConfigFile;
@@ -58,6 +61,9 @@ pub struct Config {
 
     #[builder(default)]
     audit: AuditDatabaseConfig,
+
+    #[builder(default)]
+    billing_events_sinks: Vec<SinkConfig>,
 }
 
 impl StandardConfig for Config {
@@ -100,6 +106,12 @@ impl Config {
     pub fn audit(&self) -> &AuditDatabaseConfig {
         &self.audit
     }
+
+    /// Gets a reference to the configs of the sinks billing events are delivered to in addition
+    /// to the data warehouse stream.
+    pub fn billing_events_sinks(&self) -> &[SinkConfig] {
+        &self.billing_events_sinks
+    }
 }
 
 #[allow(missing_docs)]
@@ -117,6 +129,8 @@ pub struct ConfigFile {
     pub enable_audit_logs_app: bool,
     #[serde(default)]
     pub audit: AuditDatabaseConfig,
+    #[serde(default)]
+    pub billing_events_sinks: Vec<SinkConfig>,
 }
 
 impl Default for ConfigFile {
@@ -128,12 +142,22 @@ impl Default for ConfigFile {
             data_warehouse_stream_name: default_data_warehouse_stream_name(),
             enable_audit_logs_app: default_enable_audit_logs_app(),
             audit: Default::default(),
+            billing_events_sinks: Default::default(),
         }
     }
 }
 
 impl StandardConfigFile for ConfigFile {
     type Error = ConfigError;
+
+    fn validate(&self, report: &mut ValidationReport) {
+        report.require_non_empty("instance_id", &self.instance_id);
+        report.check(
+            self.concurrency_limit > 0,
+            "concurrency_limit",
+            "must be greater than zero",
+        );
+    }
 }
 
 impl TryFrom<ConfigFile> for Config {
@@ -149,6 +173,7 @@ impl TryFrom<ConfigFile> for Config {
             data_warehouse_stream_name: value.data_warehouse_stream_name,
             enable_audit_logs_app: value.enable_audit_logs_app,
             audit: value.audit,
+            billing_events_sinks: value.billing_events_sinks,
         })
     }
 }
@@ -27,9 +27,13 @@
 
 mod config;
 mod server;
+mod sink;
 
 pub use config::Config;
 pub use config::ConfigError;
 pub use config::ConfigFile;
 pub use config::StandardConfigFile;
 pub use server::Server;
+pub use sink::S3SinkConfig;
+pub use sink::SinkConfig;
+pub use sink::WebhookSinkConfig;
@@ -7,7 +7,7 @@ use thiserror::Error;
 use tokio::task::JoinError;
 use tokio_util::sync::CancellationToken;
 
-use crate::config::Config;
+use crate::{config::Config, sink::SinkConfig};
 
 mod app;
 
@@ -95,12 +95,14 @@ impl Server {
             config.concurrency_limit(),
             audit_bag,
             config.data_warehouse_stream_name(),
+            config.billing_events_sinks(),
             token,
         )
         .await
     }
 
     /// Creates a forklift server with a running naxum task with running services.
+    #[allow(clippy::too_many_arguments)]
     #[instrument(name = "forklift.init.from_services", level = "info", skip_all)]
     pub async fn from_services(
         connection_metadata: Arc<ConnectionMetadata>,
@@ -109,6 +111,7 @@ impl Server {
         concurrency_limit: usize,
         audit_bag: Option<(AuditDatabaseContext, usize)>,
         data_warehouse_stream_name: Option<&str>,
+        billing_events_sinks: &[SinkConfig],
         token: CancellationToken,
     ) -> Result<Self> {
         let metadata = Arc::new(ServerMetadata {
@@ -138,6 +141,7 @@ impl Server {
             connection_metadata,
             concurrency_limit,
             data_warehouse_stream_name,
+            billing_events_sinks,
             token.clone(),
         )
         .await?;
@@ -6,6 +6,8 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
 
+use crate::sink::SinkConfig;
+
 mod audit_logs;
 mod billing_events;
 
@@ -59,6 +61,7 @@ pub(crate) async fn billing_events(
     connection_metadata: Arc<ConnectionMetadata>,
     concurrency_limit: usize,
     data_warehouse_stream_name: Option<&str>,
+    billing_events_sinks: &[SinkConfig],
     token: CancellationToken,
 ) -> Result<Box<dyn Future<Output = io::Result<()>> + Unpin + Send>> {
     Ok(billing_events::build_and_run(
@@ -67,6 +70,7 @@ pub(crate) async fn billing_events(
         connection_metadata,
         concurrency_limit,
         data_warehouse_stream_name,
+        billing_events_sinks,
         token,
     )
     .await?)
@@ -34,6 +34,8 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
 
+use crate::sink::{Sink, SinkConfig, SinkError};
+
 mod app_state;
 mod handlers;
 
@@ -45,6 +47,8 @@ pub enum BillingEventsAppSetupError {
     AsyncNatsStream(#[from] AsyncNatsError<StreamErrorKind>),
     #[error("billing events error: {0}")]
     BillingEvents(#[from] BillingEventsError),
+    #[error("sink error: {0}")]
+    Sink(#[from] SinkError),
 }
 
 type Result<T> = std::result::Result<T, BillingEventsAppSetupError>;
@@ -60,6 +64,7 @@ pub(crate) async fn build_and_run(
     connection_metadata: Arc<ConnectionMetadata>,
     concurrency_limit: usize,
     data_warehouse_stream_name: Option<&str>,
+    billing_events_sinks: &[SinkConfig],
     token: CancellationToken,
 ) -> Result<Box<dyn Future<Output = io::Result<()>> + Unpin + Send>> {
     let incoming = {
@@ -78,12 +83,16 @@ pub(crate) async fn build_and_run(
             .await?
     };
 
-    let inner = match data_warehouse_stream_name {
-        Some(stream_name) => {
-            info!(%stream_name, "creating billing events app in data warehouse stream delivery mode...");
-            let client = DataWarehouseStreamClient::new(stream_name).await;
-            let state = AppState::new(client);
-            build_app(
+    let sinks = billing_events_sinks
+        .iter()
+        .map(|config| Sink::from_config(config, token.clone()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let inner = match (data_warehouse_stream_name, sinks.is_empty()) {
+        (None, true) => {
+            info!("creating billing events app in no-op mode...");
+            let state = NoopAppState::new();
+            build_noop_app(
                 state,
                 connection_metadata,
                 incoming,
@@ -91,10 +100,23 @@ pub(crate) async fn build_and_run(
                 token.clone(),
             )?
         }
-        None => {
-            info!("creating billing events app in no-op mode...");
-            let state = NoopAppState::new();
-            build_noop_app(
+        (maybe_stream_name, _) => {
+            let sink_kinds: Vec<_> = sinks.iter().map(Sink::kind).collect();
+            let client = match maybe_stream_name {
+                Some(stream_name) => {
+                    info!(%stream_name, ?sink_kinds, "creating billing events app in data warehouse stream delivery mode...");
+                    Some(DataWarehouseStreamClient::new(stream_name).await)
+                }
+                None => {
+                    info!(
+                        ?sink_kinds,
+                        "creating billing events app in sink delivery mode..."
+                    );
+                    None
+                }
+            };
+            let state = AppState::new(client, sinks);
+            build_app(
                 state,
                 connection_metadata,
                 incoming,
@@ -1,14 +1,23 @@
+use std::sync::Arc;
+
 use data_warehouse_stream_client::DataWarehouseStreamClient;
 
+use crate::sink::Sink;
+
 #[derive(Debug, Clone)]
 pub(crate) struct AppState {
-    pub(crate) data_warehouse_stream_client: DataWarehouseStreamClient,
+    pub(crate) data_warehouse_stream_client: Option<DataWarehouseStreamClient>,
+    pub(crate) sinks: Arc<Vec<Sink>>,
 }
 
 impl AppState {
-    pub(crate) fn new(data_warehouse_stream_client: DataWarehouseStreamClient) -> Self {
+    pub(crate) fn new(
+        data_warehouse_stream_client: Option<DataWarehouseStreamClient>,
+        sinks: Vec<Sink>,
+    ) -> Self {
         Self {
             data_warehouse_stream_client,
+            sinks: Arc::new(sinks),
         }
     }
 }
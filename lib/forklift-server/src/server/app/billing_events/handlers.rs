@@ -1,7 +1,8 @@
 use billing_events::BillingEvent;
 use data_warehouse_stream_client::DataWarehouseStreamClientError;
+use futures::future::try_join_all;
 use naxum::{
-    extract::State,
+    extract::{message_parts::Reply, State},
     response::{IntoResponse, Response},
     Json,
 };
@@ -10,6 +11,7 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use super::app_state::{AppState, NoopAppState};
+use crate::sink::SinkError;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -18,6 +20,8 @@ pub(crate) enum HandlerError {
     DataWarehouseStreamClient(#[from] DataWarehouseStreamClientError),
     #[error("serde json error: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("{0} sink error: {1}")]
+    Sink(&'static str, #[source] SinkError),
 }
 
 type HandlerResult<T> = Result<T, HandlerError>;
@@ -32,6 +36,7 @@ impl IntoResponse for HandlerError {
 pub(crate) async fn process_request(
     State(state): State<AppState>,
     _subject: Subject,
+    Reply(reply): Reply,
     Json(request): Json<BillingEvent>,
 ) -> HandlerResult<()> {
     let span = Span::current();
@@ -39,11 +44,25 @@ pub(crate) async fn process_request(
     span.record("si.workspace.id", request.workspace_id.to_string());
     span.record("si.change_set.id", request.change_set_id.to_string());
 
-    let serialized_request = serde_json::to_vec(&request)?;
-    state
-        .data_warehouse_stream_client
-        .publish(serialized_request)
-        .await?;
+    if let Some(data_warehouse_stream_client) = &state.data_warehouse_stream_client {
+        let serialized_request = serde_json::to_vec(&request)?;
+        data_warehouse_stream_client
+            .publish(serialized_request)
+            .await?;
+    }
+
+    // The message is only acked once every sink has accepted the event, otherwise it is
+    // redelivered from the stream
+    let stream_sequence = reply.as_ref().and_then(stream_sequence);
+    try_join_all(state.sinks.iter().map(|sink| {
+        let request = &request;
+        async move {
+            sink.deliver(request, stream_sequence)
+                .await
+                .map_err(|err| HandlerError::Sink(sink.kind(), err))
+        }
+    }))
+    .await?;
 
     info!(kind = ?request.kind, ?request, "processed billing event");
     Ok(())
@@ -66,3 +85,16 @@ pub(crate) async fn process_request_noop(
     );
     Ok(())
 }
+
+/// Parses the stream sequence out of the reply subject of a Jetstream message, which is its ack
+/// subject of the form `$JS.ACK.<stream>.<consumer>.<delivered>.<stream_seq>.<consumer_seq>...`,
+/// or with a domain and account hash after `$JS.ACK` on newer servers.
+fn stream_sequence(reply: &Subject) -> Option<u64> {
+    let tokens: Vec<&str> = reply.split('.').collect();
+    let index = match tokens.as_slice() {
+        ["$JS", "ACK", rest @ ..] if rest.len() == 7 => 5,
+        ["$JS", "ACK", rest @ ..] if rest.len() >= 10 => 7,
+        _ => return None,
+    };
+    tokens[index].parse().ok()
+}
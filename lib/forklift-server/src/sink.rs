@@ -0,0 +1,84 @@
+//! Sinks that billing events are delivered to in addition to the data warehouse stream.
+//!
+//! A billing event is only acknowledged once every sink has accepted it. Failed deliveries are
+//! negatively acknowledged and redelivered from the stream, so sinks may see an event more than
+//! once, but never miss one. The stream sequence of each event is passed to the sinks so that
+//! they can let downstream consumers deduplicate.
+
+use billing_events::BillingEvent;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+mod s3;
+mod webhook;
+
+pub use self::s3::S3SinkConfig;
+pub use webhook::WebhookSinkConfig;
+
+pub(crate) use self::s3::{S3Sink, S3SinkError};
+pub(crate) use webhook::{WebhookSink, WebhookSinkError};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub(crate) enum SinkError {
+    #[error("s3 sink error: {0}")]
+    S3(#[from] S3SinkError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("webhook sink error: {0}")]
+    Webhook(#[from] WebhookSinkError),
+}
+
+type SinkResult<T> = Result<T, SinkError>;
+
+/// The config for an additional destination of billing events.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// Writes billing events to an S3 bucket in batches of newline-delimited JSON.
+    S3(S3SinkConfig),
+    /// Posts each billing event to an HTTPS endpoint.
+    Webhook(WebhookSinkConfig),
+}
+
+/// A destination for billing events.
+#[derive(Clone, Debug)]
+pub(crate) enum Sink {
+    S3(S3Sink),
+    Webhook(WebhookSink),
+}
+
+impl Sink {
+    /// Creates a sink from its config. Sinks that run in the background shut down when the token
+    /// is cancelled.
+    #[instrument(name = "forklift.sink.from_config", level = "info", skip_all)]
+    pub(crate) fn from_config(config: &SinkConfig, token: CancellationToken) -> SinkResult<Self> {
+        Ok(match config {
+            SinkConfig::S3(config) => Self::S3(S3Sink::new(config, token)?),
+            SinkConfig::Webhook(config) => Self::Webhook(WebhookSink::new(config)?),
+        })
+    }
+
+    /// Delivers a billing event, returning once the sink has durably accepted it.
+    pub(crate) async fn deliver(
+        &self,
+        event: &BillingEvent,
+        stream_sequence: Option<u64>,
+    ) -> SinkResult<()> {
+        let payload = serde_json::to_vec(event)?;
+        match self {
+            Self::S3(sink) => sink.deliver(payload, stream_sequence).await?,
+            Self::Webhook(sink) => sink.deliver(payload, stream_sequence).await?,
+        }
+        Ok(())
+    }
+
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::S3(_) => "s3",
+            Self::Webhook(_) => "webhook",
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use s3::{creds::Credentials, error::S3Error, Bucket, Region};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+use ulid::Ulid;
+
+const DEFAULT_BATCH_SIZE: usize = 500;
+const DEFAULT_FLUSH_INTERVAL_SECS: u64 = 10;
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+#[remain::sorted]
+#[derive(Clone, Debug, Error)]
+pub(crate) enum S3SinkError {
+    #[error("s3 sink batcher has shut down")]
+    Closed,
+    #[error("s3 credentials error: {0}")]
+    Credentials(Arc<s3::creds::error::CredentialsError>),
+    #[error("s3 put object of {0} failed with status code {1}")]
+    PutObjectStatus(String, u16),
+    #[error("invalid s3 region: {0}")]
+    Region(String),
+    #[error("s3 error: {0}")]
+    S3(Arc<S3Error>),
+}
+
+impl From<S3Error> for S3SinkError {
+    fn from(value: S3Error) -> Self {
+        Self::S3(Arc::new(value))
+    }
+}
+
+type S3SinkResult<T> = Result<T, S3SinkError>;
+
+/// The config for delivering billing events to an S3 bucket.
+///
+/// Events are written in batches as newline-delimited JSON objects, named after the range of
+/// stream sequences they contain. A batch is written once it holds `batch_size` events or once
+/// its oldest event has waited `flush_interval_secs`, whichever comes first. Events are not
+/// acknowledged until their batch is written, so the flush interval should stay well below the
+/// consumer's ack wait.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct S3SinkConfig {
+    /// The AWS access key ID. Credentials are loaded from the environment when unset.
+    pub access_key_id: Option<String>,
+    /// The AWS secret access key. Credentials are loaded from the environment when unset.
+    pub secret_access_key: Option<String>,
+    /// The region of the bucket.
+    pub region: String,
+    /// The name of the bucket.
+    pub bucket: String,
+    /// The prefix of the key of every written object.
+    pub path_prefix: String,
+    /// The maximum number of events in a batch.
+    pub batch_size: usize,
+    /// The maximum number of seconds an event waits for its batch to be written.
+    pub flush_interval_secs: u64,
+}
+
+impl Default for S3SinkConfig {
+    fn default() -> Self {
+        Self {
+            access_key_id: None,
+            secret_access_key: None,
+            region: String::from("us-east-2"),
+            bucket: String::new(),
+            path_prefix: String::from("billing-events"),
+            batch_size: DEFAULT_BATCH_SIZE,
+            flush_interval_secs: DEFAULT_FLUSH_INTERVAL_SECS,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingEvent {
+    line: Vec<u8>,
+    stream_sequence: Option<u64>,
+    written_tx: oneshot::Sender<S3SinkResult<()>>,
+}
+
+/// Delivers billing events to an S3 bucket through a background batcher task.
+#[derive(Clone, Debug)]
+pub(crate) struct S3Sink {
+    pending_tx: mpsc::Sender<PendingEvent>,
+}
+
+impl S3Sink {
+    pub(crate) fn new(config: &S3SinkConfig, token: CancellationToken) -> S3SinkResult<Self> {
+        let credentials = Credentials::new(
+            config.access_key_id.as_deref(),
+            config.secret_access_key.as_deref(),
+            None,
+            None,
+            None,
+        )
+        .map_err(|err| S3SinkError::Credentials(Arc::new(err)))?;
+        let region = config
+            .region
+            .parse::<Region>()
+            .map_err(|err| S3SinkError::Region(err.to_string()))?;
+        let bucket = Bucket::new(&config.bucket, region, credentials)?;
+
+        Ok(Self::spawn(bucket, config, token))
+    }
+
+    fn spawn(writer: impl BatchWriter, config: &S3SinkConfig, token: CancellationToken) -> Self {
+        let batch_size = config.batch_size.max(1);
+        let (pending_tx, pending_rx) = mpsc::channel(batch_size);
+        let batcher = Batcher {
+            writer,
+            path_prefix: config.path_prefix.trim_end_matches('/').to_owned(),
+            batch_size,
+            flush_interval: Duration::from_secs(config.flush_interval_secs),
+            pending_rx,
+            token,
+        };
+        tokio::spawn(batcher.run());
+
+        Self { pending_tx }
+    }
+
+    /// Queues an event and waits until the batch containing it has been written.
+    pub(crate) async fn deliver(
+        &self,
+        line: Vec<u8>,
+        stream_sequence: Option<u64>,
+    ) -> S3SinkResult<()> {
+        let (written_tx, written_rx) = oneshot::channel();
+        self.pending_tx
+            .send(PendingEvent {
+                line,
+                stream_sequence,
+                written_tx,
+            })
+            .await
+            .map_err(|_| S3SinkError::Closed)?;
+
+        written_rx.await.map_err(|_| S3SinkError::Closed)?
+    }
+}
+
+/// Writes batches of events, to an S3 bucket outside of tests.
+trait BatchWriter: Send + Sync + 'static {
+    fn put(&self, key: &str, body: &[u8]) -> impl Future<Output = S3SinkResult<()>> + Send;
+}
+
+impl BatchWriter for Box<Bucket> {
+    async fn put(&self, key: &str, body: &[u8]) -> S3SinkResult<()> {
+        let response = self
+            .put_object_with_content_type(key, body, NDJSON_CONTENT_TYPE)
+            .await?;
+        if (200..300).contains(&response.status_code()) {
+            Ok(())
+        } else {
+            Err(S3SinkError::PutObjectStatus(
+                key.to_owned(),
+                response.status_code(),
+            ))
+        }
+    }
+}
+
+struct Batcher<W> {
+    writer: W,
+    path_prefix: String,
+    batch_size: usize,
+    flush_interval: Duration,
+    pending_rx: mpsc::Receiver<PendingEvent>,
+    token: CancellationToken,
+}
+
+impl<W: BatchWriter> Batcher<W> {
+    async fn run(mut self) {
+        let mut batch = Vec::with_capacity(self.batch_size);
+        loop {
+            // Wait for the first event of the next batch
+            let first = tokio::select! {
+                maybe_event = self.pending_rx.recv() => match maybe_event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = self.token.cancelled() => break,
+            };
+            batch.push(first);
+
+            // Fill the batch until it is full or its first event has waited long enough
+            let deadline = tokio::time::sleep(self.flush_interval);
+            tokio::pin!(deadline);
+            while batch.len() < self.batch_size {
+                tokio::select! {
+                    maybe_event = self.pending_rx.recv() => match maybe_event {
+                        Some(event) => batch.push(event),
+                        None => break,
+                    },
+                    _ = &mut deadline => break,
+                    _ = self.token.cancelled() => break,
+                }
+            }
+
+            self.write(&mut batch).await;
+        }
+
+        // Write whatever was queued before shutting down so that waiting handlers can ack
+        self.pending_rx.close();
+        while let Some(event) = self.pending_rx.recv().await {
+            batch.push(event);
+        }
+        if !batch.is_empty() {
+            self.write(&mut batch).await;
+        }
+        debug!("s3 sink batcher shut down");
+    }
+
+    #[instrument(
+        name = "forklift.sink.s3.write",
+        level = "info",
+        skip_all,
+        fields(batch.size = batch.len())
+    )]
+    async fn write(&self, batch: &mut Vec<PendingEvent>) {
+        let key = self.key_for(batch);
+        let mut body = Vec::new();
+        for event in batch.iter() {
+            body.extend_from_slice(&event.line);
+            body.push(b'\n');
+        }
+
+        let result = self.writer.put(&key, &body).await;
+        if let Err(err) = &result {
+            error!(si.error.message = ?err, "failed to write billing events batch");
+        }
+
+        for event in batch.drain(..) {
+            // The handler may have gone away, in which case the event is redelivered anyway
+            let _ = event.written_tx.send(result.clone());
+        }
+    }
+
+    /// Names a batch after the range of stream sequences it holds, so that a batch rewritten
+    /// after a redelivery replaces the original object. Batches without sequences get a unique
+    /// name instead.
+    fn key_for(&self, batch: &[PendingEvent]) -> String {
+        let date = Utc::now().format("%Y/%m/%d");
+        let sequences = batch.iter().filter_map(|event| event.stream_sequence);
+        match (sequences.clone().min(), sequences.max()) {
+            (Some(first), Some(last)) => {
+                format!("{}/{date}/{first:020}-{last:020}.ndjson", self.path_prefix)
+            }
+            _ => format!("{}/{date}/{}.ndjson", self.path_prefix, Ulid::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Records the batches it is asked to write, failing them all if asked to.
+    #[derive(Clone, Default)]
+    struct RecordingWriter {
+        written: Arc<Mutex<Vec<(String, String)>>>,
+        fail: bool,
+    }
+
+    impl RecordingWriter {
+        fn written(&self) -> Vec<(String, String)> {
+            self.written.lock().expect("poisoned").clone()
+        }
+    }
+
+    impl BatchWriter for RecordingWriter {
+        async fn put(&self, key: &str, body: &[u8]) -> S3SinkResult<()> {
+            self.written.lock().expect("poisoned").push((
+                key.to_owned(),
+                String::from_utf8(body.to_vec()).expect("body is not utf-8"),
+            ));
+            if self.fail {
+                Err(S3SinkError::PutObjectStatus(key.to_owned(), 500))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn config(batch_size: usize, flush_interval_secs: u64) -> S3SinkConfig {
+        S3SinkConfig {
+            path_prefix: "billing-events/".to_owned(),
+            batch_size,
+            flush_interval_secs,
+            ..Default::default()
+        }
+    }
+
+    fn event(n: u64) -> Vec<u8> {
+        format!("{{\"event\":{n}}}").into_bytes()
+    }
+
+    #[tokio::test]
+    async fn writes_full_batches_as_ndjson() {
+        let writer = RecordingWriter::default();
+        let sink = S3Sink::spawn(writer.clone(), &config(3, 3600), CancellationToken::new());
+
+        let (first, second, third) = tokio::join!(
+            sink.deliver(event(1), Some(7)),
+            sink.deliver(event(2), Some(9)),
+            sink.deliver(event(3), Some(8)),
+        );
+        assert!(first.is_ok() && second.is_ok() && third.is_ok());
+
+        let written = writer.written();
+        assert_eq!(1, written.len());
+        let (key, body) = &written[0];
+        let date = Utc::now().format("%Y/%m/%d");
+        assert_eq!(
+            &format!("billing-events/{date}/00000000000000000007-00000000000000000009.ndjson"),
+            key
+        );
+        assert_eq!("{\"event\":1}\n{\"event\":2}\n{\"event\":3}\n", body);
+    }
+
+    #[tokio::test]
+    async fn writes_partial_batches_once_the_flush_interval_elapses() {
+        let writer = RecordingWriter::default();
+        let sink = S3Sink::spawn(writer.clone(), &config(500, 0), CancellationToken::new());
+
+        sink.deliver(event(1), Some(1))
+            .await
+            .expect("failed to deliver");
+        sink.deliver(event(2), Some(2))
+            .await
+            .expect("failed to deliver");
+
+        let written = writer.written();
+        assert_eq!(2, written.len());
+        assert_eq!("{\"event\":1}\n", written[0].1);
+        assert_eq!("{\"event\":2}\n", written[1].1);
+    }
+
+    #[tokio::test]
+    async fn batches_without_sequences_get_unique_keys() {
+        let writer = RecordingWriter::default();
+        let sink = S3Sink::spawn(writer.clone(), &config(1, 3600), CancellationToken::new());
+
+        sink.deliver(event(1), None)
+            .await
+            .expect("failed to deliver");
+        sink.deliver(event(2), None)
+            .await
+            .expect("failed to deliver");
+
+        let written = writer.written();
+        assert_eq!(2, written.len());
+        assert_ne!(written[0].0, written[1].0);
+        assert!(written
+            .iter()
+            .all(|(key, _)| key.starts_with("billing-events/") && key.ends_with(".ndjson")));
+    }
+
+    #[tokio::test]
+    async fn failed_writes_fail_every_event_of_the_batch() {
+        let writer = RecordingWriter {
+            fail: true,
+            ..Default::default()
+        };
+        let sink = S3Sink::spawn(writer.clone(), &config(2, 3600), CancellationToken::new());
+
+        let (first, second) = tokio::join!(
+            sink.deliver(event(1), Some(1)),
+            sink.deliver(event(2), Some(2)),
+        );
+        assert!(matches!(first, Err(S3SinkError::PutObjectStatus(_, 500))));
+        assert!(matches!(second, Err(S3SinkError::PutObjectStatus(_, 500))));
+        assert_eq!(1, writer.written().len());
+    }
+
+    #[tokio::test]
+    async fn writes_queued_events_on_shutdown_then_closes() {
+        let writer = RecordingWriter::default();
+        let token = CancellationToken::new();
+        let sink = S3Sink::spawn(writer.clone(), &config(500, 3600), token.clone());
+
+        let delivery = tokio::spawn({
+            let sink = sink.clone();
+            async move { sink.deliver(event(1), Some(1)).await }
+        });
+        tokio::task::yield_now().await;
+        token.cancel();
+        delivery
+            .await
+            .expect("delivery task panicked")
+            .expect("queued event was not written");
+        assert_eq!(1, writer.written().len());
+
+        // The batcher is gone once it has written what was queued
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while sink.deliver(event(2), Some(2)).await.is_ok() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("sink did not close");
+        assert!(matches!(
+            sink.deliver(event(3), Some(3)).await,
+            Err(S3SinkError::Closed)
+        ));
+    }
+}
@@ -0,0 +1,354 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{header, StatusCode};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 250;
+const DEFAULT_MAX_BACKOFF_MS: u64 = 5_000;
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 5;
+
+/// Lets the receiver deduplicate events that were redelivered from the stream.
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub(crate) enum WebhookSinkError {
+    #[error("http client build error: {0}")]
+    ClientBuild(#[source] reqwest::Error),
+    #[error("webhook request error: {0}")]
+    Request(#[source] reqwest::Error),
+    #[error("webhook responded with status code {0}")]
+    Status(StatusCode),
+}
+
+type WebhookSinkResult<T> = Result<T, WebhookSinkError>;
+
+/// The config for delivering billing events to an HTTPS webhook.
+///
+/// Each event is posted as a JSON body, along with an `Idempotency-Key` header holding its
+/// stream sequence. Failed requests, server errors and rate limiting responses are retried with
+/// exponential backoff. Every attempt happens before the event is acknowledged, so the total
+/// time across attempts should stay well below the consumer's ack wait.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WebhookSinkConfig {
+    /// The URL events are posted to.
+    pub url: String,
+    /// An optional bearer token sent with every request.
+    pub bearer_token: Option<String>,
+    /// The maximum number of attempts for each event.
+    pub max_attempts: u32,
+    /// The number of milliseconds to wait before the first retry, doubled for each retry after.
+    pub initial_backoff_ms: u64,
+    /// The maximum number of milliseconds to wait between attempts.
+    pub max_backoff_ms: u64,
+    /// The number of seconds before an attempt times out.
+    pub request_timeout_secs: u64,
+}
+
+impl Default for WebhookSinkConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            bearer_token: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff_ms: DEFAULT_INITIAL_BACKOFF_MS,
+            max_backoff_ms: DEFAULT_MAX_BACKOFF_MS,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// Delivers billing events to an HTTPS webhook.
+#[derive(Clone, Debug)]
+pub(crate) struct WebhookSink {
+    client: reqwest::Client,
+    config: Arc<WebhookSinkConfig>,
+}
+
+impl WebhookSink {
+    pub(crate) fn new(config: &WebhookSinkConfig) -> WebhookSinkResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.request_timeout_secs))
+            .build()
+            .map_err(WebhookSinkError::ClientBuild)?;
+
+        Ok(Self {
+            client,
+            config: Arc::new(config.clone()),
+        })
+    }
+
+    /// Posts an event, retrying until it is accepted or the attempts run out.
+    #[instrument(
+        name = "forklift.sink.webhook.deliver",
+        level = "debug",
+        skip_all,
+        fields(stream_sequence = ?stream_sequence)
+    )]
+    pub(crate) async fn deliver(
+        &self,
+        payload: Vec<u8>,
+        stream_sequence: Option<u64>,
+    ) -> WebhookSinkResult<()> {
+        let max_attempts = self.config.max_attempts.max(1);
+        let max_backoff = Duration::from_millis(self.config.max_backoff_ms);
+        let mut backoff = Duration::from_millis(self.config.initial_backoff_ms);
+
+        let mut attempt = 1;
+        loop {
+            match self.attempt(payload.clone(), stream_sequence).await {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt < max_attempts && is_retryable(&err) => {
+                    warn!(
+                        si.error.message = ?err,
+                        attempt,
+                        ?backoff,
+                        "failed to post billing event to webhook, retrying"
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    async fn attempt(
+        &self,
+        payload: Vec<u8>,
+        stream_sequence: Option<u64>,
+    ) -> WebhookSinkResult<()> {
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(payload);
+        if let Some(bearer_token) = &self.config.bearer_token {
+            request = request.bearer_auth(bearer_token);
+        }
+        if let Some(stream_sequence) = stream_sequence {
+            request = request.header(IDEMPOTENCY_KEY_HEADER, stream_sequence.to_string());
+        }
+
+        let response = request.send().await.map_err(WebhookSinkError::Request)?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(WebhookSinkError::Status(status))
+        }
+    }
+}
+
+fn is_retryable(err: &WebhookSinkError) -> bool {
+    match err {
+        WebhookSinkError::ClientBuild(_) => false,
+        WebhookSinkError::Request(_) => true,
+        WebhookSinkError::Status(status) => {
+            status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+
+    /// A request as the fake webhook received it, with lowercased header names.
+    #[derive(Debug)]
+    struct Received {
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    impl Received {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    type Requests = Arc<Mutex<Vec<Received>>>;
+
+    /// Starts a fake webhook that answers requests with the given statuses in order, then with
+    /// `200 OK` once they run out.
+    async fn webhook(statuses: Vec<u16>) -> (String, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind fake webhook");
+        let url = format!("http://{}/events", listener.local_addr().expect("no addr"));
+        let requests = Requests::default();
+
+        let received = requests.clone();
+        tokio::spawn(async move {
+            let mut statuses = statuses.into_iter();
+            while let Ok((stream, _)) = listener.accept().await {
+                let status = statuses.next().unwrap_or(200);
+                respond(stream, status, &received).await;
+            }
+        });
+
+        (url, requests)
+    }
+
+    async fn respond(mut stream: TcpStream, status: u16, received: &Mutex<Vec<Received>>) {
+        let mut buf = Vec::new();
+        let head_len = loop {
+            if let Some(pos) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+                break pos + 4;
+            }
+            let mut chunk = [0; 1024];
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            }
+        };
+
+        let head = String::from_utf8_lossy(&buf[..head_len]).into_owned();
+        let headers: Vec<(String, String)> = head
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_lowercase(), value.trim().to_owned()))
+            .collect();
+        let content_length = headers
+            .iter()
+            .find(|(key, _)| key == "content-length")
+            .and_then(|(_, value)| value.parse::<usize>().ok())
+            .unwrap_or(0);
+
+        let mut body = buf.split_off(head_len);
+        while body.len() < content_length {
+            let mut chunk = [0; 1024];
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => body.extend_from_slice(&chunk[..n]),
+            }
+        }
+        received
+            .lock()
+            .expect("poisoned")
+            .push(Received { headers, body });
+
+        let response =
+            format!("HTTP/1.1 {status} Fake\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+    }
+
+    fn sink(url: String, max_attempts: u32) -> WebhookSink {
+        WebhookSink::new(&WebhookSinkConfig {
+            url,
+            bearer_token: Some("sekrit".to_owned()),
+            max_attempts,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 2,
+            request_timeout_secs: 5,
+        })
+        .expect("failed to build sink")
+    }
+
+    fn attempts(requests: &Requests) -> usize {
+        requests.lock().expect("poisoned").len()
+    }
+
+    #[tokio::test]
+    async fn posts_events_as_json_with_idempotency_keys() {
+        let (url, requests) = webhook(vec![]).await;
+        let sink = sink(url, 1);
+
+        sink.deliver(br#"{"n":1}"#.to_vec(), Some(42))
+            .await
+            .expect("delivery failed");
+        sink.deliver(br#"{"n":2}"#.to_vec(), None)
+            .await
+            .expect("delivery failed");
+
+        let requests = requests.lock().expect("poisoned");
+        assert_eq!(2, requests.len());
+        assert_eq!(br#"{"n":1}"#, requests[0].body.as_slice());
+        assert_eq!(Some("application/json"), requests[0].header("content-type"));
+        assert_eq!(Some("Bearer sekrit"), requests[0].header("authorization"));
+        assert_eq!(Some("42"), requests[0].header("idempotency-key"));
+        assert_eq!(br#"{"n":2}"#, requests[1].body.as_slice());
+        assert_eq!(None, requests[1].header("idempotency-key"));
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_and_rate_limits_until_accepted() {
+        let (url, requests) = webhook(vec![500, 429, 503]).await;
+
+        sink(url, 4)
+            .deliver(b"{}".to_vec(), Some(7))
+            .await
+            .expect("delivery failed");
+
+        let requests = requests.lock().expect("poisoned");
+        assert_eq!(4, requests.len());
+        assert!(requests
+            .iter()
+            .all(|request| request.header("idempotency-key") == Some("7")));
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let (url, requests) = webhook(vec![400]).await;
+
+        let result = sink(url, 4).deliver(b"{}".to_vec(), Some(7)).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(WebhookSinkError::Status(StatusCode::BAD_REQUEST))
+            ),
+            "unexpected result: {result:?}"
+        );
+        assert_eq!(1, attempts(&requests));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (url, requests) = webhook(vec![502; 5]).await;
+
+        let result = sink(url, 3).deliver(b"{}".to_vec(), Some(7)).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(WebhookSinkError::Status(StatusCode::BAD_GATEWAY))
+            ),
+            "unexpected result: {result:?}"
+        );
+        assert_eq!(3, attempts(&requests));
+    }
+
+    #[tokio::test]
+    async fn unreachable_webhooks_are_request_errors() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind");
+        let url = format!("http://{}/events", listener.local_addr().expect("no addr"));
+        drop(listener);
+
+        let result = sink(url, 2).deliver(b"{}".to_vec(), None).await;
+
+        assert!(
+            matches!(result, Err(WebhookSinkError::Request(_))),
+            "unexpected result: {result:?}"
+        );
+    }
+}
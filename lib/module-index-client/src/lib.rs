@@ -4,6 +4,10 @@ use thiserror::Error;
 use ulid::Ulid;
 use url::Url;
 
+mod publish;
+
+pub use publish::{ModuleUpload, PublishProgress};
+
 // Re-export all module index types so that client users do not have to import two crates.
 pub use module_index_types::*;
 
@@ -105,40 +109,23 @@ impl ModuleIndexClient {
         module_schema_variant_id: Option<String>,
         module_schema_variant_version: Option<String>,
     ) -> ModuleIndexClientResult<ModuleDetailsResponse> {
-        let module_upload_part = reqwest::multipart::Part::bytes(module_bytes)
-            .file_name(format!("{module_name}_{module_version}.tar"));
-
-        let mut multipart_form =
-            reqwest::multipart::Form::new().part(MODULE_BUNDLE_FIELD_NAME, module_upload_part);
-
-        if let Some(module_based_on_hash) = module_based_on_hash {
-            multipart_form = multipart_form.part(
-                MODULE_BASED_ON_HASH_FIELD_NAME,
-                reqwest::multipart::Part::text(module_based_on_hash),
-            );
-        }
-
-        if let Some(schema_id) = module_schema_id {
-            multipart_form = multipart_form.part(
-                MODULE_SCHEMA_ID_FIELD_NAME,
-                reqwest::multipart::Part::text(schema_id),
-            );
-        }
-
-        if let Some(schema_variant_id) = module_schema_variant_id {
-            multipart_form = multipart_form.part(
-                MODULE_SCHEMA_VARIANT_ID_FIELD_NAME,
-                reqwest::multipart::Part::text(schema_variant_id),
-            );
-        }
-
-        if let Some(schema_variant_version) = module_schema_variant_version {
-            multipart_form = multipart_form.part(
-                MODULE_SCHEMA_VARIANT_VERSION_FIELD_NAME,
-                reqwest::multipart::Part::text(schema_variant_version),
-            );
-        }
+        let multipart_form = module_upload_form(
+            module_name,
+            module_version,
+            module_based_on_hash,
+            module_schema_id,
+            module_bytes,
+            module_schema_variant_id,
+            module_schema_variant_version,
+        );
+
+        self.post_module_upload(multipart_form).await
+    }
 
+    async fn post_module_upload(
+        &self,
+        multipart_form: reqwest::multipart::Form,
+    ) -> ModuleIndexClientResult<ModuleDetailsResponse> {
         let upload_url = self.base_url.join("modules")?;
         let upload_response = reqwest::Client::new()
             .post(upload_url)
@@ -293,12 +280,23 @@ impl ModuleIndexClient {
         Ok(export_data)
     }
 
-    /// Lists all of the latest, _promoted_ [`Modules`](Model) (route: GET /modules/latest).
+    /// Lists all of the latest, _promoted_ stable [`Modules`](Model) (route: GET /modules/latest).
     pub async fn list_latest_modules(&self) -> ModuleIndexClientResult<ListLatestModulesResponse> {
+        self.list_latest_modules_in_channel(ModuleChannel::Stable)
+            .await
+    }
+
+    /// Lists all of the latest, _promoted_ [`Modules`](Model) of a channel (route: GET
+    /// /modules/latest).
+    pub async fn list_latest_modules_in_channel(
+        &self,
+        channel: ModuleChannel,
+    ) -> ModuleIndexClientResult<ListLatestModulesResponse> {
         let url = self.base_url.join("modules/")?.join("latest")?;
 
         Ok(reqwest::Client::new()
             .get(url)
+            .query(&ListLatestModulesRequest { channel })
             .bearer_auth(&self.auth_token)
             .send()
             .await?
@@ -321,3 +319,49 @@ impl ModuleIndexClient {
             .await?)
     }
 }
+
+fn module_upload_form(
+    module_name: &str,
+    module_version: &str,
+    module_based_on_hash: Option<String>,
+    module_schema_id: Option<String>,
+    module_bytes: Vec<u8>,
+    module_schema_variant_id: Option<String>,
+    module_schema_variant_version: Option<String>,
+) -> reqwest::multipart::Form {
+    let module_upload_part = reqwest::multipart::Part::bytes(module_bytes)
+        .file_name(format!("{module_name}_{module_version}.tar"));
+
+    let mut multipart_form =
+        reqwest::multipart::Form::new().part(MODULE_BUNDLE_FIELD_NAME, module_upload_part);
+
+    if let Some(module_based_on_hash) = module_based_on_hash {
+        multipart_form = multipart_form.part(
+            MODULE_BASED_ON_HASH_FIELD_NAME,
+            reqwest::multipart::Part::text(module_based_on_hash),
+        );
+    }
+
+    if let Some(schema_id) = module_schema_id {
+        multipart_form = multipart_form.part(
+            MODULE_SCHEMA_ID_FIELD_NAME,
+            reqwest::multipart::Part::text(schema_id),
+        );
+    }
+
+    if let Some(schema_variant_id) = module_schema_variant_id {
+        multipart_form = multipart_form.part(
+            MODULE_SCHEMA_VARIANT_ID_FIELD_NAME,
+            reqwest::multipart::Part::text(schema_variant_id),
+        );
+    }
+
+    if let Some(schema_variant_version) = module_schema_variant_version {
+        multipart_form = multipart_form.part(
+            MODULE_SCHEMA_VARIANT_VERSION_FIELD_NAME,
+            reqwest::multipart::Part::text(schema_variant_version),
+        );
+    }
+
+    multipart_form
+}
@@ -0,0 +1,142 @@
+//! Publishing modules to the index and managing the versions which have been published.
+//!
+//! A module is published once, after which each new version is uploaded against the version it
+//! is based on, so that the index keeps every version under the same schema. Versions are tagged
+//! with a [`ModuleChannel`] as they are uploaded and can be moved to another channel or yanked
+//! afterwards.
+
+use module_index_types::{
+    ModuleChannel, ModuleDetailsResponse, SetModuleChannelRequest, MODULE_CHANNEL_FIELD_NAME,
+};
+use ulid::Ulid;
+
+use crate::{module_upload_form, ModuleIndexClient, ModuleIndexClientResult};
+
+/// An exported module bundle to upload to the index.
+#[derive(Debug, Clone)]
+pub struct ModuleUpload {
+    pub name: String,
+    pub version: String,
+    pub schema_variant_id: Option<String>,
+    pub schema_variant_version: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+/// The steps of an upload, reported as each one begins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishProgress {
+    /// The bundle is being sent to the index.
+    Uploading { total_bytes: u64 },
+    /// The index has stored the version and tagged it with its channel.
+    Published,
+}
+
+impl ModuleIndexClient {
+    /// Publishes a module which has no earlier versions in the index to the given channel.
+    pub async fn publish_module(
+        &self,
+        upload: ModuleUpload,
+        channel: ModuleChannel,
+        progress: impl FnMut(PublishProgress),
+    ) -> ModuleIndexClientResult<ModuleDetailsResponse> {
+        self.upload_to_channel(upload, None, None, channel, progress)
+            .await
+    }
+
+    /// Uploads a new version of a published module to the given channel, based on the version
+    /// with the given hash. Without a schema id, the index uses the one of the base version.
+    pub async fn upload_module_version(
+        &self,
+        based_on_hash: String,
+        schema_id: Option<String>,
+        upload: ModuleUpload,
+        channel: ModuleChannel,
+        progress: impl FnMut(PublishProgress),
+    ) -> ModuleIndexClientResult<ModuleDetailsResponse> {
+        self.upload_to_channel(
+            upload,
+            Some(based_on_hash),
+            Some(schema_id),
+            channel,
+            progress,
+        )
+        .await
+    }
+
+    /// Yanks a module version, so that it is no longer listed or offered for install. Only the
+    /// module's owner can yank it, and the index records them as the one who yanked it.
+    pub async fn yank_module(
+        &self,
+        module_id: Ulid,
+    ) -> ModuleIndexClientResult<ModuleDetailsResponse> {
+        let yank_url = self
+            .base_url
+            .join("modules/")?
+            .join(&format!("{module_id}/"))?
+            .join("yank")?;
+
+        Ok(reqwest::Client::new()
+            .post(yank_url)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Moves a module version to another channel, for example to promote a beta to stable.
+    pub async fn set_module_channel(
+        &self,
+        module_id: Ulid,
+        channel: ModuleChannel,
+    ) -> ModuleIndexClientResult<ModuleDetailsResponse> {
+        let channel_url = self
+            .base_url
+            .join("modules/")?
+            .join(&format!("{module_id}/"))?
+            .join("channel")?;
+
+        Ok(reqwest::Client::new()
+            .post(channel_url)
+            .json(&SetModuleChannelRequest { channel })
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    async fn upload_to_channel(
+        &self,
+        upload: ModuleUpload,
+        based_on_hash: Option<String>,
+        schema_id: Option<String>,
+        channel: ModuleChannel,
+        mut progress: impl FnMut(PublishProgress),
+    ) -> ModuleIndexClientResult<ModuleDetailsResponse> {
+        progress(PublishProgress::Uploading {
+            total_bytes: upload.bytes.len() as u64,
+        });
+
+        let multipart_form = module_upload_form(
+            &upload.name,
+            &upload.version,
+            based_on_hash,
+            schema_id,
+            upload.bytes,
+            upload.schema_variant_id,
+            upload.schema_variant_version,
+        )
+        .part(
+            MODULE_CHANNEL_FIELD_NAME,
+            reqwest::multipart::Part::text(channel.as_str()),
+        );
+        let response = self.post_module_upload(multipart_form).await?;
+
+        progress(PublishProgress::Published);
+
+        Ok(response)
+    }
+}
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use si_data_pg::PgPoolConfig;
 use si_jwt_public_key::JwtAlgo;
 use si_posthog::PosthogConfig;
+use si_settings::ValidationReport;
 use si_std::{CanonicalFile, CanonicalFileError};
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -168,6 +169,14 @@ impl Default for ConfigFile {
 
 impl StandardConfigFile for ConfigFile {
     type Error = ConfigError;
+
+    fn validate(&self, report: &mut ValidationReport) {
+        report.require_non_empty("instance_id", &self.instance_id);
+        report.require_non_empty(
+            "jwt_signing_public_key_path",
+            &self.jwt_signing_public_key_path,
+        );
+    }
 }
 
 impl TryFrom<ConfigFile> for Config {
@@ -0,0 +1,4 @@
+ALTER TABLE modules
+    ADD channel TEXT,
+    ADD yanked_at timestamp with time zone,
+    ADD yanked_by_display_name TEXT;
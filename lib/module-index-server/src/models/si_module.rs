@@ -1,4 +1,4 @@
-use module_index_types::{LatestModuleResponse, ModuleDetailsResponse};
+use module_index_types::{LatestModuleResponse, ModuleChannel, ModuleDetailsResponse};
 use sea_orm::{entity::prelude::*, sea_query, TryGetError};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -33,11 +33,15 @@ pub struct Model {
     #[sea_orm(column_type = r##"custom("ident")"##, nullable)]
     pub schema_variant_id: Option<SchemaVariantId>,
     pub schema_variant_version: Option<String>,
+    pub channel: Option<String>,
+    pub yanked_at: Option<DateTimeWithTimeZone>,
+    pub yanked_by_display_name: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ModuleKind {
+    Delta,
     Module,
     WorkspaceBackup,
 }
@@ -45,6 +49,7 @@ pub enum ModuleKind {
 impl ModuleKind {
     pub fn to_db_kind(&self) -> String {
         match self {
+            ModuleKind::Delta => "delta".into(),
             ModuleKind::Module => "module".into(),
             ModuleKind::WorkspaceBackup => "workspaceBackup".into(),
         }
@@ -56,6 +61,7 @@ impl FromStr for ModuleKind {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
+            "delta" => ModuleKind::Delta,
             "module" => ModuleKind::Module,
             "workspaceBackup" => ModuleKind::WorkspaceBackup,
             _ => return Err(sea_query::ValueTypeErr),
@@ -147,6 +153,10 @@ pub fn make_module_details_response(
             .schema_variant_id
             .map(|schema_variant_id| schema_variant_id.to_string()),
         schema_variant_version: module.schema_variant_version,
+        channel: module
+            .channel
+            .and_then(|channel| ModuleChannel::from_str(&channel).ok()),
+        yanked_at: module.yanked_at.map(Into::into),
         past_hashes: Some(
             linked_modules
                 .into_iter()
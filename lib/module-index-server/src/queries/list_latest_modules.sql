@@ -14,7 +14,10 @@ WITH RECURSIVE filtered_modules AS (
         kind,
         is_builtin_at,
         is_builtin_at_by_display_name,
-        schema_id
+        schema_id,
+        channel,
+        yanked_at,
+        yanked_by_display_name
     FROM
         modules 
     WHERE 
@@ -37,7 +40,10 @@ WITH RECURSIVE filtered_modules AS (
         a.kind,
         a.is_builtin_at,
         a.is_builtin_at_by_display_name,
-        a.schema_id
+        a.schema_id,
+        a.channel,
+        a.yanked_at,
+        a.yanked_by_display_name
     FROM
         modules a
     JOIN
@@ -60,11 +66,17 @@ SELECT DISTINCT ON (filtered_modules.schema_id)
     filtered_modules.kind,
     filtered_modules.is_builtin_at,
     filtered_modules.is_builtin_at_by_display_name,
-    filtered_modules.schema_id
+    filtered_modules.schema_id,
+    filtered_modules.channel,
+    filtered_modules.yanked_at,
+    filtered_modules.yanked_by_display_name
 FROM
     filtered_modules
 WHERE
     filtered_modules.is_builtin_at IS NOT NULL
+    AND filtered_modules.yanked_at IS NULL
+    -- Versions which predate channels are stable
+    AND COALESCE(filtered_modules.channel, 'stable') = $1
 ORDER BY
     filtered_modules.schema_id,
     filtered_modules.created_at DESC;
@@ -21,8 +21,10 @@ mod list_latest_modules_route;
 mod list_modules_route;
 pub(crate) mod promote_builtin_route;
 pub(crate) mod reject_module_route;
+mod set_module_channel_route;
 pub(crate) mod upsert_module_route;
 mod upsert_workspace_route;
+mod yank_module_route;
 
 use super::{app_state::AppState, server::ServerError};
 
@@ -69,6 +71,14 @@ pub fn routes(state: AppState) -> Router {
             "/modules/:module_id/reject",
             post(reject_module_route::reject_module),
         )
+        .route(
+            "/modules/:module_id/yank",
+            post(yank_module_route::yank_module_route),
+        )
+        .route(
+            "/modules/:module_id/channel",
+            post(set_module_channel_route::set_module_channel_route),
+        )
         .layer(CorsLayer::permissive())
         .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
         .layer(CompressionLayer::new());
@@ -1,9 +1,10 @@
 use axum::{
+    extract::Query,
     response::{IntoResponse, Response},
     Json,
 };
 use hyper::StatusCode;
-use module_index_types::ListLatestModulesResponse;
+use module_index_types::{ListLatestModulesRequest, ListLatestModulesResponse};
 use sea_orm::{DbBackend, DbErr, EntityTrait, Statement};
 use thiserror::Error;
 
@@ -37,14 +38,16 @@ impl IntoResponse for ListModulesError {
     }
 }
 
+/// Lists the latest promoted version of each module in the requested channel.
 pub async fn list_latest_modules_route(
     DbConnection(txn): DbConnection,
+    Query(request): Query<ListLatestModulesRequest>,
 ) -> Result<Json<ListLatestModulesResponse>, ListModulesError> {
     let raw_modules = si_module::Entity::find()
         .from_raw_sql(Statement::from_sql_and_values(
             DbBackend::Postgres,
             LIST_LATEST_MODULES_QUERY,
-            [],
+            [request.channel.as_str().into()],
         ))
         .all(&txn)
         .await?;
@@ -56,3 +59,40 @@ pub async fn list_latest_modules_route(
 
     Ok(Json(ListLatestModulesResponse { modules }))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Uri;
+    use module_index_types::ModuleChannel;
+
+    use super::*;
+
+    fn channel_for(uri: &str) -> ModuleChannel {
+        let uri: Uri = uri.parse().expect("could not parse uri");
+        Query::<ListLatestModulesRequest>::try_from_uri(&uri)
+            .expect("could not extract query")
+            .0
+            .channel
+    }
+
+    #[test]
+    fn lists_stable_modules_unless_asked_for_another_channel() {
+        assert_eq!(ModuleChannel::Stable, channel_for("/modules/latest"));
+        assert_eq!(
+            ModuleChannel::Stable,
+            channel_for("/modules/latest?channel=stable")
+        );
+        assert_eq!(
+            ModuleChannel::Beta,
+            channel_for("/modules/latest?channel=beta")
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_channels() {
+        let uri: Uri = "/modules/latest?channel=nightly"
+            .parse()
+            .expect("could not parse uri");
+        assert!(Query::<ListLatestModulesRequest>::try_from_uri(&uri).is_err());
+    }
+}
@@ -65,6 +65,7 @@ pub async fn list_module_route(
     // filters
     let query = query
         .filter(si_module::Column::RejectedAt.is_null())
+        .filter(si_module::Column::YankedAt.is_null())
         .filter(si_module::Column::Kind.eq(kind.to_db_kind()));
     let query = if !su {
         let user_id = user_claim.user_id().to_string();
@@ -0,0 +1,96 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{extract::Path, Json};
+use module_index_types::{ModuleDetailsResponse, SetModuleChannelRequest};
+use sea_orm::{ActiveModelTrait, DbErr, EntityTrait, Set};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::app_state::AppState;
+use crate::whoami::{can_manage_module, WhoamiError};
+use crate::{
+    extract::{Authorization, DbConnection},
+    models::si_module::{self, make_module_details_response, ModuleId},
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SetModuleChannelError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error(r#"not allowed to change the channel of module "{0}""#)]
+    Forbidden(ModuleId),
+    #[error(r#"Module "{0}" not found"#)]
+    NotFound(ModuleId),
+    #[error("whoami error: {0}")]
+    Whoami(#[from] WhoamiError),
+    #[error(r#"Module "{0}" has been yanked"#)]
+    Yanked(ModuleId),
+}
+
+impl IntoResponse for SetModuleChannelError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Yanked(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let error_message = self.to_string();
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+/// Tags a module version with a release channel, for example to promote a beta to stable.
+pub async fn set_module_channel_route(
+    Path(module_id): Path<ModuleId>,
+    Authorization {
+        user_claim,
+        auth_token,
+    }: Authorization,
+    DbConnection(txn): DbConnection,
+    State(state): State<AppState>,
+    Json(request): Json<SetModuleChannelRequest>,
+) -> Result<Json<ModuleDetailsResponse>, SetModuleChannelError> {
+    let module = si_module::Entity::find_by_id(module_id)
+        .one(&txn)
+        .await?
+        .ok_or(SetModuleChannelError::NotFound(module_id))?;
+
+    if !can_manage_module(
+        &user_claim.user_id().to_string(),
+        &module.owner_user_id,
+        &auth_token,
+        state.token_emails(),
+    )
+    .await?
+    {
+        return Err(SetModuleChannelError::Forbidden(module_id));
+    }
+    if module.yanked_at.is_some() {
+        return Err(SetModuleChannelError::Yanked(module_id));
+    }
+
+    info!(%module_id, channel = %request.channel, "setting module channel");
+    let mut active: si_module::ActiveModel = module.into();
+    active.channel = Set(Some(request.channel.as_str().to_owned()));
+    active.update(&txn).await?;
+
+    let (module, linked_modules) = si_module::Entity::find_by_id(module_id)
+        .find_with_linked(si_module::SchemaIdReferenceLink)
+        .all(&txn)
+        .await?
+        .first()
+        .cloned()
+        .ok_or(SetModuleChannelError::NotFound(module_id))?;
+
+    txn.commit().await?;
+
+    Ok(Json(make_module_details_response(module, linked_modules)))
+}
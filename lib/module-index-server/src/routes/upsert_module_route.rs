@@ -8,11 +8,12 @@ use axum::{
 use chrono::{DateTime, FixedOffset, Offset, Utc};
 use hyper::StatusCode;
 use module_index_types::{
-    ExtraMetadata, FuncMetadata, ModuleDetailsResponse, MODULE_SCHEMA_VARIANT_ID_FIELD_NAME,
-    MODULE_SCHEMA_VARIANT_VERSION_FIELD_NAME,
+    ExtraMetadata, FuncMetadata, ModuleChannel, ModuleDetailsResponse, UnknownModuleChannel,
+    MODULE_SCHEMA_VARIANT_ID_FIELD_NAME, MODULE_SCHEMA_VARIANT_VERSION_FIELD_NAME,
 };
 use module_index_types::{
-    MODULE_BASED_ON_HASH_FIELD_NAME, MODULE_BUNDLE_FIELD_NAME, MODULE_SCHEMA_ID_FIELD_NAME,
+    MODULE_BASED_ON_HASH_FIELD_NAME, MODULE_BUNDLE_FIELD_NAME, MODULE_CHANNEL_FIELD_NAME,
+    MODULE_SCHEMA_ID_FIELD_NAME,
 };
 use s3::error::S3Error;
 use sea_orm::{ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, Set};
@@ -53,6 +54,8 @@ pub enum UpsertModuleError {
     SiPkgError(#[from] SiPkgError),
     #[error("Ulid decode error: {0}")]
     UlidDecode(#[from] ulid::DecodeError),
+    #[error(transparent)]
+    UnknownChannel(#[from] UnknownModuleChannel),
     #[error("upload is required")]
     UploadRequiredError,
 }
@@ -84,6 +87,7 @@ pub async fn upsert_module_route(
     let mut module_schema_id = None;
     let mut module_schema_variant_id = None;
     let mut module_schema_variant_version = None;
+    let mut module_channel = None;
     while let Some(field) = multipart.next_field().await? {
         match field.name() {
             Some(MODULE_BUNDLE_FIELD_NAME) => {
@@ -101,6 +105,9 @@ pub async fn upsert_module_route(
             Some(MODULE_SCHEMA_VARIANT_VERSION_FIELD_NAME) => {
                 module_schema_variant_version = Some(field.text().await?);
             }
+            Some(MODULE_CHANNEL_FIELD_NAME) => {
+                module_channel = Some(ModuleChannel::from_str(&field.text().await?)?);
+            }
             _ => debug!("Unknown multipart form field on module upload, skipping..."),
         }
     }
@@ -120,12 +127,15 @@ pub async fn upsert_module_route(
     let module_kind = match module_metadata.kind() {
         SiPkgKind::WorkspaceBackup => ModuleKind::WorkspaceBackup,
         SiPkgKind::Module => ModuleKind::Module,
+        SiPkgKind::Delta => ModuleKind::Delta,
     };
 
     let new_schema_id = Some(SchemaId::new());
     let schema_id = match module_kind {
         ModuleKind::WorkspaceBackup => None,
-        ModuleKind::Module => match module_schema_id {
+        // Deltas share the schema of the module they are based on, so they can be reviewed
+        // against it
+        ModuleKind::Module | ModuleKind::Delta => match module_schema_id {
             Some(schema_id_string) => Some(SchemaId::from_str(&schema_id_string)?),
             None => match module_based_on_hash {
                 None => new_schema_id,
@@ -177,7 +187,7 @@ pub async fn upsert_module_route(
 
     let schema_variant_id = match module_kind {
         ModuleKind::WorkspaceBackup => None,
-        ModuleKind::Module => match module_schema_variant_id {
+        ModuleKind::Module | ModuleKind::Delta => match module_schema_variant_id {
             Some(schema_variant_id_string) => {
                 Some(SchemaVariantId::from_str(&schema_variant_id_string)?)
             }
@@ -205,6 +215,7 @@ pub async fn upsert_module_route(
         schema_id: Set(schema_id),
         schema_variant_id: Set(schema_variant_id),
         schema_variant_version: Set(module_schema_variant_version),
+        channel: Set(module_channel.map(|channel| channel.as_str().to_owned())),
         ..Default::default() // all other attributes are `NotSet`
     };
 
@@ -0,0 +1,99 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{extract::Path, Json};
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use module_index_types::ModuleDetailsResponse;
+use sea_orm::{ActiveModelTrait, DbErr, EntityTrait, Set};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::app_state::AppState;
+use crate::whoami::{can_manage_module, WhoamiError};
+use crate::{
+    extract::{Authorization, DbConnection},
+    models::si_module::{self, make_module_details_response, ModuleId},
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum YankModuleError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error(r#"not allowed to yank module "{0}""#)]
+    Forbidden(ModuleId),
+    #[error(r#"Module "{0}" not found"#)]
+    NotFound(ModuleId),
+    #[error("whoami error: {0}")]
+    Whoami(#[from] WhoamiError),
+}
+
+impl IntoResponse for YankModuleError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let error_message = self.to_string();
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+/// Yanks a module version so that it is no longer listed or offered for install, recording the
+/// user of the token as the one who yanked it. Yanking an already yanked version keeps when and by
+/// whom it was first yanked.
+pub async fn yank_module_route(
+    Path(module_id): Path<ModuleId>,
+    Authorization {
+        user_claim,
+        auth_token,
+    }: Authorization,
+    DbConnection(txn): DbConnection,
+    State(state): State<AppState>,
+) -> Result<Json<ModuleDetailsResponse>, YankModuleError> {
+    let module = si_module::Entity::find_by_id(module_id)
+        .one(&txn)
+        .await?
+        .ok_or(YankModuleError::NotFound(module_id))?;
+
+    let user_id = user_claim.user_id().to_string();
+    if !can_manage_module(
+        &user_id,
+        &module.owner_user_id,
+        &auth_token,
+        state.token_emails(),
+    )
+    .await?
+    {
+        return Err(YankModuleError::Forbidden(module_id));
+    }
+
+    if module.yanked_at.is_none() {
+        info!(%module_id, "yanking module");
+        let mut active: si_module::ActiveModel = module.into();
+        active.yanked_at = Set(Some(DateTime::<FixedOffset>::from_naive_utc_and_offset(
+            Utc::now().naive_utc(),
+            Utc.fix(),
+        )));
+        active.yanked_by_display_name = Set(Some(user_id));
+        active.update(&txn).await?;
+    }
+
+    let (module, linked_modules) = si_module::Entity::find_by_id(module_id)
+        .find_with_linked(si_module::SchemaIdReferenceLink)
+        .all(&txn)
+        .await?
+        .first()
+        .cloned()
+        .ok_or(YankModuleError::NotFound(module_id))?;
+
+    txn.commit().await?;
+
+    Ok(Json(make_module_details_response(module, linked_modules)))
+}
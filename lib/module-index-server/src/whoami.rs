@@ -49,3 +49,17 @@ pub async fn is_systeminit_auth_token(
         &get_email_for_auth_token(token, token_map).await?,
     ))
 }
+
+/// Whether the token's user may change a module they uploaded, which System Initiative users may
+/// do for any module.
+pub async fn can_manage_module(
+    user_id: &str,
+    owner_user_id: &str,
+    token: &str,
+    token_map: Arc<Mutex<HashMap<String, String>>>,
+) -> WhoamiResult<bool> {
+    if user_id == owner_user_id {
+        return Ok(true);
+    }
+    is_systeminit_auth_token(token, token_map).await
+}
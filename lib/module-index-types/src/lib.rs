@@ -1,3 +1,5 @@
+use std::{fmt, str::FromStr};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
@@ -7,6 +9,55 @@ pub const MODULE_BASED_ON_HASH_FIELD_NAME: &str = "based_on_hash";
 pub const MODULE_SCHEMA_ID_FIELD_NAME: &str = "schema_id";
 pub const MODULE_SCHEMA_VARIANT_ID_FIELD_NAME: &str = "schema_variant_id";
 pub const MODULE_SCHEMA_VARIANT_VERSION_FIELD_NAME: &str = "schema_variant_version";
+pub const MODULE_CHANNEL_FIELD_NAME: &str = "channel";
+
+/// The release channel a module version is tagged with. Versions without a channel predate
+/// channels and are treated the same as [`Stable`](Self::Stable) ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModuleChannel {
+    Beta,
+    #[default]
+    Stable,
+}
+
+impl ModuleChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Beta => "beta",
+            Self::Stable => "stable",
+        }
+    }
+}
+
+impl fmt::Display for ModuleChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ModuleChannel {
+    type Err = UnknownModuleChannel;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "beta" => Ok(Self::Beta),
+            "stable" => Ok(Self::Stable),
+            _ => Err(UnknownModuleChannel(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownModuleChannel(pub String);
+
+impl fmt::Display for UnknownModuleChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown module channel: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownModuleChannel {}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,6 +67,12 @@ pub struct ModuleRejectionResponse {}
 #[serde(rename_all = "camelCase")]
 pub struct ModulePromotedResponse {}
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetModuleChannelRequest {
+    pub channel: ModuleChannel,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BuiltinsDetailsResponse {
@@ -44,6 +101,12 @@ pub struct ModuleDetailsResponse {
     pub past_hashes: Option<Vec<String>>,
     pub schema_variant_id: Option<String>,
     pub schema_variant_version: Option<String>,
+    #[serde(default)]
+    pub channel: Option<ModuleChannel>,
+    /// Set once the version has been yanked, after which it is no longer listed or offered for
+    /// install, though it can still be downloaded by anyone who already depends on it.
+    #[serde(default)]
+    pub yanked_at: Option<DateTime<Utc>>,
 }
 
 impl ModuleDetailsResponse {
@@ -70,6 +133,15 @@ pub struct ExtraMetadata {
     pub funcs: Vec<FuncMetadata>,
 }
 
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListLatestModulesRequest {
+    /// Only lists versions tagged with this channel, or with none when listing
+    /// [`Stable`](ModuleChannel::Stable) ones. Defaults to stable.
+    #[serde(default)]
+    pub channel: ModuleChannel,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ListLatestModulesResponse {
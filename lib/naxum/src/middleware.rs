@@ -1,5 +1,7 @@
 pub mod ack;
+pub mod concurrency_limit;
 pub mod delay;
+pub mod in_progress;
 pub mod matched_subject;
 pub mod post_process;
 pub mod trace;
@@ -2,10 +2,9 @@ use std::time::Duration;
 
 use tower::Layer;
 
-use super::{on_failure::DefaultOnFailure, on_success::DefaultOnSuccess, service::Ack};
+use crate::middleware::in_progress::{progress_period_for, DEFAULT_PROGRESS_PERIOD};
 
-// Default `ack_wait` period when unset is 30 seconds (a NATS server default)
-const DEFAULT_PROGRESS_PERIOD: Duration = Duration::from_secs(20);
+use super::{on_failure::DefaultOnFailure, on_success::DefaultOnSuccess, service::Ack};
 
 pub struct AckLayer<OnSuccess = DefaultOnSuccess, OnFailure = DefaultOnFailure> {
     pub(crate) on_success: OnSuccess,
@@ -74,6 +73,11 @@ impl<OnSuccess, OnFailure> AckLayer<OnSuccess, OnFailure> {
             progress_period: new_progress_period,
         }
     }
+
+    /// Derives the progress period from the `ack_wait` of the consumer.
+    pub fn ack_wait(self, ack_wait: Duration) -> Self {
+        self.progress_period(progress_period_for(ack_wait))
+    }
 }
 
 impl<S, OnSuccess, OnFailure> Layer<S> for AckLayer<OnSuccess, OnFailure>
@@ -1,6 +1,5 @@
 mod future;
 mod layer;
-mod maintain_progress;
 mod on_failure;
 mod on_success;
 mod service;
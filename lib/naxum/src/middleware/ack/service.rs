@@ -10,13 +10,13 @@ use tower::Service;
 
 use crate::{
     message::{Message, MessageHead},
+    middleware::in_progress::MaintainProgressTask,
     response::Response,
 };
 
 use super::{
     future::ResponseFuture,
     layer::AckLayer,
-    maintain_progress::MaintainProgressTask,
     on_failure::{DefaultOnFailure, OnFailure},
     on_success::{DefaultOnSuccess, OnSuccess},
 };
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use tower::Layer;
+
+use super::{limiter::Limiter, ConcurrencyLimit};
+
+const DEFAULT_NAME: &str = "naxum";
+
+/// Applies a [`ConcurrencyLimit`] to a service.
+///
+/// By default, messages over the limit wait for a permit without bound. Set
+/// [`max_queued`](Self::max_queued) to shed messages once that many are waiting, or use
+/// [`shed`](Self::shed) to never wait at all.
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimitLayer {
+    pub(crate) name: &'static str,
+    pub(crate) max_in_flight: usize,
+    pub(crate) max_queued: Option<usize>,
+}
+
+impl ConcurrencyLimitLayer {
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            name: DEFAULT_NAME,
+            max_in_flight,
+            max_queued: None,
+        }
+    }
+
+    /// Sets the name which labels the metrics of the limit, such as the name of the app.
+    pub fn name(self, name: &'static str) -> Self {
+        Self { name, ..self }
+    }
+
+    /// Sheds messages once this many are waiting for a permit.
+    pub fn max_queued(self, max_queued: usize) -> Self {
+        Self {
+            max_queued: Some(max_queued),
+            ..self
+        }
+    }
+
+    /// Sheds messages as soon as the limit is reached rather than waiting for a permit.
+    pub fn shed(self) -> Self {
+        self.max_queued(0)
+    }
+}
+
+impl<S> Layer<S> for ConcurrencyLimitLayer {
+    type Service = ConcurrencyLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConcurrencyLimit {
+            inner,
+            limiter: Arc::new(Limiter::new(self.name, self.max_in_flight, self.max_queued)),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::info;
+
+/// The permits and queue shared by every clone of a [`ConcurrencyLimit`](super::ConcurrencyLimit)
+/// service.
+#[derive(Debug)]
+pub(crate) struct Limiter {
+    name: &'static str,
+    semaphore: Arc<Semaphore>,
+    max_queued: Option<usize>,
+    queued: AtomicUsize,
+}
+
+impl Limiter {
+    pub(crate) fn new(name: &'static str, max_in_flight: usize, max_queued: Option<usize>) -> Self {
+        Self {
+            name,
+            semaphore: Arc::new(Semaphore::new(max_in_flight)),
+            max_queued,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Acquires a permit, waiting for one if the queue has room. Returns `None` if the message
+    /// should be shed.
+    pub(crate) async fn acquire(self: Arc<Self>) -> Option<InFlight> {
+        let permit = match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let Some(_queued) = Queued::enter(self.clone()) else {
+                    info!(
+                        metrics = true,
+                        monotonic_counter.naxum.concurrency_limit.shed = 1,
+                        naxum.concurrency_limit.name = self.name,
+                    );
+                    return None;
+                };
+                // Errors only if the semaphore is closed, which never happens
+                self.semaphore.clone().acquire_owned().await.ok()?
+            }
+        };
+
+        Some(InFlight::new(self, permit))
+    }
+}
+
+/// Holds a place in the queue until it is dropped, whether a permit was acquired or the waiting
+/// future was cancelled.
+struct Queued(Arc<Limiter>);
+
+impl Queued {
+    fn enter(limiter: Arc<Limiter>) -> Option<Self> {
+        let entered = limiter
+            .queued
+            .fetch_update(
+                Ordering::AcqRel,
+                Ordering::Acquire,
+                |queued| match limiter.max_queued {
+                    Some(max_queued) if queued >= max_queued => None,
+                    _ => Some(queued + 1),
+                },
+            )
+            .is_ok();
+        if !entered {
+            return None;
+        }
+
+        info!(
+            metrics = true,
+            counter.naxum.concurrency_limit.queued = 1,
+            naxum.concurrency_limit.name = limiter.name,
+        );
+        Some(Self(limiter))
+    }
+}
+
+impl Drop for Queued {
+    fn drop(&mut self) {
+        self.0.queued.fetch_sub(1, Ordering::AcqRel);
+        info!(
+            metrics = true,
+            counter.naxum.concurrency_limit.queued = -1,
+            naxum.concurrency_limit.name = self.0.name,
+        );
+    }
+}
+
+/// Holds a permit until the message has been processed.
+pub(crate) struct InFlight {
+    limiter: Arc<Limiter>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl InFlight {
+    fn new(limiter: Arc<Limiter>, permit: OwnedSemaphorePermit) -> Self {
+        info!(
+            metrics = true,
+            counter.naxum.concurrency_limit.in_flight = 1,
+            naxum.concurrency_limit.name = limiter.name,
+        );
+        Self {
+            limiter,
+            _permit: permit,
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        info!(
+            metrics = true,
+            counter.naxum.concurrency_limit.in_flight = -1,
+            naxum.concurrency_limit.name = self.limiter.name,
+        );
+    }
+}
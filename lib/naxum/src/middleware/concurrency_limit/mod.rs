@@ -0,0 +1,198 @@
+//! Limits the number of messages a service processes at once.
+//!
+//! Unlike [`serve_with_incoming_limit`](crate::serve_with_incoming_limit), which stops reading
+//! from the stream once enough messages are being processed, this middleware sits within the
+//! service stack. Messages over the limit either wait for a permit or are shed with a `503
+//! Service Unavailable` response (which, for example, an [`Ack`](super::ack::Ack) middleware
+//! around it turns into a nack so the message is redelivered later).
+//!
+//! The number of messages in flight and waiting are reported as `counter` metrics labelled with
+//! the name of the layer, and every shed message is counted as a `monotonic_counter` metric.
+
+mod layer;
+mod limiter;
+mod service;
+
+pub use self::{layer::ConcurrencyLimitLayer, service::ConcurrencyLimit};
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use bytes::Bytes;
+    use tokio::{sync::Semaphore, task::JoinHandle};
+    use tower::{service_fn, Layer, Service, ServiceExt};
+
+    use super::ConcurrencyLimitLayer;
+    use crate::{response::Response, Message};
+
+    type TestMessage = Message<async_nats::Message>;
+
+    fn message() -> TestMessage {
+        Message::from(async_nats::Message {
+            subject: "test".into(),
+            reply: None,
+            payload: Bytes::new(),
+            headers: None,
+            status: None,
+            description: None,
+            length: 0,
+        })
+    }
+
+    /// A service which holds messages until it is released, counting how many it holds at once.
+    #[derive(Clone)]
+    struct Held {
+        gate: Arc<Semaphore>,
+        in_flight: Arc<AtomicUsize>,
+        max_in_flight: Arc<AtomicUsize>,
+    }
+
+    impl Held {
+        fn new() -> Self {
+            Self {
+                gate: Arc::new(Semaphore::new(0)),
+                in_flight: Default::default(),
+                max_in_flight: Default::default(),
+            }
+        }
+
+        fn service(
+            &self,
+        ) -> impl Service<TestMessage, Response = Response<()>, Error = Infallible, Future = impl Send>
+               + Clone
+               + Send
+               + 'static {
+            let held = self.clone();
+            service_fn(move |_: TestMessage| {
+                let held = held.clone();
+                async move {
+                    let in_flight = held.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    held.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                    let _permit = held.gate.acquire().await;
+                    held.in_flight.fetch_sub(1, Ordering::SeqCst);
+                    Ok(Response::default_ok())
+                }
+            })
+        }
+
+        fn in_flight(&self) -> usize {
+            self.in_flight.load(Ordering::SeqCst)
+        }
+
+        fn release(&self) {
+            self.gate.add_permits(1);
+        }
+    }
+
+    /// Lets the spawned messages run until they are held or waiting for a permit.
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    fn spawn<S>(service: &S) -> JoinHandle<Response<()>>
+    where
+        S: Service<TestMessage, Response = Response<()>, Error = Infallible> + Clone + Send,
+        S: 'static,
+        S::Future: Send,
+    {
+        let service = service.clone();
+        tokio::spawn(async move {
+            match service.oneshot(message()).await {
+                Ok(response) => response,
+                Err(infallible) => match infallible {},
+            }
+        })
+    }
+
+    async fn join(handle: JoinHandle<Response<()>>) -> u16 {
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("message was not processed in time")
+            .expect("message task panicked")
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn waits_for_a_permit_by_default() {
+        let held = Held::new();
+        let service = ConcurrencyLimitLayer::new(2).layer(held.service());
+
+        let handles: Vec<_> = (0..5).map(|_| spawn(&service)).collect();
+        settle().await;
+        assert_eq!(2, held.in_flight());
+
+        held.release();
+        for handle in handles {
+            assert_eq!(200, join(handle).await);
+        }
+        assert_eq!(2, held.max_in_flight.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn sheds_messages_once_the_queue_is_full() {
+        let held = Held::new();
+        let service = ConcurrencyLimitLayer::new(1)
+            .max_queued(1)
+            .layer(held.service());
+
+        let in_flight = spawn(&service);
+        settle().await;
+        let queued = spawn(&service);
+        settle().await;
+        assert_eq!(503, join(spawn(&service)).await);
+        assert_eq!(1, held.in_flight());
+
+        held.release();
+        assert_eq!(200, join(in_flight).await);
+        assert_eq!(200, join(queued).await);
+    }
+
+    #[tokio::test]
+    async fn shed_never_waits() {
+        let held = Held::new();
+        let service = ConcurrencyLimitLayer::new(1).shed().layer(held.service());
+
+        let in_flight = spawn(&service);
+        settle().await;
+        assert_eq!(503, join(spawn(&service)).await);
+
+        held.release();
+        assert_eq!(200, join(in_flight).await);
+        // The permit is returned once the message has been processed
+        assert_eq!(200, join(spawn(&service)).await);
+    }
+
+    #[tokio::test]
+    async fn cancelled_messages_leave_the_queue() {
+        let held = Held::new();
+        let service = ConcurrencyLimitLayer::new(1)
+            .max_queued(1)
+            .layer(held.service());
+
+        let in_flight = spawn(&service);
+        settle().await;
+        let cancelled = spawn(&service);
+        settle().await;
+        cancelled.abort();
+        settle().await;
+
+        let queued = spawn(&service);
+        settle().await;
+        assert!(!queued.is_finished(), "message was shed rather than queued");
+
+        held.release();
+        assert_eq!(200, join(in_flight).await);
+        assert_eq!(200, join(queued).await);
+    }
+}
@@ -0,0 +1,63 @@
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tower::{Layer, Service};
+
+use crate::{response::Response, Message};
+
+use super::{limiter::Limiter, ConcurrencyLimitLayer};
+
+/// Limits the number of messages the inner service processes at once. Clones share the limit.
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimit<S> {
+    pub(crate) inner: S,
+    pub(crate) limiter: Arc<Limiter>,
+}
+
+impl<S> ConcurrencyLimit<S> {
+    pub fn new(inner: S, max_in_flight: usize) -> Self {
+        ConcurrencyLimitLayer::new(max_in_flight).layer(inner)
+    }
+
+    pub fn layer(max_in_flight: usize) -> ConcurrencyLimitLayer {
+        ConcurrencyLimitLayer::new(max_in_flight)
+    }
+}
+
+impl<S, R, B> Service<Message<R>> for ConcurrencyLimit<S>
+where
+    S: Service<Message<R>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+    R: Send + 'static,
+    B: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Message<R>) -> Self::Future {
+        let clone = self.inner.clone();
+        // Take the service that was ready
+        //
+        // See documentation for [`Service`] trait
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            let Some(_in_flight) = limiter.acquire().await else {
+                return Ok(Response::default_service_unavailable());
+            };
+
+            inner.call(req).await
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use pin_project_lite::pin_project;
+use tokio_util::sync::DropGuard;
+
+pin_project! {
+    pub struct ResponseFuture<F> {
+        #[pin]
+        pub(crate) inner: F,
+        // Cancels the associated `MaintainProgressTask` when the response is ready or when this
+        // future is dropped
+        pub(crate) _shutdown_guard: DropGuard,
+    }
+}
+
+impl<F> Future for ResponseFuture<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx)
+    }
+}
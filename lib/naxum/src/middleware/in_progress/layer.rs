@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use tower::Layer;
+
+use super::{progress_period_for, InProgress, DEFAULT_PROGRESS_PERIOD};
+
+#[derive(Clone, Copy, Debug)]
+pub struct InProgressLayer {
+    pub(crate) progress_period: Duration,
+}
+
+impl Default for InProgressLayer {
+    fn default() -> Self {
+        Self {
+            progress_period: DEFAULT_PROGRESS_PERIOD,
+        }
+    }
+}
+
+impl InProgressLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how often an in progress ack is sent.
+    pub fn progress_period(self, progress_period: Duration) -> Self {
+        Self { progress_period }
+    }
+
+    /// Derives how often an in progress ack is sent from the `ack_wait` of the consumer.
+    pub fn ack_wait(self, ack_wait: Duration) -> Self {
+        self.progress_period(progress_period_for(ack_wait))
+    }
+}
+
+impl<S> Layer<S> for InProgressLayer {
+    type Service = InProgress<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        InProgress {
+            inner,
+            progress_period: self.progress_period,
+        }
+    }
+}
@@ -12,7 +12,7 @@ pub struct MaintainProgressTask {
 }
 
 impl MaintainProgressTask {
-    const NAME: &'static str = "Naxum::Ack::MaintainProgressTask";
+    const NAME: &'static str = "Naxum::InProgress::MaintainProgressTask";
 
     pub fn new(
         acker: Arc<Acker>,
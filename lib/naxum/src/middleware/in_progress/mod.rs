@@ -0,0 +1,227 @@
+//! Extends the ack deadline of a JetStream message while it is being processed.
+//!
+//! A JetStream consumer redelivers a message that is not acked within its `ack_wait`, so a
+//! handler that runs longer than that has its message redelivered while it is still working on
+//! it. This middleware sends an in progress ack right away and then periodically for as long as
+//! the inner service's future is pending, which resets the consumer's timer each time.
+//!
+//! The [`Ack`](super::ack::Ack) middleware already does this for the messages it acks, so this is
+//! for stacks which ack some other way, such as from within the handler.
+
+use std::time::Duration;
+
+mod future;
+mod layer;
+mod maintain_progress;
+mod service;
+
+pub(crate) use self::maintain_progress::MaintainProgressTask;
+pub use self::{layer::InProgressLayer, service::InProgress};
+
+// Default `ack_wait` period when unset is 30 seconds (a NATS server default)
+pub(crate) const DEFAULT_PROGRESS_PERIOD: Duration = Duration::from_secs(20);
+
+/// Returns a progress period which comfortably resets a consumer's `ack_wait` before it elapses.
+pub fn progress_period_for(ack_wait: Duration) -> Duration {
+    ack_wait * 2 / 3
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, io, time::Duration};
+
+    use async_nats::jetstream;
+    use bytes::Bytes;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        net::{TcpListener, TcpStream},
+        sync::mpsc,
+        time,
+    };
+    use tower::{service_fn, Layer, ServiceExt};
+
+    use super::{progress_period_for, InProgressLayer};
+    use crate::{response::Response, Message};
+
+    const ACK_SUBJECT: &str = "$JS.ACK.TEST.consumer.1.1.1.0.0";
+    const PROGRESS_PERIOD: Duration = Duration::from_millis(50);
+
+    /// A NATS server which accepts a single client and forwards the payloads it publishes.
+    async fn fake_nats_server() -> (jetstream::Context, mpsc::UnboundedReceiver<(String, Bytes)>) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind fake nats server");
+        let addr = listener.local_addr().expect("failed to get local addr");
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            if let Ok((stream, _)) = listener.accept().await {
+                let _ = serve_client(stream, tx).await;
+            }
+        });
+
+        let client = async_nats::connect(format!("nats://{addr}"))
+            .await
+            .expect("failed to connect to fake nats server");
+        (jetstream::new(client), rx)
+    }
+
+    async fn serve_client(
+        stream: TcpStream,
+        published: mpsc::UnboundedSender<(String, Bytes)>,
+    ) -> io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        writer
+            .write_all(b"INFO {\"server_id\":\"test\",\"version\":\"2.10.0\",\"proto\":1,\"headers\":true,\"max_payload\":1048576}\r\n")
+            .await?;
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let mut args = line.split_whitespace();
+            match args.next() {
+                Some("PING") => writer.write_all(b"PONG\r\n").await?,
+                Some("PUB") => {
+                    let args: Vec<&str> = args.collect();
+                    let (Some(subject), Some(Ok(len))) =
+                        (args.first(), args.last().map(|len| len.parse::<usize>()))
+                    else {
+                        return Ok(());
+                    };
+                    // The payload is followed by a CRLF
+                    let mut payload = vec![0; len + 2];
+                    reader.read_exact(&mut payload).await?;
+                    payload.truncate(len);
+                    let _ = published.send((subject.to_string(), payload.into()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn message(context: &jetstream::Context, reply: Option<&str>) -> Message<jetstream::Message> {
+        Message::from(jetstream::Message {
+            message: async_nats::Message {
+                subject: "test".into(),
+                reply: reply.map(Into::into),
+                payload: Bytes::from_static(b"payload"),
+                headers: None,
+                status: None,
+                description: None,
+                length: 0,
+            },
+            context: context.clone(),
+        })
+    }
+
+    /// Waits a few progress periods for any acks still on their way, then takes them.
+    async fn acks(published: &mut mpsc::UnboundedReceiver<(String, Bytes)>) -> usize {
+        time::sleep(PROGRESS_PERIOD * 3).await;
+        let mut acks = 0;
+        while let Ok((subject, payload)) = published.try_recv() {
+            assert_eq!(ACK_SUBJECT, subject);
+            assert_eq!(Bytes::from_static(b"+WPI"), payload);
+            acks += 1;
+        }
+        acks
+    }
+
+    #[test]
+    fn progress_period_resets_the_ack_wait_before_it_elapses() {
+        assert_eq!(
+            Duration::from_secs(20),
+            progress_period_for(Duration::from_secs(30))
+        );
+        assert_eq!(
+            Duration::from_secs(20),
+            InProgressLayer::new()
+                .ack_wait(Duration::from_secs(30))
+                .progress_period
+        );
+    }
+
+    #[tokio::test]
+    async fn acks_progress_while_the_inner_service_runs() {
+        let (context, mut published) = fake_nats_server().await;
+        let service = InProgressLayer::new()
+            .progress_period(PROGRESS_PERIOD)
+            .layer(service_fn(
+                |message: Message<jetstream::Message>| async move {
+                    assert_eq!(b"payload".as_slice(), message.payload.as_ref());
+                    time::sleep(PROGRESS_PERIOD * 3).await;
+                    Ok::<_, Infallible>(Response::<()>::default_ok())
+                },
+            ));
+
+        let response = service
+            .oneshot(message(&context, Some(ACK_SUBJECT)))
+            .await
+            .expect("inner service is infallible");
+        assert_eq!(200, response.status().as_u16());
+
+        // An ack right away and one per elapsed period
+        assert!(acks(&mut published).await >= 3);
+        // Nothing more once the response is ready
+        assert_eq!(0, acks(&mut published).await);
+    }
+
+    #[tokio::test]
+    async fn returns_inner_errors_and_stops_acking() {
+        let (context, mut published) = fake_nats_server().await;
+        let service = InProgressLayer::new()
+            .progress_period(PROGRESS_PERIOD)
+            .layer(service_fn(|_: Message<jetstream::Message>| async move {
+                time::sleep(PROGRESS_PERIOD * 2).await;
+                Err::<Response<()>, _>("inner failure")
+            }));
+
+        let result = service.oneshot(message(&context, Some(ACK_SUBJECT))).await;
+        assert!(matches!(result, Err("inner failure")));
+
+        assert!(acks(&mut published).await >= 2);
+        assert_eq!(0, acks(&mut published).await);
+    }
+
+    #[tokio::test]
+    async fn stops_acking_when_the_response_is_dropped() {
+        let (context, mut published) = fake_nats_server().await;
+        let service = InProgressLayer::new()
+            .progress_period(PROGRESS_PERIOD)
+            .layer(service_fn(|_: Message<jetstream::Message>| async move {
+                time::sleep(Duration::from_secs(60)).await;
+                Ok::<_, Infallible>(Response::<()>::default_ok())
+            }));
+
+        let result = time::timeout(
+            PROGRESS_PERIOD * 3,
+            service.oneshot(message(&context, Some(ACK_SUBJECT))),
+        )
+        .await;
+        assert!(result.is_err(), "inner service should not have completed");
+
+        assert!(acks(&mut published).await >= 3);
+        assert_eq!(0, acks(&mut published).await);
+    }
+
+    #[tokio::test]
+    async fn failed_acks_do_not_fail_the_message() {
+        let (context, mut published) = fake_nats_server().await;
+        let service = InProgressLayer::new()
+            .progress_period(PROGRESS_PERIOD)
+            .layer(service_fn(|_: Message<jetstream::Message>| async move {
+                time::sleep(PROGRESS_PERIOD * 2).await;
+                Ok::<_, Infallible>(Response::<()>::default_ok())
+            }));
+
+        // Acking a message without a reply subject fails
+        let response = service
+            .oneshot(message(&context, None))
+            .await
+            .expect("inner service is infallible");
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!(0, acks(&mut published).await);
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use async_nats::jetstream;
+use tokio_util::sync::CancellationToken;
+use tower::Service;
+
+use crate::message::Message;
+
+use super::{
+    future::ResponseFuture, layer::InProgressLayer, MaintainProgressTask, DEFAULT_PROGRESS_PERIOD,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct InProgress<S> {
+    pub(crate) inner: S,
+    pub(crate) progress_period: Duration,
+}
+
+impl<S> InProgress<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            progress_period: DEFAULT_PROGRESS_PERIOD,
+        }
+    }
+
+    pub fn layer() -> InProgressLayer {
+        InProgressLayer::new()
+    }
+}
+
+impl<S> Service<Message<jetstream::Message>> for InProgress<S>
+where
+    S: Service<Message<jetstream::Message>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Message<jetstream::Message>) -> Self::Future {
+        let (jetstream_message, extensions) = req.split();
+
+        // Split an acker off of a copy of the message, leaving the original for the inner
+        // service to ack however it sees fit
+        let (_, acker) = jetstream::Message {
+            message: jetstream_message.message.clone(),
+            context: jetstream_message.context.clone(),
+        }
+        .split();
+
+        let task_shutdown = CancellationToken::new();
+        let task =
+            MaintainProgressTask::new(Arc::new(acker), self.progress_period, task_shutdown.clone());
+        tokio::spawn(task.run());
+
+        let inner = self
+            .inner
+            .call(Message::new_with_extensions(jetstream_message, extensions));
+
+        ResponseFuture {
+            inner,
+            _shutdown_guard: task_shutdown.drop_guard(),
+        }
+    }
+}
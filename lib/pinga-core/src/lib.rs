@@ -1,9 +1,20 @@
+use std::time::Duration;
+
 use si_data_nats::{async_nats, jetstream};
 
 const NATS_WORK_QUEUE_STREAM_NAME: &str = "PINGA_JOBS";
 const NATS_WORK_QUEUE_STREAM_SUBJECTS: &[&str] = &["pinga.jobs.>"];
 
+const NATS_SCHEDULED_STREAM_NAME: &str = "PINGA_SCHEDULED_JOBS";
+const NATS_SCHEDULED_STREAM_SUBJECTS: &[&str] = &["pinga.scheduled.>"];
+
+const NATS_DEAD_LETTER_STREAM_NAME: &str = "PINGA_DEAD_LETTERS";
+const NATS_DEAD_LETTER_STREAM_SUBJECTS: &[&str] = &["pinga.dead_letters.>"];
+const NATS_DEAD_LETTER_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
 pub const REPLY_INBOX_HEADER_NAME: &str = "X-Reply-Inbox";
+/// Set on jobs which are not to run before a given time, as an RFC 3339 timestamp.
+pub const RUN_AT_HEADER_NAME: &str = "X-Run-At";
 
 pub async fn pinga_work_queue(
     context: &jetstream::Context,
@@ -30,6 +41,61 @@ pub async fn pinga_work_queue(
     Ok(stream)
 }
 
+/// Gets or creates the stream of jobs which are not to run before a given time. Pinga moves each of
+/// them to the work queue once it is due, so that waiting jobs never hold up due ones.
+pub async fn pinga_scheduled_queue(
+    context: &jetstream::Context,
+    prefix: Option<&str>,
+) -> Result<async_nats::jetstream::stream::Stream, async_nats::jetstream::context::CreateStreamError>
+{
+    let subjects: Vec<_> = NATS_SCHEDULED_STREAM_SUBJECTS
+        .iter()
+        .map(|suffix| subject::nats_subject(prefix, suffix).to_string())
+        .collect();
+
+    let stream = context
+        .get_or_create_stream(async_nats::jetstream::stream::Config {
+            name: nats_stream_name(prefix, NATS_SCHEDULED_STREAM_NAME),
+            description: Some("Pinga jobs waiting until they are due".to_owned()),
+            retention: async_nats::jetstream::stream::RetentionPolicy::WorkQueue,
+            discard: async_nats::jetstream::stream::DiscardPolicy::New,
+            allow_direct: true,
+            subjects,
+            ..Default::default()
+        })
+        .await?;
+
+    Ok(stream)
+}
+
+/// Gets or creates the stream of jobs which failed permanently, which holds them until they are
+/// replayed or expire.
+pub async fn pinga_dead_letter_queue(
+    context: &jetstream::Context,
+    prefix: Option<&str>,
+) -> Result<async_nats::jetstream::stream::Stream, async_nats::jetstream::context::CreateStreamError>
+{
+    let subjects: Vec<_> = NATS_DEAD_LETTER_STREAM_SUBJECTS
+        .iter()
+        .map(|suffix| subject::nats_subject(prefix, suffix).to_string())
+        .collect();
+
+    let stream = context
+        .get_or_create_stream(async_nats::jetstream::stream::Config {
+            name: nats_stream_name(prefix, NATS_DEAD_LETTER_STREAM_NAME),
+            description: Some("Pinga jobs which failed permanently".to_owned()),
+            retention: async_nats::jetstream::stream::RetentionPolicy::Limits,
+            discard: async_nats::jetstream::stream::DiscardPolicy::Old,
+            max_age: NATS_DEAD_LETTER_MAX_AGE,
+            allow_direct: true,
+            subjects,
+            ..Default::default()
+        })
+        .await?;
+
+    Ok(stream)
+}
+
 fn nats_stream_name(prefix: Option<&str>, suffix: impl AsRef<str>) -> String {
     let suffix = suffix.as_ref();
 
@@ -44,6 +110,9 @@ pub mod subject {
 
     const INCOMING_SUBJECT: &str = "pinga.jobs.*.*.*";
     const SUBJECT_PREFIX: &str = "pinga.jobs";
+    const SCHEDULED_INCOMING_SUBJECT: &str = "pinga.scheduled.*.*.*";
+    const SCHEDULED_SUBJECT_PREFIX: &str = "pinga.scheduled";
+    const DEAD_LETTER_SUBJECT_PREFIX: &str = "pinga.dead_letters";
 
     #[inline]
     pub fn incoming(prefix: Option<&str>) -> Subject {
@@ -66,6 +135,45 @@ pub mod subject {
         )
     }
 
+    #[inline]
+    pub fn incoming_scheduled(prefix: Option<&str>) -> Subject {
+        nats_subject(prefix, SCHEDULED_INCOMING_SUBJECT)
+    }
+
+    #[inline]
+    pub fn scheduled_job(
+        prefix: Option<&str>,
+        workspace_id: &str,
+        change_set_id: &str,
+        kind: &str,
+    ) -> Subject {
+        nats_subject(
+            prefix,
+            format!("{SCHEDULED_SUBJECT_PREFIX}.{workspace_id}.{change_set_id}.{kind}"),
+        )
+    }
+
+    #[inline]
+    pub fn dead_letter(
+        prefix: Option<&str>,
+        workspace_id: &str,
+        change_set_id: &str,
+        kind: &str,
+    ) -> Subject {
+        nats_subject(
+            prefix,
+            format!("{DEAD_LETTER_SUBJECT_PREFIX}.{workspace_id}.{change_set_id}.{kind}"),
+        )
+    }
+
+    #[inline]
+    pub fn dead_letters_for_workspace(prefix: Option<&str>, workspace_id: &str) -> Subject {
+        nats_subject(
+            prefix,
+            format!("{DEAD_LETTER_SUBJECT_PREFIX}.{workspace_id}.>"),
+        )
+    }
+
     pub(crate) fn nats_subject(prefix: Option<&str>, suffix: impl AsRef<str>) -> Subject {
         let suffix = suffix.as_ref();
         match prefix {
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use dal::DalContextBuilder;
 
-use crate::server::ServerMetadata;
+use crate::{coalesce::JobCoalescer, server::ServerMetadata};
 
 /// Application state.
 #[derive(Clone, Debug)]
@@ -11,6 +11,8 @@ pub struct AppState {
     pub concurrency_limit: usize,
     /// DAL context builder for each processing request
     pub ctx_builder: DalContextBuilder,
+    /// Coalesces duplicate jobs for the same change set
+    pub(crate) coalescer: JobCoalescer,
 }
 
 impl AppState {
@@ -24,6 +26,7 @@ impl AppState {
             metadata,
             concurrency_limit,
             ctx_builder,
+            coalescer: JobCoalescer::default(),
         }
     }
 }
@@ -0,0 +1,362 @@
+//! Coalescing of duplicate jobs, so that rapid edits which each enqueue a job for the same change
+//! set do not each pay for a full run.
+//!
+//! While a coalescable job runs for a change set, further jobs of the same kind for that change set
+//! are not run alongside it. Instead, the latest of them is kept as pending and run once the current
+//! job finishes, on behalf of all of them. This relies on coalescable jobs not depending on their
+//! arguments: a [`DependentValuesUpdate`](dal::job::definition::DependentValuesUpdate) processes
+//! every dependent value root of the change set, whichever job enqueued it.
+//!
+//! The message of a coalesced job is only acked once a run on its behalf has finished, so its
+//! handler waits for that run. If the run is abandoned, for instance because its handler panicked,
+//! the waiting handlers fail and their messages are redelivered.
+//!
+//! Jobs are only coalesced within a single pinga instance.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use dal::{job::consumer::JobInfo, ChangeSetId, WorkspacePk};
+use si_data_nats::Subject;
+use tokio::sync::oneshot;
+
+/// The kinds of jobs which are coalesced.
+const COALESCABLE_JOB_KINDS: &[&str] = &["DependentValuesUpdate"];
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct CoalesceKey {
+    kind: String,
+    workspace_pk: Option<WorkspacePk>,
+    change_set_id: ChangeSetId,
+}
+
+impl CoalesceKey {
+    /// Returns the key of a job, or `None` if jobs of its kind are not coalesced.
+    pub(crate) fn for_job(job_info: &JobInfo) -> Option<Self> {
+        COALESCABLE_JOB_KINDS
+            .contains(&job_info.kind.as_str())
+            .then(|| Self {
+                kind: job_info.kind.clone(),
+                workspace_pk: job_info.access_builder.tenancy().workspace_pk_opt(),
+                change_set_id: job_info.visibility.change_set_id,
+            })
+    }
+
+    pub(crate) fn kind(&self) -> &str {
+        &self.kind
+    }
+}
+
+/// A job to run, along with the callers waiting for a reply once it has run.
+#[derive(Debug)]
+pub(crate) struct CoalescedJob {
+    pub(crate) job_info: JobInfo,
+    pub(crate) reply_subjects: Vec<Subject>,
+    /// How many jobs this one runs on behalf of, besides itself.
+    pub(crate) coalesced: usize,
+    /// Whether a failed run is dead-lettered rather than redelivered, which is the case once the
+    /// message of any job it runs on behalf of is on its last delivery.
+    pub(crate) last_attempt: bool,
+    /// The handlers of the jobs this one runs on behalf of, waiting for it to have run.
+    waiters: Vec<oneshot::Sender<()>>,
+}
+
+impl CoalescedJob {
+    pub(crate) fn new(
+        job_info: JobInfo,
+        reply_subject: Option<Subject>,
+        last_attempt: bool,
+    ) -> Self {
+        Self {
+            job_info,
+            reply_subjects: reply_subject.into_iter().collect(),
+            coalesced: 0,
+            last_attempt,
+            waiters: Vec::new(),
+        }
+    }
+
+    /// Takes the handlers waiting for the job to have run, which are to be notified once it has.
+    pub(crate) fn take_waiters(&mut self) -> Waiters {
+        Waiters(std::mem::take(&mut self.waiters))
+    }
+
+    /// Replaces the job with a later one, which runs on behalf of both.
+    fn merge(&mut self, later: Self) {
+        let blocking = self.job_info.blocking || later.job_info.blocking;
+        self.job_info = later.job_info;
+        self.job_info.blocking = blocking;
+        self.reply_subjects.extend(later.reply_subjects);
+        self.waiters.extend(later.waiters);
+        self.coalesced += later.coalesced + 1;
+        self.last_attempt |= later.last_attempt;
+    }
+}
+
+/// The handlers waiting for a job to have run. Dropping them without notifying them fails them, so
+/// that their messages are redelivered.
+#[derive(Debug)]
+pub(crate) struct Waiters(Vec<oneshot::Sender<()>>);
+
+impl Waiters {
+    /// Lets the waiting handlers return, once the run has succeeded or was dead-lettered.
+    pub(crate) fn notify(self) {
+        for waiter in self.0 {
+            // The handler is gone if its message was abandoned, which leaves nothing to notify
+            let _ = waiter.send(());
+        }
+    }
+}
+
+/// What to do with a coalescable job.
+#[derive(Debug)]
+pub(crate) enum Begin {
+    /// Run the job now, then the jobs which the guard yields.
+    Run(CoalescedJob, RunGuard),
+    /// The job was merged into the pending job of a running one. The receiver resolves once the
+    /// pending job has run, or errors if the run was abandoned.
+    Wait(oneshot::Receiver<()>),
+}
+
+/// Marks a key as running for as long as it is held. Dropping the guard before it runs out of jobs,
+/// say because the run panicked, clears the key along with its pending job, so that later jobs
+/// for the key run again.
+#[derive(Debug)]
+pub(crate) struct RunGuard {
+    coalescer: JobCoalescer,
+    key: CoalesceKey,
+    finished: bool,
+}
+
+impl RunGuard {
+    /// Returns the job to run next for the key, if any job was merged while the last one ran.
+    pub(crate) fn next(&mut self) -> Option<CoalescedJob> {
+        let next = self.coalescer.finish(&self.key);
+        self.finished = next.is_none();
+        next
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.coalescer.abandon(&self.key);
+        }
+    }
+}
+
+/// Tracks the running and pending coalescable jobs by change set.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct JobCoalescer {
+    /// Every key with a running job, with the job to run after it, if any.
+    jobs: Arc<Mutex<HashMap<CoalesceKey, Option<CoalescedJob>>>>,
+}
+
+impl JobCoalescer {
+    /// Runs the job now if no job is running for its key, or else merges it into the pending job
+    /// of the running one.
+    pub(crate) fn begin(&self, key: &CoalesceKey, mut job: CoalescedJob) -> Begin {
+        let mut jobs = self.jobs.lock().unwrap_or_else(|err| err.into_inner());
+        match jobs.entry(key.clone()) {
+            Entry::Occupied(mut entry) => {
+                let (waiter, ran) = oneshot::channel();
+                job.waiters.push(waiter);
+                match entry.get_mut() {
+                    Some(pending) => pending.merge(job),
+                    None => {
+                        entry.insert(Some(job));
+                    }
+                }
+                Begin::Wait(ran)
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(None);
+                Begin::Run(
+                    job,
+                    RunGuard {
+                        coalescer: self.clone(),
+                        key: key.clone(),
+                        finished: false,
+                    },
+                )
+            }
+        }
+    }
+
+    fn finish(&self, key: &CoalesceKey) -> Option<CoalescedJob> {
+        let mut jobs = self.jobs.lock().unwrap_or_else(|err| err.into_inner());
+        let next = jobs.get_mut(key).and_then(Option::take);
+        if next.is_none() {
+            jobs.remove(key);
+        }
+        next
+    }
+
+    /// Clears a key whose run was abandoned. Dropping its pending job fails the handlers waiting
+    /// for it.
+    fn abandon(&self, key: &CoalesceKey) {
+        let mut jobs = self.jobs.lock().unwrap_or_else(|err| err.into_inner());
+        jobs.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use dal::{AccessBuilder, HistoryActor, Tenancy, Visibility};
+
+    use super::*;
+
+    fn job(kind: &str, change_set_id: ChangeSetId, blocking: bool) -> CoalescedJob {
+        CoalescedJob::new(
+            JobInfo {
+                id: ulid::Ulid::new().to_string(),
+                kind: kind.to_owned(),
+                created_at: Utc::now(),
+                arg: serde_json::Value::Null,
+                access_builder: AccessBuilder::new(
+                    Tenancy::new(WorkspacePk::new()),
+                    HistoryActor::SystemInit,
+                ),
+                visibility: Visibility::new(change_set_id),
+                blocking,
+                run_at: None,
+                failed_attempts: Vec::new(),
+            },
+            None,
+            false,
+        )
+    }
+
+    fn key(job: &CoalescedJob) -> CoalesceKey {
+        CoalesceKey::for_job(&job.job_info).expect("job should be coalescable")
+    }
+
+    #[test]
+    fn only_coalescable_kinds_have_keys() {
+        let change_set_id = ChangeSetId::new();
+        assert!(CoalesceKey::for_job(&job("ActionJob", change_set_id, false).job_info).is_none());
+        assert!(
+            CoalesceKey::for_job(&job("DependentValuesUpdate", change_set_id, false).job_info)
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn jobs_for_a_running_key_wait_for_one_merged_run() {
+        let coalescer = JobCoalescer::default();
+        let change_set_id = ChangeSetId::new();
+        let first = job("DependentValuesUpdate", change_set_id, false);
+        let key = key(&first);
+
+        let Begin::Run(mut running, mut run) = coalescer.begin(&key, first) else {
+            panic!("first job should run");
+        };
+        assert!(running.take_waiters().0.is_empty());
+
+        let second = job("DependentValuesUpdate", change_set_id, true);
+        let third = job("DependentValuesUpdate", change_set_id, false);
+        let third_id = third.job_info.id.clone();
+        let Begin::Wait(mut second_ran) = coalescer.begin(&key, second) else {
+            panic!("second job should wait");
+        };
+        let Begin::Wait(third_ran) = coalescer.begin(&key, third) else {
+            panic!("third job should wait");
+        };
+
+        // The waiting jobs are not done until the run on their behalf is
+        let mut next = run.next().expect("merged job should run next");
+        assert!(second_ran.try_recv().is_err());
+        assert_eq!(third_id, next.job_info.id);
+        assert_eq!(1, next.coalesced);
+        assert!(next.job_info.blocking);
+        assert!(!next.last_attempt);
+
+        next.take_waiters().notify();
+        second_ran.await.expect("second job should have run");
+        third_ran.await.expect("third job should have run");
+
+        assert!(run.next().is_none());
+        drop(run);
+        assert!(matches!(
+            coalescer.begin(&key, job("DependentValuesUpdate", change_set_id, false)),
+            Begin::Run(..)
+        ));
+    }
+
+    #[test]
+    fn merged_jobs_are_on_their_last_attempt_if_any_of_them_is() {
+        let change_set_id = ChangeSetId::new();
+        let mut pending = job("DependentValuesUpdate", change_set_id, false);
+        let mut last = job("DependentValuesUpdate", change_set_id, false);
+        last.last_attempt = true;
+
+        pending.merge(last);
+        assert!(pending.last_attempt);
+        pending.merge(job("DependentValuesUpdate", change_set_id, false));
+        assert!(pending.last_attempt);
+    }
+
+    #[tokio::test]
+    async fn failed_runs_fail_their_waiters() {
+        let coalescer = JobCoalescer::default();
+        let change_set_id = ChangeSetId::new();
+        let first = job("DependentValuesUpdate", change_set_id, false);
+        let key = key(&first);
+
+        let Begin::Run(_running, mut run) = coalescer.begin(&key, first) else {
+            panic!("first job should run");
+        };
+        let Begin::Wait(ran) =
+            coalescer.begin(&key, job("DependentValuesUpdate", change_set_id, false))
+        else {
+            panic!("second job should wait");
+        };
+
+        // As if the merged run failed with deliveries left
+        let mut next = run.next().expect("merged job should run next");
+        drop(next.take_waiters());
+        assert!(ran.await.is_err());
+        assert!(run.next().is_none());
+    }
+
+    #[tokio::test]
+    async fn abandoned_runs_fail_their_waiters_and_free_the_key() {
+        let coalescer = JobCoalescer::default();
+        let change_set_id = ChangeSetId::new();
+        let first = job("DependentValuesUpdate", change_set_id, false);
+        let key = key(&first);
+
+        let Begin::Run(_running, run) = coalescer.begin(&key, first) else {
+            panic!("first job should run");
+        };
+        let Begin::Wait(ran) =
+            coalescer.begin(&key, job("DependentValuesUpdate", change_set_id, false))
+        else {
+            panic!("second job should wait");
+        };
+
+        // As if the handler running the key panicked
+        drop(run);
+        assert!(ran.await.is_err());
+        assert!(matches!(
+            coalescer.begin(&key, job("DependentValuesUpdate", change_set_id, false)),
+            Begin::Run(..)
+        ));
+    }
+
+    #[test]
+    fn keys_are_per_change_set() {
+        let coalescer = JobCoalescer::default();
+        let one = job("DependentValuesUpdate", ChangeSetId::new(), false);
+        let other = job("DependentValuesUpdate", ChangeSetId::new(), false);
+        let (one_key, other_key) = (key(&one), key(&other));
+
+        let Begin::Run(_, _one_run) = coalescer.begin(&one_key, one) else {
+            panic!("job should run");
+        };
+        assert!(matches!(coalescer.begin(&other_key, other), Begin::Run(..)));
+    }
+}
@@ -3,6 +3,7 @@ use std::{result, str::Utf8Error, sync::Arc};
 use dal::{
     job::{
         consumer::{JobConsumer, JobConsumerError, JobInfo},
+        dead_letter::{DeadLetter, JobAttempt},
         definition::{compute_validation::ComputeValidation, ActionJob, DependentValuesUpdate},
         producer::BlockingJobError,
     },
@@ -11,19 +12,26 @@ use dal::{
 use naxum::{
     extract::{message_parts::Headers, State},
     response::{IntoResponse, Response},
-    Json,
+    Extensions, Json, Message,
 };
 use pinga_core::REPLY_INBOX_HEADER_NAME;
-use si_data_nats::Subject;
+use si_data_nats::{async_nats::jetstream, Subject};
 use telemetry::prelude::*;
 use telemetry_nats::propagation;
+use telemetry_utils::metric;
 use thiserror::Error;
 
-use crate::{app_state::AppState, server::ServerMetadata};
+use crate::{
+    app_state::AppState,
+    coalesce::{Begin, CoalesceKey, CoalescedJob},
+    server::{ServerMetadata, CONSUMER_MAX_DELIVER},
+};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum HandlerError {
+    #[error("the run which this job was coalesced into was abandoned or failed")]
+    CoalescedRunAbandoned,
     #[error("job consumer error: {0}")]
     JobConsumer(#[from] JobConsumerError),
     #[error("unknown job kind {0}")]
@@ -34,6 +42,27 @@ pub enum HandlerError {
 
 type Result<T> = result::Result<T, HandlerError>;
 
+/// How many times the message of a job was delivered, so that a failed job is only dead-lettered
+/// once the consumer will not deliver it again.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Delivery {
+    delivered: i64,
+}
+
+impl Delivery {
+    /// Records the delivery of a message in its extensions.
+    pub(crate) fn record(mut req: Message<jetstream::Message>) -> Message<jetstream::Message> {
+        if let Some(delivered) = req.info().ok().map(|info| info.delivered) {
+            req.extensions_mut().insert(Self { delivered });
+        }
+        req
+    }
+
+    fn is_last(&self) -> bool {
+        self.delivered >= CONSUMER_MAX_DELIVER
+    }
+}
+
 impl IntoResponse for HandlerError {
     fn into_response(self) -> Response {
         error!(si.error.message = ?self, "failed to process message");
@@ -45,6 +74,7 @@ pub async fn process_request(
     State(state): State<AppState>,
     subject: Subject,
     Headers(maybe_headers): Headers,
+    extensions: Extensions,
     Json(job_info): Json<JobInfo>,
 ) -> Result<()> {
     let workspace_id_str = job_info
@@ -66,16 +96,62 @@ pub async fn process_request(
         None => None,
     };
 
-    execute_job(
-        state.metadata,
+    // A message which is not redelivered gets a single attempt
+    let last_attempt = extensions.get::<Delivery>().map_or(true, Delivery::is_last);
+    let job = CoalescedJob::new(job_info, reply_subject, last_attempt);
+    let Some(key) = CoalesceKey::for_job(&job.job_info) else {
+        return execute_job(
+            state.metadata,
+            state.concurrency_limit,
+            state.ctx_builder,
+            subject,
+            job,
+        )
+        .await;
+    };
+
+    let (job, mut run) = match state.coalescer.begin(&key, job) {
+        Begin::Run(job, run) => (job, run),
+        // Leave the job to the run which follows the one in flight for the same change set, and
+        // only have the message acked once that run has finished
+        Begin::Wait(ran) => {
+            metric!(
+                monotonic_counter.pinga.job.coalesced = 1,
+                job_kind = key.kind()
+            );
+            debug!(job.kind = key.kind(), "coalesced job into pending run");
+            return ran.await.map_err(|_| HandlerError::CoalescedRunAbandoned);
+        }
+    };
+    let result = execute_coalesced_job(&state, &subject, job).await;
+    // Later runs are on behalf of the waiting handlers, which each learn how theirs went
+    while let Some(next) = run.next() {
+        let _ = execute_coalesced_job(&state, &subject, next).await;
+    }
+
+    result
+}
+
+async fn execute_coalesced_job(
+    state: &AppState,
+    subject: &Subject,
+    mut job: CoalescedJob,
+) -> Result<()> {
+    let waiters = job.take_waiters();
+    let result = execute_job(
+        state.metadata.clone(),
         state.concurrency_limit,
-        state.ctx_builder,
-        subject,
-        reply_subject,
-        job_info,
+        state.ctx_builder.clone(),
+        subject.clone(),
+        job,
     )
     .await;
-    Ok(())
+    // Dropping the waiting handlers fails them, so that their messages get another attempt too
+    if result.is_ok() {
+        waiters.notify();
+    }
+
+    result
 }
 
 #[instrument(
@@ -87,11 +163,13 @@ pub async fn process_request(
         // concurrency.at_capacity = concurrency_limit == concurrency_count,
         // concurrency.count = concurrency_count,
         concurrency.limit = concurrency_limit,
-        job.id = job_info.id,
+        job.coalesced = job.coalesced,
+        job.id = job.job_info.id,
         job.instance = metadata.instance_id(),
         job.invoked_args = Empty,
-        job.invoked_name = job_info.kind,
+        job.invoked_name = job.job_info.kind,
         job.invoked_provider = metadata.job_invoked_provider(),
+        job.run_at = Empty,
         job.trigger = "pubsub",
         messaging.destination = Empty,
         messaging.destination_kind = "topic",
@@ -100,8 +178,8 @@ pub async fn process_request(
         otel.name = Empty,
         otel.status_code = Empty,
         otel.status_message = Empty,
-        si.change_set.id = %job_info.visibility.change_set_id,
-        si.job.blocking = job_info.blocking,
+        si.change_set.id = %job.job_info.visibility.change_set_id,
+        si.job.blocking = job.job_info.blocking,
         si.workspace.id = Empty,
     )
 )]
@@ -110,11 +188,19 @@ async fn execute_job(
     concurrency_limit: usize,
     ctx_builder: DalContextBuilder,
     subject: Subject,
-    maybe_reply_subject: Option<Subject>,
-    job_info: JobInfo,
-) {
+    job: CoalescedJob,
+) -> Result<()> {
     let span = current_span_for_instrument_at!("info");
+    let CoalescedJob {
+        job_info,
+        reply_subjects,
+        last_attempt,
+        ..
+    } = job;
     let id = job_info.id.clone();
+    if let Some(run_at) = job_info.run_at {
+        span.record("job.run_at", run_at.to_rfc3339());
+    }
 
     let arg_str = serde_json::to_string(&job_info.arg)
         .unwrap_or_else(|_| "arg failed to serialize".to_string());
@@ -145,11 +231,21 @@ async fn execute_job(
     span.record("otel.name", otel_name.as_str());
     span.record("si.workspace.id", workspace_id_str);
 
-    let reply_message = match execute_job_inner(ctx_builder.clone(), job_info).await {
+    let reply_message = match execute_job_inner(ctx_builder.clone(), &job_info).await {
         Ok(_) => {
             span.record_ok();
             Ok(())
         }
+        // Callers only get a reply once the job has succeeded or was dead-lettered
+        Err(err) if !last_attempt => {
+            warn!(
+                si.error.message = ?err,
+                job.invocation_id = %id,
+                job.instance = metadata.instance_id(),
+                "job execution failed, leaving it to be redelivered"
+            );
+            return Err(span.record_err(err));
+        }
         Err(err) => {
             error!(
                 error = ?err,
@@ -158,21 +254,26 @@ async fn execute_job(
                 "job execution failed"
             );
             let new_err = Err(BlockingJobError::JobExecution(err.to_string()));
+            dead_letter_job(&ctx_builder, &metadata, job_info, &err).await;
             span.record_err(err);
 
             new_err
         }
     };
 
-    // If a reply subject is set then the caller has requested we publish a reply
-    if let Some(reply_subject) = maybe_reply_subject {
-        if let Ok(message) = serde_json::to_vec(&reply_message) {
+    // Every caller which set a reply subject has requested we publish a reply, including the
+    // callers of the jobs this one ran on behalf of
+    if reply_subjects.is_empty() {
+        return Ok(());
+    }
+    if let Ok(message) = serde_json::to_vec(&reply_message) {
+        for reply_subject in reply_subjects {
             if let Err(err) = ctx_builder
                 .nats_conn()
                 .publish_with_headers(
                     reply_subject,
                     propagation::empty_injected_headers(),
-                    message.into(),
+                    message.clone().into(),
                 )
                 .await
             {
@@ -180,9 +281,40 @@ async fn execute_job(
             };
         }
     }
+
+    Ok(())
+}
+
+/// Keeps a permanently failed job around for inspection and replay.
+async fn dead_letter_job(
+    ctx_builder: &DalContextBuilder,
+    metadata: &ServerMetadata,
+    mut job_info: JobInfo,
+    err: &HandlerError,
+) {
+    job_info
+        .failed_attempts
+        .push(JobAttempt::failed(metadata.instance_id(), err));
+    let dead_letter = DeadLetter::new(job_info);
+
+    match dead_letter.publish(ctx_builder.nats_conn()).await {
+        Ok(()) => {
+            metric!(
+                monotonic_counter.pinga.job.dead_lettered = 1,
+                job_kind = dead_letter.job_info.kind.as_str()
+            );
+        }
+        Err(err) => {
+            error!(
+                si.error.message = ?err,
+                job.invocation_id = %dead_letter.job_info.id,
+                "failed to dead letter job",
+            );
+        }
+    }
 }
 
-async fn execute_job_inner(mut ctx_builder: DalContextBuilder, job_info: JobInfo) -> Result<()> {
+async fn execute_job_inner(mut ctx_builder: DalContextBuilder, job_info: &JobInfo) -> Result<()> {
     if job_info.blocking {
         ctx_builder.set_blocking();
     }
@@ -1,6 +1,8 @@
 mod app_state;
+mod coalesce;
 mod config;
 mod handlers;
+mod scheduled;
 pub mod server;
 
 use std::io;
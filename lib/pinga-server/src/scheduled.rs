@@ -0,0 +1,167 @@
+//! Holding back of scheduled jobs until they are due.
+//!
+//! A job enqueued to run at a later time is published to the scheduled jobs stream rather than the
+//! work queue, and carries that time in the [`RUN_AT_HEADER_NAME`] header. When a message for such
+//! a job arrives before it is due, it is negatively acknowledged with a delay of the time left, so
+//! that Jetstream redelivers it once it is due. Once due, the job is moved to the work queue. Waiting
+//! jobs therefore survive restarts, and only count towards the pending acks of the scheduled jobs
+//! consumer, never holding up the jobs which are due.
+
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use naxum::{
+    extract::{message_parts::Headers, State},
+    response::{IntoResponse, Response},
+    Message,
+};
+use pinga_core::{subject, RUN_AT_HEADER_NAME};
+use si_data_nats::{
+    async_nats::jetstream::{self, AckKind},
+    Subject,
+};
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+use thiserror::Error;
+use tower::{Layer, Service};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub(crate) enum ScheduledError {
+    #[error("invalid scheduled job subject: {0}")]
+    InvalidSubject(Subject),
+    #[error("failed to move due job to the work queue: {0}")]
+    Publish(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl IntoResponse for ScheduledError {
+    fn into_response(self) -> Response {
+        error!(si.error.message = ?self, "failed to move due scheduled job");
+        Response::default_internal_server_error()
+    }
+}
+
+/// Application state of the scheduled jobs consumer.
+#[derive(Clone, Debug)]
+pub(crate) struct ScheduledState {
+    pub(crate) context: jetstream::Context,
+    pub(crate) prefix: Option<String>,
+}
+
+/// Moves a scheduled job which is due to the work queue, keeping its headers. The scheduled
+/// message is only acked once the work queue has it.
+pub(crate) async fn move_due_job(
+    State(state): State<ScheduledState>,
+    subject: Subject,
+    Headers(maybe_headers): Headers,
+    payload: Bytes,
+) -> Result<(), ScheduledError> {
+    let mut parts = subject.as_str().rsplitn(4, '.');
+    let (Some(kind), Some(change_set_id), Some(workspace_id)) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ScheduledError::InvalidSubject(subject));
+    };
+    let work_queue_subject =
+        subject::pinga_job(state.prefix.as_deref(), workspace_id, change_set_id, kind);
+
+    state
+        .context
+        .publish_with_headers(
+            work_queue_subject,
+            maybe_headers.unwrap_or_default(),
+            payload,
+        )
+        .await
+        .map_err(|err| ScheduledError::Publish(Box::new(err)))?
+        .await
+        .map_err(|err| ScheduledError::Publish(Box::new(err)))?;
+
+    Ok(())
+}
+
+/// Defers messages of scheduled jobs which are not due yet.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DeferScheduledLayer;
+
+impl<S> Layer<S> for DeferScheduledLayer {
+    type Service = DeferScheduled<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeferScheduled { inner }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DeferScheduled<S> {
+    inner: S,
+}
+
+impl<S> Service<Message<jetstream::Message>> for DeferScheduled<S>
+where
+    S: Service<Message<jetstream::Message>, Response = Response>,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Message<jetstream::Message>) -> Self::Future {
+        let Some(run_at) = run_at(&req) else {
+            return Box::pin(self.inner.call(req));
+        };
+        // The first delivery of a scheduled job starts its wait, and any later one its run
+        let first_delivery = req.info().map_or(true, |info| info.delivered <= 1);
+
+        match (run_at - Utc::now()).to_std() {
+            Ok(wait) if !wait.is_zero() => {
+                if first_delivery {
+                    metric!(counter.pinga.job.scheduled = 1);
+                }
+                Box::pin(async move {
+                    debug!(
+                        job.run_at = %run_at.to_rfc3339(),
+                        subject = req.subject().as_str(),
+                        "deferring scheduled job until it is due",
+                    );
+                    if let Err(err) = req.ack_with(AckKind::Nak(Some(wait))).await {
+                        warn!(
+                            si.error.message = ?err,
+                            subject = req.subject().as_str(),
+                            "failed to defer scheduled job",
+                        );
+                    }
+                    Ok(Response::default_ok())
+                })
+            }
+            _ => {
+                if !first_delivery {
+                    metric!(counter.pinga.job.scheduled = -1);
+                }
+                Box::pin(self.inner.call(req))
+            }
+        }
+    }
+}
+
+fn run_at(req: &Message<jetstream::Message>) -> Option<DateTime<Utc>> {
+    let value = req.headers()?.get(RUN_AT_HEADER_NAME)?;
+    match DateTime::parse_from_rfc3339(value.as_str()) {
+        Ok(run_at) => Some(run_at.with_timezone(&Utc)),
+        Err(err) => {
+            // Running a job early beats never running it
+            warn!(
+                si.error.message = ?err,
+                header = value.as_str(),
+                "invalid scheduled job run at header, running job now",
+            );
+            None
+        }
+    }
+}
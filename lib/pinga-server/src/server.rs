@@ -3,24 +3,27 @@ use std::{
     future::{Future, IntoFuture as _},
     io,
     sync::Arc,
+    time::Duration,
 };
 
 use dal::{
     feature_flags::FeatureFlagService, DalContext, DedicatedExecutor, JetstreamStreams,
     JobQueueProcessor, NatsProcessor, ServicesContext,
 };
+use futures::TryFutureExt as _;
 use naxum::{
     extract::MatchedSubject,
     handler::Handler as _,
     middleware::{
         ack::AckLayer,
+        concurrency_limit::ConcurrencyLimitLayer,
         matched_subject::{ForSubject, MatchedSubjectLayer},
         trace::TraceLayer,
     },
     response::{IntoResponse, Response},
     MessageHead, ServiceBuilder, ServiceExt as _, TowerServiceExt as _,
 };
-use pinga_core::{pinga_work_queue, subject};
+use pinga_core::{pinga_scheduled_queue, pinga_work_queue, subject};
 use rebaser_client::RebaserClient;
 use si_crypto::{
     SymmetricCryptoService, SymmetricCryptoServiceConfig, VeritechCryptoConfig,
@@ -34,9 +37,21 @@ use telemetry_utils::metric;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use veritech_client::Client as VeritechClient;
 
-use crate::{app_state::AppState, handlers, Config, ServerError, ServerResult};
+use crate::{
+    app_state::AppState,
+    handlers,
+    scheduled::{self, DeferScheduledLayer, ScheduledState},
+    Config, ServerError, ServerResult,
+};
 
 const CONSUMER_NAME: &str = "pinga-server";
+const SCHEDULED_CONSUMER_NAME: &str = "pinga-server-scheduled";
+/// How long the consumer waits for an ack before redelivering. Messages are kept in progress
+/// while they are processed, so this only bounds how long a crashed instance holds on to them.
+const CONSUMER_ACK_WAIT: Duration = Duration::from_secs(30);
+/// How many times a job is delivered before it is dead-lettered instead of redelivered when it
+/// fails.
+pub(crate) const CONSUMER_MAX_DELIVER: i64 = 5;
 
 /// Server metadata, used with telemetry.
 #[derive(Clone, Debug)]
@@ -156,6 +171,13 @@ impl Server {
             .messages()
             .await?;
 
+        let scheduled_incoming = pinga_scheduled_queue(&context, prefix.as_deref())
+            .await?
+            .create_consumer(Self::scheduled_consumer_config(prefix.as_deref()))
+            .await?
+            .messages()
+            .await?;
+
         let ctx_builder = DalContext::builder(services_context, false);
 
         let state = AppState::new(metadata.clone(), concurrency_limit, ctx_builder);
@@ -172,18 +194,36 @@ impl Server {
                     )
                     .on_response(telemetry_nats::NatsOnResponse::new()),
             )
-            .layer(AckLayer::new())
+            .layer(AckLayer::new().ack_wait(CONSUMER_ACK_WAIT))
+            .layer(ConcurrencyLimitLayer::new(concurrency_limit).name("pinga"))
+            .map_request(handlers::Delivery::record)
             .service(handlers::process_request.with_state(state))
             .map_response(Response::into_response);
 
+        // Read up to another `concurrency_limit` messages ahead so that a permit is picked up as
+        // soon as it is released. Those messages wait in the concurrency limit, which reports
+        // them as queued, while the ack middleware keeps them in progress.
+        let incoming_limit = concurrency_limit.saturating_mul(2);
         let inner =
-            naxum::serve_with_incoming_limit(incoming, app.into_make_service(), concurrency_limit)
+            naxum::serve_with_incoming_limit(incoming, app.into_make_service(), incoming_limit)
                 .with_graceful_shutdown(naxum::wait_on_cancelled(shutdown_token.clone()));
 
+        // Scheduled jobs wait on their own consumer, and are moved to the work queue once due
+        let scheduled_app = ServiceBuilder::new()
+            .layer(DeferScheduledLayer)
+            .layer(AckLayer::new().ack_wait(CONSUMER_ACK_WAIT))
+            .service(scheduled::move_due_job.with_state(ScheduledState { context, prefix }))
+            .map_response(Response::into_response);
+        let scheduled_inner = naxum::serve(scheduled_incoming, scheduled_app.into_make_service())
+            .with_graceful_shutdown(naxum::wait_on_cancelled(shutdown_token.clone()));
+
+        let inner = futures::future::try_join(inner.into_future(), scheduled_inner.into_future())
+            .map_ok(|((), ())| ());
+
         metric!(monotonic_counter.pinga.concurrency.limit = concurrency_limit);
         Ok(Self {
             metadata,
-            inner: Box::new(inner.into_future()),
+            inner: Box::new(Box::pin(inner)),
             shutdown_token,
         })
     }
@@ -267,7 +307,24 @@ impl Server {
     ) -> async_nats::jetstream::consumer::pull::Config {
         async_nats::jetstream::consumer::pull::Config {
             durable_name: Some(CONSUMER_NAME.to_owned()),
+            ack_wait: CONSUMER_ACK_WAIT,
             filter_subject: subject::incoming(subject_prefix).to_string(),
+            max_deliver: CONSUMER_MAX_DELIVER,
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    fn scheduled_consumer_config(
+        subject_prefix: Option<&str>,
+    ) -> async_nats::jetstream::consumer::pull::Config {
+        async_nats::jetstream::consumer::pull::Config {
+            durable_name: Some(SCHEDULED_CONSUMER_NAME.to_owned()),
+            ack_wait: CONSUMER_ACK_WAIT,
+            filter_subject: subject::incoming_scheduled(subject_prefix).to_string(),
+            // Every scheduled job waits unacked until it is due, so there is no telling how many
+            // are pending at once
+            max_ack_pending: -1,
             ..Default::default()
         }
     }
@@ -5,7 +5,9 @@ use pending_events::{PendingEventsError, PendingEventsStream};
 use rebaser_core::{
     api_types::HeaderMapParseMessageInfoError,
     api_types::{
-        enqueue_updates_request::{EnqueueUpdatesRequest, EnqueueUpdatesRequestVCurrent},
+        enqueue_updates_request::{
+            EnqueueUpdatesRequest, EnqueueUpdatesRequestVCurrent, RebasePrecondition,
+        },
         enqueue_updates_response::EnqueueUpdatesResponse,
     },
     api_types::{
@@ -86,6 +88,16 @@ impl Client {
         Ok(Self { nats, context })
     }
 
+    /// Returns the number of change sets which have updates waiting to be processed by a Rebaser.
+    pub async fn pending_task_count(&self) -> Result<u64> {
+        // Fetching the stream returns its current state, so no further request is needed
+        let stream = nats::rebaser_tasks_jetstream_stream(&self.context)
+            .await
+            .map_err(Error::CreateStream)?;
+
+        Ok(stream.cached_info().state.messages)
+    }
+
     /// Asynchronously enqueues graph updates for processing by a Rebaser & return a [`RequestId`].
     #[instrument(
         name = "rebaser_client.enqueue_updates",
@@ -110,6 +122,7 @@ impl Client {
             None,
             None,
             event_session_id,
+            Vec::new(),
         )
         .await
     }
@@ -140,12 +153,16 @@ impl Client {
             Some(from_change_set_id),
             None,
             event_session_id,
+            Vec::new(),
         )
         .await
     }
 
     /// Enqueues graph updates for processing by a Rebaser and return a [`Future`] that will await
     /// the Rebaser's response with status.
+    ///
+    /// The updates are only performed if every precondition holds, otherwise the Rebaser responds
+    /// with a `PreconditionFailed` status.
     #[instrument(
         name = "rebaser_client.enqueue_updates_with_reply",
         level = "info",
@@ -161,6 +178,7 @@ impl Client {
         change_set_id: ChangeSetId,
         updates_address: RebaseBatchAddress,
         event_session_id: EventSessionId,
+        preconditions: Vec<RebasePrecondition>,
     ) -> Result<(
         RequestId,
         BoxFuture<'static, Result<EnqueueUpdatesResponse>>,
@@ -171,6 +189,7 @@ impl Client {
             updates_address,
             None,
             event_session_id,
+            preconditions,
         )
         .await
     }
@@ -203,6 +222,7 @@ impl Client {
             updates_address,
             Some(from_change_set_id),
             event_session_id,
+            Vec::new(),
         )
         .await
     }
@@ -215,6 +235,7 @@ impl Client {
         from_change_set_id: Option<ChangeSetId>,
         maybe_reply_inbox: Option<&Subject>,
         event_session_id: EventSessionId,
+        preconditions: Vec<RebasePrecondition>,
     ) -> Result<RequestId> {
         let id = RequestId::new();
 
@@ -231,6 +252,7 @@ impl Client {
             updates_address,
             from_change_set_id,
             event_session_id: Some(event_session_id),
+            preconditions,
         });
 
         // Cut down on the amount of `String` allocations dealing with ids
@@ -280,6 +302,7 @@ impl Client {
         updates_address: RebaseBatchAddress,
         from_change_set_id: Option<ChangeSetId>,
         event_session_id: EventSessionId,
+        preconditions: Vec<RebasePrecondition>,
     ) -> Result<(
         RequestId,
         BoxFuture<'static, Result<EnqueueUpdatesResponse>>,
@@ -308,6 +331,7 @@ impl Client {
                 from_change_set_id,
                 Some(&reply_inbox),
                 event_session_id,
+                preconditions,
             )
             .await?;
 
@@ -0,0 +1,28 @@
+use naxum_api_types::RequestId;
+use serde::{Deserialize, Serialize};
+use si_events::{
+    merkle_tree_hash::MerkleTreeHash, rebase_batch_address::RebaseBatchAddress, ulid::Ulid,
+    ChangeSetId, EventSessionId, WorkspacePk,
+};
+
+#[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnqueueUpdatesRequestV3 {
+    pub id: RequestId,
+    pub workspace_id: WorkspacePk,
+    pub change_set_id: ChangeSetId,
+    pub updates_address: RebaseBatchAddress,
+    pub from_change_set_id: Option<ChangeSetId>,
+    pub event_session_id: Option<EventSessionId>,
+    /// Nodes which must be unchanged in the change set's snapshot for the updates to be performed.
+    pub preconditions: Vec<RebasePrecondition>,
+}
+
+/// Requires the merkle tree hash of a node to match when the updates are performed, so that
+/// writes which were checked against an earlier version of the node are rejected.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RebasePrecondition {
+    pub node_id: Ulid,
+    pub merkle_tree_hash: MerkleTreeHash,
+}
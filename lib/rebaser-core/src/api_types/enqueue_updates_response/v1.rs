@@ -1,6 +1,6 @@
 use naxum_api_types::RequestId;
 use serde::{Deserialize, Serialize};
-use si_events::{rebase_batch_address::RebaseBatchAddress, ChangeSetId, WorkspacePk};
+use si_events::{rebase_batch_address::RebaseBatchAddress, ulid::Ulid, ChangeSetId, WorkspacePk};
 
 #[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -20,4 +20,8 @@ pub enum RebaseStatus {
     Error {
         message: String,
     },
+    /// A precondition of the request did not hold, so no updates were performed.
+    PreconditionFailed {
+        node_id: Ulid,
+    },
 }
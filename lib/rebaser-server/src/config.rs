@@ -9,6 +9,7 @@ use si_crypto::{
 use si_data_nats::NatsConfig;
 use si_data_pg::PgPoolConfig;
 use si_layer_cache::{db::LayerDbConfig, error::LayerDbError};
+use si_settings::ValidationReport;
 use si_std::CanonicalFileError;
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -166,6 +167,20 @@ impl Default for ConfigFile {
 
 impl StandardConfigFile for ConfigFile {
     type Error = ConfigError;
+
+    fn validate(&self, report: &mut ValidationReport) {
+        report.require_non_empty("instance_id", &self.instance_id);
+        report.check(
+            self.concurrency_limit != Some(0),
+            "concurrency_limit",
+            "must be greater than zero when set",
+        );
+        report.check(
+            self.quiescent_period_secs > 0,
+            "quiescent_period_secs",
+            "must be greater than zero",
+        );
+    }
 }
 
 impl TryFrom<ConfigFile> for Config {
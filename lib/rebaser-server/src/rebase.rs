@@ -1,9 +1,11 @@
 use audit_logs_stream::AuditLogsStreamError;
 use dal::{
     change_set::{ChangeSet, ChangeSetError, ChangeSetId},
+    search::SearchIndex,
+    snapshot_subscription::{SnapshotSubscription, SnapshotSubscriptionError},
     workspace_snapshot::WorkspaceSnapshotError,
-    DalContext, TransactionsError, Workspace, WorkspaceError, WorkspacePk, WorkspaceSnapshot,
-    WsEvent, WsEventError,
+    DalContext, DalContextBuilder, TransactionsError, Workspace, WorkspaceError, WorkspacePk,
+    WorkspaceSnapshot, WsEvent, WsEventError,
 };
 use pending_events::PendingEventsError;
 use rebaser_core::api_types::{
@@ -34,6 +36,8 @@ pub(crate) enum RebaseError {
     PendingEvents(#[from] PendingEventsError),
     #[error("shuttle error: {0}")]
     Shuttle(#[from] ShuttleError),
+    #[error("snapshot subscription error: {0}")]
+    SnapshotSubscription(#[from] SnapshotSubscriptionError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
     #[error("workspace error: {0}")]
@@ -82,6 +86,32 @@ pub async fn perform_rebase(
     let to_rebase_workspace_snapshot =
         WorkspaceSnapshot::find(ctx, to_rebase_workspace_snapshot_address).await?;
 
+    // Requests are processed one at a time per change set, so nothing can change the snapshot
+    // between this check and the updates below
+    for precondition in &request.preconditions {
+        let current = match to_rebase_workspace_snapshot
+            .get_node_index_by_id_opt(precondition.node_id)
+            .await
+        {
+            Some(node_index) => Some(
+                to_rebase_workspace_snapshot
+                    .get_node_weight(node_index)
+                    .await?
+                    .merkle_tree_hash(),
+            ),
+            None => None,
+        };
+        if current != Some(precondition.merkle_tree_hash) {
+            info!(
+                si.rebaser.precondition.node_id = %precondition.node_id,
+                "rebase precondition failed, no updates performed",
+            );
+            return Ok(RebaseStatus::PreconditionFailed {
+                node_id: precondition.node_id,
+            });
+        }
+    }
+
     let rebase_batch = ctx
         .layer_db()
         .rebase_batch()
@@ -145,6 +175,29 @@ pub async fn perform_rebase(
         });
     }
 
+    // Keep the search index for HEAD up to date, since that is what workspace search reads
+    if updating_head && !corrected_updates.is_empty() {
+        let ctx_clone = ctx.clone();
+        server_tracker.spawn(async move {
+            if let Err(err) = SearchIndex::materialize(&ctx_clone).await {
+                error!(?err, "search index error");
+            }
+        });
+
+        // Built separately, since publishing needs its own transaction
+        let ctx_builder = ctx.to_builder();
+        let workspace_pk = request.workspace_id;
+        let change_set_id = request.change_set_id;
+        server_tracker.spawn(async move {
+            if let Err(err) =
+                publish_snapshot_subscription_updates(ctx_builder, workspace_pk, change_set_id)
+                    .await
+            {
+                error!(?err, "snapshot subscription error");
+            }
+        });
+    }
+
     if updating_head && *workspace.pk() != WorkspacePk::NONE {
         //todo(brit): what do we want to do about change sets that haven't
         // been applied yet, but are approved? (like gh merge-queue)
@@ -231,6 +284,20 @@ pub(crate) async fn evict_unused_snapshots(
     Ok(())
 }
 
+async fn publish_snapshot_subscription_updates(
+    ctx_builder: DalContextBuilder,
+    workspace_pk: WorkspacePk,
+    change_set_id: ChangeSetId,
+) -> RebaseResult<()> {
+    let mut ctx = ctx_builder
+        .build_for_change_set_as_system(workspace_pk, change_set_id)
+        .await?;
+    SnapshotSubscription::publish_updates(&mut ctx).await?;
+    ctx.commit_no_rebase().await?;
+
+    Ok(())
+}
+
 async fn replay_changes(
     ctx: &DalContext,
     workspace_pk: WorkspacePk,
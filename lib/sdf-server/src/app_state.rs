@@ -11,7 +11,8 @@ use tokio::sync::{Mutex, RwLock};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    nats_multiplexer::NatsMultiplexerClients, service::ws::crdt::BroadcastGroups,
+    nats_multiplexer::NatsMultiplexerClients,
+    service::{v2::workspace::WorkspaceImportUploads, ws::crdt::BroadcastGroups},
     WorkspacePermissions, WorkspacePermissionsMode,
 };
 
@@ -38,6 +39,7 @@ pub struct AppState {
     shutdown_token: CancellationToken,
     spicedb_client: Option<SpiceDbClient>,
     audit_database_context: AuditDatabaseContext,
+    workspace_import_uploads: WorkspaceImportUploads,
 }
 
 impl AppState {
@@ -78,6 +80,7 @@ impl AppState {
             shutdown_token,
             spicedb_client,
             audit_database_context,
+            workspace_import_uploads: Default::default(),
         }
     }
 
@@ -5,6 +5,7 @@ use si_crypto::VeritechCryptoConfig;
 use si_data_spicedb::SpiceDbConfig;
 use si_jwt_public_key::{JwtAlgo, JwtConfig};
 use si_layer_cache::{db::LayerDbConfig, error::LayerDbError};
+use si_settings::ValidationReport;
 use std::collections::HashSet;
 use std::{
     env,
@@ -26,6 +27,11 @@ use si_std::{CanonicalFile, CanonicalFileError};
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use crate::{
+    data_residency::DataResidencyConfig,
+    middleware::{LoadShedConfig, RateLimitConfig},
+};
+
 pub use dal::MigrationMode;
 pub use si_settings::{StandardConfig, StandardConfigFile};
 
@@ -133,6 +139,9 @@ pub struct Config {
     #[builder(default)]
     jwt_secondary_signing_public_key: Option<JwtConfig>,
 
+    #[builder(default)]
+    jwt_automation_signing_key: Option<JwtConfig>,
+
     #[builder(default = "default_layer_db_config()")]
     layer_db_config: LayerDbConfig,
 
@@ -152,6 +161,21 @@ pub struct Config {
 
     #[builder(default)]
     dev_mode: bool,
+
+    #[builder(default)]
+    serve_embedded_web: bool,
+
+    #[builder(default)]
+    serve_graphql: bool,
+
+    #[builder(default)]
+    rate_limit: RateLimitConfig,
+
+    #[builder(default)]
+    load_shed: LoadShedConfig,
+
+    #[builder(default)]
+    data_residency: DataResidencyConfig,
 }
 
 impl StandardConfig for Config {
@@ -198,6 +222,11 @@ impl Config {
         self.jwt_secondary_signing_public_key.as_ref()
     }
 
+    /// Gets a reference to the config's private key for signing workspace automation tokens.
+    pub fn jwt_automation_signing_key(&self) -> Option<&JwtConfig> {
+        self.jwt_automation_signing_key.as_ref()
+    }
+
     /// Gets a reference to the config's cyclone public key path.
     #[must_use]
     pub fn crypto(&self) -> &VeritechCryptoConfig {
@@ -278,6 +307,32 @@ impl Config {
     pub fn dev_mode(&self) -> bool {
         self.dev_mode
     }
+
+    /// Whether to serve the web app embedded in the binary, for deployments without a separate web
+    /// server.
+    pub fn serve_embedded_web(&self) -> bool {
+        self.serve_embedded_web
+    }
+
+    /// Whether to serve the read-only GraphQL API.
+    pub fn serve_graphql(&self) -> bool {
+        self.serve_graphql
+    }
+
+    /// Gets the limits for requests made with the same token in the same workspace.
+    pub fn rate_limit(&self) -> &RateLimitConfig {
+        &self.rate_limit
+    }
+
+    /// Gets the budgets and saturation thresholds for shedding load.
+    pub fn load_shed(&self) -> &LoadShedConfig {
+        &self.load_shed
+    }
+
+    /// Gets the regions workspaces can reside in, and which workspaces reside where.
+    pub fn data_residency(&self) -> &DataResidencyConfig {
+        &self.data_residency
+    }
 }
 
 impl ConfigBuilder {
@@ -307,6 +362,8 @@ pub struct ConfigFile {
     #[serde(default)]
     pub jwt_secondary_signing_public_key: Option<JwtConfig>,
     #[serde(default)]
+    pub jwt_automation_signing_key: Option<JwtConfig>,
+    #[serde(default)]
     pub crypto: VeritechCryptoConfig,
     #[serde(default = "default_pkgs_path")]
     pub pkgs_path: String,
@@ -334,6 +391,16 @@ pub struct ConfigFile {
     spicedb: SpiceDbConfig,
     #[serde(default)]
     audit: AuditDatabaseConfig,
+    #[serde(default)]
+    pub serve_embedded_web: bool,
+    #[serde(default)]
+    pub serve_graphql: bool,
+    #[serde(default)]
+    rate_limit: RateLimitConfig,
+    #[serde(default)]
+    load_shed: LoadShedConfig,
+    #[serde(default)]
+    data_residency: DataResidencyConfig,
 }
 
 impl Default for ConfigFile {
@@ -345,6 +412,7 @@ impl Default for ConfigFile {
             migration_mode: Default::default(),
             jwt_signing_public_key: Default::default(),
             jwt_secondary_signing_public_key: Default::default(),
+            jwt_automation_signing_key: Default::default(),
             crypto: Default::default(),
             pkgs_path: default_pkgs_path(),
             posthog: Default::default(),
@@ -360,12 +428,33 @@ impl Default for ConfigFile {
             spicedb: Default::default(),
             audit: Default::default(),
             dev_mode: false,
+            serve_embedded_web: false,
+            serve_graphql: false,
+            rate_limit: Default::default(),
+            load_shed: Default::default(),
+            data_residency: Default::default(),
         }
     }
 }
 
 impl StandardConfigFile for ConfigFile {
     type Error = ConfigError;
+
+    fn validate(&self, report: &mut ValidationReport) {
+        report.require_non_empty("instance_id", &self.instance_id);
+        report.check(
+            self.auth_api_url.parse::<url::Url>().is_ok(),
+            "auth_api_url",
+            "must be a valid url",
+        );
+        if !self.module_index_url.is_empty() {
+            report.check(
+                self.module_index_url.parse::<url::Url>().is_ok(),
+                "module_index_url",
+                "must be a valid url",
+            );
+        }
+    }
 }
 
 impl TryFrom<ConfigFile> for Config {
@@ -382,6 +471,7 @@ impl TryFrom<ConfigFile> for Config {
             migration_mode: value.migration_mode,
             jwt_signing_public_key: value.jwt_signing_public_key,
             jwt_secondary_signing_public_key: value.jwt_secondary_signing_public_key,
+            jwt_automation_signing_key: value.jwt_automation_signing_key,
             crypto: value.crypto,
             pkgs_path: value.pkgs_path.try_into()?,
             posthog: value.posthog,
@@ -397,6 +487,11 @@ impl TryFrom<ConfigFile> for Config {
             spicedb: value.spicedb,
             audit: value.audit,
             dev_mode: value.dev_mode,
+            serve_embedded_web: value.serve_embedded_web,
+            serve_graphql: value.serve_graphql,
+            rate_limit: value.rate_limit,
+            load_shed: value.load_shed,
+            data_residency: value.data_residency,
         })
     }
 }
@@ -0,0 +1,136 @@
+//! Routes workspaces to the region their data has to reside in, so that a single sdf fleet can
+//! serve e.g. EU-resident workspaces from EU storage without a separate deployment.
+//!
+//! Every region has its own Postgres, NATS and layer db endpoints, each with their own connection
+//! pools. Contexts for requests made with a token for a workspace which is assigned to a region are
+//! built from the services of that region, while all other workspaces are served from the default
+//! endpoints. Assigning a workspace to a region does not move its data.
+//!
+//! The websocket multiplexers only subscribe to the default NATS, so regional NATS servers are
+//! expected to be connected to it (e.g. as leaf nodes) for events to reach clients.
+
+use std::{collections::HashMap, fmt, str::FromStr, sync::Arc};
+
+use dal::{ServicesContext, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use si_data_nats::NatsConfig;
+use si_data_pg::PgPoolConfig;
+use si_layer_cache::db::LayerDbConfig;
+use thiserror::Error;
+
+/// The path segment preceding the workspace pk in workspace-scoped routes.
+const WORKSPACES_PATH_SEGMENT: &str = "workspaces";
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum DataResidencyError {
+    #[error("invalid workspace pk assigned to a region: {0}")]
+    InvalidWorkspacePk(String),
+    #[error("workspace {workspace_pk} is assigned to unknown region {region}")]
+    UnknownRegion {
+        workspace_pk: String,
+        region: String,
+    },
+}
+
+/// The regions workspaces can reside in, and which workspaces reside where.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct DataResidencyConfig {
+    /// The endpoints of every region, by region name.
+    #[serde(default)]
+    pub regions: HashMap<String, RegionConfig>,
+    /// The region of every workspace which is not served from the default endpoints, by
+    /// workspace pk.
+    #[serde(default)]
+    pub workspaces: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RegionConfig {
+    pub pg: PgPoolConfig,
+    pub nats: NatsConfig,
+    pub layer_db_config: LayerDbConfig,
+}
+
+/// The services of every region, and the regions workspaces are assigned to.
+#[derive(Clone, Default)]
+pub struct DataResidency {
+    regions: Arc<HashMap<String, ServicesContext>>,
+    workspace_regions: Arc<HashMap<WorkspacePk, String>>,
+}
+
+impl fmt::Debug for DataResidency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataResidency")
+            .field("regions", &self.regions.keys().collect::<Vec<_>>())
+            .field("workspace_regions", &self.workspace_regions)
+            .finish()
+    }
+}
+
+impl DataResidency {
+    /// Creates the routing from the services of every region and the region names assigned to
+    /// workspaces, by workspace pk.
+    pub fn new(
+        regions: HashMap<String, ServicesContext>,
+        workspaces: &HashMap<String, String>,
+    ) -> Result<Self, DataResidencyError> {
+        let mut workspace_regions = HashMap::with_capacity(workspaces.len());
+        for (workspace_pk, region) in workspaces {
+            if !regions.contains_key(region) {
+                return Err(DataResidencyError::UnknownRegion {
+                    workspace_pk: workspace_pk.to_owned(),
+                    region: region.to_owned(),
+                });
+            }
+            let workspace_pk = WorkspacePk::from_str(workspace_pk)
+                .map_err(|_| DataResidencyError::InvalidWorkspacePk(workspace_pk.to_owned()))?;
+            workspace_regions.insert(workspace_pk, region.to_owned());
+        }
+
+        Ok(Self {
+            regions: Arc::new(regions),
+            workspace_regions: Arc::new(workspace_regions),
+        })
+    }
+
+    /// Whether no workspace is served from a region.
+    pub fn is_empty(&self) -> bool {
+        self.workspace_regions.is_empty()
+    }
+
+    /// The name of the region a workspace resides in, if it is not served from the default
+    /// endpoints.
+    pub fn region_for_workspace(&self, workspace_pk: WorkspacePk) -> Option<&str> {
+        self.workspace_regions
+            .get(&workspace_pk)
+            .map(String::as_str)
+    }
+
+    /// The services of the region a workspace resides in, if it is not served from the default
+    /// endpoints.
+    pub fn services_context_for_workspace(
+        &self,
+        workspace_pk: WorkspacePk,
+    ) -> Option<&ServicesContext> {
+        self.region_for_workspace(workspace_pk)
+            .and_then(|region| self.regions.get(region))
+    }
+
+    /// Finds the workspace pk in the path of a workspace-scoped route, such as
+    /// `/api/v2/workspaces/:workspace_pk/change-sets/:change_set_id/...`.
+    pub fn workspace_pk_from_path(path: &str) -> Option<WorkspacePk> {
+        let mut segments = path.split('/');
+        segments.find(|segment| *segment == WORKSPACES_PATH_SEGMENT)?;
+        segments
+            .next()
+            .and_then(|segment| WorkspacePk::from_str(segment).ok())
+    }
+
+    /// The services of every region, by region name.
+    pub fn regions(&self) -> impl Iterator<Item = (&str, &ServicesContext)> {
+        self.regions
+            .iter()
+            .map(|(region, services_context)| (region.as_str(), services_context))
+    }
+}
@@ -0,0 +1,104 @@
+//! Serves the web app embedded into the binary at build time, for single-binary deployments which
+//! do not run a separate web server in front of sdf.
+//!
+//! The web app must be built (into `app/web/dist`) before sdf is compiled for it to be embedded.
+//! Vite fingerprints everything it emits under `assets/`, so those files can be cached forever;
+//! everything else is revalidated using its ETag. Any other path which does not look like a file is
+//! a route of the single page app and is served `index.html`.
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Router,
+};
+use rust_embed::{EmbeddedFile, RustEmbed};
+use telemetry::prelude::*;
+
+const INDEX: &str = "index.html";
+const FINGERPRINTED_PREFIX: &str = "assets/";
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const REVALIDATE: &str = "no-cache";
+
+#[derive(RustEmbed)]
+#[folder = "../../app/web/dist"]
+#[allow_missing = true]
+struct WebAssets;
+
+/// Serves the embedded web app for any request not handled by the router.
+pub fn with_embedded_web(router: Router) -> Router {
+    if WebAssets::get(INDEX).is_none() {
+        warn!("serving the embedded web app, but it was not built before compiling sdf");
+    }
+    with_embedded_assets::<WebAssets>(router)
+}
+
+/// Serves the given embedded assets as a single page app for any request not handled by the
+/// router.
+pub fn with_embedded_assets<A: RustEmbed + Send + Sync + 'static>(router: Router) -> Router {
+    router.fallback(serve_embedded_assets::<A>)
+}
+
+async fn serve_embedded_assets<A: RustEmbed>(uri: Uri, headers: HeaderMap) -> Response {
+    let path = uri.path().trim_start_matches('/');
+    // Unknown api routes should not be answered with the app
+    if path == "api" || path.starts_with("api/") {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let path = if path.is_empty() { INDEX } else { path };
+    match A::get(path) {
+        Some(file) => file_response(path, file, &headers),
+        None if looks_like_file(path) => StatusCode::NOT_FOUND.into_response(),
+        None => match A::get(INDEX) {
+            Some(file) => file_response(INDEX, file, &headers),
+            None => StatusCode::NOT_FOUND.into_response(),
+        },
+    }
+}
+
+/// Paths with an extension are missing files rather than routes of the app.
+fn looks_like_file(path: &str) -> bool {
+    path.rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains('.'))
+}
+
+fn file_response(path: &str, file: EmbeddedFile, headers: &HeaderMap) -> Response {
+    let etag = format!("\"{}\"", hex::encode(file.metadata.sha256_hash()));
+    let cache_control = if path.starts_with(FINGERPRINTED_PREFIX) {
+        IMMUTABLE
+    } else {
+        REVALIDATE
+    };
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    if not_modified {
+        return (
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, cache_control.to_owned()),
+            ],
+        )
+            .into_response();
+    }
+
+    let content_type = mime_guess::from_path(path).first_or_octet_stream();
+    let mut response = (
+        [
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, cache_control.to_owned()),
+        ],
+        file.data.into_owned(),
+    )
+        .into_response();
+    if let Ok(content_type) = HeaderValue::from_str(content_type.as_ref()) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, content_type);
+    }
+    response
+}
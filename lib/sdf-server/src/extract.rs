@@ -1,21 +1,23 @@
-use std::fmt;
+use std::{fmt, marker::PhantomData, str::FromStr};
 
 use axum::{
     async_trait,
     extract::{FromRequestParts, Query},
     http::request::Parts,
-    http::StatusCode,
+    http::{header, StatusCode},
     Json,
 };
 use dal::{
-    context::{self, DalContextBuilder},
-    User, WorkspacePk,
+    api_token::{ApiToken, ApiTokenId},
+    context::{self, DalContextBuilder, DryRun},
+    workspace_role::{WorkspaceOperation, WorkspaceRole, WorkspaceRoleError},
+    ServicesContext, User, WorkspacePk,
 };
 use derive_more::{Deref, Into};
 use serde::Deserialize;
 use si_jwt_public_key::{validate_raw_token, SiJwt, SiJwtClaimRole};
 
-use crate::app_state::AppState;
+use crate::{app_state::AppState, DataResidency};
 
 type ErrorResponse = (StatusCode, Json<serde_json::Value>);
 
@@ -103,6 +105,26 @@ impl FromRequestParts<AppState> for AdminAccessBuilder {
     }
 }
 
+/// Verifies that the token carries the admin claim. This should only be used as a route middleware
+/// for admin routes which act across workspaces, in addition to [`AdminAccessBuilder`].
+pub struct AdminClaim;
+
+#[async_trait]
+impl FromRequestParts<AppState> for AdminClaim {
+    type Rejection = ErrorResponse;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let ValidatedToken(token) = ValidatedToken::from_request_parts(parts, state).await?;
+        if !token.custom.is_admin() {
+            return Err(unauthorized_error("token does not have the admin claim"));
+        }
+        Ok(Self)
+    }
+}
+
 #[derive(Clone, Debug, Deref, Into)]
 pub struct HandlerContext(pub DalContextBuilder);
 
@@ -111,14 +133,15 @@ impl FromRequestParts<AppState> for HandlerContext {
     type Rejection = ErrorResponse;
 
     async fn from_request_parts(
-        _parts: &mut Parts,
+        parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let builder = state
-            .services_context()
-            .clone()
-            .into_inner()
+        let mut builder = services_context_for_request(parts, state)
+            .await
             .into_builder(state.for_tests());
+        if let Some(dry_run) = parts.extensions.get::<DryRun>() {
+            builder.set_dry_run(dry_run.clone());
+        }
         Ok(Self(builder))
     }
 }
@@ -164,14 +187,38 @@ impl FromRequestParts<AppState> for Nats {
     type Rejection = ErrorResponse;
 
     async fn from_request_parts(
-        _parts: &mut Parts,
+        parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let services_context = state.services_context();
+        let services_context = services_context_for_request(parts, state).await;
         Ok(Self(services_context.nats_conn().clone()))
     }
 }
 
+/// Gets the services of the region the workspace of the request resides in (see
+/// [`DataResidency`]), or the default services for any other workspace.
+///
+/// The workspace is that of the token, or the one in the path for requests without a valid token,
+/// so that requests which are rejected later on still never touch the storage of another region.
+pub(crate) async fn services_context_for_request(
+    parts: &mut Parts,
+    state: &AppState,
+) -> ServicesContext {
+    let default_services_context = || state.services_context().clone().into_inner();
+    let Some(data_residency) = parts.extensions.get::<DataResidency>().cloned() else {
+        return default_services_context();
+    };
+
+    let workspace_pk = match ValidatedToken::from_request_parts(parts, state).await {
+        Ok(ValidatedToken(token)) => Some(token.custom.workspace_id()),
+        Err(_) => DataResidency::workspace_pk_from_path(parts.uri.path()),
+    };
+    workspace_pk
+        .and_then(|workspace_pk| data_residency.services_context_for_workspace(workspace_pk))
+        .cloned()
+        .unwrap_or_else(default_services_context)
+}
+
 ///
 /// Handles the whole endpoint authorization (checking if the user is a member of the workspace
 /// as well as checking that their token has the correct role).
@@ -199,8 +246,9 @@ impl FromRequestParts<AppState> for EndpointAuthorization {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let WorkspaceMember { user, workspace_id } =
-            WorkspaceMember::from_request_parts(parts, state).await?;
+        let WorkspaceMember {
+            user, workspace_id, ..
+        } = WorkspaceMember::from_request_parts(parts, state).await?;
         let AuthorizedRole(authorized_role) =
             AuthorizedRole::from_request_parts(parts, state).await?;
         Ok(Self {
@@ -219,6 +267,7 @@ impl FromRequestParts<AppState> for EndpointAuthorization {
 struct WorkspaceMember {
     pub user: User,
     pub workspace_id: WorkspacePk,
+    pub role: Option<WorkspaceRole>,
 }
 
 #[async_trait]
@@ -251,8 +300,31 @@ impl FromRequestParts<AppState> for WorkspaceMember {
             .find(|m| m.pk() == token.custom.user_id())
             .ok_or_else(|| unauthorized_error("User not a member of the workspace"))?;
 
+        // Automation tokens issued by sdf can be revoked before they expire
+        if let Some(api_token_id) = token
+            .jwt_id
+            .as_deref()
+            .and_then(|jwt_id| ApiTokenId::from_str(jwt_id).ok())
+        {
+            let api_token = ApiToken::get_by_id(&ctx, workspace_id, api_token_id)
+                .await
+                .map_err(internal_error)?;
+            if api_token.is_some_and(|api_token| !api_token.is_active()) {
+                return Err(unauthorized_error("Token has been revoked"));
+            }
+        }
+
+        // Get the role of the user in the workspace, while we have a context for it
+        let role = WorkspaceRole::get_for_user(&ctx, workspace_id, user.pk())
+            .await
+            .map_err(internal_error)?;
+
         // Stash and return the result
-        let result = Self { user, workspace_id };
+        let result = Self {
+            user,
+            workspace_id,
+            role,
+        };
         parts.extensions.insert(result.clone());
         Ok(result)
     }
@@ -353,6 +425,80 @@ impl FromRequestParts<AppState> for AuthorizedForAutomationRole {
     }
 }
 
+///
+/// A workspace member whose role allows them to perform the operation `Op`, one of the
+/// [`operation`] markers, e.g. `AuthorizedTo<operation::ApplyChangeSet>`.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct AuthorizedTo<Op>(PhantomData<Op>);
+
+/// An operation of a workspace which members are authorized to perform based on their role.
+pub trait AuthorizedOperation: Send + Sync + 'static {
+    const OPERATION: WorkspaceOperation;
+}
+
+#[async_trait]
+impl<Op: AuthorizedOperation> FromRequestParts<AppState> for AuthorizedTo<Op> {
+    type Rejection = ErrorResponse;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        authorize_workspace_operation(parts, state, Op::OPERATION).await?;
+        Ok(Self(PhantomData))
+    }
+}
+
+/// Markers for the [`WorkspaceOperation`]s, to be authorized with [`AuthorizedTo`].
+pub mod operation {
+    use dal::workspace_role::WorkspaceOperation;
+
+    use super::AuthorizedOperation;
+
+    macro_rules! operations {
+        ($($operation:ident),* $(,)?) => {
+            $(
+                #[derive(Clone, Copy, Debug)]
+                pub struct $operation;
+
+                impl AuthorizedOperation for $operation {
+                    const OPERATION: WorkspaceOperation = WorkspaceOperation::$operation;
+                }
+            )*
+        };
+    }
+
+    operations!(
+        ApplyChangeSet,
+        ApproveChangeSet,
+        DeleteComponent,
+        EditFunc,
+        ForceApplyChangeSet,
+        SetApprovalPolicy,
+        SetFeatureFlags,
+    );
+}
+
+/// Checks the role of the authorized user in the workspace against the operation, after doing the
+/// whole endpoint authorization. The role is the one looked up alongside the workspace membership.
+async fn authorize_workspace_operation(
+    parts: &mut Parts,
+    state: &AppState,
+    operation: WorkspaceOperation,
+) -> Result<(), ErrorResponse> {
+    EndpointAuthorization::from_request_parts(parts, state).await?;
+    let WorkspaceMember {
+        user,
+        workspace_id,
+        role,
+    } = WorkspaceMember::from_request_parts(parts, state).await?;
+
+    role.ok_or(WorkspaceRoleError::NotAMember(user.pk(), workspace_id))
+        .and_then(|role| role.ensure_can(operation))
+        .map_err(forbidden_error)
+}
+
 ///
 /// Validated JWT with unverified claims inside.
 ///
@@ -492,6 +638,59 @@ impl FromRequestParts<AppState> for TokenFromQueryParam {
     }
 }
 
+/// The entity tags provided in the "If-Match" header, if any. Used by mutating endpoints to
+/// reject updates made against a stale version of an entity with a "412 Precondition Failed".
+#[derive(Clone, Debug, Default)]
+pub struct IfMatch(pub Option<Vec<String>>);
+
+impl IfMatch {
+    /// Returns true if no "If-Match" header was provided, if it was the wildcard ("*") or if one
+    /// of its entity tags matches the provided current tag. Only strong tags are compared, as
+    /// weak tags never match a precondition on a write.
+    pub fn matches(&self, current: impl AsRef<str>) -> bool {
+        let current = current.as_ref();
+        match &self.0 {
+            None => true,
+            Some(tags) => tags.iter().any(|tag| tag == "*" || tag == current),
+        }
+    }
+
+    /// Returns true if the header was the wildcard ("*"), which matches any current tag.
+    pub fn is_wildcard(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_some_and(|tags| tags.iter().any(|tag| tag == "*"))
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for IfMatch {
+    type Rejection = ErrorResponse;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(raw_header) = parts.headers.get(header::IF_MATCH) else {
+            return Ok(Self(None));
+        };
+
+        let raw_header = raw_header.to_str().map_err(bad_request_error)?;
+
+        // The header is a comma separated list of (possibly weak) quoted entity tags. Weak tags
+        // are dropped, so a header with nothing but weak tags matches nothing
+        let tags = raw_header
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.starts_with("W/"))
+            .map(|tag| tag.trim_matches('"').to_owned())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        Ok(Self(Some(tags)))
+    }
+}
+
 fn internal_error(message: impl fmt::Display) -> ErrorResponse {
     let status_code = StatusCode::INTERNAL_SERVER_ERROR;
     (
@@ -506,6 +705,20 @@ fn internal_error(message: impl fmt::Display) -> ErrorResponse {
     )
 }
 
+fn bad_request_error(message: impl fmt::Display) -> ErrorResponse {
+    let status_code = StatusCode::BAD_REQUEST;
+    (
+        status_code,
+        Json(serde_json::json!({
+            "error": {
+                "message": message.to_string(),
+                "statusCode": status_code.as_u16(),
+                "code": 42,
+            },
+        })),
+    )
+}
+
 pub fn unauthorized_error(message: impl fmt::Display) -> ErrorResponse {
     let status_code = StatusCode::UNAUTHORIZED;
     (
@@ -520,6 +733,20 @@ pub fn unauthorized_error(message: impl fmt::Display) -> ErrorResponse {
     )
 }
 
+fn forbidden_error(message: impl fmt::Display) -> ErrorResponse {
+    let status_code = StatusCode::FORBIDDEN;
+    (
+        status_code,
+        Json(serde_json::json!({
+            "error": {
+                "message": message.to_string(),
+                "statusCode": status_code.as_u16(),
+                "code": 42,
+            },
+        })),
+    )
+}
+
 fn not_found_error(message: &str) -> ErrorResponse {
     let status_code = StatusCode::NOT_FOUND;
     (
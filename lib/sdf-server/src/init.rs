@@ -1,4 +1,4 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use dal::{
     feature_flags::FeatureFlagService, DalLayerDb, DedicatedExecutor, JetstreamStreams,
@@ -21,7 +21,10 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
 
-use crate::Config;
+use crate::{
+    data_residency::{DataResidency, DataResidencyConfig, DataResidencyError},
+    Config,
+};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -32,6 +35,8 @@ pub enum InitError {
     DalInitialization(#[from] dal::InitializationError),
     #[error("failed to initialize a dal jetstream streams: {0}")]
     DalJetstreamStreams(#[source] dal::JetstreamStreamsError),
+    #[error("data residency error: {0}")]
+    DataResidency(#[from] DataResidencyError),
     #[error("jwt key error")]
     JwtKey(#[from] JwtPublicSigningKeyError),
     #[error("layer cache error: {0}")]
@@ -105,6 +110,52 @@ pub(crate) async fn services_context_from_config(
     Ok((services_context, layer_db_graceful_shutdown))
 }
 
+/// Connects to the endpoints of every region workspaces can reside in. The services of the
+/// regions share everything but their connections with the default services.
+#[instrument(name = "sdf.init.data_residency_from_config", level = "info", skip_all)]
+pub(crate) async fn data_residency_from_config(
+    config: &DataResidencyConfig,
+    services_context: &ServicesContext,
+    helping_tasks_token: CancellationToken,
+) -> InitResult<(DataResidency, Vec<LayerDbGracefulShutdown>)> {
+    let mut regions = HashMap::with_capacity(config.regions.len());
+    let mut layer_db_graceful_shutdowns = Vec::with_capacity(config.regions.len());
+
+    for (region, region_config) in &config.regions {
+        let nats = connect_to_nats(&region_config.nats).await?;
+        let jetstream_streams = get_or_create_jetstream_streams(nats.clone()).await?;
+        let pg_pool = create_pg_pool(&region_config.pg).await?;
+        let rebaser = create_rebaser_client(nats.clone()).await?;
+        let veritech = create_veritech_client(nats.clone());
+        let job_processor = create_job_processor(nats.clone());
+        let (layer_db, layer_db_graceful_shutdown) = initialize_layer_db(
+            region_config.layer_db_config.clone(),
+            services_context.compute_executor().clone(),
+            helping_tasks_token.clone(),
+        )
+        .await?;
+        debug!(region, "connected to the services of region");
+
+        regions.insert(
+            region.to_owned(),
+            services_context.with_regional_services(
+                pg_pool,
+                nats,
+                jetstream_streams,
+                job_processor,
+                rebaser,
+                veritech,
+                layer_db,
+            ),
+        );
+        layer_db_graceful_shutdowns.push(layer_db_graceful_shutdown);
+    }
+
+    let data_residency = DataResidency::new(regions, &config.workspaces)?;
+
+    Ok((data_residency, layer_db_graceful_shutdowns))
+}
+
 #[instrument(name = "sdf.init.load_encryption_key", level = "info", skip_all)]
 pub(crate) async fn load_encryption_key(
     crypto_config: VeritechCryptoConfig,
@@ -198,8 +249,14 @@ pub(crate) async fn initialize_layer_db(
 pub(crate) async fn load_jwt_public_signing_key(
     primary: JwtConfig,
     secondary: Option<JwtConfig>,
+    automation: Option<JwtConfig>,
 ) -> InitResult<JwtPublicSigningKeyChain> {
-    Ok(JwtPublicSigningKeyChain::from_config(primary, secondary).await?)
+    let key_chain = JwtPublicSigningKeyChain::from_config(primary, secondary).await?;
+
+    Ok(match automation {
+        Some(automation) => key_chain.with_automation_signing_key(automation.into_sign().await?),
+        None => key_chain,
+    })
 }
 
 pub(crate) fn initialize_posthog(
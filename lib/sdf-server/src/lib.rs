@@ -21,6 +21,8 @@ use thiserror::Error;
 mod app;
 mod app_state;
 mod config;
+mod data_residency;
+pub mod embedded_web;
 mod extract;
 mod init;
 pub mod middleware;
@@ -41,6 +43,7 @@ pub use self::{
         Config, ConfigBuilder, ConfigError, ConfigFile, IncomingStream, MigrationMode,
         StandardConfig, StandardConfigFile, WorkspacePermissions, WorkspacePermissionsMode,
     },
+    data_residency::{DataResidency, DataResidencyConfig, DataResidencyError, RegionConfig},
     migrations::Migrator,
     nats_multiplexer::CRDT_MULTIPLEXER_SUBJECT,
     server::{Server, ServerMetadata, ServerSocket},
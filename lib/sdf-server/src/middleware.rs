@@ -1,3 +1,16 @@
+mod change_set_apply_lock;
+mod dry_run;
+mod load_shed;
+mod rate_limit;
 mod workspace_permission;
 
+pub use self::change_set_apply_lock::{
+    change_set_apply_lock_middleware, change_set_apply_lock_v1_middleware, ChangeSetApplyLockError,
+    ChangeSetApplyLockGuard, ChangeSetApplyLocks,
+};
+pub use self::dry_run::{dry_run_middleware, DRY_RUN_HEADER};
+pub use self::load_shed::{load_shed_middleware, LoadShedConfig, LoadShedder, RouteClassBudget};
+pub use self::rate_limit::{
+    rate_limit_middleware, RateLimitConfig, RateLimiter, RouteGroupRateLimit,
+};
 pub use self::workspace_permission::{WorkspacePermission, WorkspacePermissionLayer};
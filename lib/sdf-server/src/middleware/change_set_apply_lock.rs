@@ -0,0 +1,303 @@
+//! A per-change-set lock that is held while a change set is being applied. Requests that would
+//! mutate a locked change set are rejected with a `409 Conflict` (and a `Retry-After` hint)
+//! instead of landing mid-apply and being lost.
+//!
+//! The locks are stored in the database of the workspace's region (see
+//! [`dal::change_set::apply_lock`]), so a change set being applied through one server is locked on
+//! every server.
+//!
+//! A change set awaiting approval is locked in the same way. The middleware is only layered onto
+//! the routes which mutate change sets: the v2 routes with the change set in their path, and the
+//! v1 routes with the change set in the visibility of their body. The requests which move a change
+//! set through its approval (approving, withdrawing the request, applying, etc.) and read-only
+//! requests are not affected.
+
+use std::{convert::Infallible, time::Instant};
+
+use axum::{
+    async_trait,
+    body::{Body, Bytes},
+    extract::{FromRequest, FromRequestParts, Path},
+    http::{header, request::Parts, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use dal::{
+    change_set::apply_lock::ChangeSetApplyLocks as DalChangeSetApplyLocks, ChangeSetError,
+    ChangeSetId, DalContext, Visibility, WorkspacePk, WsEvent, WsEventError,
+};
+use serde::Deserialize;
+use serde_json::json;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    extract::{services_context_for_request, ValidatedToken},
+    AppState,
+};
+
+/// How long clients are asked to wait before retrying a rejected request.
+const RETRY_AFTER_SECONDS: u64 = 2;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ChangeSetApplyLockError {
+    #[error("change set {0} is awaiting approval, withdraw the request to edit it")]
+    AwaitingApproval(ChangeSetId),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
+    #[error("change set {0} is being applied, please retry shortly")]
+    Locked(ChangeSetId),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
+}
+
+impl IntoResponse for ChangeSetApplyLockError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Locked(change_set_id) => {
+                let body = Json(json!({
+                    "error": {
+                        "message": self.to_string(),
+                        "code": 42,
+                        "statusCode": StatusCode::CONFLICT.as_u16(),
+                        "changeSetId": change_set_id,
+                        "retryAfterSeconds": RETRY_AFTER_SECONDS,
+                    },
+                }));
+                (
+                    StatusCode::CONFLICT,
+                    [(header::RETRY_AFTER, RETRY_AFTER_SECONDS.to_string())],
+                    body,
+                )
+                    .into_response()
+            }
+            Self::AwaitingApproval(change_set_id) => {
+                let body = Json(json!({
+                    "error": {
+                        "message": self.to_string(),
+                        "code": 42,
+                        "statusCode": StatusCode::CONFLICT.as_u16(),
+                        "changeSetId": change_set_id,
+                    },
+                }));
+                (StatusCode::CONFLICT, body).into_response()
+            }
+            Self::ChangeSet(_) | Self::WsEvent(_) => {
+                let body = Json(json!({
+                    "error": {
+                        "message": self.to_string(),
+                        "code": 42,
+                        "statusCode": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    },
+                }));
+                (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+            }
+        }
+    }
+}
+
+/// The change sets currently being applied, across all servers. Extracted from the request, so
+/// that the locks are those of the region the workspace of the request resides in.
+#[derive(Clone, Debug)]
+pub struct ChangeSetApplyLocks(DalChangeSetApplyLocks);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ChangeSetApplyLocks {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let services_context = services_context_for_request(parts, state).await;
+        Ok(Self(DalChangeSetApplyLocks::new(
+            services_context.pg_pool().clone(),
+        )))
+    }
+}
+
+impl ChangeSetApplyLocks {
+    /// Lock the change set of the provided [`DalContext`] and notify clients, until the returned
+    /// guard is released. Fails if the change set is already locked.
+    pub async fn acquire(
+        &self,
+        ctx: &DalContext,
+    ) -> Result<ChangeSetApplyLockGuard, ChangeSetApplyLockError> {
+        let change_set_id = ctx.change_set_id();
+        if !self.0.try_acquire(change_set_id).await? {
+            return Err(ChangeSetApplyLockError::Locked(change_set_id));
+        }
+
+        let guard = ChangeSetApplyLockGuard {
+            locks: self.clone(),
+            ctx: ctx.clone(),
+            locked_at: Instant::now(),
+        };
+        let published = match WsEvent::change_set_apply_locked(ctx, change_set_id).await {
+            Ok(event) => event.publish_immediately(ctx).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = published {
+            guard.release().await;
+            return Err(err.into());
+        }
+
+        Ok(guard)
+    }
+
+    pub async fn is_locked(
+        &self,
+        change_set_id: ChangeSetId,
+    ) -> Result<bool, ChangeSetApplyLockError> {
+        Ok(self.0.is_locked(change_set_id).await?)
+    }
+
+    pub async fn is_awaiting_approval(
+        &self,
+        change_set_id: ChangeSetId,
+    ) -> Result<bool, ChangeSetApplyLockError> {
+        Ok(self.0.is_awaiting_approval(change_set_id).await?)
+    }
+
+    /// Fails if the change set is being applied or is awaiting approval. Change sets of other
+    /// workspaces are let through, since the routes reject them themselves, so that their locks
+    /// cannot be probed.
+    async fn check(
+        &self,
+        workspace_pk: WorkspacePk,
+        change_set_id: ChangeSetId,
+    ) -> Result<(), ChangeSetApplyLockError> {
+        if !self.0.is_in_workspace(change_set_id, workspace_pk).await? {
+            return Ok(());
+        }
+        if self.is_locked(change_set_id).await? {
+            return Err(ChangeSetApplyLockError::Locked(change_set_id));
+        }
+        if self.is_awaiting_approval(change_set_id).await? {
+            return Err(ChangeSetApplyLockError::AwaitingApproval(change_set_id));
+        }
+
+        Ok(())
+    }
+}
+
+/// The apply lock for a change set, which must be released once the apply is done. If it is not
+/// (e.g. the server goes away mid-apply), the lock expires on its own.
+#[must_use]
+pub struct ChangeSetApplyLockGuard {
+    locks: ChangeSetApplyLocks,
+    ctx: DalContext,
+    locked_at: Instant,
+}
+
+impl ChangeSetApplyLockGuard {
+    /// Release the lock and notify clients. Failures are logged rather than returned, so that they
+    /// do not fail an apply which has already happened.
+    pub async fn release(self) {
+        let change_set_id = self.ctx.change_set_id();
+
+        match self.locks.0.release(change_set_id).await {
+            Ok(()) => debug!(
+                si.change_set.id = %change_set_id,
+                elapsed_ms = self.locked_at.elapsed().as_millis(),
+                "released change set apply lock",
+            ),
+            Err(err) => error!(
+                si.error.message = ?err,
+                si.change_set.id = %change_set_id,
+                "unable to release change set apply lock",
+            ),
+        }
+
+        let result = match WsEvent::change_set_apply_unlocked(&self.ctx, change_set_id).await {
+            Ok(event) => event.publish_immediately(&self.ctx).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            error!(
+                si.error.message = ?err,
+                si.change_set.id = %change_set_id,
+                "unable to publish change set apply unlocked event",
+            );
+        }
+    }
+}
+
+/// The parameters of the routes nested under a change set.
+#[derive(Deserialize)]
+pub struct ChangeSetPath {
+    workspace_id: WorkspacePk,
+    change_set_id: ChangeSetId,
+}
+
+/// Rejects requests that would mutate a change set which is currently being applied or is awaiting
+/// approval. Layered onto routes nested under the change set in their path, after their
+/// authentication.
+pub async fn change_set_apply_lock_middleware(
+    locks: ChangeSetApplyLocks,
+    ValidatedToken(token): ValidatedToken,
+    Path(ChangeSetPath {
+        workspace_id,
+        change_set_id,
+    }): Path<ChangeSetPath>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    // Requests for another workspace are rejected by the route itself
+    if is_read_only(&request) || token.custom.workspace_id() != workspace_id {
+        return next.run(request).await;
+    }
+
+    if let Err(err) = locks.check(workspace_id, change_set_id).await {
+        return err.into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Rejects v1 requests that would mutate a change set which is currently being applied or is
+/// awaiting approval. The v1 routes carry the change set in the [`Visibility`] of their JSON body,
+/// so the body is read ahead of the route. Requests without a valid token or a visibility are left
+/// for the route to handle.
+pub async fn change_set_apply_lock_v1_middleware(
+    locks: ChangeSetApplyLocks,
+    token: Option<ValidatedToken>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let Some(ValidatedToken(token)) = token else {
+        return next.run(request).await;
+    };
+    if is_read_only(&request) {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    // Read with the default body limit, as the route's own JSON extractor would
+    let bytes = match Bytes::from_request(Request::new(body), &()).await {
+        Ok(bytes) => bytes,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let visibility = serde_json::from_slice::<Visibility>(&bytes);
+    let request = Request::from_parts(parts, Body::from(bytes));
+
+    if let Ok(visibility) = visibility {
+        if let Err(err) = locks
+            .check(token.custom.workspace_id(), visibility.change_set_id)
+            .await
+        {
+            return err.into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+fn is_read_only(request: &Request<Body>) -> bool {
+    matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    )
+}
@@ -0,0 +1,110 @@
+//! Dry runs for the mutating v2 routes, so that automation can be tested against production
+//! workspaces without changing them.
+//!
+//! Any non-`GET` request with a `dry_run=true` query parameter is served by a handler whose
+//! [`DalContext`](dal::DalContext) rolls back instead of committing. Successful JSON responses are
+//! wrapped in an envelope which holds the would-be response and a summary of the graph mutations
+//! the request would have committed. Error responses are returned as they are.
+
+use axum::{
+    body::{boxed, Body, Full},
+    extract::Query,
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dal::context::DryRun;
+use serde::Deserialize;
+use serde_json::json;
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+
+/// Set on every response to a dry run request, whether or not its body could be wrapped.
+pub const DRY_RUN_HEADER: &str = "x-si-dry-run";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+struct DryRunQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Serves `?dry_run=true` requests with non-committing contexts and wraps their responses with a
+/// summary of the mutations they would have committed.
+pub async fn dry_run_middleware(mut request: Request<Body>, next: Next<Body>) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        return next.run(request).await;
+    }
+    let Query(DryRunQuery { dry_run }) =
+        Query::<DryRunQuery>::try_from_uri(request.uri()).unwrap_or_default();
+    if !dry_run {
+        return next.run(request).await;
+    }
+
+    let dry_run = DryRun::new();
+    request.extensions_mut().insert(dry_run.clone());
+    metric!(counter.sdf.dry_run.requests = 1);
+
+    let response = next.run(request).await;
+    let mut response = if response.status().is_success() && is_json(&response) {
+        wrap_response(response, &dry_run).await
+    } else {
+        response
+    };
+    response
+        .headers_mut()
+        .insert(DRY_RUN_HEADER, HeaderValue::from_static("true"));
+
+    response
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+async fn wrap_response(response: Response, dry_run: &DryRun) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            warn!(si.error.message = ?err, "failed to read dry run response body");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    // Empty bodies are valid responses for handlers which only report success
+    let would_be_response = if bytes.is_empty() {
+        serde_json::Value::Null
+    } else {
+        match serde_json::from_slice::<serde_json::Value>(&bytes) {
+            Ok(value) => value,
+            Err(err) => {
+                warn!(si.error.message = ?err, "failed to parse dry run response body");
+                parts.headers.remove(header::CONTENT_LENGTH);
+                return Response::from_parts(parts, boxed(Full::from(bytes)));
+            }
+        }
+    };
+
+    let envelope = json!({
+        "dryRun": true,
+        "response": would_be_response,
+        "summary": dry_run.summary(),
+    });
+    let body = match serde_json::to_vec(&envelope) {
+        Ok(body) => body,
+        Err(err) => {
+            warn!(si.error.message = ?err, "failed to serialize dry run response");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, boxed(Full::from(body)))
+}
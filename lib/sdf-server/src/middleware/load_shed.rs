@@ -0,0 +1,382 @@
+//! Adaptive load shedding, so that bulk operations cannot starve interactive traffic of database
+//! connections and rebaser capacity.
+//!
+//! Requests are grouped into interactive and bulk route classes. Every class has a concurrency
+//! limit which adapts to the latency of its requests: it shrinks while they are slower than the
+//! latency target of the class and grows back while they are faster. On top of that, bulk requests
+//! are shed entirely while the pg pool or the rebaser queue is saturated. Shed requests get a
+//! `503 Service Unavailable` with a `Retry-After` hint before any work is done for them.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use dal::ServicesContext;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use strum::AsRefStr;
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+use tokio_util::sync::CancellationToken;
+
+/// The path segments of the routes which start bulk operations.
+const BULK_PATH_SEGMENTS: &[&str] = &[
+    "apply",
+    "apply_change_set",
+    "contribute",
+    "contribute_delta",
+    "export",
+    "force_apply",
+    "import",
+    "install",
+    "install_module",
+    "rebase_on_base",
+    "refresh",
+    "sync",
+    "upgrade_component",
+];
+/// The web socket routes hold their connection open, so their latency says nothing about load.
+const UNSHED_PATH_PREFIXES: &[&str] = &["/api/ws", "/api/readiness"];
+/// How much a single request moves the latency average of its route class.
+const LATENCY_EWMA_WEIGHT: f64 = 0.1;
+/// How much the concurrency limit of a route class shrinks for every request over its target.
+const LIMIT_DECREASE_FACTOR: f64 = 0.95;
+const SATURATED_RETRY_AFTER: Duration = Duration::from_secs(5);
+const OVER_BUDGET_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// The budgets for each class of routes and the thresholds at which dependencies are considered
+/// saturated. Load shedding is disabled by default.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LoadShedConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_interactive_budget")]
+    pub interactive: RouteClassBudget,
+    #[serde(default = "default_bulk_budget")]
+    pub bulk: RouteClassBudget,
+    /// The pg pool is saturated once this many callers wait for a connection per connection of
+    /// the pool.
+    #[serde(default = "default_pg_pool_max_waiting_ratio")]
+    pub pg_pool_max_waiting_ratio: f64,
+    /// The rebaser queue is saturated once this many change sets wait to be processed.
+    #[serde(default = "default_rebaser_max_pending_tasks")]
+    pub rebaser_max_pending_tasks: u64,
+    /// How often the pg pool and the rebaser queue are checked for saturation.
+    #[serde(default = "default_sample_interval_ms")]
+    pub sample_interval_ms: u64,
+}
+
+impl Default for LoadShedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interactive: default_interactive_budget(),
+            bulk: default_bulk_budget(),
+            pg_pool_max_waiting_ratio: default_pg_pool_max_waiting_ratio(),
+            rebaser_max_pending_tasks: default_rebaser_max_pending_tasks(),
+            sample_interval_ms: default_sample_interval_ms(),
+        }
+    }
+}
+
+/// Serves between `min_concurrency` and `max_concurrency` requests at once, depending on whether
+/// they complete within `latency_target_ms` milliseconds.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct RouteClassBudget {
+    pub max_concurrency: usize,
+    pub min_concurrency: usize,
+    pub latency_target_ms: u64,
+}
+
+fn default_interactive_budget() -> RouteClassBudget {
+    RouteClassBudget {
+        max_concurrency: 256,
+        min_concurrency: 32,
+        latency_target_ms: 1_000,
+    }
+}
+
+fn default_bulk_budget() -> RouteClassBudget {
+    RouteClassBudget {
+        max_concurrency: 32,
+        min_concurrency: 2,
+        latency_target_ms: 10_000,
+    }
+}
+
+fn default_pg_pool_max_waiting_ratio() -> f64 {
+    0.5
+}
+
+fn default_rebaser_max_pending_tasks() -> u64 {
+    200
+}
+
+fn default_sample_interval_ms() -> u64 {
+    1_000
+}
+
+#[remain::sorted]
+#[derive(AsRefStr, Clone, Copy, Debug, Eq, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+enum RouteClass {
+    Bulk,
+    Interactive,
+}
+
+impl RouteClass {
+    fn for_request(method: &Method, path: &str) -> Option<Self> {
+        if *method == Method::OPTIONS
+            || UNSHED_PATH_PREFIXES
+                .iter()
+                .any(|prefix| path.starts_with(prefix))
+        {
+            return None;
+        }
+
+        if path
+            .split('/')
+            .any(|segment| BULK_PATH_SEGMENTS.contains(&segment))
+        {
+            Some(Self::Bulk)
+        } else {
+            Some(Self::Interactive)
+        }
+    }
+}
+
+#[remain::sorted]
+#[derive(AsRefStr, Clone, Copy, Debug)]
+#[strum(serialize_all = "snake_case")]
+enum ShedReason {
+    OverBudget,
+    PgPoolSaturated,
+    RebaserSaturated,
+}
+
+impl ShedReason {
+    fn retry_after(&self) -> Duration {
+        match self {
+            Self::OverBudget => OVER_BUDGET_RETRY_AFTER,
+            Self::PgPoolSaturated | Self::RebaserSaturated => SATURATED_RETRY_AFTER,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RouteClassState {
+    in_flight: usize,
+    limit: f64,
+    latency_ewma_ms: Option<f64>,
+}
+
+impl RouteClassState {
+    fn new(budget: &RouteClassBudget) -> Self {
+        Self {
+            in_flight: 0,
+            limit: budget.max_concurrency as f64,
+            latency_ewma_ms: None,
+        }
+    }
+
+    /// Shrinks the limit while requests are over the latency target and grows it back by about
+    /// one request per limit's worth of requests which are under it.
+    fn complete(&mut self, budget: &RouteClassBudget, latency: Duration) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        let latency_ewma_ms = match self.latency_ewma_ms {
+            Some(ewma) => LATENCY_EWMA_WEIGHT * latency_ms + (1.0 - LATENCY_EWMA_WEIGHT) * ewma,
+            None => latency_ms,
+        };
+        self.latency_ewma_ms = Some(latency_ewma_ms);
+
+        self.limit = if latency_ewma_ms > budget.latency_target_ms as f64 {
+            self.limit * LIMIT_DECREASE_FACTOR
+        } else {
+            self.limit + 1.0 / self.limit.max(1.0)
+        }
+        .clamp(
+            budget.min_concurrency.max(1) as f64,
+            budget.max_concurrency.max(1) as f64,
+        );
+    }
+}
+
+/// The state of the load shedding middleware.
+#[derive(Clone, Debug)]
+pub struct LoadShedder {
+    config: Arc<LoadShedConfig>,
+    interactive: Arc<Mutex<RouteClassState>>,
+    bulk: Arc<Mutex<RouteClassState>>,
+    pg_pool_saturated: Arc<AtomicBool>,
+    rebaser_saturated: Arc<AtomicBool>,
+}
+
+impl LoadShedder {
+    pub fn new(config: LoadShedConfig) -> Self {
+        Self {
+            interactive: Arc::new(Mutex::new(RouteClassState::new(&config.interactive))),
+            bulk: Arc::new(Mutex::new(RouteClassState::new(&config.bulk))),
+            config: Arc::new(config),
+            pg_pool_saturated: Default::default(),
+            rebaser_saturated: Default::default(),
+        }
+    }
+
+    /// Checks the pg pool and the rebaser queue for saturation every sample interval, until the
+    /// token is cancelled.
+    pub fn spawn_saturation_sampler(
+        &self,
+        services_context: ServicesContext,
+        token: CancellationToken,
+    ) {
+        let shedder = self.clone();
+        let interval = Duration::from_millis(self.config.sample_interval_ms.max(1));
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => shedder.sample(&services_context).await,
+                    _ = token.cancelled() => break,
+                }
+            }
+        });
+    }
+
+    async fn sample(&self, services_context: &ServicesContext) {
+        let pg_pool_status = services_context.pg_pool().status();
+        let pg_pool_saturated = pg_pool_status.waiting as f64
+            >= pg_pool_status.max_size as f64 * self.config.pg_pool_max_waiting_ratio
+            && pg_pool_status.waiting > 0;
+        self.pg_pool_saturated
+            .store(pg_pool_saturated, Ordering::Relaxed);
+
+        // A failing rebaser check should not take the API down with it
+        let rebaser_saturated = match services_context.rebaser().pending_task_count().await {
+            Ok(pending_tasks) => pending_tasks >= self.config.rebaser_max_pending_tasks,
+            Err(err) => {
+                warn!(si.error.message = ?err, "failed to check rebaser queue for saturation");
+                false
+            }
+        };
+        self.rebaser_saturated
+            .store(rebaser_saturated, Ordering::Relaxed);
+    }
+
+    fn class(&self, class: RouteClass) -> (&Mutex<RouteClassState>, &RouteClassBudget) {
+        match class {
+            RouteClass::Bulk => (&self.bulk, &self.config.bulk),
+            RouteClass::Interactive => (&self.interactive, &self.config.interactive),
+        }
+    }
+
+    fn try_admit(&self, class: RouteClass) -> Result<(), ShedReason> {
+        if class == RouteClass::Bulk {
+            if self.pg_pool_saturated.load(Ordering::Relaxed) {
+                return Err(ShedReason::PgPoolSaturated);
+            }
+            if self.rebaser_saturated.load(Ordering::Relaxed) {
+                return Err(ShedReason::RebaserSaturated);
+            }
+        }
+
+        let (state, _) = self.class(class);
+        let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+        if state.in_flight as f64 >= state.limit.floor() {
+            return Err(ShedReason::OverBudget);
+        }
+        state.in_flight += 1;
+
+        Ok(())
+    }
+
+    fn complete(&self, class: RouteClass, latency: Duration) {
+        let (state, budget) = self.class(class);
+        state
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .complete(budget, latency);
+    }
+}
+
+/// Releases the slot of an admitted request once its response is produced or it is dropped.
+struct Admitted<'a> {
+    shedder: &'a LoadShedder,
+    class: RouteClass,
+    started_at: Instant,
+}
+
+impl Drop for Admitted<'_> {
+    fn drop(&mut self) {
+        self.shedder.complete(self.class, self.started_at.elapsed());
+    }
+}
+
+/// Rejects requests early while their route class is over its budget, or while the dependencies
+/// bulk requests rely on are saturated.
+pub async fn load_shed_middleware(
+    State(shedder): State<LoadShedder>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let Some(class) = RouteClass::for_request(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    match shedder.try_admit(class) {
+        Ok(()) => {
+            let _admitted = Admitted {
+                shedder: &shedder,
+                class,
+                started_at: Instant::now(),
+            };
+            next.run(request).await
+        }
+        Err(reason) => {
+            let retry_after_seconds = reason.retry_after().as_secs().max(1);
+            metric!(
+                counter.sdf.load_shed.rejected = 1,
+                route_class = class.as_ref(),
+                reason = reason.as_ref()
+            );
+            debug!(
+                route_class = class.as_ref(),
+                reason = reason.as_ref(),
+                retry_after_seconds,
+                "shed request",
+            );
+
+            let body = Json(json!({
+                "error": {
+                    "message": format!(
+                        "server is too busy to serve {} requests, please retry in {retry_after_seconds}s",
+                        class.as_ref(),
+                    ),
+                    "code": 42,
+                    "statusCode": StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+                    "retryAfterSeconds": retry_after_seconds,
+                },
+            }));
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, retry_after_seconds.to_string())],
+                body,
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,327 @@
+//! Per-workspace, per-token rate limiting, so that a misbehaving automation script cannot saturate
+//! the API for everyone else in its workspace.
+//!
+//! Requests are grouped into reads, writes and applies, each with its own limit, and every group
+//! is limited by a token bucket keyed by the workspace and subject of the bearer token. The buckets
+//! are stored in the database, so the limit holds across every sdf server rather than per server.
+//! Requests without a valid bearer token are not limited here: the routes themselves either reject
+//! them or serve them anonymously. Rejected requests get a `429 Too Many Requests` with a
+//! `Retry-After` hint.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use dal::WorkspacePk;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use si_data_pg::{PgPool, PgPoolError};
+use si_jwt_public_key::{validate_raw_token, JwtPublicSigningKeyChain, SiJwt};
+use strum::AsRefStr;
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+
+use crate::extract::ValidatedToken;
+
+/// The last path segments of the routes which apply change sets.
+const APPLY_PATH_SEGMENTS: &[&str] = &["apply", "apply_change_set", "force_apply"];
+/// How often buckets which have refilled completely are deleted.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+const TAKE_QUERY: &str =
+    "SELECT taken, retry_after_seconds FROM rate_limit_take_v1($1, $2, $3, $4, $5)";
+const PRUNE_QUERY: &str = "
+    DELETE FROM rate_limit_buckets
+        WHERE updated_at < CLOCK_TIMESTAMP() - make_interval(secs => $1)
+";
+
+/// The limits for each group of routes. Rate limiting is disabled by default.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RateLimitConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_reads_limit")]
+    pub reads: RouteGroupRateLimit,
+    #[serde(default = "default_writes_limit")]
+    pub writes: RouteGroupRateLimit,
+    #[serde(default = "default_applies_limit")]
+    pub applies: RouteGroupRateLimit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            reads: default_reads_limit(),
+            writes: default_writes_limit(),
+            applies: default_applies_limit(),
+        }
+    }
+}
+
+/// Allows `requests` requests every `per_seconds` seconds, which may all be made at once.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct RouteGroupRateLimit {
+    pub requests: u32,
+    pub per_seconds: u64,
+}
+
+impl RouteGroupRateLimit {
+    fn refill_per_second(&self) -> f64 {
+        f64::from(self.requests) / self.per_seconds.max(1) as f64
+    }
+}
+
+fn default_reads_limit() -> RouteGroupRateLimit {
+    RouteGroupRateLimit {
+        requests: 600,
+        per_seconds: 60,
+    }
+}
+
+fn default_writes_limit() -> RouteGroupRateLimit {
+    RouteGroupRateLimit {
+        requests: 120,
+        per_seconds: 60,
+    }
+}
+
+fn default_applies_limit() -> RouteGroupRateLimit {
+    RouteGroupRateLimit {
+        requests: 10,
+        per_seconds: 60,
+    }
+}
+
+#[remain::sorted]
+#[derive(AsRefStr, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+enum RouteGroup {
+    Applies,
+    Reads,
+    Writes,
+}
+
+impl RouteGroup {
+    fn for_request(method: &Method, path: &str) -> Option<Self> {
+        match *method {
+            Method::OPTIONS => None,
+            Method::GET | Method::HEAD => Some(Self::Reads),
+            _ => {
+                let last_segment = path.trim_end_matches('/').rsplit('/').next();
+                if last_segment.is_some_and(|segment| APPLY_PATH_SEGMENTS.contains(&segment)) {
+                    Some(Self::Applies)
+                } else {
+                    Some(Self::Writes)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct RateLimitKey {
+    workspace_pk: WorkspacePk,
+    subject: String,
+    group: RouteGroup,
+}
+
+/// The state of the rate limiting middleware.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    jwt_public_signing_key_chain: JwtPublicSigningKeyChain,
+    pg_pool: PgPool,
+    last_pruned_at: Arc<Mutex<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        config: RateLimitConfig,
+        jwt_public_signing_key_chain: JwtPublicSigningKeyChain,
+        pg_pool: PgPool,
+    ) -> Self {
+        Self {
+            config: Arc::new(config),
+            jwt_public_signing_key_chain,
+            pg_pool,
+            last_pruned_at: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    fn limit(&self, group: RouteGroup) -> &RouteGroupRateLimit {
+        match group {
+            RouteGroup::Applies => &self.config.applies,
+            RouteGroup::Reads => &self.config.reads,
+            RouteGroup::Writes => &self.config.writes,
+        }
+    }
+
+    /// Takes a token from the key's bucket, or returns how long it will take until one is
+    /// available.
+    async fn check(&self, key: &RateLimitKey) -> Result<Result<(), Duration>, PgPoolError> {
+        let limit = *self.limit(key.group);
+        let client = self.pg_pool.get().await?;
+        let row = client
+            .query_one(
+                TAKE_QUERY,
+                &[
+                    &key.workspace_pk,
+                    &key.subject,
+                    &key.group.as_ref(),
+                    &f64::from(limit.requests),
+                    &limit.refill_per_second(),
+                ],
+            )
+            .await?;
+
+        let taken: bool = row.try_get("taken")?;
+        if taken {
+            return Ok(Ok(()));
+        }
+        let retry_after_seconds: Option<f64> = row.try_get("retry_after_seconds")?;
+        Ok(Err(match retry_after_seconds {
+            Some(seconds) => Duration::from_secs_f64(seconds.max(0.0)),
+            None => Duration::from_secs(limit.per_seconds.max(1)),
+        }))
+    }
+
+    /// Deletes buckets which have not been used for longer than it takes them to refill, since
+    /// they are no different from the full buckets created on the next request. Runs at most once
+    /// every [`PRUNE_INTERVAL`] per server.
+    fn maybe_prune(&self) {
+        {
+            let mut last_pruned_at = self
+                .last_pruned_at
+                .lock()
+                .unwrap_or_else(|err| err.into_inner());
+            if last_pruned_at.elapsed() < PRUNE_INTERVAL {
+                return;
+            }
+            *last_pruned_at = Instant::now();
+        }
+
+        let idle_seconds = [
+            &self.config.reads,
+            &self.config.writes,
+            &self.config.applies,
+        ]
+        .iter()
+        .map(|limit| limit.per_seconds.max(1))
+        .max()
+        .unwrap_or(1) as f64;
+        let pg_pool = self.pg_pool.clone();
+        tokio::spawn(async move {
+            let result = match pg_pool.get().await {
+                Ok(client) => client
+                    .execute(PRUNE_QUERY, &[&idle_seconds])
+                    .await
+                    .map_err(PgPoolError::from),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                warn!(si.error.message = ?err, "failed to prune rate limit buckets");
+            }
+        });
+    }
+}
+
+/// Rejects requests once the bearer token they were made with has exceeded the limit of their
+/// route group.
+pub async fn rate_limit_middleware(
+    State(limiter): State<RateLimiter>,
+    mut request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let Some(group) = RouteGroup::for_request(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let Some(raw_token) = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(ToOwned::to_owned)
+    else {
+        return next.run(request).await;
+    };
+    let Ok(token) =
+        validate_raw_token(limiter.jwt_public_signing_key_chain.clone(), raw_token).await
+    else {
+        return next.run(request).await;
+    };
+
+    let key = RateLimitKey {
+        workspace_pk: token.custom.workspace_id(),
+        subject: token_subject(&token),
+        group,
+    };
+    let workspace_pk = key.workspace_pk;
+    // Spare the routes from validating the token again
+    request.extensions_mut().insert(ValidatedToken(token));
+
+    let result = limiter.check(&key).await;
+    limiter.maybe_prune();
+    match result {
+        Ok(Ok(())) => next.run(request).await,
+        // Fail open: an unavailable database should not take down every route with it
+        Err(err) => {
+            warn!(
+                si.error.message = ?err,
+                si.workspace.id = %workspace_pk,
+                "failed to check rate limit, allowing request",
+            );
+            next.run(request).await
+        }
+        Ok(Err(retry_after)) => {
+            let retry_after_seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            metric!(
+                counter.sdf.rate_limit.rejected = 1,
+                route_group = group.as_ref()
+            );
+            debug!(
+                si.workspace.id = %workspace_pk,
+                route_group = group.as_ref(),
+                retry_after_seconds,
+                "rate limited request",
+            );
+
+            let body = Json(json!({
+                "error": {
+                    "message": format!(
+                        "rate limit exceeded for {} requests, please retry in {retry_after_seconds}s",
+                        group.as_ref(),
+                    ),
+                    "code": 42,
+                    "statusCode": StatusCode::TOO_MANY_REQUESTS.as_u16(),
+                    "retryAfterSeconds": retry_after_seconds,
+                },
+            }));
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after_seconds.to_string())],
+                body,
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Identifies the token within its workspace: automation tokens by their id, so that every token
+/// has its own limit, and any other token by the user it was issued to.
+fn token_subject(token: &SiJwt) -> String {
+    token
+        .jwt_id
+        .clone()
+        .or_else(|| token.subject.clone())
+        .unwrap_or_else(|| token.custom.user_id().to_string())
+}
@@ -1,18 +1,26 @@
-use std::future::IntoFuture as _;
+use std::{future::IntoFuture as _, time::Duration};
 
 use audit_database::{
     AuditDatabaseContext, AuditDatabaseContextError, AuditDatabaseMigrationError,
 };
 use dal::{
-    cached_module::CachedModule, slow_rt::SlowRuntimeError,
-    workspace_snapshot::migrator::SnapshotGraphMigrator, ServicesContext,
+    cached_module::CachedModule,
+    provider_credential::{ProviderCredential, ProviderCredentialError},
+    slow_rt::SlowRuntimeError,
+    workspace_snapshot::migrator::SnapshotGraphMigrator,
+    ServicesContext,
 };
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::task::JoinError;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
-use crate::{init, Config};
+use crate::{init, Config, DataResidency};
+
+/// How many provider credentials are re-encrypted at a time after a symmetric key rotation.
+const REENCRYPT_BATCH_SIZE: usize = 100;
+/// How long to pause between batches of re-encrypted provider credentials.
+const REENCRYPT_BATCH_PAUSE: Duration = Duration::from_millis(100);
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -35,6 +43,8 @@ pub enum MigratorError {
     MigrateSnapshots(#[source] Box<dyn std::error::Error + 'static + Sync + Send>),
     #[error("module index url not set")]
     ModuleIndexNotSet,
+    #[error("error while re-encrypting provider credentials: {0}")]
+    ReencryptProviderCredentials(#[source] ProviderCredentialError),
     #[error("slow runtime: {0}")]
     SlowRuntime(#[from] SlowRuntimeError),
 }
@@ -61,6 +71,7 @@ type MigratorResult<T> = std::result::Result<T, MigratorError>;
 pub struct Migrator {
     services_context: ServicesContext,
     audit_database_context: AuditDatabaseContext,
+    data_residency: DataResidency,
 }
 
 impl Migrator {
@@ -71,16 +82,27 @@ impl Migrator {
         helping_tasks_token: CancellationToken,
     ) -> MigratorResult<Self> {
         let (services_context, layer_db_graceful_shutdown) =
-            init::services_context_from_config(&config, helping_tasks_token).await?;
+            init::services_context_from_config(&config, helping_tasks_token.clone()).await?;
+        let (data_residency, regional_layer_db_graceful_shutdowns) =
+            init::data_residency_from_config(
+                config.data_residency(),
+                &services_context,
+                helping_tasks_token,
+            )
+            .await?;
 
         // Spawn helping tasks and track them for graceful shutdown
         helping_tasks_tracker.spawn(layer_db_graceful_shutdown.into_future());
+        for graceful_shutdown in regional_layer_db_graceful_shutdowns {
+            helping_tasks_tracker.spawn(graceful_shutdown.into_future());
+        }
 
         let audit_database_context = AuditDatabaseContext::from_config(config.audit()).await?;
 
         Ok(Self::from_services(
             services_context,
             audit_database_context,
+            data_residency,
         ))
     }
 
@@ -88,10 +110,12 @@ impl Migrator {
     pub fn from_services(
         services_context: ServicesContext,
         audit_database_context: AuditDatabaseContext,
+        data_residency: DataResidency,
     ) -> Self {
         Self {
             services_context,
             audit_database_context,
+            data_residency,
         }
     }
 
@@ -111,17 +135,34 @@ impl Migrator {
             .await
             .map_err(|err| span.record_err(err))?;
 
-        self.migrate_layer_db_database()
-            .await
-            .map_err(|err| span.record_err(err))?;
+        // The databases of every region are migrated, starting with the default ones
+        let regions = self
+            .data_residency
+            .regions()
+            .map(|(region, services_context)| (Some(region.to_owned()), services_context.clone()));
+        for (region, services_context) in
+            std::iter::once((None, self.services_context.clone())).chain(regions)
+        {
+            if let Some(region) = &region {
+                info!(region, "migrating the databases of region");
+            }
 
-        self.migrate_dal_database()
-            .await
-            .map_err(|err| span.record_err(err))?;
+            Self::migrate_layer_db_database(&services_context)
+                .await
+                .map_err(|err| span.record_err(err))?;
 
-        self.migrate_snapshots()
-            .await
-            .map_err(|err| span.record_err(err))?;
+            Self::migrate_dal_database(&services_context)
+                .await
+                .map_err(|err| span.record_err(err))?;
+
+            Self::migrate_snapshots(&services_context)
+                .await
+                .map_err(|err| span.record_err(err))?;
+
+            Self::reencrypt_provider_credentials(&services_context)
+                .await
+                .map_err(|err| span.record_err(err))?;
+        }
 
         if update_module_cache {
             self.migrate_module_cache()
@@ -145,8 +186,8 @@ impl Migrator {
         level = "info",
         skip_all
     )]
-    async fn migrate_layer_db_database(&self) -> MigratorResult<()> {
-        self.services_context
+    async fn migrate_layer_db_database(services_context: &ServicesContext) -> MigratorResult<()> {
+        services_context
             .layer_db()
             .pg_migrate()
             .await
@@ -154,15 +195,15 @@ impl Migrator {
     }
 
     #[instrument(name = "sdf.migrator.migrate_dal_database", level = "info", skip_all)]
-    async fn migrate_dal_database(&self) -> MigratorResult<()> {
-        dal::migrate_all_with_progress(&self.services_context)
+    async fn migrate_dal_database(services_context: &ServicesContext) -> MigratorResult<()> {
+        dal::migrate_all_with_progress(services_context)
             .await
             .map_err(MigratorError::MigrateDalDatabase)
     }
 
     #[instrument(name = "sdf.migrator.migrate_snapshots", level = "info", skip_all)]
-    async fn migrate_snapshots(&self) -> MigratorResult<()> {
-        let dal_context = self.services_context.clone().into_builder(true);
+    async fn migrate_snapshots(services_context: &ServicesContext) -> MigratorResult<()> {
+        let dal_context = services_context.clone().into_builder(true);
         let ctx = dal_context
             .build_default()
             .await
@@ -179,6 +220,29 @@ impl Migrator {
         Ok(())
     }
 
+    /// Re-wraps the provider credentials still wrapped with a retired symmetric key with the
+    /// active one, so that the retired key can be dropped from the configuration.
+    #[instrument(
+        name = "sdf.migrator.reencrypt_provider_credentials",
+        level = "info",
+        skip_all
+    )]
+    async fn reencrypt_provider_credentials(
+        services_context: &ServicesContext,
+    ) -> MigratorResult<()> {
+        let dal_context = services_context.clone().into_builder(true);
+        let ctx = dal_context
+            .build_default()
+            .await
+            .map_err(|err| MigratorError::ReencryptProviderCredentials(err.into()))?;
+
+        ProviderCredential::reencrypt_materials(&ctx, REENCRYPT_BATCH_SIZE, REENCRYPT_BATCH_PAUSE)
+            .await
+            .map_err(MigratorError::ReencryptProviderCredentials)?;
+
+        Ok(())
+    }
+
     #[instrument(name = "sdf.migrator.migrate_module_cache", level = "info", skip_all)]
     async fn migrate_module_cache(&self) -> MigratorResult<()> {
         let dal_context = self.services_context.clone().into_builder(true);
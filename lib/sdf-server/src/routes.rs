@@ -20,6 +20,7 @@ use tower_http::{
 
 use crate::{
     app_state::{AppState, ApplicationRuntimeMode},
+    middleware::change_set_apply_lock_v1_middleware,
     ServerError,
 };
 
@@ -48,22 +49,43 @@ async fn app_state_middeware<B>(
 pub fn routes(state: AppState) -> Router {
     let mut router: Router<AppState> = Router::new();
     router = router
-        .nest("/api/action", crate::service::action::routes())
+        .nest(
+            "/api/action",
+            change_set_mutations(state.clone(), crate::service::action::routes()),
+        )
         .nest("/api/node_debug", crate::service::node_debug::routes())
-        .nest("/api/attribute", crate::service::attribute::routes())
+        .nest(
+            "/api/attribute",
+            change_set_mutations(state.clone(), crate::service::attribute::routes()),
+        )
         .nest("/api/change_set", crate::service::change_set::routes())
-        .nest("/api/component", crate::service::component::routes())
-        .nest("/api/diagram", crate::service::diagram::routes())
+        .nest(
+            "/api/component",
+            change_set_mutations(state.clone(), crate::service::component::routes()),
+        )
+        .nest(
+            "/api/diagram",
+            change_set_mutations(state.clone(), crate::service::diagram::routes()),
+        )
         .nest("/api/graphviz", crate::service::graphviz::routes())
         .nest(
             "/api/qualification",
             crate::service::qualification::routes(),
         )
-        .nest("/api/secret", crate::service::secret::routes())
+        .nest(
+            "/api/secret",
+            change_set_mutations(state.clone(), crate::service::secret::routes()),
+        )
         .nest("/api/session", crate::service::session::routes())
         .nest("/api/ws", crate::service::ws::routes())
-        .nest("/api/module", crate::service::module::routes())
-        .nest("/api/variant", crate::service::variant::routes())
+        .nest(
+            "/api/module",
+            change_set_mutations(state.clone(), crate::service::module::routes()),
+        )
+        .nest(
+            "/api/variant",
+            change_set_mutations(state.clone(), crate::service::variant::routes()),
+        )
         .nest("/api/v2", crate::service::v2::routes(state.clone()))
         .nest("/api/whoami", crate::service::whoami::routes())
         .layer(CompressionLayer::new())
@@ -97,6 +119,9 @@ pub fn routes(state: AppState) -> Router {
             state.clone(),
             app_state_middeware,
         ))
+        // the readiness probe is also served in maintenance mode, since it reports on the
+        // dependencies of the server rather than on whether it accepts requests
+        .nest("/api/readiness", crate::service::readiness::routes())
         // root health route is currently pinged by auth portal to check if backend is up and running so we need permissive CORS headers
         // it is last in the list so that it still services even if we are in maintenance mode
         .nest(
@@ -112,6 +137,14 @@ pub fn routes(state: AppState) -> Router {
     router.with_state(state)
 }
 
+/// Rejects the v1 mutations of a change set while it is being applied or is awaiting approval.
+fn change_set_mutations(state: AppState, routes: Router<AppState>) -> Router<AppState> {
+    routes.route_layer(middleware::from_fn_with_state(
+        state,
+        change_set_apply_lock_v1_middleware,
+    ))
+}
+
 async fn system_status_route() -> Json<Value> {
     Json(json!({ "ok": true }))
 }
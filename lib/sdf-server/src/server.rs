@@ -2,7 +2,7 @@ use std::{fmt, future::IntoFuture as _, net::SocketAddr, path::PathBuf, sync::Ar
 
 use asset_sprayer::AssetSprayer;
 use audit_database::AuditDatabaseContext;
-use axum::{async_trait, routing::IntoMakeService, Router};
+use axum::{async_trait, routing::IntoMakeService, Extension, Router};
 use dal::ServicesContext;
 use hyper::server::accept::Accept;
 use nats_multiplexer::Multiplexer;
@@ -10,7 +10,7 @@ use nats_multiplexer_client::MultiplexerClient;
 use si_data_spicedb::SpiceDbClient;
 use si_jwt_public_key::JwtPublicSigningKeyChain;
 use si_posthog::PosthogClient;
-use telemetry::prelude::*;
+use telemetry::{prelude::*, ApplicationTelemetryClient};
 use tokio::{
     io::{AsyncRead, AsyncWrite},
     signal,
@@ -19,12 +19,17 @@ use tokio::{
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 use crate::{
-    init,
+    embedded_web, init,
+    middleware::{
+        load_shed_middleware, rate_limit_middleware, LoadShedConfig, LoadShedder, RateLimitConfig,
+        RateLimiter,
+    },
     nats_multiplexer::{CRDT_MULTIPLEXER_SUBJECT, WS_MULTIPLEXER_SUBJECT},
     runnable::Runnable,
+    service::v2::graphql,
     uds::UdsIncomingStream,
-    ApplicationRuntimeMode, AxumApp, Config, IncomingStream, Migrator, ServerError, ServerResult,
-    WorkspacePermissions, WorkspacePermissionsMode,
+    ApplicationRuntimeMode, AxumApp, Config, DataResidency, IncomingStream, Migrator, ServerError,
+    ServerResult, WorkspacePermissions, WorkspacePermissionsMode,
 };
 
 /// Server metadata, used with telemetry.
@@ -51,6 +56,7 @@ pub struct Server {
 struct MigratorToolkit {
     services_context: ServicesContext,
     audit_database_context: AuditDatabaseContext,
+    data_residency: DataResidency,
 }
 
 impl fmt::Debug for Server {
@@ -80,13 +86,22 @@ impl Server {
         token: CancellationToken,
         helping_tasks_tracker: &TaskTracker,
         helping_tasks_token: CancellationToken,
+        telemetry: ApplicationTelemetryClient,
     ) -> ServerResult<Self> {
         let (services_context, layer_db_graceful_shutdown) =
             init::services_context_from_config(&config, helping_tasks_token.clone()).await?;
+        let (data_residency, regional_layer_db_graceful_shutdowns) =
+            init::data_residency_from_config(
+                config.data_residency(),
+                &services_context,
+                helping_tasks_token.clone(),
+            )
+            .await?;
 
         let jwt_public_signing_key = init::load_jwt_public_signing_key(
             config.jwt_signing_public_key().clone(),
             config.jwt_secondary_signing_public_key().cloned(),
+            config.jwt_automation_signing_key().cloned(),
         )
         .await?;
         let (posthog_sender, posthog_client) =
@@ -127,6 +142,9 @@ impl Server {
 
         // Spawn helping tasks and track them for graceful shutdown
         helping_tasks_tracker.spawn(layer_db_graceful_shutdown.into_future());
+        for graceful_shutdown in regional_layer_db_graceful_shutdowns {
+            helping_tasks_tracker.spawn(graceful_shutdown.into_future());
+        }
         helping_tasks_tracker.spawn(posthog_sender.run());
         helping_tasks_tracker.spawn(ws_multiplexer.run());
         helping_tasks_tracker.spawn(crdt_multiplexer.run());
@@ -149,6 +167,12 @@ impl Server {
             token,
             spicedb_client,
             audit_database_context,
+            config.serve_embedded_web(),
+            config.serve_graphql(),
+            config.rate_limit().clone(),
+            config.load_shed().clone(),
+            data_residency,
+            telemetry,
         )
         .await
     }
@@ -171,7 +195,26 @@ impl Server {
         token: CancellationToken,
         spicedb_client: Option<SpiceDbClient>,
         audit_database_context: AuditDatabaseContext,
+        serve_embedded_web: bool,
+        serve_graphql: bool,
+        rate_limit_config: RateLimitConfig,
+        load_shed_config: LoadShedConfig,
+        data_residency: DataResidency,
+        telemetry: ApplicationTelemetryClient,
     ) -> ServerResult<Self> {
+        let rate_limiter = rate_limit_config.enabled.then(|| {
+            RateLimiter::new(
+                rate_limit_config,
+                jwt_public_signing_key_chain.clone(),
+                services_context.pg_pool().clone(),
+            )
+        });
+        let load_shedder = load_shed_config.enabled.then(|| {
+            let load_shedder = LoadShedder::new(load_shed_config);
+            load_shedder.spawn_saturation_sampler(services_context.clone(), token.clone());
+            load_shedder
+        });
+
         let app = AxumApp::from_services(
             services_context.clone(),
             jwt_public_signing_key_chain,
@@ -189,6 +232,39 @@ impl Server {
             audit_database_context.clone(),
         )
         .into_inner();
+        let app = if serve_embedded_web {
+            embedded_web::with_embedded_web(app)
+        } else {
+            app
+        };
+        let app = if serve_graphql {
+            app.layer(Extension(graphql::build_schema()))
+        } else {
+            app
+        };
+        // Lets contexts be built from the services of the region a workspace resides in
+        let app = if data_residency.is_empty() {
+            app
+        } else {
+            app.layer(Extension(data_residency.clone()))
+        };
+        // Lets admin routes adjust this process' tracing level at runtime
+        let app = app.layer(Extension(telemetry));
+        let app = match rate_limiter {
+            Some(rate_limiter) => app.layer(axum::middleware::from_fn_with_state(
+                rate_limiter,
+                rate_limit_middleware,
+            )),
+            None => app,
+        };
+        // Shedding happens before rate limiting, so that shed requests do not use up tokens
+        let app = match load_shedder {
+            Some(load_shedder) => app.layer(axum::middleware::from_fn_with_state(
+                load_shedder,
+                load_shed_middleware,
+            )),
+            None => app,
+        };
 
         let (inner, socket): (Box<dyn Runnable + Send>, _) = match incoming_stream {
             IncomingStream::TcpSocket(socket_addr) => {
@@ -227,6 +303,7 @@ impl Server {
                 services_context,
                 // TODO(nick): split the migrator context and the reader-only context (should be read-only pg pool).
                 audit_database_context,
+                data_residency,
             },
             socket,
         })
@@ -244,6 +321,7 @@ impl Server {
         Migrator::from_services(
             self.migrator_toolkit.services_context.clone(),
             self.migrator_toolkit.audit_database_context.clone(),
+            self.migrator_toolkit.data_residency.clone(),
         )
     }
 }
@@ -19,6 +19,7 @@ pub mod graphviz;
 pub mod module;
 pub mod node_debug;
 pub mod qualification;
+pub mod readiness;
 pub mod secret;
 pub mod session;
 pub mod v2;
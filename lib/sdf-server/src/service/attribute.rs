@@ -8,6 +8,7 @@ use thiserror::Error;
 use super::impl_default_error_into_response;
 use crate::AppState;
 
+pub mod explain;
 pub mod get_prototype_arguments;
 
 #[remain::sorted]
@@ -40,8 +41,10 @@ pub type AttributeResult<T> = Result<T, AttributeError>;
 impl_default_error_into_response!(AttributeError);
 
 pub fn routes() -> Router<AppState> {
-    Router::new().route(
-        "/get_prototype_arguments",
-        get(get_prototype_arguments::get_prototype_arguments),
-    )
+    Router::new()
+        .route(
+            "/get_prototype_arguments",
+            get(get_prototype_arguments::get_prototype_arguments),
+        )
+        .route("/explain", get(explain::explain))
 }
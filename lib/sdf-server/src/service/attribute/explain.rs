@@ -0,0 +1,27 @@
+use axum::{extract::Query, Json};
+use dal::attribute::value::AttributeValueExplanation;
+use dal::{AttributeValue, AttributeValueId, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::AttributeResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainRequest {
+    pub attribute_value_id: AttributeValueId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn explain(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ExplainRequest>,
+) -> AttributeResult<Json<AttributeValueExplanation>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let explanation = AttributeValue::explain(&ctx, request.attribute_value_id).await?;
+
+    Ok(Json(explanation))
+}
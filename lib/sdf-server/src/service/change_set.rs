@@ -7,7 +7,7 @@ use axum::{
 use dal::{
     action::{prototype::ActionPrototypeError, ActionError},
     ActionPrototypeId, ChangeSetApplyError as DalChangeSetApplyError,
-    ChangeSetError as DalChangeSetError, ChangeSetId, ComponentError, FuncError, SchemaError,
+    ChangeSetError as DalChangeSetError, ComponentError, FuncError, SchemaError,
     SchemaVariantError, StandardModelError, TransactionsError, WorkspaceError,
     WorkspaceSnapshotError, WsEventError,
 };
@@ -15,7 +15,7 @@ use dal::{
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::AppState;
+use crate::{middleware::ChangeSetApplyLockError, AppState};
 
 use super::ApiError;
 
@@ -42,6 +42,8 @@ pub enum ChangeSetError {
     ActionPrototype(#[from] ActionPrototypeError),
     #[error("cannot abandon head change set")]
     CannotAbandonHead,
+    #[error("change set apply lock error: {0}")]
+    ChangeSetApplyLock(#[from] ChangeSetApplyLockError),
     #[error("change set not found")]
     ChangeSetNotFound,
     #[error("component error: {0}")]
@@ -50,8 +52,6 @@ pub enum ChangeSetError {
     DalChangeSet(#[from] DalChangeSetError),
     #[error("dal change set apply error: {0}")]
     DalChangeSetApply(#[from] DalChangeSetApplyError),
-    #[error("dvu roots are not empty for change set: {0}")]
-    DvuRootsNotEmpty(ChangeSetId),
     #[error("func error: {0}")]
     Func(#[from] FuncError),
     #[error("invalid header name {0}")]
@@ -77,12 +77,16 @@ pub type ChangeSetResult<T> = std::result::Result<T, ChangeSetError>;
 impl IntoResponse for ChangeSetError {
     fn into_response(self) -> Response {
         let (status_code, error_message) = match self {
+            ChangeSetError::ChangeSetApplyLock(err) => return err.into_response(),
             ChangeSetError::ActionAlreadyEnqueued(_) => {
                 (StatusCode::NOT_MODIFIED, self.to_string())
             }
             ChangeSetError::ChangeSetNotFound => (StatusCode::NOT_FOUND, self.to_string()),
             ChangeSetError::DalChangeSetApply(_) => (StatusCode::CONFLICT, self.to_string()),
-            ChangeSetError::DvuRootsNotEmpty(_) => (
+            ChangeSetError::DalChangeSet(DalChangeSetError::ChangeSetNotApprovedForApply(_)) => {
+                (StatusCode::PRECONDITION_FAILED, self.to_string())
+            }
+            ChangeSetError::DalChangeSet(DalChangeSetError::DvuRootsNotEmpty(_)) => (
                 StatusCode::PRECONDITION_REQUIRED,
                 "There are dependent values that still need to be calculated. Please retry!"
                     .to_string(),
@@ -1,7 +1,10 @@
 use std::num::ParseIntError;
 
 use super::ApiError;
-use crate::{service::component::conflicts_for_component::conflicts_for_component, AppState};
+use crate::{
+    extract::IfMatch, service::component::conflicts_for_component::conflicts_for_component,
+    AppState,
+};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -22,7 +25,7 @@ use dal::{
     SchemaVariantError, SecretError as DalSecretError, WsEventError,
 };
 use dal::{attribute::value::AttributeValueError, component::debug::ComponentDebugViewError};
-use dal::{ChangeSetError, TransactionsError};
+use dal::{ChangeSetError, DalContext, TransactionsError};
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::task::JoinError;
@@ -33,6 +36,7 @@ pub mod delete_property_editor_value;
 pub mod get_actions;
 pub mod get_code;
 pub mod get_diff;
+pub mod get_prop_value_suggestions;
 pub mod get_property_editor_schema;
 pub mod get_property_editor_values;
 pub mod get_resource;
@@ -42,6 +46,7 @@ pub mod list_qualifications;
 mod manage;
 pub mod refresh;
 pub mod restore_default_function;
+pub mod set_labels;
 pub mod set_name;
 pub mod set_resource_id;
 pub mod set_type;
@@ -82,6 +87,8 @@ pub enum ComponentError {
     NotFound(ComponentId),
     #[error(transparent)]
     ParseInt(#[from] ParseIntError),
+    #[error("component {0} has been modified (current version: {1})")]
+    PreconditionFailed(ComponentId, String),
     #[error(transparent)]
     Prop(#[from] PropError),
     #[error("property editor error: {0}")]
@@ -141,11 +148,16 @@ impl IntoResponse for ComponentError {
             ComponentError::SchemaVariantUpgradeSkipped => {
                 (StatusCode::NOT_MODIFIED, self.to_string())
             }
+            ComponentError::PreconditionFailed(_, _)
+            | ComponentError::Transactions(TransactionsError::RebasePreconditionFailed(..)) => {
+                (StatusCode::PRECONDITION_FAILED, self.to_string())
+            }
             ComponentError::KeyAlreadyExists(_) => {
                 (StatusCode::UNPROCESSABLE_ENTITY, self.to_string())
             }
             ComponentError::DalComponent(err) => match err {
                 DalComponentError::NotFound(_) => (StatusCode::NOT_FOUND, err.to_string()),
+                DalComponentError::InvalidLabelKey(_) => (StatusCode::BAD_REQUEST, err.to_string()),
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
             },
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
@@ -155,6 +167,43 @@ impl IntoResponse for ComponentError {
     }
 }
 
+/// Returns the quoted entity tag for the current version of a [`Component`](dal::Component).
+pub async fn component_etag(
+    ctx: &DalContext,
+    component_id: ComponentId,
+) -> ComponentResult<String> {
+    let version = dal::Component::version_by_id(ctx, component_id).await?;
+    Ok(format!("\"{version}\""))
+}
+
+/// Ensures that the provided "If-Match" header matches the current version of the
+/// [`Component`](dal::Component), returning [`ComponentError::PreconditionFailed`] otherwise.
+///
+/// The version is checked again when the context is committed, so a concurrent write which lands
+/// first makes the commit fail with a precondition failure as well.
+pub async fn check_if_match(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    if_match: &IfMatch,
+) -> ComponentResult<()> {
+    if if_match.0.is_none() {
+        return Ok(());
+    }
+
+    let version = dal::Component::version_by_id(ctx, component_id).await?;
+    if !if_match.matches(version.to_string()) {
+        return Err(ComponentError::PreconditionFailed(
+            component_id,
+            version.to_string(),
+        ));
+    }
+    if !if_match.is_wildcard() {
+        ctx.add_commit_precondition(component_id, version).await;
+    }
+
+    Ok(())
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/get_actions", get(get_actions::get_actions))
@@ -172,6 +221,10 @@ pub fn routes() -> Router<AppState> {
         )
         .route("/get_code", get(get_code::get_code))
         .route("/get_diff", get(get_diff::get_diff))
+        .route(
+            "/get_prop_value_suggestions",
+            get(get_prop_value_suggestions::get_prop_value_suggestions),
+        )
         .route("/get_resource", get(get_resource::get_resource))
         .route(
             "/update_property_editor_value",
@@ -191,6 +244,7 @@ pub fn routes() -> Router<AppState> {
         )
         .route("/set_type", post(set_type::set_type))
         .route("/set_name", post(set_name::set_name))
+        .route("/set_labels", post(set_labels::set_labels))
         .route("/set_resource_id", post(set_resource_id::set_resource_id))
         .route("/refresh", post(refresh::refresh))
         .route("/debug", get(debug::debug_component))
@@ -8,8 +8,11 @@ use dal::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext},
-    service::{component::ComponentResult, force_change_set_response::ForceChangeSetResponse},
+    extract::{AccessBuilder, HandlerContext, IfMatch},
+    service::{
+        component::{check_if_match, ComponentResult},
+        force_change_set_response::ForceChangeSetResponse,
+    },
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -26,10 +29,13 @@ pub struct DeletePropertyEditorValueRequest {
 pub async fn delete_property_editor_value(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    if_match: IfMatch,
     Json(request): Json<DeletePropertyEditorValueRequest>,
 ) -> ComponentResult<ForceChangeSetResponse<()>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
+    check_if_match(&ctx, request.component_id, &if_match).await?;
+
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     AttributeValue::remove_by_id(&ctx, request.attribute_value_id).await?;
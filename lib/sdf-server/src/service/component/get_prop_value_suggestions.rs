@@ -0,0 +1,34 @@
+use axum::{extract::Query, Json};
+use dal::{component::suggestion::PropValueSuggestion, Component, ComponentId, PropId, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPropValueSuggestionsRequest {
+    pub component_id: ComponentId,
+    pub prop_id: PropId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPropValueSuggestionsResponse {
+    pub suggestions: Vec<PropValueSuggestion>,
+}
+
+pub async fn get_prop_value_suggestions(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetPropValueSuggestionsRequest>,
+) -> ComponentResult<Json<GetPropValueSuggestionsResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let suggestions =
+        Component::suggest_values_for_prop(&ctx, request.component_id, request.prop_id).await?;
+
+    Ok(Json(GetPropValueSuggestionsResponse { suggestions }))
+}
@@ -1,8 +1,8 @@
-use axum::{extract::Query, Json};
+use axum::{extract::Query, http::header, Json};
 use dal::{property_editor::values::PropertyEditorValues, ComponentId, Visibility};
 use serde::{Deserialize, Serialize};
 
-use super::ComponentResult;
+use super::{component_etag, ComponentResult};
 use crate::extract::{AccessBuilder, HandlerContext};
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -15,16 +15,19 @@ pub struct GetPropertyEditorValuesRequest {
 
 pub type GetPropertyEditorValuesResponse = PropertyEditorValues;
 
+/// Returns the property editor values for a [`Component`](dal::Component) alongside an "ETag"
+/// header containing its current version, which can be sent back as "If-Match" on updates.
 pub async fn get_property_editor_values(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     Query(request): Query<GetPropertyEditorValuesRequest>,
-) -> ComponentResult<Json<serde_json::Value>> {
+) -> ComponentResult<([(header::HeaderName, String); 1], Json<serde_json::Value>)> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let prop_edit_values = PropertyEditorValues::assemble(&ctx, request.component_id).await?;
 
     let prop_edit_values = serde_json::to_value(prop_edit_values)?;
+    let etag = component_etag(&ctx, request.component_id).await?;
 
-    Ok(Json(prop_edit_values))
+    Ok(([(header::ETAG, etag)], Json(prop_edit_values)))
 }
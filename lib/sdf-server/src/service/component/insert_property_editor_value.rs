@@ -8,11 +8,11 @@ use dal::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext},
+    extract::{AccessBuilder, HandlerContext, IfMatch},
     service::force_change_set_response::ForceChangeSetResponse,
 };
 
-use super::{ComponentError, ComponentResult};
+use super::{check_if_match, ComponentError, ComponentResult};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -29,10 +29,13 @@ pub struct InsertPropertyEditorValueRequest {
 pub async fn insert_property_editor_value(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    if_match: IfMatch,
     Json(request): Json<InsertPropertyEditorValueRequest>,
 ) -> ComponentResult<ForceChangeSetResponse<()>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
+    check_if_match(&ctx, request.component_id, &if_match).await?;
+
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     if let Some(key) = &request.key {
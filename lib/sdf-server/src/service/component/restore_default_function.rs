@@ -5,9 +5,9 @@ use axum::{
 use dal::{AttributeValue, AttributeValueId, ChangeSet, Visibility};
 use serde::{Deserialize, Serialize};
 
-use super::ComponentResult;
+use super::{check_if_match, ComponentResult};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{AccessBuilder, HandlerContext, IfMatch, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -26,10 +26,14 @@ pub async fn restore_default_function(
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    if_match: IfMatch,
     Json(request): Json<RestoreDefaultFunctionRequest>,
 ) -> ComponentResult<ForceChangeSetResponse<()>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
+    let component_id = AttributeValue::component_id(&ctx, request.attribute_value_id).await?;
+    check_if_match(&ctx, component_id, &if_match).await?;
+
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     AttributeValue::use_default_prototype(&ctx, request.attribute_value_id).await?;
@@ -0,0 +1,81 @@
+use std::collections::{BTreeMap, HashMap};
+
+use axum::{
+    extract::{Host, OriginalUri},
+    Json,
+};
+use dal::{ChangeSet, Component, ComponentId, Visibility, WsEvent};
+use serde::{Deserialize, Serialize};
+
+use super::{check_if_match, ComponentResult};
+use crate::{
+    extract::{AccessBuilder, HandlerContext, IfMatch, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    track,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLabelsRequest {
+    pub component_id: ComponentId,
+    /// The full set of labels for the component. Labels that are not present are removed.
+    pub labels: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn set_labels(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    if_match: IfMatch,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Json(SetLabelsRequest {
+        component_id,
+        labels,
+        visibility,
+    }): Json<SetLabelsRequest>,
+) -> ComponentResult<ForceChangeSetResponse<()>> {
+    let mut ctx = builder.build(request_ctx.build(visibility)).await?;
+
+    check_if_match(&ctx, component_id, &if_match).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let component = Component::get_by_id(&ctx, component_id)
+        .await?
+        .set_labels(&ctx, labels)
+        .await?;
+
+    let mut socket_map = HashMap::new();
+    let payload = component
+        .into_frontend_type(
+            &ctx,
+            None,
+            component.change_status(&ctx).await?,
+            &mut socket_map,
+        )
+        .await?;
+    WsEvent::component_updated(&ctx, payload)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "set_component_labels",
+        serde_json::json!({
+            "how": "/component/set_labels",
+            "component_id": component.id(),
+            "label_count": component.labels().len(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::empty(force_change_set_id))
+}
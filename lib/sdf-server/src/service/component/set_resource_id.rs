@@ -7,9 +7,9 @@ use axum::{
 use dal::{ChangeSet, Component, ComponentId, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
 
-use super::ComponentResult;
+use super::{check_if_match, ComponentResult};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{AccessBuilder, HandlerContext, IfMatch, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -26,6 +26,7 @@ pub struct SetResourceIdRequest {
 pub async fn set_resource_id(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    if_match: IfMatch,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
@@ -37,6 +38,8 @@ pub async fn set_resource_id(
 ) -> ComponentResult<ForceChangeSetResponse<()>> {
     let mut ctx = builder.build(request_ctx.build(visibility)).await?;
 
+    check_if_match(&ctx, component_id, &if_match).await?;
+
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     let component = Component::get_by_id(&ctx, component_id).await?;
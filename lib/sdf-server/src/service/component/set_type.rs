@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 
-use super::ComponentResult;
+use super::{check_if_match, ComponentResult};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{AccessBuilder, HandlerContext, IfMatch, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -26,6 +26,7 @@ pub struct SetTypeRequest {
 pub async fn set_type(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    if_match: IfMatch,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
@@ -37,6 +38,8 @@ pub async fn set_type(
 ) -> ComponentResult<ForceChangeSetResponse<()>> {
     let mut ctx = builder.build(request_ctx.build(visibility)).await?;
 
+    check_if_match(&ctx, component_id, &if_match).await?;
+
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     Component::set_type_by_id(&ctx, component_id, component_type).await?;
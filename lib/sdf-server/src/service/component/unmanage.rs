@@ -6,12 +6,12 @@ use dal::{ChangeSet, Component, ComponentId, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{AccessBuilder, HandlerContext, IfMatch, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
 
-use super::ComponentResult;
+use super::{check_if_match, ComponentResult};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -25,6 +25,7 @@ pub struct UnmanageComponentRequest {
 pub async fn unmanage(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    if_match: IfMatch,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
@@ -35,6 +36,10 @@ pub async fn unmanage(
     }): Json<UnmanageComponentRequest>,
 ) -> ComponentResult<ForceChangeSetResponse<()>> {
     let mut ctx = builder.build(request_ctx.build(visibility)).await?;
+
+    // The precondition applies to the managed component, whose management edge is changing
+    check_if_match(&ctx, managed_component_id, &if_match).await?;
+
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     Component::unmanage_component(&ctx, manager_component_id, managed_component_id).await?;
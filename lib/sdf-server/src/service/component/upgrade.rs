@@ -10,9 +10,9 @@ use serde::{Deserialize, Serialize};
 use si_events::audit_log::AuditLogKind;
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{AccessBuilder, HandlerContext, IfMatch, PosthogClient},
     service::{
-        component::{ComponentError, ComponentResult},
+        component::{check_if_match, ComponentError, ComponentResult},
         force_change_set_response::ForceChangeSetResponse,
     },
     track,
@@ -29,6 +29,7 @@ pub struct UpgradeComponentRequest {
 pub async fn upgrade(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    if_match: IfMatch,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
@@ -36,6 +37,8 @@ pub async fn upgrade(
 ) -> ComponentResult<ForceChangeSetResponse<()>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
+    check_if_match(&ctx, request.component_id, &if_match).await?;
+
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     let current_component = Component::get_by_id(&ctx, request.component_id).await?;
@@ -26,12 +26,21 @@ use tokio::task::JoinError;
 use super::ApiError;
 use crate::AppState;
 
+pub mod compatible_input_sockets;
+pub mod component_deletion_impact;
+pub mod connect_matching_sockets;
+pub mod copy_attribute_subtree;
+pub mod create_annotation;
 pub mod create_component;
 pub mod create_connection;
 pub mod get_diagram;
+pub mod get_diagram_delta;
+pub mod list_annotations;
 pub mod list_schemas;
 pub mod set_component_position;
+pub mod update_annotation;
 
+pub mod delete_annotation;
 pub mod delete_component;
 pub mod delete_connection;
 pub mod remove_delete_intent;
@@ -140,9 +149,19 @@ impl IntoResponse for DiagramError {
             | DiagramError::FrameSocketNotFound(_)
             | DiagramError::EdgeNotFound
             | DiagramError::SocketNotFound => StatusCode::NOT_FOUND,
+            DiagramError::AttributeValue(
+                AttributeValueError::CannotCopySubtreeBetweenVariants(_, _)
+                | AttributeValueError::CannotExplicitlySetSocketValues(_),
+            ) => StatusCode::UNPROCESSABLE_ENTITY,
             DiagramError::Component(ComponentError::ComponentAlreadyInView(_, _)) => {
                 StatusCode::UNPROCESSABLE_ENTITY
             }
+            DiagramError::DalDiagram(dal::diagram::DiagramError::AnnotationNotFound(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            DiagramError::DalDiagram(
+                dal::diagram::DiagramError::GeometryNotFoundForComponentAndView(_, _),
+            ) => StatusCode::UNPROCESSABLE_ENTITY,
             DiagramError::Component(ComponentError::Diagram(e)) => match *e {
                 dal::diagram::DiagramError::DeletingLastGeometryForComponent(_, _) => {
                     StatusCode::UNPROCESSABLE_ENTITY
@@ -175,10 +194,18 @@ pub fn routes() -> Router<AppState> {
             "/remove_delete_intent",
             post(remove_delete_intent::remove_delete_intent),
         )
+        .route(
+            "/copy_attribute_subtree",
+            post(copy_attribute_subtree::copy_attribute_subtree),
+        )
         .route(
             "/create_connection",
             post(create_connection::create_connection),
         )
+        .route(
+            "/connect_matching_sockets",
+            post(connect_matching_sockets::connect_matching_sockets),
+        )
         .route(
             "/create_component",
             post(create_component::create_component),
@@ -187,12 +214,34 @@ pub fn routes() -> Router<AppState> {
             "/set_component_position",
             post(set_component_position::set_component_position),
         )
+        .route(
+            "/create_annotation",
+            post(create_annotation::create_annotation),
+        )
+        .route(
+            "/update_annotation",
+            post(update_annotation::update_annotation),
+        )
+        .route(
+            "/delete_annotation",
+            post(delete_annotation::delete_annotation),
+        )
+        .route("/list_annotations", get(list_annotations::list_annotations))
         // Gets diagram for default view TODO: Delete this
         .route("/get_diagram", get(get_diagram::get_diagram))
+        .route("/delta", get(get_diagram_delta::get_diagram_delta))
         .route(
             "/get_all_components_and_edges",
             get(get_all_components_and_edges::get_all_components_and_edges),
         )
+        .route(
+            "/component_deletion_impact",
+            get(component_deletion_impact::component_deletion_impact),
+        )
+        .route(
+            "/compatible_input_sockets",
+            get(compatible_input_sockets::compatible_input_sockets),
+        )
         .route("/list_schemas", get(list_schemas::list_schemas))
         .route("/dvu_roots", get(dvu_roots::dvu_roots))
 }
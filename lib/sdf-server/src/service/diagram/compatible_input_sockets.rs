@@ -0,0 +1,32 @@
+use axum::extract::{Json, Query};
+use dal::{
+    component::socket_compatibility::CompatibleInputSocket, Component, ComponentId, OutputSocketId,
+    Visibility,
+};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibleInputSocketsRequest {
+    pub component_id: ComponentId,
+    pub output_socket_id: OutputSocketId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn compatible_input_sockets(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<CompatibleInputSocketsRequest>,
+) -> DiagramResult<Json<Vec<CompatibleInputSocket>>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let compatible =
+        Component::compatible_input_sockets(&ctx, request.component_id, request.output_socket_id)
+            .await?;
+
+    Ok(Json(compatible))
+}
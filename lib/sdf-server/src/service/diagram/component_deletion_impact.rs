@@ -0,0 +1,28 @@
+use axum::extract::{Json, Query};
+use dal::{component::delete::ComponentDeletionImpact, Component, ComponentId, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDeletionImpactRequest {
+    pub component_id: ComponentId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Lists what deleting a [`Component`](dal::Component) would break, for confirming the delete
+/// before it happens.
+pub async fn component_deletion_impact(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ComponentDeletionImpactRequest>,
+) -> DiagramResult<Json<ComponentDeletionImpact>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let impact = Component::deletion_impact(&ctx, request.component_id).await?;
+
+    Ok(Json(impact))
+}
@@ -0,0 +1,111 @@
+use super::DiagramResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    track,
+};
+use axum::{
+    extract::{Host, OriginalUri},
+    Json,
+};
+use dal::{
+    change_status::ChangeStatus, component::connect_matching::ConnectMatchingReport,
+    diagram::SummaryDiagramEdge, ChangeSet, Component, ComponentId, InputSocket, OutputSocket,
+    Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectMatchingSocketsRequest {
+    pub from_component_ids: Vec<ComponentId>,
+    pub to_component_id: ComponentId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn connect_matching_sockets(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Json(request): Json<ConnectMatchingSocketsRequest>,
+) -> DiagramResult<ForceChangeSetResponse<ConnectMatchingReport>> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let report = Component::connect_matching_sockets(
+        &ctx,
+        &request.from_component_ids,
+        request.to_component_id,
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "connect_matching_sockets",
+        serde_json::json!({
+            "how": "/diagram/connect_matching_sockets",
+            "from_component_count": request.from_component_ids.len(),
+            "to_component_id": request.to_component_id,
+            "created_count": report.created.len(),
+            "skipped_count": report.skipped.len(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    let to_component = Component::get_by_id(&ctx, request.to_component_id).await?;
+    let to_component_name = to_component.name(&ctx).await?;
+    let incoming_connections = to_component.incoming_connections(&ctx).await?;
+    for created in &report.created {
+        let from_component = Component::get_by_id(&ctx, created.source_component_id).await?;
+        for incoming_connection in &incoming_connections {
+            if incoming_connection.attribute_prototype_argument_id
+                == created.attribute_prototype_argument_id
+            {
+                let edge = SummaryDiagramEdge::assemble(
+                    incoming_connection.clone(),
+                    &from_component,
+                    &to_component,
+                    ChangeStatus::Added,
+                )?;
+                WsEvent::connection_upserted(&ctx, edge.into())
+                    .await?
+                    .publish_on_commit(&ctx)
+                    .await?;
+            }
+        }
+
+        let to_socket_name = InputSocket::get_by_id(&ctx, created.input_socket_id)
+            .await?
+            .name()
+            .to_string();
+        ctx.write_audit_log(
+            AuditLogKind::CreateConnection {
+                from_component_id: created.source_component_id,
+                from_component_name: from_component.name(&ctx).await?,
+                from_socket_id: created.output_socket_id,
+                from_socket_name: OutputSocket::get_by_id(&ctx, created.output_socket_id)
+                    .await?
+                    .name()
+                    .to_string(),
+                to_component_id: request.to_component_id,
+                to_component_name: to_component_name.clone(),
+                to_socket_id: created.input_socket_id,
+                to_socket_name: to_socket_name.clone(),
+            },
+            format!("{to_component_name} --- {to_socket_name}"),
+        )
+        .await?;
+    }
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::new(force_change_set_id, report))
+}
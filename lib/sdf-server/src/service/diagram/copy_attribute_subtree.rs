@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Host, OriginalUri},
+    Json,
+};
+use dal::{
+    AttributeValue, AttributeValueId, ChangeSet, Component, ComponentId, Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    track,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyAttributeSubtreeRequest {
+    pub source_attribute_value_id: AttributeValueId,
+    pub target_component_id: ComponentId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyAttributeSubtreeResponse {
+    /// The value in the target component the subtree was copied onto.
+    pub attribute_value_id: AttributeValueId,
+}
+
+/// Copies a configured subtree of attribute values from one [`Component`](dal::Component) to
+/// another of the same schema variant. Creating change set if on head.
+pub async fn copy_attribute_subtree(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Json(request): Json<CopyAttributeSubtreeRequest>,
+) -> DiagramResult<ForceChangeSetResponse<CopyAttributeSubtreeResponse>> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let attribute_value_id = AttributeValue::copy_subtree(
+        &ctx,
+        request.source_attribute_value_id,
+        request.target_component_id,
+    )
+    .await?;
+
+    let component = Component::get_by_id(&ctx, request.target_component_id).await?;
+    let payload = component
+        .into_frontend_type(
+            &ctx,
+            None,
+            component.change_status(&ctx).await?,
+            &mut HashMap::new(),
+        )
+        .await?;
+    WsEvent::component_updated(&ctx, payload)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "copy_attribute_subtree",
+        serde_json::json!({
+            "how": "/diagram/copy_attribute_subtree",
+            "source_attribute_value_id": request.source_attribute_value_id,
+            "component_id": request.target_component_id,
+            "component_schema_name": component.schema(&ctx).await?.name(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::new(
+        force_change_set_id,
+        CopyAttributeSubtreeResponse { attribute_value_id },
+    ))
+}
@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Host, OriginalUri},
+    Json,
+};
+use dal::{
+    diagram::{annotation::Annotation, view::ViewId},
+    ChangeSet, ComponentId, Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    track,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAnnotationRequest {
+    pub view_id: ViewId,
+    pub body: String,
+    pub x: isize,
+    pub y: isize,
+    /// Anchors the annotation to a component (or frame) on the view, with the position relative
+    /// to it.
+    pub component_id: Option<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type CreateAnnotationResponse = Annotation;
+
+pub async fn create_annotation(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Json(request): Json<CreateAnnotationRequest>,
+) -> DiagramResult<ForceChangeSetResponse<CreateAnnotationResponse>> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let annotation = Annotation::new(
+        &ctx,
+        request.view_id,
+        request.body,
+        request.x,
+        request.y,
+        request.component_id,
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "create_annotation",
+        serde_json::json!({
+            "how": "/diagram/create_annotation",
+            "annotation_id": annotation.id(),
+            "view_id": annotation.view_id(),
+            "component_id": annotation.component_id(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    WsEvent::annotation_created(&ctx, annotation.clone())
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::new(force_change_set_id, annotation))
+}
@@ -0,0 +1,65 @@
+use axum::{
+    extract::{Host, OriginalUri},
+    Json,
+};
+use dal::{
+    diagram::annotation::{Annotation, AnnotationId},
+    ChangeSet, Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    track,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAnnotationRequest {
+    pub annotation_id: AnnotationId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn delete_annotation(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Json(request): Json<DeleteAnnotationRequest>,
+) -> DiagramResult<ForceChangeSetResponse<()>> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let view_id = Annotation::get_by_id(&ctx, request.annotation_id)
+        .await?
+        .view_id();
+    Annotation::remove(&ctx, request.annotation_id).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "delete_annotation",
+        serde_json::json!({
+            "how": "/diagram/delete_annotation",
+            "annotation_id": request.annotation_id,
+            "view_id": view_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    WsEvent::annotation_deleted(&ctx, view_id, request.annotation_id)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::empty(force_change_set_id))
+}
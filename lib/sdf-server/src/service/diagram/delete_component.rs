@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 
 use super::DiagramResult;
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -25,6 +25,7 @@ pub struct DeleteComponentsRequest {
 
 /// Delete a set of [`Component`](dal::Component)s via their componentId. Creates change-set if on head
 pub async fn delete_components(
+    _: AuthorizedTo<operation::DeleteComponent>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
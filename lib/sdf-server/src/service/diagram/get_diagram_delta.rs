@@ -0,0 +1,229 @@
+use std::{collections::BTreeMap, fmt::Display, time::Duration};
+
+use axum::{extract::Query, Json};
+use dal::{
+    diagram::{
+        view::ViewObjectView, Diagram, SummaryDiagramEdge, SummaryDiagramInferredEdge,
+        SummaryDiagramManagementEdge,
+    },
+    slow_rt, ChangeSetId, DalContext, Visibility,
+};
+use serde::{Deserialize, Serialize};
+use si_events::WorkspaceSnapshotAddress;
+use si_frontend_types::DiagramElementDelta;
+
+use super::DiagramResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+/// The number of versions of the diagram of a change set kept around to compute deltas from.
+const MAX_VERSIONS_PER_CHANGE_SET: usize = 4;
+
+/// How long the versions of the diagram of a change set nobody asked about are kept around.
+const VERSIONS_TTL: Duration = Duration::from_secs(15 * 60);
+
+pub type DiagramDelta = si_frontend_types::DiagramDelta<
+    SummaryDiagramEdge,
+    SummaryDiagramInferredEdge,
+    SummaryDiagramManagementEdge,
+    ViewObjectView,
+>;
+
+const GET_VERSION_QUERY: &str = "
+    UPDATE diagram_versions SET used_at = CLOCK_TIMESTAMP()
+        WHERE change_set_id = $1 AND snapshot_address = $2
+        RETURNING diagram
+";
+const INSERT_VERSION_QUERY: &str = "
+    INSERT INTO diagram_versions (change_set_id, snapshot_address, diagram)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (change_set_id, snapshot_address) DO UPDATE SET used_at = CLOCK_TIMESTAMP()
+";
+const PRUNE_CHANGE_SET_VERSIONS_QUERY: &str = "
+    DELETE FROM diagram_versions
+        WHERE change_set_id = $1 AND snapshot_address NOT IN (
+            SELECT snapshot_address FROM diagram_versions
+                WHERE change_set_id = $1
+                ORDER BY used_at DESC
+                LIMIT $2
+        )
+";
+const PRUNE_UNUSED_VERSIONS_QUERY: &str = "
+    DELETE FROM diagram_versions
+        WHERE used_at < CLOCK_TIMESTAMP() - make_interval(secs => $1)
+";
+
+/// Gets a version of the diagram of a change set handed out by any sdf server, so that deltas can
+/// be computed without assembling the diagram from older snapshots. Clients asking for a delta
+/// from a version that isn't known get the whole diagram instead.
+async fn get_version(
+    ctx: &DalContext,
+    change_set_id: ChangeSetId,
+    snapshot_id: WorkspaceSnapshotAddress,
+) -> DiagramResult<Option<Diagram>> {
+    let client = ctx.pg_pool().get().await?;
+    let maybe_row = client
+        .query_opt(GET_VERSION_QUERY, &[&change_set_id, &snapshot_id])
+        .await?;
+
+    match maybe_row {
+        Some(row) => {
+            let diagram: serde_json::Value = row.try_get("diagram")?;
+            Ok(Some(serde_json::from_value(diagram)?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Records a version of the diagram of a change set, keeping only the latest
+/// [`MAX_VERSIONS_PER_CHANGE_SET`] of them and dropping the versions of change sets nobody asked
+/// about for [`VERSIONS_TTL`].
+async fn insert_version(
+    ctx: &DalContext,
+    change_set_id: ChangeSetId,
+    snapshot_id: WorkspaceSnapshotAddress,
+    diagram: &Diagram,
+) -> DiagramResult<()> {
+    let client = ctx.pg_pool().get().await?;
+    client
+        .execute(
+            INSERT_VERSION_QUERY,
+            &[
+                &change_set_id,
+                &snapshot_id,
+                &serde_json::to_value(diagram)?,
+            ],
+        )
+        .await?;
+    client
+        .execute(
+            PRUNE_CHANGE_SET_VERSIONS_QUERY,
+            &[&change_set_id, &(MAX_VERSIONS_PER_CHANGE_SET as i64)],
+        )
+        .await?;
+    client
+        .execute(PRUNE_UNUSED_VERSIONS_QUERY, &[&VERSIONS_TTL.as_secs_f64()])
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    /// The version of the diagram the client has, if any.
+    pub since: Option<WorkspaceSnapshotAddress>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Returns the changes to the diagram of all views since the version the client has, along with
+/// the version the changes bring it to.
+pub async fn get_diagram_delta(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<Request>,
+) -> DiagramResult<Json<DiagramDelta>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+    let change_set_id = ctx.change_set_id();
+    let snapshot_id = ctx.workspace_snapshot()?.id().await;
+
+    let current = match get_version(&ctx, change_set_id, snapshot_id).await? {
+        Some(diagram) => diagram,
+        None => {
+            let ctx_clone = ctx.clone();
+            let diagram = slow_rt::spawn(async move {
+                let ctx = &ctx_clone;
+                Diagram::assemble(ctx, None).await
+            })?
+            .await??;
+            insert_version(&ctx, change_set_id, snapshot_id, &diagram).await?;
+            diagram
+        }
+    };
+    let previous = match request.since {
+        Some(since) => get_version(&ctx, change_set_id, since).await?,
+        None => None,
+    };
+
+    Ok(Json(diagram_delta(
+        snapshot_id,
+        previous.as_ref(),
+        &current,
+    )))
+}
+
+fn diagram_delta(
+    snapshot_id: WorkspaceSnapshotAddress,
+    previous: Option<&Diagram>,
+    current: &Diagram,
+) -> DiagramDelta {
+    fn element_delta<T: Clone + PartialEq, K: Display>(
+        previous: Option<&[T]>,
+        current: &[T],
+        key: impl Fn(&T) -> K,
+    ) -> DiagramElementDelta<T> {
+        let keyed = |elements: &[T]| -> BTreeMap<String, T> {
+            elements
+                .iter()
+                .map(|element| (key(element).to_string(), element.clone()))
+                .collect()
+        };
+
+        DiagramElementDelta::between(&keyed(previous.unwrap_or_default()), &keyed(current))
+    }
+
+    // Edges are keyed the same way the frontend identifies them.
+    DiagramDelta {
+        snapshot_id,
+        full: previous.is_none(),
+        components: element_delta(
+            previous.map(|diagram| diagram.components.as_slice()),
+            &current.components,
+            |component| component.id,
+        ),
+        edges: element_delta(
+            previous.map(|diagram| diagram.edges.as_slice()),
+            &current.edges,
+            |edge| {
+                format!(
+                    "{}_{}_{}_{}",
+                    edge.to_component_id,
+                    edge.to_socket_id,
+                    edge.from_socket_id,
+                    edge.from_component_id
+                )
+            },
+        ),
+        inferred_edges: element_delta(
+            previous.map(|diagram| diagram.inferred_edges.as_slice()),
+            &current.inferred_edges,
+            |edge| {
+                format!(
+                    "{}_{}_{}_{}",
+                    edge.to_component_id,
+                    edge.to_socket_id,
+                    edge.from_socket_id,
+                    edge.from_component_id
+                )
+            },
+        ),
+        management_edges: element_delta(
+            previous.map(|diagram| diagram.management_edges.as_slice()),
+            &current.management_edges,
+            |edge| {
+                format!(
+                    "{}_{}_{}_{}",
+                    edge.to_component_id,
+                    edge.to_socket_id,
+                    edge.from_socket_id,
+                    edge.from_component_id
+                )
+            },
+        ),
+        views: element_delta(
+            previous.map(|diagram| diagram.views.as_slice()),
+            &current.views,
+            ViewObjectView::id,
+        ),
+    }
+}
@@ -0,0 +1,31 @@
+use axum::extract::{Json, Query};
+use dal::{
+    diagram::{annotation::Annotation, view::ViewId},
+    Visibility,
+};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListAnnotationsRequest {
+    pub view_id: ViewId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type ListAnnotationsResponse = Vec<Annotation>;
+
+pub async fn list_annotations(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListAnnotationsRequest>,
+) -> DiagramResult<Json<ListAnnotationsResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let annotations = Annotation::list_for_view(&ctx, request.view_id).await?;
+
+    Ok(Json(annotations))
+}
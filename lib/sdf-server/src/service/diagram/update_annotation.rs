@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Host, OriginalUri},
+    Json,
+};
+use dal::{
+    diagram::annotation::{Annotation, AnnotationId},
+    ChangeSet, ComponentId, Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use super::DiagramResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    track,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAnnotationRequest {
+    pub annotation_id: AnnotationId,
+    pub body: String,
+    pub x: isize,
+    pub y: isize,
+    /// Anchors the annotation to a component (or frame) on its view, or detaches it if unset.
+    pub component_id: Option<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type UpdateAnnotationResponse = Annotation;
+
+pub async fn update_annotation(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Json(request): Json<UpdateAnnotationRequest>,
+) -> DiagramResult<ForceChangeSetResponse<UpdateAnnotationResponse>> {
+    let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let mut annotation = Annotation::get_by_id(&ctx, request.annotation_id).await?;
+    annotation
+        .update(
+            &ctx,
+            request.body,
+            request.x,
+            request.y,
+            request.component_id,
+        )
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "update_annotation",
+        serde_json::json!({
+            "how": "/diagram/update_annotation",
+            "annotation_id": annotation.id(),
+            "view_id": annotation.view_id(),
+            "component_id": annotation.component_id(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    WsEvent::annotation_updated(&ctx, annotation.clone())
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::new(force_change_set_id, annotation))
+}
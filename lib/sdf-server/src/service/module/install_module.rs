@@ -24,6 +24,10 @@ use telemetry::prelude::*;
 #[serde(rename_all = "camelCase")]
 pub struct InstallModuleRequest {
     pub ids: Vec<Ulid>,
+    /// Derive the ids of the installed funcs and schemas from the workspace and the module, so
+    /// that installing the same module elsewhere yields the same ids.
+    #[serde(default)]
+    pub deterministic_ids: bool,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -106,6 +110,7 @@ pub async fn install_module(
             Some(ImportOptions {
                 schema_id,
                 past_module_hashes,
+                deterministic_ids: request.deterministic_ids,
                 ..Default::default()
             }),
         )
@@ -0,0 +1,200 @@
+//! The readiness probe, which unlike the root health route verifies that the services sdf depends on
+//! are reachable.
+//!
+//! Every dependency is checked with a cheap round trip, bounded by a timeout. Postgres and NATS are
+//! required to serve any request, so the server is unavailable while either of them is failing. The
+//! job queue is only needed for some requests, so while it is failing (or a required dependency is
+//! slow or reconnecting) the server is degraded, but still ready. The Postgres and NATS of every
+//! data residency region are checked as well, and only degrade the server when they fail, since
+//! every other workspace can still be served.
+
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::State, http::StatusCode, response::IntoResponse, routing::get, Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use si_data_nats::{async_nats::connection::State as NatsConnectionState, NatsClient};
+use telemetry::prelude::*;
+
+use crate::{AppState, DataResidency};
+
+/// How long a single dependency check may take before it is considered failed.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+/// Checks which succeed, but take longer than this, are reported as degraded.
+const SLOW_CHECK_THRESHOLD: Duration = Duration::from_millis(500);
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(readiness))
+}
+
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadinessStatus {
+    Degraded,
+    Failed,
+    Ok,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyReadiness {
+    pub status: ReadinessStatus,
+    pub latency_ms: u64,
+    /// Why the dependency is not ok, if it isn't.
+    pub message: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessDependencies {
+    pub pg: DependencyReadiness,
+    pub nats: DependencyReadiness,
+    pub job_queue: DependencyReadiness,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionReadiness {
+    pub pg: DependencyReadiness,
+    pub nats: DependencyReadiness,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessResponse {
+    /// `ok` or `degraded` while the server is ready, and `failed` otherwise.
+    pub status: ReadinessStatus,
+    pub dependencies: ReadinessDependencies,
+    /// The dependencies of every data residency region, by region name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub regions: BTreeMap<String, RegionReadiness>,
+}
+
+async fn readiness(
+    State(state): State<AppState>,
+    data_residency: Option<Extension<DataResidency>>,
+) -> impl IntoResponse {
+    let services_context = state.services_context();
+    let job_processor = services_context.job_processor();
+
+    let regions = async {
+        let mut regions = BTreeMap::new();
+        if let Some(Extension(data_residency)) = &data_residency {
+            for (region, services_context) in data_residency.regions() {
+                let (pg, nats) = tokio::join!(
+                    check(services_context.pg_pool().test_connection()),
+                    check_nats(services_context.nats_conn()),
+                );
+                regions.insert(region.to_owned(), RegionReadiness { pg, nats });
+            }
+        }
+        regions
+    };
+    let (pg, nats, job_queue, regions) = tokio::join!(
+        check(services_context.pg_pool().test_connection()),
+        check_nats(services_context.nats_conn()),
+        check(job_processor.check_ready()),
+        regions,
+    );
+
+    let regions_ok = regions.values().all(|region| {
+        region.pg.status == ReadinessStatus::Ok && region.nats.status == ReadinessStatus::Ok
+    });
+    let status = match (pg.status, nats.status, job_queue.status) {
+        (ReadinessStatus::Failed, _, _) | (_, ReadinessStatus::Failed, _) => {
+            ReadinessStatus::Failed
+        }
+        (ReadinessStatus::Ok, ReadinessStatus::Ok, ReadinessStatus::Ok) if regions_ok => {
+            ReadinessStatus::Ok
+        }
+        _ => ReadinessStatus::Degraded,
+    };
+    let status_code = match status {
+        ReadinessStatus::Degraded | ReadinessStatus::Ok => StatusCode::OK,
+        ReadinessStatus::Failed => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    if status != ReadinessStatus::Ok {
+        warn!(
+            pg = ?pg.status,
+            nats = ?nats.status,
+            job_queue = ?job_queue.status,
+            regions_ok,
+            "sdf is not fully ready",
+        );
+    }
+
+    (
+        status_code,
+        Json(ReadinessResponse {
+            status,
+            dependencies: ReadinessDependencies {
+                pg,
+                nats,
+                job_queue,
+            },
+            regions,
+        }),
+    )
+}
+
+/// Checks NATS with a round trip to the server. A client which is reconnecting is degraded rather
+/// than failed, since it buffers what is published until it is connected again.
+async fn check_nats(nats: &NatsClient) -> DependencyReadiness {
+    match nats.connection_state() {
+        NatsConnectionState::Connected => check(nats.flush()).await,
+        NatsConnectionState::Pending => DependencyReadiness {
+            status: ReadinessStatus::Degraded,
+            latency_ms: 0,
+            message: Some("reconnecting".to_owned()),
+        },
+        NatsConnectionState::Disconnected => DependencyReadiness {
+            status: ReadinessStatus::Failed,
+            latency_ms: 0,
+            message: Some("disconnected".to_owned()),
+        },
+    }
+}
+
+/// Runs a single dependency check, failing it once it exceeds the timeout and degrading it when it
+/// is slow.
+async fn check<E>(fut: impl Future<Output = Result<(), E>>) -> DependencyReadiness
+where
+    E: ToString,
+{
+    let start = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, fut).await;
+    let elapsed = start.elapsed();
+    let latency_ms = elapsed.as_millis() as u64;
+
+    match outcome {
+        Ok(Ok(())) if elapsed > SLOW_CHECK_THRESHOLD => DependencyReadiness {
+            status: ReadinessStatus::Degraded,
+            latency_ms,
+            message: Some(format!("check took {latency_ms}ms")),
+        },
+        Ok(Ok(())) => DependencyReadiness {
+            status: ReadinessStatus::Ok,
+            latency_ms,
+            message: None,
+        },
+        Ok(Err(err)) => DependencyReadiness {
+            status: ReadinessStatus::Failed,
+            latency_ms,
+            message: Some(err.to_string()),
+        },
+        Err(_) => DependencyReadiness {
+            status: ReadinessStatus::Failed,
+            latency_ms,
+            message: Some(format!(
+                "check timed out after {}ms",
+                CHECK_TIMEOUT.as_millis()
+            )),
+        },
+    }
+}
@@ -5,8 +5,9 @@ use axum::{
     Router,
 };
 use dal::{
-    workspace_integrations::WorkspaceIntegrationsError, KeyPairError, StandardModelError,
-    TransactionsError, UserError, UserPk, WorkspaceError, WorkspacePk,
+    workspace_integrations::WorkspaceIntegrationsError, workspace_role::WorkspaceRoleError,
+    KeyPairError, StandardModelError, TransactionsError, UserError, UserPk, WorkspaceError,
+    WorkspacePk,
 };
 use serde::{Deserialize, Serialize};
 use si_data_spicedb::SpiceDbError;
@@ -30,8 +31,12 @@ pub enum SessionError {
     ContextTransactions(#[from] TransactionsError),
     #[error("Invalid user: {0}")]
     InvalidUser(UserPk),
+    #[error("Invalid user id: {0}")]
+    InvalidUserId(String),
     #[error("Invalid workspace: {0}")]
     InvalidWorkspace(WorkspacePk),
+    #[error("Invalid workspace id: {0}")]
+    InvalidWorkspaceId(String),
     #[error("json serialize failed")]
     JSONSerialize(#[from] serde_json::Error),
     #[error(transparent)]
@@ -60,6 +65,8 @@ pub enum SessionError {
     WorkspaceNotYetMigrated(WorkspacePk),
     #[error("invalid workspace permission: {0}")]
     WorkspacePermission(&'static str),
+    #[error("workspace role error: {0}")]
+    WorkspaceRole(#[from] WorkspaceRoleError),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
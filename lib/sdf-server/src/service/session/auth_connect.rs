@@ -3,8 +3,8 @@ use axum::{
     Json,
 };
 use dal::{
-    workspace_integrations::WorkspaceIntegration, DalContext, HistoryActor, KeyPair, Tenancy, User,
-    UserPk, Workspace, WorkspacePk, WorkspaceSnapshotGraph,
+    workspace_integrations::WorkspaceIntegration, workspace_role::WorkspaceRole, DalContext,
+    HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk, WorkspaceSnapshotGraph,
 };
 use hyper::Uri;
 use permissions::{Relation, RelationBuilder};
@@ -203,6 +203,9 @@ async fn find_or_create_user_and_workspace(
 
     // ensure workspace is associated to user
     user.associate_workspace(&ctx, *workspace.pk()).await?;
+    if user.pk() == auth_api_workspace.creator_user_id {
+        WorkspaceRole::set_for_user(&ctx, *workspace.pk(), user.pk(), WorkspaceRole::Owner).await?;
+    }
 
     // Check the workspace integration
     // We need to ensure that the integrations row is available for older workspaces
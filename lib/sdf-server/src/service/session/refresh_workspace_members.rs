@@ -3,11 +3,10 @@ use axum::{
     http::uri::Uri,
     Json,
 };
-use dal::{DalContext, User};
+use dal::{workspace_role::WorkspaceRole, DalContext, User, UserPk, WorkspacePk};
 use permissions::{ObjectType, Relation, RelationBuilder};
 use serde::{Deserialize, Serialize};
 use si_data_spicedb::SpiceDbClient;
-use strum::{Display, EnumString};
 
 use super::{SessionError, SessionResult};
 use crate::{
@@ -31,15 +30,6 @@ pub struct WorkspaceMember {
     pub role: WorkspaceRole,
 }
 
-#[derive(Clone, Display, Debug, Deserialize, EnumString, Serialize)]
-#[strum(serialize_all = "UPPERCASE")]
-#[serde(rename_all = "UPPERCASE")]
-pub enum WorkspaceRole {
-    Approver,
-    Editor,
-    Owner,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RefreshWorkspaceMembersResponse {
@@ -102,7 +92,10 @@ pub async fn refresh_workspace_members(
     }
 
     let members = User::list_members_for_workspace(&ctx, request.workspace_id.clone()).await?;
-    let member_ids: Vec<_> = workspace_members.into_iter().map(|w| w.user_id).collect();
+    let member_ids: Vec<_> = workspace_members
+        .iter()
+        .map(|w| w.user_id.clone())
+        .collect();
     let users_to_remove: Vec<_> = members
         .into_iter()
         .filter(|u| !member_ids.contains(&u.pk().to_string()))
@@ -113,6 +106,20 @@ pub async fn refresh_workspace_members(
         User::delete_user_from_workspace(&ctx, remove.pk(), request.workspace_id.clone()).await?;
     }
 
+    let workspace_pk: WorkspacePk = request
+        .workspace_id
+        .parse()
+        .map_err(|_| SessionError::InvalidWorkspaceId(request.workspace_id.clone()))?;
+    for member in workspace_members {
+        let user_pk: UserPk = member
+            .user_id
+            .parse()
+            .map_err(|_| SessionError::InvalidUserId(member.user_id.clone()))?;
+        WorkspaceRole::set_for_user(&ctx, workspace_pk, user_pk, member.role).await?;
+    }
+
+    ctx.commit_no_rebase().await?;
+
     Ok(Json(RefreshWorkspaceMembersResponse { success: true }))
 }
 
@@ -1,14 +1,25 @@
-use axum::Router;
+use axum::{middleware, Router};
 
-use crate::AppState;
+use crate::{
+    middleware::{change_set_apply_lock_middleware, dry_run_middleware},
+    AppState,
+};
 
 pub mod admin;
 pub mod audit_log;
 pub mod change_set;
+pub mod component;
 pub mod func;
+pub mod graphql;
+pub mod hooks;
 pub mod integrations;
 pub mod management;
 pub mod module;
+pub mod provider_credentials;
+pub mod search;
+pub mod share_links;
+pub mod snapshot_subscriptions;
+pub mod tokens;
 pub mod variant;
 pub mod view;
 pub mod workspace;
@@ -24,15 +35,68 @@ pub fn routes(state: AppState) -> Router<AppState> {
     Router::new()
         .nest("/admin", admin::v2_routes(state.clone()))
         .nest(&format!("{PREFIX}/audit-logs"), audit_log::v2_routes())
+        .nest(
+            &format!("{WORKSPACES_PREFIX}/audit"),
+            audit_log::v2_workspace_routes(state.clone()),
+        )
         .nest(CHANGE_SET_PREFIX, change_set::v2_routes(state.clone()))
-        .nest(&format!("{PREFIX}/funcs"), func::v2_routes())
-        .nest(&format!("{PREFIX}/modules"), module::v2_routes())
-        .nest(&format!("{PREFIX}/schema-variants"), variant::v2_routes())
-        .nest(&format!("{PREFIX}/management"), management::v2_routes())
-        .nest(&format!("{PREFIX}/views"), view::v2_routes())
-        .nest(WORKSPACES_PREFIX, workspace::v2_routes())
+        .nest(
+            &format!("{PREFIX}/components"),
+            change_set_mutations(state.clone(), component::v2_routes()),
+        )
+        .nest(&format!("{PREFIX}/funcs"), func::v2_routes(state.clone()))
+        .nest(&format!("{PREFIX}/graphql"), graphql::v2_routes())
+        .nest(
+            &format!("{PREFIX}/modules"),
+            change_set_mutations(state.clone(), module::v2_routes()),
+        )
+        .nest(
+            &format!("{PREFIX}/schema-variants"),
+            change_set_mutations(state.clone(), variant::v2_routes()),
+        )
+        .nest(
+            &format!("{PREFIX}/management"),
+            change_set_mutations(state.clone(), management::v2_routes()),
+        )
+        .nest(&format!("{PREFIX}/search"), search::v2_change_set_routes())
+        .nest(
+            &format!("{PREFIX}/views"),
+            change_set_mutations(state.clone(), view::v2_routes()),
+        )
+        .nest(WORKSPACES_PREFIX, workspace::v2_routes(state.clone()))
         .nest(
             &format!("{WORKSPACES_PREFIX}/integrations"),
             integrations::v2_routes(),
         )
+        .nest(
+            &format!("{WORKSPACES_PREFIX}/hooks"),
+            hooks::v2_routes(state.clone()),
+        )
+        .nest(
+            &format!("{WORKSPACES_PREFIX}/provider-credentials"),
+            provider_credentials::v2_routes(),
+        )
+        .nest(&format!("{WORKSPACES_PREFIX}/search"), search::v2_routes())
+        .nest(
+            &format!("{WORKSPACES_PREFIX}/share-links"),
+            share_links::v2_routes(state.clone()),
+        )
+        .nest("/shared", share_links::v2_shared_routes())
+        .nest(
+            &format!("{WORKSPACES_PREFIX}/snapshot-subscriptions"),
+            snapshot_subscriptions::v2_routes(),
+        )
+        .nest(
+            &format!("{WORKSPACES_PREFIX}/tokens"),
+            tokens::v2_routes(state.clone()),
+        )
+        .layer(middleware::from_fn(dry_run_middleware))
+}
+
+/// Rejects the mutations of a change set while it is being applied or is awaiting approval.
+pub(crate) fn change_set_mutations(state: AppState, routes: Router<AppState>) -> Router<AppState> {
+    routes.route_layer(middleware::from_fn_with_state(
+        state,
+        change_set_apply_lock_middleware,
+    ))
 }
@@ -15,16 +15,28 @@ use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::{extract::AdminAccessBuilder, service::ApiError, AppState};
+use crate::{
+    extract::{AdminAccessBuilder, AdminClaim},
+    service::ApiError,
+    AppState,
+};
 
+mod content_gc;
+mod dead_letters;
+mod force_change_set_status;
+mod get_change_set_jobs;
 mod get_snapshot;
 mod kill_execution;
 mod list_change_sets;
+mod list_change_sets_by_status;
 mod list_workspace_users;
 mod prompts;
+mod prune_snapshots;
+mod restore_snapshot;
 mod search_workspaces;
 mod set_concurrency_limit;
 mod set_snapshot;
+mod tracing_level;
 mod update_module_cache;
 
 // 1GB
@@ -33,22 +45,38 @@ const MAX_UPLOAD_BYTES: usize = 1024 * 1024 * 1024;
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum AdminAPIError {
+    #[error("action error: {0}")]
+    Action(#[from] dal::action::ActionError),
     #[error("axum http error: {0}")]
     AxumHttp(#[from] axum::http::Error),
     #[error("cached module error: {0}")]
     CachedModule(#[from] CachedModuleError),
+    #[error("cannot abandon the default change set {0} of a workspace")]
+    CannotAbandonHead(ChangeSetId),
     #[error("change set error: {0}")]
     ChangeSet(#[from] dal::ChangeSetError),
     #[error("change set {0} not found")]
     ChangeSetNotFound(ChangeSetId),
+    #[error("change set {0} is not quarantined")]
+    ChangeSetNotQuarantined(ChangeSetId),
+    #[error("content gc error: {0}")]
+    ContentGc(#[from] dal::content_gc::ContentGcError),
+    #[error("dead letter error: {0}")]
+    DeadLetter(#[from] dal::job::dead_letter::DeadLetterError),
     #[error("func runner error: {0}")]
     FuncRunner(#[from] FuncRunnerError),
+    #[error("invalid change set status: {0}")]
+    InvalidChangeSetStatus(String),
     #[error("layer db error: {0}")]
     LayerDb(#[from] si_layer_cache::LayerDbError),
     #[error("multipart error: {0}")]
     Multipart(#[from] axum::extract::multipart::MultipartError),
     #[error("No multipart data found in request")]
     NoMultipartData,
+    #[error("snapshot pruning error: {0}")]
+    SnapshotPruning(#[from] dal::snapshot_pruning::SnapshotPruningError),
+    #[error("telemetry client error: {0}")]
+    Telemetry(#[from] telemetry::ClientError),
     #[error("tokio join error: {0}")]
     TokioJoin(#[from] tokio::task::JoinError),
     #[error("transactions error: {0}")]
@@ -103,6 +131,9 @@ pub struct AdminChangeSet {
     pub workspace_snapshot_address: WorkspaceSnapshotAddress,
     pub workspace_id: Option<WorkspacePk>,
     pub merge_requested_by_user_id: Option<UserPk>,
+    pub quarantined_at: Option<DateTime<Utc>>,
+    pub last_good_snapshot_address: Option<WorkspaceSnapshotAddress>,
+    pub read_only: bool,
 }
 
 impl From<ChangeSet> for AdminChangeSet {
@@ -117,6 +148,9 @@ impl From<ChangeSet> for AdminChangeSet {
             workspace_snapshot_address: value.workspace_snapshot_address,
             workspace_id: value.workspace_id,
             merge_requested_by_user_id: value.merge_requested_by_user_id,
+            quarantined_at: value.quarantined_at,
+            last_good_snapshot_address: value.last_good_snapshot_address,
+            read_only: value.read_only,
         }
     }
 }
@@ -148,6 +182,27 @@ impl IntoResponse for AdminAPIError {
             AdminAPIError::FuncRunner(FuncRunnerError::DoNotHavePermissionToKillExecution) => {
                 StatusCode::UNAUTHORIZED
             }
+            Self::ChangeSetNotFound(_)
+            | Self::DeadLetter(dal::job::dead_letter::DeadLetterError::NotFound(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            Self::CannotAbandonHead(_)
+            | Self::ChangeSetNotQuarantined(_)
+            | Self::ChangeSet(dal::ChangeSetError::NoLastGoodSnapshot(_))
+            | Self::ContentGc(
+                dal::content_gc::ContentGcError::InvalidBatchSize(_)
+                | dal::content_gc::ContentGcError::InvalidRetention(_),
+            )
+            | Self::SnapshotPruning(
+                dal::snapshot_pruning::SnapshotPruningError::InvalidBatchSize(_)
+                | dal::snapshot_pruning::SnapshotPruningError::InvalidMinAge(_),
+            )
+            | Self::Telemetry(telemetry::ClientError::InvalidDirectives(_))
+            | Self::InvalidChangeSetStatus(_) => StatusCode::BAD_REQUEST,
+            Self::ContentGc(dal::content_gc::ContentGcError::AlreadyRunning)
+            | Self::SnapshotPruning(dal::snapshot_pruning::SnapshotPruningError::AlreadyRunning) => {
+                StatusCode::CONFLICT
+            }
             _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
         };
 
@@ -189,9 +244,54 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
             post(set_snapshot::set_snapshot),
         )
         .nest("/prompts", prompts::routes())
+        .merge(change_set_triage_routes(state.clone()))
         .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
         .route_layer(axum::middleware::from_extractor_with_state::<
             AdminAccessBuilder,
             AppState,
         >(state))
 }
+
+/// Routes for triaging stuck change sets and failed jobs in any workspace. These additionally
+/// require the admin claim in the token.
+fn change_set_triage_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/change_sets",
+            get(list_change_sets_by_status::list_change_sets_by_status),
+        )
+        .route(
+            "/workspaces/:workspace_pk/change_sets/:change_set_id/jobs",
+            get(get_change_set_jobs::get_change_set_jobs),
+        )
+        .route(
+            "/workspaces/:workspace_pk/dead_letters",
+            get(dead_letters::list_dead_letters),
+        )
+        .route(
+            "/workspaces/:workspace_pk/dead_letters/:sequence/replay",
+            post(dead_letters::replay_dead_letter),
+        )
+        .route(
+            "/workspaces/:workspace_pk/change_sets/:change_set_id/force_open",
+            post(force_change_set_status::force_open_change_set),
+        )
+        .route(
+            "/workspaces/:workspace_pk/change_sets/:change_set_id/abandon",
+            post(force_change_set_status::abandon_change_set),
+        )
+        .route(
+            "/workspaces/:workspace_pk/change_sets/:change_set_id/restore_snapshot",
+            post(restore_snapshot::restore_snapshot),
+        )
+        .route("/content_gc", post(content_gc::collect_content_garbage))
+        .route("/prune_snapshots", post(prune_snapshots::prune_snapshots))
+        .route(
+            "/tracing_level",
+            get(tracing_level::get_tracing_level).put(tracing_level::update_tracing_level),
+        )
+        .route_layer(axum::middleware::from_extractor_with_state::<
+            AdminClaim,
+            AppState,
+        >(state))
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use axum::{extract::Query, Json};
+use dal::content_gc::{ContentGcOptions, ContentGcRun};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use ulid::Ulid;
+
+use super::AdminAPIResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+/// Whether a run deletes anything is only decided by the `dry_run` query parameter, like for every
+/// other v2 route, so that it cannot be contradicted by the body.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectContentGarbageQuery {
+    /// Only report what would be deleted. Defaults to true, so content is only deleted with
+    /// `?dry_run=false`.
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CollectContentGarbageRequest {
+    /// Unreferenced content is only deleted once it is older than this. Defaults to a week.
+    pub retention_hours: Option<u64>,
+    /// How many content store keys to scan (and delete) at a time.
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectContentGarbageResponse {
+    /// Identifies the run in the logs, which is where its report ends up.
+    pub id: Ulid,
+}
+
+/// Starts a garbage collection run over the content store in the background, unless one is already
+/// in progress.
+#[instrument(name = "admin.collect_content_garbage", level = "info", skip_all)]
+pub async fn collect_content_garbage(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(query): Query<CollectContentGarbageQuery>,
+    Json(request): Json<CollectContentGarbageRequest>,
+) -> AdminAPIResult<Json<CollectContentGarbageResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let defaults = ContentGcOptions::default();
+    let options = ContentGcOptions {
+        retention: request
+            .retention_hours
+            .map(|hours| Duration::from_secs(hours * 60 * 60))
+            .unwrap_or(defaults.retention),
+        batch_size: request.batch_size.unwrap_or(defaults.batch_size),
+        dry_run: query.dry_run.unwrap_or(defaults.dry_run),
+    };
+    options.validate()?;
+    let run = ContentGcRun::try_claim(&ctx).await?;
+
+    let task_id = Ulid::new();
+    tokio::task::spawn(
+        async move {
+            match run.collect_garbage(&ctx, options).await {
+                Ok(report) => info!(?report, "content garbage collection run finished"),
+                Err(err) => {
+                    error!(si.error.message = ?err, "content garbage collection run failed")
+                }
+            }
+        }
+        .instrument(info_span!("admin.content_gc_run", %task_id)),
+    );
+
+    Ok(Json(CollectContentGarbageResponse { id: task_id }))
+}
@@ -0,0 +1,116 @@
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use dal::{
+    job::dead_letter::{DeadLetter, JobAttempt, StoredDeadLetter},
+    ChangeSetId, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use super::AdminAPIResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1_000;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeadLettersRequest {
+    /// The maximum number of dead letters to list, oldest first. Defaults to 100, and is clamped
+    /// to between 1 and 1000.
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeadLettersResponse {
+    dead_letters: Vec<AdminDeadLetter>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminDeadLetter {
+    /// Identifies the dead letter, for replaying it.
+    sequence: u64,
+    job_id: String,
+    kind: String,
+    change_set_id: ChangeSetId,
+    arg: serde_json::Value,
+    dead_lettered_at: DateTime<Utc>,
+    error_chain: Vec<String>,
+    attempts: Vec<JobAttempt>,
+}
+
+impl From<StoredDeadLetter> for AdminDeadLetter {
+    fn from(value: StoredDeadLetter) -> Self {
+        let error_chain = value.dead_letter.error_chain().to_vec();
+        let job_info = value.dead_letter.job_info;
+        Self {
+            sequence: value.sequence,
+            job_id: job_info.id,
+            kind: job_info.kind,
+            change_set_id: job_info.visibility.change_set_id,
+            arg: job_info.arg,
+            dead_lettered_at: value.dead_letter.dead_lettered_at,
+            error_chain,
+            attempts: job_info.failed_attempts,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayDeadLetterResponse {
+    job_id: String,
+}
+
+#[instrument(
+    name = "admin.list_dead_letters",
+    level = "info",
+    skip_all,
+    fields(si.workspace.id = %workspace_pk),
+)]
+pub async fn list_dead_letters(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(workspace_pk): Path<WorkspacePk>,
+    Query(request): Query<ListDeadLettersRequest>,
+) -> AdminAPIResult<Json<ListDeadLettersResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let dead_letters = DeadLetter::list_for_workspace(&ctx, workspace_pk, limit)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(ListDeadLettersResponse { dead_letters }))
+}
+
+/// Publishes a dead-lettered job back to the main queue.
+#[instrument(
+    name = "admin.replay_dead_letter",
+    level = "info",
+    skip_all,
+    fields(
+        si.workspace.id = %workspace_pk,
+        sequence,
+    ),
+)]
+pub async fn replay_dead_letter(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((workspace_pk, sequence)): Path<(WorkspacePk, u64)>,
+) -> AdminAPIResult<Json<ReplayDeadLetterResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let job_info = DeadLetter::replay(&ctx, workspace_pk, sequence).await?;
+
+    Ok(Json(ReplayDeadLetterResponse {
+        job_id: job_info.id,
+    }))
+}
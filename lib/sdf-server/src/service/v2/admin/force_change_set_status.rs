@@ -0,0 +1,117 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{ChangeSet, ChangeSetId, Workspace, WorkspaceError, WorkspacePk};
+use telemetry::prelude::*;
+
+use super::{AdminAPIError, AdminAPIResult, AdminChangeSet};
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    track_no_ctx,
+};
+
+/// Forces a change set which is stuck in the approval flow back to Open, clearing any approvals.
+#[instrument(
+    name = "admin.force_open_change_set",
+    level = "info",
+    skip_all,
+    fields(
+        si.workspace.id = %workspace_pk,
+        si.change_set.id = %change_set_id,
+    ),
+)]
+pub async fn force_open_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> AdminAPIResult<Json<AdminChangeSet>> {
+    let access_builder = dal::AccessBuilder::new(
+        dal::Tenancy::new(workspace_pk),
+        *access_builder.history_actor(),
+    );
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut change_set = ChangeSet::find(&ctx, change_set_id)
+        .await?
+        .ok_or(AdminAPIError::ChangeSetNotFound(change_set_id))?;
+    let old_status = change_set.status;
+
+    change_set.reopen_change_set(&ctx).await?;
+
+    ctx.commit_no_rebase().await?;
+
+    track_no_ctx(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        Some(workspace_pk.to_string()),
+        Some(change_set_id.to_string()),
+        "admin.force_open_change_set",
+        serde_json::json!({
+            "old_status": old_status,
+        }),
+    );
+
+    Ok(Json(change_set.into()))
+}
+
+/// Abandons a change set, regardless of where it is in the approval flow.
+#[instrument(
+    name = "admin.abandon_change_set",
+    level = "info",
+    skip_all,
+    fields(
+        si.workspace.id = %workspace_pk,
+        si.change_set.id = %change_set_id,
+    ),
+)]
+pub async fn abandon_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> AdminAPIResult<Json<AdminChangeSet>> {
+    let access_builder = dal::AccessBuilder::new(
+        dal::Tenancy::new(workspace_pk),
+        *access_builder.history_actor(),
+    );
+    let ctx = builder.build_head(access_builder).await?;
+
+    let workspace = Workspace::get_by_pk(&ctx, &workspace_pk)
+        .await?
+        .ok_or(WorkspaceError::WorkspaceNotFound(workspace_pk))?;
+    if workspace.default_change_set_id() == change_set_id {
+        return Err(AdminAPIError::CannotAbandonHead(change_set_id));
+    }
+
+    let mut change_set = ChangeSet::find(&ctx, change_set_id)
+        .await?
+        .ok_or(AdminAPIError::ChangeSetNotFound(change_set_id))?;
+    let old_status = change_set.status;
+
+    change_set.abandon(&ctx).await?;
+
+    ctx.commit_no_rebase().await?;
+
+    track_no_ctx(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        Some(workspace_pk.to_string()),
+        Some(change_set_id.to_string()),
+        "admin.abandon_change_set",
+        serde_json::json!({
+            "old_status": old_status,
+        }),
+    );
+
+    Ok(Json(change_set.into()))
+}
@@ -0,0 +1,79 @@
+use axum::{extract::Path, Json};
+use dal::{
+    action::{Action, ActionId, ActionState},
+    ChangeSet, ChangeSetId, ComponentId, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use super::{AdminAPIError, AdminAPIResult, AdminChangeSet};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChangeSetJobsResponse {
+    change_set: AdminChangeSet,
+    /// The number of values still waiting on a dependent values update.
+    dependent_value_root_count: usize,
+    /// Every action which has not finished yet, in the order they would run.
+    actions: Vec<AdminPendingAction>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminPendingAction {
+    id: ActionId,
+    state: ActionState,
+    component_id: Option<ComponentId>,
+    originating_change_set_id: ChangeSetId,
+}
+
+#[instrument(
+    name = "admin.get_change_set_jobs",
+    level = "info",
+    skip_all,
+    fields(
+        si.workspace.id = %workspace_pk,
+        si.change_set.id = %change_set_id,
+    ),
+)]
+pub async fn get_change_set_jobs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> AdminAPIResult<Json<GetChangeSetJobsResponse>> {
+    let access_builder = dal::AccessBuilder::new(
+        dal::Tenancy::new(workspace_pk),
+        *access_builder.history_actor(),
+    );
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let change_set = ChangeSet::find(&ctx, change_set_id)
+        .await?
+        .ok_or(AdminAPIError::ChangeSetNotFound(change_set_id))?;
+
+    let dependent_value_root_count = ctx
+        .workspace_snapshot()?
+        .get_dependent_value_roots()
+        .await?
+        .len();
+
+    let mut actions = Vec::new();
+    for action_id in Action::list_topologically(&ctx).await? {
+        let action = Action::get_by_id(&ctx, action_id).await?;
+        actions.push(AdminPendingAction {
+            id: action_id,
+            state: action.state(),
+            component_id: Action::component_id(&ctx, action_id).await?,
+            originating_change_set_id: action.originating_changeset_id(),
+        });
+    }
+
+    Ok(Json(GetChangeSetJobsResponse {
+        change_set: change_set.into(),
+        dependent_value_root_count,
+        actions,
+    }))
+}
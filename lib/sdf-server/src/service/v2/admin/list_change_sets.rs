@@ -16,10 +16,12 @@ pub struct ListChangesetsResponse {
 
 #[instrument(name = "admin.list_change_sets", skip_all)]
 pub async fn list_change_sets(
-    HandlerContext(builder): HandlerContext,
+    HandlerContext(mut builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     Path(workspace_pk): Path<WorkspacePk>,
 ) -> AdminAPIResult<Json<ListChangesetsResponse>> {
+    // Listing is read-only, so it can be served by the read replica
+    builder.set_replica_reads();
     let ctx = builder.build_head(access_builder).await?;
 
     let change_sets = ChangeSet::list_all_for_workspace(&ctx, workspace_pk)
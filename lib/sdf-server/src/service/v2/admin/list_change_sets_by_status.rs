@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+use axum::{extract::Query, Json};
+use dal::{ChangeSetId, ChangeSetStatus};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use super::{AdminAPIError, AdminAPIResult, AdminChangeSet};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListChangeSetsByStatusRequest {
+    /// A comma separated list of statuses. Defaults to the statuses a change set can get stuck in.
+    pub statuses: Option<String>,
+    /// The maximum number of change sets to list. Defaults to 100 and is capped at 1000.
+    pub limit: Option<u32>,
+    /// The `nextCursor` of the previous page, if any.
+    pub cursor: Option<ChangeSetId>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListChangeSetsByStatusResponse {
+    change_sets: Vec<AdminChangeSet>,
+    /// Present when there may be more change sets to list.
+    next_cursor: Option<ChangeSetId>,
+}
+
+#[instrument(name = "admin.list_change_sets_by_status", skip_all)]
+pub async fn list_change_sets_by_status(
+    HandlerContext(mut builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListChangeSetsByStatusRequest>,
+) -> AdminAPIResult<Json<ListChangeSetsByStatusResponse>> {
+    // Listing is read-only, so it can be served by the read replica
+    builder.set_replica_reads();
+    let ctx = builder.build_head(access_builder).await?;
+
+    let statuses = match request.statuses {
+        Some(statuses) => statuses
+            .split(',')
+            .map(|status| {
+                ChangeSetStatus::from_str(status.trim())
+                    .map_err(|_| AdminAPIError::InvalidChangeSetStatus(status.to_owned()))
+            })
+            .collect::<AdminAPIResult<Vec<_>>>()?,
+        None => vec![
+            ChangeSetStatus::NeedsApproval,
+            ChangeSetStatus::NeedsAbandonApproval,
+            ChangeSetStatus::Approved,
+        ],
+    };
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let change_sets: Vec<AdminChangeSet> = dal::ChangeSet::list_by_status_for_all_workspaces(
+        &ctx,
+        &statuses,
+        request.cursor,
+        limit.into(),
+    )
+    .await?
+    .into_iter()
+    .map(Into::into)
+    .collect();
+
+    let next_cursor = if change_sets.len() == limit as usize {
+        change_sets.last().map(|change_set| change_set.id)
+    } else {
+        None
+    };
+
+    Ok(Json(ListChangeSetsByStatusResponse {
+        change_sets,
+        next_cursor,
+    }))
+}
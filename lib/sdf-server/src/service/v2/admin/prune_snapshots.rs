@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use axum::Json;
+use dal::snapshot_pruning::{self, SnapshotPruningOptions, SnapshotPruningReport};
+use serde::Deserialize;
+use telemetry::prelude::*;
+
+use super::AdminAPIResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneSnapshotsRequest {
+    /// Only report what would be pruned. Defaults to true.
+    pub dry_run: Option<bool>,
+    /// Snapshots are only pruned once they are older than this. Defaults to 30 days.
+    pub min_age_hours: Option<u64>,
+    /// How many snapshots to scan (and prune) at a time.
+    pub batch_size: Option<usize>,
+}
+
+/// Prunes workspace snapshots which no open change set needs anymore, reporting how much was
+/// reclaimed.
+#[instrument(name = "admin.prune_snapshots", level = "info", skip_all)]
+pub async fn prune_snapshots(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Json(request): Json<PruneSnapshotsRequest>,
+) -> AdminAPIResult<Json<SnapshotPruningReport>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let defaults = SnapshotPruningOptions::default();
+    let options = SnapshotPruningOptions {
+        min_age: request
+            .min_age_hours
+            .map(|hours| Duration::from_secs(hours * 60 * 60))
+            .unwrap_or(defaults.min_age),
+        batch_size: request.batch_size.unwrap_or(defaults.batch_size),
+        dry_run: request.dry_run.unwrap_or(defaults.dry_run),
+    };
+
+    let report = snapshot_pruning::prune_snapshots(&ctx, options).await?;
+
+    Ok(Json(report))
+}
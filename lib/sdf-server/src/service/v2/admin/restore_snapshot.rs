@@ -0,0 +1,73 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{ChangeSet, ChangeSetId, WorkspacePk};
+use telemetry::prelude::*;
+
+use super::{AdminAPIError, AdminAPIResult, AdminChangeSet};
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    track_no_ctx,
+};
+
+/// Restores a quarantined change set to the last snapshot whose content was verified, lifting
+/// the quarantine.
+#[instrument(
+    name = "admin.restore_snapshot",
+    level = "info",
+    skip_all,
+    fields(
+        si.workspace.id = %workspace_pk,
+        si.change_set.id = %change_set_id,
+        si.workspace_snapshot.address = Empty,
+    ),
+)]
+pub async fn restore_snapshot(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> AdminAPIResult<Json<AdminChangeSet>> {
+    let span = current_span_for_instrument_at!("info");
+
+    let access_builder = dal::AccessBuilder::new(
+        dal::Tenancy::new(workspace_pk),
+        *access_builder.history_actor(),
+    );
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut change_set = ChangeSet::find(&ctx, change_set_id)
+        .await?
+        .ok_or(AdminAPIError::ChangeSetNotFound(change_set_id))?;
+    if !change_set.is_quarantined() {
+        return Err(AdminAPIError::ChangeSetNotQuarantined(change_set_id));
+    }
+    let corrupt_snapshot_address = change_set.workspace_snapshot_address;
+
+    let workspace_snapshot_address = change_set.restore_last_good_snapshot(&ctx).await?;
+    span.record(
+        "si.workspace_snapshot.address",
+        workspace_snapshot_address.to_string(),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    track_no_ctx(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        Some(workspace_pk.to_string()),
+        Some(change_set_id.to_string()),
+        "admin.restore_snapshot",
+        serde_json::json!({
+            "corrupt_workspace_snapshot_address": corrupt_snapshot_address.to_string(),
+            "workspace_snapshot_address": workspace_snapshot_address.to_string(),
+        }),
+    );
+
+    Ok(Json(change_set.into()))
+}
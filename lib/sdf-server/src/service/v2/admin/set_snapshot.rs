@@ -83,8 +83,9 @@ pub async fn set_snapshot(
         .write_bytes_to_durable_storage(&workspace_snapshot_address, &snapshot_data)
         .await?;
 
+    // Setting a snapshot also lifts any quarantine, since it is how admins fix corrupt ones
     change_set
-        .update_pointer(&ctx, workspace_snapshot_address)
+        .restore_snapshot(&ctx, workspace_snapshot_address)
         .await?;
 
     ctx.commit_no_rebase().await?;
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use telemetry::{prelude::*, ApplicationTelemetryClient};
+
+use super::AdminAPIResult;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTracingLevelRequest {
+    /// Restores the tracing level the process started with before applying anything else.
+    #[serde(default)]
+    pub reset: bool,
+    /// Replaces all tracing directives, i.e. `info,dal=debug`.
+    pub directives: Option<String>,
+    /// Levels for individual modules, applied on top of the resulting directives.
+    #[serde(default)]
+    pub modules: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TracingLevelResponse {
+    pub directives: String,
+}
+
+/// Returns the tracing directives this sdf process is currently filtering with.
+#[instrument(name = "admin.get_tracing_level", level = "info", skip_all)]
+pub async fn get_tracing_level(
+    Extension(telemetry): Extension<ApplicationTelemetryClient>,
+) -> AdminAPIResult<Json<TracingLevelResponse>> {
+    Ok(Json(TracingLevelResponse {
+        directives: telemetry.tracing_directives().await?,
+    }))
+}
+
+/// Updates the tracing directives of this sdf process without a restart.
+///
+/// Invalid directives are rejected and leave the current tracing level in effect.
+#[instrument(name = "admin.update_tracing_level", level = "info", skip_all)]
+pub async fn update_tracing_level(
+    Extension(mut telemetry): Extension<ApplicationTelemetryClient>,
+    Json(request): Json<UpdateTracingLevelRequest>,
+) -> AdminAPIResult<Json<TracingLevelResponse>> {
+    if request.reset {
+        telemetry.reset_tracing_and_wait().await?;
+    }
+    if let Some(directives) = request.directives {
+        telemetry.set_custom_tracing_and_wait(directives).await?;
+    }
+    for (module, level) in request.modules {
+        telemetry.set_module_tracing_and_wait(module, level).await?;
+    }
+
+    Ok(Json(TracingLevelResponse {
+        directives: telemetry.tracing_directives().await?,
+    }))
+}
@@ -1,14 +1,17 @@
 use axum::{
+    http::StatusCode,
     response::{IntoResponse, Response},
     routing::get,
     Router,
 };
+use chrono::{DateTime, Utc};
 use si_events::{ChangeSetId, UserPk};
 use thiserror::Error;
 
-use crate::{service::ApiError, AppState};
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
 
 mod list_audit_logs;
+mod query_audit_logs;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -23,6 +26,12 @@ pub enum AuditLogError {
     DalTransactions(#[from] dal::TransactionsError),
     #[error("dal user error: {0}")]
     DalUser(#[from] dal::UserError),
+    #[error("invalid actor, expected a user pk or \"system\": {0}")]
+    InvalidActor(String),
+    #[error("invalid time range, from ({0}) must be before to ({1})")]
+    InvalidTimeRange(DateTime<Utc>, DateTime<Utc>),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("user not found for id: {0}")]
     UserNotFound(UserPk),
 }
@@ -33,8 +42,8 @@ impl IntoResponse for AuditLogError {
     fn into_response(self) -> Response {
         let err_string = self.to_string();
 
-        #[allow(clippy::match_single_binding)]
         let (status_code, maybe_message) = match self {
+            Self::InvalidActor(_) | Self::InvalidTimeRange(_, _) => (StatusCode::BAD_REQUEST, None),
             _ => (ApiError::DEFAULT_ERROR_STATUS_CODE, None),
         };
 
@@ -45,3 +54,15 @@ impl IntoResponse for AuditLogError {
 pub fn v2_routes() -> Router<AppState> {
     Router::new().route("/", get(list_audit_logs::list_audit_logs))
 }
+
+/// Routes for querying the audit trail of the whole workspace, which is limited to users who can
+/// manage the workspace.
+pub fn v2_workspace_routes(state: AppState) -> Router<AppState> {
+    Router::new().route(
+        "/",
+        get(query_audit_logs::query_audit_logs).layer(WorkspacePermissionLayer::new(
+            state,
+            permissions::Permission::Manage,
+        )),
+    )
+}
@@ -62,7 +62,7 @@ pub async fn list_audit_logs(
 }
 
 #[derive(Debug)]
-struct Assembler {
+pub(super) struct Assembler {
     change_set_cache: HashMap<ChangeSetId, ChangeSet>,
     user_cache: HashMap<UserPk, User>,
 }
@@ -0,0 +1,235 @@
+use std::borrow::Cow;
+
+use audit_database::AuditLogFilter;
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use dal::{audit_logging, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use si_events::{Actor, ChangeSetId, UserPk};
+use si_frontend_types as frontend_types;
+
+use super::{list_audit_logs::Assembler, AuditLogError, AuditLogResult};
+use crate::{
+    extract::{AccessBuilder, HandlerContext},
+    AppState,
+};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 1000;
+/// Exports page through the logs internally, up to this many rows. If there are more, the cursor
+/// to continue from is returned in the [`NEXT_CURSOR_HEADER`].
+const MAX_EXPORT_ROWS: usize = 100_000;
+const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Csv,
+    #[default]
+    Json,
+    Ndjson,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryAuditLogsRequest {
+    /// Either a user pk or "system".
+    actor: Option<String>,
+    entity_type: Option<String>,
+    change_set_id: Option<ChangeSetId>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    cursor: Option<i64>,
+    limit: Option<usize>,
+    sort_ascending: Option<bool>,
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryAuditLogsResponse {
+    logs: Vec<frontend_types::AuditLog>,
+    /// Pass this as the `cursor` to get the next page. `None` on the last page.
+    next_cursor: Option<i64>,
+}
+
+pub async fn query_audit_logs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Query(request): Query<QueryAuditLogsRequest>,
+    State(state): State<AppState>,
+) -> AuditLogResult<Response> {
+    let filter = request.filter()?;
+    let sort_ascending = request.sort_ascending.unwrap_or(false);
+    if let (Some(from), Some(to)) = (filter.from, filter.to) {
+        if from >= to {
+            return Err(AuditLogError::InvalidTimeRange(from, to));
+        }
+    }
+
+    let ctx = builder.build_head(access_builder).await?;
+
+    let (database_logs, next_cursor) = match request.format {
+        ExportFormat::Json => {
+            let limit = request
+                .limit
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+                .clamp(1, MAX_PAGE_SIZE);
+            audit_logging::query(
+                &ctx,
+                state.audit_database_context(),
+                &filter,
+                request.cursor,
+                limit,
+                sort_ascending,
+            )
+            .await?
+        }
+        ExportFormat::Csv | ExportFormat::Ndjson => {
+            let mut database_logs = Vec::new();
+            let mut cursor = request.cursor;
+            loop {
+                let remaining = MAX_EXPORT_ROWS - database_logs.len();
+                let (page, next_cursor) = audit_logging::query(
+                    &ctx,
+                    state.audit_database_context(),
+                    &filter,
+                    cursor,
+                    remaining.min(MAX_PAGE_SIZE),
+                    sort_ascending,
+                )
+                .await?;
+                database_logs.extend(page);
+                cursor = next_cursor;
+                if cursor.is_none() || database_logs.len() >= MAX_EXPORT_ROWS {
+                    break;
+                }
+            }
+            (database_logs, cursor)
+        }
+    };
+
+    let mut assembler = Assembler::new();
+    let mut logs = Vec::with_capacity(database_logs.len());
+    for database_log in database_logs {
+        logs.push(assembler.assemble(&ctx, database_log).await?);
+    }
+
+    let (content_type, filename, body) = match request.format {
+        ExportFormat::Json => {
+            return Ok(Json(QueryAuditLogsResponse { logs, next_cursor }).into_response())
+        }
+        ExportFormat::Csv => ("text/csv", "audit-logs.csv", to_csv(&logs)?),
+        ExportFormat::Ndjson => (
+            "application/x-ndjson",
+            "audit-logs.ndjson",
+            to_ndjson(&logs)?,
+        ),
+    };
+
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, content_type.to_owned()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response();
+    if let Some(next_cursor) = next_cursor {
+        response
+            .headers_mut()
+            .insert(NEXT_CURSOR_HEADER, next_cursor.into());
+    }
+
+    Ok(response)
+}
+
+impl QueryAuditLogsRequest {
+    fn filter(&self) -> AuditLogResult<AuditLogFilter> {
+        let actor = match self.actor.as_deref() {
+            None => None,
+            Some("system") => Some(Actor::System),
+            Some(user_pk) => {
+                Some(Actor::User(user_pk.parse::<UserPk>().map_err(|_| {
+                    AuditLogError::InvalidActor(user_pk.to_owned())
+                })?))
+            }
+        };
+
+        Ok(AuditLogFilter {
+            actor,
+            entity_type: self.entity_type.to_owned(),
+            change_set_id: self.change_set_id,
+            from: self.from,
+            to: self.to,
+        })
+    }
+}
+
+const CSV_HEADER: &str =
+    "timestamp,kind,title,entityType,entityName,userId,userEmail,userName,changeSetId,changeSetName,metadata";
+
+fn to_csv(logs: &[frontend_types::AuditLog]) -> AuditLogResult<String> {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for log in logs {
+        let fields = [
+            log.timestamp.to_owned(),
+            log.kind.to_owned(),
+            log.title.to_owned(),
+            log.entity_type.to_owned(),
+            log.entity_name.to_owned(),
+            log.user_id.map(|id| id.to_string()).unwrap_or_default(),
+            log.user_email.to_owned().unwrap_or_default(),
+            log.user_name.to_owned().unwrap_or_default(),
+            log.change_set_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            log.change_set_name.to_owned().unwrap_or_default(),
+            serde_json::to_string(&log.metadata)?,
+        ];
+        for (index, field) in fields.iter().enumerate() {
+            if index > 0 {
+                csv.push(',');
+            }
+            csv.push_str(&csv_escape(field));
+        }
+        csv.push('\n');
+    }
+    Ok(csv)
+}
+
+fn csv_escape(field: &str) -> String {
+    // Spreadsheets evaluate cells starting with any of these as formulas, and fields such as
+    // entity names are user controlled, so they are prefixed to be read as plain text
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{field}"))
+    } else {
+        Cow::Borrowed(field)
+    };
+
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into_owned()
+    }
+}
+
+fn to_ndjson(logs: &[frontend_types::AuditLog]) -> AuditLogResult<String> {
+    let mut ndjson = String::new();
+    for log in logs {
+        ndjson.push_str(&serde_json::to_string(log)?);
+        ndjson.push('\n');
+    }
+    Ok(ndjson)
+}
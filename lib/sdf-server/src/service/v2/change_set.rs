@@ -11,33 +11,49 @@ use dal::{
     HistoryEventError, WorkspacePk, WsEventError,
 };
 use reqwest::Client;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use si_data_spicedb::SpiceDbError;
 use thiserror::Error;
 
-use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
+use crate::{
+    middleware::{ChangeSetApplyLockError, WorkspacePermissionLayer},
+    service::ApiError,
+    AppState,
+};
 
 mod apply;
 mod approve;
 mod cancel_approval_request;
+mod dry_run_apply;
 mod force_apply;
 mod list;
+mod list_pending_approvals;
+mod plan;
 mod reject;
 mod rename;
 mod reopen;
 mod request_approval;
+mod set_approval_policy;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("action error: {0}")]
+    Action(#[from] dal::action::ActionError),
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] dal::action::prototype::ActionPrototypeError),
     #[error("change set error: {0}")]
     ChangeSet(#[from] dal::ChangeSetError),
     #[error("change set apply error: {0}")]
     ChangeSetApply(#[from] dal::ChangeSetApplyError),
+    #[error("change set apply lock error: {0}")]
+    ChangeSetApplyLock(#[from] ChangeSetApplyLockError),
     #[error("change set not approved for apply. Current state: {0}")]
     ChangeSetNotApprovedForApply(ChangeSetStatus),
     #[error("change set not found: {0}")]
     ChangeSetNotFound(ChangeSetId),
+    #[error("component error: {0}")]
+    Component(#[from] dal::ComponentError),
     #[error("dvu roots are not empty for change set: {0}")]
     DvuRootsNotEmpty(ChangeSetId),
     #[error("func error: {0}")]
@@ -62,6 +78,8 @@ pub enum Error {
     UnexpectedNumberOfOpenChangeSetsMatchingDefaultChangeSet(Vec<ChangeSetId>),
     #[error("Failed to post to webhook: {0}")]
     Webhook(String),
+    #[error("workspace error: {0}")]
+    Workspace(#[from] dal::WorkspaceError),
     #[error("workspace integration error: {0}")]
     WorkspaceIntegrations(#[from] dal::workspace_integrations::WorkspaceIntegrationsError),
     #[error("workspace snapshot error: {0}")]
@@ -72,7 +90,19 @@ pub enum Error {
 
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
+        // Lock conflicts carry their own retry hints
+        if let Self::ChangeSetApplyLock(err) = self {
+            return err.into_response();
+        }
+
         let status_code = match &self {
+            Self::ChangeSet(dal::ChangeSetError::ChangeSetNotApprovedForApply(_)) => {
+                StatusCode::PRECONDITION_FAILED
+            }
+            Self::ChangeSet(dal::ChangeSetError::ChangeSetNotAwaitingApproval(..)) => {
+                StatusCode::CONFLICT
+            }
+            Self::ChangeSet(dal::ChangeSetError::SelfApproval(_)) => StatusCode::FORBIDDEN,
             Self::ChangeSetApply(_) => StatusCode::CONFLICT,
             Self::DvuRootsNotEmpty(_) => StatusCode::PRECONDITION_FAILED,
             Self::Transactions(dal::TransactionsError::BadWorkspaceAndChangeSet) => {
@@ -89,6 +119,13 @@ pub type ChangeSetAPIError = Error;
 
 type Result<T> = result::Result<T, Error>;
 
+/// The optional body of approvals and rejections.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequest {
+    pub comment: Option<String>,
+}
+
 #[derive(Serialize)]
 struct SlackMessage<'a> {
     text: &'a str,
@@ -127,6 +164,8 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
             "/:change_set_id",
             Router::new()
                 .route("/apply", post(apply::apply))
+                .route("/apply/dry_run", post(dry_run_apply::dry_run_apply))
+                .route("/plan", post(plan::plan))
                 .route(
                     "/request_approval",
                     post(request_approval::request_approval),
@@ -160,5 +199,13 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
                 )
                 .route("/rename", post(rename::rename)),
         )
+        .route(
+            "/approval_policy",
+            post(set_approval_policy::set_approval_policy),
+        )
+        .route(
+            "/pending_approvals",
+            get(list_pending_approvals::list_pending_approvals),
+        )
         .route("/", get(list::list_actionable))
 }
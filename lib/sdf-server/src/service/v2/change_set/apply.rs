@@ -1,66 +1,97 @@
-use axum::extract::{Host, OriginalUri, Path};
-use dal::{ChangeSet, ChangeSetId, WorkspacePk};
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{
+    change_set::{apply_metrics::ChangeSetApplyMetrics, size::ChangeSetSizeViolation},
+    ChangeSet, ChangeSetId, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
 use si_events::audit_log::AuditLogKind;
 
 use super::{post_to_webhook, Error, Result};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
+    middleware::ChangeSetApplyLocks,
     track,
 };
 
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyChangeSetResponse {
+    /// Timing and size of the apply, for completion summaries.
+    pub metrics: ChangeSetApplyMetrics,
+    /// Soft size limits the change set exceeded. It was applied regardless.
+    pub size_warnings: Vec<ChangeSetSizeViolation>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn apply(
+    _: AuthorizedTo<operation::ApplyChangeSet>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
     Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
-) -> Result<()> {
+    apply_locks: ChangeSetApplyLocks,
+) -> Result<Json<ApplyChangeSetResponse>> {
     let mut ctx = builder
         .build(request_ctx.build(change_set_id.into()))
         .await?;
-    let change_set = ChangeSet::find(&ctx, change_set_id)
-        .await?
-        .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
-    ChangeSet::prepare_for_apply(&ctx).await?;
+    let apply_lock = apply_locks.acquire(&ctx).await?;
+    // The lock is released whether or not the apply succeeds
+    let result: Result<Json<ApplyChangeSetResponse>> = async {
+        let change_set = ChangeSet::find(&ctx, change_set_id)
+            .await?
+            .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
+        let size_report = ChangeSet::prepare_for_apply(&ctx).await?;
 
-    // We need to run a commit before apply so changes get saved
-    ctx.commit().await?;
+        // We need to run a commit before apply so changes get saved
+        ctx.commit().await?;
 
-    ChangeSet::apply_to_base_change_set(&mut ctx).await?;
+        let (_, metrics) = ChangeSet::apply_to_base_change_set_with_metrics(&mut ctx).await?;
 
-    let change_set_view = ChangeSet::find(&ctx, ctx.visibility().change_set_id)
-        .await?
-        .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?
-        .into_frontend_type(&ctx)
-        .await?;
+        let change_set_view = ChangeSet::find(&ctx, ctx.visibility().change_set_id)
+            .await?
+            .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?
+            .into_frontend_type(&ctx)
+            .await?;
 
-    track(
-        &posthog_client,
-        &ctx,
-        &original_uri,
-        &host_name,
-        "apply_change_set",
-        serde_json::json!({
-            "merged_change_set": change_set_id,
-        }),
-    );
+        track(
+            &posthog_client,
+            &ctx,
+            &original_uri,
+            &host_name,
+            "apply_change_set",
+            serde_json::json!({
+                "merged_change_set": change_set_id,
+            }),
+        );
 
-    ctx.write_audit_log(AuditLogKind::ApplyChangeSet, change_set.name)
-        .await?;
+        ctx.write_audit_log(AuditLogKind::ApplyChangeSet, change_set.name)
+            .await?;
+
+        let actor = ctx.history_actor().email(&ctx).await?;
+        let change_set_url = format!("https://{}/w/{}/{}", host_name, workspace_pk, change_set_id);
+        let message = format!(
+            "{} applied change set {} to HEAD: {}",
+            actor,
+            change_set_view.name.clone(),
+            change_set_url
+        );
+        post_to_webhook(&ctx, workspace_pk, message.as_str()).await?;
 
-    let actor = ctx.history_actor().email(&ctx).await?;
-    let change_set_url = format!("https://{}/w/{}/{}", host_name, workspace_pk, change_set_id);
-    let message = format!(
-        "{} applied change set {} to HEAD: {}",
-        actor,
-        change_set_view.name.clone(),
-        change_set_url
-    );
-    post_to_webhook(&ctx, workspace_pk, message.as_str()).await?;
+        // WS Event fires from the dal
+        ctx.commit().await?;
 
-    // WS Event fires from the dal
-    ctx.commit().await?;
+        Ok(Json(ApplyChangeSetResponse {
+            metrics,
+            size_warnings: size_report.warnings,
+        }))
+    }
+    .await;
 
-    Ok(())
+    apply_lock.release().await;
+    result
 }
@@ -1,21 +1,32 @@
-use axum::extract::{Host, OriginalUri, Path};
-use dal::{ChangeSet, ChangeSetId, WorkspacePk, WsEvent};
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{
+    change_set::approval::{ChangeSetApprovalState, ChangeSetApprovalStatus},
+    ChangeSet, ChangeSetId, WorkspacePk, WsEvent,
+};
 use si_events::audit_log::AuditLogKind;
 
-use super::{post_to_webhook, Error, Result};
+use super::{post_to_webhook, ApprovalRequest, Error, Result};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     track,
 };
 
+/// Records the approval of the change set by the user, which approves it for apply if it was the
+/// last approval required by the workspace's policy.
+#[allow(clippy::too_many_arguments)]
 pub async fn approve(
+    _: AuthorizedTo<operation::ApproveChangeSet>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
     Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
-) -> Result<()> {
+    request: Option<Json<ApprovalRequest>>,
+) -> Result<Json<ChangeSetApprovalState>> {
     let ctx = builder
         .build(request_ctx.build(change_set_id.into()))
         .await?;
@@ -40,7 +51,10 @@ pub async fn approve(
         .await?
         .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
     let old_status = change_set.status;
-    change_set.approve_change_set_for_apply(&ctx).await?;
+    let comment = request.and_then(|Json(request)| request.comment);
+    let approval_state = change_set
+        .record_approval(&ctx, ChangeSetApprovalStatus::Approved, comment)
+        .await?;
 
     track(
         &posthog_client,
@@ -50,6 +64,7 @@ pub async fn approve(
         "approve_change_set_apply",
         serde_json::json!({
             "merged_change_set": change_set_id,
+            "approved_for_apply": approval_state.status != old_status,
         }),
     );
     let change_set_view = ChangeSet::find(&ctx, ctx.visibility().change_set_id)
@@ -75,12 +90,18 @@ pub async fn approve(
     );
     post_to_webhook(&ctx, workspace_pk, message.as_str()).await?;
 
-    WsEvent::change_set_status_changed(&ctx, old_status, change_set_view)
+    WsEvent::change_set_approvals_changed(&ctx, approval_state.clone())
         .await?
         .publish_on_commit(&ctx)
         .await?;
+    if approval_state.status != old_status {
+        WsEvent::change_set_status_changed(&ctx, old_status, change_set_view)
+            .await?
+            .publish_on_commit(&ctx)
+            .await?;
+    }
 
     ctx.commit().await?;
 
-    Ok(())
+    Ok(Json(approval_state))
 }
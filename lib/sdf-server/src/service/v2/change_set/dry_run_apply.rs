@@ -0,0 +1,107 @@
+use axum::{extract::Path, Json};
+use dal::{
+    action::{
+        dependency_graph::ActionDependencyGraph,
+        prototype::{ActionKind, ActionPrototype},
+        Action, ActionId, ActionState,
+    },
+    change_set::size::ChangeSetSizeReport,
+    ChangeSet, ChangeSetId, ChangeSetStatus, Component, ComponentId, WorkspacePk,
+};
+use serde::Serialize;
+use telemetry::prelude::*;
+
+use super::{Error, Result};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunApplyResponse {
+    pub change_set_id: ChangeSetId,
+    /// Whether the change set can be applied as is, or needs approval first.
+    pub approved_for_apply: bool,
+    /// The actions that applying would enqueue on HEAD, in the order they would run.
+    pub actions: Vec<PlannedAction>,
+    /// How large the change set is, and which size limits it exceeds. Applying is blocked while
+    /// it exceeds a hard limit.
+    pub size: ChangeSetSizeReport,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedAction {
+    pub id: ActionId,
+    pub name: String,
+    pub kind: ActionKind,
+    pub state: ActionState,
+    pub component_id: Option<ComponentId>,
+    pub component_name: Option<String>,
+    // Actions that need to finish before this one can start
+    pub dependent_on: Vec<ActionId>,
+}
+
+/// Plans what applying the change set would do, without locking variants, touching HEAD or
+/// enqueuing any jobs.
+pub async fn dry_run_apply(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<DryRunApplyResponse>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+    let change_set = ChangeSet::find(&ctx, change_set_id)
+        .await?
+        .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
+
+    // Same as apply: until values have settled, the planned actions may still change
+    if !ctx
+        .workspace_snapshot()?
+        .get_dependent_value_roots()
+        .await?
+        .is_empty()
+    {
+        return Err(Error::DvuRootsNotEmpty(ctx.change_set_id()));
+    }
+
+    let action_graph = ActionDependencyGraph::for_workspace(&ctx).await?;
+    if !action_graph.is_acyclic() {
+        warn!("action graph for change set {change_set_id} has a cycle");
+    }
+
+    let mut actions = Vec::new();
+    for action_id in Action::list_topologically(&ctx).await? {
+        let action = Action::get_by_id(&ctx, action_id).await?;
+        // Actions that were already on HEAD are not enqueued by applying
+        if action.originating_changeset_id() != change_set_id {
+            continue;
+        }
+
+        let prototype_id = Action::prototype_id(&ctx, action_id).await?;
+        let prototype = ActionPrototype::get_by_id(&ctx, prototype_id).await?;
+        let component_id = Action::component_id(&ctx, action_id).await?;
+        let component_name = match component_id {
+            Some(component_id) => Some(Component::name_by_id(&ctx, component_id).await?),
+            None => None,
+        };
+
+        actions.push(PlannedAction {
+            id: action_id,
+            name: prototype.name().clone(),
+            kind: prototype.kind,
+            state: action.state(),
+            component_id,
+            component_name,
+            dependent_on: action_graph.direct_dependencies_of(action_id),
+        });
+    }
+
+    let size = ChangeSet::size_report(&ctx).await?;
+
+    Ok(Json(DryRunApplyResponse {
+        change_set_id,
+        approved_for_apply: change_set.status == ChangeSetStatus::Approved,
+        actions,
+        size,
+    }))
+}
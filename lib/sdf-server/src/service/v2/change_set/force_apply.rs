@@ -1,61 +1,81 @@
-use axum::extract::{Host, OriginalUri, Path};
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
 use dal::{ChangeSet, ChangeSetId, WorkspacePk};
 use si_events::audit_log::AuditLogKind;
 
-use super::{Error, Result};
+use super::{apply::ApplyChangeSetResponse, Error, Result};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
+    middleware::ChangeSetApplyLocks,
     track,
 };
 
+/// Applies a change set regardless of the workspace's approval policy. Only owners may do this, and
+/// it is audit logged separately from regular approvals so that it can be told apart from them.
+#[allow(clippy::too_many_arguments)]
 pub async fn force_apply(
+    _: AuthorizedTo<operation::ForceApplyChangeSet>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
     Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
-) -> Result<()> {
+    apply_locks: ChangeSetApplyLocks,
+) -> Result<Json<ApplyChangeSetResponse>> {
     let mut ctx = builder
         .build(request_ctx.build(change_set_id.into()))
         .await?;
-    let change_set = ChangeSet::find(&ctx, change_set_id)
-        .await?
-        .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
-    let old_status = change_set.status;
-    ChangeSet::prepare_for_force_apply(&ctx).await?;
-    ctx.write_audit_log(
-        AuditLogKind::ApproveChangeSetApply {
-            from_status: old_status.into(),
-        },
-        change_set.name,
-    )
-    .await?;
-    // We need to run a commit before apply so changes get saved
-    ctx.commit().await?;
-
-    ChangeSet::apply_to_base_change_set(&mut ctx).await?;
-
-    track(
-        &posthog_client,
-        &ctx,
-        &original_uri,
-        &host_name,
-        "apply_change_set",
-        serde_json::json!({
-            "merged_change_set": change_set_id,
-        }),
-    );
-
-    let change_set = ChangeSet::find(&ctx, ctx.visibility().change_set_id)
-        .await?
-        .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
-
-    ctx.write_audit_log(AuditLogKind::ApplyChangeSet, change_set.name)
+    let apply_lock = apply_locks.acquire(&ctx).await?;
+    // The lock is released whether or not the apply succeeds
+    let result: Result<Json<ApplyChangeSetResponse>> = async {
+        let change_set = ChangeSet::find(&ctx, change_set_id)
+            .await?
+            .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
+        let old_status = change_set.status;
+        let size_report = ChangeSet::prepare_for_force_apply(&ctx).await?;
+        ctx.write_audit_log(
+            AuditLogKind::ForceApplyChangeSet {
+                from_status: old_status.into(),
+            },
+            change_set.name,
+        )
         .await?;
-    // Ws Event fires from the dal
+        // We need to run a commit before apply so changes get saved
+        ctx.commit().await?;
+
+        let (_, metrics) = ChangeSet::apply_to_base_change_set_with_metrics(&mut ctx).await?;
+
+        track(
+            &posthog_client,
+            &ctx,
+            &original_uri,
+            &host_name,
+            "apply_change_set",
+            serde_json::json!({
+                "merged_change_set": change_set_id,
+            }),
+        );
+
+        let change_set = ChangeSet::find(&ctx, ctx.visibility().change_set_id)
+            .await?
+            .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
+
+        ctx.write_audit_log(AuditLogKind::ApplyChangeSet, change_set.name)
+            .await?;
+        // Ws Event fires from the dal
+
+        ctx.commit().await?;
 
-    ctx.commit().await?;
+        Ok(Json(ApplyChangeSetResponse {
+            metrics,
+            size_warnings: size_report.warnings,
+        }))
+    }
+    .await;
 
-    Ok(())
+    apply_lock.release().await;
+    result
 }
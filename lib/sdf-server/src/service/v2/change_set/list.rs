@@ -18,15 +18,9 @@ pub async fn list_actionable(
     Host(host_name): Host,
     State(mut state): State<AppState>,
     Path(workspace_pk): Path<WorkspacePk>,
-) -> Result<Json<si_frontend_types::WorkspaceMetadata>> {
+) -> Result<Json<si_frontend_types::WorkspaceView>> {
     let ctx = builder.build_head(request_ctx).await?;
 
-    // List all actionable change sets and assemble them into individual views.
-    let open_change_sets = ChangeSet::list_active(&ctx).await?;
-    let mut views = Vec::with_capacity(open_change_sets.len());
-    for change_set in open_change_sets {
-        views.push(change_set.into_frontend_type(&ctx).await?);
-    }
     let client = state.spicedb_client().ok_or(Error::SpiceDBNotFound)?;
     //todo(brit): wire this through the spicedb internals
     let approvers = client
@@ -37,13 +31,23 @@ pub async fn list_actionable(
             "user".to_owned(),
         )
         .await?;
+    let is_approver = ChangeSet::extract_userid_from_context(&ctx)
+        .await
+        .is_some_and(|user_pk| approvers.contains(&user_pk.to_string()));
+
+    // List all actionable change sets and assemble them into individual views.
+    let open_change_sets = ChangeSet::list_active(&ctx).await?;
+    let mut views = Vec::with_capacity(open_change_sets.len());
+    for change_set in open_change_sets {
+        views.push(change_set.into_frontend_view(&ctx, is_approver).await?);
+    }
 
     // Ensure that we find exactly one change set view that matches the open change sets found.
     let head_change_set_id = ctx.get_workspace_default_change_set_id().await?;
     let maybe_head_change_set_id: Vec<ChangeSetId> = views
         .iter()
         .filter_map(|v| {
-            if v.id == head_change_set_id {
+            if v.change_set.id == head_change_set_id {
                 Some(head_change_set_id)
             } else {
                 None
@@ -58,12 +62,13 @@ pub async fn list_actionable(
         );
     }
     let workspace = &ctx.get_workspace().await?;
-    let workspace_view = si_frontend_types::WorkspaceMetadata {
+    let workspace_view = si_frontend_types::WorkspaceView {
         name: workspace.name().to_string(),
         id: workspace.pk().to_string(),
         default_change_set_id: head_change_set_id,
         change_sets: views,
         approvers,
+        can_approve: is_approver,
     };
     track(
         &posthog_client,
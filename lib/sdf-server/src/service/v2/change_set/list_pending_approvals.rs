@@ -0,0 +1,17 @@
+use axum::{extract::Path, Json};
+use dal::{change_set::approval::ChangeSetApprovalState, ChangeSet, WorkspacePk};
+
+use super::Result;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+/// Lists the change sets of the workspace which are awaiting approval, along with the approvals
+/// they have gotten so far.
+pub async fn list_pending_approvals(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> Result<Json<Vec<ChangeSetApprovalState>>> {
+    let ctx = builder.build_head(request_ctx).await?;
+
+    Ok(Json(ChangeSet::list_pending_approvals(&ctx).await?))
+}
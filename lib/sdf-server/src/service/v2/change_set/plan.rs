@@ -0,0 +1,29 @@
+use axum::{extract::Path, Json};
+use dal::{action::plan::ActionPlan, ChangeSetId, WorkspacePk};
+
+use super::{Error, Result};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+/// Simulates the create, update and destroy actions queued in the change set, previewing what
+/// each would do to its component's resource. Nothing is enqueued or recorded.
+pub async fn plan(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<ActionPlan>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    // Same as apply: until values have settled, the simulated funcs would see stale properties
+    if !ctx
+        .workspace_snapshot()?
+        .get_dependent_value_roots()
+        .await?
+        .is_empty()
+    {
+        return Err(Error::DvuRootsNotEmpty(ctx.change_set_id()));
+    }
+
+    Ok(Json(ActionPlan::for_change_set(&ctx).await?))
+}
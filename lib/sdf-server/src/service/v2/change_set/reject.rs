@@ -1,21 +1,31 @@
-use axum::extract::{Host, OriginalUri, Path};
-use dal::{ChangeSet, ChangeSetId, WorkspacePk, WsEvent};
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{
+    change_set::approval::{ChangeSetApprovalState, ChangeSetApprovalStatus},
+    ChangeSet, ChangeSetId, WorkspacePk, WsEvent,
+};
 use si_events::audit_log::AuditLogKind;
 
-use super::{post_to_webhook, Error, Result};
+use super::{post_to_webhook, ApprovalRequest, Error, Result};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     track,
 };
 
+/// Records the rejection of the change set by the user, which rejects it for apply.
+#[allow(clippy::too_many_arguments)]
 pub async fn reject(
+    _: AuthorizedTo<operation::ApproveChangeSet>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
     Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
-) -> Result<()> {
+    request: Option<Json<ApprovalRequest>>,
+) -> Result<Json<ChangeSetApprovalState>> {
     let ctx = builder
         .build(request_ctx.build(change_set_id.into()))
         .await?;
@@ -25,7 +35,10 @@ pub async fn reject(
         .ok_or(Error::ChangeSetNotFound(ctx.change_set_id()))?;
     let old_status = change_set.status;
 
-    change_set.reject_change_set_for_apply(&ctx).await?;
+    let comment = request.and_then(|Json(request)| request.comment);
+    let approval_state = change_set
+        .record_approval(&ctx, ChangeSetApprovalStatus::Rejected, comment.clone())
+        .await?;
 
     track(
         &posthog_client,
@@ -54,13 +67,20 @@ pub async fn reject(
     let actor = ctx.history_actor().email(&ctx).await?;
     let change_set_url = format!("https://{}/w/{}/{}", host_name, workspace_pk, change_set_id);
     let message = format!(
-        "{} rejected merge of change set {}: {}",
+        "{} rejected merge of change set {}{}: {}",
         actor,
         change_set_view.name.clone(),
+        comment
+            .map(|comment| format!(" ({comment})"))
+            .unwrap_or_default(),
         change_set_url
     );
     post_to_webhook(&ctx, workspace_pk, message.as_str()).await?;
 
+    WsEvent::change_set_approvals_changed(&ctx, approval_state.clone())
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
     WsEvent::change_set_status_changed(&ctx, old_status, change_set_view)
         .await?
         .publish_on_commit(&ctx)
@@ -68,5 +88,5 @@ pub async fn reject(
 
     ctx.commit().await?;
 
-    Ok(())
+    Ok(Json(approval_state))
 }
@@ -0,0 +1,37 @@
+use axum::{extract::Path, Json};
+use dal::{Workspace, WorkspaceError, WorkspacePk};
+use serde::{Deserialize, Serialize};
+
+use super::Result;
+use crate::extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalPolicy {
+    /// The number of users, other than the one who requested it, who have to approve a change
+    /// set before it can be applied. Without one, a single approval from anyone is enough.
+    pub required_approvals: Option<i32>,
+}
+
+pub async fn set_approval_policy(
+    _: AuthorizedTo<operation::SetApprovalPolicy>,
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path(workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<ApprovalPolicy>,
+) -> Result<Json<ApprovalPolicy>> {
+    let ctx = builder.build_head(request_ctx).await?;
+
+    let mut workspace = Workspace::get_by_pk(&ctx, &workspace_pk)
+        .await?
+        .ok_or(WorkspaceError::WorkspaceNotFound(workspace_pk))?;
+    workspace
+        .set_required_change_set_approvals(&ctx, request.required_approvals)
+        .await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(ApprovalPolicy {
+        required_approvals: workspace.required_change_set_approvals(),
+    }))
+}
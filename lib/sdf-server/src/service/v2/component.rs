@@ -0,0 +1,41 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use dal::{ComponentError, TransactionsError};
+use thiserror::Error;
+
+use crate::{service::ApiError, AppState};
+
+pub mod get_diff;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ComponentAPIError {
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ComponentAPIResult<T> = Result<T, ComponentAPIError>;
+
+impl IntoResponse for ComponentAPIError {
+    fn into_response(self) -> Response {
+        let status_code = match &self {
+            Self::Component(ComponentError::NotFound(_)) => StatusCode::NOT_FOUND,
+            Self::Transactions(TransactionsError::BadWorkspaceAndChangeSet) => {
+                StatusCode::FORBIDDEN
+            }
+            _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new().route("/:component_id/diff", get(get_diff::get_diff))
+}
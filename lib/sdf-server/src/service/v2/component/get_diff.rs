@@ -0,0 +1,40 @@
+use axum::{extract::Path, Json};
+use dal::{
+    component::diff::{ComponentDiff, ComponentDiffStatus, ComponentStructuredDiff},
+    ChangeSetId, Component, ComponentId, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentAPIResult;
+use crate::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDiffResponse {
+    /// The prop-by-prop diff against _head_.
+    pub diff: ComponentStructuredDiff,
+    /// The diff rendered as a diff of the component's JSON, unless the component was removed.
+    pub rendered: Option<ComponentDiff>,
+}
+
+pub async fn get_diff(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id, component_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        ComponentId,
+    )>,
+) -> ComponentAPIResult<Json<GetDiffResponse>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let diff = Component::get_structured_diff(&ctx, component_id).await?;
+    let rendered = match diff.status {
+        ComponentDiffStatus::Removed => None,
+        _ => Some(Component::get_diff(&ctx, component_id).await?),
+    };
+
+    Ok(Json(GetDiffResponse { diff, rendered }))
+}
@@ -34,6 +34,7 @@ pub mod get_code;
 pub mod get_func_run;
 pub mod list_all_funcs;
 pub mod list_funcs;
+pub mod list_params;
 pub mod save_code;
 pub mod test_execute;
 pub mod update_func;
@@ -67,8 +68,14 @@ pub enum FuncAPIError {
     FuncNameReserved(String),
     #[error("The function does not exist")]
     FuncNotFound(FuncId),
+    #[error("func runner error: {0}")]
+    FuncRunner(#[from] FuncRunnerError),
     #[error("hyper error: {0}")]
     Http(#[from] axum::http::Error),
+    #[error("invalid cursor: {0}")]
+    InvalidCursor(String),
+    #[error("invalid func id: {0}")]
+    InvalidFuncId(String),
     #[error("layer db error: {0}")]
     LayerDb(#[from] LayerDbError),
     #[error("missing action kind")]
@@ -122,7 +129,9 @@ impl IntoResponse for FuncAPIError {
                     }
                     FunctionResultFailureErrorKind::InvalidReturnType
                     | FunctionResultFailureErrorKind::KilledExecution
-                    | FunctionResultFailureErrorKind::ActionFieldWrongType => {
+                    | FunctionResultFailureErrorKind::ActionFieldWrongType
+                    | FunctionResultFailureErrorKind::LimitExceeded(_)
+                    | FunctionResultFailureErrorKind::UnsupportedRuntimeVersion => {
                         (StatusCode::UNPROCESSABLE_ENTITY, Some(message))
                     }
                     FunctionResultFailureErrorKind::UserCodeException(lang_server_error_kind) => {
@@ -146,6 +155,8 @@ impl IntoResponse for FuncAPIError {
             | Self::MissingOutputLocationForAttributeFunc
             | Self::MissingPrototypeId
             | Self::MissingSchemaVariantAndFunc
+            | Self::InvalidCursor(_)
+            | Self::InvalidFuncId(_)
             | Self::Func(FuncError::FuncLocked(_))
             | Self::SchemaVariant(dal::SchemaVariantError::SchemaVariantLocked(_)) => {
                 (StatusCode::BAD_REQUEST, None)
@@ -178,6 +189,7 @@ impl IntoResponse for FuncAPIError {
             Self::FuncAuthoring(FuncAuthoringError::AttributeValue(AttributeValueError::FuncRunner(err))) =>
                 func_runner_err_to_status_and_message(*err),
             Self::FuncAuthoring(FuncAuthoringError::FuncRunner(err)) => func_runner_err_to_status_and_message(err),
+            Self::FuncRunner(err) => func_runner_err_to_status_and_message(err),
 
 
             _ => (ApiError::DEFAULT_ERROR_STATUS_CODE, None)
@@ -187,13 +199,16 @@ impl IntoResponse for FuncAPIError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
-    Router::new()
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    let routes = Router::new()
         // Func Stuff
         .route("/", get(list_funcs::list_funcs))
         .route("/including_pruned", get(list_all_funcs::list_all_funcs))
-        .route("/code", get(get_code::get_code)) // accepts a list of func_ids
         .route("/runs/:func_run_id", get(get_func_run::get_func_run)) // accepts a list of func_ids
+        .route(
+            "/runs/:func_run_id/logs",
+            get(get_func_run::get_func_run_logs),
+        ) // paginated with a `cursor` query param
         .route("/", post(create_func::create_func))
         .route("/:func_id", put(update_func::update_func)) // only save the func's metadata
         .route("/:func_id/code", put(save_code::save_code)) // only saves func code
@@ -238,7 +253,12 @@ pub fn v2_routes() -> Router<AppState> {
         .route(
             "/:func_id/generate_aws_function",
             get(generate_aws_function::generate_aws_function),
-        )
+        );
+
+    super::change_set_mutations(state, routes)
+        // Reads, even when posted, so they are served while the change set is locked
+        .route("/code", get(get_code::get_code)) // accepts a list of func_ids
+        .route("/code", post(get_code::get_code_batch)) // accepts a list of func_ids in the body
 }
 
 // helper to assemble the front end struct to return the code and types so SDF can decide when these events need to fire
@@ -10,12 +10,14 @@ use si_events::audit_log::AuditLogKind;
 use si_frontend_types::FuncSummary;
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::{force_change_set_response::ForceChangeSetResponse, v2::func::FuncAPIResult},
     track,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub async fn delete_func_argument(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
@@ -10,7 +10,7 @@ use si_events::audit_log::AuditLogKind;
 use si_frontend_types as frontend_types;
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::{
         force_change_set_response::ForceChangeSetResponse,
         v2::func::{FuncAPIError, FuncAPIResult},
@@ -18,7 +18,9 @@ use crate::{
     track,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub async fn reset_attribute_binding(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
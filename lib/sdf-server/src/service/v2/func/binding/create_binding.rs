@@ -14,7 +14,7 @@ use si_events::audit_log::AuditLogKind;
 use si_frontend_types as frontend_types;
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::{
         force_change_set_response::ForceChangeSetResponse,
         v2::func::{FuncAPIError, FuncAPIResult},
@@ -22,7 +22,9 @@ use crate::{
     track,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_binding(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
@@ -1,5 +1,5 @@
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::{
         force_change_set_response::ForceChangeSetResponse,
         v2::func::{FuncAPIError, FuncAPIResult},
@@ -24,7 +24,9 @@ use si_events::audit_log::AuditLogKind;
 use si_frontend_types as frontend_types;
 use std::collections::HashSet;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn delete_binding(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
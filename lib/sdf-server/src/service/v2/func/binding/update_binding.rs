@@ -16,7 +16,7 @@ use dal::{
 use si_frontend_types::{self as frontend_types, FuncBinding};
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::{
         force_change_set_response::ForceChangeSetResponse,
         v2::func::{FuncAPIError, FuncAPIResult},
@@ -160,7 +160,9 @@ async fn update_mangement_func_bindings(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn update_binding(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
@@ -20,7 +20,7 @@ use si_frontend_types::{self as frontend_types, FuncBinding, FuncCode, FuncSumma
 
 use super::{get_code_response, FuncAPIError, FuncAPIResult};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -41,7 +41,9 @@ pub struct CreateFuncResponse {
     code: FuncCode,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_func(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
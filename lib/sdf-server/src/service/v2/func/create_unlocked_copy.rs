@@ -12,7 +12,7 @@ use si_frontend_types::{FuncCode, FuncSummary};
 
 use super::{get_code_response, FuncAPIError, FuncAPIResult};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -29,7 +29,9 @@ pub struct CreateFuncResponse {
     code: FuncCode,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_unlocked_copy(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
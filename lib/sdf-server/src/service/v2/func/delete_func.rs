@@ -5,7 +5,7 @@ use si_events::audit_log::AuditLogKind;
 
 use super::{FuncAPIError, FuncAPIResult};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -18,6 +18,7 @@ pub struct DeleteFuncResponse {
 }
 
 pub async fn delete_func(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
@@ -1,23 +1,27 @@
+use std::{collections::HashSet, str::FromStr};
+
 use axum::{
-    extract::{OriginalUri, Path, Query},
+    extract::{OriginalUri, Path, RawQuery},
     Json,
 };
-use dal::{ChangeSetId, FuncId, WorkspacePk};
+use dal::{ChangeSetId, DalContext, FuncId, WorkspacePk};
 
 use serde::{Deserialize, Serialize};
 use si_frontend_types::FuncCode;
 
 use crate::extract::{AccessBuilder, HandlerContext, PosthogClient};
 
-use super::{get_code_response, FuncAPIResult};
+use super::{get_code_response, FuncAPIError, FuncAPIResult};
+
+/// The query parameter holding the ids, which can be repeated as either `id=<...>` or
+/// `id[]=<...>`.
+const ID_PARAM: &str = "id";
+const ID_ARRAY_PARAM: &str = "id[]";
 
-// TODO: find the right way to pass a Vec<FuncId>
-// the API call uses the `id[]=<...>&id[]=<...?` format
-// but that doesn't work here with Rust
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct GetRequest {
-    pub id: FuncId,
+pub struct GetCodeRequest {
+    pub ids: Vec<FuncId>,
 }
 
 pub async fn get_code(
@@ -26,13 +30,55 @@ pub async fn get_code(
     PosthogClient(_posthog_client): PosthogClient,
     OriginalUri(_original_uri): OriginalUri,
     Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
-    Query(request): Query<GetRequest>,
+    RawQuery(query): RawQuery,
 ) -> FuncAPIResult<Json<Vec<FuncCode>>> {
+    let ids = func_ids_from_query(query.as_deref().unwrap_or_default())?;
+
     let ctx = builder
         .build(access_builder.build(change_set_id.into()))
         .await?;
-    let mut funcs = Vec::new();
 
-    funcs.push(get_code_response(&ctx, request.id).await?);
-    Ok(Json(funcs))
+    Ok(Json(get_codes(&ctx, ids).await?))
+}
+
+/// Like [`get_code`], but takes the ids in the body, for when there are too many to fit in a URL.
+pub async fn get_code_batch(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(_posthog_client): PosthogClient,
+    OriginalUri(_original_uri): OriginalUri,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Json(request): Json<GetCodeRequest>,
+) -> FuncAPIResult<Json<Vec<FuncCode>>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    Ok(Json(get_codes(&ctx, request.ids).await?))
+}
+
+async fn get_codes(ctx: &DalContext, ids: Vec<FuncId>) -> FuncAPIResult<Vec<FuncCode>> {
+    let mut seen = HashSet::with_capacity(ids.len());
+    let mut funcs = Vec::with_capacity(ids.len());
+    for id in ids {
+        // Requesting the same func twice returns its code once
+        if seen.insert(id) {
+            funcs.push(get_code_response(ctx, id).await?);
+        }
+    }
+    Ok(funcs)
+}
+
+fn func_ids_from_query(query: &str) -> FuncAPIResult<Vec<FuncId>> {
+    let mut ids = Vec::new();
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        if key != ID_PARAM && key != ID_ARRAY_PARAM {
+            continue;
+        }
+        ids.push(
+            FuncId::from_str(&value)
+                .map_err(|_| FuncAPIError::InvalidFuncId(value.into_owned()))?,
+        );
+    }
+    Ok(ids)
 }
@@ -1,12 +1,13 @@
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::Json;
 use chrono::{DateTime, Utc};
-use dal::{ContentHash, DalContext, WorkspacePk};
+use dal::{func::runner::FuncRunner, ContentHash, DalContext, WorkspacePk};
 use serde::{Deserialize, Serialize};
 use si_events::{
     ActionId, ActionKind, ActionPrototypeId, ActionResultState, Actor, AttributeValueId, CasValue,
     ChangeSetId, ComponentId, FuncBackendKind, FuncBackendResponseType, FuncKind, FuncRun,
-    FuncRunId, FuncRunLog, FuncRunLogId, FuncRunState, OutputLine,
+    FuncRunId, FuncRunLog, FuncRunLogEntry, FuncRunLogId, FuncRunState, OutputLine,
+    OutputStreamKind,
 };
 use std::sync::Arc;
 
@@ -40,6 +41,26 @@ impl From<&OutputLine> for OutputLineView {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunLogEntryView {
+    sequence: u64,
+    stream_kind: OutputStreamKind,
+    timestamp: DateTime<Utc>,
+    line: OutputLineView,
+}
+
+impl From<FuncRunLogEntry> for FuncRunLogEntryView {
+    fn from(entry: FuncRunLogEntry) -> Self {
+        Self {
+            sequence: entry.sequence,
+            stream_kind: entry.stream_kind,
+            timestamp: entry.timestamp,
+            line: (&entry.line).into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct FuncRunLogView {
@@ -235,3 +256,38 @@ pub async fn get_func_run(
         None => Ok(Json(GetFuncRunResponse { func_run: None })),
     }
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFuncRunLogsRequest {
+    pub cursor: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFuncRunLogsResponse {
+    pub entries: Vec<FuncRunLogEntryView>,
+    pub next_cursor: Option<u64>,
+}
+
+pub async fn get_func_run_logs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id, func_run_id)): Path<(
+        WorkspacePk,
+        dal::ChangeSetId,
+        FuncRunId,
+    )>,
+    Query(request): Query<GetFuncRunLogsRequest>,
+) -> FuncAPIResult<Json<GetFuncRunLogsResponse>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let page = FuncRunner::list_log_entries(&ctx, func_run_id, request.cursor).await?;
+
+    Ok(Json(GetFuncRunLogsResponse {
+        entries: page.entries.into_iter().map(Into::into).collect(),
+        next_cursor: page.next_cursor,
+    }))
+}
@@ -1,11 +1,14 @@
 use axum::{
-    extract::{OriginalUri, Path},
+    extract::{OriginalUri, Path, Query},
     Json,
 };
 use dal::{ChangeSetId, Func, WorkspacePk};
 use telemetry::prelude::*;
 
-use super::FuncAPIResult;
+use super::{
+    list_params::{ListFuncsParams, ListFuncsResponse},
+    FuncAPIResult,
+};
 use crate::extract::{AccessBuilder, HandlerContext, PosthogClient};
 
 pub async fn list_all_funcs(
@@ -14,14 +17,22 @@ pub async fn list_all_funcs(
     PosthogClient(_posthog_client): PosthogClient,
     OriginalUri(_original_uri): OriginalUri,
     Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
-) -> FuncAPIResult<Json<Vec<si_frontend_types::FuncSummary>>> {
+    Query(params): Query<ListFuncsParams>,
+) -> FuncAPIResult<Json<ListFuncsResponse>> {
     let ctx = builder
         .build(access_builder.build(change_set_id.into()))
         .await?;
 
+    let matching: Vec<Func> = Func::list_all(&ctx)
+        .await?
+        .into_iter()
+        .filter(|func| params.matches(func))
+        .collect();
+    let page = params.paginate(matching, |func| func)?;
+
     let mut funcs = Vec::new();
 
-    for func in Func::list_all(&ctx).await? {
+    for func in page.items {
         match func.into_frontend_type(&ctx).await {
             Ok(f) => {
                 funcs.push(f);
@@ -34,5 +45,10 @@ pub async fn list_all_funcs(
             }
         }
     }
-    Ok(Json(funcs))
+
+    Ok(Json(params.response(
+        funcs,
+        page.total_count,
+        page.next_cursor,
+    )))
 }
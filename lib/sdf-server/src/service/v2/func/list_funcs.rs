@@ -1,14 +1,16 @@
 use axum::{
-    extract::{OriginalUri, Path},
+    extract::{OriginalUri, Path, Query},
     Json,
 };
 use dal::func::binding::FuncBinding;
 use dal::{ChangeSetId, DalContext, Func, SchemaId, SchemaVariant, SchemaVariantId, WorkspacePk};
-use si_frontend_types as frontend_types;
 use std::collections::HashMap;
 use telemetry::prelude::*;
 
-use super::FuncAPIResult;
+use super::{
+    list_params::{ListFuncsParams, ListFuncsResponse},
+    FuncAPIResult,
+};
 use crate::extract::{AccessBuilder, HandlerContext, PosthogClient};
 
 pub async fn list_funcs(
@@ -17,18 +19,40 @@ pub async fn list_funcs(
     PosthogClient(_posthog_client): PosthogClient,
     OriginalUri(_original_uri): OriginalUri,
     Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
-) -> FuncAPIResult<Json<Vec<frontend_types::FuncSummary>>> {
+    Query(params): Query<ListFuncsParams>,
+) -> FuncAPIResult<Json<ListFuncsResponse>> {
     let ctx = builder
         .build(access_builder.build(change_set_id.into()))
         .await?;
-    let mut funcs = Vec::new();
 
+    // Visibility has to be determined for every matching func to get the total count, but only
+    // the requested page is converted into the frontend type
+    let mut visible = Vec::new();
     for func in Func::list_all(&ctx).await? {
-        match treat_single_function(&ctx, &func).await {
+        if !params.matches(&func) {
+            continue;
+        }
+        match bindings_if_visible(&ctx, &func).await {
             Ok(None) => {}
-            Ok(Some(f)) => {
-                funcs.push(f);
+            Ok(Some(bindings)) => visible.push((func, bindings)),
+            Err(err) => {
+                error!(
+                    ?err,
+                    "could not make func with id {} into frontend type", func.id
+                )
             }
+        }
+    }
+
+    let page = params.paginate(visible, |(func, _)| func)?;
+
+    let mut funcs = Vec::with_capacity(page.items.len());
+    for (func, bindings) in page.items {
+        match func
+            .into_frontend_type_sideload_bindings(&ctx, bindings)
+            .await
+        {
+            Ok(f) => funcs.push(f),
             Err(err) => {
                 error!(
                     ?err,
@@ -37,13 +61,19 @@ pub async fn list_funcs(
             }
         }
     }
-    Ok(Json(funcs))
+
+    Ok(Json(params.response(
+        funcs,
+        page.total_count,
+        page.next_cursor,
+    )))
 }
 
-async fn treat_single_function(
+/// Returns the bindings of the func, or `None` if the func should not be listed.
+async fn bindings_if_visible(
     ctx: &DalContext,
     func: &Func,
-) -> FuncAPIResult<Option<frontend_types::FuncSummary>> {
+) -> FuncAPIResult<Option<Vec<FuncBinding>>> {
     // compute bindings
     let bindings = FuncBinding::for_func_id(ctx, func.id).await?;
 
@@ -103,9 +133,5 @@ async fn treat_single_function(
         }
     }
 
-    // Convert to frontend type
-    Ok(Some(
-        func.into_frontend_type_sideload_bindings(ctx, bindings)
-            .await?,
-    ))
+    Ok(Some(bindings))
 }
@@ -0,0 +1,229 @@
+//! Filtering, sorting and cursor-based pagination shared by the func list routes.
+//!
+//! Pagination is opt-in: without a `limit` or `cursor`, every matching func is returned as a
+//! plain list, as before.
+//!
+//! Cursors are opaque to clients. They hold the sort value and id of the last func of a page, so
+//! that the next page resumes after that position even if the func has since been removed.
+
+use std::cmp::Ordering;
+
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use dal::{func::FuncKind, Func, FuncId};
+use serde::{Deserialize, Serialize};
+use si_frontend_types::FuncSummary;
+
+use super::{FuncAPIError, FuncAPIResult};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FuncSortKey {
+    Kind,
+    #[default]
+    Name,
+    UpdatedAt,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFuncsParams {
+    pub kind: Option<FuncKind>,
+    pub locked: Option<bool>,
+    /// Case insensitive match against the name and display name.
+    pub search: Option<String>,
+    #[serde(default)]
+    pub sort: FuncSortKey,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// The `nextCursor` of the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncPage {
+    pub funcs: Vec<FuncSummary>,
+    /// The number of funcs matching the filters, across all pages.
+    pub total_count: usize,
+    /// Pass this as the `cursor` to get the next page. `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum ListFuncsResponse {
+    All(Vec<FuncSummary>),
+    Page(FuncPage),
+}
+
+pub(crate) struct Paged<T> {
+    pub items: Vec<T>,
+    pub total_count: usize,
+    pub next_cursor: Option<String>,
+}
+
+/// The value a func is sorted by. Names are compared case insensitively.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+enum SortValue {
+    Kind(String),
+    Name(String),
+    UpdatedAt(DateTime<Utc>),
+}
+
+/// The position of a func in a sorted list. Ties on the sort value are broken by id, so that
+/// positions are unique.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+struct FuncCursor {
+    value: SortValue,
+    id: FuncId,
+}
+
+impl SortValue {
+    fn sort_key(&self) -> FuncSortKey {
+        match self {
+            Self::Kind(_) => FuncSortKey::Kind,
+            Self::Name(_) => FuncSortKey::Name,
+            Self::UpdatedAt(_) => FuncSortKey::UpdatedAt,
+        }
+    }
+}
+
+impl FuncCursor {
+    fn encode(&self) -> FuncAPIResult<String> {
+        Ok(BASE64_URL_SAFE_NO_PAD.encode(serde_json::to_vec(self)?))
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let bytes = BASE64_URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+impl ListFuncsParams {
+    pub fn is_paginated(&self) -> bool {
+        self.cursor.is_some() || self.limit.is_some()
+    }
+
+    pub fn matches(&self, func: &Func) -> bool {
+        if self.kind.is_some_and(|kind| kind != func.kind) {
+            return false;
+        }
+        if self.locked.is_some_and(|locked| locked != func.is_locked) {
+            return false;
+        }
+        match self.search.as_deref().map(str::trim) {
+            Some(search) if !search.is_empty() => {
+                let search = search.to_lowercase();
+                func.name.to_lowercase().contains(&search)
+                    || func
+                        .display_name
+                        .as_deref()
+                        .is_some_and(|display_name| display_name.to_lowercase().contains(&search))
+            }
+            _ => true,
+        }
+    }
+
+    fn position(&self, func: &Func) -> FuncCursor {
+        let value = match self.sort {
+            FuncSortKey::Kind => SortValue::Kind(func.kind.as_ref().to_owned()),
+            FuncSortKey::Name => SortValue::Name(func.name.to_lowercase()),
+            FuncSortKey::UpdatedAt => SortValue::UpdatedAt(func.timestamp.updated_at),
+        };
+
+        FuncCursor { value, id: func.id }
+    }
+
+    fn order(&self, ordering: Ordering) -> Ordering {
+        match self.order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    }
+
+    pub fn response(
+        &self,
+        funcs: Vec<FuncSummary>,
+        total_count: usize,
+        next_cursor: Option<String>,
+    ) -> ListFuncsResponse {
+        if self.is_paginated() {
+            ListFuncsResponse::Page(FuncPage {
+                funcs,
+                total_count,
+                next_cursor,
+            })
+        } else {
+            ListFuncsResponse::All(funcs)
+        }
+    }
+
+    /// Sort the items and cut out the page requested by the cursor and limit. Without
+    /// pagination, every item is returned.
+    pub(crate) fn paginate<T>(
+        &self,
+        mut items: Vec<T>,
+        func: impl Fn(&T) -> &Func,
+    ) -> FuncAPIResult<Paged<T>> {
+        items.sort_by(|a, b| self.order(self.position(func(a)).cmp(&self.position(func(b)))));
+        let total_count = items.len();
+
+        if !self.is_paginated() {
+            return Ok(Paged {
+                items,
+                total_count,
+                next_cursor: None,
+            });
+        }
+
+        let start = match self.cursor.as_deref() {
+            Some(cursor) => {
+                let cursor = FuncCursor::decode(cursor)
+                    .filter(|decoded| decoded.value.sort_key() == self.sort)
+                    .ok_or_else(|| FuncAPIError::InvalidCursor(cursor.to_owned()))?;
+                // Resume after the cursor's position, whether or not its func is still listed
+                items
+                    .iter()
+                    .position(|item| {
+                        self.order(self.position(func(item)).cmp(&cursor)) == Ordering::Greater
+                    })
+                    .unwrap_or(items.len())
+            }
+            None => 0,
+        };
+        let limit = self
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+
+        let mut page: Vec<T> = items.into_iter().skip(start).take(limit + 1).collect();
+        let next_cursor = if page.len() > limit {
+            page.truncate(limit);
+            page.last()
+                .map(|item| self.position(func(item)).encode())
+                .transpose()?
+        } else {
+            None
+        };
+
+        Ok(Paged {
+            items: page,
+            total_count,
+            next_cursor,
+        })
+    }
+}
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use super::{get_code_response, FuncAPIResult};
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -21,7 +21,9 @@ pub struct SaveCodeRequest {
     pub code: String,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn save_code(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
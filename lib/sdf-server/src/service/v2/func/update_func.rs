@@ -1,6 +1,6 @@
 use super::FuncAPIResult;
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -25,7 +25,9 @@ pub struct UpdateFuncRequest {
     client_ulid: Ulid,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn update_func(
+    _: AuthorizedTo<operation::EditFunc>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
@@ -0,0 +1,65 @@
+//! A read-only GraphQL API over the data of a change set, for dashboards which need flexible
+//! queries (such as components with failing qualifications, grouped by schema) without a bespoke
+//! endpoint for each one.
+//!
+//! The API is only served when sdf is configured with `serve_graphql`. Requests are authorized
+//! exactly like the other routes of the change set.
+
+use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use dal::TransactionsError;
+use thiserror::Error;
+
+use crate::{service::ApiError, AppState};
+
+pub mod query;
+pub mod schema;
+
+/// The most nested a query can be, which bounds how much work a single query can ask for.
+const MAX_DEPTH: usize = 10;
+const MAX_COMPLEXITY: usize = 1000;
+
+/// The GraphQL schema served for reads. It has no mutations or subscriptions.
+pub type ReadSchema = Schema<schema::Query, EmptyMutation, EmptySubscription>;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum GraphqlAPIError {
+    #[error("the graphql api is not enabled")]
+    NotEnabled,
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type GraphqlAPIResult<T> = Result<T, GraphqlAPIError>;
+
+impl IntoResponse for GraphqlAPIError {
+    fn into_response(self) -> Response {
+        let status_code = match &self {
+            Self::NotEnabled => StatusCode::NOT_FOUND,
+            Self::Transactions(TransactionsError::BadWorkspaceAndChangeSet) => {
+                StatusCode::FORBIDDEN
+            }
+            _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new().route("/", post(query::query))
+}
+
+/// Builds the schema, which is added to the app as an extension to enable the API.
+pub fn build_schema() -> ReadSchema {
+    Schema::build(schema::Query, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
@@ -0,0 +1,22 @@
+use axum::{extract::Path, Extension, Json};
+use dal::{ChangeSetId, WorkspacePk};
+
+use super::{GraphqlAPIError, GraphqlAPIResult, ReadSchema};
+use crate::extract::{AccessBuilder, HandlerContext};
+
+pub async fn query(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    schema: Option<Extension<ReadSchema>>,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Json(request): Json<async_graphql::Request>,
+) -> GraphqlAPIResult<Json<async_graphql::Response>> {
+    let Extension(schema) = schema.ok_or(GraphqlAPIError::NotEnabled)?;
+
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    // Resolvers only read, so the context is never committed
+    Ok(Json(schema.execute(request.data(ctx)).await))
+}
@@ -0,0 +1,362 @@
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    ComplexObject, Context, Object, OutputType, Result, SimpleObject, ID,
+};
+use dal::{
+    action::{prototype::ActionPrototype, Action, ActionId},
+    qualification::QualificationSubCheckStatus,
+    ChangeSet, Component, ComponentId, DalContext, Schema, SchemaId,
+};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+/// The root of all queries. Everything is read from the change set of the request.
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The components of the change set, ordered by name.
+    async fn components(
+        &self,
+        context: &Context<'_>,
+        schema_name: Option<String>,
+        failing_qualifications: Option<bool>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<usize, ComponentObject>> {
+        let ctx = dal_context(context)?;
+        let components = list_components(ctx, |schema| {
+            schema_name
+                .as_deref()
+                .map_or(true, |name| schema.name() == name)
+        })
+        .await?;
+        let components = filter_by_qualifications(ctx, components, failing_qualifications).await?;
+
+        paginate(components, after, first)
+    }
+
+    /// The schemas installed in the change set, ordered by name.
+    async fn schemas(
+        &self,
+        context: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<usize, SchemaObject>> {
+        let ctx = dal_context(context)?;
+        let mut schemas: Vec<_> = Schema::list(ctx)
+            .await?
+            .into_iter()
+            .map(|schema| SchemaObject {
+                id: ID(schema.id().to_string()),
+                name: schema.name().to_owned(),
+                schema_id: schema.id(),
+            })
+            .collect();
+        schemas.sort_by(|a, b| a.name.cmp(&b.name));
+
+        paginate(schemas, after, first)
+    }
+
+    /// The open change sets of the workspace, newest first.
+    async fn change_sets(
+        &self,
+        context: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<usize, ChangeSetObject>> {
+        let ctx = dal_context(context)?;
+        let mut change_sets = ChangeSet::list_active(ctx).await?;
+        change_sets.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        let change_sets = change_sets
+            .into_iter()
+            .map(|change_set| ChangeSetObject {
+                id: ID(change_set.id.to_string()),
+                name: change_set.name,
+                status: change_set.status.to_string(),
+                base_change_set_id: change_set.base_change_set_id.map(|id| ID(id.to_string())),
+                created_at: change_set.created_at.to_rfc3339(),
+                updated_at: change_set.updated_at.to_rfc3339(),
+            })
+            .collect();
+
+        paginate(change_sets, after, first)
+    }
+
+    /// The func runs of the change set, newest first.
+    async fn func_runs(
+        &self,
+        context: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<usize, FuncRunObject>> {
+        let ctx = dal_context(context)?;
+        let change_set_id = ctx.change_set_id();
+        let mut func_runs: Vec<_> = ctx
+            .layer_db()
+            .func_run()
+            .read_many_for_workspace(ctx.workspace_pk()?)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|func_run| func_run.change_set_id() == change_set_id)
+            .collect();
+        func_runs.sort_by_key(|func_run| std::cmp::Reverse(func_run.created_at()));
+        let func_runs = func_runs
+            .into_iter()
+            .map(|func_run| FuncRunObject {
+                id: ID(func_run.id().to_string()),
+                state: func_run.state().to_string(),
+                function_name: func_run.function_name().to_owned(),
+                function_kind: func_run.function_kind().to_string(),
+                component_id: func_run.component_id().map(|id| ID(id.to_string())),
+                component_name: func_run.component_name().map(ToOwned::to_owned),
+                schema_name: func_run.schema_name().map(ToOwned::to_owned),
+                action_kind: func_run.action_kind().map(|kind| kind.to_string()),
+                created_at: func_run.created_at().to_rfc3339(),
+                updated_at: func_run.updated_at().to_rfc3339(),
+            })
+            .collect();
+
+        paginate(func_runs, after, first)
+    }
+
+    /// The actions of the change set, in the order they would run.
+    async fn actions(
+        &self,
+        context: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<usize, ActionObject>> {
+        let ctx = dal_context(context)?;
+        let mut actions = vec![];
+        for action_id in Action::list_topologically(ctx).await? {
+            actions.push(action_object(ctx, action_id).await?);
+        }
+
+        paginate(actions, after, first)
+    }
+}
+
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(name = "Component", complex)]
+pub struct ComponentObject {
+    id: ID,
+    name: String,
+    schema_name: String,
+    schema_variant_id: ID,
+    #[graphql(skip)]
+    component_id: ComponentId,
+}
+
+#[ComplexObject]
+impl ComponentObject {
+    /// The results of the component's qualifications.
+    async fn qualifications(&self, context: &Context<'_>) -> Result<QualificationCounts> {
+        let ctx = dal_context(context)?;
+        qualification_counts(ctx, self.component_id).await
+    }
+
+    /// The actions enqueued for the component.
+    async fn actions(&self, context: &Context<'_>) -> Result<Vec<ActionObject>> {
+        let ctx = dal_context(context)?;
+        let mut actions = vec![];
+        for action_id in Action::find_for_component_id(ctx, self.component_id).await? {
+            actions.push(action_object(ctx, action_id).await?);
+        }
+
+        Ok(actions)
+    }
+}
+
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(name = "Schema", complex)]
+pub struct SchemaObject {
+    id: ID,
+    name: String,
+    #[graphql(skip)]
+    schema_id: SchemaId,
+}
+
+#[ComplexObject]
+impl SchemaObject {
+    /// The components of the change set which use the schema, ordered by name.
+    async fn components(
+        &self,
+        context: &Context<'_>,
+        failing_qualifications: Option<bool>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<usize, ComponentObject>> {
+        let ctx = dal_context(context)?;
+        let components = list_components(ctx, |schema| schema.id() == self.schema_id).await?;
+        let components = filter_by_qualifications(ctx, components, failing_qualifications).await?;
+
+        paginate(components, after, first)
+    }
+}
+
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(name = "ChangeSet")]
+pub struct ChangeSetObject {
+    id: ID,
+    name: String,
+    status: String,
+    base_change_set_id: Option<ID>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(name = "FuncRun")]
+pub struct FuncRunObject {
+    id: ID,
+    state: String,
+    function_name: String,
+    function_kind: String,
+    component_id: Option<ID>,
+    component_name: Option<String>,
+    schema_name: Option<String>,
+    action_kind: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(name = "Action")]
+pub struct ActionObject {
+    id: ID,
+    name: String,
+    kind: String,
+    state: String,
+    component_id: Option<ID>,
+    originating_change_set_id: ID,
+}
+
+#[derive(Clone, Copy, Debug, Default, SimpleObject)]
+pub struct QualificationCounts {
+    total: usize,
+    succeeded: usize,
+    warned: usize,
+    failed: usize,
+}
+
+fn dal_context<'a>(context: &Context<'a>) -> Result<&'a DalContext> {
+    context.data::<DalContext>()
+}
+
+async fn list_components(
+    ctx: &DalContext,
+    include_schema: impl Fn(&Schema) -> bool,
+) -> Result<Vec<ComponentObject>> {
+    let mut components = vec![];
+    for component in Component::list(ctx).await? {
+        let schema = component.schema(ctx).await?;
+        if !include_schema(&schema) {
+            continue;
+        }
+
+        components.push(ComponentObject {
+            id: ID(component.id().to_string()),
+            name: component.name(ctx).await?,
+            schema_name: schema.name().to_owned(),
+            schema_variant_id: ID(Component::schema_variant_id(ctx, component.id())
+                .await?
+                .to_string()),
+            component_id: component.id(),
+        });
+    }
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(components)
+}
+
+/// Keeps only the components with (or without) a failing qualification, if asked to.
+async fn filter_by_qualifications(
+    ctx: &DalContext,
+    components: Vec<ComponentObject>,
+    failing_qualifications: Option<bool>,
+) -> Result<Vec<ComponentObject>> {
+    let Some(failing) = failing_qualifications else {
+        return Ok(components);
+    };
+
+    let mut filtered = vec![];
+    for component in components {
+        let counts = qualification_counts(ctx, component.component_id).await?;
+        if (counts.failed > 0) == failing {
+            filtered.push(component);
+        }
+    }
+
+    Ok(filtered)
+}
+
+async fn qualification_counts(
+    ctx: &DalContext,
+    component_id: ComponentId,
+) -> Result<QualificationCounts> {
+    let statuses = Component::list_qualification_statuses(ctx, component_id).await?;
+
+    let mut counts = QualificationCounts {
+        total: statuses.len(),
+        ..Default::default()
+    };
+    for status in statuses.into_iter().flatten() {
+        match status {
+            QualificationSubCheckStatus::Success => counts.succeeded += 1,
+            QualificationSubCheckStatus::Warning => counts.warned += 1,
+            QualificationSubCheckStatus::Failure => counts.failed += 1,
+            QualificationSubCheckStatus::Unknown => {}
+        }
+    }
+
+    Ok(counts)
+}
+
+async fn action_object(ctx: &DalContext, action_id: ActionId) -> Result<ActionObject> {
+    let action = Action::get_by_id(ctx, action_id).await?;
+    let prototype =
+        ActionPrototype::get_by_id(ctx, Action::prototype_id(ctx, action_id).await?).await?;
+    let component_id = Action::component_id(ctx, action_id).await?;
+
+    Ok(ActionObject {
+        id: ID(action.id().to_string()),
+        name: prototype.name,
+        kind: prototype.kind.to_string(),
+        state: action.state().to_string(),
+        component_id: component_id.map(|id| ID(id.to_string())),
+        originating_change_set_id: ID(action.originating_changeset_id().to_string()),
+    })
+}
+
+/// Pages through items with offset cursors. Only forward pagination is supported.
+fn paginate<T: OutputType>(
+    items: Vec<T>,
+    after: Option<String>,
+    first: Option<i32>,
+) -> Result<Connection<usize, T>> {
+    let start = match after {
+        Some(cursor) => usize::decode_cursor(&cursor)? + 1,
+        None => 0,
+    };
+    let first = first.map_or(DEFAULT_PAGE_SIZE, |first| {
+        usize::try_from(first)
+            .unwrap_or_default()
+            .min(MAX_PAGE_SIZE)
+    });
+    let end = start.saturating_add(first).min(items.len());
+
+    let mut connection = Connection::new(start > 0, end < items.len());
+    connection.edges.extend(
+        items
+            .into_iter()
+            .enumerate()
+            .skip(start)
+            .take(end.saturating_sub(start))
+            .map(|(index, item)| Edge::new(index, item)),
+    );
+
+    Ok(connection)
+}
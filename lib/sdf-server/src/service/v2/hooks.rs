@@ -0,0 +1,96 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Router,
+};
+use dal::{
+    action::{prototype::ActionPrototypeError, ActionError},
+    management::{prototype::ManagementPrototypeError, ManagementError},
+    workspace_hooks::{WorkspaceHookId, WorkspaceHooksError},
+    ChangeSetError, ComponentError, TransactionsError,
+};
+use thiserror::Error;
+
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
+
+pub mod create_hook;
+pub mod delete_hook;
+pub mod list_hooks;
+pub mod trigger_hook;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum HooksError {
+    #[error("action error: {0}")]
+    Action(#[from] ActionError),
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] ActionPrototypeError),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("hook with id {0} not found")]
+    HookNotFound(WorkspaceHookId),
+    #[error("invalid hook payload: {0}")]
+    InvalidPayload(serde_json::Error),
+    #[error("invalid hook signature")]
+    InvalidSignature,
+    #[error("hook timestamp is invalid or too far from the current time")]
+    InvalidTimestamp,
+    #[error("management error: {0}")]
+    Management(#[from] ManagementError),
+    #[error("management prototype error: {0}")]
+    ManagementPrototype(#[from] ManagementPrototypeError),
+    #[error("missing hook signature")]
+    MissingSignature,
+    #[error("missing hook timestamp")]
+    MissingTimestamp,
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace hooks error: {0}")]
+    WorkspaceHooks(#[from] WorkspaceHooksError),
+}
+
+pub type HooksResult<T> = Result<T, HooksError>;
+
+impl IntoResponse for HooksError {
+    fn into_response(self) -> Response {
+        let status_code = match self {
+            HooksError::HookNotFound(_) => StatusCode::NOT_FOUND,
+            HooksError::InvalidPayload(_) => StatusCode::BAD_REQUEST,
+            HooksError::InvalidSignature
+            | HooksError::InvalidTimestamp
+            | HooksError::MissingSignature
+            | HooksError::MissingTimestamp => StatusCode::UNAUTHORIZED,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+/// Hooks can run management functions and actions as the system, so managing them is limited to
+/// users who can manage the workspace. Triggering a hook is authenticated by its signature instead.
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_hooks::list_hooks)
+                .post(create_hook::create_hook)
+                .layer(WorkspacePermissionLayer::new(
+                    state.clone(),
+                    permissions::Permission::Manage,
+                )),
+        )
+        .route("/:hook_id", post(trigger_hook::trigger_hook))
+        .route(
+            "/:hook_id",
+            delete(delete_hook::delete_hook).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Manage,
+            )),
+        )
+}
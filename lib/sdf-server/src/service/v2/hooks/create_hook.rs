@@ -0,0 +1,37 @@
+use axum::{extract::Path, Json};
+use dal::workspace_hooks::{WorkspaceHook, WorkspaceHookTarget};
+use dal::WorkspacePk;
+use serde::{Deserialize, Serialize};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::HooksResult;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateHookRequest {
+    pub target: WorkspaceHookTarget,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateHookResponse {
+    pub hook: WorkspaceHook,
+    /// The secret used to sign payloads for the hook. This is only ever returned on creation.
+    pub secret: String,
+}
+
+pub async fn create_hook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<CreateHookRequest>,
+) -> HooksResult<Json<CreateHookResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let (hook, secret) = WorkspaceHook::new(&ctx, request.target).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateHookResponse { hook, secret }))
+}
@@ -0,0 +1,24 @@
+use axum::extract::Path;
+use dal::workspace_hooks::{WorkspaceHook, WorkspaceHookId};
+use dal::WorkspacePk;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{HooksError, HooksResult};
+
+pub async fn delete_hook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, hook_id)): Path<(WorkspacePk, WorkspaceHookId)>,
+) -> HooksResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let hook = WorkspaceHook::get_by_pk(&ctx, ctx.workspace_pk()?, hook_id)
+        .await?
+        .ok_or(HooksError::HookNotFound(hook_id))?;
+    hook.delete(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use axum::{extract::Path, Json};
+use dal::workspace_hooks::WorkspaceHook;
+use dal::WorkspacePk;
+use serde::Serialize;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::HooksResult;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListHooksResponse {
+    pub hooks: Vec<WorkspaceHook>,
+}
+
+pub async fn list_hooks(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> HooksResult<Json<ListHooksResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let hooks = WorkspaceHook::list_for_workspace(&ctx).await?;
+
+    Ok(Json(ListHooksResponse { hooks }))
+}
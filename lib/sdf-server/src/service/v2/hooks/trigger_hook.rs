@@ -0,0 +1,231 @@
+use axum::{body::Bytes, extract::Path, http::HeaderMap, Json};
+use chrono::Utc;
+use dal::{
+    action::{
+        prototype::{ActionKind, ActionPrototype},
+        Action,
+    },
+    management::{prototype::ManagementPrototype, ManagementFuncReturn, ManagementOperator},
+    workspace_hooks::{WorkspaceHook, WorkspaceHookId, WorkspaceHookTarget},
+    AccessBuilder, ChangeSet, ChangeSetId, Component, ComponentId, DalContext, HistoryActor,
+    Tenancy, WorkspacePk,
+};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+use veritech_client::ManagementFuncStatus;
+
+use crate::extract::HandlerContext;
+
+use super::{HooksError, HooksResult};
+
+/// The header containing the signature of the request, formatted as `sha256=<hex digest>`. The
+/// digest is the HMAC-SHA256 of `"{timestamp}.{body}"`, with the value of [`TIMESTAMP_HEADER`] and
+/// the raw request body, keyed by the secret of the hook.
+const SIGNATURE_HEADER: &str = "x-si-signature-256";
+const SIGNATURE_PREFIX: &str = "sha256=";
+/// The header containing the time the request was signed at, in seconds since the unix epoch.
+const TIMESTAMP_HEADER: &str = "x-si-timestamp";
+/// How far the signing time may be from now, so that captured requests cannot be replayed later.
+const TIMESTAMP_TOLERANCE_SECS: u64 = 5 * 60;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerHookResponse {
+    /// The change set the hook ran in. Management functions always run in a new change set.
+    pub change_set_id: ChangeSetId,
+    pub status: Option<ManagementFuncStatus>,
+    pub message: Option<String>,
+}
+
+/// Triggers a [`WorkspaceHook`]. This endpoint is unauthenticated: callers instead prove that
+/// they know the secret of the hook by signing the request body and a recent timestamp with it.
+pub async fn trigger_hook(
+    HandlerContext(builder): HandlerContext,
+    Path((workspace_pk, hook_id)): Path<(WorkspacePk, WorkspaceHookId)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> HooksResult<Json<TriggerHookResponse>> {
+    let mut ctx = builder
+        .build_head(AccessBuilder::new(
+            Tenancy::new(workspace_pk),
+            HistoryActor::SystemInit,
+        ))
+        .await?;
+
+    let hook = WorkspaceHook::get_by_pk(&ctx, workspace_pk, hook_id)
+        .await?
+        .ok_or(HooksError::HookNotFound(hook_id))?;
+
+    verify_signature(&hook.secret(&ctx)?, &headers, &body, Utc::now().timestamp())?;
+
+    let payload: Option<Value> = if body.is_empty() {
+        None
+    } else {
+        Some(serde_json::from_slice(&body).map_err(HooksError::InvalidPayload)?)
+    };
+
+    info!(
+        si.workspace.id = %workspace_pk,
+        si.workspace_hook.id = %hook_id,
+        "triggering workspace hook",
+    );
+
+    let response = match hook.target() {
+        WorkspaceHookTarget::ManagementPrototype {
+            prototype_id,
+            component_id,
+            view_id,
+        } => {
+            let change_set = ChangeSet::fork_head(&ctx, format!("Hook {hook_id}")).await?;
+            ctx.update_visibility_and_snapshot_to_visibility(change_set.id)
+                .await?;
+
+            let mut execution_result = ManagementPrototype::execute_by_id_with_payload(
+                &ctx,
+                *prototype_id,
+                *component_id,
+                *view_id,
+                payload,
+            )
+            .await?;
+
+            let (status, message) = match execution_result.result.take() {
+                Some(result) => {
+                    let result: ManagementFuncReturn = result.try_into()?;
+                    if result.status == ManagementFuncStatus::Ok {
+                        if let Some(operations) = result.operations {
+                            ManagementOperator::new(
+                                &ctx,
+                                *component_id,
+                                operations,
+                                execution_result,
+                                *view_id,
+                            )
+                            .await?
+                            .operate()
+                            .await?;
+                        }
+                    }
+                    (Some(result.status), result.message)
+                }
+                None => (None, None),
+            };
+
+            TriggerHookResponse {
+                change_set_id: change_set.id,
+                status,
+                message,
+            }
+        }
+        WorkspaceHookTarget::Refresh { component_ids } => {
+            enqueue_refresh(&ctx, component_ids).await?;
+
+            TriggerHookResponse {
+                change_set_id: ctx.change_set_id(),
+                status: None,
+                message: None,
+            }
+        }
+    };
+
+    ctx.commit().await?;
+
+    Ok(Json(response))
+}
+
+fn verify_signature(secret: &str, headers: &HeaderMap, body: &[u8], now: i64) -> HooksResult<()> {
+    let timestamp = headers
+        .get(TIMESTAMP_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(HooksError::MissingTimestamp)?;
+    let signed_at: i64 = timestamp
+        .parse()
+        .map_err(|_| HooksError::InvalidTimestamp)?;
+    if now.abs_diff(signed_at) > TIMESTAMP_TOLERANCE_SECS {
+        return Err(HooksError::InvalidTimestamp);
+    }
+
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(HooksError::MissingSignature)?;
+    let signature = signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(|digest| hex::decode(digest).ok())
+        .ok_or(HooksError::InvalidSignature)?;
+
+    let mut signed = Vec::with_capacity(timestamp.len() + 1 + body.len());
+    signed.extend_from_slice(timestamp.as_bytes());
+    signed.push(b'.');
+    signed.extend_from_slice(body);
+
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::verify(&key, &signed, &signature).map_err(|_| HooksError::InvalidSignature)
+}
+
+async fn enqueue_refresh(ctx: &DalContext, component_ids: &[ComponentId]) -> HooksResult<()> {
+    for &component_id in component_ids {
+        let variant = Component::schema_variant_for_component_id(ctx, component_id).await?;
+
+        for prototype in ActionPrototype::for_variant(ctx, variant.id()).await? {
+            if prototype.kind == ActionKind::Refresh {
+                Action::new(ctx, prototype.id(), Some(component_id)).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn signed_headers(secret: &str, timestamp: &str, body: &[u8]) -> HeaderMap {
+        let mut signed = timestamp.as_bytes().to_vec();
+        signed.push(b'.');
+        signed.extend_from_slice(body);
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+        let signature = hex::encode(hmac::sign(&key, &signed).as_ref());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TIMESTAMP_HEADER,
+            HeaderValue::from_str(timestamp).expect("invalid timestamp header"),
+        );
+        headers.insert(
+            SIGNATURE_HEADER,
+            HeaderValue::from_str(&format!("{SIGNATURE_PREFIX}{signature}"))
+                .expect("invalid signature header"),
+        );
+        headers
+    }
+
+    #[test]
+    fn accepts_signatures_within_the_tolerance() {
+        let now = 1_700_000_000;
+        let headers = signed_headers("secret", &(now - 60).to_string(), b"{}");
+
+        assert!(verify_signature("secret", &headers, b"{}", now).is_ok());
+        assert!(matches!(
+            verify_signature("other", &headers, b"{}", now),
+            Err(HooksError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn rejects_timestamps_outside_the_tolerance() {
+        let now = 1_700_000_000;
+        for timestamp in [now - 600, now + 600, i64::MIN, i64::MIN + 1, i64::MAX] {
+            let headers = signed_headers("secret", &timestamp.to_string(), b"{}");
+            assert!(matches!(
+                verify_signature("secret", &headers, b"{}", now),
+                Err(HooksError::InvalidTimestamp)
+            ));
+        }
+    }
+}
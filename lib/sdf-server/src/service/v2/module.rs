@@ -14,6 +14,7 @@ use crate::{service::ApiError, AppState};
 
 mod builtins;
 mod contribute;
+mod contribute_delta;
 mod list;
 mod module_by_hash;
 mod module_by_id;
@@ -59,6 +60,11 @@ impl IntoResponse for ModulesAPIError {
                 StatusCode::NOT_FOUND
             }
             Self::Module(dal::module::ModuleError::EmptyMetadata(_, _)) => StatusCode::BAD_REQUEST,
+            Self::Module(dal::module::ModuleError::EmptyDelta(_)) => StatusCode::BAD_REQUEST,
+            Self::Module(dal::module::ModuleError::NotDerivedFromModule(_)) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Self::Module(dal::module::ModuleError::HashMismatch(_, _)) => StatusCode::CONFLICT,
             Self::ContributionFailure(_) => StatusCode::BAD_REQUEST,
             Self::ModuleHashNotFound(_) => StatusCode::NOT_FOUND,
             _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
@@ -71,6 +77,10 @@ impl IntoResponse for ModulesAPIError {
 pub fn v2_routes() -> Router<AppState> {
     Router::new()
         .route("/contribute", post(contribute::contribute))
+        .route(
+            "/contribute_delta",
+            post(contribute_delta::contribute_delta),
+        )
         .route("/sync", get(sync::sync))
         .route("/", get(list::list))
         .route("/:module_id/builtins/reject", post(builtins::reject))
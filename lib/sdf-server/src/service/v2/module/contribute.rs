@@ -3,8 +3,11 @@ use axum::{
     response::IntoResponse,
     Json,
 };
-use dal::{module::Module, ChangeSetId, WorkspacePk};
-use module_index_client::ModuleIndexClient;
+use dal::{
+    module::{Module, ModulePublication},
+    ChangeSetId, WorkspacePk,
+};
+use module_index_client::{ModuleChannel, ModuleIndexClient};
 use si_events::audit_log::AuditLogKind;
 use si_frontend_types as frontend_types;
 
@@ -36,34 +39,26 @@ pub async fn contribute(
     };
     let index_client = ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token);
 
-    let (
+    let publication = Module::publish(
+        &ctx,
+        &index_client,
+        request.name.as_str(),
+        request.version.as_str(),
+        request.schema_variant_id,
+        ModuleChannel::Stable,
+    )
+    .await?;
+    let ModulePublication {
         name,
         version,
         based_on_hash,
         schema_id,
-        payload,
         created_by_name,
         created_by_email,
         schema_variant_version,
-    ) = Module::prepare_contribution(
-        &ctx,
-        request.name.as_str(),
-        request.version.as_str(),
-        request.schema_variant_id,
-    )
-    .await?;
-
-    let response = index_client
-        .upload_module(
-            name.as_str(),
-            version.as_str(),
-            based_on_hash.clone(),
-            schema_id.map(|id| id.to_string()),
-            payload,
-            Some(request.schema_variant_id.to_string()),
-            Some(schema_variant_version.clone()),
-        )
-        .await?;
+        channel,
+        details,
+    } = publication;
 
     ctx.write_audit_log(
         AuditLogKind::ContributeModule {
@@ -90,7 +85,8 @@ pub async fn contribute(
             "pkg_created_by_email": created_by_email,
             "schema_variant_id": request.schema_variant_id,
             "schema_id": schema_id,
-            "pkg_hash": response.latest_hash,
+            "pkg_hash": details.latest_hash,
+            "channel": channel,
         }),
     );
     ctx.commit().await?;
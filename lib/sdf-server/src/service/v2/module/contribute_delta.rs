@@ -0,0 +1,159 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{
+    module::{Module, ModuleError},
+    ChangeSetId, WorkspacePk,
+};
+use module_index_client::ModuleIndexClient;
+use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
+use si_frontend_types as frontend_types;
+use si_pkg::SiPkg;
+use ulid::Ulid;
+
+use super::{ModuleAPIResult, ModulesAPIError};
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient, RawAccessToken},
+    track,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributeDeltaResponse {
+    pub name: String,
+    pub version: String,
+    pub based_on_hash: String,
+    pub based_on_name: String,
+    pub based_on_version: String,
+    pub funcs: Vec<String>,
+    pub props: Vec<String>,
+    pub sockets: Vec<String>,
+    pub pkg_hash: String,
+}
+
+/// Contributes only what was added to a schema variant on top of the module it was installed
+/// from, so that the change can be reviewed against its upstream module.
+#[allow(clippy::too_many_arguments)]
+pub async fn contribute_delta(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Json(request): Json<frontend_types::ModuleContributeRequest>,
+) -> ModuleAPIResult<Json<ContributeDeltaResponse>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let module_index_url = match ctx.module_index_url() {
+        Some(url) => url,
+        None => return Err(ModulesAPIError::ModuleIndexNotConfigured),
+    };
+    let index_client = ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token);
+
+    // Fetch the package the schema variant was installed from, which is what the delta is
+    // computed against.
+    let base_module = Module::find_for_schema_variant_id(&ctx, request.schema_variant_id)
+        .await?
+        .ok_or(ModuleError::NotDerivedFromModule(request.schema_variant_id))?;
+    let base_module_id = find_module_id_for_hash(&index_client, base_module.root_hash()).await?;
+    let base_pkg = SiPkg::load_from_bytes(&index_client.download_module(base_module_id).await?)?;
+
+    let contribution = Module::prepare_delta_contribution(
+        &ctx,
+        request.name.as_str(),
+        request.version.as_str(),
+        request.schema_variant_id,
+        &base_pkg,
+    )
+    .await?;
+
+    let response = index_client
+        .upload_module(
+            contribution.name.as_str(),
+            contribution.version.as_str(),
+            Some(contribution.based_on_hash.clone()),
+            contribution.schema_id.map(|id| id.to_string()),
+            contribution.payload,
+            Some(request.schema_variant_id.to_string()),
+            Some(contribution.schema_variant_version.clone()),
+        )
+        .await?;
+
+    ctx.write_audit_log(
+        AuditLogKind::ContributeModule {
+            version: contribution.version.clone(),
+            schema_id: contribution.schema_id.map(Into::into),
+            schema_variant_id: request.schema_variant_id.into(),
+            schema_variant_version: Some(contribution.schema_variant_version.clone()),
+        },
+        contribution.name.clone(),
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "export_module_delta",
+        serde_json::json!({
+            "pkg_name": contribution.name,
+            "pkg_version": contribution.version,
+            "based_on_hash": contribution.based_on_hash,
+            "pkg_created_by_name": contribution.created_by_name,
+            "pkg_created_by_email": contribution.created_by_email,
+            "schema_variant_id": request.schema_variant_id,
+            "schema_id": contribution.schema_id,
+            "pkg_hash": response.latest_hash,
+            "func_count": contribution.funcs.len(),
+            "prop_count": contribution.props.len(),
+            "socket_count": contribution.sockets.len(),
+        }),
+    );
+    ctx.commit().await?;
+
+    Ok(Json(ContributeDeltaResponse {
+        name: contribution.name,
+        version: contribution.version,
+        based_on_hash: contribution.based_on_hash,
+        based_on_name: contribution.based_on_name,
+        based_on_version: contribution.based_on_version,
+        funcs: contribution.funcs,
+        props: contribution.props,
+        sockets: contribution.sockets,
+        pkg_hash: response.latest_hash,
+    }))
+}
+
+/// Installed modules are usually promoted builtins, which the module listing skips, so the latest
+/// promoted modules are checked first.
+async fn find_module_id_for_hash(
+    index_client: &ModuleIndexClient,
+    hash: &str,
+) -> ModuleAPIResult<Ulid> {
+    let maybe_id = match index_client
+        .list_latest_modules()
+        .await?
+        .modules
+        .into_iter()
+        .find(|module| module.latest_hash == hash)
+    {
+        Some(module) => Some(module.id),
+        None => index_client
+            .list_module_details()
+            .await?
+            .modules
+            .into_iter()
+            .find(|module| module.latest_hash == hash)
+            .map(|module| module.id),
+    };
+
+    let id = maybe_id.ok_or_else(|| ModulesAPIError::ModuleHashNotFound(hash.to_owned()))?;
+    Ulid::from_string(&id).map_err(|_| ModulesAPIError::ModuleHashNotFound(hash.to_owned()))
+}
@@ -0,0 +1,86 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Router,
+};
+use dal::{
+    provider_credential::{
+        ProviderCredentialConsentId, ProviderCredentialError, ProviderCredentialId,
+    },
+    FuncError, FuncId, TransactionsError,
+};
+use thiserror::Error;
+
+use crate::{service::ApiError, AppState};
+
+pub mod create_credential;
+pub mod grant_consent;
+pub mod list_consents;
+pub mod list_credentials;
+pub mod revoke_consent;
+pub mod revoke_credential;
+pub mod rotate_credential;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ProviderCredentialsError {
+    #[error("consent with id {0} not found")]
+    ConsentNotFound(ProviderCredentialConsentId),
+    #[error("provider credential with id {0} not found")]
+    CredentialNotFound(ProviderCredentialId),
+    #[error("func error: {0}")]
+    Func(#[from] FuncError),
+    #[error("func with id {0} not found")]
+    FuncNotFound(FuncId),
+    #[error("provider credential error: {0}")]
+    ProviderCredential(#[from] ProviderCredentialError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ProviderCredentialsResult<T> = Result<T, ProviderCredentialsError>;
+
+impl IntoResponse for ProviderCredentialsError {
+    fn into_response(self) -> Response {
+        let status_code = match self {
+            ProviderCredentialsError::ConsentNotFound(_)
+            | ProviderCredentialsError::CredentialNotFound(_)
+            | ProviderCredentialsError::FuncNotFound(_) => StatusCode::NOT_FOUND,
+            ProviderCredentialsError::ProviderCredential(
+                ProviderCredentialError::ExpiryInPast
+                | ProviderCredentialError::ExpiryRequired(_)
+                | ProviderCredentialError::InvalidTtl,
+            ) => StatusCode::BAD_REQUEST,
+            ProviderCredentialsError::ProviderCredential(ProviderCredentialError::Revoked(_)) => {
+                StatusCode::CONFLICT
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_credentials::list_credentials))
+        .route("/", post(create_credential::create_credential))
+        .route("/:credential_id", put(rotate_credential::rotate_credential))
+        .route(
+            "/:credential_id",
+            delete(revoke_credential::revoke_credential),
+        )
+        .route(
+            "/:credential_id/consents",
+            get(list_consents::list_consents),
+        )
+        .route(
+            "/:credential_id/consents",
+            post(grant_consent::grant_consent),
+        )
+        .route(
+            "/:credential_id/consents/:consent_id",
+            delete(revoke_consent::revoke_consent),
+        )
+}
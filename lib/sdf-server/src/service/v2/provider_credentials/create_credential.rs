@@ -0,0 +1,38 @@
+use axum::{extract::Path, Json};
+use chrono::{DateTime, Utc};
+use dal::{
+    provider_credential::{ProviderCredential, ProviderCredentialMaterial},
+    WorkspacePk,
+};
+use serde::Deserialize;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::ProviderCredentialsResult;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCredentialRequest {
+    pub name: String,
+    pub material: ProviderCredentialMaterial,
+    /// When the material stops being valid at the provider. Required for material which
+    /// short-lived credentials can't be minted from.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Stores a provider credential in the vault of the workspace. The material is never returned.
+pub async fn create_credential(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<CreateCredentialRequest>,
+) -> ProviderCredentialsResult<Json<ProviderCredential>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let credential =
+        ProviderCredential::new(&ctx, request.name, &request.material, request.expires_at).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(credential))
+}
@@ -0,0 +1,43 @@
+use axum::{extract::Path, Json};
+use dal::{
+    provider_credential::{ProviderCredential, ProviderCredentialConsent, ProviderCredentialId},
+    ChangeSetId, Func, FuncId, WorkspacePk,
+};
+use serde::Deserialize;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{ProviderCredentialsError, ProviderCredentialsResult};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantConsentRequest {
+    /// The change set to read the code of the func from.
+    pub change_set_id: ChangeSetId,
+    pub func_id: FuncId,
+}
+
+/// Grants the code of a func, as it is in the change set, the use of a provider credential. Editing
+/// the code of the func voids the consent.
+pub async fn grant_consent(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, credential_id)): Path<(WorkspacePk, ProviderCredentialId)>,
+    Json(request): Json<GrantConsentRequest>,
+) -> ProviderCredentialsResult<Json<ProviderCredentialConsent>> {
+    let ctx = builder
+        .build(access_builder.build(request.change_set_id.into()))
+        .await?;
+
+    let credential = ProviderCredential::get_by_id(&ctx, ctx.workspace_pk()?, credential_id)
+        .await?
+        .ok_or(ProviderCredentialsError::CredentialNotFound(credential_id))?;
+    let func = Func::get_by_id(&ctx, request.func_id)
+        .await?
+        .ok_or(ProviderCredentialsError::FuncNotFound(request.func_id))?;
+    let consent = ProviderCredentialConsent::grant(&ctx, &credential, &func).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(consent))
+}
@@ -0,0 +1,31 @@
+use axum::{extract::Path, Json};
+use dal::{
+    provider_credential::{ProviderCredential, ProviderCredentialConsent, ProviderCredentialId},
+    WorkspacePk,
+};
+use serde::Serialize;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{ProviderCredentialsError, ProviderCredentialsResult};
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListConsentsResponse {
+    pub consents: Vec<ProviderCredentialConsent>,
+}
+
+pub async fn list_consents(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, credential_id)): Path<(WorkspacePk, ProviderCredentialId)>,
+) -> ProviderCredentialsResult<Json<ListConsentsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    ProviderCredential::get_by_id(&ctx, ctx.workspace_pk()?, credential_id)
+        .await?
+        .ok_or(ProviderCredentialsError::CredentialNotFound(credential_id))?;
+    let consents = ProviderCredentialConsent::list_for_credential(&ctx, credential_id).await?;
+
+    Ok(Json(ListConsentsResponse { consents }))
+}
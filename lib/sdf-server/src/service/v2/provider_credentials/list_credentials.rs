@@ -0,0 +1,25 @@
+use axum::{extract::Path, Json};
+use dal::{provider_credential::ProviderCredential, WorkspacePk};
+use serde::Serialize;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::ProviderCredentialsResult;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCredentialsResponse {
+    pub credentials: Vec<ProviderCredential>,
+}
+
+pub async fn list_credentials(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> ProviderCredentialsResult<Json<ListCredentialsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let credentials = ProviderCredential::list_for_workspace(&ctx).await?;
+
+    Ok(Json(ListCredentialsResponse { credentials }))
+}
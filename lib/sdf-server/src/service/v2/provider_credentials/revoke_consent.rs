@@ -0,0 +1,37 @@
+use axum::{extract::Path, Json};
+use dal::{
+    provider_credential::{
+        ProviderCredential, ProviderCredentialConsent, ProviderCredentialConsentId,
+        ProviderCredentialId,
+    },
+    WorkspacePk,
+};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{ProviderCredentialsError, ProviderCredentialsResult};
+
+pub async fn revoke_consent(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, credential_id, consent_id)): Path<(
+        WorkspacePk,
+        ProviderCredentialId,
+        ProviderCredentialConsentId,
+    )>,
+) -> ProviderCredentialsResult<Json<ProviderCredentialConsent>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    ProviderCredential::get_by_id(&ctx, ctx.workspace_pk()?, credential_id)
+        .await?
+        .ok_or(ProviderCredentialsError::CredentialNotFound(credential_id))?;
+    let consent = ProviderCredentialConsent::get_by_id(&ctx, credential_id, consent_id)
+        .await?
+        .ok_or(ProviderCredentialsError::ConsentNotFound(consent_id))?
+        .revoke(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(consent))
+}
@@ -0,0 +1,27 @@
+use axum::{extract::Path, Json};
+use dal::{
+    provider_credential::{ProviderCredential, ProviderCredentialId},
+    WorkspacePk,
+};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{ProviderCredentialsError, ProviderCredentialsResult};
+
+pub async fn revoke_credential(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, credential_id)): Path<(WorkspacePk, ProviderCredentialId)>,
+) -> ProviderCredentialsResult<Json<ProviderCredential>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let credential = ProviderCredential::get_by_id(&ctx, ctx.workspace_pk()?, credential_id)
+        .await?
+        .ok_or(ProviderCredentialsError::CredentialNotFound(credential_id))?
+        .revoke(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(credential))
+}
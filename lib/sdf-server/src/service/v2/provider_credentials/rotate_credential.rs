@@ -0,0 +1,37 @@
+use axum::{extract::Path, Json};
+use chrono::{DateTime, Utc};
+use dal::{
+    provider_credential::{ProviderCredential, ProviderCredentialId, ProviderCredentialMaterial},
+    WorkspacePk,
+};
+use serde::Deserialize;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{ProviderCredentialsError, ProviderCredentialsResult};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateCredentialRequest {
+    pub material: ProviderCredentialMaterial,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub async fn rotate_credential(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, credential_id)): Path<(WorkspacePk, ProviderCredentialId)>,
+    Json(request): Json<RotateCredentialRequest>,
+) -> ProviderCredentialsResult<Json<ProviderCredential>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let credential = ProviderCredential::get_by_id(&ctx, ctx.workspace_pk()?, credential_id)
+        .await?
+        .ok_or(ProviderCredentialsError::CredentialNotFound(credential_id))?
+        .rotate(&ctx, &request.material, request.expires_at)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(credential))
+}
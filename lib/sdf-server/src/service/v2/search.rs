@@ -0,0 +1,68 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::str::FromStr;
+
+use dal::{
+    search::{SearchError as DalSearchError, SearchResultKind},
+    TransactionsError,
+};
+use thiserror::Error;
+
+use crate::{service::ApiError, AppState};
+
+pub mod search_change_set;
+pub mod search_workspace;
+
+const DEFAULT_LIMIT: usize = 25;
+const MAX_LIMIT: usize = 100;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SearchError {
+    #[error("dal search error: {0}")]
+    DalSearch(#[from] DalSearchError),
+    #[error("search query is empty")]
+    EmptyQuery,
+    #[error("invalid search result kind: {0}")]
+    InvalidKind(String),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SearchResult<T> = Result<T, SearchError>;
+
+impl IntoResponse for SearchError {
+    fn into_response(self) -> Response {
+        let status_code = match self {
+            SearchError::EmptyQuery | SearchError::InvalidKind(_) => StatusCode::BAD_REQUEST,
+            _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new().route("/", get(search_workspace::search_workspace))
+}
+
+pub fn v2_change_set_routes() -> Router<AppState> {
+    Router::new().route("/", get(search_change_set::search_change_set))
+}
+
+/// Parses a comma separated list of kinds, e.g. `component,attributeValue`.
+fn parse_kinds(kinds: Option<&str>) -> SearchResult<Vec<SearchResultKind>> {
+    kinds
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|kind| !kind.is_empty())
+        .map(|kind| {
+            SearchResultKind::from_str(kind).map_err(|_| SearchError::InvalidKind(kind.to_owned()))
+        })
+        .collect()
+}
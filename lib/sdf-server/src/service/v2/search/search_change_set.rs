@@ -0,0 +1,36 @@
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
+use dal::{search, ChangeSetId, WorkspacePk};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{
+    parse_kinds,
+    search_workspace::{SearchWorkspaceRequest, SearchWorkspaceResponse},
+    SearchError, SearchResult, DEFAULT_LIMIT, MAX_LIMIT,
+};
+
+/// Like [`search_workspace`](super::search_workspace::search_workspace), over a change set
+/// instead of HEAD. This is what the command palette uses.
+pub async fn search_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(request): Query<SearchWorkspaceRequest>,
+) -> SearchResult<Json<SearchWorkspaceResponse>> {
+    if request.q.trim().is_empty() {
+        return Err(SearchError::EmptyQuery);
+    }
+    let kinds = parse_kinds(request.kinds.as_deref())?;
+
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let results = search::search(&ctx, &request.q, &kinds, limit).await?;
+
+    Ok(Json(SearchWorkspaceResponse { results }))
+}
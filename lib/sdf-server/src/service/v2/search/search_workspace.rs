@@ -0,0 +1,50 @@
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
+use dal::{
+    search::{self, SearchHit},
+    WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{parse_kinds, SearchError, SearchResult, DEFAULT_LIMIT, MAX_LIMIT};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchWorkspaceRequest {
+    pub q: String,
+    /// Comma separated kinds of results to return. Defaults to every kind.
+    pub kinds: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchWorkspaceResponse {
+    pub results: Vec<SearchHit>,
+}
+
+/// Fuzzy search across the schemas, components, funcs, props and values on HEAD, best matches
+/// first.
+pub async fn search_workspace(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Query(request): Query<SearchWorkspaceRequest>,
+) -> SearchResult<Json<SearchWorkspaceResponse>> {
+    if request.q.trim().is_empty() {
+        return Err(SearchError::EmptyQuery);
+    }
+
+    let kinds = parse_kinds(request.kinds.as_deref())?;
+
+    let ctx = builder.build_head(access_builder).await?;
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let results = search::search(&ctx, &request.q, &kinds, limit).await?;
+
+    Ok(Json(SearchWorkspaceResponse { results }))
+}
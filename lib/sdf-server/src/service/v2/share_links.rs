@@ -0,0 +1,124 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Router,
+};
+use dal::{
+    diagram::DiagramError,
+    share_link::{ShareLink, ShareLinkError, ShareLinkId},
+    TransactionsError,
+};
+use thiserror::Error;
+
+use crate::{
+    middleware::WorkspacePermissionLayer,
+    service::{v2::view::ViewError, ApiError},
+    AppState,
+};
+
+pub mod create_share_link;
+pub mod get_shared_diagram;
+pub mod list_share_links;
+pub mod revoke_share_link;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ShareLinksError {
+    #[error("diagram error: {0}")]
+    Diagram(#[from] DiagramError),
+    #[error("share link expiry must be in the future")]
+    ExpiryInPast,
+    #[error("share links can be valid for at most {0} days")]
+    ExpiryTooFar(i64),
+    #[error("invalid share link token")]
+    InvalidToken,
+    #[error("share link error: {0}")]
+    ShareLink(#[from] ShareLinkError),
+    #[error("share link {0} has expired")]
+    ShareLinkExpired(ShareLinkId),
+    #[error("share link with id {0} not found")]
+    ShareLinkNotFound(ShareLinkId),
+    #[error("share link {0} has been revoked")]
+    ShareLinkRevoked(ShareLinkId),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("view error: {0}")]
+    View(#[from] ViewError),
+}
+
+pub type ShareLinksResult<T> = Result<T, ShareLinksError>;
+
+impl IntoResponse for ShareLinksError {
+    fn into_response(self) -> Response {
+        let status_code = match self {
+            ShareLinksError::ExpiryInPast | ShareLinksError::ExpiryTooFar(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            ShareLinksError::InvalidToken => StatusCode::UNAUTHORIZED,
+            ShareLinksError::ShareLinkExpired(_) | ShareLinksError::ShareLinkRevoked(_) => {
+                StatusCode::GONE
+            }
+            ShareLinksError::Diagram(DiagramError::ViewNotFound(_))
+            | ShareLinksError::ShareLinkNotFound(_) => StatusCode::NOT_FOUND,
+            ShareLinksError::View(err) => return err.into_response(),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+/// Routes for managing the share links of a workspace, which require permission to manage it.
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_share_links::list_share_links).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Manage,
+            )),
+        )
+        .route(
+            "/",
+            post(create_share_link::create_share_link).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Manage,
+            )),
+        )
+        .route(
+            "/:share_link_id",
+            delete(revoke_share_link::revoke_share_link).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Manage,
+            )),
+        )
+}
+
+/// Routes for anonymous holders of a share link token.
+pub fn v2_shared_routes() -> Router<AppState> {
+    Router::new().route("/:token", get(get_shared_diagram::get_shared_diagram))
+}
+
+/// Tokens are formatted as `<share link id>.<secret>`, where the secret is the one returned when
+/// the link was created.
+fn format_token(share_link: &ShareLink, secret: &str) -> String {
+    format!("{}.{secret}", share_link.id())
+}
+
+/// Splits a token into the id of its share link and its secret, which must be verified with
+/// [`verify_token`] once the link has been found.
+fn parse_token(token: &str) -> ShareLinksResult<(ShareLinkId, &str)> {
+    let (id, secret) = token.split_once('.').ok_or(ShareLinksError::InvalidToken)?;
+    let id = id.parse().map_err(|_| ShareLinksError::InvalidToken)?;
+
+    Ok((id, secret))
+}
+
+fn verify_token(share_link: &ShareLink, secret: &str) -> ShareLinksResult<()> {
+    if share_link.verify_secret(secret) {
+        Ok(())
+    } else {
+        Err(ShareLinksError::InvalidToken)
+    }
+}
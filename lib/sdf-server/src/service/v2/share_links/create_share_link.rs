@@ -0,0 +1,85 @@
+use axum::{extract::Path, Json};
+use chrono::{DateTime, Duration, Utc};
+use dal::{
+    diagram::view::{View, ViewId},
+    share_link::ShareLink,
+    ChangeSetId, WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{format_token, ShareLinksError, ShareLinksResult};
+
+/// How long a share link can be valid for at most.
+const MAX_SHARE_LINK_DAYS: i64 = 30;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShareLinkRequest {
+    pub change_set_id: ChangeSetId,
+    /// The view to share. Defaults to the default view of the change set.
+    pub view_id: Option<ViewId>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShareLinkResponse {
+    pub share_link: ShareLink,
+    /// The token granting access to the shared diagram. This is only ever returned on
+    /// creation.
+    pub token: String,
+}
+
+/// Creates a link which gives anyone holding its token read-only access to the diagram of a
+/// change set, without being a member of the workspace.
+pub async fn create_share_link(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<CreateShareLinkRequest>,
+) -> ShareLinksResult<Json<CreateShareLinkResponse>> {
+    let ctx = builder
+        .build(access_builder.build(request.change_set_id.into()))
+        .await?;
+
+    let now = Utc::now();
+    if request.expires_at <= now {
+        return Err(ShareLinksError::ExpiryInPast);
+    }
+    if request.expires_at - now > Duration::days(MAX_SHARE_LINK_DAYS) {
+        return Err(ShareLinksError::ExpiryTooFar(MAX_SHARE_LINK_DAYS));
+    }
+
+    // Fail early for views which do not exist, rather than when the link is used
+    let view_id = match request.view_id {
+        Some(view_id) => view_id,
+        None => View::get_id_for_default(&ctx).await?,
+    };
+    let view = View::get_by_id(&ctx, view_id).await?;
+
+    let (share_link, secret) = ShareLink::new(
+        &ctx,
+        request.change_set_id,
+        request.view_id,
+        request.expires_at,
+    )
+    .await?;
+    let token = format_token(&share_link, &secret);
+
+    ctx.write_audit_log(
+        AuditLogKind::CreateShareLink {
+            share_link_id: share_link.id(),
+            view_id: request.view_id,
+            expires_at: request.expires_at.to_rfc3339(),
+        },
+        view.name().to_owned(),
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateShareLinkResponse { share_link, token }))
+}
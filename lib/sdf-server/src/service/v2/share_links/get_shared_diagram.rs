@@ -0,0 +1,72 @@
+use axum::{extract::Path, Json};
+use dal::{diagram::view::View, share_link::ShareLink, AccessBuilder, HistoryActor, Tenancy};
+use si_events::audit_log::AuditLogKind;
+use telemetry::prelude::*;
+
+use crate::{
+    extract::HandlerContext,
+    service::v2::view::get_diagram::{get_diagram_inner, Response},
+};
+
+use super::{parse_token, verify_token, ShareLinksError, ShareLinksResult};
+
+/// Returns the diagram shared by a share link. This endpoint is unauthenticated: the token of the
+/// link is the only credential, and it only grants read access to the shared view.
+pub async fn get_shared_diagram(
+    HandlerContext(builder): HandlerContext,
+    Path(token): Path<String>,
+) -> ShareLinksResult<Json<Response>> {
+    let (share_link_id, secret) = parse_token(&token)?;
+
+    let share_link = {
+        let ctx = builder.build_default().await?;
+        ShareLink::get_by_id_for_any_workspace(&ctx, share_link_id)
+            .await?
+            .ok_or(ShareLinksError::InvalidToken)?
+    };
+    verify_token(&share_link, secret)?;
+    if share_link.revoked_at().is_some() {
+        return Err(ShareLinksError::ShareLinkRevoked(share_link_id));
+    }
+    if !share_link.is_active() {
+        return Err(ShareLinksError::ShareLinkExpired(share_link_id));
+    }
+
+    let ctx = builder
+        .build(
+            AccessBuilder::new(
+                Tenancy::new(share_link.workspace_pk()),
+                HistoryActor::SystemInit,
+            )
+            .build(share_link.change_set_id().into()),
+        )
+        .await?;
+
+    info!(
+        si.workspace.id = %share_link.workspace_pk(),
+        si.change_set.id = %share_link.change_set_id(),
+        si.share_link.id = %share_link_id,
+        "serving shared diagram",
+    );
+
+    let view_id = match share_link.view_id() {
+        Some(view_id) => view_id,
+        None => View::get_id_for_default(&ctx).await?,
+    };
+    let view = View::get_by_id(&ctx, view_id).await?;
+    let view_name = view.name().to_owned();
+    let response = get_diagram_inner(&ctx, view).await?;
+
+    ctx.write_audit_log(
+        AuditLogKind::ViewSharedDiagram {
+            share_link_id,
+            view_id,
+        },
+        view_name,
+    )
+    .await?;
+    // Nothing else was written, so only the audit log needs to be published
+    ctx.commit_no_rebase().await?;
+
+    Ok(response)
+}
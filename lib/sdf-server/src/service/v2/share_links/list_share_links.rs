@@ -0,0 +1,16 @@
+use axum::{extract::Path, Json};
+use dal::{share_link::ShareLink, WorkspacePk};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::ShareLinksResult;
+
+pub async fn list_share_links(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> ShareLinksResult<Json<Vec<ShareLink>>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    Ok(Json(ShareLink::list_for_workspace(&ctx).await?))
+}
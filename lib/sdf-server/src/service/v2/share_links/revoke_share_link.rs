@@ -0,0 +1,34 @@
+use axum::{extract::Path, Json};
+use dal::{
+    share_link::{ShareLink, ShareLinkId},
+    WorkspacePk,
+};
+use si_events::audit_log::AuditLogKind;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{ShareLinksError, ShareLinksResult};
+
+pub async fn revoke_share_link(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, share_link_id)): Path<(WorkspacePk, ShareLinkId)>,
+) -> ShareLinksResult<Json<ShareLink>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let share_link = ShareLink::get_by_id(&ctx, ctx.workspace_pk()?, share_link_id)
+        .await?
+        .ok_or(ShareLinksError::ShareLinkNotFound(share_link_id))?
+        .revoke(&ctx)
+        .await?;
+
+    ctx.write_audit_log(
+        AuditLogKind::RevokeShareLink { share_link_id },
+        share_link_id.to_string(),
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(share_link))
+}
@@ -0,0 +1,56 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Router,
+};
+use dal::{
+    snapshot_subscription::{SnapshotSubscriptionError, SnapshotSubscriptionId},
+    TransactionsError,
+};
+use thiserror::Error;
+
+use crate::{service::ApiError, AppState};
+
+pub mod create_subscription;
+pub mod delete_subscription;
+pub mod get_projection;
+pub mod list_subscriptions;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SnapshotSubscriptionsError {
+    #[error("snapshot subscription error: {0}")]
+    SnapshotSubscription(#[from] SnapshotSubscriptionError),
+    #[error("snapshot subscription with id {0} not found")]
+    SubscriptionNotFound(SnapshotSubscriptionId),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SnapshotSubscriptionsResult<T> = Result<T, SnapshotSubscriptionsError>;
+
+impl IntoResponse for SnapshotSubscriptionsError {
+    fn into_response(self) -> Response {
+        let status_code = match self {
+            SnapshotSubscriptionsError::SubscriptionNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_subscriptions::list_subscriptions))
+        .route("/", post(create_subscription::create_subscription))
+        .route(
+            "/:subscription_id",
+            delete(delete_subscription::delete_subscription),
+        )
+        .route(
+            "/:subscription_id/projection",
+            get(get_projection::get_projection),
+        )
+}
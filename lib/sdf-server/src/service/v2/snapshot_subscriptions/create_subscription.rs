@@ -0,0 +1,46 @@
+use axum::{extract::Path, Json};
+use dal::{
+    snapshot_subscription::{SnapshotSubscription, SnapshotSubscriptionFilter},
+    WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::SnapshotSubscriptionsResult;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubscriptionRequest {
+    pub name: String,
+    pub filter: SnapshotSubscriptionFilter,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubscriptionResponse {
+    pub subscription: SnapshotSubscription,
+    /// The NATS subject that updates are published to.
+    pub subject: String,
+}
+
+/// Subscribes to a projection of HEAD. Updates are only published from now on; the current
+/// projection can be fetched from the projection endpoint.
+pub async fn create_subscription(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<CreateSubscriptionRequest>,
+) -> SnapshotSubscriptionsResult<Json<CreateSubscriptionResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let subscription = SnapshotSubscription::new(&ctx, request.name, request.filter).await?;
+    let subject = subscription.subject();
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateSubscriptionResponse {
+        subscription,
+        subject,
+    }))
+}
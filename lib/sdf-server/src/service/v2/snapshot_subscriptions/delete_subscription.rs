@@ -0,0 +1,29 @@
+use axum::extract::Path;
+use dal::{
+    snapshot_subscription::{SnapshotSubscription, SnapshotSubscriptionId},
+    WorkspacePk,
+};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{SnapshotSubscriptionsError, SnapshotSubscriptionsResult};
+
+pub async fn delete_subscription(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, subscription_id)): Path<(WorkspacePk, SnapshotSubscriptionId)>,
+) -> SnapshotSubscriptionsResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    SnapshotSubscription::get_by_id(&ctx, ctx.workspace_pk()?, subscription_id)
+        .await?
+        .ok_or(SnapshotSubscriptionsError::SubscriptionNotFound(
+            subscription_id,
+        ))?
+        .delete(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use axum::{extract::Path, Json};
+use dal::{
+    snapshot_subscription::{SnapshotSubscription, SnapshotSubscriptionId},
+    WorkspacePk,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{SnapshotSubscriptionsError, SnapshotSubscriptionsResult};
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetProjectionResponse {
+    /// The sequence number of the latest update the projection includes. Subsequent updates
+    /// apply on top of it.
+    pub sequence: i64,
+    pub projection: Value,
+}
+
+/// Returns the projection of a subscription as of its latest update, for consumers starting up or
+/// recovering from missed updates.
+pub async fn get_projection(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, subscription_id)): Path<(WorkspacePk, SnapshotSubscriptionId)>,
+) -> SnapshotSubscriptionsResult<Json<GetProjectionResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let subscription = SnapshotSubscription::get_by_id(&ctx, ctx.workspace_pk()?, subscription_id)
+        .await?
+        .ok_or(SnapshotSubscriptionsError::SubscriptionNotFound(
+            subscription_id,
+        ))?;
+
+    Ok(Json(GetProjectionResponse {
+        sequence: subscription.sequence(),
+        projection: subscription.projection().clone(),
+    }))
+}
@@ -0,0 +1,16 @@
+use axum::{extract::Path, Json};
+use dal::{snapshot_subscription::SnapshotSubscription, WorkspacePk};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::SnapshotSubscriptionsResult;
+
+pub async fn list_subscriptions(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> SnapshotSubscriptionsResult<Json<Vec<SnapshotSubscription>>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    Ok(Json(SnapshotSubscription::list_for_workspace(&ctx).await?))
+}
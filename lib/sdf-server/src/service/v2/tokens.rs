@@ -0,0 +1,71 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Router,
+};
+use dal::{
+    api_token::{ApiTokenError, ApiTokenId},
+    TransactionsError,
+};
+use si_jwt_public_key::JwtPublicSigningKeyError;
+use thiserror::Error;
+
+use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
+
+pub mod create_token;
+pub mod list_tokens;
+pub mod revoke_token;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum TokensError {
+    #[error("api token error: {0}")]
+    ApiToken(#[from] ApiTokenError),
+    #[error("token expiry must be in the future")]
+    ExpiryInPast,
+    #[error("jwt error: {0}")]
+    Jwt(#[from] JwtPublicSigningKeyError),
+    #[error("token with id {0} not found")]
+    TokenNotFound(ApiTokenId),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type TokensResult<T> = Result<T, TokensError>;
+
+impl IntoResponse for TokensError {
+    fn into_response(self) -> Response {
+        let status_code = match self {
+            TokensError::ExpiryInPast => StatusCode::BAD_REQUEST,
+            TokensError::Jwt(JwtPublicSigningKeyError::NoAutomationSigningKey) => {
+                StatusCode::NOT_IMPLEMENTED
+            }
+            TokensError::TokenNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+/// Any member may create a token acting on their own behalf, but listing and revoking the tokens
+/// of the whole workspace is limited to users who can manage it.
+pub fn v2_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_tokens::list_tokens).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Manage,
+            )),
+        )
+        .route("/", post(create_token::create_token))
+        .route(
+            "/:token_id",
+            delete(revoke_token::revoke_token).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Manage,
+            )),
+        )
+}
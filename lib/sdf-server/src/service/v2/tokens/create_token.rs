@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use dal::{api_token::ApiToken, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use si_jwt_public_key::SiJwtClaims;
+
+use crate::{
+    extract::{AccessBuilder, HandlerContext},
+    AppState,
+};
+
+use super::{TokensError, TokensResult};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTokenRequest {
+    pub name: String,
+    /// When the token stops being valid. Tokens without an expiry are valid until revoked.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTokenResponse {
+    pub token: ApiToken,
+    /// The signed token, for use as a bearer token. This is only ever returned on creation.
+    pub jwt: String,
+}
+
+/// Creates a workspace automation token acting on behalf of the requesting user. The token only
+/// carries the automation role, so it cannot be used for endpoints that require the web role.
+pub async fn create_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    State(state): State<AppState>,
+    Path(workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<CreateTokenRequest>,
+) -> TokensResult<Json<CreateTokenResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let valid_for = match request.expires_at {
+        Some(expires_at) => Some(
+            (expires_at - Utc::now())
+                .to_std()
+                .map_err(|_| TokensError::ExpiryInPast)?,
+        ),
+        None => None,
+    };
+
+    let token = ApiToken::new(&ctx, request.name, request.expires_at).await?;
+    let claims = SiJwtClaims::automation_token(
+        token.created_by_user_pk(),
+        workspace_pk,
+        token.id(),
+        valid_for,
+    );
+    let jwt = state
+        .jwt_public_signing_key_chain()
+        .sign_automation_token(claims)?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateTokenResponse { token, jwt }))
+}
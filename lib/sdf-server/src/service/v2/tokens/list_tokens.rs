@@ -0,0 +1,25 @@
+use axum::{extract::Path, Json};
+use dal::{api_token::ApiToken, WorkspacePk};
+use serde::Serialize;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::TokensResult;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListTokensResponse {
+    pub tokens: Vec<ApiToken>,
+}
+
+pub async fn list_tokens(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> TokensResult<Json<ListTokensResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let tokens = ApiToken::list_for_workspace(&ctx).await?;
+
+    Ok(Json(ListTokensResponse { tokens }))
+}
@@ -0,0 +1,27 @@
+use axum::{extract::Path, Json};
+use dal::{
+    api_token::{ApiToken, ApiTokenId},
+    WorkspacePk,
+};
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{TokensError, TokensResult};
+
+pub async fn revoke_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, token_id)): Path<(WorkspacePk, ApiTokenId)>,
+) -> TokensResult<Json<ApiToken>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let token = ApiToken::get_by_id(&ctx, ctx.workspace_pk()?, token_id)
+        .await?
+        .ok_or(TokensError::TokenNotFound(token_id))?
+        .revoke(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(token))
+}
@@ -1,7 +1,7 @@
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 use dal::{
@@ -17,6 +17,7 @@ pub mod create_unlocked_copy;
 mod delete_unlocked_variant;
 mod get_variant;
 mod list_variants;
+mod update_metadata;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -53,7 +54,9 @@ impl IntoResponse for SchemaVariantsAPIError {
             Self::Transactions(dal::TransactionsError::BadWorkspaceAndChangeSet) => {
                 StatusCode::FORBIDDEN
             }
-            Self::CannotDeleteVariantWithComponents | Self::CannotDeleteLockedSchemaVariant(_) => {
+            Self::CannotDeleteVariantWithComponents
+            | Self::CannotDeleteLockedSchemaVariant(_)
+            | Self::SchemaVariant(dal::SchemaVariantError::SchemaVariantLocked(_)) => {
                 StatusCode::PRECONDITION_FAILED
             }
             // When a graph node cannot be found for a schema variant, it is not found
@@ -77,4 +80,8 @@ pub fn v2_routes() -> Router<AppState> {
             "/:schema_variant_id",
             delete(delete_unlocked_variant::delete_unlocked_variant),
         )
+        .route(
+            "/:schema_variant_id/metadata",
+            put(update_metadata::update_metadata),
+        )
 }
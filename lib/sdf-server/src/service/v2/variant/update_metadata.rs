@@ -0,0 +1,121 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{
+    schema::variant::SchemaVariantMetadataUpdate, ChangeSet, ChangeSetId, SchemaVariant,
+    SchemaVariantId, WorkspacePk, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
+use si_frontend_types as frontend_types;
+
+use super::SchemaVariantsAPIResult;
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    track,
+};
+
+/// Metadata to change on an unlocked variant. Omitted fields are left alone, and `null` clears
+/// the description or link.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMetadataRequest {
+    pub display_name: Option<String>,
+    pub category: Option<String>,
+    pub color: Option<String>,
+    pub component_type: Option<frontend_types::ComponentType>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub description: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub link: Option<Option<String>>,
+}
+
+pub async fn update_metadata(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id, schema_variant_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        SchemaVariantId,
+    )>,
+    Json(request): Json<UpdateMetadataRequest>,
+) -> SchemaVariantsAPIResult<ForceChangeSetResponse<frontend_types::SchemaVariant>> {
+    let mut ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let before = SchemaVariant::get_by_id_or_error(&ctx, schema_variant_id).await?;
+    let schema = before.schema(&ctx).await?;
+
+    let updated = SchemaVariant::update_metadata(
+        &ctx,
+        schema_variant_id,
+        SchemaVariantMetadataUpdate {
+            display_name: request.display_name,
+            category: request.category,
+            color: request.color,
+            component_type: request.component_type.map(Into::into),
+            description: request.description,
+            link: request.link,
+        },
+    )
+    .await?;
+
+    ctx.write_audit_log(
+        AuditLogKind::UpdateSchemaVariant {
+            old_display_name: before.display_name().to_owned(),
+            new_display_name: updated.display_name().to_owned(),
+            old_description: before.description().unwrap_or_default(),
+            new_description: updated.description().unwrap_or_default(),
+            old_category: before.category().to_owned(),
+            new_category: updated.category().to_owned(),
+            old_link: before.link().unwrap_or_default(),
+            new_link: updated.link().unwrap_or_default(),
+            old_color: before.color().to_owned(),
+            new_color: updated.color().to_owned(),
+            old_component_type: before.component_type().to_string(),
+            new_component_type: updated.component_type().to_string(),
+        },
+        schema.name().to_owned(),
+    )
+    .await?;
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "update_variant_metadata",
+        serde_json::json!({
+            "schema_name": schema.name(),
+            "variant_id": updated.id(),
+            "variant_category": updated.category(),
+            "variant_display_name": updated.display_name(),
+        }),
+    );
+
+    WsEvent::schema_variant_updated(&ctx, schema.id(), updated.clone())
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::new(
+        force_change_set_id,
+        updated.into_frontend_type(&ctx, schema.id()).await?,
+    ))
+}
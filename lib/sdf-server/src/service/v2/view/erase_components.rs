@@ -9,7 +9,7 @@ use dal::diagram::geometry::Geometry;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
     service::force_change_set_response::ForceChangeSetResponse,
     track,
 };
@@ -25,7 +25,9 @@ pub struct Request {
     pub component_ids: Vec<ComponentId>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn erase_components(
+    _: AuthorizedTo<operation::DeleteComponent>,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
@@ -108,7 +108,7 @@ pub async fn get_default_diagram(
     get_diagram_inner(&ctx, view).await
 }
 
-async fn get_diagram_inner(ctx: &DalContext, view: View) -> ViewResult<Json<Response>> {
+pub(crate) async fn get_diagram_inner(ctx: &DalContext, view: View) -> ViewResult<Json<Response>> {
     let ctx_clone = ctx.clone();
     let view_id = view.id();
     let diagram = slow_rt::spawn(async move {
@@ -1,27 +1,53 @@
-use crate::{app_state::AppState, service::ApiError};
+use crate::{app_state::AppState, middleware::WorkspacePermissionLayer, service::ApiError};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::post,
+    routing::{get, post, put},
     Router,
 };
-use dal::{TransactionsError, UserError, UserPk, WorkspaceError, WorkspacePk};
+use dal::{TransactionsError, UserError, UserPk, WorkspaceError, WorkspacePk, WsEventError};
 use thiserror::Error;
+use ulid::Ulid;
 
+mod download_workspace;
 mod export_workspace;
+mod feature_flags;
+mod import_workspace;
 mod install_workspace;
 
+pub use import_workspace::WorkspaceImportUploads;
+
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum WorkspaceAPIError {
     #[error("Trying to export from/import into root tenancy")]
     ExportingImportingWithRootTenancy,
+    #[error("feature flag error: {0}")]
+    FeatureFlag(#[from] dal::feature_flags::FeatureFlagError),
+    #[error("upload offset {actual} does not match the {expected} bytes received so far")]
+    ImportOffsetMismatch { expected: u64, actual: u64 },
+    #[error("workspace backup of {0} bytes is too large to import")]
+    ImportTooLarge(u64),
+    #[error("chunk exceeds the upload length of {0} bytes")]
+    ImportUploadExceedsLength(u64),
+    #[error("workspace import upload not found: {0}")]
+    ImportUploadNotFound(Ulid),
+    #[error("missing or invalid upload header: {0}")]
+    InvalidUploadHeader(&'static str),
     #[error("invalid user: {0}")]
     InvalidUser(UserPk),
+    #[error("invalid workspace backup: {0}")]
+    InvalidWorkspaceBackup(serde_json::Error),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("tokio join error: {0}")]
+    Join(#[from] tokio::task::JoinError),
     #[error("Module index: {0}")]
     ModuleIndex(#[from] module_index_client::ModuleIndexClientError),
     #[error("Module index not configured")]
     ModuleIndexNotConfigured,
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
     #[error("Unable to parse URL: {0}")]
@@ -32,6 +58,8 @@ pub enum WorkspaceAPIError {
     Workspace(#[from] WorkspaceError),
     #[error("Could not find current workspace {0}")]
     WorkspaceNotFound(WorkspacePk),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
 }
 
 pub type WorkspaceAPIResult<T> = Result<T, WorkspaceAPIError>;
@@ -39,7 +67,21 @@ pub type WorkspaceAPIResult<T> = Result<T, WorkspaceAPIError>;
 impl IntoResponse for WorkspaceAPIError {
     fn into_response(self) -> Response {
         let (status_code, error_message) = match self {
-            WorkspaceAPIError::WorkspaceNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            WorkspaceAPIError::WorkspaceNotFound(_)
+            | WorkspaceAPIError::ImportUploadNotFound(_) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            WorkspaceAPIError::ImportOffsetMismatch { .. } => {
+                (StatusCode::CONFLICT, self.to_string())
+            }
+            WorkspaceAPIError::ImportTooLarge(_)
+            | WorkspaceAPIError::ImportUploadExceedsLength(_) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, self.to_string())
+            }
+            WorkspaceAPIError::InvalidUploadHeader(_)
+            | WorkspaceAPIError::InvalidWorkspaceBackup(_) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -47,8 +89,33 @@ impl IntoResponse for WorkspaceAPIError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+/// Backups contain the whole workspace, so downloading and importing them is limited to users who
+/// can manage the workspace.
+pub fn v2_routes(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/install", post(install_workspace::install_workspace))
         .route("/export", post(export_workspace::export_workspace))
+        .route(
+            "/export",
+            get(download_workspace::download_workspace).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Manage,
+            )),
+        )
+        .route("/feature-flags", get(feature_flags::list_feature_flags))
+        .route("/feature-flags/:flag", put(feature_flags::set_feature_flag))
+        .route(
+            "/import",
+            post(import_workspace::import_workspace).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Manage,
+            )),
+        )
+        .route(
+            "/import/:upload_id",
+            get(import_workspace::get_import_status).layer(WorkspacePermissionLayer::new(
+                state,
+                permissions::Permission::Manage,
+            )),
+        )
 }
@@ -0,0 +1,76 @@
+use axum::{
+    body::{Bytes, StreamBody},
+    extract::Path,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use dal::{Workspace, WorkspacePk};
+use futures::stream;
+use si_events::audit_log::AuditLogKind;
+use telemetry::prelude::info;
+
+use crate::extract::{AccessBuilder, HandlerContext};
+
+use super::{WorkspaceAPIError, WorkspaceAPIResult};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Streams a backup of the current workspace, in the same format that is uploaded to the module
+/// index, so that it can be imported into another workspace.
+pub async fn download_workspace(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> WorkspaceAPIResult<Response> {
+    let ctx = builder.build_head(request_ctx).await?;
+
+    let current_workspace = {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk_opt()
+            .ok_or(WorkspaceAPIError::ExportingImportingWithRootTenancy)?;
+        Workspace::get_by_pk(&ctx, &workspace_pk)
+            .await?
+            .ok_or(WorkspaceAPIError::WorkspaceNotFound(workspace_pk))?
+    };
+
+    info!("Downloading workspace backup");
+    let version = Utc::now().format("%Y-%m-%d_%H:%M:%S").to_string();
+    let workspace_payload = current_workspace
+        .generate_export_data(&ctx, &version)
+        .await?;
+    let bytes = Bytes::from(serde_json::to_vec(&workspace_payload)?);
+
+    ctx.write_audit_log(
+        AuditLogKind::ExportWorkspace {
+            id: *current_workspace.pk(),
+            name: current_workspace.name().clone(),
+            version: version.clone(),
+        },
+        current_workspace.name().to_string(),
+    )
+    .await?;
+
+    let filename = format!("{}_{version}.json", current_workspace.name());
+    let content_length = bytes.len();
+    let chunks = (0..content_length)
+        .step_by(CHUNK_SIZE)
+        .map(move |start| {
+            Ok::<_, std::io::Error>(bytes.slice(start..(start + CHUNK_SIZE).min(content_length)))
+        })
+        .collect::<Vec<_>>();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_owned()),
+            (header::CONTENT_LENGTH, content_length.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        StreamBody::new(stream::iter(chunks)),
+    )
+        .into_response())
+}
@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{
+    feature_flags::{FeatureFlag, FeatureFlagState},
+    WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    extract::{operation, AccessBuilder, AuthorizedTo, HandlerContext, PosthogClient},
+    track,
+};
+
+use super::WorkspaceAPIResult;
+
+pub async fn list_feature_flags(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> WorkspaceAPIResult<Json<Vec<FeatureFlagState>>> {
+    let ctx = builder.build_head(request_ctx).await?;
+
+    Ok(Json(FeatureFlag::list_for_workspace(&ctx).await?))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFeatureFlagRequest {
+    /// Overrides the default of the flag for the workspace. Without one, the workspace goes back
+    /// to the default.
+    pub enabled: Option<bool>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn set_feature_flag(
+    _: AuthorizedTo<operation::SetFeatureFlags>,
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, flag)): Path<(WorkspacePk, FeatureFlag)>,
+    Json(request): Json<SetFeatureFlagRequest>,
+) -> WorkspaceAPIResult<Json<FeatureFlagState>> {
+    let ctx = builder.build_head(request_ctx).await?;
+
+    let state = flag.set_workspace_override(&ctx, request.enabled).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "set_feature_flag",
+        serde_json::json!({
+            "how": "/workspace/set_feature_flag",
+            "flag": flag.to_string(),
+            "override_enabled": request.enabled,
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(state))
+}
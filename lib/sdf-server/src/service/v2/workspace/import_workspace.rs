@@ -0,0 +1,360 @@
+//! Resumable uploads of workspace backups, which are imported into the current workspace once
+//! complete.
+//!
+//! A backup is uploaded as a series of chunks, each sent with `POST .../import` and no larger than
+//! the default request body limit (2 MiB):
+//!
+//! - `upload-id`: omitted for the first chunk, which starts a new upload. Every response contains
+//!   the id to send with the remaining chunks.
+//! - `upload-offset`: the byte offset of the chunk within the backup.
+//! - `upload-length`: the total size of the backup in bytes, required for the first chunk.
+//!
+//! If the connection drops, `GET .../import/:upload_id` returns the offset to resume from. Progress
+//! is reported to the workspace as [`WsEvent::workspace_import_progress`] events, and the
+//! completion (or failure) of the import as an async finish (or error) event with the upload id.
+//!
+//! Received chunks are spooled to a temporary file rather than held in memory, which is removed
+//! once the upload completes or expires.
+
+use std::{
+    collections::HashMap,
+    io::{BufReader, Seek},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Bytes,
+    extract::{Host, OriginalUri, Path, State},
+    http::{HeaderMap, Uri},
+    Json,
+};
+use dal::{pkg::WorkspaceImportStage, DalContext, Workspace, WorkspacePk, WsEvent};
+use serde::{Deserialize, Serialize};
+use si_pkg::WorkspaceExport;
+use telemetry::prelude::*;
+use tokio::io::AsyncWriteExt;
+use ulid::Ulid;
+
+use crate::{
+    extract::{AccessBuilder, HandlerContext, PosthogClient},
+    service::async_route::handle_error,
+};
+
+use super::{install_workspace::import_workspace_data, WorkspaceAPIError, WorkspaceAPIResult};
+
+const UPLOAD_ID_HEADER: &str = "upload-id";
+const UPLOAD_OFFSET_HEADER: &str = "upload-offset";
+const UPLOAD_LENGTH_HEADER: &str = "upload-length";
+
+/// The largest backup that can be uploaded.
+const MAX_UPLOAD_LENGTH: u64 = 1024 * 1024 * 1024;
+/// Uploads which have not received a chunk for this long are discarded.
+const UPLOAD_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The workspace backups currently being uploaded to this server.
+#[derive(Clone, Debug, Default)]
+pub struct WorkspaceImportUploads(
+    Arc<Mutex<HashMap<Ulid, Arc<tokio::sync::Mutex<WorkspaceImportUpload>>>>>,
+);
+
+#[derive(Debug)]
+struct WorkspaceImportUpload {
+    workspace_pk: WorkspacePk,
+    length: u64,
+    received: u64,
+    /// An anonymous temporary file, which is deleted when closed.
+    file: tokio::fs::File,
+    updated_at: Instant,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportWorkspaceResponse {
+    pub upload_id: Ulid,
+    /// The number of bytes received so far, i.e. the offset of the next chunk.
+    pub offset: u64,
+    pub length: u64,
+    /// Whether the whole backup has been received and is being imported.
+    pub complete: bool,
+}
+
+impl WorkspaceImportUploads {
+    fn get(&self, upload_id: Ulid) -> Option<Arc<tokio::sync::Mutex<WorkspaceImportUpload>>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(&upload_id)
+            .cloned()
+    }
+
+    async fn status(
+        &self,
+        workspace_pk: WorkspacePk,
+        upload_id: Ulid,
+    ) -> WorkspaceAPIResult<ImportWorkspaceResponse> {
+        let upload = self
+            .get(upload_id)
+            .ok_or(WorkspaceAPIError::ImportUploadNotFound(upload_id))?;
+        let upload = upload.lock().await;
+        if upload.workspace_pk != workspace_pk {
+            return Err(WorkspaceAPIError::ImportUploadNotFound(upload_id));
+        }
+
+        Ok(ImportWorkspaceResponse {
+            upload_id,
+            offset: upload.received,
+            length: upload.length,
+            complete: false,
+        })
+    }
+
+    /// Starts a new upload, discarding the expired ones.
+    fn start(
+        &self,
+        workspace_pk: WorkspacePk,
+        maybe_length: Option<u64>,
+    ) -> WorkspaceAPIResult<(Ulid, Arc<tokio::sync::Mutex<WorkspaceImportUpload>>)> {
+        let length =
+            maybe_length.ok_or(WorkspaceAPIError::InvalidUploadHeader(UPLOAD_LENGTH_HEADER))?;
+        if length > MAX_UPLOAD_LENGTH {
+            return Err(WorkspaceAPIError::ImportTooLarge(length));
+        }
+
+        let now = Instant::now();
+        let upload_id = Ulid::new();
+        let upload = Arc::new(tokio::sync::Mutex::new(WorkspaceImportUpload {
+            workspace_pk,
+            length,
+            received: 0,
+            file: tokio::fs::File::from_std(tempfile::tempfile()?),
+            updated_at: now,
+        }));
+
+        let mut uploads = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        // Uploads receiving a chunk right now are not expired
+        uploads.retain(|_, upload| {
+            !upload
+                .try_lock()
+                .is_ok_and(|upload| now.duration_since(upload.updated_at) >= UPLOAD_TTL)
+        });
+        uploads.insert(upload_id, upload.clone());
+
+        Ok((upload_id, upload))
+    }
+
+    /// Appends the chunk to the upload, starting a new upload if there is no id. Once complete,
+    /// the upload is removed and its file returned, rewound to the start.
+    async fn append(
+        &self,
+        workspace_pk: WorkspacePk,
+        maybe_upload_id: Option<Ulid>,
+        offset: u64,
+        maybe_length: Option<u64>,
+        chunk: &[u8],
+    ) -> WorkspaceAPIResult<(ImportWorkspaceResponse, Option<std::fs::File>)> {
+        let (upload_id, upload) = match maybe_upload_id {
+            Some(upload_id) => (
+                upload_id,
+                self.get(upload_id)
+                    .ok_or(WorkspaceAPIError::ImportUploadNotFound(upload_id))?,
+            ),
+            None => self.start(workspace_pk, maybe_length)?,
+        };
+
+        let mut upload = upload.lock().await;
+        if upload.workspace_pk != workspace_pk {
+            return Err(WorkspaceAPIError::ImportUploadNotFound(upload_id));
+        }
+        if offset != upload.received {
+            return Err(WorkspaceAPIError::ImportOffsetMismatch {
+                expected: upload.received,
+                actual: offset,
+            });
+        }
+        if upload.received + chunk.len() as u64 > upload.length {
+            return Err(WorkspaceAPIError::ImportUploadExceedsLength(upload.length));
+        }
+        upload.file.write_all(chunk).await?;
+        upload.received += chunk.len() as u64;
+        upload.updated_at = Instant::now();
+
+        let response = ImportWorkspaceResponse {
+            upload_id,
+            offset: upload.received,
+            length: upload.length,
+            complete: upload.received == upload.length,
+        };
+        if !response.complete {
+            return Ok((response, None));
+        }
+
+        self.0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(&upload_id);
+        upload.file.flush().await?;
+        let mut file = upload.file.try_clone().await?.into_std().await;
+        file.rewind()?;
+
+        Ok((response, Some(file)))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn import_workspace(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    State(uploads): State<WorkspaceImportUploads>,
+    headers: HeaderMap,
+    chunk: Bytes,
+) -> WorkspaceAPIResult<Json<ImportWorkspaceResponse>> {
+    let ctx = builder.build_head(request_ctx).await?;
+
+    let mut current_workspace = {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk_opt()
+            .ok_or(WorkspaceAPIError::ExportingImportingWithRootTenancy)?;
+        Workspace::get_by_pk(&ctx, &workspace_pk)
+            .await?
+            .ok_or(WorkspaceAPIError::WorkspaceNotFound(workspace_pk))?
+    };
+
+    let maybe_upload_id = parse_header(&headers, UPLOAD_ID_HEADER)?;
+    let offset = parse_header(&headers, UPLOAD_OFFSET_HEADER)?.unwrap_or(0);
+    let maybe_length = parse_header(&headers, UPLOAD_LENGTH_HEADER)?;
+
+    let (response, maybe_file) = uploads
+        .append(
+            *current_workspace.pk(),
+            maybe_upload_id,
+            offset,
+            maybe_length,
+            &chunk,
+        )
+        .await?;
+
+    WsEvent::workspace_import_progress(
+        &ctx,
+        response.upload_id,
+        WorkspaceImportStage::Uploading,
+        response.offset,
+        response.length,
+    )
+    .await?
+    .publish_immediately(&ctx)
+    .await?;
+
+    let Some(file) = maybe_file else {
+        return Ok(Json(response));
+    };
+
+    // Reject a broken backup right away, rather than reporting it asynchronously
+    let workspace_data: WorkspaceExport = tokio::task::spawn_blocking(move || {
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(WorkspaceAPIError::InvalidWorkspaceBackup)
+    })
+    .await??;
+
+    let task_id = response.upload_id;
+    let total_bytes = response.length;
+    tokio::task::spawn(async move {
+        if let Err(err) = import_workspace_inner(
+            &ctx,
+            task_id,
+            total_bytes,
+            &mut current_workspace,
+            workspace_data,
+            &original_uri,
+            &host_name,
+            PosthogClient(posthog_client),
+        )
+        .await
+        {
+            handle_error(&ctx, original_uri, task_id, err).await;
+        } else {
+            match WsEvent::async_finish_workspace(&ctx, task_id).await {
+                Ok(event) => {
+                    if let Err(err) = event.publish_immediately(&ctx).await {
+                        handle_error(&ctx, original_uri, task_id, err).await;
+                    }
+                }
+                Err(err) => {
+                    handle_error(&ctx, original_uri, task_id, err).await;
+                }
+            }
+        }
+    });
+
+    Ok(Json(response))
+}
+
+pub async fn get_import_status(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, upload_id)): Path<(WorkspacePk, Ulid)>,
+    State(uploads): State<WorkspaceImportUploads>,
+) -> WorkspaceAPIResult<Json<ImportWorkspaceResponse>> {
+    let ctx = builder.build_head(request_ctx).await?;
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk_opt()
+        .ok_or(WorkspaceAPIError::ExportingImportingWithRootTenancy)?;
+
+    Ok(Json(uploads.status(workspace_pk, upload_id).await?))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn import_workspace_inner(
+    ctx: &DalContext,
+    task_id: Ulid,
+    total_bytes: u64,
+    current_workspace: &mut Workspace,
+    workspace_data: WorkspaceExport,
+    original_uri: &Uri,
+    host_name: &String,
+    posthog_client: PosthogClient,
+) -> WorkspaceAPIResult<()> {
+    info!("Importing uploaded workspace backup");
+    WsEvent::workspace_import_progress(
+        ctx,
+        task_id,
+        WorkspaceImportStage::Importing,
+        total_bytes,
+        total_bytes,
+    )
+    .await?
+    .publish_immediately(ctx)
+    .await?;
+
+    import_workspace_data(
+        ctx,
+        current_workspace,
+        workspace_data,
+        original_uri,
+        host_name,
+        posthog_client,
+    )
+    .await
+}
+
+fn parse_header<T: std::str::FromStr>(
+    headers: &HeaderMap,
+    name: &'static str,
+) -> WorkspaceAPIResult<Option<T>> {
+    headers
+        .get(name)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .ok_or(WorkspaceAPIError::InvalidUploadHeader(name))
+        })
+        .transpose()
+}
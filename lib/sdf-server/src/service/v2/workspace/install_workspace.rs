@@ -7,7 +7,7 @@ use dal::{DalContext, Workspace, WorkspacePk, WsEvent};
 use module_index_client::ModuleIndexClient;
 use serde::{Deserialize, Serialize};
 use si_events::audit_log::AuditLogKind;
-use si_pkg::WorkspaceExportContentV0;
+use si_pkg::{WorkspaceExport, WorkspaceExportContentV0};
 use telemetry::prelude::info;
 use ulid::Ulid;
 
@@ -100,6 +100,26 @@ async fn install_workspace_inner(
             .await?
     };
 
+    import_workspace_data(
+        ctx,
+        &mut current_workspace,
+        workspace_data,
+        original_uri,
+        host_name,
+        PosthogClient(posthog_client),
+    )
+    .await
+}
+
+/// Imports the workspace data into the current workspace, replacing its change sets, and commits.
+pub(super) async fn import_workspace_data(
+    ctx: &DalContext,
+    current_workspace: &mut Workspace,
+    workspace_data: WorkspaceExport,
+    original_uri: &Uri,
+    host_name: &String,
+    PosthogClient(posthog_client): PosthogClient,
+) -> WorkspaceAPIResult<()> {
     current_workspace
         .import(ctx, workspace_data.clone())
         .await?;
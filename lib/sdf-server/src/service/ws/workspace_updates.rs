@@ -19,9 +19,10 @@ use crate::{
 #[allow(clippy::unused_async)]
 pub async fn workspace_updates(
     wsu: WebSocketUpgrade,
-    Nats(nats): Nats,
     _: TokenFromQueryParam, // This tells it to pull the token from the "token" param
     auth: EndpointAuthorization,
+    // After the token, so that events are published to the region of the workspace
+    Nats(nats): Nats,
     State(shutdown_token): State<CancellationToken>,
     State(channel_multiplexer_clients): State<NatsMultiplexerClients>,
 ) -> Result<impl IntoResponse, WsError> {
@@ -71,13 +72,16 @@ async fn run_workspace_updates_proto(
 }
 
 mod workspace_updates {
+    use std::{borrow::Cow, cell::OnceCell, collections::HashSet};
+
     use axum::extract::ws::{self, WebSocket};
     use dal::{
         component::ComponentSetPositionPayload, user::CursorPayload, user::OnlinePayload,
-        ChangeSetId, UserPk, WorkspacePk, WsEvent, WsEventError,
+        ChangeSetId, ComponentId, UserPk, WorkspacePk, WsEvent, WsEventError,
     };
     use nats_multiplexer_client::{MultiplexerClient, MultiplexerClientError};
     use serde::{Deserialize, Serialize};
+    use serde_json::value::RawValue;
     use si_data_nats::{NatsClient, Subject};
     use si_events::ViewId;
     use std::error::Error;
@@ -115,6 +119,135 @@ mod workspace_updates {
             view_id: Option<ViewId>,
             idle: bool,
         },
+        /// Replaces the filter of the events sent to the client.
+        Subscribe(WorkspaceUpdatesSubscription),
+    }
+
+    /// Limits the events sent to a client to those it is interested in, so that it does not have
+    /// to receive and discard every event of a big workspace. Every criterion which is left out
+    /// (or empty) matches all events.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct WorkspaceUpdatesSubscription {
+        /// Events of other change sets are filtered out. Events which do not belong to a change
+        /// set, and changes sets being applied to this one, are always sent.
+        change_set_id: Option<ChangeSetId>,
+        /// The kinds of events which are sent, e.g. `ComponentUpdated`.
+        #[serde(default)]
+        event_kinds: HashSet<String>,
+        /// Events about other components are filtered out. Events which do not concern any
+        /// component are always sent.
+        #[serde(default)]
+        component_ids: HashSet<ComponentId>,
+    }
+
+    /// The parts of a serialized [`WsEvent`] which are needed to filter it. The data of the
+    /// payload is left unparsed until a filter needs it.
+    #[derive(Deserialize)]
+    struct WsEventHeader<'a> {
+        change_set_id: Option<ChangeSetId>,
+        #[serde(borrow)]
+        payload: WsEventHeaderPayload<'a>,
+    }
+
+    #[derive(Deserialize)]
+    struct WsEventHeaderPayload<'a> {
+        #[serde(borrow)]
+        kind: Cow<'a, str>,
+        #[serde(borrow, default)]
+        data: Option<&'a RawValue>,
+    }
+
+    impl WsEventHeaderPayload<'_> {
+        /// The fields of the data which are needed to filter the event. Data which does not
+        /// follow the conventions of the payloads is treated as if it had none of the fields.
+        fn data(&self) -> WsEventData {
+            self.data
+                .and_then(|data| serde_json::from_str(data.get()).ok())
+                .unwrap_or_default()
+        }
+    }
+
+    /// The fields of the payload data of the events which filters look at. The components an
+    /// event is about go by the conventions of their payloads: a `componentId`, a `component`
+    /// with an `id`, or `positions` of components.
+    #[derive(Deserialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    struct WsEventData {
+        to_rebase_change_set_id: Option<ChangeSetId>,
+        component_id: Option<ComponentId>,
+        component: Option<WsEventDataComponent>,
+        #[serde(default)]
+        positions: Vec<WsEventDataPosition>,
+    }
+
+    #[derive(Deserialize)]
+    struct WsEventDataComponent {
+        id: Option<ComponentId>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct WsEventDataPosition {
+        component_id: Option<ComponentId>,
+    }
+
+    impl WsEventData {
+        fn component_ids(&self) -> impl Iterator<Item = ComponentId> + '_ {
+            self.component_id
+                .into_iter()
+                .chain(self.component.as_ref().and_then(|component| component.id))
+                .chain(
+                    self.positions
+                        .iter()
+                        .filter_map(|position| position.component_id),
+                )
+        }
+    }
+
+    impl WorkspaceUpdatesSubscription {
+        fn is_empty(&self) -> bool {
+            self.change_set_id.is_none()
+                && self.event_kinds.is_empty()
+                && self.component_ids.is_empty()
+        }
+
+        /// Whether a serialized [`WsEvent`] should be sent. Messages which are not events are
+        /// always sent.
+        fn matches(&self, message: &[u8]) -> bool {
+            let Ok(event) = serde_json::from_slice::<WsEventHeader<'_>>(message) else {
+                return true;
+            };
+            let kind = event.payload.kind.as_ref();
+            let data = OnceCell::new();
+            let data = || data.get_or_init(|| event.payload.data());
+
+            if !self.event_kinds.is_empty() && !self.event_kinds.contains(kind) {
+                return false;
+            }
+
+            if let (Some(subscribed), Some(change_set_id)) =
+                (self.change_set_id, event.change_set_id)
+            {
+                if change_set_id != subscribed
+                    && !(kind == "ChangeSetApplied"
+                        && data().to_rebase_change_set_id == Some(subscribed))
+                {
+                    return false;
+                }
+            }
+
+            if !self.component_ids.is_empty() {
+                let mut component_ids = data().component_ids().peekable();
+                if component_ids.peek().is_some()
+                    && !component_ids.any(|id| self.component_ids.contains(&id))
+                {
+                    return false;
+                }
+            }
+
+            true
+        }
     }
 
     pub fn run(
@@ -174,6 +307,7 @@ mod workspace_updates {
                 workspace_pk: self.workspace_pk,
                 receiver,
                 token: self.token,
+                subscription: None,
             })
         }
     }
@@ -184,6 +318,7 @@ mod workspace_updates {
         nats: NatsClient,
         receiver: broadcast::Receiver<si_data_nats::Message>,
         token: CancellationToken,
+        subscription: Option<WorkspaceUpdatesSubscription>,
     }
 
     impl WorkspaceUpdatesStarted {
@@ -257,6 +392,9 @@ mod workspace_updates {
                                         let event = WsEvent::reflect_component_position(self.workspace_pk, payload.change_set_id(), payload).await?;
                                         self.nats.publish(subject, serde_json::to_vec(&event)?.into()).await?;
                                     }
+                                    WebsocketEventRequest::Subscribe(subscription) => {
+                                        self.subscription = (!subscription.is_empty()).then_some(subscription);
+                                    }
                                 }
                             },
                             Some(Err(err)) => return Err(err.into()),
@@ -266,6 +404,9 @@ mod workspace_updates {
                     recv_result = self.receiver.recv() => {
                         // NOTE(nick): in the long term, determine if we want to return this result or just log it.
                         let nats_msg =  recv_result?;
+                        if self.subscription.as_ref().is_some_and(|subscription| !subscription.matches(nats_msg.payload())) {
+                            continue;
+                        }
                         let msg = ws::Message::Text(String::from_utf8_lossy(nats_msg.payload()).to_string());
 
                         if let Err(err) = ws.send(msg).await {
@@ -307,4 +448,106 @@ mod workspace_updates {
             Ok(())
         }
     }
+
+    #[cfg(test)]
+    mod test {
+        use serde_json::json;
+
+        use super::*;
+
+        fn event(
+            change_set_id: Option<ChangeSetId>,
+            kind: &str,
+            data: serde_json::Value,
+        ) -> Vec<u8> {
+            serde_json::to_vec(&json!({
+                "version": 1,
+                "workspace_pk": WorkspacePk::generate(),
+                "change_set_id": change_set_id,
+                "payload_version": 1,
+                "payload": { "kind": kind, "data": data },
+            }))
+            .expect("serialize event")
+        }
+
+        fn subscription(value: serde_json::Value) -> WorkspaceUpdatesSubscription {
+            serde_json::from_value(value).expect("deserialize subscription")
+        }
+
+        #[test]
+        fn sends_messages_which_are_not_events() {
+            let subscription = subscription(json!({ "eventKinds": ["ComponentUpdated"] }));
+
+            assert!(subscription.matches(b"not an event"));
+            assert!(subscription.matches(br#"{"kind":"ComponentUpdated"}"#));
+        }
+
+        #[test]
+        fn filters_on_event_kinds() {
+            let subscription = subscription(json!({ "eventKinds": ["ComponentUpdated"] }));
+
+            assert!(subscription.matches(&event(None, "ComponentUpdated", json!({}))));
+            assert!(!subscription.matches(&event(None, "ChangeSetCreated", json!({}))));
+        }
+
+        #[test]
+        fn filters_on_change_set() {
+            let subscribed = ChangeSetId::generate();
+            let other = ChangeSetId::generate();
+            let subscription = subscription(json!({ "changeSetId": subscribed }));
+
+            assert!(subscription.matches(&event(Some(subscribed), "ComponentUpdated", json!({}))));
+            assert!(subscription.matches(&event(None, "ComponentUpdated", json!({}))));
+            assert!(!subscription.matches(&event(Some(other), "ComponentUpdated", json!({}))));
+            assert!(subscription.matches(&event(
+                Some(other),
+                "ChangeSetApplied",
+                json!({ "changeSetId": other, "toRebaseChangeSetId": subscribed }),
+            )));
+            assert!(!subscription.matches(&event(
+                Some(other),
+                "ChangeSetApplied",
+                json!({ "changeSetId": other, "toRebaseChangeSetId": other }),
+            )));
+        }
+
+        #[test]
+        fn filters_on_components() {
+            let subscribed = ComponentId::generate();
+            let other = ComponentId::generate();
+            let subscription = subscription(json!({ "componentIds": [subscribed] }));
+
+            assert!(subscription.matches(&event(
+                None,
+                "ComponentUpdated",
+                json!({ "componentId": subscribed }),
+            )));
+            assert!(!subscription.matches(&event(
+                None,
+                "ComponentUpdated",
+                json!({ "componentId": other }),
+            )));
+            assert!(subscription.matches(&event(
+                None,
+                "ComponentCreated",
+                json!({ "component": { "id": subscribed, "displayName": "subscribed" } }),
+            )));
+            assert!(!subscription.matches(&event(
+                None,
+                "ComponentCreated",
+                json!({ "component": { "id": other, "displayName": "other" } }),
+            )));
+            assert!(subscription.matches(&event(
+                None,
+                "SetComponentPosition",
+                json!({ "positions": [{ "componentId": other }, { "componentId": subscribed }] }),
+            )));
+            assert!(!subscription.matches(&event(
+                None,
+                "SetComponentPosition",
+                json!({ "positions": [{ "componentId": other }] }),
+            )));
+            assert!(subscription.matches(&event(None, "ChangeSetCreated", json!({}))));
+        }
+    }
 }
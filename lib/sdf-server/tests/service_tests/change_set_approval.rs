@@ -0,0 +1,137 @@
+use dal::{workspace_role::WorkspaceRole, DalContext};
+use dal_test::{sdf_test, SdfTestClient, WorkspaceSignup};
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+fn change_set_path(ctx: &DalContext) -> String {
+    format!(
+        "/api/v2/workspaces/{}/change-sets/{}",
+        ctx.workspace_pk().expect("could not get workspace pk"),
+        ctx.change_set_id(),
+    )
+}
+
+#[sdf_test]
+async fn change_set_awaiting_approval_is_locked_for_edits(
+    ctx: &mut DalContext,
+    client: SdfTestClient,
+) {
+    let path = change_set_path(ctx);
+
+    let response = client
+        .request(Method::POST, format!("{path}/request_approval"))
+        .send()
+        .await
+        .expect("could not request approval");
+    assert_eq!(StatusCode::OK, response.status());
+
+    // Edits are rejected while the change set is awaiting approval
+    let response = client
+        .request(Method::POST, format!("{path}/views"))
+        .json(&json!({ "name": "locked" }))
+        .send()
+        .await
+        .expect("could not create view");
+    assert_eq!(StatusCode::CONFLICT, response.status());
+
+    // Reads are still served, even when posted
+    let response = client
+        .request(Method::POST, format!("{path}/funcs/code"))
+        .json(&json!({ "ids": [] }))
+        .send()
+        .await
+        .expect("could not get func code");
+    assert_eq!(StatusCode::OK, response.status());
+
+    // Withdrawing the request unlocks the change set again
+    let response = client
+        .request(Method::POST, format!("{path}/cancel_approval_request"))
+        .send()
+        .await
+        .expect("could not cancel approval request");
+    assert_eq!(StatusCode::OK, response.status());
+
+    let response = client
+        .request(Method::POST, format!("{path}/views"))
+        .json(&json!({ "name": "unlocked" }))
+        .send()
+        .await
+        .expect("could not create view");
+    assert_eq!(StatusCode::OK, response.status());
+}
+
+#[sdf_test]
+async fn v1_edits_of_a_change_set_awaiting_approval_are_rejected(
+    ctx: &mut DalContext,
+    client: SdfTestClient,
+) {
+    let path = change_set_path(ctx);
+    let response = client
+        .request(Method::POST, format!("{path}/request_approval"))
+        .send()
+        .await
+        .expect("could not request approval");
+    assert_eq!(StatusCode::OK, response.status());
+
+    // Rejected ahead of the route, whatever the rest of the body holds
+    let response = client
+        .request(Method::POST, "/api/component/set_name")
+        .json(&json!({ "visibility_change_set_pk": ctx.change_set_id() }))
+        .send()
+        .await
+        .expect("could not set name");
+    assert_eq!(StatusCode::CONFLICT, response.status());
+
+    let response = client
+        .request(Method::POST, format!("{path}/cancel_approval_request"))
+        .send()
+        .await
+        .expect("could not cancel approval request");
+    assert_eq!(StatusCode::OK, response.status());
+
+    let response = client
+        .request(Method::POST, "/api/component/set_name")
+        .json(&json!({ "visibility_change_set_pk": ctx.change_set_id() }))
+        .send()
+        .await
+        .expect("could not set name");
+    assert_ne!(StatusCode::CONFLICT, response.status());
+}
+
+#[sdf_test]
+async fn v1_apply_requires_an_approved_change_set(ctx: &mut DalContext, client: SdfTestClient) {
+    let response = client
+        .request(Method::POST, "/api/change_set/apply_change_set")
+        .json(&json!({ "visibility_change_set_pk": ctx.change_set_id() }))
+        .send()
+        .await
+        .expect("could not apply change set");
+    assert_eq!(StatusCode::PRECONDITION_FAILED, response.status());
+}
+
+#[sdf_test]
+async fn only_owners_can_force_apply(
+    ctx: &mut DalContext,
+    nw: &WorkspaceSignup,
+    client: SdfTestClient,
+) {
+    WorkspaceRole::set_for_user(
+        ctx,
+        *nw.workspace.pk(),
+        nw.user.pk(),
+        WorkspaceRole::Approver,
+    )
+    .await
+    .expect("could not set role");
+    ctx.commit_no_rebase().await.expect("could not commit");
+
+    let response = client
+        .request(
+            Method::POST,
+            format!("{}/force_apply", change_set_path(ctx)),
+        )
+        .send()
+        .await
+        .expect("could not force apply");
+    assert_eq!(StatusCode::FORBIDDEN, response.status());
+}
@@ -0,0 +1,100 @@
+use dal::{Component, DalContext};
+use dal_test::{
+    helpers::{create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers},
+    sdf_test, SdfTestClient,
+};
+use reqwest::{header, Method, StatusCode};
+use serde_json::json;
+
+#[sdf_test]
+async fn set_name_respects_if_match(ctx: &mut DalContext, client: SdfTestClient) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "brick")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let response = client
+        .request(Method::GET, "/api/component/get_property_editor_values")
+        .query(&[
+            ("componentId", component.id().to_string()),
+            ("visibility_change_set_pk", ctx.change_set_id().to_string()),
+        ])
+        .send()
+        .await
+        .expect("could not get property editor values");
+    assert_eq!(StatusCode::OK, response.status());
+    let etag = response
+        .headers()
+        .get(header::ETAG)
+        .expect("no etag header")
+        .to_str()
+        .expect("etag is not a string")
+        .to_owned();
+
+    let set_name = |if_match: &str, name: &str| {
+        client
+            .request(Method::POST, "/api/component/set_name")
+            .header(header::IF_MATCH, if_match)
+            .json(&json!({
+                "componentId": component.id(),
+                "name": name,
+                "visibility_change_set_pk": ctx.change_set_id(),
+            }))
+            .send()
+    };
+
+    let response = set_name("\"stale\"", "plate")
+        .await
+        .expect("could not set name");
+    assert_eq!(StatusCode::PRECONDITION_FAILED, response.status());
+
+    // Weak tags never match a precondition on a write, even for the current version
+    let response = set_name(&format!("W/{etag}"), "plate")
+        .await
+        .expect("could not set name");
+    assert_eq!(StatusCode::PRECONDITION_FAILED, response.status());
+
+    let response = set_name(&etag, "plate").await.expect("could not set name");
+    assert_eq!(StatusCode::OK, response.status());
+
+    // The name change produced a new version, so the previous tag is now stale
+    let response = set_name(&etag, "tile").await.expect("could not set name");
+    assert_eq!(StatusCode::PRECONDITION_FAILED, response.status());
+
+    ctx.update_snapshot_to_visibility()
+        .await
+        .expect("could not update snapshot to visibility");
+    let component = Component::get_by_id(ctx, component.id())
+        .await
+        .expect("could not get component");
+    assert_eq!(
+        "plate",
+        component.name(ctx).await.expect("could not get name")
+    );
+}
+
+#[sdf_test]
+async fn upgrade_respects_if_match(ctx: &mut DalContext, client: SdfTestClient) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "brick")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let response = client
+        .request(Method::POST, "/api/component/upgrade_component")
+        .header(header::IF_MATCH, "\"stale\"")
+        .json(&json!({
+            "componentId": component.id(),
+            "visibility_change_set_pk": ctx.change_set_id(),
+        }))
+        .send()
+        .await
+        .expect("could not upgrade component");
+    assert_eq!(StatusCode::PRECONDITION_FAILED, response.status());
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Extension, Router,
+};
+use dal::{DalContext, WorkspacePk};
+use dal_test::{sdf_test, WorkspaceSignup};
+use sdf_server::{
+    service::readiness::{ReadinessResponse, ReadinessStatus},
+    DataResidency, DataResidencyError,
+};
+use tower::ServiceExt;
+
+#[sdf_test]
+async fn routes_assigned_workspaces_to_their_region(ctx: &DalContext, nw: WorkspaceSignup) {
+    let data_residency = DataResidency::new(
+        HashMap::from([("eu".to_owned(), ctx.services_context())]),
+        &HashMap::from([(nw.workspace.pk().to_string(), "eu".to_owned())]),
+    )
+    .expect("could not create data residency");
+
+    assert!(!data_residency.is_empty());
+    assert_eq!(
+        Some("eu"),                                              // expected
+        data_residency.region_for_workspace(*nw.workspace.pk()), // actual
+    );
+    assert!(data_residency
+        .services_context_for_workspace(*nw.workspace.pk())
+        .is_some());
+
+    // Every other workspace is served from the default services
+    let other_workspace_pk = WorkspacePk::generate();
+    assert_eq!(
+        None,
+        data_residency.region_for_workspace(other_workspace_pk)
+    );
+    assert!(data_residency
+        .services_context_for_workspace(other_workspace_pk)
+        .is_none());
+}
+
+#[sdf_test]
+async fn rejects_invalid_assignments(ctx: &DalContext) {
+    let workspace_pk = WorkspacePk::generate().to_string();
+
+    let err = DataResidency::new(
+        HashMap::from([("eu".to_owned(), ctx.services_context())]),
+        &HashMap::from([(workspace_pk.clone(), "us".to_owned())]),
+    )
+    .expect_err("a workspace in an unknown region should be rejected");
+    assert!(matches!(
+        err,
+        DataResidencyError::UnknownRegion { workspace_pk: pk, region }
+            if pk == workspace_pk && region == "us"
+    ));
+
+    let err = DataResidency::new(
+        HashMap::from([("eu".to_owned(), ctx.services_context())]),
+        &HashMap::from([("not-a-workspace".to_owned(), "eu".to_owned())]),
+    )
+    .expect_err("an invalid workspace pk should be rejected");
+    assert!(matches!(
+        err,
+        DataResidencyError::InvalidWorkspacePk(pk) if pk == "not-a-workspace"
+    ));
+}
+
+#[test]
+fn finds_workspace_pk_in_path() {
+    let workspace_pk = WorkspacePk::generate();
+
+    assert_eq!(
+        Some(workspace_pk),
+        DataResidency::workspace_pk_from_path(&format!(
+            "/api/v2/workspaces/{workspace_pk}/change-sets/01JAVTXXHBMVEM0KRK3NSSRMD5/funcs"
+        )),
+    );
+    assert_eq!(
+        Some(workspace_pk),
+        DataResidency::workspace_pk_from_path(&format!("/api/v2/workspaces/{workspace_pk}")),
+    );
+    assert_eq!(
+        None,
+        DataResidency::workspace_pk_from_path("/api/v2/workspaces/not-a-workspace/export"),
+    );
+    assert_eq!(
+        None,
+        DataResidency::workspace_pk_from_path("/api/session/load_workspace"),
+    );
+}
+
+#[sdf_test]
+async fn readiness_checks_every_region(ctx: &DalContext, app: Router) {
+    let data_residency = DataResidency::new(
+        HashMap::from([("eu".to_owned(), ctx.services_context())]),
+        &HashMap::new(),
+    )
+    .expect("could not create data residency");
+
+    let response = app
+        .layer(Extension(data_residency))
+        .oneshot(
+            Request::builder()
+                .uri("/api/readiness")
+                .body(Body::empty())
+                .expect("could not build request"),
+        )
+        .await
+        .expect("could not send request");
+    assert_eq!(StatusCode::OK, response.status());
+
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("could not read body");
+    let readiness: ReadinessResponse =
+        serde_json::from_slice(&body).expect("could not deserialize readiness response");
+
+    let region = readiness
+        .regions
+        .get("eu")
+        .expect("the region should have been checked");
+    assert_ne!(ReadinessStatus::Failed, region.pg.status);
+    assert_ne!(ReadinessStatus::Failed, region.nats.status);
+}
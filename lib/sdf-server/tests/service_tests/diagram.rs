@@ -0,0 +1,91 @@
+use dal::DalContext;
+use dal_test::{
+    helpers::{create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers},
+    sdf_test, SdfTestClient,
+};
+use sdf_server::service::diagram::get_diagram_delta::DiagramDelta;
+use si_events::WorkspaceSnapshotAddress;
+
+async fn get_delta(
+    ctx: &DalContext,
+    client: &SdfTestClient,
+    since: Option<WorkspaceSnapshotAddress>,
+) -> DiagramDelta {
+    let mut path = format!(
+        "/api/diagram/delta?visibility_change_set_pk={}",
+        ctx.change_set_id()
+    );
+    if let Some(since) = since {
+        path.push_str(&format!("&since={since}"));
+    }
+
+    client.get(path).await.expect("could not get diagram delta")
+}
+
+#[sdf_test]
+async fn diagram_delta_is_computed_from_the_version_the_client_has(
+    ctx: &mut DalContext,
+    client: SdfTestClient,
+) {
+    let brick =
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "brick")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // Without a version, the client gets the whole diagram
+    let first = get_delta(ctx, &client, None).await;
+    assert!(first.full);
+    assert_eq!(
+        vec![brick.id()],
+        first
+            .components
+            .added
+            .iter()
+            .map(|component| component.id)
+            .collect::<Vec<_>>()
+    );
+
+    // Nothing changed since the version the client has
+    let unchanged = get_delta(ctx, &client, Some(first.snapshot_id)).await;
+    assert!(!unchanged.full);
+    assert_eq!(first.snapshot_id, unchanged.snapshot_id);
+    assert!(unchanged.components.is_empty());
+    assert!(unchanged.edges.is_empty());
+    assert!(unchanged.views.is_empty());
+
+    let plate =
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "plate")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // Only the new component is sent
+    let second = get_delta(ctx, &client, Some(first.snapshot_id)).await;
+    assert!(!second.full);
+    assert_ne!(first.snapshot_id, second.snapshot_id);
+    assert_eq!(
+        vec![plate.id()],
+        second
+            .components
+            .added
+            .iter()
+            .map(|component| component.id)
+            .collect::<Vec<_>>()
+    );
+    assert!(second.components.removed.is_empty());
+
+    // A version nobody handed out gets the whole diagram again
+    let unknown = get_delta(
+        ctx,
+        &client,
+        Some(WorkspaceSnapshotAddress::new(b"unknown")),
+    )
+    .await;
+    assert!(unknown.full);
+    assert_eq!(2, unknown.components.added.len());
+}
@@ -0,0 +1,39 @@
+use dal::{diagram::view::View, DalContext};
+use dal_test::{sdf_test, SdfTestClient};
+use serde_json::{json, Value};
+
+#[sdf_test]
+async fn dry_run_commits_nothing(ctx: &mut DalContext, client: SdfTestClient) {
+    let response: Value = client
+        .post(
+            format!(
+                "/api/v2/workspaces/{}/change-sets/{}/views?dry_run=true",
+                ctx.workspace_pk().expect("could not get workspace pk"),
+                ctx.change_set_id(),
+            ),
+            &json!({ "name": "dry" }),
+        )
+        .await
+        .expect("could not dry run create view");
+
+    // The would-be response is returned along with what would have been committed
+    assert_eq!(json!(true), response["dryRun"]);
+    assert_eq!(json!("dry"), response["response"]["name"]);
+    assert!(
+        response["summary"]["commits"]
+            .as_u64()
+            .expect("commits is not a number")
+            >= 1
+    );
+    assert!(response["summary"]["mutations"]["newNodes"]
+        .as_object()
+        .is_some_and(|new_nodes| !new_nodes.is_empty()));
+
+    ctx.update_snapshot_to_visibility()
+        .await
+        .expect("could not update snapshot to visibility");
+    assert!(View::find_by_name(ctx, "dry")
+        .await
+        .expect("could not find view")
+        .is_none());
+}
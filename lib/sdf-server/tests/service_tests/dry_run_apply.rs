@@ -0,0 +1,112 @@
+use dal::{
+    action::{Action, ActionState},
+    ChangeSet, ChangeSetStatus, DalContext,
+};
+use dal_test::{
+    helpers::{create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers},
+    sdf_test, SdfTestClient,
+};
+use serde_json::{json, Value};
+
+#[sdf_test]
+async fn dry_run_apply_lists_actions_without_applying(ctx: &mut DalContext, client: SdfTestClient) {
+    let component = create_component_for_default_schema_name_in_default_view(ctx, "swifty", "tes")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    let action_ids = Action::find_for_component_id(ctx, component.id())
+        .await
+        .expect("could not find actions");
+    assert_eq!(1, action_ids.len());
+
+    let response: Value = client
+        .post(
+            format!(
+                "/api/v2/workspaces/{}/change-sets/{}/apply/dry_run",
+                ctx.workspace_pk().expect("could not get workspace pk"),
+                ctx.change_set_id(),
+            ),
+            &json!({}),
+        )
+        .await
+        .expect("could not dry run apply");
+
+    assert_eq!(json!(ctx.change_set_id()), response["changeSetId"]);
+    assert_eq!(json!(false), response["approvedForApply"]);
+    let actions = response["actions"]
+        .as_array()
+        .expect("actions is not an array");
+    assert_eq!(1, actions.len());
+    assert_eq!(json!(action_ids[0]), actions[0]["id"]);
+    assert_eq!(json!("Create"), actions[0]["kind"]);
+    assert_eq!(json!(component.id()), actions[0]["componentId"]);
+    assert_eq!(json!("tes"), actions[0]["componentName"]);
+    assert_eq!(json!([]), actions[0]["dependentOn"]);
+    assert_eq!(json!(1), response["size"]["metrics"]["componentsTouched"]);
+    assert_eq!(json!(1), response["size"]["metrics"]["actionsQueued"]);
+    assert_eq!(json!([]), response["size"]["warnings"]);
+    assert_eq!(json!([]), response["size"]["blocking"]);
+
+    // Nothing was applied: the change set is still open and HEAD has no actions
+    let change_set = ChangeSet::find(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set")
+        .expect("change set not found");
+    assert_eq!(ChangeSetStatus::Open, change_set.status);
+
+    let head_ctx = ctx
+        .clone_with_head()
+        .await
+        .expect("could not get head context");
+    assert!(Action::list_topologically(&head_ctx)
+        .await
+        .expect("could not list actions")
+        .is_empty());
+}
+
+#[sdf_test]
+async fn plan_simulates_queued_actions(ctx: &mut DalContext, client: SdfTestClient) {
+    let component = create_component_for_default_schema_name_in_default_view(ctx, "swifty", "tes")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    let action_ids = Action::find_for_component_id(ctx, component.id())
+        .await
+        .expect("could not find actions");
+    assert_eq!(1, action_ids.len());
+
+    let response: Value = client
+        .post(
+            format!(
+                "/api/v2/workspaces/{}/change-sets/{}/plan",
+                ctx.workspace_pk().expect("could not get workspace pk"),
+                ctx.change_set_id(),
+            ),
+            &json!({}),
+        )
+        .await
+        .expect("could not plan");
+
+    let components = response["components"]
+        .as_array()
+        .expect("components is not an array");
+    assert_eq!(1, components.len());
+    assert_eq!(json!(component.id()), components[0]["componentId"]);
+    let entries = components[0]["entries"]
+        .as_array()
+        .expect("entries is not an array");
+    assert_eq!(1, entries.len());
+    assert_eq!(json!(action_ids[0]), entries[0]["actionId"]);
+    assert_eq!(json!("Create"), entries[0]["kind"]);
+    assert_eq!(Value::Null, entries[0]["currentPayload"]);
+
+    // Simulating leaves the action queued
+    let action = Action::get_by_id(ctx, action_ids[0])
+        .await
+        .expect("could not get action");
+    assert_eq!(ActionState::Queued, action.state());
+}
@@ -0,0 +1,94 @@
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use rust_embed::RustEmbed;
+use sdf_server::embedded_web::with_embedded_assets;
+use tower::ServiceExt;
+
+const INDEX_HTML: &str = include_str!("../fixtures/embedded_web/index.html");
+
+#[derive(RustEmbed)]
+#[folder = "tests/fixtures/embedded_web"]
+struct Fixture;
+
+async fn get(path: &str, if_none_match: Option<&str>) -> axum::response::Response {
+    let mut request = Request::builder().uri(path);
+    if let Some(if_none_match) = if_none_match {
+        request = request.header(header::IF_NONE_MATCH, if_none_match);
+    }
+    with_embedded_assets::<Fixture>(Router::new())
+        .oneshot(
+            request
+                .body(Body::empty())
+                .expect("could not build request"),
+        )
+        .await
+        .expect("could not send request")
+}
+
+async fn body(response: axum::response::Response) -> String {
+    let bytes = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("could not read body");
+    String::from_utf8(bytes.to_vec()).expect("body is not utf-8")
+}
+
+fn header_value(response: &axum::response::Response, name: header::HeaderName) -> &str {
+    response
+        .headers()
+        .get(name)
+        .expect("header not found")
+        .to_str()
+        .expect("header is not a string")
+}
+
+#[tokio::test]
+async fn serves_index_for_root_and_app_routes() {
+    for path in ["/", "/w/01HRFEV0S23R1G23RP75QQDCA7/head/c", "/index.html"] {
+        let response = get(path, None).await;
+        assert_eq!(StatusCode::OK, response.status(), "{path}");
+        assert_eq!("no-cache", header_value(&response, header::CACHE_CONTROL));
+        assert!(header_value(&response, header::CONTENT_TYPE).starts_with("text/html"));
+        assert_eq!(INDEX_HTML, body(response).await);
+    }
+}
+
+#[tokio::test]
+async fn caches_fingerprinted_assets_forever() {
+    let response = get("/assets/index-3f1c2a.js", None).await;
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(
+        "public, max-age=31536000, immutable",
+        header_value(&response, header::CACHE_CONTROL)
+    );
+    assert!(header_value(&response, header::CONTENT_TYPE).contains("javascript"));
+}
+
+#[tokio::test]
+async fn revalidates_with_etag() {
+    let response = get("/", None).await;
+    let etag = header_value(&response, header::ETAG).to_owned();
+
+    let response = get("/", Some(&etag)).await;
+    assert_eq!(StatusCode::NOT_MODIFIED, response.status());
+    assert_eq!(etag, header_value(&response, header::ETAG));
+    assert!(body(response).await.is_empty());
+
+    let response = get("/", Some("\"stale\"")).await;
+    assert_eq!(StatusCode::OK, response.status());
+}
+
+#[tokio::test]
+async fn does_not_answer_missing_files_or_api_routes_with_the_app() {
+    for path in [
+        "/assets/missing-123abc.js",
+        "/favicon.ico",
+        "/api",
+        "/api/unknown",
+    ] {
+        let response = get(path, None).await;
+        assert_eq!(StatusCode::NOT_FOUND, response.status(), "{path}");
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+
+use dal::DalContext;
+use dal_test::{sdf_test, SdfTestClient};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+
+fn funcs_path(ctx: &DalContext) -> String {
+    format!(
+        "/api/v2/workspaces/{}/change-sets/{}/funcs",
+        ctx.workspace_pk().expect("could not get workspace pk"),
+        ctx.change_set_id(),
+    )
+}
+
+fn func_ids(funcs: &Value) -> Vec<String> {
+    funcs
+        .as_array()
+        .expect("funcs is not an array")
+        .iter()
+        .map(|func| {
+            func["funcId"]
+                .as_str()
+                .expect("func id is not a string")
+                .to_owned()
+        })
+        .collect()
+}
+
+#[sdf_test]
+async fn list_funcs_pages_through_every_func(ctx: &mut DalContext, client: SdfTestClient) {
+    let path = funcs_path(ctx);
+
+    // Without pagination, the plain list is returned
+    let all: Value = client.get(&path).await.expect("could not list funcs");
+    let all_ids = func_ids(&all);
+    assert!(all_ids.len() > 5);
+
+    let mut paged_ids = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut query = vec![("limit", "5".to_owned())];
+        if let Some(cursor) = &cursor {
+            query.push(("cursor", cursor.clone()));
+        }
+        let page: Value = client
+            .request(Method::GET, &path)
+            .query(&query)
+            .send()
+            .await
+            .expect("could not list funcs")
+            .json()
+            .await
+            .expect("response is not valid json");
+
+        assert_eq!(all_ids.len() as u64, page["totalCount"]);
+        let ids = func_ids(&page["funcs"]);
+        assert!(ids.len() <= 5);
+        paged_ids.extend(ids);
+
+        match page["nextCursor"].as_str() {
+            Some(next_cursor) => cursor = Some(next_cursor.to_owned()),
+            None => break,
+        }
+    }
+
+    assert_eq!(all_ids.len(), paged_ids.len());
+    assert_eq!(
+        all_ids.into_iter().collect::<HashSet<_>>(),
+        paged_ids.into_iter().collect::<HashSet<_>>()
+    );
+}
+
+#[sdf_test]
+async fn list_funcs_filters_and_sorts(ctx: &mut DalContext, client: SdfTestClient) {
+    let path = funcs_path(ctx);
+
+    let page: Value = client
+        .request(Method::GET, &path)
+        .query(&[
+            ("kind", "Attribute"),
+            ("sort", "name"),
+            ("order", "desc"),
+            ("limit", "500"),
+        ])
+        .send()
+        .await
+        .expect("could not list funcs")
+        .json()
+        .await
+        .expect("response is not valid json");
+
+    let funcs = page["funcs"].as_array().expect("funcs is not an array");
+    assert!(!funcs.is_empty());
+    assert_eq!(funcs.len() as u64, page["totalCount"]);
+    assert!(funcs.iter().all(|func| func["kind"] == "Attribute"));
+
+    let names: Vec<String> = funcs
+        .iter()
+        .map(|func| {
+            func["name"]
+                .as_str()
+                .expect("name is not a string")
+                .to_lowercase()
+        })
+        .collect();
+    let mut sorted = names.clone();
+    sorted.sort_by(|a, b| b.cmp(a));
+    assert_eq!(sorted, names);
+
+    let search: Value = client
+        .request(Method::GET, &path)
+        .query(&[("search", "SI:IDENTITY"), ("limit", "500")])
+        .send()
+        .await
+        .expect("could not list funcs")
+        .json()
+        .await
+        .expect("response is not valid json");
+    let search_funcs = search["funcs"].as_array().expect("funcs is not an array");
+    assert!(!search_funcs.is_empty());
+    assert!(search_funcs.iter().all(|func| func["name"]
+        .as_str()
+        .expect("name is not a string")
+        .to_lowercase()
+        .contains("si:identity")));
+}
+
+#[sdf_test]
+async fn list_funcs_rejects_malformed_cursor(ctx: &mut DalContext, client: SdfTestClient) {
+    // Cursors are opaque, a bare func id is not one
+    let response = client
+        .request(Method::GET, funcs_path(ctx))
+        .query(&[("cursor", dal::FuncId::generate().to_string())])
+        .send()
+        .await
+        .expect("could not list funcs");
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+}
+
+#[sdf_test]
+async fn get_code_returns_every_requested_func(ctx: &mut DalContext, client: SdfTestClient) {
+    let path = funcs_path(ctx);
+    let all: Value = client.get(&path).await.expect("could not list funcs");
+    let ids: Vec<String> = func_ids(&all).into_iter().take(3).collect();
+    assert_eq!(3, ids.len());
+
+    let code_path = format!("{path}/code");
+    let returned_ids = |codes: Value| {
+        codes
+            .as_array()
+            .expect("codes is not an array")
+            .iter()
+            .map(|code| {
+                code["funcId"]
+                    .as_str()
+                    .expect("func id is not a string")
+                    .to_owned()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Both the bracketed and the plain form of the repeated param are accepted, and duplicates
+    // are returned once
+    let query: Vec<(&str, &str)> = vec![
+        ("id[]", ids[0].as_str()),
+        ("id", ids[1].as_str()),
+        ("id[]", ids[2].as_str()),
+        ("id", ids[0].as_str()),
+    ];
+    let codes: Value = client
+        .request(Method::GET, &code_path)
+        .query(&query)
+        .send()
+        .await
+        .expect("could not get code")
+        .json()
+        .await
+        .expect("response is not valid json");
+    assert_eq!(ids, returned_ids(codes));
+
+    let codes: Value = client
+        .post(
+            &code_path,
+            &json!({ "ids": [&ids[0], &ids[1], &ids[2], &ids[1]] }),
+        )
+        .await
+        .expect("could not get code");
+    assert_eq!(ids, returned_ids(codes));
+
+    let response = client
+        .request(Method::GET, &code_path)
+        .query(&[("id[]", "not a func id")])
+        .send()
+        .await
+        .expect("could not get code");
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+}
@@ -0,0 +1,80 @@
+use axum::{http::Method, Extension, Router};
+use dal::DalContext;
+use dal_test::{
+    helpers::{create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers},
+    sdf_test, AuthTokenRef,
+};
+use sdf_server::service::v2::graphql::build_schema;
+use serde_json::{json, Value};
+
+use crate::service_tests::api_request_auth_json;
+
+const QUERY: &str = "{
+    schemas(first: 500) {
+        edges {
+            node {
+                name
+                components {
+                    nodes { name schemaName qualifications { total failed } }
+                }
+            }
+        }
+    }
+    components(first: 1) {
+        pageInfo { hasNextPage endCursor }
+        nodes { name }
+    }
+}";
+
+#[sdf_test]
+async fn graphql_reads_components_grouped_by_schema(
+    ctx: &mut DalContext,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    for name in ["brick", "plate"] {
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", name)
+            .await
+            .expect("could not create component");
+    }
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let uri = format!(
+        "/api/v2/workspaces/{}/change-sets/{}/graphql",
+        ctx.workspace_pk().expect("could not get workspace pk"),
+        ctx.change_set_id(),
+    );
+    let app = app.layer(Extension(build_schema()));
+    let response: Value = api_request_auth_json(
+        app,
+        Method::POST,
+        uri,
+        auth_token,
+        &json!({ "query": QUERY }),
+    )
+    .await;
+    assert_eq!(None, response.get("errors"));
+
+    let schema = response["data"]["schemas"]["edges"]
+        .as_array()
+        .expect("schemas is not an array")
+        .iter()
+        .map(|edge| &edge["node"])
+        .find(|node| node["name"] == "small odd lego")
+        .expect("schema not found");
+    assert_eq!(
+        vec!["brick", "plate"],
+        schema["components"]["nodes"]
+            .as_array()
+            .expect("components is not an array")
+            .iter()
+            .map(|node| node["name"].as_str().expect("name is not a string"))
+            .collect::<Vec<_>>()
+    );
+
+    let components = &response["data"]["components"];
+    assert_eq!(json!([{ "name": "brick" }]), components["nodes"]);
+    assert_eq!(json!(true), components["pageInfo"]["hasNextPage"]);
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware,
+    response::Response,
+    routing::get,
+    Extension, Router,
+};
+use sdf_server::middleware::{load_shed_middleware, LoadShedConfig, LoadShedder, RouteClassBudget};
+use tokio::sync::Notify;
+use tower::ServiceExt;
+
+const BULK_PATH: &str = "/api/v2/workspaces/w/change-sets/c/apply";
+
+/// Lets a test hold a request in its handler until it is released.
+#[derive(Default)]
+struct Gate {
+    entered: Notify,
+    release: Notify,
+}
+
+async fn hold(Extension(gate): Extension<Arc<Gate>>) -> &'static str {
+    gate.entered.notify_one();
+    gate.release.notified().await;
+    "held"
+}
+
+async fn fast() -> &'static str {
+    "fast"
+}
+
+fn budget(
+    max_concurrency: usize,
+    min_concurrency: usize,
+    latency_target_ms: u64,
+) -> RouteClassBudget {
+    RouteClassBudget {
+        max_concurrency,
+        min_concurrency,
+        latency_target_ms,
+    }
+}
+
+fn app(interactive: RouteClassBudget, bulk: RouteClassBudget, gate: Arc<Gate>) -> Router {
+    let shedder = LoadShedder::new(LoadShedConfig {
+        enabled: true,
+        interactive,
+        bulk,
+        ..Default::default()
+    });
+
+    Router::new()
+        .route("/api/hold", get(hold))
+        .route("/api/fast", get(fast))
+        .route("/api/readiness", get(fast))
+        .route(BULK_PATH, get(fast))
+        .layer(Extension(gate))
+        .layer(middleware::from_fn_with_state(
+            shedder,
+            load_shed_middleware,
+        ))
+}
+
+async fn send(app: &Router, path: &str) -> Response {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .uri(path)
+                .body(Body::empty())
+                .expect("could not build request"),
+        )
+        .await
+        .expect("could not send request")
+}
+
+#[tokio::test]
+async fn sheds_requests_over_the_budget_of_their_route_class() {
+    let gate = Arc::new(Gate::default());
+    let app = app(budget(1, 1, 60_000), budget(1, 1, 60_000), gate.clone());
+
+    let held = tokio::spawn({
+        let app = app.clone();
+        async move { send(&app, "/api/hold").await }
+    });
+    gate.entered.notified().await;
+
+    // The interactive budget is used up by the held request
+    let response = send(&app, "/api/fast").await;
+    assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
+    assert_eq!(
+        Some("1"),
+        response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok()),
+    );
+
+    // Bulk requests have a budget of their own and readiness is never shed
+    assert_eq!(StatusCode::OK, send(&app, BULK_PATH).await.status());
+    assert_eq!(StatusCode::OK, send(&app, "/api/readiness").await.status());
+
+    // Completing the held request frees its slot
+    gate.release.notify_one();
+    let response = held.await.expect("held request panicked");
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(StatusCode::OK, send(&app, "/api/fast").await.status());
+}
+
+#[tokio::test]
+async fn shrinks_the_budget_while_requests_are_over_the_latency_target() {
+    let gate = Arc::new(Gate::default());
+    let app = app(budget(2, 1, 0), budget(1, 1, 60_000), gate.clone());
+
+    // Every request is over a latency target of zero, so the limit shrinks from two to one
+    assert_eq!(StatusCode::OK, send(&app, "/api/fast").await.status());
+
+    let held = tokio::spawn({
+        let app = app.clone();
+        async move { send(&app, "/api/hold").await }
+    });
+    gate.entered.notified().await;
+
+    assert_eq!(
+        StatusCode::SERVICE_UNAVAILABLE,
+        send(&app, "/api/fast").await.status()
+    );
+
+    gate.release.notify_one();
+    let response = held.await.expect("held request panicked");
+    assert_eq!(StatusCode::OK, response.status());
+}
@@ -0,0 +1,101 @@
+use base64::{engine::general_purpose, Engine};
+use chrono::{Duration, Utc};
+use dal::{DalContext, Func, FuncBackendKind, FuncBackendResponseType};
+use dal_test::{helpers::ChangeSetTestHelpers, sdf_test, SdfTestClient};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+
+#[sdf_test]
+async fn provider_credentials_are_granted_to_func_code(
+    ctx: &mut DalContext,
+    client: SdfTestClient,
+) {
+    let func = Func::new(
+        ctx,
+        "import",
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        false,
+        false,
+        FuncBackendKind::Management,
+        FuncBackendResponseType::Management,
+        Some("main"),
+        Some(general_purpose::STANDARD_NO_PAD.encode("async function main() { return {}; }")),
+    )
+    .await
+    .expect("could not create func");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    let path = format!(
+        "/api/v2/workspaces/{}/provider-credentials",
+        ctx.workspace_pk().expect("could not get workspace pk"),
+    );
+
+    // Bearer tokens can't be minted from, so they must be stored with an expiry
+    let response = client
+        .request(Method::POST, &path)
+        .json(&json!({
+            "name": "ci token",
+            "material": { "kind": "token", "token": "t0ken" },
+        }))
+        .send()
+        .await
+        .expect("could not create provider credential");
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let credential: Value = client
+        .post(
+            &path,
+            &json!({
+                "name": "ci token",
+                "material": { "kind": "token", "token": "t0ken" },
+                "expiresAt": Utc::now() + Duration::days(30),
+            }),
+        )
+        .await
+        .expect("could not create provider credential");
+    assert_eq!(json!("token"), credential["kind"]);
+    // The material is never returned
+    assert!(!credential.to_string().contains("t0ken"));
+    let credential_id = credential["id"].as_str().expect("id is not a string");
+
+    let credentials: Value = client
+        .get(&path)
+        .await
+        .expect("could not list provider credentials");
+    assert_eq!(json!([credential]), credentials["credentials"]);
+
+    let consent: Value = client
+        .post(
+            format!("{path}/{credential_id}/consents"),
+            &json!({ "changeSetId": ctx.change_set_id(), "funcId": func.id }),
+        )
+        .await
+        .expect("could not grant consent");
+    assert_eq!(json!(func.id), consent["funcId"]);
+    assert!(consent["funcCodeHash"].is_string());
+
+    let consents: Value = client
+        .get(format!("{path}/{credential_id}/consents"))
+        .await
+        .expect("could not list consents");
+    assert_eq!(json!([consent]), consents["consents"]);
+
+    let consent_id = consent["id"].as_str().expect("id is not a string");
+    let response = client
+        .request(
+            Method::DELETE,
+            format!("{path}/{credential_id}/consents/{consent_id}"),
+        )
+        .send()
+        .await
+        .expect("could not revoke consent");
+    assert_eq!(StatusCode::OK, response.status());
+    let consents: Value = client
+        .get(format!("{path}/{credential_id}/consents"))
+        .await
+        .expect("could not list consents");
+    assert_eq!(json!([]), consents["consents"]);
+}
@@ -0,0 +1,56 @@
+use dal_test::{sdf_test, SdfTestClient};
+use reqwest::{Method, StatusCode};
+use sdf_server::service::readiness::{ReadinessResponse, ReadinessStatus};
+
+#[sdf_test]
+async fn readiness_reports_every_dependency(client: SdfTestClient) {
+    let response = client
+        .request(Method::GET, "/api/readiness")
+        .send()
+        .await
+        .expect("could not call readiness");
+    assert_eq!(StatusCode::OK, response.status());
+
+    let readiness: ReadinessResponse = response
+        .json()
+        .await
+        .expect("could not deserialize readiness response");
+
+    // Every dependency is up in tests, although a slow check may still degrade one of them
+    assert_ne!(ReadinessStatus::Failed, readiness.status);
+    for (name, dependency) in [
+        ("pg", &readiness.dependencies.pg),
+        ("nats", &readiness.dependencies.nats),
+        ("job queue", &readiness.dependencies.job_queue),
+    ] {
+        assert_ne!(
+            ReadinessStatus::Failed,
+            dependency.status,
+            "{name} failed: {:?}",
+            dependency.message,
+        );
+        if dependency.status == ReadinessStatus::Ok {
+            assert_eq!(None, dependency.message, "{name} is ok with a message");
+        }
+    }
+    if readiness.status == ReadinessStatus::Ok {
+        assert!([
+            &readiness.dependencies.pg,
+            &readiness.dependencies.nats,
+            &readiness.dependencies.job_queue,
+        ]
+        .iter()
+        .all(|dependency| dependency.status == ReadinessStatus::Ok));
+    }
+}
+
+#[sdf_test]
+async fn readiness_does_not_require_authentication(client: SdfTestClient) {
+    let response = reqwest::Client::new()
+        .get(format!("{}/api/readiness", client.base_url()))
+        .send()
+        .await
+        .expect("could not call readiness");
+
+    assert_eq!(StatusCode::OK, response.status());
+}
@@ -0,0 +1,15 @@
+use dal_test::{sdf_test, SdfTestClient, WorkspaceSignup};
+
+#[sdf_test]
+async fn whoami(client: SdfTestClient, nw: WorkspaceSignup) {
+    let response: serde_json::Value = client
+        .get("/api/whoami")
+        .await
+        .expect("could not call whoami");
+
+    assert_eq!(
+        serde_json::json!(nw.workspace.pk()),
+        response["workspaceId"]
+    );
+    assert_eq!(serde_json::json!(nw.user.pk()), response["userId"]);
+}
@@ -0,0 +1,142 @@
+use dal::{
+    diagram::view::View,
+    workspace_role::{WorkspaceOperation, WorkspaceRole},
+    DalContext, FuncId,
+};
+use dal_test::{sdf_test, SdfTestClient, WorkspaceSignup};
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+async fn set_role(ctx: &DalContext, nw: &WorkspaceSignup, role: WorkspaceRole) {
+    WorkspaceRole::set_for_user(ctx, *nw.workspace.pk(), nw.user.pk(), role)
+        .await
+        .expect("could not set role");
+    ctx.commit_no_rebase().await.expect("could not commit");
+}
+
+async fn assert_forbidden(
+    client: &SdfTestClient,
+    method: Method,
+    path: String,
+    body: serde_json::Value,
+    operation: WorkspaceOperation,
+) {
+    let response = client
+        .request(method, &path)
+        .json(&body)
+        .send()
+        .await
+        .expect("could not send request");
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        response.status(),
+        "{operation} was not rejected at {path}"
+    );
+}
+
+#[sdf_test]
+async fn viewer_is_rejected_from_every_gated_route(
+    ctx: &DalContext,
+    nw: &WorkspaceSignup,
+    client: &SdfTestClient,
+) {
+    let view_id = View::get_id_for_default(ctx)
+        .await
+        .expect("could not get default view");
+    set_role(ctx, nw, WorkspaceRole::Viewer).await;
+
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+    let path = format!(
+        "/api/v2/workspaces/{workspace_pk}/change-sets/{}",
+        ctx.change_set_id()
+    );
+    // The role is checked before the func is looked up, so any id will do
+    let func_path = format!("{path}/funcs/{}", FuncId::new());
+
+    let cases = [
+        (
+            Method::DELETE,
+            format!("{path}/views/{view_id}/erase_components"),
+            json!({ "componentIds": [] }),
+            WorkspaceOperation::DeleteComponent,
+        ),
+        (
+            Method::POST,
+            "/api/diagram/delete_components".to_string(),
+            json!({
+                "componentIds": [],
+                "forceErase": false,
+                "visibility_change_set_pk": ctx.change_set_id(),
+            }),
+            WorkspaceOperation::DeleteComponent,
+        ),
+        (
+            Method::PUT,
+            format!("{func_path}/code"),
+            json!({ "code": "" }),
+            WorkspaceOperation::EditFunc,
+        ),
+        (
+            Method::DELETE,
+            func_path.clone(),
+            json!({}),
+            WorkspaceOperation::EditFunc,
+        ),
+        (
+            Method::POST,
+            format!("{func_path}/arguments"),
+            json!({}),
+            WorkspaceOperation::EditFunc,
+        ),
+        (
+            Method::PUT,
+            format!("{func_path}/bindings"),
+            json!({}),
+            WorkspaceOperation::EditFunc,
+        ),
+        (
+            Method::POST,
+            format!("{path}/apply"),
+            json!({}),
+            WorkspaceOperation::ApplyChangeSet,
+        ),
+    ];
+    for (method, path, body, operation) in cases {
+        assert_forbidden(client, method, path, body, operation).await;
+    }
+}
+
+#[sdf_test]
+async fn editor_is_rejected_from_owner_routes(
+    ctx: &DalContext,
+    nw: &WorkspaceSignup,
+    client: &SdfTestClient,
+) {
+    set_role(ctx, nw, WorkspaceRole::Editor).await;
+
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+    assert_forbidden(
+        client,
+        Method::POST,
+        format!("/api/v2/workspaces/{workspace_pk}/change-sets/approval_policy"),
+        json!({ "requiredApprovals": 1 }),
+        WorkspaceOperation::SetApprovalPolicy,
+    )
+    .await;
+
+    // Editors may still edit funcs, so they get past the role check to the missing func
+    let response = client
+        .request(
+            Method::PUT,
+            format!(
+                "/api/v2/workspaces/{workspace_pk}/change-sets/{}/funcs/{}/code",
+                ctx.change_set_id(),
+                FuncId::new()
+            ),
+        )
+        .json(&json!({ "code": "" }))
+        .send()
+        .await
+        .expect("could not send request");
+    assert_ne!(StatusCode::FORBIDDEN, response.status());
+}
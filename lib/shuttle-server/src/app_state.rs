@@ -1,10 +1,13 @@
 use si_data_nats::{jetstream::Context, Subject};
 use tokio_util::sync::CancellationToken;
 
+use crate::sequence::ShuttledSequence;
+
 #[derive(Debug, Clone)]
 pub(crate) struct AppState {
     pub(crate) context: Context,
     pub(crate) destination_subject: Subject,
+    pub(crate) sequence: ShuttledSequence,
     pub(crate) self_shutdown_token: CancellationToken,
 }
 
@@ -12,11 +15,13 @@ impl AppState {
     pub(crate) fn new(
         context: Context,
         destination_subject: Subject,
+        sequence: ShuttledSequence,
         self_shutdown_token: CancellationToken,
     ) -> Self {
         Self {
             context,
             destination_subject,
+            sequence,
             self_shutdown_token,
         }
     }
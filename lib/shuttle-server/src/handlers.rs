@@ -5,10 +5,10 @@ use naxum::{
 };
 use shuttle_core::DESTINATION_SUBJECT_SUFFIX_HEADER_KEY;
 use si_data_nats::{
-    async_nats::{self, jetstream},
+    async_nats::{self, header::NATS_MESSAGE_ID, jetstream},
     Subject,
 };
-use telemetry::tracing::error;
+use telemetry::tracing::{error, trace};
 use telemetry_nats::propagation;
 use thiserror::Error;
 
@@ -17,6 +17,8 @@ use crate::{app_state::AppState, FINAL_MESSAGE_HEADER_KEY};
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub(crate) enum HandlerError {
+    #[error("error parsing message info: {0}")]
+    MessageInfo(#[source] async_nats::Error),
     #[error("error publishing message: {0}")]
     NatsPublish(#[from] async_nats::jetstream::context::PublishError),
 }
@@ -48,16 +50,31 @@ pub(crate) async fn default(
         None => state.destination_subject,
     };
 
+    let info = msg.info().map_err(HandlerError::MessageInfo)?;
+    let stream_sequence = info.stream_sequence;
+
+    // An ordered consumer re-created after a disconnect redelivers from where it believes it
+    // stopped, which can include messages that were already shuttled
+    if state.sequence.is_shuttled(stream_sequence) {
+        trace!(stream_sequence, "skipping already shuttled message");
+        return Ok(());
+    }
+
+    // Lets a destination stream drop a message published again before its sequence was recorded
+    let mut headers = propagation::empty_injected_headers();
+    headers.insert(
+        NATS_MESSAGE_ID,
+        format!("{}.{stream_sequence}", info.stream),
+    );
+
     let ack = state
         .context
-        .publish_with_headers(
-            destination_subject,
-            propagation::empty_injected_headers(),
-            msg.payload.to_owned(),
-        )
+        .publish_with_headers(destination_subject, headers, msg.payload.to_owned())
         .await?;
     ack.await?;
 
+    state.sequence.record(stream_sequence);
+
     Ok(())
 }
 
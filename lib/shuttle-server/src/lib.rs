@@ -33,10 +33,13 @@ use futures::Future;
 use middleware::DeleteMessageOnSuccess;
 use naxum::{
     handler::Handler,
-    middleware::{post_process::PostProcessLayer, trace::TraceLayer},
+    middleware::{
+        concurrency_limit::ConcurrencyLimitLayer, post_process::PostProcessLayer, trace::TraceLayer,
+    },
     response::{IntoResponse, Response},
     ServiceBuilder, ServiceExt, TowerServiceExt,
 };
+use sequence::ShuttledSequence;
 use si_data_nats::{
     async_nats::{
         self,
@@ -51,14 +54,19 @@ use si_events::ulid::Ulid;
 use telemetry::prelude::*;
 use telemetry::tracing::error;
 use thiserror::Error;
+use tokio::task::JoinHandle;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 mod app_state;
 mod handlers;
 mod middleware;
+mod sequence;
 
 pub use shuttle_core::FINAL_MESSAGE_HEADER_KEY;
 
+/// The most messages shuttled at once. Further messages wait until one is done.
+const MAX_IN_FLIGHT_MESSAGES: usize = 64;
+
 #[allow(missing_docs)]
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -69,6 +77,10 @@ pub enum ShuttleError {
     AsyncNatsRequest(#[from] async_nats::error::Error<RequestErrorKind>),
     #[error("async nats stream error: {0}")]
     AsyncNatsStream(#[from] async_nats::error::Error<StreamErrorKind>),
+    #[error("create key value error: {0}")]
+    CreateKeyValue(#[from] async_nats::jetstream::context::CreateKeyValueError),
+    #[error("key value entry error: {0}")]
+    KeyValueEntry(#[from] async_nats::jetstream::kv::EntryError),
     #[error("naxum error: {0}")]
     Naxum(#[source] io::Error),
 }
@@ -113,6 +125,7 @@ impl Shuttle {
 
         let deliver_subject = nats.new_inbox();
         let connection_metadata = nats.metadata_clone();
+        let subject_prefix = nats.metadata().subject_prefix().map(|s| s.to_owned());
         let context = jetstream::new(nats);
 
         let consumer_name = format!("shuttle-{}", Ulid::new());
@@ -123,6 +136,15 @@ impl Shuttle {
             .name
             .to_owned();
 
+        // Resume after the last message shuttled for the same source, if any
+        let sequence = ShuttledSequence::load(
+            &context,
+            subject_prefix.as_deref(),
+            &source_stream_name,
+            &source_subject,
+        )
+        .await?;
+
         let incoming = {
             limits_based_source_stream
                 .create_consumer(async_nats::jetstream::consumer::push::OrderedConfig {
@@ -139,6 +161,7 @@ impl Shuttle {
         let state = crate::app_state::AppState::new(
             context.clone(),
             destination_subject.clone(),
+            sequence.clone(),
             self_shutdown_token.clone(),
         );
 
@@ -154,9 +177,13 @@ impl Shuttle {
                 PostProcessLayer::new()
                     .on_success(DeleteMessageOnSuccess::new(limits_based_source_stream)),
             )
+            .layer(ConcurrencyLimitLayer::new(MAX_IN_FLIGHT_MESSAGES).name("shuttle"))
             .service(crate::handlers::default.with_state(state))
             .map_response(Response::into_response);
 
+        let flush_token = CancellationToken::new();
+        let flush_task = sequence.spawn_flush_task(&tracker, flush_token.clone());
+
         let inner = naxum::serve(incoming, app.into_make_service())
             .with_graceful_shutdown(naxum::wait_on_cancelled(self_shutdown_token));
 
@@ -167,6 +194,9 @@ impl Shuttle {
                 consumer_name,
                 source_stream_name,
                 context,
+                sequence,
+                flush_token,
+                flush_task,
                 tracker,
             },
             inner: Box::new(inner.into_future()),
@@ -176,7 +206,11 @@ impl Shuttle {
     /// Fallibly awaits the inner naxum task.
     #[instrument(name = "shuttle.try_run", level = "trace", skip_all)]
     pub async fn try_run(self) -> Result<()> {
-        self.inner.await.map_err(ShuttleError::Naxum)?;
+        if let Err(err) = self.inner.await {
+            // Keep the sequence shuttled so far for the next shuttle of the same source
+            self.shutdown_cleanup_toolkit.flush_token.cancel();
+            return Err(ShuttleError::Naxum(err));
+        }
         trace!(%self.source_subject, %self.destination_subject, "shuttle inner loop exited, now performing cleanup");
         self.shutdown_cleanup_toolkit.spawn_cleanup_task()?;
         trace!(%self.source_subject, %self.destination_subject, "shuttle main loop shutdown complete");
@@ -189,6 +223,9 @@ struct ShuttleShutdownCleanupToolkit {
     consumer_name: String,
     source_stream_name: String,
     context: Context,
+    sequence: ShuttledSequence,
+    flush_token: CancellationToken,
+    flush_task: JoinHandle<()>,
     tracker: TaskTracker,
 }
 
@@ -207,6 +244,14 @@ impl ShuttleShutdownCleanupToolkit {
             {
                 error!(?err, "error deleting consumer from stream");
             }
+            // The final flush must land before the purge, or it would outlive the shuttle
+            self.flush_token.cancel();
+            if let Err(err) = self.flush_task.await {
+                error!(?err, "error flushing shuttled sequence");
+            }
+            if let Err(err) = self.sequence.purge().await {
+                error!(?err, "error purging shuttled sequence");
+            }
         });
         Ok(())
     }
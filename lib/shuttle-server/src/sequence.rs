@@ -0,0 +1,229 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use si_data_nats::{async_nats::jetstream::kv, jetstream::Context, Subject};
+use telemetry::prelude::*;
+use tokio::task::JoinHandle;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+const BUCKET_NAME: &str = "SHUTTLE_SEQUENCES";
+// Sequences of shuttles which never saw their final message are left to expire
+const BUCKET_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+// Messages shuttled since the last flush are dropped as duplicates by the destination stream, so
+// this only needs to stay well within its duplicate window
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The last source stream sequence shuttled to the destination subject, persisted in a NATS KV
+/// bucket so that it outlives consumer re-creations and restarts.
+///
+/// A shuttle is keyed on its source stream and subject, so that a shuttle re-created for the same
+/// source picks up where the last one stopped. Recorded sequences are kept in memory and written
+/// to the bucket periodically by [`ShuttledSequence::spawn_flush_task`].
+#[derive(Clone, Debug)]
+pub(crate) struct ShuttledSequence {
+    store: kv::Store,
+    key: String,
+    progress: Arc<Progress>,
+}
+
+impl ShuttledSequence {
+    pub(crate) async fn load(
+        context: &Context,
+        subject_prefix: Option<&str>,
+        source_stream_name: &str,
+        source_subject: &Subject,
+    ) -> crate::Result<Self> {
+        let store = context
+            .create_key_value(kv::Config {
+                bucket: bucket_name(subject_prefix),
+                description: "Last shuttled source sequences".to_owned(),
+                history: 1,
+                max_age: BUCKET_MAX_AGE,
+                ..Default::default()
+            })
+            .await?;
+        let key = key_for(source_stream_name, source_subject);
+
+        let last = match store.get(&key).await? {
+            Some(value) => std::str::from_utf8(&value)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(|| {
+                    warn!(%key, "invalid shuttled sequence, shuttling from the start");
+                    0
+                }),
+            None => 0,
+        };
+
+        Ok(Self {
+            store,
+            key,
+            progress: Arc::new(Progress::new(last)),
+        })
+    }
+
+    /// Returns true if the message at the stream sequence was already shuttled.
+    pub(crate) fn is_shuttled(&self, stream_sequence: u64) -> bool {
+        self.progress.is_shuttled(stream_sequence)
+    }
+
+    /// Records the message at the stream sequence as shuttled.
+    pub(crate) fn record(&self, stream_sequence: u64) {
+        self.progress.record(stream_sequence);
+    }
+
+    /// Spawns a task writing recorded sequences to the bucket, flushing a last time once the token
+    /// is cancelled.
+    pub(crate) fn spawn_flush_task(
+        &self,
+        tracker: &TaskTracker,
+        token: CancellationToken,
+    ) -> JoinHandle<()> {
+        let sequence = self.clone();
+        tracker.spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = token.cancelled() => break,
+                    _ = interval.tick() => sequence.flush_or_warn().await,
+                }
+            }
+            sequence.flush_or_warn().await;
+        })
+    }
+
+    async fn flush_or_warn(&self) {
+        if let Some(last) = self.progress.unflushed() {
+            match self.store.put(&self.key, last.to_string().into()).await {
+                Ok(_) => self.progress.flushed(last),
+                Err(err) => {
+                    warn!(si.error.message = ?err, key = %self.key, "failed to flush shuttled sequence")
+                }
+            }
+        }
+    }
+
+    /// Forgets the sequence once the shuttle has seen its final message.
+    pub(crate) async fn purge(&self) -> Result<(), kv::PurgeError> {
+        self.store.purge(&self.key).await
+    }
+}
+
+#[derive(Debug)]
+struct Progress {
+    last: AtomicU64,
+    flushed: AtomicU64,
+}
+
+impl Progress {
+    fn new(last: u64) -> Self {
+        Self {
+            last: AtomicU64::new(last),
+            flushed: AtomicU64::new(last),
+        }
+    }
+
+    fn is_shuttled(&self, stream_sequence: u64) -> bool {
+        stream_sequence <= self.last.load(Ordering::Acquire)
+    }
+
+    fn record(&self, stream_sequence: u64) {
+        self.last.fetch_max(stream_sequence, Ordering::AcqRel);
+    }
+
+    // Returns the last recorded sequence if it was not flushed yet
+    fn unflushed(&self) -> Option<u64> {
+        let last = self.last.load(Ordering::Acquire);
+        (last > self.flushed.load(Ordering::Acquire)).then_some(last)
+    }
+
+    fn flushed(&self, stream_sequence: u64) {
+        self.flushed.fetch_max(stream_sequence, Ordering::AcqRel);
+    }
+}
+
+fn bucket_name(subject_prefix: Option<&str>) -> String {
+    match subject_prefix {
+        Some(prefix) => format!("{prefix}_{BUCKET_NAME}"),
+        None => BUCKET_NAME.to_owned(),
+    }
+}
+
+// KV keys only allow a subset of the characters valid in subjects, wildcards excluded. Anything
+// else is hex escaped behind an `_`, which is escaped itself so that distinct subjects never share
+// a key.
+fn key_for(source_stream_name: &str, source_subject: &Subject) -> String {
+    let mut key = String::new();
+    for byte in format!("{source_stream_name}.{source_subject}").bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'/' | b'=' | b'.' => {
+                key.push(byte as char)
+            }
+            _ => key.push_str(&format!("_{byte:02X}")),
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards_get_distinct_keys() {
+        let single = key_for("SOURCE", &Subject::from("source.some.*"));
+        let full = key_for("SOURCE", &Subject::from("source.some.>"));
+
+        assert_eq!("SOURCE.source.some._2A", single);
+        assert_eq!("SOURCE.source.some._3E", full);
+    }
+
+    #[test]
+    fn escapes_do_not_collide_with_literal_tokens() {
+        assert_ne!(
+            key_for("SOURCE", &Subject::from("source._2A")),
+            key_for("SOURCE", &Subject::from("source.*")),
+        );
+        assert_eq!(
+            "SOURCE.source_5F2A",
+            key_for("SOURCE", &Subject::from("source_2A"))
+        );
+    }
+
+    #[test]
+    fn recorded_sequences_are_shuttled() {
+        let progress = Progress::new(3);
+        assert!(progress.is_shuttled(3));
+        assert!(!progress.is_shuttled(4));
+
+        progress.record(5);
+        // Out of order completions never move the sequence back
+        progress.record(4);
+        assert!(progress.is_shuttled(4));
+        assert!(progress.is_shuttled(5));
+        assert!(!progress.is_shuttled(6));
+    }
+
+    #[test]
+    fn only_new_sequences_are_flushed() {
+        let progress = Progress::new(3);
+        assert_eq!(None, progress.unflushed());
+
+        progress.record(4);
+        progress.record(7);
+        assert_eq!(Some(7), progress.unflushed());
+
+        progress.flushed(7);
+        assert_eq!(None, progress.unflushed());
+
+        progress.record(8);
+        assert_eq!(Some(8), progress.unflushed());
+    }
+}
@@ -22,12 +22,21 @@ mod veritech;
 
 pub use sensitive_strings::SensitiveStrings;
 pub use symmetric::{
+    envelope::{SymmetricEnvelope, SymmetricEnvelopeStore, SYMMETRIC_ENVELOPE_VERSION},
     SymmetricCryptoError, SymmetricCryptoResult, SymmetricCryptoService,
     SymmetricCryptoServiceConfig, SymmetricCryptoServiceConfigFile, SymmetricKey, SymmetricNonce,
 };
 pub use veritech::{
     config::VeritechCryptoConfig,
-    decryption_key::{VeritechDecryptionKey, VeritechDecryptionKeyError},
+    decryption_key::{
+        VeritechDecryptionKey, VeritechDecryptionKeyError, VeritechDecryptionKeyLookup,
+    },
     encryption_key::{VeritechEncryptionKey, VeritechEncryptionKeyError},
     key_pair::{VeritechKeyPair, VeritechKeyPairError},
+    key_provider::{
+        key_provider_from_config, AwsKmsKeyProvider, CachedKeyProvider, VaultKeyProvider,
+        VeritechKeyProvider, VeritechKeyProviderConfig, VeritechKeyProviderError,
+        VeritechKeySource,
+    },
+    rotating_decryption_key::{RotatingVeritechDecryptionKey, VeritechDecryptionKeyRing},
 };
@@ -1,6 +1,6 @@
 //! Working with redacting sensitive substrings from content and output.
 
-use std::collections::HashSet;
+use std::{borrow::Cow, collections::HashSet};
 
 use si_std::SensitiveString;
 
@@ -17,8 +17,13 @@ impl SensitiveStrings {
     }
 
     /// Inserts a new "sensitive" substring, which will be redacted when using [`redact`].
+    ///
+    /// Empty strings are ignored, as they would otherwise match everywhere.
     pub fn insert(&mut self, value: impl Into<SensitiveString>) {
-        self.0.insert(value.into());
+        let value = value.into();
+        if !value.as_str().is_empty() {
+            self.0.insert(value);
+        }
     }
 
     /// Inserts multiple "sensitive" substrings from an iterator.
@@ -26,14 +31,12 @@ impl SensitiveStrings {
     where
         I: IntoIterator<Item = SensitiveString>,
     {
-        self.0.extend(iter)
+        iter.into_iter().for_each(|value| self.insert(value))
     }
 
     /// Returns whether or not the given string contains at least one sensitive substring.
     pub fn has_sensitive(&self, s: &str) -> bool {
-        self.0
-            .iter()
-            .any(|sensitive_s| s.contains(sensitive_s.as_str()))
+        self.variants().any(|sensitive_s| s.contains(&*sensitive_s))
     }
 
     /// Builds a new string with any "sensitive" substrings redacted.
@@ -41,22 +44,51 @@ impl SensitiveStrings {
     pub fn redact(&self, s: &str) -> String {
         let mut redacted = s.to_string();
 
-        for redacted_str in self.0.iter() {
+        for redacted_str in self.variants() {
             // Note: This brings a possibility of random substrings being matched out of context,
             // exposing that we have a secret by censoring it but trying to infer word boundary
             // might leak the plaintext credential which is arguably worse
-            if s.contains(redacted_str.as_str()) {
-                redacted = redacted.replace(redacted_str.as_str(), REDACTED_TXT);
+            if redacted.contains(&*redacted_str) {
+                redacted = redacted.replace(&*redacted_str, REDACTED_TXT);
             }
         }
 
         redacted
     }
+
+    /// Iterates over every sensitive substring, along with its JSON-escaped form when that
+    /// differs (e.g. when a function logs `JSON.stringify()` of an object holding a credential).
+    fn variants(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.0.iter().flat_map(|sensitive_s| {
+            let raw = sensitive_s.as_str();
+            let escaped = json_escape(raw);
+            let escaped = (escaped != raw).then_some(Cow::Owned(escaped));
+            std::iter::once(Cow::Borrowed(raw)).chain(escaped)
+        })
+    }
+}
+
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 impl From<HashSet<SensitiveString>> for SensitiveStrings {
     fn from(value: HashSet<SensitiveString>) -> Self {
-        Self(value)
+        let mut sensitive_strings = Self::default();
+        sensitive_strings.extend(value);
+        sensitive_strings
     }
 }
 
@@ -127,4 +159,32 @@ mod tests {
             sensitive_strings.redact("One pony said to the other pony: 'I have an apple.'")
         );
     }
+
+    #[test]
+    fn redact_ignores_empty() {
+        let mut sensitive_strings = SensitiveStrings::default();
+        sensitive_strings.insert("");
+
+        assert!(!sensitive_strings.has_sensitive("nothing changed"));
+        assert_eq!(
+            "nothing changed",
+            sensitive_strings.redact("nothing changed")
+        );
+    }
+
+    #[test]
+    fn redact_json_escaped() {
+        let mut sensitive_strings = SensitiveStrings::default();
+        sensitive_strings.insert("pa\"ss\\word");
+
+        assert!(sensitive_strings.has_sensitive(r#"{"password":"pa\"ss\\word"}"#));
+        assert_eq!(
+            r#"{"password":"[redacted]"}"#,
+            sensitive_strings.redact(r#"{"password":"pa\"ss\\word"}"#)
+        );
+        assert_eq!(
+            "raw: [redacted]",
+            sensitive_strings.redact("raw: pa\"ss\\word")
+        );
+    }
 }
@@ -13,6 +13,8 @@ use tokio::task::JoinError;
 
 pub use sodiumoxide::crypto::secretbox::Nonce as SymmetricNonce;
 
+pub mod envelope;
+
 /// An error that can be returned when working with the [`SymmetricCryptoService`].
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -44,6 +46,9 @@ pub enum SymmetricCryptoError {
     /// When a Tokio task join fails
     #[error("error joining task: {0}")]
     TaskJoin(#[from] JoinError),
+    /// When decoding an envelope written in an unknown format version
+    #[error("unsupported symmetric envelope version: {0}")]
+    UnsupportedEnvelopeVersion(u8),
 }
 
 /// A result type when working with a [`SymmetricCryptoService`].
@@ -0,0 +1,372 @@
+//! Envelope encryption with [`SymmetricCryptoService`] keys.
+//!
+//! Each message is encrypted with its own randomly generated data key, which is in turn encrypted
+//! (or "wrapped") with the active key of the service. The resulting [`SymmetricEnvelope`] records
+//! the [`Hash`] of the wrapping key, so that it can be opened by any service which has that key
+//! loaded, be it the active key or one of the extra keys.
+//!
+//! Rotating the active key only requires re-wrapping the data keys, which leaves the encrypted
+//! messages untouched. This can be done gradually with
+//! [`SymmetricCryptoService::reencrypt_envelopes`] while the old key is still loaded as an extra
+//! key, after which the old key can be dropped.
+
+use std::{io::Cursor, time::Duration};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use si_hash::Hash;
+use sodiumoxide::crypto::secretbox;
+use telemetry::prelude::*;
+
+use super::{SymmetricCryptoError, SymmetricCryptoResult, SymmetricCryptoService, SymmetricNonce};
+
+/// The version of the [`SymmetricEnvelope`] format written by this crate.
+pub const SYMMETRIC_ENVELOPE_VERSION: u8 = 1;
+
+/// A message encrypted with its own data key, along with the data key encrypted with a
+/// [`SymmetricKey`](super::SymmetricKey) and the [`Hash`] of that key.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SymmetricEnvelope {
+    version: u8,
+    key_hash: Hash,
+    wrapped_data_key: Vec<u8>,
+    wrapped_data_key_nonce: SymmetricNonce,
+    ciphertext: Vec<u8>,
+    nonce: SymmetricNonce,
+}
+
+impl SymmetricEnvelope {
+    /// Returns the version of the format of the envelope.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the [`Hash`] of the key the data key of the envelope is wrapped with.
+    pub fn key_hash(&self) -> &Hash {
+        &self.key_hash
+    }
+
+    /// Encodes the envelope into bytes, suitable for storage.
+    pub fn to_bytes(&self) -> SymmetricCryptoResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Decodes an envelope from bytes made by [`SymmetricEnvelope::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if the bytes could not be parsed or if the envelope was written in a format
+    /// version this crate does not know about.
+    pub fn from_bytes(bytes: &[u8]) -> SymmetricCryptoResult<Self> {
+        let envelope: Self = ciborium::from_reader(Cursor::new(bytes))?;
+        if envelope.version != SYMMETRIC_ENVELOPE_VERSION {
+            return Err(SymmetricCryptoError::UnsupportedEnvelopeVersion(
+                envelope.version,
+            ));
+        }
+
+        Ok(envelope)
+    }
+}
+
+/// A store of [`SymmetricEnvelope`]s, such as a database table, whose envelopes can be
+/// re-encrypted with [`SymmetricCryptoService::reencrypt_envelopes`].
+#[async_trait]
+pub trait SymmetricEnvelopeStore: Send + Sync {
+    /// The identifier of an envelope in the store.
+    type Id: Send;
+    /// The error returned by the store.
+    type Error: From<SymmetricCryptoError> + Send;
+
+    /// Lists up to `limit` envelopes whose data key is *not* wrapped with the key with the given
+    /// [`Hash`].
+    async fn list_not_wrapped_with(
+        &self,
+        key_hash: &Hash,
+        limit: usize,
+    ) -> Result<Vec<(Self::Id, SymmetricEnvelope)>, Self::Error>;
+
+    /// Replaces the envelope with the given identifier.
+    async fn replace(&self, id: Self::Id, envelope: SymmetricEnvelope) -> Result<(), Self::Error>;
+}
+
+impl SymmetricCryptoService {
+    /// Returns the [`Hash`] of the active key, which is used for all encryption.
+    pub fn active_key_hash(&self) -> &Hash {
+        self.active_key_hash.as_ref()
+    }
+
+    /// Encrypts a message with a newly generated data key and wraps the data key with the active
+    /// key.
+    pub fn seal_envelope(&self, message: &[u8]) -> SymmetricEnvelope {
+        let data_key = secretbox::gen_key();
+        let nonce = secretbox::gen_nonce();
+        let ciphertext = secretbox::seal(message, &nonce, &data_key);
+
+        let (wrapped_data_key, wrapped_data_key_nonce, key_hash) = self.encrypt(data_key.as_ref());
+
+        SymmetricEnvelope {
+            version: SYMMETRIC_ENVELOPE_VERSION,
+            key_hash: *key_hash,
+            wrapped_data_key,
+            wrapped_data_key_nonce,
+            ciphertext,
+            nonce,
+        }
+    }
+
+    /// Decrypts the message of an envelope, using whichever loaded key its data key is wrapped
+    /// with.
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if:
+    ///
+    /// - No key was loaded for the key hash of the envelope
+    /// - The data key or the message fails to decrypt
+    pub fn open_envelope(&self, envelope: &SymmetricEnvelope) -> SymmetricCryptoResult<Vec<u8>> {
+        let data_key = self.unwrap_data_key(envelope)?;
+
+        secretbox::open(&envelope.ciphertext, &envelope.nonce, &data_key)
+            .map_err(|_| SymmetricCryptoError::DecryptionFailed)
+    }
+
+    /// Returns whether the data key of the envelope is wrapped with a key other than the active
+    /// key.
+    pub fn needs_reencryption(&self, envelope: &SymmetricEnvelope) -> bool {
+        envelope.key_hash != *self.active_key_hash
+    }
+
+    /// Re-wraps the data key of an envelope with the active key. The encrypted message itself is
+    /// left as is.
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if no key was loaded for the key hash of the envelope, or if the data key
+    /// fails to decrypt.
+    pub fn reencrypt_envelope(
+        &self,
+        envelope: &SymmetricEnvelope,
+    ) -> SymmetricCryptoResult<SymmetricEnvelope> {
+        let data_key = self.unwrap_data_key(envelope)?;
+        let (wrapped_data_key, wrapped_data_key_nonce, key_hash) = self.encrypt(data_key.as_ref());
+
+        Ok(SymmetricEnvelope {
+            version: SYMMETRIC_ENVELOPE_VERSION,
+            key_hash: *key_hash,
+            wrapped_data_key,
+            wrapped_data_key_nonce,
+            ciphertext: envelope.ciphertext.clone(),
+            nonce: envelope.nonce,
+        })
+    }
+
+    /// Re-encrypts every envelope of the store which is not wrapped with the active key, in
+    /// batches of `batch_size` with a `pause` in between so that it can be left running in the
+    /// background after a key rotation. Returns the number of re-encrypted envelopes.
+    ///
+    /// The store must stop listing envelopes once they are replaced, otherwise this never
+    /// returns.
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` as soon as the store fails or an envelope cannot be re-encrypted, e.g.
+    /// because the key it is wrapped with is not loaded. Envelopes re-encrypted up to then stay
+    /// re-encrypted.
+    pub async fn reencrypt_envelopes<S: SymmetricEnvelopeStore>(
+        &self,
+        store: &S,
+        batch_size: usize,
+        pause: Duration,
+    ) -> Result<usize, S::Error> {
+        let mut reencrypted: usize = 0;
+
+        loop {
+            let batch = store
+                .list_not_wrapped_with(self.active_key_hash(), batch_size)
+                .await?;
+            if batch.is_empty() {
+                break;
+            }
+
+            for (id, envelope) in batch {
+                let envelope = self.reencrypt_envelope(&envelope)?;
+                store.replace(id, envelope).await?;
+                reencrypted = reencrypted.saturating_add(1);
+            }
+            debug!(reencrypted, "re-encrypted batch of symmetric envelopes");
+
+            tokio::time::sleep(pause).await;
+        }
+
+        info!(
+            reencrypted,
+            key_hash = %self.active_key_hash(),
+            "finished re-encrypting symmetric envelopes",
+        );
+
+        Ok(reencrypted)
+    }
+
+    fn unwrap_data_key(
+        &self,
+        envelope: &SymmetricEnvelope,
+    ) -> SymmetricCryptoResult<secretbox::Key> {
+        let data_key = self.decrypt(
+            &envelope.wrapped_data_key,
+            &envelope.wrapped_data_key_nonce,
+            &envelope.key_hash,
+        )?;
+
+        secretbox::Key::from_slice(&data_key).ok_or(SymmetricCryptoError::DecryptionFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct InMemoryStore(Arc<Mutex<HashMap<usize, SymmetricEnvelope>>>);
+
+    #[async_trait]
+    impl SymmetricEnvelopeStore for InMemoryStore {
+        type Id = usize;
+        type Error = SymmetricCryptoError;
+
+        async fn list_not_wrapped_with(
+            &self,
+            key_hash: &Hash,
+            limit: usize,
+        ) -> Result<Vec<(usize, SymmetricEnvelope)>, Self::Error> {
+            let envelopes = self.0.lock().expect("poisoned lock");
+
+            Ok(envelopes
+                .iter()
+                .filter(|(_, envelope)| envelope.key_hash() != key_hash)
+                .take(limit)
+                .map(|(id, envelope)| (*id, envelope.clone()))
+                .collect())
+        }
+
+        async fn replace(&self, id: usize, envelope: SymmetricEnvelope) -> Result<(), Self::Error> {
+            self.0.lock().expect("poisoned lock").insert(id, envelope);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn envelope_round_trip() {
+        let key = SymmetricCryptoService::generate_key();
+        let service = SymmetricCryptoService::new(key, vec![]);
+
+        let message = b"I'm gonna make him an offer he can't refuse.";
+
+        let envelope = service.seal_envelope(message);
+        assert_eq!(SYMMETRIC_ENVELOPE_VERSION, envelope.version());
+        assert_eq!(service.active_key_hash(), envelope.key_hash());
+
+        let decoded =
+            SymmetricEnvelope::from_bytes(&envelope.to_bytes().expect("Should encode envelope"))
+                .expect("Should decode envelope");
+        assert_eq!(envelope, decoded);
+
+        let opened = service
+            .open_envelope(&decoded)
+            .expect("Should be able to open envelope");
+        assert_eq!(message.as_slice(), opened);
+    }
+
+    #[test]
+    fn unsupported_envelope_version() {
+        let key = SymmetricCryptoService::generate_key();
+        let service = SymmetricCryptoService::new(key, vec![]);
+
+        let mut envelope = service.seal_envelope(b"Never go against the family.");
+        envelope.version = 42;
+
+        let result =
+            SymmetricEnvelope::from_bytes(&envelope.to_bytes().expect("Should encode envelope"));
+
+        assert!(matches!(
+            result,
+            Err(SymmetricCryptoError::UnsupportedEnvelopeVersion(42))
+        ));
+    }
+
+    #[test]
+    fn envelope_key_rotation() {
+        let old_key = SymmetricCryptoService::generate_key();
+        let old_service = SymmetricCryptoService::new(old_key.clone(), vec![]);
+
+        let message = b"Keep your friends close, but your enemies closer.";
+        let envelope = old_service.seal_envelope(message);
+
+        let new_key = SymmetricCryptoService::generate_key();
+        let new_service = SymmetricCryptoService::new(new_key.clone(), vec![old_key]);
+        assert!(new_service.needs_reencryption(&envelope));
+
+        let opened = new_service
+            .open_envelope(&envelope)
+            .expect("Should be able to open envelope with an extra key");
+        assert_eq!(message.as_slice(), opened);
+
+        let reencrypted = new_service
+            .reencrypt_envelope(&envelope)
+            .expect("Should be able to re-encrypt envelope");
+        assert!(!new_service.needs_reencryption(&reencrypted));
+        assert_eq!(envelope.ciphertext, reencrypted.ciphertext);
+
+        let newest_service = SymmetricCryptoService::new(new_key, vec![]);
+        let opened = newest_service
+            .open_envelope(&reencrypted)
+            .expect("Should be able to open re-encrypted envelope without the old key");
+        assert_eq!(message.as_slice(), opened);
+    }
+
+    #[tokio::test]
+    async fn reencrypt_envelopes_in_batches() {
+        let old_key = SymmetricCryptoService::generate_key();
+        let old_service = SymmetricCryptoService::new(old_key.clone(), vec![]);
+
+        let store = InMemoryStore::default();
+        for id in 0..5 {
+            store
+                .replace(id, old_service.seal_envelope(id.to_string().as_bytes()))
+                .await
+                .expect("Should store envelope");
+        }
+
+        let new_key = SymmetricCryptoService::generate_key();
+        let new_service = SymmetricCryptoService::new(new_key, vec![old_key]);
+
+        let reencrypted = new_service
+            .reencrypt_envelopes(&store, 2, Duration::ZERO)
+            .await
+            .expect("Should re-encrypt envelopes");
+        assert_eq!(5, reencrypted);
+
+        let envelopes = store.0.lock().expect("poisoned lock").clone();
+        for (id, envelope) in envelopes {
+            assert!(!new_service.needs_reencryption(&envelope));
+            assert!(matches!(
+                old_service.open_envelope(&envelope),
+                Err(SymmetricCryptoError::MissingKeyForHash)
+            ));
+
+            let opened = new_service
+                .open_envelope(&envelope)
+                .expect("Should be able to open envelope");
+            assert_eq!(id.to_string().as_bytes(), opened);
+        }
+    }
+}
@@ -2,3 +2,5 @@ pub(crate) mod config;
 pub(crate) mod decryption_key;
 pub(crate) mod encryption_key;
 pub(crate) mod key_pair;
+pub(crate) mod key_provider;
+pub(crate) mod rotating_decryption_key;
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use si_std::CanonicalFile;
 
+use crate::VeritechKeyProviderConfig;
+
 /// Configuration for how to load the key for [`CryptoConfig`].
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct VeritechCryptoConfig {
@@ -8,8 +10,12 @@ pub struct VeritechCryptoConfig {
     pub encryption_key_base64: Option<String>,
     /// Key file on disk
     pub encryption_key_file: Option<CanonicalFile>,
+    /// Key fetched from an external key management service
+    pub encryption_key_provider: Option<VeritechKeyProviderConfig>,
     /// Key file encoded as a base64 string
     pub decryption_key_base64: Option<String>,
     /// Key file on disk
     pub decryption_key_file: Option<CanonicalFile>,
+    /// Key fetched from an external key management service
+    pub decryption_key_provider: Option<VeritechKeyProviderConfig>,
 }
@@ -1,4 +1,4 @@
-use crate::VeritechCryptoConfig;
+use crate::{key_provider_from_config, VeritechCryptoConfig, VeritechKeyProvider};
 use std::{io, path::Path};
 
 use base64::{engine::general_purpose, Engine};
@@ -19,11 +19,14 @@ pub enum VeritechDecryptionKeyError {
     #[error("failed to decrypt encryption key from bytes")]
     DecryptionFailed,
     /// When a key cannot be made from the supplied config
-    #[error("key cannot be made from the supplied config, must supply either a base64 string or a filepath")]
+    #[error("key cannot be made from the supplied config, must supply exactly one of a base64 string, a filepath or a key provider")]
     FromConfig,
     /// When a key fails to be parsed from bytes
     #[error("failed to load key from bytes")]
     KeyParse,
+    /// When a key fails to be fetched from a key provider
+    #[error("key provider error: {0}")]
+    KeyProvider(#[from] crate::VeritechKeyProviderError),
     /// When an error is return while reading from a key file
     #[error("failed to load key from file: {0}")]
     LoadKeyIO(#[source] io::Error),
@@ -49,13 +52,21 @@ impl VeritechDecryptionKey {
     /// - A key file was not readable (i.e. incorrect permission and/or ownership)
     /// - A key file could not be successfuly parsed
     /// - A key string could not be successfully parsed
+    /// - A key could not be fetched from the key provider
     /// - An invalid configuration was passed in
     pub async fn from_config(
         config: VeritechCryptoConfig,
     ) -> Result<Self, VeritechDecryptionKeyError> {
-        match (config.decryption_key_file, config.decryption_key_base64) {
-            (Some(path), None) => Self::load(path).await,
-            (None, Some(b64_string)) => Self::decode(b64_string).await,
+        match (
+            config.decryption_key_file,
+            config.decryption_key_base64,
+            config.decryption_key_provider,
+        ) {
+            (Some(path), None, None) => Self::load(path).await,
+            (None, Some(b64_string), None) => Self::decode(b64_string).await,
+            (None, None, Some(provider_config)) => {
+                Self::fetch(key_provider_from_config(&provider_config).await?.as_ref()).await
+            }
             _ => Err(VeritechDecryptionKeyError::FromConfig),
         }
     }
@@ -127,6 +138,24 @@ impl VeritechDecryptionKey {
         })
     }
 
+    /// Fetches a [`VeritechDecryptionKey`] from a [`VeritechKeyProvider`].
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if:
+    ///
+    /// - A key could not be fetched from the key provider
+    /// - A key could not be successfully parsed
+    pub async fn fetch(
+        key_provider: &dyn VeritechKeyProvider,
+    ) -> Result<Self, VeritechDecryptionKeyError> {
+        let buf = key_provider.fetch_key().await?;
+        let secret_key =
+            BoxSecretKey::from_slice(&buf).ok_or(VeritechDecryptionKeyError::KeyParse)?;
+
+        Ok(secret_key.into())
+    }
+
     /// Decrypts an encrypted message which is Base64 encoded.
     ///
     /// # Errors
@@ -156,6 +185,20 @@ impl VeritechDecryptionKey {
     }
 }
 
+/// Finds the [`VeritechDecryptionKey`] for messages encoded with an encryption key, by the hash
+/// of that encryption key.
+pub trait VeritechDecryptionKeyLookup: Send + Sync {
+    /// Returns the key which decrypts messages encoded with the encryption key of this hash, if
+    /// there is one.
+    fn for_encryption_key_hash(&self, encryption_key_hash: &str) -> Option<&VeritechDecryptionKey>;
+}
+
+impl VeritechDecryptionKeyLookup for VeritechDecryptionKey {
+    fn for_encryption_key_hash(&self, encryption_key_hash: &str) -> Option<&VeritechDecryptionKey> {
+        (self.encryption_key_hash_str() == encryption_key_hash).then_some(self)
+    }
+}
+
 impl From<BoxSecretKey> for VeritechDecryptionKey {
     fn from(value: BoxSecretKey) -> Self {
         let public_key = value.public_key();
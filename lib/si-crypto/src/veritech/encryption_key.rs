@@ -1,4 +1,4 @@
-use crate::VeritechCryptoConfig;
+use crate::{key_provider_from_config, VeritechCryptoConfig, VeritechKeyProvider};
 use std::{io, path::Path};
 
 use base64::{engine::general_purpose, Engine};
@@ -16,11 +16,14 @@ pub enum VeritechEncryptionKeyError {
     #[error("failed to decode base64 encoded key")]
     Base64Decode(#[source] base64::DecodeError),
     /// When a key cannot be made from the supplied config
-    #[error("key cannot be made from the supplied config, must supply either a base64 string or a filepath")]
+    #[error("key cannot be made from the supplied config, must supply exactly one of a base64 string, a filepath or a key provider")]
     FromConfig,
     /// When a key fails to be parsed from bytes
     #[error("failed to load key from bytes")]
     KeyParse,
+    /// When a key fails to be fetched from a key provider
+    #[error("key provider error: {0}")]
+    KeyProvider(#[from] crate::VeritechKeyProviderError),
     /// When an error is return while reading from a key file
     #[error("failed to load key from file: {0}")]
     LoadKeyIO(#[source] io::Error),
@@ -44,13 +47,21 @@ impl VeritechEncryptionKey {
     /// - A key file was not readable (i.e. incorrect permission and/or ownership)
     /// - A key file could not be successfuly parsed
     /// - A key string could not be successfully parsed
+    /// - A key could not be fetched from the key provider
     /// - An invalid configuration was passed in
     pub async fn from_config(
         config: VeritechCryptoConfig,
     ) -> Result<Self, VeritechEncryptionKeyError> {
-        match (config.encryption_key_file, config.encryption_key_base64) {
-            (Some(path), None) => Self::load(path).await,
-            (None, Some(b64_string)) => Self::decode(b64_string).await,
+        match (
+            config.encryption_key_file,
+            config.encryption_key_base64,
+            config.encryption_key_provider,
+        ) {
+            (Some(path), None, None) => Self::load(path).await,
+            (None, Some(b64_string), None) => Self::decode(b64_string).await,
+            (None, None, Some(provider_config)) => {
+                Self::fetch(key_provider_from_config(&provider_config).await?.as_ref()).await
+            }
             _ => Err(VeritechEncryptionKeyError::FromConfig),
         }
     }
@@ -112,6 +123,23 @@ impl VeritechEncryptionKey {
         })
     }
 
+    /// Fetches a [`VeritechEncryptionKey`] from a [`VeritechKeyProvider`].
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if:
+    ///
+    /// - A key could not be fetched from the key provider
+    /// - A key could not be successfully parsed
+    pub async fn fetch(
+        key_provider: &dyn VeritechKeyProvider,
+    ) -> Result<Self, VeritechEncryptionKeyError> {
+        let buf = key_provider.fetch_key().await?;
+        let public_key = PublicKey::from_slice(&buf).ok_or(VeritechEncryptionKeyError::KeyParse)?;
+
+        Ok(public_key.into())
+    }
+
     /// Encrypts an message and encodes it as a Base64 string.
     pub fn encrypt_and_encode(&self, message: impl AsRef<[u8]>) -> String {
         let crypted = sodiumoxide::crypto::sealedbox::seal(message.as_ref(), &self.public_key);
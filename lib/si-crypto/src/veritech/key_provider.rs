@@ -0,0 +1,347 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use aws_sdk_kms::{error::SdkError, operation::decrypt::DecryptError, primitives::Blob};
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+use si_std::SensitiveString;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+/// The default for how long a key fetched from a [`VeritechKeyProvider`] is used before it is
+/// fetched again, picking up rotated keys.
+const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+
+/// The default for how long fetching a key from a [`VeritechKeyProvider`] may take before it is
+/// abandoned.
+const DEFAULT_FETCH_TIMEOUT_SECS: u64 = 10;
+
+/// An error that can be returned when fetching a key from a [`VeritechKeyProvider`].
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum VeritechKeyProviderError {
+    /// When AWS KMS fails to decrypt the encrypted key
+    #[error("aws kms decrypt error: {0}")]
+    AwsKmsDecrypt(#[source] Box<SdkError<DecryptError>>),
+    /// When AWS KMS decrypts the encrypted key to nothing
+    #[error("aws kms returned no plaintext for the encrypted key")]
+    AwsKmsMissingPlaintext,
+    /// When a base64 encoded key fails to be decoded
+    #[error("base64 decode error: {0}")]
+    Base64Decode(#[from] base64::DecodeError),
+    /// When a request to Vault fails
+    #[error("vault request error: {0}")]
+    Vault(#[from] reqwest::Error),
+    /// When the Vault secret does not hold the key under the configured field
+    #[error("vault secret is missing field: {0}")]
+    VaultMissingField(String),
+}
+
+type VeritechKeyProviderResult<T> = Result<T, VeritechKeyProviderError>;
+
+/// Configuration for fetching a key from an external key management service rather than reading
+/// it from disk.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VeritechKeyProviderConfig {
+    /// Where the key is fetched from
+    #[serde(flatten)]
+    pub source: VeritechKeySource,
+    /// How long a fetched key is used before it is fetched again, in seconds
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// How long fetching the key may take before it is abandoned, in seconds
+    #[serde(default = "default_fetch_timeout_secs")]
+    pub fetch_timeout_secs: u64,
+}
+
+impl VeritechKeyProviderConfig {
+    /// Returns how long a fetched key is used before it is fetched again.
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.cache_ttl_secs)
+    }
+
+    /// Returns how long fetching the key may take before it is abandoned.
+    pub fn fetch_timeout(&self) -> Duration {
+        Duration::from_secs(self.fetch_timeout_secs)
+    }
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    DEFAULT_CACHE_TTL_SECS
+}
+
+fn default_fetch_timeout_secs() -> u64 {
+    DEFAULT_FETCH_TIMEOUT_SECS
+}
+
+/// The external services a key can be fetched from.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum VeritechKeySource {
+    /// The key is kept encrypted by an AWS KMS key and is decrypted on fetch. AWS credentials
+    /// and region are loaded from the environment.
+    AwsKms {
+        /// The key encrypted by KMS, encoded as a base64 string
+        ciphertext_base64: String,
+    },
+    /// The key is kept in a Vault KV version 2 secrets engine, encoded as a base64 string.
+    Vault {
+        /// The address of the Vault server, e.g. `https://vault.example.com:8200`
+        address: String,
+        /// The token used to authenticate to Vault
+        token: SensitiveString,
+        /// The mount path of the secrets engine
+        mount: String,
+        /// The path of the secret within the secrets engine
+        path: String,
+        /// The field of the secret holding the key
+        field: String,
+    },
+}
+
+/// A source of key material for Veritech encryption and decryption keys.
+#[async_trait]
+pub trait VeritechKeyProvider: fmt::Debug + Send + Sync {
+    /// Fetches the raw bytes of the key.
+    async fn fetch_key(&self) -> VeritechKeyProviderResult<Vec<u8>>;
+}
+
+/// Creates the [`VeritechKeyProvider`] for the supplied configuration. Keys are cached for the
+/// configured time, after which they are fetched again so that rotated keys are picked up.
+pub async fn key_provider_from_config(
+    config: &VeritechKeyProviderConfig,
+) -> VeritechKeyProviderResult<Arc<dyn VeritechKeyProvider>> {
+    let provider: Box<dyn VeritechKeyProvider> = match &config.source {
+        VeritechKeySource::AwsKms { ciphertext_base64 } => {
+            Box::new(AwsKmsKeyProvider::new(ciphertext_base64, config.fetch_timeout()).await?)
+        }
+        VeritechKeySource::Vault {
+            address,
+            token,
+            mount,
+            path,
+            field,
+        } => Box::new(VaultKeyProvider::new(
+            address,
+            token.clone(),
+            mount,
+            path,
+            field,
+            config.fetch_timeout(),
+        )?),
+    };
+
+    Ok(Arc::new(CachedKeyProvider::new(
+        provider,
+        config.cache_ttl(),
+    )))
+}
+
+/// Fetches a key by decrypting it with AWS KMS.
+#[derive(Debug)]
+pub struct AwsKmsKeyProvider {
+    client: aws_sdk_kms::Client,
+    ciphertext: Vec<u8>,
+}
+
+impl AwsKmsKeyProvider {
+    /// Creates an [`AwsKmsKeyProvider`] for the key encrypted by KMS, loading AWS configuration
+    /// from the environment. Requests to KMS are abandoned after the timeout.
+    pub async fn new(
+        ciphertext_base64: impl AsRef<[u8]>,
+        timeout: Duration,
+    ) -> VeritechKeyProviderResult<Self> {
+        let ciphertext = general_purpose::STANDARD.decode(ciphertext_base64)?;
+        let config = aws_config::from_env()
+            .timeout_config(
+                aws_config::timeout::TimeoutConfig::builder()
+                    .operation_timeout(timeout)
+                    .build(),
+            )
+            .load()
+            .await;
+
+        Ok(Self {
+            client: aws_sdk_kms::Client::new(&config),
+            ciphertext,
+        })
+    }
+}
+
+#[async_trait]
+impl VeritechKeyProvider for AwsKmsKeyProvider {
+    async fn fetch_key(&self) -> VeritechKeyProviderResult<Vec<u8>> {
+        trace!("fetching veritech key from aws kms");
+        let output = self
+            .client
+            .decrypt()
+            .ciphertext_blob(Blob::new(self.ciphertext.clone()))
+            .send()
+            .await
+            .map_err(|err| VeritechKeyProviderError::AwsKmsDecrypt(Box::new(err)))?;
+
+        output
+            .plaintext
+            .map(Blob::into_inner)
+            .ok_or(VeritechKeyProviderError::AwsKmsMissingPlaintext)
+    }
+}
+
+/// Fetches a key from a Vault KV version 2 secrets engine.
+#[derive(Debug)]
+pub struct VaultKeyProvider {
+    client: reqwest::Client,
+    url: String,
+    token: SensitiveString,
+    field: String,
+}
+
+impl VaultKeyProvider {
+    /// Creates a [`VaultKeyProvider`] for the field of the secret at the path. Requests to Vault
+    /// are abandoned after the timeout.
+    pub fn new(
+        address: impl AsRef<str>,
+        token: impl Into<SensitiveString>,
+        mount: impl AsRef<str>,
+        path: impl AsRef<str>,
+        field: impl Into<String>,
+        timeout: Duration,
+    ) -> VeritechKeyProviderResult<Self> {
+        let url = format!(
+            "{}/v1/{}/data/{}",
+            address.as_ref().trim_end_matches('/'),
+            mount.as_ref().trim_matches('/'),
+            path.as_ref().trim_matches('/'),
+        );
+
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(timeout).build()?,
+            url,
+            token: token.into(),
+            field: field.into(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct VaultSecretResponse {
+    data: VaultSecretData,
+}
+
+#[derive(Deserialize)]
+struct VaultSecretData {
+    data: serde_json::Map<String, serde_json::Value>,
+}
+
+#[async_trait]
+impl VeritechKeyProvider for VaultKeyProvider {
+    async fn fetch_key(&self) -> VeritechKeyProviderResult<Vec<u8>> {
+        trace!(url = %self.url, "fetching veritech key from vault");
+        let response: VaultSecretResponse = self
+            .client
+            .get(&self.url)
+            .header("X-Vault-Token", self.token.as_str())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let key_base64 = response
+            .data
+            .data
+            .get(&self.field)
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| VeritechKeyProviderError::VaultMissingField(self.field.clone()))?;
+
+        Ok(general_purpose::STANDARD.decode(key_base64)?)
+    }
+}
+
+/// Wraps a [`VeritechKeyProvider`], reusing the fetched key until it is older than the TTL.
+///
+/// The cache is not locked while the key is fetched, so callers racing an expired key may each
+/// fetch it; the last one to finish is cached.
+#[derive(Debug)]
+pub struct CachedKeyProvider {
+    inner: Box<dyn VeritechKeyProvider>,
+    ttl: Duration,
+    cached: Mutex<Option<(Instant, Vec<u8>)>>,
+}
+
+impl CachedKeyProvider {
+    /// Creates a [`CachedKeyProvider`] which caches keys fetched from the provider for the TTL.
+    pub fn new(inner: Box<dyn VeritechKeyProvider>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            cached: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl VeritechKeyProvider for CachedKeyProvider {
+    async fn fetch_key(&self) -> VeritechKeyProviderResult<Vec<u8>> {
+        if let Some((fetched_at, key)) = self
+            .cached
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .as_ref()
+        {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(key.clone());
+            }
+        }
+
+        let key = self.inner.fetch_key().await?;
+        *self.cached.lock().unwrap_or_else(|err| err.into_inner()) =
+            Some((Instant::now(), key.clone()));
+
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct CountingKeyProvider {
+        fetches: AtomicU8,
+    }
+
+    #[async_trait]
+    impl VeritechKeyProvider for Arc<CountingKeyProvider> {
+        async fn fetch_key(&self) -> VeritechKeyProviderResult<Vec<u8>> {
+            let fetches = self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![fetches])
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_key_provider_refetches_after_ttl() {
+        let counting = Arc::new(CountingKeyProvider::default());
+
+        let cached = CachedKeyProvider::new(Box::new(counting.clone()), Duration::from_secs(60));
+        assert_eq!(vec![0], cached.fetch_key().await.expect("failed to fetch"));
+        assert_eq!(vec![0], cached.fetch_key().await.expect("failed to fetch"));
+
+        let uncached = CachedKeyProvider::new(Box::new(counting.clone()), Duration::ZERO);
+        assert_eq!(
+            vec![1],
+            uncached.fetch_key().await.expect("failed to fetch")
+        );
+        assert_eq!(
+            vec![2],
+            uncached.fetch_key().await.expect("failed to fetch")
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use telemetry::prelude::*;
+use tokio::sync::Mutex;
+
+use crate::{
+    key_provider_from_config, VeritechCryptoConfig, VeritechDecryptionKey,
+    VeritechDecryptionKeyError, VeritechDecryptionKeyLookup, VeritechKeyProvider,
+};
+
+/// A [`VeritechDecryptionKey`] which, when fetched from a [`VeritechKeyProvider`], is fetched
+/// again once it is older than the provider's cache TTL so that rotated keys are picked up
+/// without a restart. Keys loaded from a file or a base64 string never change.
+///
+/// Keys which have been rotated out are kept, so that messages encrypted before a rotation can
+/// still be decrypted after it.
+#[derive(Clone, Debug)]
+pub struct RotatingVeritechDecryptionKey {
+    provider: Option<(Arc<dyn VeritechKeyProvider>, Duration)>,
+    state: Arc<RwLock<RotatingState>>,
+    refreshing: Arc<Mutex<()>>,
+}
+
+#[derive(Debug)]
+struct RotatingState {
+    fetched_at: Instant,
+    keys: Arc<VeritechDecryptionKeyRing>,
+}
+
+/// The current [`VeritechDecryptionKey`] along with the keys it replaced, keyed by the hash of
+/// their encryption keys.
+#[derive(Clone, Debug)]
+pub struct VeritechDecryptionKeyRing {
+    current: Arc<VeritechDecryptionKey>,
+    keys: HashMap<String, Arc<VeritechDecryptionKey>>,
+}
+
+impl VeritechDecryptionKeyRing {
+    fn new(current: VeritechDecryptionKey) -> Self {
+        let current = Arc::new(current);
+        let keys = HashMap::from([(
+            current.encryption_key_hash_str().to_owned(),
+            current.clone(),
+        )]);
+
+        Self { current, keys }
+    }
+
+    fn rotated_to(&self, key: VeritechDecryptionKey) -> Self {
+        let current = Arc::new(key);
+        let mut keys = self.keys.clone();
+        keys.insert(
+            current.encryption_key_hash_str().to_owned(),
+            current.clone(),
+        );
+
+        Self { current, keys }
+    }
+
+    /// Returns the current key.
+    pub fn current(&self) -> &VeritechDecryptionKey {
+        &self.current
+    }
+}
+
+impl VeritechDecryptionKeyLookup for VeritechDecryptionKeyRing {
+    fn for_encryption_key_hash(&self, encryption_key_hash: &str) -> Option<&VeritechDecryptionKey> {
+        self.keys.get(encryption_key_hash).map(AsRef::as_ref)
+    }
+}
+
+impl RotatingVeritechDecryptionKey {
+    /// Creates an instance of [`RotatingVeritechDecryptionKey`] based on the supplied
+    /// configuration.
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if the key cannot be made from the configuration, see
+    /// [`VeritechDecryptionKey::from_config`].
+    pub async fn from_config(
+        config: VeritechCryptoConfig,
+    ) -> Result<Self, VeritechDecryptionKeyError> {
+        match (
+            &config.decryption_key_file,
+            &config.decryption_key_base64,
+            &config.decryption_key_provider,
+        ) {
+            (None, None, Some(provider_config)) => {
+                let provider = key_provider_from_config(provider_config).await?;
+                Self::from_provider(provider, provider_config.cache_ttl()).await
+            }
+            _ => Ok(VeritechDecryptionKey::from_config(config).await?.into()),
+        }
+    }
+
+    /// Creates an instance of [`RotatingVeritechDecryptionKey`] which fetches the key from the
+    /// provider, and fetches it again once it is older than the TTL.
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if the key cannot be fetched, see [`VeritechDecryptionKey::fetch`].
+    pub async fn from_provider(
+        provider: Arc<dyn VeritechKeyProvider>,
+        ttl: Duration,
+    ) -> Result<Self, VeritechDecryptionKeyError> {
+        let key = VeritechDecryptionKey::fetch(provider.as_ref()).await?;
+
+        Ok(Self {
+            provider: Some((provider, ttl)),
+            state: Arc::new(RwLock::new(RotatingState {
+                fetched_at: Instant::now(),
+                keys: Arc::new(VeritechDecryptionKeyRing::new(key)),
+            })),
+            refreshing: Arc::new(Mutex::new(())),
+        })
+    }
+
+    /// Returns the current key and the keys it replaced, fetching the key again first if it is
+    /// due for a refresh. If the refresh fails, the current key is kept and the refresh is retried
+    /// once it is due again.
+    ///
+    /// Only one caller refreshes the key at a time. Everyone else gets the keys as they are
+    /// rather than waiting on the fetch.
+    pub async fn keys(&self) -> Arc<VeritechDecryptionKeyRing> {
+        if let Some((provider, ttl)) = &self.provider {
+            if self.is_due(*ttl) {
+                if let Ok(_refreshing) = self.refreshing.try_lock() {
+                    // Another caller may have finished a refresh since we checked
+                    if self.is_due(*ttl) {
+                        self.refresh(provider.as_ref()).await;
+                    }
+                }
+            }
+        }
+
+        self.read_state().keys.clone()
+    }
+
+    fn is_due(&self, ttl: Duration) -> bool {
+        self.read_state().fetched_at.elapsed() >= ttl
+    }
+
+    async fn refresh(&self, provider: &dyn VeritechKeyProvider) {
+        // The key is fetched before taking the lock on the state, so that nobody waits on it
+        let fetched = VeritechDecryptionKey::fetch(provider).await;
+
+        let mut state = self.state.write().unwrap_or_else(|err| err.into_inner());
+        match fetched {
+            Ok(key) => {
+                if key.encryption_key_hash() != state.keys.current().encryption_key_hash() {
+                    info!(
+                        encryption_key_hash = key.encryption_key_hash_str(),
+                        "veritech decryption key rotated",
+                    );
+                    state.keys = Arc::new(state.keys.rotated_to(key));
+                }
+            }
+            Err(err) => {
+                warn!(
+                    si.error.message = ?err,
+                    "failed to refresh veritech decryption key, keeping current key",
+                );
+            }
+        }
+        state.fetched_at = Instant::now();
+    }
+
+    fn read_state(&self) -> std::sync::RwLockReadGuard<'_, RotatingState> {
+        self.state.read().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl From<VeritechDecryptionKey> for RotatingVeritechDecryptionKey {
+    fn from(value: VeritechDecryptionKey) -> Self {
+        Self {
+            provider: None,
+            state: Arc::new(RwLock::new(RotatingState {
+                fetched_at: Instant::now(),
+                keys: Arc::new(VeritechDecryptionKeyRing::new(value)),
+            })),
+            refreshing: Arc::new(Mutex::new(())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+    use sodiumoxide::crypto::box_::gen_keypair;
+
+    use super::*;
+    use crate::VeritechKeyProviderError;
+
+    /// Hands out each of its keys in turn, then the last one forever.
+    #[derive(Debug)]
+    struct RotatingKeyProvider {
+        keys: Vec<Vec<u8>>,
+        fetches: AtomicUsize,
+    }
+
+    impl RotatingKeyProvider {
+        fn new(count: usize) -> Self {
+            Self {
+                keys: (0..count)
+                    .map(|_| gen_keypair().1.as_ref().to_vec())
+                    .collect(),
+                fetches: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl VeritechKeyProvider for RotatingKeyProvider {
+        async fn fetch_key(&self) -> Result<Vec<u8>, VeritechKeyProviderError> {
+            let fetch = self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(self
+                .keys
+                .get(fetch)
+                .or(self.keys.last())
+                .cloned()
+                .unwrap_or_default())
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_keys_it_rotated_out() {
+        let provider = Arc::new(RotatingKeyProvider::new(2));
+        let rotating = RotatingVeritechDecryptionKey::from_provider(provider, Duration::ZERO)
+            .await
+            .expect("failed to fetch key");
+
+        let first = rotating.keys().await;
+        let second = rotating.keys().await;
+        assert_ne!(
+            first.current().encryption_key_hash(),
+            second.current().encryption_key_hash(),
+        );
+
+        let previous_hash = first.current().encryption_key_hash_str();
+        assert_eq!(
+            Some(previous_hash),
+            second
+                .for_encryption_key_hash(previous_hash)
+                .map(VeritechDecryptionKey::encryption_key_hash_str),
+        );
+        assert!(second.for_encryption_key_hash("unknown").is_none());
+    }
+
+    #[tokio::test]
+    async fn keeps_the_key_until_it_is_due() {
+        let provider = Arc::new(RotatingKeyProvider::new(2));
+        let rotating =
+            RotatingVeritechDecryptionKey::from_provider(provider.clone(), Duration::from_secs(60))
+                .await
+                .expect("failed to fetch key");
+
+        let first = rotating.keys().await;
+        let second = rotating.keys().await;
+        assert_eq!(
+            first.current().encryption_key_hash(),
+            second.current().encryption_key_hash(),
+        );
+        assert_eq!(1, provider.fetches.load(Ordering::SeqCst));
+    }
+
+    /// Hands out its key twice, then hangs on every fetch after that.
+    #[derive(Debug)]
+    struct HangingKeyProvider {
+        key: Vec<u8>,
+        fetches: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl VeritechKeyProvider for HangingKeyProvider {
+        async fn fetch_key(&self) -> Result<Vec<u8>, VeritechKeyProviderError> {
+            if self.fetches.fetch_add(1, Ordering::SeqCst) > 1 {
+                std::future::pending::<()>().await;
+            }
+            Ok(self.key.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_refresh_does_not_block_other_callers() {
+        let provider = Arc::new(HangingKeyProvider {
+            key: gen_keypair().1.as_ref().to_vec(),
+            fetches: AtomicUsize::new(0),
+        });
+        let rotating =
+            RotatingVeritechDecryptionKey::from_provider(provider.clone(), Duration::ZERO)
+                .await
+                .expect("failed to fetch key");
+        let expected_hash = rotating
+            .keys()
+            .await
+            .current()
+            .encryption_key_hash_str()
+            .to_owned();
+
+        // Wait for the refresh to start, so it holds the refresh guard while it hangs
+        let refreshing = tokio::spawn({
+            let rotating = rotating.clone();
+            async move { rotating.keys().await }
+        });
+        while provider.fetches.load(Ordering::SeqCst) < 3 {
+            tokio::task::yield_now().await;
+        }
+
+        let keys = tokio::time::timeout(Duration::from_secs(5), rotating.keys())
+            .await
+            .expect("keys waited on the hanging refresh");
+        assert_eq!(expected_hash, keys.current().encryption_key_hash_str());
+
+        refreshing.abort();
+    }
+}
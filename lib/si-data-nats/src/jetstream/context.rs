@@ -14,7 +14,7 @@ use async_nats::{
         stream::{Config, ConsumerError, DeleteStatus, Info, Stream},
     },
     subject::ToSubject,
-    HeaderMap, HeaderValue,
+    HeaderMap, HeaderValue, Subject,
 };
 use bytes::Bytes;
 use serde::{de::DeserializeOwned, Serialize};
@@ -50,6 +50,20 @@ impl Context {
         &self.metadata
     }
 
+    fn prefixed_stream_config(&self, mut config: Config) -> Config {
+        config.name = self.metadata.prefixed_name(&config.name);
+        config.subjects = config
+            .subjects
+            .into_iter()
+            .map(|subject| {
+                self.metadata
+                    .prefixed_subject(Subject::from(subject))
+                    .to_string()
+            })
+            .collect();
+        config
+    }
+
     pub(crate) fn with_prefix(client: Client, prefix: &str) -> Self {
         let (inner_client, metadata) = client.into_parts();
         let inner = async_nats::jetstream::with_prefix(inner_client, prefix);
@@ -149,7 +163,7 @@ impl Context {
     ) -> Result<PublishAckFuture, PublishError> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", payload.len());
         span.record(
@@ -227,7 +241,7 @@ impl Context {
     ) -> Result<PublishAckFuture, PublishError> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", payload.len());
         span.record(
@@ -305,7 +319,7 @@ impl Context {
     ) -> Result<PublishAckFuture, PublishError> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", publish.payload.len());
         span.record(
@@ -421,7 +435,7 @@ impl Context {
 
         let stream = self
             .inner
-            .create_stream(stream_config)
+            .create_stream(self.prefixed_stream_config(Config::from(stream_config)))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -484,7 +498,7 @@ impl Context {
 
         let stream = self
             .inner
-            .get_stream_no_info(stream)
+            .get_stream_no_info(self.metadata.prefixed_name(stream.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -539,7 +553,7 @@ impl Context {
 
         let stream = self
             .inner
-            .get_stream(stream)
+            .get_stream(self.metadata.prefixed_name(stream.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -614,7 +628,7 @@ impl Context {
 
         let stream = self
             .inner
-            .get_or_create_stream(stream_config)
+            .get_or_create_stream(self.prefixed_stream_config(stream_config.into()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -672,7 +686,7 @@ impl Context {
 
         let status = self
             .inner
-            .delete_stream(stream)
+            .delete_stream(self.metadata.prefixed_name(stream.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -742,7 +756,7 @@ impl Context {
 
         let info = self
             .inner
-            .update_stream(config)
+            .update_stream(self.prefixed_stream_config(config.borrow().clone()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -803,7 +817,11 @@ impl Context {
 
         let name = self
             .inner
-            .stream_by_subject(subject)
+            .stream_by_subject(
+                self.metadata
+                    .prefixed_subject(Subject::from(subject.into()))
+                    .to_string(),
+            )
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -911,7 +929,7 @@ impl Context {
 
         let store = self
             .inner
-            .get_key_value(bucket)
+            .get_key_value(self.metadata.prefixed_name(&bucket.into()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -967,13 +985,16 @@ impl Context {
     )]
     pub async fn create_key_value(
         &self,
-        config: async_nats::jetstream::kv::Config,
+        mut config: async_nats::jetstream::kv::Config,
     ) -> Result<async_nats::jetstream::kv::Store, CreateKeyValueError> {
         let span = current_span_for_instrument_at!("debug");
 
         let store = self
             .inner
-            .create_key_value(config)
+            .create_key_value({
+                config.bucket = self.metadata.prefixed_name(&config.bucket);
+                config
+            })
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -1035,7 +1056,7 @@ impl Context {
 
         let status = self
             .inner
-            .delete_key_value(bucket)
+            .delete_key_value(self.metadata.prefixed_name(bucket.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -1106,7 +1127,7 @@ impl Context {
 
         let consumer = self
             .inner
-            .get_consumer_from_stream(consumer, stream)
+            .get_consumer_from_stream(consumer, self.metadata.prefixed_name(stream.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -1171,7 +1192,7 @@ impl Context {
 
         let status = self
             .inner
-            .delete_consumer_from_stream(consumer, stream)
+            .delete_consumer_from_stream(consumer, self.metadata.prefixed_name(stream.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -1240,7 +1261,7 @@ impl Context {
 
         let consumer = self
             .inner
-            .create_consumer_on_stream(config, stream)
+            .create_consumer_on_stream(config, self.metadata.prefixed_name(stream.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -1369,13 +1390,16 @@ impl Context {
     )]
     pub async fn create_object_store(
         &self,
-        config: async_nats::jetstream::object_store::Config,
+        mut config: async_nats::jetstream::object_store::Config,
     ) -> Result<async_nats::jetstream::object_store::ObjectStore, CreateObjectStoreError> {
         let span = current_span_for_instrument_at!("debug");
 
         let store = self
             .inner
-            .create_object_store(config)
+            .create_object_store({
+                config.bucket = self.metadata.prefixed_name(&config.bucket);
+                config
+            })
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -1431,7 +1455,7 @@ impl Context {
 
         let store = self
             .inner
-            .get_object_store(bucket_name)
+            .get_object_store(self.metadata.prefixed_name(bucket_name.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -1486,7 +1510,7 @@ impl Context {
         let span = current_span_for_instrument_at!("debug");
 
         self.inner
-            .delete_object_store(bucket_name)
+            .delete_object_store(self.metadata.prefixed_name(bucket_name.as_ref()))
             .await
             .map_err(|err| span.record_err(err))?;
 
@@ -22,6 +22,8 @@ mod subscriber;
 
 pub mod jetstream;
 pub mod service;
+pub mod subject_prefix;
+pub mod typed;
 
 pub use async_nats::{
     self, connection::State, header, header::HeaderMap, rustls, status, subject, Auth, AuthError,
@@ -36,6 +38,10 @@ pub type NatsError = Error;
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("error deserializing reply: {0}")]
+    Deserialize(#[source] serde_path_to_error::Error<serde_json::Error>),
+    #[error("error replied to request: {0}")]
+    ErrorReply(#[source] typed::ReplyError),
     #[error("io error: {0}")]
     Io(#[from] io::Error),
     #[error("nats connect error: {0}")]
@@ -50,6 +56,8 @@ pub enum Error {
     NatsSubscribe(#[from] async_nats::SubscribeError),
     #[error("nats unsubscribe error: {0}")]
     NatsUnsubscribe(#[from] async_nats::UnsubscribeError),
+    #[error("payload of {0} bytes exceeds the server maximum of {1} bytes")]
+    PayloadTooLarge(usize, usize),
     #[error("error serializing object: {0}")]
     Serialize(#[source] serde_json::Error),
 }
@@ -62,6 +70,10 @@ pub struct NatsConfig {
     pub creds: Option<String>,
     pub creds_file: Option<String>,
     pub subject_prefix: Option<String>,
+    /// Transparently applies the subject prefix to subjects, stream names and bucket names. See
+    /// the [`subject_prefix`](crate::subject_prefix) module.
+    #[serde(default)]
+    pub auto_prefix_subjects: bool,
     pub url: String,
 }
 
@@ -72,6 +84,7 @@ impl Default for NatsConfig {
             creds: None,
             creds_file: None,
             subject_prefix: None,
+            auto_prefix_subjects: false,
             url: "localhost".to_string(),
         }
     }
@@ -121,7 +134,29 @@ impl Client {
         if let Some(connection_name) = &config.connection_name {
             options = options.name(connection_name);
         }
-        Self::connect_with_options(&config.url, config.subject_prefix.clone(), options).await
+        let client =
+            Self::connect_with_options(&config.url, config.subject_prefix.clone(), options).await?;
+
+        Ok(if config.auto_prefix_subjects {
+            client.with_subject_prefixing(true)
+        } else {
+            client
+        })
+    }
+
+    /// Returns a client which uses subjects as given, without applying the subject prefix even if
+    /// automatic prefixing is configured. Stream and bucket names are not prefixed either.
+    pub fn with_absolute_subjects(&self) -> Self {
+        self.clone().with_subject_prefixing(false)
+    }
+
+    fn with_subject_prefixing(mut self, auto_prefix_subjects: bool) -> Self {
+        if self.metadata.auto_prefix_subjects != auto_prefix_subjects {
+            let mut metadata = self.metadata.as_ref().clone();
+            metadata.auto_prefix_subjects = auto_prefix_subjects;
+            self.metadata = Arc::new(metadata);
+        }
+        self
     }
 
     /// Returns last received info from the server.
@@ -212,7 +247,7 @@ impl Client {
     pub async fn publish(&self, subject: impl ToSubject, payload: Bytes) -> Result<()> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", payload.len());
         span.record(
@@ -284,7 +319,7 @@ impl Client {
     ) -> Result<()> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", payload.len());
         span.record(
@@ -352,7 +387,7 @@ impl Client {
     ) -> Result<()> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", payload.len());
         span.record(
@@ -430,7 +465,7 @@ impl Client {
     ) -> Result<()> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", payload.len());
         span.record(
@@ -492,7 +527,7 @@ impl Client {
     pub async fn request(&self, subject: impl ToSubject, payload: Bytes) -> Result<Message> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", payload.len());
         span.record(
@@ -564,7 +599,7 @@ impl Client {
     ) -> Result<Message> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record("messaging.message.body.size", payload.len());
         span.record(
@@ -629,7 +664,7 @@ impl Client {
     pub async fn send_request(&self, subject: impl ToSubject, request: Request) -> Result<Message> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         if let Some(ref payload) = request.payload {
             span.record("messaging.message.body.size", payload.len());
@@ -718,7 +753,7 @@ impl Client {
     pub async fn subscribe(&self, subject: impl ToSubject) -> Result<Subscriber> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record(
             "otel.name",
@@ -792,7 +827,7 @@ impl Client {
     ) -> Result<Subscriber> {
         let span = current_span_for_instrument_at!("debug");
 
-        let subject = subject.to_subject();
+        let subject = self.metadata.prefixed_subject(subject.to_subject());
         span.record("messaging.destination.name", subject.as_str());
         span.record(
             "otel.name",
@@ -1022,6 +1057,7 @@ impl Client {
             server_address: server_info.host,
             server_port: server_info.port,
             subject_prefix,
+            auto_prefix_subjects: false,
         };
 
         span.record("messaging.client_id", metadata.messaging_client_id.as_str());
@@ -1081,6 +1117,7 @@ pub struct ConnectionMetadata {
     server_address: String,
     server_port: u16,
     subject_prefix: Option<String>,
+    auto_prefix_subjects: bool,
 }
 
 impl ConnectionMetadata {
@@ -1148,6 +1185,31 @@ impl ConnectionMetadata {
     pub fn subject_prefix(&self) -> Option<&str> {
         self.subject_prefix.as_deref()
     }
+
+    /// Returns true if the subject prefix is applied automatically.
+    pub fn auto_prefix_subjects(&self) -> bool {
+        self.auto_prefix_subjects
+    }
+
+    /// Applies the subject prefix to the subject if it is applied automatically.
+    pub fn prefixed_subject(&self, subject: Subject) -> Subject {
+        match self.auto_prefix() {
+            Some(prefix) => subject_prefix::prefix_subject(prefix, subject),
+            None => subject,
+        }
+    }
+
+    /// Applies the subject prefix to a stream or bucket name if it is applied automatically.
+    pub fn prefixed_name(&self, name: &str) -> String {
+        match self.auto_prefix() {
+            Some(prefix) => subject_prefix::prefix_name(prefix, name),
+            None => name.to_owned(),
+        }
+    }
+
+    fn auto_prefix(&self) -> Option<&str> {
+        self.subject_prefix().filter(|_| self.auto_prefix_subjects)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,125 @@
+//! Automatic subject prefixing, so that several stacks can share one NATS cluster.
+//!
+//! With [`NatsConfig::auto_prefix_subjects`](crate::NatsConfig) set, a [`Client`](crate::Client)
+//! and any Jetstream [`Context`](crate::jetstream::Context) made from it apply the configured
+//! subject prefix to every subject they publish, request or subscribe to, and to the names of the
+//! streams, key value buckets and object stores they manage. Subjects become `{prefix}.{subject}`
+//! and names become `{prefix}_{name}`, the same as when prefixing by hand, and anything which is
+//! already prefixed is left alone so that both can be used side by side.
+//!
+//! System subjects, which start with `$` (such as the Jetstream API) or are inboxes, are never
+//! prefixed. Any other subject can be used as-is through
+//! [`Client::with_absolute_subjects`](crate::Client::with_absolute_subjects).
+//!
+//! Handles returned by `async_nats`, such as a stream's consumers and their filter subjects, are
+//! not covered and still need to be prefixed by hand.
+
+use async_nats::Subject;
+
+const INBOX_PREFIX: &str = "_INBOX";
+
+pub(crate) fn prefix_subject(prefix: &str, subject: Subject) -> Subject {
+    if subject.starts_with('$')
+        || subject.starts_with(INBOX_PREFIX)
+        || is_prefixed(prefix, &subject, '.')
+    {
+        subject
+    } else {
+        Subject::from(format!("{prefix}.{subject}"))
+    }
+}
+
+pub(crate) fn prefix_name(prefix: &str, name: &str) -> String {
+    if is_prefixed(prefix, name, '_') {
+        name.to_owned()
+    } else {
+        format!("{prefix}_{name}")
+    }
+}
+
+fn is_prefixed(prefix: &str, value: &str, separator: char) -> bool {
+    value
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.starts_with(separator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionMetadata;
+
+    fn metadata(subject_prefix: Option<&str>, auto_prefix_subjects: bool) -> ConnectionMetadata {
+        ConnectionMetadata {
+            messaging_client_id: "1".to_owned(),
+            messaging_nats_server_id: "server".to_owned(),
+            messaging_nats_server_name: "server".to_owned(),
+            messaging_nats_server_version: "2.10.0".to_owned(),
+            messaging_system: "nats",
+            messaging_url: "localhost".to_owned(),
+            network_peer_address: "127.0.0.1".to_owned(),
+            network_protocol_name: "nats",
+            network_protocol_version: "1".to_owned(),
+            network_transport: "ip_tcp",
+            server_address: "localhost".to_owned(),
+            server_port: 4222,
+            subject_prefix: subject_prefix.map(ToOwned::to_owned),
+            auto_prefix_subjects,
+        }
+    }
+
+    #[test]
+    fn prefixes_subjects() {
+        assert_eq!(
+            "stack.pinga.jobs",
+            prefix_subject("stack", Subject::from("pinga.jobs")).as_str()
+        );
+        // Only a whole token counts as the prefix
+        assert_eq!(
+            "stack.stacks.jobs",
+            prefix_subject("stack", Subject::from("stacks.jobs")).as_str()
+        );
+    }
+
+    #[test]
+    fn leaves_prefixed_and_system_subjects_alone() {
+        for subject in ["stack.pinga.jobs", "$JS.API.INFO", "_INBOX.abc123"] {
+            assert_eq!(
+                subject,
+                prefix_subject("stack", Subject::from(subject)).as_str()
+            );
+        }
+    }
+
+    #[test]
+    fn prefixes_names_once() {
+        assert_eq!("stack_PINGA_JOBS", prefix_name("stack", "PINGA_JOBS"));
+        assert_eq!("stack_PINGA_JOBS", prefix_name("stack", "stack_PINGA_JOBS"));
+        assert_eq!("stack_stackPINGA", prefix_name("stack", "stackPINGA"));
+    }
+
+    #[test]
+    fn only_prefixes_automatically_when_configured() {
+        let subject = Subject::from("pinga.jobs");
+
+        let manual = metadata(Some("stack"), false);
+        assert_eq!(
+            "pinga.jobs",
+            manual.prefixed_subject(subject.clone()).as_str()
+        );
+        assert_eq!("PINGA_JOBS", manual.prefixed_name("PINGA_JOBS"));
+
+        let unprefixed = metadata(None, true);
+        assert_eq!(
+            "pinga.jobs",
+            unprefixed.prefixed_subject(subject.clone()).as_str()
+        );
+        assert_eq!("PINGA_JOBS", unprefixed.prefixed_name("PINGA_JOBS"));
+
+        let automatic = metadata(Some("stack"), true);
+        assert_eq!(
+            "stack.pinga.jobs",
+            automatic.prefixed_subject(subject).as_str()
+        );
+        assert_eq!("stack_PINGA_JOBS", automatic.prefixed_name("PINGA_JOBS"));
+    }
+}
@@ -0,0 +1,517 @@
+//! Typed request/reply over core NATS.
+//!
+//! A request is serialized as JSON and sent with the current span's propagation telemetry in its
+//! headers. The responder answers with a [`Reply`], which is either the typed response or a
+//! [`ReplyError`] describing why the request failed, so that failures are reported to the
+//! requester as errors rather than as malformed responses.
+//!
+//! Payloads larger than the server's maximum payload are rejected before being sent.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use async_nats::subject::ToSubject;
+use bytes::Bytes;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use telemetry::{
+    opentelemetry::{global, propagation::Injector},
+    prelude::*,
+};
+
+use crate::{Client, Error, HeaderMap, HeaderName, HeaderValue, Message, Request, Result, Subject};
+
+// Matches the header used for the content type of versioned naxum API types
+const CONTENT_TYPE_HEADER: &str = "X-CONTENT-TYPE";
+const CONTENT_TYPE_JSON: &str = "application/json";
+
+/// The body of a reply to a typed request.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Reply<T> {
+    Ok(T),
+    Err(ReplyError),
+}
+
+impl<T> Reply<T> {
+    pub fn err(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Err(ReplyError {
+            code: code.into(),
+            message: message.into(),
+        })
+    }
+
+    pub fn into_result(self) -> std::result::Result<T, ReplyError> {
+        match self {
+            Self::Ok(value) => Ok(value),
+            Self::Err(err) => Err(err),
+        }
+    }
+}
+
+impl<T, E> From<std::result::Result<T, E>> for Reply<T>
+where
+    E: Into<ReplyError>,
+{
+    fn from(value: std::result::Result<T, E>) -> Self {
+        match value {
+            Ok(value) => Self::Ok(value),
+            Err(err) => Self::Err(err.into()),
+        }
+    }
+}
+
+/// An error replied by the responder of a typed request.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyError {
+    /// A short, machine readable description of the error, such as `not_found`.
+    pub code: String,
+    pub message: String,
+}
+
+impl fmt::Display for ReplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ReplyError {}
+
+impl Client {
+    /// Sends a typed request and waits up to the timeout for its typed reply.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), si_data_nats::Error> {
+    /// let client = si_data_nats::Client::connect_with_options(
+    ///     "demo.nats.io",
+    ///     None,
+    ///     Default::default(),
+    /// ).await?;
+    /// let sum: u64 = client
+    ///     .request_typed("math.sum", &[1, 2, 3], Duration::from_secs(5))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(
+        name = "nats_client.request_typed",
+        skip_all,
+        level = "debug",
+        fields(
+            messaging.destination.name = Empty,
+            otel.kind = SpanKind::Client.as_str(),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+        )
+    )]
+    pub async fn request_typed<Req, Resp>(
+        &self,
+        subject: impl ToSubject,
+        request: &Req,
+        timeout: Duration,
+    ) -> Result<Resp>
+    where
+        Req: Serialize + ?Sized,
+        Resp: DeserializeOwned,
+    {
+        let span = current_span_for_instrument_at!("debug");
+
+        let subject = subject.to_subject();
+        span.record("messaging.destination.name", subject.as_str());
+
+        let payload = self
+            .json_payload(request)
+            .map_err(|err| span.record_err(err))?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE_HEADER, CONTENT_TYPE_JSON);
+        inject_current_span(&mut headers);
+
+        let message = self
+            .send_request(
+                subject,
+                Request::new()
+                    .headers(headers)
+                    .payload(payload)
+                    .timeout(Some(timeout)),
+            )
+            .await
+            .map_err(|err| span.record_err(err))?;
+
+        let reply: Reply<Resp> = deserialize(&message).map_err(|err| span.record_err(err))?;
+        let response = reply
+            .into_result()
+            .map_err(|err| span.record_err(Error::ErrorReply(err)))?;
+
+        span.record_ok();
+        Ok(response)
+    }
+
+    /// Replies to a typed request, such as one sent by [`Client::request_typed`].
+    ///
+    /// The reply subject is used as given, as it is an inbox of the requester.
+    pub async fn reply_typed<T>(&self, reply_subject: Subject, reply: &Reply<T>) -> Result<()>
+    where
+        T: Serialize,
+    {
+        let payload = self.json_payload(reply)?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE_HEADER, CONTENT_TYPE_JSON);
+
+        self.with_absolute_subjects()
+            .publish_with_headers(reply_subject, headers, payload)
+            .await
+    }
+
+    fn json_payload<T>(&self, value: &T) -> Result<Bytes>
+    where
+        T: Serialize + ?Sized,
+    {
+        let payload = serde_json::to_vec(value).map_err(Error::Serialize)?;
+        let max_payload = self.server_info().max_payload;
+        if payload.len() > max_payload {
+            return Err(Error::PayloadTooLarge(payload.len(), max_payload));
+        }
+
+        Ok(payload.into())
+    }
+}
+
+fn deserialize<T>(message: &Message) -> Result<Reply<T>>
+where
+    T: DeserializeOwned,
+{
+    let deserializer = &mut serde_json::Deserializer::from_slice(message.payload());
+    serde_path_to_error::deserialize(deserializer).map_err(Error::Deserialize)
+}
+
+fn inject_current_span(headers: &mut HeaderMap) {
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let ctx = Span::current().context();
+    let mut injector = HeaderInjector(headers);
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&ctx, &mut injector));
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (HeaderName::from_str(key), HeaderValue::from_str(&value)) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use futures::StreamExt;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        net::{tcp::OwnedReadHalf, TcpListener, TcpStream},
+        sync::mpsc,
+    };
+
+    use super::*;
+
+    const SUBJECT: &str = "math.sum";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// The subscriptions of a fake NATS server: the subject pattern and sid of each, along with
+    /// the connection to deliver matching messages to.
+    type Subscriptions = Arc<Mutex<Vec<(String, String, mpsc::UnboundedSender<Vec<u8>>)>>>;
+
+    /// Starts a fake NATS server which routes published messages to the subscriptions of its
+    /// clients, and connects a client to it.
+    async fn connect(max_payload: usize) -> Client {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind fake nats server");
+        let addr = listener.local_addr().expect("failed to get local addr");
+        let subscriptions = Subscriptions::default();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_client(stream, max_payload, subscriptions.clone()));
+            }
+        });
+
+        Client::connect_with_options(format!("nats://{addr}"), None, Default::default())
+            .await
+            .expect("failed to connect to fake nats server")
+    }
+
+    async fn serve_client(
+        stream: TcpStream,
+        max_payload: usize,
+        subscriptions: Subscriptions,
+    ) -> io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        let (tx, mut rx) = mpsc::unbounded_channel::<Vec<u8>>();
+        tokio::spawn(async move {
+            while let Some(bytes) = rx.recv().await {
+                if writer.write_all(&bytes).await.is_err() {
+                    break;
+                }
+            }
+        });
+        let _ = tx.send(
+            format!(
+                "INFO {{\"server_id\":\"test\",\"version\":\"2.10.0\",\"proto\":1,\"headers\":true,\"max_payload\":{max_payload}}}\r\n"
+            )
+            .into_bytes(),
+        );
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let args: Vec<&str> = line.split_whitespace().collect();
+            match args.as_slice() {
+                ["PING"] => {
+                    let _ = tx.send(b"PONG\r\n".to_vec());
+                }
+                ["SUB", subject, .., sid] => subscriptions.lock().expect("poisoned").push((
+                    subject.to_string(),
+                    sid.to_string(),
+                    tx.clone(),
+                )),
+                ["UNSUB", sid, ..] => subscriptions
+                    .lock()
+                    .expect("poisoned")
+                    .retain(|(_, subscribed, _)| subscribed != sid),
+                ["PUB", subject, reply @ .., len] => {
+                    let payload = read_payload(&mut reader, len).await?;
+                    route(&subscriptions, "MSG", subject, reply, &[len], &payload);
+                }
+                ["HPUB", subject, reply @ .., headers_len, len] => {
+                    let payload = read_payload(&mut reader, len).await?;
+                    route(
+                        &subscriptions,
+                        "HMSG",
+                        subject,
+                        reply,
+                        &[headers_len, len],
+                        &payload,
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads a payload, along with the CRLF which follows it.
+    async fn read_payload(reader: &mut BufReader<OwnedReadHalf>, len: &str) -> io::Result<Vec<u8>> {
+        let len: usize = len
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let mut payload = vec![0; len + 2];
+        reader.read_exact(&mut payload).await?;
+        Ok(payload)
+    }
+
+    fn route(
+        subscriptions: &Subscriptions,
+        operation: &str,
+        subject: &str,
+        reply: &[&str],
+        lens: &[&str],
+        payload: &[u8],
+    ) {
+        for (pattern, sid, tx) in subscriptions.lock().expect("poisoned").iter() {
+            if matches_subject(pattern, subject) {
+                let mut bytes = [&[operation, subject, sid][..], reply, lens]
+                    .concat()
+                    .join(" ")
+                    .into_bytes();
+                bytes.extend_from_slice(b"\r\n");
+                bytes.extend_from_slice(payload);
+                let _ = tx.send(bytes);
+            }
+        }
+    }
+
+    fn matches_subject(pattern: &str, subject: &str) -> bool {
+        let mut subject = subject.split('.');
+        for token in pattern.split('.') {
+            match (token, subject.next()) {
+                (">", Some(_)) => return true,
+                ("*", Some(_)) => {}
+                (token, Some(subject_token)) if token == subject_token => {}
+                _ => return false,
+            }
+        }
+        subject.next().is_none()
+    }
+
+    /// Answers the next request on the subject with the reply returned for its payload.
+    async fn respond<F>(client: &Client, reply: F)
+    where
+        F: FnOnce(&Message) -> Bytes + Send + 'static,
+    {
+        let mut subscriber = client
+            .subscribe(SUBJECT)
+            .await
+            .expect("failed to subscribe");
+        let client = client.clone();
+        tokio::spawn(async move {
+            let Some(request) = subscriber.next().await else {
+                return;
+            };
+            let reply_subject = request
+                .reply()
+                .expect("request has no reply subject")
+                .clone();
+            client
+                .with_absolute_subjects()
+                .publish(reply_subject, reply(&request))
+                .await
+                .expect("failed to reply");
+        });
+    }
+
+    fn json<T: Serialize>(value: &T) -> Bytes {
+        serde_json::to_vec(value)
+            .expect("failed to serialize")
+            .into()
+    }
+
+    #[test]
+    fn fake_server_matches_subjects_like_nats() {
+        assert!(matches_subject("math.sum", "math.sum"));
+        assert!(matches_subject("_INBOX.abc.*", "_INBOX.abc.1"));
+        assert!(matches_subject("math.>", "math.sum.all"));
+        assert!(!matches_subject("math.*", "math.sum.all"));
+        assert!(!matches_subject("math.sum", "math"));
+        assert!(!matches_subject("math.>", "math"));
+    }
+
+    #[tokio::test]
+    async fn typed_requests_round_trip() {
+        let client = connect(1024 * 1024).await;
+        let (requests_tx, mut requests_rx) = mpsc::unbounded_channel();
+        respond(&client, move |request| {
+            let _ = requests_tx.send(request.headers().cloned());
+            let numbers: Vec<u64> =
+                serde_json::from_slice(request.payload()).expect("request is not json");
+            json(&Reply::Ok(numbers.iter().sum::<u64>()))
+        })
+        .await;
+
+        let sum: u64 = client
+            .request_typed(SUBJECT, &[1, 2, 3], TIMEOUT)
+            .await
+            .expect("request failed");
+        assert_eq!(6, sum);
+
+        let headers = requests_rx
+            .recv()
+            .await
+            .flatten()
+            .expect("request has no headers");
+        assert_eq!(
+            Some(CONTENT_TYPE_JSON),
+            headers.get(CONTENT_TYPE_HEADER).map(HeaderValue::as_str)
+        );
+    }
+
+    #[tokio::test]
+    async fn reply_typed_answers_request_typed() {
+        let client = connect(1024 * 1024).await;
+        let mut subscriber = client
+            .subscribe(SUBJECT)
+            .await
+            .expect("failed to subscribe");
+        let responder = client.clone();
+        tokio::spawn(async move {
+            if let Some(request) = subscriber.next().await {
+                let reply_subject = request.reply().expect("request has no reply subject");
+                responder
+                    .reply_typed(reply_subject.clone(), &Reply::Ok("pong"))
+                    .await
+                    .expect("failed to reply");
+            }
+        });
+
+        let pong: String = client
+            .request_typed(SUBJECT, "ping", TIMEOUT)
+            .await
+            .expect("request failed");
+        assert_eq!("pong", pong);
+    }
+
+    #[tokio::test]
+    async fn error_replies_are_errors() {
+        let client = connect(1024 * 1024).await;
+        respond(&client, |_| {
+            json(&Reply::<u64>::err("not_found", "no such sum"))
+        })
+        .await;
+
+        let result: Result<u64> = client.request_typed(SUBJECT, &[1, 2, 3], TIMEOUT).await;
+        assert!(
+            matches!(
+                &result,
+                Err(Error::ErrorReply(ReplyError { code, message }))
+                    if code == "not_found" && message == "no such sum"
+            ),
+            "expected an error reply, got: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_replies_are_errors() {
+        let client = connect(1024 * 1024).await;
+        respond(&client, |_| Bytes::from_static(b"{\"ok\":")).await;
+
+        let result: Result<u64> = client.request_typed(SUBJECT, &[1, 2, 3], TIMEOUT).await;
+        assert!(
+            matches!(result, Err(Error::Deserialize(_))),
+            "expected a deserialize error, got: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_without_a_reply_time_out() {
+        let client = connect(1024 * 1024).await;
+        // Subscribed, so the request is delivered, but never answered
+        let _subscriber = client
+            .subscribe(SUBJECT)
+            .await
+            .expect("failed to subscribe");
+
+        let result: Result<u64> = client
+            .request_typed(SUBJECT, &[1, 2, 3], Duration::from_millis(100))
+            .await;
+        assert!(
+            matches!(
+                &result,
+                Err(Error::NatsRequest(err))
+                    if err.kind() == async_nats::RequestErrorKind::TimedOut
+            ),
+            "expected a timeout, got: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn payloads_over_the_server_maximum_are_rejected() {
+        let client = connect(16).await;
+
+        let result: Result<u64> = client.request_typed(SUBJECT, &[u64::MAX; 4], TIMEOUT).await;
+        assert!(
+            matches!(result, Err(Error::PayloadTooLarge(_, 16))),
+            "expected the payload to be rejected, got: {result:?}"
+        );
+    }
+}
@@ -17,12 +17,13 @@ use tokio_postgres_rustls::MakeRustlsConnect;
 use base64::{engine::general_purpose, Engine};
 use std::{
     cmp,
+    collections::{BTreeMap, HashMap},
     fmt::{self, Debug},
     io::Write,
     net::ToSocketAddrs,
     path::Path,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use bytes::Buf;
@@ -30,9 +31,9 @@ use deadpool::managed::Object;
 use deadpool_postgres::SslMode;
 use deadpool_postgres::{
     Config, ConfigError, CreatePoolError, Manager, ManagerConfig, Pool, PoolConfig, PoolError,
-    RecyclingMethod, Transaction, TransactionBuilder,
+    RecyclingMethod, StatementCache, Transaction, TransactionBuilder,
 };
-use futures::{Stream, StreamExt};
+use futures::{Future, Stream, StreamExt};
 
 use ouroboros::self_referencing;
 
@@ -52,7 +53,15 @@ pub use tokio_postgres::error::SqlState;
 pub use tokio_postgres::types as postgres_types;
 
 const MIGRATION_LOCK_NUMBER: i64 = 42;
+const MIGRATION_SESSION_TIMEOUTS: &str =
+    "SET statement_timeout = 0; SET idle_in_transaction_session_timeout = 0";
+// Resetting returns to the values set through the connection options
+const RESET_SESSION_TIMEOUTS: &str =
+    "RESET statement_timeout; RESET idle_in_transaction_session_timeout";
 const MAX_POOL_SIZE_MINIMUM: usize = 32;
+// Enough to hold every distinct query the services issue, while bounding the memory used on the
+// server for each connection
+const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 512;
 
 const TEST_QUERY: &str = "SELECT 1";
 
@@ -147,6 +156,24 @@ pub struct PgPoolConfig {
     pub pool_timeout_wait_secs: Option<u64>,
     pub pool_timeout_create_secs: Option<u64>,
     pub pool_timeout_recycle_secs: Option<u64>,
+    /// The maximum number of prepared statements cached per connection. The least recently used
+    /// statements are evicted when a cache would grow past this. `0` disables caching, e.g. when
+    /// connecting through a pooler which does not support prepared statements.
+    pub statement_cache_capacity: usize,
+    /// The hostname of a read replica to route read-only queries to through [`PgPool::read`]. The
+    /// replica is connected to with the same credentials and settings as the primary.
+    pub read_replica_hostname: Option<String>,
+    /// The port of the read replica, defaulting to the port of the primary.
+    pub read_replica_port: Option<u16>,
+    /// Aborts statements which run for longer than this, by setting `statement_timeout` on every
+    /// connection in the pool.
+    pub statement_timeout_ms: Option<u64>,
+    /// Terminates connections which sit idle in an open transaction for longer than this, by
+    /// setting `idle_in_transaction_session_timeout` on every connection in the pool.
+    pub idle_in_transaction_timeout_ms: Option<u64>,
+    /// Queries which take at least this long emit a `pg.slow_query` warning span with the
+    /// statement and its duration.
+    pub slow_query_threshold_ms: Option<u64>,
 }
 
 impl Default for PgPoolConfig {
@@ -167,6 +194,12 @@ impl Default for PgPoolConfig {
             pool_timeout_wait_secs: None,
             pool_timeout_create_secs: None,
             pool_timeout_recycle_secs: None,
+            statement_cache_capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+            read_replica_hostname: None,
+            read_replica_port: None,
+            statement_timeout_ms: None,
+            idle_in_transaction_timeout_ms: None,
+            slow_query_threshold_ms: None,
         }
     }
 }
@@ -175,16 +208,31 @@ impl Default for PgPoolConfig {
 pub struct PgPool {
     pool: Pool,
     metadata: Arc<ConnectionMetadata>,
+    read_replica: Option<Arc<PgPool>>,
 }
 
 impl std::fmt::Debug for PgPool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PgPool")
             .field("metadata", &self.metadata)
+            .field("read_replica", &self.read_replica)
             .finish_non_exhaustive()
     }
 }
 
+/// A snapshot of the utilization of a [`PgPool`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PgPoolStatus {
+    /// The maximum number of connections of the pool.
+    pub max_size: usize,
+    /// The number of connections currently held by the pool, in use or not.
+    pub size: usize,
+    /// The number of idle connections.
+    pub available: usize,
+    /// The number of callers waiting for a connection.
+    pub waiting: usize,
+}
+
 #[derive(Clone, Debug)]
 struct ConnectionMetadata {
     db_system: &'static str,
@@ -192,12 +240,75 @@ struct ConnectionMetadata {
     db_name: String,
     db_user: String,
     db_pool_max_size: usize,
+    db_statement_cache_capacity: usize,
+    statement_recency: Arc<StatementRecency>,
     net_peer_ip: String,
     net_peer_port: u16,
     net_transport: &'static str,
+    slow_query_threshold: Option<Duration>,
+}
+
+impl ConnectionMetadata {
+    fn slow_query_guard<'a>(
+        &'a self,
+        operation: &'static str,
+        statement: &'a str,
+    ) -> Option<SlowQueryGuard<'a>> {
+        self.slow_query_threshold.map(|threshold| SlowQueryGuard {
+            operation,
+            statement,
+            threshold,
+            start: Instant::now(),
+        })
+    }
+}
+
+/// Emits a `pg.slow_query` warning span when dropped if the query it was made for took at least
+/// the configured threshold, whether or not the query succeeded.
+struct SlowQueryGuard<'a> {
+    operation: &'static str,
+    statement: &'a str,
+    threshold: Duration,
+    start: Instant,
+}
+
+impl Drop for SlowQueryGuard<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        if elapsed < self.threshold {
+            return;
+        }
+
+        warn_span!(
+            "pg.slow_query",
+            db.operation = self.operation,
+            db.statement = self.statement,
+            db.duration_ms = elapsed.as_millis() as u64,
+            db.slow_query_threshold_ms = self.threshold.as_millis() as u64,
+        )
+        .in_scope(|| {
+            warn!(
+                db.operation = self.operation,
+                db.duration_ms = elapsed.as_millis() as u64,
+                "slow query",
+            )
+        });
+    }
 }
 
 impl PgPool {
+    /// Creates a pool for the primary and, if one is configured, a pool for the read replica.
+    pub async fn new(settings: &PgPoolConfig) -> PgPoolResult<Self> {
+        let mut pg_pool = Self::connect(settings, &settings.hostname, settings.port).await?;
+
+        if let Some(hostname) = &settings.read_replica_hostname {
+            let port = settings.read_replica_port.unwrap_or(settings.port);
+            pg_pool.read_replica = Some(Arc::new(Self::connect(settings, hostname, port).await?));
+        }
+
+        Ok(pg_pool)
+    }
+
     #[instrument(
         name = "pg_pool::new",
         skip_all,
@@ -213,16 +324,17 @@ impl PgPool {
             net.transport = Empty,
         )
     )]
-    pub async fn new(settings: &PgPoolConfig) -> PgPoolResult<Self> {
+    async fn connect(settings: &PgPoolConfig, hostname: &str, port: u16) -> PgPoolResult<Self> {
         let span = current_span_for_instrument_at!("debug");
 
         let mut cfg = Config::new();
-        cfg.hosts = Some(vec![settings.hostname.clone()]);
-        cfg.port = Some(settings.port);
+        cfg.hosts = Some(vec![hostname.to_owned()]);
+        cfg.port = Some(port);
         cfg.user = Some(settings.user.clone());
         cfg.password = Some(settings.password.clone().into());
         cfg.dbname = Some(settings.dbname.clone());
         cfg.application_name = Some(settings.application_name.clone());
+        cfg.options = session_options(settings);
         cfg.manager = Some(ManagerConfig {
             recycling_method: RecyclingMethod::Custom(CONNECTION_RECYCLING_METHOD.to_string()),
         });
@@ -247,7 +359,7 @@ impl PgPool {
         cfg.pool = Some(pool_config);
         let pool = cfg.create_pool(Some(deadpool_postgres::Runtime::Tokio1), tls_config)?;
 
-        let resolving_hostname = format!("{}:{}", settings.hostname, settings.port);
+        let resolving_hostname = format!("{hostname}:{port}");
         let net_peer_ip = tokio::task::spawn_blocking(move || {
             resolving_hostname
                 .to_socket_addrs()
@@ -261,14 +373,17 @@ impl PgPool {
             db_system: "postgresql",
             db_connection_string: format!(
                 "postgresql://{}:{}/{}?application_name={}",
-                settings.hostname, settings.port, settings.dbname, settings.application_name
+                hostname, port, settings.dbname, settings.application_name
             ),
             db_name: settings.dbname.clone(),
             db_user: settings.user.clone(),
             db_pool_max_size: settings.pool_max_size,
+            db_statement_cache_capacity: settings.statement_cache_capacity,
+            statement_recency: Arc::new(StatementRecency::new(settings.statement_cache_capacity)),
             net_peer_ip,
-            net_peer_port: settings.port,
+            net_peer_port: port,
             net_transport: "ip_tcp",
+            slow_query_threshold: settings.slow_query_threshold_ms.map(Duration::from_millis),
         };
 
         span.record("db.system", metadata.db_system);
@@ -286,6 +401,7 @@ impl PgPool {
         let pg_pool = Self {
             pool,
             metadata: Arc::new(metadata),
+            read_replica: None,
         };
 
         // Warm up the pool and test that we can connect to the database. Note that this is only
@@ -418,11 +534,39 @@ impl PgPool {
         Ok(())
     }
 
+    /// Gets the pool for read-only queries, which is the read replica's if one is configured and
+    /// the primary's otherwise. Reads from a replica can lag behind writes to the primary.
+    pub fn read(&self) -> &PgPool {
+        self.read_replica.as_deref().unwrap_or(self)
+    }
+
+    /// Gets the pool of the primary, for writes and transactions.
+    pub fn write(&self) -> &PgPool {
+        self
+    }
+
+    /// Returns true if a read replica is configured.
+    pub fn has_read_replica(&self) -> bool {
+        self.read_replica.is_some()
+    }
+
     /// Gets the database name for connections in the pool.
     pub fn db_name(&self) -> &str {
         &self.metadata.db_name
     }
 
+    /// Gets a snapshot of how many connections of the pool are in use and how many callers are
+    /// waiting for one.
+    pub fn status(&self) -> PgPoolStatus {
+        let status = self.pool.status();
+        PgPoolStatus {
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available,
+            waiting: status.waiting,
+        }
+    }
+
     /// Retrieve object from pool or wait for one to become available.
     #[instrument(
         name = "pg_pool.get",
@@ -474,21 +618,19 @@ impl PgPool {
     )]
     pub async fn migrate(&self, runner: refinery::Runner) -> PgPoolResult<()> {
         let mut conn = self.pool.get().await?;
+        // Migrations, and waiting on another instance's migrations, can take far longer than the
+        // configured timeouts allow any other statement
+        conn.batch_execute(MIGRATION_SESSION_TIMEOUTS).await?;
         conn.query_one("SELECT pg_advisory_lock($1)", &[&MIGRATION_LOCK_NUMBER])
             .await?;
         let client = &mut **conn;
-        match runner.run_async(client).await {
-            Ok(_) => {
-                conn.query_one("SELECT pg_advisory_unlock($1)", &[&MIGRATION_LOCK_NUMBER])
-                    .await?;
-                Ok(())
-            }
-            Err(e) => {
-                conn.query_one("SELECT pg_advisory_unlock($1)", &[&MIGRATION_LOCK_NUMBER])
-                    .await?;
-                Err(e.into())
-            }
-        }
+        let result = runner.run_async(client).await;
+        conn.query_one("SELECT pg_advisory_unlock($1)", &[&MIGRATION_LOCK_NUMBER])
+            .await?;
+        // Back to the pool's timeouts before the connection is returned to it
+        conn.batch_execute(RESET_SESSION_TIMEOUTS).await?;
+
+        result.map(|_| ()).map_err(Into::into)
     }
 
     #[instrument(
@@ -518,6 +660,154 @@ macro_rules! current_span_for_debug {
     };
 }
 
+/// Tracks when each query was last prepared across all connections of a pool, so that a full
+/// [`StatementCache`] can evict its least recently used statements instead of starting over.
+///
+/// Connections in a pool serve interchangeable work, so the pool-wide order is a close stand-in
+/// for the order of any one connection, and it avoids having to identify connections.
+#[derive(Debug)]
+struct StatementRecency {
+    /// The number of queries to remember, beyond which the least recently used are forgotten.
+    tracked_capacity: usize,
+    inner: std::sync::Mutex<StatementRecencyInner>,
+}
+
+#[derive(Debug, Default)]
+struct StatementRecencyInner {
+    tick: u64,
+    last_used: HashMap<Arc<str>, u64>,
+    by_last_used: BTreeMap<u64, Arc<str>>,
+}
+
+impl StatementRecency {
+    // Remember more queries than any one connection caches, as each connection caches a different
+    // subset of them
+    const TRACKED_PER_CACHED: usize = 4;
+
+    fn new(statement_cache_capacity: usize) -> Self {
+        Self {
+            tracked_capacity: statement_cache_capacity.saturating_mul(Self::TRACKED_PER_CACHED),
+            inner: std::sync::Mutex::new(StatementRecencyInner::default()),
+        }
+    }
+
+    /// Records that a query was just prepared.
+    fn touch(&self, query: &str) {
+        let mut inner = self.inner.lock().unwrap_or_else(|err| err.into_inner());
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        let query: Arc<str> = match inner.last_used.get_key_value(query) {
+            Some((query, previous)) => {
+                let (query, previous) = (query.clone(), *previous);
+                inner.by_last_used.remove(&previous);
+                query
+            }
+            None => Arc::from(query),
+        };
+        inner.last_used.insert(query.clone(), tick);
+        inner.by_last_used.insert(tick, query);
+
+        while inner.last_used.len() > self.tracked_capacity {
+            match inner.by_last_used.pop_first() {
+                Some((_, forgotten)) => {
+                    inner.last_used.remove(&forgotten);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Removes statements from a full cache, least recently used first, until it has room for one
+    /// more. Returns the number of statements removed.
+    ///
+    /// Falls back to emptying the cache if none of the statements it holds are still tracked.
+    fn evict(&self, statement_cache: &impl CachedStatements, capacity: usize) -> usize {
+        let inner = self.inner.lock().unwrap_or_else(|err| err.into_inner());
+
+        let mut evicted = 0;
+        for query in inner.by_last_used.values() {
+            if statement_cache.size() < capacity {
+                return evicted;
+            }
+            if statement_cache.remove(query) {
+                evicted += 1;
+            }
+        }
+
+        let size = statement_cache.size();
+        if size >= capacity {
+            statement_cache.clear();
+            evicted += size;
+        }
+        evicted
+    }
+}
+
+/// The operations on a connection's [`StatementCache`] needed to keep it within capacity.
+trait CachedStatements {
+    fn size(&self) -> usize;
+
+    /// Removes the statement prepared for a query, returning whether it was cached.
+    fn remove(&self, query: &str) -> bool;
+
+    fn clear(&self);
+}
+
+impl CachedStatements for StatementCache {
+    fn size(&self) -> usize {
+        StatementCache::size(self)
+    }
+
+    fn remove(&self, query: &str) -> bool {
+        // Statements are always prepared without explicit parameter types
+        StatementCache::remove(self, query, &[]).is_some()
+    }
+
+    fn clear(&self) {
+        StatementCache::clear(self);
+    }
+}
+
+/// Prepares a statement through a connection's [`StatementCache`], evicting the least recently
+/// used statements first if it is full and recording whether the statement was already cached.
+async fn prepare_within_capacity<T>(
+    statement_cache: &impl CachedStatements,
+    capacity: usize,
+    recency: &StatementRecency,
+    query: &str,
+    prepare_cached: impl Future<Output = Result<T, tokio_postgres::Error>>,
+) -> Result<T, PgError> {
+    if statement_cache.size() >= capacity {
+        let evicted = recency.evict(statement_cache, capacity);
+        metric!(counter.pg.statement_cache.evicted = evicted);
+    }
+    let size = statement_cache.size();
+
+    let statement = prepare_cached.await?;
+    recency.touch(query);
+    if statement_cache.size() > size {
+        metric!(counter.pg.statement_cache.miss = 1);
+    } else {
+        metric!(counter.pg.statement_cache.hit = 1);
+    }
+
+    Ok(statement)
+}
+
+/// Builds the `options` connection parameter which sets the configured timeouts for each session.
+fn session_options(settings: &PgPoolConfig) -> Option<String> {
+    let mut options = Vec::new();
+    if let Some(ms) = settings.statement_timeout_ms {
+        options.push(format!("-c statement_timeout={ms}"));
+    }
+    if let Some(ms) = settings.idle_in_transaction_timeout_ms {
+        options.push(format!("-c idle_in_transaction_session_timeout={ms}"));
+    }
+
+    (!options.is_empty()).then(|| options.join(" "))
+}
+
 /// An instrumented wrapper for `deadpool::managed::Object<deadpool_postgres::Manager>`
 pub struct InstrumentedClient {
     inner: Object<Manager>,
@@ -525,6 +815,24 @@ pub struct InstrumentedClient {
 }
 
 impl InstrumentedClient {
+    /// Prepares a statement for a raw query string, using the connection's statement cache unless
+    /// it is disabled.
+    async fn statement(&self, query: &str) -> Result<Statement, PgError> {
+        match self.metadata.db_statement_cache_capacity {
+            0 => self.inner.prepare(query).await.map_err(Into::into),
+            capacity => {
+                prepare_within_capacity(
+                    &self.inner.statement_cache,
+                    capacity,
+                    &self.metadata.statement_recency,
+                    query,
+                    self.inner.prepare_cached(query),
+                )
+                .await
+            }
+        }
+    }
+
     /// Like [`tokio_postgres::Transaction::prepare`](#method.prepare-1)
     /// but uses an existing statement from the cache if possible.
     #[instrument(
@@ -706,11 +1014,13 @@ impl InstrumentedClient {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<Vec<PgRow>, PgError> {
+        let _slow_query = self.metadata.slow_query_guard("pg_client.query", statement);
+        let statement = self.statement(statement).await?;
         let span = current_span_for_instrument_at!("debug");
 
         let r = self
             .inner
-            .query(statement, params)
+            .query(&statement, params)
             .await
             .map(|rows| {
                 rows.into_iter()
@@ -760,11 +1070,15 @@ impl InstrumentedClient {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<PgRow, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_client.query_one", statement);
+        let statement = self.statement(statement).await?;
         let span = current_span_for_instrument_at!("debug");
 
         let r = self
             .inner
-            .query_one(statement, params)
+            .query_one(&statement, params)
             .await
             .map(|inner| PgRow { inner })
             .map_err(Into::into);
@@ -810,11 +1124,15 @@ impl InstrumentedClient {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<Option<PgRow>, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_client.query_opt", statement);
+        let statement = self.statement(statement).await?;
         let span = current_span_for_instrument_at!("debug");
 
         let r = self
             .inner
-            .query_opt(statement, params)
+            .query_opt(&statement, params)
             .await
             .map(|maybe| maybe.map(|inner| PgRow { inner }))
             .map_err(Into::into);
@@ -870,6 +1188,9 @@ impl InstrumentedClient {
         I: IntoIterator<Item = P>,
         I::IntoIter: ExactSizeIterator,
     {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_client.query_raw", statement);
         self.inner
             .query_raw(statement, params)
             .await
@@ -915,8 +1236,12 @@ impl InstrumentedClient {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<u64, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_client.execute", statement);
+        let statement = self.statement(statement).await?;
         self.inner
-            .execute(statement, params)
+            .execute(&statement, params)
             .await
             .map_err(Into::into)
     }
@@ -957,6 +1282,9 @@ impl InstrumentedClient {
         I: IntoIterator<Item = P>,
         I::IntoIter: ExactSizeIterator,
     {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_client.execute_raw", statement);
         self.inner
             .execute_raw(statement, params)
             .await
@@ -1057,6 +1385,9 @@ impl InstrumentedClient {
         )
     )]
     pub async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_client.simple_query", query);
         self.inner.simple_query(query).await.map_err(Into::into)
     }
 
@@ -1088,6 +1419,9 @@ impl InstrumentedClient {
         )
     )]
     pub async fn batch_execute(&self, query: &str) -> Result<(), PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_client.batch_execute", query);
         self.inner.batch_execute(query).await.map_err(Into::into)
     }
 
@@ -1153,6 +1487,31 @@ impl<'a> InstrumentedTransaction<'a> {
         }
     }
 
+    /// Prepares a statement for a raw query string, using the connection's statement cache unless
+    /// it is disabled.
+    async fn statement(&self, query: &str) -> Result<Statement, PgError> {
+        match self.metadata.db_statement_cache_capacity {
+            0 => self
+                .inner
+                .prepare(query)
+                .instrument(self.tx_span.clone())
+                .await
+                .map_err(Into::into),
+            capacity => {
+                prepare_within_capacity(
+                    &self.inner.statement_cache,
+                    capacity,
+                    &self.metadata.statement_recency,
+                    query,
+                    self.inner
+                        .prepare_cached(query)
+                        .instrument(self.tx_span.clone()),
+                )
+                .await
+            }
+        }
+    }
+
     /// Like [`tokio_postgres::Transaction::prepare`](#method.prepare-1)
     /// but uses an existing statement from the cache if possible.
     #[instrument(
@@ -1384,12 +1743,16 @@ impl<'a> InstrumentedTransaction<'a> {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<Vec<PgRow>, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_transaction.query", statement);
+        let statement = self.statement(statement).await?;
         let span = current_span_for_instrument_at!("debug");
 
         span.follows_from(&self.tx_span);
         let r = self
             .inner
-            .query(statement, params)
+            .query(&statement, params)
             .instrument(self.tx_span.clone())
             .await
             .map(|rows| {
@@ -1440,12 +1803,16 @@ impl<'a> InstrumentedTransaction<'a> {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<PgRow, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_transaction.query_one", statement);
+        let statement = self.statement(statement).await?;
         let span = current_span_for_instrument_at!("debug");
 
         span.follows_from(&self.tx_span);
         let r = self
             .inner
-            .query_one(statement, params)
+            .query_one(&statement, params)
             .instrument(self.tx_span.clone())
             .await
             .map(|inner| PgRow { inner })
@@ -1492,12 +1859,16 @@ impl<'a> InstrumentedTransaction<'a> {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<Option<PgRow>, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_transaction.query_opt", statement);
+        let statement = self.statement(statement).await?;
         let span = current_span_for_instrument_at!("debug");
 
         span.follows_from(&self.tx_span);
         let r = self
             .inner
-            .query_opt(statement, params)
+            .query_opt(&statement, params)
             .instrument(self.tx_span.clone())
             .await
             .map(|maybe| maybe.map(|inner| PgRow { inner }))
@@ -1554,6 +1925,9 @@ impl<'a> InstrumentedTransaction<'a> {
         I: IntoIterator<Item = P>,
         I::IntoIter: ExactSizeIterator,
     {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_transaction.query_raw", statement);
         let span = current_span_for_instrument_at!("debug");
 
         span.follows_from(&self.tx_span);
@@ -1603,11 +1977,15 @@ impl<'a> InstrumentedTransaction<'a> {
         statement: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> Result<u64, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_transaction.execute", statement);
+        let statement = self.statement(statement).await?;
         let span = current_span_for_instrument_at!("debug");
 
         span.follows_from(&self.tx_span);
         self.inner
-            .execute(statement, params)
+            .execute(&statement, params)
             .instrument(self.tx_span.clone())
             .await
             .map_err(Into::into)
@@ -1649,6 +2027,9 @@ impl<'a> InstrumentedTransaction<'a> {
         I: IntoIterator<Item = P>,
         I::IntoIter: ExactSizeIterator,
     {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_transaction.execute_raw", statement);
         let span = current_span_for_instrument_at!("debug");
 
         span.follows_from(&self.tx_span);
@@ -1921,6 +2302,9 @@ impl<'a> InstrumentedTransaction<'a> {
         )
     )]
     pub async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_transaction.simple_query", query);
         let span = current_span_for_instrument_at!("debug");
 
         span.follows_from(&self.tx_span);
@@ -1959,6 +2343,9 @@ impl<'a> InstrumentedTransaction<'a> {
         )
     )]
     pub async fn batch_execute(&self, query: &str) -> Result<(), PgError> {
+        let _slow_query = self
+            .metadata
+            .slow_query_guard("pg_transaction.batch_execute", query);
         let span = current_span_for_instrument_at!("debug");
 
         span.follows_from(&self.tx_span);
@@ -2891,3 +3278,168 @@ impl PgOwnedTransaction {
 async fn test_connection_infallible_and_warm_up_pool_task(check_pool: PgPool) {
     let _result = check_pool.test_connection().await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_options_set_configured_timeouts() {
+        assert_eq!(None, session_options(&PgPoolConfig::default()));
+
+        let config = PgPoolConfig {
+            statement_timeout_ms: Some(30_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some("-c statement_timeout=30000"),
+            session_options(&config).as_deref()
+        );
+
+        let config = PgPoolConfig {
+            statement_timeout_ms: Some(30_000),
+            idle_in_transaction_timeout_ms: Some(60_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some("-c statement_timeout=30000 -c idle_in_transaction_session_timeout=60000"),
+            session_options(&config).as_deref()
+        );
+    }
+
+    #[test]
+    fn migrations_lift_and_restore_every_session_timeout() {
+        for setting in ["statement_timeout", "idle_in_transaction_session_timeout"] {
+            assert!(MIGRATION_SESSION_TIMEOUTS.contains(&format!("SET {setting} = 0")));
+            assert!(RESET_SESSION_TIMEOUTS.contains(&format!("RESET {setting}")));
+        }
+    }
+
+    /// Holds queries in place of prepared statements, which need a live connection.
+    #[derive(Default)]
+    struct FakeStatementCache {
+        queries: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl FakeStatementCache {
+        fn with(queries: &[&str]) -> Self {
+            Self {
+                queries: std::sync::Mutex::new(queries.iter().map(|q| q.to_string()).collect()),
+            }
+        }
+
+        fn queries(&self) -> Vec<String> {
+            let mut queries = self.queries.lock().expect("poisoned").clone();
+            queries.sort();
+            queries
+        }
+
+        /// Prepares a query through the cache like a connection would, returning whether it was
+        /// a cache miss.
+        async fn prepare(&self, capacity: usize, recency: &StatementRecency, query: &str) -> bool {
+            let missed = !self
+                .queries
+                .lock()
+                .expect("poisoned")
+                .iter()
+                .any(|q| q == query);
+            let prepare_cached = async {
+                let mut queries = self.queries.lock().expect("poisoned");
+                if !queries.iter().any(|q| q == query) {
+                    queries.push(query.to_owned());
+                }
+                Ok(())
+            };
+            prepare_within_capacity(self, capacity, recency, query, prepare_cached)
+                .await
+                .expect("failed to prepare");
+            missed
+        }
+    }
+
+    impl CachedStatements for FakeStatementCache {
+        fn size(&self) -> usize {
+            self.queries.lock().expect("poisoned").len()
+        }
+
+        fn remove(&self, query: &str) -> bool {
+            let mut queries = self.queries.lock().expect("poisoned");
+            let size = queries.len();
+            queries.retain(|q| q != query);
+            queries.len() < size
+        }
+
+        fn clear(&self) {
+            self.queries.lock().expect("poisoned").clear();
+        }
+    }
+
+    #[tokio::test]
+    async fn evicts_the_least_recently_used_statement_at_capacity() {
+        let recency = StatementRecency::new(2);
+        let cache = FakeStatementCache::default();
+
+        assert!(cache.prepare(2, &recency, "a").await);
+        assert!(cache.prepare(2, &recency, "b").await);
+        assert!(!cache.prepare(2, &recency, "a").await);
+        assert!(cache.prepare(2, &recency, "c").await);
+
+        assert_eq!(vec!["a", "c"], cache.queries());
+    }
+
+    #[tokio::test]
+    async fn re_prepares_statements_after_they_were_evicted() {
+        let recency = StatementRecency::new(2);
+        let cache = FakeStatementCache::default();
+
+        for query in ["a", "b", "c"] {
+            assert!(cache.prepare(2, &recency, query).await);
+        }
+        assert_eq!(vec!["b", "c"], cache.queries());
+
+        assert!(cache.prepare(2, &recency, "a").await);
+        assert_eq!(vec!["a", "c"], cache.queries());
+        assert!(!cache.prepare(2, &recency, "a").await);
+    }
+
+    #[tokio::test]
+    async fn orders_evictions_by_use_across_connections() {
+        let recency = StatementRecency::new(2);
+        let (first, second) = (FakeStatementCache::default(), FakeStatementCache::default());
+
+        first.prepare(2, &recency, "a").await;
+        first.prepare(2, &recency, "b").await;
+        // Another connection using "a" makes "b" the least recently used
+        second.prepare(2, &recency, "a").await;
+        first.prepare(2, &recency, "c").await;
+
+        assert_eq!(vec!["a", "c"], first.queries());
+        assert_eq!(vec!["a"], second.queries());
+    }
+
+    #[test]
+    fn empties_full_caches_of_untracked_statements() {
+        let recency = StatementRecency::new(2);
+        recency.touch("a");
+        let cache = FakeStatementCache::with(&["x", "y"]);
+
+        assert_eq!(2, recency.evict(&cache, 2));
+        assert_eq!(0, cache.size());
+    }
+
+    #[test]
+    fn forgets_the_least_recently_used_queries_beyond_the_tracked_capacity() {
+        let recency = StatementRecency::new(1);
+        for query in ["a", "b", "c", "d"] {
+            recency.touch(query);
+        }
+        recency.touch("a");
+        recency.touch("e");
+
+        let inner = recency.inner.lock().expect("poisoned");
+        let mut tracked: Vec<_> = inner.last_used.keys().map(|q| q.to_string()).collect();
+        tracked.sort();
+        assert_eq!(vec!["a", "c", "d", "e"], tracked);
+        assert_eq!(4, inner.by_last_used.len());
+    }
+}
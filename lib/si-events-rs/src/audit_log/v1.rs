@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use si_id::ManagementPrototypeId;
+use si_id::{ManagementPrototypeId, ShareLinkId};
 use strum::{Display, EnumDiscriminants};
 
 use crate::{
@@ -131,6 +131,11 @@ pub enum AuditLogKindV1 {
         name: String,
         secret_id: SecretId,
     },
+    CreateShareLink {
+        share_link_id: ShareLinkId,
+        view_id: Option<ViewId>,
+        expires_at: String,
+    },
     CreateView {
         view_id: ViewId,
     },
@@ -188,6 +193,9 @@ pub enum AuditLogKindV1 {
         name: String,
         version: String,
     },
+    ForceApplyChangeSet {
+        from_status: ChangeSetStatus,
+    },
 
     GenerateTemplate {
         schema_variant_id: SchemaVariantId,
@@ -246,6 +254,9 @@ pub enum AuditLogKindV1 {
         func_display_name: Option<String>,
         func_name: String,
     },
+    RevokeShareLink {
+        share_link_id: ShareLinkId,
+    },
     RunAction {
         prototype_id: ActionPrototypeId,
         action_kind: ActionKind,
@@ -385,6 +396,10 @@ pub enum AuditLogKindV1 {
         old_schema_variant_id: SchemaVariantId,
         old_schema_variant_name: String,
     },
+    ViewSharedDiagram {
+        share_link_id: ShareLinkId,
+        view_id: ViewId,
+    },
     WithdrawRequestForChangeSetApply {
         from_status: ChangeSetStatus,
     },
@@ -519,6 +534,12 @@ pub enum AuditLogMetadataV1 {
     #[serde(rename_all = "camelCase")]
     CreateSecret { name: String, secret_id: SecretId },
     #[serde(rename_all = "camelCase")]
+    CreateShareLink {
+        share_link_id: ShareLinkId,
+        view_id: Option<ViewId>,
+        expires_at: String,
+    },
+    #[serde(rename_all = "camelCase")]
     CreateView { view_id: ViewId },
     #[serde(rename_all = "camelCase")]
     DeleteComponent {
@@ -579,6 +600,8 @@ pub enum AuditLogMetadataV1 {
         name: String,
         version: String,
     },
+    #[serde(rename_all = "camelCase")]
+    ForceApplyChangeSet { from_status: ChangeSetStatus },
 
     #[serde(rename_all = "camelCase")]
     GenerateTemplate {
@@ -640,6 +663,8 @@ pub enum AuditLogMetadataV1 {
         func_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    RevokeShareLink { share_link_id: ShareLinkId },
+    #[serde(rename_all = "camelCase")]
     RunAction {
         prototype_id: ActionPrototypeId,
         action_kind: ActionKind,
@@ -788,6 +813,11 @@ pub enum AuditLogMetadataV1 {
         old_schema_variant_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    ViewSharedDiagram {
+        share_link_id: ShareLinkId,
+        view_id: ViewId,
+    },
+    #[serde(rename_all = "camelCase")]
     WithdrawRequestForChangeSetApply { from_status: ChangeSetStatus },
 }
 
@@ -819,6 +849,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::CreateFuncArgument => ("Created", Some("Function Argument")),
             MetadataDiscrim::CreateSchemaVariant => ("Created", Some("Schema Variant")),
             MetadataDiscrim::CreateSecret => ("Created", Some("Secret")),
+            MetadataDiscrim::CreateShareLink => ("Created", Some("Share Link")),
             MetadataDiscrim::CreateView => ("Created", Some("View")),
             MetadataDiscrim::DeleteComponent => ("Deleted", Some("Component")),
             MetadataDiscrim::DeleteConnection => ("Deleted", Some("Connection")),
@@ -830,6 +861,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::DetachFunc => ("Detached", Some("Function")),
             MetadataDiscrim::ExecuteFunc => ("Executed", Some("Function")),
             MetadataDiscrim::ExportWorkspace => ("Exported", Some("Workspace")),
+            MetadataDiscrim::ForceApplyChangeSet => ("Force Applied", Some("Change Set")),
             MetadataDiscrim::InstallWorkspace => ("Installed", Some("Workspace")),
             MetadataDiscrim::GenerateTemplate => ("Generated", Some("Template")),
             MetadataDiscrim::Login => ("Authenticated", None),
@@ -845,6 +877,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::ReopenChangeSet => ("Reopened", Some("Change Set")),
             MetadataDiscrim::RequestChangeSetApproval => ("Requested to Apply", Some("Change Set")),
             MetadataDiscrim::RetryAction => ("Retried", Some("Action")),
+            MetadataDiscrim::RevokeShareLink => ("Revoked", Some("Share Link")),
             MetadataDiscrim::RunAction => ("Ran", Some("Action")),
             MetadataDiscrim::TestFunction => ("Tested", Some("Function")),
             MetadataDiscrim::UnlockFunc => ("Unlocked", Some("Function")),
@@ -864,6 +897,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::UpdateSchemaVariant => ("Updated", Some("Schema Variant")),
             MetadataDiscrim::UpdateView => ("Updated", Some("View")),
             MetadataDiscrim::UpgradeComponent => ("Upgraded", Some("Component")),
+            MetadataDiscrim::ViewSharedDiagram => ("Viewed", Some("Shared Diagram")),
             MetadataDiscrim::WithdrawRequestForChangeSetApply => {
                 ("Withdrew Request to Apply", Some("Change Set"))
             }
@@ -1057,6 +1091,15 @@ impl From<Kind> for Metadata {
                 schema_variant_id,
             },
             Kind::CreateSecret { name, secret_id } => Self::CreateSecret { name, secret_id },
+            Kind::CreateShareLink {
+                share_link_id,
+                view_id,
+                expires_at,
+            } => Self::CreateShareLink {
+                share_link_id,
+                view_id,
+                expires_at,
+            },
             Kind::CreateView { view_id } => Self::CreateView { view_id },
             Kind::DeleteComponent {
                 name,
@@ -1140,6 +1183,7 @@ impl From<Kind> for Metadata {
             Kind::ExportWorkspace { id, name, version } => {
                 Self::ExportWorkspace { id, name, version }
             }
+            Kind::ForceApplyChangeSet { from_status } => Self::ForceApplyChangeSet { from_status },
             Kind::GenerateTemplate {
                 schema_variant_id,
                 management_prototype_id,
@@ -1217,6 +1261,7 @@ impl From<Kind> for Metadata {
                 func_display_name,
                 func_name,
             },
+            Kind::RevokeShareLink { share_link_id } => Self::RevokeShareLink { share_link_id },
             Kind::RunAction {
                 prototype_id,
                 action_kind,
@@ -1466,6 +1511,13 @@ impl From<Kind> for Metadata {
                 old_schema_variant_id,
                 old_schema_variant_name,
             },
+            Kind::ViewSharedDiagram {
+                share_link_id,
+                view_id,
+            } => Self::ViewSharedDiagram {
+                share_link_id,
+                view_id,
+            },
             Kind::WithdrawRequestForChangeSetApply { from_status } => {
                 Self::WithdrawRequestForChangeSetApply { from_status }
             }
@@ -92,6 +92,8 @@ pub enum FuncBackendKind {
     Unset,
     Validation,
     Management,
+    /// (Experimental) An attribute function compiled to a WASI module.
+    Wasm,
 }
 
 // NOTE(nick,zack): do not add "remain::sorted" for postcard de/ser. We need the order to be
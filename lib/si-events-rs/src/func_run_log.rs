@@ -16,6 +16,80 @@ pub struct OutputLine {
     pub timestamp: u64,
 }
 
+impl OutputLine {
+    /// The kind of stream this line was written to.
+    pub fn stream_kind(&self) -> OutputStreamKind {
+        match self.stream.as_str() {
+            "stdout" => OutputStreamKind::Stdout,
+            "stderr" => OutputStreamKind::Stderr,
+            "output" | "result" => OutputStreamKind::Result,
+            _ => OutputStreamKind::Other,
+        }
+    }
+}
+
+/// The stream an [`OutputLine`] was written to.
+#[remain::sorted]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::AsRefStr,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum OutputStreamKind {
+    Other,
+    Result,
+    Stderr,
+    Stdout,
+}
+
+/// A single [`OutputLine`] of a function run, stored on its own so that the logs of a run can
+/// be read a page at a time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FuncRunLogEntry {
+    pub func_run_id: FuncRunId,
+    /// The position of the line in the function run's output, starting at zero.
+    pub sequence: u64,
+    pub stream_kind: OutputStreamKind,
+    pub timestamp: DateTime<Utc>,
+    pub line: OutputLine,
+}
+
+impl FuncRunLogEntry {
+    pub fn new(func_run_id: FuncRunId, sequence: u64, line: OutputLine) -> Self {
+        // Output lines carry seconds since the UNIX epoch
+        let timestamp = i64::try_from(line.timestamp)
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .unwrap_or_else(Utc::now);
+
+        Self {
+            func_run_id,
+            sequence,
+            stream_kind: line.stream_kind(),
+            timestamp,
+            line,
+        }
+    }
+}
+
+/// A page of [`FuncRunLogEntry`] values, in sequence order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FuncRunLogEntryPage {
+    pub entries: Vec<FuncRunLogEntry>,
+    /// Pass as the cursor to fetch the following page, if there may be one.
+    pub next_cursor: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FuncRunLog {
     id: FuncRunLogId,
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ActionId, ComponentId, FuncKind, FuncRun, FuncRunId, FuncRunState, WsEventDefinition,
+    WsEventKind,
+};
+
+/// A step in the lifecycle of a [`FuncRun`], streamed to clients so that they can show live
+/// execution state instead of polling for it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunStatusEvent {
+    pub func_run_id: FuncRunId,
+    pub function_name: String,
+    pub function_kind: FuncKind,
+    pub component_id: Option<ComponentId>,
+    pub action_id: Option<ActionId>,
+    pub status: FuncRunStatus,
+    /// When the func run reached this status.
+    pub at: DateTime<Utc>,
+}
+
+/// The status of a [`FuncRun`]. Finished func runs carry how long they took, from creation to
+/// finish, in milliseconds.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(
+    tag = "state",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum FuncRunStatus {
+    Created,
+    Dispatched,
+    Running,
+    PostProcessing,
+    Success { duration_ms: i64 },
+    Failure { duration_ms: i64 },
+    Killed { duration_ms: i64 },
+}
+
+impl From<&FuncRun> for FuncRunStatusEvent {
+    fn from(func_run: &FuncRun) -> Self {
+        let duration_ms = (func_run.updated_at() - func_run.created_at()).num_milliseconds();
+        let status = match func_run.state() {
+            FuncRunState::Created => FuncRunStatus::Created,
+            FuncRunState::Dispatched => FuncRunStatus::Dispatched,
+            FuncRunState::Running => FuncRunStatus::Running,
+            FuncRunState::PostProcessing => FuncRunStatus::PostProcessing,
+            FuncRunState::Success => FuncRunStatus::Success { duration_ms },
+            FuncRunState::Failure => FuncRunStatus::Failure { duration_ms },
+            FuncRunState::Killed => FuncRunStatus::Killed { duration_ms },
+        };
+
+        Self {
+            func_run_id: func_run.id(),
+            function_name: func_run.function_name().to_owned(),
+            function_kind: func_run.function_kind(),
+            component_id: func_run.component_id(),
+            action_id: func_run.action_id(),
+            status,
+            at: func_run.updated_at(),
+        }
+    }
+}
+
+impl WsEventDefinition for FuncRunStatusEvent {
+    const KIND: WsEventKind = WsEventKind::FuncRunStatusChanged;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_serialization() {
+        assert_eq!(
+            serde_json::json!({"state": "postProcessing"}),
+            serde_json::to_value(FuncRunStatus::PostProcessing).expect("could not serialize")
+        );
+        assert_eq!(
+            serde_json::json!({"state": "success", "durationMs": 42}),
+            serde_json::to_value(FuncRunStatus::Success { duration_ms: 42 })
+                .expect("could not serialize")
+        );
+    }
+}
@@ -16,6 +16,7 @@ mod func;
 mod func_execution;
 mod func_run;
 mod func_run_log;
+mod func_run_status;
 mod resource_metadata;
 mod schema;
 mod schema_variant;
@@ -25,6 +26,7 @@ mod tenancy;
 mod timestamp;
 mod vector_clock_id;
 mod web_event;
+mod ws_event_schema;
 
 pub use crate::{
     actor::Actor,
@@ -42,7 +44,11 @@ pub use crate::{
         FuncBackendResponseType, FuncKind, FuncRun, FuncRunBuilder, FuncRunBuilderError, FuncRunId,
         FuncRunState, FuncRunValue, ManagementPrototypeId, ViewId,
     },
-    func_run_log::{FuncRunLog, FuncRunLogId, OutputLine},
+    func_run_log::{
+        FuncRunLog, FuncRunLogEntry, FuncRunLogEntryPage, FuncRunLogId, OutputLine,
+        OutputStreamKind,
+    },
+    func_run_status::{FuncRunStatus, FuncRunStatusEvent},
     resource_metadata::{ResourceMetadata, ResourceStatus},
     schema::SchemaId,
     schema_variant::{PropId, SchemaVariantId},
@@ -55,4 +61,8 @@ pub use crate::{
     vector_clock_id::{VectorClockActorId, VectorClockChangeSetId, VectorClockId},
     web_event::WebEvent,
     workspace_snapshot_address::WorkspaceSnapshotAddress,
+    ws_event_schema::{
+        VersionedWsPayload, WsEventDefinition, WsEventKind, WsEventSchema, WsEventSchemaError,
+        WsEventSchemaResult, WsEventVersion,
+    },
 };
@@ -0,0 +1,272 @@
+//! The registry of the events sent to the frontend over the websocket.
+//!
+//! Every kind of event has a version for the shape of its payload, which is sent along with the
+//! event. The version of a kind must be bumped whenever its payload changes in a way which older
+//! frontends would mis-parse, so that they can detect the incompatibility instead.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+use thiserror::Error;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WsEventSchemaError {
+    #[error("incompatible {kind} event payload version: expected {expected}, found {found}")]
+    IncompatibleVersion {
+        kind: WsEventKind,
+        expected: WsEventVersion,
+        found: WsEventVersion,
+    },
+    #[error("expected {expected} event payload, found {found}")]
+    KindMismatch {
+        expected: WsEventKind,
+        found: WsEventKind,
+    },
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+pub type WsEventSchemaResult<T> = Result<T, WsEventSchemaError>;
+
+/// The version of the shape of the payload of a kind of event.
+pub type WsEventVersion = u32;
+
+macro_rules! ws_event_kinds {
+    ($($kind:ident => $version:literal),+ $(,)?) => {
+        /// Every kind of event sent to the frontend over the websocket.
+        #[remain::sorted]
+        #[derive(
+            Clone,
+            Copy,
+            Debug,
+            Deserialize,
+            Display,
+            EnumIter,
+            EnumString,
+            Eq,
+            Hash,
+            Ord,
+            PartialEq,
+            PartialOrd,
+            Serialize,
+        )]
+        pub enum WsEventKind {
+            $($kind,)+
+        }
+
+        impl WsEventKind {
+            /// Returns the current version of the payload of this kind of event.
+            pub fn version(self) -> WsEventVersion {
+                match self {
+                    $(Self::$kind => $version,)+
+                }
+            }
+        }
+    };
+}
+
+ws_event_kinds! {
+    ActionsListUpdated => 1,
+    AnnotationCreated => 1,
+    AnnotationDeleted => 1,
+    AnnotationUpdated => 1,
+    AsyncError => 1,
+    AsyncFinish => 1,
+    AuditLogsPublished => 1,
+    ChangeSetAbandoned => 1,
+    ChangeSetAbandonVote => 1,
+    ChangeSetApplied => 1,
+    ChangeSetApplyLocked => 1,
+    ChangeSetApplyUnlocked => 1,
+    ChangeSetApprovalsChanged => 1,
+    ChangeSetBeginAbandonProcess => 1,
+    ChangeSetBeginApprovalProcess => 1,
+    ChangeSetCancelAbandonProcess => 1,
+    ChangeSetCancelApprovalProcess => 1,
+    ChangeSetCanceled => 1,
+    ChangeSetCreated => 1,
+    ChangeSetMergeVote => 1,
+    ChangeSetQuarantined => 1,
+    ChangeSetRename => 1,
+    ChangeSetStatusChanged => 1,
+    ChangeSetWritten => 1,
+    CheckedQualifications => 1,
+    ComponentCreated => 1,
+    ComponentDeleted => 1,
+    ComponentUpdated => 1,
+    ComponentUpgraded => 1,
+    ConnectionDeleted => 1,
+    ConnectionUpserted => 1,
+    Cursor => 1,
+    FeatureFlagUpdated => 1,
+    FuncArgumentsSaved => 1,
+    FuncCodeSaved => 1,
+    FuncCreated => 1,
+    FuncDeleted => 1,
+    FuncGenerating => 1,
+    FuncRunLogUpdated => 1,
+    FuncRunStatusChanged => 1,
+    FuncSaved => 1,
+    FuncUpdated => 1,
+    ImportWorkspaceVote => 1,
+    InferredEdgeRemove => 1,
+    InferredEdgeUpsert => 1,
+    ManagementFuncExecuted => 1,
+    ManagementOperationsComplete => 1,
+    ModuleImported => 1,
+    ModulePublishProgress => 1,
+    Online => 1,
+    PromptUpdated => 1,
+    ResourceRefreshed => 1,
+    SchemaVariantCloned => 1,
+    SchemaVariantCreated => 1,
+    SchemaVariantDeleted => 1,
+    SchemaVariantReplaced => 1,
+    SchemaVariantSaved => 1,
+    SchemaVariantUpdated => 1,
+    SchemaVariantUpdateFinished => 1,
+    SecretCreated => 1,
+    SecretDeleted => 1,
+    SecretUpdated => 1,
+    SetComponentPosition => 1,
+    StatusUpdate => 1,
+    ViewComponentsUpdate => 1,
+    ViewCreated => 1,
+    ViewDeleted => 1,
+    ViewObjectCreated => 1,
+    ViewObjectRemoved => 1,
+    ViewUpdated => 1,
+    WorkspaceImportBeginApprovalProcess => 1,
+    WorkspaceImportCancelApprovalProcess => 1,
+    WorkspaceImportProgress => 1,
+}
+
+/// A kind of event along with the current version of its payload.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsEventSchema {
+    pub kind: WsEventKind,
+    pub version: WsEventVersion,
+}
+
+impl WsEventKind {
+    /// Returns the schema of this kind of event.
+    pub fn schema(self) -> WsEventSchema {
+        WsEventSchema {
+            kind: self,
+            version: self.version(),
+        }
+    }
+
+    /// Returns the schemas of all kinds of events, to be compared against the ones a client
+    /// knows about.
+    pub fn registry() -> Vec<WsEventSchema> {
+        Self::iter().map(Self::schema).collect()
+    }
+}
+
+/// A payload struct which defines a kind of event.
+pub trait WsEventDefinition: Serialize + DeserializeOwned {
+    const KIND: WsEventKind;
+}
+
+/// A payload serialized along with the kind and version of the event it was serialized for.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionedWsPayload {
+    pub kind: WsEventKind,
+    pub version: WsEventVersion,
+    pub data: serde_json::Value,
+}
+
+impl VersionedWsPayload {
+    /// Serializes the payload with the current version of its kind of event.
+    pub fn new<T: WsEventDefinition>(payload: &T) -> WsEventSchemaResult<Self> {
+        Ok(Self {
+            kind: T::KIND,
+            version: T::KIND.version(),
+            data: serde_json::to_value(payload)?,
+        })
+    }
+
+    /// Returns whether the payload was serialized with the current version of its kind of event.
+    pub fn is_current(&self) -> bool {
+        self.version == self.kind.version()
+    }
+
+    /// Deserializes the payload, failing rather than guessing if it was serialized for another
+    /// kind of event or another version of it.
+    pub fn decode<T: WsEventDefinition>(self) -> WsEventSchemaResult<T> {
+        if self.kind != T::KIND {
+            return Err(WsEventSchemaError::KindMismatch {
+                expected: T::KIND,
+                found: self.kind,
+            });
+        }
+        if !self.is_current() {
+            return Err(WsEventSchemaError::IncompatibleVersion {
+                kind: self.kind,
+                expected: self.kind.version(),
+                found: self.version,
+            });
+        }
+
+        Ok(serde_json::from_value(self.data)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+    struct CursorPayload {
+        x: i64,
+        y: i64,
+    }
+
+    impl WsEventDefinition for CursorPayload {
+        const KIND: WsEventKind = WsEventKind::Cursor;
+    }
+
+    #[test]
+    fn versioned_payload_round_trip() {
+        let payload = CursorPayload { x: 1, y: 2 };
+
+        let versioned = VersionedWsPayload::new(&payload).expect("could not serialize");
+        assert_eq!(
+            serde_json::json!({"kind": "Cursor", "version": 1, "data": {"x": 1, "y": 2}}),
+            serde_json::to_value(&versioned).expect("could not serialize")
+        );
+
+        let decoded: CursorPayload = versioned.decode().expect("could not decode");
+        assert_eq!(payload, decoded);
+    }
+
+    #[test]
+    fn versioned_payload_incompatible() {
+        let mut versioned =
+            VersionedWsPayload::new(&CursorPayload { x: 1, y: 2 }).expect("could not serialize");
+        versioned.version += 1;
+        assert!(matches!(
+            versioned.clone().decode::<CursorPayload>(),
+            Err(WsEventSchemaError::IncompatibleVersion { .. })
+        ));
+
+        versioned.kind = WsEventKind::Online;
+        assert!(matches!(
+            versioned.decode::<CursorPayload>(),
+            Err(WsEventSchemaError::KindMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn registry_has_every_kind() {
+        let registry = WsEventKind::registry();
+        assert_eq!(WsEventKind::iter().count(), registry.len());
+        assert!(registry.contains(&WsEventSchema {
+            kind: WsEventKind::ChangeSetWritten,
+            version: 1,
+        }));
+    }
+}
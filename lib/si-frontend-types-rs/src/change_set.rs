@@ -19,3 +19,15 @@ pub struct ChangeSet {
     pub reviewed_by_user: Option<String>,
     pub reviewed_at: Option<DateTime<Utc>>,
 }
+
+/// A [`ChangeSet`] along with what the requesting user can do with it. The capabilities are
+/// computed by the backend so that the frontend does not need to mirror its rules.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetView {
+    #[serde(flatten)]
+    pub change_set: ChangeSet,
+    pub can_apply: bool,
+    pub can_approve: bool,
+    pub can_edit: bool,
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use si_events::{ComponentId, SchemaId, SchemaVariantId, ViewId};
+use std::collections::BTreeMap;
 use std::num::ParseIntError;
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 
@@ -161,4 +162,5 @@ pub struct DiagramComponentView {
     pub can_be_upgraded: bool,
     pub from_base_change_set: bool,
     pub view_data: Option<GeometryAndView>,
+    pub labels: BTreeMap<String, String>,
 }
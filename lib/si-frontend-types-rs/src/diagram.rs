@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use si_events::WorkspaceSnapshotAddress;
+
+use crate::DiagramComponentView;
+
+/// The changes to a diagram since a version of it the client already has. Versions of a diagram
+/// are identified by the workspace snapshot it was assembled from.
+///
+/// The edge and view types are generic, since their representations are owned by the backend.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagramDelta<Edge, InferredEdge, ManagementEdge, View> {
+    /// The version of the diagram this delta brings the client to, to be sent back as the
+    /// version to get the next delta from.
+    pub snapshot_id: WorkspaceSnapshotAddress,
+    /// Set when the version the client has is unknown, in which case the delta is from an empty
+    /// diagram and the client should replace everything it has.
+    pub full: bool,
+    pub components: DiagramElementDelta<DiagramComponentView>,
+    pub edges: DiagramElementDelta<Edge>,
+    pub inferred_edges: DiagramElementDelta<InferredEdge>,
+    pub management_edges: DiagramElementDelta<ManagementEdge>,
+    pub views: DiagramElementDelta<View>,
+}
+
+/// The changes to one kind of diagram element. Removed elements are identified by their key, which
+/// is the id of components and views.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagramElementDelta<T> {
+    pub added: Vec<T>,
+    pub updated: Vec<T>,
+    pub removed: Vec<String>,
+}
+
+impl<T> Default for DiagramElementDelta<T> {
+    fn default() -> Self {
+        Self {
+            added: vec![],
+            updated: vec![],
+            removed: vec![],
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> DiagramElementDelta<T> {
+    /// Computes the changes between two versions of the elements, keyed the same way.
+    pub fn between(previous: &BTreeMap<String, T>, current: &BTreeMap<String, T>) -> Self {
+        let mut delta = Self::default();
+        for (key, element) in current {
+            match previous.get(key) {
+                None => delta.added.push(element.clone()),
+                Some(previous_element) if previous_element != element => {
+                    delta.updated.push(element.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        delta.removed = previous
+            .keys()
+            .filter(|key| !current.contains_key(*key))
+            .cloned()
+            .collect();
+
+        delta
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
@@ -2,19 +2,21 @@ mod audit_log;
 mod change_set;
 mod component;
 mod conflict;
+mod diagram;
 mod func;
 mod module;
 mod schema_variant;
 mod workspace;
 
 pub use crate::audit_log::AuditLog;
-pub use crate::change_set::ChangeSet;
+pub use crate::change_set::{ChangeSet, ChangeSetView};
 pub use crate::component::{
     ChangeStatus, ConnectionAnnotation, DiagramComponentView, DiagramSocket,
     DiagramSocketDirection, DiagramSocketNodeSide, GeometryAndView, GridPoint, RawGeometry, Size2D,
     StringGeometry,
 };
 pub use crate::conflict::ConflictWithHead;
+pub use crate::diagram::{DiagramDelta, DiagramElementDelta};
 pub use crate::func::{
     AttributeArgumentBinding, FuncArgument, FuncArgumentKind, FuncBinding, FuncBindings, FuncCode,
     FuncSummary, LeafInputLocation,
@@ -26,4 +28,5 @@ pub use crate::module::{
 pub use crate::schema_variant::{
     ComponentType, InputSocket, OutputSocket, Prop, PropKind, SchemaVariant, UninstalledVariant,
 };
-pub use crate::workspace::WorkspaceMetadata;
+pub use crate::workspace::{WorkspaceMetadata, WorkspaceView};
+pub use si_events::{FuncRunStatus, FuncRunStatusEvent};
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use si_events::ChangeSetId;
 
-use crate::change_set::ChangeSet;
+use crate::change_set::{ChangeSet, ChangeSetView};
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -13,3 +13,18 @@ pub struct WorkspaceMetadata {
     /// list of user ids that are approvers for this workspace
     pub approvers: Vec<String>,
 }
+
+/// [`WorkspaceMetadata`] along with what the requesting user can do in the workspace and each of
+/// its change sets.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceView {
+    pub name: String,
+    pub id: String,
+    pub default_change_set_id: ChangeSetId,
+    pub change_sets: Vec<ChangeSetView>,
+    /// list of user ids that are approvers for this workspace
+    pub approvers: Vec<String>,
+    /// whether the requesting user is an approver for this workspace
+    pub can_approve: bool,
+}
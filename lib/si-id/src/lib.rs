@@ -35,6 +35,7 @@ pub use ::ulid as ulid_upstream;
 // Please keep these alphabetically sorted!
 id!(ActionPrototypeId);
 id!(ActivityId);
+id!(AnnotationId);
 id!(AttributePrototypeArgumentId);
 id!(AttributePrototypeId);
 id!(AuthenticationPrototypeId);
@@ -59,17 +60,25 @@ id!(StaticArgumentValueId);
 id!(ValidationOutputId);
 id!(VectorClockActorId);
 id!(VectorClockChangeSetId);
-id!(ViewId);
 id!(WorkspaceSnapshotNodeId);
 
 // Please keep these alphabetically sorted!
 id_with_pg_types!(ActionId);
+id_with_pg_types!(ActionRunId);
+id_with_pg_types!(ApiTokenId);
 id_with_pg_types!(CachedModuleId);
+id_with_pg_types!(ChangeSetApprovalId);
 id_with_pg_types!(ChangeSetId);
 id_with_pg_types!(ComponentId);
 id_with_pg_types!(FuncId);
 id_with_pg_types!(FuncRunId);
+id_with_pg_types!(ProviderCredentialConsentId);
+id_with_pg_types!(ProviderCredentialId);
+id_with_pg_types!(ShareLinkId);
+id_with_pg_types!(SnapshotSubscriptionId);
 id_with_pg_types!(UserPk);
+id_with_pg_types!(ViewId);
+id_with_pg_types!(WorkspaceHookId);
 id_with_pg_types!(WorkspaceIntegrationId);
 
 // Please keep these alphabetically sorted!
@@ -24,6 +24,8 @@ pub enum JwtPublicSigningKeyError {
     Io(#[from] std::io::Error),
     #[error("JWT error: {0}")]
     Jwt(#[from] jwt_simple::Error),
+    #[error("no automation token signing key configured")]
+    NoAutomationSigningKey,
     #[error("{0}")]
     TaskJoin(#[from] JoinError),
     #[error("Unsupported JWT signing algorithm: {0}")]
@@ -76,6 +78,17 @@ impl JwtConfig {
             }
         })
     }
+
+    /// Load a private key (rather than a public key) from the config, for signing tokens.
+    pub async fn into_sign(self) -> JwtKeyResult<Arc<dyn JwtKeyPairSign>> {
+        let algo = self.algo;
+        let pem = self.to_pem().await?;
+
+        Ok(match algo {
+            JwtAlgo::ES256 => Arc::new(ES256KeyPair::from_pem(&pem)?) as Arc<dyn JwtKeyPairSign>,
+            JwtAlgo::RS256 => Arc::new(RS256KeyPair::from_pem(&pem)?) as Arc<dyn JwtKeyPairSign>,
+        })
+    }
 }
 
 /** Role indicating what permissions the user should have */
@@ -114,6 +127,9 @@ pub struct SiJwtClaimsV2 {
     user_id: UserPk,
     workspace_id: WorkspacePk,
     role: SiJwtClaimRole,
+    /// Grants access to the cross-workspace admin routes.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    admin: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
@@ -145,6 +161,13 @@ impl SiJwtClaims {
         }
     }
 
+    pub fn is_admin(&self) -> bool {
+        match self {
+            Self::V2(SiJwtClaimsV2 { admin, .. }) => *admin,
+            Self::V1(SiJwtClaimsV1 { .. }) => false,
+        }
+    }
+
     pub fn authorized_for(&self, required_role: SiJwtClaimRole) -> bool {
         self.role().is_superset_of(required_role)
     }
@@ -155,9 +178,38 @@ impl SiJwtClaims {
             user_id,
             workspace_id,
             role: SiJwtClaimRole::Web,
+            admin: false,
         })
     }
 
+    pub fn for_automation(user_id: UserPk, workspace_id: WorkspacePk) -> Self {
+        Self::V2(SiJwtClaimsV2 {
+            version: MustBe!("2"),
+            user_id,
+            workspace_id,
+            role: SiJwtClaimRole::Automation,
+            admin: false,
+        })
+    }
+
+    /// The whole token for a long-lived automation token. The `jwt_id` identifies the token so that
+    /// it can be revoked; tokens without a validity period never expire.
+    pub fn automation_token(
+        user_id: UserPk,
+        workspace_id: WorkspacePk,
+        jwt_id: impl ToString,
+        valid_for: Option<std::time::Duration>,
+    ) -> SiJwt {
+        let validity = Duration::from_secs(valid_for.unwrap_or_default().as_secs());
+        let mut claims =
+            Claims::with_custom_claims(Self::for_automation(user_id, workspace_id), validity)
+                .with_jwt_id(jwt_id);
+        if valid_for.is_none() {
+            claims.expires_at = None;
+        }
+        claims
+    }
+
     pub async fn from_bearer_token(
         public_key: JwtPublicSigningKeyChain,
         token: impl AsRef<str>,
@@ -209,10 +261,48 @@ impl JwtPublicKeyVerify for ES256PublicKey {
     }
 }
 
+/// A private key that can sign tokens, and verify the tokens it signed.
+pub trait JwtKeyPairSign: JwtPublicKeyVerify {
+    fn sign_token(&self, claims: SiJwt) -> JwtKeyResult<String>;
+}
+
+impl JwtPublicKeyVerify for RS256KeyPair {
+    fn algo(&self) -> JwtAlgo {
+        JwtAlgo::RS256
+    }
+
+    fn verify(&self, token: &str, options: Option<VerificationOptions>) -> JwtKeyResult<SiJwt> {
+        self.public_key().verify(token, options)
+    }
+}
+
+impl JwtKeyPairSign for RS256KeyPair {
+    fn sign_token(&self, claims: SiJwt) -> JwtKeyResult<String> {
+        Ok(self.sign(claims)?)
+    }
+}
+
+impl JwtPublicKeyVerify for ES256KeyPair {
+    fn algo(&self) -> JwtAlgo {
+        JwtAlgo::ES256
+    }
+
+    fn verify(&self, token: &str, options: Option<VerificationOptions>) -> JwtKeyResult<SiJwt> {
+        self.public_key().verify(token, options)
+    }
+}
+
+impl JwtKeyPairSign for ES256KeyPair {
+    fn sign_token(&self, claims: SiJwt) -> JwtKeyResult<String> {
+        Ok(self.sign(claims)?)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct JwtPublicSigningKeyChain {
     primary: Arc<dyn JwtPublicKeyVerify>,
     secondary: Option<Arc<dyn JwtPublicKeyVerify>>,
+    automation: Option<Arc<dyn JwtKeyPairSign>>,
 }
 
 impl JwtPublicSigningKeyChain {
@@ -226,20 +316,36 @@ impl JwtPublicSigningKeyChain {
                 Some(jwt_cfg) => Some(jwt_cfg.into_verify().await?),
                 None => None,
             },
+            automation: None,
         })
     }
 
+    /// Add the key used to sign (and verify) long-lived automation tokens for workspaces.
+    pub fn with_automation_signing_key(mut self, key: Arc<dyn JwtKeyPairSign>) -> Self {
+        self.automation = Some(key);
+        self
+    }
+
+    /// Sign an automation token. The claims should use the
+    /// [automation role](SiJwtClaimRole::Automation), since the token is not tied to a session.
+    pub fn sign_automation_token(&self, claims: SiJwt) -> JwtKeyResult<String> {
+        self.automation
+            .as_ref()
+            .ok_or(JwtPublicSigningKeyError::NoAutomationSigningKey)?
+            .sign_token(claims)
+    }
+
     /// Attempt to verify that this token was signed by either the primary or
-    /// secondary key(s)
+    /// secondary key(s), falling back to the automation token signing key
     pub fn verify_token(
         &self,
         token: &str,
         options: Option<VerificationOptions>,
     ) -> JwtKeyResult<SiJwt> {
-        match self.primary.verify(token, options.clone()) {
+        let result = match self.primary.verify(token, options.clone()) {
             Ok(claims) => Ok(claims),
             Err(err) => match self.secondary.as_ref() {
-                Some(secondary) => match secondary.verify(token, options) {
+                Some(secondary) => match secondary.verify(token, options.clone()) {
                     Ok(claims) => Ok(claims),
                     Err(second_err) => Err(JwtPublicSigningKeyError::VerifySecondaryFail(
                         err.to_string(),
@@ -248,6 +354,11 @@ impl JwtPublicSigningKeyChain {
                 },
                 None => Err(err),
             },
+        };
+
+        match (result, self.automation.as_ref()) {
+            (Err(err), Some(automation)) => automation.verify(token, options).map_err(|_| err),
+            (result, _) => result,
         }
     }
 }
@@ -447,4 +558,45 @@ mod tests {
             assert_eq!(si_claim, claims.custom);
         }
     }
+
+    #[tokio::test]
+    async fn validate_with_automation_es256() {
+        let key_pair = ES256KeyPair::generate();
+        let automation_key_pair = ES256KeyPair::generate();
+
+        let pub_key_pem = key_pair.public_key().to_pem().expect("get pub key pem");
+        let primary_cfg = JwtConfig {
+            key_file: None,
+            key_base64: Some(general_purpose::STANDARD.encode(pub_key_pem)),
+            algo: JwtAlgo::ES256,
+        };
+        let key_chain = JwtPublicSigningKeyChain::from_config(primary_cfg, None)
+            .await
+            .expect("make key chain");
+
+        let si_claim = SiJwtClaims::for_automation(UserPk::generate(), WorkspacePk::generate());
+        let claims = Claims::with_custom_claims(si_claim.clone(), Duration::from_hours(1));
+
+        // Without an automation key, nothing can be signed
+        assert!(key_chain.sign_automation_token(claims.clone()).is_err());
+
+        let key_chain = key_chain.with_automation_signing_key(Arc::new(automation_key_pair));
+        let signed = key_chain
+            .sign_automation_token(claims.clone())
+            .expect("sign automation token");
+        let bearer_token = format!("Bearer {signed}");
+
+        let validated = validate_bearer_token(key_chain.clone(), &bearer_token)
+            .await
+            .expect("should validate");
+        assert_eq!(si_claim, validated.custom);
+        assert_eq!(SiJwtClaimRole::Automation, validated.custom.role());
+
+        // Tokens signed by the primary key still validate
+        let signed = key_pair.sign(claims).expect("sign the key");
+        let validated = validate_bearer_token(key_chain, &format!("Bearer {signed}"))
+            .await
+            .expect("should validate");
+        assert_eq!(si_claim, validated.custom);
+    }
 }
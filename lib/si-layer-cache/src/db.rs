@@ -16,6 +16,7 @@ use ulid::Ulid;
 use crate::db::encrypted_secret::EncryptedSecretDb;
 use crate::db::func_run::FuncRunDb;
 use crate::db::func_run_log::FuncRunLogDb;
+use crate::db::materialized_view::MaterializedViewDb;
 use crate::hybrid_cache::CacheConfig;
 use crate::{
     activity_client::ActivityClient,
@@ -34,6 +35,7 @@ pub mod cas;
 pub mod encrypted_secret;
 pub mod func_run;
 pub mod func_run_log;
+pub mod materialized_view;
 pub mod rebase_batch;
 pub mod serialize;
 pub mod workspace_snapshot;
@@ -50,6 +52,7 @@ where
     encrypted_secret: EncryptedSecretDb<EncryptedSecretValue>,
     func_run: FuncRunDb,
     func_run_log: FuncRunLogDb,
+    materialized_view: MaterializedViewDb,
     rebase_batch: RebaseBatchDb<RebaseBatchValue>,
     workspace_snapshot: WorkspaceSnapshotDb<WorkspaceSnapshotValue>,
     pg_pool: PgPool,
@@ -106,6 +109,7 @@ where
             encrypted_secret_cache,
             func_run_cache,
             func_run_log_cache,
+            materialized_view_cache,
             rebase_batch_cache,
             snapshot_cache,
         ) = try_join!(
@@ -149,6 +153,16 @@ where
                 5,
                 5
             ),
+            create_layer_cache(
+                materialized_view::CACHE_NAME,
+                pg_pool.clone(),
+                cache_config.clone(),
+                compute_executor.clone(),
+                tracker.clone(),
+                token.clone(),
+                5,
+                5
+            ),
             create_layer_cache(
                 rebase_batch::CACHE_NAME,
                 pg_pool.clone(),
@@ -178,6 +192,7 @@ where
             encrypted_secret_cache.clone(),
             func_run_cache.clone(),
             func_run_log_cache.clone(),
+            materialized_view_cache.clone(),
             rebase_batch_cache.clone(),
             snapshot_cache.clone(),
             token.clone(),
@@ -200,6 +215,8 @@ where
             EncryptedSecretDb::new(encrypted_secret_cache, persister_client.clone());
         let func_run = FuncRunDb::new(func_run_cache, persister_client.clone());
         let func_run_log = FuncRunLogDb::new(func_run_log_cache, persister_client.clone());
+        let materialized_view =
+            MaterializedViewDb::new(materialized_view_cache, persister_client.clone());
         let workspace_snapshot = WorkspaceSnapshotDb::new(snapshot_cache, persister_client.clone());
         let rebase_batch = RebaseBatchDb::new(rebase_batch_cache, persister_client.clone());
 
@@ -212,6 +229,7 @@ where
             encrypted_secret,
             func_run,
             func_run_log,
+            materialized_view,
             workspace_snapshot,
             pg_pool,
             persister_client,
@@ -251,6 +269,10 @@ where
         &self.func_run_log
     }
 
+    pub fn materialized_view(&self) -> &MaterializedViewDb {
+        &self.materialized_view
+    }
+
     pub fn rebase_batch(&self) -> &RebaseBatchDb<RebaseBatchValue> {
         &self.rebase_batch
     }
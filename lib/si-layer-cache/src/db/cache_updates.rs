@@ -23,6 +23,7 @@ enum CacheName {
     EncryptedSecret,
     FuncRun,
     FuncRunLog,
+    MaterializedViews,
     WorkspaceSnapshots,
 }
 
@@ -41,6 +42,7 @@ pub struct CacheUpdatesTask<
     encrypted_secret_cache: Arc<LayerCache<Arc<EncryptedSecretValue>>>,
     func_run_cache: Arc<LayerCache<Arc<FuncRun>>>,
     func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
+    materialized_view_cache: Arc<LayerCache<Arc<Vec<u8>>>>,
     rebase_batch_cache: Arc<LayerCache<Arc<RebaseBatchValue>>>,
     snapshot_cache: Arc<LayerCache<Arc<WorkspaceSnapshotValue>>>,
     event_channel: UnboundedReceiver<LayeredEvent>,
@@ -66,6 +68,7 @@ where
         encrypted_secret_cache: Arc<LayerCache<Arc<EncryptedSecretValue>>>,
         func_run_cache: Arc<LayerCache<Arc<FuncRun>>>,
         func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
+        materialized_view_cache: Arc<LayerCache<Arc<Vec<u8>>>>,
         rebase_batch_cache: Arc<LayerCache<Arc<RebaseBatchValue>>>,
         snapshot_cache: Arc<LayerCache<Arc<WorkspaceSnapshotValue>>>,
         shutdown_token: CancellationToken,
@@ -82,6 +85,7 @@ where
             encrypted_secret_cache,
             func_run_cache,
             func_run_log_cache,
+            materialized_view_cache,
             rebase_batch_cache,
             snapshot_cache,
             event_channel,
@@ -111,6 +115,7 @@ where
                 self.encrypted_secret_cache.clone(),
                 self.func_run_cache.clone(),
                 self.func_run_log_cache.clone(),
+                self.materialized_view_cache.clone(),
                 self.snapshot_cache.clone(),
                 self.rebase_batch_cache.clone(),
             );
@@ -131,6 +136,7 @@ where
     encrypted_secret_cache: Arc<LayerCache<Arc<R>>>,
     func_run_cache: Arc<LayerCache<Arc<FuncRun>>>,
     func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
+    materialized_view_cache: Arc<LayerCache<Arc<Vec<u8>>>>,
     snapshot_cache: Arc<LayerCache<Arc<S>>>,
     rebase_batch_cache: Arc<LayerCache<Arc<T>>>,
 }
@@ -147,6 +153,7 @@ where
         encrypted_secret_cache: Arc<LayerCache<Arc<R>>>,
         func_run_cache: Arc<LayerCache<Arc<FuncRun>>>,
         func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
+        materialized_view_cache: Arc<LayerCache<Arc<Vec<u8>>>>,
         snapshot_cache: Arc<LayerCache<Arc<S>>>,
         rebase_batch_cache: Arc<LayerCache<Arc<T>>>,
     ) -> CacheUpdateTask<Q, R, S, T> {
@@ -155,6 +162,7 @@ where
             encrypted_secret_cache,
             func_run_cache,
             func_run_log_cache,
+            materialized_view_cache,
             snapshot_cache,
             rebase_batch_cache,
         }
@@ -178,6 +186,14 @@ where
                         .insert_from_cache_updates(event.key, serialized_value);
                 }
             }
+            crate::event::LayeredEventKind::MaterializedViewWrite => {
+                if !self.materialized_view_cache.contains(&event.key) {
+                    let serialized_value =
+                        Arc::try_unwrap(event.payload.value).unwrap_or_else(|arc| (*arc).clone());
+                    self.materialized_view_cache
+                        .insert_from_cache_updates(event.key, serialized_value);
+                }
+            }
             crate::event::LayeredEventKind::Raw => {
                 warn!("Recevied a 'raw' layered event kind - this is for testing only. Bug!");
             }
@@ -1,6 +1,7 @@
 use std::sync::Arc;
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
 
+use chrono::{DateTime, Utc};
 use serde::{de::DeserializeOwned, Serialize};
 use si_events::{Actor, ContentHash, Tenancy, WebEvent};
 
@@ -91,6 +92,72 @@ where
         })
     }
 
+    /// Returns the keys whose bytes in durable storage no longer hash to the key. Keys which are
+    /// not in durable storage (yet) are not checked.
+    pub async fn find_corrupted(&self, keys: &[ContentHash]) -> LayerDbResult<Vec<ContentHash>> {
+        let keys: Vec<Arc<str>> = keys.iter().map(|key| key.to_string().into()).collect();
+
+        let mut corrupted = Vec::new();
+        if let Some(found) = self.cache.pg().get_many(&keys).await? {
+            for (key, bytes) in found {
+                let key = ContentHash::from_str(&key)
+                    .map_err(|err| LayerDbError::CouldNotConvertToKeyFromString(err.to_string()))?;
+                if ContentHash::new(&bytes) != key {
+                    corrupted.push(key);
+                }
+            }
+        }
+
+        Ok(corrupted)
+    }
+
+    /// Lists up to `limit` keys in durable storage which were created before `created_before`, in
+    /// key order, starting after `after_key` (if given).
+    pub async fn list_keys_created_before(
+        &self,
+        created_before: DateTime<Utc>,
+        after_key: Option<&ContentHash>,
+        limit: i64,
+    ) -> LayerDbResult<Vec<ContentHash>> {
+        let after_key = after_key.map(ToString::to_string).unwrap_or_default();
+
+        self.cache
+            .pg()
+            .list_created_before(created_before, &after_key, limit)
+            .await?
+            .into_iter()
+            .map(|(key, _)| Ok(ContentHash::from_str(&key)?))
+            .collect()
+    }
+
+    /// Deletes the given keys from durable storage (and from memory), skipping any key which was
+    /// (re)written at or after `created_before`. Returns how many keys were deleted.
+    pub async fn delete_created_before(
+        &self,
+        keys: &[ContentHash],
+        created_before: DateTime<Utc>,
+    ) -> LayerDbResult<u64> {
+        let keys: Vec<String> = keys.iter().map(ToString::to_string).collect();
+        let deleted = self
+            .cache
+            .pg()
+            .delete_created_before(&keys, created_before)
+            .await?;
+
+        for (key, _) in &deleted {
+            self.cache.remove_from_memory(key);
+        }
+
+        Ok(deleted.len() as u64)
+    }
+
+    /// Returns the total size of the given keys in durable storage, as stored (serialized and
+    /// compressed). Keys which are not in durable storage (yet) are not counted.
+    pub async fn stored_size(&self, keys: &[ContentHash]) -> LayerDbResult<u64> {
+        let keys: Vec<String> = keys.iter().map(ToString::to_string).collect();
+        self.cache.pg().total_size(&keys).await
+    }
+
     pub async fn read_many(
         &self,
         keys: &[ContentHash],
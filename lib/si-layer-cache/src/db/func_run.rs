@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -29,10 +31,14 @@ pub struct FuncRunDb {
     persister_client: PersisterClient,
     ready_many_for_workspace_id_query: String,
     get_last_qualification_for_attribute_value_id: String,
+    get_last_run_for_attribute_value_id: String,
     list_action_history: String,
     get_last_action_by_action_id: String,
     list_management_history: String,
     get_last_management_by_func_and_component_id: String,
+    list_in_flight_for_change_set: String,
+    filter_referenced_cas_addresses: String,
+    list_change_sets_with_in_flight_runs: String,
 }
 
 impl FuncRunDb {
@@ -49,6 +55,12 @@ impl FuncRunDb {
                    ORDER BY updated_at DESC
                    LIMIT 1",
             ),
+            get_last_run_for_attribute_value_id: format!(
+                "SELECT value FROM {DBNAME}
+                   WHERE attribute_value_id = $3 AND workspace_id = $1 AND change_set_id = $2
+                   ORDER BY updated_at DESC
+                   LIMIT 1",
+            ),
             list_action_history: format!(
                 "SELECT value FROM {DBNAME}
                    WHERE function_kind = 'Action' AND workspace_id = $1
@@ -76,6 +88,28 @@ impl FuncRunDb {
                 LIMIT 1
             "#
             ),
+            list_in_flight_for_change_set: format!(
+                "SELECT value FROM {DBNAME}
+                   WHERE workspace_id = $1 AND change_set_id = $2
+                     AND state IN ('Created', 'Dispatched', 'Running')",
+            ),
+            filter_referenced_cas_addresses: format!(
+                "SELECT address FROM unnest($1::text[]) AS address
+                   WHERE EXISTS (
+                       SELECT 1 FROM {DBNAME} WHERE json_value->>'function_args_cas_address' = address
+                     ) OR EXISTS (
+                       SELECT 1 FROM {DBNAME} WHERE json_value->>'function_code_cas_address' = address
+                     ) OR EXISTS (
+                       SELECT 1 FROM {DBNAME} WHERE json_value->>'result_value_cas_address' = address
+                     ) OR EXISTS (
+                       SELECT 1 FROM {DBNAME}
+                         WHERE json_value->>'result_unprocessed_value_cas_address' = address
+                     )",
+            ),
+            list_change_sets_with_in_flight_runs: format!(
+                "SELECT DISTINCT change_set_id FROM {DBNAME}
+                   WHERE state IN ('Created', 'Dispatched', 'Running')",
+            ),
         }
     }
 
@@ -155,6 +189,75 @@ impl FuncRunDb {
         Ok(result)
     }
 
+    /// Lists the func runs of a change set which have not reached a terminal state yet.
+    pub async fn list_in_flight_for_change_set(
+        &self,
+        workspace_pk: WorkspacePk,
+        change_set_id: ChangeSetId,
+    ) -> LayerDbResult<Option<Vec<FuncRun>>> {
+        let maybe_rows = self
+            .cache
+            .pg()
+            .query(
+                &self.list_in_flight_for_change_set,
+                &[&workspace_pk, &change_set_id],
+            )
+            .await?;
+        let result = match maybe_rows {
+            Some(rows) => {
+                let mut result_rows = Vec::with_capacity(rows.len());
+                for row in rows.into_iter() {
+                    let postcard_bytes: Vec<u8> = row.get("value");
+                    let func_run: FuncRun = serialize::from_bytes(&postcard_bytes[..])?;
+                    result_rows.push(func_run);
+                }
+                Some(result_rows)
+            }
+            None => None,
+        };
+        Ok(result)
+    }
+
+    /// Returns which of the given content store addresses are referenced by any func run, so that
+    /// the content can be kept when collecting garbage.
+    pub async fn filter_referenced_cas_addresses(
+        &self,
+        addresses: &[ContentHash],
+    ) -> LayerDbResult<HashSet<ContentHash>> {
+        let addresses: Vec<String> = addresses.iter().map(ToString::to_string).collect();
+        let rows = self
+            .cache
+            .pg()
+            .query(&self.filter_referenced_cas_addresses, &[&addresses])
+            .await?
+            .unwrap_or_default();
+
+        rows.into_iter()
+            .map(|row| Ok(ContentHash::from_str(row.get("address"))?))
+            .collect()
+    }
+
+    /// Lists the change sets, across all workspaces, which have func runs that have not reached a
+    /// terminal state yet.
+    pub async fn list_change_sets_with_in_flight_runs(
+        &self,
+    ) -> LayerDbResult<HashSet<ChangeSetId>> {
+        let rows = self
+            .cache
+            .pg()
+            .query(&self.list_change_sets_with_in_flight_runs, &[])
+            .await?
+            .unwrap_or_default();
+
+        rows.into_iter()
+            .map(|row| {
+                let change_set_id: String = row.get("change_set_id");
+                ChangeSetId::from_str(&change_set_id)
+                    .map_err(|err| LayerDbError::CouldNotConvertToKeyFromString(err.to_string()))
+            })
+            .collect()
+    }
+
     pub async fn get_last_management_run_for_func_and_component_id(
         &self,
         workspace_pk: WorkspacePk,
@@ -179,6 +282,36 @@ impl FuncRunDb {
 
         Ok(maybe_func)
     }
+
+    /// Returns the most recent [`FuncRun`] of any kind for the attribute value in the change set,
+    /// if there is one.
+    /// Unlike [`Self::get_last_qualification_for_attribute_value_id`], this does not wait for a
+    /// run to be persisted.
+    pub async fn get_last_run_for_attribute_value_id(
+        &self,
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+        attribute_value_id: AttributeValueId,
+    ) -> LayerDbResult<Option<FuncRun>> {
+        let maybe_row = self
+            .cache
+            .pg()
+            .query_opt(
+                &self.get_last_run_for_attribute_value_id,
+                &[&workspace_id, &change_set_id, &attribute_value_id],
+            )
+            .await?;
+
+        let maybe_func_run = if let Some(row) = maybe_row {
+            let postcard_bytes: Vec<u8> = row.get("value");
+            Some(serialize::from_bytes(&postcard_bytes[..])?)
+        } else {
+            None
+        };
+
+        Ok(maybe_func_run)
+    }
+
     pub async fn get_last_qualification_for_attribute_value_id(
         &self,
         workspace_id: WorkspacePk,
@@ -243,6 +376,8 @@ impl FuncRunDb {
         Ok(())
     }
 
+    /// Records the result values of the func run and marks it successful, returning the func run
+    /// as written.
     pub async fn set_values_and_set_state_to_success(
         &self,
         func_run_id: FuncRunId,
@@ -250,19 +385,22 @@ impl FuncRunDb {
         value_cas: Option<ContentHash>,
         tenancy: Tenancy,
         actor: Actor,
-    ) -> LayerDbResult<()> {
+    ) -> LayerDbResult<Arc<FuncRun>> {
         let func_run_old = self.try_read(func_run_id).await?;
         let mut func_run_new = Arc::unwrap_or_clone(func_run_old);
         func_run_new.set_result_unprocessed_value_cas_address(unprocessed_value_cas);
         func_run_new.set_result_value_cas_address(value_cas);
         func_run_new.set_state_to_success();
 
-        self.write(Arc::new(func_run_new), None, tenancy, actor)
+        let func_run_new = Arc::new(func_run_new);
+        self.write(func_run_new.clone(), None, tenancy, actor)
             .await?;
 
-        Ok(())
+        Ok(func_run_new)
     }
 
+    /// Records the result of the management func run and marks it successful, returning the func
+    /// run as written.
     pub async fn set_management_result_success(
         &self,
         func_run_id: FuncRunId,
@@ -271,7 +409,7 @@ impl FuncRunDb {
         value_cas: Option<ContentHash>,
         tenancy: Tenancy,
         actor: Actor,
-    ) -> LayerDbResult<()> {
+    ) -> LayerDbResult<Arc<FuncRun>> {
         let func_run_old = self.try_read(func_run_id).await?;
         let mut func_run_new = Arc::unwrap_or_clone(func_run_old);
         func_run_new.set_result_unprocessed_value_cas_address(unprocessed_value_cas);
@@ -279,42 +417,47 @@ impl FuncRunDb {
         func_run_new.set_state_to_success();
         func_run_new.set_action_result_state(Some(run_result));
 
-        self.write(Arc::new(func_run_new), None, tenancy, actor)
+        let func_run_new = Arc::new(func_run_new);
+        self.write(func_run_new.clone(), None, tenancy, actor)
             .await?;
 
-        Ok(())
+        Ok(func_run_new)
     }
 
+    /// Marks the func run successful, returning the func run as written.
     pub async fn set_state_to_success(
         &self,
         func_run_id: FuncRunId,
         tenancy: Tenancy,
         actor: Actor,
-    ) -> LayerDbResult<()> {
+    ) -> LayerDbResult<Arc<FuncRun>> {
         let func_run_old = self.try_read(func_run_id).await?;
         let mut func_run_new = Arc::unwrap_or_clone(func_run_old);
         func_run_new.set_state_to_success();
 
-        self.write(Arc::new(func_run_new), None, tenancy, actor)
+        let func_run_new = Arc::new(func_run_new);
+        self.write(func_run_new.clone(), None, tenancy, actor)
             .await?;
 
-        Ok(())
+        Ok(func_run_new)
     }
 
+    /// Marks the func run killed, returning the func run as written.
     pub async fn set_state_to_killed(
         &self,
         func_run_id: FuncRunId,
         tenancy: Tenancy,
         actor: Actor,
-    ) -> LayerDbResult<()> {
+    ) -> LayerDbResult<Arc<FuncRun>> {
         let func_run_old = self.try_read(func_run_id).await?;
         let mut func_run_new = Arc::unwrap_or_clone(func_run_old);
         func_run_new.set_state_to_killed();
 
-        self.write(Arc::new(func_run_new), None, tenancy, actor)
+        let func_run_new = Arc::new(func_run_new);
+        self.write(func_run_new.clone(), None, tenancy, actor)
             .await?;
 
-        Ok(())
+        Ok(func_run_new)
     }
 
     pub async fn set_action_result_state(
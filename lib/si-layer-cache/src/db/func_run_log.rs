@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use si_events::{Actor, FuncRunId, FuncRunLog, Tenancy, WebEvent};
+use si_events::{
+    Actor, FuncRunId, FuncRunLog, FuncRunLogEntry, FuncRunLogEntryPage, Tenancy, WebEvent,
+};
 
 use crate::{
     error::LayerDbResult,
@@ -14,12 +16,14 @@ use super::serialize;
 pub const DBNAME: &str = "func_run_logs";
 pub const CACHE_NAME: &str = DBNAME;
 pub const PARTITION_KEY: &str = "workspace_id";
+pub const ENTRIES_DBNAME: &str = "func_run_log_entries";
 
 #[derive(Debug, Clone)]
 pub struct FuncRunLogDb {
     pub cache: Arc<LayerCache<Arc<FuncRunLog>>>,
     persister_client: PersisterClient,
     get_for_func_run_id_query: String,
+    list_entries_query: String,
 }
 
 impl FuncRunLogDb {
@@ -28,6 +32,12 @@ impl FuncRunLogDb {
             cache,
             persister_client,
             get_for_func_run_id_query: format!("SELECT value FROM {DBNAME} WHERE func_run_id = $1"),
+            list_entries_query: format!(
+                "SELECT value FROM {ENTRIES_DBNAME}
+                    WHERE func_run_id = $1 AND sequence >= $2
+                    ORDER BY sequence
+                    LIMIT $3"
+            ),
         }
     }
 
@@ -121,4 +131,94 @@ impl FuncRunLogDb {
             .await?;
         Ok(())
     }
+
+    /// Stores log entries for a function run directly in pg.
+    ///
+    /// Entries are never updated once written, so rewriting an entry with a sequence that has
+    /// already been stored is a no-op.
+    pub async fn write_entries(
+        &self,
+        tenancy: Tenancy,
+        entries: &[FuncRunLogEntry],
+    ) -> LayerDbResult<()> {
+        for entry in entries {
+            let sequence = i64::try_from(entry.sequence)?;
+            self.cache
+                .pg()
+                .insert_raw(
+                    &format!(
+                        "INSERT INTO {ENTRIES_DBNAME} (
+                        func_run_id,
+                        sequence,
+                        workspace_id,
+                        change_set_id,
+                        stream_kind,
+                        timestamp,
+                        value
+                    ) VALUES (
+                        $1,
+                        $2,
+                        $3,
+                        $4,
+                        $5,
+                        $6,
+                        $7
+                    ) ON CONFLICT (func_run_id, sequence) DO NOTHING;"
+                    ),
+                    &[
+                        &entry.func_run_id.to_string(),
+                        &sequence,
+                        &tenancy.workspace_pk.to_string(),
+                        &tenancy.change_set_id.to_string(),
+                        &entry.stream_kind.as_ref(),
+                        &entry.timestamp,
+                        &serialize::to_vec(entry)?.0,
+                    ],
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Lists up to `limit` log entries of a function run, starting at the entry with sequence
+    /// `cursor` (or the first entry, if there is no cursor).
+    pub async fn list_entries(
+        &self,
+        func_run_id: FuncRunId,
+        cursor: Option<u64>,
+        limit: u64,
+    ) -> LayerDbResult<FuncRunLogEntryPage> {
+        let start = i64::try_from(cursor.unwrap_or_default())?;
+        // Ask for one more entry than requested to find out whether there is a following page
+        let fetch_limit = i64::try_from(limit.saturating_add(1))?;
+
+        let rows = self
+            .cache
+            .pg()
+            .query(
+                &self.list_entries_query,
+                &[&func_run_id.to_string(), &start, &fetch_limit],
+            )
+            .await?
+            .unwrap_or_default();
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let postcard_bytes: Vec<u8> = row.get("value");
+            let entry: FuncRunLogEntry = serialize::from_bytes(&postcard_bytes[..])?;
+            entries.push(entry);
+        }
+
+        let next_cursor = if entries.len() as u64 > limit {
+            entries.truncate(limit as usize);
+            entries.last().map(|entry| entry.sequence + 1)
+        } else {
+            None
+        };
+
+        Ok(FuncRunLogEntryPage {
+            entries,
+            next_cursor,
+        })
+    }
 }
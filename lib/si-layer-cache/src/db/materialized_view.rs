@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use serde::{de::DeserializeOwned, Serialize};
+use si_events::{Actor, ContentHash, Tenancy, WebEvent};
+
+use crate::{
+    error::LayerDbResult,
+    event::{LayeredEvent, LayeredEventKind},
+    layer_cache::LayerCache,
+    persister::{PersisterClient, PersisterStatusReader},
+};
+
+use super::serialize;
+
+const KEYWORD_SINGULAR: &str = "materialized_view";
+const KEYWORD_PLURAL: &str = "materialized_views";
+
+pub const PARTITION_KEY: &str = KEYWORD_PLURAL;
+pub const DBNAME: &str = KEYWORD_PLURAL;
+pub const CACHE_NAME: &str = KEYWORD_PLURAL;
+pub const SORT_KEY: &str = KEYWORD_SINGULAR;
+
+/// Stores views that are expensive to compute, keyed by a [`ContentHash`] of everything the view
+/// was computed from. Since the key changes whenever an input changes, entries never need to be
+/// invalidated: a stale view is simply never looked up again.
+///
+/// Views are stored as JSON bytes so that any type can be materialized without threading another
+/// value type through [`LayerDb`](crate::LayerDb). Unlike postcard, JSON supports the
+/// `#[serde(flatten)]` attributes that are common in frontend types.
+#[derive(Debug, Clone)]
+pub struct MaterializedViewDb {
+    pub cache: Arc<LayerCache<Arc<Vec<u8>>>>,
+    persister_client: PersisterClient,
+}
+
+impl MaterializedViewDb {
+    pub fn new(cache: Arc<LayerCache<Arc<Vec<u8>>>>, persister_client: PersisterClient) -> Self {
+        MaterializedViewDb {
+            cache,
+            persister_client,
+        }
+    }
+
+    pub fn write<T>(
+        &self,
+        key: ContentHash,
+        view: &T,
+        web_events: Option<Vec<WebEvent>>,
+        tenancy: Tenancy,
+        actor: Actor,
+    ) -> LayerDbResult<PersisterStatusReader>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = Arc::new(serde_json::to_vec(view)?);
+        let (postcard_value, size_hint) = serialize::to_vec(&value)?;
+
+        let cache_key: Arc<str> = key.to_string().into();
+
+        self.cache.insert(cache_key.clone(), value, size_hint);
+
+        let event = LayeredEvent::new(
+            LayeredEventKind::MaterializedViewWrite,
+            Arc::new(DBNAME.to_string()),
+            cache_key,
+            Arc::new(postcard_value),
+            Arc::new(SORT_KEY.to_string()),
+            web_events,
+            tenancy,
+            actor,
+        );
+        let reader = self.persister_client.write_event(event)?;
+
+        Ok(reader)
+    }
+
+    pub async fn read<T>(&self, key: &ContentHash) -> LayerDbResult<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        match self.cache.get(key.to_string().into()).await? {
+            Some(view_bytes) => Ok(Some(serde_json::from_slice(&view_bytes)?)),
+            None => Ok(None),
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use std::{sync::Arc, time::Instant};
+use std::{str::FromStr, sync::Arc, time::Instant};
 
+use chrono::{DateTime, Utc};
 use serde::{de::DeserializeOwned, Serialize};
 use si_events::{Actor, Tenancy, WebEvent, WorkspaceSnapshotAddress};
 use telemetry::prelude::*;
@@ -9,6 +10,7 @@ use crate::{
     event::{LayeredEvent, LayeredEventKind},
     layer_cache::LayerCache,
     persister::{PersisterClient, PersisterStatusReader},
+    LayerDbError,
 };
 
 use super::serialize;
@@ -183,4 +185,52 @@ where
 
         Ok(())
     }
+
+    /// Lists up to `limit` snapshots in durable storage (along with their size in bytes) which
+    /// were created before `created_before`, in address order, starting after `after` (if given).
+    pub async fn list_created_before(
+        &self,
+        created_before: DateTime<Utc>,
+        after: Option<&WorkspaceSnapshotAddress>,
+        limit: i64,
+    ) -> LayerDbResult<Vec<(WorkspaceSnapshotAddress, u64)>> {
+        let after = after.map(ToString::to_string).unwrap_or_default();
+
+        self.cache
+            .pg()
+            .list_created_before(created_before, &after, limit)
+            .await?
+            .into_iter()
+            .map(|(key, size)| Ok((parse_address(&key)?, size)))
+            .collect()
+    }
+
+    /// Deletes the given snapshots from durable storage (and from memory), skipping any snapshot
+    /// which was (re)written at or after `created_before`. Returns the deleted snapshots along
+    /// with their size in bytes.
+    pub async fn delete_created_before(
+        &self,
+        addresses: &[WorkspaceSnapshotAddress],
+        created_before: DateTime<Utc>,
+    ) -> LayerDbResult<Vec<(WorkspaceSnapshotAddress, u64)>> {
+        let keys: Vec<String> = addresses.iter().map(ToString::to_string).collect();
+        let deleted = self
+            .cache
+            .pg()
+            .delete_created_before(&keys, created_before)
+            .await?;
+
+        deleted
+            .into_iter()
+            .map(|(key, size)| {
+                self.cache.remove_from_memory(&key);
+                Ok((parse_address(&key)?, size))
+            })
+            .collect()
+    }
+}
+
+fn parse_address(key: &str) -> LayerDbResult<WorkspaceSnapshotAddress> {
+    WorkspaceSnapshotAddress::from_str(key)
+        .map_err(|err| LayerDbError::CouldNotConvertToKeyFromString(err.to_string()))
 }
@@ -71,6 +71,7 @@ pub enum LayeredEventKind {
     EncryptedSecretInsertion,
     FuncRunLogWrite,
     FuncRunWrite,
+    MaterializedViewWrite,
     Raw,
     RebaseBatchEvict,
     RebaseBatchWrite,
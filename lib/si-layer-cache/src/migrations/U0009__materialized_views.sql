@@ -0,0 +1,10 @@
+CREATE TABLE materialized_views
+(
+    key               text                      NOT NULL PRIMARY KEY,
+    sort_key          text                      NOT NULL,
+    created_at        timestamp with time zone  NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    value             bytea                     NOT NULL,
+    serialization_lib text                      NOT NULL DEFAULT 'postcard'
+);
+
+CREATE INDEX IF NOT EXISTS materialized_views_sort_key ON materialized_views (sort_key);
@@ -0,0 +1,18 @@
+CREATE TABLE func_run_log_entries
+(
+    func_run_id       text                     NOT NULL,
+    sequence          bigint                   NOT NULL,
+    created_at        timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+
+    workspace_id      text                     NOT NULL,
+    change_set_id     text                     NOT NULL,
+
+    stream_kind       text                     NOT NULL,
+    timestamp         timestamp with time zone NOT NULL,
+    value             bytea                    NOT NULL,
+    serialization_lib text                     NOT NULL DEFAULT 'postcard',
+
+    PRIMARY KEY (func_run_id, sequence)
+);
+
+CREATE INDEX IF NOT EXISTS func_run_log_entries_workspace_id ON func_run_log_entries (workspace_id);
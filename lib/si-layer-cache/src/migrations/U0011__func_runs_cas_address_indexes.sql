@@ -0,0 +1,4 @@
+CREATE INDEX IF NOT EXISTS func_runs_function_args_cas_address ON func_runs ((json_value->>'function_args_cas_address'));
+CREATE INDEX IF NOT EXISTS func_runs_function_code_cas_address ON func_runs ((json_value->>'function_code_cas_address'));
+CREATE INDEX IF NOT EXISTS func_runs_result_value_cas_address ON func_runs ((json_value->>'result_value_cas_address'));
+CREATE INDEX IF NOT EXISTS func_runs_result_unprocessed_value_cas_address ON func_runs ((json_value->>'result_unprocessed_value_cas_address'));
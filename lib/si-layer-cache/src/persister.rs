@@ -323,13 +323,22 @@ impl PersistEventTask {
     pub async fn write_to_pg(&self, event: Arc<LayeredEvent>) -> LayerDbResult<()> {
         let pg_layer = PgLayer::new(self.pg_pool.clone(), event.payload.db_name.as_ref());
         match event.event_kind {
-            LayeredEventKind::CasInsertion
-            | LayeredEventKind::EncryptedSecretInsertion
+            // Content and snapshots are garbage collected, so rewriting them has to keep them
+            LayeredEventKind::CasInsertion | LayeredEventKind::SnapshotWrite => {
+                pg_layer
+                    .insert_or_refresh(
+                        &event.payload.key,
+                        event.payload.sort_key.as_ref(),
+                        &event.payload.value[..],
+                    )
+                    .await?;
+            }
+            LayeredEventKind::EncryptedSecretInsertion
+            | LayeredEventKind::MaterializedViewWrite
             | LayeredEventKind::Raw
             | LayeredEventKind::RebaseBatchEvict
             | LayeredEventKind::RebaseBatchWrite
-            | LayeredEventKind::SnapshotEvict
-            | LayeredEventKind::SnapshotWrite => {
+            | LayeredEventKind::SnapshotEvict => {
                 pg_layer
                     .insert(
                         &event.payload.key,
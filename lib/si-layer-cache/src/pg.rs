@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use si_data_pg::{postgres_types::ToSql, PgPool, PgPoolConfig, PgRow};
 use telemetry::tracing::info;
 use telemetry_utils::metric;
@@ -16,6 +18,12 @@ mod embedded {
 pub const DBNAME: &str = "si_layer_db";
 pub const APPLICATION_NAME: &str = "si-layer-db";
 
+/// Writing a value which is already in durable storage with [`PgLayer::insert_or_refresh`] moves
+/// its `created_at` forward if it is older than this, so that a value which becomes referenced
+/// again is not garbage collected. Any garbage collection retention window must be longer than
+/// this.
+pub const CREATED_AT_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 pub fn default_pg_pool_config() -> PgPoolConfig {
     PgPoolConfig {
         dbname: DBNAME.into(),
@@ -34,8 +42,12 @@ pub struct PgLayer {
     get_value_many_query: String,
     get_most_recent_query: String,
     insert_value_query: String,
+    insert_or_refresh_value_query: String,
+    list_created_before_query: String,
+    delete_created_before_query: String,
     contains_key_query: String,
     search_query: String,
+    total_size_query: String,
 }
 
 impl PgLayer {
@@ -49,8 +61,29 @@ impl PgLayer {
             get_value_many_query: format!("SELECT key, value FROM {table_name} WHERE key = any($1)"),
             get_most_recent_query: format!("SELECT key, value FROM {table_name} ORDER BY created_at LIMIT $1"),
             insert_value_query: format!("INSERT INTO {table_name} (key, sort_key, value) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING"),
+            insert_or_refresh_value_query: format!(
+                "INSERT INTO {table_name} (key, sort_key, value) VALUES ($1, $2, $3)
+                   ON CONFLICT (key) DO UPDATE SET created_at = CLOCK_TIMESTAMP()
+                   WHERE {table_name}.created_at < CLOCK_TIMESTAMP() - INTERVAL '{} seconds'",
+                CREATED_AT_REFRESH_INTERVAL.as_secs(),
+            ),
+            list_created_before_query: format!(
+                "SELECT key, octet_length(value)::bigint AS size FROM {table_name}
+                   WHERE created_at < $1 AND key > $2
+                   ORDER BY key
+                   LIMIT $3"
+            ),
+            delete_created_before_query: format!(
+                "DELETE FROM {table_name}
+                   WHERE key = ANY($1) AND created_at < $2
+                   RETURNING key, octet_length(value)::bigint AS size"
+            ),
             contains_key_query: format!("SELECT key FROM {table_name} WHERE key = $1 LIMIT 1"),
             search_query: format!("SELECT value FROM {table_name} WHERE sort_key LIKE $1"),
+            total_size_query: format!(
+                "SELECT COALESCE(SUM(octet_length(value)), 0)::bigint AS size FROM {table_name}
+                   WHERE key = ANY($1)"
+            ),
             table_name,
         }
     }
@@ -198,6 +231,72 @@ impl PgLayer {
         Ok(())
     }
 
+    /// Like [`Self::insert`], but refreshes `created_at` if the key is already present (see
+    /// [`CREATED_AT_REFRESH_INTERVAL`]).
+    pub async fn insert_or_refresh(
+        &self,
+        key: &str,
+        sort_key: impl AsRef<str>,
+        value: &[u8],
+    ) -> LayerDbResult<()> {
+        let client = self.pool.get().await?;
+        let sort_key = sort_key.as_ref();
+        client
+            .query(
+                &self.insert_or_refresh_value_query,
+                &[&key, &sort_key, &value],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Lists up to `limit` keys (along with the size of their values) which were created before
+    /// `created_before`, in key order, starting after `after_key`.
+    pub async fn list_created_before(
+        &self,
+        created_before: DateTime<Utc>,
+        after_key: &str,
+        limit: i64,
+    ) -> LayerDbResult<Vec<(String, u64)>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                &self.list_created_before_query,
+                &[&created_before, &after_key, &limit],
+            )
+            .await?;
+
+        rows.into_iter()
+            .map(|row| Ok((row.get("key"), row.get::<_, i64>("size").try_into()?)))
+            .collect()
+    }
+
+    /// Deletes the given keys, skipping any key which was (re)written at or after
+    /// `created_before`. Returns the deleted keys along with the size of their values.
+    pub async fn delete_created_before(
+        &self,
+        keys: &[String],
+        created_before: DateTime<Utc>,
+    ) -> LayerDbResult<Vec<(String, u64)>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(&self.delete_created_before_query, &[&keys, &created_before])
+            .await?;
+
+        rows.into_iter()
+            .map(|row| Ok((row.get("key"), row.get::<_, i64>("size").try_into()?)))
+            .collect()
+    }
+
+    /// Returns the total size of the stored values for the given keys. Keys which are not stored
+    /// are ignored.
+    pub async fn total_size(&self, keys: &[String]) -> LayerDbResult<u64> {
+        let client = self.pool.get().await?;
+        let row = client.query_one(&self.total_size_query, &[&keys]).await?;
+
+        Ok(row.get::<_, i64>("size").try_into()?)
+    }
+
     pub async fn insert_raw(
         &self,
         query: &str,
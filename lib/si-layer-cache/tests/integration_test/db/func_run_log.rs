@@ -1,7 +1,8 @@
 use std::{sync::Arc, time::Duration};
 
 use si_events::{
-    Actor, ChangeSetId, FuncRunId, FuncRunLog, OutputLine, Tenancy, UserPk, WorkspacePk,
+    Actor, ChangeSetId, FuncRunId, FuncRunLog, FuncRunLogEntry, OutputLine, OutputStreamKind,
+    Tenancy, UserPk, WorkspacePk,
 };
 use si_layer_cache::LayerDb;
 use si_layer_cache::{db::serialize, hybrid_cache::CacheConfig};
@@ -227,3 +228,67 @@ async fn write_and_get_for_func_run_id() {
 
     assert_eq!(value.id(), read_value.id());
 }
+
+#[tokio::test]
+async fn write_and_list_entries() {
+    let token = CancellationToken::new();
+
+    let (ldb, _): (TestLayerDb, _) = LayerDb::from_services(
+        setup_pg_db("func_run_log_write_and_list_entries").await,
+        setup_nats_client(Some("func_run_log_write_and_list_entries".to_string())).await,
+        setup_compute_executor(),
+        CacheConfig::default(),
+        token,
+    )
+    .await
+    .expect("cannot create layerdb");
+    ldb.pg_migrate().await.expect("migrate layer db");
+
+    let tenancy = Tenancy::new(WorkspacePk::new(), ChangeSetId::new());
+    let func_run_id = FuncRunId::new();
+    let entries: Vec<FuncRunLogEntry> = ["stdout", "stderr", "output"]
+        .into_iter()
+        .enumerate()
+        .map(|(sequence, stream)| {
+            FuncRunLogEntry::new(
+                func_run_id,
+                sequence as u64,
+                OutputLine {
+                    stream: stream.to_string(),
+                    execution_id: "execution".to_string(),
+                    level: "info".to_string(),
+                    group: None,
+                    message: format!("line {sequence}"),
+                    timestamp: 1_700_000_000,
+                },
+            )
+        })
+        .collect();
+
+    ldb.func_run_log()
+        .write_entries(tenancy, &entries)
+        .await
+        .expect("failed to write entries");
+    // Writing the same entries again must not duplicate them
+    ldb.func_run_log()
+        .write_entries(tenancy, &entries)
+        .await
+        .expect("failed to rewrite entries");
+
+    let first_page = ldb
+        .func_run_log()
+        .list_entries(func_run_id, None, 2)
+        .await
+        .expect("failed to list entries");
+    assert_eq!(entries[..2], first_page.entries[..]);
+    assert_eq!(Some(2), first_page.next_cursor);
+
+    let second_page = ldb
+        .func_run_log()
+        .list_entries(func_run_id, first_page.next_cursor, 2)
+        .await
+        .expect("failed to list entries");
+    assert_eq!(entries[2..], second_page.entries[..]);
+    assert_eq!(OutputStreamKind::Result, second_page.entries[0].stream_kind);
+    assert_eq!(None, second_page.next_cursor);
+}
@@ -0,0 +1,449 @@
+//! Compute the delta between a [`PkgSpec`] and the package it was derived from, so that edits to
+//! an installed module can be contributed back upstream without resubmitting the whole module.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{FuncSpec, PkgSpec, PropSpec, SchemaSpec, SchemaVariantSpec, SiPkgKind};
+
+/// The part of a [`PkgSpec`] that is not present in the package it was derived from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PkgDelta {
+    /// A package containing only the new and changed funcs and the new props. Ancestors of new
+    /// props are kept so that every prop can be placed, but they are not part of the delta. The
+    /// package is of kind [`SiPkgKind::Delta`], as it is meant to be reviewed on top of its base
+    /// and cannot be installed on its own.
+    pub spec: PkgSpec,
+    /// The names of funcs which are new or whose definition changed.
+    pub funcs: Vec<String>,
+    /// The paths of props which are new or whose kind changed, e.g. "/root/domain/region".
+    pub props: Vec<String>,
+    /// The names of sockets which are new.
+    pub sockets: Vec<String>,
+}
+
+impl PkgDelta {
+    pub fn is_empty(&self) -> bool {
+        self.funcs.is_empty() && self.props.is_empty() && self.sockets.is_empty()
+    }
+}
+
+/// Compute the [`PkgDelta`] of `local` against `base`. Schemas are matched by name and each
+/// variant of `local` is compared against the latest non-deleted variant of the base schema.
+pub fn delta(base: &PkgSpec, local: &PkgSpec) -> PkgDelta {
+    let mut delta_funcs = vec![];
+    let mut func_names = vec![];
+    for func in local.funcs.iter().filter(|func| !func.deleted) {
+        let unchanged = base
+            .funcs
+            .iter()
+            .find(|base_func| base_func.unique_id == func.unique_id || base_func.name == func.name)
+            .is_some_and(|base_func| func_definition_eq(base_func, func));
+        if !unchanged {
+            func_names.push(func.name.to_owned());
+            delta_funcs.push(func.to_owned());
+        }
+    }
+    let delta_func_unique_ids: HashSet<&str> = delta_funcs
+        .iter()
+        .map(|func| func.unique_id.as_str())
+        .collect();
+
+    let mut props = vec![];
+    let mut sockets = vec![];
+    let mut schemas = vec![];
+    for schema in local.schemas.iter().filter(|schema| !schema.deleted) {
+        let base_variant = base
+            .schemas
+            .iter()
+            .find(|base_schema| base_schema.name == schema.name)
+            .and_then(|base_schema| {
+                base_schema
+                    .variants
+                    .iter()
+                    .rev()
+                    .find(|variant| !variant.deleted)
+            });
+
+        let variants = schema
+            .variants
+            .iter()
+            .filter(|variant| !variant.deleted)
+            .map(|variant| {
+                delta_variant(
+                    base_variant,
+                    variant,
+                    &delta_func_unique_ids,
+                    &mut props,
+                    &mut sockets,
+                )
+            })
+            .collect();
+
+        schemas.push(SchemaSpec {
+            variants,
+            ..schema.to_owned()
+        });
+    }
+
+    PkgDelta {
+        spec: PkgSpec {
+            kind: SiPkgKind::Delta,
+            schemas,
+            funcs: delta_funcs,
+            change_sets: vec![],
+            ..local.to_owned()
+        },
+        funcs: func_names,
+        props,
+        sockets,
+    }
+}
+
+fn delta_variant(
+    base: Option<&SchemaVariantSpec>,
+    variant: &SchemaVariantSpec,
+    delta_func_unique_ids: &HashSet<&str>,
+    props: &mut Vec<String>,
+    sockets: &mut Vec<String>,
+) -> SchemaVariantSpec {
+    let mut delta = variant.to_owned();
+
+    delta.domain = delta_root_prop(&variant.domain, base.map(|base| &base.domain), props);
+    delta.secrets = delta_root_prop(&variant.secrets, base.map(|base| &base.secrets), props);
+    delta.resource_value = delta_root_prop(
+        &variant.resource_value,
+        base.map(|base| &base.resource_value),
+        props,
+    );
+    delta.secret_definition = variant.secret_definition.as_ref().map(|secret_definition| {
+        delta_root_prop(
+            secret_definition,
+            base.and_then(|base| base.secret_definition.as_ref()),
+            props,
+        )
+    });
+
+    delta.sockets.retain(|socket| {
+        let is_new = !base.is_some_and(|base| {
+            base.sockets.iter().any(|base_socket| {
+                base_socket.name == socket.name && base_socket.kind() == socket.kind()
+            })
+        });
+        if is_new {
+            sockets.push(socket.name.to_owned());
+        }
+        is_new
+    });
+
+    // Only keep the bindings of funcs that are part of the delta
+    let in_delta = |func_unique_id: &str| delta_func_unique_ids.contains(func_unique_id);
+    delta
+        .action_funcs
+        .retain(|func| in_delta(&func.func_unique_id));
+    delta
+        .auth_funcs
+        .retain(|func| in_delta(&func.func_unique_id));
+    delta
+        .leaf_functions
+        .retain(|func| in_delta(&func.func_unique_id));
+    delta
+        .si_prop_funcs
+        .retain(|func| in_delta(&func.func_unique_id));
+    delta
+        .management_funcs
+        .retain(|func| in_delta(&func.func_unique_id));
+    delta
+        .root_prop_funcs
+        .retain(|func| in_delta(&func.func_unique_id));
+
+    delta
+}
+
+/// Root props always exist on a variant, so they are kept even if none of their children are new.
+fn delta_root_prop(local: &PropSpec, base: Option<&PropSpec>, props: &mut Vec<String>) -> PropSpec {
+    let path = format!("/root/{}", local.name());
+    match (local, base) {
+        (PropSpec::Object { .. }, Some(base @ PropSpec::Object { .. })) => {
+            delta_prop(local, Some(base), &path, props).unwrap_or_else(|| PropSpec::Object {
+                name: local.name().to_owned(),
+                data: local.data().cloned(),
+                unique_id: None,
+                entries: vec![],
+            })
+        }
+        _ => {
+            props.push(path);
+            local.to_owned()
+        }
+    }
+}
+
+/// Returns the part of `local` which is not in `base`, or `None` if nothing is new.
+fn delta_prop(
+    local: &PropSpec,
+    base: Option<&PropSpec>,
+    path: &str,
+    props: &mut Vec<String>,
+) -> Option<PropSpec> {
+    let Some(base) = base.filter(|base| base.kind() == local.kind()) else {
+        props.push(path.to_owned());
+        return Some(local.to_owned());
+    };
+
+    match (local, base) {
+        (
+            PropSpec::Object {
+                name,
+                data,
+                unique_id,
+                entries,
+            },
+            PropSpec::Object {
+                entries: base_entries,
+                ..
+            },
+        ) => {
+            let entries: Vec<PropSpec> = entries
+                .iter()
+                .filter_map(|entry| {
+                    let base_entry = base_entries
+                        .iter()
+                        .find(|base_entry| base_entry.name() == entry.name());
+                    let entry_path = format!("{path}/{}", entry.name());
+                    delta_prop(entry, base_entry, &entry_path, props)
+                })
+                .collect();
+
+            (!entries.is_empty()).then(|| PropSpec::Object {
+                name: name.to_owned(),
+                data: data.to_owned(),
+                unique_id: unique_id.to_owned(),
+                entries,
+            })
+        }
+        (
+            PropSpec::Array {
+                name,
+                data,
+                unique_id,
+                type_prop,
+            },
+            PropSpec::Array {
+                type_prop: base_type_prop,
+                ..
+            },
+        ) => {
+            let type_prop_path = format!("{path}/{}", type_prop.name());
+            delta_prop(type_prop, Some(base_type_prop), &type_prop_path, props).map(|type_prop| {
+                PropSpec::Array {
+                    name: name.to_owned(),
+                    data: data.to_owned(),
+                    unique_id: unique_id.to_owned(),
+                    type_prop: Box::new(type_prop),
+                }
+            })
+        }
+        (
+            PropSpec::Map {
+                name,
+                data,
+                unique_id,
+                type_prop,
+                map_key_funcs,
+            },
+            PropSpec::Map {
+                type_prop: base_type_prop,
+                ..
+            },
+        ) => {
+            let type_prop_path = format!("{path}/{}", type_prop.name());
+            delta_prop(type_prop, Some(base_type_prop), &type_prop_path, props).map(|type_prop| {
+                PropSpec::Map {
+                    name: name.to_owned(),
+                    data: data.to_owned(),
+                    unique_id: unique_id.to_owned(),
+                    type_prop: Box::new(type_prop),
+                    map_key_funcs: map_key_funcs.to_owned(),
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Funcs are re-identified on install, so they are compared by their definition rather than by
+/// their unique id.
+fn func_definition_eq(base: &FuncSpec, local: &FuncSpec) -> bool {
+    let definition = |func: &FuncSpec| {
+        (
+            serde_json::to_value(&func.data).ok(),
+            serde_json::to_value(&func.arguments).ok(),
+        )
+    };
+
+    definition(base) == definition(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecData, PropSpecKind,
+        SchemaVariantSpecData,
+    };
+
+    use super::*;
+
+    fn func(name: &str, code: &str) -> FuncSpec {
+        FuncSpec::builder()
+            .name(name)
+            .unique_id(format!("{name}-{code}"))
+            .data(
+                FuncSpecData::builder()
+                    .name(name)
+                    .handler("main")
+                    .code_plaintext(code)
+                    .backend_kind(FuncSpecBackendKind::JsAttribute)
+                    .response_type(FuncSpecBackendResponseType::String)
+                    .build()
+                    .expect("build func data"),
+            )
+            .build()
+            .expect("build func")
+    }
+
+    fn prop(name: &str, kind: PropSpecKind, entries: Vec<PropSpec>) -> PropSpec {
+        let mut builder = PropSpec::builder();
+        builder.name(name).kind(kind);
+        for entry in entries {
+            builder.entry(entry);
+        }
+        builder.build().expect("build prop")
+    }
+
+    fn spec(domain_entries: Vec<PropSpec>, funcs: Vec<FuncSpec>) -> PkgSpec {
+        let mut variant = SchemaVariantSpec::builder();
+        variant.version("v0").data(
+            SchemaVariantSpecData::builder()
+                .version("v0")
+                .color("#ff9900")
+                .func_unique_id("asset")
+                .build()
+                .expect("build variant data"),
+        );
+        for entry in domain_entries {
+            variant.domain_prop(entry);
+        }
+
+        PkgSpec::builder()
+            .name("pkg")
+            .version("0")
+            .created_by("sally@systeminit.com")
+            .schema(
+                SchemaSpec::builder()
+                    .name("schema")
+                    .variant(variant.build().expect("build variant"))
+                    .build()
+                    .expect("build schema"),
+            )
+            .funcs(funcs)
+            .build()
+            .expect("build pkg spec")
+    }
+
+    #[test]
+    fn unchanged_package_has_empty_delta() {
+        let base = spec(
+            vec![prop("region", PropSpecKind::String, vec![])],
+            vec![func("asset", "function main() {}")],
+        );
+
+        let delta = delta(&base, &base);
+
+        assert!(delta.is_empty());
+        assert!(delta.spec.funcs.is_empty());
+        let variant = &delta.spec.schemas[0].variants[0];
+        assert!(variant.domain.direct_children().is_empty());
+    }
+
+    #[test]
+    fn delta_contains_only_new_funcs_and_props() {
+        let base = spec(
+            vec![
+                prop("region", PropSpecKind::String, vec![]),
+                prop(
+                    "tags",
+                    PropSpecKind::Object,
+                    vec![prop("owner", PropSpecKind::String, vec![])],
+                ),
+            ],
+            vec![
+                func("asset", "function main() {}"),
+                func("validate", "function main() { return true; }"),
+            ],
+        );
+        let local = spec(
+            vec![
+                prop("region", PropSpecKind::String, vec![]),
+                prop(
+                    "tags",
+                    PropSpecKind::Object,
+                    vec![
+                        prop("owner", PropSpecKind::String, vec![]),
+                        prop("team", PropSpecKind::String, vec![]),
+                    ],
+                ),
+                prop("size", PropSpecKind::Number, vec![]),
+            ],
+            vec![
+                func("asset", "function main() {}"),
+                func("validate", "function main() { return false; }"),
+                func("resize", "function main() {}"),
+            ],
+        );
+
+        let delta = delta(&base, &local);
+
+        assert_eq!(vec!["validate", "resize"], delta.funcs);
+        assert_eq!(
+            vec!["/root/domain/tags/team", "/root/domain/size"],
+            delta.props
+        );
+        assert!(delta.sockets.is_empty());
+
+        let domain = &delta.spec.schemas[0].variants[0].domain;
+        let names: Vec<&str> = domain
+            .direct_children()
+            .into_iter()
+            .map(|prop| prop.name())
+            .collect();
+        assert_eq!(vec!["tags", "size"], names);
+    }
+
+    #[test]
+    fn delta_package_is_marked_as_delta() {
+        let base = spec(vec![], vec![func("asset", "function main() {}")]);
+        let local = spec(
+            vec![],
+            vec![
+                func("asset", "function main() {}"),
+                func("resize", "function main() {}"),
+            ],
+        );
+
+        let delta = delta(&base, &local);
+        assert_eq!(SiPkgKind::Delta, delta.spec.kind);
+
+        let bytes = crate::SiPkg::load_from_spec(delta.spec)
+            .expect("load delta spec")
+            .write_to_bytes()
+            .expect("write delta pkg");
+        let pkg = crate::SiPkg::load_from_bytes(&bytes).expect("read delta pkg");
+        assert_eq!(
+            SiPkgKind::Delta,
+            pkg.metadata().expect("get metadata").kind()
+        );
+    }
+}
@@ -1,8 +1,12 @@
+mod delta;
+mod lint;
 pub(crate) mod node;
 mod pkg;
 mod spec;
 mod workspace;
 
+pub use delta::{delta, PkgDelta};
+pub use lint::{lint, LintFinding, LintRule, LintSeverity, MAX_PROP_DEPTH};
 pub use pkg::*;
 pub use spec::*;
 pub use workspace::{
@@ -75,6 +79,42 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn pkg_bindings_round_trip() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let hash_without_bindings = SiPkg::load_from_spec(spec.clone())
+            .expect("failed to load spec")
+            .hash()
+            .expect("get hash");
+
+        let mut spec_with_bindings = spec.clone();
+        spec_with_bindings.bindings.push(
+            BindingSpec::builder()
+                .kind(BindingSpecKind::SecretDefinition)
+                .schema_name("k8s deployment")
+                .input_socket_name("Kubeconfig")
+                .source_schema_name("Kubeconfig")
+                .source_output_socket_name("Kubeconfig")
+                .build()
+                .expect("build binding"),
+        );
+        let pkg = SiPkg::load_from_spec(spec_with_bindings).expect("failed to load spec");
+        assert_ne!(hash_without_bindings, pkg.hash().expect("get hash"));
+
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(&pkg_data).expect("failed to load pkg from bytes");
+
+        let bindings = read_pkg.bindings().expect("get bindings");
+        assert_eq!(1, bindings.len());
+        let binding = bindings.first().expect("has binding");
+        assert_eq!(BindingSpecKind::SecretDefinition, binding.kind());
+        assert_eq!("k8s deployment", binding.schema_name());
+        assert_eq!("Kubeconfig", binding.source_output_socket_name());
+
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        assert!(pkg.bindings().expect("get bindings").is_empty());
+    }
+
     #[tokio::test]
     async fn pkg_bytes_round_trip() {
         let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
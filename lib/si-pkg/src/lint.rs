@@ -0,0 +1,478 @@
+//! Lint rules for [`PkgSpec`]s, so that the module index and CI can gate contributions on the
+//! quality of a package rather than only on whether it can be imported.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display};
+
+use crate::{
+    FuncSpec, PkgSpec, PropSpec, SchemaSpec, SchemaVariantSpec, SiPkgKind, SocketSpecKind,
+};
+
+/// Props nested deeper than this underneath one of the root props (e.g. "/root/domain") are
+/// hard to work with in the attribute panel.
+pub const MAX_PROP_DEPTH: usize = 6;
+
+/// How severe a [`LintFinding`] is. Ordered from least to most severe, so that callers can gate
+/// on a minimum severity.
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum LintSeverity {
+    // NOTE: the order of the variants determines the ordering of severities
+    Info,
+    Warning,
+    Error,
+}
+
+#[remain::sorted]
+#[derive(AsRefStr, Clone, Copy, Debug, Deserialize, Display, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum LintRule {
+    /// Variant colors must be hex colors of the form `#rrggbb`.
+    ColorFormat,
+    /// The package, its variants and its funcs should describe themselves.
+    MissingDescription,
+    /// Props should not be nested deeper than [`MAX_PROP_DEPTH`].
+    PropNestingTooDeep,
+    /// Socket names must be unique per direction and must not have surrounding whitespace.
+    SocketName,
+    /// Every func in a module should be used by one of its schema variants.
+    UnusedFunc,
+}
+
+impl LintRule {
+    pub fn severity(&self) -> LintSeverity {
+        match self {
+            Self::ColorFormat => LintSeverity::Error,
+            Self::MissingDescription => LintSeverity::Warning,
+            Self::PropNestingTooDeep => LintSeverity::Warning,
+            Self::SocketName => LintSeverity::Error,
+            Self::UnusedFunc => LintSeverity::Warning,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    /// A human readable path to the offending part of the package.
+    pub location: String,
+    pub message: String,
+}
+
+impl LintFinding {
+    fn new(rule: LintRule, location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            severity: rule.severity(),
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Lint the provided [`PkgSpec`], returning the findings in the order they were found. An empty
+/// list means the package is clean.
+pub fn lint(spec: &PkgSpec) -> Vec<LintFinding> {
+    let mut findings = vec![];
+
+    if is_blank(Some(spec.description.as_str())) {
+        findings.push(LintFinding::new(
+            LintRule::MissingDescription,
+            "package",
+            "package has no description",
+        ));
+    }
+
+    for schema in spec.schemas.iter().filter(|schema| !schema.deleted) {
+        for variant in schema.variants.iter().filter(|variant| !variant.deleted) {
+            lint_variant(schema, variant, &mut findings);
+        }
+    }
+
+    for func in spec.funcs.iter().filter(|func| !func.deleted) {
+        lint_func(func, &mut findings);
+    }
+
+    // Funcs of workspace backups are also used by components, which are not linted
+    if spec.kind == SiPkgKind::Module {
+        let used_func_unique_ids = used_func_unique_ids(spec);
+        for func in spec.funcs.iter().filter(|func| !func.deleted) {
+            if !used_func_unique_ids.contains(func.unique_id.as_str()) {
+                findings.push(LintFinding::new(
+                    LintRule::UnusedFunc,
+                    func_location(func),
+                    "func is not used by any schema variant",
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+fn lint_variant(schema: &SchemaSpec, variant: &SchemaVariantSpec, findings: &mut Vec<LintFinding>) {
+    let location = format!("schema \"{}\" variant \"{}\"", schema.name, variant.version);
+
+    match variant.data.as_ref() {
+        Some(data) => {
+            if is_blank(data.description.as_deref()) {
+                findings.push(LintFinding::new(
+                    LintRule::MissingDescription,
+                    &location,
+                    "schema variant has no description",
+                ));
+            }
+            if let Some(color) = data.color.as_deref() {
+                if !is_hex_color(color) {
+                    findings.push(LintFinding::new(
+                        LintRule::ColorFormat,
+                        &location,
+                        format!("color \"{color}\" is not of the form #rrggbb"),
+                    ));
+                }
+            }
+        }
+        None => findings.push(LintFinding::new(
+            LintRule::MissingDescription,
+            &location,
+            "schema variant has no description",
+        )),
+    }
+
+    for root_prop in [&variant.domain, &variant.secrets, &variant.resource_value]
+        .into_iter()
+        .chain(variant.secret_definition.as_ref())
+    {
+        let root_path = format!("/root/{}", root_prop.name());
+        lint_prop_depth(&location, root_prop, &root_path, 0, findings);
+    }
+
+    let mut socket_names = HashSet::new();
+    for socket in &variant.sockets {
+        let kind = socket.kind();
+        let socket_location = format!("{location} socket \"{}\"", socket.name);
+        if socket.name.trim().is_empty() {
+            findings.push(LintFinding::new(
+                LintRule::SocketName,
+                socket_location,
+                "socket name is empty",
+            ));
+            continue;
+        }
+        if socket.name.trim() != socket.name {
+            findings.push(LintFinding::new(
+                LintRule::SocketName,
+                &socket_location,
+                "socket name has leading or trailing whitespace",
+            ));
+        }
+        if !socket_names.insert((kind, socket.name.trim().to_lowercase())) {
+            let direction = match kind {
+                Some(SocketSpecKind::Input) => "input ",
+                Some(SocketSpecKind::Output) => "output ",
+                None => "",
+            };
+            findings.push(LintFinding::new(
+                LintRule::SocketName,
+                &socket_location,
+                format!("another {direction}socket has the same name"),
+            ));
+        }
+    }
+}
+
+/// Only the first prop exceeding [`MAX_PROP_DEPTH`] on each path is reported.
+fn lint_prop_depth(
+    location: &str,
+    prop: &PropSpec,
+    path: &str,
+    depth: usize,
+    findings: &mut Vec<LintFinding>,
+) {
+    if depth > MAX_PROP_DEPTH {
+        findings.push(LintFinding::new(
+            LintRule::PropNestingTooDeep,
+            format!("{location} prop \"{path}\""),
+            format!("prop is nested {depth} levels deep, the maximum is {MAX_PROP_DEPTH}"),
+        ));
+        return;
+    }
+
+    for child in prop.direct_children() {
+        let child_path = format!("{path}/{}", child.name());
+        lint_prop_depth(location, child, &child_path, depth + 1, findings);
+    }
+}
+
+fn lint_func(func: &FuncSpec, findings: &mut Vec<LintFinding>) {
+    let has_description = func
+        .data
+        .as_ref()
+        .is_some_and(|data| !is_blank(data.description.as_deref()));
+    if !has_description {
+        findings.push(LintFinding::new(
+            LintRule::MissingDescription,
+            func_location(func),
+            "func has no description",
+        ));
+    }
+}
+
+fn used_func_unique_ids(spec: &PkgSpec) -> HashSet<&str> {
+    let mut used = HashSet::new();
+
+    for variant in spec.schemas.iter().flat_map(|schema| &schema.variants) {
+        if let Some(data) = variant.data.as_ref() {
+            used.insert(data.func_unique_id.as_str());
+        }
+        used.extend(
+            variant
+                .action_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        used.extend(
+            variant
+                .auth_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        used.extend(
+            variant
+                .leaf_functions
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        used.extend(
+            variant
+                .si_prop_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        used.extend(
+            variant
+                .management_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        used.extend(
+            variant
+                .root_prop_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        used.extend(
+            variant
+                .sockets
+                .iter()
+                .filter_map(|socket| socket.data.as_ref())
+                .filter_map(|data| data.func_unique_id.as_deref()),
+        );
+
+        let mut props: Vec<&PropSpec> =
+            [&variant.domain, &variant.secrets, &variant.resource_value]
+                .into_iter()
+                .chain(variant.secret_definition.as_ref())
+                .collect();
+        while let Some(prop) = props.pop() {
+            used.extend(prop.func_unique_id());
+            if let PropSpec::Map {
+                map_key_funcs: Some(map_key_funcs),
+                ..
+            } = prop
+            {
+                used.extend(
+                    map_key_funcs
+                        .iter()
+                        .map(|func| func.func_unique_id.as_str()),
+                );
+            }
+            props.extend(prop.direct_children());
+        }
+    }
+
+    used
+}
+
+fn func_location(func: &FuncSpec) -> String {
+    format!("func \"{}\"", func.name)
+}
+
+fn is_blank(value: Option<&str>) -> bool {
+    value.is_none_or(|value| value.trim().is_empty())
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecData, PropSpecKind,
+        SchemaVariantSpecData, SocketSpec, SocketSpecData,
+    };
+
+    use super::*;
+
+    fn func(unique_id: &str, description: Option<&str>) -> FuncSpec {
+        let mut data = FuncSpecData::builder();
+        data.name(unique_id)
+            .handler("main")
+            .code_plaintext("function main() {}")
+            .backend_kind(FuncSpecBackendKind::JsAttribute)
+            .response_type(FuncSpecBackendResponseType::String);
+        if let Some(description) = description {
+            data.description(description);
+        }
+
+        FuncSpec::builder()
+            .name(unique_id)
+            .unique_id(unique_id)
+            .data(data.build().expect("build func data"))
+            .build()
+            .expect("build func")
+    }
+
+    fn socket(name: &str, kind: SocketSpecKind) -> SocketSpec {
+        SocketSpec::builder()
+            .name(name)
+            .data(
+                SocketSpecData::builder()
+                    .name(name)
+                    .kind(kind)
+                    .connection_annotations("[]")
+                    .build()
+                    .expect("build socket data"),
+            )
+            .build()
+            .expect("build socket")
+    }
+
+    fn nested_prop(depth: usize) -> PropSpec {
+        let mut prop = PropSpec::builder()
+            .name(format!("level{depth}"))
+            .kind(PropSpecKind::String)
+            .build()
+            .expect("build prop");
+        for level in (1..depth).rev() {
+            prop = PropSpec::builder()
+                .name(format!("level{level}"))
+                .kind(PropSpecKind::Object)
+                .entry(prop)
+                .build()
+                .expect("build prop");
+        }
+        prop
+    }
+
+    fn spec(
+        color: &str,
+        sockets: Vec<SocketSpec>,
+        prop: PropSpec,
+        funcs: Vec<FuncSpec>,
+    ) -> PkgSpec {
+        let variant = SchemaVariantSpec::builder()
+            .version("v0")
+            .data(
+                SchemaVariantSpecData::builder()
+                    .version("v0")
+                    .color(color)
+                    .description(Some("a variant".to_owned()))
+                    .func_unique_id("asset")
+                    .build()
+                    .expect("build variant data"),
+            )
+            .sockets(sockets)
+            .domain_prop(prop)
+            .build()
+            .expect("build variant");
+
+        PkgSpec::builder()
+            .name("pkg")
+            .version("0")
+            .description("a package")
+            .created_by("sally@systeminit.com")
+            .schema(
+                SchemaSpec::builder()
+                    .name("schema")
+                    .variant(variant)
+                    .build()
+                    .expect("build schema"),
+            )
+            .funcs(funcs)
+            .build()
+            .expect("build pkg spec")
+    }
+
+    #[test]
+    fn clean_package_has_no_findings() {
+        let spec = spec(
+            "#ff9900",
+            vec![
+                socket("Region", SocketSpecKind::Input),
+                socket("Region", SocketSpecKind::Output),
+            ],
+            nested_prop(MAX_PROP_DEPTH),
+            vec![func("asset", Some("the asset func"))],
+        );
+
+        assert_eq!(Vec::<LintFinding>::new(), lint(&spec));
+    }
+
+    #[test]
+    fn reports_findings_with_severity() {
+        let spec = spec(
+            "orange",
+            vec![
+                socket("Region", SocketSpecKind::Input),
+                socket("region ", SocketSpecKind::Input),
+            ],
+            nested_prop(MAX_PROP_DEPTH + 2),
+            vec![func("asset", None), func("unused", Some("not used"))],
+        );
+
+        let findings = lint(&spec);
+        let rules: Vec<LintRule> = findings.iter().map(|finding| finding.rule).collect();
+        assert_eq!(
+            vec![
+                LintRule::ColorFormat,
+                LintRule::PropNestingTooDeep,
+                LintRule::SocketName,
+                LintRule::SocketName,
+                LintRule::MissingDescription,
+                LintRule::UnusedFunc,
+            ],
+            rules
+        );
+        assert_eq!(
+            Some(LintSeverity::Error),
+            findings.iter().map(|finding| finding.severity).max()
+        );
+        assert_eq!(
+            "schema \"schema\" variant \"v0\" prop \"/root/domain/level1/level2/level3/level4/level5/level6/level7\"",
+            findings[1].location
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+use object_tree::{
+    read_key_value_line, write_key_value_line, GraphError, NodeChild, NodeKind, NodeWithChildren,
+    ReadBytes, WriteBytes,
+};
+
+use super::PkgNode;
+use crate::{BindingSpec, BindingSpecKind};
+
+const KEY_KIND_STR: &str = "kind";
+const KEY_SCHEMA_NAME_STR: &str = "schema_name";
+const KEY_INPUT_SOCKET_NAME_STR: &str = "input_socket_name";
+const KEY_SOURCE_SCHEMA_NAME_STR: &str = "source_schema_name";
+const KEY_SOURCE_OUTPUT_SOCKET_NAME_STR: &str = "source_output_socket_name";
+
+#[derive(Clone, Debug)]
+pub struct BindingNode {
+    pub kind: BindingSpecKind,
+    pub schema_name: String,
+    pub input_socket_name: String,
+    pub source_schema_name: String,
+    pub source_output_socket_name: String,
+}
+
+impl WriteBytes for BindingNode {
+    fn write_bytes<W: Write>(&self, writer: &mut W) -> Result<(), GraphError> {
+        write_key_value_line(writer, KEY_KIND_STR, self.kind)?;
+        write_key_value_line(writer, KEY_SCHEMA_NAME_STR, &self.schema_name)?;
+        write_key_value_line(writer, KEY_INPUT_SOCKET_NAME_STR, &self.input_socket_name)?;
+        write_key_value_line(writer, KEY_SOURCE_SCHEMA_NAME_STR, &self.source_schema_name)?;
+        write_key_value_line(
+            writer,
+            KEY_SOURCE_OUTPUT_SOCKET_NAME_STR,
+            &self.source_output_socket_name,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl ReadBytes for BindingNode {
+    fn read_bytes<R: BufRead>(reader: &mut R) -> Result<Option<Self>, GraphError>
+    where
+        Self: std::marker::Sized,
+    {
+        let kind_str = read_key_value_line(reader, KEY_KIND_STR)?;
+        let kind = BindingSpecKind::from_str(&kind_str).map_err(GraphError::parse)?;
+        let schema_name = read_key_value_line(reader, KEY_SCHEMA_NAME_STR)?;
+        let input_socket_name = read_key_value_line(reader, KEY_INPUT_SOCKET_NAME_STR)?;
+        let source_schema_name = read_key_value_line(reader, KEY_SOURCE_SCHEMA_NAME_STR)?;
+        let source_output_socket_name =
+            read_key_value_line(reader, KEY_SOURCE_OUTPUT_SOCKET_NAME_STR)?;
+
+        Ok(Some(Self {
+            kind,
+            schema_name,
+            input_socket_name,
+            source_schema_name,
+            source_output_socket_name,
+        }))
+    }
+}
+
+impl NodeChild for BindingSpec {
+    type NodeType = PkgNode;
+
+    fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        NodeWithChildren::new(
+            NodeKind::Leaf,
+            Self::NodeType::Binding(BindingNode {
+                kind: self.kind,
+                schema_name: self.schema_name.to_owned(),
+                input_socket_name: self.input_socket_name.to_owned(),
+                source_schema_name: self.source_schema_name.to_owned(),
+                source_output_socket_name: self.source_output_socket_name.to_owned(),
+            }),
+            vec![],
+        )
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::{BufRead, Write};
+
+use object_tree::{
+    read_key_value_line, write_key_value_line, GraphError, NameStr, NodeChild, NodeKind,
+    NodeWithChildren, ReadBytes, WriteBytes,
+};
+
+use super::PkgNode;
+use crate::{BlueprintComponentSpec, BlueprintParameterSpec, BlueprintSpec};
+
+const KEY_NAME_STR: &str = "name";
+const KEY_DESCRIPTION_STR: &str = "description";
+const KEY_COMPONENTS_STR: &str = "components";
+const KEY_PARAMETERS_STR: &str = "parameters";
+
+#[derive(Clone, Debug)]
+pub struct BlueprintNode {
+    pub name: String,
+    pub description: String,
+    pub components: Vec<BlueprintComponentSpec>,
+    pub parameters: Vec<BlueprintParameterSpec>,
+}
+
+impl NameStr for BlueprintNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl WriteBytes for BlueprintNode {
+    fn write_bytes<W: Write>(&self, writer: &mut W) -> Result<(), GraphError> {
+        write_key_value_line(writer, KEY_NAME_STR, self.name())?;
+        write_key_value_line(writer, KEY_DESCRIPTION_STR, &self.description)?;
+        write_key_value_line(
+            writer,
+            KEY_COMPONENTS_STR,
+            serde_json::to_string(&self.components).map_err(GraphError::parse)?,
+        )?;
+        write_key_value_line(
+            writer,
+            KEY_PARAMETERS_STR,
+            serde_json::to_string(&self.parameters).map_err(GraphError::parse)?,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl ReadBytes for BlueprintNode {
+    fn read_bytes<R: BufRead>(reader: &mut R) -> Result<Option<Self>, GraphError>
+    where
+        Self: std::marker::Sized,
+    {
+        let name = read_key_value_line(reader, KEY_NAME_STR)?;
+        let description = read_key_value_line(reader, KEY_DESCRIPTION_STR)?;
+        let components_str = read_key_value_line(reader, KEY_COMPONENTS_STR)?;
+        let components = serde_json::from_str(&components_str).map_err(GraphError::parse)?;
+        let parameters_str = read_key_value_line(reader, KEY_PARAMETERS_STR)?;
+        let parameters = serde_json::from_str(&parameters_str).map_err(GraphError::parse)?;
+
+        Ok(Some(Self {
+            name,
+            description,
+            components,
+            parameters,
+        }))
+    }
+}
+
+impl NodeChild for BlueprintSpec {
+    type NodeType = PkgNode;
+
+    fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        NodeWithChildren::new(
+            NodeKind::Leaf,
+            Self::NodeType::Blueprint(BlueprintNode {
+                name: self.name.to_owned(),
+                description: self.description.to_owned(),
+                components: self.components.to_owned(),
+                parameters: self.parameters.to_owned(),
+            }),
+            vec![],
+        )
+    }
+}
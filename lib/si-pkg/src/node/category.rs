@@ -6,10 +6,12 @@ use object_tree::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ChangeSetSpec, FuncSpec, SchemaSpec};
+use crate::{BindingSpec, BlueprintSpec, ChangeSetSpec, FuncSpec, SchemaSpec};
 
 use super::PkgNode;
 
+const CATEGORY_TYPE_BINDINGS: &str = "bindings";
+const CATEGORY_TYPE_BLUEPRINTS: &str = "blueprints";
 const CATEGORY_TYPE_CHANGE_SETS: &str = "change_sets";
 const CATEGORY_TYPE_SCHEMAS: &str = "schemas";
 const CATEGORY_TYPE_FUNCS: &str = "funcs";
@@ -20,6 +22,8 @@ const KEY_KIND_STR: &str = "kind";
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum PackageCategory {
+    Bindings(Vec<BindingSpec>),
+    Blueprints(Vec<BlueprintSpec>),
     ChangeSets(Vec<ChangeSetSpec>),
     Funcs(Vec<FuncSpec>),
     Schemas(Vec<SchemaSpec>),
@@ -28,6 +32,8 @@ pub enum PackageCategory {
 #[remain::sorted]
 #[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
 pub enum CategoryNode {
+    Bindings,
+    Blueprints,
     ChangeSets,
     Funcs,
     Schemas,
@@ -36,6 +42,8 @@ pub enum CategoryNode {
 impl CategoryNode {
     pub fn kind_str(&self) -> &'static str {
         match self {
+            Self::Bindings => CATEGORY_TYPE_BINDINGS,
+            Self::Blueprints => CATEGORY_TYPE_BLUEPRINTS,
             Self::ChangeSets => CATEGORY_TYPE_CHANGE_SETS,
             Self::Funcs => CATEGORY_TYPE_FUNCS,
             Self::Schemas => CATEGORY_TYPE_SCHEMAS,
@@ -46,6 +54,8 @@ impl CategoryNode {
 impl NameStr for CategoryNode {
     fn name(&self) -> &str {
         match self {
+            Self::Bindings => CATEGORY_TYPE_BINDINGS,
+            Self::Blueprints => CATEGORY_TYPE_BLUEPRINTS,
             Self::ChangeSets => CATEGORY_TYPE_CHANGE_SETS,
             Self::Schemas => CATEGORY_TYPE_SCHEMAS,
             Self::Funcs => CATEGORY_TYPE_FUNCS,
@@ -68,6 +78,8 @@ impl ReadBytes for CategoryNode {
         let kind_str = read_key_value_line(reader, KEY_KIND_STR)?;
 
         let node = match kind_str.as_str() {
+            CATEGORY_TYPE_BINDINGS => Self::Bindings,
+            CATEGORY_TYPE_BLUEPRINTS => Self::Blueprints,
             CATEGORY_TYPE_CHANGE_SETS => Self::ChangeSets,
             CATEGORY_TYPE_FUNCS => Self::Funcs,
             CATEGORY_TYPE_SCHEMAS => Self::Schemas,
@@ -88,6 +100,26 @@ impl NodeChild for PackageCategory {
 
     fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
         match self {
+            Self::Bindings(entries) => NodeWithChildren::new(
+                NodeKind::Tree,
+                Self::NodeType::Category(CategoryNode::Bindings),
+                entries
+                    .iter()
+                    .map(|binding| {
+                        Box::new(binding.clone()) as Box<dyn NodeChild<NodeType = Self::NodeType>>
+                    })
+                    .collect(),
+            ),
+            Self::Blueprints(entries) => NodeWithChildren::new(
+                NodeKind::Tree,
+                Self::NodeType::Category(CategoryNode::Blueprints),
+                entries
+                    .iter()
+                    .map(|blueprint| {
+                        Box::new(blueprint.clone()) as Box<dyn NodeChild<NodeType = Self::NodeType>>
+                    })
+                    .collect(),
+            ),
             Self::ChangeSets(entries) => NodeWithChildren::new(
                 NodeKind::Tree,
                 Self::NodeType::Category(CategoryNode::ChangeSets),
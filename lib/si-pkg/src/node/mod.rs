@@ -15,6 +15,8 @@ mod attr_func_input;
 mod attribute_value;
 mod attribute_value_child;
 mod auth_func;
+mod binding;
+mod blueprint;
 mod category;
 mod change_set;
 mod change_set_child;
@@ -42,6 +44,8 @@ pub(crate) use self::{
     attr_func_input::AttrFuncInputNode,
     attribute_value::AttributeValueNode,
     attribute_value_child::AttributeValueChildNode,
+    binding::BindingNode,
+    blueprint::BlueprintNode,
     category::CategoryNode,
     change_set::ChangeSetNode,
     change_set_child::{ChangeSetChild, ChangeSetChildNode},
@@ -69,6 +73,8 @@ const NODE_KIND_AUTH_FUNC: &str = "auth_func";
 const NODE_KIND_ATTRIBUTE_VALUE: &str = "attribute_value";
 const NODE_KIND_ATTRIBUTE_VALUE_CHILD: &str = "attribute_value_child";
 const NODE_KIND_ATTR_FUNC_INPUT: &str = "attr_func_input";
+const NODE_KIND_BINDING: &str = "binding";
+const NODE_KIND_BLUEPRINT: &str = "blueprint";
 const NODE_KIND_CATEGORY: &str = "category";
 const NODE_KIND_CHANGE_SET: &str = "change_set";
 const NODE_KIND_CHANGE_SET_CHILD: &str = "change_set_child";
@@ -142,6 +148,8 @@ pub enum PkgNode {
     AttributeValue(AttributeValueNode),
     AttributeValueChild(AttributeValueChildNode),
     AuthFunc(AuthFuncNode),
+    Binding(BindingNode),
+    Blueprint(BlueprintNode),
     Category(CategoryNode),
     ChangeSet(ChangeSetNode),
     ChangeSetChild(ChangeSetChildNode),
@@ -171,6 +179,8 @@ impl PkgNode {
     pub const ATTR_FUNC_INPUT_KIND_STR: &'static str = NODE_KIND_ATTR_FUNC_INPUT;
     pub const ATTRIBUTE_VALUE_KIND_STR: &'static str = NODE_KIND_ATTRIBUTE_VALUE;
     pub const ATTRIBUTE_VALUE_CHILD_KIND_STR: &'static str = NODE_KIND_ATTRIBUTE_VALUE_CHILD;
+    pub const BINDING_KIND_STR: &'static str = NODE_KIND_BINDING;
+    pub const BLUEPRINT_KIND_STR: &'static str = NODE_KIND_BLUEPRINT;
     pub const CATEGORY_KIND_STR: &'static str = NODE_KIND_CATEGORY;
     pub const CHANGE_SET_KIND_STR: &'static str = NODE_KIND_CHANGE_SET;
     pub const CHANGE_SET_CHILD_KIND_STR: &'static str = NODE_KIND_CHANGE_SET_CHILD;
@@ -199,6 +209,8 @@ impl PkgNode {
             Self::AttrFuncInput(_) => NODE_KIND_ATTR_FUNC_INPUT,
             Self::AttributeValue(_) => NODE_KIND_ATTRIBUTE_VALUE,
             Self::AttributeValueChild(_) => NODE_KIND_ATTRIBUTE_VALUE_CHILD,
+            Self::Binding(_) => NODE_KIND_BINDING,
+            Self::Blueprint(_) => NODE_KIND_BLUEPRINT,
             Self::Category(_) => NODE_KIND_CATEGORY,
             Self::ChangeSet(_) => NODE_KIND_CHANGE_SET,
             Self::ChangeSetChild(_) => NODE_KIND_CHANGE_SET_CHILD,
@@ -232,6 +244,8 @@ impl NameStr for PkgNode {
             Self::AttrFuncInput(node) => node.name(),
             Self::AttributeValue(_) => NODE_KIND_ATTRIBUTE_VALUE,
             Self::AttributeValueChild(node) => node.name(),
+            Self::Binding(_) => NODE_KIND_BINDING,
+            Self::Blueprint(node) => node.name(),
             Self::Category(node) => node.name(),
             Self::ChangeSet(node) => node.name(),
             Self::ChangeSetChild(node) => node.name(),
@@ -268,6 +282,8 @@ impl WriteBytes for PkgNode {
             Self::AttrFuncInput(node) => node.write_bytes(writer)?,
             Self::AttributeValue(node) => node.write_bytes(writer)?,
             Self::AttributeValueChild(node) => node.write_bytes(writer)?,
+            Self::Binding(node) => node.write_bytes(writer)?,
+            Self::Blueprint(node) => node.write_bytes(writer)?,
             Self::Category(node) => node.write_bytes(writer)?,
             Self::ChangeSet(node) => node.write_bytes(writer)?,
             Self::ChangeSetChild(node) => node.write_bytes(writer)?,
@@ -314,6 +330,8 @@ impl ReadBytes for PkgNode {
             NODE_KIND_ATTRIBUTE_VALUE_CHILD => {
                 AttributeValueChildNode::read_bytes(reader)?.map(Self::AttributeValueChild)
             }
+            NODE_KIND_BINDING => BindingNode::read_bytes(reader)?.map(Self::Binding),
+            NODE_KIND_BLUEPRINT => BlueprintNode::read_bytes(reader)?.map(Self::Blueprint),
             NODE_KIND_CATEGORY => CategoryNode::read_bytes(reader)?.map(Self::Category),
             NODE_KIND_CHANGE_SET => ChangeSetNode::read_bytes(reader)?.map(Self::ChangeSet),
             NODE_KIND_CHANGE_SET_CHILD => {
@@ -117,12 +117,27 @@ impl NodeChild for PkgSpec {
                 workspace_name: self.workspace_name.to_owned(),
             }),
             match self.kind {
-                SiPkgKind::Module => vec![
-                    Box::new(PackageCategory::Schemas(self.schemas.clone()))
-                        as Box<dyn NodeChild<NodeType = Self::NodeType>>,
-                    Box::new(PackageCategory::Funcs(self.funcs.clone()))
-                        as Box<dyn NodeChild<NodeType = Self::NodeType>>,
-                ],
+                SiPkgKind::Delta | SiPkgKind::Module => {
+                    let mut children = vec![
+                        Box::new(PackageCategory::Schemas(self.schemas.clone()))
+                            as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+                        Box::new(PackageCategory::Funcs(self.funcs.clone()))
+                            as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+                    ];
+                    // Optional categories are only written when present so that packages
+                    // without them keep the same hash they always had
+                    if !self.bindings.is_empty() {
+                        children.push(Box::new(PackageCategory::Bindings(self.bindings.clone()))
+                            as Box<dyn NodeChild<NodeType = Self::NodeType>>);
+                    }
+                    if !self.blueprints.is_empty() {
+                        children.push(
+                            Box::new(PackageCategory::Blueprints(self.blueprints.clone()))
+                                as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+                        );
+                    }
+                    children
+                }
                 SiPkgKind::WorkspaceBackup => {
                     vec![
                         Box::new(PackageCategory::ChangeSets(self.change_sets.clone()))
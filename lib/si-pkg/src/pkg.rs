@@ -15,6 +15,8 @@ mod action_func;
 mod attr_func_input;
 mod attribute_value;
 mod auth_func;
+mod binding;
+mod blueprint;
 mod change_set;
 mod component;
 mod edge;
@@ -31,14 +33,15 @@ mod socket;
 mod variant;
 
 pub use {
-    action_func::*, attr_func_input::*, attribute_value::*, auth_func::*, change_set::*,
-    component::*, edge::*, func::*, leaf_function::*, management_func::*, map_key_func::*,
-    position::*, prop::*, root_prop_func::*, schema::*, si_prop_func::*, socket::*, variant::*,
+    action_func::*, attr_func_input::*, attribute_value::*, auth_func::*, binding::*, blueprint::*,
+    change_set::*, component::*, edge::*, func::*, leaf_function::*, management_func::*,
+    map_key_func::*, position::*, prop::*, root_prop_func::*, schema::*, si_prop_func::*,
+    socket::*, variant::*,
 };
 
 use crate::{
     node::{CategoryNode, PkgNode},
-    spec::{FuncSpec, PkgSpec, SchemaVariantSpecPropRoot, SpecError},
+    spec::{BindingSpec, BlueprintSpec, FuncSpec, PkgSpec, SchemaVariantSpecPropRoot, SpecError},
 };
 
 #[remain::sorted]
@@ -193,6 +196,34 @@ impl SiPkg {
         Ok(change_sets)
     }
 
+    pub fn bindings(&self) -> PkgResult<Vec<SiPkgBinding>> {
+        let (graph, root_idx) = self.as_petgraph();
+
+        let node_idxs = category_node_idxs(CategoryNode::Bindings, graph, root_idx)?;
+
+        let mut bindings = Vec::with_capacity(node_idxs.len());
+
+        for node_idx in node_idxs {
+            bindings.push(SiPkgBinding::from_graph(graph, node_idx)?);
+        }
+
+        Ok(bindings)
+    }
+
+    pub fn blueprints(&self) -> PkgResult<Vec<SiPkgBlueprint>> {
+        let (graph, root_idx) = self.as_petgraph();
+
+        let node_idxs = category_node_idxs(CategoryNode::Blueprints, graph, root_idx)?;
+
+        let mut blueprints = Vec::with_capacity(node_idxs.len());
+
+        for node_idx in node_idxs {
+            blueprints.push(SiPkgBlueprint::from_graph(graph, node_idx)?);
+        }
+
+        Ok(blueprints)
+    }
+
     pub fn schema_by_name(&self, name: impl AsRef<str>) -> PkgResult<SiPkgSchema> {
         let (graph, root_idx) = self.as_petgraph();
 
@@ -242,6 +273,14 @@ impl SiPkg {
             builder.schema(schema.to_spec().await?);
         }
 
+        for binding in self.bindings()? {
+            builder.binding(BindingSpec::try_from(binding)?);
+        }
+
+        for blueprint in self.blueprints()? {
+            builder.blueprint(BlueprintSpec::try_from(blueprint)?);
+        }
+
         if let SiPkgKind::WorkspaceBackup = metadata.kind() {
             if let Some(default_change_set) = metadata.default_change_set() {
                 builder.default_change_set(default_change_set);
@@ -350,6 +389,8 @@ impl fmt::Debug for Source<'_> {
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "camelCase")]
 pub enum SiPkgKind {
+    /// Only what was changed on top of another module, for review. Deltas cannot be installed.
+    Delta,
     Module,
     WorkspaceBackup,
 }
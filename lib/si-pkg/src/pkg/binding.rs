@@ -0,0 +1,89 @@
+use object_tree::{Hash, HashedNode};
+use petgraph::prelude::*;
+
+use super::{PkgResult, SiPkgError, Source};
+use crate::node::PkgNode;
+use crate::{BindingSpec, BindingSpecKind};
+
+#[derive(Clone, Debug)]
+pub struct SiPkgBinding<'a> {
+    kind: BindingSpecKind,
+    schema_name: String,
+    input_socket_name: String,
+    source_schema_name: String,
+    source_output_socket_name: String,
+
+    hash: Hash,
+    source: Source<'a>,
+}
+
+impl<'a> SiPkgBinding<'a> {
+    pub fn from_graph(
+        graph: &'a Graph<HashedNode<PkgNode>, ()>,
+        node_idx: NodeIndex,
+    ) -> PkgResult<Self> {
+        let hashed_node = &graph[node_idx];
+        let node = match hashed_node.inner() {
+            PkgNode::Binding(node) => node.clone(),
+            unexpected => {
+                return Err(SiPkgError::UnexpectedPkgNodeType(
+                    PkgNode::BINDING_KIND_STR,
+                    unexpected.node_kind_str(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            kind: node.kind,
+            schema_name: node.schema_name,
+            input_socket_name: node.input_socket_name,
+            source_schema_name: node.source_schema_name,
+            source_output_socket_name: node.source_output_socket_name,
+
+            hash: hashed_node.hash(),
+            source: Source::new(graph, node_idx),
+        })
+    }
+
+    pub fn kind(&self) -> BindingSpecKind {
+        self.kind
+    }
+
+    pub fn schema_name(&self) -> &str {
+        self.schema_name.as_str()
+    }
+
+    pub fn input_socket_name(&self) -> &str {
+        self.input_socket_name.as_str()
+    }
+
+    pub fn source_schema_name(&self) -> &str {
+        self.source_schema_name.as_str()
+    }
+
+    pub fn source_output_socket_name(&self) -> &str {
+        self.source_output_socket_name.as_str()
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn source(&self) -> &Source<'a> {
+        &self.source
+    }
+}
+
+impl<'a> TryFrom<SiPkgBinding<'a>> for BindingSpec {
+    type Error = SiPkgError;
+
+    fn try_from(value: SiPkgBinding<'a>) -> Result<Self, Self::Error> {
+        Ok(BindingSpec::builder()
+            .kind(value.kind())
+            .schema_name(value.schema_name())
+            .input_socket_name(value.input_socket_name())
+            .source_schema_name(value.source_schema_name())
+            .source_output_socket_name(value.source_output_socket_name())
+            .build()?)
+    }
+}
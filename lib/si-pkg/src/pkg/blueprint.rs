@@ -0,0 +1,82 @@
+use object_tree::{Hash, HashedNode};
+use petgraph::prelude::*;
+
+use super::{PkgResult, SiPkgError, Source};
+use crate::node::PkgNode;
+use crate::{BlueprintComponentSpec, BlueprintParameterSpec, BlueprintSpec};
+
+#[derive(Clone, Debug)]
+pub struct SiPkgBlueprint<'a> {
+    name: String,
+    description: String,
+    components: Vec<BlueprintComponentSpec>,
+    parameters: Vec<BlueprintParameterSpec>,
+
+    hash: Hash,
+    source: Source<'a>,
+}
+
+impl<'a> SiPkgBlueprint<'a> {
+    pub fn from_graph(
+        graph: &'a Graph<HashedNode<PkgNode>, ()>,
+        node_idx: NodeIndex,
+    ) -> PkgResult<Self> {
+        let hashed_node = &graph[node_idx];
+        let node = match hashed_node.inner() {
+            PkgNode::Blueprint(node) => node.clone(),
+            unexpected => {
+                return Err(SiPkgError::UnexpectedPkgNodeType(
+                    PkgNode::BLUEPRINT_KIND_STR,
+                    unexpected.node_kind_str(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            name: node.name,
+            description: node.description,
+            components: node.components,
+            parameters: node.parameters,
+
+            hash: hashed_node.hash(),
+            source: Source::new(graph, node_idx),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    pub fn components(&self) -> &[BlueprintComponentSpec] {
+        &self.components
+    }
+
+    pub fn parameters(&self) -> &[BlueprintParameterSpec] {
+        &self.parameters
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn source(&self) -> &Source<'a> {
+        &self.source
+    }
+}
+
+impl<'a> TryFrom<SiPkgBlueprint<'a>> for BlueprintSpec {
+    type Error = SiPkgError;
+
+    fn try_from(value: SiPkgBlueprint<'a>) -> Result<Self, Self::Error> {
+        Ok(BlueprintSpec::builder()
+            .name(value.name)
+            .description(value.description)
+            .components(value.components)
+            .parameters(value.parameters)
+            .build()?)
+    }
+}
@@ -7,6 +7,8 @@ mod action_func;
 mod attr_func_input;
 mod attribute_value;
 mod authentication_func;
+mod binding;
+mod blueprint;
 mod change_set;
 mod component;
 mod edge;
@@ -23,9 +25,10 @@ mod socket;
 mod variant;
 
 pub use {
-    action_func::*, attr_func_input::*, attribute_value::*, authentication_func::*, change_set::*,
-    component::*, edge::*, func::*, leaf_function::*, management_func::*, map_key_func::*,
-    position::*, prop::*, root_prop_func::*, schema::*, si_prop_func::*, socket::*, variant::*,
+    action_func::*, attr_func_input::*, attribute_value::*, authentication_func::*, binding::*,
+    blueprint::*, change_set::*, component::*, edge::*, func::*, leaf_function::*,
+    management_func::*, map_key_func::*, position::*, prop::*, root_prop_func::*, schema::*,
+    si_prop_func::*, socket::*, variant::*,
 };
 
 use super::SiPkgKind;
@@ -66,6 +69,15 @@ pub struct PkgSpec {
     #[builder(setter(each(name = "change_set", into)), default)]
     #[serde(default)]
     pub change_sets: Vec<ChangeSetSpec>,
+
+    /// Wiring to schemas outside of the package, restored on import when those schemas exist.
+    #[builder(setter(each(name = "binding", into)), default)]
+    #[serde(default)]
+    pub bindings: Vec<BindingSpec>,
+
+    #[builder(setter(each(name = "blueprint", into)), default)]
+    #[serde(default)]
+    pub blueprints: Vec<BlueprintSpec>,
 }
 
 impl PkgSpec {
@@ -0,0 +1,51 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+
+use super::SpecError;
+
+#[remain::sorted]
+#[derive(
+    Deserialize,
+    Serialize,
+    AsRefStr,
+    Display,
+    EnumIter,
+    EnumString,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+)]
+pub enum BindingSpecKind {
+    /// The source is the output socket of a secret defining schema variant.
+    SecretDefinition,
+    /// The source is any other output socket whose connection annotations fit the input socket.
+    SocketAnnotation,
+}
+
+/// Records that an input socket of a schema in the package was fed by an output socket of a
+/// schema outside of it in the workspace the package was exported from, so that the wiring can
+/// be restored when the package is imported somewhere that schema also exists.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct BindingSpec {
+    #[builder(setter(into))]
+    pub kind: BindingSpecKind,
+    #[builder(setter(into))]
+    pub schema_name: String,
+    #[builder(setter(into))]
+    pub input_socket_name: String,
+    #[builder(setter(into))]
+    pub source_schema_name: String,
+    #[builder(setter(into))]
+    pub source_output_socket_name: String,
+}
+
+impl BindingSpec {
+    pub fn builder() -> BindingSpecBuilder {
+        BindingSpecBuilder::default()
+    }
+}
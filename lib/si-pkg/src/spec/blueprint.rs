@@ -0,0 +1,109 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use super::{PositionSpec, SpecError};
+
+/// A connection from an output socket of a blueprint component to an input socket of another
+/// component in the same blueprint.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct BlueprintConnectionSpec {
+    #[builder(setter(into))]
+    pub from_socket_name: String,
+    #[builder(setter(into))]
+    pub to_placeholder: String,
+    #[builder(setter(into))]
+    pub to_socket_name: String,
+}
+
+impl BlueprintConnectionSpec {
+    pub fn builder() -> BlueprintConnectionSpecBuilder {
+        BlueprintConnectionSpecBuilder::default()
+    }
+}
+
+/// A component to create when the blueprint is instantiated. Placeholders are unique within the
+/// blueprint and are used as the name of the created component.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct BlueprintComponentSpec {
+    #[builder(setter(into))]
+    pub placeholder: String,
+    #[builder(setter(into))]
+    pub schema_name: String,
+    /// The values of the component that were not set by functions, in the shape of the
+    /// component's prop tree starting below the root prop.
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub properties: serde_json::Value,
+    #[builder(setter(into))]
+    pub position: PositionSpec,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[builder(setter(each(name = "connection", into)), default)]
+    #[serde(default)]
+    pub connections: Vec<BlueprintConnectionSpec>,
+}
+
+impl BlueprintComponentSpec {
+    pub fn builder() -> BlueprintComponentSpecBuilder {
+        BlueprintComponentSpecBuilder::default()
+    }
+}
+
+/// A value that is supplied when the blueprint is instantiated, rather than fixed in it.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct BlueprintParameterSpec {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The placeholder of the component the parameter sets a value on.
+    #[builder(setter(into))]
+    pub placeholder: String,
+    /// The full path of the prop the parameter sets, such as `["root", "domain", "region"]`.
+    #[builder(setter(into))]
+    pub prop_path: Vec<String>,
+    /// Used when no value is supplied for the parameter. Parameters without a default are
+    /// required.
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub default_value: Option<serde_json::Value>,
+}
+
+impl BlueprintParameterSpec {
+    pub fn builder() -> BlueprintParameterSpecBuilder {
+        BlueprintParameterSpecBuilder::default()
+    }
+}
+
+/// A reusable set of components, the connections between them and their values, captured from a
+/// change set so that it can be stamped into other change sets.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct BlueprintSpec {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub description: String,
+    #[builder(setter(each(name = "component", into)), default)]
+    #[serde(default)]
+    pub components: Vec<BlueprintComponentSpec>,
+    #[builder(setter(each(name = "parameter", into)), default)]
+    #[serde(default)]
+    pub parameters: Vec<BlueprintParameterSpec>,
+}
+
+impl BlueprintSpec {
+    pub fn builder() -> BlueprintSpecBuilder {
+        BlueprintSpecBuilder::default()
+    }
+}
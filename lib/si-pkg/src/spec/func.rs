@@ -81,6 +81,7 @@ pub enum FuncSpecBackendKind {
     String,
     Unset,
     Validation,
+    Wasm,
 }
 
 #[remain::sorted]
@@ -17,6 +17,7 @@ use super::{AttrFuncInputSpec, SpecError};
     EnumIter,
     EnumString,
     Copy,
+    Hash,
 )]
 #[serde(rename_all = "camelCase")]
 pub enum SocketSpecKind {
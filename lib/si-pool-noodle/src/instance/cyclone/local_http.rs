@@ -11,7 +11,7 @@ use cyclone_client::{
 };
 use cyclone_core::{
     process::{self, ShutdownError},
-    CanonicalCommand, CycloneRequest, CycloneRequestable,
+    CanonicalCommand, CycloneRequest, CycloneRequestable, RuntimeVersion,
 };
 use derive_builder::Builder;
 use futures::StreamExt;
@@ -126,6 +126,14 @@ impl CycloneClient<TcpStream> for LocalHttpInstance {
         self.client.readiness().await
     }
 
+    async fn lang_server_version(&mut self) -> result::Result<Option<RuntimeVersion>, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        self.client.lang_server_version().await
+    }
+
     async fn execute_ping(&mut self) -> result::Result<PingExecution<TcpStream>, ClientError> {
         self.ensure_healthy_client()
             .await
@@ -226,6 +234,10 @@ pub struct LocalHttpInstanceSpec {
     /// Enables the `action` execution endpoint for a spawned Cyclone server.
     #[builder(private, setter(name = "_action"), default = "false")]
     action: bool,
+
+    /// Enables the experimental `wasm` resolver execution endpoint for a spawned Cyclone server.
+    #[builder(private, setter(name = "_wasm"), default = "false")]
+    wasm: bool,
 }
 
 #[async_trait]
@@ -317,6 +329,9 @@ impl LocalHttpInstanceSpec {
         if self.action {
             cmd.arg("--enable-action-run");
         }
+        if self.wasm {
+            cmd.arg("--enable-wasm");
+        }
 
         cmd
     }
@@ -352,6 +367,11 @@ impl LocalHttpInstanceSpecBuilder {
         self._action(true)
     }
 
+    /// Enables the experimental `wasm` resolver execution endpoint for a spawned Cyclone server.
+    pub fn wasm(&mut self) -> &mut Self {
+        self._wasm(true)
+    }
+
     /// Enables all available endpoints for a spawned Cyclone server
     pub fn all_endpoints(&mut self) -> &mut Self {
         self.action().resolver()
@@ -22,7 +22,7 @@ use cyclone_client::{
 };
 use cyclone_core::{
     process::{self, ShutdownError},
-    CanonicalCommand, CycloneRequest, CycloneRequestable,
+    CanonicalCommand, CycloneRequest, CycloneRequestable, RuntimeVersion,
 };
 use derive_builder::Builder;
 use futures::StreamExt;
@@ -171,6 +171,13 @@ impl CycloneClient<UnixStream> for LocalUdsInstance {
         self.client.readiness().await
     }
 
+    async fn lang_server_version(&mut self) -> result::Result<Option<RuntimeVersion>, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+        self.client.lang_server_version().await
+    }
+
     async fn execute_ping(&mut self) -> result::Result<PingExecution<UnixStream>, ClientError> {
         self.ensure_healthy_client()
             .await
@@ -275,6 +282,10 @@ pub struct LocalUdsInstanceSpec {
     #[builder(private, setter(name = "_action"), default = "false")]
     action: bool,
 
+    /// Enables the experimental `wasm` resolver execution endpoint for a spawned Cyclone server.
+    #[builder(private, setter(name = "_wasm"), default = "false")]
+    wasm: bool,
+
     /// Size of the pool to configure for the spec.
     #[builder(setter(into), default = "500")]
     pub pool_size: u32,
@@ -282,6 +293,11 @@ pub struct LocalUdsInstanceSpec {
     /// Sets the timeout for connecting to firecracker
     #[builder(setter(into), default = "10")]
     connect_timeout: u64,
+
+    /// Number of connections to keep ready for each spawned Cyclone server. Zero disables
+    /// connection pooling.
+    #[builder(setter(into), default)]
+    connection_pool_size: usize,
 }
 
 #[async_trait]
@@ -338,9 +354,18 @@ impl Spec for LocalUdsInstanceSpec {
             );
         }
 
+        // A server which only serves a limited number of requests never needs more connections
+        // than it has requests left
+        let pool_max_idle = match self.limit_requests {
+            Some(limit_requests) => self
+                .connection_pool_size
+                .min(limit_requests.try_into().unwrap_or(usize::MAX)),
+            None => self.connection_pool_size,
+        };
         let config = ClientConfig {
             connect_timeout: Duration::from_millis(self.connect_timeout),
             firecracker_connect,
+            pool_max_idle,
             ..Default::default()
         };
         let mut client = Client::uds(runtime.socket(), Arc::new(config))?;
@@ -378,6 +403,11 @@ impl Spec for LocalUdsInstanceSpec {
         // Spawn a task to keep the watch session open until we shut it down
         tokio::spawn(watch_task(watch_progress, watch_shutdown_rx));
 
+        // Connect ahead of the first execution, now that the server is known to be up
+        if let Err(err) = client.fill_pool().await {
+            debug!(si.error.message = ?err, "failed to fill cyclone connection pool");
+        }
+
         Ok(Self::Instance {
             _temp_path: temp_path,
             client,
@@ -418,6 +448,11 @@ impl LocalUdsInstanceSpecBuilder {
         self._action(true)
     }
 
+    /// Enables the experimental `wasm` resolver execution endpoint for a spawned Cyclone server.
+    pub fn wasm(&mut self) -> &mut Self {
+        self._wasm(true)
+    }
+
     /// Enables all available endpoints for a spawned Cyclone server
     pub fn all_endpoints(&mut self) -> &mut Self {
         self.action().resolver()
@@ -550,6 +585,9 @@ impl LocalProcessRuntime {
         if spec.action {
             cmd.arg("--enable-action-run");
         }
+        if spec.wasm {
+            cmd.arg("--enable-wasm");
+        }
 
         Ok(Box::new(LocalProcessRuntime {
             cmd,
@@ -623,6 +661,9 @@ impl LocalDockerRuntime {
         if spec.action {
             cmd.push(String::from("--enable-action-run"));
         }
+        if spec.wasm {
+            cmd.push(String::from("--enable-wasm"));
+        }
 
         let docker = Docker::connect_with_local_defaults()?;
 
@@ -25,7 +25,7 @@ pub use cyclone_core::{
     CycloneRequestable, FunctionResult, FunctionResultFailure, FunctionResultFailureError,
     FunctionResultFailureErrorKind, KillExecutionRequest, ManagementRequest,
     ManagementResultSuccess, OutputStream, ProgressMessage, ResolverFunctionRequest,
-    ResolverFunctionResultSuccess, ResourceStatus, SchemaVariantDefinitionRequest,
+    ResolverFunctionResultSuccess, ResourceStatus, RuntimeVersion, SchemaVariantDefinitionRequest,
     SchemaVariantDefinitionResultSuccess, SensitiveStrings, ValidationRequest,
     ValidationResultSuccess,
 };
@@ -5,12 +5,17 @@ use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
 pub use config_file::ValueKind;
+pub use validation::{ConfigIssue, ValidationReport};
+
+mod validation;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum SettingsError {
     #[error(transparent)]
     ConfigFile(#[from] config_file::ConfigFileError),
+    #[error("{0}")]
+    Validation(ValidationReport),
 }
 
 pub type Result<T> = std::result::Result<T, SettingsError>;
@@ -30,6 +35,14 @@ pub trait StandardConfigFile:
 {
     type Error: From<SettingsError>;
 
+    /// Checks the loaded settings, recording every problem found in `report`.
+    ///
+    /// Called by [`layered_load`](Self::layered_load) once all layers are merged.
+    fn validate(&self, _report: &mut ValidationReport) {}
+
+    /// Loads the settings from, in increasing precedence, the defaults, a config file, `SI_`
+    /// prefixed environment variables and finally any values set by `set_func` (typically from
+    /// CLI arguments), then validates the result.
     fn layered_load<F>(
         app_name: impl AsRef<str>,
         set_func: F,
@@ -38,14 +51,21 @@ pub trait StandardConfigFile:
         F: FnOnce(&mut ConfigMap),
     {
         let app_name = app_name.as_ref();
-        config_file::layered_load(
+        let config: Self = config_file::layered_load(
             app_name,
             "toml",
             &Some(format!("SI_{}_CONFIG", app_name.to_uppercase())),
             &Some(format!("SI_{}", app_name.to_uppercase())),
             set_func,
         )
-        .map_err(SettingsError::ConfigFile)
-        .map_err(Into::into)
+        .map_err(SettingsError::ConfigFile)?;
+
+        let mut report = ValidationReport::new();
+        config.validate(&mut report);
+        if !report.is_empty() {
+            return Err(SettingsError::Validation(report).into());
+        }
+
+        Ok(config)
     }
 }
@@ -0,0 +1,98 @@
+use std::fmt;
+
+/// A single problem found while validating a loaded configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfigIssue {
+    /// The dotted key of the offending setting, i.e. `nats.url`.
+    pub key: String,
+    /// What is wrong with the setting.
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Every problem found while validating a loaded configuration.
+///
+/// Validation keeps going after the first problem so that an operator can fix a configuration in
+/// one pass rather than one restart per mistake.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ValidationReport {
+    issues: Vec<ConfigIssue>,
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a problem with the setting at `key`.
+    pub fn push(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ConfigIssue {
+            key: key.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Records a problem with the setting at `key` unless `condition` holds.
+    pub fn check(&mut self, condition: bool, key: impl Into<String>, message: impl Into<String>) {
+        if !condition {
+            self.push(key, message);
+        }
+    }
+
+    /// Records a problem if the string setting at `key` is empty or only whitespace.
+    pub fn require_non_empty(&mut self, key: impl Into<String>, value: &str) {
+        self.check(!value.trim().is_empty(), key, "must not be empty");
+    }
+
+    pub fn issues(&self) -> &[ConfigIssue] {
+        &self.issues
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.issues.len() {
+            1 => write!(f, "1 configuration problem found")?,
+            count => write!(f, "{count} configuration problems found")?,
+        }
+        for issue in &self.issues {
+            write!(f, "\n  - {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_every_issue() {
+        let mut report = ValidationReport::new();
+        report.require_non_empty("instance_id", " ");
+        report.check(true, "concurrency_limit", "must be greater than zero");
+        report.check(false, "nats.url", "must be a url");
+
+        assert_eq!(
+            vec!["instance_id", "nats.url"],
+            report
+                .issues()
+                .iter()
+                .map(|issue| issue.key.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "2 configuration problems found\n  - instance_id: must not be empty\n  - nats.url: must be a url",
+            report.to_string()
+        );
+    }
+}
@@ -10,9 +10,11 @@ pub mod canonical_file;
 pub mod option;
 pub mod result;
 pub mod string;
+pub mod task_group;
 pub mod time;
 
 pub use canonical_file::{CanonicalFile, CanonicalFileError};
 pub use option::OptionExt;
 pub use result::ResultExt;
 pub use string::SensitiveString;
+pub use task_group::{BoundedTaskGroup, TaskGroupError, TaskGroupMetrics};
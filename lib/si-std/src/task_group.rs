@@ -0,0 +1,279 @@
+//! A bounded group of spawned tasks.
+//!
+//! [`BoundedTaskGroup`] wraps a [`JoinSet`] with the bookkeeping that otherwise gets hand-rolled
+//! around one: a concurrency limit, an optional per-task timeout, cancellation that propagates
+//! from a parent [`CancellationToken`], and counters describing how the tasks finished.
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use thiserror::Error;
+use tokio::{
+    sync::Semaphore,
+    task::{JoinError, JoinSet},
+};
+use tokio_util::sync::CancellationToken;
+
+/// An error returned for a task of a [`BoundedTaskGroup`] which did not run to completion.
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum TaskGroupError {
+    /// The group was cancelled before the task finished.
+    #[error("task cancelled")]
+    Cancelled,
+    /// The task panicked or was aborted.
+    #[error("task join error: {0}")]
+    Join(#[from] JoinError),
+    /// The task ran longer than the group's task timeout.
+    #[error("task timed out after {0:?}")]
+    TimedOut(Duration),
+}
+
+/// A point-in-time snapshot of the counters of a [`BoundedTaskGroup`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TaskGroupMetrics {
+    /// Number of tasks spawned into the group.
+    pub spawned: u64,
+    /// Number of tasks currently holding a concurrency permit.
+    pub running: u64,
+    /// Number of tasks which ran to completion.
+    pub completed: u64,
+    /// Number of tasks which were cancelled.
+    pub cancelled: u64,
+    /// Number of tasks which exceeded the task timeout.
+    pub timed_out: u64,
+    /// Number of tasks which panicked or were aborted.
+    pub failed: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    spawned: AtomicU64,
+    running: AtomicU64,
+    completed: AtomicU64,
+    cancelled: AtomicU64,
+    timed_out: AtomicU64,
+    failed: AtomicU64,
+}
+
+/// A group of spawned tasks of which at most `limit` run concurrently.
+///
+/// Tasks are spawned immediately but wait for a permit before their future is polled, so
+/// spawning never blocks the caller. Dropping the group aborts all of its tasks.
+#[derive(Debug)]
+pub struct BoundedTaskGroup<T> {
+    join_set: JoinSet<Result<T, TaskGroupError>>,
+    semaphore: Arc<Semaphore>,
+    task_timeout: Option<Duration>,
+    token: CancellationToken,
+    counters: Arc<Counters>,
+}
+
+impl<T> BoundedTaskGroup<T>
+where
+    T: Send + 'static,
+{
+    /// Creates a new group running at most `limit` tasks at once.
+    ///
+    /// A `limit` of zero is treated as one.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            join_set: JoinSet::new(),
+            semaphore: Arc::new(Semaphore::new(limit.max(1))),
+            task_timeout: None,
+            token: CancellationToken::new(),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Sets the maximum time a task may run once it holds a permit.
+    pub fn with_task_timeout(mut self, task_timeout: Duration) -> Self {
+        self.task_timeout = Some(task_timeout);
+        self
+    }
+
+    /// Cancels the group's tasks whenever the given `token` is cancelled.
+    pub fn with_parent_token(mut self, token: &CancellationToken) -> Self {
+        self.token = token.child_token();
+        self
+    }
+
+    /// Spawns a task into the group.
+    pub fn spawn<F>(&mut self, task: F)
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let semaphore = self.semaphore.clone();
+        let task_timeout = self.task_timeout;
+        let token = self.token.clone();
+        let counters = self.counters.clone();
+
+        counters.spawned.fetch_add(1, Ordering::Relaxed);
+
+        self.join_set.spawn(async move {
+            let result = tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(TaskGroupError::Cancelled),
+                result = run_with_permit(semaphore, task_timeout, &counters, task) => result,
+            };
+
+            match &result {
+                Ok(_) => counters.completed.fetch_add(1, Ordering::Relaxed),
+                Err(TaskGroupError::Cancelled) => {
+                    counters.cancelled.fetch_add(1, Ordering::Relaxed)
+                }
+                Err(TaskGroupError::TimedOut(_)) => {
+                    counters.timed_out.fetch_add(1, Ordering::Relaxed)
+                }
+                Err(TaskGroupError::Join(_)) => counters.failed.fetch_add(1, Ordering::Relaxed),
+            };
+
+            result
+        });
+    }
+
+    /// Waits for the next task to finish, returning `None` when the group is empty.
+    pub async fn join_next(&mut self) -> Option<Result<T, TaskGroupError>> {
+        self.join_set.join_next().await.map(|joined| {
+            joined.unwrap_or_else(|err| {
+                self.counters.failed.fetch_add(1, Ordering::Relaxed);
+                Err(err.into())
+            })
+        })
+    }
+
+    /// Waits for all tasks to finish, returning their results in completion order.
+    pub async fn join_all(mut self) -> Vec<Result<T, TaskGroupError>> {
+        let mut results = Vec::with_capacity(self.join_set.len());
+        while let Some(result) = self.join_next().await {
+            results.push(result);
+        }
+        results
+    }
+
+    /// Cancels all tasks in the group.
+    ///
+    /// Tasks which have not yet finished resolve to [`TaskGroupError::Cancelled`].
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Returns the number of tasks in the group which have not yet been joined.
+    pub fn len(&self) -> usize {
+        self.join_set.len()
+    }
+
+    /// Returns `true` if the group holds no tasks.
+    pub fn is_empty(&self) -> bool {
+        self.join_set.is_empty()
+    }
+
+    /// Returns a snapshot of the group's counters.
+    pub fn metrics(&self) -> TaskGroupMetrics {
+        TaskGroupMetrics {
+            spawned: self.counters.spawned.load(Ordering::Relaxed),
+            running: self.counters.running.load(Ordering::Relaxed),
+            completed: self.counters.completed.load(Ordering::Relaxed),
+            cancelled: self.counters.cancelled.load(Ordering::Relaxed),
+            timed_out: self.counters.timed_out.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+async fn run_with_permit<F, T>(
+    semaphore: Arc<Semaphore>,
+    task_timeout: Option<Duration>,
+    counters: &Counters,
+    task: F,
+) -> Result<T, TaskGroupError>
+where
+    F: Future<Output = T>,
+{
+    // The semaphore is never closed, so failing to acquire a permit can only mean the group is
+    // going away
+    let _permit = semaphore
+        .acquire_owned()
+        .await
+        .map_err(|_| TaskGroupError::Cancelled)?;
+
+    counters.running.fetch_add(1, Ordering::Relaxed);
+    let _running = RunningGuard(counters);
+
+    match task_timeout {
+        Some(duration) => tokio::time::timeout(duration, task)
+            .await
+            .map_err(|_| TaskGroupError::TimedOut(duration)),
+        None => Ok(task.await),
+    }
+}
+
+struct RunningGuard<'a>(&'a Counters);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.running.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn limits_concurrency() {
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let max_in_flight = Arc::new(AtomicU64::new(0));
+        let mut group = BoundedTaskGroup::new(2);
+
+        for _ in 0..8 {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            group.spawn(async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+
+        let results = group.join_all().await;
+
+        assert_eq!(8, results.len());
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(2, max_in_flight.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn times_out_and_cancels() {
+        let parent = CancellationToken::new();
+        let mut group = BoundedTaskGroup::new(4)
+            .with_task_timeout(Duration::from_millis(10))
+            .with_parent_token(&parent);
+
+        group.spawn(async { tokio::time::sleep(Duration::from_secs(60)).await });
+        assert!(matches!(
+            group.join_next().await,
+            Some(Err(TaskGroupError::TimedOut(_)))
+        ));
+
+        let mut group = BoundedTaskGroup::new(4).with_parent_token(&parent);
+        group.spawn(async { tokio::time::sleep(Duration::from_secs(60)).await });
+        parent.cancel();
+        assert!(matches!(
+            group.join_next().await,
+            Some(Err(TaskGroupError::Cancelled))
+        ));
+
+        let metrics = group.metrics();
+        assert_eq!(1, metrics.spawned);
+        assert_eq!(1, metrics.cancelled);
+        assert_eq!(0, metrics.running);
+    }
+}
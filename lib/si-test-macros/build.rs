@@ -0,0 +1,6 @@
+fn main() {
+    // The test macros skip tests by their tags at expansion time, so the tests have to be expanded
+    // again when the tags to include or exclude change
+    println!("cargo:rerun-if-env-changed=SI_TEST_INCLUDE_TAGS");
+    println!("cargo:rerun-if-env-changed=SI_TEST_EXCLUDE_TAGS");
+}
@@ -89,11 +89,21 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> DalTestFnSetup {
                                 let var = var.as_ref();
                                 expander.push_arg(parse_quote! {#var});
                             }
+                            "DalContextSecondUser" => {
+                                let var = expander.setup_dal_context_second_user();
+                                let var = var.as_ref();
+                                expander.push_arg(parse_quote! {#var});
+                            }
                             "WorkspaceSignup" => {
                                 let var = expander.setup_workspace_signup();
                                 let var = var.0.as_ref();
                                 expander.push_arg(parse_quote! {#var});
                             }
+                            "OtherWorkspaceSignup" => {
+                                let var = expander.setup_other_workspace_signup();
+                                let var = var.0.as_ref();
+                                expander.push_arg(parse_quote! {#var});
+                            }
                             "AuditDatabaseContext" => {
                                 let var = expander.setup_audit_database_context();
                                 let var = var.as_ref();
@@ -150,6 +160,11 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> DalTestFnSetup {
                                     let var = var.0.as_ref();
                                     expander.push_arg(parse_quote! {&#var});
                                 }
+                                "OtherWorkspaceSignup" => {
+                                    let var = expander.setup_other_workspace_signup();
+                                    let var = var.0.as_ref();
+                                    expander.push_arg(parse_quote! {&#var});
+                                }
                                 "AuditDatabaseContext" => {
                                     let var = expander.setup_audit_database_context();
                                     let var = var.as_ref();
@@ -209,6 +224,8 @@ struct DalTestFnSetupExpander {
     dal_context_head: Option<Rc<Ident>>,
     dal_context_head_ref: Option<Rc<Ident>>,
     dal_context_head_mut_ref: Option<Rc<Ident>>,
+    dal_context_second_user: Option<Rc<Ident>>,
+    other_workspace_signup: Option<(Rc<Ident>, Rc<Ident>)>,
 }
 
 impl DalTestFnSetupExpander {
@@ -236,6 +253,8 @@ impl DalTestFnSetupExpander {
             dal_context_head: None,
             dal_context_head_ref: None,
             dal_context_head_mut_ref: None,
+            dal_context_second_user: None,
+            other_workspace_signup: None,
         }
     }
 
@@ -420,4 +439,20 @@ impl FnSetupExpander for DalTestFnSetupExpander {
     fn set_dal_context_head_mut_ref(&mut self, value: Option<Rc<Ident>>) {
         self.dal_context_head_mut_ref = value;
     }
+
+    fn dal_context_second_user(&self) -> Option<&Rc<Ident>> {
+        self.dal_context_second_user.as_ref()
+    }
+
+    fn set_dal_context_second_user(&mut self, value: Option<Rc<Ident>>) {
+        self.dal_context_second_user = value;
+    }
+
+    fn other_workspace_signup(&self) -> Option<&(Rc<Ident>, Rc<Ident>)> {
+        self.other_workspace_signup.as_ref()
+    }
+
+    fn set_other_workspace_signup(&mut self, value: Option<(Rc<Ident>, Rc<Ident>)>) {
+        self.other_workspace_signup = value;
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{
-    Args, LOG_ENV_VAR, RT_DEFAULT_THREAD_STACK_SIZE, RT_DEFAULT_WORKER_THREADS, SPAN_EVENTS_ENV_VAR,
+    Args, EXCLUDE_TAGS_ENV_VAR, INCLUDE_TAGS_ENV_VAR, LOG_ENV_VAR, RT_DEFAULT_THREAD_STACK_SIZE,
+    RT_DEFAULT_WORKER_THREADS, SPAN_EVENTS_ENV_VAR,
 };
 use proc_macro2::{Ident, Span, TokenStream, TokenTree};
 use quote::quote;
@@ -10,7 +11,7 @@ pub(crate) trait FnSetup {
     fn into_parts(self) -> (TokenStream, Punctuated<Expr, Comma>);
 }
 
-pub(crate) fn expand_test(item: ItemFn, _args: Args, fn_setup: impl FnSetup) -> TokenStream {
+pub(crate) fn expand_test(item: ItemFn, args: Args, fn_setup: impl FnSetup) -> TokenStream {
     if item.sig.asyncness.is_none() {
         panic!("test function must be async--blocking tests not supported");
     }
@@ -25,6 +26,13 @@ pub(crate) fn expand_test(item: ItemFn, _args: Args, fn_setup: impl FnSetup) ->
         ReturnType::Type(_, typeness) => (true, quote! {-> #typeness}),
     };
     let test_attr = quote! {#[::core::prelude::v1::test]};
+    // Skipped tests are ignored rather than passed, so that test runs report them as such
+    let ignore_attr = match skipped_reason(&args.tags) {
+        Some(reason) if !attrs.iter().any(|attr| attr.path().is_ident("ignore")) => {
+            quote! {#[ignore = #reason]}
+        }
+        _ => quote! {},
+    };
 
     let thread_stack_size = RT_DEFAULT_THREAD_STACK_SIZE;
 
@@ -41,6 +49,7 @@ pub(crate) fn expand_test(item: ItemFn, _args: Args, fn_setup: impl FnSetup) ->
 
     quote! {
         #test_attr
+        #ignore_attr
         #(#attrs)*
         fn #test_name() -> ::dal_test::Result<()> {
             use ::dal_test::WrapErr;
@@ -135,6 +144,50 @@ pub(crate) fn expand_color_eyre_init() -> TokenStream {
     }
 }
 
+/// Returns why a test with the given tags is skipped, if it is.
+///
+/// Both environment variables hold comma separated lists of tags. A test is skipped if it has any
+/// of the excluded tags, or if included tags are given and it has none of them.
+fn skipped_reason(tags: &[String]) -> Option<String> {
+    // Used exclusively in tests & prefixed with `SI_TEST_`
+    #[allow(clippy::disallowed_methods)]
+    let env_tags = |env_var: &str| -> Vec<String> {
+        ::std::env::var(env_var)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let has_any = |env_tags: &[String]| env_tags.iter().any(|tag| tags.contains(tag));
+
+    let excluded_tags = env_tags(EXCLUDE_TAGS_ENV_VAR);
+    if has_any(&excluded_tags) {
+        return Some(format!(
+            "tagged {}, excluded by {EXCLUDE_TAGS_ENV_VAR}",
+            tags.join(", ")
+        ));
+    }
+
+    let included_tags = env_tags(INCLUDE_TAGS_ENV_VAR);
+    if !included_tags.is_empty() && !has_any(&included_tags) {
+        return Some(if tags.is_empty() {
+            format!("untagged, not included by {INCLUDE_TAGS_ENV_VAR}")
+        } else {
+            format!(
+                "tagged {}, not included by {INCLUDE_TAGS_ENV_VAR}",
+                tags.join(", ")
+            )
+        });
+    }
+
+    None
+}
+
 fn expand_tracing_init() -> TokenStream {
     let span_events_env_var = SPAN_EVENTS_ENV_VAR;
     let log_env_var = LOG_ENV_VAR;
@@ -225,6 +278,12 @@ pub(crate) trait FnSetupExpander {
     fn dal_context_head_mut_ref(&self) -> Option<&Rc<Ident>>;
     fn set_dal_context_head_mut_ref(&mut self, value: Option<Rc<Ident>>);
 
+    fn dal_context_second_user(&self) -> Option<&Rc<Ident>>;
+    fn set_dal_context_second_user(&mut self, value: Option<Rc<Ident>>);
+
+    fn other_workspace_signup(&self) -> Option<&(Rc<Ident>, Rc<Ident>)>;
+    fn set_other_workspace_signup(&mut self, value: Option<(Rc<Ident>, Rc<Ident>)>);
+
     fn setup_test_context(&mut self) -> Rc<Ident> {
         if let Some(ident) = self.test_context() {
             return ident.clone();
@@ -680,6 +739,83 @@ pub(crate) trait FnSetupExpander {
         self.dal_context_head_mut_ref().unwrap().clone()
     }
 
+    fn setup_dal_context_second_user(&mut self) -> Rc<Ident> {
+        if let Some(ident) = self.dal_context_second_user() {
+            return ident.clone();
+        }
+
+        // The second user joins the change set of whichever default context was already set up,
+        // so we cannot pick one on our own here without silently creating a separate change set.
+        let Some(default_ctx) = self
+            .dal_context_default_mut()
+            .or(self.dal_context_default())
+            .cloned()
+        else {
+            panic!("DalContextSecondUser must be requested after a DalContext argument");
+        };
+        let default_ctx = default_ctx.as_ref();
+
+        let dal_context_builder = self.setup_dal_context_builder();
+        let dal_context_builder = dal_context_builder.as_ref();
+        let bas = self.setup_workspace_signup();
+        let nw = bas.0.as_ref();
+
+        let var = Ident::new("dal_context_second_user", Span::call_site());
+        self.code_extend(quote! {
+            let #var = {
+                let mut ctx = #dal_context_builder
+                    .build_default()
+                    .await
+                    .wrap_err("failed to build default dal ctx for dal_context_second_user")?;
+                ctx.update_tenancy(::dal::Tenancy::new(*#nw.workspace.pk()));
+                ::dal_test::expand_helpers::join_change_set_as_new_user(
+                    &mut ctx,
+                    #default_ctx.change_set_id(),
+                ).await;
+                ctx.commit_no_rebase()
+                    .await
+                    .wrap_err("failed to commit join_change_set_as_new_user")?;
+
+                ::dal_test::DalContextSecondUser(ctx)
+            };
+        });
+        self.set_dal_context_second_user(Some(Rc::new(var)));
+
+        self.dal_context_second_user().unwrap().clone()
+    }
+
+    fn setup_other_workspace_signup(&mut self) -> (Rc<Ident>, Rc<Ident>) {
+        if let Some(idents) = self.other_workspace_signup() {
+            return idents.clone();
+        }
+
+        let dal_context_builder = self.setup_dal_context_builder();
+        let dal_context_builder = dal_context_builder.as_ref();
+
+        let var_other_nw = Ident::new("other_nw", Span::call_site());
+        let var_other_auth_token = Ident::new("other_auth_token", Span::call_site());
+        self.code_extend(quote! {
+            let (#var_other_nw, #var_other_auth_token) = {
+                let ctx = #dal_context_builder
+                    .build_default()
+                    .await
+                    .wrap_err("failed to build default dal ctx for other_workspace_signup")?;
+                let (nw, auth_token) = ::dal_test::expand_helpers::workspace_signup(&ctx).await?;
+                ctx.blocking_commit()
+                    .await
+                    .wrap_err("failed to commit other_workspace_signup")?;
+
+                (::dal_test::OtherWorkspaceSignup(nw), auth_token)
+            };
+        });
+        self.set_other_workspace_signup(Some((
+            Rc::new(var_other_nw),
+            Rc::new(var_other_auth_token),
+        )));
+
+        self.other_workspace_signup().unwrap().clone()
+    }
+
     fn setup_audit_database_context(&mut self) -> Rc<Ident> {
         let test_context = self.setup_test_context();
         let test_context = test_context.as_ref();
@@ -11,27 +11,49 @@ use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
-    Ident, ItemFn, Path, Token,
+    Ident, ItemFn, LitStr, Meta, Path, Token,
 };
 
 const LOG_ENV_VAR: &str = "SI_TEST_LOG";
 const SPAN_EVENTS_ENV_VAR: &str = "SI_TEST_LOG_SPAN_EVENTS";
+const INCLUDE_TAGS_ENV_VAR: &str = "SI_TEST_INCLUDE_TAGS";
+const EXCLUDE_TAGS_ENV_VAR: &str = "SI_TEST_EXCLUDE_TAGS";
 
 const RT_DEFAULT_WORKER_THREADS: usize = 2;
 const RT_DEFAULT_THREAD_STACK_SIZE: usize = 2 * 1024 * 1024 * 3;
 
-#[allow(dead_code)] // We aren't current using args on the macro, but when we do we can drop this
-                    // line
 struct Args {
+    #[allow(dead_code)] // We aren't current using vars on the macro, but when we do we can drop
+    // this line
     pub(crate) vars: HashSet<Ident>,
+    pub(crate) tags: Vec<String>,
 }
 
 impl Parse for Args {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let vars = Punctuated::<Ident, Token![,]>::parse_terminated(input)?;
-        Ok(Self {
-            vars: vars.into_iter().collect(),
-        })
+        let mut vars = HashSet::new();
+        let mut tags = Vec::new();
+
+        for meta in Punctuated::<Meta, Token![,]>::parse_terminated(input)? {
+            match meta {
+                Meta::Path(path) if path.get_ident().is_some() => {
+                    vars.extend(path.get_ident().cloned());
+                }
+                Meta::List(list) if list.path.is_ident("tags") => {
+                    let list_tags =
+                        list.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?;
+                    tags.extend(list_tags.iter().map(LitStr::value));
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "expected an identifier or `tags(\"...\")`",
+                    ))
+                }
+            }
+        }
+
+        Ok(Self { vars, tags })
     }
 }
 
@@ -149,8 +171,13 @@ fn path_as_string(path: &Path) -> String {
 ///    for a workspace for a visibility which is not in a change set
 /// * `services_ctx: ServicesContext`: a services context object, used to create DAL contexts
 /// * `wid: WorkspacePk: the workspace PK created for this test
+/// * `DalContextSecondUser(ctx_2): DalContextSecondUser`: a DAL context for a second user in the
+///    same workspace and change set as the default DAL context. `ctx_2` is **owned** and a
+///    `DalContext` argument must come before it.
 /// * `nw: WorkspaceSignup`: the full "new-workspace" data structure, created for this
 ///   test
+/// * `OtherWorkspaceSignup(other_nw): OtherWorkspaceSignup`: a second, separate "new-workspace"
+///    data structure, for testing isolation between workspaces
 ///
 /// # Referenced/Borrowed Types
 ///
@@ -164,6 +191,8 @@ fn path_as_string(path: &Path) -> String {
 ///    DAL contexts
 /// * `nw: &WorkspaceSignup`: a reference to the full "new-workspace" data structure,
 ///    created for this test
+/// * `other_nw: &OtherWorkspaceSignup`: a reference to a second, separate "new-workspace" data
+///    structure
 ///
 /// # Customized Tokio Runtime
 ///
@@ -204,6 +233,29 @@ fn path_as_string(path: &Path) -> String {
 ///
 /// The implementation for tracing is located in `src/extract.rs` in the `expand_tracing_init()`
 /// function.
+///
+/// # Tagging and Selective Execution
+///
+/// Heavyweight tests can be tagged, so that CI shards and local runs can include or exclude them
+/// without maintaining lists of test names:
+///
+/// ```ignore
+/// #[test(tags("slow", "veritech"))]
+/// async fn imports_every_module(ctx: &DalContext) {
+///     // ...
+/// }
+/// ```
+///
+/// The `SI_TEST_EXCLUDE_TAGS` environment variable skips every test with any of the given comma
+/// separated tags, and the `SI_TEST_INCLUDE_TAGS` environment variable skips every test with none
+/// of them, including untagged tests. Skipped tests are marked as ignored when they are expanded,
+/// so they are reported as ignored and can still be run with `--include-ignored`. For example, to
+/// split the suite into two shards:
+///
+/// ```ignore
+/// env SI_TEST_EXCLUDE_TAGS=slow cargo test
+/// env SI_TEST_INCLUDE_TAGS=slow cargo test
+/// ```
 #[proc_macro_attribute]
 pub fn dal_test(attr: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as Args);
@@ -319,10 +371,19 @@ pub fn dal_test(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///    for a visibility which is not in a change set
 /// * `DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>`: a mutable reference to a DAL context
 ///    for a workspace for a visibility which is not in a change set
+/// * `app: Router`: the sdf application router, which can be called in-process
+/// * `client: SdfTestClient`: an HTTP client for an sdf server started for this test on an
+///    ephemeral port, authenticated as the user of the created workspace. The server is shut down
+///    when the test finishes.
 /// * `services_ctx: ServicesContext`: a services context object, used to create DAL contexts
 /// * `wid: WorkspacePk: the workspace PK created for this test
+/// * `DalContextSecondUser(ctx_2): DalContextSecondUser`: a DAL context for a second user in the
+///    same workspace and change set as the default DAL context. `ctx_2` is **owned** and a
+///    `DalContext` argument must come before it.
 /// * `nw: WorkspaceSignup`: the full "new-workspace" data structure, created for this
 ///   test
+/// * `OtherWorkspaceSignup(other_nw): OtherWorkspaceSignup`: a second, separate "new-workspace"
+///    data structure, for testing isolation between workspaces
 ///
 /// # Referenced/Borrowed Types
 ///
@@ -332,10 +393,14 @@ pub fn dal_test(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// * `ctx: &DalContext`: a reference to the the default DAL context
 /// * `ctx: &mut DalContext`: a mutable reference to the the default DAL context
 /// * `builder: &DalContextBuilder`: a reference to the builder to create DAL context objects
+/// * `client: &SdfTestClient`: a reference to the authenticated HTTP client for this test's sdf
+///    server
 /// * `services_ctx: &ServicesContext`: a reference to a services context object, used to create
 ///    DAL contexts
 /// * `nw: &WorkspaceSignup`: a reference to the full "new-workspace" data structure,
 ///    created for this test
+/// * `other_nw: &OtherWorkspaceSignup`: a reference to a second, separate "new-workspace" data
+///    structure
 ///
 /// # Customized Tokio Runtime
 ///
@@ -376,6 +441,29 @@ pub fn dal_test(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// The implementation for tracing is located in `src/extract.rs` in the `expand_tracing_init()`
 /// function.
+///
+/// # Tagging and Selective Execution
+///
+/// Heavyweight tests can be tagged, so that CI shards and local runs can include or exclude them
+/// without maintaining lists of test names:
+///
+/// ```ignore
+/// #[test(tags("slow", "veritech"))]
+/// async fn imports_every_module(ctx: &DalContext) {
+///     // ...
+/// }
+/// ```
+///
+/// The `SI_TEST_EXCLUDE_TAGS` environment variable skips every test with any of the given comma
+/// separated tags, and the `SI_TEST_INCLUDE_TAGS` environment variable skips every test with none
+/// of them, including untagged tests. Skipped tests are marked as ignored when they are expanded,
+/// so they are reported as ignored and can still be run with `--include-ignored`. For example, to
+/// split the suite into two shards:
+///
+/// ```ignore
+/// env SI_TEST_EXCLUDE_TAGS=slow cargo test
+/// env SI_TEST_INCLUDE_TAGS=slow cargo test
+/// ```
 #[proc_macro_attribute]
 pub fn sdf_test(attr: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as Args);
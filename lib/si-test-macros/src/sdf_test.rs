@@ -85,6 +85,11 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> SdfTestFnSetup {
                                 let var = var.as_ref();
                                 expander.push_arg(parse_quote! {#var});
                             }
+                            "SdfTestClient" => {
+                                let var = expander.setup_sdf_test_client();
+                                let var = var.as_ref();
+                                expander.push_arg(parse_quote! {#var});
+                            }
                             "Router" => {
                                 let var = expander.setup_router();
                                 let var = var.as_ref();
@@ -100,11 +105,21 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> SdfTestFnSetup {
                                 let var = var.as_ref();
                                 expander.push_arg(parse_quote! {#var});
                             }
+                            "DalContextSecondUser" => {
+                                let var = expander.setup_dal_context_second_user();
+                                let var = var.as_ref();
+                                expander.push_arg(parse_quote! {#var});
+                            }
                             "WorkspaceSignup" => {
                                 let var = expander.setup_workspace_signup();
                                 let var = var.0.as_ref();
                                 expander.push_arg(parse_quote! {#var});
                             }
+                            "OtherWorkspaceSignup" => {
+                                let var = expander.setup_other_workspace_signup();
+                                let var = var.0.as_ref();
+                                expander.push_arg(parse_quote! {#var});
+                            }
                             "AuditDatabaseContext" => {
                                 let var = expander.setup_audit_database_context();
                                 let var = var.as_ref();
@@ -151,6 +166,11 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> SdfTestFnSetup {
                                     let var = var.as_ref();
                                     expander.push_arg(parse_quote! {&#var});
                                 }
+                                "SdfTestClient" => {
+                                    let var = expander.setup_sdf_test_client();
+                                    let var = var.as_ref();
+                                    expander.push_arg(parse_quote! {&#var});
+                                }
                                 "ServicesContext" => {
                                     let var = expander.setup_services_context();
                                     let var = var.as_ref();
@@ -161,6 +181,11 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> SdfTestFnSetup {
                                     let var = var.0.as_ref();
                                     expander.push_arg(parse_quote! {&#var});
                                 }
+                                "OtherWorkspaceSignup" => {
+                                    let var = expander.setup_other_workspace_signup();
+                                    let var = var.0.as_ref();
+                                    expander.push_arg(parse_quote! {&#var});
+                                }
                                 "AuditDatabaseContext" => {
                                     let var = expander.setup_audit_database_context();
                                     let var = var.as_ref();
@@ -230,11 +255,14 @@ struct SdfTestFnSetupExpander {
     dal_context_head: Option<Rc<Ident>>,
     dal_context_head_ref: Option<Rc<Ident>>,
     dal_context_head_mut_ref: Option<Rc<Ident>>,
+    dal_context_second_user: Option<Rc<Ident>>,
+    other_workspace_signup: Option<(Rc<Ident>, Rc<Ident>)>,
     jwt_public_signing_key: Option<Rc<Ident>>,
     posthog_client: Option<Rc<Ident>>,
     ws_multiplexer_client: Option<Rc<Ident>>,
     crdt_multiplexer_client: Option<Rc<Ident>>,
     router: Option<Rc<Ident>>,
+    sdf_test_client: Option<Rc<Ident>>,
     auth_token: Option<Rc<Ident>>,
     auth_token_ref: Option<Rc<Ident>>,
     spicedb_client: Option<Rc<Ident>>,
@@ -265,11 +293,14 @@ impl SdfTestFnSetupExpander {
             dal_context_head: None,
             dal_context_head_ref: None,
             dal_context_head_mut_ref: None,
+            dal_context_second_user: None,
+            other_workspace_signup: None,
             jwt_public_signing_key: None,
             posthog_client: None,
             ws_multiplexer_client: None,
             crdt_multiplexer_client: None,
             router: None,
+            sdf_test_client: None,
             auth_token: None,
             auth_token_ref: None,
             spicedb_client: None,
@@ -411,6 +442,42 @@ impl SdfTestFnSetupExpander {
         self.router.as_ref().unwrap().clone()
     }
 
+    fn setup_sdf_test_client(&mut self) -> Rc<Ident> {
+        if let Some(ref ident) = self.sdf_test_client {
+            return ident.clone();
+        }
+
+        let router = self.setup_router();
+        let router = router.as_ref();
+        let cancellation_token = self.setup_cancellation_token();
+        let cancellation_token = cancellation_token.as_ref();
+        let task_tracker = self.setup_task_tracker();
+        let task_tracker = task_tracker.as_ref();
+        let workspace_signup = self.setup_workspace_signup();
+        let auth_token = workspace_signup.1.as_ref();
+
+        let var = Ident::new("sdf_test_client", Span::call_site());
+        self.code_extend(quote! {
+            let #var = {
+                let server = ::axum::Server::bind(
+                    &::std::net::SocketAddr::from(([127, 0, 0, 1], 0))
+                )
+                .serve(#router.clone().into_make_service());
+                let base_url = format!("http://{}", server.local_addr());
+                #task_tracker.spawn(
+                    server.with_graceful_shutdown(#cancellation_token.clone().cancelled_owned())
+                );
+
+                ::dal_test::SdfTestClient::new(base_url, #auth_token.clone())
+            };
+            // Shuts down the server along with all other services once the test is done
+            let _sdf_test_client_drop_guard = #cancellation_token.clone().drop_guard();
+        });
+        self.sdf_test_client = Some(Rc::new(var));
+
+        self.sdf_test_client.as_ref().unwrap().clone()
+    }
+
     fn setup_auth_token(&mut self) -> Rc<Ident> {
         if let Some(ref ident) = self.auth_token {
             return ident.clone();
@@ -637,4 +704,20 @@ impl FnSetupExpander for SdfTestFnSetupExpander {
     fn set_dal_context_head_mut_ref(&mut self, value: Option<Rc<Ident>>) {
         self.dal_context_head_mut_ref = value;
     }
+
+    fn dal_context_second_user(&self) -> Option<&Rc<Ident>> {
+        self.dal_context_second_user.as_ref()
+    }
+
+    fn set_dal_context_second_user(&mut self, value: Option<Rc<Ident>>) {
+        self.dal_context_second_user = value;
+    }
+
+    fn other_workspace_signup(&self) -> Option<&(Rc<Ident>, Rc<Ident>)> {
+        self.other_workspace_signup.as_ref()
+    }
+
+    fn set_other_workspace_signup(&mut self, value: Option<(Rc<Ident>, Rc<Ident>)>) {
+        self.other_workspace_signup = value;
+    }
 }
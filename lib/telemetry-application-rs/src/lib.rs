@@ -58,9 +58,13 @@ use tracing_subscriber::{
     Registry,
 };
 
+use crate::slo::{SloFilter, SloLayer};
+
 pub use telemetry::tracing;
 pub use telemetry::{ApplicationTelemetryClient, TelemetryClient};
 
+mod slo;
+
 pub mod prelude {
     pub use super::{ConsoleLogFormat, TelemetryConfig};
     pub use telemetry::prelude::*;
@@ -403,6 +407,7 @@ fn tracing_subscriber(
     let registry = registry.with(console_log_layer);
     let registry = registry.with(otel_layer);
     let registry = registry.with(metrics_layer);
+    let registry = registry.with(SloLayer.with_filter(SloFilter));
 
     let handles = TelemetryHandles {
         console_log_filter_reload,
@@ -464,6 +469,7 @@ fn create_client(
     shutdown_token: CancellationToken,
 ) -> Result<(ApplicationTelemetryClient, TelemetryShutdownGuard)> {
     let (update_telemetry_tx, update_telemetry_rx) = mpsc::unbounded_channel();
+    let directives = TracingDirectives::from(&tracing_level);
 
     let client = ApplicationTelemetryClient::new(
         config.app_modules,
@@ -478,7 +484,7 @@ fn create_client(
     };
 
     // Spawn this task free of the tracker as we want it to outlive the tracker when shutting down
-    tokio::spawn(TelemetryUpdateTask::new(handles, update_telemetry_rx).run());
+    tokio::spawn(TelemetryUpdateTask::new(handles, directives, update_telemetry_rx).run());
 
     if config.signal_handlers {
         tracker.spawn(
@@ -554,6 +560,7 @@ struct TelemetrySignalHandlerTask {
     client: ApplicationTelemetryClient,
     shutdown_token: CancellationToken,
     sig_usr1: unix::Signal,
+    sig_usr2: unix::Signal,
 }
 
 impl TelemetrySignalHandlerTask {
@@ -564,11 +571,13 @@ impl TelemetrySignalHandlerTask {
         shutdown_token: CancellationToken,
     ) -> io::Result<Self> {
         let sig_usr1 = unix::signal(SignalKind::user_defined1())?;
+        let sig_usr2 = unix::signal(SignalKind::user_defined2())?;
 
         Ok(Self {
             client,
             shutdown_token,
             sig_usr1,
+            sig_usr2,
         })
     }
 
@@ -588,6 +597,15 @@ impl TelemetrySignalHandlerTask {
                         );
                     }
                 }
+                Some(_) = self.sig_usr2.recv() => {
+                    if let Err(err) = self.client.reset_tracing().await {
+                        warn!(
+                            task = Self::NAME,
+                            error = ?err,
+                            "error while trying to reset tracing level",
+                        );
+                    }
+                }
                 else => {
                     // All other arms are closed, nothing let to do but return
                     trace!(task = Self::NAME, "all signal listeners have closed");
@@ -602,6 +620,7 @@ impl TelemetrySignalHandlerTask {
 
 struct TelemetryUpdateTask {
     handles: TelemetryHandles,
+    directives: TracingDirectives,
     update_command_rx: mpsc::UnboundedReceiver<TelemetryCommand>,
     is_shutdown: bool,
 }
@@ -611,10 +630,12 @@ impl TelemetryUpdateTask {
 
     fn new(
         handles: TelemetryHandles,
+        directives: TracingDirectives,
         update_command_rx: mpsc::UnboundedReceiver<TelemetryCommand>,
     ) -> Self {
         Self {
             handles,
+            directives,
             update_command_rx,
             is_shutdown: false,
         }
@@ -623,6 +644,14 @@ impl TelemetryUpdateTask {
     async fn run(mut self) {
         while let Some(command) = self.update_command_rx.recv().await {
             match command {
+                TelemetryCommand::Directives(tx) => {
+                    if tx.send(self.directives.as_str().to_owned()).is_err() {
+                        trace!(
+                            task = Self::NAME,
+                            "receiver already closed when reporting tracing directives",
+                        );
+                    }
+                }
                 TelemetryCommand::TracingLevel { level, wait } => {
                     // We want a span around the update logging so this is transmitted to our
                     // OpenTelemetry endpoint. We may use this span (and associated events) as a
@@ -633,15 +662,16 @@ impl TelemetryUpdateTask {
                     // `.instrument()` combinator on the future.
                     let span = info_span!("telemetry_update_task.update_tracing_level");
                     span.in_scope(|| {
-                        if let Err(err) = self.update_tracing_level(level) {
+                        let result = self.update_tracing_level(level).map_err(|err| {
                             warn!(
                                 task = Self::NAME,
                                 error = ?err,
                                 "failed to update tracing level, using prior value",
                             );
-                        }
+                            err.to_string()
+                        });
                         if let Some(tx) = wait {
-                            if let Err(err) = tx.send(()) {
+                            if let Err(err) = tx.send(result) {
                                 warn!(
                                     error = ?err,
                                     "receiver already closed when waiting on changing tracing level",
@@ -664,9 +694,12 @@ impl TelemetryUpdateTask {
         debug!(task = Self::NAME, "shutdown complete");
     }
 
-    fn update_tracing_level(&self, tracing_level: TracingLevel) -> Result<()> {
+    fn update_tracing_level(&mut self, tracing_level: TracingLevel) -> Result<()> {
         let directives = TracingDirectives::from(tracing_level);
 
+        // Parse the directives up front so that an invalid value leaves every layer untouched
+        EnvFilter::try_new(directives.as_str())?;
+
         (self.handles.console_log_filter_reload)(EnvFilter::try_new(directives.as_str())?)?;
         (self.handles.otel_filter_reload)(EnvFilter::try_new(directives.as_str())?)?;
         (self.handles.metrics_filter_reload)(EnvFilter::try_new(directives.as_str())?)?;
@@ -676,6 +709,7 @@ impl TelemetryUpdateTask {
             directives = directives.as_str(),
             "updated tracing levels",
         );
+        self.directives = directives;
 
         Ok(())
     }
@@ -0,0 +1,99 @@
+//! A [`Layer`] which counts closed spans against the SLOs declared with [`telemetry::slo`].
+
+use std::time::Instant;
+
+use telemetry::{
+    slo,
+    tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        subscriber::Interest,
+        Metadata, Subscriber,
+    },
+    OtelStatusCode,
+};
+use tracing_subscriber::{
+    layer::{Context, Filter},
+    registry::LookupSpan,
+    Layer,
+};
+
+const STATUS_CODE_FIELD: &str = "otel.status_code";
+
+pub(crate) struct SloLayer;
+
+/// Tracked in the extensions of spans with a declared SLO.
+struct SloTiming {
+    started_at: Instant,
+    failed: bool,
+}
+
+impl<S> Layer<S> for SloLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FailedVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SloTiming {
+            started_at: Instant::now(),
+            failed: visitor.failed,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FailedVisitor::default();
+        values.record(&mut visitor);
+        if let Some(timing) = span.extensions_mut().get_mut::<SloTiming>() {
+            timing.failed |= visitor.failed;
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        if let Some(timing) = span.extensions_mut().remove::<SloTiming>() {
+            slo::record(span.name(), timing.started_at.elapsed(), timing.failed);
+        }
+    }
+}
+
+/// Only enables spans with a declared SLO. The interest is cached per callsite, and the cache is
+/// rebuilt whenever an SLO is declared (see [`slo::declare`]).
+pub(crate) struct SloFilter;
+
+impl<S> Filter<S> for SloFilter {
+    fn enabled(&self, metadata: &Metadata<'_>, _: &Context<'_, S>) -> bool {
+        metadata.is_span() && slo::is_declared(metadata.name())
+    }
+
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if metadata.is_span() && slo::is_declared(metadata.name()) {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+}
+
+#[derive(Default)]
+struct FailedVisitor {
+    failed: bool,
+}
+
+impl Visit for FailedVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == STATUS_CODE_FIELD && value == OtelStatusCode::Error.as_str() {
+            self.failed = true;
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
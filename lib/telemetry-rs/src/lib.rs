@@ -26,6 +26,8 @@ pub use opentelemetry::{self, trace::SpanKind};
 pub use tracing;
 use tracing::warn;
 
+pub mod slo;
+
 pub mod prelude {
     pub use super::{
         current_span_for_instrument_at, MessagingOperation, SpanExt, SpanKind, SpanKindExt,
@@ -182,6 +184,7 @@ pub struct ApplicationTelemetryClient {
     app_modules: Arc<Vec<&'static str>>,
     interesting_modules: Arc<Vec<&'static str>>,
     never_modules: Arc<Vec<&'static str>>,
+    initial_tracing_level: Arc<TracingLevel>,
     tracing_level: Arc<Mutex<TracingLevel>>,
     update_telemetry_tx: mpsc::UnboundedSender<TelemetryCommand>,
 }
@@ -198,49 +201,136 @@ impl ApplicationTelemetryClient {
             app_modules: Arc::new(app_modules),
             interesting_modules: Arc::new(interesting_modules),
             never_modules: Arc::new(never_modules),
+            initial_tracing_level: Arc::new(tracing_level.clone()),
             tracing_level: Arc::new(Mutex::new(tracing_level)),
             update_telemetry_tx,
         }
     }
 
     pub async fn set_verbosity_and_wait(&mut self, updated: Verbosity) -> Result<(), ClientError> {
+        let previous = self.tracing_level.lock().await.clone();
         let (tx, rx) = oneshot::channel();
 
         self.set_verbosity_inner(updated, Some(tx)).await?;
 
-        if let Err(err) = rx.await {
-            warn!(error = ?err, "sender already closed while waiting on verbosity change");
+        match rx.await {
+            Ok(result) => self.restore_on_error(result, previous).await,
+            Err(err) => {
+                warn!(error = ?err, "sender already closed while waiting on verbosity change");
+                Ok(())
+            }
         }
-
-        Ok(())
     }
 
     pub async fn modify_verbosity_and_wait(&mut self) -> Result<(), ClientError> {
+        let previous = self.tracing_level.lock().await.clone();
         let (tx, rx) = oneshot::channel();
 
         self.modify_verbosity_inner(Some(tx)).await?;
 
-        if let Err(err) = rx.await {
-            warn!(
-                error = ?err,
-                "sender already closed while waiting on verbosity increase change",
-            );
+        match rx.await {
+            Ok(result) => self.restore_on_error(result, previous).await,
+            Err(err) => {
+                warn!(
+                    error = ?err,
+                    "sender already closed while waiting on verbosity increase change",
+                );
+                Ok(())
+            }
         }
-
-        Ok(())
     }
 
+    /// Replaces the tracing directives and waits until they are applied.
+    ///
+    /// If the directives cannot be parsed, the prior tracing level remains in effect and an
+    /// [`ClientError::InvalidDirectives`] error is returned.
     pub async fn set_custom_tracing_and_wait(
         &mut self,
         directives: impl Into<String> + Send,
     ) -> Result<(), ClientError> {
+        let previous = self.tracing_level.lock().await.clone();
         let (tx, rx) = oneshot::channel();
 
         self.set_custom_tracing_inner(directives, Some(tx)).await?;
 
-        if let Err(err) = rx.await {
-            warn!(error = ?err, "sender already closed while waiting on custom tracing change");
+        match rx.await {
+            Ok(result) => self.restore_on_error(result, previous).await,
+            Err(err) => {
+                warn!(error = ?err, "sender already closed while waiting on custom tracing change");
+                Ok(())
+            }
         }
+    }
+
+    /// Sets the tracing level of a single module (i.e. `dal=debug`), keeping all other current
+    /// directives, and waits until the change is applied.
+    pub async fn set_module_tracing_and_wait(
+        &mut self,
+        module: impl AsRef<str>,
+        level: impl AsRef<str>,
+    ) -> Result<(), ClientError> {
+        let current = self.tracing_directives().await?;
+        let updated = with_module_directive(&current, module.as_ref(), level.as_ref());
+
+        self.set_custom_tracing_and_wait(updated).await
+    }
+
+    /// Restores the tracing level the process started with and waits until it is applied.
+    pub async fn reset_tracing_and_wait(&mut self) -> Result<(), ClientError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.reset_tracing_inner(Some(tx)).await?;
+
+        match rx.await {
+            Ok(result) => result.map_err(ClientError::InvalidDirectives),
+            Err(err) => {
+                warn!(error = ?err, "sender already closed while waiting on tracing reset");
+                Ok(())
+            }
+        }
+    }
+
+    /// Restores the tracing level the process started with.
+    pub async fn reset_tracing(&mut self) -> Result<(), ClientError> {
+        self.reset_tracing_inner(None).await
+    }
+
+    /// Returns the tracing directives currently in effect.
+    pub async fn tracing_directives(&self) -> Result<String, ClientError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.update_telemetry_tx
+            .send(TelemetryCommand::Directives(tx))?;
+
+        rx.await.map_err(|_| ClientError::UpdateTaskClosed)
+    }
+
+    async fn restore_on_error(
+        &self,
+        result: Result<(), String>,
+        previous: TracingLevel,
+    ) -> Result<(), ClientError> {
+        if let Err(err) = result {
+            *self.tracing_level.lock().await = previous;
+            return Err(ClientError::InvalidDirectives(err));
+        }
+
+        Ok(())
+    }
+
+    async fn reset_tracing_inner(
+        &mut self,
+        wait: Option<oneshot::Sender<Result<(), String>>>,
+    ) -> Result<(), ClientError> {
+        let mut guard = self.tracing_level.lock().await;
+        let tracing_level = guard.deref_mut();
+
+        *tracing_level = self.initial_tracing_level.as_ref().clone();
+        self.update_telemetry_tx
+            .send(TelemetryCommand::TracingLevel {
+                level: tracing_level.clone(),
+                wait,
+            })?;
 
         Ok(())
     }
@@ -248,7 +338,7 @@ impl ApplicationTelemetryClient {
     async fn set_verbosity_inner(
         &mut self,
         updated: Verbosity,
-        wait: Option<oneshot::Sender<()>>,
+        wait: Option<oneshot::Sender<Result<(), String>>>,
     ) -> Result<(), ClientError> {
         let mut guard = self.tracing_level.lock().await;
         let tracing_level = guard.deref_mut();
@@ -280,7 +370,7 @@ impl ApplicationTelemetryClient {
 
     async fn modify_verbosity_inner(
         &mut self,
-        wait: Option<oneshot::Sender<()>>,
+        wait: Option<oneshot::Sender<Result<(), String>>>,
     ) -> Result<(), ClientError> {
         let guard = self.tracing_level.lock().await;
         match guard.deref() {
@@ -299,7 +389,7 @@ impl ApplicationTelemetryClient {
     async fn set_custom_tracing_inner(
         &mut self,
         directives: impl Into<String> + Send,
-        wait: Option<oneshot::Sender<()>>,
+        wait: Option<oneshot::Sender<Result<(), String>>>,
     ) -> Result<(), ClientError> {
         let mut guard = self.tracing_level.lock().await;
         let tracing_level = guard.deref_mut();
@@ -379,6 +469,10 @@ impl TelemetryLevel for NoopClient {
 pub enum ClientError {
     #[error("custom tracing level has no verbosity")]
     CustomHasNoVerbosity,
+    #[error("invalid tracing directives: {0}")]
+    InvalidDirectives(String),
+    #[error("telemetry update task is no longer running")]
+    UpdateTaskClosed,
     #[error("error while updating tracing level")]
     UpdateTracingLevel(#[from] mpsc::error::SendError<TelemetryCommand>),
 }
@@ -386,10 +480,11 @@ pub enum ClientError {
 #[remain::sorted]
 #[derive(Debug)]
 pub enum TelemetryCommand {
+    Directives(oneshot::Sender<String>),
     Shutdown(CancellationToken),
     TracingLevel {
         level: TracingLevel,
-        wait: Option<oneshot::Sender<()>>,
+        wait: Option<oneshot::Sender<Result<(), String>>>,
     },
 }
 
@@ -503,6 +598,26 @@ impl From<Verbosity> for u8 {
     }
 }
 
+/// Returns `directives` with any directive targeting `module` replaced by `module=level`.
+fn with_module_directive(directives: &str, module: &str, level: &str) -> String {
+    let module_directive = format!("{module}={level}");
+
+    std::iter::once(module_directive.as_str())
+        .chain(
+            directives
+                .split(',')
+                .map(str::trim)
+                .filter(|directive| !directive.is_empty())
+                .filter(|directive| {
+                    directive
+                        .split_once('=')
+                        .map_or(*directive != module, |(target, _)| target != module)
+                }),
+        )
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 pub trait IntoAppModules {
     fn into_app_modules(self) -> Vec<Cow<'static, str>>;
 }
@@ -0,0 +1,465 @@
+//! Service level objectives (SLOs) for named operations.
+//!
+//! An [`Slo`] is declared once per operation, where the operation is the name of the span which
+//! instruments it. Once declared, every closed span with that name is counted against the
+//! objectives of the SLO, emitting the following metrics (all attributed with `operation` and
+//! `objective`):
+//!
+//! - `slo.events`: the number of events counted against the objective
+//! - `slo.bad_events`: the number of events which missed the objective
+//! - `slo.burn_rate`: the rate at which the error budget is being spent over the `5m` and `1h`
+//!   windows (additionally attributed with `window`), where `1.0` spends exactly the whole budget
+//!   over the SLO period
+//! - `slo.burn_rate.alert`: `1.0` while both windows burn faster than
+//!   [`FAST_BURN_ALERT_THRESHOLD`], `0.0` otherwise
+//!
+//! The burn rates are observed whenever metrics are collected rather than when events are
+//! recorded, so they return to `0.0` once the bad events have left the windows, even if the
+//! operation stops receiving traffic altogether.
+//!
+//! Recording spans is left to the application telemetry layer, so services only need to declare
+//! their SLOs:
+//!
+//! ```ignore
+//! telemetry::slo::declare(
+//!     Slo::new("sdf.change_set.apply")
+//!         .with_latency_objective(Duration::from_secs(2), 0.99)
+//!         .with_error_objective(0.999),
+//! )?;
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock},
+    time::{Duration, Instant},
+};
+
+use opentelemetry::{
+    global,
+    metrics::{Counter, ObservableGauge},
+    KeyValue,
+};
+use thiserror::Error;
+
+/// The burn rate at which both windows must be burning for `slo.burn_rate.alert` to fire. At this
+/// rate, 2% of a 30 day error budget is spent within an hour.
+pub const FAST_BURN_ALERT_THRESHOLD: f64 = 14.4;
+
+const SHORT_WINDOW: Window = Window {
+    label: "5m",
+    buckets: 5,
+};
+const LONG_WINDOW: Window = Window {
+    label: "1h",
+    buckets: 60,
+};
+const BUCKET_DURATION: Duration = Duration::from_secs(60);
+
+static REGISTRY: OnceLock<RwLock<HashMap<Cow<'static, str>, Arc<SloTracker>>>> = OnceLock::new();
+static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+
+#[remain::sorted]
+#[derive(Debug, Error, PartialEq)]
+pub enum SloError {
+    #[error("invalid target for {0} objective, must be between 0 and 1 (exclusive): {1}")]
+    InvalidTarget(&'static str, f64),
+    #[error("slo for operation has no objectives: {0}")]
+    NoObjectives(String),
+}
+
+/// The objectives for a named operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slo {
+    operation: Cow<'static, str>,
+    latency: Option<LatencyObjective>,
+    error_target: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct LatencyObjective {
+    threshold: Duration,
+    target: f64,
+}
+
+impl Slo {
+    /// Creates an SLO, without any objectives, for the spans with the given name.
+    pub fn new(operation: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            operation: operation.into(),
+            latency: None,
+            error_target: None,
+        }
+    }
+
+    /// The ratio of events which must complete within the threshold, e.g. `0.99`.
+    #[must_use]
+    pub fn with_latency_objective(mut self, threshold: Duration, target: f64) -> Self {
+        self.latency = Some(LatencyObjective { threshold, target });
+        self
+    }
+
+    /// The ratio of events which must not fail, e.g. `0.999`.
+    #[must_use]
+    pub fn with_error_objective(mut self, target: f64) -> Self {
+        self.error_target = Some(target);
+        self
+    }
+
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    fn validate(&self) -> Result<(), SloError> {
+        if self.latency.is_none() && self.error_target.is_none() {
+            return Err(SloError::NoObjectives(self.operation.to_string()));
+        }
+        let targets = [
+            (
+                Objective::Latency,
+                self.latency.map(|latency| latency.target),
+            ),
+            (Objective::Error, self.error_target),
+        ];
+        for (objective, target) in targets {
+            if let Some(target) = target {
+                if !(target > 0.0 && target < 1.0) {
+                    return Err(SloError::InvalidTarget(objective.as_str(), target));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Declares the SLO for its operation, replacing any SLO previously declared for it.
+pub fn declare(slo: Slo) -> Result<(), SloError> {
+    slo.validate()?;
+
+    let tracker = Arc::new(SloTracker::new(slo));
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(tracker.slo.operation.clone(), tracker);
+    // Subscribers cache their interest in each callsite, which changes for the spans of the
+    // operation now that it is declared
+    tracing::callsite::rebuild_interest_cache();
+    Ok(())
+}
+
+/// Whether an SLO has been declared for the operation.
+pub fn is_declared(operation: &str) -> bool {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains_key(operation)
+}
+
+/// Counts a completed event of the operation against its SLO, if one has been declared.
+pub fn record(operation: &str, duration: Duration, failed: bool) {
+    let maybe_tracker = registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(operation)
+        .cloned();
+    if let Some(tracker) = maybe_tracker {
+        tracker.record(duration, failed, Instant::now());
+    }
+}
+
+fn registry() -> &'static RwLock<HashMap<Cow<'static, str>, Arc<SloTracker>>> {
+    REGISTRY.get_or_init(Default::default)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Objective {
+    Error,
+    Latency,
+}
+
+impl Objective {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Latency => "latency",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Window {
+    label: &'static str,
+    buckets: usize,
+}
+
+struct Instruments {
+    events: Counter<u64>,
+    bad_events: Counter<u64>,
+    _burn_rate: ObservableGauge<f64>,
+    _burn_rate_alert: ObservableGauge<f64>,
+}
+
+impl Instruments {
+    fn get() -> &'static Self {
+        INSTRUMENTS.get_or_init(|| {
+            let meter = global::meter("si.slo");
+            Self {
+                events: meter
+                    .u64_counter("slo.events")
+                    .with_description("Events counted against an SLO objective")
+                    .init(),
+                bad_events: meter
+                    .u64_counter("slo.bad_events")
+                    .with_description("Events which missed an SLO objective")
+                    .init(),
+                _burn_rate: meter
+                    .f64_observable_gauge("slo.burn_rate")
+                    .with_description("Error budget burn rate of an SLO objective")
+                    .with_callback(|observer| {
+                        for_each_objective(|tracker, now| {
+                            let index = tracker.bucket_index(now);
+                            let buckets = tracker.buckets();
+                            for window in [SHORT_WINDOW, LONG_WINDOW] {
+                                let mut attributes = tracker.attributes.clone();
+                                attributes.push(KeyValue::new("window", window.label));
+                                observer.observe(
+                                    tracker.burn_rate(&buckets, window, index),
+                                    &attributes,
+                                );
+                            }
+                        });
+                    })
+                    .init(),
+                _burn_rate_alert: meter
+                    .f64_observable_gauge("slo.burn_rate.alert")
+                    .with_description("Whether an SLO objective is burning its error budget fast")
+                    .with_callback(|observer| {
+                        for_each_objective(|tracker, now| {
+                            let alerting = tracker.is_alerting(now);
+                            observer.observe(if alerting { 1.0 } else { 0.0 }, &tracker.attributes);
+                        });
+                    })
+                    .init(),
+            }
+        })
+    }
+}
+
+/// Calls the function with every objective of every declared SLO, as of now.
+fn for_each_objective(f: impl Fn(&ObjectiveTracker, Instant)) {
+    let trackers: Vec<Arc<SloTracker>> = registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .values()
+        .cloned()
+        .collect();
+    let now = Instant::now();
+    for tracker in trackers {
+        for objective in &tracker.objectives {
+            f(objective, now);
+        }
+    }
+}
+
+struct SloTracker {
+    slo: Slo,
+    objectives: Vec<ObjectiveTracker>,
+}
+
+impl SloTracker {
+    fn new(slo: Slo) -> Self {
+        let started_at = Instant::now();
+        let mut objectives = Vec::new();
+        if let Some(latency) = slo.latency {
+            objectives.push(ObjectiveTracker::new(
+                &slo.operation,
+                Objective::Latency,
+                latency.target,
+                started_at,
+            ));
+        }
+        if let Some(target) = slo.error_target {
+            objectives.push(ObjectiveTracker::new(
+                &slo.operation,
+                Objective::Error,
+                target,
+                started_at,
+            ));
+        }
+
+        Self { slo, objectives }
+    }
+
+    fn record(&self, duration: Duration, failed: bool, now: Instant) {
+        for tracker in &self.objectives {
+            let bad = match tracker.objective {
+                Objective::Error => failed,
+                // Failures are only counted against the error objective
+                Objective::Latency => self
+                    .slo
+                    .latency
+                    .is_some_and(|latency| duration > latency.threshold),
+            };
+            tracker.record(bad, now);
+        }
+    }
+}
+
+struct ObjectiveTracker {
+    objective: Objective,
+    target: f64,
+    started_at: Instant,
+    attributes: Vec<KeyValue>,
+    /// Event counts per [`BUCKET_DURATION`], covering the long window.
+    buckets: Mutex<VecDeque<Bucket>>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Bucket {
+    index: u64,
+    total: u64,
+    bad: u64,
+}
+
+impl ObjectiveTracker {
+    fn new(operation: &str, objective: Objective, target: f64, started_at: Instant) -> Self {
+        Self {
+            objective,
+            target,
+            started_at,
+            attributes: vec![
+                KeyValue::new("operation", operation.to_string()),
+                KeyValue::new("objective", objective.as_str()),
+            ],
+            buckets: Mutex::new(VecDeque::with_capacity(LONG_WINDOW.buckets)),
+        }
+    }
+
+    fn bucket_index(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.started_at).as_secs() / BUCKET_DURATION.as_secs()
+    }
+
+    fn buckets(&self) -> MutexGuard<'_, VecDeque<Bucket>> {
+        self.buckets.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record(&self, bad: bool, now: Instant) {
+        let index = self.bucket_index(now);
+        {
+            let mut buckets = self.buckets();
+            if buckets.back().is_none_or(|bucket| bucket.index != index) {
+                buckets.push_back(Bucket {
+                    index,
+                    ..Default::default()
+                });
+            }
+            if let Some(bucket) = buckets.back_mut() {
+                bucket.total += 1;
+                bucket.bad += u64::from(bad);
+            }
+            while buckets
+                .front()
+                .is_some_and(|bucket| bucket.index + (LONG_WINDOW.buckets as u64) <= index)
+            {
+                buckets.pop_front();
+            }
+        }
+
+        let instruments = Instruments::get();
+        instruments.events.add(1, &self.attributes);
+        if bad {
+            instruments.bad_events.add(1, &self.attributes);
+        }
+    }
+
+    fn is_alerting(&self, now: Instant) -> bool {
+        let index = self.bucket_index(now);
+        let buckets = self.buckets();
+        self.burn_rate(&buckets, SHORT_WINDOW, index) > FAST_BURN_ALERT_THRESHOLD
+            && self.burn_rate(&buckets, LONG_WINDOW, index) > FAST_BURN_ALERT_THRESHOLD
+    }
+
+    /// The ratio of bad events within the window, relative to the error budget.
+    fn burn_rate(&self, buckets: &VecDeque<Bucket>, window: Window, index: u64) -> f64 {
+        let (total, bad) = buckets
+            .iter()
+            .filter(|bucket| bucket.index + (window.buckets as u64) > index)
+            .fold((0, 0), |(total, bad), bucket| {
+                (total + bucket.total, bad + bucket.bad)
+            });
+        if total == 0 {
+            return 0.0;
+        }
+
+        (bad as f64 / total as f64) / (1.0 - self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(target: f64, started_at: Instant) -> ObjectiveTracker {
+        ObjectiveTracker::new("test", Objective::Error, target, started_at)
+    }
+
+    fn record(tracker: &ObjectiveTracker, total: u64, bad: u64, now: Instant) {
+        for n in 0..total {
+            tracker.record(n < bad, now);
+        }
+    }
+
+    fn burn_rate(tracker: &ObjectiveTracker, window: Window, now: Instant) -> f64 {
+        tracker.burn_rate(&tracker.buckets(), window, tracker.bucket_index(now))
+    }
+
+    #[test]
+    fn empty_window_does_not_burn() {
+        let started_at = Instant::now();
+        let tracker = tracker(0.99, started_at);
+
+        assert_eq!(0.0, burn_rate(&tracker, SHORT_WINDOW, started_at));
+        assert_eq!(0.0, burn_rate(&tracker, LONG_WINDOW, started_at));
+        assert!(!tracker.is_alerting(started_at));
+    }
+
+    #[test]
+    fn events_rotate_out_of_windows() {
+        let started_at = Instant::now();
+        let tracker = tracker(0.9, started_at);
+        record(&tracker, 10, 5, started_at);
+        assert_eq!(5.0, burn_rate(&tracker, SHORT_WINDOW, started_at).round());
+
+        // Still within the long window, but out of the short one
+        let later = started_at + BUCKET_DURATION * SHORT_WINDOW.buckets as u32;
+        assert_eq!(0.0, burn_rate(&tracker, SHORT_WINDOW, later));
+        assert_eq!(5.0, burn_rate(&tracker, LONG_WINDOW, later).round());
+
+        // Out of both windows, and dropped once the next event is recorded
+        let much_later = started_at + BUCKET_DURATION * LONG_WINDOW.buckets as u32;
+        assert_eq!(0.0, burn_rate(&tracker, LONG_WINDOW, much_later));
+        record(&tracker, 1, 0, much_later);
+        assert_eq!(1, tracker.buckets().len());
+        assert_eq!(0.0, burn_rate(&tracker, LONG_WINDOW, much_later));
+    }
+
+    #[test]
+    fn alerts_once_both_windows_cross_the_threshold() {
+        let started_at = Instant::now();
+
+        // A burn rate of 14 with a budget of 1%
+        let below = tracker(0.99, started_at);
+        record(&below, 100, 14, started_at);
+        assert!(!below.is_alerting(started_at));
+
+        // A burn rate of 15 with a budget of 1%
+        let above = tracker(0.99, started_at);
+        record(&above, 100, 15, started_at);
+        assert!(above.is_alerting(started_at));
+
+        // The short window has recovered, while the long one is still burning
+        let later = started_at + BUCKET_DURATION * SHORT_WINDOW.buckets as u32;
+        assert!(burn_rate(&above, LONG_WINDOW, later) > FAST_BURN_ALERT_THRESHOLD);
+        assert!(!above.is_alerting(later));
+    }
+}
@@ -15,12 +15,13 @@ use veritech_core::{
 
 pub use cyclone_core::{
     ActionRunRequest, ActionRunResultSuccess, BeforeFunction, ComponentKind, ComponentView,
-    ComponentViewWithGeometry, FunctionResult, FunctionResultFailure,
+    ComponentViewWithGeometry, ExecutionLimit, FunctionResult, FunctionResultFailure,
     FunctionResultFailureErrorKind, KillExecutionRequest, ManagementFuncStatus, ManagementRequest,
-    ManagementResultSuccess, OutputStream, ResolverFunctionComponent, ResolverFunctionRequest,
-    ResolverFunctionResponseType, ResolverFunctionResultSuccess, ResourceStatus,
-    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveContainer,
-    ValidationRequest, ValidationResultSuccess,
+    ManagementResultSuccess, OutputStream, ResolverFunctionBackend, ResolverFunctionComponent,
+    ResolverFunctionRequest, ResolverFunctionResponseType, ResolverFunctionResultSuccess,
+    ResourceStatus, RuntimeVersion, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, SensitiveContainer, ValidationRequest,
+    ValidationResultSuccess,
 };
 pub use veritech_core::{encrypt_value_tree, VeritechValueEncryptError};
 
@@ -3,9 +3,9 @@ use std::{collections::HashMap, env};
 use base64::{engine::general_purpose, Engine};
 use cyclone_core::{
     ActionRunRequest, ComponentKind, ComponentView, ComponentViewWithGeometry, FunctionResult,
-    FunctionResultFailureErrorKind, ManagementRequest, ResolverFunctionComponent,
-    ResolverFunctionRequest, ResolverFunctionResponseType, ResourceStatus,
-    SchemaVariantDefinitionRequest, ValidationRequest,
+    FunctionResultFailureErrorKind, ManagementRequest, ResolverFunctionBackend,
+    ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResponseType,
+    ResourceStatus, RuntimeVersion, SchemaVariantDefinitionRequest, ValidationRequest,
 };
 use si_data_nats::{NatsClient, NatsConfig};
 use test_log::test;
@@ -54,6 +54,7 @@ async fn veritech_server_for_uds_cyclone(
             .try_lang_server_cmd_path(config_file.cyclone.lang_server_cmd_path())
             .expect("failed to setup lang_js_cmd_path")
             .all_endpoints()
+            .wasm()
             .pool_size(4_u32)
             .build()
             .expect("failed to build cyclone spec"),
@@ -119,6 +120,7 @@ async fn executes_simple_management_function() {
              }",
         ),
         before: vec![],
+        min_runtime_version: None,
     };
 
     let result = client
@@ -157,6 +159,8 @@ async fn executes_simple_action_run() {
         args: serde_json::json!({ "foo": "bar", "baz": "foo" }),
         code_base64: base64_encode("function numberOfInputs(input) { return { status: 'ok', payload: Object.keys(input)?.length ?? 0 } }"),
         before: vec![],
+        min_runtime_version: None,
+        simulate: false,
     };
 
     let result = client
@@ -207,6 +211,8 @@ async fn executes_simple_resolver_function() {
             "function numberOfInputs(input) { return Object.keys(input)?.length ?? 0; }",
         ),
         before: vec![],
+        min_runtime_version: None,
+        backend: Default::default(),
     };
 
     let result = client
@@ -226,6 +232,72 @@ async fn executes_simple_resolver_function() {
     }
 }
 
+#[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+#[test(tokio::test)]
+async fn executes_simple_wasm_resolver_function() {
+    let prefix = nats_prefix();
+    run_veritech_server_for_uds_cyclone(prefix.clone()).await;
+    let client = client(prefix).await;
+
+    // Not going to check output here--we aren't emitting anything
+    let (tx, mut rx) = mpsc::channel(64);
+    tokio::spawn(async move {
+        while let Some(output) = rx.recv().await {
+            info!("output: {:?}", output)
+        }
+    });
+
+    // A WASI command which ignores its input and writes a result of 2 to stdout
+    let result_line =
+        r#"{"protocol":"result","status":"success","executionId":"1234","data":2,"unset":false}"#;
+    let module = format!(
+        r#"(module
+            (import "wasi_snapshot_preview1" "fd_write"
+                (func $fd_write (param i32 i32 i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 16) "{}\n")
+            (func (export "_start")
+                (i32.store (i32.const 0) (i32.const 16))
+                (i32.store (i32.const 4) (i32.const {}))
+                (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))))"#,
+        result_line.replace('"', "\\\""),
+        result_line.len() + 1,
+    );
+
+    let request = ResolverFunctionRequest {
+        execution_id: "1234".to_string(),
+        handler: "".to_string(),
+        component: ResolverFunctionComponent {
+            data: ComponentView {
+                properties: serde_json::json!({ "foo": "bar", "baz": "quux" }),
+                kind: ComponentKind::Standard,
+            },
+            parents: vec![],
+        },
+        response_type: ResolverFunctionResponseType::Integer,
+        code_base64: base64_encode(module),
+        before: vec![],
+        min_runtime_version: None,
+        backend: ResolverFunctionBackend::Wasm,
+    };
+
+    let result = client
+        .execute_resolver_function(tx, &request, WORKSPACE_ID, CHANGE_SET_ID)
+        .await
+        .expect("failed to execute wasm resolver function");
+
+    match result {
+        FunctionResult::Success(success) => {
+            assert_eq!(success.execution_id, "1234");
+            assert_eq!(success.data, serde_json::json!(2));
+            assert!(!success.unset);
+        }
+        FunctionResult::Failure(failure) => {
+            panic!("function did not succeed and should have: {failure:?}")
+        }
+    }
+}
+
 #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
 #[test(tokio::test)]
 async fn type_checks_resolve_function() {
@@ -276,6 +348,8 @@ async fn type_checks_resolve_function() {
             response_type,
             code_base64: base64_encode("function returnInputValue(input) { return input.value; }"),
             before: vec![],
+            min_runtime_version: None,
+            backend: Default::default(),
         };
 
         let result = client
@@ -339,6 +413,8 @@ async fn type_checks_resolve_function() {
             response_type: response_type.clone(),
             code_base64: base64_encode("function returnInputValue(input) { return input.value; }"),
             before: vec![],
+            min_runtime_version: None,
+            backend: Default::default(),
         };
 
         let result = client
@@ -362,6 +438,50 @@ async fn type_checks_resolve_function() {
     }
 }
 
+#[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+#[test(tokio::test)]
+async fn rejects_function_requiring_newer_runtime() {
+    let prefix = nats_prefix();
+    run_veritech_server_for_uds_cyclone(prefix.clone()).await;
+    let client = client(prefix).await;
+
+    // Not going to check output here--we aren't emitting anything
+    let (tx, mut rx) = mpsc::channel(64);
+    tokio::spawn(async move {
+        while let Some(output) = rx.recv().await {
+            info!("output: {:?}", output)
+        }
+    });
+
+    let execution_id = "rejects_function_requiring_newer_runtime";
+    let request = ResolverFunctionRequest {
+        execution_id: execution_id.to_string(),
+        handler: "numberOfParents".to_string(),
+        component: ResolverFunctionComponent::default(),
+        response_type: ResolverFunctionResponseType::Integer,
+        code_base64: base64_encode("function numberOfParents(input) { return 0; }"),
+        before: vec![],
+        min_runtime_version: Some(RuntimeVersion::new(9999, 0, 0)),
+        backend: Default::default(),
+    };
+
+    let result = client
+        .execute_resolver_function(tx, &request, WORKSPACE_ID, CHANGE_SET_ID)
+        .await
+        .expect("failed to execute resolver function");
+
+    match result {
+        FunctionResult::Success(success) => panic!("should have been rejected: {success:?}"),
+        FunctionResult::Failure(failure) => {
+            assert_eq!(
+                failure.error().kind,
+                FunctionResultFailureErrorKind::UnsupportedRuntimeVersion
+            );
+            assert_eq!(failure.execution_id(), execution_id);
+        }
+    }
+}
+
 #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
 #[test(tokio::test)]
 async fn executes_simple_validation() {
@@ -384,6 +504,7 @@ async fn executes_simple_validation() {
         validation_format: r#"{"type":"number","flags":{"presence":"required"},"rules":[{"name":"integer"},{"name":"min","args":{"limit":33}},{"name":"max","args":{"limit":33}}]}"#.to_string(),
         code_base64: "".to_string(),
         before: vec![],
+        min_runtime_version: None,
     };
 
     let result = client
@@ -428,6 +549,7 @@ async fn executes_simple_schema_variant_definition() {
                     };
                 }",
         ),
+        min_runtime_version: None,
     };
 
     let result = client
@@ -1,6 +1,7 @@
 use serde_json::{json, Value};
 use si_crypto::{
-    SensitiveStrings, VeritechDecryptionKey, VeritechDecryptionKeyError, VeritechEncryptionKey,
+    SensitiveStrings, VeritechDecryptionKeyError, VeritechDecryptionKeyLookup,
+    VeritechEncryptionKey,
 };
 use thiserror::Error;
 
@@ -86,7 +87,7 @@ pub fn encrypt_value_tree(
 pub fn decrypt_value_tree(
     value: &mut Value,
     sensitive_strings: &mut SensitiveStrings,
-    decryption_key: &VeritechDecryptionKey,
+    decryption_keys: &dyn VeritechDecryptionKeyLookup,
 ) -> Result<(), VeritechValueDecryptError> {
     let mut json_pointer_stack = vec!["".to_owned()];
 
@@ -102,7 +103,7 @@ pub fn decrypt_value_tree(
                     );
                 }
                 Value::Object(_) if is_value_encrypted(value) => {
-                    let decrypted_value = decrypt_value(value, decryption_key)?;
+                    let decrypted_value = decrypt_value(value, decryption_keys)?;
                     if let Value::String(sensitive_str) = &decrypted_value {
                         sensitive_strings.insert(sensitive_str);
                     }
@@ -155,7 +156,7 @@ fn encrypt_value(
 
 fn decrypt_value(
     value: &Value,
-    decryption_key: &VeritechDecryptionKey,
+    decryption_keys: &dyn VeritechDecryptionKeyLookup,
 ) -> Result<Value, VeritechValueDecryptError> {
     // Confirm value is an object
     let value = value
@@ -171,16 +172,16 @@ fn decrypt_value(
     {
         return Err(VeritechValueDecryptError::MarkerFieldValueNotTrue);
     }
-    // Confirm that key hash field value matches hash for provided decryption key
-    if value
-        .get(KEY_HASH_FIELD)
-        .ok_or(VeritechValueDecryptError::KeyHashFieldMissing)?
-        .as_str()
-        .ok_or(VeritechValueDecryptError::KeyHashFieldValueNotString)?
-        != decryption_key.encryption_key_hash_str()
-    {
-        return Err(VeritechValueDecryptError::KeyHashNoMatch);
-    }
+    // Find the decryption key matching the key hash field value
+    let decryption_key = decryption_keys
+        .for_encryption_key_hash(
+            value
+                .get(KEY_HASH_FIELD)
+                .ok_or(VeritechValueDecryptError::KeyHashFieldMissing)?
+                .as_str()
+                .ok_or(VeritechValueDecryptError::KeyHashFieldValueNotString)?,
+        )
+        .ok_or(VeritechValueDecryptError::KeyHashNoMatch)?;
 
     // Decrypt crypted field and deserialize decrypted contents as a JSON value
     let decrypted = {
@@ -310,7 +311,7 @@ mod tests {
     }
 
     mod decrypt_value {
-        use si_crypto::VeritechKeyPair;
+        use si_crypto::{VeritechDecryptionKey, VeritechKeyPair};
 
         use super::*;
 
@@ -491,6 +492,36 @@ mod tests {
                 Err(VeritechValueDecryptError::KeyHashNoMatch),
             ));
         }
+
+        #[test]
+        fn decryption_key_matching_key_hash() {
+            struct Keys(Vec<VeritechDecryptionKey>);
+
+            impl VeritechDecryptionKeyLookup for Keys {
+                fn for_encryption_key_hash(
+                    &self,
+                    encryption_key_hash: &str,
+                ) -> Option<&VeritechDecryptionKey> {
+                    self.0
+                        .iter()
+                        .find(|key| key.encryption_key_hash_str() == encryption_key_hash)
+                }
+            }
+
+            let (previous_encryption_key, previous_decryption_key) = VeritechKeyPair::create();
+            let (_, current_decryption_key) = VeritechKeyPair::create();
+
+            let encrypted = encrypted(&previous_encryption_key);
+
+            assert_eq!(
+                json!("my-secret"),
+                decrypt_value(
+                    &encrypted,
+                    &Keys(vec![current_decryption_key, previous_decryption_key]),
+                )
+                .expect("failed to decrypt with previous key"),
+            );
+        }
     }
 
     mod encrypt_value_tree {
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use si_crypto::VeritechDecryptionKey;
+use si_crypto::RotatingVeritechDecryptionKey;
 use si_data_nats::NatsClient;
 use si_pool_noodle::{
     instance::cyclone::{LocalUdsInstance, LocalUdsInstanceSpec},
@@ -10,7 +10,7 @@ use tokio::sync::oneshot;
 use tokio::sync::Mutex;
 use veritech_core::ExecutionId;
 
-use crate::server::ServerMetadata;
+use crate::{scheduler::WorkspaceScheduler, server::ServerMetadata};
 
 /// Application state.
 #[derive(Clone, Debug)]
@@ -20,11 +20,12 @@ pub struct AppState {
     // NOTE(nick,fletcher,scott): this implements clone and the inner bits are wrapped in an Arc.
     // If that changes, then I hope you read this comment before that happens.
     pub cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    pub decryption_key: Arc<VeritechDecryptionKey>,
+    pub decryption_key: RotatingVeritechDecryptionKey,
     // TODO(nick,fletcher,scott): make this mutable at runtime.
     pub cyclone_client_execution_timeout: Duration,
     pub nats: NatsClient,
     pub kill_senders: Arc<Mutex<HashMap<ExecutionId, oneshot::Sender<()>>>>,
+    pub scheduler: WorkspaceScheduler,
 }
 
 impl AppState {
@@ -32,10 +33,11 @@ impl AppState {
     pub fn new(
         metadata: Arc<ServerMetadata>,
         cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-        decryption_key: Arc<VeritechDecryptionKey>,
+        decryption_key: RotatingVeritechDecryptionKey,
         cyclone_client_execution_timeout: Duration,
         nats: NatsClient,
         kill_senders: Arc<Mutex<HashMap<ExecutionId, oneshot::Sender<()>>>>,
+        scheduler: WorkspaceScheduler,
     ) -> Self {
         Self {
             metadata,
@@ -44,6 +46,7 @@ impl AppState {
             cyclone_client_execution_timeout,
             nats,
             kill_senders,
+            scheduler,
         }
     }
 
@@ -1,4 +1,5 @@
 use si_crypto::VeritechCryptoConfig;
+use si_settings::ValidationReport;
 use si_std::CanonicalFileError;
 use std::{
     env,
@@ -74,6 +75,9 @@ pub struct Config {
     #[builder(default = "default_concurrency_limit()")]
     concurrency_limit: usize,
 
+    #[builder(default)]
+    workspace_concurrency_limit: Option<usize>,
+
     #[builder(default = "random_instance_id()")]
     instance_id: String,
 }
@@ -124,6 +128,11 @@ impl Config {
         self.concurrency_limit
     }
 
+    /// Gets the config's per-workspace concurrency limit, if workspaces are limited.
+    pub fn workspace_concurrency_limit(&self) -> Option<usize> {
+        self.workspace_concurrency_limit
+    }
+
     /// Gets the config's instance ID.
     pub fn instance_id(&self) -> &str {
         self.instance_id.as_ref()
@@ -143,6 +152,8 @@ pub struct ConfigFile {
     cyclone_client_execution_timeout_secs: u64,
     #[serde(default = "default_concurrency_limit")]
     concurrency_limit: usize,
+    #[serde(default)]
+    workspace_concurrency_limit: Option<usize>,
     #[serde(default = "random_instance_id")]
     instance_id: String,
 }
@@ -162,6 +173,7 @@ impl ConfigFile {
             healthcheck_pool: default_healthcheck_pool(),
             cyclone_client_execution_timeout_secs: default_cyclone_client_execution_timeout_secs(),
             concurrency_limit: default_concurrency_limit(),
+            workspace_concurrency_limit: None,
             instance_id: random_instance_id(),
         }
     }
@@ -174,6 +186,7 @@ impl ConfigFile {
             healthcheck_pool: default_healthcheck_pool(),
             cyclone_client_execution_timeout_secs: default_cyclone_client_execution_timeout_secs(),
             concurrency_limit: default_concurrency_limit(),
+            workspace_concurrency_limit: None,
             instance_id: random_instance_id(),
         }
     }
@@ -181,6 +194,25 @@ impl ConfigFile {
 
 impl StandardConfigFile for ConfigFile {
     type Error = ConfigError;
+
+    fn validate(&self, report: &mut ValidationReport) {
+        report.require_non_empty("instance_id", &self.instance_id);
+        report.check(
+            self.concurrency_limit > 0,
+            "concurrency_limit",
+            "must be greater than zero",
+        );
+        report.check(
+            self.workspace_concurrency_limit != Some(0),
+            "workspace_concurrency_limit",
+            "must be greater than zero",
+        );
+        report.check(
+            self.cyclone_client_execution_timeout_secs > 0,
+            "cyclone_client_execution_timeout_secs",
+            "must be greater than zero",
+        );
+    }
 }
 
 impl TryFrom<ConfigFile> for Config {
@@ -197,6 +229,7 @@ impl TryFrom<ConfigFile> for Config {
             value.cyclone_client_execution_timeout_secs,
         ));
         config.concurrency_limit(value.concurrency_limit);
+        config.workspace_concurrency_limit(value.workspace_concurrency_limit);
         config.instance_id(value.instance_id);
         config.build().map_err(Into::into)
     }
@@ -260,6 +293,8 @@ pub enum CycloneConfig {
         resolver: bool,
         #[serde(default = "default_enable_endpoint")]
         action: bool,
+        #[serde(default)]
+        wasm: bool,
     },
     LocalUds {
         #[serde(default = "default_cyclone_cmd_path")]
@@ -283,9 +318,13 @@ pub enum CycloneConfig {
         #[serde(default = "default_enable_endpoint")]
         action: bool,
         #[serde(default)]
+        wasm: bool,
+        #[serde(default)]
         pool_size: u32,
         #[serde(default)]
         connect_timeout: u64,
+        #[serde(default)]
+        connection_pool_size: usize,
     },
 }
 
@@ -301,6 +340,7 @@ impl CycloneConfig {
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
+            wasm: false,
         }
     }
 
@@ -316,8 +356,10 @@ impl CycloneConfig {
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
+            wasm: false,
             pool_size: default_pool_size(),
             connect_timeout: default_connect_timeout(),
+            connection_pool_size: Default::default(),
         }
     }
 
@@ -397,6 +439,13 @@ impl CycloneConfig {
         };
     }
 
+    pub fn set_wasm(&mut self, value: bool) {
+        match self {
+            CycloneConfig::LocalUds { wasm, .. } => *wasm = value,
+            CycloneConfig::LocalHttp { wasm, .. } => *wasm = value,
+        };
+    }
+
     pub fn set_pool_size(&mut self, value: u32) {
         if let CycloneConfig::LocalUds { pool_size, .. } = self {
             *pool_size = value
@@ -426,8 +475,10 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 ping,
                 resolver,
                 action,
+                wasm,
                 pool_size,
                 connect_timeout,
+                connection_pool_size,
             } => {
                 let mut builder = LocalUdsInstance::spec();
 
@@ -458,8 +509,12 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 if action {
                     builder.action();
                 }
+                if wasm {
+                    builder.wasm();
+                }
                 builder.pool_size(pool_size);
                 builder.connect_timeout(connect_timeout);
+                builder.connection_pool_size(connection_pool_size);
 
                 Ok(Self::LocalUds(
                     builder.build().map_err(ConfigError::cyclone_spec_build)?,
@@ -475,6 +530,7 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 ping,
                 resolver,
                 action,
+                wasm,
             } => {
                 let mut builder = LocalHttpInstance::spec();
                 builder
@@ -500,6 +556,9 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 if action {
                     builder.action();
                 }
+                if wasm {
+                    builder.wasm();
+                }
 
                 Ok(Self::LocalHttp(
                     builder.build().map_err(ConfigError::cyclone_spec_build)?,
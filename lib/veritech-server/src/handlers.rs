@@ -1,5 +1,5 @@
 use chrono::Utc;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
 use naxum::{
     extract::{message_parts::Headers, State},
     response::{IntoResponse, Response},
@@ -10,8 +10,10 @@ use si_data_nats::{InnerMessage, Subject};
 // seems strange to get these cyclone_core types from si_pool_noodle?
 use si_pool_noodle::{
     ActionRunResultSuccess, CycloneClient, CycloneRequest, CycloneRequestable, ExecutionError,
-    ManagementResultSuccess, ProgressMessage, ResolverFunctionResultSuccess,
-    SchemaVariantDefinitionResultSuccess, SensitiveStrings, ValidationResultSuccess,
+    FunctionResult, FunctionResultFailure, FunctionResultFailureError,
+    FunctionResultFailureErrorKind, ManagementResultSuccess, ProgressMessage,
+    ResolverFunctionResultSuccess, RuntimeVersion, SchemaVariantDefinitionResultSuccess,
+    SensitiveStrings, ValidationResultSuccess,
 };
 use std::{collections::HashMap, result, str::Utf8Error, sync::Arc, time::Duration};
 use telemetry::prelude::*;
@@ -64,6 +66,8 @@ pub enum HandlerError {
     PoolNoodleExecutionValidation(#[from] si_pool_noodle::ExecutionError<ValidationResultSuccess>),
     #[error("publisher error: {0}")]
     Publisher(#[from] PublisherError),
+    #[error("function requires lang server version {0} or newer, but cyclone has {1}")]
+    UnsupportedRuntimeVersion(RuntimeVersion, String),
     #[error("utf8 error when creating subject")]
     Utf8(#[from] Utf8Error),
     #[error("veritech request error: {0}")]
@@ -100,7 +104,7 @@ pub async fn process_request(
 
     // Based on whether or not there is a prefix, we need to determine how many parts there are
     // before the exact subject part we are interested in.
-    if state.nats_subject_has_prefix() {
+    let workspace_id = if state.nats_subject_has_prefix() {
         match (
             parts.next(),
             parts.next(),
@@ -111,6 +115,7 @@ pub async fn process_request(
             (Some(_), Some(_), Some(_), Some(workspace_id), Some(change_set_id)) => {
                 span.record("si.workspace.id", workspace_id);
                 span.record("si.change_set.id", change_set_id);
+                workspace_id
             }
             _ => return Err(HandlerError::InvalidIncomingSubject(subject)),
         }
@@ -119,10 +124,11 @@ pub async fn process_request(
             (Some(_), Some(_), Some(workspace_id), Some(change_set_id)) => {
                 span.record("si.workspace.id", workspace_id);
                 span.record("si.change_set.id", change_set_id);
+                workspace_id
             }
             _ => return Err(HandlerError::InvalidIncomingSubject(subject)),
         }
-    }
+    };
 
     let (Some(request_subject), None) = (parts.next(), parts.next()) else {
         return Err(HandlerError::InvalidIncomingSubject(subject));
@@ -133,6 +139,9 @@ pub async fn process_request(
 
     info!(execution_kind = %veritech_request.subject_suffix(), execution_id = %veritech_request.execution_id(), "validated request and about to execute");
 
+    // Held until the execution finishes so that one workspace cannot take every cyclone instance
+    let _permit = state.scheduler.acquire(workspace_id).await;
+
     match veritech_request {
         VeritechRequest::ActionRun(request) => {
             dispatch_request(state, request, reply_subject).await?
@@ -175,12 +184,47 @@ where
         .await
         .map_err(|err| span.record_err(HandlerError::CyclonePool(Box::new(err))))?;
 
+    // Reject functions using sandbox APIs this cyclone's lang server lacks, rather than letting
+    // them fail at runtime
+    if let Some(required) = request.min_runtime_version() {
+        let actual = client
+            .lang_server_version()
+            .await
+            .map_err(|err| span.record_err(HandlerError::PoolNoodleClient(err)))?;
+        if !actual.is_some_and(|version| version.satisfies(required)) {
+            let err = HandlerError::UnsupportedRuntimeVersion(
+                *required,
+                actual.map_or_else(
+                    || "an unknown version".to_owned(),
+                    |version| version.to_string(),
+                ),
+            );
+            warn!(si.error.message = %err, "rejecting function execution");
+            let result: FunctionResult<Request::Response> =
+                FunctionResult::Failure(FunctionResultFailure::new(
+                    request.execution_id(),
+                    FunctionResultFailureError {
+                        kind: FunctionResultFailureErrorKind::UnsupportedRuntimeVersion,
+                        message: err.to_string(),
+                    },
+                    timestamp(),
+                ));
+            Publisher::new(&state.nats, &reply_mailbox)
+                .publish_result(&result)
+                .await
+                .map_err(|err| span.record_err(HandlerError::Publisher(err)))?;
+
+            span.record_ok();
+            return Ok(());
+        }
+    }
+
     request.inc_run_metric();
 
     let mut sensitive_strings = SensitiveStrings::default();
     // Decrypt the relevant contents of the request and track any resulting sensitive strings
     // to be redacted
-    request.decrypt(&mut sensitive_strings, &state.decryption_key)?;
+    request.decrypt(&mut sensitive_strings, &*state.decryption_key.keys().await)?;
 
     // NOTE(nick,fletcher): we need to create a owned client here because publisher has its own lifetime. Yeehaw.
     let nats_for_publisher = state.nats.clone();
@@ -206,13 +250,28 @@ where
             span.record_err(err)
         })?;
 
+    let mut kill_receiver = kill_receiver.fuse();
     let progress_loop = async {
         let mut progress = unstarted_progress.start().await.map_err(|err| {
             request.dec_run_metric();
             span.record_err(err)
         })?;
 
-        while let Some(msg) = progress.next().await {
+        loop {
+            let msg = tokio::select! {
+                msg = progress.next() => msg,
+                Ok(()) = &mut kill_receiver => {
+                    // Have cyclone kill the lang server rather than leave the function running
+                    if let Err(err) = progress.cancel().await {
+                        warn!(error = ?err, "failed to cancel execution in cyclone");
+                    }
+                    return Err(HandlerError::Killed(execution_id.to_owned()));
+                }
+            };
+            let Some(msg) = msg else {
+                break;
+            };
+
             match msg {
                 Ok(ProgressMessage::OutputStream(output)) => {
                     publisher.publish_output(&output).await.map_err(|err| {
@@ -253,9 +312,6 @@ where
                 timeout,
             ))
         },
-        Ok(_) = kill_receiver => {
-            Err(HandlerError::Killed(execution_id))
-        }
         func_result = progress_loop => {
             kill_sender_remove_blocking(&state.kill_senders, execution_id).await?;
             func_result
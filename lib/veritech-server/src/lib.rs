@@ -3,6 +3,7 @@ mod config;
 mod handlers;
 mod publisher;
 mod request;
+mod scheduler;
 mod server;
 
 use std::io;
@@ -1,4 +1,4 @@
-use si_crypto::VeritechDecryptionKey;
+use si_crypto::VeritechDecryptionKeyLookup;
 use si_pool_noodle::{
     ActionRunRequest, BeforeFunction, ManagementRequest, ResolverFunctionRequest,
     SchemaVariantDefinitionRequest, SensitiveStrings, ValidationRequest,
@@ -9,7 +9,7 @@ pub trait DecryptRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_key: &dyn VeritechDecryptionKeyLookup,
     ) -> Result<(), VeritechValueDecryptError>;
 }
 
@@ -17,7 +17,7 @@ impl DecryptRequest for ResolverFunctionRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_key: &dyn VeritechDecryptionKeyLookup,
     ) -> Result<(), VeritechValueDecryptError> {
         decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
     }
@@ -27,7 +27,7 @@ impl DecryptRequest for ActionRunRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_key: &dyn VeritechDecryptionKeyLookup,
     ) -> Result<(), VeritechValueDecryptError> {
         decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
     }
@@ -37,7 +37,7 @@ impl DecryptRequest for ValidationRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_key: &dyn VeritechDecryptionKeyLookup,
     ) -> Result<(), VeritechValueDecryptError> {
         decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
     }
@@ -47,7 +47,7 @@ impl DecryptRequest for SchemaVariantDefinitionRequest {
     fn decrypt(
         &mut self,
         _sensitive_strings: &mut SensitiveStrings,
-        _decryption_key: &VeritechDecryptionKey,
+        _decryption_key: &dyn VeritechDecryptionKeyLookup,
     ) -> Result<(), VeritechValueDecryptError> {
         // No before funcs defined!
         Ok(())
@@ -58,7 +58,7 @@ impl DecryptRequest for ManagementRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_key: &dyn VeritechDecryptionKeyLookup,
     ) -> Result<(), VeritechValueDecryptError> {
         decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
     }
@@ -67,7 +67,7 @@ impl DecryptRequest for ManagementRequest {
 fn decrypt_before_func_args(
     before: &mut Vec<BeforeFunction>,
     sensitive_strings: &mut SensitiveStrings,
-    decryption_key: &VeritechDecryptionKey,
+    decryption_key: &dyn VeritechDecryptionKeyLookup,
 ) -> Result<(), VeritechValueDecryptError> {
     for func in before {
         decrypt_value_tree(&mut func.arg, sensitive_strings, decryption_key)?;
@@ -0,0 +1,387 @@
+//! Fair scheduling of function executions across workspaces.
+//!
+//! An execution holds one of a fixed number of slots, one per cyclone instance, while it runs.
+//! Free slots are handed out round-robin across the workspaces with waiting executions rather
+//! than in arrival order, so a workspace enqueueing thousands of qualifications cannot starve the
+//! other workspaces sharing this veritech. Each workspace may additionally be capped at a number
+//! of concurrent executions, whether or not there are free slots.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Instant,
+};
+
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+use tokio::sync::oneshot;
+
+/// Hands out execution slots fairly across workspaces.
+#[derive(Clone, Debug)]
+pub struct WorkspaceScheduler {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl WorkspaceScheduler {
+    /// Creates a new [`WorkspaceScheduler`] with `slots` execution slots, of which a single
+    /// workspace may hold at most `workspace_limit` (if set).
+    ///
+    /// Zero slots or a zero workspace limit are treated as one.
+    pub fn new(slots: usize, workspace_limit: Option<usize>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                slots: slots.max(1),
+                in_use: 0,
+                workspace_limit: workspace_limit.map(|limit| limit.max(1)),
+                workspaces: HashMap::new(),
+                ready: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Waits for an execution slot for the given workspace.
+    ///
+    /// The slot is held until the returned [`SchedulerPermit`] is dropped.
+    pub async fn acquire(&self, workspace_id: &str) -> SchedulerPermit {
+        let queued_at = Instant::now();
+
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut inner = self.lock();
+            inner.enqueue(workspace_id, sender);
+            inner.dispatch();
+        }
+
+        let mut waiter = Waiter {
+            scheduler: self,
+            workspace_id,
+            receiver: Some(receiver),
+        };
+        waiter.wait().await;
+
+        let wait_ms = queued_at.elapsed().as_millis() as u64;
+        metric!(
+            histogram.veritech.scheduler.queue_wait_ms = wait_ms,
+            workspace_id = workspace_id
+        );
+        debug!(
+            si.workspace.id = workspace_id,
+            wait_ms, "acquired execution slot"
+        );
+
+        SchedulerPermit {
+            scheduler: self.clone(),
+            workspace_id: workspace_id.to_owned(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // The lock is never held across an await or a panic-prone call, so recover from poisoning
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// An execution slot held by a workspace, released on drop.
+#[derive(Debug)]
+pub struct SchedulerPermit {
+    scheduler: WorkspaceScheduler,
+    workspace_id: String,
+}
+
+impl Drop for SchedulerPermit {
+    fn drop(&mut self) {
+        self.scheduler.lock().release(&self.workspace_id);
+    }
+}
+
+/// A queued execution which gives its slot back if it is dropped before it could start.
+struct Waiter<'a> {
+    scheduler: &'a WorkspaceScheduler,
+    workspace_id: &'a str,
+    receiver: Option<oneshot::Receiver<()>>,
+}
+
+impl Waiter<'_> {
+    async fn wait(&mut self) {
+        if let Some(receiver) = self.receiver.as_mut() {
+            // Senders are only dropped unsent for receivers which are already closed, and this
+            // one is open until it has been granted a slot
+            let _ = receiver.await;
+        }
+        self.receiver = None;
+    }
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        let Some(mut receiver) = self.receiver.take() else {
+            return;
+        };
+
+        // Slots are granted under the lock, so closing the receiver under it too settles whether
+        // this waiter was granted one before going away
+        let mut inner = self.scheduler.lock();
+        receiver.close();
+        if receiver.try_recv().is_ok() {
+            inner.release(self.workspace_id);
+        } else {
+            inner.forget_closed(self.workspace_id);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    slots: usize,
+    in_use: usize,
+    workspace_limit: Option<usize>,
+    workspaces: HashMap<String, WorkspaceState>,
+    /// Workspaces with waiting executions, in the order they will next be offered a slot.
+    ready: VecDeque<String>,
+}
+
+#[derive(Debug, Default)]
+struct WorkspaceState {
+    running: usize,
+    waiting: VecDeque<oneshot::Sender<()>>,
+}
+
+impl Inner {
+    fn enqueue(&mut self, workspace_id: &str, sender: oneshot::Sender<()>) {
+        let state = self.workspaces.entry(workspace_id.to_owned()).or_default();
+        if state.waiting.is_empty() {
+            self.ready.push_back(workspace_id.to_owned());
+        }
+        state.waiting.push_back(sender);
+    }
+
+    fn release(&mut self, workspace_id: &str) {
+        self.in_use = self.in_use.saturating_sub(1);
+        if let Some(state) = self.workspaces.get_mut(workspace_id) {
+            state.running = state.running.saturating_sub(1);
+            if state.running == 0 && state.waiting.is_empty() {
+                self.workspaces.remove(workspace_id);
+            }
+        }
+        self.dispatch();
+    }
+
+    fn forget_closed(&mut self, workspace_id: &str) {
+        if let Some(state) = self.workspaces.get_mut(workspace_id) {
+            state.waiting.retain(|sender| !sender.is_closed());
+            if state.waiting.is_empty() {
+                self.ready.retain(|id| id != workspace_id);
+                if state.running == 0 {
+                    self.workspaces.remove(workspace_id);
+                }
+            }
+        }
+    }
+
+    /// Grants free slots, one workspace at a time, to the next waiting execution of each
+    /// workspace which is under its limit.
+    fn dispatch(&mut self) {
+        // Stop once every ready workspace has been passed over without being granted a slot
+        let mut passed_over = 0;
+        while self.in_use < self.slots && passed_over < self.ready.len() {
+            let Some(workspace_id) = self.ready.pop_front() else {
+                break;
+            };
+            let Some(state) = self.workspaces.get_mut(&workspace_id) else {
+                continue;
+            };
+
+            if self
+                .workspace_limit
+                .is_some_and(|limit| state.running >= limit)
+            {
+                self.ready.push_back(workspace_id);
+                passed_over += 1;
+                continue;
+            }
+
+            while let Some(sender) = state.waiting.pop_front() {
+                if sender.send(()).is_ok() {
+                    state.running += 1;
+                    self.in_use += 1;
+                    passed_over = 0;
+                    break;
+                }
+            }
+
+            if !state.waiting.is_empty() {
+                self.ready.push_back(workspace_id);
+            } else if state.running == 0 {
+                self.workspaces.remove(&workspace_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{sync::mpsc, task::JoinHandle};
+
+    use super::*;
+
+    type Granted = mpsc::UnboundedReceiver<(&'static str, SchedulerPermit)>;
+
+    /// Queues an execution for the workspace, which reports its label and permit once granted.
+    async fn queue(
+        scheduler: &WorkspaceScheduler,
+        granted: &mpsc::UnboundedSender<(&'static str, SchedulerPermit)>,
+        workspace_id: &'static str,
+        label: &'static str,
+    ) -> JoinHandle<()> {
+        let scheduler = scheduler.clone();
+        let granted = granted.clone();
+        let handle = tokio::spawn(async move {
+            let permit = scheduler.acquire(workspace_id).await;
+            let _ = granted.send((label, permit));
+        });
+        settle().await;
+        handle
+    }
+
+    /// Lets spawned tasks run until they are waiting on the scheduler.
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    async fn next(granted: &mut Granted) -> (&'static str, SchedulerPermit) {
+        settle().await;
+        granted.try_recv().expect("no execution was granted a slot")
+    }
+
+    async fn assert_none_granted(granted: &mut Granted) {
+        settle().await;
+        assert!(
+            granted.try_recv().is_err(),
+            "an execution was granted a slot"
+        );
+    }
+
+    #[tokio::test]
+    async fn alternates_between_workspaces_rather_than_arrival_order() {
+        let scheduler = WorkspaceScheduler::new(1, None);
+        let (sender, mut granted) = mpsc::unbounded_channel();
+
+        queue(&scheduler, &sender, "a", "a1").await;
+        let (_, first) = next(&mut granted).await;
+        for label in ["a2", "a3", "a4"] {
+            queue(&scheduler, &sender, "a", label).await;
+        }
+        queue(&scheduler, &sender, "b", "b1").await;
+        queue(&scheduler, &sender, "b", "b2").await;
+        assert_none_granted(&mut granted).await;
+
+        let mut order = Vec::new();
+        drop(first);
+        for _ in 0..5 {
+            let (label, permit) = next(&mut granted).await;
+            order.push(label);
+            drop(permit);
+        }
+
+        assert_eq!(vec!["a2", "b1", "a3", "b2", "a4"], order);
+    }
+
+    #[tokio::test]
+    async fn waits_for_a_released_slot_once_capacity_is_exhausted() {
+        let scheduler = WorkspaceScheduler::new(2, None);
+        let (sender, mut granted) = mpsc::unbounded_channel();
+
+        queue(&scheduler, &sender, "a", "a1").await;
+        queue(&scheduler, &sender, "b", "b1").await;
+        let (_, a1) = next(&mut granted).await;
+        let (_, b1) = next(&mut granted).await;
+
+        queue(&scheduler, &sender, "c", "c1").await;
+        assert_none_granted(&mut granted).await;
+
+        drop(b1);
+        let (label, _c1) = next(&mut granted).await;
+        assert_eq!("c1", label);
+        assert_eq!(2, scheduler.lock().in_use);
+        drop(a1);
+        assert_eq!(1, scheduler.lock().in_use);
+    }
+
+    #[tokio::test]
+    async fn caps_workspaces_at_their_limit_even_with_free_slots() {
+        let scheduler = WorkspaceScheduler::new(3, Some(1));
+        let (sender, mut granted) = mpsc::unbounded_channel();
+
+        queue(&scheduler, &sender, "a", "a1").await;
+        queue(&scheduler, &sender, "a", "a2").await;
+        let (_, a1) = next(&mut granted).await;
+        assert_none_granted(&mut granted).await;
+
+        queue(&scheduler, &sender, "b", "b1").await;
+        let (label, _b1) = next(&mut granted).await;
+        assert_eq!("b1", label);
+
+        drop(a1);
+        let (label, _a2) = next(&mut granted).await;
+        assert_eq!("a2", label);
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiters_give_up_their_place() {
+        let scheduler = WorkspaceScheduler::new(1, None);
+        let (sender, mut granted) = mpsc::unbounded_channel();
+
+        queue(&scheduler, &sender, "a", "a1").await;
+        let (_, a1) = next(&mut granted).await;
+        let b1 = queue(&scheduler, &sender, "b", "b1").await;
+        queue(&scheduler, &sender, "c", "c1").await;
+
+        b1.abort();
+        settle().await;
+        assert!(!scheduler.lock().workspaces.contains_key("b"));
+
+        drop(a1);
+        let (label, _c1) = next(&mut granted).await;
+        assert_eq!("c1", label);
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiters_release_slots_granted_before_they_ran() {
+        let scheduler = WorkspaceScheduler::new(1, None);
+        let (sender, mut granted) = mpsc::unbounded_channel();
+
+        queue(&scheduler, &sender, "a", "a1").await;
+        let (_, a1) = next(&mut granted).await;
+        let b1 = queue(&scheduler, &sender, "b", "b1").await;
+
+        // Grants b1 the slot, but aborts it before it gets to run and take it
+        drop(a1);
+        b1.abort();
+        settle().await;
+        assert_none_granted(&mut granted).await;
+
+        let inner = scheduler.lock();
+        assert_eq!(0, inner.in_use);
+        assert!(inner.workspaces.is_empty());
+        assert!(inner.ready.is_empty());
+    }
+
+    #[tokio::test]
+    async fn zero_slots_and_limits_are_treated_as_one() {
+        let scheduler = WorkspaceScheduler::new(0, Some(0));
+        let (sender, mut granted) = mpsc::unbounded_channel();
+
+        queue(&scheduler, &sender, "a", "a1").await;
+        queue(&scheduler, &sender, "a", "a2").await;
+        let (_, a1) = next(&mut granted).await;
+        assert_none_granted(&mut granted).await;
+
+        drop(a1);
+        let (label, _a2) = next(&mut granted).await;
+        assert_eq!("a2", label);
+    }
+}
@@ -14,13 +14,14 @@ use naxum::{
     handler::Handler as _,
     middleware::{
         ack::AckLayer,
+        concurrency_limit::ConcurrencyLimitLayer,
         matched_subject::{ForSubject, MatchedSubjectLayer},
         trace::TraceLayer,
     },
     response::{IntoResponse, Response},
     MessageHead, ServiceBuilder, ServiceExt as _, TowerServiceExt as _,
 };
-use si_crypto::VeritechDecryptionKey;
+use si_crypto::RotatingVeritechDecryptionKey;
 use si_data_nats::{async_nats, jetstream, NatsClient, Subscriber};
 use si_pool_noodle::{
     instance::cyclone::{LocalUdsInstance, LocalUdsInstanceSpec},
@@ -35,11 +36,16 @@ use veritech_core::{incoming_subject, veritech_work_queue, ExecutionId, GetNatsS
 use crate::{
     app_state::{AppState, KillAppState},
     config::CycloneSpec,
-    handlers, Config, ServerError, ServerResult,
+    handlers,
+    scheduler::WorkspaceScheduler,
+    Config, ServerError, ServerResult,
 };
 
 const CONSUMER_NAME: &str = "veritech-server";
 const CONSUMER_MAX_DELIVERY: i64 = 5;
+/// How long the consumer waits for an ack before redelivering. Messages are kept in progress
+/// while they are processed, so this only bounds how long a crashed instance holds on to them.
+const CONSUMER_ACK_WAIT: Duration = Duration::from_secs(30);
 
 /// Server metadata, used with telemetry.
 #[derive(Clone, Debug)]
@@ -81,7 +87,8 @@ impl Server {
             instance_id: config.instance_id().into(),
         });
 
-        let decryption_key = VeritechDecryptionKey::from_config(config.crypto().clone()).await?;
+        let decryption_key =
+            RotatingVeritechDecryptionKey::from_config(config.crypto().clone()).await?;
 
         let kill_senders = Arc::new(Mutex::new(HashMap::new()));
 
@@ -136,11 +143,19 @@ impl Server {
                     .run()
                     .map_err(|e| ServerError::CyclonePool(Box::new(e)))?;
 
+                // Executions beyond the pool size would only wait on the pool, so queue them fairly
+                // in front of it instead
+                let scheduler = WorkspaceScheduler::new(
+                    spec.pool_size as usize,
+                    config.workspace_concurrency_limit(),
+                );
+
                 let inner_future = Self::build_app(
                     metadata.clone(),
                     config.concurrency_limit(),
                     cyclone_pool,
-                    Arc::new(decryption_key),
+                    scheduler,
+                    decryption_key,
                     config.cyclone_client_execution_timeout(),
                     nats.clone(),
                     kill_senders.clone(),
@@ -185,7 +200,8 @@ impl Server {
         metadata: Arc<ServerMetadata>,
         concurrency_limit: usize,
         cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-        decryption_key: Arc<VeritechDecryptionKey>,
+        scheduler: WorkspaceScheduler,
+        decryption_key: RotatingVeritechDecryptionKey,
         cyclone_client_execution_timeout: Duration,
         nats: NatsClient,
         kill_senders: Arc<Mutex<HashMap<ExecutionId, oneshot::Sender<()>>>>,
@@ -213,6 +229,7 @@ impl Server {
             cyclone_client_execution_timeout,
             nats,
             kill_senders,
+            scheduler,
         );
 
         let app = ServiceBuilder::new()
@@ -227,12 +244,17 @@ impl Server {
                     )
                     .on_response(telemetry_nats::NatsOnResponse::new()),
             )
-            .layer(AckLayer::new())
+            .layer(AckLayer::new().ack_wait(CONSUMER_ACK_WAIT))
+            .layer(ConcurrencyLimitLayer::new(concurrency_limit).name("veritech"))
             .service(handlers::process_request.with_state(state))
             .map_response(Response::into_response);
 
+        // Read up to another `concurrency_limit` messages ahead so that a permit is picked up as
+        // soon as it is released. Those messages wait in the concurrency limit, which reports
+        // them as queued, while the ack middleware keeps them in progress.
+        let incoming_limit = concurrency_limit.saturating_mul(2);
         let inner =
-            naxum::serve_with_incoming_limit(incoming, app.into_make_service(), concurrency_limit)
+            naxum::serve_with_incoming_limit(incoming, app.into_make_service(), incoming_limit)
                 .with_graceful_shutdown(naxum::wait_on_cancelled(token));
 
         Ok(Box::new(inner.into_future()))
@@ -304,6 +326,7 @@ impl Server {
     ) -> async_nats::jetstream::consumer::pull::Config {
         async_nats::jetstream::consumer::pull::Config {
             durable_name: Some(CONSUMER_NAME.to_owned()),
+            ack_wait: CONSUMER_ACK_WAIT,
             filter_subject: incoming_subject(subject_prefix).to_string(),
             max_deliver: CONSUMER_MAX_DELIVERY,
             ..Default::default()